@@ -0,0 +1,8 @@
+-- Character card import/export: keep the card fields that do not fit `persona`
+-- plus the original card so exports round-trip losslessly.
+
+ALTER TABLE characters ADD COLUMN first_message TEXT NOT NULL DEFAULT '';
+ALTER TABLE characters ADD COLUMN example_dialogue TEXT NOT NULL DEFAULT '';
+ALTER TABLE characters ADD COLUMN lorebook_json TEXT;
+ALTER TABLE characters ADD COLUMN card_json TEXT;
+ALTER TABLE characters ADD COLUMN avatar_path TEXT;
@@ -0,0 +1,10 @@
+-- Per-memory usage statistics driving periodic importance re-scoring.
+-- `retrieval_count` / `reference_count` are lifetime totals; the `pending_*`
+-- counters accumulate since the last re-score that acted on them.
+
+ALTER TABLE memories ADD COLUMN retrieval_count INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE memories ADD COLUMN reference_count INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE memories ADD COLUMN pending_retrievals INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE memories ADD COLUMN pending_references INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE memories ADD COLUMN last_retrieved_at INTEGER;
+ALTER TABLE memories ADD COLUMN last_rescored_at INTEGER;
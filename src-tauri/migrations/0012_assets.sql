@@ -0,0 +1,17 @@
+-- Content-addressable store for generated / cached files.
+-- One row per (category, sha256); `ref_count` tracks live owners so
+-- unreferenced blobs can be garbage-collected by `clean_assets`.
+
+CREATE TABLE IF NOT EXISTS assets (
+    hash TEXT NOT NULL,
+    category TEXT NOT NULL,
+    path TEXT NOT NULL,
+    size_bytes INTEGER NOT NULL,
+    ref_count INTEGER NOT NULL DEFAULT 1,
+    created_at INTEGER NOT NULL,
+    last_accessed_at INTEGER NOT NULL,
+    PRIMARY KEY (category, hash)
+);
+
+CREATE INDEX IF NOT EXISTS idx_assets_category_lru
+    ON assets(category, last_accessed_at);
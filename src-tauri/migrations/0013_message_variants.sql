@@ -0,0 +1,48 @@
+-- Conversation branching ("swipes").
+--
+-- Regenerating a reply creates a variant group anchored on the user message it
+-- answers. Each variant owns every row that follows the anchor on its branch;
+-- nested regenerations record their parent variant so switching an outer
+-- variant hides the inner branches with it. `is_active` caches whether a row
+-- is on the currently selected path.
+
+CREATE TABLE IF NOT EXISTS message_variant_groups (
+    id TEXT PRIMARY KEY,
+    conversation_id TEXT NOT NULL,
+    anchor_message_id INTEGER NOT NULL,
+    parent_group TEXT,
+    parent_index INTEGER,
+    active_index INTEGER NOT NULL DEFAULT 0,
+    variant_count INTEGER NOT NULL DEFAULT 1,
+    created_at INTEGER NOT NULL,
+    FOREIGN KEY (conversation_id) REFERENCES conversations(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_message_variant_groups_conversation
+    ON message_variant_groups(conversation_id, anchor_message_id);
+
+ALTER TABLE conversation_messages ADD COLUMN branch_group TEXT;
+ALTER TABLE conversation_messages ADD COLUMN variant_index INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE conversation_messages ADD COLUMN is_active INTEGER NOT NULL DEFAULT 1;
+
+CREATE INDEX IF NOT EXISTS idx_conversation_messages_branch
+    ON conversation_messages(conversation_id, branch_group, variant_index);
+
+-- The branch new rows are appended to (the deepest variant on the active path).
+ALTER TABLE conversations ADD COLUMN active_branch_group TEXT;
+ALTER TABLE conversations ADD COLUMN active_variant_index INTEGER NOT NULL DEFAULT 0;
+
+-- Every insert path (chat, drafts, failure events, bots) lands on the active branch.
+CREATE TRIGGER IF NOT EXISTS conversation_messages_join_active_branch
+AFTER INSERT ON conversation_messages
+WHEN NEW.branch_group IS NULL
+BEGIN
+    UPDATE conversation_messages
+    SET branch_group = (SELECT active_branch_group FROM conversations WHERE id = NEW.conversation_id),
+        variant_index = COALESCE(
+            (SELECT active_variant_index FROM conversations WHERE id = NEW.conversation_id),
+            0
+        )
+    WHERE id = NEW.id
+      AND (SELECT active_branch_group FROM conversations WHERE id = NEW.conversation_id) IS NOT NULL;
+END;
@@ -0,0 +1,8 @@
+-- LLM-extracted emotion personality traits, keyed by a hash of the persona
+-- text so each persona is only sent to the model once.
+
+CREATE TABLE IF NOT EXISTS persona_trait_cache (
+    persona_hash TEXT PRIMARY KEY,
+    traits_json TEXT NOT NULL,
+    created_at INTEGER NOT NULL
+);
@@ -0,0 +1,4 @@
+-- Per-character settings owned by the frontend (JSON object), so backend
+-- services can resolve everything about a character by id.
+
+ALTER TABLE characters ADD COLUMN settings_json TEXT NOT NULL DEFAULT '{}';
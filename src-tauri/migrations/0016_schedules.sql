@@ -0,0 +1,17 @@
+-- Time-based proactive behaviors: one-shot reminders and cron-like rules
+-- that fire a proactive message or a registered action.
+
+CREATE TABLE IF NOT EXISTS schedules (
+    id TEXT PRIMARY KEY,
+    character_id TEXT NOT NULL DEFAULT 'default',
+    label TEXT NOT NULL,
+    rule_json TEXT NOT NULL,
+    action_json TEXT NOT NULL,
+    enabled INTEGER NOT NULL DEFAULT 1,
+    -- NULL once a one-shot schedule has fired.
+    next_run_at INTEGER,
+    last_run_at INTEGER,
+    created_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_schedules_due ON schedules(enabled, next_run_at);
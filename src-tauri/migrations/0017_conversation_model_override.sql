@@ -0,0 +1,4 @@
+-- Per-conversation model pin (JSON: provider_id, model, temperature) that
+-- overrides the character binding and the global LLM config. NULL = none.
+
+ALTER TABLE conversations ADD COLUMN model_override TEXT;
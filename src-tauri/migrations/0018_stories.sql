@@ -0,0 +1,32 @@
+-- Interactive story mode: choose-your-own-adventure sessions narrated by a
+-- character. Beats form a tree; picking a choice on an earlier beat starts a
+-- new branch instead of overwriting the old one. Each beat carries the story
+-- state (flags, inventory, ...) as it stands after that beat.
+
+CREATE TABLE IF NOT EXISTS stories (
+    id TEXT PRIMARY KEY,
+    character_id TEXT NOT NULL DEFAULT 'default',
+    title TEXT NOT NULL,
+    premise TEXT NOT NULL,
+    -- The beat the next choice continues from.
+    current_beat_id TEXT,
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS story_beats (
+    id TEXT PRIMARY KEY,
+    story_id TEXT NOT NULL,
+    -- NULL for the opening beat.
+    parent_id TEXT,
+    -- What the player picked (or typed) on the parent beat.
+    choice_text TEXT,
+    narrative TEXT NOT NULL,
+    choices_json TEXT NOT NULL DEFAULT '[]',
+    state_json TEXT NOT NULL DEFAULT '{}',
+    ending INTEGER NOT NULL DEFAULT 0,
+    created_at INTEGER NOT NULL,
+    FOREIGN KEY (story_id) REFERENCES stories(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_story_beats_story ON story_beats(story_id, parent_id);
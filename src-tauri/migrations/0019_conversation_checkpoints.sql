@@ -0,0 +1,23 @@
+-- Conversation checkpoints: a named point in a conversation to rewind to.
+-- `message_id` is the last active row at the time; the emotion state and the
+-- conversation state (topic, pinned state) are copied as they stood then.
+-- Rewinding forks the conversation after `message_id` (see ai/branches.rs),
+-- so the turns that followed stay reachable as a variant.
+
+CREATE TABLE IF NOT EXISTS conversation_checkpoints (
+    id TEXT PRIMARY KEY,
+    conversation_id TEXT NOT NULL,
+    character_id TEXT NOT NULL,
+    label TEXT NOT NULL DEFAULT '',
+    message_id INTEGER NOT NULL,
+    -- Active messages up to and including `message_id`.
+    message_count INTEGER NOT NULL,
+    emotion_json TEXT NOT NULL DEFAULT '{}',
+    topic TEXT NOT NULL DEFAULT '',
+    pinned_state TEXT NOT NULL DEFAULT '{}',
+    created_at INTEGER NOT NULL,
+    FOREIGN KEY (conversation_id) REFERENCES conversations(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_conversation_checkpoints_conversation
+    ON conversation_checkpoints(conversation_id, created_at DESC);
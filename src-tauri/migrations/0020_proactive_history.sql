@@ -0,0 +1,14 @@
+-- Unprompted messages per character, so the proactive composer can avoid
+-- repeating topics and openers. Pruned to the most recent rows on insert.
+CREATE TABLE IF NOT EXISTS proactive_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    character_id TEXT NOT NULL,
+    source TEXT NOT NULL,
+    topic TEXT NOT NULL DEFAULT '',
+    opener TEXT NOT NULL DEFAULT '',
+    message TEXT NOT NULL,
+    created_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_proactive_history_character
+    ON proactive_history(character_id, created_at DESC);
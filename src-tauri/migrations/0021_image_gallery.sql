@@ -0,0 +1,22 @@
+-- Every successful image generation, so generated art can be browsed after a
+-- restart. `asset_hash` is set when the file lives in the asset store (one
+-- reference per row); otherwise `image_path` is a file owned by this row.
+-- `conversation_id` is informational and survives conversation deletion.
+
+CREATE TABLE IF NOT EXISTS generated_images (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    prompt TEXT NOT NULL,
+    negative_prompt TEXT,
+    provider_id TEXT NOT NULL,
+    seed INTEGER,
+    image_path TEXT NOT NULL,
+    asset_hash TEXT,
+    conversation_id TEXT,
+    character_id TEXT,
+    created_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_generated_images_created
+    ON generated_images(created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_generated_images_conversation
+    ON generated_images(conversation_id, created_at DESC);
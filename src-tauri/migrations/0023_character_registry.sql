@@ -0,0 +1,53 @@
+-- Character registry: the characters row is the source of truth for a
+-- character's default Live2D model and voice, and every conversation and
+-- memory refers to one.
+--
+-- SQLite cannot add a FOREIGN KEY to an existing column without rebuilding
+-- conversations and memories together with their triggers and FTS index, so
+-- the reference is enforced on insert instead: a conversation or memory for
+-- an unknown character id registers a placeholder row
+-- (source_format 'auto') that `create_character` fills in later. Ids such as
+-- 'default' are used before the frontend registers a row, so rejecting them
+-- is not an option.
+--
+-- Deleting a character does not cascade: `delete_character` moves its
+-- conversations and memories to the trash so the delete can be undone.
+
+ALTER TABLE characters ADD COLUMN model_path TEXT;
+ALTER TABLE characters ADD COLUMN voice TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_conversations_character
+    ON conversations(character_id);
+
+INSERT OR IGNORE INTO characters (id, name, source_format, created_at, updated_at)
+    SELECT character_id, character_id, 'auto', 0, 0 FROM conversations
+    UNION
+    SELECT character_id, character_id, 'auto', 0, 0 FROM memories;
+
+CREATE TRIGGER IF NOT EXISTS conversations_character_ai AFTER INSERT ON conversations BEGIN
+    INSERT OR IGNORE INTO characters (id, name, source_format, created_at, updated_at)
+        VALUES (new.character_id, new.character_id, 'auto',
+                CAST(strftime('%s', 'now') AS INTEGER) * 1000,
+                CAST(strftime('%s', 'now') AS INTEGER) * 1000);
+END;
+
+CREATE TRIGGER IF NOT EXISTS conversations_character_au AFTER UPDATE OF character_id ON conversations BEGIN
+    INSERT OR IGNORE INTO characters (id, name, source_format, created_at, updated_at)
+        VALUES (new.character_id, new.character_id, 'auto',
+                CAST(strftime('%s', 'now') AS INTEGER) * 1000,
+                CAST(strftime('%s', 'now') AS INTEGER) * 1000);
+END;
+
+CREATE TRIGGER IF NOT EXISTS memories_character_ai AFTER INSERT ON memories BEGIN
+    INSERT OR IGNORE INTO characters (id, name, source_format, created_at, updated_at)
+        VALUES (new.character_id, new.character_id, 'auto',
+                CAST(strftime('%s', 'now') AS INTEGER) * 1000,
+                CAST(strftime('%s', 'now') AS INTEGER) * 1000);
+END;
+
+CREATE TRIGGER IF NOT EXISTS memories_character_au AFTER UPDATE OF character_id ON memories BEGIN
+    INSERT OR IGNORE INTO characters (id, name, source_format, created_at, updated_at)
+        VALUES (new.character_id, new.character_id, 'auto',
+                CAST(strftime('%s', 'now') AS INTEGER) * 1000,
+                CAST(strftime('%s', 'now') AS INTEGER) * 1000);
+END;
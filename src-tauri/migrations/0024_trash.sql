@@ -0,0 +1,15 @@
+-- Trash for destructive commands, so an accidental delete can be undone.
+-- `payload` holds what restoring needs: the memory's previous status, the
+-- deleted conversation rows, or where a Live2D model folder was moved to.
+-- Entries are purged for good by `empty_trash` or after the retention period.
+
+CREATE TABLE IF NOT EXISTS trash (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    kind TEXT NOT NULL,
+    item_id TEXT NOT NULL,
+    label TEXT NOT NULL DEFAULT '',
+    payload TEXT NOT NULL DEFAULT '{}',
+    deleted_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_trash_deleted_at ON trash(deleted_at DESC);
@@ -0,0 +1,5 @@
+-- Per-character relationship state as a JSON object: how the user asked to
+-- be addressed and what they call the character, learned from chat or
+-- corrected by hand.
+
+ALTER TABLE characters ADD COLUMN relationship_json TEXT NOT NULL DEFAULT '{}';
@@ -133,6 +133,104 @@ impl ActionHandler for CaptureScreenAction {
     }
 }
 
+// ── analyze_image_file ─────────────────────────────────
+
+pub struct AnalyzeImageFileAction;
+
+#[async_trait]
+impl ActionHandler for AnalyzeImageFileAction {
+    fn name(&self) -> &str {
+        "analyze_image_file"
+    }
+
+    fn description(&self) -> &str {
+        "Look at a local image file, or the most recent images in a folder, and return descriptions. Use when the user asks about a photo or their photo library on disk."
+    }
+
+    fn parameters(&self) -> Vec<ActionParam> {
+        vec![
+            ActionParam {
+                name: "path".to_string(),
+                description:
+                    "Absolute path to an image file (png/jpg/webp/gif) or a folder of images"
+                        .to_string(),
+                required: true,
+            },
+            ActionParam {
+                name: "question".to_string(),
+                description: "Optional question to answer about a single image".to_string(),
+                required: false,
+            },
+            ActionParam {
+                name: "limit".to_string(),
+                description: "For folders: how many of the newest images to describe (default 20)"
+                    .to_string(),
+                required: false,
+            },
+        ]
+    }
+
+    fn needs_feedback(&self) -> bool {
+        true
+    }
+
+    fn risk_tags(&self) -> Vec<ActionRiskTag> {
+        vec![ActionRiskTag::Read, ActionRiskTag::Sensitive]
+    }
+
+    async fn execute(
+        &self,
+        args: HashMap<String, String>,
+        ctx: ActionContext,
+    ) -> Result<ActionResult, ActionError> {
+        let path = args
+            .get("path")
+            .map(|p| p.trim())
+            .filter(|p| !p.is_empty())
+            .ok_or_else(|| ActionError("Missing 'path' parameter".to_string()))?;
+        let watcher = ctx.app.state::<crate::vision::watcher::VisionWatcher>();
+        let cache = ctx
+            .app
+            .state::<std::sync::Arc<crate::vision::image_files::ImageDescriptionCache>>();
+
+        if std::path::Path::new(path).is_dir() {
+            let limit = args.get("limit").and_then(|l| l.trim().parse().ok());
+            let app = ctx.app.clone();
+            let result =
+                crate::vision::image_files::describe_folder(&watcher, &cache, path, limit, |p| {
+                    let _ = app.emit("vision:image-batch-progress", &p);
+                })
+                .await
+                .map_err(ActionError)?;
+
+            let mut message = format!(
+                "Described {} of {} images in {}:",
+                result.described.len(),
+                result.total_images,
+                result.folder
+            );
+            for image in &result.described {
+                message.push_str(&format!("\n- {}: {}", image.file_name, image.description));
+            }
+            if !result.failed.is_empty() {
+                message.push_str(&format!("\n({} could not be read)", result.failed.len()));
+            }
+            let data = serde_json::to_value(&result).unwrap_or_default();
+            return Ok(ActionResult::ok_with_data(message, data));
+        }
+
+        let question = args.get("question").map(String::as_str);
+        let result =
+            crate::vision::image_files::describe_image_file(&watcher, &cache, path, question)
+                .await
+                .map_err(|error| ActionError(format!("Image analysis failed: {}", error)))?;
+        Ok(ActionResult::ok_with_data(
+            format!("{}: {}", result.file_name, result.description),
+            serde_json::to_value(&result).unwrap_or_default(),
+        ))
+    }
+}
+
 // ── play_cue ──────────────────────────────────
 
 pub struct PlayCueAction;
@@ -255,6 +353,121 @@ impl ActionHandler for SetBackgroundAction {
     }
 }
 
+// ── set_desktop_wallpaper ──────────────────────────────
+
+pub struct SetDesktopWallpaperAction;
+
+#[async_trait]
+impl ActionHandler for SetDesktopWallpaperAction {
+    fn name(&self) -> &str {
+        "set_desktop_wallpaper"
+    }
+
+    fn description(&self) -> &str {
+        "Decorate the user's real desktop wallpaper (outside Kokoro Engine) with an image or a mood color palette, or undo the last change. Only works when the user enabled it in settings, and only occasionally. For the in-app background use set_background instead."
+    }
+
+    fn parameters(&self) -> Vec<ActionParam> {
+        vec![
+            ActionParam {
+                name: "image_path".to_string(),
+                description: "Local image to use, e.g. the image_url returned by generate_image".to_string(),
+                required: false,
+            },
+            ActionParam {
+                name: "prompt".to_string(),
+                description: "English prompt to generate a new wallpaper image from".to_string(),
+                required: false,
+            },
+            ActionParam {
+                name: "mood".to_string(),
+                description: "Mood for a color palette wallpaper (joy, love, sadness, anger, surprise, fear, neutral); used when no image is given, defaults to your current emotion".to_string(),
+                required: false,
+            },
+            ActionParam {
+                name: "revert".to_string(),
+                description: "\"true\" to put back the wallpaper from before the last change".to_string(),
+                required: false,
+            },
+        ]
+    }
+
+    fn risk_tags(&self) -> Vec<ActionRiskTag> {
+        vec![ActionRiskTag::Write]
+    }
+
+    fn permission_level(&self) -> ActionPermissionLevel {
+        ActionPermissionLevel::Elevated
+    }
+
+    async fn execute(
+        &self,
+        args: HashMap<String, String>,
+        ctx: ActionContext,
+    ) -> Result<ActionResult, ActionError> {
+        let arg = |name: &str| {
+            args.get(name)
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+        };
+        let wallpaper = ctx
+            .app
+            .try_state::<crate::wallpaper::WallpaperService>()
+            .ok_or_else(|| ActionError("Wallpaper service is not available".into()))?;
+
+        if arg("revert").is_some_and(|value| value.eq_ignore_ascii_case("true")) {
+            wallpaper
+                .revert(&ctx.app)
+                .await
+                .map_err(|e| ActionError(e.to_string()))?;
+            return Ok(ActionResult::ok("Reverted the desktop wallpaper."));
+        }
+
+        wallpaper
+            .check_companion_allowed()
+            .await
+            .map_err(|e| ActionError(e.to_string()))?;
+
+        let image_path = match (arg("image_path"), arg("prompt")) {
+            (Some(path), _) => Some(path),
+            (None, Some(prompt)) => {
+                let imagegen = ctx
+                    .app
+                    .try_state::<crate::imagegen::ImageGenService>()
+                    .ok_or_else(|| {
+                        ActionError("Image generation service is not available".into())
+                    })?;
+                let result = imagegen
+                    .generate(prompt, None, None, None)
+                    .await
+                    .map_err(|error| ActionError(format!("Image generation failed: {}", error)))?;
+                let _ = ctx.app.emit("imagegen:done", &result);
+                Some(result.image_url)
+            }
+            (None, None) => None,
+        };
+
+        let entry = match image_path {
+            Some(path) => wallpaper.apply_image(&ctx.app, &path, arg("mood")).await,
+            None => {
+                wallpaper
+                    .apply_palette(&ctx.app, arg("mood").as_deref())
+                    .await
+            }
+        }
+        .map_err(|e| ActionError(e.to_string()))?;
+
+        Ok(ActionResult::ok_with_data(
+            "Desktop wallpaper changed. The user can undo it from settings or by asking you to revert it.",
+            serde_json::json!({
+                "path": entry.path,
+                "source": entry.source,
+                "mood": entry.mood,
+            }),
+        ))
+    }
+}
+
 // ── generate_image ─────────────────────────────────────
 
 pub struct GenerateImageAction;
@@ -281,6 +494,21 @@ impl ActionHandler for GenerateImageAction {
                 description: "Optional image generation provider id; omit to use the configured default provider".to_string(),
                 required: false,
             },
+            ActionParam {
+                name: "selfie".to_string(),
+                description: "\"true\" when the image shows you (the character) yourself, so it is checked against your look".to_string(),
+                required: false,
+            },
+            ActionParam {
+                name: "source_image".to_string(),
+                description: "Optional image_url of an earlier image, or \"last\", to make a variation of it instead of a new picture".to_string(),
+                required: false,
+            },
+            ActionParam {
+                name: "strength".to_string(),
+                description: "How much a variation may change the source, 0.0-1.0 (default 0.6)".to_string(),
+                required: false,
+            },
         ]
     }
 
@@ -324,10 +552,40 @@ impl ActionHandler for GenerateImageAction {
             None => None,
         };
 
-        let result = imagegen
-            .generate(prompt.clone(), provider_id, None, window_size)
-            .await
-            .map_err(|error| ActionError(format!("Image generation failed: {}", error)))?;
+        let selfie = args
+            .get("selfie")
+            .map(|value| value.trim().eq_ignore_ascii_case("true"))
+            .unwrap_or(false);
+
+        let source_image = args
+            .get("source_image")
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty());
+        let strength = args
+            .get("strength")
+            .and_then(|value| value.trim().parse::<f32>().ok());
+
+        let result = if let Some(source) = source_image {
+            imagegen
+                .generate_variation(
+                    source,
+                    Some(prompt.clone()),
+                    strength,
+                    None,
+                    provider_id,
+                    window_size,
+                )
+                .await
+        } else if selfie {
+            imagegen
+                .generate_selfie(prompt.clone(), provider_id, None, window_size)
+                .await
+        } else {
+            imagegen
+                .generate(prompt.clone(), provider_id, None, window_size)
+                .await
+        }
+        .map_err(|error| ActionError(format!("Image generation failed: {}", error)))?;
 
         let _ = ctx.app.emit("imagegen:done", &result);
 
@@ -337,6 +595,7 @@ impl ActionHandler for GenerateImageAction {
                 "image_url": result.image_url,
                 "prompt": result.prompt,
                 "provider_id": result.provider_id,
+                "consistency": result.consistency,
             }),
         ))
     }
@@ -443,6 +702,15 @@ impl ActionHandler for StoreMemoryAction {
         ctx: ActionContext,
     ) -> Result<ActionResult, ActionError> {
         ensure_memory_enabled(&ctx)?;
+        if ctx
+            .app
+            .state::<crate::ai::context::AIOrchestrator>()
+            .is_incognito()
+        {
+            return Err(ActionError(
+                "Privacy mode is on; nothing from this conversation is saved.".into(),
+            ));
+        }
         let fact = args
             .get("fact")
             .ok_or_else(|| ActionError("Missing 'fact' parameter".into()))?;
@@ -527,11 +795,16 @@ impl ActionHandler for ForgetMemoryAction {
 
         if let Some(mem) = memories.first() {
             let content = mem.content.clone();
-            orchestrator
-                .memory_manager
-                .delete_memory(mem.id)
-                .await
-                .map_err(|e| ActionError(format!("Failed to delete memory: {}", e)))?;
+            // Through the trash when available, so the user can undo it.
+            match ctx.app.try_state::<crate::trash::Trash>() {
+                Some(trash) => trash.delete_memory(mem.id).await.map(|_| ()),
+                None => orchestrator
+                    .memory_manager
+                    .delete_memory(mem.id)
+                    .await
+                    .map_err(|e| crate::error::KokoroError::Database(e.to_string())),
+            }
+            .map_err(|e| ActionError(format!("Failed to delete memory: {}", e)))?;
 
             tracing::info!(
                 target: "tools",
@@ -600,17 +873,128 @@ impl ActionHandler for SendNotificationAction {
     }
 }
 
+// ── schedule_reminder ──────────────────────────────────
+
+pub struct ScheduleReminderAction;
+
+#[async_trait]
+impl ActionHandler for ScheduleReminderAction {
+    fn name(&self) -> &str {
+        "schedule_reminder"
+    }
+
+    fn description(&self) -> &str {
+        "Schedule a reminder: you will bring it up proactively at the given time. Use when the user asks to be reminded of something later"
+    }
+
+    fn parameters(&self) -> Vec<ActionParam> {
+        vec![
+            ActionParam {
+                name: "message".to_string(),
+                description: "What to remind the user about".to_string(),
+                required: true,
+            },
+            ActionParam {
+                name: "in_minutes".to_string(),
+                description: "Minutes from now (e.g. 20)".to_string(),
+                required: false,
+            },
+            ActionParam {
+                name: "at".to_string(),
+                description: "Local time of day as HH:MM; the next occurrence is used".to_string(),
+                required: false,
+            },
+        ]
+    }
+
+    fn risk_tags(&self) -> Vec<ActionRiskTag> {
+        vec![ActionRiskTag::Write]
+    }
+
+    async fn execute(
+        &self,
+        args: HashMap<String, String>,
+        ctx: ActionContext,
+    ) -> Result<ActionResult, ActionError> {
+        use crate::ai::scheduler::{NewSchedule, ScheduleAction, ScheduleRule};
+
+        let message = args
+            .get("message")
+            .map(|m| m.trim())
+            .filter(|m| !m.is_empty())
+            .ok_or_else(|| ActionError("Missing 'message' parameter".into()))?;
+
+        let now = chrono::Local::now();
+        let fire_at = if let Some(minutes) = args.get("in_minutes") {
+            let minutes: i64 = minutes
+                .trim()
+                .parse()
+                .ok()
+                .filter(|m| (1..=60 * 24 * 30).contains(m))
+                .ok_or_else(|| ActionError(format!("Invalid 'in_minutes': {}", minutes)))?;
+            now + chrono::Duration::minutes(minutes)
+        } else if let Some(at) = args.get("at") {
+            let time = chrono::NaiveTime::parse_from_str(at.trim(), "%H:%M")
+                .map_err(|_| ActionError(format!("Invalid 'at' (expected HH:MM): {}", at)))?;
+            let mut date = now.date_naive();
+            if time <= now.time() {
+                date += chrono::Duration::days(1);
+            }
+            chrono::TimeZone::from_local_datetime(&chrono::Local, &date.and_time(time))
+                .earliest()
+                .ok_or_else(|| ActionError(format!("'{}' does not exist today", at)))?
+        } else {
+            return Err(ActionError("Provide either 'in_minutes' or 'at'".into()));
+        };
+
+        let orchestrator = ctx.app.state::<crate::ai::context::AIOrchestrator>();
+        let schedule = crate::ai::scheduler::add_schedule(
+            &orchestrator.db,
+            NewSchedule {
+                character_id: Some(ctx.character_id.clone()),
+                label: format!("Reminder: {}", message.chars().take(60).collect::<String>()),
+                rule: ScheduleRule::Once {
+                    at: fire_at.timestamp(),
+                },
+                action: ScheduleAction::Proactive {
+                    instruction: format!(
+                        "Earlier the user asked you to remind them: \"{}\". Remind them now, naturally and briefly.",
+                        message
+                    ),
+                },
+            },
+            now.timestamp(),
+        )
+        .await
+        .map_err(|e| ActionError(format!("Failed to schedule reminder: {}", e)))?;
+
+        let _ = ctx.app.emit("schedules:updated", &schedule.id);
+
+        Ok(ActionResult::ok(format!(
+            "Reminder set for {}: {}",
+            fire_at.format("%Y-%m-%d %H:%M"),
+            message
+        )))
+    }
+}
+
 // ── Factory ────────────────────────────────────────────
 
 /// Register all built-in action handlers into the given registry.
 pub fn register_builtins(registry: &mut super::registry::ActionRegistry) {
     registry.register(GetTimeAction);
     registry.register(CaptureScreenAction);
+    registry.register(AnalyzeImageFileAction);
     registry.register(PlayCueAction);
     registry.register(GenerateImageAction);
     registry.register(SetBackgroundAction);
+    registry.register(SetDesktopWallpaperAction);
     registry.register(SearchMemoryAction);
     registry.register(StoreMemoryAction);
     registry.register(ForgetMemoryAction);
     registry.register(SendNotificationAction);
+    registry.register(ScheduleReminderAction);
+    registry.register(super::plan::RunPlanAction);
+    registry.register(super::code_exec::ExecuteCodeAction);
+    registry.register(super::tables::QueryTableAction);
 }
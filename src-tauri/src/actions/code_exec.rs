@@ -0,0 +1,547 @@
+//! `execute_code`: run a short snippet in a sandbox and hand back what it printed.
+//!
+//! JavaScript runs in a fresh QuickJS runtime per call. It has no host APIs
+//! besides `print`/`console.log`, and is bounded by a memory cap and a
+//! wall-clock deadline. Python needs a container runtime (docker or podman):
+//! the snippet runs in a throwaway container without network, with a
+//! read-only root and memory/CPU/process limits.
+
+use super::registry::{ActionContext, ActionError, ActionHandler, ActionParam, ActionResult};
+use crate::error::KokoroError;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::Manager;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::sync::RwLock;
+
+pub const EXECUTE_CODE_TOOL_NAME: &str = "execute_code";
+
+const MAX_CODE_CHARS: usize = 20_000;
+/// Extra time a container gets to start before the snippet deadline applies.
+const CONTAINER_START_GRACE: Duration = Duration::from_secs(5);
+const JS_STACK_BYTES: usize = 512 * 1024;
+
+const JS_PRELUDE: &str = r#"
+globalThis.__out = [];
+globalThis.__fmt = (v) => {
+    if (typeof v === "string") return v;
+    try {
+        const json = JSON.stringify(v);
+        return json === undefined ? String(v) : json;
+    } catch (_) {
+        return String(v);
+    }
+};
+globalThis.print = (...args) => { __out.push(args.map(__fmt).join(" ")); };
+globalThis.console = { log: print, info: print, warn: print, error: print, debug: print };
+"#;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CodeExecConfig {
+    #[serde(default = "default_timeout_ms")]
+    pub timeout_ms: u64,
+    #[serde(default = "default_memory_limit_mb")]
+    pub memory_limit_mb: u64,
+    /// Output returned to the model is cut to this many characters.
+    #[serde(default = "default_max_output_chars")]
+    pub max_output_chars: usize,
+    /// `docker` or `podman`; Python is unavailable while this is unset.
+    #[serde(default)]
+    pub container_runtime: Option<String>,
+    /// Must already be pulled; the runner never pulls images.
+    #[serde(default = "default_python_image")]
+    pub python_image: String,
+}
+
+fn default_timeout_ms() -> u64 {
+    5_000
+}
+
+fn default_memory_limit_mb() -> u64 {
+    64
+}
+
+fn default_max_output_chars() -> usize {
+    4_000
+}
+
+fn default_python_image() -> String {
+    "python:3.12-alpine".to_string()
+}
+
+impl Default for CodeExecConfig {
+    fn default() -> Self {
+        Self {
+            timeout_ms: default_timeout_ms(),
+            memory_limit_mb: default_memory_limit_mb(),
+            max_output_chars: default_max_output_chars(),
+            container_runtime: None,
+            python_image: default_python_image(),
+        }
+    }
+}
+
+impl CodeExecConfig {
+    pub fn sanitized(mut self) -> Self {
+        self.timeout_ms = self.timeout_ms.clamp(100, 30_000);
+        self.memory_limit_mb = self.memory_limit_mb.clamp(8, 512);
+        self.max_output_chars = self.max_output_chars.clamp(256, 20_000);
+        self.container_runtime = self
+            .container_runtime
+            .map(|runtime| runtime.trim().to_string())
+            .filter(|runtime| !runtime.is_empty());
+        self.python_image = self.python_image.trim().to_string();
+        if self.python_image.is_empty() {
+            self.python_image = default_python_image();
+        }
+        self
+    }
+
+    fn timeout(&self) -> Duration {
+        Duration::from_millis(self.timeout_ms)
+    }
+}
+
+pub fn config_path() -> PathBuf {
+    dirs_next::data_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("com.chyin.kokoro")
+        .join("code_exec.json")
+}
+
+pub fn load_config(path: &Path) -> CodeExecConfig {
+    crate::config::load_json_config::<CodeExecConfig>(path, "CODE_EXEC").sanitized()
+}
+
+pub fn save_config(path: &Path, config: &CodeExecConfig) -> Result<(), KokoroError> {
+    crate::config::save_json_config(path, config, "CODE_EXEC")
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CodeLanguage {
+    JavaScript,
+    Python,
+}
+
+impl CodeLanguage {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "" | "js" | "javascript" => Some(Self::JavaScript),
+            "py" | "python" | "python3" => Some(Self::Python),
+            _ => None,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::JavaScript => "javascript",
+            Self::Python => "python",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct CodeRunOutput {
+    pub stdout: String,
+    /// Value of the last expression (JavaScript only).
+    pub result: Option<String>,
+    pub error: Option<String>,
+    pub timed_out: bool,
+    pub truncated: bool,
+    pub duration_ms: u64,
+}
+
+impl CodeRunOutput {
+    fn truncate(&mut self, max_chars: usize) {
+        let cut = |text: &mut String| {
+            let over = text.chars().count() > max_chars;
+            if over {
+                *text = text.chars().take(max_chars).collect::<String>() + "…";
+            }
+            over
+        };
+        let stdout_cut = cut(&mut self.stdout);
+        let result_cut = self.result.as_mut().is_some_and(cut);
+        let error_cut = self.error.as_mut().is_some_and(cut);
+        self.truncated |= stdout_cut || result_cut || error_cut;
+    }
+
+    /// What the model reads back.
+    fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        if !self.stdout.is_empty() {
+            parts.push(format!("stdout:\n{}", self.stdout));
+        }
+        if let Some(result) = self.result.as_deref() {
+            parts.push(format!("result: {}", result));
+        }
+        if self.timed_out {
+            parts.push("error: time limit exceeded".to_string());
+        } else if let Some(error) = self.error.as_deref() {
+            parts.push(format!("error: {}", error));
+        }
+        if parts.is_empty() {
+            parts.push("(no output)".to_string());
+        }
+        if self.truncated {
+            parts.push("(output truncated)".to_string());
+        }
+        parts.join("\n")
+    }
+}
+
+/// Run `code` in a fresh QuickJS runtime. Blocking; call from a blocking thread.
+pub fn run_javascript(code: &str, config: &CodeExecConfig) -> Result<CodeRunOutput, String> {
+    let started = Instant::now();
+    let runtime =
+        rquickjs::Runtime::new().map_err(|e| format!("Failed to start QuickJS: {}", e))?;
+    runtime.set_memory_limit((config.memory_limit_mb * 1024 * 1024) as usize);
+    runtime.set_max_stack_size(JS_STACK_BYTES);
+    let timed_out = Arc::new(AtomicBool::new(false));
+    let deadline = started + config.timeout();
+    let flag = timed_out.clone();
+    runtime.set_interrupt_handler(Some(Box::new(move || {
+        let expired = Instant::now() >= deadline;
+        if expired {
+            flag.store(true, Ordering::Relaxed);
+        }
+        expired
+    })));
+    let context =
+        rquickjs::Context::full(&runtime).map_err(|e| format!("Failed to start QuickJS: {}", e))?;
+
+    let mut output = context.with(|ctx| {
+        ctx.eval::<(), _>(JS_PRELUDE)
+            .map_err(|e| format!("Failed to prepare sandbox: {}", e))?;
+        let mut output = CodeRunOutput::default();
+        match ctx.eval::<rquickjs::Value, _>(code) {
+            Ok(value) if value.is_undefined() => {}
+            Ok(value) => output.result = js_format(&ctx, value),
+            Err(rquickjs::Error::Exception) => {
+                let exception = ctx.catch();
+                let message = exception
+                    .as_exception()
+                    .and_then(|e| e.message())
+                    .or_else(|| js_format(&ctx, exception))
+                    .unwrap_or_else(|| "uncaught exception".to_string());
+                output.error = Some(message);
+            }
+            Err(e) => output.error = Some(e.to_string()),
+        }
+        output.stdout = ctx
+            .eval::<String, _>("__out.join('\\n')")
+            .unwrap_or_default();
+        Ok::<_, String>(output)
+    })?;
+
+    output.timed_out = timed_out.load(Ordering::Relaxed);
+    output.duration_ms = started.elapsed().as_millis() as u64;
+    output.truncate(config.max_output_chars);
+    Ok(output)
+}
+
+fn js_format<'js>(ctx: &rquickjs::Ctx<'js>, value: rquickjs::Value<'js>) -> Option<String> {
+    let fmt: rquickjs::Function = ctx.globals().get("__fmt").ok()?;
+    fmt.call::<_, String>((value,)).ok()
+}
+
+/// Run `code` with `python3` inside a locked-down, throwaway container.
+pub async fn run_python(code: &str, config: &CodeExecConfig) -> Result<CodeRunOutput, String> {
+    let runtime = config.container_runtime.as_deref().ok_or_else(|| {
+        "Python needs a container runtime (docker or podman); set one in the code execution settings"
+            .to_string()
+    })?;
+    let started = Instant::now();
+    let name = format!("kokoro-exec-{}", uuid::Uuid::new_v4());
+    let memory = format!("{}m", config.memory_limit_mb);
+
+    let mut command = tokio::process::Command::new(runtime);
+    command
+        .args(["run", "--rm", "-i", "--name", &name, "--pull", "never"])
+        .args([
+            "--network",
+            "none",
+            "--read-only",
+            "--tmpfs",
+            "/tmp:size=16m",
+        ])
+        .args(["--memory", &memory, "--memory-swap", &memory])
+        .args(["--cpus", "1", "--pids-limit", "64"])
+        .args(["--security-opt", "no-new-privileges", "--cap-drop", "ALL"])
+        .args([config.python_image.as_str(), "python3", "-I", "-"])
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .kill_on_drop(true);
+    #[cfg(target_os = "windows")]
+    {
+        // CREATE_NO_WINDOW: don't flash a console for every run
+        command.creation_flags(0x08000000);
+    }
+
+    let mut child = command
+        .spawn()
+        .map_err(|e| format!("Failed to start {}: {}", runtime, e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(code.as_bytes())
+            .await
+            .map_err(|e| format!("Failed to send code to the container: {}", e))?;
+    }
+
+    let limit = config.timeout() + CONTAINER_START_GRACE;
+    // Enough bytes for `max_output_chars` of any UTF-8, plus one to notice more.
+    let cap = config.max_output_chars.saturating_mul(4).saturating_add(1);
+    let (stdout, stderr) = (child.stdout.take(), child.stderr.take());
+    let run = async {
+        tokio::try_join!(
+            read_capped(stdout, cap),
+            read_capped(stderr, cap),
+            child.wait()
+        )
+    };
+    let mut output = CodeRunOutput::default();
+    match tokio::time::timeout(limit, run).await {
+        Ok(Ok(((stdout, stdout_cut), (stderr, stderr_cut), status))) => {
+            output.stdout = String::from_utf8_lossy(&stdout).trim_end().to_string();
+            output.truncated = stdout_cut || stderr_cut;
+            let stderr = String::from_utf8_lossy(&stderr).trim().to_string();
+            if !status.success() || !stderr.is_empty() {
+                output.error = Some(if stderr.is_empty() {
+                    format!("exited with {}", status)
+                } else {
+                    stderr
+                });
+            }
+        }
+        Ok(Err(e)) => return Err(format!("Container run failed: {}", e)),
+        Err(_) => {
+            // Killing the client doesn't stop the container; kill it by name.
+            let _ = tokio::process::Command::new(runtime)
+                .args(["kill", &name])
+                .output()
+                .await;
+            output.timed_out = true;
+        }
+    }
+
+    output.duration_ms = started.elapsed().as_millis() as u64;
+    output.truncate(config.max_output_chars);
+    Ok(output)
+}
+
+/// Read `reader` to the end but keep only its first `cap` bytes, so a snippet
+/// printing in a loop can't fill memory. The rest is drained, not left in the
+/// pipe, so the process doesn't block on a full pipe. Returns whether
+/// anything was dropped.
+async fn read_capped(
+    reader: Option<impl tokio::io::AsyncRead + Unpin>,
+    cap: usize,
+) -> std::io::Result<(Vec<u8>, bool)> {
+    let mut kept = Vec::new();
+    let mut dropped = false;
+    let Some(mut reader) = reader else {
+        return Ok((kept, dropped));
+    };
+    let mut chunk = [0u8; 8192];
+    loop {
+        let read = reader.read(&mut chunk).await?;
+        if read == 0 {
+            return Ok((kept, dropped));
+        }
+        let room = cap.saturating_sub(kept.len()).min(read);
+        kept.extend_from_slice(&chunk[..room]);
+        dropped |= room < read;
+    }
+}
+
+// ── execute_code ───────────────────────────────────────
+
+pub struct ExecuteCodeAction;
+
+#[async_trait]
+impl ActionHandler for ExecuteCodeAction {
+    fn name(&self) -> &str {
+        EXECUTE_CODE_TOOL_NAME
+    }
+
+    fn description(&self) -> &str {
+        "Run a short JavaScript or Python snippet in an isolated sandbox and get back what it printed. Use it for exact arithmetic, date math, unit conversions and data transformations instead of guessing. There is no file system or network access; print() the values you need."
+    }
+
+    fn parameters(&self) -> Vec<ActionParam> {
+        vec![
+            ActionParam {
+                name: "code".to_string(),
+                description: "Source code to run. In JavaScript the value of the last expression is also returned.".to_string(),
+                required: true,
+            },
+            ActionParam {
+                name: "language".to_string(),
+                description: "\"javascript\" (default) or \"python\"".to_string(),
+                required: false,
+            },
+        ]
+    }
+
+    fn needs_feedback(&self) -> bool {
+        true
+    }
+
+    async fn execute(
+        &self,
+        args: HashMap<String, String>,
+        ctx: ActionContext,
+    ) -> Result<ActionResult, ActionError> {
+        let code = args
+            .get("code")
+            .map(|value| value.trim())
+            .filter(|value| !value.is_empty())
+            .ok_or_else(|| ActionError("Missing 'code' parameter".into()))?
+            .to_string();
+        if code.chars().count() > MAX_CODE_CHARS {
+            return Err(ActionError(format!(
+                "Code is too long (limit {} characters)",
+                MAX_CODE_CHARS
+            )));
+        }
+        let language_arg = args.get("language").map(String::as_str).unwrap_or("");
+        let language = CodeLanguage::parse(language_arg)
+            .ok_or_else(|| ActionError(format!("Unsupported language '{}'", language_arg)))?;
+
+        let config = match ctx.app.try_state::<Arc<RwLock<CodeExecConfig>>>() {
+            Some(state) => state.read().await.clone(),
+            None => CodeExecConfig::default(),
+        };
+
+        let output = match language {
+            CodeLanguage::JavaScript => {
+                let config = config.clone();
+                tokio::task::spawn_blocking(move || run_javascript(&code, &config))
+                    .await
+                    .map_err(|e| ActionError(format!("Sandbox task failed: {}", e)))?
+            }
+            CodeLanguage::Python => run_python(&code, &config).await,
+        }
+        .map_err(ActionError)?;
+
+        tracing::info!(
+            target: "tools",
+            "[execute_code] {} run finished in {} ms (timed_out={}, error={})",
+            language.as_str(),
+            output.duration_ms,
+            output.timed_out,
+            output.error.is_some()
+        );
+
+        let data = serde_json::json!({
+            "language": language.as_str(),
+            "output": output,
+        });
+        // Errors inside the snippet are results too; the model reads and fixes them.
+        Ok(ActionResult::ok_with_data(output.summary(), data))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> CodeExecConfig {
+        CodeExecConfig {
+            timeout_ms: 500,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn javascript_returns_prints_and_last_value() {
+        let output = run_javascript(
+            "const xs = [1, 2, 3]; print('sum', xs.reduce((a, b) => a + b)); console.log({ ok: true }); 0.1 * 3",
+            &config(),
+        )
+        .unwrap();
+        assert_eq!(output.stdout, "sum 6\n{\"ok\":true}");
+        assert_eq!(output.result.as_deref(), Some("0.30000000000000004"));
+        assert!(output.error.is_none());
+        assert!(!output.timed_out);
+    }
+
+    #[test]
+    fn javascript_reports_errors_and_keeps_earlier_output() {
+        let output = run_javascript("print('before'); null.boom;", &config()).unwrap();
+        assert_eq!(output.stdout, "before");
+        assert!(output.error.unwrap().contains("null"));
+    }
+
+    #[test]
+    fn javascript_is_cut_off_by_deadline_and_memory_cap() {
+        let output = run_javascript("while (true) {}", &config()).unwrap();
+        assert!(output.timed_out);
+
+        let output = run_javascript(
+            "const a = []; while (true) a.push(new Array(1e5).fill(1));",
+            &config(),
+        )
+        .unwrap();
+        assert!(output.error.is_some());
+    }
+
+    #[test]
+    fn javascript_has_no_host_access_and_output_is_truncated() {
+        let output = run_javascript("typeof require + ' ' + typeof fetch", &config()).unwrap();
+        assert_eq!(output.result.as_deref(), Some("undefined undefined"));
+
+        let config = CodeExecConfig {
+            max_output_chars: 0,
+            ..config()
+        }
+        .sanitized();
+        let output = run_javascript("print('x'.repeat(1000))", &config).unwrap();
+        assert!(output.truncated);
+        assert_eq!(output.stdout.chars().count(), 257);
+        let output = run_javascript("'x'.repeat(1000)", &config).unwrap();
+        assert!(output.truncated);
+        assert_eq!(output.result.unwrap().chars().count(), 257);
+    }
+
+    #[test]
+    fn process_output_is_read_up_to_the_cap() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        let big = vec![b'x'; 100_000];
+        let (kept, dropped) = runtime
+            .block_on(read_capped(Some(big.as_slice()), 1025))
+            .unwrap();
+        assert_eq!(kept.len(), 1025);
+        assert!(dropped);
+        let (kept, dropped) = runtime
+            .block_on(read_capped(Some(&b"small"[..]), 1025))
+            .unwrap();
+        assert_eq!(kept, b"small");
+        assert!(!dropped);
+        let (kept, dropped) = runtime.block_on(read_capped(None::<&[u8]>, 1025)).unwrap();
+        assert!(kept.is_empty() && !dropped);
+    }
+
+    #[test]
+    fn languages_parse_and_python_requires_runtime() {
+        assert_eq!(CodeLanguage::parse(""), Some(CodeLanguage::JavaScript));
+        assert_eq!(CodeLanguage::parse("Py"), Some(CodeLanguage::Python));
+        assert_eq!(CodeLanguage::parse("ruby"), None);
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        let error = runtime
+            .block_on(run_python("print(1)", &CodeExecConfig::default()))
+            .unwrap_err();
+        assert!(error.contains("container runtime"));
+    }
+}
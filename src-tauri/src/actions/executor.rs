@@ -9,6 +9,8 @@ use crate::hooks::types::HookModifyPolicy;
 use crate::hooks::{
     ActionHookPayload, BeforeActionArgsPayload, HookEvent, HookOutcome, HookPayload, HookRuntime,
 };
+use crate::llm::messages::{assistant_tool_calls_message, tool_result_message};
+use async_openai::types::chat::ChatCompletionRequestMessage;
 use std::collections::HashMap;
 use std::sync::Arc;
 use tauri::Manager;
@@ -821,6 +823,46 @@ mod tests {
         assert!(reason.starts_with("Denied by fail-closed policy:"));
     }
 
+    #[test]
+    fn native_tool_continuation_messages_pairs_calls_with_results() {
+        let outcome = ToolExecutionOutcome {
+            invocation: sample_invocation(),
+            action: Some(sample_action()),
+            result: Err("boom".to_string()),
+            needs_feedback: true,
+            permission_decision: None,
+        };
+
+        let messages = native_tool_continuation_messages("thinking", &[outcome])
+            .expect("native call should produce continuation messages");
+
+        assert_eq!(messages.len(), 2);
+        assert!(matches!(
+            &messages[0],
+            ChatCompletionRequestMessage::Assistant(message)
+                if message.tool_calls.as_ref().map(|calls| calls.len()) == Some(1)
+        ));
+        assert!(matches!(
+            &messages[1],
+            ChatCompletionRequestMessage::Tool(message) if message.tool_call_id == "tool-call-1"
+        ));
+    }
+
+    #[test]
+    fn native_tool_continuation_messages_skips_textual_calls() {
+        let mut invocation = sample_invocation();
+        invocation.tool_call_id = None;
+        let outcome = ToolExecutionOutcome {
+            invocation,
+            action: None,
+            result: Err("boom".to_string()),
+            needs_feedback: true,
+            permission_decision: None,
+        };
+
+        assert!(native_tool_continuation_messages("", &[outcome]).is_none());
+    }
+
     #[test]
     fn policy_denial_reason_uses_stable_prefix() {
         let reason =
@@ -850,6 +892,7 @@ impl ToolExecutionOutcome {
         self.action.as_ref().map(|action| match action.source {
             crate::actions::registry::ActionSource::Builtin => "builtin",
             crate::actions::registry::ActionSource::Mcp => "mcp",
+            crate::actions::registry::ActionSource::Mod => "mod",
         })
     }
 
@@ -893,6 +936,52 @@ impl ToolExecutionOutcome {
     }
 }
 
+/// Build the assistant tool-call message followed by one tool-result message
+/// per native call, so surfaces without persisted tool history can continue a
+/// native tool round. Returns `None` when no outcome came from a native call.
+pub fn native_tool_continuation_messages(
+    text: &str,
+    outcomes: &[ToolExecutionOutcome],
+) -> Option<Vec<ChatCompletionRequestMessage>> {
+    let native = outcomes
+        .iter()
+        .filter_map(|outcome| {
+            outcome
+                .invocation
+                .tool_call_id
+                .as_ref()
+                .map(|id| (id, outcome))
+        })
+        .collect::<Vec<_>>();
+    if native.is_empty() {
+        return None;
+    }
+
+    let calls = native
+        .iter()
+        .map(|(id, outcome)| {
+            (
+                (*id).clone(),
+                outcome.tool_name().to_string(),
+                serde_json::to_string(&outcome.invocation.args)
+                    .unwrap_or_else(|_| "{}".to_string()),
+            )
+        })
+        .collect::<Vec<_>>();
+    let mut messages = vec![assistant_tool_calls_message(
+        (!text.is_empty()).then(|| text.to_string()),
+        calls,
+    )];
+    messages.extend(native.iter().map(|(id, outcome)| {
+        let content = match &outcome.result {
+            Ok(result) => result.message.clone(),
+            Err(error) => format!("Error: {}", error),
+        };
+        tool_result_message((*id).clone(), content)
+    }));
+    Some(messages)
+}
+
 pub(crate) fn tool_metadata_value(
     outcome: &ToolExecutionOutcome,
     tool_call_id: &str,
@@ -958,6 +1047,11 @@ pub async fn execute_tool_calls(
     let hook_runtime = app.try_state::<HookRuntime>();
 
     for tool_call in tool_calls {
+        let _activity = crate::ai::companion_state::enter(
+            app,
+            crate::ai::companion_state::CompanionActivity::ExecutingTool,
+            Some(tool_call.name.clone()),
+        );
         let gate = if let Some(hooks) = hook_runtime.as_ref() {
             hooks
                 .emit_action_gate(
@@ -1,12 +1,17 @@
 pub mod audit;
 pub mod builtin;
+pub mod code_exec;
 pub mod executor;
 pub mod permission;
+pub mod plan;
 pub mod registry;
+pub mod tables;
 pub mod tool_settings;
 
 pub use audit::{build_tool_audit_event, ToolAuditDecision, ToolAuditEvent, ToolAuditInput};
-pub use executor::{execute_tool_calls, ToolExecutionOutcome, ToolInvocation};
+pub use executor::{
+    execute_tool_calls, native_tool_continuation_messages, ToolExecutionOutcome, ToolInvocation,
+};
 pub use permission::{evaluate_permission_decision, PermissionDecision};
 pub use registry::{
     builtin_tool_id, mcp_tool_id, ActionContext, ActionInfo, ActionRegistry, ActionResult,
@@ -0,0 +1,578 @@
+//! Multi-step tool plans.
+//!
+//! Instead of calling tools one round at a time, the model can submit a whole
+//! plan through `run_plan` (search → fetch → save note…). The chat loop then
+//! executes the steps in order, asks for confirmation where the plan policy
+//! says so, and feeds one combined result back. Plans can be aborted between
+//! steps; the model still gets the results collected so far.
+
+use super::registry::{
+    ActionContext, ActionError, ActionHandler, ActionInfo, ActionParam, ActionPermissionLevel,
+    ActionResult, ActionRiskTag,
+};
+use crate::error::KokoroError;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use tokio::sync::{oneshot, Mutex, Notify};
+
+pub const PLAN_TOOL_NAME: &str = "run_plan";
+pub const CHAT_PLAN_EVENT: &str = "chat-plan";
+
+const MAX_PLAN_STEPS_LIMIT: usize = 16;
+/// Step results quoted in the summary are cut to this many characters.
+const RESULT_PREVIEW_CHARS: usize = 600;
+
+/// When the user is asked before a step runs.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PlanConfirmation {
+    /// Only where the tool policy already requires approval.
+    Never,
+    /// Also before steps that write, reach external services or are elevated.
+    #[default]
+    Risky,
+    /// Before every step.
+    Always,
+}
+
+/// Plan settings. Whether plans are offered at all follows the `run_plan`
+/// toggle in the tool settings.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PlanConfig {
+    #[serde(default)]
+    pub confirmation: PlanConfirmation,
+    #[serde(default = "default_max_steps")]
+    pub max_steps: usize,
+}
+
+fn default_max_steps() -> usize {
+    8
+}
+
+impl Default for PlanConfig {
+    fn default() -> Self {
+        Self {
+            confirmation: PlanConfirmation::default(),
+            max_steps: default_max_steps(),
+        }
+    }
+}
+
+impl PlanConfig {
+    pub fn sanitized(mut self) -> Self {
+        self.max_steps = self.max_steps.clamp(1, MAX_PLAN_STEPS_LIMIT);
+        self
+    }
+
+    /// Whether a step using `action` waits for the user before running.
+    pub fn needs_confirmation(&self, action: &ActionInfo) -> bool {
+        match self.confirmation {
+            PlanConfirmation::Never => false,
+            PlanConfirmation::Always => true,
+            PlanConfirmation::Risky => {
+                action.permission_level == ActionPermissionLevel::Elevated
+                    || action.risk_tags.iter().any(|tag| {
+                        matches!(
+                            tag,
+                            ActionRiskTag::Write
+                                | ActionRiskTag::External
+                                | ActionRiskTag::Sensitive
+                        )
+                    })
+            }
+        }
+    }
+}
+
+pub fn config_path() -> PathBuf {
+    dirs_next::data_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("com.chyin.kokoro")
+        .join("tool_plan.json")
+}
+
+pub fn load_config(path: &Path) -> PlanConfig {
+    crate::config::load_json_config::<PlanConfig>(path, "PLAN").sanitized()
+}
+
+pub fn save_config(path: &Path, config: &PlanConfig) -> Result<(), KokoroError> {
+    crate::config::save_json_config(path, config, "PLAN")
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlanStep {
+    pub tool: String,
+    pub args: HashMap<String, String>,
+    pub why: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToolPlan {
+    pub goal: String,
+    pub steps: Vec<PlanStep>,
+}
+
+#[derive(Deserialize)]
+struct RawStep {
+    tool: String,
+    #[serde(default)]
+    args: HashMap<String, serde_json::Value>,
+    #[serde(default)]
+    why: String,
+}
+
+/// Parse `run_plan` arguments. `steps` is a JSON array or one JSON object per
+/// line — the latter survives the bracket-delimited text tool syntax.
+pub fn parse_plan(args: &HashMap<String, String>, max_steps: usize) -> Result<ToolPlan, String> {
+    let raw = args
+        .get("steps")
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .ok_or("Missing 'steps' parameter")?;
+    let steps: Vec<RawStep> = if raw.starts_with('[') {
+        serde_json::from_str(raw).map_err(|e| format!("Invalid 'steps': {}", e))?
+    } else {
+        serde_json::Deserializer::from_str(raw)
+            .into_iter::<RawStep>()
+            .collect::<Result<_, _>>()
+            .map_err(|e| format!("Invalid 'steps': {}", e))?
+    };
+    if steps.is_empty() {
+        return Err("A plan needs at least one step".to_string());
+    }
+    if steps.len() > max_steps {
+        return Err(format!(
+            "Plan has {} steps; at most {} are allowed",
+            steps.len(),
+            max_steps
+        ));
+    }
+
+    let steps = steps
+        .into_iter()
+        .enumerate()
+        .map(|(index, step)| {
+            let tool = step.tool.trim().to_string();
+            if tool.is_empty() {
+                return Err(format!("Step {} has no tool", index + 1));
+            }
+            if is_plan_tool(&tool) {
+                return Err("Plans cannot contain another plan".to_string());
+            }
+            let args = step
+                .args
+                .into_iter()
+                .map(|(key, value)| {
+                    let value = match value {
+                        serde_json::Value::String(s) => s,
+                        other => other.to_string(),
+                    };
+                    (key, value)
+                })
+                .collect();
+            Ok(PlanStep {
+                tool,
+                args,
+                why: step.why.trim().to_string(),
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(ToolPlan {
+        goal: args
+            .get("goal")
+            .map(|g| g.trim().to_string())
+            .unwrap_or_default(),
+        steps,
+    })
+}
+
+pub fn is_plan_tool(name: &str) -> bool {
+    name == PLAN_TOOL_NAME || name == super::builtin_tool_id(PLAN_TOOL_NAME)
+}
+
+/// Replace `{{stepN}}` with the result of step N (1-based). References to
+/// steps without a result are left alone.
+pub fn resolve_step_refs(
+    args: &HashMap<String, String>,
+    results: &[Option<String>],
+) -> HashMap<String, String> {
+    args.iter()
+        .map(|(key, value)| {
+            let mut value = value.clone();
+            for (index, result) in results.iter().enumerate() {
+                if let Some(result) = result {
+                    value = value.replace(&format!("{{{{step{}}}}}", index + 1), result);
+                }
+            }
+            (key.clone(), value)
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PlanStepStatus {
+    Pending,
+    AwaitingConfirmation,
+    Running,
+    Done,
+    Failed,
+    Skipped,
+    Aborted,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PlanStatus {
+    Running,
+    Completed,
+    Failed,
+    Aborted,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PlanStepView {
+    pub index: usize,
+    pub tool: String,
+    pub why: String,
+    pub status: PlanStepStatus,
+    pub result: Option<String>,
+}
+
+/// Payload of `chat-plan`, sent whenever a plan or one of its steps changes.
+#[derive(Debug, Clone, Serialize)]
+pub struct ChatPlanEvent {
+    pub turn_id: String,
+    pub plan_id: String,
+    pub goal: String,
+    pub status: PlanStatus,
+    pub steps: Vec<PlanStepView>,
+}
+
+impl ChatPlanEvent {
+    pub fn new(turn_id: &str, plan_id: &str, plan: &ToolPlan) -> Self {
+        Self {
+            turn_id: turn_id.to_string(),
+            plan_id: plan_id.to_string(),
+            goal: plan.goal.clone(),
+            status: PlanStatus::Running,
+            steps: plan
+                .steps
+                .iter()
+                .enumerate()
+                .map(|(index, step)| PlanStepView {
+                    index,
+                    tool: step.tool.clone(),
+                    why: step.why.clone(),
+                    status: PlanStepStatus::Pending,
+                    result: None,
+                })
+                .collect(),
+        }
+    }
+
+    pub fn set_step(&mut self, index: usize, status: PlanStepStatus, result: Option<String>) {
+        if let Some(step) = self.steps.get_mut(index) {
+            step.status = status;
+            if result.is_some() {
+                step.result = result;
+            }
+        }
+    }
+
+    /// Mark every step that never started.
+    pub fn close_remaining(&mut self, status: PlanStepStatus, note: &str) {
+        for step in &mut self.steps {
+            if matches!(
+                step.status,
+                PlanStepStatus::Pending | PlanStepStatus::AwaitingConfirmation
+            ) {
+                step.status = status;
+                step.result = Some(note.to_string());
+            }
+        }
+    }
+
+    /// Result handed back to the model for the whole plan.
+    pub fn summary(&self) -> String {
+        let done = self
+            .steps
+            .iter()
+            .filter(|s| s.status == PlanStepStatus::Done)
+            .count();
+        let mut lines = vec![format!(
+            "Plan{} — {} ({} of {} steps done):",
+            if self.goal.is_empty() {
+                String::new()
+            } else {
+                format!(" \"{}\"", self.goal)
+            },
+            match self.status {
+                PlanStatus::Running => "running",
+                PlanStatus::Completed => "completed",
+                PlanStatus::Failed => "stopped after a failed step",
+                PlanStatus::Aborted => "aborted by the user",
+            },
+            done,
+            self.steps.len()
+        )];
+        for step in &self.steps {
+            let status = match step.status {
+                PlanStepStatus::Done => "ok",
+                PlanStepStatus::Failed => "failed",
+                PlanStepStatus::Skipped => "skipped",
+                PlanStepStatus::Aborted => "not run",
+                PlanStepStatus::Pending
+                | PlanStepStatus::AwaitingConfirmation
+                | PlanStepStatus::Running => "pending",
+            };
+            let result = step
+                .result
+                .as_deref()
+                .map(|r| {
+                    let mut preview: String = r.chars().take(RESULT_PREVIEW_CHARS).collect();
+                    if r.chars().count() > RESULT_PREVIEW_CHARS {
+                        preview.push('…');
+                    }
+                    format!(": {}", preview)
+                })
+                .unwrap_or_default();
+            lines.push(format!(
+                "{}. {} [{}]{}",
+                step.index + 1,
+                step.tool,
+                status,
+                result
+            ));
+        }
+        lines.join("\n")
+    }
+}
+
+/// Confirmations and abort requests for plans that are running.
+#[derive(Default)]
+pub struct PlanControlState {
+    confirmations: Mutex<HashMap<(String, usize), oneshot::Sender<bool>>>,
+    aborted: Mutex<HashSet<String>>,
+    wakeup: Notify,
+}
+
+impl PlanControlState {
+    /// Register a confirmation for a step; resolves with the user's answer.
+    pub async fn request_confirmation(
+        &self,
+        plan_id: &str,
+        step_index: usize,
+    ) -> oneshot::Receiver<bool> {
+        let (tx, rx) = oneshot::channel();
+        self.confirmations
+            .lock()
+            .await
+            .insert((plan_id.to_string(), step_index), tx);
+        rx
+    }
+
+    pub async fn confirm(
+        &self,
+        plan_id: &str,
+        step_index: usize,
+        approve: bool,
+    ) -> Result<(), KokoroError> {
+        let sender = self
+            .confirmations
+            .lock()
+            .await
+            .remove(&(plan_id.to_string(), step_index))
+            .ok_or_else(|| {
+                KokoroError::Validation(format!(
+                    "Step {} of plan '{}' is not waiting for confirmation",
+                    step_index + 1,
+                    plan_id
+                ))
+            })?;
+        sender.send(approve).map_err(|_| {
+            KokoroError::Validation(format!("Plan '{}' is no longer running", plan_id))
+        })
+    }
+
+    /// Stop a plan before its next step. The step already running finishes.
+    pub async fn abort(&self, plan_id: &str) {
+        self.aborted.lock().await.insert(plan_id.to_string());
+        self.confirmations
+            .lock()
+            .await
+            .retain(|(id, _), _| id != plan_id);
+        self.wakeup.notify_waiters();
+    }
+
+    pub async fn is_aborted(&self, plan_id: &str) -> bool {
+        self.aborted.lock().await.contains(plan_id)
+    }
+
+    /// Resolves once `plan_id` is aborted.
+    pub async fn wait_aborted(&self, plan_id: &str) {
+        loop {
+            let notified = self.wakeup.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+            if self.is_aborted(plan_id).await {
+                return;
+            }
+            notified.await;
+        }
+    }
+
+    pub async fn finish(&self, plan_id: &str) {
+        self.aborted.lock().await.remove(plan_id);
+        self.confirmations
+            .lock()
+            .await
+            .retain(|(id, _), _| id != plan_id);
+    }
+}
+
+// ── run_plan ───────────────────────────────────────────
+
+/// Registry entry for `run_plan`. The chat loop intercepts the call and runs
+/// the steps itself, so confirmations and aborts reach the chat UI.
+pub struct RunPlanAction;
+
+#[async_trait]
+impl ActionHandler for RunPlanAction {
+    fn name(&self) -> &str {
+        PLAN_TOOL_NAME
+    }
+
+    fn description(&self) -> &str {
+        "Run several tools as one plan when a task needs a fixed sequence of steps (e.g. search, then fetch, then save a note). Steps run in order and all results come back together; the user may confirm or abort steps"
+    }
+
+    fn parameters(&self) -> Vec<ActionParam> {
+        vec![
+            ActionParam {
+                name: "goal".to_string(),
+                description: "One short sentence describing what the plan achieves".to_string(),
+                required: false,
+            },
+            ActionParam {
+                name: "steps".to_string(),
+                description: "One JSON object per line: {\"tool\": \"<canonical tool id>\", \"args\": {...}, \"why\": \"<reason>\"}. Write {{step1}}, {{step2}}… in args to use an earlier step's result".to_string(),
+                required: true,
+            },
+        ]
+    }
+
+    fn needs_feedback(&self) -> bool {
+        true
+    }
+
+    async fn execute(
+        &self,
+        _args: HashMap<String, String>,
+        _ctx: ActionContext,
+    ) -> Result<ActionResult, ActionError> {
+        Err(ActionError(
+            "run_plan is only available in chat replies".to_string(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actions::ActionSource;
+
+    fn args(steps: &str) -> HashMap<String, String> {
+        HashMap::from([
+            ("goal".to_string(), " Save today's news ".to_string()),
+            ("steps".to_string(), steps.to_string()),
+        ])
+    }
+
+    #[test]
+    fn parses_json_lines_and_arrays() {
+        let lines = r#"{"tool": "mcp__web__search", "args": {"query": "rust 2024", "limit": 3}, "why": "find it"}
+{"tool": "builtin__store_memory", "args": {"content": "{{step1}}"}}"#;
+        let plan = parse_plan(&args(lines), 8).unwrap();
+        assert_eq!(plan.goal, "Save today's news");
+        assert_eq!(plan.steps.len(), 2);
+        assert_eq!(plan.steps[0].args["limit"], "3");
+        assert_eq!(plan.steps[0].why, "find it");
+
+        let array = r#"[{"tool": "get_time"}, {"tool": "search_memory", "args": {"query": "x"}}]"#;
+        assert_eq!(parse_plan(&args(array), 8).unwrap().steps.len(), 2);
+    }
+
+    #[test]
+    fn rejects_bad_plans() {
+        assert!(parse_plan(&HashMap::new(), 8).is_err());
+        assert!(parse_plan(&args("not json"), 8).is_err());
+        assert!(parse_plan(&args("[]"), 8).is_err());
+        assert!(parse_plan(&args(r#"{"tool": " "}"#), 8).is_err());
+        assert!(parse_plan(&args(r#"{"tool": "builtin__run_plan"}"#), 8).is_err());
+        let three = r#"{"tool": "a"}
+{"tool": "b"}
+{"tool": "c"}"#;
+        assert!(parse_plan(&args(three), 2).is_err());
+    }
+
+    #[test]
+    fn substitutes_earlier_results() {
+        let step_args = HashMap::from([(
+            "content".to_string(),
+            "Summary: {{step1}} / {{step3}}".to_string(),
+        )]);
+        let resolved = resolve_step_refs(&step_args, &[Some("found".to_string()), None]);
+        assert_eq!(resolved["content"], "Summary: found / {{step3}}");
+    }
+
+    #[test]
+    fn confirmation_policy_follows_risk() {
+        let mut action = ActionInfo {
+            id: "builtin__search_memory".to_string(),
+            name: "search_memory".to_string(),
+            source: ActionSource::Builtin,
+            server_name: None,
+            description: String::new(),
+            parameters: Vec::new(),
+            needs_feedback: true,
+            risk_tags: vec![ActionRiskTag::Read],
+            permission_level: ActionPermissionLevel::Safe,
+        };
+        let mut config = PlanConfig::default();
+        assert!(!config.needs_confirmation(&action));
+        action.risk_tags.push(ActionRiskTag::External);
+        assert!(config.needs_confirmation(&action));
+        config.confirmation = PlanConfirmation::Never;
+        assert!(!config.needs_confirmation(&action));
+        config.confirmation = PlanConfirmation::Always;
+        action.risk_tags = vec![ActionRiskTag::Read];
+        assert!(config.needs_confirmation(&action));
+    }
+
+    #[test]
+    fn summary_reports_each_step() {
+        let plan = parse_plan(
+            &args(
+                r#"{"tool": "search"}
+{"tool": "fetch"}
+{"tool": "save"}"#,
+            ),
+            8,
+        )
+        .unwrap();
+        let mut event = ChatPlanEvent::new("turn", "plan", &plan);
+        event.set_step(0, PlanStepStatus::Done, Some("3 links".to_string()));
+        event.set_step(1, PlanStepStatus::Skipped, Some("declined".to_string()));
+        event.close_remaining(PlanStepStatus::Aborted, "plan aborted");
+        event.status = PlanStatus::Aborted;
+        let summary = event.summary();
+        assert!(summary.starts_with("Plan \"Save today's news\" — aborted by the user (1 of 3"));
+        assert!(summary.contains("1. search [ok]: 3 links"));
+        assert!(summary.contains("2. fetch [skipped]: declined"));
+        assert!(summary.contains("3. save [not run]: plan aborted"));
+    }
+}
@@ -2,7 +2,8 @@
 // Reason: 该文件同时承载 action 元数据、LLM prompt 生成与执行 handler trait；当前阶段只在现有中心点上最小增补 metadata。
 //! Tool Registry — core framework for tool calling.
 //!
-//! Provides a registry of actions that the LLM can invoke via `[TOOL_CALL:name|args]` tags.
+//! Provides a registry of actions that the LLM can invoke through native function calling,
+//! or via `[TOOL_CALL:name|args]` tags for providers without it.
 //! Actions are registered at startup and can be invoked by the chat pipeline.
 
 use crate::actions::tool_settings::ToolSettings;
@@ -22,6 +23,42 @@ pub struct ActionParam {
     pub required: bool,
 }
 
+impl ActionParam {
+    /// Parameters from a JSON Schema object's `properties` and `required`.
+    pub fn from_json_schema(schema: &serde_json::Value) -> Vec<ActionParam> {
+        let properties = match schema.get("properties").and_then(|p| p.as_object()) {
+            Some(p) => p,
+            None => return Vec::new(),
+        };
+
+        let required: Vec<String> = schema
+            .get("required")
+            .and_then(|r| r.as_array())
+            .map(|arr| {
+                arr.iter()
+                    .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        properties
+            .iter()
+            .map(|(name, prop)| {
+                let description = prop
+                    .get("description")
+                    .and_then(|d| d.as_str())
+                    .unwrap_or("")
+                    .to_string();
+                ActionParam {
+                    name: name.clone(),
+                    description,
+                    required: required.contains(name),
+                }
+            })
+            .collect()
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ActionResult {
     pub success: bool,
@@ -80,6 +117,8 @@ pub struct ActionContext {
 pub enum ActionSource {
     Builtin,
     Mcp,
+    /// Registered by the running mod through `Kokoro.registerTool`.
+    Mod,
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
@@ -104,6 +143,7 @@ pub struct ActionInfo {
     pub id: String,
     pub name: String,
     pub source: ActionSource,
+    /// MCP server name, or the mod id for mod tools.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub server_name: Option<String>,
     pub description: String,
@@ -161,6 +201,7 @@ pub struct ActionRegistry {
     entries_by_id: HashMap<String, ActionEntry>,
     alias_to_ids: HashMap<String, Vec<String>>,
     mcp_tool_ids: HashSet<String>,
+    mod_tool_ids: HashSet<String>,
 }
 
 const MEMORY_ACTIONS: &[&str] = &["search_memory", "store_memory", "forget_memory"];
@@ -182,6 +223,14 @@ pub fn mcp_tool_id(server_name: &str, tool_name: &str) -> String {
     )
 }
 
+pub fn mod_tool_id(mod_id: &str, tool_name: &str) -> String {
+    format!(
+        "mod__{}__{}",
+        encode_tool_id_segment(mod_id),
+        encode_tool_id_segment(tool_name)
+    )
+}
+
 impl Default for ActionRegistry {
     fn default() -> Self {
         Self::new()
@@ -194,6 +243,7 @@ impl ActionRegistry {
             entries_by_id: HashMap::new(),
             alias_to_ids: HashMap::new(),
             mcp_tool_ids: HashSet::new(),
+            mod_tool_ids: HashSet::new(),
         }
     }
 
@@ -209,6 +259,10 @@ impl ActionRegistry {
                 let server_name = server_name.as_deref().unwrap_or_default();
                 mcp_tool_id(server_name, &name)
             }
+            ActionSource::Mod => {
+                let mod_id = server_name.as_deref().unwrap_or_default();
+                mod_tool_id(mod_id, &name)
+            }
         };
 
         ActionInfo {
@@ -240,8 +294,14 @@ impl ActionRegistry {
     fn insert_entry(&mut self, info: ActionInfo, handler: Arc<dyn ActionHandler>) {
         if let Some(old_entry) = self.entries_by_id.remove(&info.id) {
             self.remove_alias_mapping(&old_entry.info);
-            if old_entry.info.source == ActionSource::Mcp {
-                self.mcp_tool_ids.remove(&old_entry.info.id);
+            match old_entry.info.source {
+                ActionSource::Mcp => {
+                    self.mcp_tool_ids.remove(&old_entry.info.id);
+                }
+                ActionSource::Mod => {
+                    self.mod_tool_ids.remove(&old_entry.info.id);
+                }
+                ActionSource::Builtin => {}
             }
         }
 
@@ -251,8 +311,14 @@ impl ActionRegistry {
             alias_ids.sort();
         }
 
-        if info.source == ActionSource::Mcp {
-            self.mcp_tool_ids.insert(info.id.clone());
+        match info.source {
+            ActionSource::Mcp => {
+                self.mcp_tool_ids.insert(info.id.clone());
+            }
+            ActionSource::Mod => {
+                self.mod_tool_ids.insert(info.id.clone());
+            }
+            ActionSource::Builtin => {}
         }
 
         tracing::info!(target: "tools", "Registered: {} ({})", info.id, info.name);
@@ -310,6 +376,26 @@ impl ActionRegistry {
         }
     }
 
+    /// Register a tool offered by a mod script (tracked separately for cleanup).
+    pub fn register_mod(
+        &mut self,
+        mod_id: impl Into<String>,
+        handler: impl ActionHandler + 'static,
+    ) {
+        let info = Self::make_action_info(ActionSource::Mod, Some(mod_id.into()), &handler);
+        self.insert_entry(info, Arc::new(handler));
+    }
+
+    /// Remove all tools registered by mods, e.g. when the mod is unloaded.
+    pub fn clear_mod_tools(&mut self) {
+        let ids: Vec<_> = self.mod_tool_ids.drain().collect();
+        for id in ids {
+            if let Some(entry) = self.entries_by_id.remove(&id) {
+                self.remove_alias_mapping(&entry.info);
+            }
+        }
+    }
+
     pub fn resolve_action_id_for_input(&self, input: &str) -> Result<String, ActionError> {
         Ok(self.resolve_entry(input)?.info.id.clone())
     }
@@ -570,6 +656,11 @@ impl ActionRegistry {
                 action.id,
                 action.server_name.as_deref().unwrap_or("unknown")
             ),
+            ActionSource::Mod => format!(
+                "{} (mod/{})",
+                action.id,
+                action.server_name.as_deref().unwrap_or("unknown")
+            ),
         }
     }
 
@@ -917,6 +1008,42 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_clear_mod_tools_keeps_mcp_and_builtin() {
+        let mut reg = ActionRegistry::new();
+        reg.register(TestAction {
+            name: "get_time",
+            description: "Get time",
+            needs_feedback: true,
+        });
+        reg.register_mcp(
+            "clock",
+            TestAction {
+                name: "get_time",
+                description: "Remote time",
+                needs_feedback: true,
+            },
+        );
+        reg.register_mod(
+            "weather-mod",
+            TestAction {
+                name: "get_weather",
+                description: "Weather",
+                needs_feedback: true,
+            },
+        );
+
+        let action = reg.resolve_action("get_weather").unwrap();
+        assert_eq!(action.id, "mod__weather-mod__get_weather");
+        assert_eq!(action.source, ActionSource::Mod);
+        assert_eq!(action.server_name.as_deref(), Some("weather-mod"));
+
+        reg.clear_mod_tools();
+        assert!(reg.resolve_action("get_weather").is_err());
+        assert!(reg.resolve_action("mcp__clock__get_time").is_ok());
+        assert!(reg.resolve_action("builtin__get_time").is_ok());
+    }
+
     #[test]
     fn test_resolve_alias_when_unique() {
         let mut reg = ActionRegistry::new();
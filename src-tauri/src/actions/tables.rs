@@ -0,0 +1,1167 @@
+//! `query_table`: constrained queries over CSV files the user loaded.
+//!
+//! The user loads a CSV (a bank export, a spreadsheet saved as CSV…) from the
+//! tool settings and it is parsed into an in-memory table. The model never
+//! reads the file; it asks `describe`, `stats`, `filter` or `group_by`
+//! questions and gets compact answers back.
+
+use super::registry::{ActionContext, ActionError, ActionHandler, ActionParam, ActionResult};
+use crate::error::KokoroError;
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use serde::Serialize;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+use tauri::Manager;
+
+pub const QUERY_TABLE_TOOL_NAME: &str = "query_table";
+
+const MAX_FILE_BYTES: u64 = 20 * 1024 * 1024;
+const MAX_ROWS: usize = 200_000;
+const DEFAULT_LIMIT: usize = 20;
+const MAX_LIMIT: usize = 50;
+const MAX_CELL_CHARS: usize = 80;
+/// Cells sampled per column when guessing its type.
+const KIND_SAMPLE: usize = 500;
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ColumnKind {
+    Number,
+    Date,
+    Text,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ColumnSummary {
+    pub name: String,
+    pub kind: ColumnKind,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TableSummary {
+    pub name: String,
+    pub source_path: String,
+    pub columns: Vec<ColumnSummary>,
+    pub row_count: usize,
+    pub loaded_at: i64,
+}
+
+#[derive(Debug)]
+pub struct Table {
+    pub name: String,
+    pub source_path: String,
+    columns: Vec<String>,
+    kinds: Vec<ColumnKind>,
+    /// Per column: slash dates are day-first (`31/12/2026`) rather than US order.
+    day_first: Vec<bool>,
+    rows: Vec<Vec<String>>,
+    loaded_at: i64,
+}
+
+impl Table {
+    pub fn from_csv(name: &str, source_path: &str, text: &str) -> Result<Self, String> {
+        let (columns, rows) = parse_csv(text)?;
+        let mut kinds = Vec::with_capacity(columns.len());
+        let mut day_first = Vec::with_capacity(columns.len());
+        for index in 0..columns.len() {
+            let sample: Vec<&str> = rows
+                .iter()
+                .map(|row| row[index].trim())
+                .filter(|cell| !cell.is_empty())
+                .take(KIND_SAMPLE)
+                .collect();
+            let order = detect_day_first(&sample);
+            kinds.push(detect_kind(&sample, order));
+            day_first.push(order);
+        }
+        Ok(Self {
+            name: name.to_string(),
+            source_path: source_path.to_string(),
+            columns,
+            kinds,
+            day_first,
+            rows,
+            loaded_at: chrono::Utc::now().timestamp_millis(),
+        })
+    }
+
+    pub fn summary(&self) -> TableSummary {
+        TableSummary {
+            name: self.name.clone(),
+            source_path: self.source_path.clone(),
+            columns: self
+                .columns
+                .iter()
+                .zip(&self.kinds)
+                .map(|(name, kind)| ColumnSummary {
+                    name: name.clone(),
+                    kind: *kind,
+                })
+                .collect(),
+            row_count: self.rows.len(),
+            loaded_at: self.loaded_at,
+        }
+    }
+
+    fn column(&self, name: &str) -> Result<usize, String> {
+        let wanted = name.trim();
+        self.columns
+            .iter()
+            .position(|c| c.eq_ignore_ascii_case(wanted))
+            .ok_or_else(|| {
+                format!(
+                    "Unknown column '{}'; columns are: {}",
+                    wanted,
+                    self.columns.join(", ")
+                )
+            })
+    }
+
+    fn number(&self, row: &[String], index: usize) -> Option<f64> {
+        parse_number(&row[index])
+    }
+
+    /// Date cells as ISO strings so they sort and compare as text.
+    fn date(&self, raw: &str, index: usize) -> Option<String> {
+        parse_date(raw, self.day_first[index]).map(|d| d.format("%Y-%m-%d").to_string())
+    }
+
+    fn key(&self, raw: &str, index: usize) -> Key {
+        match self.kinds[index] {
+            ColumnKind::Number => parse_number(raw)
+                .map(Key::Num)
+                .unwrap_or_else(|| Key::Text(raw.trim().to_lowercase())),
+            ColumnKind::Date => Key::Text(
+                self.date(raw, index)
+                    .unwrap_or_else(|| raw.trim().to_lowercase()),
+            ),
+            ColumnKind::Text => Key::Text(raw.trim().to_lowercase()),
+        }
+    }
+}
+
+/// CSV tables loaded this session, by name.
+#[derive(Default)]
+pub struct TableStore {
+    tables: RwLock<HashMap<String, Arc<Table>>>,
+}
+
+impl TableStore {
+    /// Add `table`, replacing any table with the same name.
+    pub fn insert(&self, table: Table) -> TableSummary {
+        let summary = table.summary();
+        if let Ok(mut tables) = self.tables.write() {
+            tables.insert(table.name.clone(), Arc::new(table));
+        }
+        summary
+    }
+
+    pub fn list(&self) -> Vec<TableSummary> {
+        let mut list: Vec<TableSummary> = self
+            .tables
+            .read()
+            .map(|tables| tables.values().map(|t| t.summary()).collect())
+            .unwrap_or_default();
+        list.sort_by(|a, b| a.name.cmp(&b.name));
+        list
+    }
+
+    pub fn remove(&self, name: &str) -> bool {
+        self.tables
+            .write()
+            .map(|mut tables| tables.remove(name).is_some())
+            .unwrap_or(false)
+    }
+
+    /// `name`, or the only loaded table when no name is given.
+    fn get(&self, name: Option<&str>) -> Result<Arc<Table>, String> {
+        let tables = self
+            .tables
+            .read()
+            .map_err(|_| "Table store is unavailable".to_string())?;
+        let mut names: Vec<&String> = tables.keys().collect();
+        names.sort();
+        match name {
+            Some(name) => tables
+                .iter()
+                .find(|(key, _)| key.eq_ignore_ascii_case(name.trim()))
+                .map(|(_, table)| table.clone())
+                .ok_or_else(|| {
+                    if names.is_empty() {
+                        "No tables are loaded; ask the user to load a CSV first".to_string()
+                    } else {
+                        format!("Unknown table '{}'; loaded tables: {:?}", name, names)
+                    }
+                }),
+            None if tables.len() > 1 => Err(format!(
+                "Several tables are loaded; pass 'table': {:?}",
+                names
+            )),
+            None => tables.values().next().cloned().ok_or_else(|| {
+                "No tables are loaded; ask the user to load a CSV first".to_string()
+            }),
+        }
+    }
+}
+
+/// Read and parse a CSV file. The table is named after the file unless `name` is given.
+pub async fn load_csv_file(path: &Path, name: Option<String>) -> Result<Table, KokoroError> {
+    let metadata = tokio::fs::metadata(path).await?;
+    if metadata.len() > MAX_FILE_BYTES {
+        return Err(KokoroError::Validation(format!(
+            "CSV is too large ({} MB, limit {} MB)",
+            metadata.len() / (1024 * 1024),
+            MAX_FILE_BYTES / (1024 * 1024)
+        )));
+    }
+    let bytes = tokio::fs::read(path).await?;
+    let text = String::from_utf8_lossy(&bytes);
+    let name = name
+        .map(|n| table_name(&n))
+        .filter(|n| !n.is_empty())
+        .or_else(|| {
+            path.file_stem()
+                .map(|stem| table_name(&stem.to_string_lossy()))
+                .filter(|n| !n.is_empty())
+        })
+        .unwrap_or_else(|| "table".to_string());
+    Table::from_csv(&name, &path.to_string_lossy(), &text).map_err(KokoroError::Validation)
+}
+
+fn table_name(raw: &str) -> String {
+    raw.trim()
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>()
+        .trim_matches('_')
+        .to_string()
+}
+
+// ── Parsing ────────────────────────────────────────────
+
+/// Parse CSV text into a header and rows padded/cut to the header width.
+/// The delimiter (`,`, `;` or tab) is taken from the header line.
+pub fn parse_csv(text: &str) -> Result<(Vec<String>, Vec<Vec<String>>), String> {
+    let text = text.trim_start_matches('\u{feff}');
+    let header_line = text
+        .lines()
+        .find(|line| !line.trim().is_empty())
+        .unwrap_or("");
+    let delimiter = [',', ';', '\t']
+        .into_iter()
+        .max_by_key(|d| header_line.matches(*d).count())
+        .unwrap_or(',');
+
+    let mut records: Vec<Vec<String>> = Vec::new();
+    let mut record: Vec<String> = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => in_quotes = false,
+                _ => field.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' if field.trim().is_empty() => {
+                field.clear();
+                in_quotes = true;
+            }
+            '\r' => {}
+            '\n' => {
+                record.push(std::mem::take(&mut field));
+                push_record(&mut records, std::mem::take(&mut record))?;
+            }
+            c if c == delimiter => record.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        push_record(&mut records, record)?;
+    }
+
+    let mut records = records.into_iter();
+    let header = records.next().ok_or("The CSV is empty")?;
+    let mut columns: Vec<String> = Vec::with_capacity(header.len());
+    for (index, raw) in header.iter().enumerate() {
+        let base = match raw.trim() {
+            "" => format!("column_{}", index + 1),
+            name => name.to_string(),
+        };
+        let mut name = base.clone();
+        let mut suffix = 2;
+        while columns.iter().any(|c| c.eq_ignore_ascii_case(&name)) {
+            name = format!("{}_{}", base, suffix);
+            suffix += 1;
+        }
+        columns.push(name);
+    }
+    let rows = records
+        .map(|mut row| {
+            row.resize(columns.len(), String::new());
+            row
+        })
+        .collect();
+    Ok((columns, rows))
+}
+
+fn push_record(records: &mut Vec<Vec<String>>, record: Vec<String>) -> Result<(), String> {
+    if record.iter().all(|field| field.trim().is_empty()) {
+        return Ok(());
+    }
+    if records.len() > MAX_ROWS {
+        return Err(format!("The CSV has more than {} rows", MAX_ROWS));
+    }
+    records.push(record);
+    Ok(())
+}
+
+/// Numbers as written in exports: `1,234.56`, `1.234,56`, `-€12.50`, `(12.50)`.
+pub fn parse_number(raw: &str) -> Option<f64> {
+    let trimmed = raw.trim();
+    let (negative, body) = match trimmed.strip_prefix('(').and_then(|t| t.strip_suffix(')')) {
+        Some(inner) => (true, inner),
+        None => (false, trimmed),
+    };
+    let cleaned: String = body
+        .chars()
+        .filter(|c| !c.is_whitespace() && !matches!(c, '$' | '€' | '£' | '¥' | '₩' | '₽' | '\''))
+        .collect();
+    if cleaned.is_empty()
+        || cleaned.chars().any(|c| c.is_alphabetic())
+        || !cleaned.chars().any(|c| c.is_ascii_digit())
+    {
+        return None;
+    }
+    let normalized = match (cleaned.rfind('.'), cleaned.rfind(',')) {
+        (Some(dot), Some(comma)) if comma > dot => cleaned.replace('.', "").replace(',', "."),
+        (Some(_), Some(_)) => cleaned.replace(',', ""),
+        (None, Some(comma)) => {
+            let decimals = cleaned.len() - comma - 1;
+            if cleaned.matches(',').count() == 1 && decimals != 3 {
+                cleaned.replace(',', ".")
+            } else {
+                cleaned.replace(',', "")
+            }
+        }
+        _ => cleaned,
+    };
+    let value: f64 = normalized.parse().ok().filter(|v: &f64| v.is_finite())?;
+    Some(if negative { -value } else { value })
+}
+
+/// Dates with an optional time part. Slash dates follow `day_first`.
+pub fn parse_date(raw: &str, day_first: bool) -> Option<NaiveDate> {
+    let head = raw.trim().split([' ', 'T']).next()?;
+    let slash = if day_first { "%d/%m/%Y" } else { "%m/%d/%Y" };
+    ["%Y-%m-%d", "%Y/%m/%d", "%d.%m.%Y", slash]
+        .into_iter()
+        .find_map(|format| NaiveDate::parse_from_str(head, format).ok())
+}
+
+fn detect_day_first(sample: &[&str]) -> bool {
+    for cell in sample {
+        let mut parts = cell.split('/');
+        let first = parts.next().and_then(|p| p.trim().parse::<u32>().ok());
+        let second = parts.next().and_then(|p| p.trim().parse::<u32>().ok());
+        match (first, second) {
+            (Some(first), _) if first > 12 && first <= 31 => return true,
+            (_, Some(second)) if second > 12 && second <= 31 => return false,
+            _ => {}
+        }
+    }
+    false
+}
+
+fn detect_kind(sample: &[&str], day_first: bool) -> ColumnKind {
+    if sample.is_empty() {
+        return ColumnKind::Text;
+    }
+    let mostly = |count: usize| count * 10 >= sample.len() * 9;
+    if mostly(sample.iter().filter(|c| parse_number(c).is_some()).count()) {
+        ColumnKind::Number
+    } else if mostly(
+        sample
+            .iter()
+            .filter(|c| parse_date(c, day_first).is_some())
+            .count(),
+    ) {
+        ColumnKind::Date
+    } else {
+        ColumnKind::Text
+    }
+}
+
+// ── Queries ────────────────────────────────────────────
+
+#[derive(Debug, Clone, PartialEq)]
+enum Key {
+    Num(f64),
+    Text(String),
+}
+
+impl Key {
+    fn text(&self) -> String {
+        match self {
+            Key::Num(value) => format_number(*value),
+            Key::Text(text) => text.clone(),
+        }
+    }
+
+    fn compare(&self, other: &Key) -> Ordering {
+        match (self, other) {
+            (Key::Num(a), Key::Num(b)) => a.partial_cmp(b).unwrap_or(Ordering::Equal),
+            _ => self.text().cmp(&other.text()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Eq,
+    Ne,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+    Contains,
+}
+
+#[derive(Debug, Clone)]
+struct Condition {
+    column: usize,
+    op: Op,
+    value: String,
+}
+
+impl Condition {
+    fn parse(table: &Table, raw: &str) -> Result<Self, String> {
+        const OPS: [(&str, Op); 7] = [
+            ("!=", Op::Ne),
+            (">=", Op::Ge),
+            ("<=", Op::Le),
+            ("=", Op::Eq),
+            (">", Op::Gt),
+            ("<", Op::Lt),
+            ("~", Op::Contains),
+        ];
+        let (at, token, op) = OPS
+            .iter()
+            .filter_map(|(token, op)| raw.find(token).map(|at| (at, *token, *op)))
+            // Leftmost operator wins; at the same spot the two-char one does.
+            .min_by_key(|(at, token, _)| (*at, std::cmp::Reverse(token.len())))
+            .ok_or_else(|| format!("No operator in condition '{}'", raw.trim()))?;
+        let value = raw[at + token.len()..]
+            .trim()
+            .trim_matches(|c| c == '"' || c == '\'')
+            .to_string();
+        Ok(Self {
+            column: table.column(&raw[..at])?,
+            op,
+            value,
+        })
+    }
+
+    fn matches(&self, table: &Table, row: &[String]) -> bool {
+        let cell = &row[self.column];
+        if self.op == Op::Contains {
+            let haystack = match table.kinds[self.column] {
+                ColumnKind::Date => table
+                    .date(cell, self.column)
+                    .unwrap_or_else(|| cell.to_lowercase()),
+                _ => cell.to_lowercase(),
+            };
+            return haystack.contains(&self.value.to_lowercase());
+        }
+        let ordering = table
+            .key(cell, self.column)
+            .compare(&table.key(&self.value, self.column));
+        match self.op {
+            Op::Eq => ordering == Ordering::Equal,
+            Op::Ne => ordering != Ordering::Equal,
+            Op::Gt => ordering == Ordering::Greater,
+            Op::Ge => ordering != Ordering::Less,
+            Op::Lt => ordering == Ordering::Less,
+            Op::Le => ordering != Ordering::Greater,
+            Op::Contains => unreachable!(),
+        }
+    }
+}
+
+/// `Category = Food; Amount < 0 and Date >= 2026-09-01`
+fn parse_where(table: &Table, raw: Option<&str>) -> Result<Vec<Condition>, String> {
+    let Some(raw) = raw.map(str::trim).filter(|r| !r.is_empty()) else {
+        return Ok(Vec::new());
+    };
+    let mut parts = Vec::new();
+    for chunk in raw.split(';') {
+        let mut rest = chunk;
+        while let Some(at) = rest.to_ascii_lowercase().find(" and ") {
+            parts.push(&rest[..at]);
+            rest = &rest[at + 5..];
+        }
+        parts.push(rest);
+    }
+    parts
+        .into_iter()
+        .filter(|part| !part.trim().is_empty())
+        .map(|part| Condition::parse(table, part))
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Aggregate {
+    Count,
+    Sum,
+    Avg,
+    Min,
+    Max,
+}
+
+impl Aggregate {
+    fn parse(raw: Option<&str>) -> Result<Self, String> {
+        match raw.map(|r| r.trim().to_ascii_lowercase()).as_deref() {
+            None | Some("") | Some("count") => Ok(Self::Count),
+            Some("sum") => Ok(Self::Sum),
+            Some("avg") | Some("mean") | Some("average") => Ok(Self::Avg),
+            Some("min") => Ok(Self::Min),
+            Some("max") => Ok(Self::Max),
+            Some(other) => Err(format!(
+                "Unknown agg '{}'; use count, sum, avg, min or max",
+                other
+            )),
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Count => "count",
+            Self::Sum => "sum",
+            Self::Avg => "avg",
+            Self::Min => "min",
+            Self::Max => "max",
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+struct NumberStats {
+    count: usize,
+    sum: f64,
+    min: Option<f64>,
+    max: Option<f64>,
+}
+
+impl NumberStats {
+    fn add(&mut self, value: f64) {
+        self.count += 1;
+        self.sum += value;
+        self.min = Some(self.min.map_or(value, |m| m.min(value)));
+        self.max = Some(self.max.map_or(value, |m| m.max(value)));
+    }
+
+    fn mean(&self) -> Option<f64> {
+        (self.count > 0).then(|| self.sum / self.count as f64)
+    }
+}
+
+/// A tool call's answer: a compact text for the model plus structured data.
+#[derive(Debug)]
+pub struct QueryOutput {
+    pub text: String,
+    pub data: serde_json::Value,
+}
+
+/// Run one `query_table` call against `table`.
+pub fn run_query(table: &Table, args: &HashMap<String, String>) -> Result<QueryOutput, String> {
+    let arg = |name: &str| {
+        args.get(name)
+            .map(|value| value.trim())
+            .filter(|value| !value.is_empty())
+    };
+    let conditions = parse_where(table, arg("where"))?;
+    let rows: Vec<&Vec<String>> = table
+        .rows
+        .iter()
+        .filter(|row| conditions.iter().all(|c| c.matches(table, row)))
+        .collect();
+    let limit = arg("limit")
+        .and_then(|l| l.parse::<usize>().ok())
+        .unwrap_or(DEFAULT_LIMIT)
+        .clamp(1, MAX_LIMIT);
+
+    match arg("op")
+        .unwrap_or("describe")
+        .to_ascii_lowercase()
+        .as_str()
+    {
+        "describe" => Ok(describe(table)),
+        "stats" => {
+            let column = table.column(arg("column").ok_or("'stats' needs 'column'")?)?;
+            Ok(stats(table, &rows, column))
+        }
+        "filter" => filter(table, &rows, arg("columns"), arg("sort"), limit),
+        "group_by" => {
+            let group = table.column(arg("group").ok_or("'group_by' needs 'group'")?)?;
+            let agg = Aggregate::parse(arg("agg"))?;
+            let value = match (agg, arg("value")) {
+                (Aggregate::Count, _) => None,
+                (_, Some(value)) => Some(table.column(value)?),
+                (_, None) => return Err(format!("agg '{}' needs 'value'", agg.label())),
+            };
+            group_by(
+                table,
+                &rows,
+                group,
+                arg("bucket"),
+                agg,
+                value,
+                arg("sort"),
+                limit,
+            )
+        }
+        other => Err(format!(
+            "Unknown op '{}'; use describe, stats, filter or group_by",
+            other
+        )),
+    }
+}
+
+fn describe(table: &Table) -> QueryOutput {
+    let summary = table.summary();
+    let columns: Vec<String> = summary
+        .columns
+        .iter()
+        .map(|c| format!("{} ({})", c.name, format!("{:?}", c.kind).to_lowercase()))
+        .collect();
+    let mut text = format!(
+        "Table '{}': {} rows. Columns: {}",
+        table.name,
+        table.rows.len(),
+        columns.join(", ")
+    );
+    if let Some(first) = table.rows.first() {
+        text.push_str(&format!("\nFirst row: {}", render_row(first)));
+    }
+    QueryOutput {
+        text,
+        data: serde_json::to_value(&summary).unwrap_or_default(),
+    }
+}
+
+fn stats(table: &Table, rows: &[&Vec<String>], column: usize) -> QueryOutput {
+    let name = &table.columns[column];
+    let filled: Vec<&str> = rows
+        .iter()
+        .map(|row| row[column].trim())
+        .filter(|cell| !cell.is_empty())
+        .collect();
+    match table.kinds[column] {
+        ColumnKind::Number => {
+            let mut numbers = NumberStats::default();
+            for row in rows {
+                if let Some(value) = table.number(row, column) {
+                    numbers.add(value);
+                }
+            }
+            let mean = numbers.mean();
+            QueryOutput {
+                text: format!(
+                    "{} over {} rows: count {}, sum {}, avg {}, min {}, max {}",
+                    name,
+                    rows.len(),
+                    numbers.count,
+                    format_number(numbers.sum),
+                    format_optional(mean),
+                    format_optional(numbers.min),
+                    format_optional(numbers.max),
+                ),
+                data: serde_json::json!({
+                    "column": name,
+                    "rows": rows.len(),
+                    "count": numbers.count,
+                    "sum": numbers.sum,
+                    "avg": mean,
+                    "min": numbers.min,
+                    "max": numbers.max,
+                }),
+            }
+        }
+        kind => {
+            let mut counts: HashMap<String, usize> = HashMap::new();
+            for cell in &filled {
+                let key = match kind {
+                    ColumnKind::Date => {
+                        table.date(cell, column).unwrap_or_else(|| cell.to_string())
+                    }
+                    _ => cell.to_string(),
+                };
+                *counts.entry(key).or_default() += 1;
+            }
+            let mut top: Vec<(String, usize)> = counts.into_iter().collect();
+            top.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+            let distinct = top.len();
+            let range = (kind == ColumnKind::Date)
+                .then(|| {
+                    let first = top.iter().map(|(d, _)| d).min()?;
+                    let last = top.iter().map(|(d, _)| d).max()?;
+                    Some(format!(", from {} to {}", first, last))
+                })
+                .flatten()
+                .unwrap_or_default();
+            top.truncate(5);
+            let listed: Vec<String> = top
+                .iter()
+                .map(|(value, count)| format!("{} ×{}", clip(value), count))
+                .collect();
+            QueryOutput {
+                text: format!(
+                    "{} over {} rows: {} filled, {} distinct{}. Most common: {}",
+                    name,
+                    rows.len(),
+                    filled.len(),
+                    distinct,
+                    range,
+                    listed.join(", ")
+                ),
+                data: serde_json::json!({
+                    "column": name,
+                    "rows": rows.len(),
+                    "filled": filled.len(),
+                    "distinct": distinct,
+                    "top": top,
+                }),
+            }
+        }
+    }
+}
+
+fn filter(
+    table: &Table,
+    rows: &[&Vec<String>],
+    columns: Option<&str>,
+    sort: Option<&str>,
+    limit: usize,
+) -> Result<QueryOutput, String> {
+    let selected: Vec<usize> = match columns {
+        Some(list) => list
+            .split(',')
+            .filter(|c| !c.trim().is_empty())
+            .map(|c| table.column(c))
+            .collect::<Result<_, _>>()?,
+        None => (0..table.columns.len()).collect(),
+    };
+    let mut rows = rows.to_vec();
+    if let Some(sort) = sort {
+        let (column, descending) = parse_sort(sort);
+        let column = table.column(column)?;
+        rows.sort_by(|a, b| {
+            let ordering = table
+                .key(&a[column], column)
+                .compare(&table.key(&b[column], column));
+            if descending {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        });
+    }
+    let matched = rows.len();
+    rows.truncate(limit);
+
+    let header: Vec<&str> = selected
+        .iter()
+        .map(|&i| table.columns[i].as_str())
+        .collect();
+    let mut lines = vec![format!("{} matching rows", matched), header.join(" | ")];
+    let mut data_rows = Vec::with_capacity(rows.len());
+    for row in &rows {
+        let cells: Vec<String> = selected.iter().map(|&i| clip(&row[i])).collect();
+        lines.push(cells.join(" | "));
+        data_rows.push(serde_json::Value::Object(
+            selected
+                .iter()
+                .map(|&i| (table.columns[i].clone(), serde_json::json!(row[i])))
+                .collect(),
+        ));
+    }
+    if matched > rows.len() {
+        lines.push(format!("… {} more", matched - rows.len()));
+    }
+    Ok(QueryOutput {
+        text: lines.join("\n"),
+        data: serde_json::json!({ "matched": matched, "rows": data_rows }),
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+fn group_by(
+    table: &Table,
+    rows: &[&Vec<String>],
+    group: usize,
+    bucket: Option<&str>,
+    agg: Aggregate,
+    value: Option<usize>,
+    sort: Option<&str>,
+    limit: usize,
+) -> Result<QueryOutput, String> {
+    let bucket_len = match (table.kinds[group], bucket.map(str::to_ascii_lowercase)) {
+        (_, None) => None,
+        (ColumnKind::Date, Some(b)) if b == "day" => Some(10),
+        (ColumnKind::Date, Some(b)) if b == "month" => Some(7),
+        (ColumnKind::Date, Some(b)) if b == "year" => Some(4),
+        (ColumnKind::Date, Some(b)) => {
+            return Err(format!("Unknown bucket '{}'; use day, month or year", b))
+        }
+        (_, Some(_)) => return Err("'bucket' only applies to date columns".to_string()),
+    };
+
+    let mut groups: HashMap<String, NumberStats> = HashMap::new();
+    for row in rows {
+        let raw = row[group].trim();
+        let mut label = match table.kinds[group] {
+            ColumnKind::Date => table.date(raw, group).unwrap_or_else(|| raw.to_string()),
+            _ => raw.to_string(),
+        };
+        if let Some(len) = bucket_len {
+            label.truncate(len.min(label.len()));
+        }
+        if label.is_empty() {
+            label = "(empty)".to_string();
+        }
+        let entry = groups.entry(label).or_default();
+        match value {
+            Some(column) => {
+                if let Some(number) = table.number(row, column) {
+                    entry.add(number);
+                }
+            }
+            None => entry.add(0.0),
+        }
+    }
+
+    let mut results: Vec<(String, f64)> = groups
+        .into_iter()
+        .filter_map(|(label, stats)| {
+            let result = match agg {
+                Aggregate::Count => Some(stats.count as f64),
+                Aggregate::Sum => Some(stats.sum),
+                Aggregate::Avg => stats.mean(),
+                Aggregate::Min => stats.min,
+                Aggregate::Max => stats.max,
+            }?;
+            Some((label, result))
+        })
+        .collect();
+    match sort.map(|s| s.trim().to_ascii_lowercase()).as_deref() {
+        Some("asc") => results.sort_by(|a, b| a.1.total_cmp(&b.1).then_with(|| a.0.cmp(&b.0))),
+        Some("key") | Some("group") => results.sort_by(|a, b| a.0.cmp(&b.0)),
+        _ => results.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0))),
+    }
+    let total_groups = results.len();
+    results.truncate(limit);
+
+    let measure = match value {
+        Some(column) => format!("{}({})", agg.label(), table.columns[column]),
+        None => agg.label().to_string(),
+    };
+    let mut lines = vec![format!(
+        "{} by {} over {} rows ({} groups)",
+        measure,
+        table.columns[group],
+        rows.len(),
+        total_groups
+    )];
+    lines.extend(
+        results
+            .iter()
+            .map(|(label, result)| format!("{}: {}", clip(label), format_number(*result))),
+    );
+    if total_groups > results.len() {
+        lines.push(format!("… {} more groups", total_groups - results.len()));
+    }
+    Ok(QueryOutput {
+        text: lines.join("\n"),
+        data: serde_json::json!({
+            "measure": measure,
+            "groups": results
+                .iter()
+                .map(|(label, result)| serde_json::json!({ "group": label, "value": result }))
+                .collect::<Vec<_>>(),
+            "total_groups": total_groups,
+        }),
+    })
+}
+
+/// `Amount desc` → (`Amount`, true)
+fn parse_sort(raw: &str) -> (&str, bool) {
+    let trimmed = raw.trim();
+    let lower = trimmed.to_ascii_lowercase();
+    if lower.ends_with(" desc") {
+        (&trimmed[..trimmed.len() - 5], true)
+    } else if lower.ends_with(" asc") {
+        (&trimmed[..trimmed.len() - 4], false)
+    } else {
+        (trimmed, false)
+    }
+}
+
+fn render_row(row: &[String]) -> String {
+    row.iter().map(|c| clip(c)).collect::<Vec<_>>().join(" | ")
+}
+
+fn clip(text: &str) -> String {
+    let text = text.trim();
+    if text.chars().count() > MAX_CELL_CHARS {
+        text.chars().take(MAX_CELL_CHARS).collect::<String>() + "…"
+    } else {
+        text.to_string()
+    }
+}
+
+fn format_number(value: f64) -> String {
+    if value.fract() == 0.0 && value.abs() < 1e15 {
+        format!("{}", value as i64)
+    } else {
+        format!("{:.2}", value)
+    }
+}
+
+fn format_optional(value: Option<f64>) -> String {
+    value.map(format_number).unwrap_or_else(|| "-".to_string())
+}
+
+// ── query_table ────────────────────────────────────────
+
+pub struct QueryTableAction;
+
+#[async_trait]
+impl ActionHandler for QueryTableAction {
+    fn name(&self) -> &str {
+        QUERY_TABLE_TOOL_NAME
+    }
+
+    fn description(&self) -> &str {
+        "Query a CSV table the user loaded (bank exports, spreadsheets). Start with op=describe to see the columns. Ops: stats (column), filter (where, columns, sort, limit), group_by (group, agg, value, bucket, sort, limit). 'where' is like `Category = Food; Amount < 0; Date >= 2026-09-01` (operators = != > >= < <= and ~ for contains)."
+    }
+
+    fn parameters(&self) -> Vec<ActionParam> {
+        let param = |name: &str, description: &str| ActionParam {
+            name: name.to_string(),
+            description: description.to_string(),
+            required: false,
+        };
+        vec![
+            ActionParam {
+                name: "op".to_string(),
+                description: "describe, stats, filter or group_by".to_string(),
+                required: true,
+            },
+            param(
+                "table",
+                "Table name; optional when only one table is loaded",
+            ),
+            param("where", "Conditions separated by ';' or 'and'"),
+            param("column", "Column for stats"),
+            param("columns", "Comma-separated columns to return from filter"),
+            param("group", "Column to group by"),
+            param("agg", "count (default), sum, avg, min or max"),
+            param("value", "Numeric column aggregated by group_by"),
+            param("bucket", "For date groups: day, month or year"),
+            param(
+                "sort",
+                "filter: 'Column asc|desc'; group_by: desc (default), asc or key",
+            ),
+            param("limit", "Rows or groups to return (default 20, max 50)"),
+        ]
+    }
+
+    fn needs_feedback(&self) -> bool {
+        true
+    }
+
+    async fn execute(
+        &self,
+        args: HashMap<String, String>,
+        ctx: ActionContext,
+    ) -> Result<ActionResult, ActionError> {
+        let store = ctx
+            .app
+            .try_state::<Arc<TableStore>>()
+            .ok_or_else(|| ActionError("Table store is not available".into()))?;
+        let table = store
+            .get(
+                args.get("table")
+                    .map(|t| t.trim())
+                    .filter(|t| !t.is_empty()),
+            )
+            .map_err(ActionError)?;
+        let output = run_query(&table, &args).map_err(ActionError)?;
+        Ok(ActionResult::ok_with_data(output.text, output.data))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BANK: &str = "\u{feff}Date;Payee;Category;Amount\r\n\
+        28.08.2026;Rent Co;Housing;-900,00\r\n\
+        02.09.2026;\"Market; Fresh\";Food;-45,50\r\n\
+        05.09.2026;Cafe;Food;-4,20\r\n\
+        10.09.2026;Employer;Income;2.500,00\r\n\
+        21.09.2026;Cinema;Fun;\"-12,00\"\r\n\
+        \r\n";
+
+    fn bank() -> Table {
+        Table::from_csv("bank", "bank.csv", BANK).unwrap()
+    }
+
+    fn query(table: &Table, pairs: &[(&str, &str)]) -> Result<QueryOutput, String> {
+        let args = pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        run_query(table, &args)
+    }
+
+    #[test]
+    fn parses_quoted_fields_and_detects_column_kinds() {
+        let table = bank();
+        assert_eq!(table.columns, ["Date", "Payee", "Category", "Amount"]);
+        assert_eq!(
+            table.kinds,
+            [
+                ColumnKind::Date,
+                ColumnKind::Text,
+                ColumnKind::Text,
+                ColumnKind::Number
+            ]
+        );
+        assert_eq!(table.rows.len(), 5);
+        assert_eq!(table.rows[1][1], "Market; Fresh");
+
+        let (columns, rows) = parse_csv("a,,a\n\"x \"\"y\"\"\",2\n").unwrap();
+        assert_eq!(columns, ["a", "column_2", "a_2"]);
+        assert_eq!(rows, [["x \"y\"", "2", ""]]);
+    }
+
+    #[test]
+    fn numbers_and_dates_follow_export_conventions() {
+        assert_eq!(parse_number("1,234.56"), Some(1234.56));
+        assert_eq!(parse_number("1.234,56"), Some(1234.56));
+        assert_eq!(parse_number("-€12,5"), Some(-12.5));
+        assert_eq!(parse_number("(7.00)"), Some(-7.0));
+        assert_eq!(parse_number("1,000"), Some(1000.0));
+        assert_eq!(parse_number("2026-09-01"), None);
+        assert_eq!(parse_number("abc"), None);
+
+        assert!(detect_day_first(&["05/09/2026", "21/09/2026"]));
+        assert!(!detect_day_first(&["09/05/2026", "09/21/2026"]));
+        assert_eq!(
+            parse_date("2026-09-21T10:00:00", false),
+            NaiveDate::from_ymd_opt(2026, 9, 21)
+        );
+    }
+
+    #[test]
+    fn group_by_month_answers_spending_questions() {
+        let table = bank();
+        let output = query(
+            &table,
+            &[
+                ("op", "group_by"),
+                ("group", "category"),
+                ("agg", "sum"),
+                ("value", "amount"),
+                ("where", "Amount < 0 and Date ~ 2026-09"),
+                ("sort", "asc"),
+            ],
+        )
+        .unwrap();
+        let lines: Vec<&str> = output.text.lines().collect();
+        assert_eq!(lines[0], "sum(Amount) by Category over 3 rows (2 groups)");
+        assert_eq!(lines[1], "Food: -49.70");
+        assert_eq!(lines[2], "Fun: -12");
+
+        let output = query(
+            &table,
+            &[
+                ("op", "group_by"),
+                ("group", "Date"),
+                ("bucket", "month"),
+                ("sort", "key"),
+            ],
+        )
+        .unwrap();
+        assert!(output.text.contains("2026-08: 1\n2026-09: 4"));
+    }
+
+    #[test]
+    fn filter_sorts_limits_and_stats_summarize() {
+        let table = bank();
+        let output = query(
+            &table,
+            &[
+                ("op", "filter"),
+                ("where", "Date >= 2026-09-01; Category != Income"),
+                ("columns", "Payee, Amount"),
+                ("sort", "Amount asc"),
+                ("limit", "2"),
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            output.text,
+            "3 matching rows\nPayee | Amount\nMarket; Fresh | -45,50\nCinema | -12,00\n… 1 more"
+        );
+
+        let output = query(&table, &[("op", "stats"), ("column", "amount")]).unwrap();
+        assert!(output.text.contains("sum 1538.30"));
+        assert!(output.text.contains("min -900"));
+
+        let output = query(&table, &[("op", "stats"), ("column", "Date")]).unwrap();
+        assert!(output.text.contains("from 2026-08-28 to 2026-09-21"));
+    }
+
+    #[test]
+    fn bad_queries_explain_themselves() {
+        let table = bank();
+        assert!(query(&table, &[("op", "stats"), ("column", "nope")])
+            .unwrap_err()
+            .contains("Date, Payee, Category, Amount"));
+        assert!(query(&table, &[("op", "filter"), ("where", "Amount")])
+            .unwrap_err()
+            .contains("No operator"));
+        assert!(query(
+            &table,
+            &[("op", "group_by"), ("group", "Payee"), ("agg", "sum")]
+        )
+        .unwrap_err()
+        .contains("needs 'value'"));
+
+        let store = TableStore::default();
+        assert!(store.get(None).unwrap_err().contains("No tables"));
+        store.insert(bank());
+        assert_eq!(store.get(None).unwrap().name, "bank");
+        assert_eq!(store.get(Some("BANK")).unwrap().name, "bank");
+        assert!(store.remove("bank"));
+    }
+}
@@ -0,0 +1,450 @@
+//! Forms of address — what the character calls the user and what the user
+//! calls the character.
+//!
+//! User messages are scanned for explicit requests ("call me Alex",
+//! "以后叫我小明", "アレックスと呼んで", "can I call you Mimi?") and the form is
+//! kept per character in `characters.relationship_json`. `compose_prompt`
+//! injects it as an `<address_forms>` block that outranks any nickname in the
+//! persona. Detection is rule-based, so the UI can view and correct the
+//! learned forms; a manual correction is replaced only by a new explicit
+//! request in chat.
+
+use crate::error::KokoroError;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+
+/// Longest form kept for space-separated scripts.
+const MAX_FORM_CHARS: usize = 24;
+const MAX_FORM_WORDS: usize = 3;
+/// Longest form kept for unspaced CJK text, where the end is only a guess.
+const MAX_CJK_FORM_CHARS: usize = 8;
+
+/// Cues followed by the form. Matched against ASCII-lowercased text.
+const LEADING_CUES: &[(&str, AddressTarget)] = &[
+    ("call me ", AddressTarget::User),
+    ("以后叫我", AddressTarget::User),
+    ("你可以叫我", AddressTarget::User),
+    ("请叫我", AddressTarget::User),
+    ("就叫我", AddressTarget::User),
+    ("称呼我为", AddressTarget::User),
+    ("i'll call you ", AddressTarget::Character),
+    ("i’ll call you ", AddressTarget::Character),
+    ("i will call you ", AddressTarget::Character),
+    ("can i call you ", AddressTarget::Character),
+    ("may i call you ", AddressTarget::Character),
+    ("let me call you ", AddressTarget::Character),
+    ("以后叫你", AddressTarget::Character),
+    ("以后我叫你", AddressTarget::Character),
+    ("我就叫你", AddressTarget::Character),
+    ("我可以叫你", AddressTarget::Character),
+    ("称呼你为", AddressTarget::Character),
+];
+
+/// Japanese cues that follow the form ("ミクって呼んで").
+const TRAILING_CUES: &[&str] = &["と呼んで", "って呼んで", "と呼ぶ", "って呼ぶ"];
+
+/// A clause containing one of these is a refusal or a question, not a request.
+const NEGATIONS: &[&str] = &[
+    "don't", "dont", "do not", "never", "stop", "why", "what", "别", "不要", "不准", "不许",
+];
+
+/// Words that end the form ("call me Alex from now on").
+const FORM_ENDINGS: &[&str] = &[
+    " from now on",
+    " please",
+    " instead",
+    " okay",
+    " ok",
+    " if ",
+    " when ",
+    " because",
+    " and ",
+    "吧",
+    "就好",
+    "就行",
+    "就可以",
+    "好了",
+    "好吗",
+    "好不好",
+    "可以吗",
+    "行吗",
+    "嘛",
+    "呀",
+];
+
+/// First words that mean the cue wasn't about a name ("call me later").
+const NOT_A_NAME: &[&str] = &[
+    "later", "back", "tomorrow", "tonight", "soon", "when", "if", "again", "now", "today", "after",
+    "at", "in", "on", "by", "a", "an", "the", "anytime", "sometime", "whatever", "起床", "去",
+    "来", "帮", "一起",
+];
+
+const TERMINATORS: &[char] = &[
+    '.', ',', '!', '?', ';', ':', '\n', '(', ')', '~', '。', '，', '！', '？', '；', '：', '、',
+    '（', '）', '～',
+];
+const QUOTES: &[char] = &['"', '\'', '“', '”', '‘', '’', '「', '」', '『', '』'];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AddressTarget {
+    /// What the character calls the user.
+    User,
+    /// What the user calls the character.
+    Character,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AddressSource {
+    /// Picked up from a chat message.
+    Learned,
+    /// Set or corrected in the UI.
+    Manual,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AddressForm {
+    pub form: String,
+    pub source: AddressSource,
+    /// Unix timestamp in milliseconds.
+    pub updated_at: i64,
+}
+
+/// Per-character relationship state, stored in `characters.relationship_json`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RelationshipState {
+    #[serde(default)]
+    pub user_address: Option<AddressForm>,
+    #[serde(default)]
+    pub character_address: Option<AddressForm>,
+}
+
+impl RelationshipState {
+    fn slot(&mut self, target: AddressTarget) -> &mut Option<AddressForm> {
+        match target {
+            AddressTarget::User => &mut self.user_address,
+            AddressTarget::Character => &mut self.character_address,
+        }
+    }
+
+    /// Set (or with `None`, clear) a form. Returns whether anything changed.
+    pub fn set(
+        &mut self,
+        target: AddressTarget,
+        form: Option<String>,
+        source: AddressSource,
+        now: i64,
+    ) -> bool {
+        let slot = self.slot(target);
+        let next = form.map(|form| AddressForm {
+            form,
+            source,
+            updated_at: now,
+        });
+        let unchanged = match (slot.as_ref(), next.as_ref()) {
+            (Some(current), Some(next)) => {
+                current.form == next.form && current.source == next.source
+            }
+            (None, None) => true,
+            _ => false,
+        };
+        if !unchanged {
+            *slot = next;
+        }
+        !unchanged
+    }
+}
+
+/// Forms requested in one message.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct DetectedAddress {
+    pub user: Option<String>,
+    pub character: Option<String>,
+}
+
+impl DetectedAddress {
+    fn record(&mut self, target: AddressTarget, form: String) {
+        let slot = match target {
+            AddressTarget::User => &mut self.user,
+            AddressTarget::Character => &mut self.character,
+        };
+        slot.get_or_insert(form);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.user.is_none() && self.character.is_none()
+    }
+}
+
+pub fn detect_address_forms(text: &str) -> DetectedAddress {
+    let mut detected = DetectedAddress::default();
+    // ASCII lowercasing keeps byte offsets valid for slicing `text`.
+    let lower = text.to_ascii_lowercase();
+
+    for &(cue, target) in LEADING_CUES {
+        for (start, _) in lower.match_indices(cue) {
+            if is_negated(clause_before(&lower, start)) {
+                continue;
+            }
+            if let Some(form) = form_after(&text[start + cue.len()..]) {
+                detected.record(target, form);
+                break;
+            }
+        }
+    }
+
+    for cue in TRAILING_CUES {
+        for (start, _) in text.match_indices(cue) {
+            let after = &text[start + cue.len()..];
+            let target = if after.starts_with("いい") || after.starts_with("もいい") {
+                // "ミクって呼んでいい？" asks to call the character that.
+                AddressTarget::Character
+            } else if cue.ends_with("呼ぶ") {
+                if !["ね", "よ", "から"]
+                    .iter()
+                    .any(|end| after.starts_with(end))
+                {
+                    continue;
+                }
+                AddressTarget::Character
+            } else if after.starts_with("ほしくない") || after.starts_with("欲しくない") {
+                continue;
+            } else {
+                AddressTarget::User
+            };
+            if let Some(form) = form_before(&text[..start]) {
+                detected.record(target, form);
+                break;
+            }
+        }
+    }
+
+    detected
+}
+
+fn is_terminator(c: char) -> bool {
+    TERMINATORS.contains(&c)
+}
+
+/// The part of the sentence before byte offset `end`.
+fn clause_before(text: &str, end: usize) -> &str {
+    let before = &text[..end];
+    match before.rfind(is_terminator) {
+        Some(i) => &before[i..],
+        None => before,
+    }
+}
+
+fn is_negated(clause: &str) -> bool {
+    NEGATIONS.iter().any(|word| clause.contains(word))
+}
+
+fn form_after(rest: &str) -> Option<String> {
+    let end = rest.find(is_terminator).unwrap_or(rest.len());
+    let mut form = &rest[..end];
+    let lower = form.to_ascii_lowercase();
+    if let Some(cut) = FORM_ENDINGS.iter().filter_map(|e| lower.find(e)).min() {
+        form = &form[..cut];
+    }
+    clean_form(form)
+}
+
+fn form_before(before: &str) -> Option<String> {
+    let start = before
+        .rfind(|c: char| is_terminator(c) || c.is_whitespace())
+        .map(|i| i + before[i..].chars().next().map_or(1, char::len_utf8))
+        .unwrap_or(0);
+    let clause = &before[start..];
+    // "私のことはアレックス" → "アレックス". A particle at the very start
+    // belongs to the name ("はるか").
+    let form = clause
+        .char_indices()
+        .skip(1)
+        .find(|(_, c)| *c == 'は' || *c == 'を')
+        .map_or(clause, |(i, c)| &clause[i + c.len_utf8()..]);
+    clean_form(form)
+}
+
+fn clean_form(form: &str) -> Option<String> {
+    let form = form.trim().trim_matches(QUOTES).trim();
+    let first_word = form.split_whitespace().next()?.to_lowercase();
+    // Unspaced CJK has no first word, so match those entries as prefixes.
+    let not_a_name =
+        |word: &&str| first_word == *word || (word.chars().any(is_cjk) && form.starts_with(*word));
+    if NOT_A_NAME.iter().any(not_a_name) {
+        return None;
+    }
+    let chars = form.chars().count();
+    let fits = if form.chars().any(is_cjk) && !form.contains(' ') {
+        chars <= MAX_CJK_FORM_CHARS
+    } else {
+        chars <= MAX_FORM_CHARS && form.split_whitespace().count() <= MAX_FORM_WORDS
+    };
+    fits.then(|| form.to_string())
+}
+
+fn is_cjk(c: char) -> bool {
+    let kana = ('\u{3040}'..='\u{30ff}').contains(&c);
+    let han = ('\u{3400}'..='\u{4dbf}').contains(&c) || ('\u{4e00}'..='\u{9fff}').contains(&c);
+    let hangul = ('\u{ac00}'..='\u{d7af}').contains(&c);
+    kana || han || hangul
+}
+
+/// The character's relationship state; `None` when there is no such character row.
+pub async fn load_relationship_state(
+    db: &SqlitePool,
+    character_id: &str,
+) -> Result<Option<RelationshipState>, KokoroError> {
+    let raw: Option<String> =
+        sqlx::query_scalar("SELECT relationship_json FROM characters WHERE id = ?")
+            .bind(character_id)
+            .fetch_optional(db)
+            .await?;
+    match raw {
+        Some(raw) => Ok(Some(serde_json::from_str(&raw)?)),
+        None => Ok(None),
+    }
+}
+
+pub async fn save_relationship_state(
+    db: &SqlitePool,
+    character_id: &str,
+    state: &RelationshipState,
+) -> Result<(), KokoroError> {
+    let result = sqlx::query("UPDATE characters SET relationship_json = ? WHERE id = ?")
+        .bind(serde_json::to_string(state)?)
+        .bind(character_id)
+        .execute(db)
+        .await?;
+    if result.rows_affected() == 0 {
+        return Err(KokoroError::NotFound(format!(
+            "Character {} not found",
+            character_id
+        )));
+    }
+    Ok(())
+}
+
+/// Learn any form of address requested in a user message. Returns whether
+/// the stored state changed.
+pub async fn learn_from_message(
+    db: &SqlitePool,
+    character_id: &str,
+    text: &str,
+) -> Result<bool, KokoroError> {
+    let detected = detect_address_forms(text);
+    if detected.is_empty() {
+        return Ok(false);
+    }
+    let Some(mut state) = load_relationship_state(db, character_id).await? else {
+        return Ok(false);
+    };
+    let now = chrono::Utc::now().timestamp_millis();
+    let mut changed = false;
+    for (target, form) in [
+        (AddressTarget::User, detected.user),
+        (AddressTarget::Character, detected.character),
+    ] {
+        if form.is_some() {
+            changed |= state.set(target, form, AddressSource::Learned, now);
+        }
+    }
+    if changed {
+        save_relationship_state(db, character_id, &state).await?;
+    }
+    Ok(changed)
+}
+
+/// The `<address_forms>` prompt block, if any form is known.
+pub fn render_block(state: &RelationshipState) -> Option<String> {
+    let mut lines = Vec::new();
+    if let Some(user) = &state.user_address {
+        lines.push(format!(
+            "The user wants to be called \"{}\". Address them this way consistently; it overrides any other name or nickname for the user.",
+            user.form
+        ));
+    }
+    if let Some(character) = &state.character_address {
+        lines.push(format!(
+            "The user calls you \"{}\". Treat it as your name between the two of you and answer to it naturally.",
+            character.form
+        ));
+    }
+    (!lines.is_empty()).then(|| format!("<address_forms>\n{}\n</address_forms>", lines.join("\n")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_requests_and_skips_refusals() {
+        let detected = detect_address_forms("From now on, call me Alex please!");
+        assert_eq!(detected.user.as_deref(), Some("Alex"));
+        assert_eq!(
+            detect_address_forms("Can I call you Mimi?")
+                .character
+                .as_deref(),
+            Some("Mimi")
+        );
+        assert_eq!(
+            detect_address_forms("以后叫我小明吧").user.as_deref(),
+            Some("小明")
+        );
+        assert_eq!(
+            detect_address_forms("私のことはアレックスと呼んで")
+                .user
+                .as_deref(),
+            Some("アレックス")
+        );
+        assert_eq!(
+            detect_address_forms("ミクちゃんって呼んでいい？")
+                .character
+                .as_deref(),
+            Some("ミクちゃん")
+        );
+
+        assert!(detect_address_forms("Don't call me Bob.").is_empty());
+        assert!(detect_address_forms("call me later, ok?").is_empty());
+        assert!(detect_address_forms("别叫我小明").is_empty());
+        assert!(detect_address_forms("I'll call you when I get home").is_empty());
+    }
+
+    #[tokio::test]
+    async fn learned_forms_are_stored_and_rendered() {
+        let db = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::migrate!("./migrations").run(&db).await.unwrap();
+        sqlx::query("INSERT INTO characters (id, name) VALUES ('c1', 'Mio')")
+            .execute(&db)
+            .await
+            .unwrap();
+
+        assert!(!learn_from_message(&db, "missing", "call me Alex")
+            .await
+            .unwrap());
+        assert!(learn_from_message(&db, "c1", "call me Alex").await.unwrap());
+        assert!(!learn_from_message(&db, "c1", "Call me Alex!")
+            .await
+            .unwrap());
+
+        let mut state = load_relationship_state(&db, "c1").await.unwrap().unwrap();
+        assert_eq!(
+            state.user_address.as_ref().map(|a| a.source),
+            Some(AddressSource::Learned)
+        );
+        assert!(state.set(
+            AddressTarget::Character,
+            Some("Mimi".to_string()),
+            AddressSource::Manual,
+            0
+        ));
+        save_relationship_state(&db, "c1", &state).await.unwrap();
+
+        let state = load_relationship_state(&db, "c1").await.unwrap().unwrap();
+        let block = render_block(&state).unwrap();
+        assert!(block.contains("\"Alex\""));
+        assert!(block.contains("\"Mimi\""));
+        assert!(render_block(&RelationshipState::default()).is_none());
+    }
+}
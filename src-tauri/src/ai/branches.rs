@@ -0,0 +1,570 @@
+//! Conversation branching ("swipes").
+//!
+//! Regenerating the last reply does not delete it. The rows after the last
+//! user message become variant 0 of a *variant group* anchored on that user
+//! message, and the new reply is written as the next variant. Only rows on
+//! the selected path carry `is_active = 1`, so every reader that filters on
+//! it sees a plain linear conversation.
+//!
+//! New rows are tagged by the `conversation_messages_join_active_branch`
+//! trigger (migration 0013) with the conversation's tip, so no insert site
+//! needs to know about branches.
+
+use crate::error::KokoroError;
+use serde::Serialize;
+use sqlx::{Row, SqliteConnection, SqlitePool};
+use std::collections::HashMap;
+
+/// Characters of the first reply shown per variant in `list_message_variants`.
+const VARIANT_PREVIEW_CHARS: usize = 120;
+
+/// Returned by `regenerate_last_response`; the caller re-sends `user_message`
+/// through `stream_chat` with `regenerate: true`.
+#[derive(Debug, Clone, Serialize)]
+pub struct RegenerationTicket {
+    pub user_message: String,
+    pub user_metadata: Option<String>,
+    pub variant_group: String,
+    pub variant_index: i64,
+    pub variant_count: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MessageVariant {
+    pub index: i64,
+    /// Start of the first assistant reply on this variant, empty if it has none yet.
+    pub preview: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MessageVariantGroup {
+    pub id: String,
+    /// Row id of the user message the variants answer.
+    pub anchor_message_id: i64,
+    pub active_index: i64,
+    pub variant_count: i64,
+    pub variants: Vec<MessageVariant>,
+}
+
+#[derive(Debug, Clone)]
+struct GroupRow {
+    id: String,
+    anchor_message_id: i64,
+    parent_group: Option<String>,
+    parent_index: Option<i64>,
+    active_index: i64,
+    variant_count: i64,
+}
+
+/// Move the current reply to the last user message aside and open a fresh
+/// variant for the next one. The conversation tip points at the new variant,
+/// so whatever `stream_chat` persists next lands there.
+pub async fn prepare_regeneration(
+    db: &SqlitePool,
+    conversation_id: &str,
+) -> Result<RegenerationTicket, KokoroError> {
+    let mut tx = db.begin().await?;
+
+    let anchor = sqlx::query(
+        "SELECT id, content, metadata FROM conversation_messages
+         WHERE conversation_id = ? AND role = 'user' AND is_active = 1
+         ORDER BY id DESC LIMIT 1",
+    )
+    .bind(conversation_id)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| {
+        KokoroError::Validation("Conversation has no user message to regenerate".to_string())
+    })?;
+    let anchor_id: i64 = anchor.get("id");
+
+    let existing: Option<(String, i64)> = sqlx::query_as(
+        "SELECT id, variant_count FROM message_variant_groups
+         WHERE conversation_id = ? AND anchor_message_id = ?",
+    )
+    .bind(conversation_id)
+    .bind(anchor_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let (group_id, variant_index) = match existing {
+        Some((group_id, variant_count)) => {
+            sqlx::query(
+                "UPDATE conversation_messages SET is_active = 0
+                 WHERE conversation_id = ? AND id > ? AND is_active = 1",
+            )
+            .bind(conversation_id)
+            .bind(anchor_id)
+            .execute(&mut *tx)
+            .await?;
+            (group_id, variant_count)
+        }
+        None => {
+            let (parent_group, parent_index): (Option<String>, i64) = sqlx::query_as(
+                "SELECT active_branch_group, active_variant_index FROM conversations WHERE id = ?",
+            )
+            .bind(conversation_id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or_else(|| {
+                KokoroError::NotFound(format!("Conversation {} not found", conversation_id))
+            })?;
+
+            let group_id = uuid::Uuid::new_v4().to_string();
+            sqlx::query(
+                "INSERT INTO message_variant_groups
+                 (id, conversation_id, anchor_message_id, parent_group, parent_index, active_index, variant_count, created_at)
+                 VALUES (?, ?, ?, ?, ?, 0, 1, ?)",
+            )
+            .bind(&group_id)
+            .bind(conversation_id)
+            .bind(anchor_id)
+            .bind(&parent_group)
+            .bind(parent_group.as_ref().map(|_| parent_index))
+            .bind(chrono::Utc::now().timestamp())
+            .execute(&mut *tx)
+            .await?;
+
+            // The existing reply becomes variant 0 of the new group.
+            sqlx::query(
+                "UPDATE conversation_messages
+                 SET branch_group = ?, variant_index = 0, is_active = 0
+                 WHERE conversation_id = ? AND id > ? AND is_active = 1",
+            )
+            .bind(&group_id)
+            .bind(conversation_id)
+            .bind(anchor_id)
+            .execute(&mut *tx)
+            .await?;
+            (group_id, 1)
+        }
+    };
+
+    sqlx::query(
+        "UPDATE message_variant_groups SET active_index = ?, variant_count = ? WHERE id = ?",
+    )
+    .bind(variant_index)
+    .bind(variant_index + 1)
+    .bind(&group_id)
+    .execute(&mut *tx)
+    .await?;
+    sqlx::query(
+        "UPDATE conversations SET active_branch_group = ?, active_variant_index = ? WHERE id = ?",
+    )
+    .bind(&group_id)
+    .bind(variant_index)
+    .bind(conversation_id)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(RegenerationTicket {
+        user_message: anchor.get("content"),
+        user_metadata: anchor.get("metadata"),
+        variant_group: group_id,
+        variant_index,
+        variant_count: variant_index + 1,
+    })
+}
+
+/// Branch the conversation right after `anchor_id`, which must be on the
+/// active path. Everything that followed it on the same branch (nested
+/// groups included) becomes a variant, and an empty variant is selected so
+/// new rows continue from the anchor. Returns `false` if nothing followed.
+pub async fn fork_after(
+    conn: &mut SqliteConnection,
+    conversation_id: &str,
+    anchor_id: i64,
+) -> Result<bool, KokoroError> {
+    let anchor: Option<(Option<String>, i64)> = sqlx::query_as(
+        "SELECT branch_group, variant_index FROM conversation_messages
+         WHERE id = ? AND conversation_id = ? AND is_active = 1",
+    )
+    .bind(anchor_id)
+    .bind(conversation_id)
+    .fetch_optional(&mut *conn)
+    .await?;
+    let Some((branch, branch_index)) = anchor else {
+        return Err(KokoroError::Validation(format!(
+            "Message {} is not on the current branch",
+            anchor_id
+        )));
+    };
+    let branch_index = branch.as_ref().map(|_| branch_index);
+
+    let following: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM conversation_messages
+         WHERE conversation_id = ? AND id > ? AND is_active = 1",
+    )
+    .bind(conversation_id)
+    .bind(anchor_id)
+    .fetch_one(&mut *conn)
+    .await?;
+    if following == 0 {
+        return Ok(false);
+    }
+
+    // A regeneration may already have grouped the rows after this anchor.
+    let existing: Option<(String, i64)> = sqlx::query_as(
+        "SELECT id, variant_count FROM message_variant_groups
+         WHERE conversation_id = ? AND anchor_message_id = ?
+           AND parent_group IS ? AND parent_index IS ?",
+    )
+    .bind(conversation_id)
+    .bind(anchor_id)
+    .bind(&branch)
+    .bind(branch_index)
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    match existing {
+        Some((group_id, variant_count)) => {
+            sqlx::query(
+                "UPDATE message_variant_groups SET active_index = ?, variant_count = ? WHERE id = ?",
+            )
+            .bind(variant_count)
+            .bind(variant_count + 1)
+            .bind(&group_id)
+            .execute(&mut *conn)
+            .await?;
+        }
+        None => {
+            let group_id = uuid::Uuid::new_v4().to_string();
+            sqlx::query(
+                "INSERT INTO message_variant_groups
+                 (id, conversation_id, anchor_message_id, parent_group, parent_index, active_index, variant_count, created_at)
+                 VALUES (?, ?, ?, ?, ?, 1, 2, ?)",
+            )
+            .bind(&group_id)
+            .bind(conversation_id)
+            .bind(anchor_id)
+            .bind(&branch)
+            .bind(branch_index)
+            .bind(chrono::Utc::now().timestamp())
+            .execute(&mut *conn)
+            .await?;
+
+            // The anchor's branch continues as variant 0; groups hanging off
+            // it further down move along.
+            sqlx::query(
+                "UPDATE conversation_messages SET branch_group = ?, variant_index = 0
+                 WHERE conversation_id = ? AND id > ?
+                   AND branch_group IS ? AND variant_index = ?",
+            )
+            .bind(&group_id)
+            .bind(conversation_id)
+            .bind(anchor_id)
+            .bind(&branch)
+            .bind(branch_index.unwrap_or(0))
+            .execute(&mut *conn)
+            .await?;
+            sqlx::query(
+                "UPDATE message_variant_groups SET parent_group = ?, parent_index = 0
+                 WHERE conversation_id = ? AND id != ? AND anchor_message_id > ?
+                   AND parent_group IS ? AND parent_index IS ?",
+            )
+            .bind(&group_id)
+            .bind(conversation_id)
+            .bind(&group_id)
+            .bind(anchor_id)
+            .bind(&branch)
+            .bind(branch_index)
+            .execute(&mut *conn)
+            .await?;
+        }
+    }
+
+    refresh_active_path(conn, conversation_id).await?;
+    Ok(true)
+}
+
+/// Select another variant of a group. Returns the conversation it belongs to.
+pub async fn switch_variant(
+    db: &SqlitePool,
+    group_id: &str,
+    variant_index: i64,
+) -> Result<String, KokoroError> {
+    let mut tx = db.begin().await?;
+
+    let (conversation_id, variant_count): (String, i64) = sqlx::query_as(
+        "SELECT conversation_id, variant_count FROM message_variant_groups WHERE id = ?",
+    )
+    .bind(group_id)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| KokoroError::NotFound(format!("Variant group {} not found", group_id)))?;
+
+    if variant_index < 0 || variant_index >= variant_count {
+        return Err(KokoroError::Validation(format!(
+            "Variant {} out of range (group has {})",
+            variant_index, variant_count
+        )));
+    }
+
+    sqlx::query("UPDATE message_variant_groups SET active_index = ? WHERE id = ?")
+        .bind(variant_index)
+        .bind(group_id)
+        .execute(&mut *tx)
+        .await?;
+    refresh_active_path(&mut tx, &conversation_id).await?;
+
+    tx.commit().await?;
+    Ok(conversation_id)
+}
+
+/// Variant groups on the active path, oldest first.
+pub async fn list_variants(
+    db: &SqlitePool,
+    conversation_id: &str,
+) -> Result<Vec<MessageVariantGroup>, KokoroError> {
+    let mut conn = db.acquire().await?;
+    let groups = load_groups(&mut conn, conversation_id).await?;
+    let on_path = groups_on_active_path(&groups);
+
+    let mut result = Vec::new();
+    for group in groups
+        .iter()
+        .filter(|g| on_path.contains_key(g.id.as_str()))
+    {
+        let rows: Vec<(i64, String, Option<String>)> = sqlx::query_as(
+            "SELECT variant_index, content, metadata FROM conversation_messages
+             WHERE branch_group = ? AND role = 'assistant'
+             ORDER BY id ASC",
+        )
+        .bind(&group.id)
+        .fetch_all(&mut *conn)
+        .await?;
+
+        let mut previews: HashMap<i64, String> = HashMap::new();
+        for (index, content, metadata) in rows {
+            let is_tool_call = metadata
+                .as_deref()
+                .is_some_and(|raw| raw.contains("\"assistant_tool_calls\""));
+            if is_tool_call || content.trim().is_empty() {
+                continue;
+            }
+            previews
+                .entry(index)
+                .or_insert_with(|| content.trim().chars().take(VARIANT_PREVIEW_CHARS).collect());
+        }
+
+        result.push(MessageVariantGroup {
+            id: group.id.clone(),
+            anchor_message_id: group.anchor_message_id,
+            active_index: group.active_index,
+            variant_count: group.variant_count,
+            variants: (0..group.variant_count)
+                .map(|index| MessageVariant {
+                    index,
+                    preview: previews.remove(&index).unwrap_or_default(),
+                })
+                .collect(),
+        });
+    }
+    Ok(result)
+}
+
+async fn load_groups(
+    conn: &mut SqliteConnection,
+    conversation_id: &str,
+) -> Result<Vec<GroupRow>, sqlx::Error> {
+    let rows = sqlx::query(
+        "SELECT id, anchor_message_id, parent_group, parent_index, active_index, variant_count
+         FROM message_variant_groups WHERE conversation_id = ?
+         ORDER BY anchor_message_id ASC",
+    )
+    .bind(conversation_id)
+    .fetch_all(&mut *conn)
+    .await?;
+    Ok(rows
+        .into_iter()
+        .map(|row| GroupRow {
+            id: row.get("id"),
+            anchor_message_id: row.get("anchor_message_id"),
+            parent_group: row.get("parent_group"),
+            parent_index: row.get("parent_index"),
+            active_index: row.get("active_index"),
+            variant_count: row.get("variant_count"),
+        })
+        .collect())
+}
+
+/// Groups whose whole ancestor chain is on the selected variants, mapped to
+/// their selected index. Groups are ordered by anchor, and a parent's anchor
+/// always precedes its children's, so one pass suffices.
+fn groups_on_active_path(groups: &[GroupRow]) -> HashMap<&str, i64> {
+    let mut on_path: HashMap<&str, i64> = HashMap::new();
+    for group in groups {
+        let visible = match (&group.parent_group, group.parent_index) {
+            (None, _) => true,
+            (Some(parent), Some(index)) => on_path.get(parent.as_str()) == Some(&index),
+            (Some(_), None) => false,
+        };
+        if visible {
+            on_path.insert(group.id.as_str(), group.active_index);
+        }
+    }
+    on_path
+}
+
+/// Recompute `is_active` for every row and move the tip to the deepest
+/// group on the selected path.
+async fn refresh_active_path(
+    conn: &mut SqliteConnection,
+    conversation_id: &str,
+) -> Result<(), sqlx::Error> {
+    let groups = load_groups(conn, conversation_id).await?;
+    let on_path = groups_on_active_path(&groups);
+
+    sqlx::query(
+        "UPDATE conversation_messages
+         SET is_active = CASE WHEN branch_group IS NULL THEN 1 ELSE 0 END
+         WHERE conversation_id = ?",
+    )
+    .bind(conversation_id)
+    .execute(&mut *conn)
+    .await?;
+    for (group_id, index) in &on_path {
+        sqlx::query(
+            "UPDATE conversation_messages SET is_active = 1
+             WHERE conversation_id = ? AND branch_group = ? AND variant_index = ?",
+        )
+        .bind(conversation_id)
+        .bind(group_id)
+        .bind(index)
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    let tip = groups.iter().rev().find_map(|g| {
+        on_path
+            .get(g.id.as_str())
+            .map(|index| (g.id.clone(), *index))
+    });
+    sqlx::query(
+        "UPDATE conversations SET active_branch_group = ?, active_variant_index = ? WHERE id = ?",
+    )
+    .bind(tip.as_ref().map(|(id, _)| id.clone()))
+    .bind(tip.map(|(_, index)| index).unwrap_or(0))
+    .bind(conversation_id)
+    .execute(&mut *conn)
+    .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn setup_db() -> SqlitePool {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+        sqlx::query(
+            "INSERT INTO conversations (id, character_id, title, created_at, updated_at)
+             VALUES ('c1', 'char', 't', '2026-01-01', '2026-01-01')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        pool
+    }
+
+    async fn push(pool: &SqlitePool, role: &str, content: &str) {
+        sqlx::query(
+            "INSERT INTO conversation_messages (conversation_id, role, content, created_at)
+             VALUES ('c1', ?, ?, '2026-01-01')",
+        )
+        .bind(role)
+        .bind(content)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    async fn active_contents(pool: &SqlitePool) -> Vec<String> {
+        sqlx::query_scalar(
+            "SELECT content FROM conversation_messages
+             WHERE conversation_id = 'c1' AND is_active = 1 ORDER BY id ASC",
+        )
+        .fetch_all(pool)
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn regenerate_keeps_previous_reply_as_variant() {
+        let pool = setup_db().await;
+        push(&pool, "user", "hi").await;
+        push(&pool, "assistant", "hello A").await;
+
+        let ticket = prepare_regeneration(&pool, "c1").await.unwrap();
+        assert_eq!(ticket.user_message, "hi");
+        assert_eq!((ticket.variant_index, ticket.variant_count), (1, 2));
+        assert_eq!(active_contents(&pool).await, vec!["hi"]);
+
+        push(&pool, "assistant", "hello B").await;
+        assert_eq!(active_contents(&pool).await, vec!["hi", "hello B"]);
+
+        let again = prepare_regeneration(&pool, "c1").await.unwrap();
+        assert_eq!(again.variant_group, ticket.variant_group);
+        assert_eq!((again.variant_index, again.variant_count), (2, 3));
+        push(&pool, "assistant", "hello C").await;
+
+        switch_variant(&pool, &ticket.variant_group, 0)
+            .await
+            .unwrap();
+        assert_eq!(active_contents(&pool).await, vec!["hi", "hello A"]);
+
+        let groups = list_variants(&pool, "c1").await.unwrap();
+        assert_eq!(groups.len(), 1);
+        let previews: Vec<_> = groups[0]
+            .variants
+            .iter()
+            .map(|v| v.preview.as_str())
+            .collect();
+        assert_eq!(previews, vec!["hello A", "hello B", "hello C"]);
+
+        assert!(switch_variant(&pool, &ticket.variant_group, 3)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn switching_outer_variant_hides_nested_branches() {
+        let pool = setup_db().await;
+        push(&pool, "user", "q1").await;
+        push(&pool, "assistant", "a1").await;
+        let outer = prepare_regeneration(&pool, "c1").await.unwrap();
+        push(&pool, "assistant", "a1'").await;
+
+        // Continue on variant 1 and branch again one turn later.
+        push(&pool, "user", "q2").await;
+        push(&pool, "assistant", "a2").await;
+        let inner = prepare_regeneration(&pool, "c1").await.unwrap();
+        push(&pool, "assistant", "a2'").await;
+        assert_eq!(active_contents(&pool).await, vec!["q1", "a1'", "q2", "a2'"]);
+
+        switch_variant(&pool, &outer.variant_group, 0)
+            .await
+            .unwrap();
+        assert_eq!(active_contents(&pool).await, vec!["q1", "a1"]);
+        assert_eq!(list_variants(&pool, "c1").await.unwrap().len(), 1);
+
+        // New messages continue the selected branch.
+        push(&pool, "user", "q3").await;
+        switch_variant(&pool, &outer.variant_group, 1)
+            .await
+            .unwrap();
+        assert_eq!(active_contents(&pool).await, vec!["q1", "a1'", "q2", "a2'"]);
+
+        switch_variant(&pool, &inner.variant_group, 0)
+            .await
+            .unwrap();
+        assert_eq!(active_contents(&pool).await, vec!["q1", "a1'", "q2", "a2"]);
+        switch_variant(&pool, &outer.variant_group, 0)
+            .await
+            .unwrap();
+        assert_eq!(active_contents(&pool).await, vec!["q1", "a1", "q3"]);
+    }
+}
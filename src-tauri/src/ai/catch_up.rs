@@ -0,0 +1,144 @@
+//! "Previously…" recap for conversations reopened after a long break.
+//!
+//! Reopening a weeks-old chat leaves the model with only the last few raw
+//! messages. `AIOrchestrator::catch_up_conversation` notes how long the
+//! conversation sat idle and condenses the running summary plus the turns
+//! just before the raw window into a short recap, which `compose_prompt`
+//! injects as a `<previously>` block in place of the conversation summary.
+
+use crate::ai::context::{is_summary_candidate_message, Message};
+use crate::ai::history::MAX_BUFFERED_MESSAGES;
+use sqlx::{Row, SqlitePool};
+
+/// Idle time after which reopening a conversation gets a recap.
+pub const CATCH_UP_AFTER_SECS: i64 = 3 * 24 * 60 * 60;
+/// Older turns fed into the recap next to the running summary.
+const RECAP_SOURCE_MESSAGES: usize = 40;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CatchUp {
+    pub conversation_id: String,
+    pub idle_secs: i64,
+    /// `None` while the recap is being written, or when every message is
+    /// still in the raw window.
+    pub recap: Option<String>,
+}
+
+/// Seconds between `updated_at` (RFC 3339) and `now`, if past the threshold.
+pub fn idle_secs(updated_at: &str, now: chrono::DateTime<chrono::Utc>) -> Option<i64> {
+    let last = chrono::DateTime::parse_from_rfc3339(updated_at).ok()?;
+    let idle = (now - last.with_timezone(&chrono::Utc)).num_seconds();
+    (idle >= CATCH_UP_AFTER_SECS).then_some(idle)
+}
+
+/// Coarse, human wording of an idle gap ("5 days", "3 weeks", "2 months").
+pub fn describe_gap(idle_secs: i64) -> String {
+    let days = idle_secs / 86_400;
+    let (count, unit) = match days {
+        0..=13 => (days.max(1), "day"),
+        14..=59 => (days / 7, "week"),
+        60..=364 => (days / 30, "month"),
+        _ => (days / 365, "year"),
+    };
+    if count == 1 {
+        format!("1 {unit}")
+    } else {
+        format!("{count} {unit}s")
+    }
+}
+
+pub fn render_block(catch_up: &CatchUp) -> String {
+    let mut block = format!(
+        "<previously>\nThis conversation was reopened after about {} away.",
+        describe_gap(catch_up.idle_secs)
+    );
+    if let Some(recap) = &catch_up.recap {
+        block.push_str("\nPreviously:\n");
+        block.push_str(recap.trim());
+    }
+    block.push_str(
+        "\n\nPick the thread back up naturally. Do not recite this recap, and trust the recent raw messages over it.\n</previously>",
+    );
+    block
+}
+
+/// Transcript of the visible turns after `after_message_id` that fell out of
+/// the raw history window, most recent `RECAP_SOURCE_MESSAGES` of them.
+pub async fn recap_transcript(
+    db: &SqlitePool,
+    conversation_id: &str,
+    after_message_id: i64,
+) -> Result<Option<String>, sqlx::Error> {
+    let rows = sqlx::query(
+        "SELECT role, content, metadata FROM conversation_messages
+         WHERE conversation_id = ? AND id > ? AND is_active = 1
+         ORDER BY id ASC",
+    )
+    .bind(conversation_id)
+    .bind(after_message_id)
+    .fetch_all(db)
+    .await?;
+    let older = rows.len().saturating_sub(MAX_BUFFERED_MESSAGES);
+    let turns: Vec<Message> = rows
+        .into_iter()
+        .take(older)
+        .map(|row| Message {
+            role: row.get("role"),
+            content: row.get("content"),
+            metadata: row
+                .get::<Option<String>, _>("metadata")
+                .as_deref()
+                .and_then(|raw| serde_json::from_str(raw).ok()),
+        })
+        .filter(|message| {
+            (message.role == "user" || message.role == "assistant")
+                && is_summary_candidate_message(message)
+                && message
+                    .metadata
+                    .as_ref()
+                    .and_then(|meta| meta.get("type"))
+                    .and_then(|value| value.as_str())
+                    != Some("assistant_tool_calls")
+        })
+        .collect();
+    if turns.is_empty() {
+        return Ok(None);
+    }
+    let skip = turns.len().saturating_sub(RECAP_SOURCE_MESSAGES);
+    Ok(Some(
+        turns[skip..]
+            .iter()
+            .map(|message| format!("{}: {}", message.role, message.content))
+            .collect::<Vec<_>>()
+            .join("\n"),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn idle_gaps_are_thresholded_and_worded() {
+        let now = chrono::DateTime::parse_from_rfc3339("2024-03-30T12:00:00Z")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+        assert_eq!(idle_secs("2024-03-29T12:00:00Z", now), None);
+        assert_eq!(idle_secs("2024-03-25T12:00:00Z", now), Some(5 * 86_400));
+        assert_eq!(idle_secs("not a date", now), None);
+
+        assert_eq!(describe_gap(5 * 86_400), "5 days");
+        assert_eq!(describe_gap(21 * 86_400), "3 weeks");
+        assert_eq!(describe_gap(65 * 86_400), "2 months");
+        assert_eq!(describe_gap(400 * 86_400), "1 year");
+
+        let block = render_block(&CatchUp {
+            conversation_id: "c".to_string(),
+            idle_secs: 21 * 86_400,
+            recap: Some("They planned a trip.".to_string()),
+        });
+        assert!(block.starts_with("<previously>\n"));
+        assert!(block.contains("about 3 weeks away"));
+        assert!(block.contains("Previously:\nThey planned a trip."));
+    }
+}
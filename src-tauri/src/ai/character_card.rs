@@ -0,0 +1,520 @@
+//! Character cards — SillyTavern-compatible import/export.
+//!
+//! Supported inputs: v1/v2/v3 card JSON, PNG images carrying the card in a
+//! `chara` (v2) or `ccv3` (v3) text chunk, and `.charx` archives (zip with
+//! `card.json` plus embedded assets). Exports always write a v3 card; PNG
+//! exports additionally embed a v2 copy for older readers.
+
+use crate::error::KokoroError;
+use base64::Engine;
+use serde_json::{json, Value};
+use std::io::{Cursor, Read, Write};
+
+const PNG_SIGNATURE: [u8; 8] = [137, 80, 78, 71, 13, 10, 26, 10];
+const ZIP_SIGNATURE: [u8; 4] = [b'P', b'K', 3, 4];
+
+/// A card normalized into the fields Kokoro stores per character.
+#[derive(Debug, Clone)]
+pub struct ParsedCard {
+    /// `tavern-v2`, `tavern-v3` or `charx`.
+    pub source_format: String,
+    pub name: String,
+    /// Prompt persona composed from description / personality / scenario / examples.
+    pub persona: String,
+    pub first_message: String,
+    pub example_dialogue: String,
+    /// The card's `character_book`, if any.
+    pub lorebook: Option<Value>,
+    /// Full card as a v3 envelope (`spec`, `spec_version`, `data`), kept for lossless export.
+    pub card: Value,
+    /// Avatar image (PNG cards: the image itself; charx: the main icon asset).
+    pub avatar: Option<Vec<u8>>,
+}
+
+/// Parse any supported card container, sniffing the format from the bytes.
+pub fn parse_card(bytes: &[u8]) -> Result<ParsedCard, KokoroError> {
+    if bytes.starts_with(&PNG_SIGNATURE) {
+        parse_png_card(bytes)
+    } else if bytes.starts_with(&ZIP_SIGNATURE) {
+        parse_charx(bytes)
+    } else {
+        let text = std::str::from_utf8(bytes)
+            .map_err(|_| KokoroError::Validation("Card file is not valid UTF-8 JSON".into()))?;
+        let value: Value = serde_json::from_str(text.trim_start_matches('\u{feff}'))
+            .map_err(|e| KokoroError::Validation(format!("Invalid card JSON: {}", e)))?;
+        Ok(card_from_json(value, None, None))
+    }
+}
+
+fn parse_png_card(bytes: &[u8]) -> Result<ParsedCard, KokoroError> {
+    let chunks = png_text_chunks(bytes);
+    // Prefer the v3 payload when both are present, as the v3 spec recommends.
+    let payload = chunks
+        .iter()
+        .find(|(k, _)| k == "ccv3")
+        .or_else(|| chunks.iter().find(|(k, _)| k == "chara"))
+        .map(|(_, v)| v.as_str())
+        .ok_or_else(|| {
+            KokoroError::Validation(
+                "No \"chara\" or \"ccv3\" metadata found in PNG; not a character card".into(),
+            )
+        })?;
+    let value = decode_card_payload(payload)?;
+    Ok(card_from_json(value, Some(bytes.to_vec()), None))
+}
+
+fn parse_charx(bytes: &[u8]) -> Result<ParsedCard, KokoroError> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(bytes))?;
+    let mut card_json = String::new();
+    archive
+        .by_name("card.json")
+        .map_err(|_| KokoroError::Validation("charx archive has no card.json".into()))?
+        .read_to_string(&mut card_json)?;
+    let value: Value = serde_json::from_str(&card_json)
+        .map_err(|e| KokoroError::Validation(format!("Invalid card.json: {}", e)))?;
+
+    // Main icon asset: `embeded://` (sic, per spec) URIs point inside the archive.
+    let icon_path = value
+        .pointer("/data/assets")
+        .and_then(Value::as_array)
+        .and_then(|assets| {
+            assets
+                .iter()
+                .filter(|a| a.get("type").and_then(Value::as_str) == Some("icon"))
+                .find(|a| a.get("name").and_then(Value::as_str) == Some("main"))
+                .or_else(|| {
+                    assets
+                        .iter()
+                        .find(|a| a.get("type").and_then(Value::as_str) == Some("icon"))
+                })
+        })
+        .and_then(|a| a.get("uri").and_then(Value::as_str))
+        .and_then(|uri| uri.strip_prefix("embeded://"))
+        .map(str::to_owned);
+    let avatar = icon_path.and_then(|path| {
+        let mut file = archive.by_name(&path).ok()?;
+        let mut buf = Vec::new();
+        file.read_to_end(&mut buf).ok()?;
+        Some(buf)
+    });
+
+    Ok(card_from_json(value, avatar, Some("charx")))
+}
+
+/// Walk PNG chunks and collect `tEXt`, `zTXt` and `iTXt` keyword/value pairs.
+fn png_text_chunks(bytes: &[u8]) -> Vec<(String, String)> {
+    let mut result = Vec::new();
+    let mut offset = PNG_SIGNATURE.len();
+    while offset + 8 <= bytes.len() {
+        let len = u32::from_be_bytes([
+            bytes[offset],
+            bytes[offset + 1],
+            bytes[offset + 2],
+            bytes[offset + 3],
+        ]) as usize;
+        let kind = &bytes[offset + 4..offset + 8];
+        let data_start = offset + 8;
+        let Some(data) = bytes.get(data_start..data_start + len) else {
+            break;
+        };
+
+        let Some(nul) = data.iter().position(|&b| b == 0) else {
+            offset = data_start + len + 4;
+            continue;
+        };
+        let keyword = latin1(&data[..nul]);
+        let rest = &data[nul + 1..];
+        let value = match kind {
+            b"tEXt" => Some(latin1(rest)),
+            b"zTXt" => rest.get(1..).and_then(inflate).map(|v| latin1(&v)),
+            b"iTXt" if rest.len() >= 2 => {
+                let compressed = rest[0] == 1;
+                // Skip language tag and translated keyword (both NUL-terminated).
+                let mut tail = &rest[2..];
+                for _ in 0..2 {
+                    let end = tail.iter().position(|&b| b == 0).unwrap_or(tail.len());
+                    tail = tail.get(end + 1..).unwrap_or_default();
+                }
+                let text = if compressed {
+                    inflate(tail)
+                } else {
+                    Some(tail.to_vec())
+                };
+                text.map(|t| String::from_utf8_lossy(&t).into_owned())
+            }
+            _ => None,
+        };
+        if let Some(value) = value {
+            result.push((keyword, value));
+        }
+
+        if kind == b"IEND" {
+            break;
+        }
+        offset = data_start + len + 4;
+    }
+    result
+}
+
+fn latin1(bytes: &[u8]) -> String {
+    bytes.iter().map(|&b| b as char).collect()
+}
+
+fn inflate(bytes: &[u8]) -> Option<Vec<u8>> {
+    let mut out = Vec::new();
+    flate2::read::ZlibDecoder::new(bytes)
+        .read_to_end(&mut out)
+        .ok()?;
+    Some(out)
+}
+
+/// Card payloads are base64-encoded UTF-8 JSON; some tools store raw JSON.
+fn decode_card_payload(payload: &str) -> Result<Value, KokoroError> {
+    let trimmed = payload.trim();
+    if let Ok(decoded) = base64::engine::general_purpose::STANDARD.decode(trimmed) {
+        if let Ok(value) = serde_json::from_slice::<Value>(&decoded) {
+            return Ok(value);
+        }
+    }
+    serde_json::from_str(trimmed)
+        .map_err(|e| KokoroError::Validation(format!("Invalid embedded card data: {}", e)))
+}
+
+fn str_field<'a>(data: &'a Value, keys: &[&str]) -> &'a str {
+    keys.iter()
+        .find_map(|k| data.get(*k).and_then(Value::as_str))
+        .filter(|s| !s.is_empty())
+        .unwrap_or("")
+}
+
+/// Wrap v1 (flat) cards into a v3 envelope; v2/v3 envelopes are upgraded in place.
+fn normalize_card(value: Value) -> (Value, bool) {
+    let is_v3 = value.get("spec").and_then(Value::as_str) == Some("chara_card_v3")
+        || value.pointer("/data/spec_version").and_then(Value::as_str) == Some("3.0")
+        || value.get("spec_version").and_then(Value::as_str) == Some("3.0");
+
+    let data = match value.get("data") {
+        Some(data) if data.is_object() => data.clone(),
+        _ => {
+            // v1: flat fields, with some legacy aliases.
+            json!({
+                "name": str_field(&value, &["name", "char_name"]),
+                "description": str_field(&value, &["description", "char_persona"]),
+                "personality": str_field(&value, &["personality"]),
+                "scenario": str_field(&value, &["scenario", "world_scenario"]),
+                "first_mes": str_field(&value, &["first_mes", "char_greeting"]),
+                "mes_example": str_field(&value, &["mes_example", "example_dialogue"]),
+            })
+        }
+    };
+
+    (
+        json!({
+            "spec": "chara_card_v3",
+            "spec_version": "3.0",
+            "data": data,
+        }),
+        is_v3,
+    )
+}
+
+/// Same composition the frontend used for imported cards, so prompts stay stable.
+fn compose_persona(data: &Value) -> String {
+    let mut parts: Vec<String> = Vec::new();
+    let system_prompt = str_field(data, &["system_prompt"]);
+    if !system_prompt.is_empty() {
+        parts.push(system_prompt.to_string());
+    }
+    let description = str_field(data, &["description", "char_persona"]);
+    if !description.is_empty() {
+        parts.push(description.to_string());
+    }
+    let personality = str_field(data, &["personality"]);
+    if !personality.is_empty() {
+        parts.push(format!("Personality: {}", personality));
+    }
+    let scenario = str_field(data, &["scenario", "world_scenario"]);
+    if !scenario.is_empty() {
+        parts.push(format!("Scenario: {}", scenario));
+    }
+    let greeting = str_field(data, &["first_mes", "char_greeting"]);
+    if !greeting.is_empty() {
+        parts.push(format!("First greeting: {}", greeting));
+    }
+    let examples = str_field(data, &["mes_example", "example_dialogue"]);
+    if !examples.is_empty() {
+        parts.push(format!("Example dialogue:\n{}", examples));
+    }
+    parts.join("\n\n")
+}
+
+fn card_from_json(value: Value, avatar: Option<Vec<u8>>, format: Option<&str>) -> ParsedCard {
+    let (card, is_v3) = normalize_card(value);
+    let data = &card["data"];
+    let name = match str_field(data, &["name", "char_name"]) {
+        "" => "Unnamed Character".to_string(),
+        name => name.to_string(),
+    };
+    let lorebook = data
+        .get("character_book")
+        .filter(|book| book.is_object())
+        .cloned();
+
+    ParsedCard {
+        source_format: format
+            .unwrap_or(if is_v3 { "tavern-v3" } else { "tavern-v2" })
+            .to_string(),
+        name,
+        persona: compose_persona(data),
+        first_message: str_field(data, &["first_mes"]).to_string(),
+        example_dialogue: str_field(data, &["mes_example"]).to_string(),
+        lorebook,
+        card,
+        avatar,
+    }
+}
+
+// ── Export ─────────────────────────────────────────────
+
+/// Fields of a stored character needed to produce a card.
+pub struct CardSource<'a> {
+    pub name: &'a str,
+    pub persona: &'a str,
+    pub first_message: &'a str,
+    pub example_dialogue: &'a str,
+    pub lorebook: Option<&'a Value>,
+    /// Card preserved from import, if the character came from one.
+    pub original_card: Option<&'a Value>,
+}
+
+/// Build a v3 card. Imported cards keep every original field; stored edits win.
+pub fn build_card(source: &CardSource<'_>) -> Value {
+    let mut card = source
+        .original_card
+        .cloned()
+        .map(|card| normalize_card(card).0)
+        .unwrap_or_else(|| {
+            json!({
+                "spec": "chara_card_v3",
+                "spec_version": "3.0",
+                "data": {
+                    "description": source.persona,
+                    "personality": "",
+                    "scenario": "",
+                    "creator_notes": "",
+                    "system_prompt": "",
+                    "post_history_instructions": "",
+                    "alternate_greetings": [],
+                    "tags": [],
+                    "creator": "",
+                    "character_version": "",
+                    "extensions": {},
+                    "group_only_greetings": [],
+                }
+            })
+        });
+
+    let data = &mut card["data"];
+    data["name"] = json!(source.name);
+    data["first_mes"] = json!(source.first_message);
+    data["mes_example"] = json!(source.example_dialogue);
+    match source.lorebook {
+        Some(book) => data["character_book"] = book.clone(),
+        None => {
+            if let Some(obj) = data.as_object_mut() {
+                obj.remove("character_book");
+            }
+        }
+    }
+    card
+}
+
+fn v2_copy(card: &Value) -> Value {
+    let mut v2 = card.clone();
+    v2["spec"] = json!("chara_card_v2");
+    v2["spec_version"] = json!("2.0");
+    v2
+}
+
+fn png_chunk(kind: &[u8; 4], data: &[u8]) -> Vec<u8> {
+    let mut crc = flate2::Crc::new();
+    crc.update(kind);
+    crc.update(data);
+    let mut chunk = Vec::with_capacity(data.len() + 12);
+    chunk.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    chunk.extend_from_slice(kind);
+    chunk.extend_from_slice(data);
+    chunk.extend_from_slice(&crc.sum().to_be_bytes());
+    chunk
+}
+
+fn text_chunk(keyword: &str, card: &Value) -> Vec<u8> {
+    let payload = base64::engine::general_purpose::STANDARD.encode(card.to_string());
+    let mut data = keyword.as_bytes().to_vec();
+    data.push(0);
+    data.extend_from_slice(payload.as_bytes());
+    png_chunk(b"tEXt", &data)
+}
+
+/// Plain 400×600 PNG used when a character has no avatar to embed into.
+fn placeholder_png() -> Result<Vec<u8>, KokoroError> {
+    let image = image::RgbaImage::from_pixel(400, 600, image::Rgba([236, 228, 240, 255]));
+    let mut out = Cursor::new(Vec::new());
+    image
+        .write_to(&mut out, image::ImageFormat::Png)
+        .map_err(|e| KokoroError::Internal(format!("Failed to encode placeholder PNG: {}", e)))?;
+    Ok(out.into_inner())
+}
+
+/// Embed `card` into a PNG (existing card chunks are replaced).
+pub fn write_png_card(avatar: Option<&[u8]>, card: &Value) -> Result<Vec<u8>, KokoroError> {
+    let base = match avatar.filter(|a| a.starts_with(&PNG_SIGNATURE)) {
+        Some(png) => png.to_vec(),
+        None => placeholder_png()?,
+    };
+
+    let mut out = PNG_SIGNATURE.to_vec();
+    let mut offset = PNG_SIGNATURE.len();
+    while offset + 8 <= base.len() {
+        let len = u32::from_be_bytes([
+            base[offset],
+            base[offset + 1],
+            base[offset + 2],
+            base[offset + 3],
+        ]) as usize;
+        let end = (offset + 12 + len).min(base.len());
+        let kind = &base[offset + 4..offset + 8];
+        let data = &base[(offset + 8).min(end)..(offset + 8 + len).min(end)];
+
+        let is_card_chunk = matches!(kind, b"tEXt" | b"zTXt" | b"iTXt")
+            && data
+                .split(|&b| b == 0)
+                .next()
+                .is_some_and(|k| k == b"chara" || k == b"ccv3");
+        if kind == b"IEND" {
+            out.extend(text_chunk("chara", &v2_copy(card)));
+            out.extend(text_chunk("ccv3", card));
+        }
+        if !is_card_chunk {
+            out.extend_from_slice(&base[offset..end]);
+        }
+        if kind == b"IEND" {
+            break;
+        }
+        offset = end;
+    }
+    Ok(out)
+}
+
+/// Pack `card` (and the avatar as the main icon) into a `.charx` archive.
+pub fn write_charx(avatar: Option<&[u8]>, card: &Value) -> Result<Vec<u8>, KokoroError> {
+    let mut card = card.clone();
+    let icon_path = "assets/icon/images/main.png";
+    if avatar.is_some() {
+        card["data"]["assets"] = json!([{
+            "type": "icon",
+            "uri": format!("embeded://{}", icon_path),
+            "name": "main",
+            "ext": "png",
+        }]);
+    }
+
+    let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+    zip.start_file("card.json", options)?;
+    zip.write_all(serde_json::to_string_pretty(&card)?.as_bytes())?;
+    if let Some(avatar) = avatar {
+        zip.start_file(icon_path, options)?;
+        zip.write_all(avatar)?;
+    }
+    Ok(zip.finish()?.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_v2() -> Value {
+        json!({
+            "spec": "chara_card_v2",
+            "spec_version": "2.0",
+            "data": {
+                "name": "Aiko",
+                "description": "A cheerful shrine maiden.",
+                "personality": "Kind",
+                "scenario": "",
+                "first_mes": "Welcome back!",
+                "mes_example": "<START>\n{{char}}: Hi!",
+                "character_book": { "entries": [{ "keys": ["shrine"], "content": "The shrine is old." }] },
+                "extensions": { "talkativeness": "0.5" }
+            }
+        })
+    }
+
+    #[test]
+    fn parses_v2_json_and_maps_fields() {
+        let card = parse_card(sample_v2().to_string().as_bytes()).unwrap();
+        assert_eq!(card.source_format, "tavern-v2");
+        assert_eq!(card.name, "Aiko");
+        assert_eq!(card.first_message, "Welcome back!");
+        assert!(card.persona.contains("Personality: Kind"));
+        assert!(card.lorebook.is_some());
+        assert_eq!(card.card["spec"], "chara_card_v3");
+    }
+
+    #[test]
+    fn parses_v1_flat_card() {
+        let v1 = json!({ "char_name": "Old", "char_greeting": "Yo", "char_persona": "Legacy" });
+        let card = parse_card(v1.to_string().as_bytes()).unwrap();
+        assert_eq!(card.name, "Old");
+        assert_eq!(card.first_message, "Yo");
+        assert_eq!(card.persona, "Legacy\n\nFirst greeting: Yo");
+    }
+
+    #[test]
+    fn png_round_trip_preserves_card_and_extensions() {
+        let parsed = parse_card(sample_v2().to_string().as_bytes()).unwrap();
+        let card = build_card(&CardSource {
+            name: &parsed.name,
+            persona: &parsed.persona,
+            first_message: "Edited greeting",
+            example_dialogue: &parsed.example_dialogue,
+            lorebook: parsed.lorebook.as_ref(),
+            original_card: Some(&parsed.card),
+        });
+        let png = write_png_card(None, &card).unwrap();
+        // Re-embedding must replace, not duplicate, the card chunks.
+        let png = write_png_card(Some(&png), &card).unwrap();
+        assert_eq!(
+            png_text_chunks(&png)
+                .iter()
+                .filter(|(k, _)| k == "ccv3")
+                .count(),
+            1
+        );
+
+        let reparsed = parse_card(&png).unwrap();
+        assert_eq!(reparsed.source_format, "tavern-v3");
+        assert_eq!(reparsed.first_message, "Edited greeting");
+        assert_eq!(reparsed.card["data"]["extensions"]["talkativeness"], "0.5");
+        assert!(image::load_from_memory(&png).is_ok());
+    }
+
+    #[test]
+    fn charx_round_trip_keeps_avatar() {
+        let parsed = parse_card(sample_v2().to_string().as_bytes()).unwrap();
+        let avatar = placeholder_png().unwrap();
+        let charx = write_charx(Some(&avatar), &parsed.card).unwrap();
+        let reparsed = parse_card(&charx).unwrap();
+        assert_eq!(reparsed.source_format, "charx");
+        assert_eq!(reparsed.name, "Aiko");
+        assert_eq!(reparsed.avatar.as_deref(), Some(avatar.as_slice()));
+    }
+
+    #[test]
+    fn png_without_card_is_rejected() {
+        let png = placeholder_png().unwrap();
+        assert!(matches!(parse_card(&png), Err(KokoroError::Validation(_))));
+    }
+}
@@ -0,0 +1,63 @@
+//! Standalone character contexts.
+//!
+//! The orchestrator's system prompt, emotion engine and model/voice bindings
+//! all follow the active character. A [`CharacterContext`] carries the same
+//! pieces for any stored character, so several characters can talk in one
+//! session without switching the active one back and forth.
+
+use crate::ai::context::AIOrchestrator;
+use crate::ai::emotion::EmotionEngine;
+use crate::ai::emotion_personality;
+use crate::character_profiles::{CharacterProfile, CharacterProfileService};
+use crate::error::KokoroError;
+use crate::llm::service::LlmConversationOverride;
+use crate::tts::TtsCharacterBinding;
+use tauri::{AppHandle, Manager};
+
+pub struct CharacterContext {
+    pub character_id: String,
+    pub name: String,
+    pub persona: String,
+    /// What this character calls the user.
+    pub user_nickname: String,
+    pub llm: LlmConversationOverride,
+    pub voice: TtsCharacterBinding,
+    /// Private to this context; the orchestrator's engine is not touched.
+    pub emotion: EmotionEngine,
+}
+
+impl CharacterContext {
+    pub async fn load(app: &AppHandle, character_id: &str) -> Result<Self, KokoroError> {
+        let orchestrator = app
+            .try_state::<AIOrchestrator>()
+            .ok_or_else(|| KokoroError::Internal("AI orchestrator is not ready".to_string()))?;
+        let record = crate::commands::characters::load_character(&orchestrator.db, character_id)
+            .await?
+            .ok_or_else(|| {
+                KokoroError::NotFound(format!("Character '{}' not found", character_id))
+            })?;
+        let profile = match app.try_state::<CharacterProfileService>() {
+            Some(profiles) => profiles.get_profile(character_id).await,
+            None => CharacterProfile::default(),
+        }
+        .with_record_defaults(&record);
+        let personality = match profile.personality {
+            Some(explicit) => explicit.clamped(),
+            None => {
+                emotion_personality::resolve(&orchestrator.db, &record.persona)
+                    .await
+                    .0
+            }
+        };
+
+        Ok(Self {
+            character_id: record.id,
+            name: record.name,
+            persona: record.persona,
+            user_nickname: record.user_nickname,
+            llm: profile.llm_override(),
+            voice: profile.tts_binding(),
+            emotion: EmotionEngine::new(personality),
+        })
+    }
+}
@@ -0,0 +1,349 @@
+//! Conversation checkpoints ("save points").
+//!
+//! A checkpoint remembers the last message on the active path together with
+//! the emotion and conversation state (topic, pinned state) at that moment.
+//! Rewinding does not delete anything: the turns after the checkpoint are
+//! moved aside as a variant via [`branches::fork_after`], so the user can try
+//! something else and still switch back.
+
+use crate::ai::branches;
+use crate::ai::emotion::{emotion_from_label, EmotionState};
+use crate::error::KokoroError;
+use serde::{Deserialize, Serialize};
+use sqlx::{Row, SqlitePool};
+
+/// Characters of the last message used as the label when none is given.
+const DEFAULT_LABEL_CHARS: usize = 60;
+
+/// Emotion state as stored in `emotion_json`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedEmotion {
+    pub emotion: String,
+    pub intensity: f32,
+    pub mood: f32,
+}
+
+impl SavedEmotion {
+    pub fn from_state(state: &EmotionState) -> Self {
+        Self {
+            emotion: state.emotion.to_string(),
+            intensity: state.intensity,
+            mood: state.mood,
+        }
+    }
+
+    pub fn to_state(&self) -> EmotionState {
+        EmotionState {
+            emotion: emotion_from_label(&self.emotion).unwrap_or("neutral"),
+            intensity: self.intensity,
+            mood: self.mood,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Checkpoint {
+    pub id: String,
+    pub conversation_id: String,
+    pub character_id: String,
+    pub label: String,
+    pub message_id: i64,
+    pub message_count: i64,
+    /// Missing if the stored value could not be read.
+    pub emotion: Option<SavedEmotion>,
+    pub topic: String,
+    pub pinned_state: String,
+    pub created_at: i64,
+}
+
+fn row_to_checkpoint(row: &sqlx::sqlite::SqliteRow) -> Checkpoint {
+    let emotion_json: String = row.get("emotion_json");
+    Checkpoint {
+        id: row.get("id"),
+        conversation_id: row.get("conversation_id"),
+        character_id: row.get("character_id"),
+        label: row.get("label"),
+        message_id: row.get("message_id"),
+        message_count: row.get("message_count"),
+        emotion: serde_json::from_str(&emotion_json).ok(),
+        topic: row.get("topic"),
+        pinned_state: row.get("pinned_state"),
+        created_at: row.get("created_at"),
+    }
+}
+
+/// Save the current point of a conversation.
+pub async fn create_checkpoint(
+    db: &SqlitePool,
+    conversation_id: &str,
+    label: Option<&str>,
+    emotion: &EmotionState,
+) -> Result<Checkpoint, KokoroError> {
+    let (character_id, topic, pinned_state): (String, String, String) =
+        sqlx::query_as("SELECT character_id, topic, pinned_state FROM conversations WHERE id = ?")
+            .bind(conversation_id)
+            .fetch_optional(db)
+            .await?
+            .ok_or_else(|| {
+                KokoroError::NotFound(format!("Conversation {} not found", conversation_id))
+            })?;
+
+    let last = sqlx::query(
+        "SELECT id, content, (SELECT COUNT(*) FROM conversation_messages
+                              WHERE conversation_id = ?1 AND is_active = 1) AS message_count
+         FROM conversation_messages
+         WHERE conversation_id = ?1 AND is_active = 1
+         ORDER BY id DESC LIMIT 1",
+    )
+    .bind(conversation_id)
+    .fetch_optional(db)
+    .await?
+    .ok_or_else(|| KokoroError::Validation("Conversation has no messages yet".to_string()))?;
+
+    let label = label
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .map(str::to_string)
+        .unwrap_or_else(|| {
+            let content: String = last.get("content");
+            content.trim().chars().take(DEFAULT_LABEL_CHARS).collect()
+        });
+
+    let checkpoint = Checkpoint {
+        id: uuid::Uuid::new_v4().to_string(),
+        conversation_id: conversation_id.to_string(),
+        character_id,
+        label,
+        message_id: last.get("id"),
+        message_count: last.get("message_count"),
+        emotion: Some(SavedEmotion::from_state(emotion)),
+        topic,
+        pinned_state,
+        created_at: chrono::Utc::now().timestamp(),
+    };
+
+    sqlx::query(
+        "INSERT INTO conversation_checkpoints
+         (id, conversation_id, character_id, label, message_id, message_count,
+          emotion_json, topic, pinned_state, created_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&checkpoint.id)
+    .bind(&checkpoint.conversation_id)
+    .bind(&checkpoint.character_id)
+    .bind(&checkpoint.label)
+    .bind(checkpoint.message_id)
+    .bind(checkpoint.message_count)
+    .bind(serde_json::to_string(&checkpoint.emotion)?)
+    .bind(&checkpoint.topic)
+    .bind(&checkpoint.pinned_state)
+    .bind(checkpoint.created_at)
+    .execute(db)
+    .await?;
+
+    Ok(checkpoint)
+}
+
+/// Checkpoints of a conversation, newest first.
+pub async fn list_checkpoints(
+    db: &SqlitePool,
+    conversation_id: &str,
+) -> Result<Vec<Checkpoint>, KokoroError> {
+    let rows = sqlx::query(
+        "SELECT * FROM conversation_checkpoints WHERE conversation_id = ?
+         ORDER BY created_at DESC, message_id DESC",
+    )
+    .bind(conversation_id)
+    .fetch_all(db)
+    .await?;
+    Ok(rows.iter().map(row_to_checkpoint).collect())
+}
+
+pub async fn delete_checkpoint(db: &SqlitePool, id: &str) -> Result<(), KokoroError> {
+    let result = sqlx::query("DELETE FROM conversation_checkpoints WHERE id = ?")
+        .bind(id)
+        .execute(db)
+        .await?;
+    if result.rows_affected() == 0 {
+        return Err(KokoroError::NotFound(format!(
+            "Checkpoint {} not found",
+            id
+        )));
+    }
+    Ok(())
+}
+
+/// Return the conversation to a checkpoint: later turns become a variant and
+/// the topic and pinned state are put back. The caller restores the emotion
+/// state and reloads the history.
+pub async fn rewind_to_checkpoint(db: &SqlitePool, id: &str) -> Result<Checkpoint, KokoroError> {
+    let mut tx = db.begin().await?;
+
+    let checkpoint = sqlx::query("SELECT * FROM conversation_checkpoints WHERE id = ?")
+        .bind(id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .map(|row| row_to_checkpoint(&row))
+        .ok_or_else(|| KokoroError::NotFound(format!("Checkpoint {} not found", id)))?;
+
+    branches::fork_after(&mut tx, &checkpoint.conversation_id, checkpoint.message_id).await?;
+    sqlx::query(
+        "UPDATE conversations SET topic = ?, pinned_state = ?, updated_at = ? WHERE id = ?",
+    )
+    .bind(&checkpoint.topic)
+    .bind(&checkpoint.pinned_state)
+    .bind(chrono::Utc::now().to_rfc3339())
+    .bind(&checkpoint.conversation_id)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(checkpoint)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::branches::{prepare_regeneration, switch_variant};
+
+    async fn setup_db() -> SqlitePool {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+        sqlx::query(
+            "INSERT INTO conversations (id, character_id, title, created_at, updated_at)
+             VALUES ('c1', 'char', 't', '2026-01-01', '2026-01-01')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        pool
+    }
+
+    async fn push(pool: &SqlitePool, role: &str, content: &str) {
+        sqlx::query(
+            "INSERT INTO conversation_messages (conversation_id, role, content, created_at)
+             VALUES ('c1', ?, ?, '2026-01-01')",
+        )
+        .bind(role)
+        .bind(content)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    async fn active_contents(pool: &SqlitePool) -> Vec<String> {
+        sqlx::query_scalar(
+            "SELECT content FROM conversation_messages
+             WHERE conversation_id = 'c1' AND is_active = 1 ORDER BY id ASC",
+        )
+        .fetch_all(pool)
+        .await
+        .unwrap()
+    }
+
+    async fn set_topic(pool: &SqlitePool, topic: &str) {
+        sqlx::query("UPDATE conversations SET topic = ? WHERE id = 'c1'")
+            .bind(topic)
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+
+    fn emotion(name: &'static str) -> EmotionState {
+        EmotionState {
+            emotion: name,
+            intensity: 0.7,
+            mood: 0.6,
+        }
+    }
+
+    #[tokio::test]
+    async fn rewind_keeps_later_turns_as_a_variant() {
+        let pool = setup_db().await;
+        assert!(create_checkpoint(&pool, "c1", None, &emotion("joy"))
+            .await
+            .is_err());
+
+        push(&pool, "user", "hi").await;
+        push(&pool, "assistant", "hello").await;
+        set_topic(&pool, "greetings").await;
+        let checkpoint = create_checkpoint(&pool, "c1", None, &emotion("joy"))
+            .await
+            .unwrap();
+        assert_eq!(checkpoint.label, "hello");
+        assert_eq!(checkpoint.message_count, 2);
+
+        push(&pool, "user", "let's fight").await;
+        push(&pool, "assistant", "grr").await;
+        set_topic(&pool, "argument").await;
+
+        let restored = rewind_to_checkpoint(&pool, &checkpoint.id).await.unwrap();
+        assert_eq!(restored.emotion.unwrap().to_state(), emotion("joy"));
+        assert_eq!(active_contents(&pool).await, vec!["hi", "hello"]);
+        let topic: String = sqlx::query_scalar("SELECT topic FROM conversations WHERE id = 'c1'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(topic, "greetings");
+
+        push(&pool, "user", "let's hug").await;
+        assert_eq!(
+            active_contents(&pool).await,
+            vec!["hi", "hello", "let's hug"]
+        );
+
+        // Rewinding again opens a third path off the same point.
+        rewind_to_checkpoint(&pool, &checkpoint.id).await.unwrap();
+        let groups = branches::list_variants(&pool, "c1").await.unwrap();
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].variant_count, 3);
+        switch_variant(&pool, &groups[0].id, 0).await.unwrap();
+        assert_eq!(
+            active_contents(&pool).await,
+            vec!["hi", "hello", "let's fight", "grr"]
+        );
+    }
+
+    #[tokio::test]
+    async fn rewind_past_a_regeneration_moves_it_along() {
+        let pool = setup_db().await;
+        push(&pool, "user", "q1").await;
+        push(&pool, "assistant", "a1").await;
+        let checkpoint = create_checkpoint(&pool, "c1", Some(" start "), &emotion("neutral"))
+            .await
+            .unwrap();
+        assert_eq!(checkpoint.label, "start");
+
+        push(&pool, "user", "q2").await;
+        push(&pool, "assistant", "a2").await;
+        let swipe = prepare_regeneration(&pool, "c1").await.unwrap();
+        push(&pool, "assistant", "a2'").await;
+
+        rewind_to_checkpoint(&pool, &checkpoint.id).await.unwrap();
+        assert_eq!(active_contents(&pool).await, vec!["q1", "a1"]);
+
+        let fork = &branches::list_variants(&pool, "c1").await.unwrap()[0];
+        switch_variant(&pool, &fork.id, 0).await.unwrap();
+        assert_eq!(active_contents(&pool).await, vec!["q1", "a1", "q2", "a2'"]);
+        switch_variant(&pool, &swipe.variant_group, 0)
+            .await
+            .unwrap();
+        assert_eq!(active_contents(&pool).await, vec!["q1", "a1", "q2", "a2"]);
+
+        // A checkpoint on a hidden branch cannot be rewound to.
+        switch_variant(&pool, &fork.id, 1).await.unwrap();
+        push(&pool, "user", "q3").await;
+        let later = create_checkpoint(&pool, "c1", None, &emotion("neutral"))
+            .await
+            .unwrap();
+        switch_variant(&pool, &fork.id, 0).await.unwrap();
+        assert!(matches!(
+            rewind_to_checkpoint(&pool, &later.id).await,
+            Err(KokoroError::Validation(_))
+        ));
+
+        assert_eq!(list_checkpoints(&pool, "c1").await.unwrap().len(), 2);
+        delete_checkpoint(&pool, &later.id).await.unwrap();
+        assert!(delete_checkpoint(&pool, &later.id).await.is_err());
+    }
+}
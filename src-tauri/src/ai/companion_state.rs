@@ -0,0 +1,260 @@
+//! Companion status line — the single source of truth for what the character
+//! is doing right now.
+//!
+//! Work that takes time (a chat turn waiting for its first token, TTS
+//! synthesis, a tool call, an image generation) holds an [`ActivityGuard`];
+//! long-lived modes (microphone open, audio playing in the frontend) are
+//! toggled with [`set_active`]. The visible activity is the highest-priority
+//! one currently held, and every change is broadcast as `companion:state`.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+
+pub const COMPANION_STATE_EVENT: &str = "companion:state";
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum CompanionActivity {
+    Idle,
+    Listening,
+    Thinking,
+    Speaking,
+    ExecutingTool,
+    GeneratingImage,
+}
+
+/// Highest priority first: what the user sees/hears wins over background work.
+const PRIORITY: [CompanionActivity; 5] = [
+    CompanionActivity::Speaking,
+    CompanionActivity::ExecutingTool,
+    CompanionActivity::GeneratingImage,
+    CompanionActivity::Thinking,
+    CompanionActivity::Listening,
+];
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct CompanionState {
+    pub activity: CompanionActivity,
+    /// e.g. the tool name while executing a tool.
+    pub detail: Option<String>,
+    /// Every activity currently in progress, highest priority first.
+    pub active: Vec<CompanionActivity>,
+    /// Unix ms when `activity` last changed.
+    pub since: i64,
+    /// Monotonic counter; surfaces ignore events older than the last one seen.
+    pub seq: u64,
+}
+
+#[derive(Default)]
+struct Tracker {
+    counts: HashMap<CompanionActivity, u32>,
+    flags: HashSet<CompanionActivity>,
+    details: HashMap<CompanionActivity, String>,
+    seq: u64,
+    since: i64,
+    current: Option<(CompanionActivity, Option<String>)>,
+}
+
+impl Tracker {
+    fn is_active(&self, activity: CompanionActivity) -> bool {
+        self.flags.contains(&activity) || self.counts.get(&activity).copied().unwrap_or(0) > 0
+    }
+
+    fn active(&self) -> Vec<CompanionActivity> {
+        PRIORITY
+            .iter()
+            .copied()
+            .filter(|a| self.is_active(*a))
+            .collect()
+    }
+
+    fn visible(&self) -> (CompanionActivity, Option<String>) {
+        let activity = self
+            .active()
+            .first()
+            .copied()
+            .unwrap_or(CompanionActivity::Idle);
+        (activity, self.details.get(&activity).cloned())
+    }
+
+    fn snapshot(&self) -> CompanionState {
+        let (activity, detail) = self.visible();
+        CompanionState {
+            activity,
+            detail,
+            active: self.active(),
+            since: self.since,
+            seq: self.seq,
+        }
+    }
+
+    /// Recompute the visible state; `Some` when it changed.
+    fn settle(&mut self, now_ms: i64) -> Option<CompanionState> {
+        let visible = self.visible();
+        if self.current.as_ref() == Some(&visible) {
+            return None;
+        }
+        if self.current.as_ref().map(|(a, _)| *a) != Some(visible.0) {
+            self.since = now_ms;
+        }
+        self.current = Some(visible);
+        self.seq += 1;
+        Some(self.snapshot())
+    }
+
+    fn begin(&mut self, activity: CompanionActivity, detail: Option<String>) {
+        *self.counts.entry(activity).or_insert(0) += 1;
+        match detail {
+            Some(detail) => self.details.insert(activity, detail),
+            None => self.details.remove(&activity),
+        };
+    }
+
+    fn end(&mut self, activity: CompanionActivity) {
+        if let Some(count) = self.counts.get_mut(&activity) {
+            *count = count.saturating_sub(1);
+        }
+        if !self.is_active(activity) {
+            self.details.remove(&activity);
+        }
+    }
+
+    fn set_flag(&mut self, activity: CompanionActivity, on: bool) {
+        if on {
+            self.flags.insert(activity);
+        } else {
+            self.flags.remove(&activity);
+            if !self.is_active(activity) {
+                self.details.remove(&activity);
+            }
+        }
+    }
+}
+
+pub struct CompanionStateService {
+    tracker: Mutex<Tracker>,
+}
+
+impl Default for CompanionStateService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CompanionStateService {
+    pub fn new() -> Self {
+        Self {
+            tracker: Mutex::new(Tracker::default()),
+        }
+    }
+
+    pub fn snapshot(&self) -> CompanionState {
+        self.tracker
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .snapshot()
+    }
+
+    fn update(&self, app: &AppHandle, f: impl FnOnce(&mut Tracker)) {
+        let changed = {
+            let mut tracker = self.tracker.lock().unwrap_or_else(|e| e.into_inner());
+            f(&mut tracker);
+            tracker.settle(chrono::Utc::now().timestamp_millis())
+        };
+        if let Some(state) = changed {
+            let _ = app.emit(COMPANION_STATE_EVENT, state);
+        }
+    }
+}
+
+/// Holds an activity until dropped, so early returns and errors can't leave
+/// the character stuck "thinking".
+pub struct ActivityGuard {
+    app: Option<AppHandle>,
+    activity: CompanionActivity,
+}
+
+impl ActivityGuard {
+    /// End the activity now rather than at the end of scope.
+    pub fn finish(mut self) {
+        self.release();
+    }
+
+    fn release(&mut self) {
+        if let Some(app) = self.app.take() {
+            if let Some(service) = app.try_state::<CompanionStateService>() {
+                service.update(&app, |t| t.end(self.activity));
+            }
+        }
+    }
+}
+
+impl Drop for ActivityGuard {
+    fn drop(&mut self) {
+        self.release();
+    }
+}
+
+/// Start a counted activity; it ends when the returned guard is dropped.
+pub fn enter(
+    app: &AppHandle,
+    activity: CompanionActivity,
+    detail: Option<String>,
+) -> ActivityGuard {
+    let Some(service) = app.try_state::<CompanionStateService>() else {
+        return ActivityGuard {
+            app: None,
+            activity,
+        };
+    };
+    service.update(app, |t| t.begin(activity, detail));
+    ActivityGuard {
+        app: Some(app.clone()),
+        activity,
+    }
+}
+
+/// Toggle a mode-like activity (microphone open, frontend audio playback).
+pub fn set_active(app: &AppHandle, activity: CompanionActivity, active: bool) {
+    if let Some(service) = app.try_state::<CompanionStateService>() {
+        service.update(app, |t| t.set_flag(activity, active));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use CompanionActivity::*;
+
+    #[test]
+    fn visible_activity_follows_priority_and_counts() {
+        let mut t = Tracker::default();
+        assert_eq!(t.settle(1).unwrap().activity, Idle);
+        assert!(t.settle(2).is_none());
+
+        t.set_flag(Listening, true);
+        t.begin(Thinking, None);
+        t.begin(ExecutingTool, Some("get_time".to_string()));
+        let state = t.settle(3).unwrap();
+        assert_eq!(state.activity, ExecutingTool);
+        assert_eq!(state.detail.as_deref(), Some("get_time"));
+        assert_eq!(state.active, vec![ExecutingTool, Thinking, Listening]);
+
+        // Two overlapping turns: thinking lasts until both end.
+        t.begin(Thinking, None);
+        t.end(ExecutingTool);
+        t.end(Thinking);
+        let state = t.settle(4).unwrap();
+        assert_eq!((state.activity, state.detail), (Thinking, None));
+        assert_eq!(state.since, 4);
+
+        t.end(Thinking);
+        t.end(Thinking);
+        t.set_flag(Listening, false);
+        let state = t.settle(5).unwrap();
+        assert_eq!(state.activity, Idle);
+        assert_eq!(state.seq, 4);
+    }
+}
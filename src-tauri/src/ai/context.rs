@@ -1,15 +1,27 @@
+use crate::ai::catch_up::CatchUp;
+use crate::ai::context_breakdown::{
+    estimate_tokens, ContextBreakdown, ContextSection, HistoryCutoff, HistoryWindow,
+    InjectedMemory, SectionPlacement, SummaryUsed,
+};
 use crate::ai::curiosity::CuriosityModule;
+use crate::ai::emotion::EmotionEngine;
+use crate::ai::emotion_personality::EmotionPersonality;
+use crate::ai::history::{
+    load_conversation_messages, ChatSurface, ConversationHistories, HistoryKey,
+};
 use crate::ai::idle_behaviors::IdleBehaviorSystem;
 use crate::ai::initiative::InitiativeSystem;
+use crate::ai::input_queue::InputQueue;
 use crate::ai::memory::MemoryManager;
 use crate::ai::router::{ModelRouter, ModelType};
+use crate::ai::system_state::{describe_system_state, SystemState};
+use crate::ai::topic_shift::{ExtractionTrigger, TopicShiftOptions, TopicTracker};
 use crate::llm::messages::user_text_message;
 use crate::llm::provider::LlmProvider;
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use sqlx::{Row, SqlitePool};
-use std::collections::{HashMap, VecDeque};
-use std::str::FromStr;
+use std::collections::HashMap;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Instant;
@@ -34,12 +46,22 @@ pub fn is_vision_context_message(message: &Message) -> bool {
             == Some("vision_observation")
 }
 
+/// Messages added while privacy mode was on.
+pub fn is_incognito_message(message: &Message) -> bool {
+    message
+        .metadata
+        .as_ref()
+        .and_then(|meta| meta.get("incognito"))
+        .and_then(|value| value.as_bool())
+        == Some(true)
+}
+
 pub fn is_memory_candidate_message(message: &Message) -> bool {
-    !is_vision_context_message(message)
+    !is_vision_context_message(message) && !is_incognito_message(message)
 }
 
 pub fn is_summary_candidate_message(message: &Message) -> bool {
-    !is_vision_context_message(message)
+    !is_vision_context_message(message) && !is_incognito_message(message)
 }
 
 fn latest_vision_context_index(messages: &[Message]) -> Option<usize> {
@@ -90,6 +112,23 @@ fn truncate_message_content(content: String, max_chars: usize) -> String {
     }
 }
 
+/// Text of the newest real user turn; hidden instructions are never stored,
+/// so this is what the user actually wrote.
+fn latest_user_text(history: &[Message]) -> Option<&str> {
+    history
+        .iter()
+        .rev()
+        .find(|msg| {
+            msg.role == "user"
+                && msg
+                    .metadata
+                    .as_ref()
+                    .and_then(|meta| meta.get("type"))
+                    .is_none()
+        })
+        .map(|msg| msg.content.as_str())
+}
+
 fn normalized_language_name(language: &str) -> Option<&str> {
     let trimmed = language.trim();
     (!trimmed.is_empty()).then_some(trimmed)
@@ -105,7 +144,11 @@ fn memory_write_language_instruction(response_language: &str) -> Option<String>
     ))
 }
 
-fn build_conversation_summary_prompt(transcript: &str, target_language: &str) -> String {
+fn build_conversation_summary_prompt(
+    transcript: &str,
+    previous_summary: Option<&str>,
+    target_language: &str,
+) -> String {
     let language_requirement = normalized_language_name(target_language)
         .map(|language| {
             format!(
@@ -114,18 +157,87 @@ fn build_conversation_summary_prompt(transcript: &str, target_language: &str) ->
         })
         .unwrap_or_default();
 
-    format!(
-        "Summarize the following conversation in 2-3 sentences, focusing on key facts, \
-         decisions, emotional shifts, and unresolved threads.{language_requirement} \
-         Output only the summary, no preamble.\n\n{}",
-        transcript
-    )
+    match previous_summary {
+        Some(previous) => format!(
+            "Below is the running summary of a conversation so far, followed by the messages that came next. \
+             Rewrite it as one updated summary in at most 6 sentences, keeping key facts, decisions, \
+             emotional shifts, and unresolved threads from both; drop details that no longer matter.{language_requirement} \
+             Output only the summary, no preamble.\n\nSummary so far:\n{}\n\nNew messages:\n{}",
+            previous, transcript
+        ),
+        None => format!(
+            "Summarize the following conversation in 2-3 sentences, focusing on key facts, \
+             decisions, emotional shifts, and unresolved threads.{language_requirement} \
+             Output only the summary, no preamble.\n\n{}",
+            transcript
+        ),
+    }
+}
+
+/// Conservative estimate for mixed CJK/Latin text.
+const HISTORY_CHARS_PER_TOKEN: usize = 2;
+const HISTORY_TOKEN_BUDGET: usize = 6000;
+const HISTORY_MAX_MESSAGES: usize = 20;
+
+/// Index of the oldest message that still fits the raw history budget.
+/// Walks backwards from the newest message; everything before the returned
+/// index is left to the rolling conversation summary.
+fn history_window_start(history: &[Message]) -> usize {
+    let budget_chars = HISTORY_TOKEN_BUDGET * HISTORY_CHARS_PER_TOKEN;
+    let mut used_chars = 0usize;
+    let mut selected = 0usize;
+
+    for msg in history.iter().rev() {
+        let msg_chars = msg.content.chars().count();
+        if used_chars + msg_chars > budget_chars && selected > 0 {
+            break;
+        }
+        used_chars += msg_chars;
+        selected += 1;
+        if selected >= HISTORY_MAX_MESSAGES {
+            break;
+        }
+    }
+    history.len() - selected
+}
+
+/// How `history_window_start` trimmed `eligible`, out of `available` buffered messages.
+fn describe_history_window(
+    available: usize,
+    eligible: &[Message],
+    window_start: usize,
+) -> HistoryWindow {
+    let included = &eligible[window_start..];
+    let cutoff = (window_start > 0).then(|| {
+        if included.len() >= HISTORY_MAX_MESSAGES {
+            HistoryCutoff::MessageLimit
+        } else {
+            HistoryCutoff::TokenBudget
+        }
+    });
+    HistoryWindow {
+        available,
+        excluded: available.saturating_sub(eligible.len()),
+        included: included.len(),
+        dropped: window_start,
+        cutoff,
+        truncated_messages: included
+            .iter()
+            .filter(|msg| msg.content.ends_with(TRUNCATION_MARKER))
+            .count(),
+        budget_tokens: HISTORY_TOKEN_BUDGET,
+        used_tokens: included
+            .iter()
+            .map(|msg| estimate_tokens(&msg.content))
+            .sum(),
+    }
 }
 
 pub struct AIOrchestrator {
     pub db: SqlitePool,
     pub system_prompt: Arc<Mutex<String>>,
-    pub history: Arc<Mutex<VecDeque<Message>>>,
+    /// Per-conversation buffers; see [`ChatSurface`] for who reads which.
+    pub history: Arc<Mutex<ConversationHistories>>,
     pub max_history_tokens: usize, // Soft limit for history
     pub memory_manager: Arc<MemoryManager>,
     pub router: Arc<ModelRouter>,
@@ -133,14 +245,17 @@ pub struct AIOrchestrator {
     message_count: Arc<Mutex<u64>>,
     /// Counts user messages that occurred while the memory system was enabled.
     memory_trigger_count: Arc<Mutex<u64>>,
-    /// History index boundary used to prevent extracting conversations from disabled periods.
-    memory_history_boundary: Arc<Mutex<usize>>,
     /// Current character ID for memory isolation.
     character_id: Arc<Mutex<String>>,
     /// In-memory cooldown map for memory event trigger throttling.
     memory_event_cooldowns: Arc<Mutex<HashMap<String, Instant>>>,
+    /// Per-character topic segmentation driving memory extraction.
+    topic_trackers: Arc<Mutex<HashMap<String, TopicTracker>>>,
     /// Global toggle for all automatic memory reads/writes/injection.
     memory_enabled: Arc<AtomicBool>,
+    /// Privacy mode: nothing from the current session is persisted or
+    /// extracted into memory. Existing memories are still read.
+    incognito: Arc<AtomicBool>,
     /// Timestamp of last user activity (for idle detection).
     pub last_activity: Arc<Mutex<Instant>>,
     /// Total message count across sessions (for relationship depth).
@@ -155,28 +270,44 @@ pub struct AIOrchestrator {
     character_name: Arc<Mutex<String>>,
     /// User name for {{user}} placeholder replacement.
     user_name: Arc<Mutex<String>>,
+    /// Active character's emotion personality, set by `character_profiles::switch_character`.
+    personality: Arc<Mutex<EmotionPersonality>>,
 
     // Autonomous Behavior Modules
+    pub emotion: Arc<Mutex<EmotionEngine>>,
     pub curiosity: Arc<Mutex<CuriosityModule>>,
     pub initiative: Arc<Mutex<InitiativeSystem>>,
     pub idle_behaviors: Arc<Mutex<IdleBehaviorSystem>>,
+    /// Messages sent while a reply is still streaming, per chat window.
+    pub input_queue: Arc<InputQueue>,
     /// Whether proactive (idle auto-talk) messages are enabled.
     pub proactive_enabled: Arc<std::sync::atomic::AtomicBool>,
     /// 当前活跃对话 ID
     pub current_conversation_id: Arc<Mutex<Option<String>>>,
+    /// Conversations of surfaces other than the desktop, e.g. a Telegram chat
+    /// after `/new`.
+    surface_conversations: Arc<Mutex<HashMap<ChatSurface, String>>>,
     /// Context management strategy: "window" | "summary"
     pub context_strategy: Arc<Mutex<String>>,
     /// Max characters per message before truncation
     pub max_message_chars: Arc<Mutex<usize>>,
     /// Screen context history injected into the LLM prompt: "latest" | "full".
     pub vision_context_history_mode: Arc<Mutex<String>>,
+    /// Latest battery / network snapshot, refreshed by the heartbeat loop.
+    pub system_state: Arc<Mutex<Option<SystemState>>>,
+    /// Memories injected into the latest prompt, checked against the next reply
+    /// to feed usage-driven importance re-scoring.
+    last_injected_memories: Arc<Mutex<Vec<(i64, String)>>>,
+    /// What the latest `compose_prompt` put in front of the model.
+    last_context_breakdown: Arc<Mutex<Option<ContextBreakdown>>>,
+    /// Recap for a conversation reopened after a long break.
+    catch_up: Arc<Mutex<Option<CatchUp>>>,
 }
 
 impl AIOrchestrator {
     pub async fn new(db_url: &str) -> Result<Self> {
         // Create database if it doesn't exist
-        let options = sqlx::sqlite::SqliteConnectOptions::from_str(db_url)?.create_if_missing(true);
-        let pool = SqlitePool::connect_with(options).await?;
+        let pool = crate::db::connect(db_url).await?;
 
         // Run all database migrations
         sqlx::migrate!("./migrations").run(&pool).await?;
@@ -194,16 +325,17 @@ impl AIOrchestrator {
         Ok(Self {
             db: pool,
             system_prompt: Arc::new(Mutex::new("You are a helpful assistant.".to_string())),
-            history: Arc::new(Mutex::new(VecDeque::new())),
+            history: Arc::new(Mutex::new(ConversationHistories::default())),
             max_history_tokens: 4000,
             memory_manager,
             router: Arc::new(ModelRouter::new()),
             message_count: Arc::new(Mutex::new(0)),
             memory_trigger_count: Arc::new(Mutex::new(0)),
-            memory_history_boundary: Arc::new(Mutex::new(0)),
             character_id: Arc::new(Mutex::new("default".to_string())),
             memory_event_cooldowns: Arc::new(Mutex::new(HashMap::new())),
+            topic_trackers: Arc::new(Mutex::new(HashMap::new())),
             memory_enabled: Arc::new(AtomicBool::new(true)),
+            incognito: Arc::new(AtomicBool::new(false)),
             last_activity: Arc::new(Mutex::new(Instant::now())),
             conversation_count: Arc::new(Mutex::new(0)),
             response_language: Arc::new(Mutex::new(String::new())),
@@ -211,14 +343,22 @@ impl AIOrchestrator {
             jailbreak_prompt: Arc::new(Mutex::new(String::new())),
             character_name: Arc::new(Mutex::new("Kokoro".to_string())),
             user_name: Arc::new(Mutex::new("User".to_string())),
+            personality: Arc::new(Mutex::new(EmotionPersonality::default())),
+            emotion: Arc::new(Mutex::new(EmotionEngine::default())),
             curiosity: Arc::new(Mutex::new(CuriosityModule::new())),
             initiative: Arc::new(Mutex::new(InitiativeSystem::new())),
             idle_behaviors: Arc::new(Mutex::new(IdleBehaviorSystem::new())),
+            input_queue: Arc::new(InputQueue::default()),
             proactive_enabled: Arc::new(std::sync::atomic::AtomicBool::new(true)),
             current_conversation_id: Arc::new(Mutex::new(None)),
+            surface_conversations: Arc::new(Mutex::new(HashMap::new())),
             context_strategy: Arc::new(Mutex::new("window".to_string())),
             max_message_chars: Arc::new(Mutex::new(2000)),
             vision_context_history_mode: Arc::new(Mutex::new("latest".to_string())),
+            system_state: Arc::new(Mutex::new(None)),
+            last_injected_memories: Arc::new(Mutex::new(Vec::new())),
+            last_context_breakdown: Arc::new(Mutex::new(None)),
+            catch_up: Arc::new(Mutex::new(None)),
         })
     }
 
@@ -246,6 +386,16 @@ impl AIOrchestrator {
         *lang = language;
     }
 
+    /// Metadata for a user message in auto language mode (no response
+    /// language configured): the language the message was detected as.
+    pub async fn user_message_metadata(&self, text: &str) -> Option<String> {
+        if !self.response_language.lock().await.trim().is_empty() {
+            return None;
+        }
+        crate::ai::language_detect::detect_language(text)
+            .map(|detected| serde_json::json!({ "detected_language": detected.code }).to_string())
+    }
+
     pub async fn set_user_language(&self, language: String) {
         let mut lang = self.user_language.lock().await;
         *lang = language;
@@ -256,6 +406,19 @@ impl AIOrchestrator {
         *cn = name;
     }
 
+    pub async fn get_character_name(&self) -> String {
+        self.character_name.lock().await.clone()
+    }
+
+    pub async fn set_personality(&self, personality: EmotionPersonality) {
+        *self.personality.lock().await = personality;
+        self.emotion.lock().await.set_personality(personality);
+    }
+
+    pub async fn get_personality(&self) -> EmotionPersonality {
+        *self.personality.lock().await
+    }
+
     pub async fn set_user_name(&self, name: String) {
         let mut un = self.user_name.lock().await;
         *un = name;
@@ -313,55 +476,121 @@ impl AIOrchestrator {
         metadata: Option<String>,
         character_id: &str,
         summary_provider: Option<Arc<dyn LlmProvider>>,
+    ) {
+        self.add_message_on(
+            ChatSurface::Desktop,
+            role,
+            content,
+            metadata,
+            character_id,
+            summary_provider,
+        )
+        .await;
+    }
+
+    /// Record a turn in `surface`'s conversation.
+    pub async fn add_message_on(
+        &self,
+        surface: ChatSurface,
+        role: String,
+        content: String,
+        metadata: Option<String>,
+        character_id: &str,
+        summary_provider: Option<Arc<dyn LlmProvider>>,
     ) {
         let summary_provider = summary_provider.clone();
+        let incognito = self.is_incognito();
         // Track user message count for memory extraction triggers
         if role == "user" {
             let mut count = self.message_count.lock().await;
             *count += 1;
-            if self.is_memory_enabled() {
+            if self.is_memory_write_enabled() {
                 let mut memory_count = self.memory_trigger_count.lock().await;
                 *memory_count += 1;
             }
         }
 
+        // Check whether the reply drew on the memories injected for it.
+        if role == "assistant" && self.is_memory_write_enabled() && !content.trim().is_empty() {
+            let is_tool_call_turn = metadata
+                .as_deref()
+                .is_some_and(|raw| raw.contains("\"assistant_tool_calls\""));
+            if !is_tool_call_turn {
+                let injected = std::mem::take(&mut *self.last_injected_memories.lock().await);
+                if !injected.is_empty() {
+                    let memory_manager = self.memory_manager.clone();
+                    let reply = content.clone();
+                    tauri::async_runtime::spawn(async move {
+                        if let Err(e) = memory_manager
+                            .record_memory_references(&injected, &reply)
+                            .await
+                        {
+                            tracing::warn!(target: "memory", "[Memory] Failed to record references: {}", e);
+                        }
+                    });
+                }
+            }
+        }
+
+        // Learn "call me ..." style requests; incognito turns leave no trace.
+        if role == "user" && !incognito {
+            match crate::ai::address_forms::learn_from_message(&self.db, character_id, &content)
+                .await
+            {
+                Ok(true) => {
+                    tracing::info!(target: "context", "[Context] Learned a form of address for {}", character_id);
+                }
+                Ok(false) => {}
+                Err(e) => {
+                    tracing::warn!(target: "context", "[Context] Failed to learn address forms for {}: {}", character_id, e);
+                }
+            }
+        }
+
         // Truncate single message before it enters persisted conversation history.
         let max_chars = *self.max_message_chars.lock().await;
         let content = truncate_message_content(content, max_chars);
 
+        // Load an evicted buffer before this turn lands in the DB, or it
+        // would be read back and then pushed a second time.
+        self.history_key(surface).await;
         // Persist to database FIRST so no code path can skip it
-        let _ = self
-            .persist_message(&role, &content, metadata.as_deref(), character_id)
-            .await;
-        let current_conversation_id = self.current_conversation_id.lock().await.clone();
+        if !incognito {
+            let _ = self
+                .persist_message(surface, &role, &content, metadata.as_deref(), character_id)
+                .await;
+        }
+        let current_conversation_id = self.conversation_id_on(surface).await;
+        let key = HistoryKey::new(surface, current_conversation_id.as_deref());
 
-        let mut history = self.history.lock().await;
-        let parsed_metadata = metadata
+        let mut parsed_metadata = metadata
             .as_deref()
             .and_then(|raw| serde_json::from_str::<serde_json::Value>(raw).ok());
-        history.push_back(Message {
-            role: role.clone(),
-            content: content.clone(),
-            metadata: parsed_metadata,
-        });
-
-        // Rolling window: keep at most 20 messages in memory. Summary generation is now
+        if incognito {
+            // Tagged so the extractor skips it even if a job was already queued.
+            match parsed_metadata.as_mut().and_then(|m| m.as_object_mut()) {
+                Some(object) => {
+                    object.insert("incognito".to_string(), serde_json::Value::Bool(true));
+                }
+                None => parsed_metadata = Some(serde_json::json!({ "incognito": true })),
+            }
+        }
+        // Rolling window: keep at most MAX_BUFFERED_MESSAGES in memory. Summary generation is now
         // non-destructive and derives from persisted conversation_messages instead of popped history.
-        let strategy = self.context_strategy.lock().await.clone();
-        let evicted = if history.len() > 20 {
-            history.pop_front();
-            true
-        } else {
-            false
+        let keep_recent = {
+            let mut histories = self.history.lock().await;
+            let buffer = histories.entry(&key);
+            buffer.push(Message {
+                role: role.clone(),
+                content: content.clone(),
+                metadata: parsed_metadata,
+            });
+            // Messages older than what compose_prompt will send raw get folded into the summary.
+            buffer.messages.len() - history_window_start(buffer.messages.make_contiguous())
         };
-        drop(history);
-
-        if evicted {
-            let mut boundary = self.memory_history_boundary.lock().await;
-            *boundary = boundary.saturating_sub(1);
-        }
+        let strategy = self.context_strategy.lock().await.clone();
 
-        if strategy == "summary" && self.is_memory_enabled() {
+        if strategy == "summary" && self.is_memory_write_enabled() {
             if let (Some(conversation_id), Some(provider)) =
                 (current_conversation_id.clone(), summary_provider)
             {
@@ -370,7 +599,7 @@ impl AIOrchestrator {
                 let summary_language = self.response_language.lock().await.clone();
                 tauri::async_runtime::spawn(async move {
                     let task = match memory_manager
-                        .get_conversation_summary_task(&conversation_id, &cid)
+                        .get_conversation_summary_task(&conversation_id, &cid, keep_recent)
                         .await
                     {
                         Ok(Some(task)) => task,
@@ -397,8 +626,11 @@ impl AIOrchestrator {
                         return;
                     }
 
-                    let prompt =
-                        build_conversation_summary_prompt(&task.transcript, &summary_language);
+                    let prompt = build_conversation_summary_prompt(
+                        &task.transcript,
+                        task.previous_summary.as_deref(),
+                        &summary_language,
+                    );
 
                     match provider.chat(vec![user_text_message(prompt)], None).await {
                         Ok(text) if !text.trim().is_empty() => {
@@ -436,48 +668,26 @@ impl AIOrchestrator {
     /// 将消息持久化到 SQLite，如果没有活跃对话则自动创建
     async fn persist_message(
         &self,
+        surface: ChatSurface,
         role: &str,
         content: &str,
         metadata: Option<&str>,
         character_id: &str,
     ) -> Result<()> {
-        let cid = character_id;
-        let mut conv_id_lock = self.current_conversation_id.lock().await;
-
-        let conv_id = if let Some(ref id) = *conv_id_lock {
-            id.clone()
-        } else {
-            // 自动创建新对话
-            let new_id = uuid::Uuid::new_v4().to_string();
-            let title = if role == "user" {
-                let chars: Vec<char> = content.chars().collect();
-                if chars.len() > 20 {
-                    format!("{}...", chars[..20].iter().collect::<String>())
-                } else {
-                    content.to_string()
-                }
+        // 自动创建新对话
+        let title = if role == "user" {
+            let chars: Vec<char> = content.chars().collect();
+            if chars.len() > 20 {
+                format!("{}...", chars[..20].iter().collect::<String>())
             } else {
-                "新对话".to_string()
-            };
-            let now = chrono::Utc::now().to_rfc3339();
-
-            sqlx::query(
-                "INSERT INTO conversations (id, character_id, title, topic, pinned_state, created_at, updated_at) VALUES (?, ?, ?, '', '{}', ?, ?)"
-            )
-            .bind(&new_id)
-            .bind(cid)
-            .bind(&title)
-            .bind(&now)
-            .bind(&now)
-            .execute(&self.db)
-            .await?;
-
-            *conv_id_lock = Some(new_id.clone());
-            // Persist conversation_id to disk for hot-reload recovery
-            Self::persist_conversation_id(Some(&new_id));
-            new_id
+                content.to_string()
+            }
+        } else {
+            "新对话".to_string()
         };
-        drop(conv_id_lock);
+        let conv_id = self
+            .ensure_conversation(surface, character_id, &title)
+            .await?;
 
         let now = chrono::Utc::now().to_rfc3339();
         sqlx::query(
@@ -520,6 +730,102 @@ impl AIOrchestrator {
         Ok(())
     }
 
+    /// The conversation `surface` is talking in, if one was persisted yet.
+    pub async fn conversation_id_on(&self, surface: ChatSurface) -> Option<String> {
+        match surface {
+            ChatSurface::Desktop => self.current_conversation_id.lock().await.clone(),
+            _ => self
+                .surface_conversations
+                .lock()
+                .await
+                .get(&surface)
+                .cloned(),
+        }
+    }
+
+    /// `surface`'s conversation, created with `title` if it has none yet.
+    /// The slot stays locked meanwhile so racing turns share one conversation
+    /// and the draft buffer is handed over before anyone else can use it.
+    async fn ensure_conversation(
+        &self,
+        surface: ChatSurface,
+        character_id: &str,
+        title: &str,
+    ) -> Result<String> {
+        if surface == ChatSurface::Desktop {
+            let mut conv_id = self.current_conversation_id.lock().await;
+            if let Some(id) = conv_id.clone() {
+                return Ok(id);
+            }
+            let id = self.create_conversation(character_id, title).await?;
+            self.history.lock().await.adopt(surface, &id);
+            *conv_id = Some(id.clone());
+            // Persist conversation_id to disk for hot-reload recovery
+            Self::persist_conversation_id(Some(&id));
+            return Ok(id);
+        }
+        let mut conversations = self.surface_conversations.lock().await;
+        if let Some(id) = conversations.get(&surface) {
+            return Ok(id.clone());
+        }
+        let id = self.create_conversation(character_id, title).await?;
+        self.history.lock().await.adopt(surface, &id);
+        conversations.insert(surface, id.clone());
+        Ok(id)
+    }
+
+    async fn create_conversation(&self, character_id: &str, title: &str) -> Result<String> {
+        let id = uuid::Uuid::new_v4().to_string();
+        let now = chrono::Utc::now().to_rfc3339();
+        sqlx::query(
+            "INSERT INTO conversations (id, character_id, title, topic, pinned_state, created_at, updated_at) VALUES (?, ?, ?, '', '{}', ?, ?)"
+        )
+        .bind(&id)
+        .bind(character_id)
+        .bind(title)
+        .bind(&now)
+        .bind(&now)
+        .execute(&self.db)
+        .await?;
+        Ok(id)
+    }
+
+    /// Key of `surface`'s buffer. A conversation buffer that was evicted from
+    /// the cache is loaded from the DB again.
+    async fn history_key(&self, surface: ChatSurface) -> HistoryKey {
+        let conversation_id = self.conversation_id_on(surface).await;
+        let key = HistoryKey::new(surface, conversation_id.as_deref());
+        let Some(id) = conversation_id else {
+            return key;
+        };
+        if self.history.lock().await.contains(&key) {
+            return key;
+        }
+        match load_conversation_messages(&self.db, &id).await {
+            Ok(messages) => {
+                let mut histories = self.history.lock().await;
+                if !histories.contains(&key) {
+                    histories.restore(&key, messages);
+                }
+            }
+            Err(e) => {
+                tracing::error!(target: "context", "[Context] Failed to load history of '{}': {}", id, e);
+            }
+        }
+        key
+    }
+
+    /// `surface`'s buffered messages, oldest first.
+    pub async fn history_on(&self, surface: ChatSurface) -> Vec<Message> {
+        let key = self.history_key(surface).await;
+        self.history
+            .lock()
+            .await
+            .get(&key)
+            .map(|buffer| buffer.messages.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
     /// Persist current_conversation_id to disk for hot-reload recovery.
     pub fn persist_conversation_id(id: Option<&str>) {
         let app_data = dirs_next::data_dir()
@@ -558,31 +864,15 @@ impl AIOrchestrator {
     }
 
     /// Insert a streaming assistant draft into the DB. Returns the row id for later update.
+    /// In privacy mode nothing is written and the returned id is 0.
     pub async fn persist_streaming_draft(&self, content: &str, character_id: &str) -> Result<i64> {
-        let cid = character_id;
-        let mut conv_id_lock = self.current_conversation_id.lock().await;
-
+        if self.is_incognito() {
+            return Ok(0);
+        }
         // Ensure conversation exists
-        let conv_id = if let Some(ref id) = *conv_id_lock {
-            id.clone()
-        } else {
-            let new_id = uuid::Uuid::new_v4().to_string();
-            let now = chrono::Utc::now().to_rfc3339();
-            sqlx::query(
-                "INSERT INTO conversations (id, character_id, title, topic, pinned_state, created_at, updated_at) VALUES (?, ?, ?, '', '{}', ?, ?)"
-            )
-            .bind(&new_id)
-            .bind(cid)
-            .bind("新对话")
-            .bind(&now)
-            .bind(&now)
-            .execute(&self.db)
+        let conv_id = self
+            .ensure_conversation(ChatSurface::Desktop, character_id, "新对话")
             .await?;
-            *conv_id_lock = Some(new_id.clone());
-            Self::persist_conversation_id(Some(&new_id));
-            new_id
-        };
-        drop(conv_id_lock);
 
         let now = chrono::Utc::now().to_rfc3339();
         let result = sqlx::query(
@@ -604,6 +894,9 @@ impl AIOrchestrator {
         content: &str,
         metadata: Option<&str>,
     ) -> Result<()> {
+        if self.is_incognito() {
+            return Ok(());
+        }
         sqlx::query("UPDATE conversation_messages SET content = ?, metadata = ? WHERE id = ?")
             .bind(content)
             .bind(metadata)
@@ -643,11 +936,11 @@ impl AIOrchestrator {
 
     /// Returns the last `n` messages from history for memory extraction.
     pub async fn get_recent_history(&self, n: usize) -> Vec<Message> {
-        let history = self.history.lock().await;
-        let filtered = history
-            .iter()
-            .filter(|message| is_summary_candidate_message(message))
-            .cloned()
+        let filtered = self
+            .history_on(ChatSurface::Desktop)
+            .await
+            .into_iter()
+            .filter(is_summary_candidate_message)
             .collect::<Vec<_>>();
         let start = filtered.len().saturating_sub(n);
         filtered.into_iter().skip(start).collect()
@@ -655,11 +948,23 @@ impl AIOrchestrator {
 
     /// Returns the last `n` messages after the current memory boundary.
     pub async fn get_recent_memory_history(&self, n: usize) -> Vec<Message> {
-        let history = self.history.lock().await;
-        let boundary = (*self.memory_history_boundary.lock().await).min(history.len());
-        let filtered = history
-            .iter()
-            .skip(boundary)
+        self.get_recent_memory_history_on(ChatSurface::Desktop, n)
+            .await
+    }
+
+    /// Like [`get_recent_memory_history`](Self::get_recent_memory_history)
+    /// for `surface`'s conversation.
+    pub async fn get_recent_memory_history_on(
+        &self,
+        surface: ChatSurface,
+        n: usize,
+    ) -> Vec<Message> {
+        let key = self.history_key(surface).await;
+        let histories = self.history.lock().await;
+        let filtered = histories
+            .get(&key)
+            .into_iter()
+            .flat_map(|buffer| buffer.unseen())
             .filter(|message| is_memory_candidate_message(message))
             .cloned()
             .collect::<Vec<_>>();
@@ -675,17 +980,43 @@ impl AIOrchestrator {
         self.memory_enabled.clone()
     }
 
+    pub fn is_incognito(&self) -> bool {
+        self.incognito.load(Ordering::SeqCst)
+    }
+
+    /// Whether the current session may be written into memory (extraction,
+    /// consolidation triggers, summaries). False in privacy mode.
+    pub fn is_memory_write_enabled(&self) -> bool {
+        self.is_memory_enabled() && !self.is_incognito()
+    }
+
+    /// Enter or leave privacy mode. Either way the in-memory session starts
+    /// over, so private turns never leak into a persisted conversation's
+    /// context and vice versa. Returns false when the mode was already set.
+    pub async fn set_incognito(&self, enabled: bool) -> bool {
+        if self.incognito.swap(enabled, Ordering::SeqCst) == enabled {
+            return false;
+        }
+        self.history.lock().await.discard_drafts();
+        self.surface_conversations.lock().await.clear();
+        *self.memory_trigger_count.lock().await = 0;
+        self.topic_trackers.lock().await.clear();
+        self.last_injected_memories.lock().await.clear();
+        *self.current_conversation_id.lock().await = None;
+        if !enabled {
+            Self::persist_conversation_id(None);
+        }
+        true
+    }
+
     pub async fn set_memory_enabled(&self, enabled: bool) {
         self.memory_enabled.store(enabled, Ordering::SeqCst);
         {
             let mut trigger_count = self.memory_trigger_count.lock().await;
             *trigger_count = 0;
         }
-        {
-            let history_len = self.history.lock().await.len();
-            let mut boundary = self.memory_history_boundary.lock().await;
-            *boundary = history_len;
-        }
+        self.topic_trackers.lock().await.clear();
+        self.history.lock().await.mark_all_seen();
     }
 
     /// Append a message to in-memory history only and keep the memory boundary aligned
@@ -694,26 +1025,35 @@ impl AIOrchestrator {
         let max_chars = *self.max_message_chars.lock().await;
         message.content = truncate_message_content(message.content, max_chars);
 
-        let mut history = self.history.lock().await;
-        history.push_back(message);
-        let evicted = if history.len() > 20 {
-            history.pop_front();
-            true
-        } else {
-            false
-        };
-        drop(history);
-
-        if evicted {
-            let mut boundary = self.memory_history_boundary.lock().await;
-            *boundary = boundary.saturating_sub(1);
-        }
+        let key = self.history_key(ChatSurface::Desktop).await;
+        self.history.lock().await.entry(&key).push(message);
     }
 
     /// Composes a prompt based on the user query, budgeting tokens for context
     pub async fn compose_prompt(
         &self,
         query: &str,
+        allow_image_gen: bool,
+        tool_prompt: Option<String>,
+        native_tools_enabled: bool,
+        character_id: &str,
+    ) -> Result<(Vec<Message>, Vec<String>)> {
+        self.compose_prompt_on(
+            ChatSurface::Desktop,
+            query,
+            allow_image_gen,
+            tool_prompt,
+            native_tools_enabled,
+            character_id,
+        )
+        .await
+    }
+
+    /// [`compose_prompt`](Self::compose_prompt) for `surface`'s conversation.
+    pub async fn compose_prompt_on(
+        &self,
+        surface: ChatSurface,
+        query: &str,
         _allow_image_gen: bool,
         tool_prompt: Option<String>,
         native_tools_enabled: bool,
@@ -731,11 +1071,22 @@ impl AIOrchestrator {
         // Only if query looks like it needs context or every N turns
         // For now, always try to fetch relevant memories (scoped to current character)
         let cid = character_id;
-        let current_conversation_id = self.current_conversation_id.lock().await.clone();
+        let current_conversation_id = self.conversation_id_on(surface).await;
         let mut warnings: Vec<String> = Vec::new();
+        let mut breakdown = ContextBreakdown::new(cid, current_conversation_id.clone());
         let memories = if self.is_memory_enabled() {
             match self.memory_manager.search_memories(query, 5, cid).await {
-                Ok(m) => Some(m),
+                Ok(m) => {
+                    let ids: Vec<i64> = m.iter().map(|snippet| snippet.id).collect();
+                    if let Err(e) = self.memory_manager.record_memory_retrievals(&ids).await {
+                        tracing::warn!(target: "memory", "[Memory] Failed to record retrievals: {}", e);
+                    }
+                    *self.last_injected_memories.lock().await = m
+                        .iter()
+                        .map(|snippet| (snippet.id, snippet.content.clone()))
+                        .collect();
+                    Some(m)
+                }
                 Err(e) => {
                     warnings.push(format!("记忆检索失败（本次对话将不含记忆上下文）：{e}"));
                     None
@@ -744,6 +1095,12 @@ impl AIOrchestrator {
         } else {
             None
         };
+        let catch_up = self
+            .catch_up
+            .lock()
+            .await
+            .clone()
+            .filter(|c| current_conversation_id.as_deref() == Some(c.conversation_id.as_str()));
         let conversation_summary = if self.is_memory_enabled() {
             if let Some(ref conversation_id) = current_conversation_id {
                 self.memory_manager
@@ -776,9 +1133,27 @@ impl AIOrchestrator {
 
         // Read all lock-guarded values upfront and drop locks immediately.
         // This prevents holding multiple mutexes across .await points.
-        let sp = self.system_prompt.lock().await.clone();
+        // A request for a character other than the active one (e.g. a Telegram
+        // bot bound to a specific character) uses that character's stored persona.
+        let stored_character = if cid != self.get_character_id().await {
+            crate::commands::characters::load_character(&self.db, cid)
+                .await
+                .unwrap_or_else(|e| {
+                    tracing::warn!(target: "context", "[Context] Failed to load character {}: {}", cid, e);
+                    None
+                })
+        } else {
+            None
+        };
+        let (sp, char_name) = match stored_character {
+            Some(record) => (record.persona, record.name),
+            None => (
+                self.system_prompt.lock().await.clone(),
+                self.character_name.lock().await.clone(),
+            ),
+        };
         let vision_context_history_mode = self.vision_context_history_mode.lock().await.clone();
-        let history_snapshot: Vec<Message> = self.history.lock().await.iter().cloned().collect();
+        let history_snapshot: Vec<Message> = self.history_on(surface).await;
         let latest_vision_index = latest_vision_context_index(&history_snapshot);
         let recent_history_snapshot: Vec<Message> = history_snapshot
             .iter()
@@ -814,7 +1189,6 @@ impl AIOrchestrator {
         // Section 2: Character persona (jailbreak + system prompt)
         let jailbreak = self.jailbreak_prompt.lock().await.clone();
         let character_block = if !jailbreak.is_empty() {
-            let char_name = self.character_name.lock().await.clone();
             let user_name = self.user_name.lock().await.clone();
             // Preserve base system prompt alongside jailbreak
             let processed_jailbreak = jailbreak
@@ -832,9 +1206,49 @@ impl AIOrchestrator {
         // Emotion state hint — subtly colors tone without overriding character persona
         system_parts.push(format!("<character>\n{}\n</character>", character_block));
 
+        // Section 2a: Learned forms of address (override nicknames in the persona)
+        match crate::ai::address_forms::load_relationship_state(&self.db, cid).await {
+            Ok(state) => {
+                if let Some(block) = state
+                    .as_ref()
+                    .and_then(crate::ai::address_forms::render_block)
+                {
+                    system_parts.push(block);
+                }
+            }
+            Err(e) => {
+                tracing::warn!(target: "context", "[Context] Failed to load address forms for {}: {}", cid, e);
+            }
+        }
+
+        // Section 2b: Lorebook / world info triggered by recent messages
+        match crate::ai::lorebook::load_lorebook(&self.db, cid).await {
+            Ok(Some(book)) => {
+                let mut scanned: Vec<&str> = recent_history_snapshot
+                    .iter()
+                    .filter(|msg| msg.role == "user" || msg.role == "assistant")
+                    .map(|msg| msg.content.as_str())
+                    .collect();
+                scanned.push(query);
+                let entries = crate::ai::lorebook::activate_entries(&book, &scanned);
+                let user_name = self.user_name.lock().await.clone();
+                if let Some(block) =
+                    crate::ai::lorebook::render_world_info(&entries, &char_name, &user_name)
+                {
+                    breakdown.lorebook_entry_ids = entries.iter().map(|entry| entry.id).collect();
+                    dynamic_context_parts.push(block);
+                }
+            }
+            Ok(None) => {}
+            Err(e) => {
+                tracing::warn!(target: "context", "[Context] Failed to load lorebook for {}: {}", cid, e);
+            }
+        }
+
         // Section 3: Long-term memory (higher priority than summaries)
         if let Some(ref mems) = memories {
             if !mems.is_empty() {
+                breakdown.memories = mems.iter().map(InjectedMemory::from).collect();
                 let memory_block = mems
                     .iter()
                     .map(|m| format!("- {}", m.content))
@@ -873,12 +1287,25 @@ impl AIOrchestrator {
         }
 
         // Section 5: Conversation summary (lower priority than long-term memory and recent raw messages)
-        if let Some(summary_record) = conversation_summary {
+        if let Some(ref catch_up) = catch_up {
+            let block = crate::ai::catch_up::render_block(catch_up);
+            breakdown.summaries.push(SummaryUsed::CatchUp {
+                idle_secs: catch_up.idle_secs,
+                tokens: estimate_tokens(&block),
+            });
+            dynamic_context_parts.push(block);
+        }
+        if catch_up.as_ref().is_some_and(|c| c.recap.is_some()) {
+            // The recap stands in for the running and fallback summaries.
+        } else if let Some(summary_record) = conversation_summary {
             if !summary_record.summary.trim().is_empty() {
+                breakdown
+                    .summaries
+                    .push(SummaryUsed::conversation(&summary_record));
                 dynamic_context_parts.push(format!(
                     concat!(
                         "<conversation_summary>\n",
-                        "This is a compressed summary of earlier messages in the current conversation:\n{}\n\n",
+                        "The conversation so far (earlier messages no longer shown verbatim), compressed into a running summary:\n{}\n\n",
                         "Use it as background only. If it conflicts with long-term memory or recent raw messages, trust long-term memory and recent raw messages.\n",
                         "</conversation_summary>"
                     ),
@@ -894,6 +1321,10 @@ impl AIOrchestrator {
                         .map(|(i, s)| format!("{}. {}", i + 1, s))
                         .collect::<Vec<_>>()
                         .join("\n");
+                    breakdown.summaries.push(SummaryUsed::Session {
+                        count: summaries.len(),
+                        tokens: estimate_tokens(&summary_block),
+                    });
                     dynamic_context_parts.push(format!(
                         "<conversation_summary>\nFallback summaries from recent sessions (most recent first):\n{}\n</conversation_summary>",
                         summary_block
@@ -902,6 +1333,17 @@ impl AIOrchestrator {
             }
         }
 
+        // Section 5b: Machine state (battery / connectivity)
+        if let Some(block) = self
+            .system_state
+            .lock()
+            .await
+            .as_ref()
+            .and_then(describe_system_state)
+        {
+            dynamic_context_parts.push(block);
+        }
+
         // Section 6: Tool prompt
         if let Some(ref tp) = tool_prompt {
             if !tp.is_empty() {
@@ -949,6 +1391,9 @@ impl AIOrchestrator {
             ));
         }
 
+        breakdown.add_blocks(SectionPlacement::System, &system_parts);
+        breakdown.add_blocks(SectionPlacement::DynamicContext, &dynamic_context_parts);
+
         final_messages.push(Message {
             role: "system".to_string(),
             content: system_parts.join("\n\n"),
@@ -967,45 +1412,42 @@ impl AIOrchestrator {
         {
             let user_lang = self.user_language.lock().await;
             if !user_lang.is_empty() && !resp_lang.is_empty() && *user_lang != resp_lang {
+                let content = format!(
+                    "IMPORTANT: After your dialogue response, \
+                     append a translation of your ENTIRE dialogue response into {} using this EXACT format:\n\
+                     [TRANSLATE: <your entire response translated into {}>]\n\
+                     The content inside [TRANSLATE:...] MUST be written in {}, NOT in {}. \
+                     This is an explicit exception to the language rule above. \
+                     Only translate the dialogue text. Do NOT include any control tags inside the translation.\n\
+                     This translation tag is mandatory for every response.",
+                    user_lang, user_lang, user_lang, resp_lang
+                );
+                breakdown.add_section(
+                    "translation_instruction",
+                    SectionPlacement::Instruction,
+                    &content,
+                );
                 final_messages.push(Message {
                     role: "system".to_string(),
-                    content: format!(
-                        "IMPORTANT: After your dialogue response, \
-                         append a translation of your ENTIRE dialogue response into {} using this EXACT format:\n\
-                         [TRANSLATE: <your entire response translated into {}>]\n\
-                         The content inside [TRANSLATE:...] MUST be written in {}, NOT in {}. \
-                         This is an explicit exception to the language rule above. \
-                         Only translate the dialogue text. Do NOT include any control tags inside the translation.\n\
-                         This translation tag is mandatory for every response.",
-                        user_lang, user_lang, user_lang, resp_lang
-                    ),
+                    content,
                     metadata: Some(serde_json::json!({"type": "translation_instruction"})),
                 });
             }
         }
         // -- Recent History (P2) --
         // Token-budget-aware trimming: walk backwards from newest, stop when budget exhausted.
-        const CHARS_PER_TOKEN: usize = 2; // conservative for mixed CJK/Latin
-        const HISTORY_TOKEN_BUDGET: usize = 6000;
-        let budget_chars = HISTORY_TOKEN_BUDGET * CHARS_PER_TOKEN;
-        let mut used_chars = 0usize;
-        let mut selected: Vec<&Message> = Vec::new();
-
-        for msg in recent_history_snapshot.iter().rev() {
-            let msg_chars = msg.content.chars().count();
-            if used_chars + msg_chars > budget_chars && !selected.is_empty() {
-                break;
-            }
-            used_chars += msg_chars;
-            selected.push(msg);
-            if selected.len() >= 20 {
-                break;
-            }
-        }
-        selected.reverse();
-        for msg in selected {
-            final_messages.push(msg.clone());
-        }
+        let window_start = history_window_start(&recent_history_snapshot);
+        breakdown.history = describe_history_window(
+            history_snapshot.len(),
+            &recent_history_snapshot,
+            window_start,
+        );
+        breakdown.sections.push(ContextSection {
+            name: "history".to_string(),
+            placement: SectionPlacement::History,
+            tokens: breakdown.history.used_tokens,
+        });
+        final_messages.extend(recent_history_snapshot[window_start..].iter().cloned());
 
         // -- Final Language Reminder (recency effect) --
         // Placed after history so it's the last system instruction the LLM sees.
@@ -1019,6 +1461,23 @@ impl AIOrchestrator {
                 ),
                 metadata: Some(serde_json::json!({"type": "language_reminder"})),
             });
+        } else if let Some(detected) = latest_user_text(&recent_history_snapshot)
+            .and_then(crate::ai::language_detect::detect_language)
+            .or_else(|| crate::ai::language_detect::detect_language(query))
+        {
+            // Auto mode: follow the user's language. Small models otherwise
+            // tend to drift into English whatever the user writes in.
+            final_messages.push(Message {
+                role: "system".to_string(),
+                content: format!(
+                    "[Reminder] The user is writing in {}. Reply in {} unless they ask for another language.",
+                    detected.name, detected.name
+                ),
+                metadata: Some(serde_json::json!({
+                    "type": "language_reminder",
+                    "detected_language": detected.code,
+                })),
+            });
         }
 
         // -- Current User Query --
@@ -1030,9 +1489,29 @@ impl AIOrchestrator {
         // OR we can make `compose_prompt` take the current message and add it.
         // Let's stick to returning context *state*.
 
+        if let Some(reminder) = final_messages.last().filter(|msg| {
+            msg.metadata
+                .as_ref()
+                .and_then(|meta| meta.get("type"))
+                .and_then(|value| value.as_str())
+                == Some("language_reminder")
+        }) {
+            breakdown.add_section(
+                "language_reminder",
+                SectionPlacement::Instruction,
+                &reminder.content,
+            );
+        }
+        *self.last_context_breakdown.lock().await = Some(breakdown.finish(&warnings));
+
         Ok((final_messages, warnings))
     }
 
+    /// Breakdown of the most recently composed prompt.
+    pub async fn last_context_breakdown(&self) -> Option<ContextBreakdown> {
+        self.last_context_breakdown.lock().await.clone()
+    }
+
     pub async fn get_context_settings(&self) -> (String, usize) {
         let strategy = self.context_strategy.lock().await.clone();
         let max_chars = *self.max_message_chars.lock().await;
@@ -1049,18 +1528,192 @@ impl AIOrchestrator {
             crate::vision::config::normalize_vision_context_history_mode(&mode);
     }
 
+    pub async fn set_system_state(&self, state: SystemState) {
+        *self.system_state.lock().await = Some(state);
+    }
+
+    pub async fn get_system_state(&self) -> Option<SystemState> {
+        self.system_state.lock().await.clone()
+    }
+
+    /// Start a new conversation. The previous one's buffer stays cached in
+    /// case it is reopened.
     pub async fn clear_history(&self) {
-        let mut history = self.history.lock().await;
-        history.clear();
-        drop(history);
-        *self.memory_history_boundary.lock().await = 0;
+        self.history
+            .lock()
+            .await
+            .discard(&HistoryKey::Draft(ChatSurface::Desktop));
         *self.memory_trigger_count.lock().await = 0;
+        self.topic_trackers.lock().await.clear();
+        *self.catch_up.lock().await = None;
         // 清空当前对话 ID，下次发消息时会创建新对话
         let mut conv_id = self.current_conversation_id.lock().await;
         *conv_id = None;
         Self::persist_conversation_id(None);
     }
 
+    /// Start `surface` over; its next persisted turn creates a conversation.
+    pub async fn new_conversation_on(&self, surface: ChatSurface) {
+        if surface == ChatSurface::Desktop {
+            self.clear_history().await;
+            return;
+        }
+        self.surface_conversations.lock().await.remove(&surface);
+        self.history
+            .lock()
+            .await
+            .discard(&HistoryKey::Draft(surface));
+    }
+
+    /// Make `conversation_id` the desktop's current conversation. Its buffer
+    /// comes from the cache of recently used conversations or is loaded from
+    /// the DB (`loaded` skips both for callers that just read the rows).
+    /// Reloaded turns were already seen by memory extraction, so the
+    /// extraction boundary starts after them.
+    pub async fn switch_conversation(
+        &self,
+        conversation_id: &str,
+        loaded: Option<Vec<Message>>,
+    ) -> Result<()> {
+        let key = HistoryKey::Conversation(conversation_id.to_string());
+        let cached = self.history.lock().await.contains(&key);
+        let loaded = match loaded {
+            Some(messages) => Some(messages),
+            None if !cached => Some(load_conversation_messages(&self.db, conversation_id).await?),
+            None => None,
+        };
+        {
+            let mut histories = self.history.lock().await;
+            if let Some(messages) = loaded {
+                histories.restore(&key, messages);
+            }
+            histories.discard(&HistoryKey::Draft(ChatSurface::Desktop));
+        }
+        *self.memory_trigger_count.lock().await = 0;
+        self.topic_trackers.lock().await.clear();
+        *self.current_conversation_id.lock().await = Some(conversation_id.to_string());
+        Self::persist_conversation_id(Some(conversation_id));
+        Ok(())
+    }
+
+    /// Note how long `conversation_id` sat idle and, past
+    /// `CATCH_UP_AFTER_SECS`, recap what came before the raw history window.
+    /// The idle note applies at once; `provider` writes the recap in the
+    /// background.
+    pub async fn catch_up_conversation(
+        &self,
+        conversation_id: &str,
+        provider: Arc<dyn LlmProvider>,
+    ) -> Result<()> {
+        *self.catch_up.lock().await = None;
+        let updated_at: Option<String> =
+            sqlx::query_scalar("SELECT updated_at FROM conversations WHERE id = ?")
+                .bind(conversation_id)
+                .fetch_optional(&self.db)
+                .await?;
+        let Some(idle_secs) = updated_at
+            .as_deref()
+            .and_then(|at| crate::ai::catch_up::idle_secs(at, chrono::Utc::now()))
+        else {
+            return Ok(());
+        };
+
+        let previous = self
+            .memory_manager
+            .get_latest_conversation_summary(conversation_id)
+            .await?
+            .map(|record| (record.end_message_id, record.summary.trim().to_string()))
+            .filter(|(_, summary)| !summary.is_empty());
+        let transcript = crate::ai::catch_up::recap_transcript(
+            &self.db,
+            conversation_id,
+            previous.as_ref().map_or(0, |(end, _)| *end),
+        )
+        .await?;
+        let previous = previous.map(|(_, summary)| summary);
+        *self.catch_up.lock().await = Some(CatchUp {
+            conversation_id: conversation_id.to_string(),
+            idle_secs,
+            // Nothing new since the running summary: it is the recap.
+            recap: previous.clone().filter(|_| transcript.is_none()),
+        });
+        let Some(transcript) = transcript else {
+            return Ok(());
+        };
+
+        let prompt = build_conversation_summary_prompt(
+            &transcript,
+            previous.as_deref(),
+            &self.response_language.lock().await.clone(),
+        );
+        let slot = self.catch_up.clone();
+        let conversation_id = conversation_id.to_string();
+        tauri::async_runtime::spawn(async move {
+            match provider.chat(vec![user_text_message(prompt)], None).await {
+                Ok(text) if !text.trim().is_empty() => {
+                    let mut slot = slot.lock().await;
+                    if let Some(catch_up) = slot
+                        .as_mut()
+                        .filter(|c| c.conversation_id == conversation_id)
+                    {
+                        catch_up.recap = Some(text.trim().to_string());
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    tracing::warn!(
+                        target: "context",
+                        "[Context] Failed to write catch-up recap for '{}': {}",
+                        conversation_id, e
+                    );
+                }
+            }
+        });
+        Ok(())
+    }
+
+    /// Drop cached buffers of conversations whose stored messages were
+    /// deleted or rewritten outside the chat pipeline.
+    pub async fn forget_conversation_histories<'a>(
+        &self,
+        conversation_ids: impl IntoIterator<Item = &'a String>,
+    ) {
+        self.history
+            .lock()
+            .await
+            .forget(conversation_ids.into_iter().map(String::as_str));
+    }
+
+    /// Conversations that were deleted or purged: every surface talking in
+    /// one of them starts over, and their buffers are dropped.
+    pub async fn close_conversations(&self, conversation_ids: &[String]) {
+        let current = self.current_conversation_id.lock().await.clone();
+        if current.is_some_and(|id| conversation_ids.contains(&id)) {
+            self.clear_history().await;
+        }
+        self.surface_conversations
+            .lock()
+            .await
+            .retain(|_, id| !conversation_ids.contains(id));
+        self.forget_conversation_histories(conversation_ids).await;
+    }
+
+    /// Drop the newest `count` turns of `surface`'s conversation from memory.
+    /// Returns how many were removed and the conversation they belong to.
+    pub async fn drop_last_messages(
+        &self,
+        surface: ChatSurface,
+        count: usize,
+    ) -> (usize, Option<String>) {
+        let key = self.history_key(surface).await;
+        let removed = self.history.lock().await.entry(&key).truncate_last(count);
+        let conversation_id = match key {
+            HistoryKey::Conversation(id) => Some(id),
+            HistoryKey::Draft(_) => None,
+        };
+        (removed, conversation_id)
+    }
+
     pub async fn should_trigger_memory_event(
         &self,
         cooldown_key: &str,
@@ -1078,6 +1731,43 @@ impl AIOrchestrator {
         cooldowns.insert(cooldown_key.to_string(), now);
         true
     }
+
+    /// Feed a user message to the character's topic tracker; returns a trigger
+    /// when the conversation just reached a good point for memory extraction.
+    pub async fn observe_topic_turn(
+        &self,
+        character_id: &str,
+        text: &str,
+        options: &TopicShiftOptions,
+    ) -> Option<ExtractionTrigger> {
+        let embedding = match self.memory_manager.embed(text).await {
+            Ok(embedding) => Some(embedding),
+            Err(e) => {
+                tracing::debug!(target: "memory", "[Memory] Topic embedding unavailable: {}", e);
+                None
+            }
+        };
+        self.topic_trackers
+            .lock()
+            .await
+            .entry(character_id.to_string())
+            .or_default()
+            .observe(text, embedding, options)
+    }
+
+    /// Close the character's open topic once the conversation has gone quiet.
+    pub async fn take_idle_topic_end(
+        &self,
+        character_id: &str,
+        idle_secs: u64,
+        threshold_secs: u64,
+    ) -> Option<ExtractionTrigger> {
+        self.topic_trackers
+            .lock()
+            .await
+            .get_mut(character_id)?
+            .idle_end(idle_secs, threshold_secs)
+    }
 }
 
 #[cfg(test)]
@@ -1100,12 +1790,36 @@ mod tests {
 
     #[test]
     fn conversation_summary_prompt_uses_response_language() {
-        let prompt = build_conversation_summary_prompt("user: hello", "中文");
+        let prompt = build_conversation_summary_prompt("user: hello", None, "中文");
 
         assert!(prompt.contains("Write the summary in 中文"));
         assert!(prompt.contains("translate or summarize it into 中文"));
     }
 
+    #[test]
+    fn conversation_summary_prompt_folds_in_previous_summary() {
+        let prompt =
+            build_conversation_summary_prompt("user: bye", Some("They met at the shrine."), "");
+
+        assert!(prompt.contains("Summary so far:\nThey met at the shrine."));
+        assert!(prompt.contains("New messages:\nuser: bye"));
+    }
+
+    #[test]
+    fn history_window_respects_budget_and_message_cap() {
+        let short = |content: &str| Message {
+            role: "user".to_string(),
+            content: content.to_string(),
+            metadata: None,
+        };
+        let many: Vec<Message> = (0..30).map(|i| short(&i.to_string())).collect();
+        assert_eq!(history_window_start(&many), 10);
+
+        let long = short(&"x".repeat(HISTORY_TOKEN_BUDGET * HISTORY_CHARS_PER_TOKEN));
+        let mixed = vec![short("old"), long, short("newest")];
+        assert_eq!(history_window_start(&mixed), 2);
+    }
+
     #[tokio::test]
     async fn compose_prompt_places_dynamic_context_after_stable_system() {
         let orchestrator = setup_test_orchestrator().await;
@@ -1184,6 +1898,18 @@ mod tests {
             history_index > 1,
             "dynamic context should appear before recent history"
         );
+
+        let breakdown = orchestrator
+            .last_context_breakdown()
+            .await
+            .expect("breakdown should be recorded");
+        assert_eq!(breakdown.conversation_id.as_deref(), Some(conversation_id));
+        assert!(breakdown.sections.iter().any(|section| {
+            section.name == "conversation_state"
+                && section.placement == SectionPlacement::DynamicContext
+        }));
+        assert_eq!(breakdown.history.included, 1);
+        assert_eq!(breakdown.history.cutoff, None);
     }
 
     #[tokio::test]
@@ -1383,7 +2109,7 @@ mod tests {
             .add_message("user".to_string(), long_message, "test_char")
             .await;
 
-        let history = orchestrator.history.lock().await;
+        let history = orchestrator.history_on(ChatSurface::Desktop).await;
         assert_eq!(history.len(), 1, "History should contain one message");
 
         let msg = &history[0];
@@ -1411,7 +2137,7 @@ mod tests {
                 .await;
         }
 
-        let history = orchestrator.history.lock().await;
+        let history = orchestrator.history_on(ChatSurface::Desktop).await;
         assert!(
             history.len() <= 20,
             "History should not exceed 20 messages, got {}",
@@ -1476,7 +2202,7 @@ mod tests {
 
         // Verify messages were added
         {
-            let history = orchestrator.history.lock().await;
+            let history = orchestrator.history_on(ChatSurface::Desktop).await;
             assert_eq!(history.len(), 5, "Should have 5 messages before clear");
         }
 
@@ -1485,14 +2211,14 @@ mod tests {
 
         // Verify all state is reset
         {
-            let history = orchestrator.history.lock().await;
+            let history = orchestrator.history_on(ChatSurface::Desktop).await;
             assert_eq!(history.len(), 0, "History should be empty after clear");
         }
 
-        {
-            let boundary = *orchestrator.memory_history_boundary.lock().await;
-            assert_eq!(boundary, 0, "Memory boundary should be 0 after clear");
-        }
+        assert!(
+            orchestrator.get_recent_memory_history(10).await.is_empty(),
+            "Nothing should be left for memory extraction after clear"
+        );
 
         {
             let trigger_count = *orchestrator.memory_trigger_count.lock().await;
@@ -1511,6 +2237,137 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn switching_conversations_restores_their_own_history() {
+        let orchestrator = setup_test_orchestrator().await;
+        orchestrator
+            .add_message("user".to_string(), "About cats".to_string(), "test_char")
+            .await;
+        let first = orchestrator
+            .current_conversation_id
+            .lock()
+            .await
+            .clone()
+            .expect("first message should create a conversation");
+
+        orchestrator.clear_history().await;
+        orchestrator
+            .add_message("user".to_string(), "About dogs".to_string(), "test_char")
+            .await;
+        let second = orchestrator
+            .current_conversation_id
+            .lock()
+            .await
+            .clone()
+            .unwrap();
+        assert_ne!(first, second);
+
+        // Served from the cache of conversation buffers.
+        orchestrator
+            .switch_conversation(&first, None)
+            .await
+            .unwrap();
+        let history = orchestrator.history_on(ChatSurface::Desktop).await;
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].content, "About cats");
+
+        // Loaded from the DB once the cached buffer is gone; reloaded turns
+        // are not extracted into memory again.
+        orchestrator.forget_conversation_histories([&second]).await;
+        orchestrator
+            .switch_conversation(&second, None)
+            .await
+            .unwrap();
+        let history = orchestrator.history_on(ChatSurface::Desktop).await;
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].content, "About dogs");
+        assert!(orchestrator.get_recent_memory_history(10).await.is_empty());
+        assert_eq!(
+            orchestrator.current_conversation_id.lock().await.as_deref(),
+            Some(second.as_str())
+        );
+    }
+
+    #[tokio::test]
+    async fn interleaved_surfaces_keep_their_own_conversations() {
+        let orchestrator = setup_test_orchestrator().await;
+        let telegram = ChatSurface::Telegram(7);
+        orchestrator.new_conversation_on(telegram).await;
+
+        for (surface, content) in [
+            (ChatSurface::Desktop, "desktop 1"),
+            (telegram, "telegram 1"),
+            (ChatSurface::Desktop, "desktop 2"),
+            (telegram, "telegram 2"),
+        ] {
+            orchestrator
+                .add_message_on(
+                    surface,
+                    "user".to_string(),
+                    content.to_string(),
+                    None,
+                    "test_char",
+                    None,
+                )
+                .await;
+        }
+
+        let contents =
+            |messages: Vec<Message>| messages.into_iter().map(|m| m.content).collect::<Vec<_>>();
+        assert_eq!(
+            contents(orchestrator.history_on(ChatSurface::Desktop).await),
+            ["desktop 1", "desktop 2"]
+        );
+        assert_eq!(
+            contents(orchestrator.history_on(telegram).await),
+            ["telegram 1", "telegram 2"]
+        );
+        let desktop_id = orchestrator
+            .conversation_id_on(ChatSurface::Desktop)
+            .await
+            .unwrap();
+        let telegram_id = orchestrator.conversation_id_on(telegram).await.unwrap();
+        assert_ne!(desktop_id, telegram_id);
+
+        let (prompt, _) = orchestrator
+            .compose_prompt_on(telegram, "hello", false, None, true, "test_char")
+            .await
+            .unwrap();
+        assert!(prompt.iter().any(|m| m.content.contains("telegram 2")));
+        assert!(!prompt
+            .iter()
+            .any(|m| m.content.contains("desktop 1") || m.content.contains("desktop 2")));
+
+        // An evicted buffer is reloaded from its own conversation's rows.
+        orchestrator
+            .forget_conversation_histories([&telegram_id])
+            .await;
+        assert_eq!(
+            contents(orchestrator.history_on(telegram).await),
+            ["telegram 1", "telegram 2"]
+        );
+
+        // Regenerating on Telegram drops its reply only.
+        crate::commands::context::delete_last_messages_on(&orchestrator, telegram, 1)
+            .await
+            .unwrap();
+        assert_eq!(
+            contents(orchestrator.history_on(telegram).await),
+            ["telegram 1"]
+        );
+        assert_eq!(orchestrator.history_on(ChatSurface::Desktop).await.len(), 2);
+
+        orchestrator.new_conversation_on(telegram).await;
+        assert!(orchestrator.history_on(telegram).await.is_empty());
+        assert_eq!(
+            orchestrator
+                .conversation_id_on(ChatSurface::Desktop)
+                .await
+                .as_deref(),
+            Some(desktop_id.as_str())
+        );
+    }
+
     #[tokio::test]
     async fn test_set_memory_enabled_false_resets_trigger_count() {
         let orchestrator = setup_test_orchestrator().await;
@@ -1563,12 +2420,17 @@ mod tests {
 
         // Disable memory (should set boundary to current history length)
         orchestrator.set_memory_enabled(false).await;
-
-        let boundary = *orchestrator.memory_history_boundary.lock().await;
-        assert_eq!(
-            boundary, 5,
-            "Boundary should be set to history length (5) when disabling memory"
+        assert!(
+            orchestrator.get_recent_memory_history(10).await.is_empty(),
+            "Messages before disabling memory should not be extracted"
         );
+
+        orchestrator
+            .add_message("user".to_string(), "Message 5".to_string(), "test_char")
+            .await;
+        let recent = orchestrator.get_recent_memory_history(10).await;
+        assert_eq!(recent.len(), 1);
+        assert_eq!(recent[0].content, "Message 5");
     }
 
     #[tokio::test]
@@ -1586,7 +2448,7 @@ mod tests {
                 .await;
         }
 
-        let history = orchestrator.history.lock().await;
+        let history = orchestrator.history_on(ChatSurface::Desktop).await;
         assert!(
             history.len() <= 20,
             "History should not exceed 20 messages after push_history_message"
@@ -1609,7 +2471,7 @@ mod tests {
             })
             .await;
 
-        let history = orchestrator.history.lock().await;
+        let history = orchestrator.history_on(ChatSurface::Desktop).await;
         assert_eq!(history.len(), 1, "History should contain one message");
 
         let msg = &history[0];
@@ -1653,4 +2515,43 @@ mod tests {
             "Message count should remain 0 for non-user messages"
         );
     }
+
+    #[tokio::test]
+    async fn test_privacy_mode_keeps_session_out_of_db_and_memory() {
+        let orchestrator = setup_test_orchestrator().await;
+        orchestrator
+            .add_message("user".to_string(), "Saved".to_string(), "test_char")
+            .await;
+        assert!(orchestrator.set_incognito(true).await);
+        assert!(!orchestrator.set_incognito(true).await);
+        assert!(!orchestrator.is_memory_write_enabled());
+        assert!(orchestrator.get_recent_history(10).await.is_empty());
+
+        orchestrator
+            .add_message("user".to_string(), "Secret".to_string(), "test_char")
+            .await;
+        let draft = orchestrator
+            .persist_streaming_draft("Reply", "test_char")
+            .await
+            .unwrap();
+        assert_eq!(draft, 0);
+
+        let stored: Vec<(String,)> = sqlx::query_as("SELECT content FROM conversation_messages")
+            .fetch_all(&orchestrator.db)
+            .await
+            .unwrap();
+        assert_eq!(stored, vec![("Saved".to_string(),)]);
+        let history = orchestrator.history_on(ChatSurface::Desktop).await;
+        assert_eq!(history.len(), 1);
+        assert!(is_incognito_message(&history[0]));
+        assert!(orchestrator.get_recent_memory_history(10).await.is_empty());
+        assert_eq!(orchestrator.get_memory_trigger_count().await, 0);
+
+        assert!(orchestrator.set_incognito(false).await);
+        assert!(orchestrator
+            .history_on(ChatSurface::Desktop)
+            .await
+            .is_empty());
+        assert!(orchestrator.is_memory_write_enabled());
+    }
 }
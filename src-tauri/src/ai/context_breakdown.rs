@@ -0,0 +1,195 @@
+//! What the last composed prompt contained, for `get_context_breakdown`.
+//!
+//! `compose_prompt` records every block it emits along with the memories,
+//! summaries and lorebook entries behind them and how the raw history was
+//! cut, so the UI can show what the model was actually shown. Token counts
+//! use the same chars-per-token estimate as the history trimmer.
+
+use crate::ai::context::MemorySnippet;
+use crate::ai::memory::ConversationSummaryRecord;
+use serde::Serialize;
+
+/// Conservative estimate for mixed CJK/Latin text.
+const CHARS_PER_TOKEN: usize = 2;
+
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(CHARS_PER_TOKEN)
+}
+
+/// Where in the prompt a section sits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SectionPlacement {
+    /// The stable system message, reused across turns.
+    System,
+    /// The per-turn context message after it.
+    DynamicContext,
+    /// Raw recent messages.
+    History,
+    /// Standalone instructions around the history.
+    Instruction,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ContextSection {
+    /// The block's tag (`rules`, `long_term_memory`, ...) or instruction type.
+    pub name: String,
+    pub placement: SectionPlacement,
+    pub tokens: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct InjectedMemory {
+    pub id: i64,
+    pub tier: String,
+    pub importance: f64,
+    pub tokens: usize,
+}
+
+impl From<&MemorySnippet> for InjectedMemory {
+    fn from(snippet: &MemorySnippet) -> Self {
+        Self {
+            id: snippet.id,
+            tier: snippet.tier.clone(),
+            importance: snippet.importance,
+            tokens: estimate_tokens(&snippet.content),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SummaryUsed {
+    /// The running summary of the current conversation.
+    Conversation {
+        version: i64,
+        /// Last message folded into the summary.
+        end_message_id: i64,
+        tokens: usize,
+    },
+    /// Summaries of earlier sessions, used when the conversation has none.
+    Session { count: usize, tokens: usize },
+    /// Recap of a conversation reopened after `idle_secs` away.
+    CatchUp { idle_secs: i64, tokens: usize },
+}
+
+impl SummaryUsed {
+    pub fn conversation(record: &ConversationSummaryRecord) -> Self {
+        SummaryUsed::Conversation {
+            version: record.version,
+            end_message_id: record.end_message_id,
+            tokens: estimate_tokens(record.summary.trim()),
+        }
+    }
+}
+
+/// Why older history stopped being included.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HistoryCutoff {
+    TokenBudget,
+    MessageLimit,
+}
+
+/// How the recent history was trimmed.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct HistoryWindow {
+    /// Messages in the in-memory history buffer.
+    pub available: usize,
+    /// Screen context and other technical messages left out by type.
+    pub excluded: usize,
+    pub included: usize,
+    /// Older messages left to the conversation summary.
+    pub dropped: usize,
+    pub cutoff: Option<HistoryCutoff>,
+    /// Included messages cut at the max message length when stored.
+    pub truncated_messages: usize,
+    pub budget_tokens: usize,
+    pub used_tokens: usize,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ContextBreakdown {
+    /// Unix seconds.
+    pub composed_at: i64,
+    pub character_id: String,
+    pub conversation_id: Option<String>,
+    pub sections: Vec<ContextSection>,
+    pub memories: Vec<InjectedMemory>,
+    pub summaries: Vec<SummaryUsed>,
+    pub lorebook_entry_ids: Vec<i64>,
+    pub history: HistoryWindow,
+    pub total_tokens: usize,
+    pub warnings: Vec<String>,
+}
+
+impl ContextBreakdown {
+    pub fn new(character_id: &str, conversation_id: Option<String>) -> Self {
+        Self {
+            composed_at: chrono::Utc::now().timestamp(),
+            character_id: character_id.to_string(),
+            conversation_id,
+            ..Default::default()
+        }
+    }
+
+    /// Record the `<tag>`-wrapped blocks joined into one message.
+    pub fn add_blocks(&mut self, placement: SectionPlacement, blocks: &[String]) {
+        for block in blocks {
+            self.add_section(block_name(block), placement, block);
+        }
+    }
+
+    pub fn add_section(&mut self, name: &str, placement: SectionPlacement, content: &str) {
+        self.sections.push(ContextSection {
+            name: name.to_string(),
+            placement,
+            tokens: estimate_tokens(content),
+        });
+    }
+
+    /// Sum the sections once the prompt is complete.
+    pub fn finish(mut self, warnings: &[String]) -> Self {
+        self.total_tokens = self.sections.iter().map(|s| s.tokens).sum();
+        self.warnings = warnings.to_vec();
+        self
+    }
+}
+
+/// Tag of a `<name>...</name>` block.
+fn block_name(block: &str) -> &str {
+    block
+        .strip_prefix('<')
+        .and_then(|rest| rest.split_once('>'))
+        .map(|(tag, _)| tag)
+        .filter(|tag| !tag.is_empty() && !tag.contains(char::is_whitespace))
+        .unwrap_or("other")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blocks_are_named_by_tag_and_totals_add_up() {
+        let mut breakdown = ContextBreakdown::new("char", None);
+        breakdown.add_blocks(
+            SectionPlacement::System,
+            &[
+                "<rules>\nbe kind\n</rules>".to_string(),
+                "plain".to_string(),
+            ],
+        );
+        breakdown.add_section("history", SectionPlacement::History, "abcd");
+        let breakdown = breakdown.finish(&["warned".to_string()]);
+
+        let names: Vec<&str> = breakdown.sections.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["rules", "other", "history"]);
+        assert_eq!(breakdown.sections[2].tokens, 2);
+        assert_eq!(
+            breakdown.total_tokens,
+            breakdown.sections.iter().map(|s| s.tokens).sum::<usize>()
+        );
+        assert_eq!(breakdown.warnings, ["warned"]);
+    }
+}
@@ -0,0 +1,543 @@
+//! Companion-to-companion conversation ("duet").
+//!
+//! Two stored characters talk to each other about a topic for a fixed number
+//! of turns. Each side gets its own [`CharacterContext`] — persona, model
+//! binding, voice and emotion state — so neither borrows the active
+//! character's. Lines stream to the UI as `duet:*` events and can be voiced
+//! one after another; the user may interject between turns. Nothing is
+//! written to chat history.
+
+use crate::ai::character_context::CharacterContext;
+use crate::ai::emotion::emotion_from_label;
+use crate::error::KokoroError;
+use crate::llm::messages::{assistant_text_message, system_message, user_text_message};
+use crate::llm::provider::LlmStreamEvent;
+use crate::llm::service::LlmService;
+use crate::telegram::bot::clean_model_reply;
+use crate::tts::emotion_tts::condition_params;
+use crate::tts::queue::TtsPriority;
+use crate::tts::{TtsParams, TtsService};
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+use tauri::async_runtime::JoinHandle;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::{mpsc, Mutex};
+
+pub const DUET_LINE_START_EVENT: &str = "duet:line-start";
+pub const DUET_DELTA_EVENT: &str = "duet:delta";
+pub const DUET_LINE_EVENT: &str = "duet:line";
+pub const DUET_INTERJECTION_EVENT: &str = "duet:interjection";
+pub const DUET_FINISH_EVENT: &str = "duet:finish";
+
+pub const MAX_DUET_TURNS: u32 = 40;
+/// Lines of transcript each speaker sees; older ones fall out of the prompt.
+const TRANSCRIPT_WINDOW: usize = 24;
+/// Weight of a line's own `[EMOTION:...]` tag on the speaker's state.
+const LINE_EMOTION_STRENGTH: f32 = 0.7;
+const TTS_SOURCE: &str = "duet";
+
+fn default_turns() -> u32 {
+    8
+}
+
+fn default_speak() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DuetRequest {
+    /// Exactly two character ids; the first one opens.
+    pub characters: Vec<String>,
+    pub topic: String,
+    #[serde(default = "default_turns")]
+    pub turns: u32,
+    /// Voice each line with the speaker's own TTS binding.
+    #[serde(default = "default_speak")]
+    pub speak: bool,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct DuetSpeaker {
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DuetStatus {
+    pub active: bool,
+    pub topic: String,
+    pub speakers: Vec<DuetSpeaker>,
+    /// Lines finished so far.
+    pub turn: u32,
+    pub turns: u32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum DuetEntry {
+    Line { speaker: usize, text: String },
+    Interjection(String),
+}
+
+/// Which side speaks on `turn` (0-based). Alternates, first speaker opens.
+fn speaker_for_turn(turn: u32) -> usize {
+    (turn % 2) as usize
+}
+
+fn duet_system_prompt(me: &CharacterContext, other_name: &str, topic: &str) -> String {
+    let persona = me.persona.trim();
+    let mut prompt = String::new();
+    if !persona.is_empty() {
+        prompt.push_str(persona);
+        prompt.push_str("\n\n");
+    }
+    prompt.push_str(&format!(
+        "You are {me}, talking with {other} about: {topic}\n\
+         {user} is listening and may chime in.\n\
+         Reply with one short spoken turn as {me} (1-3 sentences). \
+         Never write {other}'s lines or narrate, and don't prefix your name. \
+         You may start with one [EMOTION:label] tag for how you feel.",
+        me = me.name,
+        other = other_name,
+        topic = topic,
+        user = me.user_nickname,
+    ));
+    prompt
+}
+
+/// Chat roles as seen by `me`: own lines are assistant turns, everyone
+/// else's are user turns labelled with the speaker's name.
+fn transcript_messages(
+    entries: &[DuetEntry],
+    me: usize,
+    names: &[String; 2],
+    user_name: &str,
+) -> Vec<(&'static str, String)> {
+    let start = entries.len().saturating_sub(TRANSCRIPT_WINDOW);
+    entries[start..]
+        .iter()
+        .map(|entry| match entry {
+            DuetEntry::Line { speaker, text } if *speaker == me => ("assistant", text.clone()),
+            DuetEntry::Line { speaker, text } => ("user", format!("{}: {}", names[*speaker], text)),
+            DuetEntry::Interjection(text) => ("user", format!("{}: {}", user_name, text)),
+        })
+        .collect()
+}
+
+/// Split a leading `[EMOTION:label]` tag off a raw reply.
+fn split_emotion_tag(raw: &str) -> (Option<&'static str>, &str) {
+    let trimmed = raw.trim_start();
+    if let Some(rest) = trimmed.strip_prefix("[EMOTION:") {
+        if let Some(end) = rest.find(']') {
+            return (emotion_from_label(&rest[..end]), &rest[end + 1..]);
+        }
+    }
+    (None, raw)
+}
+
+/// The part of a streaming reply that is safe to show: hides an opening tag
+/// until it is complete.
+fn visible_so_far(raw: &str) -> &str {
+    let trimmed = raw.trim_start();
+    if "[EMOTION:".starts_with(trimmed)
+        || (trimmed.starts_with("[EMOTION:") && !trimmed.contains(']'))
+    {
+        return "";
+    }
+    split_emotion_tag(raw).1.trim_start()
+}
+
+struct RunningDuet {
+    task: JoinHandle<()>,
+    interjections: mpsc::UnboundedSender<String>,
+    status: DuetStatus,
+    turn: Arc<AtomicU32>,
+}
+
+/// Managed Tauri state; at most one duet runs at a time.
+#[derive(Default)]
+pub struct DuetService {
+    running: Mutex<Option<RunningDuet>>,
+}
+
+impl DuetService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub async fn status(&self) -> DuetStatus {
+        let running = self.running.lock().await;
+        match running.as_ref() {
+            Some(duet) => DuetStatus {
+                active: !duet.task.inner().is_finished(),
+                turn: duet.turn.load(Ordering::SeqCst),
+                ..duet.status.clone()
+            },
+            None => DuetStatus {
+                active: false,
+                topic: String::new(),
+                speakers: Vec::new(),
+                turn: 0,
+                turns: 0,
+            },
+        }
+    }
+
+    pub async fn start(
+        &self,
+        app: &AppHandle,
+        request: DuetRequest,
+    ) -> Result<DuetStatus, KokoroError> {
+        let topic = request.topic.trim().to_string();
+        if topic.is_empty() {
+            return Err(KokoroError::Validation("topic must not be empty".into()));
+        }
+        let [first, second] = <[String; 2]>::try_from(request.characters)
+            .map_err(|_| KokoroError::Validation("a duet needs exactly two characters".into()))?;
+        if first == second {
+            return Err(KokoroError::Validation(
+                "a duet needs two different characters".into(),
+            ));
+        }
+        let turns = request.turns.clamp(1, MAX_DUET_TURNS);
+
+        let mut running = self.running.lock().await;
+        if running
+            .as_ref()
+            .is_some_and(|duet| !duet.task.inner().is_finished())
+        {
+            return Err(KokoroError::Validation("a duet is already running".into()));
+        }
+
+        let contexts = [
+            CharacterContext::load(app, &first).await?,
+            CharacterContext::load(app, &second).await?,
+        ];
+        let status = DuetStatus {
+            active: true,
+            topic: topic.clone(),
+            speakers: contexts
+                .iter()
+                .map(|c| DuetSpeaker {
+                    id: c.character_id.clone(),
+                    name: c.name.clone(),
+                })
+                .collect(),
+            turn: 0,
+            turns,
+        };
+        let (tx, rx) = mpsc::unbounded_channel();
+        let turn = Arc::new(AtomicU32::new(0));
+        let task = tauri::async_runtime::spawn(run_duet(
+            app.clone(),
+            contexts,
+            topic,
+            turns,
+            request.speak,
+            rx,
+            turn.clone(),
+        ));
+        tracing::info!(
+            target: "duet",
+            "[Duet] Started {} ↔ {} for {} turns",
+            first,
+            second,
+            turns
+        );
+        *running = Some(RunningDuet {
+            task,
+            interjections: tx,
+            status: status.clone(),
+            turn,
+        });
+        Ok(status)
+    }
+
+    /// Queue a user line; it is heard before the next turn.
+    pub async fn interject(&self, app: &AppHandle, text: &str) -> Result<(), KokoroError> {
+        let text = text.trim();
+        if text.is_empty() {
+            return Err(KokoroError::Validation("message must not be empty".into()));
+        }
+        let running = self.running.lock().await;
+        let duet = running
+            .as_ref()
+            .filter(|duet| !duet.task.inner().is_finished())
+            .ok_or_else(|| KokoroError::NotFound("no duet is running".into()))?;
+        duet.interjections
+            .send(text.to_string())
+            .map_err(|_| KokoroError::NotFound("no duet is running".into()))?;
+        // Cut the current speaker short so the reply comes sooner.
+        if let Some(tts) = app.try_state::<TtsService>() {
+            tts.stop_speaking(app, "duet_interjection");
+        }
+        Ok(())
+    }
+
+    pub async fn stop(&self, app: &AppHandle) -> DuetStatus {
+        let status = self.status().await;
+        if let Some(duet) = self.running.lock().await.take() {
+            if !duet.task.inner().is_finished() {
+                duet.task.abort();
+                if let Some(tts) = app.try_state::<TtsService>() {
+                    tts.stop_speaking(app, "duet_stopped");
+                }
+                emit_finish(app, "stopped");
+                tracing::info!(target: "duet", "[Duet] Stopped");
+            }
+        }
+        DuetStatus {
+            active: false,
+            ..status
+        }
+    }
+}
+
+fn emit_finish(app: &AppHandle, status: &str) {
+    let _ = app.emit(DUET_FINISH_EVENT, serde_json::json!({ "status": status }));
+}
+
+async fn run_duet(
+    app: AppHandle,
+    mut contexts: [CharacterContext; 2],
+    topic: String,
+    turns: u32,
+    speak: bool,
+    mut interjections: mpsc::UnboundedReceiver<String>,
+    progress: Arc<AtomicU32>,
+) {
+    let names = [contexts[0].name.clone(), contexts[1].name.clone()];
+    let mut entries: Vec<DuetEntry> = Vec::new();
+    let mut last_spoke = [Instant::now(), Instant::now()];
+
+    for turn in 0..turns {
+        while let Ok(text) = interjections.try_recv() {
+            let _ = app.emit(
+                DUET_INTERJECTION_EVENT,
+                serde_json::json!({ "turn": turn, "text": text }),
+            );
+            entries.push(DuetEntry::Interjection(text));
+        }
+
+        let me = speaker_for_turn(turn);
+        let ctx = &mut contexts[me];
+        let _ = app.emit(
+            DUET_LINE_START_EVENT,
+            serde_json::json!({ "turn": turn, "speaker_id": ctx.character_id, "name": ctx.name }),
+        );
+
+        let raw = match generate_line(
+            &app,
+            ctx,
+            &names[1 - me],
+            &topic,
+            &entries,
+            me,
+            &names,
+            turn,
+        )
+        .await
+        {
+            Ok(raw) => raw,
+            Err(e) => {
+                tracing::warn!(target: "duet", "[Duet] {} failed to reply: {}", ctx.name, e);
+                let _ = app.emit(
+                    DUET_FINISH_EVENT,
+                    serde_json::json!({ "status": "error", "error": e.to_string() }),
+                );
+                return;
+            }
+        };
+
+        let (tagged, rest) = split_emotion_tag(&raw);
+        let (text, _) = clean_model_reply(rest);
+        ctx.emotion.decay(last_spoke[me].elapsed().as_secs_f32());
+        last_spoke[me] = Instant::now();
+        if let Some(emotion) = tagged {
+            ctx.emotion.observe(emotion, LINE_EMOTION_STRENGTH);
+        }
+        let feeling = ctx.emotion.state();
+        let _ = app.emit(
+            DUET_LINE_EVENT,
+            serde_json::json!({
+                "turn": turn,
+                "speaker_id": ctx.character_id,
+                "name": ctx.name,
+                "text": text,
+                "emotion": feeling.emotion,
+                "intensity": feeling.intensity,
+            }),
+        );
+        progress.store(turn + 1, Ordering::SeqCst);
+        if text.is_empty() {
+            continue;
+        }
+
+        if speak {
+            if let Some(tts) = app.try_state::<TtsService>() {
+                let mut params = TtsParams::default();
+                condition_params(
+                    &mut params,
+                    feeling.emotion,
+                    feeling.intensity,
+                    feeling.mood,
+                );
+                if let Err(e) = tts
+                    .speak_as(
+                        app.clone(),
+                        text.clone(),
+                        ctx.voice.clone(),
+                        Some(params),
+                        TtsPriority::Normal,
+                        Some(TTS_SOURCE.to_string()),
+                    )
+                    .await
+                {
+                    tracing::warn!(target: "duet", "[Duet] TTS failed for {}: {}", ctx.name, e);
+                }
+            }
+        }
+        entries.push(DuetEntry::Line { speaker: me, text });
+    }
+
+    tracing::info!(target: "duet", "[Duet] Finished after {} turns", turns);
+    emit_finish(&app, "completed");
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn generate_line(
+    app: &AppHandle,
+    ctx: &CharacterContext,
+    other_name: &str,
+    topic: &str,
+    entries: &[DuetEntry],
+    me: usize,
+    names: &[String; 2],
+    turn: u32,
+) -> Result<String, KokoroError> {
+    let llm = app
+        .try_state::<LlmService>()
+        .ok_or_else(|| KokoroError::Internal("LLM service is not ready".into()))?;
+
+    let mut messages = vec![system_message(duet_system_prompt(ctx, other_name, topic))];
+    let transcript = transcript_messages(entries, me, names, &ctx.user_nickname);
+    if transcript
+        .first()
+        .is_none_or(|(role, _)| *role == "assistant")
+    {
+        // The opener (or a speaker who'd otherwise reply to themselves)
+        // needs a user turn to answer.
+        messages.push(user_text_message(format!(
+            "({} starts talking about \"{}\".)",
+            ctx.name, topic
+        )));
+    }
+    for (role, content) in transcript {
+        messages.push(match role {
+            "assistant" => assistant_text_message(content),
+            _ => user_text_message(content),
+        });
+    }
+
+    let provider = llm.provider_for(Some(&ctx.llm)).await;
+    let params = llm.chat_params_for(Some(&ctx.llm)).await;
+    let mut stream = provider
+        .chat_stream_rich(messages.into_iter().map(Into::into).collect(), params)
+        .await
+        .map_err(KokoroError::Llm)?;
+    let mut raw = String::new();
+    while let Some(item) = stream.next().await {
+        match item {
+            Ok(LlmStreamEvent::Text(text)) => {
+                raw.push_str(&text);
+                let _ = app.emit(
+                    DUET_DELTA_EVENT,
+                    serde_json::json!({
+                        "turn": turn,
+                        "speaker_id": ctx.character_id,
+                        "text": visible_so_far(&raw),
+                    }),
+                );
+            }
+            Ok(_) => {}
+            Err(e) => return Err(KokoroError::Llm(e)),
+        }
+    }
+    Ok(raw)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names() -> [String; 2] {
+        ["Aki".to_string(), "Mio".to_string()]
+    }
+
+    #[test]
+    fn speakers_alternate_starting_with_the_first() {
+        let order: Vec<usize> = (0..5).map(speaker_for_turn).collect();
+        assert_eq!(order, vec![0, 1, 0, 1, 0]);
+    }
+
+    #[test]
+    fn transcript_is_seen_from_each_speaker() {
+        let entries = vec![
+            DuetEntry::Line {
+                speaker: 0,
+                text: "Cats are better.".into(),
+            },
+            DuetEntry::Interjection("What about dogs?".into()),
+            DuetEntry::Line {
+                speaker: 1,
+                text: "Dogs, obviously.".into(),
+            },
+        ];
+        let aki = transcript_messages(&entries, 0, &names(), "Sam");
+        assert_eq!(
+            aki,
+            vec![
+                ("assistant", "Cats are better.".to_string()),
+                ("user", "Sam: What about dogs?".to_string()),
+                ("user", "Mio: Dogs, obviously.".to_string()),
+            ]
+        );
+        let mio = transcript_messages(&entries, 1, &names(), "Sam");
+        assert_eq!(mio[0], ("user", "Aki: Cats are better.".to_string()));
+        assert_eq!(mio[2], ("assistant", "Dogs, obviously.".to_string()));
+    }
+
+    #[test]
+    fn transcript_keeps_only_recent_lines() {
+        let entries: Vec<DuetEntry> = (0..TRANSCRIPT_WINDOW + 6)
+            .map(|i| DuetEntry::Line {
+                speaker: i % 2,
+                text: i.to_string(),
+            })
+            .collect();
+        let seen = transcript_messages(&entries, 0, &names(), "Sam");
+        assert_eq!(seen.len(), TRANSCRIPT_WINDOW);
+        assert_eq!(seen[0].1, "6");
+    }
+
+    #[test]
+    fn leading_emotion_tag_is_split_off() {
+        assert_eq!(
+            split_emotion_tag(" [EMOTION:joy] Hello!"),
+            (Some("joy"), " Hello!")
+        );
+        assert_eq!(split_emotion_tag("Hello!"), (None, "Hello!"));
+        // Only a leading tag counts.
+        assert_eq!(split_emotion_tag("Hi [EMOTION:sad]").0, None);
+    }
+
+    #[test]
+    fn partial_tag_is_hidden_while_streaming() {
+        assert_eq!(visible_so_far("[EMO"), "");
+        assert_eq!(visible_so_far("[EMOTION:hap"), "");
+        assert_eq!(visible_so_far("[EMOTION:joy] Hel"), "Hel");
+        assert_eq!(visible_so_far("Hello"), "Hello");
+    }
+}
@@ -0,0 +1,137 @@
+//! LRU cache for text embeddings, keyed by normalized text.
+//!
+//! Retrieval re-embeds the same or near-identical strings constantly (proactive
+//! prompts, greetings, repeated tool queries); the local ONNX model is the
+//! slowest part of a memory search, so a small cache pays off quickly.
+
+use serde::Serialize;
+use std::collections::HashMap;
+
+pub const DEFAULT_EMBEDDING_CACHE_CAPACITY: usize = 512;
+
+/// Collapse whitespace, lowercase, and drop trailing punctuation so
+/// "Good morning!" and "good  morning" share an entry.
+pub fn normalize_embedding_key(text: &str) -> String {
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+        .trim_end_matches(['.', '!', '?', '~', '。', '！', '？', '…'])
+        .trim_end()
+        .to_string()
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct EmbeddingCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub entries: usize,
+    pub capacity: usize,
+    /// `hits / (hits + misses)`, 0.0 before the first lookup.
+    pub hit_rate: f64,
+}
+
+pub struct EmbeddingCache {
+    capacity: usize,
+    entries: HashMap<String, (Vec<f32>, u64)>,
+    tick: u64,
+    hits: u64,
+    misses: u64,
+}
+
+impl EmbeddingCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            entries: HashMap::new(),
+            tick: 0,
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// Look up `key` (already normalized), counting the hit or miss.
+    pub fn get(&mut self, key: &str) -> Option<Vec<f32>> {
+        self.tick += 1;
+        match self.entries.get_mut(key) {
+            Some((embedding, last_used)) => {
+                *last_used = self.tick;
+                self.hits += 1;
+                Some(embedding.clone())
+            }
+            None => {
+                self.misses += 1;
+                None
+            }
+        }
+    }
+
+    pub fn insert(&mut self, key: String, embedding: Vec<f32>) {
+        self.tick += 1;
+        if !self.entries.contains_key(&key) && self.entries.len() >= self.capacity {
+            if let Some(oldest) = self
+                .entries
+                .iter()
+                .min_by_key(|(_, (_, last_used))| *last_used)
+                .map(|(k, _)| k.clone())
+            {
+                self.entries.remove(&oldest);
+            }
+        }
+        self.entries.insert(key, (embedding, self.tick));
+    }
+
+    pub fn stats(&self) -> EmbeddingCacheStats {
+        let lookups = self.hits + self.misses;
+        EmbeddingCacheStats {
+            hits: self.hits,
+            misses: self.misses,
+            entries: self.entries.len(),
+            capacity: self.capacity,
+            hit_rate: if lookups == 0 {
+                0.0
+            } else {
+                self.hits as f64 / lookups as f64
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_case_whitespace_and_trailing_punctuation() {
+        assert_eq!(
+            normalize_embedding_key("  Good   Morning!! "),
+            "good morning"
+        );
+        assert_eq!(normalize_embedding_key("早上好！"), "早上好");
+        assert_eq!(normalize_embedding_key("what's up?"), "what's up");
+    }
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let mut cache = EmbeddingCache::new(2);
+        cache.insert("a".into(), vec![1.0]);
+        cache.insert("b".into(), vec![2.0]);
+        assert!(cache.get("a").is_some()); // "b" is now the LRU entry
+        cache.insert("c".into(), vec![3.0]);
+        assert!(cache.get("b").is_none());
+        assert_eq!(cache.get("a"), Some(vec![1.0]));
+        assert_eq!(cache.get("c"), Some(vec![3.0]));
+    }
+
+    #[test]
+    fn tracks_hit_rate() {
+        let mut cache = EmbeddingCache::new(4);
+        assert_eq!(cache.stats().hit_rate, 0.0);
+        assert!(cache.get("x").is_none());
+        cache.insert("x".into(), vec![0.5]);
+        assert!(cache.get("x").is_some());
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses, stats.entries), (1, 1, 1));
+        assert!((stats.hit_rate - 0.5).abs() < f64::EPSILON);
+    }
+}
@@ -0,0 +1,319 @@
+//! Smoothed emotion state shared with the avatar, mods and integrations.
+//!
+//! Cues, mods and external callers push raw emotion observations; the engine
+//! blends them through the character's [`EmotionPersonality`] (inertia,
+//! expressiveness) and lets them fade between messages on heartbeat ticks.
+//! Every change is broadcast as `emotion:update`.
+
+use crate::ai::context::AIOrchestrator;
+use crate::ai::emotion_personality::EmotionPersonality;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Listener, Manager, Runtime};
+
+pub const EMOTION_UPDATE_EVENT: &str = "emotion:update";
+
+/// Emotion names, shared with [`crate::tts::emotion_tts`].
+pub const EMOTIONS: [&str; 7] = [
+    "neutral", "joy", "love", "sadness", "anger", "surprise", "fear",
+];
+
+/// Intensity below which the character is considered back at rest.
+const REST_INTENSITY: f32 = 0.05;
+
+/// Keywords recognised in cue names, checked in order.
+const LABEL_KEYWORDS: [(&str, &[&str]); 7] = [
+    (
+        "joy",
+        &[
+            "happy", "joy", "smile", "laugh", "glad", "excite", "cheer", "grin",
+        ],
+    ),
+    (
+        "love",
+        &["love", "blush", "shy", "affection", "heart", "kiss"],
+    ),
+    (
+        "sadness",
+        &["sad", "cry", "tear", "sorrow", "upset", "lonely", "sulk"],
+    ),
+    (
+        "anger",
+        &["angry", "anger", "mad", "annoy", "pout", "rage", "furious"],
+    ),
+    (
+        "surprise",
+        &["surprise", "shock", "startle", "amaze", "wow"],
+    ),
+    (
+        "fear",
+        &["fear", "scare", "afraid", "nervous", "anxious", "worr"],
+    ),
+    ("neutral", &["neutral", "idle", "calm", "default", "normal"]),
+];
+
+/// Map a free-form label (a cue name, a mod's emotion string) to one of
+/// [`EMOTIONS`].
+pub fn emotion_from_label(label: &str) -> Option<&'static str> {
+    let label = label.trim().to_lowercase();
+    if let Some(known) = EMOTIONS.iter().find(|e| **e == label) {
+        return Some(known);
+    }
+    LABEL_KEYWORDS
+        .iter()
+        .find(|(_, keywords)| keywords.iter().any(|k| label.contains(k)))
+        .map(|(emotion, _)| *emotion)
+}
+
+/// -1.0 (unpleasant) to 1.0 (pleasant).
+fn valence(emotion: &str) -> f32 {
+    match emotion {
+        "joy" => 1.0,
+        "love" => 0.8,
+        "surprise" => 0.2,
+        "fear" => -0.6,
+        "anger" => -0.7,
+        "sadness" => -0.8,
+        _ => 0.0,
+    }
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct EmotionState {
+    pub emotion: &'static str,
+    /// 0.0–1.0, fades toward 0 between messages.
+    pub intensity: f32,
+    /// 0.0–1.0 longer-term mood, drifting back to the personality's default.
+    pub mood: f32,
+}
+
+/// Payload of `emotion:update`.
+#[derive(Debug, Clone, Serialize)]
+pub struct EmotionUpdateEvent {
+    pub character_id: String,
+    #[serde(flatten)]
+    pub state: EmotionState,
+    /// "cue", "mod", "external", "decay", "reset" (character switch) or
+    /// "checkpoint" (rewind).
+    pub source: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct EmotionEngine {
+    personality: EmotionPersonality,
+    state: EmotionState,
+}
+
+impl Default for EmotionEngine {
+    fn default() -> Self {
+        Self::new(EmotionPersonality::default())
+    }
+}
+
+impl EmotionEngine {
+    pub fn new(personality: EmotionPersonality) -> Self {
+        Self {
+            personality,
+            state: EmotionState {
+                emotion: "neutral",
+                intensity: 0.0,
+                mood: personality.default_mood.clamp(0.0, 1.0),
+            },
+        }
+    }
+
+    /// Start over for a newly selected character.
+    pub fn reset(&mut self, personality: EmotionPersonality) {
+        *self = Self::new(personality);
+    }
+
+    /// Keep the current state but follow an edited personality.
+    pub fn set_personality(&mut self, personality: EmotionPersonality) {
+        self.personality = personality;
+    }
+
+    pub fn state(&self) -> EmotionState {
+        self.state.clone()
+    }
+
+    /// Put back a previously saved state, e.g. when rewinding a conversation.
+    pub fn restore(&mut self, state: EmotionState) {
+        self.state = EmotionState {
+            intensity: state.intensity.clamp(0.0, 1.0),
+            mood: state.mood.clamp(0.0, 1.0),
+            ..state
+        };
+    }
+
+    /// Blend in an observed emotion of `strength` (0–1). A different emotion
+    /// takes over only once it outweighs the current one; until then it wears
+    /// the current one down.
+    pub fn observe(&mut self, emotion: &'static str, strength: f32) {
+        let p = &self.personality;
+        let gain = (1.0 - p.inertia.clamp(0.0, 1.0) * 0.7)
+            * (0.5 + p.expressiveness.clamp(0.0, 1.0) * 0.5);
+        let push = strength.clamp(0.0, 1.0) * gain;
+        let state = &mut self.state;
+
+        if emotion == "neutral" {
+            state.intensity *= 1.0 - push;
+        } else if emotion == state.emotion {
+            state.intensity += push * (1.0 - state.intensity);
+        } else if push >= state.intensity {
+            state.emotion = emotion;
+            state.intensity = push;
+        } else {
+            state.intensity -= push * 0.5;
+        }
+        if state.intensity < REST_INTENSITY {
+            state.emotion = "neutral";
+            state.intensity = 0.0;
+        }
+
+        let target_mood = 0.5 + valence(emotion) * 0.5;
+        state.mood = (state.mood + (target_mood - state.mood) * push * 0.5).clamp(0.0, 1.0);
+    }
+
+    /// Let `elapsed_secs` pass. Returns whether the state moved.
+    pub fn decay(&mut self, elapsed_secs: f32) -> bool {
+        let before = self.state.clone();
+        // Stubborn characters hold on to a feeling for up to two minutes.
+        let half_life = 20.0 + 100.0 * self.personality.inertia.clamp(0.0, 1.0);
+        let factor = 0.5_f32.powf(elapsed_secs.max(0.0) / half_life);
+        let state = &mut self.state;
+        state.intensity *= factor;
+        if state.intensity < REST_INTENSITY {
+            state.emotion = "neutral";
+            state.intensity = 0.0;
+        }
+        let rest = self.personality.default_mood.clamp(0.0, 1.0);
+        // Mood settles three times slower than the emotion itself.
+        state.mood = rest + (state.mood - rest) * factor.powf(1.0 / 3.0);
+        if (state.mood - rest).abs() < 0.005 {
+            state.mood = rest;
+        }
+        self.state != before
+    }
+}
+
+/// Apply an observation to the active character and broadcast the result.
+pub async fn observe<R: Runtime>(
+    app: &AppHandle<R>,
+    emotion: &'static str,
+    strength: f32,
+    source: &str,
+) {
+    let Some(orchestrator) = app.try_state::<AIOrchestrator>() else {
+        return;
+    };
+    let state = {
+        let mut engine = orchestrator.emotion.lock().await;
+        engine.observe(emotion, strength);
+        engine.state()
+    };
+    broadcast(app, &orchestrator, state, source).await;
+}
+
+pub async fn broadcast<R: Runtime>(
+    app: &AppHandle<R>,
+    orchestrator: &AIOrchestrator,
+    state: EmotionState,
+    source: &str,
+) {
+    let character_id = orchestrator.get_character_id().await;
+    if let Some(webhooks) = app.try_state::<crate::webhooks::WebhookService>() {
+        webhooks
+            .mood_changed(&orchestrator.db, &character_id, state.emotion, state.mood)
+            .await;
+    }
+    let _ = app.emit(
+        EMOTION_UPDATE_EVENT,
+        EmotionUpdateEvent {
+            character_id,
+            state,
+            source: source.to_string(),
+        },
+    );
+}
+
+/// Feed cues into the engine. Call once at startup.
+pub fn forward_app_events(app: &AppHandle) {
+    let handle = app.clone();
+    app.listen_any("chat-cue", move |event| {
+        let Ok(payload) = serde_json::from_str::<serde_json::Value>(event.payload()) else {
+            return;
+        };
+        let Some(emotion) = payload
+            .get("cue")
+            .and_then(|v| v.as_str())
+            .and_then(emotion_from_label)
+        else {
+            return;
+        };
+        let handle = handle.clone();
+        tauri::async_runtime::spawn(async move {
+            observe(&handle, emotion, 0.8, "cue").await;
+        });
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_cue_names_to_emotions() {
+        assert_eq!(emotion_from_label("Joy"), Some("joy"));
+        assert_eq!(emotion_from_label("smile_big"), Some("joy"));
+        assert_eq!(emotion_from_label("blushing"), Some("love"));
+        assert_eq!(emotion_from_label("scared"), Some("fear"));
+        assert_eq!(emotion_from_label("idle_01"), Some("neutral"));
+        assert_eq!(emotion_from_label("wave"), None);
+    }
+
+    #[test]
+    fn competing_emotion_needs_to_outweigh_current_one() {
+        let mut engine = EmotionEngine::default();
+        engine.observe("joy", 1.0);
+        let joy = engine.state();
+        assert_eq!(joy.emotion, "joy");
+        assert!(joy.mood > 0.5);
+
+        engine.observe("joy", 1.0);
+        assert!(engine.state().intensity > joy.intensity);
+
+        engine.observe("sadness", 0.3);
+        assert_eq!(engine.state().emotion, "joy");
+        assert!(engine.state().intensity < 1.0);
+    }
+
+    #[test]
+    fn decays_back_to_rest_and_stubborn_characters_hold_longer() {
+        let stubborn = EmotionPersonality {
+            inertia: 1.0,
+            ..Default::default()
+        };
+        let fickle = EmotionPersonality {
+            inertia: 0.0,
+            ..Default::default()
+        };
+        let mut slow = EmotionEngine::new(stubborn);
+        let mut fast = EmotionEngine::new(fickle);
+        slow.observe("anger", 1.0);
+        fast.observe("anger", 1.0);
+        let slow_start = slow.state().intensity;
+        let fast_start = fast.state().intensity;
+
+        assert!(slow.decay(30.0));
+        assert!(fast.decay(30.0));
+        assert!(slow.state().intensity / slow_start > fast.state().intensity / fast_start);
+
+        for _ in 0..200 {
+            fast.decay(10.0);
+        }
+        let rest = fast.state();
+        assert_eq!(rest.emotion, "neutral");
+        assert_eq!(rest.intensity, 0.0);
+        assert_eq!(rest.mood, 0.5);
+        assert!(!fast.decay(10.0));
+    }
+}
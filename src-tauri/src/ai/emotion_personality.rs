@@ -0,0 +1,270 @@
+//! Emotion personality — how a character's mood behaves.
+//!
+//! Resolution order: explicit profile values, then a one-time LLM extraction
+//! cached per persona hash ([`resolve`]), then the persona's temperament
+//! keywords (see [`crate::ai::persona_lint::trait_signals`]).
+
+use crate::ai::persona_lint::{self, PersonaTrait};
+use crate::error::KokoroError;
+use crate::llm::messages::{system_message, user_text_message};
+use crate::llm::provider::LlmProvider;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::SqlitePool;
+
+/// How far one net keyword moves a score away from its default.
+const KEYWORD_STEP: f32 = 0.12;
+/// Net keyword counts beyond this are ignored so long personas don't saturate.
+const MAX_NET_KEYWORDS: i32 = 3;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct EmotionPersonality {
+    /// 0.0–1.0: how strongly mood resists change between turns.
+    pub inertia: f32,
+    /// 0.0–1.0: how openly emotions show (faster, livelier replies).
+    pub expressiveness: f32,
+    /// 0.0–1.0: resting mood, 0.5 being neutral.
+    pub default_mood: f32,
+    /// 0.0–1.0: how much the user's mood pulls on the character's.
+    pub empathy: f32,
+}
+
+impl Default for EmotionPersonality {
+    fn default() -> Self {
+        Self {
+            inertia: 0.5,
+            expressiveness: 0.6,
+            default_mood: 0.5,
+            empathy: 0.5,
+        }
+    }
+}
+
+impl EmotionPersonality {
+    /// Keyword-based estimate from persona text. Personas without
+    /// temperament keywords get the defaults.
+    pub fn parse_from_persona(persona: &str) -> Self {
+        let signals = persona_lint::trait_signals(persona);
+        let net = |trait_name: PersonaTrait| -> f32 {
+            signals
+                .iter()
+                .filter(|s| s.trait_name == trait_name)
+                .map(|s| s.direction as i32)
+                .sum::<i32>()
+                .clamp(-MAX_NET_KEYWORDS, MAX_NET_KEYWORDS) as f32
+        };
+
+        let defaults = Self::default();
+        let energy = net(PersonaTrait::Energy);
+        let warmth = net(PersonaTrait::Warmth);
+        let expressiveness = net(PersonaTrait::Expressiveness);
+        let stability = net(PersonaTrait::Stability);
+        let confidence = net(PersonaTrait::Confidence);
+
+        Self {
+            inertia: defaults.inertia + KEYWORD_STEP * stability,
+            expressiveness: defaults.expressiveness
+                + KEYWORD_STEP * expressiveness
+                + KEYWORD_STEP * 0.5 * (energy + confidence),
+            default_mood: defaults.default_mood + KEYWORD_STEP * 0.5 * (energy + warmth),
+            empathy: defaults.empathy + KEYWORD_STEP * warmth,
+        }
+        .clamped()
+    }
+
+    pub fn clamped(self) -> Self {
+        Self {
+            inertia: self.inertia.clamp(0.0, 1.0),
+            expressiveness: self.expressiveness.clamp(0.0, 1.0),
+            default_mood: self.default_mood.clamp(0.0, 1.0),
+            empathy: self.empathy.clamp(0.0, 1.0),
+        }
+    }
+}
+
+const EXTRACTION_PROMPT: &str = r#"You rate a roleplay character's emotional temperament from their persona.
+Reply with ONLY a JSON object, no prose, using scores from 0.0 to 1.0:
+{"inertia": how strongly their mood resists change (stubborn, calm = high; moody, volatile = low),
+ "expressiveness": how openly they show emotion (reserved = low; animated = high),
+ "default_mood": resting mood (gloomy = low; 0.5 neutral; sunny = high),
+ "empathy": how much other people's feelings affect them (aloof = low; caring = high)}
+Judge the character as a whole: sarcasm, irony and "pretends to be cold but..." tropes count as they are really meant."#;
+
+/// LLM reply shape; missing scores fall back to the keyword estimate.
+#[derive(Debug, Default, Deserialize)]
+struct ExtractedTraits {
+    inertia: Option<f32>,
+    expressiveness: Option<f32>,
+    default_mood: Option<f32>,
+    empathy: Option<f32>,
+}
+
+/// Stable cache key for a persona; whitespace-only edits keep the same key.
+pub fn persona_hash(persona: &str) -> String {
+    let normalized = persona.split_whitespace().collect::<Vec<_>>().join(" ");
+    let mut hasher = Sha256::new();
+    hasher.update(normalized.as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+/// Parse the extraction reply, tolerating code fences and surrounding prose.
+/// Returns `None` when no score could be read.
+fn parse_extraction(response: &str, fallback: EmotionPersonality) -> Option<EmotionPersonality> {
+    let start = response.find('{')?;
+    let end = response.rfind('}')?;
+    if end <= start {
+        return None;
+    }
+    let traits: ExtractedTraits = serde_json::from_str(&response[start..=end]).ok()?;
+    if traits.inertia.is_none()
+        && traits.expressiveness.is_none()
+        && traits.default_mood.is_none()
+        && traits.empathy.is_none()
+    {
+        return None;
+    }
+    let score =
+        |value: Option<f32>, default: f32| value.filter(|v| v.is_finite()).unwrap_or(default);
+    Some(
+        EmotionPersonality {
+            inertia: score(traits.inertia, fallback.inertia),
+            expressiveness: score(traits.expressiveness, fallback.expressiveness),
+            default_mood: score(traits.default_mood, fallback.default_mood),
+            empathy: score(traits.empathy, fallback.empathy),
+        }
+        .clamped(),
+    )
+}
+
+/// Previously extracted traits for this persona, if any.
+pub async fn load_cached(db: &SqlitePool, persona: &str) -> Option<EmotionPersonality> {
+    let json: Option<String> =
+        sqlx::query_scalar("SELECT traits_json FROM persona_trait_cache WHERE persona_hash = ?")
+            .bind(persona_hash(persona))
+            .fetch_optional(db)
+            .await
+            .unwrap_or_else(|e| {
+                tracing::warn!(target: "ai", "[Personality] Failed to read trait cache: {}", e);
+                None
+            });
+    json.and_then(|json| serde_json::from_str::<EmotionPersonality>(&json).ok())
+        .map(EmotionPersonality::clamped)
+}
+
+/// Ask the LLM to score the persona and cache the result. Cached personas are
+/// returned without a model call.
+pub async fn extract_with_llm(
+    db: &SqlitePool,
+    provider: &dyn LlmProvider,
+    persona: &str,
+) -> Result<EmotionPersonality, KokoroError> {
+    if let Some(cached) = load_cached(db, persona).await {
+        return Ok(cached);
+    }
+    if persona.trim().is_empty() {
+        return Err(KokoroError::Validation("persona is empty".to_string()));
+    }
+
+    let messages = vec![
+        system_message(EXTRACTION_PROMPT.to_string()),
+        user_text_message(format!("Persona:\n\n{}", persona)),
+    ];
+    let response = provider
+        .chat(messages, None)
+        .await
+        .map_err(KokoroError::Llm)?;
+    let keyword_estimate = EmotionPersonality::parse_from_persona(persona);
+    let personality = parse_extraction(&response, keyword_estimate).ok_or_else(|| {
+        KokoroError::Llm(format!(
+            "unparseable trait extraction reply: {}",
+            response.chars().take(200).collect::<String>()
+        ))
+    })?;
+
+    let traits_json =
+        serde_json::to_string(&personality).map_err(|e| KokoroError::Internal(e.to_string()))?;
+    sqlx::query(
+        "INSERT OR REPLACE INTO persona_trait_cache (persona_hash, traits_json, created_at) VALUES (?, ?, ?)",
+    )
+    .bind(persona_hash(persona))
+    .bind(traits_json)
+    .bind(chrono::Utc::now().timestamp())
+    .execute(db)
+    .await?;
+
+    tracing::info!(target: "ai", "[Personality] Extracted traits via LLM: {:?}", personality);
+    Ok(personality)
+}
+
+/// Best available personality without blocking on the model: the cached LLM
+/// extraction when present, the keyword estimate otherwise.
+pub async fn resolve(db: &SqlitePool, persona: &str) -> (EmotionPersonality, bool) {
+    match load_cached(db, persona).await {
+        Some(cached) => (cached, true),
+        None => (EmotionPersonality::parse_from_persona(persona), false),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keywords_shift_scores_in_the_expected_direction() {
+        let neutral = EmotionPersonality::parse_from_persona("A librarian in a small town.");
+        assert_eq!(neutral, EmotionPersonality::default());
+
+        let lively = EmotionPersonality::parse_from_persona("Cheerful, talkative and bold.");
+        assert!(lively.expressiveness > neutral.expressiveness);
+        assert!(lively.default_mood > neutral.default_mood);
+
+        let moody = EmotionPersonality::parse_from_persona("内向，喜怒无常，不太自信。");
+        assert!(moody.inertia < neutral.inertia);
+        assert!(moody.expressiveness < neutral.expressiveness);
+    }
+
+    #[test]
+    fn extraction_reply_is_parsed_and_clamped() {
+        let fallback = EmotionPersonality::default();
+        let parsed = parse_extraction(
+            "```json\n{\"inertia\": 0.9, \"expressiveness\": 1.4, \"empathy\": 0.2}\n```",
+            fallback,
+        )
+        .expect("parsed");
+        assert_eq!(parsed.inertia, 0.9);
+        assert_eq!(parsed.expressiveness, 1.0);
+        assert_eq!(parsed.default_mood, fallback.default_mood);
+        assert_eq!(parsed.empathy, 0.2);
+
+        assert!(parse_extraction("I think she is nice.", fallback).is_none());
+        assert!(parse_extraction("{\"mood\": \"happy\"}", fallback).is_none());
+    }
+
+    #[tokio::test]
+    async fn extracted_traits_are_cached_per_persona() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        let persona = "A tsundere who secretly cares.";
+        assert!(load_cached(&pool, persona).await.is_none());
+        let (estimate, cached) = resolve(&pool, persona).await;
+        assert!(!cached);
+        assert_eq!(estimate, EmotionPersonality::parse_from_persona(persona));
+
+        let stored = EmotionPersonality {
+            empathy: 0.8,
+            ..EmotionPersonality::default()
+        };
+        sqlx::query(
+            "INSERT INTO persona_trait_cache (persona_hash, traits_json, created_at) VALUES (?, ?, 0)",
+        )
+        .bind(persona_hash("A tsundere  who secretly cares.\n"))
+        .bind(serde_json::to_string(&stored).unwrap())
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        assert_eq!(resolve(&pool, persona).await, (stored, true));
+    }
+}
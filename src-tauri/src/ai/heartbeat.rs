@@ -1,22 +1,33 @@
 use crate::ai::context::AIOrchestrator;
 use crate::ai::initiative::InitiativeDecision;
-use chrono::Timelike;
+use crate::ai::proactive_composer::{ProactiveComposer, ProactivePlan};
+use crate::ai::proactive_policy::{ProactiveLimiter, ProactivePolicy, Suppressed};
+use crate::ai::system_state::{probe_system_state, SystemStateMonitor};
+use crate::ai::user_presence::{
+    probe_input_idle_secs, PresenceMonitor, UserPresence, UserPresenceEvent,
+};
+use crate::character_profiles::CharacterProfileService;
+use crate::telegram::TelegramConfig;
+use chrono::{NaiveDateTime, Timelike};
 use serde::Serialize;
 use tauri::{AppHandle, Emitter, Manager};
 
+/// Seconds between heartbeat ticks.
+const HEARTBEAT_SECS: u64 = 10;
+
 /// Configuration for the heartbeat system.
 pub struct HeartbeatConfig {
     /// Seconds of idle before triggering a proactive message.
     pub idle_threshold_secs: u64,
-    /// Minimum seconds between proactive messages (cooldown).
-    pub cooldown_secs: u64,
+    /// Seconds between battery / network polls.
+    pub system_state_interval_secs: u64,
 }
 
 impl Default for HeartbeatConfig {
     fn default() -> Self {
         Self {
             idle_threshold_secs: 300, // 5 minutes
-            cooldown_secs: 600,       // 10 minutes between proactive messages
+            system_state_interval_secs: 60,
         }
     }
 }
@@ -28,7 +39,7 @@ struct IdleBehaviorEvent {
 }
 
 /// Get a time-of-day greeting context string.
-fn time_of_day_context() -> &'static str {
+pub(crate) fn time_of_day_context() -> &'static str {
     let hour = chrono::Local::now().hour();
     match hour {
         5..=8 => "It is early morning. The user may have just woken up.",
@@ -44,13 +55,19 @@ fn time_of_day_context() -> &'static str {
 /// Main heartbeat loop. Spawned once at app startup.
 pub async fn heartbeat_loop(app_handle: AppHandle) {
     let config = HeartbeatConfig::default();
-    let mut last_proactive_ts = std::time::Instant::now();
+    let mut proactive_limiter = ProactiveLimiter::new();
     let _last_time_period = current_time_period();
     let mut last_prune_ts = std::time::Instant::now();
     let mut last_dream_date: Option<chrono::NaiveDate> = None;
+    let mut last_system_state_ts: Option<std::time::Instant> = None;
+    let mut system_state_monitor = SystemStateMonitor::new();
+    let mut presence_monitor = PresenceMonitor::new();
+    let mut db_maintenance = crate::db::MaintenanceSchedule::new();
+    let mut last_asset_clean_ts = std::time::Instant::now();
+    let mut last_wake_greeting_ts: Option<std::time::Instant> = None;
 
     loop {
-        tokio::time::sleep(tokio::time::Duration::from_secs(10)).await;
+        tokio::time::sleep(tokio::time::Duration::from_secs(HEARTBEAT_SECS)).await;
 
         // Get orchestrator state
         let orchestrator = match app_handle.try_state::<AIOrchestrator>() {
@@ -61,14 +78,49 @@ pub async fn heartbeat_loop(app_handle: AppHandle) {
         // Gather metrics
         let idle_secs = orchestrator.idle_seconds().await;
         let conversation_count = orchestrator.get_conversation_count().await;
+        let input_idle_secs = probe_input_idle_secs().await;
+        let presence =
+            UserPresence::classify(idle_secs, input_idle_secs, config.idle_threshold_secs);
+        let presence_update = presence_monitor.observe(presence, input_idle_secs);
+        if presence_update.changed {
+            let _ = app_handle.emit(
+                "user-presence-changed",
+                UserPresenceEvent {
+                    presence,
+                    input_idle_secs,
+                },
+            );
+        }
+
+        // Back from OS sleep: the monotonic timers below stood still while the
+        // machine was asleep, so count the sleep towards them.
+        let resume = app_handle
+            .try_state::<crate::lifecycle::LifecycleManager>()
+            .and_then(|lifecycle| lifecycle.take_pending_resume());
+        if let Some(resume) = resume {
+            let slept = std::time::Duration::from_secs(resume.slept_secs);
+            last_prune_ts = last_prune_ts.checked_sub(slept).unwrap_or(last_prune_ts);
+            last_asset_clean_ts = last_asset_clean_ts
+                .checked_sub(slept)
+                .unwrap_or(last_asset_clean_ts);
+            db_maintenance.catch_up(slept);
+            last_system_state_ts = None;
+        }
 
         // ── Autonomous Systems Updates ──
 
-        // 1. Curiosity Decay
+        // 1. Curiosity + Emotion Decay
         {
             let mut curiosity = orchestrator.curiosity.lock().await;
             curiosity.decay();
         }
+        let emotion = {
+            let mut engine = orchestrator.emotion.lock().await;
+            engine.decay(HEARTBEAT_SECS as f32).then(|| engine.state())
+        };
+        if let Some(emotion) = emotion {
+            crate::ai::emotion::broadcast(&app_handle, &orchestrator, emotion, "decay").await;
+        }
 
         // 2. Idle Behaviors (Animations)
         {
@@ -78,19 +130,181 @@ pub async fn heartbeat_loop(app_handle: AppHandle) {
             }
         }
 
+        // 2b. Battery / network snapshot (context injection + proactive nudges)
+        if last_system_state_ts
+            .map(|ts| ts.elapsed().as_secs() >= config.system_state_interval_secs)
+            .unwrap_or(true)
+        {
+            last_system_state_ts = Some(std::time::Instant::now());
+            let state = probe_system_state().await;
+            let changed = orchestrator
+                .get_system_state()
+                .await
+                .map(|prev| prev.differs_from(&state))
+                .unwrap_or(true);
+            if changed {
+                let _ = app_handle.emit("system-state-changed", &state);
+            }
+            crate::offline::apply_network_state(&app_handle, state.network_online).await;
+            let triggers = system_state_monitor.observe(&state);
+            orchestrator.set_system_state(state).await;
+
+            if orchestrator.is_proactive_enabled() {
+                for trigger in triggers {
+                    if !proactive_allowed(&app_handle, &orchestrator, &proactive_limiter).await {
+                        break;
+                    }
+                    trigger_proactive_message(
+                        &app_handle,
+                        &orchestrator,
+                        &mut proactive_limiter,
+                        trigger.trigger_type(),
+                        ProactivePlan::event(trigger.trigger_type(), trigger.instruction()),
+                        presence,
+                        input_idle_secs,
+                    )
+                    .await;
+                }
+            }
+        }
+
+        // 2c. Welcome the user back after a long time away from the computer
+        let greeted_on_wake = last_wake_greeting_ts.is_some_and(|ts| ts.elapsed().as_secs() < 600);
+        if let Some(away_secs) = presence_update
+            .returned_after_secs
+            .filter(|_| !greeted_on_wake)
+        {
+            if orchestrator.is_proactive_enabled()
+                && proactive_allowed(&app_handle, &orchestrator, &proactive_limiter).await
+            {
+                trigger_proactive_message(
+                    &app_handle,
+                    &orchestrator,
+                    &mut proactive_limiter,
+                    "welcome_back",
+                    ProactivePlan::event(
+                        "welcome_back",
+                        format!(
+                            "The user just came back to the computer after about {} minutes away. Welcome them back briefly.",
+                            away_secs / 60
+                        ),
+                    ),
+                    presence,
+                    input_idle_secs,
+                )
+                .await;
+            }
+        }
+
+        // 2d. Greet the user when the machine wakes after a long absence
+        let absent_secs = resume.map(|resume| resume.slept_secs.max(idle_secs));
+        if let Some(absent_secs) =
+            absent_secs.filter(|secs| *secs >= crate::lifecycle::GREET_AFTER_SECS)
+        {
+            if orchestrator.is_proactive_enabled()
+                && proactive_allowed(&app_handle, &orchestrator, &proactive_limiter).await
+            {
+                last_wake_greeting_ts = Some(std::time::Instant::now());
+                trigger_proactive_message(
+                    &app_handle,
+                    &orchestrator,
+                    &mut proactive_limiter,
+                    "welcome_back",
+                    ProactivePlan::event(
+                        "welcome_back",
+                        crate::lifecycle::greeting_instruction(absent_secs),
+                    ),
+                    presence,
+                    input_idle_secs,
+                )
+                .await;
+            }
+        }
+
         // 3. Auto Backup Check (interval configured by user)
         crate::commands::auto_backup::check_and_run(&app_handle).await;
 
-        // 4. Memory Decay Pruning (once per hour)
+        // 4. Memory Importance Re-scoring + Decay Pruning (once per hour)
         if orchestrator.is_memory_enabled() && last_prune_ts.elapsed().as_secs() >= 3600 {
             last_prune_ts = std::time::Instant::now();
             let memory_mgr = orchestrator.memory_manager.clone();
             let char_id = orchestrator.get_character_id().await;
             tauri::async_runtime::spawn(async move {
+                // Re-score from usage stats first so demoted memories can be pruned this pass.
+                if let Err(e) = memory_mgr.rescore_memory_importance(&char_id).await {
+                    tracing::warn!(target: "memory", "[Memory] Importance re-scoring failed: {}", e);
+                }
                 let _ = memory_mgr.prune_decayed_memories(&char_id, 0.05).await;
             });
         }
 
+        // 4b. SQLite maintenance (PRAGMA optimize, VACUUM while idle)
+        db_maintenance
+            .run_due(&orchestrator.db, idle_secs >= config.idle_threshold_secs)
+            .await;
+
+        // 4c. Asset store cleanup (every 6 hours)
+        if last_asset_clean_ts.elapsed().as_secs() >= 6 * 3600 {
+            last_asset_clean_ts = std::time::Instant::now();
+            if let Some(store) = app_handle.try_state::<crate::assets::AssetStore>() {
+                let store = store.inner().clone();
+                tauri::async_runtime::spawn(async move {
+                    let quotas = crate::assets::load_config(&crate::assets::config_path());
+                    if let Err(e) = store.clean(&quotas, false).await {
+                        tracing::warn!(target: "assets", "[Assets] Scheduled cleanup failed: {}", e);
+                    }
+                });
+            }
+        }
+
+        // 4d. Scheduled greetings, reminders and actions
+        crate::ai::scheduler::run_due(&app_handle, &orchestrator).await;
+
+        // 4e. Memory extraction for a topic the user walked away from
+        if orchestrator.is_memory_write_enabled() {
+            let char_id = orchestrator.get_character_id().await;
+            if let Some(trigger) = orchestrator
+                .take_idle_topic_end(&char_id, idle_secs, config.idle_threshold_secs)
+                .await
+            {
+                let history = orchestrator.get_recent_memory_history(10).await;
+                let memory_mgr = orchestrator.memory_manager.clone();
+                let memory_enabled = orchestrator.memory_enabled_flag();
+                let target_language = orchestrator.response_language.lock().await.clone();
+                let provider = app_handle
+                    .try_state::<crate::llm::service::LlmService>()
+                    .map(|state| state.inner().clone());
+                tauri::async_runtime::spawn(async move {
+                    let Some(llm_state) = provider else {
+                        return;
+                    };
+                    if !memory_enabled.load(std::sync::atomic::Ordering::SeqCst) {
+                        return;
+                    }
+                    let observation_started_at = std::time::Instant::now();
+                    let _ = memory_mgr
+                        .record_periodic_write_if_enabled(
+                            &char_id,
+                            "chat",
+                            trigger.as_str(),
+                            observation_started_at,
+                        )
+                        .await;
+                    crate::ai::memory_extractor::extract_and_store_memories_with_options(
+                        &history,
+                        &memory_mgr,
+                        llm_state.system_provider().await,
+                        char_id,
+                        crate::ai::memory_extractor::MemoryExtractionOptions {
+                            structured_memory_enabled: false,
+                            target_language: Some(target_language),
+                        },
+                    )
+                    .await;
+                });
+            }
+        }
+
         // 5. Dream Memory v2 daily consolidation (once per local day after configured hour)
         if orchestrator.is_memory_enabled() {
             let memory_config = crate::config::load_memory_upgrade_config(
@@ -156,85 +370,233 @@ pub async fn heartbeat_loop(app_handle: AppHandle) {
         if !orchestrator.is_proactive_enabled() {
             continue;
         }
-        if last_proactive_ts.elapsed().as_secs() >= config.cooldown_secs {
+        // Nobody is at the desktop to hear it; only speak up if Telegram can reach them.
+        if presence == UserPresence::Away && !telegram_proactive_enabled(&app_handle).await {
+            continue;
+        }
+        if proactive_allowed(&app_handle, &orchestrator, &proactive_limiter).await {
             let decision = {
                 let mut initiative = orchestrator.initiative.lock().await;
                 let mut curiosity = orchestrator.curiosity.lock().await;
                 initiative.decide(&mut curiosity, conversation_count, idle_secs)
             };
 
-            match decision {
-                InitiativeDecision::StayQuiet => {
-                    // Do nothing
+            let (trigger_type, curiosity) = match decision {
+                InitiativeDecision::StayQuiet | InitiativeDecision::VideoShare { .. } => continue,
+                InitiativeDecision::AskQuestion { topic } => ("curiosity", Some((topic, true))),
+                // "random" has no subject of its own; let the composer pick an angle.
+                InitiativeDecision::ShareThought { topic } if topic == "random" => {
+                    ("initiative", None)
                 }
-                InitiativeDecision::AskQuestion { topic } => {
-                    trigger_proactive_message(
-                        &app_handle,
-                        &orchestrator,
-                        "curiosity",
-                        &format!("Ask the user about: {}", topic),
-                    )
-                    .await;
-                    last_proactive_ts = std::time::Instant::now();
-                }
-                InitiativeDecision::ShareThought { topic } => {
-                    let instruction = if topic == "random" {
-                        "Share a random thought or observation relevant to the current context/time."
-                    } else {
-                        &format!("Share a thought about: {}", topic)
-                    };
+                InitiativeDecision::ShareThought { topic } => ("initiative", Some((topic, false))),
+            };
+            let Some(composer) = app_handle.try_state::<ProactiveComposer>() else {
+                continue;
+            };
+            match composer
+                .compose(&app_handle, &orchestrator, curiosity)
+                .await
+            {
+                Some(plan) => {
                     trigger_proactive_message(
                         &app_handle,
                         &orchestrator,
-                        "initiative",
-                        instruction,
+                        &mut proactive_limiter,
+                        trigger_type,
+                        plan,
+                        presence,
+                        input_idle_secs,
                     )
                     .await;
-                    last_proactive_ts = std::time::Instant::now();
                 }
-                InitiativeDecision::VideoShare { .. } => {
-                    // Not implemented
+                None => {
+                    tracing::debug!(
+                        target: "chat",
+                        "[Heartbeat] Every proactive angle was used recently, staying quiet"
+                    );
                 }
             }
         }
     }
 }
 
+/// Check the active character's quiet hours and frequency caps.
+async fn proactive_allowed(
+    app_handle: &AppHandle,
+    orchestrator: &AIOrchestrator,
+    limiter: &ProactiveLimiter,
+) -> bool {
+    let character_id = orchestrator.get_character_id().await;
+    let policy = proactive_policy(app_handle, &character_id).await;
+    let now = chrono::Local::now().naive_local();
+    match limiter.check(&character_id, &policy, now) {
+        Ok(()) => true,
+        Err(reason) => {
+            tracing::debug!(
+                target: "chat",
+                "[Heartbeat] Proactive message for '{}' suppressed: {:?}",
+                character_id,
+                reason
+            );
+            false
+        }
+    }
+}
+
+async fn proactive_policy(app_handle: &AppHandle, character_id: &str) -> ProactivePolicy {
+    match app_handle.try_state::<CharacterProfileService>() {
+        Some(profiles) => profiles.get_profile(character_id).await.proactive,
+        None => Default::default(),
+    }
+}
+
+/// Where a proactive message is delivered.
+#[derive(Debug, PartialEq)]
+enum ProactiveDelivery {
+    Desktop,
+    Telegram(TelegramConfig),
+}
+
+/// The character's quiet hours and caps apply however the message is
+/// delivered; Telegram is only used while the desktop is away and there are
+/// paired chats to send to.
+fn proactive_delivery(
+    limiter: &ProactiveLimiter,
+    character_id: &str,
+    policy: &ProactivePolicy,
+    now: NaiveDateTime,
+    telegram: Option<TelegramConfig>,
+    desktop_away: impl FnOnce(&TelegramConfig) -> bool,
+) -> Result<ProactiveDelivery, Suppressed> {
+    limiter.check(character_id, policy, now)?;
+    Ok(
+        match telegram
+            .filter(|config| !config.proactive_recipients().is_empty() && desktop_away(config))
+        {
+            Some(config) => ProactiveDelivery::Telegram(config),
+            None => ProactiveDelivery::Desktop,
+        },
+    )
+}
+
+async fn telegram_proactive_enabled(app_handle: &AppHandle) -> bool {
+    match app_handle.try_state::<crate::telegram::TelegramService>() {
+        Some(service) => service.proactive_config().await.is_some(),
+        None => false,
+    }
+}
+
 async fn trigger_proactive_message(
     app_handle: &AppHandle,
     orchestrator: &AIOrchestrator,
+    limiter: &mut ProactiveLimiter,
     trigger_type: &str,
-    instruction: &str,
+    plan: ProactivePlan,
+    presence: UserPresence,
+    input_idle_secs: Option<u64>,
 ) {
     let time_ctx = time_of_day_context();
     let idle_secs = orchestrator.idle_seconds().await;
+    let character_id = orchestrator.get_character_id().await;
 
-    let full_instruction = format!(
-        "User has been idle for {:.0} minutes. {} {}",
-        idle_secs as f64 / 60.0,
+    let telegram_config = match app_handle.try_state::<crate::telegram::TelegramService>() {
+        Some(service) => service.proactive_config().await,
+        None => None,
+    };
+    let policy = proactive_policy(app_handle, &character_id).await;
+    let delivery = match proactive_delivery(
+        limiter,
+        &character_id,
+        &policy,
+        chrono::Local::now().naive_local(),
+        telegram_config,
+        |config| {
+            // Keyboard/mouse idle is the better signal when the OS reports it.
+            let away_idle_secs = input_idle_secs.unwrap_or(idle_secs);
+            desktop_away(app_handle, away_idle_secs, config.away_after_idle_minutes)
+        },
+    ) {
+        Ok(delivery) => delivery,
+        Err(reason) => {
+            tracing::debug!(
+                target: "chat",
+                "[Heartbeat] Proactive message for '{}' suppressed: {:?}",
+                character_id,
+                reason
+            );
+            return;
+        }
+    };
+
+    let mut full_instruction = format!(
+        "{} {} {}",
+        presence.describe(idle_secs, input_idle_secs),
         time_ctx,
-        instruction
+        plan.instruction
     );
+    if let Some(composer) = app_handle.try_state::<ProactiveComposer>() {
+        full_instruction = composer
+            .with_diversity_hint(&orchestrator.db, &character_id, &full_instruction)
+            .await;
+        composer
+            .expect_reply(&character_id, &full_instruction, &plan)
+            .await;
+    }
 
     tracing::info!(
         target: "chat",
-        "[Heartbeat] Trigger '{}' fired: {}",
-        trigger_type, instruction
+        "[Heartbeat] Trigger '{}' fired ({}): {}",
+        trigger_type, plan.source.as_str(), plan.instruction
     );
 
-    let _ = app_handle.emit(
-        "proactive-trigger",
-        serde_json::json!({
-            "trigger": trigger_type,
-            "idle_seconds": idle_secs,
-            "instruction": full_instruction,
-        }),
-    );
+    match delivery {
+        ProactiveDelivery::Telegram(config) => {
+            tracing::info!(
+                target: "chat",
+                "[Heartbeat] Desktop is away, sending '{}' via Telegram",
+                trigger_type
+            );
+            let app = app_handle.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) =
+                    crate::telegram::bot::send_proactive_message(&app, &config, &full_instruction)
+                        .await
+                {
+                    tracing::error!(target: "telegram", "[Telegram] Proactive message failed: {}", e);
+                }
+            });
+        }
+        ProactiveDelivery::Desktop => {
+            let _ = app_handle.emit(
+                "proactive-trigger",
+                serde_json::json!({
+                    "trigger": trigger_type,
+                    "idle_seconds": idle_secs,
+                    "instruction": full_instruction,
+                }),
+            );
+        }
+    }
+
+    limiter.record(&character_id, chrono::Local::now().naive_local());
 
     // Reset idle timer so we don't re-trigger immediately
     orchestrator.touch_activity().await;
 }
 
+/// Nobody is looking at the desktop: neither the main nor the pet window is
+/// on screen, or the one that is has been left unfocused and idle.
+fn desktop_away(app_handle: &AppHandle, idle_secs: u64, away_after_idle_minutes: u32) -> bool {
+    let idle_away = idle_secs >= u64::from(away_after_idle_minutes) * 60;
+    !["main", "pet"].iter().any(|label| {
+        app_handle.get_webview_window(label).is_some_and(|win| {
+            let on_screen =
+                win.is_visible().unwrap_or(false) && !win.is_minimized().unwrap_or(false);
+            on_screen && (win.is_focused().unwrap_or(false) || !idle_away)
+        })
+    })
+}
+
 /// Time period enum for detecting transitions.
 #[derive(Debug, Clone, Copy, PartialEq)]
 enum TimePeriod {
@@ -259,3 +621,84 @@ fn current_time_period() -> TimePeriod {
         _ => TimePeriod::LateNight,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::proactive_policy::QuietHours;
+    use chrono::NaiveDate;
+
+    fn at(h: u32, m: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 5, 1)
+            .unwrap()
+            .and_hms_opt(h, m, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn telegram_delivery_respects_quiet_hours_and_character_limits() {
+        let policy = ProactivePolicy {
+            quiet_hours: vec![QuietHours {
+                start: "23:00".to_string(),
+                end: "07:00".to_string(),
+            }],
+            min_interval_minutes: 60,
+            daily_cap: Some(1),
+        };
+        let telegram = TelegramConfig {
+            allowed_chat_ids: vec![111],
+            proactive_when_away: true,
+            ..TelegramConfig::default()
+        };
+        let mut limiter = ProactiveLimiter::new();
+        let deliver = |limiter: &ProactiveLimiter, character_id: &str, now, away: bool| {
+            proactive_delivery(
+                limiter,
+                character_id,
+                &policy,
+                now,
+                Some(telegram.clone()),
+                |_| away,
+            )
+        };
+
+        // Being away from the desktop does not lift quiet hours.
+        assert_eq!(
+            deliver(&limiter, "a", at(23, 30), true),
+            Err(Suppressed::QuietHours)
+        );
+        assert_eq!(
+            deliver(&limiter, "a", at(9, 0), true),
+            Ok(ProactiveDelivery::Telegram(telegram.clone()))
+        );
+        assert_eq!(
+            deliver(&limiter, "a", at(9, 0), false),
+            Ok(ProactiveDelivery::Desktop)
+        );
+
+        // A Telegram send counts against the same per-character limits.
+        limiter.record("a", at(9, 0));
+        assert_eq!(
+            deliver(&limiter, "a", at(9, 30), true),
+            Err(Suppressed::MinInterval)
+        );
+        assert_eq!(
+            deliver(&limiter, "a", at(15, 0), true),
+            Err(Suppressed::DailyCap)
+        );
+        assert_eq!(
+            deliver(&limiter, "b", at(15, 0), true),
+            Ok(ProactiveDelivery::Telegram(telegram.clone()))
+        );
+
+        // Without paired chats the message stays on the desktop.
+        let unpaired = TelegramConfig {
+            allowed_chat_ids: Vec::new(),
+            ..telegram.clone()
+        };
+        assert_eq!(
+            proactive_delivery(&limiter, "b", &policy, at(15, 0), Some(unpaired), |_| true),
+            Ok(ProactiveDelivery::Desktop)
+        );
+    }
+}
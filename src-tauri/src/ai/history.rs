@@ -0,0 +1,347 @@
+//! In-memory chat history, one buffer per conversation.
+//!
+//! Every chat surface (the desktop window, a Telegram chat running its own
+//! `/new` conversation, ...) reads and writes the buffer of its own
+//! conversation, so turns from one never end up in another's prompt. Before a
+//! surface's first message is persisted (and always in privacy mode) it has
+//! no conversation id yet and uses a draft buffer of its own. Conversation
+//! buffers live in a small LRU; one that was evicted is loaded again from
+//! `conversation_messages` the next time it is needed. Buffers still holding
+//! turns memory extraction has not read are kept until it has.
+
+use crate::ai::context::Message;
+use sqlx::SqlitePool;
+use std::collections::VecDeque;
+
+/// Messages kept per buffer; older ones live on in the DB and the summary.
+pub const MAX_BUFFERED_MESSAGES: usize = 20;
+/// Conversation buffers kept before the least recently used is dropped.
+const CONVERSATION_CAPACITY: usize = 8;
+
+/// Where a chat turn comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChatSurface {
+    /// The desktop chat window's current conversation, which the HTTP API
+    /// and Telegram's `/continue` mode share.
+    Desktop,
+    /// A Telegram chat that started its own conversation with `/new`.
+    Telegram(i64),
+}
+
+/// Identifies one buffer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HistoryKey {
+    Conversation(String),
+    /// Turns of a surface that has no persisted conversation yet.
+    Draft(ChatSurface),
+}
+
+impl HistoryKey {
+    pub fn new(surface: ChatSurface, conversation_id: Option<&str>) -> Self {
+        match conversation_id {
+            Some(id) => HistoryKey::Conversation(id.to_string()),
+            None => HistoryKey::Draft(surface),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct HistoryBuffer {
+    pub messages: VecDeque<Message>,
+    /// Index of the first message memory extraction may read; turns before it
+    /// were reloaded or sent while memory was off.
+    pub memory_boundary: usize,
+}
+
+impl HistoryBuffer {
+    /// Append a message, dropping the oldest past `MAX_BUFFERED_MESSAGES`.
+    pub fn push(&mut self, message: Message) {
+        self.messages.push_back(message);
+        if self.messages.len() > MAX_BUFFERED_MESSAGES {
+            self.messages.pop_front();
+            self.memory_boundary = self.memory_boundary.saturating_sub(1);
+        }
+    }
+
+    /// Drop the newest `count` messages. Returns how many were removed.
+    pub fn truncate_last(&mut self, count: usize) -> usize {
+        let removed = count.min(self.messages.len());
+        self.messages.truncate(self.messages.len() - removed);
+        self.memory_boundary = self.memory_boundary.min(self.messages.len());
+        removed
+    }
+
+    /// Messages memory extraction has not seen yet.
+    pub fn unseen(&self) -> impl Iterator<Item = &Message> {
+        self.messages.iter().skip(self.memory_boundary)
+    }
+
+    fn from_loaded(messages: Vec<Message>) -> Self {
+        let skip = messages.len().saturating_sub(MAX_BUFFERED_MESSAGES);
+        let messages: VecDeque<Message> = messages.into_iter().skip(skip).collect();
+        Self {
+            memory_boundary: messages.len(),
+            messages,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ConversationHistories {
+    /// Least recently used first.
+    buffers: VecDeque<(HistoryKey, HistoryBuffer)>,
+}
+
+impl ConversationHistories {
+    pub fn contains(&self, key: &HistoryKey) -> bool {
+        self.position(key).is_some()
+    }
+
+    pub fn get(&self, key: &HistoryKey) -> Option<&HistoryBuffer> {
+        self.position(key).map(|index| &self.buffers[index].1)
+    }
+
+    /// The buffer of `key`, created empty if missing, marked most recently
+    /// used. Conversation buffers that were evicted must be
+    /// [`restore`](Self::restore)d first or they start over empty.
+    pub fn entry(&mut self, key: &HistoryKey) -> &mut HistoryBuffer {
+        let entry = self
+            .position(key)
+            .and_then(|index| self.buffers.remove(index))
+            .unwrap_or_else(|| (key.clone(), HistoryBuffer::default()));
+        self.buffers.push_back(entry);
+        self.evict();
+        &mut self.buffers.back_mut().expect("just pushed").1
+    }
+
+    /// Replace the buffer of `key` with messages read from the DB. They were
+    /// already seen by memory extraction, so the boundary starts after them.
+    pub fn restore(&mut self, key: &HistoryKey, messages: Vec<Message>) {
+        *self.entry(key) = HistoryBuffer::from_loaded(messages);
+    }
+
+    /// The draft of `surface` now belongs to the conversation `id` that was
+    /// just created for it.
+    pub fn adopt(&mut self, surface: ChatSurface, id: &str) {
+        let target = HistoryKey::Conversation(id.to_string());
+        if self.contains(&target) {
+            return;
+        }
+        let draft = self
+            .position(&HistoryKey::Draft(surface))
+            .and_then(|index| self.buffers.remove(index))
+            .map(|(_, buffer)| buffer)
+            .unwrap_or_default();
+        self.buffers.push_back((target, draft));
+        self.evict();
+    }
+
+    pub fn discard(&mut self, key: &HistoryKey) {
+        self.buffers.retain(|(existing, _)| existing != key);
+    }
+
+    /// Throw away every draft buffer.
+    pub fn discard_drafts(&mut self) {
+        self.buffers
+            .retain(|(key, _)| !matches!(key, HistoryKey::Draft(_)));
+    }
+
+    /// Drop cached buffers of conversations whose messages changed or went
+    /// away; they are reloaded from the DB when used again.
+    pub fn forget<'a>(&mut self, ids: impl IntoIterator<Item = &'a str>) {
+        for id in ids {
+            self.discard(&HistoryKey::Conversation(id.to_string()));
+        }
+    }
+
+    /// Hide everything buffered so far from memory extraction.
+    pub fn mark_all_seen(&mut self) {
+        for (_, buffer) in &mut self.buffers {
+            buffer.memory_boundary = buffer.messages.len();
+        }
+    }
+
+    fn position(&self, key: &HistoryKey) -> Option<usize> {
+        self.buffers
+            .iter()
+            .position(|(existing, _)| existing == key)
+    }
+
+    /// Drafts are never evicted: they have no copy in the DB. Neither are
+    /// buffers with turns memory extraction has not seen yet, since a reload
+    /// would mark them seen; the LRU grows past its capacity until they are.
+    fn evict(&mut self) {
+        let conversations = |buffers: &VecDeque<(HistoryKey, HistoryBuffer)>| {
+            buffers
+                .iter()
+                .filter(|(key, _)| matches!(key, HistoryKey::Conversation(_)))
+                .count()
+        };
+        while conversations(&self.buffers) > CONVERSATION_CAPACITY {
+            // The newest buffer is the one just used; it always stays.
+            let newest = self.buffers.len() - 1;
+            let Some(oldest) = self.buffers.iter().take(newest).position(|(key, buffer)| {
+                matches!(key, HistoryKey::Conversation(_)) && buffer.unseen().next().is_none()
+            }) else {
+                break;
+            };
+            self.buffers.remove(oldest);
+        }
+    }
+}
+
+/// Active-branch messages of a conversation, oldest first.
+pub async fn load_conversation_messages(
+    db: &SqlitePool,
+    conversation_id: &str,
+) -> Result<Vec<Message>, sqlx::Error> {
+    let rows = sqlx::query_as::<_, (String, String, Option<String>)>(
+        "SELECT role, content, metadata FROM conversation_messages WHERE conversation_id = ? AND is_active = 1 ORDER BY id ASC",
+    )
+    .bind(conversation_id)
+    .fetch_all(db)
+    .await?;
+    Ok(rows
+        .into_iter()
+        .map(|(role, content, metadata)| Message {
+            role,
+            content,
+            metadata: metadata
+                .as_deref()
+                .and_then(|raw| serde_json::from_str::<serde_json::Value>(raw).ok()),
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(content: &str) -> Message {
+        Message {
+            role: "user".to_string(),
+            content: content.to_string(),
+            metadata: None,
+        }
+    }
+
+    fn contents(histories: &ConversationHistories, key: &HistoryKey) -> Vec<String> {
+        histories
+            .get(key)
+            .map(|buffer| buffer.messages.iter().map(|m| m.content.clone()).collect())
+            .unwrap_or_default()
+    }
+
+    fn conversation(id: &str) -> HistoryKey {
+        HistoryKey::Conversation(id.to_string())
+    }
+
+    #[test]
+    fn drafts_are_adopted_and_restored_buffers_start_seen() {
+        let mut histories = ConversationHistories::default();
+        let draft = HistoryKey::new(ChatSurface::Desktop, None);
+        histories.entry(&draft).push(message("draft"));
+        histories.adopt(ChatSurface::Desktop, "a");
+        assert!(!histories.contains(&draft));
+        assert_eq!(contents(&histories, &conversation("a")), ["draft"]);
+
+        histories.restore(
+            &conversation("b"),
+            (0..30).map(|i| message(&i.to_string())).collect(),
+        );
+        let b = histories.get(&conversation("b")).unwrap();
+        assert_eq!(b.messages.len(), MAX_BUFFERED_MESSAGES);
+        assert_eq!(b.messages[0].content, "10");
+        assert_eq!(b.unseen().count(), 0);
+
+        histories.forget(["b"]);
+        assert!(!histories.contains(&conversation("b")));
+        assert!(histories.contains(&conversation("a")));
+    }
+
+    #[test]
+    fn interleaved_conversations_keep_their_own_turns() {
+        let mut histories = ConversationHistories::default();
+        let telegram = ChatSurface::Telegram(42);
+        histories.restore(&conversation("desktop"), vec![message("d0")]);
+
+        // Telegram's /new conversation talks while the desktop does too.
+        histories
+            .entry(&HistoryKey::Draft(telegram))
+            .push(message("t1"));
+        histories
+            .entry(&conversation("desktop"))
+            .push(message("d1"));
+        histories.adopt(telegram, "tg");
+        histories.entry(&conversation("tg")).push(message("t2"));
+        histories
+            .entry(&conversation("desktop"))
+            .push(message("d2"));
+
+        assert_eq!(
+            contents(&histories, &conversation("desktop")),
+            ["d0", "d1", "d2"]
+        );
+        assert_eq!(contents(&histories, &conversation("tg")), ["t1", "t2"]);
+        assert!(!histories.contains(&HistoryKey::Draft(ChatSurface::Desktop)));
+
+        // Only the turns after the reload are new to memory extraction.
+        let desktop = histories.get(&conversation("desktop")).unwrap();
+        assert_eq!(desktop.unseen().count(), 2);
+        assert_eq!(histories.entry(&conversation("tg")).truncate_last(1), 1);
+        assert_eq!(contents(&histories, &conversation("tg")), ["t1"]);
+    }
+
+    #[test]
+    fn least_recently_used_conversations_are_evicted_but_drafts_stay() {
+        let mut histories = ConversationHistories::default();
+        histories
+            .entry(&HistoryKey::Draft(ChatSurface::Desktop))
+            .push(message("draft"));
+        for i in 0..=CONVERSATION_CAPACITY {
+            histories
+                .entry(&conversation(&i.to_string()))
+                .push(message(&i.to_string()));
+            histories.mark_all_seen();
+        }
+        // "0" was used first and pushed out; touching "1" saves it from "2"'s fate.
+        assert!(!histories.contains(&conversation("0")));
+        histories.entry(&conversation("1"));
+        histories.entry(&conversation("extra"));
+        assert!(histories.contains(&conversation("1")));
+        assert!(!histories.contains(&conversation("2")));
+        assert_eq!(
+            contents(&histories, &HistoryKey::Draft(ChatSurface::Desktop)),
+            ["draft"]
+        );
+    }
+
+    #[test]
+    fn conversations_with_unseen_turns_are_not_evicted() {
+        let mut histories = ConversationHistories::default();
+        histories
+            .entry(&conversation("busy"))
+            .push(message("unread"));
+        for i in 0..CONVERSATION_CAPACITY {
+            histories.restore(&conversation(&i.to_string()), vec![message("old")]);
+        }
+        // "busy" is the oldest, but extraction has not read it: "0" goes instead.
+        assert!(histories.contains(&conversation("busy")));
+        assert!(!histories.contains(&conversation("0")));
+
+        // With nothing evictable the LRU grows instead of losing turns.
+        for i in 0..CONVERSATION_CAPACITY {
+            histories
+                .entry(&conversation(&i.to_string()))
+                .push(message("new"));
+        }
+        histories.entry(&conversation("more")).push(message("new"));
+        assert_eq!(histories.buffers.len(), CONVERSATION_CAPACITY + 2);
+
+        // Once extraction caught up, the next use trims back to capacity.
+        histories.mark_all_seen();
+        histories.entry(&conversation("busy"));
+        assert_eq!(histories.buffers.len(), CONVERSATION_CAPACITY);
+        assert!(histories.contains(&conversation("busy")));
+    }
+}
@@ -0,0 +1,392 @@
+//! Per-window queue for messages sent while a reply is still streaming.
+//!
+//! Every `stream_chat` call takes a ticket and waits for the turn ahead of it,
+//! so messages never race each other. In [`InputQueueMode::Restart`] a new user
+//! message also cancels the running turn and absorbs messages still waiting:
+//! those are saved to history but not answered on their own, so the reply that
+//! finally runs sees all of them together.
+
+use crate::error::KokoroError;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
+use tauri::{AppHandle, Emitter};
+
+pub const CHAT_QUEUE_EVENT: &str = "chat-queue";
+
+const PREVIEW_CHARS: usize = 80;
+const MAX_PENDING_LIMIT: usize = 64;
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum InputQueueMode {
+    /// Answer queued messages one after another.
+    #[default]
+    Sequential,
+    /// Cancel the running reply and answer everything sent so far in one go.
+    Restart,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct InputQueueConfig {
+    #[serde(default)]
+    pub mode: InputQueueMode,
+    /// Messages allowed to wait per window; further sends are rejected.
+    #[serde(default = "default_max_pending")]
+    pub max_pending: usize,
+}
+
+fn default_max_pending() -> usize {
+    8
+}
+
+impl Default for InputQueueConfig {
+    fn default() -> Self {
+        Self {
+            mode: InputQueueMode::default(),
+            max_pending: default_max_pending(),
+        }
+    }
+}
+
+impl InputQueueConfig {
+    pub fn sanitized(mut self) -> Self {
+        self.max_pending = self.max_pending.clamp(1, MAX_PENDING_LIMIT);
+        self
+    }
+}
+
+pub fn config_path() -> PathBuf {
+    dirs_next::data_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("com.chyin.kokoro")
+        .join("input_queue.json")
+}
+
+pub fn load_config(path: &Path) -> InputQueueConfig {
+    crate::config::load_json_config::<InputQueueConfig>(path, "INPUT_QUEUE").sanitized()
+}
+
+pub fn save_config(path: &Path, config: &InputQueueConfig) -> Result<(), KokoroError> {
+    crate::config::save_json_config(path, config, "INPUT_QUEUE")
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct QueuedInput {
+    pub id: String,
+    /// First characters of the message, for the queue indicator.
+    pub preview: String,
+    pub hidden: bool,
+    pub queued_at: i64,
+}
+
+impl QueuedInput {
+    fn new(message: &str, hidden: bool) -> Self {
+        let trimmed = message.trim();
+        let mut preview: String = trimmed.chars().take(PREVIEW_CHARS).collect();
+        if trimmed.chars().count() > PREVIEW_CHARS {
+            preview.push('…');
+        }
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            preview,
+            hidden,
+            queued_at: chrono::Utc::now().timestamp_millis(),
+        }
+    }
+}
+
+/// Payload of `chat-queue`.
+#[derive(Debug, Clone, Serialize)]
+pub struct ChatQueueEvent {
+    pub window_label: String,
+    pub mode: InputQueueMode,
+    pub running: Option<QueuedInput>,
+    pub pending: Vec<QueuedInput>,
+}
+
+#[derive(Debug, Default)]
+struct LaneState {
+    running: Option<QueuedInput>,
+    pending: VecDeque<QueuedInput>,
+    /// Pending inputs absorbed by a newer message (restart mode).
+    merged: HashSet<String>,
+}
+
+impl LaneState {
+    /// Queue `item`; returns whether it should cancel the running turn.
+    fn push(
+        &mut self,
+        item: QueuedInput,
+        restart: bool,
+        max_pending: usize,
+    ) -> Result<bool, KokoroError> {
+        if self.pending.len() >= max_pending {
+            return Err(KokoroError::Validation(format!(
+                "Too many queued messages (limit {})",
+                max_pending
+            )));
+        }
+        let restart = restart && !item.hidden;
+        if restart {
+            let absorbed = self
+                .pending
+                .iter()
+                .filter(|p| !p.hidden)
+                .map(|p| p.id.clone());
+            self.merged.extend(absorbed);
+        }
+        self.pending.push_back(item);
+        Ok(restart && self.running.is_some())
+    }
+
+    /// Move `id` from pending to running; returns whether it was absorbed.
+    fn start(&mut self, id: &str) -> bool {
+        let Some(index) = self.pending.iter().position(|p| p.id == id) else {
+            return false;
+        };
+        self.running = self.pending.remove(index);
+        self.merged.remove(id)
+    }
+
+    fn finish(&mut self) {
+        self.running = None;
+    }
+}
+
+#[derive(Default)]
+struct Lane {
+    /// Tokio's mutex hands out the lock in FIFO order, which is the queue order.
+    gate: Arc<tokio::sync::Mutex<()>>,
+    state: Mutex<LaneState>,
+}
+
+impl Lane {
+    fn emit(&self, app: &AppHandle, window_label: &str, mode: InputQueueMode) {
+        let event = {
+            let Ok(state) = self.state.lock() else {
+                return;
+            };
+            ChatQueueEvent {
+                window_label: window_label.to_string(),
+                mode,
+                running: state.running.clone(),
+                pending: state.pending.iter().cloned().collect(),
+            }
+        };
+        let _ = app.emit(CHAT_QUEUE_EVENT, &event);
+    }
+}
+
+/// Input queues for every chat window, owned by the orchestrator.
+#[derive(Default)]
+pub struct InputQueue {
+    config: RwLock<InputQueueConfig>,
+    lanes: Mutex<HashMap<String, Arc<Lane>>>,
+}
+
+impl InputQueue {
+    pub fn config(&self) -> InputQueueConfig {
+        self.config
+            .read()
+            .map(|config| config.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn set_config(&self, config: InputQueueConfig) {
+        if let Ok(mut current) = self.config.write() {
+            *current = config;
+        }
+    }
+
+    pub fn snapshot(&self, window_label: &str) -> ChatQueueEvent {
+        let lane = self
+            .lanes
+            .lock()
+            .ok()
+            .and_then(|lanes| lanes.get(window_label).cloned());
+        let (running, pending) = lane
+            .and_then(|lane| {
+                let state = lane.state.lock().ok()?;
+                Some((
+                    state.running.clone(),
+                    state.pending.iter().cloned().collect(),
+                ))
+            })
+            .unwrap_or_default();
+        ChatQueueEvent {
+            window_label: window_label.to_string(),
+            mode: self.config().mode,
+            running,
+            pending,
+        }
+    }
+
+    /// Join the queue of `window_label`.
+    pub fn enqueue(
+        &self,
+        app: &AppHandle,
+        window_label: &str,
+        message: &str,
+        hidden: bool,
+    ) -> Result<InputTicket, KokoroError> {
+        let config = self.config();
+        let lane = self
+            .lanes
+            .lock()
+            .map_err(|_| KokoroError::Internal("input queue poisoned".to_string()))?
+            .entry(window_label.to_string())
+            .or_default()
+            .clone();
+        let item = QueuedInput::new(message, hidden);
+        let supersedes_running = lane
+            .state
+            .lock()
+            .map_err(|_| KokoroError::Internal("input queue poisoned".to_string()))?
+            .push(
+                item.clone(),
+                config.mode == InputQueueMode::Restart,
+                config.max_pending,
+            )?;
+        lane.emit(app, window_label, config.mode);
+        Ok(InputTicket {
+            lane,
+            app: app.clone(),
+            window_label: window_label.to_string(),
+            mode: config.mode,
+            id: item.id,
+            supersedes_running,
+        })
+    }
+}
+
+/// A place in a window's queue.
+pub struct InputTicket {
+    lane: Arc<Lane>,
+    app: AppHandle,
+    window_label: String,
+    mode: InputQueueMode,
+    id: String,
+    supersedes_running: bool,
+}
+
+impl InputTicket {
+    /// The caller should cancel the turn running in this window.
+    pub fn supersedes_running(&self) -> bool {
+        self.supersedes_running
+    }
+
+    /// Wait until every turn queued before this one has finished.
+    pub async fn wait_turn(self) -> InputSlot {
+        let permit = self.lane.gate.clone().lock_owned().await;
+        let merged = self
+            .lane
+            .state
+            .lock()
+            .map(|mut state| state.start(&self.id))
+            .unwrap_or(false);
+        self.lane.emit(&self.app, &self.window_label, self.mode);
+        InputSlot {
+            _permit: permit,
+            lane: self.lane,
+            app: self.app,
+            window_label: self.window_label,
+            mode: self.mode,
+            merged,
+        }
+    }
+}
+
+/// The running turn of a window; the next one starts when this is dropped.
+pub struct InputSlot {
+    _permit: tokio::sync::OwnedMutexGuard<()>,
+    lane: Arc<Lane>,
+    app: AppHandle,
+    window_label: String,
+    mode: InputQueueMode,
+    merged: bool,
+}
+
+impl InputSlot {
+    /// A newer message absorbed this one; save it but don't answer it alone.
+    pub fn merged(&self) -> bool {
+        self.merged
+    }
+}
+
+impl Drop for InputSlot {
+    fn drop(&mut self) {
+        if let Ok(mut state) = self.lane.state.lock() {
+            state.finish();
+        }
+        self.lane.emit(&self.app, &self.window_label, self.mode);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input(message: &str, hidden: bool) -> QueuedInput {
+        QueuedInput::new(message, hidden)
+    }
+
+    #[test]
+    fn sequential_lane_keeps_every_message() {
+        let mut lane = LaneState::default();
+        let first = input("hello", false);
+        let second = input("are you there?", false);
+        assert!(!lane.push(first.clone(), false, 8).unwrap());
+        assert!(!lane.start(&first.id));
+        assert!(!lane.push(second.clone(), false, 8).unwrap());
+        lane.finish();
+        assert!(!lane.start(&second.id));
+        assert_eq!(lane.running.as_ref().map(|r| &r.id), Some(&second.id));
+        assert!(lane.pending.is_empty());
+    }
+
+    #[test]
+    fn restart_lane_supersedes_running_and_absorbs_waiting() {
+        let mut lane = LaneState::default();
+        let running = input("tell me a story", false);
+        let proactive = input("[proactive]", true);
+        let second = input("actually", false);
+        let third = input("make it short", false);
+        lane.push(running.clone(), true, 8).unwrap();
+        lane.start(&running.id);
+
+        assert!(!lane.push(proactive.clone(), true, 8).unwrap());
+        assert!(lane.push(second.clone(), true, 8).unwrap());
+        assert!(lane.push(third.clone(), true, 8).unwrap());
+
+        lane.finish();
+        assert!(!lane.start(&proactive.id));
+        lane.finish();
+        assert!(lane.start(&second.id));
+        lane.finish();
+        assert!(!lane.start(&third.id));
+        assert!(lane.merged.is_empty());
+    }
+
+    #[test]
+    fn full_lane_rejects_and_previews_are_trimmed() {
+        let mut lane = LaneState::default();
+        lane.push(input("a", false), false, 1).unwrap();
+        assert!(lane.push(input("b", false), false, 1).is_err());
+
+        let long = "x".repeat(PREVIEW_CHARS + 5);
+        let preview = input(&format!("  {}  ", long), false).preview;
+        assert_eq!(preview.chars().count(), PREVIEW_CHARS + 1);
+        assert!(preview.ends_with('…'));
+        assert_eq!(
+            InputQueueConfig {
+                max_pending: 0,
+                ..Default::default()
+            }
+            .sanitized()
+            .max_pending,
+            1
+        );
+    }
+}
@@ -0,0 +1,220 @@
+//! Lightweight local language identification for user messages.
+//!
+//! Used when no response language is configured: the reply language follows
+//! the user's. Scripts settle most cases (a Chinese message never contains
+//! kana or Hangul); Latin-script languages are told apart by common function
+//! words. Anything too short or ambiguous returns `None` rather than a guess.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DetectedLanguage {
+    /// ISO 639-1 code, e.g. "zh".
+    pub code: &'static str,
+    /// English name, used in prompt directives.
+    pub name: &'static str,
+}
+
+const fn lang(code: &'static str, name: &'static str) -> DetectedLanguage {
+    DetectedLanguage { code, name }
+}
+
+/// Common function words per Latin-script language; the language with the
+/// most hits wins, and a tie is treated as unknown.
+const LATIN_STOPWORDS: &[(DetectedLanguage, &[&str])] = &[
+    (
+        lang("en", "English"),
+        &[
+            "the", "and", "is", "are", "you", "i", "what", "how", "with", "this", "that", "it",
+            "my", "me", "do", "can", "have", "was", "of", "to", "for", "not", "your", "hi",
+            "hello", "thanks", "please", "why", "where", "when",
+        ],
+    ),
+    (
+        lang("es", "Spanish"),
+        &[
+            "el", "los", "las", "es", "y", "que", "por", "para", "una", "con", "como", "pero",
+            "hola", "gracias", "qué", "cómo", "estás", "muy", "yo", "tú", "está",
+        ],
+    ),
+    (
+        lang("fr", "French"),
+        &[
+            "le", "les", "et", "est", "je", "tu", "vous", "nous", "une", "des", "du", "pas",
+            "avec", "pour", "mais", "bonjour", "merci", "c'est", "suis", "oui", "très",
+        ],
+    ),
+    (
+        lang("de", "German"),
+        &[
+            "der", "die", "das", "und", "ist", "ich", "du", "nicht", "ein", "eine", "mit", "auf",
+            "wie", "was", "hallo", "danke", "bitte", "sehr", "bin", "auch", "für",
+        ],
+    ),
+    (
+        lang("pt", "Portuguese"),
+        &[
+            "o", "os", "as", "é", "não", "um", "uma", "com", "você", "obrigado", "obrigada", "olá",
+            "muito", "estou", "isso", "eu", "mas", "também", "tudo",
+        ],
+    ),
+    (
+        lang("it", "Italian"),
+        &[
+            "il", "lo", "gli", "è", "e", "che", "non", "sono", "ciao", "grazie", "perché", "molto",
+            "anche", "io", "come", "questo", "della", "sei",
+        ],
+    ),
+];
+
+#[derive(Default)]
+struct ScriptCounts {
+    han: usize,
+    kana: usize,
+    hangul: usize,
+    cyrillic: usize,
+    ukrainian: usize,
+    arabic: usize,
+    hebrew: usize,
+    greek: usize,
+    thai: usize,
+    devanagari: usize,
+    latin: usize,
+}
+
+fn count_scripts(text: &str) -> ScriptCounts {
+    let mut counts = ScriptCounts::default();
+    for ch in text.chars() {
+        match ch {
+            '\u{4E00}'..='\u{9FFF}' | '\u{3400}'..='\u{4DBF}' => counts.han += 1,
+            '\u{3040}'..='\u{30FF}' => counts.kana += 1,
+            '\u{AC00}'..='\u{D7AF}' | '\u{1100}'..='\u{11FF}' => counts.hangul += 1,
+            'і' | 'ї' | 'є' | 'ґ' | 'І' | 'Ї' | 'Є' | 'Ґ' => {
+                counts.cyrillic += 1;
+                counts.ukrainian += 1;
+            }
+            '\u{0400}'..='\u{04FF}' => counts.cyrillic += 1,
+            '\u{0600}'..='\u{06FF}' => counts.arabic += 1,
+            '\u{0590}'..='\u{05FF}' => counts.hebrew += 1,
+            '\u{0370}'..='\u{03FF}' => counts.greek += 1,
+            '\u{0E00}'..='\u{0E7F}' => counts.thai += 1,
+            '\u{0900}'..='\u{097F}' => counts.devanagari += 1,
+            c if c.is_alphabetic() && (c.is_ascii() || ('\u{00C0}'..='\u{024F}').contains(&c)) => {
+                counts.latin += 1
+            }
+            _ => {}
+        }
+    }
+    counts
+}
+
+/// Text with URLs, mentions and inline code removed, so a Chinese message that
+/// pastes a link or a function name still reads as Chinese.
+fn prose(text: &str) -> String {
+    let mut in_code = false;
+    text.split('`')
+        .filter(|_| {
+            let keep = !in_code;
+            in_code = !in_code;
+            keep
+        })
+        .flat_map(str::split_whitespace)
+        .filter(|word| !word.contains("://") && !word.starts_with('@') && !word.starts_with('#'))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn detect_latin(text: &str) -> Option<DetectedLanguage> {
+    let lower = text.to_lowercase();
+    let words: Vec<&str> = lower
+        .split(|c: char| !(c.is_alphabetic() || c == '\''))
+        .filter(|w| !w.is_empty())
+        .collect();
+    let mut best: Option<(DetectedLanguage, usize)> = None;
+    let mut tied = false;
+    for (language, stopwords) in LATIN_STOPWORDS {
+        let hits = words.iter().filter(|w| stopwords.contains(w)).count();
+        if hits == 0 {
+            continue;
+        }
+        match best {
+            Some((_, top)) if hits < top => {}
+            Some((_, top)) if hits == top => tied = true,
+            _ => {
+                best = Some((*language, hits));
+                tied = false;
+            }
+        }
+    }
+    best.filter(|_| !tied).map(|(language, _)| language)
+}
+
+/// Identify the language a message is written in.
+pub fn detect_language(text: &str) -> Option<DetectedLanguage> {
+    let text = prose(text);
+    let counts = count_scripts(&text);
+
+    // Kana only occurs in Japanese; a few among kanji settle it.
+    if counts.kana >= 2 || (counts.kana >= 1 && counts.kana * 4 >= counts.han) {
+        return Some(lang("ja", "Japanese"));
+    }
+
+    // One CJK character carries about as much as a short Latin word.
+    let scripts = [
+        (counts.han * 3, lang("zh", "Chinese")),
+        (counts.hangul * 3, lang("ko", "Korean")),
+        (
+            counts.cyrillic,
+            if counts.ukrainian > 0 {
+                lang("uk", "Ukrainian")
+            } else {
+                lang("ru", "Russian")
+            },
+        ),
+        (counts.arabic, lang("ar", "Arabic")),
+        (counts.hebrew, lang("he", "Hebrew")),
+        (counts.greek, lang("el", "Greek")),
+        (counts.thai, lang("th", "Thai")),
+        (counts.devanagari, lang("hi", "Hindi")),
+    ];
+    let (weight, language) = scripts.into_iter().max_by_key(|(weight, _)| *weight)?;
+    if weight >= 4 && weight >= counts.latin {
+        return Some(language);
+    }
+    if counts.latin >= 2 {
+        return detect_latin(&text);
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn code(text: &str) -> Option<&'static str> {
+        detect_language(text).map(|l| l.code)
+    }
+
+    #[test]
+    fn detects_by_script_and_function_words() {
+        assert_eq!(code("你好，今天天气怎么样？"), Some("zh"));
+        assert_eq!(code("帮我看看这个 Python function 为什么报错"), Some("zh"));
+        assert_eq!(
+            code("看一下 `fn parse_config(path: &Path)` 和 https://example.com/docs"),
+            Some("zh")
+        );
+        assert_eq!(code("今日は何をしていますか"), Some("ja"));
+        assert_eq!(code("東京に行きたい"), Some("ja"));
+        assert_eq!(code("안녕하세요, 잘 지내요?"), Some("ko"));
+        assert_eq!(code("Привет, как дела?"), Some("ru"));
+        assert_eq!(code("Привіт, як справи? Що нового?"), Some("uk"));
+        assert_eq!(code("What are you doing today?"), Some("en"));
+        assert_eq!(code("Hola, ¿cómo estás? Muy bien, gracias"), Some("es"));
+        assert_eq!(code("Bonjour, je suis très content"), Some("fr"));
+        assert_eq!(code("Ich bin heute sehr müde"), Some("de"));
+
+        // Too short or no evidence either way.
+        assert_eq!(code("ok"), None);
+        assert_eq!(code("Kokoro"), None);
+        assert_eq!(code("😂😂"), None);
+        assert_eq!(code("好"), None);
+    }
+}
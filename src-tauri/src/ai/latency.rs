@@ -0,0 +1,244 @@
+//! First-token latency budget for `stream_chat`.
+//!
+//! When the provider stays silent past the budget, `chat-turn-waiting` tells
+//! the frontend the reply is delayed and, optionally, carries a short filler
+//! line ("Hmm, let me think…") for the character to say while waiting. The
+//! filler is never added to the reply or to history.
+
+use crate::error::KokoroError;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+pub const CHAT_TURN_WAITING_EVENT: &str = "chat-turn-waiting";
+
+const MIN_BUDGET_SECS: f32 = 1.0;
+const MAX_BUDGET_SECS: f32 = 60.0;
+
+const FILLERS_EN: &[&str] = &[
+    "Hmm, let me think…",
+    "Give me a second…",
+    "Let me see…",
+    "Oh, good question…",
+];
+const FILLERS_ZH: &[&str] = &["嗯……让我想想。", "稍等一下哦……", "我想想看……"];
+const FILLERS_JA: &[&str] = &[
+    "うーん、ちょっと考えるね…",
+    "ちょっと待ってね…",
+    "えっとね…",
+];
+const FILLERS_KO: &[&str] = &["음, 잠깐 생각해 볼게…", "잠시만…", "어디 보자…"];
+const FILLERS_RU: &[&str] = &["Хм, дай подумать…", "Секундочку…", "Так, посмотрим…"];
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LatencyBudgetConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Seconds without a visible delta before the turn counts as delayed.
+    #[serde(default = "default_first_token_secs")]
+    pub first_token_secs: f32,
+    /// Let the character say a filler line while waiting.
+    #[serde(default)]
+    pub filler_enabled: bool,
+    /// Custom filler lines; empty uses built-ins in the response language.
+    #[serde(default)]
+    pub fillers: Vec<String>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_first_token_secs() -> f32 {
+    4.0
+}
+
+impl Default for LatencyBudgetConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            first_token_secs: default_first_token_secs(),
+            filler_enabled: false,
+            fillers: Vec::new(),
+        }
+    }
+}
+
+impl LatencyBudgetConfig {
+    pub fn sanitized(mut self) -> Self {
+        self.first_token_secs = if self.first_token_secs.is_finite() {
+            self.first_token_secs
+                .clamp(MIN_BUDGET_SECS, MAX_BUDGET_SECS)
+        } else {
+            default_first_token_secs()
+        };
+        self.fillers = self
+            .fillers
+            .into_iter()
+            .map(|line| line.trim().to_string())
+            .filter(|line| !line.is_empty())
+            .collect();
+        self
+    }
+
+    /// Filler for this turn, rotating by `seed` so consecutive turns vary.
+    pub fn pick_filler(&self, response_language: &str, seed: usize) -> Option<String> {
+        if !self.filler_enabled {
+            return None;
+        }
+        if !self.fillers.is_empty() {
+            return Some(self.fillers[seed % self.fillers.len()].clone());
+        }
+        let builtin = builtin_fillers(response_language);
+        Some(builtin[seed % builtin.len()].to_string())
+    }
+}
+
+/// Response languages are free-form names ("Japanese", "中文", "ko-KR"…).
+fn builtin_fillers(response_language: &str) -> &'static [&'static str] {
+    let lang = response_language.trim().to_lowercase();
+    let code = lang.split(['-', '_']).next().unwrap_or_default();
+    let is = |iso: &str, names: &[&str]| code == iso || names.iter().any(|n| lang.contains(n));
+    if is("zh", &["chinese", "中文", "汉语", "漢語", "粵"]) {
+        FILLERS_ZH
+    } else if is("ja", &["japanese", "日本"]) {
+        FILLERS_JA
+    } else if is("ko", &["korean", "한국"]) {
+        FILLERS_KO
+    } else if is("ru", &["russian", "рус"]) {
+        FILLERS_RU
+    } else {
+        FILLERS_EN
+    }
+}
+
+pub fn config_path() -> PathBuf {
+    dirs_next::data_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("com.chyin.kokoro")
+        .join("latency_budget.json")
+}
+
+pub fn load_config(path: &Path) -> LatencyBudgetConfig {
+    crate::config::load_json_config::<LatencyBudgetConfig>(path, "LATENCY").sanitized()
+}
+
+pub fn save_config(path: &Path, config: &LatencyBudgetConfig) -> Result<(), KokoroError> {
+    crate::config::save_json_config(path, config, "LATENCY")
+}
+
+/// Payload of `chat-turn-waiting`.
+#[derive(Debug, Clone, Serialize)]
+pub struct ChatTurnWaitingEvent {
+    pub turn_id: String,
+    pub waited_ms: u64,
+    pub provider_id: String,
+    pub filler: Option<String>,
+}
+
+/// Fires `chat-turn-waiting` once the budget runs out. Drop it (or call
+/// [`FirstTokenWatch::stop`]) when the first visible delta goes out.
+pub struct FirstTokenWatch {
+    handle: Option<tauri::async_runtime::JoinHandle<()>>,
+}
+
+impl FirstTokenWatch {
+    pub fn start(
+        app: &AppHandle,
+        config: &LatencyBudgetConfig,
+        turn_id: &str,
+        provider_id: &str,
+        response_language: &str,
+    ) -> Self {
+        if !config.enabled {
+            return Self { handle: None };
+        }
+        let budget = Duration::from_secs_f32(config.first_token_secs);
+        let seed = turn_id.bytes().map(usize::from).sum();
+        let event = ChatTurnWaitingEvent {
+            turn_id: turn_id.to_string(),
+            waited_ms: budget.as_millis() as u64,
+            provider_id: provider_id.to_string(),
+            filler: config.pick_filler(response_language, seed),
+        };
+        let app = app.clone();
+        let handle = tauri::async_runtime::spawn(async move {
+            tokio::time::sleep(budget).await;
+            tracing::info!(
+                target: "chat",
+                "[Chat] No output from '{}' after {} ms (turn {})",
+                event.provider_id,
+                event.waited_ms,
+                event.turn_id
+            );
+            let _ = app.emit(CHAT_TURN_WAITING_EVENT, &event);
+        });
+        Self {
+            handle: Some(handle),
+        }
+    }
+
+    pub fn stop(&mut self) {
+        if let Some(handle) = self.handle.take() {
+            handle.abort();
+        }
+    }
+}
+
+impl Drop for FirstTokenWatch {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sanitizes_budget_and_fillers() {
+        let config = LatencyBudgetConfig {
+            first_token_secs: 0.1,
+            fillers: vec!["  ".to_string(), " Um… ".to_string()],
+            ..Default::default()
+        }
+        .sanitized();
+        assert_eq!(config.first_token_secs, MIN_BUDGET_SECS);
+        assert_eq!(config.fillers, vec!["Um…".to_string()]);
+
+        let config = LatencyBudgetConfig {
+            first_token_secs: f32::NAN,
+            ..Default::default()
+        }
+        .sanitized();
+        assert_eq!(config.first_token_secs, default_first_token_secs());
+    }
+
+    #[test]
+    fn fillers_follow_language_and_custom_lines() {
+        let mut config = LatencyBudgetConfig::default();
+        assert_eq!(config.pick_filler("English", 0), None);
+
+        config.filler_enabled = true;
+        assert_eq!(
+            config.pick_filler("Japanese", 0).as_deref(),
+            Some(FILLERS_JA[0])
+        );
+        assert_eq!(
+            config.pick_filler("简体中文", 1).as_deref(),
+            Some(FILLERS_ZH[1])
+        );
+        assert_eq!(
+            config.pick_filler("ko-KR", 0).as_deref(),
+            Some(FILLERS_KO[0])
+        );
+        assert_eq!(config.pick_filler("", 5).as_deref(), Some(FILLERS_EN[1]));
+
+        config.fillers = vec!["Wait for it…".to_string()];
+        assert_eq!(
+            config.pick_filler("Japanese", 3).as_deref(),
+            Some("Wait for it…")
+        );
+    }
+}
@@ -0,0 +1,395 @@
+//! Lorebook / world info — keyword-triggered context injection.
+//!
+//! Entries live in `characters.lorebook_json` using the SillyTavern
+//! `character_book` layout, so imported cards work as-is and exports round-trip.
+//! `compose_prompt` scans the most recent messages for entry keys and injects
+//! matching entries (plus anything they trigger recursively) within a token budget.
+
+use crate::error::KokoroError;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sqlx::SqlitePool;
+use std::collections::HashSet;
+
+const DEFAULT_SCAN_DEPTH: usize = 4;
+const DEFAULT_TOKEN_BUDGET: usize = 512;
+const DEFAULT_RECURSION_DEPTH: usize = 2;
+const DEFAULT_INSERTION_ORDER: i64 = 100;
+/// Same conservative estimate as the history trimmer in `compose_prompt`.
+const CHARS_PER_TOKEN: usize = 2;
+
+fn default_scan_depth() -> usize {
+    DEFAULT_SCAN_DEPTH
+}
+
+fn default_token_budget() -> usize {
+    DEFAULT_TOKEN_BUDGET
+}
+
+fn default_insertion_order() -> i64 {
+    DEFAULT_INSERTION_ORDER
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LorebookEntry {
+    #[serde(default)]
+    pub id: i64,
+    #[serde(default)]
+    pub keys: Vec<String>,
+    /// With `selective`, at least one of these must also match.
+    #[serde(default)]
+    pub secondary_keys: Vec<String>,
+    #[serde(default)]
+    pub selective: bool,
+    #[serde(default)]
+    pub content: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Always injected, regardless of keys.
+    #[serde(default)]
+    pub constant: bool,
+    #[serde(default)]
+    pub case_sensitive: Option<bool>,
+    /// Lower values are injected first and win when the budget runs out.
+    #[serde(default = "default_insertion_order")]
+    pub insertion_order: i64,
+    /// Card fields we don't interpret (comment, position, extensions, ...).
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Lorebook {
+    /// Number of recent messages scanned for keys.
+    #[serde(default = "default_scan_depth")]
+    pub scan_depth: usize,
+    /// Approximate token cap for all injected entries.
+    #[serde(default = "default_token_budget")]
+    pub token_budget: usize,
+    #[serde(default)]
+    pub recursive_scanning: bool,
+    /// How many times injected content may itself trigger further entries.
+    /// Absent in plain card books; derived from `recursive_scanning` on load.
+    #[serde(default)]
+    pub recursion_depth: Option<usize>,
+    #[serde(default)]
+    pub entries: Vec<LorebookEntry>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl Default for Lorebook {
+    fn default() -> Self {
+        Self {
+            scan_depth: DEFAULT_SCAN_DEPTH,
+            token_budget: DEFAULT_TOKEN_BUDGET,
+            recursive_scanning: false,
+            recursion_depth: Some(0),
+            entries: Vec::new(),
+            extra: Map::new(),
+        }
+    }
+}
+
+impl Lorebook {
+    pub fn from_value(value: Value) -> Result<Self, KokoroError> {
+        let mut book: Lorebook = serde_json::from_value(value)
+            .map_err(|e| KokoroError::Validation(format!("Invalid lorebook: {}", e)))?;
+        book.normalize();
+        Ok(book)
+    }
+
+    /// Fill derived settings and give every entry a unique, non-zero id.
+    pub fn normalize(&mut self) {
+        let depth = self.recursion_depth.unwrap_or(if self.recursive_scanning {
+            DEFAULT_RECURSION_DEPTH
+        } else {
+            0
+        });
+        self.recursion_depth = Some(depth);
+        self.recursive_scanning = depth > 0;
+
+        let mut seen = HashSet::new();
+        let mut next_id = self.entries.iter().map(|e| e.id).max().unwrap_or(0).max(0) + 1;
+        for entry in &mut self.entries {
+            if entry.id <= 0 || !seen.insert(entry.id) {
+                entry.id = next_id;
+                seen.insert(next_id);
+                next_id += 1;
+            }
+        }
+    }
+
+    pub fn next_entry_id(&self) -> i64 {
+        self.entries.iter().map(|e| e.id).max().unwrap_or(0).max(0) + 1
+    }
+}
+
+fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(CHARS_PER_TOKEN)
+}
+
+fn any_key_matches(
+    keys: &[String],
+    haystack: &str,
+    haystack_lower: &str,
+    case_sensitive: bool,
+) -> bool {
+    keys.iter()
+        .map(|k| k.trim())
+        .filter(|k| !k.is_empty())
+        .any(|key| {
+            if case_sensitive {
+                haystack.contains(key)
+            } else {
+                haystack_lower.contains(&key.to_lowercase())
+            }
+        })
+}
+
+fn entry_matches(entry: &LorebookEntry, haystack: &str, haystack_lower: &str) -> bool {
+    let case_sensitive = entry.case_sensitive.unwrap_or(false);
+    if !any_key_matches(&entry.keys, haystack, haystack_lower, case_sensitive) {
+        return false;
+    }
+    !entry.selective
+        || entry.secondary_keys.iter().all(|k| k.trim().is_empty())
+        || any_key_matches(
+            &entry.secondary_keys,
+            haystack,
+            haystack_lower,
+            case_sensitive,
+        )
+}
+
+/// Entries triggered by `recent_messages` (oldest first), in insertion order
+/// and trimmed to the book's token budget.
+pub fn activate_entries<'a>(
+    book: &'a Lorebook,
+    recent_messages: &[&str],
+) -> Vec<&'a LorebookEntry> {
+    let window_start = recent_messages.len().saturating_sub(book.scan_depth);
+    let mut haystack = recent_messages[window_start..].join("\n");
+    let mut active: Vec<usize> = Vec::new();
+
+    for depth in 0..=book.recursion_depth.unwrap_or(0) {
+        let haystack_lower = haystack.to_lowercase();
+        let newly: Vec<usize> = book
+            .entries
+            .iter()
+            .enumerate()
+            .filter(|(index, entry)| {
+                entry.enabled
+                    && !entry.content.trim().is_empty()
+                    && !active.contains(index)
+                    && ((depth == 0 && entry.constant)
+                        || entry_matches(entry, &haystack, &haystack_lower))
+            })
+            .map(|(index, _)| index)
+            .collect();
+        if newly.is_empty() {
+            break;
+        }
+        // Recursion only scans what was just injected; the window was already checked.
+        haystack = newly
+            .iter()
+            .map(|&i| book.entries[i].content.as_str())
+            .collect::<Vec<_>>()
+            .join("\n");
+        active.extend(newly);
+    }
+
+    let mut entries: Vec<&LorebookEntry> = active.into_iter().map(|i| &book.entries[i]).collect();
+    entries.sort_by_key(|entry| (entry.insertion_order, entry.id));
+
+    let mut used = 0usize;
+    entries.retain(|entry| {
+        let cost = estimate_tokens(&entry.content);
+        if used + cost > book.token_budget {
+            return false;
+        }
+        used += cost;
+        true
+    });
+    entries
+}
+
+/// Render activated entries as a `<world_info>` prompt block.
+pub fn render_world_info(
+    entries: &[&LorebookEntry],
+    char_name: &str,
+    user_name: &str,
+) -> Option<String> {
+    if entries.is_empty() {
+        return None;
+    }
+    let body = entries
+        .iter()
+        .map(|entry| {
+            entry
+                .content
+                .trim()
+                .replace("{{char}}", char_name)
+                .replace("{{user}}", user_name)
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    Some(format!(
+        "<world_info>\nBackground lore relevant to the current conversation:\n{}\n</world_info>",
+        body
+    ))
+}
+
+/// Load a character's lorebook. `None` if the character doesn't exist or has none.
+pub async fn load_lorebook(
+    db: &SqlitePool,
+    character_id: &str,
+) -> Result<Option<Lorebook>, KokoroError> {
+    let raw: Option<String> =
+        sqlx::query_scalar("SELECT lorebook_json FROM characters WHERE id = ?")
+            .bind(character_id)
+            .fetch_optional(db)
+            .await?
+            .flatten();
+    match raw {
+        Some(raw) => Ok(Some(Lorebook::from_value(serde_json::from_str(&raw)?)?)),
+        None => Ok(None),
+    }
+}
+
+pub async fn save_lorebook(
+    db: &SqlitePool,
+    character_id: &str,
+    book: &Lorebook,
+) -> Result<(), KokoroError> {
+    let result =
+        sqlx::query("UPDATE characters SET lorebook_json = ?, updated_at = ? WHERE id = ?")
+            .bind(serde_json::to_string(book)?)
+            .bind(chrono::Utc::now().timestamp_millis())
+            .bind(character_id)
+            .execute(db)
+            .await?;
+    if result.rows_affected() == 0 {
+        return Err(KokoroError::NotFound(format!(
+            "Character {} not found",
+            character_id
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: i64, keys: &[&str], content: &str) -> LorebookEntry {
+        LorebookEntry {
+            id,
+            keys: keys.iter().map(|k| k.to_string()).collect(),
+            secondary_keys: Vec::new(),
+            selective: false,
+            content: content.to_string(),
+            enabled: true,
+            constant: false,
+            case_sensitive: None,
+            insertion_order: DEFAULT_INSERTION_ORDER,
+            extra: Map::new(),
+        }
+    }
+
+    fn ids(entries: &[&LorebookEntry]) -> Vec<i64> {
+        entries.iter().map(|e| e.id).collect()
+    }
+
+    #[test]
+    fn parses_card_book_and_assigns_ids() {
+        let book = Lorebook::from_value(serde_json::json!({
+            "recursive_scanning": true,
+            "entries": [
+                { "keys": ["shrine"], "content": "The shrine is old.", "case_sensitive": null, "comment": "note" },
+                { "keys": ["fox"], "content": "Foxes guard it.", "id": 1 }
+            ]
+        }))
+        .unwrap();
+        assert_eq!(book.recursion_depth, Some(DEFAULT_RECURSION_DEPTH));
+        assert_eq!(book.scan_depth, DEFAULT_SCAN_DEPTH);
+        assert_eq!(ids(&book.entries.iter().collect::<Vec<_>>()), vec![2, 1]);
+        // Unknown fields survive a round trip for card export.
+        let value = serde_json::to_value(&book).unwrap();
+        assert_eq!(value["entries"][0]["comment"], "note");
+    }
+
+    #[test]
+    fn activates_on_keys_within_scan_depth() {
+        let mut book = Lorebook {
+            scan_depth: 2,
+            entries: vec![
+                entry(1, &["Shrine"], "The shrine is old."),
+                entry(2, &["forest"], "The forest is dark."),
+            ],
+            ..Lorebook::default()
+        };
+        let messages = ["we walked through the forest", "hello", "visit the SHRINE?"];
+        assert_eq!(ids(&activate_entries(&book, &messages)), vec![1]);
+
+        book.entries[0].case_sensitive = Some(true);
+        assert!(activate_entries(&book, &messages).is_empty());
+    }
+
+    #[test]
+    fn selective_entries_need_a_secondary_key() {
+        let mut selective = entry(1, &["sword"], "The sword is cursed.");
+        selective.selective = true;
+        selective.secondary_keys = vec!["curse".to_string()];
+        let book = Lorebook {
+            entries: vec![selective],
+            ..Lorebook::default()
+        };
+        assert!(activate_entries(&book, &["a sword"]).is_empty());
+        assert_eq!(
+            ids(&activate_entries(&book, &["the sword's curse"])),
+            vec![1]
+        );
+    }
+
+    #[test]
+    fn recursion_respects_depth() {
+        let mut book = Lorebook {
+            entries: vec![
+                entry(1, &["shrine"], "The shrine is guarded by a fox."),
+                entry(2, &["fox"], "The fox serves the moon goddess."),
+                entry(3, &["goddess"], "The goddess sleeps."),
+            ],
+            ..Lorebook::default()
+        };
+        assert_eq!(ids(&activate_entries(&book, &["the shrine"])), vec![1]);
+        book.recursion_depth = Some(1);
+        assert_eq!(ids(&activate_entries(&book, &["the shrine"])), vec![1, 2]);
+        book.recursion_depth = Some(5);
+        assert_eq!(
+            ids(&activate_entries(&book, &["the shrine"])),
+            vec![1, 2, 3]
+        );
+    }
+
+    #[test]
+    fn budget_keeps_lowest_insertion_order_first() {
+        let mut first = entry(1, &["a"], &"x".repeat(20));
+        first.insertion_order = 200;
+        let mut second = entry(2, &["a"], &"y".repeat(20));
+        second.insertion_order = 10;
+        let mut always = entry(3, &[], "zz");
+        always.constant = true;
+        always.insertion_order = 300;
+        let book = Lorebook {
+            token_budget: 11,
+            entries: vec![first, second, always],
+            ..Lorebook::default()
+        };
+        assert_eq!(ids(&activate_entries(&book, &["a"])), vec![2, 3]);
+    }
+}
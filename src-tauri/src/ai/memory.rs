@@ -11,6 +11,9 @@ use std::collections::{HashMap, HashSet};
 use tokio::sync::Mutex;
 
 use crate::ai::context::MemorySnippet;
+use crate::ai::embedding_cache::{
+    normalize_embedding_key, EmbeddingCache, EmbeddingCacheStats, DEFAULT_EMBEDDING_CACHE_CAPACITY,
+};
 #[cfg(not(test))]
 use crate::ai::memory_embedding_model;
 
@@ -22,6 +25,7 @@ pub use crate::ai::memory_embedding_model::{
 pub struct MemoryManager {
     #[cfg(not(test))]
     embedder: tokio::sync::OnceCell<Mutex<TextEmbedding>>,
+    embedding_cache: std::sync::Mutex<EmbeddingCache>,
     db: SqlitePool,
 }
 
@@ -117,6 +121,8 @@ struct ActiveMemoryInsert<'a> {
     metadata: &'a MemoryMetadata,
     canonical_hash: &'a str,
     now: i64,
+    source_kind: &'a str,
+    source_refs: String,
 }
 
 struct DreamProposalInsert<'a> {
@@ -347,6 +353,8 @@ pub struct ConversationSummaryTask {
     pub start_message_id: i64,
     pub end_message_id: i64,
     pub transcript: String,
+    /// The last ready "conversation so far" summary, folded into the new version.
+    pub previous_summary: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -1547,6 +1555,9 @@ impl MemoryManager {
         Self {
             #[cfg(not(test))]
             embedder: tokio::sync::OnceCell::new(),
+            embedding_cache: std::sync::Mutex::new(EmbeddingCache::new(
+                DEFAULT_EMBEDDING_CACHE_CAPACITY,
+            )),
             db,
         }
     }
@@ -1646,8 +1657,34 @@ impl MemoryManager {
             .await
     }
 
-    #[cfg(not(test))]
+    /// Embed `text`, serving repeated / near-identical strings from the LRU cache.
     pub async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        let key = normalize_embedding_key(text);
+        if let Some(cached) = self
+            .embedding_cache
+            .lock()
+            .ok()
+            .and_then(|mut cache| cache.get(&key))
+        {
+            return Ok(cached);
+        }
+        let embedding = self.embed_uncached(text).await?;
+        if let Ok(mut cache) = self.embedding_cache.lock() {
+            cache.insert(key, embedding.clone());
+        }
+        Ok(embedding)
+    }
+
+    /// Hit/miss counters for the embedding cache.
+    pub fn embedding_cache_stats(&self) -> EmbeddingCacheStats {
+        self.embedding_cache
+            .lock()
+            .map(|cache| cache.stats())
+            .unwrap_or_else(|poisoned| poisoned.into_inner().stats())
+    }
+
+    #[cfg(not(test))]
+    async fn embed_uncached(&self, text: &str) -> Result<Vec<f32>> {
         let embedder = self.get_embedder().await?;
         let mut guard = embedder.lock().await;
         let text_owned = text.to_owned();
@@ -1666,7 +1703,7 @@ impl MemoryManager {
     }
 
     #[cfg(test)]
-    pub async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+    async fn embed_uncached(&self, text: &str) -> Result<Vec<f32>> {
         Ok(test_embedding(text))
     }
 
@@ -1907,7 +1944,7 @@ impl MemoryManager {
              (content, embedding, created_at, updated_at, importance, character_id, tier, \
               memory_type, entity_key, status, confidence, first_seen_at, last_seen_at, evidence_count, \
               source_kind, source_refs, canonical_hash) \
-             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, 'active', ?, ?, ?, 1, 'extractor', '[]', ?)",
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, 'active', ?, ?, ?, 1, ?, ?, ?)",
         )
         .bind(&storage_content)
         .bind(insert.embedding_bytes)
@@ -1921,6 +1958,8 @@ impl MemoryManager {
         .bind(DREAM_CONFIDENCE_AUTO_APPLY)
         .bind(insert.now)
         .bind(insert.now)
+        .bind(insert.source_kind)
+        .bind(&insert.source_refs)
         .bind(insert.canonical_hash)
         .execute(&self.db)
         .await?;
@@ -2391,10 +2430,16 @@ impl MemoryManager {
         }))
     }
 
+    /// Prepare the next rolling summary step for a conversation.
+    ///
+    /// Only messages that have fallen out of the raw history window are summarized:
+    /// the newest `keep_recent` visible messages are still sent verbatim, so they
+    /// are left for a later step.
     pub async fn get_conversation_summary_task(
         &self,
         conversation_id: &str,
         character_id: &str,
+        keep_recent: usize,
     ) -> Result<Option<ConversationSummaryTask>> {
         let now = chrono::Utc::now().timestamp();
 
@@ -2456,7 +2501,7 @@ impl MemoryManager {
         let message_rows = sqlx::query(
             "SELECT id, role, content, metadata
              FROM conversation_messages
-             WHERE conversation_id = ? AND id > ?
+             WHERE conversation_id = ? AND id > ? AND is_active = 1
              ORDER BY id ASC",
         )
         .bind(conversation_id)
@@ -2494,11 +2539,12 @@ impl MemoryManager {
             ));
         }
 
-        if visible.len() < CONVERSATION_SUMMARY_MIN_MESSAGES {
+        let evicted = visible.len().saturating_sub(keep_recent);
+        if evicted < CONVERSATION_SUMMARY_MIN_MESSAGES {
             return Ok(None);
         }
 
-        let chunk = &visible[..visible.len().min(CONVERSATION_SUMMARY_MAX_MESSAGES)];
+        let chunk = &visible[..evicted.min(CONVERSATION_SUMMARY_MAX_MESSAGES)];
         let start_message_id = chunk.first().map(|(id, _, _)| *id).unwrap_or(0);
         let end_message_id = chunk.last().map(|(id, _, _)| *id).unwrap_or(0);
         if start_message_id == 0 || end_message_id == 0 {
@@ -2533,6 +2579,11 @@ impl MemoryManager {
             .map(|(_, role, content)| format!("{}: {}", role, content))
             .collect::<Vec<_>>()
             .join("\n");
+        let previous_summary = self
+            .get_latest_conversation_summary(conversation_id)
+            .await?
+            .map(|record| record.summary)
+            .filter(|summary| !summary.trim().is_empty());
 
         Ok(Some(ConversationSummaryTask {
             record_id,
@@ -2542,6 +2593,7 @@ impl MemoryManager {
             start_message_id,
             end_message_id,
             transcript,
+            previous_summary,
         }))
     }
 
@@ -2684,6 +2736,8 @@ impl MemoryManager {
                 metadata: &metadata,
                 canonical_hash: &hash,
                 now,
+                source_kind: "extractor",
+                source_refs: "[]".to_string(),
             })
             .await?;
         self.mark_candidate_decision(candidate_id, "inserted", Some(memory_id))
@@ -2698,6 +2752,56 @@ impl MemoryManager {
         Ok(())
     }
 
+    /// Store `content`, phrased by the user, as a core memory right away instead
+    /// of waiting for the extractor. An identical memory is promoted to core
+    /// rather than duplicated. Returns the memory id.
+    pub async fn pin_memory(
+        &self,
+        content: &str,
+        character_id: &str,
+        source_message_id: Option<i64>,
+    ) -> Result<i64> {
+        let content = content.trim();
+        if content.is_empty() {
+            anyhow::bail!("Memory content is empty");
+        }
+        let metadata = infer_memory_metadata(content);
+        let hash = canonical_hash(content);
+        let now = now_ts();
+        if let Some(id) = self
+            .refresh_exact_duplicate_by_hash(character_id, &hash, now, 1.0)
+            .await?
+        {
+            return Ok(id);
+        }
+
+        let embedding = self.embed(content).await?;
+        let memory_id = self
+            .insert_active_memory(ActiveMemoryInsert {
+                content,
+                embedding_bytes: bincode::serialize(&embedding)?,
+                character_id,
+                importance: 1.0,
+                // Keep the user's phrasing as written.
+                metadata: &MemoryMetadata {
+                    canonical_content: None,
+                    ..metadata
+                },
+                canonical_hash: &hash,
+                now,
+                source_kind: "user_pinned",
+                source_refs: serde_json::json!(source_message_id
+                    .map(|id| vec![format!("message:{}", id)])
+                    .unwrap_or_default())
+                .to_string(),
+            })
+            .await?;
+        let _ = self
+            .check_and_invalidate_contradictions(content, &embedding, character_id)
+            .await;
+        Ok(memory_id)
+    }
+
     /// After storing a new memory, scan existing memories in the CONTRADICTION_BAND
     /// (similarity 0.70–0.95) and create review proposals for likely contradictions.
     ///
@@ -3943,6 +4047,281 @@ impl MemoryManager {
     }
 }
 
+// ── Usage Statistics & Importance Re-scoring ──────────────────
+
+/// Retrievals needed before a memory's usefulness ratio is trusted.
+const RESCORE_MIN_RETRIEVALS: i64 = 5;
+
+/// Referenced in at least this share of retrievals → promote.
+const RESCORE_USEFUL_RATIO: f64 = 0.5;
+
+/// Referenced in less than this share of retrievals → demote.
+const RESCORE_USELESS_RATIO: f64 = 0.1;
+
+/// Ephemeral memories not retrieved for this long start decaying faster.
+const RESCORE_UNUSED_AFTER_DAYS: f64 = 14.0;
+
+/// Half-life of the extra decay applied to unused memories (on top of the
+/// time decay used at retrieval).
+const RESCORE_UNUSED_HALF_LIFE_DAYS: f64 = 15.0;
+
+/// Importance floor after re-scoring; pruning decides when to archive.
+const RESCORE_MIN_IMPORTANCE: f64 = 0.05;
+
+const REFERENCE_STOPWORDS: &[&str] = &[
+    "that", "this", "with", "have", "from", "they", "them", "their", "there", "about", "user",
+    "would", "what", "when", "were", "been", "your", "will", "into", "also", "very",
+];
+
+fn is_cjk(c: char) -> bool {
+    matches!(c, '\u{3040}'..='\u{30ff}' | '\u{4e00}'..='\u{9fff}' | '\u{ac00}'..='\u{d7af}')
+}
+
+/// Content terms used for reference detection: lowercase words of 4+ chars
+/// (minus stopwords, trailing "s" folded) and CJK character bigrams.
+fn reference_terms(text: &str) -> HashSet<String> {
+    fn flush(word: &mut String, terms: &mut HashSet<String>) {
+        if word.chars().count() >= 4 && !REFERENCE_STOPWORDS.contains(&word.as_str()) {
+            // Crude plural / 3rd-person folding so "loves" matches "love".
+            if word.chars().count() > 4 && word.ends_with('s') && !word.ends_with("ss") {
+                word.pop();
+            }
+            terms.insert(std::mem::take(word));
+        }
+        word.clear();
+    }
+
+    let mut terms = HashSet::new();
+    let mut word = String::new();
+    let mut prev_cjk: Option<char> = None;
+
+    for c in text.to_lowercase().chars() {
+        if is_cjk(c) {
+            flush(&mut word, &mut terms);
+            if let Some(prev) = prev_cjk {
+                terms.insert(format!("{}{}", prev, c));
+            }
+            prev_cjk = Some(c);
+        } else if c.is_alphanumeric() {
+            prev_cjk = None;
+            word.push(c);
+        } else {
+            prev_cjk = None;
+            flush(&mut word, &mut terms);
+        }
+    }
+    flush(&mut word, &mut terms);
+    terms
+}
+
+/// Cheap lexical check for whether `response` drew on `memory`: enough of the
+/// memory's content terms reappear in the response.
+pub fn memory_referenced_in(memory: &str, response: &str) -> bool {
+    let memory_terms = reference_terms(strip_structured_memory_prefix(memory));
+    if memory_terms.is_empty() {
+        return false;
+    }
+    let response_terms = reference_terms(response);
+    let hits = memory_terms
+        .iter()
+        .filter(|term| response_terms.contains(*term))
+        .count();
+    hits >= memory_terms.len().min(2) && hits * 10 >= memory_terms.len() * 3
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RescoreOutcome {
+    Unchanged,
+    Boosted,
+    PromotedToCore,
+    Demoted,
+    Decayed,
+}
+
+impl RescoreOutcome {
+    /// Whether the pending counters were consumed by this outcome.
+    fn consumes_pending(self) -> bool {
+        matches!(self, Self::Boosted | Self::PromotedToCore | Self::Demoted)
+    }
+}
+
+/// Pure re-scoring rule. `unused_decay_days` is how many days of "unused"
+/// decay to apply since the last re-score (0 when recently retrieved).
+pub fn rescore_importance(
+    importance: f64,
+    tier: &str,
+    pending_retrievals: i64,
+    pending_references: i64,
+    unused_decay_days: f64,
+) -> (f64, RescoreOutcome) {
+    if pending_retrievals >= RESCORE_MIN_RETRIEVALS {
+        let ratio = pending_references as f64 / pending_retrievals as f64;
+        if ratio >= RESCORE_USEFUL_RATIO {
+            let boosted = (importance + 0.1).min(1.0);
+            if tier != "core" && boosted >= 0.9 {
+                return (boosted, RescoreOutcome::PromotedToCore);
+            }
+            return (boosted, RescoreOutcome::Boosted);
+        }
+        if ratio < RESCORE_USELESS_RATIO && tier != "core" {
+            let demoted = (importance - 0.05).max(RESCORE_MIN_IMPORTANCE);
+            return (demoted, RescoreOutcome::Demoted);
+        }
+        return (importance, RescoreOutcome::Unchanged);
+    }
+
+    if tier != "core" && unused_decay_days > 0.0 {
+        let decayed = (importance
+            * 0.5_f64.powf(unused_decay_days / RESCORE_UNUSED_HALF_LIFE_DAYS))
+        .max(RESCORE_MIN_IMPORTANCE);
+        if decayed < importance {
+            return (decayed, RescoreOutcome::Decayed);
+        }
+    }
+    (importance, RescoreOutcome::Unchanged)
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MemoryRescoreSummary {
+    pub evaluated: usize,
+    pub boosted: usize,
+    pub promoted: usize,
+    pub demoted: usize,
+    pub decayed: usize,
+}
+
+impl MemoryManager {
+    /// Count memories injected into a prompt.
+    pub async fn record_memory_retrievals(&self, ids: &[i64]) -> Result<()> {
+        let now = chrono::Utc::now().timestamp();
+        for id in ids {
+            sqlx::query(
+                "UPDATE memories SET retrieval_count = retrieval_count + 1, \
+                 pending_retrievals = pending_retrievals + 1, last_retrieved_at = ? WHERE rowid = ?",
+            )
+            .bind(now)
+            .bind(id)
+            .execute(&self.db)
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// Count which injected memories the response actually drew on. Returns how many.
+    pub async fn record_memory_references(
+        &self,
+        memories: &[(i64, String)],
+        response: &str,
+    ) -> Result<usize> {
+        let mut referenced = 0;
+        for (id, content) in memories {
+            if !memory_referenced_in(content, response) {
+                continue;
+            }
+            sqlx::query(
+                "UPDATE memories SET reference_count = reference_count + 1, \
+                 pending_references = pending_references + 1 WHERE rowid = ?",
+            )
+            .bind(id)
+            .execute(&self.db)
+            .await?;
+            referenced += 1;
+        }
+        Ok(referenced)
+    }
+
+    /// Re-score importance from usage statistics: frequently useful memories are
+    /// boosted (and promoted to core), retrieved-but-ignored ones are demoted and
+    /// long-unused ephemeral ones decay faster. Safe to call at any cadence.
+    pub async fn rescore_memory_importance(
+        &self,
+        character_id: &str,
+    ) -> Result<MemoryRescoreSummary> {
+        let now = chrono::Utc::now().timestamp();
+        let rows = sqlx::query(
+            "SELECT rowid, importance, tier, created_at, last_retrieved_at, last_rescored_at, \
+             pending_retrievals, pending_references FROM memories \
+             WHERE character_id = ? AND status = 'active'",
+        )
+        .bind(character_id)
+        .fetch_all(&self.db)
+        .await?;
+
+        let mut summary = MemoryRescoreSummary::default();
+        for row in rows {
+            let id: i64 = row.get("rowid");
+            let importance: f64 = row.get("importance");
+            let tier: String = row.get("tier");
+            let created_at: i64 = row.get("created_at");
+            let last_retrieved_at: Option<i64> = row.get("last_retrieved_at");
+            let last_rescored_at: Option<i64> = row.get("last_rescored_at");
+            let pending_retrievals: i64 = row.get("pending_retrievals");
+            let pending_references: i64 = row.get("pending_references");
+
+            let idle_days = (now - last_retrieved_at.unwrap_or(created_at)) as f64 / 86400.0;
+            let since_rescore_days =
+                (now - last_rescored_at.unwrap_or(created_at)) as f64 / 86400.0;
+            let unused_decay_days = if idle_days >= RESCORE_UNUSED_AFTER_DAYS {
+                (idle_days - RESCORE_UNUSED_AFTER_DAYS).min(since_rescore_days)
+            } else {
+                0.0
+            };
+
+            let (new_importance, outcome) = rescore_importance(
+                importance,
+                &tier,
+                pending_retrievals,
+                pending_references,
+                unused_decay_days,
+            );
+            summary.evaluated += 1;
+            match outcome {
+                RescoreOutcome::Unchanged => {}
+                RescoreOutcome::Boosted => summary.boosted += 1,
+                RescoreOutcome::PromotedToCore => summary.promoted += 1,
+                RescoreOutcome::Demoted => summary.demoted += 1,
+                RescoreOutcome::Decayed => summary.decayed += 1,
+            }
+
+            let new_tier = if outcome == RescoreOutcome::PromotedToCore {
+                "core"
+            } else {
+                tier.as_str()
+            };
+            let (pending_retrievals, pending_references) = if outcome.consumes_pending() {
+                (0, 0)
+            } else {
+                (pending_retrievals, pending_references)
+            };
+            sqlx::query(
+                "UPDATE memories SET importance = ?, tier = ?, pending_retrievals = ?, \
+                 pending_references = ?, last_rescored_at = ? WHERE rowid = ?",
+            )
+            .bind(new_importance)
+            .bind(new_tier)
+            .bind(pending_retrievals)
+            .bind(pending_references)
+            .bind(now)
+            .bind(id)
+            .execute(&self.db)
+            .await?;
+        }
+
+        if summary.boosted + summary.promoted + summary.demoted + summary.decayed > 0 {
+            tracing::info!(
+                target: "memory",
+                "[Memory] Re-scored '{}': {} boosted, {} promoted to core, {} demoted, {} decayed",
+                character_id,
+                summary.boosted,
+                summary.promoted,
+                summary.demoted,
+                summary.decayed
+            );
+        }
+        Ok(summary)
+    }
+}
+
 // ── Memory Consolidation ──────────────────────────────────────
 
 impl MemoryManager {
@@ -4560,6 +4939,46 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_pin_memory_stores_core_tier_and_promotes_duplicates() {
+        let pool = setup_test_pool().await;
+        let manager = MemoryManager::new(pool.clone());
+
+        manager
+            .add_memory_with_importance("The user's cat is called Mochi", "char", 0.3)
+            .await
+            .unwrap();
+        let promoted = manager
+            .pin_memory("The user's cat is called Mochi", "char", None)
+            .await
+            .unwrap();
+        let pinned = manager
+            .pin_memory("  Never schedule anything on Sundays ", "char", Some(42))
+            .await
+            .unwrap();
+        assert!(manager.pin_memory("   ", "char", None).await.is_err());
+
+        let rows: Vec<(i64, String, String, String, String)> = sqlx::query_as(
+            "SELECT id, content, tier, source_kind, source_refs FROM memories ORDER BY id",
+        )
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].0, promoted);
+        assert_eq!(rows[0].2, "core");
+        assert_eq!(
+            rows[1],
+            (
+                pinned,
+                "Never schedule anything on Sundays".to_string(),
+                "core".to_string(),
+                "user_pinned".to_string(),
+                "[\"message:42\"]".to_string(),
+            )
+        );
+    }
+
     #[tokio::test]
     async fn test_memory_manager_character_isolation() {
         let pool = setup_test_pool().await;
@@ -4694,4 +5113,157 @@ mod tests {
             "The user hates jazz music"
         ));
     }
+
+    #[test]
+    fn test_memory_reference_detection() {
+        assert!(memory_referenced_in(
+            "[type:preference|key:user.drink] The user loves matcha lattes",
+            "Want me to make you a matcha latte? I know how much you love matcha!"
+        ));
+        assert!(!memory_referenced_in(
+            "The user loves matcha lattes",
+            "Good morning! How did you sleep?"
+        ));
+        assert!(memory_referenced_in(
+            "用户喜欢抹茶拿铁",
+            "要不要来一杯抹茶拿铁？"
+        ));
+    }
+
+    #[test]
+    fn test_rescore_promotes_useful_and_demotes_ignored() {
+        let (importance, outcome) = rescore_importance(0.85, "ephemeral", 6, 4, 0.0);
+        assert_eq!(outcome, RescoreOutcome::PromotedToCore);
+        assert!((importance - 0.95).abs() < 1e-9);
+
+        let (importance, outcome) = rescore_importance(0.5, "ephemeral", 10, 0, 0.0);
+        assert_eq!(outcome, RescoreOutcome::Demoted);
+        assert!((importance - 0.45).abs() < 1e-9);
+
+        // Too few retrievals to judge, no unused decay.
+        assert_eq!(
+            rescore_importance(0.5, "ephemeral", 2, 0, 0.0),
+            (0.5, RescoreOutcome::Unchanged)
+        );
+    }
+
+    #[test]
+    fn test_rescore_decays_unused_ephemeral_only() {
+        let (importance, outcome) =
+            rescore_importance(0.8, "ephemeral", 0, 0, RESCORE_UNUSED_HALF_LIFE_DAYS);
+        assert_eq!(outcome, RescoreOutcome::Decayed);
+        assert!((importance - 0.4).abs() < 1e-9);
+
+        assert_eq!(
+            rescore_importance(0.8, "core", 0, 0, 30.0),
+            (0.8, RescoreOutcome::Unchanged)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_usage_stats_drive_rescoring() {
+        let pool = setup_test_pool().await;
+        let manager = MemoryManager::new(pool.clone());
+        manager
+            .add_memory_with_importance("The user loves matcha lattes", "stats_char", 0.85)
+            .await
+            .expect("add memory");
+        let id: i64 = sqlx::query_scalar("SELECT rowid FROM memories WHERE character_id = ?")
+            .bind("stats_char")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+
+        let injected = vec![(id, "The user loves matcha lattes".to_string())];
+        for _ in 0..RESCORE_MIN_RETRIEVALS {
+            manager.record_memory_retrievals(&[id]).await.unwrap();
+            manager
+                .record_memory_references(&injected, "A matcha latte for someone who loves matcha!")
+                .await
+                .unwrap();
+        }
+
+        let summary = manager
+            .rescore_memory_importance("stats_char")
+            .await
+            .unwrap();
+        assert_eq!(summary.promoted, 1);
+
+        let (tier, pending, retrievals): (String, i64, i64) = sqlx::query_as(
+            "SELECT tier, pending_retrievals, retrieval_count FROM memories WHERE rowid = ?",
+        )
+        .bind(id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(tier, "core");
+        assert_eq!(pending, 0);
+        assert_eq!(retrievals, RESCORE_MIN_RETRIEVALS);
+    }
+
+    #[tokio::test]
+    async fn test_summary_task_only_covers_evicted_messages() {
+        let pool = setup_test_pool().await;
+        let manager = MemoryManager::new(pool.clone());
+        let now = chrono::Utc::now().to_rfc3339();
+        sqlx::query(
+            "INSERT INTO conversations (id, character_id, title, created_at, updated_at) VALUES ('conv', 'char', 't', ?, ?)",
+        )
+        .bind(&now)
+        .bind(&now)
+        .execute(&pool)
+        .await
+        .unwrap();
+        for i in 1..=12 {
+            sqlx::query(
+                "INSERT INTO conversation_messages (conversation_id, role, content, created_at) VALUES ('conv', 'user', ?, ?)",
+            )
+            .bind(format!("message {}", i))
+            .bind(&now)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        // Only 6 messages have left the raw window: not enough to summarize yet.
+        assert!(manager
+            .get_conversation_summary_task("conv", "char", 6)
+            .await
+            .unwrap()
+            .is_none());
+
+        let task = manager
+            .get_conversation_summary_task("conv", "char", 2)
+            .await
+            .unwrap()
+            .expect("task");
+        assert_eq!(task.end_message_id, 10);
+        assert!(task.previous_summary.is_none());
+        assert!(!task.transcript.contains("message 11"));
+        manager
+            .complete_conversation_summary(task.record_id, "They said hello ten times.")
+            .await
+            .unwrap();
+
+        for i in 13..=20 {
+            sqlx::query(
+                "INSERT INTO conversation_messages (conversation_id, role, content, created_at) VALUES ('conv', 'user', ?, ?)",
+            )
+            .bind(format!("message {}", i))
+            .bind(&now)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+        let next = manager
+            .get_conversation_summary_task("conv", "char", 2)
+            .await
+            .unwrap()
+            .expect("rolling task");
+        assert_eq!(next.start_message_id, 11);
+        assert_eq!(
+            next.previous_summary.as_deref(),
+            Some("They said hello ten times.")
+        );
+    }
 }
@@ -8,6 +8,7 @@ use crate::ai::context::{is_memory_candidate_message, Message};
 use crate::ai::memory::MemoryManager;
 use crate::llm::messages::{system_message, user_text_message};
 use crate::llm::provider::LlmProvider;
+use crate::stt::diarization::mentions_other_speakers;
 use std::sync::Arc;
 
 /// System prompt for the memory extraction LLM call.
@@ -30,6 +31,14 @@ const EXTRACTION_PROMPT: &str = concat!(
     "IMPORTANT: Output ONLY the JSON array, no explanation or markdown."
 );
 
+/// Added when a voice message was split by speaker (see `stt::diarization`).
+const DIARIZED_TRANSCRIPT_NOTE: &str = concat!(
+    "\n\nSome user messages are voice transcripts split by speaker. Lines starting with ",
+    "\"Other person:\" (or \"Other person 2:\", ...) were said by someone else near the user, ",
+    "not by the user. Never record those as facts about the user; if worth remembering, ",
+    "store them as facts about that other person."
+);
+
 #[derive(Debug, Clone, Default)]
 pub struct MemoryExtractionOptions {
     pub structured_memory_enabled: bool,
@@ -183,8 +192,22 @@ pub async fn extract_and_store_memories_with_options(
         .collect::<Vec<_>>()
         .join("\n");
 
+    let diarized_note = if candidate_history
+        .iter()
+        .any(|m| m.role == "user" && mentions_other_speakers(&m.content))
+    {
+        DIARIZED_TRANSCRIPT_NOTE
+    } else {
+        ""
+    };
+
     let messages = vec![
-        system_message(format!("{}{}", extraction_prompt(&options), existing_block)),
+        system_message(format!(
+            "{}{}{}",
+            extraction_prompt(&options),
+            diarized_note,
+            existing_block
+        )),
         user_text_message(format!("Conversation to analyze:\n\n{}", transcript)),
     ];
 
@@ -1,15 +1,42 @@
+pub mod address_forms;
+pub mod branches;
+pub mod catch_up;
+pub mod character_card;
+pub mod character_context;
+pub mod checkpoints;
+pub mod companion_state;
 pub mod context;
+pub mod context_breakdown;
 pub mod curiosity;
+pub mod duet;
+pub mod embedding_cache;
+pub mod emotion;
+pub mod emotion_personality;
 pub mod heartbeat;
+pub mod history;
 pub mod idle_behaviors;
 pub mod initiative;
+pub mod input_queue;
+pub mod language_detect;
+pub mod latency;
+pub mod lorebook;
 pub mod memory;
 pub mod memory_embedding_model;
 pub mod memory_event_ingress;
 pub mod memory_extractor;
+pub mod onboarding;
+pub mod persona_lint;
+pub mod proactive_composer;
+pub mod proactive_policy;
 pub mod prompts;
+pub mod purge;
 pub mod router;
+pub mod scheduler;
+pub mod story;
+pub mod system_state;
+pub mod topic_shift;
 pub mod typing_sim;
+pub mod user_presence;
 
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,237 @@
+//! "About me" onboarding interview.
+//!
+//! On first run the character asks the user a short, fixed list of questions.
+//! The plan lives here so every frontend asks the same things in the same
+//! order, and the progress is persisted so an interview closed halfway picks
+//! up where it stopped. Answers skip the memory extractor: each one is pinned
+//! as a core memory and folded into the user profile (`user_profile.json`),
+//! so the first real conversation already knows who it is talking to.
+
+use crate::error::KokoroError;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Longest answer accepted for any question.
+pub const MAX_ANSWER_CHARS: usize = 200;
+/// Names end up in every system prompt, so they get a tighter limit.
+pub const MAX_NAME_CHARS: usize = 40;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OnboardingQuestion {
+    Name,
+    Pronouns,
+    Interests,
+    Schedule,
+}
+
+impl OnboardingQuestion {
+    /// The interview plan, in the order it is asked.
+    pub const PLAN: [OnboardingQuestion; 4] = [
+        OnboardingQuestion::Name,
+        OnboardingQuestion::Pronouns,
+        OnboardingQuestion::Interests,
+        OnboardingQuestion::Schedule,
+    ];
+
+    pub fn max_chars(self) -> usize {
+        match self {
+            OnboardingQuestion::Name => MAX_NAME_CHARS,
+            _ => MAX_ANSWER_CHARS,
+        }
+    }
+
+    /// The core memory stored for an answer.
+    pub fn memory(self, answer: &str) -> String {
+        match self {
+            OnboardingQuestion::Name => format!("The user's name is {}.", answer),
+            OnboardingQuestion::Pronouns => format!("The user's pronouns are {}.", answer),
+            OnboardingQuestion::Interests => format!("The user's interests: {}.", answer),
+            OnboardingQuestion::Schedule => format!("The user's usual daily schedule: {}.", answer),
+        }
+    }
+
+    /// Line label in the user persona. The name goes to `user_name` instead.
+    pub fn persona_label(self) -> Option<&'static str> {
+        match self {
+            OnboardingQuestion::Name => None,
+            OnboardingQuestion::Pronouns => Some("Pronouns"),
+            OnboardingQuestion::Interests => Some("Interests"),
+            OnboardingQuestion::Schedule => Some("Schedule"),
+        }
+    }
+}
+
+/// Persisted interview progress.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OnboardingProgress {
+    /// Answers so far; `None` marks a skipped question.
+    #[serde(default)]
+    pub answers: BTreeMap<OnboardingQuestion, Option<String>>,
+    /// Unix timestamp (seconds) at which the interview was finished or
+    /// dismissed.
+    #[serde(default)]
+    pub completed_at: Option<i64>,
+}
+
+/// What the UI needs to render the interview.
+#[derive(Debug, Clone, Serialize)]
+pub struct OnboardingStatus {
+    /// The question to ask now; `None` once the interview is over.
+    pub next: Option<OnboardingQuestion>,
+    pub answered: usize,
+    pub total: usize,
+    pub completed: bool,
+}
+
+impl OnboardingProgress {
+    pub fn next_question(&self) -> Option<OnboardingQuestion> {
+        if self.completed_at.is_some() {
+            return None;
+        }
+        OnboardingQuestion::PLAN
+            .into_iter()
+            .find(|question| !self.answers.contains_key(question))
+    }
+
+    /// Record an answer (or a skip) and close the interview after the last
+    /// question. Returns whether this finished it.
+    pub fn record(
+        &mut self,
+        question: OnboardingQuestion,
+        answer: Option<String>,
+        now: i64,
+    ) -> bool {
+        self.answers.insert(question, answer);
+        if self.completed_at.is_none() && self.next_question().is_none() {
+            self.completed_at = Some(now);
+            return true;
+        }
+        false
+    }
+
+    /// Answers that were given rather than skipped, in plan order.
+    pub fn given_answers(&self) -> impl Iterator<Item = (OnboardingQuestion, &str)> {
+        self.answers
+            .iter()
+            .filter_map(|(question, answer)| Some((*question, answer.as_deref()?)))
+    }
+
+    pub fn status(&self) -> OnboardingStatus {
+        OnboardingStatus {
+            next: self.next_question(),
+            answered: self.answers.len(),
+            total: OnboardingQuestion::PLAN.len(),
+            completed: self.completed_at.is_some(),
+        }
+    }
+}
+
+/// Set `label: value` in a free-form persona, replacing an earlier line with
+/// the same label so retaking the interview doesn't pile up duplicates.
+pub fn merge_persona_line(persona: &str, label: &str, value: &str) -> String {
+    let prefix = format!("{}:", label);
+    let line = format!("{} {}", prefix, value);
+    let mut replaced = false;
+    let mut lines: Vec<String> = persona
+        .lines()
+        .map(|existing| {
+            if !replaced && existing.trim_start().starts_with(&prefix) {
+                replaced = true;
+                line.clone()
+            } else {
+                existing.to_string()
+            }
+        })
+        .collect();
+    if !replaced {
+        if lines.last().is_some_and(|last| last.trim().is_empty()) {
+            lines.pop();
+        }
+        lines.push(line);
+    }
+    lines.join("\n")
+}
+
+/// Instruction for the character's reply once the interview is done.
+pub fn completion_instruction(progress: &OnboardingProgress) -> Option<String> {
+    let facts: Vec<String> = progress
+        .given_answers()
+        .map(|(question, answer)| question.memory(answer))
+        .collect();
+    if facts.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "The user just finished a short introduction and told you about themselves: {} Thank them warmly, in character, and mention one thing you look forward to talking about with them.",
+        facts.join(" ")
+    ))
+}
+
+pub fn progress_path() -> PathBuf {
+    dirs_next::data_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("com.chyin.kokoro")
+        .join("onboarding.json")
+}
+
+pub fn load_progress(path: &Path) -> OnboardingProgress {
+    crate::config::load_json_config(path, "ONBOARDING")
+}
+
+pub fn save_progress(path: &Path, progress: &OnboardingProgress) -> Result<(), KokoroError> {
+    crate::config::save_json_config(path, progress, "ONBOARDING")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interview_follows_the_plan_and_merges_persona_lines() {
+        let mut progress = OnboardingProgress::default();
+        assert_eq!(progress.next_question(), Some(OnboardingQuestion::Name));
+
+        assert!(!progress.record(OnboardingQuestion::Name, Some("Alex".to_string()), 10));
+        assert!(!progress.record(OnboardingQuestion::Pronouns, None, 20));
+        assert_eq!(
+            progress.next_question(),
+            Some(OnboardingQuestion::Interests)
+        );
+        assert!(!progress.record(
+            OnboardingQuestion::Interests,
+            Some("climbing, synthwave".to_string()),
+            30
+        ));
+        assert!(progress.record(OnboardingQuestion::Schedule, None, 40));
+        assert_eq!(progress.completed_at, Some(40));
+        assert_eq!(progress.next_question(), None);
+        assert_eq!(
+            progress
+                .given_answers()
+                .map(|(question, _)| question)
+                .collect::<Vec<_>>(),
+            [OnboardingQuestion::Name, OnboardingQuestion::Interests]
+        );
+        assert!(completion_instruction(&progress)
+            .unwrap()
+            .contains("The user's name is Alex."));
+
+        let json = serde_json::to_string(&progress).unwrap();
+        let restored: OnboardingProgress = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.status().answered, 4);
+        assert!(restored.status().completed);
+
+        let persona = merge_persona_line("Likes tea.\n", "Pronouns", "they/them");
+        assert_eq!(persona, "Likes tea.\nPronouns: they/them");
+        assert_eq!(
+            merge_persona_line(&persona, "Pronouns", "she/her"),
+            "Likes tea.\nPronouns: she/her"
+        );
+        assert_eq!(
+            merge_persona_line("", "Schedule", "nights"),
+            "Schedule: nights"
+        );
+    }
+}
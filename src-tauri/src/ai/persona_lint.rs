@@ -0,0 +1,678 @@
+//! Persona linting — static checks on character persona text.
+//!
+//! `analyze_persona` reports what a persona will actually do once it is
+//! wrapped into the system prompt: which temperament keywords it contains,
+//! where those keywords pull in opposite directions, whether it tells the
+//! model which language and speaking style to use, and whether it fights the
+//! core rules that are always prepended.
+
+use serde::Serialize;
+use std::collections::BTreeSet;
+
+/// Rough chars-per-token ratio used for the size warning (mixed CJK/Latin text).
+const CHARS_PER_TOKEN: usize = 2;
+/// Personas above this many estimated tokens crowd out history and memories.
+const LONG_PERSONA_TOKENS: usize = 3000;
+/// Chars of context shown around a matched keyword.
+const EXCERPT_RADIUS: usize = 24;
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum PersonaTrait {
+    Energy,
+    Warmth,
+    Expressiveness,
+    Stability,
+    Confidence,
+}
+
+impl PersonaTrait {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Energy => "energy",
+            Self::Warmth => "warmth",
+            Self::Expressiveness => "expressiveness",
+            Self::Stability => "stability",
+            Self::Confidence => "confidence",
+        }
+    }
+}
+
+/// Keywords per trait, as (positive pole, negative pole).
+const TRAIT_KEYWORDS: &[(PersonaTrait, &[&str], &[&str])] = &[
+    (
+        PersonaTrait::Energy,
+        &[
+            "energetic",
+            "lively",
+            "cheerful",
+            "hyper",
+            "bubbly",
+            "playful",
+            "活泼",
+            "元气",
+            "开朗",
+            "好动",
+        ],
+        &[
+            "calm",
+            "quiet",
+            "lazy",
+            "sleepy",
+            "laid-back",
+            "composed",
+            "安静",
+            "冷静",
+            "慵懒",
+            "沉稳",
+        ],
+    ),
+    (
+        PersonaTrait::Warmth,
+        &[
+            "kind",
+            "gentle",
+            "caring",
+            "warm",
+            "affectionate",
+            "sweet",
+            "温柔",
+            "体贴",
+            "善良",
+            "亲切",
+        ],
+        &[
+            "cold",
+            "aloof",
+            "distant",
+            "harsh",
+            "rude",
+            "sarcastic",
+            "冷漠",
+            "高冷",
+            "毒舌",
+            "冷淡",
+        ],
+    ),
+    (
+        PersonaTrait::Expressiveness,
+        &[
+            "expressive",
+            "emotional",
+            "dramatic",
+            "talkative",
+            "outgoing",
+            "情绪化",
+            "话多",
+            "外向",
+            "夸张",
+        ],
+        &[
+            "reserved",
+            "stoic",
+            "expressionless",
+            "taciturn",
+            "introverted",
+            "内向",
+            "寡言",
+            "面无表情",
+            "沉默",
+        ],
+    ),
+    (
+        PersonaTrait::Stability,
+        &[
+            "stable",
+            "patient",
+            "steady",
+            "even-tempered",
+            "耐心",
+            "稳重",
+            "平和",
+        ],
+        &[
+            "moody",
+            "temperamental",
+            "short-tempered",
+            "irritable",
+            "volatile",
+            "喜怒无常",
+            "暴躁",
+            "易怒",
+            "急躁",
+        ],
+    ),
+    (
+        PersonaTrait::Confidence,
+        &[
+            "confident",
+            "bold",
+            "proud",
+            "assertive",
+            "arrogant",
+            "自信",
+            "骄傲",
+            "大胆",
+            "傲慢",
+        ],
+        &[
+            "shy", "timid", "insecure", "nervous", "bashful", "害羞", "胆小", "自卑", "腼腆",
+        ],
+    ),
+];
+
+/// Words that mark an intentional contrast ("cold outside but gentle inside").
+const CONTRAST_MARKERS: &[&str] = &[
+    " but ",
+    "however",
+    "on the outside",
+    "inside",
+    "deep down",
+    "sometimes",
+    "when ",
+    "around ",
+    "但",
+    "却",
+    "其实",
+    "表面",
+    "内心",
+    "有时",
+    "偶尔",
+    "傲娇",
+];
+
+const ENGLISH_NEGATIONS: &[&str] = &["not", "never", "isn't", "doesn't", "no", "hardly"];
+const CJK_NEGATIONS: &[&str] = &["不", "不太", "没有", "从不", "并不", "不是"];
+
+/// Language mentions, mapped to a canonical code for comparison with the
+/// configured response language.
+const LANGUAGE_KEYWORDS: &[(&str, &[&str])] = &[
+    ("en", &["english", "英文", "英语"]),
+    ("zh", &["chinese", "mandarin", "中文", "汉语", "普通话"]),
+    ("ja", &["japanese", "日语", "日文", "日本語"]),
+    ("ko", &["korean", "韩语", "韩文", "한국어"]),
+];
+
+const LANGUAGE_GUIDANCE_KEYWORDS: &[&str] = &[
+    "respond in",
+    "reply in",
+    "speak in",
+    "speaks in",
+    "language",
+    "用中文",
+    "用日语",
+    "用英语",
+    "语言",
+];
+
+const STYLE_GUIDANCE_KEYWORDS: &[&str] = &[
+    "tone",
+    "style",
+    "speaks",
+    "speech",
+    "talks like",
+    "catchphrase",
+    "verbal tic",
+    "sentence",
+    "emoji",
+    "kaomoji",
+    "语气",
+    "说话",
+    "口癖",
+    "口头禅",
+    "风格",
+    "句尾",
+    "颜文字",
+    "自称",
+];
+
+/// Phrases that contradict the core rule "always respond as this character, never as an AI".
+const AI_IDENTITY_PHRASES: &[&str] = &[
+    "you are an ai",
+    "you are a language model",
+    "as an ai",
+    "ai assistant",
+    "language model",
+    "chatbot",
+    "你是ai",
+    "你是一个ai",
+    "人工智能助手",
+    "ai助手",
+    "语言模型",
+];
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LintSeverity {
+    Info,
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PersonaIssue {
+    pub severity: LintSeverity,
+    /// Stable identifier the frontend can translate, e.g. `trait_conflict`.
+    pub code: String,
+    pub message: String,
+    pub excerpt: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TraitSignal {
+    #[serde(rename = "trait")]
+    pub trait_name: PersonaTrait,
+    pub keyword: String,
+    /// +1 for the positive pole, -1 for the negative one (after negation).
+    pub direction: i8,
+    pub negated: bool,
+    pub excerpt: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PersonaReport {
+    pub char_count: usize,
+    pub estimated_tokens: usize,
+    pub traits: Vec<TraitSignal>,
+    pub languages_mentioned: Vec<String>,
+    pub has_language_guidance: bool,
+    pub has_style_guidance: bool,
+    pub placeholders: Vec<String>,
+    pub issues: Vec<PersonaIssue>,
+}
+
+impl PersonaReport {
+    pub fn has_errors(&self) -> bool {
+        self.issues
+            .iter()
+            .any(|issue| issue.severity == LintSeverity::Error)
+    }
+}
+
+fn excerpt_around(text: &str, byte_start: usize, byte_len: usize) -> String {
+    let before: String = text[..byte_start]
+        .chars()
+        .rev()
+        .take(EXCERPT_RADIUS)
+        .collect::<Vec<_>>()
+        .into_iter()
+        .rev()
+        .collect();
+    let after: String = text[byte_start + byte_len..]
+        .chars()
+        .take(EXCERPT_RADIUS)
+        .collect();
+    format!(
+        "{}{}{}",
+        before,
+        &text[byte_start..byte_start + byte_len],
+        after
+    )
+    .replace('\n', " ")
+    .trim()
+    .to_string()
+}
+
+/// Byte offsets of `keyword` in `lower`. ASCII keywords must sit on word
+/// boundaries so "kind" does not match "kindergarten".
+fn keyword_positions(lower: &str, keyword: &str) -> Vec<usize> {
+    let ascii = keyword.is_ascii();
+    lower
+        .match_indices(keyword)
+        .map(|(pos, _)| pos)
+        .filter(|&pos| {
+            if !ascii {
+                return true;
+            }
+            let before_ok = lower[..pos]
+                .chars()
+                .next_back()
+                .is_none_or(|c| !c.is_ascii_alphanumeric());
+            let after_ok = lower[pos + keyword.len()..]
+                .chars()
+                .next()
+                .is_none_or(|c| !c.is_ascii_alphanumeric());
+            before_ok && after_ok
+        })
+        .collect()
+}
+
+fn is_negated(lower: &str, pos: usize, keyword: &str) -> bool {
+    let prefix = &lower[..pos];
+    if keyword.is_ascii() {
+        let words: Vec<&str> = prefix
+            .split(|c: char| !c.is_ascii_alphanumeric() && c != '\'')
+            .filter(|w| !w.is_empty())
+            .collect();
+        words
+            .iter()
+            .rev()
+            .take(2)
+            .any(|w| ENGLISH_NEGATIONS.contains(w) || w.ends_with("n't"))
+    } else {
+        let tail: String = prefix
+            .chars()
+            .rev()
+            .take(3)
+            .collect::<Vec<_>>()
+            .into_iter()
+            .rev()
+            .collect();
+        CJK_NEGATIONS.iter().any(|neg| tail.ends_with(neg))
+    }
+}
+
+fn contains_any(lower: &str, needles: &[&str]) -> bool {
+    needles.iter().any(|needle| lower.contains(needle))
+}
+
+fn language_code(name: &str) -> Option<&'static str> {
+    let lower = name.trim().to_lowercase();
+    if lower.is_empty() {
+        return None;
+    }
+    LANGUAGE_KEYWORDS
+        .iter()
+        .find(|(code, words)| lower.as_str() == *code || words.iter().any(|w| lower.contains(w)))
+        .map(|(code, _)| *code)
+}
+
+fn collect_placeholders(persona: &str) -> Vec<String> {
+    let mut found = BTreeSet::new();
+    let mut rest = persona;
+    while let Some(start) = rest.find("{{") {
+        let after = &rest[start + 2..];
+        match after.find("}}") {
+            Some(end) => {
+                found.insert(format!("{{{{{}}}}}", &after[..end]));
+                rest = &after[end + 2..];
+            }
+            None => break,
+        }
+    }
+    found.into_iter().collect()
+}
+
+fn issue(
+    severity: LintSeverity,
+    code: &str,
+    message: String,
+    excerpt: Option<String>,
+) -> PersonaIssue {
+    PersonaIssue {
+        severity,
+        code: code.to_string(),
+        message,
+        excerpt,
+    }
+}
+
+/// Temperament keywords in `persona`, with negation applied ("not shy"
+/// counts toward confidence). Sorted by trait, then keyword.
+pub fn trait_signals(persona: &str) -> Vec<TraitSignal> {
+    let lower = persona.to_lowercase();
+    let mut traits = Vec::new();
+    for (trait_name, positive, negative) in TRAIT_KEYWORDS {
+        for (keywords, pole) in [(*positive, 1i8), (*negative, -1i8)] {
+            for keyword in keywords {
+                for pos in keyword_positions(&lower, keyword) {
+                    let negated = is_negated(&lower, pos, keyword);
+                    traits.push(TraitSignal {
+                        trait_name: *trait_name,
+                        keyword: keyword.to_string(),
+                        direction: if negated { -pole } else { pole },
+                        negated,
+                        excerpt: excerpt_around(&lower, pos, keyword.len()),
+                    });
+                }
+            }
+        }
+    }
+    traits.sort_by(|a, b| {
+        a.trait_name
+            .cmp(&b.trait_name)
+            .then_with(|| a.keyword.cmp(&b.keyword))
+    });
+    traits
+}
+
+/// Analyze persona text. `response_language` is the configured reply
+/// language (empty when unset); it is compared against languages the
+/// persona asks for.
+pub fn analyze_persona(persona: &str, response_language: &str) -> PersonaReport {
+    let trimmed = persona.trim();
+    let lower = trimmed.to_lowercase();
+    let char_count = trimmed.chars().count();
+    let estimated_tokens = char_count.div_ceil(CHARS_PER_TOKEN);
+    let mut issues = Vec::new();
+
+    if trimmed.is_empty() {
+        issues.push(issue(
+            LintSeverity::Error,
+            "empty_persona",
+            "Persona is empty; the character will fall back to generic assistant behavior."
+                .to_string(),
+            None,
+        ));
+    }
+
+    // Temperament keywords
+    let traits = trait_signals(trimmed);
+
+    let has_contrast = contains_any(&lower, CONTRAST_MARKERS);
+    for (trait_name, _, _) in TRAIT_KEYWORDS {
+        let signals: Vec<&TraitSignal> = traits
+            .iter()
+            .filter(|s| s.trait_name == *trait_name)
+            .collect();
+        let up: Vec<&str> = signals
+            .iter()
+            .filter(|s| s.direction > 0)
+            .map(|s| s.keyword.as_str())
+            .collect();
+        let down: Vec<&str> = signals
+            .iter()
+            .filter(|s| s.direction < 0)
+            .map(|s| s.keyword.as_str())
+            .collect();
+        if up.is_empty() || down.is_empty() {
+            continue;
+        }
+        let trait_label = trait_name.label();
+        let (severity, message) = if has_contrast {
+            (
+                LintSeverity::Info,
+                format!(
+                    "{} is described both ways ({} vs {}); the contrast wording suggests this is intentional, \
+                     but say when each side shows so replies stay consistent.",
+                    trait_label,
+                    up.join(", "),
+                    down.join(", ")
+                ),
+            )
+        } else {
+            (
+                LintSeverity::Warning,
+                format!(
+                    "{} is described both ways ({} vs {}); the model will pick one at random per reply.",
+                    trait_label,
+                    up.join(", "),
+                    down.join(", ")
+                ),
+            )
+        };
+        issues.push(issue(severity, "trait_conflict", message, None));
+    }
+
+    if !trimmed.is_empty() && traits.is_empty() {
+        issues.push(issue(
+            LintSeverity::Info,
+            "no_temperament",
+            "No temperament keywords found; tone will be inferred from the rest of the text."
+                .to_string(),
+            None,
+        ));
+    }
+
+    // Language guidance
+    let languages_mentioned: Vec<String> = LANGUAGE_KEYWORDS
+        .iter()
+        .filter(|(_, words)| words.iter().any(|w| lower.contains(w)))
+        .map(|(code, _)| code.to_string())
+        .collect();
+    let has_language_guidance =
+        contains_any(&lower, LANGUAGE_GUIDANCE_KEYWORDS) || !languages_mentioned.is_empty();
+    let configured = language_code(response_language);
+    if !has_language_guidance && configured.is_none() && !trimmed.is_empty() {
+        issues.push(issue(
+            LintSeverity::Warning,
+            "missing_language",
+            "Neither the persona nor the response language setting says which language to reply in; \
+             the model will mirror whatever the user writes."
+                .to_string(),
+            None,
+        ));
+    }
+    if let Some(code) = configured {
+        if !languages_mentioned.is_empty() && !languages_mentioned.iter().any(|l| l == code) {
+            issues.push(issue(
+                LintSeverity::Warning,
+                "language_conflict",
+                format!(
+                    "Persona mentions {} but the response language is set to '{}'; the setting is injected later and usually wins.",
+                    languages_mentioned.join(", "),
+                    response_language.trim()
+                ),
+                None,
+            ));
+        }
+    }
+
+    // Style guidance
+    let has_style_guidance = contains_any(&lower, STYLE_GUIDANCE_KEYWORDS);
+    if !has_style_guidance && !trimmed.is_empty() {
+        issues.push(issue(
+            LintSeverity::Info,
+            "missing_style",
+            "No speaking-style guidance (tone, sentence length, catchphrases); replies will use the model's default voice."
+                .to_string(),
+            None,
+        ));
+    }
+
+    // Conflicts with the always-on core rules
+    for phrase in AI_IDENTITY_PHRASES {
+        if let Some(pos) = lower.find(phrase) {
+            issues.push(issue(
+                LintSeverity::Warning,
+                "conflicts_core_rules",
+                "Persona describes the character as an AI/assistant, but the core rules tell the model never to respond as an AI."
+                    .to_string(),
+                Some(excerpt_around(&lower, pos, phrase.len())),
+            ));
+            break;
+        }
+    }
+
+    let placeholders = collect_placeholders(trimmed);
+    for placeholder in &placeholders {
+        if placeholder != "{{char}}" && placeholder != "{{user}}" {
+            issues.push(issue(
+                LintSeverity::Warning,
+                "unknown_placeholder",
+                format!(
+                    "{} is not substituted; only {{{{char}}}} and {{{{user}}}} are replaced.",
+                    placeholder
+                ),
+                None,
+            ));
+        }
+    }
+
+    if estimated_tokens > LONG_PERSONA_TOKENS {
+        issues.push(issue(
+            LintSeverity::Warning,
+            "persona_too_long",
+            format!(
+                "Persona is about {} tokens; it is sent with every message and leaves less room for history and memories.",
+                estimated_tokens
+            ),
+            None,
+        ));
+    }
+
+    PersonaReport {
+        char_count,
+        estimated_tokens,
+        traits,
+        languages_mentioned,
+        has_language_guidance,
+        has_style_guidance,
+        placeholders,
+        issues,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn codes(report: &PersonaReport) -> Vec<&str> {
+        report.issues.iter().map(|i| i.code.as_str()).collect()
+    }
+
+    #[test]
+    fn flags_conflicting_traits_unless_contrast_is_explained() {
+        let report = analyze_persona("She is shy and bold. Speaks in short sentences.", "English");
+        let conflict = report
+            .issues
+            .iter()
+            .find(|i| i.code == "trait_conflict")
+            .expect("conflict");
+        assert_eq!(conflict.severity, LintSeverity::Warning);
+
+        let report = analyze_persona(
+            "She acts bold on the outside but is shy deep down. Speaks softly.",
+            "English",
+        );
+        let conflict = report
+            .issues
+            .iter()
+            .find(|i| i.code == "trait_conflict")
+            .expect("conflict");
+        assert_eq!(conflict.severity, LintSeverity::Info);
+    }
+
+    #[test]
+    fn negation_flips_trait_direction() {
+        let report = analyze_persona("He is not shy at all. 他从不冷漠。", "");
+        let shy = report.traits.iter().find(|t| t.keyword == "shy").unwrap();
+        assert!(shy.negated);
+        assert_eq!(shy.direction, 1);
+        let cold = report.traits.iter().find(|t| t.keyword == "冷漠").unwrap();
+        assert!(cold.negated);
+        assert_eq!(cold.direction, 1);
+        // "kind" must not match inside "kindergarten".
+        let report = analyze_persona("Works at a kindergarten.", "");
+        assert!(report.traits.is_empty());
+    }
+
+    #[test]
+    fn reports_language_style_and_core_rule_problems() {
+        let report = analyze_persona("You are an AI assistant named {{char}}. {{mood}}", "");
+        let found = codes(&report);
+        assert!(found.contains(&"missing_language"));
+        assert!(found.contains(&"missing_style"));
+        assert!(found.contains(&"conflicts_core_rules"));
+        assert!(found.contains(&"unknown_placeholder"));
+        assert_eq!(report.placeholders, vec!["{{char}}", "{{mood}}"]);
+
+        let report = analyze_persona("请用日语回答，语气温柔。", "中文");
+        assert!(report.has_language_guidance);
+        assert!(report.has_style_guidance);
+        assert_eq!(report.languages_mentioned, vec!["ja"]);
+        assert!(codes(&report).contains(&"language_conflict"));
+
+        assert!(analyze_persona("  ", "").has_errors());
+    }
+}
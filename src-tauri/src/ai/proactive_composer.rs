@@ -0,0 +1,690 @@
+//! Proactive message composer.
+//!
+//! Idle messages used to come from a single fixed instruction and kept
+//! circling back to the same few lines. The composer gathers candidate
+//! angles — a memory about the user, what is on screen, a curiosity topic,
+//! the moment itself — drops those too close to what the character said
+//! unprompted lately, and picks one by the configured weights. Every
+//! proactive instruction also lists the recent openers to steer away from.
+//!
+//! Replies are recorded per character in `proactive_history` (matched to the
+//! instruction that produced them), so novelty holds across restarts.
+
+use crate::ai::context::AIOrchestrator;
+use crate::error::KokoroError;
+use serde::{Deserialize, Serialize};
+use sqlx::{Row, SqlitePool};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+use tokio::sync::{Mutex, RwLock};
+
+/// Rows kept per character; older ones are pruned on insert.
+const HISTORY_KEEP: i64 = 100;
+/// Memories sampled per compose; each costs an embedding lookup.
+const MEMORY_SAMPLE: usize = 4;
+/// Bigram overlap that counts as a repeat when no embedding model is installed.
+const LEXICAL_REPEAT_THRESHOLD: f32 = 0.6;
+/// Opener length cap, in words and in characters (for unspaced scripts).
+const OPENER_WORDS: usize = 5;
+const OPENER_CHARS: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProactiveSource {
+    Memory,
+    Vision,
+    Curiosity,
+    Time,
+    /// System-state nudges and welcome-backs; not picked by weight.
+    Event,
+}
+
+impl ProactiveSource {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Memory => "memory",
+            Self::Vision => "vision",
+            Self::Curiosity => "curiosity",
+            Self::Time => "time",
+            Self::Event => "event",
+        }
+    }
+
+    fn parse(value: &str) -> Self {
+        match value {
+            "memory" => Self::Memory,
+            "vision" => Self::Vision,
+            "curiosity" => Self::Curiosity,
+            "time" => Self::Time,
+            _ => Self::Event,
+        }
+    }
+}
+
+/// Relative chance of each angle; 0 disables it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SourceWeights {
+    pub memory: f32,
+    pub vision: f32,
+    pub curiosity: f32,
+    pub time: f32,
+}
+
+impl Default for SourceWeights {
+    fn default() -> Self {
+        Self {
+            memory: 1.0,
+            vision: 0.6,
+            curiosity: 1.2,
+            time: 0.4,
+        }
+    }
+}
+
+impl SourceWeights {
+    fn weight(&self, source: ProactiveSource) -> f32 {
+        match source {
+            ProactiveSource::Memory => self.memory,
+            ProactiveSource::Vision => self.vision,
+            ProactiveSource::Curiosity => self.curiosity,
+            ProactiveSource::Time => self.time,
+            ProactiveSource::Event => 0.0,
+        }
+        .max(0.0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProactiveComposerConfig {
+    pub weights: SourceWeights,
+    /// Candidates whose embedding similarity to a recent proactive message
+    /// reaches this are skipped (0–1; lower is stricter).
+    pub novelty_threshold: f32,
+    /// Recent proactive messages per character checked for repeats and openers.
+    pub history_window: usize,
+}
+
+impl Default for ProactiveComposerConfig {
+    fn default() -> Self {
+        Self {
+            weights: SourceWeights::default(),
+            novelty_threshold: 0.82,
+            history_window: 12,
+        }
+    }
+}
+
+impl ProactiveComposerConfig {
+    pub fn validate(&self) -> Result<(), KokoroError> {
+        let w = &self.weights;
+        if [w.memory, w.vision, w.curiosity, w.time]
+            .iter()
+            .any(|v| !v.is_finite() || *v < 0.0)
+        {
+            return Err(KokoroError::Validation(
+                "proactive source weights must be zero or positive".to_string(),
+            ));
+        }
+        if !(0.0..=1.0).contains(&self.novelty_threshold) {
+            return Err(KokoroError::Validation(format!(
+                "novelty threshold must be between 0 and 1, got {}",
+                self.novelty_threshold
+            )));
+        }
+        if !(1..=50).contains(&self.history_window) {
+            return Err(KokoroError::Validation(format!(
+                "history window must be between 1 and 50, got {}",
+                self.history_window
+            )));
+        }
+        Ok(())
+    }
+}
+
+fn data_dir() -> PathBuf {
+    dirs_next::data_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("com.chyin.kokoro")
+}
+
+pub fn config_path() -> PathBuf {
+    data_dir().join("proactive_composer.json")
+}
+
+pub fn load_config(path: &Path) -> ProactiveComposerConfig {
+    crate::config::load_json_config(path, "PROACTIVE_COMPOSER")
+}
+
+pub fn save_config(path: &Path, config: &ProactiveComposerConfig) -> Result<(), KokoroError> {
+    crate::config::save_json_config(path, config, "PROACTIVE_COMPOSER")
+}
+
+/// What to say unprompted and why.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProactivePlan {
+    pub source: ProactiveSource,
+    /// Short subject used for repeat checks.
+    pub topic: String,
+    pub instruction: String,
+}
+
+impl ProactivePlan {
+    pub fn event(trigger_type: &str, instruction: impl Into<String>) -> Self {
+        Self {
+            source: ProactiveSource::Event,
+            topic: trigger_type.to_string(),
+            instruction: instruction.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProactiveHistoryEntry {
+    pub id: i64,
+    pub character_id: String,
+    pub source: ProactiveSource,
+    pub topic: String,
+    pub opener: String,
+    pub message: String,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Clone)]
+struct Pending {
+    instruction: String,
+    source: ProactiveSource,
+    topic: String,
+}
+
+pub struct ProactiveComposer {
+    config: RwLock<ProactiveComposerConfig>,
+    /// Instruction sent per character, waiting for its reply.
+    pending: Mutex<HashMap<String, Pending>>,
+}
+
+impl ProactiveComposer {
+    pub fn new(config: ProactiveComposerConfig) -> Self {
+        Self {
+            config: RwLock::new(config),
+            pending: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub async fn config(&self) -> ProactiveComposerConfig {
+        self.config.read().await.clone()
+    }
+
+    pub async fn set_config(&self, config: ProactiveComposerConfig) {
+        *self.config.write().await = config;
+    }
+
+    /// Pick an idle-message angle for the active character. `curiosity` is the
+    /// initiative system's topic, if it had one. `None` means every angle was
+    /// recently used; staying quiet beats repeating.
+    pub async fn compose(
+        &self,
+        app: &AppHandle,
+        orchestrator: &AIOrchestrator,
+        curiosity: Option<(String, bool)>,
+    ) -> Option<ProactivePlan> {
+        let config = self.config().await;
+        let character_id = orchestrator.get_character_id().await;
+        let recent = list_history(&orchestrator.db, &character_id, config.history_window)
+            .await
+            .unwrap_or_default();
+
+        let mut candidates = Vec::new();
+        if let Some((topic, ask)) = curiosity {
+            candidates.push(curiosity_plan(topic, ask));
+        }
+        if config.weights.memory > 0.0 && orchestrator.is_memory_enabled() {
+            candidates.extend(memory_plans(orchestrator, &character_id).await);
+        }
+        if config.weights.vision > 0.0 {
+            if let Some(watcher) = app.try_state::<crate::vision::watcher::VisionWatcher>() {
+                if let Some(observation) = watcher
+                    .context
+                    .latest_completed_observation(chrono::Utc::now())
+                    .await
+                {
+                    candidates.push(vision_plan(&observation.summary));
+                }
+            }
+        }
+        candidates.push(time_plan(chrono::Local::now()));
+
+        let judge = NoveltyJudge::new(orchestrator, &recent, config.novelty_threshold).await;
+        let mut fresh = Vec::new();
+        for candidate in candidates {
+            let weight = config.weights.weight(candidate.source);
+            if weight <= 0.0 {
+                continue;
+            }
+            if judge.is_repeat(orchestrator, &candidate.topic).await {
+                tracing::debug!(
+                    target: "chat",
+                    "[Proactive] Skipping {} angle '{}': said recently",
+                    candidate.source.as_str(),
+                    candidate.topic
+                );
+                continue;
+            }
+            fresh.push((candidate, weight));
+        }
+
+        let index = pick_weighted(
+            &fresh.iter().map(|(_, w)| *w).collect::<Vec<_>>(),
+            rand::random::<f32>(),
+        )?;
+        Some(fresh.swap_remove(index).0)
+    }
+
+    /// Add the "don't repeat yourself" guidance to a plan's instruction. The
+    /// result is what gets sent; pass it to [`Self::expect_reply`].
+    pub async fn with_diversity_hint(
+        &self,
+        db: &SqlitePool,
+        character_id: &str,
+        instruction: &str,
+    ) -> String {
+        let window = self.config.read().await.history_window;
+        let recent = list_history(db, character_id, window)
+            .await
+            .unwrap_or_default();
+        match diversity_hint(&recent) {
+            Some(hint) => format!("{} {}", instruction, hint),
+            None => instruction.to_string(),
+        }
+    }
+
+    /// Remember that `instruction` was sent for `plan`, so the reply can be
+    /// attributed when it arrives.
+    pub async fn expect_reply(&self, character_id: &str, instruction: &str, plan: &ProactivePlan) {
+        self.pending.lock().await.insert(
+            character_id.to_string(),
+            Pending {
+                instruction: instruction.to_string(),
+                source: plan.source,
+                topic: plan.topic.clone(),
+            },
+        );
+    }
+
+    /// Record the reply to a hidden instruction if it was a proactive one.
+    pub async fn record_reply(
+        &self,
+        db: &SqlitePool,
+        character_id: &str,
+        instruction: &str,
+        reply: &str,
+    ) {
+        let pending = {
+            let mut pending = self.pending.lock().await;
+            match pending.get(character_id) {
+                Some(p) if p.instruction == instruction => pending.remove(character_id),
+                _ => None,
+            }
+        };
+        let Some(pending) = pending else {
+            return;
+        };
+        if let Err(e) = insert_history(db, character_id, &pending, reply).await {
+            tracing::warn!(target: "chat", "[Proactive] Failed to record reply: {}", e);
+        }
+    }
+}
+
+// ── Candidates ─────────────────────────────────────────
+
+fn curiosity_plan(topic: String, ask: bool) -> ProactivePlan {
+    let instruction = if ask {
+        format!("Ask the user about: {}", topic)
+    } else {
+        format!("Share a thought about: {}", topic)
+    };
+    ProactivePlan {
+        source: ProactiveSource::Curiosity,
+        topic,
+        instruction,
+    }
+}
+
+async fn memory_plans(orchestrator: &AIOrchestrator, character_id: &str) -> Vec<ProactivePlan> {
+    let memories = orchestrator
+        .memory_manager
+        .list_memories(character_id, 40, 0)
+        .await
+        .unwrap_or_default();
+    let mut picked: Vec<_> = memories.into_iter().map(|m| m.content).collect();
+    // Partial Fisher–Yates: a random handful, not always the newest.
+    let take = picked.len().min(MEMORY_SAMPLE);
+    for i in 0..take {
+        let j = i + (rand::random::<f32>() * (picked.len() - i) as f32) as usize;
+        picked.swap(i, j.min(picked.len() - 1));
+    }
+    picked.truncate(take);
+    picked
+        .into_iter()
+        .map(|memory| ProactivePlan {
+            source: ProactiveSource::Memory,
+            instruction: format!(
+                "Bring up something you remember about the user and ask how it is going: {}",
+                memory
+            ),
+            topic: memory,
+        })
+        .collect()
+}
+
+fn vision_plan(summary: &str) -> ProactivePlan {
+    ProactivePlan {
+        source: ProactiveSource::Vision,
+        topic: summary.to_string(),
+        instruction: format!(
+            "Casually comment on what the user seems to be doing on screen, without describing it back to them in detail: {}",
+            summary
+        ),
+    }
+}
+
+fn time_plan(now: chrono::DateTime<chrono::Local>) -> ProactivePlan {
+    use chrono::{Datelike, Timelike};
+    let period = match now.hour() {
+        5..=11 => "morning",
+        12..=17 => "afternoon",
+        18..=21 => "evening",
+        _ => "night",
+    };
+    let topic = format!("{} {}", now.weekday(), period);
+    ProactivePlan {
+        source: ProactiveSource::Time,
+        instruction: format!(
+            "Start a light conversation that fits the moment ({}): plans, rest, food, the weather — whatever feels natural.",
+            topic
+        ),
+        topic,
+    }
+}
+
+/// Index chosen by weight for `roll` in [0, 1); `None` if nothing has weight.
+fn pick_weighted(weights: &[f32], roll: f32) -> Option<usize> {
+    let total: f32 = weights.iter().filter(|w| **w > 0.0).sum();
+    if total <= 0.0 {
+        return None;
+    }
+    let mut target = roll.clamp(0.0, 1.0) * total;
+    let mut last = None;
+    for (i, weight) in weights.iter().enumerate() {
+        if *weight <= 0.0 {
+            continue;
+        }
+        if target < *weight {
+            return Some(i);
+        }
+        target -= weight;
+        last = Some(i);
+    }
+    last
+}
+
+// ── Novelty ────────────────────────────────────────────
+
+/// Compares candidates against recent proactive topics and messages, by
+/// embedding when the local model is installed and by text overlap otherwise.
+struct NoveltyJudge {
+    texts: Vec<String>,
+    embeddings: Option<Vec<Vec<f32>>>,
+    threshold: f32,
+}
+
+impl NoveltyJudge {
+    async fn new(
+        orchestrator: &AIOrchestrator,
+        recent: &[ProactiveHistoryEntry],
+        threshold: f32,
+    ) -> Self {
+        let texts: Vec<String> = recent
+            .iter()
+            .flat_map(|e| [e.topic.clone(), e.message.clone()])
+            .filter(|t| !t.trim().is_empty())
+            .collect();
+        let embeddings =
+            if crate::ai::memory_embedding_model::memory_embedding_model_status().installed {
+                let mut embeddings = Vec::with_capacity(texts.len());
+                for text in &texts {
+                    match orchestrator.memory_manager.embed(text).await {
+                        Ok(e) => embeddings.push(e),
+                        Err(_) => break,
+                    }
+                }
+                (embeddings.len() == texts.len()).then_some(embeddings)
+            } else {
+                None
+            };
+        Self {
+            texts,
+            embeddings,
+            threshold,
+        }
+    }
+
+    async fn is_repeat(&self, orchestrator: &AIOrchestrator, topic: &str) -> bool {
+        if let Some(embeddings) = &self.embeddings {
+            if let Ok(candidate) = orchestrator.memory_manager.embed(topic).await {
+                return embeddings.iter().any(|e| {
+                    crate::ai::memory::cosine_similarity(&candidate, e) >= self.threshold
+                });
+            }
+        }
+        self.texts
+            .iter()
+            .any(|text| bigram_overlap(topic, text) >= LEXICAL_REPEAT_THRESHOLD)
+    }
+}
+
+fn bigrams(text: &str) -> HashSet<(char, char)> {
+    let chars: Vec<char> = text
+        .to_lowercase()
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .collect();
+    chars.windows(2).map(|w| (w[0], w[1])).collect()
+}
+
+/// Overlap of character bigrams relative to the smaller text (0–1), so a
+/// short topic contained in a longer message still counts.
+fn bigram_overlap(a: &str, b: &str) -> f32 {
+    let (a, b) = (bigrams(a), bigrams(b));
+    let smaller = a.len().min(b.len());
+    if smaller == 0 {
+        return 0.0;
+    }
+    a.intersection(&b).count() as f32 / smaller as f32
+}
+
+/// The first words of a message, normalised for comparison.
+pub fn opener_of(message: &str) -> String {
+    let clause = message
+        .trim()
+        .split(|c: char| ",.!?~…，。！？、".contains(c))
+        .find(|part| !part.trim().is_empty())
+        .unwrap_or("")
+        .trim();
+    let words: Vec<&str> = clause.split_whitespace().take(OPENER_WORDS).collect();
+    words
+        .join(" ")
+        .to_lowercase()
+        .chars()
+        .take(OPENER_CHARS)
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+fn diversity_hint(recent: &[ProactiveHistoryEntry]) -> Option<String> {
+    let mut seen = HashSet::new();
+    let openers: Vec<String> = recent
+        .iter()
+        .map(|e| e.opener.clone())
+        .filter(|o| !o.is_empty() && seen.insert(o.clone()))
+        .map(|o| format!("\"{}\"", o))
+        .collect();
+    if openers.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "Your recent unprompted messages opened with {}; start differently and do not repeat what they said.",
+        openers.join(", ")
+    ))
+}
+
+// ── Storage ────────────────────────────────────────────
+
+pub async fn list_history(
+    db: &SqlitePool,
+    character_id: &str,
+    limit: usize,
+) -> Result<Vec<ProactiveHistoryEntry>, KokoroError> {
+    let rows = sqlx::query(
+        "SELECT id, character_id, source, topic, opener, message, created_at
+         FROM proactive_history WHERE character_id = ?
+         ORDER BY created_at DESC, id DESC LIMIT ?",
+    )
+    .bind(character_id)
+    .bind(limit as i64)
+    .fetch_all(db)
+    .await?;
+    Ok(rows
+        .into_iter()
+        .map(|row| ProactiveHistoryEntry {
+            id: row.get("id"),
+            character_id: row.get("character_id"),
+            source: ProactiveSource::parse(row.get::<String, _>("source").as_str()),
+            topic: row.get("topic"),
+            opener: row.get("opener"),
+            message: row.get("message"),
+            created_at: row.get("created_at"),
+        })
+        .collect())
+}
+
+async fn insert_history(
+    db: &SqlitePool,
+    character_id: &str,
+    pending: &Pending,
+    reply: &str,
+) -> Result<(), KokoroError> {
+    sqlx::query(
+        "INSERT INTO proactive_history (character_id, source, topic, opener, message, created_at)
+         VALUES (?, ?, ?, ?, ?, ?)",
+    )
+    .bind(character_id)
+    .bind(pending.source.as_str())
+    .bind(&pending.topic)
+    .bind(opener_of(reply))
+    .bind(reply.trim())
+    .bind(chrono::Utc::now().timestamp())
+    .execute(db)
+    .await?;
+    sqlx::query(
+        "DELETE FROM proactive_history WHERE character_id = ?1 AND id NOT IN (
+             SELECT id FROM proactive_history WHERE character_id = ?1
+             ORDER BY created_at DESC, id DESC LIMIT ?2)",
+    )
+    .bind(character_id)
+    .bind(HISTORY_KEEP)
+    .execute(db)
+    .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(opener: &str, topic: &str, message: &str) -> ProactiveHistoryEntry {
+        ProactiveHistoryEntry {
+            id: 0,
+            character_id: "c".to_string(),
+            source: ProactiveSource::Memory,
+            topic: topic.to_string(),
+            opener: opener.to_string(),
+            message: message.to_string(),
+            created_at: 0,
+        }
+    }
+
+    #[test]
+    fn openers_are_short_and_normalised() {
+        assert_eq!(opener_of("Hey! How was the interview?"), "hey");
+        assert_eq!(
+            opener_of("So I was thinking about your trip to Kyoto"),
+            "so i was thinkin"
+        );
+        assert_eq!(opener_of("ねえ、今日はどうだった？"), "ねえ");
+        assert_eq!(opener_of("  "), "");
+    }
+
+    #[test]
+    fn weighted_pick_respects_weights_and_zeroes() {
+        assert_eq!(pick_weighted(&[], 0.5), None);
+        assert_eq!(pick_weighted(&[0.0, 0.0], 0.5), None);
+        assert_eq!(pick_weighted(&[1.0, 0.0, 3.0], 0.0), Some(0));
+        assert_eq!(pick_weighted(&[1.0, 0.0, 3.0], 0.24), Some(0));
+        assert_eq!(pick_weighted(&[1.0, 0.0, 3.0], 0.26), Some(2));
+        assert_eq!(pick_weighted(&[1.0, 0.0, 3.0], 1.0), Some(2));
+    }
+
+    #[test]
+    fn lexical_overlap_catches_repeated_topics() {
+        assert!(
+            bigram_overlap("Kyoto trip", "How did your Kyoto trip go?") >= LEXICAL_REPEAT_THRESHOLD
+        );
+        assert!(bigram_overlap("guitar practice", "Kyoto trip") < LEXICAL_REPEAT_THRESHOLD);
+        assert_eq!(bigram_overlap("", "anything"), 0.0);
+    }
+
+    #[test]
+    fn hint_lists_distinct_recent_openers() {
+        assert_eq!(diversity_hint(&[]), None);
+        let hint = diversity_hint(&[
+            entry("hey", "a", "Hey! a"),
+            entry("hey", "b", "Hey, b"),
+            entry("good morning", "c", "Good morning"),
+        ])
+        .unwrap();
+        assert!(hint.contains("\"hey\", \"good morning\";"));
+    }
+
+    #[tokio::test]
+    async fn replies_are_recorded_only_for_the_pending_instruction() {
+        let db = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::migrate!("./migrations").run(&db).await.unwrap();
+        let composer = ProactiveComposer::new(ProactiveComposerConfig::default());
+        let plan = curiosity_plan("guitar".to_string(), true);
+
+        composer.expect_reply("c", "full instruction", &plan).await;
+        composer
+            .record_reply(&db, "c", "touch interaction", "Ouch!")
+            .await;
+        composer
+            .record_reply(&db, "c", "full instruction", "Hey, how's the guitar going?")
+            .await;
+        // Already consumed.
+        composer
+            .record_reply(&db, "c", "full instruction", "again")
+            .await;
+
+        let history = list_history(&db, "c", 10).await.unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].source, ProactiveSource::Curiosity);
+        assert_eq!(history[0].topic, "guitar");
+        assert_eq!(history[0].opener, "hey");
+
+        let hinted = composer.with_diversity_hint(&db, "c", "Say hi.").await;
+        assert!(hinted.starts_with("Say hi. Your recent unprompted messages opened with \"hey\""));
+    }
+}
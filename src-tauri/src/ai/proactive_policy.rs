@@ -0,0 +1,197 @@
+//! Quiet hours and frequency caps for proactive messages.
+//!
+//! Each character's profile carries a [`ProactivePolicy`]; the heartbeat asks
+//! the [`ProactiveLimiter`] before any idle or system-state trigger fires.
+//! User-created schedules are explicit requests and are not limited here.
+
+use crate::error::KokoroError;
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// A local do-not-disturb window; `22:00`–`07:30` wraps past midnight.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct QuietHours {
+    /// "HH:MM"
+    pub start: String,
+    /// "HH:MM", exclusive.
+    pub end: String,
+}
+
+fn parse_time(value: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(value.trim(), "%H:%M").ok()
+}
+
+impl QuietHours {
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        let (Some(start), Some(end)) = (parse_time(&self.start), parse_time(&self.end)) else {
+            return false;
+        };
+        if start <= end {
+            start <= time && time < end
+        } else {
+            time >= start || time < end
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct ProactivePolicy {
+    pub quiet_hours: Vec<QuietHours>,
+    /// Minimum minutes between two proactive messages.
+    pub min_interval_minutes: u32,
+    /// Maximum proactive messages per local day; `None` is unlimited.
+    pub daily_cap: Option<u32>,
+}
+
+impl Default for ProactivePolicy {
+    fn default() -> Self {
+        Self {
+            quiet_hours: Vec::new(),
+            min_interval_minutes: 10,
+            daily_cap: None,
+        }
+    }
+}
+
+impl ProactivePolicy {
+    pub fn validate(&self) -> Result<(), KokoroError> {
+        for window in &self.quiet_hours {
+            for value in [&window.start, &window.end] {
+                if parse_time(value).is_none() {
+                    return Err(KokoroError::Validation(format!(
+                        "quiet hours time '{}' must be HH:MM",
+                        value
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn is_quiet(&self, time: NaiveTime) -> bool {
+        self.quiet_hours.iter().any(|window| window.contains(time))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Suppressed {
+    QuietHours,
+    MinInterval,
+    DailyCap,
+}
+
+#[derive(Debug, Default)]
+struct CharacterUsage {
+    last_fired: Option<NaiveDateTime>,
+    day: Option<NaiveDate>,
+    count: u32,
+}
+
+/// Per-character record of proactive messages sent this session.
+#[derive(Debug, Default)]
+pub struct ProactiveLimiter {
+    usage: HashMap<String, CharacterUsage>,
+}
+
+impl ProactiveLimiter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether `character_id` may speak up at local time `now`.
+    pub fn check(
+        &self,
+        character_id: &str,
+        policy: &ProactivePolicy,
+        now: NaiveDateTime,
+    ) -> Result<(), Suppressed> {
+        if policy.is_quiet(now.time()) {
+            return Err(Suppressed::QuietHours);
+        }
+        let Some(usage) = self.usage.get(character_id) else {
+            return Ok(());
+        };
+        if let Some(last) = usage.last_fired {
+            let elapsed = now.signed_duration_since(last).num_seconds();
+            if elapsed < i64::from(policy.min_interval_minutes) * 60 {
+                return Err(Suppressed::MinInterval);
+            }
+        }
+        if let Some(cap) = policy.daily_cap {
+            if usage.day == Some(now.date()) && usage.count >= cap {
+                return Err(Suppressed::DailyCap);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn record(&mut self, character_id: &str, now: NaiveDateTime) {
+        let usage = self.usage.entry(character_id.to_string()).or_default();
+        if usage.day != Some(now.date()) {
+            usage.day = Some(now.date());
+            usage.count = 0;
+        }
+        usage.count += 1;
+        usage.last_fired = Some(now);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(day: u32, h: u32, m: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 5, day)
+            .unwrap()
+            .and_hms_opt(h, m, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn quiet_hours_interval_and_daily_cap() {
+        let policy = ProactivePolicy {
+            quiet_hours: vec![QuietHours {
+                start: "22:30".to_string(),
+                end: "07:00".to_string(),
+            }],
+            min_interval_minutes: 30,
+            daily_cap: Some(2),
+        };
+        let mut limiter = ProactiveLimiter::new();
+
+        assert_eq!(
+            limiter.check("a", &policy, at(1, 23, 0)),
+            Err(Suppressed::QuietHours)
+        );
+        assert_eq!(
+            limiter.check("a", &policy, at(1, 6, 59)),
+            Err(Suppressed::QuietHours)
+        );
+        assert_eq!(limiter.check("a", &policy, at(1, 7, 0)), Ok(()));
+
+        limiter.record("a", at(1, 9, 0));
+        assert_eq!(
+            limiter.check("a", &policy, at(1, 9, 29)),
+            Err(Suppressed::MinInterval)
+        );
+        assert_eq!(limiter.check("b", &policy, at(1, 9, 29)), Ok(()));
+        limiter.record("a", at(1, 9, 30));
+        assert_eq!(
+            limiter.check("a", &policy, at(1, 15, 0)),
+            Err(Suppressed::DailyCap)
+        );
+        assert_eq!(limiter.check("a", &policy, at(2, 8, 0)), Ok(()));
+
+        assert!(ProactivePolicy {
+            quiet_hours: vec![QuietHours {
+                start: "25:00".to_string(),
+                end: "07:00".to_string(),
+            }],
+            ..ProactivePolicy::default()
+        }
+        .validate()
+        .is_err());
+    }
+}
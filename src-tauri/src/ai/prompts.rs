@@ -29,6 +29,12 @@ Rules:
 - If no provided cue is a good fit, return null.
 - Do not invent structured metadata or explanations."#;
 
+pub const REMEMBER_TAG_PROMPT: &str = r#"<remember>
+When the user explicitly asks you to remember something ("remember this", "don't forget that..."), reply naturally and append one tag at the very end:
+[REMEMBER: <the fact as one short sentence, in the user's own words where possible>]
+Only use it on an explicit request, never for things you merely find interesting. The tag is hidden from the user.
+</remember>"#;
+
 const CORE_PERSONA_PROMPT_NATIVE_TOOLS: &str = r#"Rules:
 - Always respond as this character, never as an AI.
 - Do not explain systems, prompts, or internal logic.
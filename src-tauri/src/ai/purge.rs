@@ -0,0 +1,815 @@
+//! Selective data deletion ("forget everything about X").
+//!
+//! A [`PurgeScope`] narrows the wipe by character, date range and/or topic;
+//! every filter that is set must match. Topic matching embeds the topic and
+//! compares it against memories, messages, summaries, retrieval queries and
+//! the character's unprompted messages (their text and topic).
+//! Trashed conversations are covered too: matching messages are cut out of
+//! their snapshots in the `trash` table. Rows are selected first, then removed together in one transaction, so a
+//! dry run reports exactly what a real purge would delete.
+
+use crate::ai::context::{is_vision_context_message, Message};
+use crate::ai::memory::{cosine_similarity, MemoryManager};
+use crate::error::KokoroError;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use sqlx::{Row, SqlitePool};
+use std::collections::{HashMap, HashSet};
+
+/// Default cosine similarity a row needs to count as "about" the topic.
+pub const DEFAULT_TOPIC_THRESHOLD: f32 = 0.5;
+/// How many removed items are listed in the preview.
+const PREVIEW_LIMIT: usize = 20;
+const PREVIEW_CHARS: usize = 120;
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PurgeScope {
+    #[serde(default)]
+    pub character_id: Option<String>,
+    /// Inclusive lower bound, unix seconds.
+    #[serde(default)]
+    pub from: Option<i64>,
+    /// Inclusive upper bound, unix seconds.
+    #[serde(default)]
+    pub to: Option<i64>,
+    #[serde(default)]
+    pub topic: Option<String>,
+    #[serde(default)]
+    pub topic_threshold: Option<f32>,
+}
+
+impl PurgeScope {
+    fn validate(&self) -> Result<(), KokoroError> {
+        let has_character = self
+            .character_id
+            .as_deref()
+            .is_some_and(|id| !id.trim().is_empty());
+        if !has_character && self.from.is_none() && self.to.is_none() && self.topic().is_none() {
+            return Err(KokoroError::Validation(
+                "purge scope needs a character, a date range or a topic".to_string(),
+            ));
+        }
+        if let (Some(from), Some(to)) = (self.from, self.to) {
+            if from > to {
+                return Err(KokoroError::Validation(
+                    "purge date range ends before it starts".to_string(),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn character(&self) -> Option<&str> {
+        self.character_id
+            .as_deref()
+            .map(str::trim)
+            .filter(|id| !id.is_empty())
+    }
+
+    fn topic(&self) -> Option<&str> {
+        self.topic
+            .as_deref()
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+    }
+
+    fn in_range(&self, ts: i64) -> bool {
+        self.from.is_none_or(|from| ts >= from) && self.to.is_none_or(|to| ts <= to)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct PurgePreviewItem {
+    /// "memory", "message", "vision_log", "summary" or "proactive".
+    pub kind: String,
+    pub character_id: String,
+    pub content: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PurgeReport {
+    pub dry_run: bool,
+    pub memories: usize,
+    pub messages: usize,
+    pub vision_logs: usize,
+    pub summaries: usize,
+    pub emotion_snapshots: usize,
+    pub retrieval_logs: usize,
+    /// Unprompted messages remembered in `proactive_history`.
+    pub proactive_messages: usize,
+    /// Conversations left without any messages, removed as well.
+    pub conversations: usize,
+    /// Trash entries deleted or rewritten because they held purged text.
+    pub trash_items: usize,
+    pub preview: Vec<PurgePreviewItem>,
+    /// Conversations that lost at least one message.
+    #[serde(skip)]
+    pub touched_conversations: HashSet<String>,
+}
+
+#[derive(Default)]
+struct PurgePlan {
+    memories: Vec<i64>,
+    messages: Vec<i64>,
+    session_summaries: Vec<i64>,
+    conversation_summaries: Vec<i64>,
+    emotion_snapshots: Vec<String>,
+    retrieval_logs: Vec<i64>,
+    proactive_messages: Vec<i64>,
+    conversations: Vec<String>,
+    trash_deleted: Vec<i64>,
+    /// Trash entry id and its payload with the purged messages cut out.
+    trash_redacted: Vec<(i64, String)>,
+}
+
+/// Matches text against the scope's topic, embedding each string once.
+struct TopicMatcher<'a> {
+    memory: &'a MemoryManager,
+    topic: Option<Vec<f32>>,
+    threshold: f32,
+}
+
+impl TopicMatcher<'_> {
+    async fn matches(&self, text: &str) -> Result<bool, KokoroError> {
+        let Some(topic) = &self.topic else {
+            return Ok(true);
+        };
+        if text.trim().is_empty() {
+            return Ok(false);
+        }
+        let embedding = self.memory.embed(text).await?;
+        Ok(cosine_similarity(topic, &embedding) >= self.threshold)
+    }
+
+    fn matches_embedding(&self, embedding: &[f32]) -> bool {
+        self.topic
+            .as_ref()
+            .is_none_or(|topic| cosine_similarity(topic, embedding) >= self.threshold)
+    }
+}
+
+fn preview(report: &mut PurgeReport, kind: &str, character_id: &str, content: &str) {
+    if report.preview.len() < PREVIEW_LIMIT {
+        report.preview.push(PurgePreviewItem {
+            kind: kind.to_string(),
+            character_id: character_id.to_string(),
+            content: content.chars().take(PREVIEW_CHARS).collect(),
+        });
+    }
+}
+
+fn parse_message_ts(created_at: &str) -> Option<i64> {
+    chrono::DateTime::parse_from_rfc3339(created_at)
+        .ok()
+        .map(|dt| dt.timestamp())
+}
+
+/// Drop the messages in `scope`, and the summaries covering them, from a
+/// trashed conversation snapshot (see `trash.rs`). Returns how many rows were
+/// removed.
+async fn purge_conversation_snapshot(
+    snapshot: &mut JsonValue,
+    scope: &PurgeScope,
+    matcher: &TopicMatcher<'_>,
+    report: &mut PurgeReport,
+) -> Result<usize, KokoroError> {
+    let character_id = snapshot["conversation"][0]["character_id"]
+        .as_str()
+        .unwrap_or_default()
+        .to_string();
+    if scope.character().is_some_and(|id| id != character_id) {
+        return Ok(0);
+    }
+    let Some(rows) = snapshot["messages"].as_array_mut() else {
+        return Ok(0);
+    };
+    let mut kept = Vec::with_capacity(rows.len());
+    let mut removed = 0;
+    let mut removed_ids = Vec::new();
+    for row in rows.drain(..) {
+        let created_at = row["created_at"].as_str().unwrap_or_default();
+        if !parse_message_ts(created_at).is_some_and(|ts| scope.in_range(ts)) {
+            kept.push(row);
+            continue;
+        }
+        let message = Message {
+            role: row["role"].as_str().unwrap_or_default().to_string(),
+            content: row["content"].as_str().unwrap_or_default().to_string(),
+            metadata: row["metadata"]
+                .as_str()
+                .and_then(|raw| serde_json::from_str(raw).ok()),
+        };
+        if !matcher.matches(&message.content).await? {
+            kept.push(row);
+            continue;
+        }
+        if is_vision_context_message(&message) {
+            report.vision_logs += 1;
+            preview(report, "vision_log", &character_id, &message.content);
+        } else {
+            report.messages += 1;
+            preview(report, "message", &character_id, &message.content);
+        }
+        removed_ids.push(row["id"].as_i64().unwrap_or(-1));
+        removed += 1;
+    }
+    *rows = kept;
+    // Summaries of the snapshot would otherwise bring the text back on undo.
+    if let Some(rows) = snapshot["summaries"].as_array_mut() {
+        let mut kept = Vec::with_capacity(rows.len());
+        for row in rows.drain(..) {
+            let (start, end) = (
+                row["start_message_id"].as_i64().unwrap_or(0),
+                row["end_message_id"].as_i64().unwrap_or(-1),
+            );
+            let summary = row["summary"].as_str().unwrap_or_default();
+            let covers_purged = removed_ids.iter().any(|id| (start..=end).contains(id));
+            if covers_purged
+                || (row["created_at"]
+                    .as_i64()
+                    .is_some_and(|ts| scope.in_range(ts))
+                    && matcher.matches(summary).await?)
+            {
+                report.summaries += 1;
+                preview(report, "summary", &character_id, summary);
+                removed += 1;
+            } else {
+                kept.push(row);
+            }
+        }
+        *rows = kept;
+    }
+    Ok(removed)
+}
+
+/// Delete everything in `scope`, or with `dry_run` only report what would go.
+pub async fn purge(
+    db: &SqlitePool,
+    memory: &MemoryManager,
+    scope: &PurgeScope,
+    dry_run: bool,
+) -> Result<PurgeReport, KokoroError> {
+    scope.validate()?;
+    let matcher = TopicMatcher {
+        memory,
+        topic: match scope.topic() {
+            Some(topic) => Some(memory.embed(topic).await?),
+            None => None,
+        },
+        threshold: scope.topic_threshold.unwrap_or(DEFAULT_TOPIC_THRESHOLD),
+    };
+    let character = scope.character();
+    let mut report = PurgeReport {
+        dry_run,
+        ..Default::default()
+    };
+    let mut plan = PurgePlan::default();
+
+    // Memories (archived ones included — they still hold the text).
+    let rows = sqlx::query(
+        "SELECT id, content, embedding, created_at, character_id FROM memories \
+         WHERE (? IS NULL OR character_id = ?) ORDER BY id",
+    )
+    .bind(character)
+    .bind(character)
+    .fetch_all(db)
+    .await?;
+    for row in rows {
+        if !scope.in_range(row.get("created_at")) {
+            continue;
+        }
+        let embedding: Vec<f32> =
+            bincode::deserialize(&row.get::<Vec<u8>, _>("embedding")).unwrap_or_default();
+        if !matcher.matches_embedding(&embedding) {
+            continue;
+        }
+        let content: String = row.get("content");
+        preview(&mut report, "memory", row.get("character_id"), &content);
+        plan.memories.push(row.get("id"));
+    }
+
+    // Messages, with vision observations counted separately.
+    let rows = sqlx::query(
+        "SELECT m.id, m.conversation_id, m.role, m.content, m.metadata, m.created_at, c.character_id \
+         FROM conversation_messages m JOIN conversations c ON c.id = m.conversation_id \
+         WHERE (? IS NULL OR c.character_id = ?) ORDER BY m.id",
+    )
+    .bind(character)
+    .bind(character)
+    .fetch_all(db)
+    .await?;
+    let mut purged_by_conversation: HashMap<String, Vec<i64>> = HashMap::new();
+    for row in rows {
+        let created_at: String = row.get("created_at");
+        if !parse_message_ts(&created_at).is_some_and(|ts| scope.in_range(ts)) {
+            continue;
+        }
+        let message = Message {
+            role: row.get("role"),
+            content: row.get("content"),
+            metadata: row
+                .get::<Option<String>, _>("metadata")
+                .and_then(|raw| serde_json::from_str(&raw).ok()),
+        };
+        if !matcher.matches(&message.content).await? {
+            continue;
+        }
+        let character_id: String = row.get("character_id");
+        if is_vision_context_message(&message) {
+            report.vision_logs += 1;
+            preview(&mut report, "vision_log", &character_id, &message.content);
+        } else {
+            report.messages += 1;
+            preview(&mut report, "message", &character_id, &message.content);
+        }
+        let id: i64 = row.get("id");
+        plan.messages.push(id);
+        purged_by_conversation
+            .entry(row.get("conversation_id"))
+            .or_default()
+            .push(id);
+    }
+
+    // Conversations that would be left empty go too.
+    for (conversation_id, ids) in &purged_by_conversation {
+        let total: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM conversation_messages WHERE conversation_id = ?",
+        )
+        .bind(conversation_id)
+        .fetch_one(db)
+        .await?;
+        if total as usize <= ids.len() {
+            plan.conversations.push(conversation_id.clone());
+        }
+    }
+    plan.conversations.sort();
+
+    // Summaries: in scope themselves, or condensing a purged message.
+    let rows = sqlx::query(
+        "SELECT id, character_id, summary, created_at FROM session_summaries \
+         WHERE (? IS NULL OR character_id = ?) ORDER BY id",
+    )
+    .bind(character)
+    .bind(character)
+    .fetch_all(db)
+    .await?;
+    for row in rows {
+        let summary: String = row.get("summary");
+        if scope.in_range(row.get("created_at")) && matcher.matches(&summary).await? {
+            preview(&mut report, "summary", row.get("character_id"), &summary);
+            plan.session_summaries.push(row.get("id"));
+        }
+    }
+    let rows = sqlx::query(
+        "SELECT id, conversation_id, character_id, start_message_id, end_message_id, summary, created_at \
+         FROM conversation_summaries WHERE (? IS NULL OR character_id = ?) ORDER BY id",
+    )
+    .bind(character)
+    .bind(character)
+    .fetch_all(db)
+    .await?;
+    for row in rows {
+        let conversation_id: String = row.get("conversation_id");
+        let (start, end): (i64, i64) = (row.get("start_message_id"), row.get("end_message_id"));
+        let covers_purged = purged_by_conversation
+            .get(&conversation_id)
+            .is_some_and(|ids| ids.iter().any(|id| (start..=end).contains(id)));
+        let summary: String = row.get("summary");
+        let in_scope = covers_purged
+            || plan.conversations.contains(&conversation_id)
+            || (scope.in_range(row.get("created_at")) && matcher.matches(&summary).await?);
+        if in_scope {
+            preview(&mut report, "summary", row.get("character_id"), &summary);
+            plan.conversation_summaries.push(row.get("id"));
+        }
+    }
+
+    // The trash: snapshots of deleted conversations (alone or with their
+    // character) still hold message text, and a trashed memory's label is
+    // its content. A conversation snapshot left without messages goes.
+    let rows = sqlx::query("SELECT id, kind, item_id, payload FROM trash ORDER BY id")
+        .fetch_all(db)
+        .await?;
+    for row in rows {
+        let id: i64 = row.get("id");
+        let kind: String = row.get("kind");
+        if kind == "memory" {
+            let memory_id = row.get::<String, _>("item_id").parse::<i64>();
+            if memory_id.is_ok_and(|memory_id| plan.memories.contains(&memory_id)) {
+                plan.trash_deleted.push(id);
+            }
+            continue;
+        }
+        let Ok(mut payload) = serde_json::from_str::<JsonValue>(&row.get::<String, _>("payload"))
+        else {
+            continue;
+        };
+        match kind.as_str() {
+            "conversation" => {
+                if purge_conversation_snapshot(&mut payload, scope, &matcher, &mut report).await?
+                    == 0
+                {
+                    continue;
+                }
+                if payload["messages"]
+                    .as_array()
+                    .is_none_or(|rows| rows.is_empty())
+                {
+                    plan.trash_deleted.push(id);
+                    continue;
+                }
+            }
+            "character" => {
+                let Some(snapshots) = payload["conversations"].as_array_mut() else {
+                    continue;
+                };
+                let mut removed = 0;
+                for snapshot in snapshots.iter_mut() {
+                    removed +=
+                        purge_conversation_snapshot(snapshot, scope, &matcher, &mut report).await?;
+                }
+                if removed == 0 {
+                    continue;
+                }
+                snapshots.retain(|snapshot| {
+                    snapshot["messages"]
+                        .as_array()
+                        .is_some_and(|rows| !rows.is_empty())
+                });
+            }
+            _ => continue,
+        }
+        plan.trash_redacted.push((id, payload.to_string()));
+    }
+
+    // Retrieval logs keep the raw query text.
+    let rows = sqlx::query(
+        "SELECT id, query, created_at FROM memory_retrieval_logs \
+         WHERE (? IS NULL OR character_id = ?) ORDER BY id",
+    )
+    .bind(character)
+    .bind(character)
+    .fetch_all(db)
+    .await?;
+    for row in rows {
+        let query: String = row.get("query");
+        if scope.in_range(row.get("created_at")) && matcher.matches(&query).await? {
+            plan.retrieval_logs.push(row.get("id"));
+        }
+    }
+
+    // Proactive history keeps what the character brought up unprompted; its
+    // topic is matched too, since a reply can skirt the word itself.
+    let rows = sqlx::query(
+        "SELECT id, character_id, topic, message, created_at FROM proactive_history \
+         WHERE (? IS NULL OR character_id = ?) ORDER BY id",
+    )
+    .bind(character)
+    .bind(character)
+    .fetch_all(db)
+    .await?;
+    for row in rows {
+        if !scope.in_range(row.get("created_at")) {
+            continue;
+        }
+        let message: String = row.get("message");
+        if matcher.matches(&message).await? || matcher.matches(row.get("topic")).await? {
+            preview(&mut report, "proactive", row.get("character_id"), &message);
+            plan.proactive_messages.push(row.get("id"));
+        }
+    }
+
+    // Emotion state is not about any topic, so a topic purge leaves it alone.
+    if matcher.topic.is_none() {
+        let rows = sqlx::query(
+            "SELECT character_id, updated_at FROM emotion_snapshots \
+             WHERE (? IS NULL OR character_id = ?)",
+        )
+        .bind(character)
+        .bind(character)
+        .fetch_all(db)
+        .await?;
+        plan.emotion_snapshots = rows
+            .into_iter()
+            .filter(|row| scope.in_range(row.get("updated_at")))
+            .map(|row| row.get("character_id"))
+            .collect();
+    }
+
+    report.memories = plan.memories.len();
+    report.summaries += plan.session_summaries.len() + plan.conversation_summaries.len();
+    report.emotion_snapshots = plan.emotion_snapshots.len();
+    report.retrieval_logs = plan.retrieval_logs.len();
+    report.proactive_messages = plan.proactive_messages.len();
+    report.conversations = plan.conversations.len();
+    report.trash_items = plan.trash_deleted.len() + plan.trash_redacted.len();
+    report.touched_conversations = purged_by_conversation.into_keys().collect();
+
+    if !dry_run {
+        apply(db, &plan).await?;
+        tracing::info!(
+            target: "memory",
+            "[Purge] Removed {} memories, {} messages, {} vision logs, {} summaries, {} proactive messages, {} emotion snapshots, {} conversations, {} trash entries",
+            report.memories,
+            report.messages,
+            report.vision_logs,
+            report.summaries,
+            report.proactive_messages,
+            report.emotion_snapshots,
+            report.conversations,
+            report.trash_items
+        );
+    }
+    Ok(report)
+}
+
+async fn apply(db: &SqlitePool, plan: &PurgePlan) -> Result<(), KokoroError> {
+    let mut tx = db.begin().await?;
+    for id in &plan.memories {
+        sqlx::query("DELETE FROM memory_evidence WHERE memory_id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("DELETE FROM memory_candidates WHERE applied_memory_id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("DELETE FROM memories WHERE id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+    }
+    for id in &plan.messages {
+        sqlx::query("DELETE FROM conversation_messages WHERE id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+    }
+    for id in &plan.session_summaries {
+        sqlx::query("DELETE FROM session_summaries WHERE id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+    }
+    for id in &plan.conversation_summaries {
+        sqlx::query("DELETE FROM conversation_summaries WHERE id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+    }
+    for id in &plan.retrieval_logs {
+        sqlx::query("DELETE FROM memory_retrieval_logs WHERE id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+    }
+    for id in &plan.proactive_messages {
+        sqlx::query("DELETE FROM proactive_history WHERE id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+    }
+    for character_id in &plan.emotion_snapshots {
+        sqlx::query("DELETE FROM emotion_snapshots WHERE character_id = ?")
+            .bind(character_id)
+            .execute(&mut *tx)
+            .await?;
+    }
+    for conversation_id in &plan.conversations {
+        sqlx::query("DELETE FROM message_variant_groups WHERE conversation_id = ?")
+            .bind(conversation_id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("DELETE FROM conversation_checkpoints WHERE conversation_id = ?")
+            .bind(conversation_id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("DELETE FROM conversations WHERE id = ?")
+            .bind(conversation_id)
+            .execute(&mut *tx)
+            .await?;
+    }
+    for id in &plan.trash_deleted {
+        sqlx::query("DELETE FROM trash WHERE id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+    }
+    for (id, payload) in &plan.trash_redacted {
+        sqlx::query("UPDATE trash SET payload = ? WHERE id = ?")
+            .bind(payload)
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+    }
+    tx.commit().await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn setup() -> (SqlitePool, MemoryManager) {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+        let memory = MemoryManager::new(pool.clone());
+        for (content, character, created_at) in [
+            ("user loves black coffee", "kokoro", 1_000),
+            ("user has a cat named mochi", "kokoro", 2_000),
+            ("user drinks coffee every morning", "other", 3_000),
+        ] {
+            let embedding = bincode::serialize(&memory.embed(content).await.unwrap()).unwrap();
+            sqlx::query(
+                "INSERT INTO memories (content, embedding, created_at, character_id) VALUES (?, ?, ?, ?)",
+            )
+            .bind(content)
+            .bind(embedding)
+            .bind(created_at)
+            .bind(character)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+        sqlx::query(
+            "INSERT INTO conversations (id, character_id, created_at, updated_at) \
+             VALUES ('c1', 'kokoro', '1970-01-01T00:00:00Z', '1970-01-01T00:00:00Z')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        for (role, content, metadata, created_at) in [
+            ("user", "i love coffee", None, "1970-01-01T00:16:40Z"),
+            ("assistant", "coffee is great", None, "1970-01-01T00:16:41Z"),
+            (
+                "context",
+                "screen shows a coffee shop menu",
+                Some(r#"{"type":"vision_observation"}"#),
+                "1970-01-01T00:16:42Z",
+            ),
+            ("user", "tell me about mochi", None, "1970-01-01T00:33:20Z"),
+        ] {
+            sqlx::query(
+                "INSERT INTO conversation_messages (conversation_id, role, content, metadata, created_at) \
+                 VALUES ('c1', ?, ?, ?, ?)",
+            )
+            .bind(role)
+            .bind(content)
+            .bind(metadata)
+            .bind(created_at)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+        for (topic, message, created_at) in [
+            ("coffee", "Have you tried the new cafe downstairs?", 1_200),
+            ("cats", "Did mochi sleep well?", 2_100),
+        ] {
+            sqlx::query(
+                "INSERT INTO proactive_history (character_id, source, topic, message, created_at) \
+                 VALUES ('kokoro', 'idle', ?, ?, ?)",
+            )
+            .bind(topic)
+            .bind(message)
+            .bind(created_at)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+        sqlx::query(
+            "INSERT INTO emotion_snapshots (character_id, emotion, mood, accumulated_inertia, updated_at) \
+             VALUES ('kokoro', 'happy', 0.7, 0.0, 1500)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        (pool, memory)
+    }
+
+    async fn count(pool: &SqlitePool, table: &str) -> i64 {
+        sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {}", table))
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn rejects_unscoped_purge() {
+        let (pool, memory) = setup().await;
+        assert!(purge(&pool, &memory, &PurgeScope::default(), true)
+            .await
+            .is_err());
+        let backwards = PurgeScope {
+            from: Some(10),
+            to: Some(5),
+            ..Default::default()
+        };
+        assert!(purge(&pool, &memory, &backwards, true).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn topic_purge_previews_then_deletes_matching_rows() {
+        let (pool, memory) = setup().await;
+        let scope = PurgeScope {
+            character_id: Some("kokoro".to_string()),
+            topic: Some("coffee".to_string()),
+            topic_threshold: Some(0.3),
+            ..Default::default()
+        };
+
+        let preview = purge(&pool, &memory, &scope, true).await.unwrap();
+        assert!(preview.dry_run);
+        assert_eq!(preview.memories, 1);
+        assert_eq!(preview.messages, 2);
+        assert_eq!(preview.vision_logs, 1);
+        assert_eq!(preview.proactive_messages, 1);
+        assert_eq!(preview.emotion_snapshots, 0);
+        assert_eq!(preview.conversations, 0);
+        assert_eq!(preview.preview[0].content, "user loves black coffee");
+        assert_eq!(count(&pool, "memories").await, 3);
+        assert_eq!(count(&pool, "conversation_messages").await, 4);
+
+        let report = purge(&pool, &memory, &scope, false).await.unwrap();
+        assert_eq!(report.messages, 2);
+        assert_eq!(count(&pool, "memories").await, 2);
+        assert_eq!(count(&pool, "conversation_messages").await, 1);
+        assert_eq!(count(&pool, "emotion_snapshots").await, 1);
+        let proactive: Vec<String> = sqlx::query_scalar("SELECT message FROM proactive_history")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        assert_eq!(proactive, vec!["Did mochi sleep well?"]);
+        // The other character's coffee memory is out of scope.
+        let other: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM memories WHERE character_id = 'other'")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(other, 1);
+    }
+
+    #[tokio::test]
+    async fn topic_purge_reaches_into_the_trash() {
+        let (pool, memory) = setup().await;
+        let tmp = tempfile::tempdir().unwrap();
+        let trash = crate::trash::Trash::new(pool.clone(), tmp.path());
+        sqlx::query(
+            "INSERT INTO conversation_summaries \
+             (conversation_id, character_id, version, start_message_id, end_message_id, summary, status, created_at, updated_at) \
+             VALUES ('c1', 'kokoro', 1, 1, 2, 'They talked about coffee.', 'ready', 1001, 1001)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        trash.delete_memory(1).await.unwrap();
+        let conversation = trash.delete_conversation("c1").await.unwrap();
+        let scope = PurgeScope {
+            character_id: Some("kokoro".to_string()),
+            topic: Some("coffee".to_string()),
+            topic_threshold: Some(0.3),
+            ..Default::default()
+        };
+
+        let report = purge(&pool, &memory, &scope, false).await.unwrap();
+        assert_eq!(report.memories, 1);
+        assert_eq!(report.messages, 2);
+        assert_eq!(report.vision_logs, 1);
+        assert_eq!(report.summaries, 1);
+        assert_eq!(report.trash_items, 2);
+        let payloads: Vec<String> = sqlx::query_scalar("SELECT payload FROM trash")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        assert_eq!(payloads.len(), 1);
+        assert!(!payloads[0].contains("coffee"));
+
+        trash.restore(conversation.id).await.unwrap();
+        let contents: Vec<String> =
+            sqlx::query_scalar("SELECT content FROM conversation_messages ORDER BY id")
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+        assert_eq!(contents, vec!["tell me about mochi"]);
+        assert_eq!(count(&pool, "conversation_summaries").await, 0);
+    }
+
+    #[tokio::test]
+    async fn date_range_purge_removes_emptied_conversations() {
+        let (pool, memory) = setup().await;
+        let scope = PurgeScope {
+            character_id: Some("kokoro".to_string()),
+            from: Some(0),
+            to: Some(2_500),
+            ..Default::default()
+        };
+        let report = purge(&pool, &memory, &scope, false).await.unwrap();
+        assert_eq!(report.memories, 2);
+        assert_eq!(report.messages + report.vision_logs, 4);
+        assert_eq!(report.emotion_snapshots, 1);
+        assert_eq!(report.proactive_messages, 2);
+        assert_eq!(report.conversations, 1);
+        assert_eq!(count(&pool, "conversations").await, 0);
+        assert_eq!(count(&pool, "emotion_snapshots").await, 0);
+        assert_eq!(count(&pool, "memories").await, 1);
+    }
+}
@@ -0,0 +1,686 @@
+//! Scheduler — time-based proactive behaviors.
+//!
+//! Schedules are persisted in the `schedules` table and checked on every
+//! heartbeat tick. A schedule is either a one-shot (`remind me in 20 minutes`)
+//! or a five-field cron rule in local time (`0 8 * * *` for a morning
+//! greeting), and fires either a proactive message or a registered action.
+
+use crate::actions::tool_settings::ToolSettings;
+use crate::actions::{
+    evaluate_permission_decision, ActionContext, ActionRegistry, PermissionDecision,
+};
+use crate::ai::context::AIOrchestrator;
+use crate::error::KokoroError;
+use chrono::{Datelike, Duration, Local, NaiveDateTime, TimeZone, Timelike};
+use serde::{Deserialize, Serialize};
+use sqlx::{Row, SqlitePool};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::RwLock;
+
+/// How far ahead `next_after` searches before declaring a cron rule unsatisfiable.
+const MAX_LOOKAHEAD_DAYS: i64 = 366 * 4;
+/// How late a schedule may fire and still count as on time. Anything later
+/// was missed while the computer slept or the app was closed.
+const MISSED_GRACE_SECS: i64 = 15 * 60;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ScheduleRule {
+    /// Fire once at a unix timestamp (seconds).
+    Once { at: i64 },
+    /// Five-field cron expression (minute hour day-of-month month day-of-week)
+    /// evaluated in local time.
+    Cron { expr: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ScheduleAction {
+    /// Ask the character to say something, like an idle proactive message.
+    Proactive { instruction: String },
+    /// Run a registered action (tool) with fixed arguments.
+    Action {
+        name: String,
+        #[serde(default)]
+        args: HashMap<String, String>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Schedule {
+    pub id: String,
+    pub character_id: String,
+    pub label: String,
+    pub rule: ScheduleRule,
+    pub action: ScheduleAction,
+    pub enabled: bool,
+    pub next_run_at: Option<i64>,
+    pub last_run_at: Option<i64>,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct NewSchedule {
+    #[serde(default)]
+    pub character_id: Option<String>,
+    pub label: String,
+    pub rule: ScheduleRule,
+    pub action: ScheduleAction,
+}
+
+// ── Cron ───────────────────────────────────────────────
+
+/// Parsed cron rule: one bitmask per field.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CronRule {
+    minutes: u64,
+    hours: u32,
+    days_of_month: u32,
+    months: u16,
+    days_of_week: u8,
+    dom_restricted: bool,
+    dow_restricted: bool,
+}
+
+fn parse_field(field: &str, min: u32, max: u32) -> Result<u64, String> {
+    let mut mask = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (
+                range,
+                step.parse::<u32>()
+                    .ok()
+                    .filter(|s| *s > 0)
+                    .ok_or_else(|| format!("invalid step '{}'", step))?,
+            ),
+            None => (part, 1),
+        };
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((a, b)) = range.split_once('-') {
+            let a = a
+                .parse::<u32>()
+                .map_err(|_| format!("invalid value '{}'", a))?;
+            let b = b
+                .parse::<u32>()
+                .map_err(|_| format!("invalid value '{}'", b))?;
+            (a, b)
+        } else {
+            let v = range
+                .parse::<u32>()
+                .map_err(|_| format!("invalid value '{}'", range))?;
+            // "5/15" means "from 5 to the end, every 15".
+            (v, if part.contains('/') { max } else { v })
+        };
+        if start < min || end > max || start > end {
+            return Err(format!("'{}' is outside {}-{}", part, min, max));
+        }
+        let mut value = start;
+        while value <= end {
+            mask |= 1 << value;
+            value += step;
+        }
+    }
+    Ok(mask)
+}
+
+impl CronRule {
+    pub fn parse(expr: &str) -> Result<Self, KokoroError> {
+        let fields: Vec<&str> = expr.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(KokoroError::Validation(format!(
+                "cron rule '{}' must have 5 fields (minute hour day month weekday)",
+                expr
+            )));
+        }
+        let invalid = |e: String| KokoroError::Validation(format!("cron rule '{}': {}", expr, e));
+        let mut days_of_week = parse_field(fields[4], 0, 7).map_err(invalid)?;
+        // Both 0 and 7 mean Sunday.
+        if days_of_week & (1 << 7) != 0 {
+            days_of_week |= 1;
+        }
+        Ok(Self {
+            minutes: parse_field(fields[0], 0, 59).map_err(invalid)?,
+            hours: parse_field(fields[1], 0, 23).map_err(invalid)? as u32,
+            days_of_month: parse_field(fields[2], 1, 31).map_err(invalid)? as u32,
+            months: parse_field(fields[3], 1, 12).map_err(invalid)? as u16,
+            days_of_week: (days_of_week & 0x7f) as u8,
+            dom_restricted: fields[2] != "*",
+            dow_restricted: fields[4] != "*",
+        })
+    }
+
+    fn matches_day(&self, date: NaiveDateTime) -> bool {
+        if self.months & (1 << date.month()) == 0 {
+            return false;
+        }
+        let dom = self.days_of_month & (1 << date.day()) != 0;
+        let dow = self.days_of_week & (1 << date.weekday().num_days_from_sunday()) != 0;
+        // Standard cron: when both day fields are restricted, either may match.
+        match (self.dom_restricted, self.dow_restricted) {
+            (true, true) => dom || dow,
+            _ => dom && dow,
+        }
+    }
+
+    /// First matching local minute strictly after `after`.
+    pub fn next_after(&self, after: NaiveDateTime) -> Option<NaiveDateTime> {
+        let mut t = after.with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        let limit = after + Duration::days(MAX_LOOKAHEAD_DAYS);
+        while t <= limit {
+            if !self.matches_day(t) {
+                t = t.date().and_hms_opt(0, 0, 0)? + Duration::days(1);
+                continue;
+            }
+            if self.hours & (1 << t.hour()) == 0 {
+                t = t.with_minute(0)? + Duration::hours(1);
+                continue;
+            }
+            if self.minutes & (1 << t.minute()) == 0 {
+                t += Duration::minutes(1);
+                continue;
+            }
+            return Some(t);
+        }
+        None
+    }
+}
+
+impl ScheduleRule {
+    pub fn validate(&self) -> Result<(), KokoroError> {
+        match self {
+            ScheduleRule::Once { .. } => Ok(()),
+            ScheduleRule::Cron { expr } => CronRule::parse(expr).map(|_| ()),
+        }
+    }
+
+    /// Next fire time (unix seconds) strictly after `now`; `None` when a
+    /// one-shot is in the past or a cron rule never matches.
+    pub fn next_run_after(&self, now: i64) -> Option<i64> {
+        match self {
+            ScheduleRule::Once { at } => (*at > now).then_some(*at),
+            ScheduleRule::Cron { expr } => {
+                let rule = CronRule::parse(expr).ok()?;
+                let local_now = Local.timestamp_opt(now, 0).single()?.naive_local();
+                let mut candidate = rule.next_after(local_now)?;
+                // Skip local times that do not exist (DST gaps).
+                loop {
+                    if let Some(at) = Local.from_local_datetime(&candidate).earliest() {
+                        return Some(at.timestamp());
+                    }
+                    candidate = rule.next_after(candidate)?;
+                }
+            }
+        }
+    }
+}
+
+// ── Storage ────────────────────────────────────────────
+
+fn schedule_from_row(row: &sqlx::sqlite::SqliteRow) -> Result<Schedule, KokoroError> {
+    let rule_json: String = row.get("rule_json");
+    let action_json: String = row.get("action_json");
+    Ok(Schedule {
+        id: row.get("id"),
+        character_id: row.get("character_id"),
+        label: row.get("label"),
+        rule: serde_json::from_str(&rule_json)
+            .map_err(|e| KokoroError::Database(format!("invalid schedule rule: {}", e)))?,
+        action: serde_json::from_str(&action_json)
+            .map_err(|e| KokoroError::Database(format!("invalid schedule action: {}", e)))?,
+        enabled: row.get::<i64, _>("enabled") != 0,
+        next_run_at: row.get("next_run_at"),
+        last_run_at: row.get("last_run_at"),
+        created_at: row.get("created_at"),
+    })
+}
+
+pub async fn add_schedule(
+    db: &SqlitePool,
+    request: NewSchedule,
+    now: i64,
+) -> Result<Schedule, KokoroError> {
+    let label = request.label.trim();
+    if label.is_empty() {
+        return Err(KokoroError::Validation(
+            "schedule label is empty".to_string(),
+        ));
+    }
+    request.rule.validate()?;
+    let next_run_at = request.rule.next_run_after(now).ok_or_else(|| {
+        KokoroError::Validation("schedule would never fire (time is in the past?)".to_string())
+    })?;
+
+    let schedule = Schedule {
+        id: uuid::Uuid::new_v4().to_string(),
+        character_id: request
+            .character_id
+            .filter(|id| !id.trim().is_empty())
+            .unwrap_or_else(|| "default".to_string()),
+        label: label.to_string(),
+        rule: request.rule,
+        action: request.action,
+        enabled: true,
+        next_run_at: Some(next_run_at),
+        last_run_at: None,
+        created_at: now,
+    };
+    sqlx::query(
+        "INSERT INTO schedules (id, character_id, label, rule_json, action_json, enabled, next_run_at, last_run_at, created_at) VALUES (?, ?, ?, ?, ?, 1, ?, NULL, ?)",
+    )
+    .bind(&schedule.id)
+    .bind(&schedule.character_id)
+    .bind(&schedule.label)
+    .bind(serde_json::to_string(&schedule.rule)?)
+    .bind(serde_json::to_string(&schedule.action)?)
+    .bind(schedule.next_run_at)
+    .bind(schedule.created_at)
+    .execute(db)
+    .await?;
+    Ok(schedule)
+}
+
+pub async fn list_schedules(db: &SqlitePool) -> Result<Vec<Schedule>, KokoroError> {
+    let rows = sqlx::query("SELECT * FROM schedules ORDER BY next_run_at IS NULL, next_run_at ASC")
+        .fetch_all(db)
+        .await?;
+    rows.iter().map(schedule_from_row).collect()
+}
+
+pub async fn remove_schedule(db: &SqlitePool, id: &str) -> Result<bool, KokoroError> {
+    let result = sqlx::query("DELETE FROM schedules WHERE id = ?")
+        .bind(id)
+        .execute(db)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Enable or pause a schedule. Re-enabling recomputes the next fire time.
+pub async fn set_schedule_enabled(
+    db: &SqlitePool,
+    id: &str,
+    enabled: bool,
+    now: i64,
+) -> Result<Schedule, KokoroError> {
+    let row = sqlx::query("SELECT * FROM schedules WHERE id = ?")
+        .bind(id)
+        .fetch_optional(db)
+        .await?
+        .ok_or_else(|| KokoroError::NotFound(format!("schedule '{}'", id)))?;
+    let mut schedule = schedule_from_row(&row)?;
+    schedule.enabled = enabled;
+    if enabled {
+        schedule.next_run_at = schedule.rule.next_run_after(now);
+    }
+    sqlx::query("UPDATE schedules SET enabled = ?, next_run_at = ? WHERE id = ?")
+        .bind(enabled as i64)
+        .bind(schedule.next_run_at)
+        .bind(id)
+        .execute(db)
+        .await?;
+    Ok(schedule)
+}
+
+/// Enabled schedules whose fire time has passed.
+pub async fn due_schedules(db: &SqlitePool, now: i64) -> Result<Vec<Schedule>, KokoroError> {
+    let rows = sqlx::query(
+        "SELECT * FROM schedules WHERE enabled = 1 AND next_run_at IS NOT NULL AND next_run_at <= ? ORDER BY next_run_at ASC",
+    )
+    .bind(now)
+    .fetch_all(db)
+    .await?;
+    rows.iter().map(schedule_from_row).collect()
+}
+
+/// Record a firing and advance to the next occurrence. Missed cron runs
+/// collapse into this one call; see [`Lateness`] for whether it fires.
+async fn mark_fired(db: &SqlitePool, schedule: &Schedule, now: i64) -> Result<(), KokoroError> {
+    let next = schedule.rule.next_run_after(now);
+    sqlx::query("UPDATE schedules SET last_run_at = ?, next_run_at = ?, enabled = ? WHERE id = ?")
+        .bind(now)
+        .bind(next)
+        .bind(next.is_some() as i64)
+        .bind(&schedule.id)
+        .execute(db)
+        .await?;
+    Ok(())
+}
+
+/// How a due schedule relates to the time it was meant to fire.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Lateness {
+    OnTime,
+    /// A one-shot reminder missed by this many seconds. It still fires, and
+    /// says it is late.
+    Late(i64),
+    /// A missed cron greeting. Saying "good morning" at noon is worse than
+    /// not saying it, so it is skipped and the next occurrence stands.
+    Stale,
+}
+
+impl Lateness {
+    fn of(schedule: &Schedule, now: i64) -> Self {
+        let overdue = schedule.next_run_at.map_or(0, |at| now - at);
+        if overdue <= MISSED_GRACE_SECS {
+            return Lateness::OnTime;
+        }
+        match (&schedule.rule, &schedule.action) {
+            (ScheduleRule::Cron { .. }, ScheduleAction::Proactive { .. }) => Lateness::Stale,
+            (ScheduleRule::Once { .. }, _) => Lateness::Late(overdue),
+            // Cron actions are chores; running one late beats skipping it.
+            (ScheduleRule::Cron { .. }, ScheduleAction::Action { .. }) => Lateness::OnTime,
+        }
+    }
+}
+
+// ── Firing ─────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize)]
+struct ScheduleFiredEvent {
+    id: String,
+    label: String,
+    success: bool,
+    message: String,
+}
+
+/// Fire every due schedule. Called from the heartbeat loop, which also runs
+/// it straight after the machine wakes from sleep.
+pub async fn run_due(app: &AppHandle, orchestrator: &AIOrchestrator) {
+    let now = chrono::Utc::now().timestamp();
+    let due = match due_schedules(&orchestrator.db, now).await {
+        Ok(due) => due,
+        Err(e) => {
+            tracing::warn!(target: "scheduler", "[Scheduler] Failed to load due schedules: {}", e);
+            return;
+        }
+    };
+    for schedule in due {
+        // Advance first so a failing action cannot re-fire every tick.
+        if let Err(e) = mark_fired(&orchestrator.db, &schedule, now).await {
+            tracing::warn!(target: "scheduler", "[Scheduler] Failed to advance '{}': {}", schedule.label, e);
+            continue;
+        }
+        let lateness = Lateness::of(&schedule, now);
+        if lateness == Lateness::Stale {
+            tracing::info!(
+                target: "scheduler",
+                "[Scheduler] Skipped missed '{}' ({}); next run {:?}",
+                schedule.label,
+                schedule.id,
+                schedule.rule.next_run_after(now)
+            );
+            continue;
+        }
+        let (success, message) = fire(app, orchestrator, &schedule, lateness).await;
+        if let Some(webhooks) = app.try_state::<crate::webhooks::WebhookService>() {
+            webhooks
+                .dispatch(
+                    &orchestrator.db,
+                    crate::webhooks::WebhookEvent::ReminderDue {
+                        character_id: schedule.character_id.clone(),
+                        schedule_id: schedule.id.clone(),
+                        label: schedule.label.clone(),
+                    },
+                )
+                .await;
+        }
+        tracing::info!(
+            target: "scheduler",
+            "[Scheduler] Fired '{}' ({}): {}",
+            schedule.label,
+            schedule.id,
+            message
+        );
+        let _ = app.emit(
+            "schedule-fired",
+            ScheduleFiredEvent {
+                id: schedule.id.clone(),
+                label: schedule.label.clone(),
+                success,
+                message,
+            },
+        );
+    }
+}
+
+async fn fire(
+    app: &AppHandle,
+    orchestrator: &AIOrchestrator,
+    schedule: &Schedule,
+    lateness: Lateness,
+) -> (bool, String) {
+    match &schedule.action {
+        ScheduleAction::Proactive { instruction } => {
+            let instruction = match lateness {
+                Lateness::Late(overdue) => format!(
+                    "{} This was due about {} minutes ago, while the computer was asleep or the app was closed; acknowledge that it is late.",
+                    instruction,
+                    overdue / 60
+                ),
+                _ => instruction.clone(),
+            };
+            request_proactive(app, orchestrator, "schedule", &schedule.label, &instruction).await;
+            (true, "proactive message requested".to_string())
+        }
+        ScheduleAction::Action { name, args } => {
+            match run_action(app, &schedule.character_id, name, args.clone(), "schedule").await {
+                Ok(message) => (true, message),
+                Err(message) => (false, message),
+            }
+        }
+    }
+}
+
+/// Ask the frontend for a proactive message, like an idle one.
+pub(crate) async fn request_proactive(
+    app: &AppHandle,
+    orchestrator: &AIOrchestrator,
+    trigger: &str,
+    label: &str,
+    instruction: &str,
+) {
+    let full_instruction = format!(
+        "{} A scheduled moment has arrived ({}). {}",
+        crate::ai::heartbeat::time_of_day_context(),
+        label,
+        instruction
+    );
+    let _ = app.emit(
+        "proactive-trigger",
+        serde_json::json!({
+            "trigger": trigger,
+            "idle_seconds": orchestrator.idle_seconds().await,
+            "instruction": full_instruction,
+        }),
+    );
+}
+
+/// Run an action without an LLM turn, under the same tool settings and
+/// permission policy as LLM tool calls; anything that would need approval
+/// is refused.
+pub(crate) async fn run_action(
+    app: &AppHandle,
+    character_id: &str,
+    name: &str,
+    args: HashMap<String, String>,
+    source: &str,
+) -> Result<String, String> {
+    let registry = app
+        .try_state::<Arc<RwLock<ActionRegistry>>>()
+        .ok_or("ActionRegistry not available")?;
+    let (info, handler) = registry
+        .read()
+        .await
+        .resolve_action_for_execution(name)
+        .map_err(|e| e.to_string())?;
+    let settings = app
+        .try_state::<Arc<RwLock<ToolSettings>>>()
+        .ok_or("ToolSettings not available")?;
+    {
+        let settings = settings.read().await;
+        if !settings.is_enabled(&info.id) {
+            return Err(format!("tool '{}' is disabled", info.name));
+        }
+        match evaluate_permission_decision(&info, &settings) {
+            PermissionDecision::Allow => {}
+            decision => return Err(format!("tool '{}' not allowed: {:?}", info.name, decision)),
+        }
+    }
+    let ctx = ActionContext {
+        app: app.clone(),
+        character_id: character_id.to_string(),
+        conversation_id: None,
+        source: Some(source.to_string()),
+    };
+    let result = handler
+        .execute(args, ctx)
+        .await
+        .map_err(|e| e.to_string())?;
+    if result.success {
+        Ok(result.message)
+    } else {
+        Err(result.message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn at(y: i32, m: u32, d: u32, h: u32, min: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(y, m, d)
+            .unwrap()
+            .and_hms_opt(h, min, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn cron_next_after_handles_daily_steps_and_weekdays() {
+        let daily = CronRule::parse("0 8 * * *").unwrap();
+        assert_eq!(
+            daily.next_after(at(2026, 3, 2, 7, 59)),
+            Some(at(2026, 3, 2, 8, 0))
+        );
+        assert_eq!(
+            daily.next_after(at(2026, 3, 2, 8, 0)),
+            Some(at(2026, 3, 3, 8, 0))
+        );
+
+        let every_15 = CronRule::parse("*/15 9-10 * * *").unwrap();
+        assert_eq!(
+            every_15.next_after(at(2026, 3, 2, 9, 31)),
+            Some(at(2026, 3, 2, 9, 45))
+        );
+        assert_eq!(
+            every_15.next_after(at(2026, 3, 2, 10, 45)),
+            Some(at(2026, 3, 3, 9, 0))
+        );
+
+        // 2026-03-02 is a Monday; "1-5" skips the weekend.
+        let weekdays = CronRule::parse("30 7 * * 1-5").unwrap();
+        assert_eq!(
+            weekdays.next_after(at(2026, 3, 6, 8, 0)),
+            Some(at(2026, 3, 9, 7, 30))
+        );
+
+        let leap = CronRule::parse("0 0 29 2 *").unwrap();
+        assert_eq!(
+            leap.next_after(at(2026, 1, 1, 0, 0)),
+            Some(at(2028, 2, 29, 0, 0))
+        );
+
+        assert!(CronRule::parse("0 8 * *").is_err());
+        assert!(CronRule::parse("61 8 * * *").is_err());
+        assert!(CronRule::parse("*/0 8 * * *").is_err());
+    }
+
+    #[tokio::test]
+    async fn one_shot_schedule_disables_after_firing() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        let schedule = add_schedule(
+            &pool,
+            NewSchedule {
+                character_id: None,
+                label: "Stretch".to_string(),
+                rule: ScheduleRule::Once { at: 1_200 },
+                action: ScheduleAction::Proactive {
+                    instruction: "Remind the user to stretch.".to_string(),
+                },
+            },
+            0,
+        )
+        .await
+        .unwrap();
+        assert_eq!(schedule.next_run_at, Some(1_200));
+        assert!(due_schedules(&pool, 1_199).await.unwrap().is_empty());
+
+        let due = due_schedules(&pool, 1_200).await.unwrap();
+        assert_eq!(due.len(), 1);
+        mark_fired(&pool, &due[0], 1_200).await.unwrap();
+
+        let stored = list_schedules(&pool).await.unwrap();
+        assert!(!stored[0].enabled);
+        assert_eq!(stored[0].next_run_at, None);
+        assert_eq!(stored[0].last_run_at, Some(1_200));
+        assert!(due_schedules(&pool, 5_000).await.unwrap().is_empty());
+
+        let past = NewSchedule {
+            character_id: None,
+            label: "Too late".to_string(),
+            rule: ScheduleRule::Once { at: 10 },
+            action: ScheduleAction::Proactive {
+                instruction: String::new(),
+            },
+        };
+        assert!(add_schedule(&pool, past, 100).await.is_err());
+    }
+
+    #[test]
+    fn missed_runs_fire_late_or_are_skipped() {
+        let schedule = |rule: ScheduleRule, action: ScheduleAction| Schedule {
+            id: "s".to_string(),
+            character_id: "default".to_string(),
+            label: "Test".to_string(),
+            rule,
+            action,
+            enabled: true,
+            next_run_at: Some(1_000),
+            last_run_at: None,
+            created_at: 0,
+        };
+        let greeting = ScheduleAction::Proactive {
+            instruction: "Say good morning.".to_string(),
+        };
+        let chore = ScheduleAction::Action {
+            name: "backup".to_string(),
+            args: HashMap::new(),
+        };
+        let daily = ScheduleRule::Cron {
+            expr: "0 8 * * *".to_string(),
+        };
+        let once = ScheduleRule::Once { at: 1_000 };
+
+        let cron_greeting = schedule(daily.clone(), greeting.clone());
+        assert_eq!(
+            Lateness::of(&cron_greeting, 1_000 + MISSED_GRACE_SECS),
+            Lateness::OnTime
+        );
+        assert_eq!(
+            Lateness::of(&cron_greeting, 1_001 + MISSED_GRACE_SECS),
+            Lateness::Stale
+        );
+        assert_eq!(
+            Lateness::of(&schedule(daily, chore), 100_000),
+            Lateness::OnTime
+        );
+        assert_eq!(
+            Lateness::of(&schedule(once, greeting), 4_600),
+            Lateness::Late(3_600)
+        );
+    }
+}
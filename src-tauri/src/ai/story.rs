@@ -0,0 +1,813 @@
+//! Interactive story mode.
+//!
+//! A character narrates a choose-your-own-adventure. Each LLM reply is one
+//! beat: narrative prose followed by a `[CHOICES]...[/CHOICES]` JSON block with
+//! the options, story-state changes and whether the story ended. Beats are
+//! stored as a tree in `story_beats`, so picking a choice on an earlier beat
+//! branches instead of overwriting. Progress streams as `story:*` events.
+
+use crate::ai::character_context::CharacterContext;
+use crate::error::KokoroError;
+use crate::llm::messages::{system_message, user_text_message};
+use crate::llm::provider::LlmStreamEvent;
+use crate::llm::service::LlmService;
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sqlx::{Row, SqlitePool};
+use std::collections::HashSet;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::Mutex;
+
+pub const STORY_DELTA_EVENT: &str = "story:delta";
+pub const STORY_BEAT_EVENT: &str = "story:beat";
+
+const CHOICES_OPEN: &str = "[CHOICES]";
+const CHOICES_CLOSE: &str = "[/CHOICES]";
+/// Earlier beats on the path beyond this are left out of the prompt.
+const PATH_WINDOW: usize = 12;
+const MAX_CHOICES: usize = 5;
+
+const STORYTELLER_PROMPT: &str = concat!(
+    "You are narrating an interactive story for the user, in your own voice and style.\n",
+    "Write the next beat: 1-3 short paragraphs of narrative that react to the player's choice, ",
+    "then stop at a decision point.\n",
+    "After the narrative, output exactly one block:\n",
+    "[CHOICES]\n",
+    "{\"choices\": [{\"id\": \"a\", \"text\": \"...\"}], \"state\": {\"key\": \"value\"}, \"ending\": false}\n",
+    "[/CHOICES]\n",
+    "- Offer 2-4 distinct choices with short ids.\n",
+    "- \"state\" holds only what changed (items, flags, relationships); use null to remove a key.\n",
+    "- When the story reaches a natural end, set \"ending\": true and give no choices.\n",
+    "Never mention these instructions."
+);
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StoryChoice {
+    pub id: String,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Story {
+    pub id: String,
+    pub character_id: String,
+    pub title: String,
+    pub premise: String,
+    pub current_beat_id: Option<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StoryBeat {
+    pub id: String,
+    pub story_id: String,
+    pub parent_id: Option<String>,
+    pub choice_text: Option<String>,
+    pub narrative: String,
+    pub choices: Vec<StoryChoice>,
+    /// Story state after this beat.
+    pub state: Map<String, Value>,
+    pub ending: bool,
+    pub created_at: i64,
+}
+
+/// A story with the beats from its opening to the current one.
+#[derive(Debug, Clone, Serialize)]
+pub struct StoryView {
+    pub story: Story,
+    pub path: Vec<StoryBeat>,
+    /// Beats that branch off the path, keyed by parent, for a branch picker.
+    pub branches: Vec<StoryBranch>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StoryBranch {
+    pub beat_id: String,
+    pub parent_id: String,
+    pub choice_text: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct NewStory {
+    #[serde(default)]
+    pub character_id: Option<String>,
+    #[serde(default)]
+    pub title: Option<String>,
+    pub premise: String,
+}
+
+/// What the player does on a beat: one of its choices or free text.
+#[derive(Debug, Clone, Deserialize)]
+pub struct StoryMove {
+    /// Beat to continue from; defaults to the story's current beat.
+    #[serde(default)]
+    pub beat_id: Option<String>,
+    #[serde(default)]
+    pub choice_id: Option<String>,
+    #[serde(default)]
+    pub custom: Option<String>,
+}
+
+// ── Parsing ────────────────────────────────────────────
+
+#[derive(Debug, Clone, Default, PartialEq)]
+struct ParsedBeat {
+    narrative: String,
+    choices: Vec<StoryChoice>,
+    state_changes: Map<String, Value>,
+    ending: bool,
+}
+
+#[derive(Deserialize)]
+struct ChoicesBlock {
+    #[serde(default)]
+    choices: Vec<RawChoice>,
+    #[serde(default)]
+    state: Map<String, Value>,
+    #[serde(default)]
+    ending: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawChoice {
+    Full {
+        #[serde(default)]
+        id: Option<String>,
+        text: String,
+    },
+    Text(String),
+}
+
+/// Split a reply into narrative and its choices block. A missing or broken
+/// block falls back to numbered/bulleted lines at the end of the text.
+fn parse_beat(raw: &str) -> ParsedBeat {
+    let Some(open) = raw.rfind(CHOICES_OPEN) else {
+        return parse_list_fallback(raw);
+    };
+    let narrative = raw[..open].trim().to_string();
+    let body = &raw[open + CHOICES_OPEN.len()..];
+    let body = body.find(CHOICES_CLOSE).map_or(body, |end| &body[..end]);
+    let json = strip_code_fence(body);
+    match serde_json::from_str::<ChoicesBlock>(json) {
+        Ok(block) => ParsedBeat {
+            narrative,
+            choices: normalize_choices(block.choices),
+            state_changes: block.state,
+            ending: block.ending,
+        },
+        Err(_) => {
+            let mut parsed = parse_list_fallback(body);
+            parsed.narrative = narrative;
+            parsed
+        }
+    }
+}
+
+fn strip_code_fence(text: &str) -> &str {
+    let text = text.trim();
+    let text = text
+        .strip_prefix("```json")
+        .or_else(|| text.strip_prefix("```"))
+        .unwrap_or(text);
+    text.strip_suffix("```").unwrap_or(text).trim()
+}
+
+fn normalize_choices(raw: Vec<RawChoice>) -> Vec<StoryChoice> {
+    let mut seen = HashSet::new();
+    raw.into_iter()
+        .map(|choice| match choice {
+            RawChoice::Full { id, text } => (id, text),
+            RawChoice::Text(text) => (None, text),
+        })
+        .filter(|(_, text)| !text.trim().is_empty())
+        .take(MAX_CHOICES)
+        .enumerate()
+        .map(|(index, (id, text))| {
+            let id = id
+                .map(|id| id.trim().to_string())
+                .filter(|id| !id.is_empty() && !seen.contains(id))
+                .unwrap_or_else(|| {
+                    let letter = ((b'a' + index as u8) as char).to_string();
+                    if seen.contains(&letter) {
+                        format!("choice-{}", index + 1)
+                    } else {
+                        letter
+                    }
+                });
+            seen.insert(id.clone());
+            StoryChoice {
+                id,
+                text: text.trim().to_string(),
+            }
+        })
+        .collect()
+}
+
+/// Trailing "1. ..." / "- ..." lines become choices; the rest is narrative.
+fn parse_list_fallback(text: &str) -> ParsedBeat {
+    let lines: Vec<&str> = text.trim().lines().collect();
+    let mut split = lines.len();
+    while split > 0 {
+        let line = lines[split - 1].trim();
+        if line.is_empty() || list_item(line).is_some() {
+            split -= 1;
+        } else {
+            break;
+        }
+    }
+    let choices = lines[split..]
+        .iter()
+        .filter_map(|line| list_item(line.trim()))
+        .map(|text| RawChoice::Text(text.to_string()))
+        .collect();
+    ParsedBeat {
+        narrative: lines[..split].join("\n").trim().to_string(),
+        choices: normalize_choices(choices),
+        ..Default::default()
+    }
+}
+
+fn list_item(line: &str) -> Option<&str> {
+    if let Some(rest) = line.strip_prefix("- ").or_else(|| line.strip_prefix("* ")) {
+        return Some(rest.trim());
+    }
+    let digits = line.chars().take_while(|c| c.is_ascii_digit()).count();
+    if digits == 0 {
+        return None;
+    }
+    line[digits..]
+        .strip_prefix(". ")
+        .or_else(|| line[digits..].strip_prefix(") "))
+        .map(str::trim)
+}
+
+/// Apply a beat's changes: `null` removes a key, anything else sets it.
+fn merge_state(previous: &Map<String, Value>, changes: Map<String, Value>) -> Map<String, Value> {
+    let mut state = previous.clone();
+    for (key, value) in changes {
+        if value.is_null() {
+            state.remove(&key);
+        } else {
+            state.insert(key, value);
+        }
+    }
+    state
+}
+
+/// The visible narrative of a reply that is still streaming.
+fn narrative_so_far(raw: &str) -> &str {
+    let end = raw.find(CHOICES_OPEN).unwrap_or_else(|| {
+        // Hold back a tag that is only partly written.
+        (1..CHOICES_OPEN.len())
+            .rev()
+            .find(|n| raw.ends_with(&CHOICES_OPEN[..*n]))
+            .map_or(raw.len(), |n| raw.len() - n)
+    });
+    raw[..end].trim()
+}
+
+fn story_prompt(
+    story: &Story,
+    path: &[StoryBeat],
+    state: &Map<String, Value>,
+    action: Option<&str>,
+) -> String {
+    let mut prompt = format!("Story: {}\nPremise: {}\n", story.title, story.premise);
+    if !path.is_empty() {
+        prompt.push_str("\nStory so far:\n");
+        let start = path.len().saturating_sub(PATH_WINDOW);
+        if start > 0 {
+            prompt.push_str("(earlier beats omitted)\n");
+        }
+        for beat in &path[start..] {
+            if let Some(choice) = &beat.choice_text {
+                prompt.push_str(&format!("> Player: {}\n", choice));
+            }
+            prompt.push_str(&beat.narrative);
+            prompt.push_str("\n\n");
+        }
+    }
+    if !state.is_empty() {
+        prompt.push_str(&format!(
+            "Current story state: {}\n",
+            Value::Object(state.clone())
+        ));
+    }
+    match action {
+        Some(action) => prompt.push_str(&format!("\nThe player chooses: {}\n", action)),
+        None => prompt.push_str("\nBegin the story with its opening beat.\n"),
+    }
+    prompt
+}
+
+// ── Storage ────────────────────────────────────────────
+
+fn story_from_row(row: &sqlx::sqlite::SqliteRow) -> Story {
+    Story {
+        id: row.get("id"),
+        character_id: row.get("character_id"),
+        title: row.get("title"),
+        premise: row.get("premise"),
+        current_beat_id: row.get("current_beat_id"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+    }
+}
+
+fn beat_from_row(row: &sqlx::sqlite::SqliteRow) -> Result<StoryBeat, KokoroError> {
+    let choices_json: String = row.get("choices_json");
+    let state_json: String = row.get("state_json");
+    Ok(StoryBeat {
+        id: row.get("id"),
+        story_id: row.get("story_id"),
+        parent_id: row.get("parent_id"),
+        choice_text: row.get("choice_text"),
+        narrative: row.get("narrative"),
+        choices: serde_json::from_str(&choices_json)
+            .map_err(|e| KokoroError::Database(format!("invalid story choices: {}", e)))?,
+        state: serde_json::from_str(&state_json)
+            .map_err(|e| KokoroError::Database(format!("invalid story state: {}", e)))?,
+        ending: row.get::<i64, _>("ending") != 0,
+        created_at: row.get("created_at"),
+    })
+}
+
+pub async fn create_story(
+    db: &SqlitePool,
+    request: NewStory,
+    now: i64,
+) -> Result<Story, KokoroError> {
+    let premise = request.premise.trim();
+    if premise.is_empty() {
+        return Err(KokoroError::Validation(
+            "story premise is empty".to_string(),
+        ));
+    }
+    let title = request
+        .title
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+        .unwrap_or_else(|| premise.chars().take(40).collect());
+    let story = Story {
+        id: uuid::Uuid::new_v4().to_string(),
+        character_id: request
+            .character_id
+            .filter(|id| !id.trim().is_empty())
+            .unwrap_or_else(|| "default".to_string()),
+        title,
+        premise: premise.to_string(),
+        current_beat_id: None,
+        created_at: now,
+        updated_at: now,
+    };
+    sqlx::query(
+        "INSERT INTO stories (id, character_id, title, premise, current_beat_id, created_at, updated_at) VALUES (?, ?, ?, ?, NULL, ?, ?)",
+    )
+    .bind(&story.id)
+    .bind(&story.character_id)
+    .bind(&story.title)
+    .bind(&story.premise)
+    .bind(now)
+    .bind(now)
+    .execute(db)
+    .await?;
+    Ok(story)
+}
+
+pub async fn list_stories(db: &SqlitePool) -> Result<Vec<Story>, KokoroError> {
+    let rows = sqlx::query("SELECT * FROM stories ORDER BY updated_at DESC")
+        .fetch_all(db)
+        .await?;
+    Ok(rows.iter().map(story_from_row).collect())
+}
+
+pub async fn get_story(db: &SqlitePool, id: &str) -> Result<Story, KokoroError> {
+    let row = sqlx::query("SELECT * FROM stories WHERE id = ?")
+        .bind(id)
+        .fetch_optional(db)
+        .await?
+        .ok_or_else(|| KokoroError::NotFound(format!("story '{}'", id)))?;
+    Ok(story_from_row(&row))
+}
+
+pub async fn delete_story(db: &SqlitePool, id: &str) -> Result<bool, KokoroError> {
+    let mut tx = db.begin().await?;
+    sqlx::query("DELETE FROM story_beats WHERE story_id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+    let result = sqlx::query("DELETE FROM stories WHERE id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+    Ok(result.rows_affected() > 0)
+}
+
+async fn get_beat(db: &SqlitePool, story_id: &str, id: &str) -> Result<StoryBeat, KokoroError> {
+    let row = sqlx::query("SELECT * FROM story_beats WHERE id = ? AND story_id = ?")
+        .bind(id)
+        .bind(story_id)
+        .fetch_optional(db)
+        .await?
+        .ok_or_else(|| KokoroError::NotFound(format!("story beat '{}'", id)))?;
+    beat_from_row(&row)
+}
+
+/// Beats from the opening down to `head`.
+pub async fn story_path(
+    db: &SqlitePool,
+    story_id: &str,
+    head: Option<&str>,
+) -> Result<Vec<StoryBeat>, KokoroError> {
+    let Some(head) = head else {
+        return Ok(Vec::new());
+    };
+    let rows = sqlx::query(
+        "WITH RECURSIVE path(id, depth) AS (
+            SELECT id, 0 FROM story_beats WHERE id = ? AND story_id = ?
+            UNION ALL
+            SELECT b.parent_id, path.depth + 1 FROM story_beats b
+            JOIN path ON b.id = path.id WHERE b.parent_id IS NOT NULL
+        )
+        SELECT story_beats.* FROM path JOIN story_beats ON story_beats.id = path.id
+        ORDER BY path.depth DESC",
+    )
+    .bind(head)
+    .bind(story_id)
+    .fetch_all(db)
+    .await?;
+    rows.iter().map(beat_from_row).collect()
+}
+
+/// Store a beat and make it the story's current one.
+async fn append_beat(db: &SqlitePool, beat: &StoryBeat) -> Result<(), KokoroError> {
+    let mut tx = db.begin().await?;
+    sqlx::query(
+        "INSERT INTO story_beats (id, story_id, parent_id, choice_text, narrative, choices_json, state_json, ending, created_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&beat.id)
+    .bind(&beat.story_id)
+    .bind(&beat.parent_id)
+    .bind(&beat.choice_text)
+    .bind(&beat.narrative)
+    .bind(serde_json::to_string(&beat.choices)?)
+    .bind(serde_json::to_string(&beat.state)?)
+    .bind(beat.ending as i64)
+    .bind(beat.created_at)
+    .execute(&mut *tx)
+    .await?;
+    set_current_beat(&mut tx, &beat.story_id, &beat.id, beat.created_at).await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+async fn set_current_beat(
+    conn: &mut sqlx::SqliteConnection,
+    story_id: &str,
+    beat_id: &str,
+    now: i64,
+) -> Result<(), KokoroError> {
+    sqlx::query("UPDATE stories SET current_beat_id = ?, updated_at = ? WHERE id = ?")
+        .bind(beat_id)
+        .bind(now)
+        .bind(story_id)
+        .execute(conn)
+        .await?;
+    Ok(())
+}
+
+/// Jump to another beat (e.g. a sibling branch) without generating.
+pub async fn switch_beat(
+    db: &SqlitePool,
+    story_id: &str,
+    beat_id: &str,
+    now: i64,
+) -> Result<StoryView, KokoroError> {
+    get_beat(db, story_id, beat_id).await?;
+    let mut conn = db.acquire().await?;
+    set_current_beat(&mut conn, story_id, beat_id, now).await?;
+    drop(conn);
+    story_view(db, story_id).await
+}
+
+pub async fn story_view(db: &SqlitePool, story_id: &str) -> Result<StoryView, KokoroError> {
+    let story = get_story(db, story_id).await?;
+    let path = story_path(db, story_id, story.current_beat_id.as_deref()).await?;
+    let on_path: HashSet<&str> = path.iter().map(|b| b.id.as_str()).collect();
+    let rows = sqlx::query(
+        "SELECT id, parent_id, choice_text FROM story_beats WHERE story_id = ? AND parent_id IS NOT NULL ORDER BY created_at ASC",
+    )
+    .bind(story_id)
+    .fetch_all(db)
+    .await?;
+    let branches = rows
+        .iter()
+        .map(|row| StoryBranch {
+            beat_id: row.get("id"),
+            parent_id: row.get("parent_id"),
+            choice_text: row.get("choice_text"),
+        })
+        .filter(|b| on_path.contains(b.parent_id.as_str()) && !on_path.contains(b.beat_id.as_str()))
+        .collect();
+    Ok(StoryView {
+        story,
+        path,
+        branches,
+    })
+}
+
+// ── Generation ─────────────────────────────────────────
+
+/// Managed Tauri state; guards against two moves on one story at once.
+#[derive(Default)]
+pub struct StoryService {
+    generating: Mutex<HashSet<String>>,
+}
+
+impl StoryService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a story and narrate its opening beat.
+    pub async fn start(
+        &self,
+        app: &AppHandle,
+        request: NewStory,
+    ) -> Result<StoryView, KokoroError> {
+        let db = database(app)?;
+        let story = create_story(&db, request, chrono::Utc::now().timestamp()).await?;
+        tracing::info!(target: "story", "[Story] Started '{}' ({})", story.title, story.id);
+        self.advance(app, &story.id, None, None).await
+    }
+
+    /// Continue from a beat with one of its choices or free text.
+    pub async fn choose(
+        &self,
+        app: &AppHandle,
+        story_id: &str,
+        mv: StoryMove,
+    ) -> Result<StoryView, KokoroError> {
+        let db = database(app)?;
+        let story = get_story(&db, story_id).await?;
+        let from = mv
+            .beat_id
+            .or(story.current_beat_id)
+            .ok_or_else(|| KokoroError::Validation("story has no beat to continue".to_string()))?;
+        let beat = get_beat(&db, story_id, &from).await?;
+        if beat.ending {
+            return Err(KokoroError::Validation(
+                "this branch has ended; pick an earlier beat".to_string(),
+            ));
+        }
+        let action = match (mv.choice_id, mv.custom) {
+            (_, Some(custom)) if !custom.trim().is_empty() => custom.trim().to_string(),
+            (Some(choice_id), _) => beat
+                .choices
+                .iter()
+                .find(|c| c.id == choice_id)
+                .map(|c| c.text.clone())
+                .ok_or_else(|| KokoroError::NotFound(format!("choice '{}'", choice_id)))?,
+            _ => {
+                return Err(KokoroError::Validation(
+                    "pick a choice or describe an action".to_string(),
+                ))
+            }
+        };
+        self.advance(app, story_id, Some(beat.id), Some(action))
+            .await
+    }
+
+    async fn advance(
+        &self,
+        app: &AppHandle,
+        story_id: &str,
+        parent_id: Option<String>,
+        action: Option<String>,
+    ) -> Result<StoryView, KokoroError> {
+        if !self.generating.lock().await.insert(story_id.to_string()) {
+            return Err(KokoroError::Validation(
+                "the story is already being written".to_string(),
+            ));
+        }
+        let result = self.generate_beat(app, story_id, parent_id, action).await;
+        self.generating.lock().await.remove(story_id);
+        result
+    }
+
+    async fn generate_beat(
+        &self,
+        app: &AppHandle,
+        story_id: &str,
+        parent_id: Option<String>,
+        action: Option<String>,
+    ) -> Result<StoryView, KokoroError> {
+        let db = database(app)?;
+        let llm = app
+            .try_state::<LlmService>()
+            .ok_or_else(|| KokoroError::Internal("LLM service is not ready".to_string()))?;
+        let story = get_story(&db, story_id).await?;
+        let narrator = CharacterContext::load(app, &story.character_id).await?;
+        let path = story_path(&db, story_id, parent_id.as_deref()).await?;
+        let previous_state = path.last().map(|b| b.state.clone()).unwrap_or_default();
+
+        let mut system = String::new();
+        if !narrator.persona.trim().is_empty() {
+            system.push_str(narrator.persona.trim());
+            system.push_str("\n\n");
+        }
+        system.push_str(STORYTELLER_PROMPT);
+        let messages = vec![
+            system_message(system),
+            user_text_message(story_prompt(
+                &story,
+                &path,
+                &previous_state,
+                action.as_deref(),
+            )),
+        ];
+
+        let provider = llm.provider_for(Some(&narrator.llm)).await;
+        let params = llm.chat_params_for(Some(&narrator.llm)).await;
+        let mut stream = provider
+            .chat_stream_rich(messages.into_iter().map(Into::into).collect(), params)
+            .await
+            .map_err(KokoroError::Llm)?;
+        let mut raw = String::new();
+        while let Some(item) = stream.next().await {
+            match item {
+                Ok(LlmStreamEvent::Text(text)) => {
+                    raw.push_str(&text);
+                    let _ = app.emit(
+                        STORY_DELTA_EVENT,
+                        serde_json::json!({ "story_id": story_id, "text": narrative_so_far(&raw) }),
+                    );
+                }
+                Ok(_) => {}
+                Err(e) => return Err(KokoroError::Llm(e)),
+            }
+        }
+
+        let parsed = parse_beat(&raw);
+        if parsed.narrative.is_empty() {
+            return Err(KokoroError::Llm(
+                "the narrator returned no story".to_string(),
+            ));
+        }
+        let beat = StoryBeat {
+            id: uuid::Uuid::new_v4().to_string(),
+            story_id: story_id.to_string(),
+            parent_id,
+            choice_text: action,
+            narrative: parsed.narrative,
+            // An ending with leftover choices is still an ending.
+            choices: if parsed.ending {
+                Vec::new()
+            } else {
+                parsed.choices
+            },
+            state: merge_state(&previous_state, parsed.state_changes),
+            ending: parsed.ending,
+            created_at: chrono::Utc::now().timestamp(),
+        };
+        append_beat(&db, &beat).await?;
+        tracing::info!(
+            target: "story",
+            "[Story] '{}' beat {} ({} choices{})",
+            story.title,
+            beat.id,
+            beat.choices.len(),
+            if beat.ending { ", ending" } else { "" }
+        );
+        let _ = app.emit(
+            STORY_BEAT_EVENT,
+            serde_json::json!({ "story_id": story_id, "beat": beat }),
+        );
+        story_view(&db, story_id).await
+    }
+}
+
+fn database(app: &AppHandle) -> Result<SqlitePool, KokoroError> {
+    app.try_state::<crate::ai::context::AIOrchestrator>()
+        .map(|orchestrator| orchestrator.db.clone())
+        .ok_or_else(|| KokoroError::Internal("AI orchestrator is not ready".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_narrative_choices_and_state() {
+        let raw = "The door creaks open.\n\n[CHOICES]\n```json\n{\"choices\": [{\"id\": \"in\", \"text\": \"Step inside\"}, \"Run away\"], \"state\": {\"lantern\": true}}\n```\n[/CHOICES]";
+        let beat = parse_beat(raw);
+        assert_eq!(beat.narrative, "The door creaks open.");
+        assert_eq!(
+            beat.choices,
+            vec![
+                StoryChoice {
+                    id: "in".into(),
+                    text: "Step inside".into()
+                },
+                StoryChoice {
+                    id: "b".into(),
+                    text: "Run away".into()
+                },
+            ]
+        );
+        assert_eq!(beat.state_changes.get("lantern"), Some(&Value::Bool(true)));
+        assert!(!beat.ending);
+    }
+
+    #[test]
+    fn falls_back_to_a_trailing_list() {
+        let beat = parse_beat("You reach a fork.\n\n1. Go left\n2) Go right\n");
+        assert_eq!(beat.narrative, "You reach a fork.");
+        let texts: Vec<_> = beat.choices.iter().map(|c| c.text.as_str()).collect();
+        assert_eq!(texts, vec!["Go left", "Go right"]);
+
+        let broken = parse_beat("Night falls.\n[CHOICES]\n- Sleep\n- Keep watch");
+        assert_eq!(broken.narrative, "Night falls.");
+        assert_eq!(broken.choices.len(), 2);
+    }
+
+    #[test]
+    fn state_changes_set_and_remove_keys() {
+        let mut previous = Map::new();
+        previous.insert("gold".into(), Value::from(5));
+        previous.insert("torch".into(), Value::Bool(true));
+        let mut changes = Map::new();
+        changes.insert("gold".into(), Value::from(2));
+        changes.insert("torch".into(), Value::Null);
+        let state = merge_state(&previous, changes);
+        assert_eq!(state.get("gold"), Some(&Value::from(2)));
+        assert!(!state.contains_key("torch"));
+    }
+
+    #[test]
+    fn streaming_narrative_hides_the_choices_block() {
+        assert_eq!(narrative_so_far("It was dark.\n[CHO"), "It was dark.");
+        assert_eq!(
+            narrative_so_far("It was dark.\n[CHOICES]\n{\"ch"),
+            "It was dark."
+        );
+        assert_eq!(narrative_so_far("It was dark"), "It was dark");
+    }
+
+    #[tokio::test]
+    async fn choosing_an_earlier_beat_branches() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+        let story = create_story(
+            &pool,
+            NewStory {
+                character_id: None,
+                title: None,
+                premise: "A haunted lighthouse".into(),
+            },
+            0,
+        )
+        .await
+        .unwrap();
+        assert_eq!(story.title, "A haunted lighthouse");
+
+        let beat = |id: &str, parent: Option<&str>, at: i64| StoryBeat {
+            id: id.into(),
+            story_id: story.id.clone(),
+            parent_id: parent.map(Into::into),
+            choice_text: parent.map(|_| format!("to {}", id)),
+            narrative: format!("beat {}", id),
+            choices: Vec::new(),
+            state: Map::new(),
+            ending: false,
+            created_at: at,
+        };
+        append_beat(&pool, &beat("root", None, 1)).await.unwrap();
+        append_beat(&pool, &beat("left", Some("root"), 2))
+            .await
+            .unwrap();
+        append_beat(&pool, &beat("left-2", Some("left"), 3))
+            .await
+            .unwrap();
+        append_beat(&pool, &beat("right", Some("root"), 4))
+            .await
+            .unwrap();
+
+        let view = story_view(&pool, &story.id).await.unwrap();
+        let path: Vec<_> = view.path.iter().map(|b| b.id.as_str()).collect();
+        assert_eq!(path, vec!["root", "right"]);
+        assert_eq!(view.branches.len(), 1);
+        assert_eq!(view.branches[0].beat_id, "left");
+
+        let view = switch_beat(&pool, &story.id, "left-2", 5).await.unwrap();
+        let path: Vec<_> = view.path.iter().map(|b| b.id.as_str()).collect();
+        assert_eq!(path, vec!["root", "left", "left-2"]);
+
+        assert!(delete_story(&pool, &story.id).await.unwrap());
+        assert!(list_stories(&pool).await.unwrap().is_empty());
+    }
+}
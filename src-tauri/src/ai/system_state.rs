@@ -0,0 +1,386 @@
+//! System State — lightweight battery / power / network snapshot.
+//!
+//! Polled by the heartbeat loop, injected into the prompt as dynamic context,
+//! and used to fire proactive nudges (low battery, machine went offline).
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Battery percentage at or below which the companion suggests plugging in.
+pub const LOW_BATTERY_THRESHOLD: u8 = 10;
+
+/// Hosts probed (TCP) to decide whether the machine has internet access.
+const CONNECTIVITY_PROBES: [&str; 2] = ["1.1.1.1:443", "8.8.8.8:53"];
+const CONNECTIVITY_TIMEOUT: Duration = Duration::from_secs(3);
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SystemState {
+    /// Battery charge 0-100. `None` on desktops or when unavailable.
+    pub battery_percent: Option<u8>,
+    /// Whether the machine is on AC power / charging. `None` when unknown.
+    pub charging: Option<bool>,
+    /// OS battery saver / low power mode. `None` when unknown.
+    pub battery_saver: Option<bool>,
+    /// Whether an outbound internet connection could be established.
+    pub network_online: bool,
+    /// Unix timestamp (seconds) of the snapshot.
+    pub checked_at: i64,
+}
+
+impl SystemState {
+    /// Whether any reading changed, ignoring the snapshot timestamp.
+    pub fn differs_from(&self, other: &SystemState) -> bool {
+        self.battery_percent != other.battery_percent
+            || self.charging != other.charging
+            || self.battery_saver != other.battery_saver
+            || self.network_online != other.network_online
+    }
+
+    pub fn is_low_battery(&self) -> bool {
+        matches!(
+            (self.battery_percent, self.charging),
+            (Some(percent), Some(false)) if percent <= LOW_BATTERY_THRESHOLD
+        )
+    }
+}
+
+/// Proactive nudges derived from a state transition.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SystemStateTrigger {
+    LowBattery { percent: u8 },
+    WentOffline,
+    BackOnline,
+}
+
+/// Remembers which nudges have already fired so each one fires once per episode.
+#[derive(Debug, Default)]
+pub struct SystemStateMonitor {
+    low_battery_notified: bool,
+    last_online: Option<bool>,
+}
+
+impl SystemStateMonitor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Compare the new snapshot with the previous one and return nudges to fire.
+    pub fn observe(&mut self, state: &SystemState) -> Vec<SystemStateTrigger> {
+        let mut triggers = Vec::new();
+
+        if state.is_low_battery() {
+            if !self.low_battery_notified {
+                self.low_battery_notified = true;
+                if let Some(percent) = state.battery_percent {
+                    triggers.push(SystemStateTrigger::LowBattery { percent });
+                }
+            }
+        } else if state.charging == Some(true)
+            || state
+                .battery_percent
+                .map(|p| p > LOW_BATTERY_THRESHOLD)
+                .unwrap_or(true)
+        {
+            self.low_battery_notified = false;
+        }
+
+        match (self.last_online, state.network_online) {
+            (Some(true), false) => triggers.push(SystemStateTrigger::WentOffline),
+            (Some(false), true) => triggers.push(SystemStateTrigger::BackOnline),
+            _ => {}
+        }
+        self.last_online = Some(state.network_online);
+
+        triggers
+    }
+}
+
+impl SystemStateTrigger {
+    pub fn trigger_type(&self) -> &'static str {
+        match self {
+            Self::LowBattery { .. } => "low_battery",
+            Self::WentOffline => "offline",
+            Self::BackOnline => "online",
+        }
+    }
+
+    pub fn instruction(&self) -> String {
+        match self {
+            Self::LowBattery { percent } => format!(
+                "The user's laptop battery is at {}% and not charging. Gently suggest plugging it in.",
+                percent
+            ),
+            Self::WentOffline => "The computer just lost its internet connection. Briefly let the user know that online features may not work until it reconnects.".to_string(),
+            Self::BackOnline => "The internet connection is back. Mention it casually if it fits.".to_string(),
+        }
+    }
+}
+
+/// Render the snapshot as a `<system_state>` block for the prompt.
+/// Returns `None` when nothing is worth mentioning (desktop on AC, online).
+pub fn describe_system_state(state: &SystemState) -> Option<String> {
+    let mut lines = Vec::new();
+
+    if let Some(percent) = state.battery_percent {
+        let power = match state.charging {
+            Some(true) => "charging",
+            Some(false) => "on battery",
+            None => "power source unknown",
+        };
+        lines.push(format!("Battery: {}% ({})", percent, power));
+        if state.is_low_battery() {
+            lines.push("The battery is low; the user may need to plug in soon.".to_string());
+        }
+    }
+    if state.battery_saver == Some(true) {
+        lines.push("Battery saver / low power mode is on.".to_string());
+    }
+    if !state.network_online {
+        lines.push(
+            "The machine is offline. Cloud services will fail until the connection returns."
+                .to_string(),
+        );
+    }
+
+    if lines.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "<system_state>\n{}\n</system_state>",
+        lines.join("\n")
+    ))
+}
+
+/// Take a fresh snapshot of battery and network state.
+pub async fn probe_system_state() -> SystemState {
+    let (battery_percent, charging, battery_saver) = tokio::task::spawn_blocking(probe_power)
+        .await
+        .unwrap_or((None, None, None));
+
+    SystemState {
+        battery_percent,
+        charging,
+        battery_saver,
+        network_online: probe_network().await,
+        checked_at: chrono::Utc::now().timestamp(),
+    }
+}
+
+async fn probe_network() -> bool {
+    for addr in CONNECTIVITY_PROBES {
+        let connect = tokio::net::TcpStream::connect(addr);
+        if let Ok(Ok(_)) = tokio::time::timeout(CONNECTIVITY_TIMEOUT, connect).await {
+            return true;
+        }
+    }
+    false
+}
+
+type PowerProbe = (Option<u8>, Option<bool>, Option<bool>);
+
+#[cfg(target_os = "linux")]
+fn probe_power() -> PowerProbe {
+    let Ok(entries) = std::fs::read_dir("/sys/class/power_supply") else {
+        return (None, None, None);
+    };
+
+    let mut percent = None;
+    let mut charging = None;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let kind = std::fs::read_to_string(path.join("type")).unwrap_or_default();
+        match kind.trim() {
+            "Battery" => {
+                if percent.is_none() {
+                    percent = std::fs::read_to_string(path.join("capacity"))
+                        .ok()
+                        .and_then(|raw| raw.trim().parse::<u8>().ok());
+                    if let Ok(status) = std::fs::read_to_string(path.join("status")) {
+                        charging = parse_linux_battery_status(&status).or(charging);
+                    }
+                }
+            }
+            "Mains" => {
+                if let Ok(online) = std::fs::read_to_string(path.join("online")) {
+                    if online.trim() == "1" {
+                        charging = Some(true);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let saver = std::fs::read_to_string("/sys/firmware/acpi/platform_profile")
+        .ok()
+        .map(|profile| profile.trim() == "low-power");
+
+    (percent, charging, saver)
+}
+
+#[cfg(any(target_os = "linux", test))]
+fn parse_linux_battery_status(status: &str) -> Option<bool> {
+    match status.trim() {
+        "Charging" | "Full" | "Not charging" => Some(true),
+        "Discharging" => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn probe_power() -> PowerProbe {
+    let batt = std::process::Command::new("pmset")
+        .args(["-g", "batt"])
+        .output()
+        .ok()
+        .map(|out| String::from_utf8_lossy(&out.stdout).to_string())
+        .unwrap_or_default();
+    let (percent, charging) = parse_pmset_batt(&batt);
+
+    let saver = std::process::Command::new("pmset")
+        .arg("-g")
+        .output()
+        .ok()
+        .map(|out| String::from_utf8_lossy(&out.stdout).to_string())
+        .and_then(|settings| {
+            settings
+                .lines()
+                .find(|line| line.trim_start().starts_with("lowpowermode"))
+                .map(|line| line.trim_end().ends_with('1'))
+        });
+
+    (percent, charging, saver)
+}
+
+/// Parse `pmset -g batt` output, e.g.
+/// `Now drawing from 'Battery Power'\n -InternalBattery-0 (id=1)\t85%; discharging; 3:12 remaining`.
+#[cfg(any(target_os = "macos", test))]
+fn parse_pmset_batt(output: &str) -> (Option<u8>, Option<bool>) {
+    let charging = if output.contains("'AC Power'") {
+        Some(true)
+    } else if output.contains("'Battery Power'") {
+        Some(false)
+    } else {
+        None
+    };
+    let percent = output
+        .split(|ch: char| ch.is_whitespace() || ch == ';')
+        .find_map(|token| token.strip_suffix('%'))
+        .and_then(|raw| raw.parse::<u8>().ok());
+    (percent, charging)
+}
+
+#[cfg(target_os = "windows")]
+fn probe_power() -> PowerProbe {
+    use std::os::windows::process::CommandExt;
+
+    let output = std::process::Command::new("powershell")
+        .args([
+            "-NoProfile",
+            "-Command",
+            "$b = Get-CimInstance Win32_Battery | Select-Object -First 1; \
+             if ($b) { \"$($b.EstimatedChargeRemaining);$($b.BatteryStatus)\" }",
+        ])
+        // CREATE_NO_WINDOW: avoid flashing a console window every poll
+        .creation_flags(0x08000000)
+        .output();
+
+    let Ok(output) = output else {
+        return (None, None, None);
+    };
+    let text = String::from_utf8_lossy(&output.stdout);
+    let mut parts = text.trim().split(';');
+    let percent = parts.next().and_then(|raw| raw.trim().parse::<u8>().ok());
+    // Win32_Battery.BatteryStatus: 1 = discharging, 2 = on AC, 6-9 = charging states
+    let charging = parts
+        .next()
+        .and_then(|raw| raw.trim().parse::<u16>().ok())
+        .map(|status| status != 1);
+
+    (percent, charging, None)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn probe_power() -> PowerProbe {
+    (None, None, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn laptop(percent: u8, charging: bool) -> SystemState {
+        SystemState {
+            battery_percent: Some(percent),
+            charging: Some(charging),
+            battery_saver: None,
+            network_online: true,
+            checked_at: 0,
+        }
+    }
+
+    #[test]
+    fn describe_is_empty_for_online_desktop() {
+        let state = SystemState {
+            network_online: true,
+            ..SystemState::default()
+        };
+        assert!(describe_system_state(&state).is_none());
+    }
+
+    #[test]
+    fn describe_mentions_low_battery_and_offline() {
+        let mut state = laptop(8, false);
+        state.network_online = false;
+        let block = describe_system_state(&state).expect("block");
+        assert!(block.contains("Battery: 8% (on battery)"));
+        assert!(block.contains("battery is low"));
+        assert!(block.contains("offline"));
+    }
+
+    #[test]
+    fn monitor_fires_low_battery_once_until_plugged_in() {
+        let mut monitor = SystemStateMonitor::new();
+        assert_eq!(
+            monitor.observe(&laptop(10, false)),
+            vec![SystemStateTrigger::LowBattery { percent: 10 }]
+        );
+        assert!(monitor.observe(&laptop(9, false)).is_empty());
+        assert!(monitor.observe(&laptop(9, true)).is_empty());
+        assert_eq!(
+            monitor.observe(&laptop(9, false)),
+            vec![SystemStateTrigger::LowBattery { percent: 9 }]
+        );
+    }
+
+    #[test]
+    fn monitor_reports_connectivity_transitions_only() {
+        let mut monitor = SystemStateMonitor::new();
+        let mut state = laptop(80, true);
+        assert!(monitor.observe(&state).is_empty());
+        state.network_online = false;
+        assert_eq!(
+            monitor.observe(&state),
+            vec![SystemStateTrigger::WentOffline]
+        );
+        assert!(monitor.observe(&state).is_empty());
+        state.network_online = true;
+        assert_eq!(
+            monitor.observe(&state),
+            vec![SystemStateTrigger::BackOnline]
+        );
+    }
+
+    #[test]
+    fn parses_pmset_battery_output() {
+        let output = "Now drawing from 'Battery Power'\n -InternalBattery-0 (id=1234)\t85%; discharging; 3:12 remaining present: true";
+        assert_eq!(parse_pmset_batt(output), (Some(85), Some(false)));
+    }
+
+    #[test]
+    fn parses_linux_battery_status() {
+        assert_eq!(parse_linux_battery_status("Discharging\n"), Some(false));
+        assert_eq!(parse_linux_battery_status("Charging\n"), Some(true));
+        assert_eq!(parse_linux_battery_status("Unknown\n"), None);
+    }
+}
@@ -0,0 +1,241 @@
+//! Topic segmentation for memory extraction.
+//!
+//! Instead of extracting every N user messages, each user turn is embedded and
+//! compared with the running centroid of the current topic. Extraction runs
+//! when the conversation moves on (topic shift), when a topic is explicitly
+//! closed ("thanks, good night") or goes idle, and — as a backstop — when one
+//! topic runs long enough that facts could scroll out of the extraction window.
+
+use crate::ai::memory::cosine_similarity;
+use std::time::Instant;
+
+/// Turns in a segment before a shift or an ending is worth an extraction.
+const MIN_SEGMENT_TURNS: usize = 2;
+/// Fixed cadence used while no embedding model is available.
+const FALLBACK_EVERY_TURNS: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExtractionTrigger {
+    /// The new message is about something else; the previous topic is done.
+    TopicShift,
+    /// The user wrapped up the conversation, or it went idle.
+    TopicEnd,
+    /// The current topic has run for `max_turns` without an extraction.
+    LongTopic,
+    /// No embeddings available; falling back to a fixed cadence.
+    Cadence,
+}
+
+impl ExtractionTrigger {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ExtractionTrigger::TopicShift => "topic_shift",
+            ExtractionTrigger::TopicEnd => "topic_end",
+            ExtractionTrigger::LongTopic => "long_topic",
+            ExtractionTrigger::Cadence => "cadence",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct TopicShiftOptions {
+    /// Cosine similarity below which a turn starts a new topic.
+    pub similarity_threshold: f32,
+    /// Turns without extraction before a long topic is extracted anyway.
+    pub max_turns: usize,
+}
+
+impl Default for TopicShiftOptions {
+    fn default() -> Self {
+        Self {
+            similarity_threshold: 0.4,
+            max_turns: 12,
+        }
+    }
+}
+
+impl From<&crate::config::MemoryUpgradeConfig> for TopicShiftOptions {
+    fn from(config: &crate::config::MemoryUpgradeConfig) -> Self {
+        Self {
+            similarity_threshold: f32::from(config.topic_shift_similarity_pct) / 100.0,
+            max_turns: config.topic_max_turns as usize,
+        }
+    }
+}
+
+/// Phrases that close a conversation topic.
+const CLOSING_PHRASES: &[&str] = &[
+    "bye",
+    "goodbye",
+    "good night",
+    "goodnight",
+    "see you",
+    "talk later",
+    "that's all",
+    "thats all",
+    "gotta go",
+    "再见",
+    "晚安",
+    "拜拜",
+    "回头聊",
+    "就这样",
+    "おやすみ",
+    "またね",
+    "じゃあね",
+    "さようなら",
+];
+
+fn is_closing(text: &str) -> bool {
+    let lower = text.trim().to_lowercase();
+    // Only short messages: "bye for now" closes, a paragraph mentioning "see you" does not.
+    lower.chars().count() <= 40 && CLOSING_PHRASES.iter().any(|p| lower.contains(p))
+}
+
+/// Current topic of one character's conversation.
+#[derive(Debug, Default)]
+pub struct TopicTracker {
+    centroid: Option<Vec<f32>>,
+    previous: Option<Vec<f32>>,
+    /// Turns in the current topic.
+    segment_turns: usize,
+    /// Turns since the last extraction.
+    pending_turns: usize,
+    last_turn_at: Option<Instant>,
+}
+
+impl TopicTracker {
+    /// Record a user turn; returns a trigger when memories should be extracted now.
+    pub fn observe(
+        &mut self,
+        text: &str,
+        embedding: Option<Vec<f32>>,
+        options: &TopicShiftOptions,
+    ) -> Option<ExtractionTrigger> {
+        self.last_turn_at = Some(Instant::now());
+        self.pending_turns += 1;
+
+        let Some(embedding) = embedding else {
+            self.segment_turns += 1;
+            if self.pending_turns >= FALLBACK_EVERY_TURNS {
+                return Some(self.extracted(ExtractionTrigger::Cadence));
+            }
+            return None;
+        };
+
+        let shifted = match (&self.centroid, &self.previous) {
+            (Some(centroid), Some(previous)) => {
+                // Either the topic as a whole or the last turn may carry the thread.
+                cosine_similarity(&embedding, centroid).max(cosine_similarity(&embedding, previous))
+                    < options.similarity_threshold
+            }
+            _ => false,
+        };
+
+        if shifted {
+            // The new turn opens the next topic; what came before is complete.
+            let extract = self.pending_turns > MIN_SEGMENT_TURNS;
+            self.centroid = Some(embedding.clone());
+            self.previous = Some(embedding);
+            self.segment_turns = 1;
+            if extract {
+                self.pending_turns = 1;
+                return Some(ExtractionTrigger::TopicShift);
+            }
+            return None;
+        }
+
+        self.segment_turns += 1;
+        self.centroid = Some(match self.centroid.take() {
+            Some(centroid) => {
+                let n = self.segment_turns as f32;
+                centroid
+                    .iter()
+                    .zip(&embedding)
+                    .map(|(c, e)| c + (e - c) / n)
+                    .collect()
+            }
+            None => embedding.clone(),
+        });
+        self.previous = Some(embedding);
+
+        if is_closing(text) && self.pending_turns >= MIN_SEGMENT_TURNS {
+            self.end_topic();
+            return Some(ExtractionTrigger::TopicEnd);
+        }
+        if self.pending_turns >= options.max_turns.max(MIN_SEGMENT_TURNS) {
+            return Some(self.extracted(ExtractionTrigger::LongTopic));
+        }
+        None
+    }
+
+    /// The conversation went quiet: extract the unfinished topic once.
+    pub fn idle_end(&mut self, idle_secs: u64, threshold_secs: u64) -> Option<ExtractionTrigger> {
+        let idle = self
+            .last_turn_at
+            .is_some_and(|at| at.elapsed().as_secs() >= threshold_secs)
+            && idle_secs >= threshold_secs;
+        if idle && self.pending_turns >= MIN_SEGMENT_TURNS {
+            self.end_topic();
+            return Some(ExtractionTrigger::TopicEnd);
+        }
+        None
+    }
+
+    fn extracted(&mut self, trigger: ExtractionTrigger) -> ExtractionTrigger {
+        self.pending_turns = 0;
+        trigger
+    }
+
+    fn end_topic(&mut self) {
+        self.centroid = None;
+        self.previous = None;
+        self.segment_turns = 0;
+        self.pending_turns = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn v(x: f32, y: f32) -> Option<Vec<f32>> {
+        Some(vec![x, y])
+    }
+
+    #[test]
+    fn extracts_on_shift_closing_and_long_topics() {
+        let options = TopicShiftOptions {
+            similarity_threshold: 0.5,
+            max_turns: 5,
+        };
+        let mut t = TopicTracker::default();
+        assert_eq!(t.observe("cats", v(1.0, 0.0), &options), None);
+        assert_eq!(t.observe("more cats", v(0.9, 0.1), &options), None);
+        assert_eq!(t.observe("my cat", v(1.0, 0.05), &options), None);
+        // Orthogonal turn: the cat topic is finished.
+        assert_eq!(
+            t.observe("taxes", v(0.0, 1.0), &options),
+            Some(ExtractionTrigger::TopicShift)
+        );
+        assert_eq!(
+            t.observe("ok good night", v(0.1, 1.0), &options),
+            Some(ExtractionTrigger::TopicEnd)
+        );
+
+        // A single long topic is extracted every `max_turns` turns.
+        let hits = (0..10)
+            .filter_map(|_| t.observe("cats", v(1.0, 0.0), &options))
+            .collect::<Vec<_>>();
+        assert_eq!(hits, vec![ExtractionTrigger::LongTopic; 2]);
+
+        // Without embeddings the old fixed cadence applies.
+        let mut plain = TopicTracker::default();
+        let hits = (0..10)
+            .filter_map(|_| plain.observe("hi", None, &options))
+            .count();
+        assert_eq!(hits, 2);
+        assert!(!is_closing(
+            "I will see you at the station tomorrow and then we can walk over together"
+        ));
+    }
+}
@@ -0,0 +1,280 @@
+//! User Presence — OS-level keyboard/mouse idle time.
+//!
+//! Chat timing alone can't tell "user away from the computer" from "user busy
+//! in another app". The heartbeat combines both: proactive messages target a
+//! user who is at the machine, wait (or go to Telegram) while they are away,
+//! and a long absence ends with a welcome back.
+
+use serde::Serialize;
+
+/// Input idle time after which the user counts as away from the computer.
+pub const AWAY_AFTER_INPUT_IDLE_SECS: u64 = 600;
+/// Absences shorter than this end silently.
+const WELCOME_BACK_AFTER_SECS: u64 = 1800;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UserPresence {
+    /// Chatting recently.
+    Active,
+    /// At the computer (recent keyboard/mouse input) but not chatting.
+    Quiet,
+    /// No keyboard/mouse input for a while.
+    Away,
+    /// The OS idle time is unavailable; only chat timing is known.
+    Unknown,
+}
+
+impl UserPresence {
+    pub fn classify(
+        chat_idle_secs: u64,
+        input_idle_secs: Option<u64>,
+        quiet_after_secs: u64,
+    ) -> Self {
+        match input_idle_secs {
+            None => Self::Unknown,
+            Some(input) if input >= AWAY_AFTER_INPUT_IDLE_SECS => Self::Away,
+            Some(_) if chat_idle_secs >= quiet_after_secs => Self::Quiet,
+            Some(_) => Self::Active,
+        }
+    }
+
+    /// How the idle stretch reads to the LLM in proactive instructions.
+    pub fn describe(&self, chat_idle_secs: u64, input_idle_secs: Option<u64>) -> String {
+        let minutes = |secs: u64| secs as f64 / 60.0;
+        match (self, input_idle_secs) {
+            (Self::Away, Some(input)) => format!(
+                "The user has been away from the computer for {:.0} minutes.",
+                minutes(input)
+            ),
+            (Self::Quiet, _) => format!(
+                "The user is at the computer but hasn't chatted for {:.0} minutes; they may be busy with something else.",
+                minutes(chat_idle_secs)
+            ),
+            _ => format!("User has been idle for {:.0} minutes.", minutes(chat_idle_secs)),
+        }
+    }
+}
+
+/// Payload of `user-presence-changed`.
+#[derive(Debug, Clone, Serialize)]
+pub struct UserPresenceEvent {
+    pub presence: UserPresence,
+    pub input_idle_secs: Option<u64>,
+}
+
+/// Tracks presence between heartbeat ticks.
+#[derive(Debug, Default)]
+pub struct PresenceMonitor {
+    last: Option<UserPresence>,
+    /// Longest input idle seen during the current absence.
+    away_secs: u64,
+}
+
+/// What a presence update means for the heartbeat.
+#[derive(Debug, Default, PartialEq)]
+pub struct PresenceUpdate {
+    pub changed: bool,
+    /// Set when the user comes back after a long absence.
+    pub returned_after_secs: Option<u64>,
+}
+
+impl PresenceMonitor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn observe(
+        &mut self,
+        presence: UserPresence,
+        input_idle_secs: Option<u64>,
+    ) -> PresenceUpdate {
+        let previous = self.last.replace(presence);
+        let mut update = PresenceUpdate {
+            changed: previous != Some(presence),
+            returned_after_secs: None,
+        };
+        if presence == UserPresence::Away {
+            self.away_secs = self.away_secs.max(input_idle_secs.unwrap_or(0));
+        } else if previous == Some(UserPresence::Away) {
+            if self.away_secs >= WELCOME_BACK_AFTER_SECS {
+                update.returned_after_secs = Some(self.away_secs);
+            }
+            self.away_secs = 0;
+        }
+        update
+    }
+}
+
+/// Seconds since the last keyboard/mouse input anywhere on the system.
+/// `None` when the platform gives no answer (e.g. an unsupported compositor).
+pub async fn probe_input_idle_secs() -> Option<u64> {
+    tokio::task::spawn_blocking(input_idle_millis)
+        .await
+        .ok()
+        .flatten()
+        .map(|millis| millis / 1000)
+}
+
+#[cfg(target_os = "windows")]
+fn input_idle_millis() -> Option<u64> {
+    #[repr(C)]
+    struct LastInputInfo {
+        cb_size: u32,
+        dw_time: u32,
+    }
+    #[link(name = "user32")]
+    extern "system" {
+        fn GetLastInputInfo(plii: *mut LastInputInfo) -> i32;
+    }
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetTickCount() -> u32;
+    }
+
+    let mut info = LastInputInfo {
+        cb_size: std::mem::size_of::<LastInputInfo>() as u32,
+        dw_time: 0,
+    };
+    // SAFETY: `info` is a correctly sized LASTINPUTINFO that outlives the call.
+    let ok = unsafe { GetLastInputInfo(&mut info) } != 0;
+    if !ok {
+        return None;
+    }
+    // SAFETY: GetTickCount has no preconditions.
+    let now = unsafe { GetTickCount() };
+    // Both counters wrap after ~49 days; wrapping_sub keeps the difference right.
+    Some(u64::from(now.wrapping_sub(info.dw_time)))
+}
+
+#[cfg(target_os = "macos")]
+fn input_idle_millis() -> Option<u64> {
+    let output = std::process::Command::new("ioreg")
+        .args(["-c", "IOHIDSystem", "-d", "4"])
+        .output()
+        .ok()?;
+    parse_ioreg_idle_nanos(&String::from_utf8_lossy(&output.stdout)).map(|nanos| nanos / 1_000_000)
+}
+
+/// `"HIDIdleTime" = 1234567890` (nanoseconds) from `ioreg -c IOHIDSystem`.
+#[cfg(any(target_os = "macos", test))]
+fn parse_ioreg_idle_nanos(output: &str) -> Option<u64> {
+    output
+        .lines()
+        .find(|line| line.contains("\"HIDIdleTime\""))
+        .and_then(|line| line.split('=').nth(1))
+        .and_then(|value| value.trim().parse().ok())
+}
+
+#[cfg(target_os = "linux")]
+fn input_idle_millis() -> Option<u64> {
+    // GNOME (X11 and Wayland) exposes the idle monitor over D-Bus.
+    let mutter = std::process::Command::new("gdbus")
+        .args([
+            "call",
+            "--session",
+            "--dest",
+            "org.gnome.Mutter.IdleMonitor",
+            "--object-path",
+            "/org/gnome/Mutter/IdleMonitor/Core",
+            "--method",
+            "org.gnome.Mutter.IdleMonitor.GetIdletime",
+        ])
+        .output()
+        .ok()
+        .filter(|out| out.status.success())
+        .and_then(|out| parse_gdbus_uint64(&String::from_utf8_lossy(&out.stdout)));
+    if mutter.is_some() {
+        return mutter;
+    }
+    // Other X11 desktops: xprintidle prints milliseconds.
+    std::process::Command::new("xprintidle")
+        .output()
+        .ok()
+        .filter(|out| out.status.success())
+        .and_then(|out| String::from_utf8_lossy(&out.stdout).trim().parse().ok())
+}
+
+/// `(uint64 12345,)` from `gdbus call`.
+#[cfg(any(target_os = "linux", test))]
+fn parse_gdbus_uint64(output: &str) -> Option<u64> {
+    output
+        .trim()
+        .trim_start_matches('(')
+        .trim_end_matches(')')
+        .trim_end_matches(',')
+        .trim()
+        .strip_prefix("uint64")?
+        .trim()
+        .parse()
+        .ok()
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn input_idle_millis() -> Option<u64> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_from_chat_and_input_idle() {
+        assert_eq!(
+            UserPresence::classify(900, None, 300),
+            UserPresence::Unknown
+        );
+        assert_eq!(
+            UserPresence::classify(30, Some(5), 300),
+            UserPresence::Active
+        );
+        assert_eq!(
+            UserPresence::classify(900, Some(5), 300),
+            UserPresence::Quiet
+        );
+        assert_eq!(
+            UserPresence::classify(900, Some(700), 300),
+            UserPresence::Away
+        );
+        assert!(UserPresence::Quiet
+            .describe(900, Some(5))
+            .contains("at the computer"));
+        assert!(UserPresence::Away
+            .describe(900, Some(1200))
+            .contains("away from the computer for 20 minutes"));
+    }
+
+    #[test]
+    fn monitor_welcomes_back_only_after_long_absence() {
+        let mut monitor = PresenceMonitor::new();
+        assert!(monitor.observe(UserPresence::Quiet, Some(5)).changed);
+        assert!(!monitor.observe(UserPresence::Quiet, Some(15)).changed);
+
+        monitor.observe(UserPresence::Away, Some(700));
+        let back = monitor.observe(UserPresence::Active, Some(1));
+        assert!(back.changed);
+        assert_eq!(back.returned_after_secs, None);
+
+        monitor.observe(UserPresence::Away, Some(700));
+        monitor.observe(UserPresence::Away, Some(2400));
+        assert_eq!(
+            monitor.observe(UserPresence::Quiet, Some(2)),
+            PresenceUpdate {
+                changed: true,
+                returned_after_secs: Some(2400),
+            }
+        );
+    }
+
+    #[test]
+    fn parses_platform_idle_output() {
+        assert_eq!(
+            parse_ioreg_idle_nanos("    | |   \"HIDIdleTime\" = 4523000000\n"),
+            Some(4_523_000_000)
+        );
+        assert_eq!(parse_ioreg_idle_nanos("nothing"), None);
+        assert_eq!(parse_gdbus_uint64("(uint64 15320,)\n"), Some(15320));
+        assert_eq!(parse_gdbus_uint64("Error: no such name"), None);
+    }
+}
@@ -0,0 +1,58 @@
+//! Local API server configuration — load/save from app data directory.
+
+use crate::error::KokoroError;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ApiServerConfig {
+    /// Start the server on app launch.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Port on 127.0.0.1 to listen on.
+    #[serde(default = "default_port")]
+    pub port: u16,
+    /// Key clients must send (direct value).
+    #[serde(default)]
+    pub api_key: Option<String>,
+    /// Or read the key from this environment variable.
+    #[serde(default)]
+    pub api_key_env: Option<String>,
+}
+
+fn default_port() -> u16 {
+    7373
+}
+
+impl Default for ApiServerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: default_port(),
+            api_key: None,
+            api_key_env: Some("KOKORO_API_KEY".to_string()),
+        }
+    }
+}
+
+impl ApiServerConfig {
+    /// Resolve the API key: check direct field first, then env var.
+    pub fn resolve_api_key(&self) -> Option<String> {
+        crate::config::resolve_api_key(&self.api_key, &self.api_key_env)
+    }
+}
+
+pub fn config_path() -> PathBuf {
+    dirs_next::data_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("com.chyin.kokoro")
+        .join("api_server_config.json")
+}
+
+pub fn load_config(path: &Path) -> ApiServerConfig {
+    crate::config::load_json_config(path, "API_SERVER")
+}
+
+pub fn save_config(path: &Path, config: &ApiServerConfig) -> Result<(), KokoroError> {
+    crate::config::save_json_config(path, config, "API_SERVER")
+}
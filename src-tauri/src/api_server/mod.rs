@@ -0,0 +1,182 @@
+//! Local API server — lets scripts and home automations talk to the running engine.
+//!
+//! Opt-in and bound to 127.0.0.1 only. Every route except `/health` requires
+//! the configured API key. Routes:
+//!
+//! - `POST /chat` — send a message; the reply streams back as server-sent events
+//! - `POST /tts` — synthesize text, returns audio bytes
+//! - `POST /stt` — transcribe an audio body
+//! - `GET/POST /memories` — list or add memories
+//! - `POST /trigger/{name}` — fire the input mappings bound to `name`
+//! - `GET /events` — WebSocket feed mirroring selected Tauri events
+//!
+//! Built on `warp` rather than `axum`: the vision upload server already pulls
+//! warp in, and one HTTP framework in the binary is enough.
+
+pub mod config;
+mod routes;
+
+pub use config::{config_path, load_config, save_config, ApiServerConfig};
+
+use serde::Serialize;
+use std::sync::Arc;
+use tauri::{AppHandle, Listener};
+use tokio::sync::{broadcast, oneshot, RwLock};
+
+/// Tauri events mirrored to `/events` clients. Audio payloads and mod UI
+/// overrides are left out on purpose.
+const FORWARDED_EVENTS: &[&str] = &[
+    "chat-turn-start",
+    "chat-turn-delta",
+    "chat-turn-text-complete",
+    "chat-turn-translation",
+    "chat-turn-tool",
+    "chat-turn-finish",
+    "chat-cue",
+    "chat-error",
+    "chat-typing",
+    "emotion:update",
+    "proactive-trigger",
+    "character-switched",
+    "character:switched",
+    "telegram:chat-sync",
+    "tts:start",
+    "tts:end",
+    "stt:wake-word-detected",
+    "memory:updated",
+    "user-presence-changed",
+    "system-state-changed",
+    "offline-mode-changed",
+    "mcp-server-status",
+    "imagegen:done",
+    "imagegen:progress",
+    "input-mapping:triggered",
+    "read-aloud:started",
+];
+
+/// Slow WebSocket clients skip events once they fall this far behind.
+const EVENT_BUFFER: usize = 256;
+
+/// One frame on the `/events` feed.
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiEvent {
+    pub event: String,
+    pub payload: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiServerStatus {
+    pub running: bool,
+    pub enabled: bool,
+    pub port: Option<u16>,
+    pub has_key: bool,
+}
+
+/// Managed Tauri state for the local API server.
+#[derive(Clone)]
+pub struct ApiServerService {
+    config: Arc<RwLock<ApiServerConfig>>,
+    /// Sender half of the shutdown signal. `Some` = server is running.
+    shutdown_tx: Arc<RwLock<Option<oneshot::Sender<()>>>>,
+    port: Arc<RwLock<Option<u16>>>,
+    events: broadcast::Sender<ApiEvent>,
+}
+
+impl ApiServerService {
+    pub fn new(config: ApiServerConfig) -> Self {
+        let (events, _) = broadcast::channel(EVENT_BUFFER);
+        Self {
+            config: Arc::new(RwLock::new(config)),
+            shutdown_tx: Arc::new(RwLock::new(None)),
+            port: Arc::new(RwLock::new(None)),
+            events,
+        }
+    }
+
+    pub async fn is_running(&self) -> bool {
+        self.shutdown_tx.read().await.is_some()
+    }
+
+    pub async fn get_config(&self) -> ApiServerConfig {
+        self.config.read().await.clone()
+    }
+
+    /// Update the in-memory config (caller is responsible for persisting to disk).
+    /// A running server keeps its port and key until restarted.
+    pub async fn update_config(&self, config: ApiServerConfig) {
+        *self.config.write().await = config;
+    }
+
+    pub async fn status(&self) -> ApiServerStatus {
+        let config = self.config.read().await.clone();
+        ApiServerStatus {
+            running: self.is_running().await,
+            enabled: config.enabled,
+            port: *self.port.read().await,
+            has_key: config.resolve_api_key().is_some(),
+        }
+    }
+
+    /// Mirror [`FORWARDED_EVENTS`] into the `/events` feed. Call once at startup.
+    pub fn forward_app_events(&self, app: &AppHandle) {
+        for &name in FORWARDED_EVENTS {
+            let events = self.events.clone();
+            app.listen_any(name, move |event| {
+                // No subscribers is the common case; nothing to do then.
+                if events.receiver_count() == 0 {
+                    return;
+                }
+                let payload =
+                    serde_json::from_str(event.payload()).unwrap_or(serde_json::Value::Null);
+                let _ = events.send(ApiEvent {
+                    event: name.to_string(),
+                    payload,
+                });
+            });
+        }
+    }
+
+    /// Bind and serve. Returns the bound port.
+    pub async fn start(&self, app: AppHandle) -> Result<u16, String> {
+        if self.is_running().await {
+            return Err("API server is already running".to_string());
+        }
+        let config = self.config.read().await.clone();
+        let api_key = config
+            .resolve_api_key()
+            .ok_or("No API key configured; refusing to start without authentication")?;
+
+        let (tx, rx) = oneshot::channel::<()>();
+        let routes = routes::routes(app, api_key, self.events.clone());
+        let (addr, server) = warp::serve(routes)
+            .try_bind_with_graceful_shutdown(([127, 0, 0, 1], config.port), async {
+                let _ = rx.await;
+            })
+            .map_err(|e| format!("Failed to bind 127.0.0.1:{}: {}", config.port, e))?;
+
+        *self.shutdown_tx.write().await = Some(tx);
+        *self.port.write().await = Some(addr.port());
+        tracing::info!(target: "api", "API server listening on http://{}", addr);
+        crate::crash::set_subsystem_active("api_server", true);
+
+        // The server only returns after the shutdown signal, and `stop` has
+        // already reset the state by then.
+        tauri::async_runtime::spawn(async move {
+            server.await;
+            tracing::info!(target: "api", "API server stopped");
+        });
+        Ok(addr.port())
+    }
+
+    pub async fn stop(&self) -> Result<(), String> {
+        match self.shutdown_tx.write().await.take() {
+            Some(tx) => {
+                let _ = tx.send(());
+                *self.port.write().await = None;
+                crate::crash::set_subsystem_active("api_server", false);
+                Ok(())
+            }
+            None => Err("API server is not running".to_string()),
+        }
+    }
+}
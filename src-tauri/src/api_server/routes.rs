@@ -0,0 +1,611 @@
+//! Local API server routes (warp filters and handlers).
+
+use super::ApiEvent;
+use crate::ai::context::AIOrchestrator;
+use crate::error::KokoroError;
+use crate::input_mapping::{InputMappingService, TriggerEvent};
+use crate::llm::messages::{is_user_message, role_text_message, user_text_message};
+use crate::llm::provider::LlmStreamEvent;
+use crate::llm::service::LlmService;
+use crate::stt::{AudioSource, SttService};
+use crate::telegram::bot::{clean_model_reply, desktop_char_id};
+use crate::tts::TtsService;
+use futures::channel::mpsc;
+use futures::{SinkExt, StreamExt};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::sync::Arc;
+use tauri::{AppHandle, Manager};
+use tokio::sync::broadcast;
+use warp::http::StatusCode;
+use warp::reply::Response;
+use warp::ws::{Message, WebSocket};
+use warp::{Filter, Rejection, Reply};
+
+const MAX_JSON_BODY: u64 = 64 * 1024;
+const MAX_AUDIO_BODY: u64 = 25 * 1024 * 1024;
+const MAX_MEMORY_PAGE: i64 = 200;
+
+#[derive(Debug)]
+struct Unauthorized;
+
+impl warp::reject::Reject for Unauthorized {}
+
+#[derive(Deserialize)]
+struct ChatRequest {
+    message: String,
+    /// Defaults to the character active in the desktop app.
+    #[serde(default)]
+    character_id: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct TtsRequest {
+    text: String,
+    #[serde(default)]
+    provider_id: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct SttQuery {
+    /// Container/codec of the request body, e.g. `wav`, `mp3`, `ogg`.
+    #[serde(default = "default_audio_format")]
+    format: String,
+    #[serde(default)]
+    language: Option<String>,
+}
+
+fn default_audio_format() -> String {
+    "wav".to_string()
+}
+
+#[derive(Deserialize)]
+struct MemoriesQuery {
+    #[serde(default)]
+    character_id: Option<String>,
+    #[serde(default = "default_memory_limit")]
+    limit: i64,
+    #[serde(default)]
+    offset: i64,
+}
+
+fn default_memory_limit() -> i64 {
+    50
+}
+
+#[derive(Deserialize)]
+struct AddMemoryRequest {
+    content: String,
+    #[serde(default)]
+    character_id: Option<String>,
+    #[serde(default)]
+    importance: Option<f64>,
+}
+
+pub(super) fn routes(
+    app: AppHandle,
+    api_key: String,
+    events: broadcast::Sender<ApiEvent>,
+) -> impl Filter<Extract = (impl Reply,), Error = Infallible> + Clone {
+    let with_app = warp::any().map(move || app.clone());
+    let auth = with_auth(Arc::new(api_key));
+
+    let health = warp::path!("health").and(warp::get()).map(|| {
+        warp::reply::json(&serde_json::json!({
+            "status": "ok",
+            "version": env!("CARGO_PKG_VERSION"),
+        }))
+    });
+
+    let chat = warp::path!("chat")
+        .and(warp::post())
+        .and(auth.clone())
+        .and(warp::body::content_length_limit(MAX_JSON_BODY))
+        .and(warp::body::json())
+        .and(with_app.clone())
+        .map(chat_stream);
+
+    let tts = warp::path!("tts")
+        .and(warp::post())
+        .and(auth.clone())
+        .and(warp::body::content_length_limit(MAX_JSON_BODY))
+        .and(warp::body::json())
+        .and(with_app.clone())
+        .then(synthesize);
+
+    let stt = warp::path!("stt")
+        .and(warp::post())
+        .and(auth.clone())
+        .and(warp::query::<SttQuery>())
+        .and(warp::body::content_length_limit(MAX_AUDIO_BODY))
+        .and(warp::body::bytes())
+        .and(with_app.clone())
+        .then(transcribe);
+
+    let list_memories = warp::path!("memories")
+        .and(warp::get())
+        .and(auth.clone())
+        .and(warp::query::<MemoriesQuery>())
+        .and(with_app.clone())
+        .then(list_memories);
+
+    let add_memory = warp::path!("memories")
+        .and(warp::post())
+        .and(auth.clone())
+        .and(warp::body::content_length_limit(MAX_JSON_BODY))
+        .and(warp::body::json())
+        .and(with_app.clone())
+        .then(add_memory);
+
+    let trigger = warp::path!("trigger" / String)
+        .and(warp::post())
+        .and(auth.clone())
+        .and(with_app)
+        .then(fire_trigger);
+
+    let event_feed =
+        warp::path!("events")
+            .and(auth)
+            .and(warp::ws())
+            .map(move |ws: warp::ws::Ws| {
+                let receiver = events.subscribe();
+                ws.on_upgrade(move |socket| forward_events(socket, receiver))
+            });
+
+    health
+        .or(chat)
+        .or(tts)
+        .or(stt)
+        .or(list_memories)
+        .or(add_memory)
+        .or(trigger)
+        .or(event_feed)
+        .recover(handle_rejection)
+}
+
+/// Accepts `Authorization: Bearer <key>`, `X-Api-Key: <key>`, or `?key=<key>`
+/// (browsers cannot set headers on WebSocket requests).
+fn with_auth(api_key: Arc<String>) -> impl Filter<Extract = (), Error = Rejection> + Clone {
+    warp::header::optional::<String>("authorization")
+        .and(warp::header::optional::<String>("x-api-key"))
+        .and(warp::query::<HashMap<String, String>>())
+        .and_then(
+            move |authorization: Option<String>,
+                  header_key: Option<String>,
+                  query: HashMap<String, String>| {
+                let api_key = api_key.clone();
+                async move {
+                    let presented = authorization
+                        .as_deref()
+                        .and_then(|value| value.strip_prefix("Bearer "))
+                        .or(header_key.as_deref())
+                        .or(query.get("key").map(String::as_str));
+                    match presented {
+                        Some(key) if keys_match(key.trim(), &api_key) => Ok(()),
+                        _ => Err(warp::reject::custom(Unauthorized)),
+                    }
+                }
+            },
+        )
+        .untuple_one()
+}
+
+/// Constant-time comparison so the key can't be guessed byte by byte.
+fn keys_match(presented: &str, expected: &str) -> bool {
+    let (a, b) = (presented.as_bytes(), expected.as_bytes());
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+fn status_for(error: &KokoroError) -> StatusCode {
+    match error {
+        KokoroError::Validation(_) => StatusCode::BAD_REQUEST,
+        KokoroError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+        KokoroError::NotFound(_) => StatusCode::NOT_FOUND,
+        KokoroError::ExternalService(_)
+        | KokoroError::Llm(_)
+        | KokoroError::Tts(_)
+        | KokoroError::Stt(_) => StatusCode::BAD_GATEWAY,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+/// Errors use the same `{ code, message }` shape as Tauri command errors.
+fn error_reply(error: KokoroError) -> Response {
+    let status = status_for(&error);
+    warp::reply::with_status(warp::reply::json(&error), status).into_response()
+}
+
+async fn handle_rejection(rejection: Rejection) -> Result<Response, Infallible> {
+    let error = if rejection.find::<Unauthorized>().is_some() {
+        KokoroError::Unauthorized("Missing or invalid API key".to_string())
+    } else if rejection.is_not_found() {
+        KokoroError::NotFound("No such route".to_string())
+    } else if let Some(e) = rejection.find::<warp::filters::body::BodyDeserializeError>() {
+        KokoroError::Validation(e.to_string())
+    } else if let Some(e) = rejection.find::<warp::reject::InvalidQuery>() {
+        KokoroError::Validation(e.to_string())
+    } else if rejection.find::<warp::reject::PayloadTooLarge>().is_some() {
+        KokoroError::Validation("Request body too large".to_string())
+    } else if rejection.find::<warp::reject::MethodNotAllowed>().is_some() {
+        KokoroError::NotFound("Method not allowed for this route".to_string())
+    } else {
+        KokoroError::Validation(format!("{:?}", rejection))
+    };
+    Ok(error_reply(error))
+}
+
+fn state<'a, T: Send + Sync + 'static>(
+    app: &'a AppHandle,
+    name: &str,
+) -> Result<tauri::State<'a, T>, KokoroError> {
+    app.try_state::<T>()
+        .ok_or_else(|| KokoroError::Internal(format!("{} not available", name)))
+}
+
+async fn resolve_character(
+    app: &AppHandle,
+    requested: Option<String>,
+) -> Result<String, KokoroError> {
+    match requested.filter(|id| !id.trim().is_empty()) {
+        Some(id) => Ok(id),
+        None => {
+            let orchestrator = state::<AIOrchestrator>(app, "AI orchestrator")?;
+            Ok(desktop_char_id(&orchestrator).await)
+        }
+    }
+}
+
+// ── /chat ──────────────────────────────────────────
+
+enum ChatStreamEvent {
+    /// Raw model text as it arrives (may still contain control tags).
+    Delta(String),
+    /// The cleaned reply, as stored in history.
+    Done {
+        text: String,
+        translation: Option<String>,
+    },
+    Error(KokoroError),
+}
+
+impl ChatStreamEvent {
+    fn into_sse(self) -> warp::sse::Event {
+        let (name, data) = match self {
+            Self::Delta(text) => ("delta", serde_json::json!({ "text": text })),
+            Self::Done { text, translation } => (
+                "done",
+                serde_json::json!({ "text": text, "translation": translation }),
+            ),
+            Self::Error(error) => ("error", serde_json::json!(error)),
+        };
+        warp::sse::Event::default()
+            .event(name)
+            .data(data.to_string())
+    }
+}
+
+fn chat_stream(request: ChatRequest, app: AppHandle) -> impl Reply {
+    let (tx, rx) = mpsc::unbounded();
+    tauri::async_runtime::spawn(async move {
+        if let Err(error) = run_chat(&app, request, &tx).await {
+            tracing::warn!(target: "api", "[API] chat failed: {}", error);
+            let _ = tx.unbounded_send(ChatStreamEvent::Error(error));
+        }
+    });
+    let stream = rx.map(|event: ChatStreamEvent| Ok::<_, Infallible>(event.into_sse()));
+    warp::sse::reply(warp::sse::keep_alive().stream(stream))
+}
+
+/// A single LLM round on the shared conversation. Tools are not executed
+/// for API clients; any tool tags in the reply are stripped.
+async fn run_chat(
+    app: &AppHandle,
+    request: ChatRequest,
+    tx: &mpsc::UnboundedSender<ChatStreamEvent>,
+) -> Result<(), KokoroError> {
+    let message = request.message.trim().to_string();
+    if message.is_empty() {
+        return Err(KokoroError::Validation(
+            "message must not be empty".to_string(),
+        ));
+    }
+    let orchestrator = state::<AIOrchestrator>(app, "AI orchestrator")?;
+    let llm_service = state::<LlmService>(app, "LLM service")?;
+    let char_id = resolve_character(app, request.character_id).await?;
+
+    let user_metadata = orchestrator.user_message_metadata(&message).await;
+    orchestrator
+        .add_message_with_metadata(
+            "user".to_string(),
+            message.clone(),
+            user_metadata,
+            &char_id,
+            None,
+        )
+        .await;
+
+    let (prompt_messages, compose_warnings) = orchestrator
+        .compose_prompt(&message, false, None, false, &char_id)
+        .await
+        .map_err(|e| KokoroError::Chat(e.to_string()))?;
+    for w in &compose_warnings {
+        tracing::warn!(target: "api", "[API compose_prompt] {}", w);
+    }
+    let mut client_messages = prompt_messages
+        .into_iter()
+        .map(|m| role_text_message(&m.role, m.content))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(KokoroError::Chat)?;
+    if !client_messages.last().map(is_user_message).unwrap_or(false) {
+        client_messages.push(user_text_message(message.clone()));
+    }
+
+    let provider = llm_service.provider().await;
+    let mut stream = provider
+        .chat_stream_rich(client_messages.into_iter().map(Into::into).collect(), None)
+        .await
+        .map_err(KokoroError::Llm)?;
+    let mut raw = String::new();
+    while let Some(item) = stream.next().await {
+        match item {
+            Ok(LlmStreamEvent::Text(text)) => {
+                raw.push_str(&text);
+                // A disconnected client doesn't stop the turn; the reply still lands in history.
+                let _ = tx.unbounded_send(ChatStreamEvent::Delta(text));
+            }
+            Ok(_) => {}
+            Err(e) => return Err(KokoroError::Llm(e)),
+        }
+    }
+
+    let (response, translation) = clean_model_reply(&raw);
+    if !response.is_empty() {
+        let mut metadata = serde_json::json!({ "delivered_via": "api" });
+        if let Some(t) = &translation {
+            metadata["translation"] = serde_json::json!(t);
+        }
+        orchestrator
+            .add_message_with_metadata(
+                "assistant".to_string(),
+                response.clone(),
+                Some(metadata.to_string()),
+                &char_id,
+                None,
+            )
+            .await;
+    }
+    let _ = tx.unbounded_send(ChatStreamEvent::Done {
+        text: response,
+        translation,
+    });
+    Ok(())
+}
+
+// ── /trigger ───────────────────────────────────────
+
+async fn fire_trigger(name: String, app: AppHandle) -> Response {
+    let result = async {
+        let mappings = state::<InputMappingService>(&app, "Input mapping")?;
+        let fired = mappings.fire(&app, TriggerEvent::Http(name.clone())).await;
+        if fired == 0 {
+            return Err(KokoroError::NotFound(format!(
+                "No enabled mapping for trigger '{}'",
+                name
+            )));
+        }
+        Ok(fired)
+    }
+    .await;
+    match result {
+        Ok(fired) => warp::reply::json(&serde_json::json!({ "fired": fired })).into_response(),
+        Err(error) => error_reply(error),
+    }
+}
+
+// ── /tts and /stt ──────────────────────────────────
+
+async fn synthesize(request: TtsRequest, app: AppHandle) -> Response {
+    let result = async {
+        if request.text.trim().is_empty() {
+            return Err(KokoroError::Validation(
+                "text must not be empty".to_string(),
+            ));
+        }
+        let tts = state::<TtsService>(&app, "TTS service")?;
+        tts.synthesize_text_with_provider(&request.text, request.provider_id.as_deref(), None)
+            .await
+            .map_err(KokoroError::Tts)
+    }
+    .await;
+    match result {
+        Ok(audio) => {
+            let mime = audio_mime(&audio);
+            warp::reply::with_header(audio, "content-type", mime).into_response()
+        }
+        Err(error) => error_reply(error),
+    }
+}
+
+/// Sniff the container so clients get a usable Content-Type.
+fn audio_mime(audio: &[u8]) -> &'static str {
+    match audio {
+        [b'R', b'I', b'F', b'F', ..] => "audio/wav",
+        [b'O', b'g', b'g', b'S', ..] => "audio/ogg",
+        [b'f', b'L', b'a', b'C', ..] => "audio/flac",
+        [b'I', b'D', b'3', ..] => "audio/mpeg",
+        [0xFF, second, ..] if second & 0xE0 == 0xE0 => "audio/mpeg",
+        _ => "application/octet-stream",
+    }
+}
+
+async fn transcribe(query: SttQuery, body: bytes::Bytes, app: AppHandle) -> Response {
+    let result = async {
+        if body.is_empty() {
+            return Err(KokoroError::Validation("audio body is empty".to_string()));
+        }
+        let stt = state::<SttService>(&app, "STT service")?;
+        let audio = AudioSource::Encoded {
+            data: body.to_vec(),
+            format: query.format,
+        };
+        stt.transcribe(&audio, query.language.as_deref())
+            .await
+            .map_err(|e| KokoroError::Stt(e.to_string()))
+    }
+    .await;
+    match result {
+        Ok(transcription) => warp::reply::json(&transcription).into_response(),
+        Err(error) => error_reply(error),
+    }
+}
+
+// ── /memories ──────────────────────────────────────
+
+async fn list_memories(query: MemoriesQuery, app: AppHandle) -> Response {
+    let result = async {
+        let orchestrator = state::<AIOrchestrator>(&app, "AI orchestrator")?;
+        let char_id = resolve_character(&app, query.character_id).await?;
+        let limit = query.limit.clamp(1, MAX_MEMORY_PAGE);
+        let memories = orchestrator
+            .memory_manager
+            .list_memories(&char_id, limit, query.offset.max(0))
+            .await
+            .map_err(|e| KokoroError::Database(e.to_string()))?;
+        let total = orchestrator
+            .memory_manager
+            .count_memories(&char_id)
+            .await
+            .map_err(|e| KokoroError::Database(e.to_string()))?;
+        Ok(serde_json::json!({
+            "character_id": char_id,
+            "memories": memories,
+            "total": total,
+        }))
+    }
+    .await;
+    match result {
+        Ok(body) => warp::reply::json(&body).into_response(),
+        Err(error) => error_reply(error),
+    }
+}
+
+async fn add_memory(request: AddMemoryRequest, app: AppHandle) -> Response {
+    let result = async {
+        let content = request.content.trim();
+        if content.is_empty() {
+            return Err(KokoroError::Validation(
+                "content must not be empty".to_string(),
+            ));
+        }
+        let importance = request.importance.unwrap_or(0.5);
+        if !(0.0..=1.0).contains(&importance) {
+            return Err(KokoroError::Validation(
+                "importance must be between 0 and 1".to_string(),
+            ));
+        }
+        let orchestrator = state::<AIOrchestrator>(&app, "AI orchestrator")?;
+        let char_id = resolve_character(&app, request.character_id).await?;
+        orchestrator
+            .memory_manager
+            .add_memory_with_importance(content, &char_id, importance)
+            .await
+            .map_err(|e| KokoroError::Database(e.to_string()))?;
+        Ok(char_id)
+    }
+    .await;
+    match result {
+        Ok(char_id) => warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({ "character_id": char_id })),
+            StatusCode::CREATED,
+        )
+        .into_response(),
+        Err(error) => error_reply(error),
+    }
+}
+
+// ── /events ────────────────────────────────────────
+
+async fn forward_events(socket: WebSocket, mut events: broadcast::Receiver<ApiEvent>) {
+    let (mut sink, mut incoming) = socket.split();
+    loop {
+        tokio::select! {
+            event = events.recv() => match event {
+                Ok(event) => {
+                    let Ok(text) = serde_json::to_string(&event) else {
+                        continue;
+                    };
+                    if sink.send(Message::text(text)).await.is_err() {
+                        break;
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    tracing::warn!(target: "api", "[API] event client lagged, skipped {} events", skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            message = incoming.next() => match message {
+                Some(Ok(message)) if message.is_close() => break,
+                Some(Ok(_)) => {}
+                _ => break,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn guarded() -> impl Filter<Extract = (impl Reply,), Error = Infallible> + Clone {
+        warp::path!("ping")
+            .and(with_auth(Arc::new("secret".to_string())))
+            .map(|| "pong")
+            .recover(handle_rejection)
+    }
+
+    #[tokio::test]
+    async fn auth_accepts_bearer_header_and_query_key() {
+        let filter = guarded();
+        for request in [
+            warp::test::request()
+                .path("/ping")
+                .header("authorization", "Bearer secret"),
+            warp::test::request()
+                .path("/ping")
+                .header("x-api-key", "secret"),
+            warp::test::request().path("/ping?key=secret"),
+        ] {
+            let res = request.reply(&filter).await;
+            assert_eq!(res.status(), StatusCode::OK);
+        }
+    }
+
+    #[tokio::test]
+    async fn auth_rejects_missing_or_wrong_key() {
+        let filter = guarded();
+        let res = warp::test::request().path("/ping").reply(&filter).await;
+        assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+        let body: serde_json::Value = serde_json::from_slice(res.body()).unwrap();
+        assert_eq!(body["code"], "Unauthorized");
+
+        let res = warp::test::request()
+            .path("/ping")
+            .header("authorization", "Bearer secreT")
+            .reply(&filter)
+            .await;
+        assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[test]
+    fn audio_mime_sniffs_common_containers() {
+        assert_eq!(audio_mime(b"RIFF\0\0\0\0WAVE"), "audio/wav");
+        assert_eq!(audio_mime(b"OggS\0"), "audio/ogg");
+        assert_eq!(audio_mime(b"ID3\x04"), "audio/mpeg");
+        assert_eq!(audio_mime(&[0xFF, 0xFB, 0x90]), "audio/mpeg");
+        assert_eq!(audio_mime(b"????"), "application/octet-stream");
+    }
+}
@@ -0,0 +1,688 @@
+//! Content-addressable asset store.
+//!
+//! Generated images, rendered audio and other engine-produced files are
+//! stored once per SHA-256 under `assets/<category>/<aa>/<hash>.<ext>` and
+//! indexed in the `assets` table with a reference count. `clean_assets`
+//! removes unreferenced blobs, enforces per-category size quotas (least
+//! recently used first) and sweeps stale scratch files, with a dry-run mode
+//! that reports what would be deleted.
+
+use crate::error::KokoroError;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::{Row, SqlitePool};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+const MB: u64 = 1024 * 1024;
+/// Vision uploads are only needed for the turn that referenced them.
+const VISION_UPLOAD_MAX_AGE: Duration = Duration::from_secs(24 * 3600);
+/// Leftover extraction dirs from interrupted Live2D imports.
+const IMPORT_TEMP_MAX_AGE: Duration = Duration::from_secs(3600);
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum AssetCategory {
+    GeneratedImages,
+    TtsAudio,
+    VisionCaptures,
+    ModelImports,
+}
+
+impl AssetCategory {
+    pub const ALL: [AssetCategory; 4] = [
+        AssetCategory::GeneratedImages,
+        AssetCategory::TtsAudio,
+        AssetCategory::VisionCaptures,
+        AssetCategory::ModelImports,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AssetCategory::GeneratedImages => "generated_images",
+            AssetCategory::TtsAudio => "tts_audio",
+            AssetCategory::VisionCaptures => "vision_captures",
+            AssetCategory::ModelImports => "model_imports",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AssetQuotaConfig {
+    #[serde(default = "default_generated_images_mb")]
+    pub generated_images_mb: u64,
+    #[serde(default = "default_tts_audio_mb")]
+    pub tts_audio_mb: u64,
+    #[serde(default = "default_vision_captures_mb")]
+    pub vision_captures_mb: u64,
+    #[serde(default = "default_model_imports_mb")]
+    pub model_imports_mb: u64,
+}
+
+fn default_generated_images_mb() -> u64 {
+    1024
+}
+
+fn default_tts_audio_mb() -> u64 {
+    512
+}
+
+fn default_vision_captures_mb() -> u64 {
+    256
+}
+
+fn default_model_imports_mb() -> u64 {
+    2048
+}
+
+impl Default for AssetQuotaConfig {
+    fn default() -> Self {
+        Self {
+            generated_images_mb: default_generated_images_mb(),
+            tts_audio_mb: default_tts_audio_mb(),
+            vision_captures_mb: default_vision_captures_mb(),
+            model_imports_mb: default_model_imports_mb(),
+        }
+    }
+}
+
+impl AssetQuotaConfig {
+    pub fn quota_bytes(&self, category: AssetCategory) -> u64 {
+        let mb = match category {
+            AssetCategory::GeneratedImages => self.generated_images_mb,
+            AssetCategory::TtsAudio => self.tts_audio_mb,
+            AssetCategory::VisionCaptures => self.vision_captures_mb,
+            AssetCategory::ModelImports => self.model_imports_mb,
+        };
+        mb.saturating_mul(MB)
+    }
+}
+
+pub fn config_path() -> PathBuf {
+    app_data_dir().join("asset_quotas.json")
+}
+
+pub fn load_config(path: &Path) -> AssetQuotaConfig {
+    crate::config::load_json_config(path, "ASSETS")
+}
+
+pub fn save_config(path: &Path, config: &AssetQuotaConfig) -> Result<(), KokoroError> {
+    crate::config::save_json_config(path, config, "ASSETS")
+}
+
+fn app_data_dir() -> PathBuf {
+    dirs_next::data_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("com.chyin.kokoro")
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StoredAsset {
+    pub hash: String,
+    pub category: AssetCategory,
+    pub path: String,
+    pub size_bytes: u64,
+    /// False when identical content was already stored and only the ref count moved.
+    pub newly_written: bool,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RemovalReason {
+    Unreferenced,
+    OverQuota,
+    /// File on disk with no index row.
+    Orphaned,
+    /// Index row whose file is already gone.
+    Missing,
+    /// Scratch file past its retention window.
+    Expired,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RemovedAsset {
+    pub path: String,
+    pub size_bytes: u64,
+    pub reason: RemovalReason,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CategoryCleanReport {
+    pub category: AssetCategory,
+    pub quota_bytes: u64,
+    pub bytes_before: u64,
+    pub bytes_after: u64,
+    pub removed: Vec<RemovedAsset>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CleanReport {
+    pub dry_run: bool,
+    pub reclaimed_bytes: u64,
+    pub categories: Vec<CategoryCleanReport>,
+}
+
+/// Index row as seen by the cleanup planner.
+#[derive(Debug, Clone)]
+struct IndexedAsset {
+    hash: String,
+    path: String,
+    size_bytes: u64,
+    ref_count: i64,
+    last_accessed_at: i64,
+}
+
+/// Decide which indexed assets to remove: everything unreferenced, then the
+/// least recently used survivors until the category fits its quota.
+fn plan_removals(
+    mut assets: Vec<IndexedAsset>,
+    quota_bytes: u64,
+) -> Vec<(IndexedAsset, RemovalReason)> {
+    assets.sort_by_key(|asset| asset.last_accessed_at);
+    let (unreferenced, mut kept): (Vec<_>, Vec<_>) =
+        assets.into_iter().partition(|asset| asset.ref_count <= 0);
+    let mut removals: Vec<_> = unreferenced
+        .into_iter()
+        .map(|asset| (asset, RemovalReason::Unreferenced))
+        .collect();
+
+    let mut total: u64 = kept.iter().map(|asset| asset.size_bytes).sum();
+    let mut evict = 0;
+    while total > quota_bytes && evict < kept.len() {
+        total -= kept[evict].size_bytes;
+        evict += 1;
+    }
+    removals.extend(
+        kept.drain(..evict)
+            .map(|asset| (asset, RemovalReason::OverQuota)),
+    );
+    removals
+}
+
+fn content_hash(bytes: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(bytes);
+    format!("{:x}", hasher.finalize())
+}
+
+fn sanitize_extension(ext: &str) -> String {
+    let ext: String = ext
+        .trim_start_matches('.')
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .take(8)
+        .collect::<String>()
+        .to_ascii_lowercase();
+    if ext.is_empty() {
+        "bin".to_string()
+    } else {
+        ext
+    }
+}
+
+fn now_ts() -> i64 {
+    chrono::Utc::now().timestamp()
+}
+
+fn collect_files(dir: &Path, out: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_files(&path, out);
+        } else {
+            out.push(path);
+        }
+    }
+}
+
+fn dir_size(path: &Path) -> u64 {
+    let mut files = Vec::new();
+    collect_files(path, &mut files);
+    files
+        .iter()
+        .filter_map(|file| std::fs::metadata(file).ok())
+        .map(|meta| meta.len())
+        .sum()
+}
+
+fn is_older_than(path: &Path, age: Duration) -> bool {
+    std::fs::metadata(path)
+        .and_then(|meta| meta.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+        .is_some_and(|elapsed| elapsed >= age)
+}
+
+/// Managed Tauri state; cheap to clone.
+#[derive(Clone)]
+pub struct AssetStore {
+    db: SqlitePool,
+    root: PathBuf,
+    /// App data dir, for sweeping legacy scratch locations.
+    app_data: PathBuf,
+}
+
+impl AssetStore {
+    pub fn new(db: SqlitePool, app_data: PathBuf) -> Self {
+        Self {
+            db,
+            root: app_data.join("assets"),
+            app_data,
+        }
+    }
+
+    fn blob_path(&self, category: AssetCategory, hash: &str, ext: &str) -> PathBuf {
+        self.root
+            .join(category.as_str())
+            .join(&hash[..2])
+            .join(format!("{}.{}", hash, ext))
+    }
+
+    /// Store `bytes`, deduplicating by content. Each call takes one reference.
+    pub async fn put(
+        &self,
+        category: AssetCategory,
+        bytes: &[u8],
+        ext: &str,
+    ) -> Result<StoredAsset, KokoroError> {
+        let hash = content_hash(bytes);
+        let now = now_ts();
+
+        let existing: Option<String> =
+            sqlx::query_scalar("SELECT path FROM assets WHERE category = ? AND hash = ?")
+                .bind(category.as_str())
+                .bind(&hash)
+                .fetch_optional(&self.db)
+                .await?;
+        if let Some(path) = existing {
+            if Path::new(&path).exists() {
+                sqlx::query(
+                    "UPDATE assets SET ref_count = ref_count + 1, last_accessed_at = ? WHERE category = ? AND hash = ?",
+                )
+                .bind(now)
+                .bind(category.as_str())
+                .bind(&hash)
+                .execute(&self.db)
+                .await?;
+                return Ok(StoredAsset {
+                    hash,
+                    category,
+                    path,
+                    size_bytes: bytes.len() as u64,
+                    newly_written: false,
+                });
+            }
+        }
+
+        let path = self.blob_path(category, &hash, &sanitize_extension(ext));
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        tokio::fs::write(&path, bytes).await?;
+        let path = path.to_string_lossy().to_string();
+        sqlx::query(
+            "INSERT INTO assets (hash, category, path, size_bytes, ref_count, created_at, last_accessed_at)
+             VALUES (?, ?, ?, ?, 1, ?, ?)
+             ON CONFLICT(category, hash) DO UPDATE SET path = excluded.path, ref_count = ref_count + 1, last_accessed_at = excluded.last_accessed_at",
+        )
+        .bind(&hash)
+        .bind(category.as_str())
+        .bind(&path)
+        .bind(bytes.len() as i64)
+        .bind(now)
+        .bind(now)
+        .execute(&self.db)
+        .await?;
+
+        Ok(StoredAsset {
+            hash,
+            category,
+            path,
+            size_bytes: bytes.len() as u64,
+            newly_written: true,
+        })
+    }
+
+    /// Take an extra reference (e.g. a second conversation showing the same image).
+    pub async fn retain(&self, category: AssetCategory, hash: &str) -> Result<(), KokoroError> {
+        self.adjust_refs(category, hash, 1).await
+    }
+
+    /// Drop a reference; the blob is deleted by the next clean once it reaches zero.
+    pub async fn release(&self, category: AssetCategory, hash: &str) -> Result<(), KokoroError> {
+        self.adjust_refs(category, hash, -1).await
+    }
+
+    async fn adjust_refs(
+        &self,
+        category: AssetCategory,
+        hash: &str,
+        delta: i64,
+    ) -> Result<(), KokoroError> {
+        let result = sqlx::query(
+            "UPDATE assets SET ref_count = MAX(ref_count + ?, 0), last_accessed_at = ? WHERE category = ? AND hash = ?",
+        )
+        .bind(delta)
+        .bind(now_ts())
+        .bind(category.as_str())
+        .bind(hash)
+        .execute(&self.db)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Err(KokoroError::NotFound(format!(
+                "Asset {}/{} not found",
+                category.as_str(),
+                hash
+            )));
+        }
+        Ok(())
+    }
+
+    /// Total indexed bytes per category.
+    pub async fn usage(&self) -> Result<Vec<(AssetCategory, u64)>, KokoroError> {
+        let mut usage = Vec::new();
+        for category in AssetCategory::ALL {
+            let bytes: i64 = sqlx::query_scalar(
+                "SELECT COALESCE(SUM(size_bytes), 0) FROM assets WHERE category = ?",
+            )
+            .bind(category.as_str())
+            .fetch_one(&self.db)
+            .await?;
+            usage.push((category, bytes.max(0) as u64));
+        }
+        Ok(usage)
+    }
+
+    /// Garbage-collect and enforce quotas. With `dry_run`, nothing is deleted
+    /// and the report lists what would be.
+    pub async fn clean(
+        &self,
+        quotas: &AssetQuotaConfig,
+        dry_run: bool,
+    ) -> Result<CleanReport, KokoroError> {
+        let mut categories = Vec::new();
+        for category in AssetCategory::ALL {
+            categories.push(self.clean_category(category, quotas, dry_run).await?);
+        }
+        let reclaimed_bytes = categories
+            .iter()
+            .flat_map(|report| report.removed.iter())
+            .map(|removed| removed.size_bytes)
+            .sum();
+
+        tracing::info!(
+            target: "assets",
+            "[Assets] {} {} bytes across {} file(s)",
+            if dry_run { "Would reclaim" } else { "Reclaimed" },
+            reclaimed_bytes,
+            categories.iter().map(|r| r.removed.len()).sum::<usize>()
+        );
+        Ok(CleanReport {
+            dry_run,
+            reclaimed_bytes,
+            categories,
+        })
+    }
+
+    async fn clean_category(
+        &self,
+        category: AssetCategory,
+        quotas: &AssetQuotaConfig,
+        dry_run: bool,
+    ) -> Result<CategoryCleanReport, KokoroError> {
+        let rows = sqlx::query(
+            "SELECT hash, path, size_bytes, ref_count, last_accessed_at FROM assets WHERE category = ?",
+        )
+        .bind(category.as_str())
+        .fetch_all(&self.db)
+        .await?;
+
+        let mut removed = Vec::new();
+        let mut present = Vec::new();
+        for row in rows {
+            let asset = IndexedAsset {
+                hash: row.get("hash"),
+                path: row.get("path"),
+                size_bytes: row.get::<i64, _>("size_bytes").max(0) as u64,
+                ref_count: row.get("ref_count"),
+                last_accessed_at: row.get("last_accessed_at"),
+            };
+            if Path::new(&asset.path).exists() {
+                present.push(asset);
+            } else {
+                if !dry_run {
+                    self.delete_row(category, &asset.hash).await?;
+                }
+                removed.push(RemovedAsset {
+                    path: asset.path,
+                    size_bytes: 0,
+                    reason: RemovalReason::Missing,
+                });
+            }
+        }
+
+        let indexed_paths: HashSet<PathBuf> =
+            present.iter().map(|a| PathBuf::from(&a.path)).collect();
+        let bytes_indexed: u64 = present.iter().map(|a| a.size_bytes).sum();
+        let quota_bytes = quotas.quota_bytes(category);
+
+        for (asset, reason) in plan_removals(present, quota_bytes) {
+            if !dry_run {
+                let _ = tokio::fs::remove_file(&asset.path).await;
+                self.delete_row(category, &asset.hash).await?;
+            }
+            removed.push(RemovedAsset {
+                path: asset.path,
+                size_bytes: asset.size_bytes,
+                reason,
+            });
+        }
+
+        // Files under the category dir that the index doesn't know about.
+        let mut on_disk = Vec::new();
+        collect_files(&self.root.join(category.as_str()), &mut on_disk);
+        let mut bytes_unindexed = 0;
+        for file in on_disk
+            .into_iter()
+            .filter(|file| !indexed_paths.contains(file))
+        {
+            let size = std::fs::metadata(&file).map(|m| m.len()).unwrap_or(0);
+            bytes_unindexed += size;
+            if !dry_run {
+                let _ = tokio::fs::remove_file(&file).await;
+            }
+            removed.push(RemovedAsset {
+                path: file.to_string_lossy().to_string(),
+                size_bytes: size,
+                reason: RemovalReason::Orphaned,
+            });
+        }
+
+        let bytes_scratch = self.sweep_scratch(category, dry_run, &mut removed).await;
+
+        let bytes_before = bytes_indexed + bytes_unindexed + bytes_scratch;
+        let bytes_after =
+            bytes_before.saturating_sub(removed.iter().map(|r| r.size_bytes).sum::<u64>());
+        Ok(CategoryCleanReport {
+            category,
+            quota_bytes,
+            bytes_before,
+            bytes_after,
+            removed,
+        })
+    }
+
+    /// Expire scratch files that live outside the store. Returns their total size before cleaning.
+    async fn sweep_scratch(
+        &self,
+        category: AssetCategory,
+        dry_run: bool,
+        removed: &mut Vec<RemovedAsset>,
+    ) -> u64 {
+        let mut total = 0;
+        match category {
+            AssetCategory::VisionCaptures => {
+                let mut files = Vec::new();
+                collect_files(&self.app_data.join("vision_uploads"), &mut files);
+                for file in files {
+                    let size = std::fs::metadata(&file).map(|m| m.len()).unwrap_or(0);
+                    total += size;
+                    if is_older_than(&file, VISION_UPLOAD_MAX_AGE) {
+                        if !dry_run {
+                            let _ = tokio::fs::remove_file(&file).await;
+                        }
+                        removed.push(RemovedAsset {
+                            path: file.to_string_lossy().to_string(),
+                            size_bytes: size,
+                            reason: RemovalReason::Expired,
+                        });
+                    }
+                }
+            }
+            AssetCategory::ModelImports => {
+                let Ok(entries) = std::fs::read_dir(&self.app_data) else {
+                    return 0;
+                };
+                for entry in entries.flatten() {
+                    let path = entry.path();
+                    let is_import_tmp = path.is_dir()
+                        && entry
+                            .file_name()
+                            .to_string_lossy()
+                            .starts_with("live2d_import_tmp_");
+                    if !is_import_tmp {
+                        continue;
+                    }
+                    let size = dir_size(&path);
+                    total += size;
+                    if is_older_than(&path, IMPORT_TEMP_MAX_AGE) {
+                        if !dry_run {
+                            let _ = tokio::fs::remove_dir_all(&path).await;
+                        }
+                        removed.push(RemovedAsset {
+                            path: path.to_string_lossy().to_string(),
+                            size_bytes: size,
+                            reason: RemovalReason::Expired,
+                        });
+                    }
+                }
+            }
+            AssetCategory::GeneratedImages | AssetCategory::TtsAudio => {}
+        }
+        total
+    }
+
+    async fn delete_row(&self, category: AssetCategory, hash: &str) -> Result<(), KokoroError> {
+        sqlx::query("DELETE FROM assets WHERE category = ? AND hash = ?")
+            .bind(category.as_str())
+            .bind(hash)
+            .execute(&self.db)
+            .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn asset(hash: &str, size: u64, refs: i64, accessed: i64) -> IndexedAsset {
+        IndexedAsset {
+            hash: hash.to_string(),
+            path: format!("/tmp/{}", hash),
+            size_bytes: size,
+            ref_count: refs,
+            last_accessed_at: accessed,
+        }
+    }
+
+    fn planned(assets: Vec<IndexedAsset>, quota: u64) -> Vec<(String, RemovalReason)> {
+        plan_removals(assets, quota)
+            .into_iter()
+            .map(|(asset, reason)| (asset.hash, reason))
+            .collect()
+    }
+
+    #[test]
+    fn plan_drops_unreferenced_then_lru_over_quota() {
+        let assets = vec![
+            asset("new", 40, 1, 30),
+            asset("old", 40, 1, 10),
+            asset("free", 40, 0, 20),
+            asset("mid", 40, 2, 20),
+        ];
+        assert_eq!(
+            planned(assets, 90),
+            vec![
+                ("free".to_string(), RemovalReason::Unreferenced),
+                ("old".to_string(), RemovalReason::OverQuota),
+            ]
+        );
+    }
+
+    #[test]
+    fn plan_keeps_everything_within_quota() {
+        assert!(planned(vec![asset("a", 10, 1, 1), asset("b", 10, 3, 2)], 20).is_empty());
+    }
+
+    #[test]
+    fn sanitizes_extensions() {
+        assert_eq!(sanitize_extension(".PNG"), "png");
+        assert_eq!(sanitize_extension("../../x"), "x");
+        assert_eq!(sanitize_extension(""), "bin");
+    }
+
+    #[tokio::test]
+    async fn put_dedups_and_clean_respects_refs() {
+        let pool = sqlx::SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+        let app_data = std::env::temp_dir().join(format!("kokoro-assets-{}", uuid::Uuid::new_v4()));
+        let store = AssetStore::new(pool, app_data.clone());
+
+        let first = store
+            .put(AssetCategory::GeneratedImages, b"pixels", "png")
+            .await
+            .unwrap();
+        let second = store
+            .put(AssetCategory::GeneratedImages, b"pixels", "png")
+            .await
+            .unwrap();
+        assert!(first.newly_written);
+        assert!(!second.newly_written);
+        assert_eq!(first.path, second.path);
+
+        store
+            .release(AssetCategory::GeneratedImages, &first.hash)
+            .await
+            .unwrap();
+        let report = store
+            .clean(&AssetQuotaConfig::default(), false)
+            .await
+            .unwrap();
+        assert_eq!(report.reclaimed_bytes, 0);
+
+        store
+            .release(AssetCategory::GeneratedImages, &first.hash)
+            .await
+            .unwrap();
+        let dry = store
+            .clean(&AssetQuotaConfig::default(), true)
+            .await
+            .unwrap();
+        assert_eq!(dry.reclaimed_bytes, 6);
+        assert!(Path::new(&first.path).exists());
+
+        let report = store
+            .clean(&AssetQuotaConfig::default(), false)
+            .await
+            .unwrap();
+        assert_eq!(report.reclaimed_bytes, 6);
+        assert!(!Path::new(&first.path).exists());
+
+        let _ = std::fs::remove_dir_all(app_data);
+    }
+}
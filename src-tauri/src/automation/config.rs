@@ -0,0 +1,274 @@
+//! Automation rules — persisted in `automation_rules.json`.
+
+use crate::ai::scheduler::{CronRule, ScheduleRule};
+use crate::error::KokoroError;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Longest pause a `delay` step may request.
+pub const MAX_DELAY_SECS: u64 = 300;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AutomationTrigger {
+    /// Once per launch, after the main window has loaded.
+    Startup,
+    /// Five-field cron expression in local time, e.g. `0 9 * * 1-5`.
+    Cron { expr: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AutomationStep {
+    LoadMod {
+        mod_id: String,
+    },
+    UnloadMod,
+    /// Run a registered action (tool) with fixed arguments.
+    RunAction {
+        name: String,
+        #[serde(default)]
+        args: HashMap<String, String>,
+    },
+    /// Ask the character to say something.
+    Proactive {
+        instruction: String,
+    },
+    /// Pause before the next step.
+    Delay {
+        seconds: u64,
+    },
+    // Steps below change state the frontend owns and are carried out there.
+    SwitchCharacter {
+        character_id: String,
+    },
+    /// Hands-free listening (continuous STT with auto-send).
+    SetVoiceMode {
+        enabled: bool,
+    },
+    SetTtsEnabled {
+        enabled: bool,
+    },
+}
+
+impl AutomationStep {
+    fn validate(&self) -> Result<(), String> {
+        let require = |value: &str, what: &str| {
+            if value.trim().is_empty() {
+                Err(format!("{} is empty", what))
+            } else {
+                Ok(())
+            }
+        };
+        match self {
+            AutomationStep::LoadMod { mod_id } => require(mod_id, "mod id"),
+            AutomationStep::RunAction { name, .. } => require(name, "action name"),
+            AutomationStep::Proactive { instruction } => require(instruction, "instruction"),
+            AutomationStep::SwitchCharacter { character_id } => {
+                require(character_id, "character id")
+            }
+            AutomationStep::Delay { seconds } if *seconds > MAX_DELAY_SECS => {
+                Err(format!("delay is limited to {} seconds", MAX_DELAY_SECS))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AutomationRule {
+    pub id: String,
+    pub name: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    pub trigger: AutomationTrigger,
+    pub steps: Vec<AutomationStep>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl AutomationRule {
+    pub fn validate(&self) -> Result<(), KokoroError> {
+        if self.name.trim().is_empty() {
+            return Err(KokoroError::Validation(
+                "automation rule name is empty".to_string(),
+            ));
+        }
+        if self.steps.is_empty() {
+            return Err(KokoroError::Validation(format!(
+                "automation rule '{}' has no steps",
+                self.name
+            )));
+        }
+        if let AutomationTrigger::Cron { expr } = &self.trigger {
+            CronRule::parse(expr)?;
+        }
+        for (index, step) in self.steps.iter().enumerate() {
+            step.validate().map_err(|e| {
+                KokoroError::Validation(format!(
+                    "automation rule '{}', step {}: {}",
+                    self.name,
+                    index + 1,
+                    e
+                ))
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Whether a cron rule has an occurrence in `(after, now]` (unix seconds).
+    pub fn cron_due(&self, after: i64, now: i64) -> bool {
+        match &self.trigger {
+            AutomationTrigger::Cron { expr } if self.enabled => {
+                ScheduleRule::Cron { expr: expr.clone() }
+                    .next_run_after(after)
+                    .is_some_and(|at| at <= now)
+            }
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct AutomationConfig {
+    #[serde(default)]
+    pub rules: Vec<AutomationRule>,
+}
+
+impl AutomationConfig {
+    /// Insert `rule`, or replace the rule with the same id.
+    pub fn upsert(&mut self, rule: AutomationRule) {
+        match self.rules.iter_mut().find(|r| r.id == rule.id) {
+            Some(existing) => *existing = rule,
+            None => self.rules.push(rule),
+        }
+    }
+
+    pub fn remove(&mut self, id: &str) -> bool {
+        let before = self.rules.len();
+        self.rules.retain(|r| r.id != id);
+        self.rules.len() != before
+    }
+
+    pub fn startup_rules(&self) -> Vec<AutomationRule> {
+        self.rules
+            .iter()
+            .filter(|r| r.enabled && r.trigger == AutomationTrigger::Startup)
+            .cloned()
+            .collect()
+    }
+
+    pub fn due_cron_rules(&self, after: i64, now: i64) -> Vec<AutomationRule> {
+        self.rules
+            .iter()
+            .filter(|r| r.cron_due(after, now))
+            .cloned()
+            .collect()
+    }
+}
+
+pub fn config_path() -> PathBuf {
+    dirs_next::data_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("com.chyin.kokoro")
+        .join("automation_rules.json")
+}
+
+pub fn load_config(path: &Path) -> AutomationConfig {
+    crate::config::load_json_config(path, "Automation")
+}
+
+pub fn save_config(path: &Path, config: &AutomationConfig) -> Result<(), KokoroError> {
+    crate::config::save_json_config(path, config, "Automation")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Local, TimeZone};
+
+    fn rule(id: &str, trigger: AutomationTrigger) -> AutomationRule {
+        AutomationRule {
+            id: id.to_string(),
+            name: id.to_string(),
+            enabled: true,
+            trigger,
+            steps: vec![AutomationStep::SetVoiceMode { enabled: true }],
+        }
+    }
+
+    fn local(y: i32, m: u32, d: u32, h: u32, min: u32) -> i64 {
+        Local
+            .with_ymd_and_hms(y, m, d, h, min, 0)
+            .earliest()
+            .unwrap()
+            .timestamp()
+    }
+
+    #[test]
+    fn parses_rules_and_validates_steps() {
+        let config: AutomationConfig = serde_json::from_str(
+            r#"{"rules":[{"id":"a","name":"Morning","trigger":{"type":"cron","expr":"0 9 * * 1-5"},
+                "steps":[{"type":"switch_character","character_id":"work"},{"type":"delay","seconds":5}]}]}"#,
+        )
+        .unwrap();
+        let parsed = &config.rules[0];
+        assert!(parsed.enabled);
+        assert!(parsed.validate().is_ok());
+        assert_eq!(
+            parsed.steps[0],
+            AutomationStep::SwitchCharacter {
+                character_id: "work".to_string()
+            }
+        );
+
+        let mut bad = rule(
+            "b",
+            AutomationTrigger::Cron {
+                expr: "0 9 * *".to_string(),
+            },
+        );
+        assert!(bad.validate().is_err());
+        bad.trigger = AutomationTrigger::Startup;
+        bad.steps = vec![AutomationStep::Delay {
+            seconds: MAX_DELAY_SECS + 1,
+        }];
+        assert!(bad.validate().is_err());
+        bad.steps.clear();
+        assert!(bad.validate().is_err());
+    }
+
+    #[test]
+    fn cron_rules_fire_once_per_window() {
+        let mut config = AutomationConfig::default();
+        config.upsert(rule("startup", AutomationTrigger::Startup));
+        config.upsert(rule(
+            "weekday",
+            AutomationTrigger::Cron {
+                expr: "0 9 * * 1-5".to_string(),
+            },
+        ));
+        assert_eq!(config.startup_rules().len(), 1);
+
+        // 2026-03-02 is a Monday.
+        let before = local(2026, 3, 2, 8, 59);
+        let at_nine = local(2026, 3, 2, 9, 0);
+        let after = local(2026, 3, 2, 9, 1);
+        assert_eq!(config.due_cron_rules(before, at_nine).len(), 1);
+        assert!(config.due_cron_rules(at_nine, after).is_empty());
+        // Saturday.
+        assert!(config
+            .due_cron_rules(local(2026, 3, 7, 8, 59), local(2026, 3, 7, 9, 0))
+            .is_empty());
+
+        config.rules[1].enabled = false;
+        assert!(config.due_cron_rules(before, at_nine).is_empty());
+
+        assert!(config.remove("weekday"));
+        assert!(!config.remove("weekday"));
+        assert_eq!(config.rules.len(), 1);
+    }
+}
@@ -0,0 +1,217 @@
+//! Automation rules — glue existing engine features together without a mod.
+//!
+//! A rule pairs a trigger (app startup, or a cron time such as weekdays at
+//! 9am) with a list of steps. Steps that touch backend state (mods, actions,
+//! proactive messages) run here; steps that change frontend-owned state
+//! (active character, voice mode, TTS toggle) are sent to the main window as
+//! `automation-step` events. Startup rules wait for the main window to report
+//! ready so those events are not lost.
+
+pub mod config;
+
+pub use config::{
+    config_path, load_config, save_config, AutomationConfig, AutomationRule, AutomationStep,
+    AutomationTrigger,
+};
+
+use crate::ai::context::AIOrchestrator;
+use crate::ai::scheduler;
+use crate::mods::ModManager;
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::{Mutex, RwLock};
+
+/// How often cron rules are checked.
+const TICK: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Serialize)]
+struct AutomationStepEvent {
+    rule_id: String,
+    step: AutomationStep,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StepOutcome {
+    pub success: bool,
+    pub message: String,
+}
+
+/// Emitted as `automation-run` after a rule finishes.
+#[derive(Debug, Clone, Serialize)]
+pub struct AutomationRunEvent {
+    pub rule_id: String,
+    pub name: String,
+    pub success: bool,
+    pub steps: Vec<StepOutcome>,
+}
+
+/// Managed Tauri state holding the rules.
+#[derive(Clone)]
+pub struct AutomationService {
+    config: Arc<RwLock<AutomationConfig>>,
+    startup_done: Arc<AtomicBool>,
+}
+
+impl AutomationService {
+    pub fn new(config: AutomationConfig) -> Self {
+        Self {
+            config: Arc::new(RwLock::new(config)),
+            startup_done: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    pub async fn get_config(&self) -> AutomationConfig {
+        self.config.read().await.clone()
+    }
+
+    /// Apply `change` to the rules and persist the result.
+    pub async fn update<T>(
+        &self,
+        change: impl FnOnce(&mut AutomationConfig) -> T,
+    ) -> Result<T, crate::error::KokoroError> {
+        let mut config = self.config.write().await;
+        let mut next = config.clone();
+        let result = change(&mut next);
+        save_config(&config_path(), &next)?;
+        *config = next;
+        Ok(result)
+    }
+
+    /// Run the startup rules once per launch. Later calls (e.g. after a
+    /// webview reload) do nothing.
+    pub async fn run_startup(&self, app: &AppHandle) -> usize {
+        if self.startup_done.swap(true, Ordering::SeqCst) {
+            return 0;
+        }
+        let rules = self.config.read().await.startup_rules();
+        let count = rules.len();
+        for rule in rules {
+            spawn_rule(app, rule);
+        }
+        count
+    }
+
+    /// Check cron rules every [`TICK`]. Occurrences while the app was closed
+    /// are not replayed.
+    pub fn spawn_cron_loop(&self, app: AppHandle) {
+        let service = self.clone();
+        tauri::async_runtime::spawn(async move {
+            let mut last = chrono::Utc::now().timestamp();
+            let mut interval = tokio::time::interval(TICK);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                interval.tick().await;
+                let now = chrono::Utc::now().timestamp();
+                if now <= last {
+                    continue;
+                }
+                let due = service.config.read().await.due_cron_rules(last, now);
+                last = now;
+                for rule in due {
+                    spawn_rule(&app, rule);
+                }
+            }
+        });
+    }
+}
+
+fn spawn_rule(app: &AppHandle, rule: AutomationRule) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        run_rule(&app, &rule).await;
+    });
+}
+
+/// Run every step of `rule` in order. A failing step is reported and the
+/// remaining steps still run.
+pub async fn run_rule(app: &AppHandle, rule: &AutomationRule) -> AutomationRunEvent {
+    tracing::info!(target: "automation", "[Automation] Running '{}' ({})", rule.name, rule.id);
+    let mut steps = Vec::with_capacity(rule.steps.len());
+    for step in &rule.steps {
+        let outcome = match run_step(app, rule, step).await {
+            Ok(message) => StepOutcome {
+                success: true,
+                message,
+            },
+            Err(message) => {
+                tracing::warn!(
+                    target: "automation",
+                    "[Automation] '{}' step failed: {}",
+                    rule.name,
+                    message
+                );
+                StepOutcome {
+                    success: false,
+                    message,
+                }
+            }
+        };
+        steps.push(outcome);
+    }
+    let event = AutomationRunEvent {
+        rule_id: rule.id.clone(),
+        name: rule.name.clone(),
+        success: steps.iter().all(|s| s.success),
+        steps,
+    };
+    let _ = app.emit("automation-run", &event);
+    event
+}
+
+async fn run_step(
+    app: &AppHandle,
+    rule: &AutomationRule,
+    step: &AutomationStep,
+) -> Result<String, String> {
+    match step {
+        AutomationStep::LoadMod { mod_id } => {
+            let manager = app
+                .try_state::<Mutex<ModManager>>()
+                .ok_or("mod manager not available")?;
+            manager.lock().await.load_mod(mod_id, app).await?;
+            Ok(format!("loaded mod '{}'", mod_id))
+        }
+        AutomationStep::UnloadMod => {
+            let manager = app
+                .try_state::<Mutex<ModManager>>()
+                .ok_or("mod manager not available")?;
+            manager.lock().await.unload_mod(app).await;
+            Ok("unloaded mod".to_string())
+        }
+        AutomationStep::RunAction { name, args } => {
+            let character_id = match app.try_state::<AIOrchestrator>() {
+                Some(orchestrator) => orchestrator.get_character_id().await,
+                None => "default".to_string(),
+            };
+            scheduler::run_action(app, &character_id, name, args.clone(), "automation").await
+        }
+        AutomationStep::Proactive { instruction } => {
+            let orchestrator = app
+                .try_state::<AIOrchestrator>()
+                .ok_or("AI orchestrator not available")?;
+            scheduler::request_proactive(app, &orchestrator, "automation", &rule.name, instruction)
+                .await;
+            Ok("proactive message requested".to_string())
+        }
+        AutomationStep::Delay { seconds } => {
+            tokio::time::sleep(Duration::from_secs((*seconds).min(config::MAX_DELAY_SECS))).await;
+            Ok(format!("waited {}s", seconds))
+        }
+        AutomationStep::SwitchCharacter { .. }
+        | AutomationStep::SetVoiceMode { .. }
+        | AutomationStep::SetTtsEnabled { .. } => {
+            app.emit(
+                "automation-step",
+                AutomationStepEvent {
+                    rule_id: rule.id.clone(),
+                    step: step.clone(),
+                },
+            )
+            .map_err(|e| e.to_string())?;
+            Ok("sent to the main window".to_string())
+        }
+    }
+}
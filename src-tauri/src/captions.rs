@@ -0,0 +1,246 @@
+//! Live captions for voice mode.
+//!
+//! While enabled, user transcripts (partials from `snapshot_audio_stream`,
+//! finals from `complete_audio_stream`) and the assistant's spoken sentences
+//! are emitted on the dedicated `captions` event with timing, so a subtitle
+//! overlay or OBS text source can follow the conversation. Optionally the
+//! latest line is also written to `captions.txt` for OBS "read from file".
+
+use crate::stt::TranscriptionResult;
+use serde::Serialize;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Instant;
+use tauri::{AppHandle, Emitter, Manager};
+
+pub const CAPTION_EVENT: &str = "captions";
+
+/// Rough speaking rates used to time assistant captions.
+const MS_PER_CJK_CHAR: f32 = 220.0;
+const MS_PER_OTHER_CHAR: f32 = 65.0;
+const MIN_CAPTION_MS: u64 = 800;
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CaptionSpeaker {
+    User,
+    Assistant,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CaptionEvent {
+    /// Monotonic counter; overlays drop events older than the last one shown.
+    pub seq: u64,
+    pub speaker: CaptionSpeaker,
+    /// Shared by all partials of one user utterance / all sentences of one reply.
+    pub utterance_id: String,
+    pub text: String,
+    /// `false` for user partials and non-final reply sentences.
+    pub is_final: bool,
+    /// When the caption starts, in ms since captions were enabled.
+    pub at_ms: u64,
+    /// How long it should stay on screen.
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CaptionStatus {
+    pub enabled: bool,
+    pub file_output: bool,
+    pub file_path: String,
+}
+
+pub struct CaptionService {
+    enabled: AtomicBool,
+    file_output: AtomicBool,
+    started: Mutex<Instant>,
+    seq: AtomicU64,
+    /// Utterance id of the user turn currently being transcribed.
+    user_utterance: Mutex<Option<String>>,
+}
+
+impl Default for CaptionService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CaptionService {
+    pub fn new() -> Self {
+        Self {
+            enabled: AtomicBool::new(false),
+            file_output: AtomicBool::new(false),
+            started: Mutex::new(Instant::now()),
+            seq: AtomicU64::new(0),
+            user_utterance: Mutex::new(None),
+        }
+    }
+
+    pub fn file_path() -> PathBuf {
+        dirs_next::data_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("com.chyin.kokoro")
+            .join("captions.txt")
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::SeqCst)
+    }
+
+    /// Turn the feed on or off; enabling restarts the caption clock.
+    pub fn set_enabled(&self, enabled: bool, file_output: bool) {
+        if enabled && !self.is_enabled() {
+            *self.started.lock().unwrap_or_else(|e| e.into_inner()) = Instant::now();
+            self.seq.store(0, Ordering::SeqCst);
+        }
+        self.enabled.store(enabled, Ordering::SeqCst);
+        self.file_output.store(file_output, Ordering::SeqCst);
+        *self
+            .user_utterance
+            .lock()
+            .unwrap_or_else(|e| e.into_inner()) = None;
+        if !enabled || !file_output {
+            let _ = std::fs::remove_file(Self::file_path());
+        }
+    }
+
+    pub fn status(&self) -> CaptionStatus {
+        CaptionStatus {
+            enabled: self.is_enabled(),
+            file_output: self.file_output.load(Ordering::SeqCst),
+            file_path: Self::file_path().to_string_lossy().to_string(),
+        }
+    }
+
+    fn now_ms(&self) -> u64 {
+        self.started
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .elapsed()
+            .as_millis() as u64
+    }
+
+    fn emit(&self, app: &AppHandle, mut event: CaptionEvent) {
+        event.seq = self.seq.fetch_add(1, Ordering::SeqCst) + 1;
+        if self.file_output.load(Ordering::SeqCst) {
+            if let Err(e) = std::fs::write(Self::file_path(), &event.text) {
+                tracing::warn!(target: "captions", "[Captions] Failed to write caption file: {}", e);
+            }
+        }
+        let _ = app.emit(CAPTION_EVENT, event);
+    }
+
+    /// Caption a user transcript; partials share an utterance id until the final.
+    pub fn user_transcript(&self, app: &AppHandle, result: &TranscriptionResult, is_final: bool) {
+        if !self.is_enabled() {
+            return;
+        }
+        let text = result.text.trim();
+        let utterance_id = {
+            let mut current = self
+                .user_utterance
+                .lock()
+                .unwrap_or_else(|e| e.into_inner());
+            if text.is_empty() && current.is_none() {
+                return;
+            }
+            let id = current
+                .get_or_insert_with(|| uuid::Uuid::new_v4().to_string())
+                .clone();
+            if is_final {
+                *current = None;
+            }
+            id
+        };
+        let spoken_ms = result
+            .segments
+            .iter()
+            .map(|segment| ((segment.end - segment.start).max(0.0) * 1000.0) as u64)
+            .sum::<u64>();
+        self.emit(
+            app,
+            CaptionEvent {
+                seq: 0,
+                speaker: CaptionSpeaker::User,
+                utterance_id,
+                text: text.to_string(),
+                is_final,
+                at_ms: self.now_ms(),
+                duration_ms: spoken_ms.max(MIN_CAPTION_MS),
+            },
+        );
+    }
+
+    /// Forget an in-progress user utterance whose audio was discarded.
+    pub fn discard_user_utterance(&self) {
+        *self
+            .user_utterance
+            .lock()
+            .unwrap_or_else(|e| e.into_inner()) = None;
+    }
+
+    /// Caption one spoken sentence of an assistant reply as its audio starts.
+    pub fn assistant_sentence(
+        &self,
+        app: &AppHandle,
+        utterance_id: &str,
+        text: &str,
+        speed: Option<f32>,
+        is_final: bool,
+    ) {
+        if !self.is_enabled() || text.trim().is_empty() {
+            return;
+        }
+        self.emit(
+            app,
+            CaptionEvent {
+                seq: 0,
+                speaker: CaptionSpeaker::Assistant,
+                utterance_id: utterance_id.to_string(),
+                text: text.trim().to_string(),
+                is_final,
+                at_ms: self.now_ms(),
+                duration_ms: estimate_speech_ms(text, speed),
+            },
+        );
+    }
+}
+
+/// Estimated playback time for `text` at the given TTS speed multiplier.
+pub fn estimate_speech_ms(text: &str, speed: Option<f32>) -> u64 {
+    let raw: f32 = text
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| {
+            if crate::utils::segment::is_cjk(c) {
+                MS_PER_CJK_CHAR
+            } else {
+                MS_PER_OTHER_CHAR
+            }
+        })
+        .sum();
+    let speed = speed.filter(|s| *s > 0.1).unwrap_or(1.0);
+    ((raw / speed) as u64).max(MIN_CAPTION_MS)
+}
+
+/// Convenience for call sites that only have an `AppHandle`.
+pub fn caption_user_transcript(app: &AppHandle, result: &TranscriptionResult, is_final: bool) {
+    if let Some(captions) = app.try_state::<CaptionService>() {
+        captions.user_transcript(app, result, is_final);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn speech_estimate_scales_with_script_and_speed() {
+        let english = estimate_speech_ms("This sentence has some words in it.", None);
+        let chinese = estimate_speech_ms("这句话里有一些字。", None);
+        assert!(english >= MIN_CAPTION_MS && chinese >= MIN_CAPTION_MS);
+        assert!(estimate_speech_ms("这句话里有一些字。", Some(2.0)) < chinese);
+        assert_eq!(estimate_speech_ms("Hi", None), MIN_CAPTION_MS);
+    }
+}
@@ -0,0 +1,690 @@
+//! Per-character bindings — model, sampling, voice, emotion personality,
+//! Live2D model and mods.
+//!
+//! Profiles live in `character_profiles.json`, keyed by character id.
+//! [`switch_character`] is the single entry point for changing the active
+//! character: it resolves everything first and then pushes the bindings into
+//! the LLM, TTS and orchestrator state under one lock, so concurrent switches
+//! cannot interleave and leave services bound to different characters.
+//! [`activate_character`] does the same for a full switch from the UI, also
+//! loading the persona, Live2D model and mods and keeping each character's
+//! emotion for when it comes back.
+
+use crate::ai::checkpoints::SavedEmotion;
+use crate::ai::context::AIOrchestrator;
+use crate::ai::emotion::EmotionState;
+use crate::ai::emotion_personality::{self, EmotionPersonality};
+use crate::ai::proactive_policy::ProactivePolicy;
+use crate::commands::characters::CharacterRecord;
+use crate::commands::context::UserProfileSettings;
+use crate::error::KokoroError;
+use crate::imagegen::preset::ImagePreset;
+use crate::llm::service::{LlmCharacterBinding, LlmConversationOverride, LlmService};
+use crate::mods::ModManager;
+use crate::tts::{TtsCharacterBinding, TtsService};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::{Mutex, RwLock};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct CharacterProfile {
+    /// LLM provider id from `llm_config.json`; `None` uses the active provider.
+    #[serde(default)]
+    pub llm_provider_id: Option<String>,
+    #[serde(default)]
+    pub llm_model: Option<String>,
+    #[serde(default)]
+    pub temperature: Option<f32>,
+    #[serde(default)]
+    pub tts_provider_id: Option<String>,
+    #[serde(default)]
+    pub tts_voice: Option<String>,
+    /// Explicit personality; `None` derives it from the persona text.
+    #[serde(default)]
+    pub personality: Option<EmotionPersonality>,
+    /// Quiet hours and frequency caps for idle auto-talk.
+    #[serde(default)]
+    pub proactive: ProactivePolicy,
+    /// Visual traits (hair, eyes, outfit) that generated selfies must match.
+    #[serde(default)]
+    pub appearance: Option<String>,
+    /// Prompt tags, LoRA/style and reference picture for generated images.
+    #[serde(default)]
+    pub image_preset: ImagePreset,
+    /// Live2D model (relative to the models dir) shown while this character is
+    /// active; `None` keeps the current model.
+    #[serde(default)]
+    pub live2d_model_path: Option<String>,
+    /// Mods loaded when this character becomes active.
+    #[serde(default)]
+    pub mods: Vec<String>,
+}
+
+impl CharacterProfile {
+    fn llm_binding(&self) -> LlmCharacterBinding {
+        LlmCharacterBinding {
+            provider_id: non_empty(&self.llm_provider_id),
+            model: non_empty(&self.llm_model),
+            temperature: self.temperature.map(|t| t.clamp(0.0, 2.0)),
+        }
+    }
+
+    pub(crate) fn tts_binding(&self) -> TtsCharacterBinding {
+        TtsCharacterBinding {
+            provider_id: non_empty(&self.tts_provider_id),
+            voice: non_empty(&self.tts_voice),
+        }
+    }
+
+    /// The model binding as a per-request override, for speaking as this
+    /// character while another one is active.
+    pub(crate) fn llm_override(&self) -> LlmConversationOverride {
+        let binding = self.llm_binding();
+        LlmConversationOverride {
+            provider_id: binding.provider_id,
+            model: binding.model,
+            temperature: binding.temperature,
+        }
+    }
+
+    /// Fill an unset Live2D model or voice from the character's registry row.
+    pub(crate) fn with_record_defaults(mut self, record: &CharacterRecord) -> Self {
+        if non_empty(&self.live2d_model_path).is_none() {
+            self.live2d_model_path = non_empty(&record.model_path);
+        }
+        if non_empty(&self.tts_voice).is_none() {
+            self.tts_voice = non_empty(&record.voice);
+        }
+        self
+    }
+
+    fn mod_ids(&self) -> Vec<String> {
+        let mut ids: Vec<String> = Vec::new();
+        for id in self
+            .mods
+            .iter()
+            .map(|id| id.trim())
+            .filter(|id| !id.is_empty())
+        {
+            if !ids.iter().any(|existing| existing == id) {
+                ids.push(id.to_string());
+            }
+        }
+        ids
+    }
+}
+
+fn non_empty(value: &Option<String>) -> Option<String> {
+    value
+        .as_deref()
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(str::to_string)
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct CharacterProfilesConfig {
+    #[serde(default)]
+    pub profiles: HashMap<String, CharacterProfile>,
+    /// Score personas with the system LLM (once per persona, cached) instead of
+    /// relying on keyword matching alone.
+    #[serde(default)]
+    pub llm_trait_extraction: bool,
+}
+
+pub fn config_path() -> PathBuf {
+    dirs_next::data_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("com.chyin.kokoro")
+        .join("character_profiles.json")
+}
+
+pub fn load_config(path: &Path) -> CharacterProfilesConfig {
+    crate::config::load_json_config(path, "PROFILES")
+}
+
+pub fn save_config(path: &Path, config: &CharacterProfilesConfig) -> Result<(), KokoroError> {
+    crate::config::save_json_config(path, config, "PROFILES")
+}
+
+/// What `switch_character` applied; also emitted as `character-switched`.
+#[derive(Debug, Clone, Serialize)]
+pub struct ActiveCharacterProfile {
+    pub character_id: String,
+    pub profile: CharacterProfile,
+    /// Personality in effect (explicit or derived from the persona).
+    pub personality: EmotionPersonality,
+    /// Hash of the persona the personality was derived from; lets a late LLM
+    /// extraction detect that the persona changed underneath it.
+    #[serde(skip)]
+    persona_hash: Option<String>,
+}
+
+/// Payload of `character:switched`, emitted once per [`activate_character`].
+#[derive(Debug, Clone, Serialize)]
+pub struct CharacterSwitched {
+    pub character_id: String,
+    pub name: String,
+    /// System prompt composed from the persona and the user profile.
+    pub persona: String,
+    pub profile: CharacterProfile,
+    pub personality: EmotionPersonality,
+    pub emotion: EmotionState,
+    /// Whether `emotion` is this character's state from when it was last active.
+    pub emotion_restored: bool,
+    pub live2d_model_path: Option<String>,
+    /// Mods that loaded; ones that failed are left out.
+    pub mods: Vec<String>,
+}
+
+pub struct CharacterProfileService {
+    config: RwLock<CharacterProfilesConfig>,
+    /// Held for the whole switch so bindings are never mixed between characters.
+    switch_lock: Mutex<Option<ActiveCharacterProfile>>,
+    /// Emotion of characters switched away from during this session.
+    emotion_snapshots: Mutex<HashMap<String, SavedEmotion>>,
+}
+
+impl CharacterProfileService {
+    pub fn new(config: CharacterProfilesConfig) -> Self {
+        Self {
+            config: RwLock::new(config),
+            switch_lock: Mutex::new(None),
+            emotion_snapshots: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub async fn get_profile(&self, character_id: &str) -> CharacterProfile {
+        self.config
+            .read()
+            .await
+            .profiles
+            .get(character_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// The profile saved for `character_id`, if any.
+    pub async fn stored_profile(&self, character_id: &str) -> Option<CharacterProfile> {
+        self.config.read().await.profiles.get(character_id).cloned()
+    }
+
+    /// Store (or with `None`, remove) a profile and persist the file.
+    pub async fn set_profile(
+        &self,
+        character_id: &str,
+        profile: Option<CharacterProfile>,
+    ) -> Result<(), KokoroError> {
+        let mut config = self.config.write().await;
+        let mut updated = config.clone();
+        match profile {
+            Some(profile) => {
+                updated.profiles.insert(character_id.to_string(), profile);
+            }
+            None => {
+                updated.profiles.remove(character_id);
+            }
+        }
+        save_config(&config_path(), &updated)?;
+        *config = updated;
+        Ok(())
+    }
+
+    pub async fn llm_trait_extraction(&self) -> bool {
+        self.config.read().await.llm_trait_extraction
+    }
+
+    pub async fn set_llm_trait_extraction(&self, enabled: bool) -> Result<(), KokoroError> {
+        let mut config = self.config.write().await;
+        let mut updated = config.clone();
+        updated.llm_trait_extraction = enabled;
+        save_config(&config_path(), &updated)?;
+        *config = updated;
+        Ok(())
+    }
+
+    pub async fn active(&self) -> Option<ActiveCharacterProfile> {
+        self.switch_lock.lock().await.clone()
+    }
+}
+
+async fn stored_record(
+    orchestrator: &AIOrchestrator,
+    character_id: &str,
+) -> Option<CharacterRecord> {
+    crate::commands::characters::load_character(&orchestrator.db, character_id)
+        .await
+        .unwrap_or_else(|e| {
+            tracing::warn!(target: "ai", "[Profiles] Failed to read character '{}': {}", character_id, e);
+            None
+        })
+}
+
+async fn persona_for(orchestrator: &AIOrchestrator, stored: Option<&CharacterRecord>) -> String {
+    match stored {
+        Some(record) if !record.persona.trim().is_empty() => record.persona.clone(),
+        _ => orchestrator.system_prompt.lock().await.clone(),
+    }
+}
+
+/// Make `character_id` the active character and bind its model, voice and
+/// personality. Re-applying the already-active character is a no-op unless
+/// `force` is set (e.g. after its profile was edited).
+pub async fn switch_character(
+    app: &AppHandle,
+    character_id: &str,
+    force: bool,
+) -> Result<ActiveCharacterProfile, KokoroError> {
+    let orchestrator = app
+        .try_state::<AIOrchestrator>()
+        .ok_or_else(|| KokoroError::Internal("AI orchestrator is not ready".to_string()))?;
+    let Some(service) = app.try_state::<CharacterProfileService>() else {
+        // Profiles unavailable: keep the plain id switch working.
+        orchestrator
+            .set_character_id(character_id.to_string())
+            .await;
+        return Ok(ActiveCharacterProfile {
+            character_id: character_id.to_string(),
+            profile: CharacterProfile::default(),
+            personality: EmotionPersonality::default(),
+            persona_hash: None,
+        });
+    };
+
+    let mut active = service.switch_lock.lock().await;
+    if !force {
+        if let Some(current) = active.as_ref() {
+            if current.character_id == character_id {
+                return Ok(current.clone());
+            }
+        }
+    }
+
+    let (applied, pending_extraction) =
+        apply_bindings(app, &orchestrator, &service, character_id, None).await;
+    *active = Some(applied.clone());
+    drop(active);
+
+    tracing::info!(target: "ai", "[Profiles] Switched to character '{}'", character_id);
+    let _ = app.emit("character-switched", &applied);
+    if let Some(persona) = pending_extraction {
+        spawn_trait_extraction(app.clone(), character_id.to_string(), persona);
+    }
+    Ok(applied)
+}
+
+/// Resolve the profile and personality, then bind model, voice, personality
+/// and memory scope. The emotion engine starts over, or picks up `restore`.
+/// Callers hold `switch_lock`. Returns a persona still waiting for LLM trait
+/// extraction, if any.
+async fn apply_bindings(
+    app: &AppHandle,
+    orchestrator: &AIOrchestrator,
+    service: &CharacterProfileService,
+    character_id: &str,
+    restore: Option<EmotionState>,
+) -> (ActiveCharacterProfile, Option<String>) {
+    // Resolve everything before touching any service.
+    let stored = stored_record(orchestrator, character_id).await;
+    let mut profile = service.get_profile(character_id).await;
+    if let Some(record) = stored.as_ref() {
+        profile = profile.with_record_defaults(record);
+    }
+    let mut pending_extraction = None;
+    let mut persona_hash = None;
+    let personality = match profile.personality {
+        Some(explicit) => explicit.clamped(),
+        None => {
+            let persona = persona_for(orchestrator, stored.as_ref()).await;
+            let (personality, cached) =
+                emotion_personality::resolve(&orchestrator.db, &persona).await;
+            persona_hash = Some(emotion_personality::persona_hash(&persona));
+            if !cached && !persona.trim().is_empty() && service.llm_trait_extraction().await {
+                pending_extraction = Some(persona);
+            }
+            personality
+        }
+    };
+
+    if let Some(llm) = app.try_state::<LlmService>() {
+        llm.set_character_binding(profile.llm_binding()).await;
+    }
+    if let Some(tts) = app.try_state::<TtsService>() {
+        tts.set_character_voice(profile.tts_binding()).await;
+    }
+    orchestrator.set_personality(personality).await;
+    orchestrator
+        .set_character_id(character_id.to_string())
+        .await;
+    let emotion = {
+        let mut engine = orchestrator.emotion.lock().await;
+        engine.reset(personality);
+        if let Some(saved) = restore {
+            engine.restore(saved);
+        }
+        engine.state()
+    };
+    crate::ai::emotion::broadcast(app, orchestrator, emotion, "reset").await;
+
+    let applied = ActiveCharacterProfile {
+        character_id: character_id.to_string(),
+        profile,
+        personality,
+        persona_hash,
+    };
+    (applied, pending_extraction)
+}
+
+/// Switch everything over to `character_id` in one step: persona and name,
+/// model/voice bindings, memory scope, emotion, Live2D model and mods. All
+/// inputs are checked before anything changes, and a single
+/// `character:switched` event reports the result.
+pub async fn activate_character(
+    app: &AppHandle,
+    character_id: &str,
+) -> Result<CharacterSwitched, KokoroError> {
+    let orchestrator = app
+        .try_state::<AIOrchestrator>()
+        .ok_or_else(|| KokoroError::Internal("AI orchestrator is not ready".to_string()))?;
+    let service = app
+        .try_state::<CharacterProfileService>()
+        .ok_or_else(|| KokoroError::Internal("Character profiles are not ready".to_string()))?;
+
+    let mut active = service.switch_lock.lock().await;
+
+    // ── Resolve and validate ──
+    let record = crate::commands::characters::load_character(&orchestrator.db, character_id)
+        .await?
+        .ok_or_else(|| KokoroError::NotFound(format!("character '{}'", character_id)))?;
+    let profile = service
+        .get_profile(character_id)
+        .await
+        .with_record_defaults(&record);
+    let live2d_model_path = match non_empty(&profile.live2d_model_path) {
+        Some(path) => Some(
+            crate::commands::live2d::normalize_relative_model_path(&path)
+                .map_err(KokoroError::Validation)?,
+        ),
+        None => None,
+    };
+    let mod_ids = profile.mod_ids();
+    let mod_manager = app.try_state::<Mutex<ModManager>>();
+    if !mod_ids.is_empty() {
+        let manager = mod_manager
+            .as_ref()
+            .ok_or_else(|| KokoroError::Mod("Mod runtime is not ready".to_string()))?;
+        let mut manager = manager.lock().await;
+        let installed: Vec<String> = manager.scan_mods().into_iter().map(|m| m.id).collect();
+        if let Some(missing) = mod_ids.iter().find(|id| !installed.contains(id)) {
+            return Err(KokoroError::NotFound(format!("mod '{}'", missing)));
+        }
+    }
+    let user = app
+        .path()
+        .app_data_dir()
+        .ok()
+        .and_then(|dir| crate::commands::context::load_user_profile_settings_from_app_data(&dir))
+        .unwrap_or_default();
+    let persona = compose_system_prompt(&record, &user);
+
+    // ── Apply ──
+    if live2d_model_path.is_some() {
+        crate::commands::live2d::set_active_live2d_model(app.clone(), live2d_model_path.clone())
+            .await
+            .map_err(KokoroError::Internal)?;
+    }
+
+    let previous = active.clone();
+    let restore = {
+        let mut snapshots = service.emotion_snapshots.lock().await;
+        if let Some(previous) = previous.as_ref() {
+            let state = orchestrator.emotion.lock().await.state();
+            snapshots.insert(
+                previous.character_id.clone(),
+                SavedEmotion::from_state(&state),
+            );
+        }
+        snapshots.get(character_id).map(SavedEmotion::to_state)
+    };
+    let emotion_restored = restore.is_some();
+
+    orchestrator.set_system_prompt(persona.clone()).await;
+    orchestrator.set_character_name(record.name.clone()).await;
+    let (applied, pending_extraction) =
+        apply_bindings(app, &orchestrator, &service, character_id, restore).await;
+    AIOrchestrator::persist_active_character_id(character_id);
+    let emotion = orchestrator.emotion.lock().await.state();
+
+    let previous_mods = previous
+        .as_ref()
+        .map(|previous| previous.profile.mod_ids())
+        .unwrap_or_default();
+    let mut loaded_mods = Vec::new();
+    if let Some(manager) = mod_manager.as_ref() {
+        if previous_mods != mod_ids {
+            let mut manager = manager.lock().await;
+            if !previous_mods.is_empty() {
+                manager.unload_mod(app).await;
+            }
+            for id in &mod_ids {
+                match manager.load_mod(id, app).await {
+                    Ok(()) => loaded_mods.push(id.clone()),
+                    Err(e) => {
+                        tracing::warn!(target: "ai", "[Profiles] Mod '{}' failed to load for '{}': {}", id, character_id, e)
+                    }
+                }
+            }
+        } else {
+            loaded_mods = mod_ids;
+        }
+    }
+
+    *active = Some(applied.clone());
+    drop(active);
+
+    let switched = CharacterSwitched {
+        character_id: character_id.to_string(),
+        name: record.name,
+        persona,
+        profile: applied.profile,
+        personality: applied.personality,
+        emotion,
+        emotion_restored,
+        live2d_model_path,
+        mods: loaded_mods,
+    };
+    tracing::info!(
+        target: "ai",
+        "[Profiles] Activated character '{}' (emotion restored: {}, mods: {:?})",
+        character_id,
+        emotion_restored,
+        switched.mods
+    );
+    let _ = app.emit("character:switched", &switched);
+    if let Some(persona) = pending_extraction {
+        spawn_trait_extraction(app.clone(), character_id.to_string(), persona);
+    }
+    Ok(switched)
+}
+
+/// The system prompt for a character, matching the one the character
+/// manager composes on the frontend.
+pub(crate) fn compose_system_prompt(
+    record: &CharacterRecord,
+    user: &UserProfileSettings,
+) -> String {
+    let mut parts = vec![format!("Your name is {}.", record.name)];
+    if !record.persona.is_empty() {
+        parts.push(record.persona.replace("{{user}}", &user.user_name));
+    }
+    if !record.user_nickname.is_empty() && record.user_nickname != "{{user}}" {
+        parts.push(format!("Address the user as \"{}\".", record.user_nickname));
+    }
+    parts.push(format!("The user's name is {}.", user.user_name));
+    if !user.user_persona.is_empty() {
+        parts.push(format!("About the user: {}", user.user_persona));
+    }
+    parts.join(" ")
+}
+
+/// Score the persona with the system LLM off the switch path, then upgrade the
+/// keyword estimate if the same character and persona are still active.
+fn spawn_trait_extraction(app: AppHandle, character_id: String, persona: String) {
+    tauri::async_runtime::spawn(async move {
+        let (Some(orchestrator), Some(llm), Some(service)) = (
+            app.try_state::<AIOrchestrator>(),
+            app.try_state::<LlmService>(),
+            app.try_state::<CharacterProfileService>(),
+        ) else {
+            return;
+        };
+        let provider = llm.system_provider().await;
+        let personality = match emotion_personality::extract_with_llm(
+            &orchestrator.db,
+            provider.as_ref(),
+            &persona,
+        )
+        .await
+        {
+            Ok(personality) => personality,
+            Err(e) => {
+                tracing::warn!(
+                    target: "ai",
+                    "[Profiles] Trait extraction for '{}' failed, keeping keyword estimate: {}",
+                    character_id,
+                    e
+                );
+                return;
+            }
+        };
+
+        let hash = emotion_personality::persona_hash(&persona);
+        let mut active = service.switch_lock.lock().await;
+        let Some(current) = active.as_mut().filter(|current| {
+            current.character_id == character_id
+                && current.profile.personality.is_none()
+                && current.persona_hash.as_deref() == Some(hash.as_str())
+        }) else {
+            return;
+        };
+        current.personality = personality;
+        orchestrator.set_personality(personality).await;
+        let applied = current.clone();
+        drop(active);
+        let _ = app.emit("character-switched", &applied);
+    });
+}
+
+/// Re-apply bindings when `character_id` is the active character, e.g. after
+/// its profile or persona changed.
+pub async fn refresh_if_active(app: &AppHandle, character_id: &str) -> Result<(), KokoroError> {
+    let Some(service) = app.try_state::<CharacterProfileService>() else {
+        return Ok(());
+    };
+    let is_active = service
+        .active()
+        .await
+        .is_some_and(|active| active.character_id == character_id);
+    if is_active {
+        switch_character(app, character_id, true).await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blank_fields_do_not_override_globals() {
+        let profile = CharacterProfile {
+            llm_provider_id: Some("  ".to_string()),
+            llm_model: Some("gpt-4o".to_string()),
+            temperature: Some(3.5),
+            tts_provider_id: None,
+            tts_voice: Some(String::new()),
+            personality: None,
+            proactive: ProactivePolicy::default(),
+            appearance: None,
+            image_preset: ImagePreset::default(),
+            live2d_model_path: None,
+            mods: Vec::new(),
+        };
+        let llm = profile.llm_binding();
+        assert_eq!(llm.provider_id, None);
+        assert_eq!(llm.model.as_deref(), Some("gpt-4o"));
+        assert_eq!(llm.temperature, Some(2.0));
+        assert_eq!(profile.tts_binding(), TtsCharacterBinding::default());
+    }
+
+    fn record(persona: &str, nickname: &str) -> CharacterRecord {
+        CharacterRecord {
+            id: "c1".to_string(),
+            name: "Kokoro".to_string(),
+            persona: persona.to_string(),
+            user_nickname: nickname.to_string(),
+            source_format: "manual".to_string(),
+            created_at: 0,
+            updated_at: 0,
+            first_message: String::new(),
+            avatar_path: None,
+            model_path: None,
+            voice: None,
+            settings: serde_json::json!({}),
+        }
+    }
+
+    #[test]
+    fn system_prompt_matches_character_manager() {
+        let user = UserProfileSettings {
+            user_name: "Alex".to_string(),
+            user_persona: "Likes tea.".to_string(),
+        };
+        assert_eq!(
+            compose_system_prompt(&record("You adore {{user}}.", "senpai"), &user),
+            "Your name is Kokoro. You adore Alex. Address the user as \"senpai\". \
+             The user's name is Alex. About the user: Likes tea."
+        );
+        assert_eq!(
+            compose_system_prompt(&record("", "{{user}}"), &UserProfileSettings::default()),
+            "Your name is Kokoro. The user's name is User."
+        );
+    }
+
+    #[test]
+    fn registry_model_and_voice_fill_unset_bindings() {
+        let mut stored = record("", "");
+        stored.model_path = Some("mio/mio.model3.json".to_string());
+        stored.voice = Some("alloy".to_string());
+
+        let filled = CharacterProfile::default().with_record_defaults(&stored);
+        assert_eq!(
+            filled.live2d_model_path.as_deref(),
+            Some("mio/mio.model3.json")
+        );
+        assert_eq!(filled.tts_voice.as_deref(), Some("alloy"));
+
+        let bound = CharacterProfile {
+            tts_voice: Some("nova".to_string()),
+            ..CharacterProfile::default()
+        }
+        .with_record_defaults(&stored);
+        assert_eq!(bound.tts_voice.as_deref(), Some("nova"));
+    }
+
+    #[test]
+    fn mod_ids_are_trimmed_and_unique() {
+        let profile = CharacterProfile {
+            mods: vec![
+                " neon ".to_string(),
+                String::new(),
+                "neon".to_string(),
+                "clock".to_string(),
+            ],
+            ..CharacterProfile::default()
+        };
+        assert_eq!(profile.mod_ids(), vec!["neon", "clock"]);
+    }
+}
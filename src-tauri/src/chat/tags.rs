@@ -4,9 +4,14 @@ use std::collections::{HashMap, HashSet};
 
 const TOOL_CALL_TAG_PREFIX: &str = "[TOOL_CALL:";
 const TRANSLATE_TAG_PREFIX: &str = "[TRANSLATE:";
+const REMEMBER_TAG_PREFIX: &str = "[REMEMBER:";
 
 /// Tag prefixes that should be buffered (not emitted to frontend mid-stream).
-const BUFFERED_TAG_PREFIXES: &[&str] = &[TOOL_CALL_TAG_PREFIX, TRANSLATE_TAG_PREFIX];
+const BUFFERED_TAG_PREFIXES: &[&str] = &[
+    TOOL_CALL_TAG_PREFIX,
+    TRANSLATE_TAG_PREFIX,
+    REMEMBER_TAG_PREFIX,
+];
 
 /// Returns the byte position up to which it's safe to emit text to the frontend.
 /// Holds back any suffix that could be the start of a known tag prefix.
@@ -145,6 +150,31 @@ pub(crate) fn extract_translate_tags(text: &str) -> (String, Option<String>) {
     (result.trim().to_string(), translation)
 }
 
+/// Extract the facts of `[REMEMBER:...]` tags, which the character adds when
+/// the user asks it to remember something, then strip them from text.
+/// An unclosed tag is dropped without a fact.
+pub(crate) fn extract_remember_tags(text: &str) -> (String, Vec<String>) {
+    let mut facts = Vec::new();
+    let mut result = text.to_string();
+    while let Some(start) = result.find(REMEMBER_TAG_PREFIX) {
+        let Some(end_bracket) = result[start..].find(']') else {
+            result = result[..start].trim_end().to_string();
+            break;
+        };
+        let fact = result[start + REMEMBER_TAG_PREFIX.len()..start + end_bracket].trim();
+        if !fact.is_empty() {
+            facts.push(fact.to_string());
+        }
+        let tag_end = start + end_bracket + 1;
+        result = format!(
+            "{}{}",
+            result[..start].trim_end(),
+            result[tag_end..].trim_start()
+        );
+    }
+    (result.trim().to_string(), facts)
+}
+
 /// Parsed tool call from `[TOOL_CALL:name|key=val|key=val]`
 #[derive(Debug, Clone, Serialize)]
 pub(crate) struct ToolCall {
@@ -416,6 +446,19 @@ mod tests {
         assert_eq!(translation, None);
     }
 
+    #[test]
+    fn test_extract_remember_tags() {
+        let input = "Got it, I won't forget! [REMEMBER: The user's cat is called Mochi]";
+        let (text, facts) = extract_remember_tags(input);
+        assert_eq!(text, "Got it, I won't forget!");
+        assert_eq!(facts, vec!["The user's cat is called Mochi".to_string()]);
+
+        let (text, facts) = extract_remember_tags("Sure [REMEMBER:]ok [REMEMBER: half");
+        assert_eq!(text, "Sureok");
+        assert!(facts.is_empty());
+        assert_eq!(find_safe_emit_boundary("Okay [REMEM"), 5);
+    }
+
     #[test]
     fn test_strip_translate_tags() {
         let input = "こんにちは[TRANSLATE:你好]";
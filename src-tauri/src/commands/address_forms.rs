@@ -0,0 +1,48 @@
+use crate::ai::address_forms::{self, AddressSource, AddressTarget, RelationshipState};
+use crate::ai::context::AIOrchestrator;
+use crate::error::KokoroError;
+use tauri::State;
+
+/// Longest form accepted from the UI.
+const MAX_FORM_CHARS: usize = 40;
+
+/// Returns how the character and the user currently address each other.
+#[tauri::command]
+pub async fn get_address_forms(
+    character_id: String,
+    orchestrator: State<'_, AIOrchestrator>,
+) -> Result<RelationshipState, KokoroError> {
+    address_forms::load_relationship_state(&orchestrator.db, &character_id)
+        .await?
+        .ok_or_else(|| KokoroError::NotFound(format!("Character {} not found", character_id)))
+}
+
+/// Correct a learned form of address. An empty or missing `form` clears it.
+#[tauri::command]
+pub async fn set_address_form(
+    character_id: String,
+    target: AddressTarget,
+    form: Option<String>,
+    orchestrator: State<'_, AIOrchestrator>,
+) -> Result<RelationshipState, KokoroError> {
+    let form = form
+        .map(|form| form.trim().to_string())
+        .filter(|form| !form.is_empty());
+    if form
+        .as_ref()
+        .is_some_and(|form| form.chars().count() > MAX_FORM_CHARS)
+    {
+        return Err(KokoroError::Validation(format!(
+            "Form of address must be at most {} characters",
+            MAX_FORM_CHARS
+        )));
+    }
+    let mut state = address_forms::load_relationship_state(&orchestrator.db, &character_id)
+        .await?
+        .ok_or_else(|| KokoroError::NotFound(format!("Character {} not found", character_id)))?;
+    let now = chrono::Utc::now().timestamp_millis();
+    if state.set(target, form, AddressSource::Manual, now) {
+        address_forms::save_relationship_state(&orchestrator.db, &character_id, &state).await?;
+    }
+    Ok(state)
+}
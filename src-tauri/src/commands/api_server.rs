@@ -0,0 +1,49 @@
+//! Local API server IPC commands.
+
+use crate::api_server::{ApiServerConfig, ApiServerService, ApiServerStatus};
+use crate::error::KokoroError;
+use tauri::State;
+
+#[tauri::command]
+pub async fn get_api_server_config(
+    state: State<'_, ApiServerService>,
+) -> Result<ApiServerConfig, KokoroError> {
+    Ok(state.get_config().await)
+}
+
+#[tauri::command]
+pub async fn save_api_server_config(
+    state: State<'_, ApiServerService>,
+    config: ApiServerConfig,
+) -> Result<(), KokoroError> {
+    if config.port == 0 {
+        return Err(KokoroError::Validation("port must not be 0".to_string()));
+    }
+    crate::api_server::save_config(&crate::api_server::config_path(), &config)?;
+    state.update_config(config).await;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn start_api_server(
+    state: State<'_, ApiServerService>,
+    app: tauri::AppHandle,
+) -> Result<ApiServerStatus, KokoroError> {
+    state
+        .start(app)
+        .await
+        .map_err(KokoroError::ExternalService)?;
+    Ok(state.status().await)
+}
+
+#[tauri::command]
+pub async fn stop_api_server(state: State<'_, ApiServerService>) -> Result<(), KokoroError> {
+    state.stop().await.map_err(KokoroError::ExternalService)
+}
+
+#[tauri::command]
+pub async fn get_api_server_status(
+    state: State<'_, ApiServerService>,
+) -> Result<ApiServerStatus, KokoroError> {
+    Ok(state.status().await)
+}
@@ -0,0 +1,26 @@
+//! Asset store IPC commands — quotas and cleanup.
+
+use crate::assets::{AssetQuotaConfig, AssetStore, CleanReport};
+use crate::error::KokoroError;
+use tauri::State;
+
+#[tauri::command]
+pub async fn get_asset_quota_config() -> Result<AssetQuotaConfig, KokoroError> {
+    Ok(crate::assets::load_config(&crate::assets::config_path()))
+}
+
+#[tauri::command]
+pub async fn save_asset_quota_config(config: AssetQuotaConfig) -> Result<(), KokoroError> {
+    crate::assets::save_config(&crate::assets::config_path(), &config)
+}
+
+/// Remove unreferenced assets, enforce quotas and expire scratch files.
+/// With `dry_run`, only reports what would be removed.
+#[tauri::command]
+pub async fn clean_assets(
+    state: State<'_, AssetStore>,
+    dry_run: bool,
+) -> Result<CleanReport, KokoroError> {
+    let quotas = crate::assets::load_config(&crate::assets::config_path());
+    state.clean(&quotas, dry_run).await
+}
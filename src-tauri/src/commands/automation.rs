@@ -0,0 +1,84 @@
+//! Automation rule IPC commands — CRUD, manual runs and the startup signal.
+
+use crate::automation::{self, AutomationRule, AutomationService};
+use crate::error::KokoroError;
+use tauri::{AppHandle, State};
+
+#[tauri::command]
+pub async fn list_automation_rules(
+    service: State<'_, AutomationService>,
+) -> Result<Vec<AutomationRule>, KokoroError> {
+    Ok(service.get_config().await.rules)
+}
+
+/// Create (empty `id`) or replace a rule.
+#[tauri::command]
+pub async fn save_automation_rule(
+    service: State<'_, AutomationService>,
+    mut rule: AutomationRule,
+) -> Result<AutomationRule, KokoroError> {
+    rule.name = rule.name.trim().to_string();
+    rule.validate()?;
+    if rule.id.trim().is_empty() {
+        rule.id = uuid::Uuid::new_v4().to_string();
+    }
+    let saved = rule.clone();
+    service.update(|config| config.upsert(rule)).await?;
+    Ok(saved)
+}
+
+#[tauri::command]
+pub async fn delete_automation_rule(
+    service: State<'_, AutomationService>,
+    id: String,
+) -> Result<(), KokoroError> {
+    if service.update(|config| config.remove(&id)).await? {
+        Ok(())
+    } else {
+        Err(KokoroError::NotFound(format!("automation rule '{}'", id)))
+    }
+}
+
+#[tauri::command]
+pub async fn set_automation_rule_enabled(
+    service: State<'_, AutomationService>,
+    id: String,
+    enabled: bool,
+) -> Result<AutomationRule, KokoroError> {
+    service
+        .update(|config| {
+            config.rules.iter_mut().find(|r| r.id == id).map(|rule| {
+                rule.enabled = enabled;
+                rule.clone()
+            })
+        })
+        .await?
+        .ok_or_else(|| KokoroError::NotFound(format!("automation rule '{}'", id)))
+}
+
+/// Run a rule now, regardless of its trigger.
+#[tauri::command]
+pub async fn run_automation_rule(
+    app: AppHandle,
+    service: State<'_, AutomationService>,
+    id: String,
+) -> Result<automation::AutomationRunEvent, KokoroError> {
+    let rule = service
+        .get_config()
+        .await
+        .rules
+        .into_iter()
+        .find(|r| r.id == id)
+        .ok_or_else(|| KokoroError::NotFound(format!("automation rule '{}'", id)))?;
+    Ok(automation::run_rule(&app, &rule).await)
+}
+
+/// Called by the main window once it is listening for `automation-step`.
+/// Startup rules run on the first call only; returns how many were started.
+#[tauri::command]
+pub async fn run_startup_automations(
+    app: AppHandle,
+    service: State<'_, AutomationService>,
+) -> Result<usize, KokoroError> {
+    Ok(service.run_startup(&app).await)
+}
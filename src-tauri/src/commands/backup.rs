@@ -25,12 +25,17 @@ const CONFIG_FILES: &[&str] = &[
     "telegram_config.json",
     "jailbreak_prompt.json",
     "proactive_enabled.json",
+    "proactive_composer.json",
     "memory_system_config.json",
     "memory_upgrade_config.json",
     "emotion_state.json",
     "context_settings.json",
     "current_conversation_id.json",
     "user_profile.json",
+    "onboarding.json",
+    "voice_commands.json",
+    "webhooks.json",
+    "mod_permissions.json",
 ];
 
 // ── Types ────────────────────────────────────────────
@@ -713,6 +718,28 @@ pub async fn import_data(
             "INSERT OR IGNORE INTO conversations (id, character_id, title, topic, pinned_state, created_at, updated_at)
              SELECT id, character_id, title, '' as topic, '{}' as pinned_state, created_at, updated_at FROM import_db.conversations"
         };
+        // Branch bookkeeping is not imported; backups that have it contribute
+        // only the messages on their selected path.
+        let import_has_branches = sqlx::query("PRAGMA import_db.table_info(conversation_messages)")
+            .fetch_all(&mut *conn)
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .any(|row| row.get::<String, _>("name") == "is_active");
+        let message_insert_sql = if import_has_branches {
+            "INSERT INTO conversation_messages (id, conversation_id, role, content, metadata, created_at)
+             SELECT id, conversation_id, role, content, metadata, created_at FROM import_db.conversation_messages WHERE is_active = 1"
+        } else {
+            "INSERT INTO conversation_messages (id, conversation_id, role, content, metadata, created_at)
+             SELECT id, conversation_id, role, content, metadata, created_at FROM import_db.conversation_messages"
+        };
+        let message_insert_skip_sql = if import_has_branches {
+            "INSERT OR IGNORE INTO conversation_messages (id, conversation_id, role, content, metadata, created_at)
+             SELECT id, conversation_id, role, content, metadata, created_at FROM import_db.conversation_messages WHERE is_active = 1"
+        } else {
+            "INSERT OR IGNORE INTO conversation_messages (id, conversation_id, role, content, metadata, created_at)
+             SELECT id, conversation_id, role, content, metadata, created_at FROM import_db.conversation_messages"
+        };
         let memory_insert_sql = "INSERT INTO memories \
              (id, content, embedding, created_at, updated_at, importance, character_id, tier, consolidated_from, \
               memory_type, entity_key, status, confidence, first_seen_at, last_seen_at, evidence_count, \
@@ -749,6 +776,18 @@ pub async fn import_data(
                 .map_err(|e| {
                     KokoroError::Database(format!("DELETE conversation_messages failed: {}", e))
                 })?;
+            sqlx::query("DELETE FROM message_variant_groups")
+                .execute(&mut *conn)
+                .await
+                .map_err(|e| {
+                    KokoroError::Database(format!("DELETE message_variant_groups failed: {}", e))
+                })?;
+            sqlx::query("DELETE FROM conversation_checkpoints")
+                .execute(&mut *conn)
+                .await
+                .map_err(|e| {
+                    KokoroError::Database(format!("DELETE conversation_checkpoints failed: {}", e))
+                })?;
             sqlx::query("DELETE FROM conversations")
                 .execute(&mut *conn)
                 .await
@@ -808,15 +847,12 @@ pub async fn import_data(
                 result.imported_conversations
             ));
 
-            sqlx::query(
-                "INSERT INTO conversation_messages (id, conversation_id, role, content, metadata, created_at)
-                 SELECT id, conversation_id, role, content, metadata, created_at FROM import_db.conversation_messages",
-            )
-            .execute(&mut *conn)
-            .await
-            .map_err(|e| {
-                KokoroError::Database(format!("INSERT conversation_messages failed: {}", e))
-            })?;
+            sqlx::query(message_insert_sql)
+                .execute(&mut *conn)
+                .await
+                .map_err(|e| {
+                    KokoroError::Database(format!("INSERT conversation_messages failed: {}", e))
+                })?;
 
             // 重建 FTS 索引并恢复触发器
             sqlx::query("INSERT INTO memories_fts(memories_fts) VALUES('rebuild')")
@@ -885,9 +921,15 @@ pub async fn import_data(
                 result.imported_conversations
             ));
 
-            sqlx::query("INSERT OR IGNORE INTO conversation_messages (id, conversation_id, role, content, metadata, created_at) SELECT id, conversation_id, role, content, metadata, created_at FROM import_db.conversation_messages")
-                .execute(&mut *conn).await
-                .map_err(|e| KokoroError::Database(format!("INSERT OR IGNORE conversation_messages failed: {}", e)))?;
+            sqlx::query(message_insert_skip_sql)
+                .execute(&mut *conn)
+                .await
+                .map_err(|e| {
+                    KokoroError::Database(format!(
+                        "INSERT OR IGNORE conversation_messages failed: {}",
+                        e
+                    ))
+                })?;
 
             sqlx::query("INSERT INTO memories_fts(memories_fts) VALUES('rebuild')")
                 .execute(&mut *conn)
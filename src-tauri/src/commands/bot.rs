@@ -1,5 +1,8 @@
 use crate::actions::tool_settings::ToolSettings;
-use crate::actions::{builtin_tool_id, execute_tool_calls, ToolExecutionOutcome, ToolInvocation};
+use crate::actions::{
+    builtin_tool_id, execute_tool_calls, native_tool_continuation_messages, ToolExecutionOutcome,
+    ToolInvocation,
+};
 use crate::ai::context::AIOrchestrator;
 use crate::ai::memory_event_ingress::{
     build_cooldown_key, select_memory_ingress_decision, should_use_structured_extraction,
@@ -12,6 +15,7 @@ use crate::llm::messages::{
     assistant_text_message, is_user_message, replace_user_message_with_images, role_text_message,
     system_message, user_message_with_images, user_text_message,
 };
+use crate::llm::provider::{collect_stream_round, LlmToolCall};
 use crate::llm::service::LlmService;
 use crate::stt::{AudioSource, SttService};
 use crate::telegram::TelegramService;
@@ -643,7 +647,20 @@ async fn generate_bot_reply(
         }),
     );
 
-    let tool_prompt = {
+    // Providers with function calling get structured tool definitions; the
+    // textual [TOOL_CALL:] prompt is only a fallback for the rest.
+    let provider = llm_service.provider().await;
+    let native_tools_enabled = llm_service.native_tools_enabled(provider.as_ref()).await;
+    let native_tools = if native_tools_enabled {
+        let registry = action_registry.read().await;
+        let settings = tool_settings.read().await;
+        registry.list_tools_for_llm_with_settings(orchestrator.is_memory_enabled(), &settings)
+    } else {
+        Vec::new()
+    };
+    let tool_prompt = if native_tools_enabled {
+        None
+    } else {
         let registry = action_registry.read().await;
         let settings = tool_settings.read().await;
         let prompt = registry.generate_tool_prompt_for_prompt_with_settings(
@@ -658,7 +675,13 @@ async fn generate_bot_reply(
     };
 
     let (prompt_messages, compose_warnings) = orchestrator
-        .compose_prompt(&prompt_text, false, tool_prompt, false, &char_id)
+        .compose_prompt(
+            &prompt_text,
+            false,
+            tool_prompt,
+            native_tools_enabled,
+            &char_id,
+        )
         .await
         .map_err(|e| e.to_string())?;
     for warning in compose_warnings {
@@ -683,7 +706,6 @@ async fn generate_bot_reply(
         client_messages.push(user_message_with_images(prompt_text.clone(), image_urls));
     }
 
-    let provider = llm_service.provider().await;
     let max_rounds = {
         let settings = tool_settings.read().await;
         settings.max_tool_rounds.max(1)
@@ -694,27 +716,41 @@ async fn generate_bot_reply(
     let mut all_generated_images = Vec::new();
 
     for round in 0..max_rounds {
-        let mut stream = provider
-            .chat_stream(client_messages.clone(), None)
-            .await
-            .map_err(|e| format!("LLM stream error: {}", e))?;
+        let stream = if native_tools_enabled {
+            provider
+                .chat_stream_with_tools(client_messages.clone(), None, native_tools.clone())
+                .await
+        } else {
+            provider
+                .chat_stream_rich(
+                    client_messages.iter().cloned().map(Into::into).collect(),
+                    None,
+                )
+                .await
+        }
+        .map_err(|e| format!("LLM stream error: {}", e))?;
 
-        let mut round_response = String::new();
-        while let Some(result) = stream.next().await {
-            match result {
-                Ok(delta) => round_response.push_str(&delta),
-                Err(error) => {
-                    tracing::error!(target: "bot", "[{}] LLM stream error: {}", platform, error);
-                    break;
-                }
-            }
+        let round = collect_stream_round(stream).await;
+        if let Some(error) = &round.error {
+            tracing::error!(target: "bot", "[{}] LLM stream error: {}", platform, error);
         }
+        let round_response = round.text;
+        let native_tool_calls = round
+            .tool_calls
+            .into_iter()
+            .map(ToolCall::from)
+            .collect::<Vec<_>>();
 
-        if round_response.is_empty() {
+        if round_response.is_empty() && native_tool_calls.is_empty() {
             break;
         }
 
-        let (cleaned, tool_calls) = parse_tool_call_tags(&round_response);
+        let (cleaned, tag_tool_calls) = parse_tool_call_tags(&round_response);
+        let tool_calls = if native_tool_calls.is_empty() {
+            tag_tool_calls
+        } else {
+            native_tool_calls
+        };
         let (cleaned, round_translation) = extract_translate_tags(&cleaned);
         let (cleaned, image_prompts) = extract_image_prompt_tags(&cleaned);
         let cleaned = strip_leaked_tags(&cleaned);
@@ -749,7 +785,7 @@ async fn generate_bot_reply(
                         &registry,
                         &tool_call.name,
                         tool_call.args.clone(),
-                        None,
+                        tool_call.tool_call_id.clone(),
                     )
                     .map_err(|error| format!("Tool resolution error: {}", error.0))
                 })
@@ -802,6 +838,11 @@ async fn generate_bot_reply(
             .iter()
             .any(|outcome| outcome.needs_feedback);
 
+        if let Some(messages) = native_tool_continuation_messages(&cleaned, &execution_outcomes) {
+            client_messages.extend(messages);
+            continue;
+        }
+
         if !any_needs_feedback {
             break;
         }
@@ -897,7 +938,7 @@ async fn trigger_bot_memory_tasks(
         char_id
     );
 
-    if orchestrator.is_memory_enabled() {
+    if orchestrator.is_memory_write_enabled() {
         if let Some(decision) = select_memory_ingress_decision(user_text, &ingress_options) {
             let cooldown_key =
                 build_cooldown_key(char_id, conversation_key, decision.event.event_type);
@@ -945,7 +986,24 @@ async fn trigger_bot_memory_tasks(
         }
     }
 
-    if orchestrator.is_memory_enabled() && memory_msg_count > 0 && memory_msg_count % 5 == 0 {
+    let topic_trigger = if orchestrator.is_memory_write_enabled() {
+        orchestrator
+            .observe_topic_turn(
+                char_id,
+                user_text,
+                &crate::ai::topic_shift::TopicShiftOptions::from(&upgrade_config),
+            )
+            .await
+    } else {
+        None
+    };
+    if let Some(trigger) = topic_trigger {
+        tracing::info!(
+            target: "memory",
+            "[{}/Memory] Triggering memory extraction (trigger={})",
+            platform,
+            trigger.as_str()
+        );
         let history = orchestrator.get_recent_memory_history(10).await;
         let memory_mgr = orchestrator.memory_manager.clone();
         let provider_for_mem = llm_service.provider().await;
@@ -980,7 +1038,8 @@ async fn trigger_bot_memory_tasks(
         });
     }
 
-    if orchestrator.is_memory_enabled() && memory_msg_count > 0 && memory_msg_count % 20 == 0 {
+    if orchestrator.is_memory_write_enabled() && memory_msg_count > 0 && memory_msg_count % 20 == 0
+    {
         let memory_mgr = orchestrator.memory_manager.clone();
         let char_id_for_consolidation = char_id.to_string();
         let provider_for_consolidation = llm_service.provider().await;
@@ -1034,6 +1093,7 @@ const TRANSLATE_TAG_PREFIX: &str = "[TRANSLATE:";
 
 #[derive(Debug, Clone)]
 struct ToolCall {
+    tool_call_id: Option<String>,
     name: String,
     args: HashMap<String, String>,
 }
@@ -1041,7 +1101,17 @@ struct ToolCall {
 impl From<ToolCall> for ToolInvocation {
     fn from(value: ToolCall) -> Self {
         Self {
-            tool_call_id: None,
+            tool_call_id: value.tool_call_id,
+            name: value.name,
+            args: value.args,
+        }
+    }
+}
+
+impl From<LlmToolCall> for ToolCall {
+    fn from(value: LlmToolCall) -> Self {
+        Self {
+            tool_call_id: Some(value.id),
             name: value.name,
             args: value.args,
         }
@@ -1067,7 +1137,14 @@ fn parse_tool_call_tags(text: &str) -> (String, Vec<ToolCall>) {
                         args.insert(key, val);
                     }
                 }
-                calls_with_positions.push((start, ToolCall { name, args }));
+                calls_with_positions.push((
+                    start,
+                    ToolCall {
+                        tool_call_id: None,
+                        name,
+                        args,
+                    },
+                ));
             }
             let tag_end = start + end_bracket + 1;
             result = format!(
@@ -1113,7 +1190,14 @@ fn parse_tool_call_tags(text: &str) -> (String, Vec<ToolCall>) {
                         args.insert(key, val);
                     }
                 }
-                calls_with_positions.push((start, ToolCall { name, args }));
+                calls_with_positions.push((
+                    start,
+                    ToolCall {
+                        tool_call_id: None,
+                        name,
+                        args,
+                    },
+                ));
                 let tag_end = start + end + 1;
                 cleaned = format!(
                     "{}{}",
@@ -1480,7 +1564,10 @@ async fn generate_bot_images(
 
     let mut images = Vec::new();
     for prompt in prompts {
-        match imagegen.generate(prompt.clone(), None, None, None).await {
+        match imagegen
+            .generate_in_character(prompt.clone(), None, None, None)
+            .await
+        {
             Ok(result) => match tokio::fs::read(&result.image_url).await {
                 Ok(data) => {
                     let _ = app.emit("imagegen:done", &result);
@@ -2571,6 +2658,8 @@ mod tests {
             allowed_chat_ids: vec![12345],
             send_voice_reply: true,
             character_id: Some("hiyori".to_string()),
+            proactive_when_away: false,
+            away_after_idle_minutes: 15,
         };
 
         std::fs::write(
@@ -0,0 +1,199 @@
+//! Capability discovery — one snapshot of which subsystems are configured and
+//! healthy, so the UI can hide or gray out features deterministically instead
+//! of probing each service on its own.
+
+use crate::error::KokoroError;
+use crate::tts::ProviderStatus;
+use crate::vision::config::VisionConfig;
+use serde::Serialize;
+use std::sync::Arc;
+use tauri::{AppHandle, Manager};
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LlmCapability {
+    pub configured: bool,
+    pub provider: Option<String>,
+}
+
+#[derive(Clone, Default, Serialize)]
+pub struct TtsCapability {
+    /// Registered providers with reachability and feature flags.
+    pub providers: Vec<ProviderStatus>,
+    /// At least one provider is reachable.
+    pub available: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SttCapability {
+    /// Provider transcription would use right now.
+    pub provider: Option<String>,
+    pub available: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, PartialEq, Eq)]
+pub struct VisionCapability {
+    /// Screen understanding is switched on and has a model to talk to.
+    pub screen: bool,
+    pub camera: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ImageGenCapability {
+    pub available: bool,
+    pub providers: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TelegramCapability {
+    pub configured: bool,
+    pub running: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct McpCapability {
+    pub connected_servers: usize,
+    pub tool_count: usize,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LocalModelsCapability {
+    pub memory_embedding: bool,
+    pub sensevoice: bool,
+    pub whisper_local: bool,
+}
+
+#[derive(Clone, Default, Serialize)]
+pub struct EngineCapabilities {
+    pub llm: LlmCapability,
+    pub tts: TtsCapability,
+    pub stt: SttCapability,
+    pub vision: VisionCapability,
+    pub imagegen: ImageGenCapability,
+    pub telegram: TelegramCapability,
+    pub mcp: McpCapability,
+    pub local_models: LocalModelsCapability,
+    pub api_server: bool,
+    /// Connected to VTube Studio.
+    pub vts: bool,
+}
+
+fn vision_capability(config: &VisionConfig) -> VisionCapability {
+    // "llm" reuses the chat model; every other provider needs its own model name.
+    let has_model = config.vlm_provider == "llm" || !config.vlm_model.trim().is_empty();
+    VisionCapability {
+        screen: config.vlm_enabled && has_model,
+        camera: config.camera_enabled,
+    }
+}
+
+/// Returns which subsystems are configured and healthy. Services that failed
+/// to initialize report as unavailable rather than erroring.
+#[tauri::command]
+pub async fn get_engine_capabilities(app: AppHandle) -> Result<EngineCapabilities, KokoroError> {
+    let mut caps = EngineCapabilities::default();
+
+    if let Some(llm) = app.try_state::<crate::llm::service::LlmService>() {
+        if let Ok(provider) = llm.try_provider().await {
+            caps.llm = LlmCapability {
+                configured: true,
+                provider: Some(provider.id().to_string()),
+            };
+        }
+    }
+
+    if let Some(tts) = app.try_state::<crate::tts::TtsService>() {
+        let providers = tts.list_providers().await;
+        caps.tts = TtsCapability {
+            available: providers.iter().any(|p| p.available),
+            providers,
+        };
+    }
+
+    if let Some(stt) = app.try_state::<crate::stt::SttService>() {
+        if let Some((provider, available)) = stt.active_provider_status().await {
+            caps.stt = SttCapability {
+                provider: Some(provider),
+                available,
+            };
+        }
+    }
+
+    if let Some(watcher) = app.try_state::<crate::vision::watcher::VisionWatcher>() {
+        let config = watcher.config.read().await;
+        caps.vision = vision_capability(&config);
+    }
+
+    if let Some(imagegen) = app.try_state::<crate::imagegen::ImageGenService>() {
+        let mut providers = imagegen.list_providers().await;
+        providers.sort();
+        caps.imagegen = ImageGenCapability {
+            available: !providers.is_empty(),
+            providers,
+        };
+    }
+
+    if let Some(telegram) = app.try_state::<crate::telegram::TelegramService>() {
+        caps.telegram = TelegramCapability {
+            configured: telegram.get_config().await.resolve_bot_token().is_some(),
+            running: telegram.is_running().await,
+        };
+    }
+
+    if let Some(mcp) = app.try_state::<Arc<tokio::sync::Mutex<crate::mcp::McpManager>>>() {
+        let statuses = mcp.lock().await.list_status().await;
+        let connected = statuses.iter().filter(|s| s.connected);
+        caps.mcp = McpCapability {
+            connected_servers: connected.clone().count(),
+            tool_count: connected.map(|s| s.tool_count).sum(),
+        };
+    }
+
+    caps.local_models = LocalModelsCapability {
+        memory_embedding: crate::ai::memory::memory_embedding_model_status().installed,
+        sensevoice: crate::stt::sensevoice_local::recommended_model_status().installed,
+        whisper_local: crate::stt::whisper_local::any_model_installed(),
+    };
+
+    if let Some(api) = app.try_state::<crate::api_server::ApiServerService>() {
+        caps.api_server = api.is_running().await;
+    }
+
+    if let Some(vts) = app.try_state::<crate::vts::VtsService>() {
+        caps.vts = vts.status().await.connected;
+    }
+
+    Ok(caps)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vision_needs_switch_and_model() {
+        let mut config = VisionConfig {
+            vlm_enabled: false,
+            ..VisionConfig::default()
+        };
+        assert!(!vision_capability(&config).screen);
+
+        config.vlm_enabled = true;
+        config.vlm_provider = "ollama".to_string();
+        config.vlm_model = " ".to_string();
+        assert!(!vision_capability(&config).screen);
+
+        config.vlm_provider = "llm".to_string();
+        assert!(vision_capability(&config).screen);
+
+        config.vlm_provider = "openai".to_string();
+        config.vlm_model = "gpt-4o".to_string();
+        config.camera_enabled = true;
+        assert_eq!(
+            vision_capability(&config),
+            VisionCapability {
+                screen: true,
+                camera: true
+            }
+        );
+    }
+}
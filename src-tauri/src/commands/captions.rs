@@ -0,0 +1,22 @@
+//! Live caption IPC commands — toggle the voice-mode caption feed.
+
+use crate::captions::{CaptionService, CaptionStatus};
+use crate::error::KokoroError;
+use tauri::State;
+
+#[tauri::command]
+pub async fn set_captions_enabled(
+    state: State<'_, CaptionService>,
+    enabled: bool,
+    file_output: Option<bool>,
+) -> Result<CaptionStatus, KokoroError> {
+    state.set_enabled(enabled, file_output.unwrap_or(false));
+    Ok(state.status())
+}
+
+#[tauri::command]
+pub async fn get_captions_status(
+    state: State<'_, CaptionService>,
+) -> Result<CaptionStatus, KokoroError> {
+    Ok(state.status())
+}
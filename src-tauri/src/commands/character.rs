@@ -1,8 +1,12 @@
+use crate::ai::character_card::{self, CardSource};
 use crate::ai::context::AIOrchestrator;
+use crate::ai::persona_lint::{self, PersonaReport};
+use crate::commands::characters::CharacterRecord;
 use crate::commands::live2d::load_active_live2d_profile;
 use crate::error::KokoroError;
-use serde::Serialize;
-use tauri::{Emitter, State};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter, State};
 
 #[derive(Serialize)]
 pub struct CharacterState {
@@ -110,3 +114,261 @@ pub async fn send_message(message: String) -> Result<ChatResponse, KokoroError>
         "send_message is deprecated. Use stream_chat for real responses.".to_string(),
     ))
 }
+
+fn character_avatars_dir() -> PathBuf {
+    dirs_next::data_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("com.chyin.kokoro")
+        .join("character_avatars")
+}
+
+/// `avatar_path` is stored relative to the avatars dir. Cards imported
+/// before that stored an absolute path, which is accepted (and made
+/// relative) only when it already points into the dir.
+pub(crate) fn normalize_avatar_path(avatar_path: &str) -> Result<String, String> {
+    normalize_avatar_path_in(&character_avatars_dir(), avatar_path)
+}
+
+fn normalize_avatar_path_in(dir: &Path, avatar_path: &str) -> Result<String, String> {
+    let mut path = Path::new(avatar_path);
+    if path.is_absolute() {
+        path = path
+            .strip_prefix(dir)
+            .map_err(|_| "Absolute avatar paths are not allowed".to_string())?;
+    }
+
+    let mut parts = Vec::new();
+    for component in path.components() {
+        match component {
+            std::path::Component::Normal(part) => {
+                parts.push(part.to_string_lossy().to_string());
+            }
+            std::path::Component::CurDir => {}
+            _ => return Err("Invalid avatar path".to_string()),
+        }
+    }
+
+    if parts.is_empty() {
+        return Err("Invalid avatar path".to_string());
+    }
+    Ok(parts.join("/"))
+}
+
+/// The avatar file a stored `avatar_path` refers to, if it exists and
+/// resolves (symlinks included) to somewhere inside the avatars dir.
+pub(crate) fn resolve_avatar_path(avatar_path: &str) -> Option<PathBuf> {
+    resolve_avatar_path_in(&character_avatars_dir(), avatar_path)
+}
+
+fn resolve_avatar_path_in(dir: &Path, avatar_path: &str) -> Option<PathBuf> {
+    let relative = normalize_avatar_path_in(dir, avatar_path).ok()?;
+    let dir = dir.canonicalize().ok()?;
+    let path = dir.join(relative).canonicalize().ok()?;
+    path.starts_with(&dir).then_some(path)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ImportCharacterCardRequest {
+    /// Card file on disk (.png / .json / .charx).
+    #[serde(default)]
+    pub path: Option<String>,
+    /// Raw file bytes, for drag-and-drop imports without a path.
+    #[serde(default)]
+    pub data: Option<Vec<u8>>,
+    /// Also make the imported character the active one.
+    #[serde(default)]
+    pub activate: bool,
+}
+
+/// Import a SillyTavern character card (PNG v2/v3, JSON, or .charx) into the
+/// `characters` table, keeping first message, examples, lorebook and avatar.
+#[tauri::command]
+pub async fn import_character_card(
+    request: ImportCharacterCardRequest,
+    state: State<'_, AIOrchestrator>,
+    app: AppHandle,
+) -> Result<CharacterRecord, KokoroError> {
+    let bytes = match (request.data, request.path.as_deref()) {
+        (Some(data), _) => data,
+        (None, Some(path)) => tokio::fs::read(path).await?,
+        (None, None) => {
+            return Err(KokoroError::Validation(
+                "Either path or data must be provided".to_string(),
+            ))
+        }
+    };
+    let card = character_card::parse_card(&bytes)?;
+
+    let id = uuid::Uuid::new_v4().to_string();
+    let now = chrono::Utc::now().timestamp_millis();
+    let avatar_path = match card.avatar.as_deref() {
+        Some(avatar) => {
+            let dir = character_avatars_dir();
+            tokio::fs::create_dir_all(&dir).await?;
+            let file_name = format!("{}.png", id);
+            tokio::fs::write(dir.join(&file_name), avatar).await?;
+            Some(file_name)
+        }
+        None => None,
+    };
+    let lorebook_json = card.lorebook.as_ref().map(|book| book.to_string());
+
+    let record = CharacterRecord {
+        id,
+        name: card.name.clone(),
+        persona: card.persona.clone(),
+        user_nickname: "{{user}}".to_string(),
+        source_format: card.source_format.clone(),
+        created_at: now,
+        updated_at: now,
+        first_message: card.first_message.clone(),
+        avatar_path: avatar_path.clone(),
+        model_path: None,
+        voice: None,
+        settings: serde_json::json!({}),
+    };
+
+    sqlx::query(
+        "INSERT INTO characters (id, name, persona, user_nickname, source_format, created_at, updated_at, first_message, example_dialogue, lorebook_json, card_json, avatar_path) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&record.id)
+    .bind(&record.name)
+    .bind(&record.persona)
+    .bind(&record.user_nickname)
+    .bind(&record.source_format)
+    .bind(record.created_at)
+    .bind(record.updated_at)
+    .bind(&card.first_message)
+    .bind(&card.example_dialogue)
+    .bind(&lorebook_json)
+    .bind(card.card.to_string())
+    .bind(&avatar_path)
+    .execute(&state.db)
+    .await?;
+
+    if request.activate {
+        state.set_system_prompt(record.persona.clone()).await;
+        state.set_character_name(record.name.clone()).await;
+        crate::character_profiles::switch_character(&app, &record.id, true).await?;
+        AIOrchestrator::persist_active_character_id(&record.id);
+    }
+
+    tracing::info!(
+        target: "character",
+        "Imported {} card '{}' as {} (lorebook: {})",
+        record.source_format,
+        record.name,
+        record.id,
+        lorebook_json.is_some()
+    );
+    Ok(record)
+}
+
+/// Export a character as a card. `format` is `png` (v2 + v3 chunks), `json` (v3) or `charx`.
+/// Returns the written path.
+#[tauri::command]
+pub async fn export_character_card(
+    id: String,
+    format: String,
+    output_path: String,
+    state: State<'_, AIOrchestrator>,
+) -> Result<String, KokoroError> {
+    type Row = (
+        String,
+        String,
+        String,
+        String,
+        Option<String>,
+        Option<String>,
+        Option<String>,
+    );
+    let (name, persona, first_message, example_dialogue, lorebook_json, card_json, avatar_path) =
+        sqlx::query_as::<_, Row>(
+            "SELECT name, persona, first_message, example_dialogue, lorebook_json, card_json, avatar_path FROM characters WHERE id = ?",
+        )
+        .bind(&id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| KokoroError::NotFound(format!("Character {} not found", id)))?;
+
+    let lorebook = lorebook_json.and_then(|raw| serde_json::from_str(&raw).ok());
+    let original_card = card_json.and_then(|raw| serde_json::from_str(&raw).ok());
+    let card = character_card::build_card(&CardSource {
+        name: &name,
+        persona: &persona,
+        first_message: &first_message,
+        example_dialogue: &example_dialogue,
+        lorebook: lorebook.as_ref(),
+        original_card: original_card.as_ref(),
+    });
+    let avatar = match avatar_path.as_deref().and_then(resolve_avatar_path) {
+        Some(path) => tokio::fs::read(&path).await.ok(),
+        None => None,
+    };
+
+    let (bytes, extension) = match format.as_str() {
+        "png" => (
+            character_card::write_png_card(avatar.as_deref(), &card)?,
+            "png",
+        ),
+        "json" => (serde_json::to_vec_pretty(&card)?, "json"),
+        "charx" => (
+            character_card::write_charx(avatar.as_deref(), &card)?,
+            "charx",
+        ),
+        other => {
+            return Err(KokoroError::Validation(format!(
+                "Unsupported card format '{}'; expected png, json or charx",
+                other
+            )))
+        }
+    };
+
+    let path = Path::new(&output_path).with_extension(extension);
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    tokio::fs::write(&path, bytes).await?;
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// Lint a persona before saving it: trait keywords, contradictions, and
+/// missing language/style guidance, checked against the current response language.
+#[tauri::command]
+pub async fn analyze_persona(
+    persona: String,
+    state: State<'_, AIOrchestrator>,
+) -> Result<PersonaReport, KokoroError> {
+    let response_language = state.response_language.lock().await.clone();
+    Ok(persona_lint::analyze_persona(&persona, &response_language))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn avatar_paths_resolve_only_inside_the_avatars_dir() {
+        let root = tempfile::tempdir().unwrap();
+        let dir = root.path().join("character_avatars");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("c1.png"), b"png").unwrap();
+        std::fs::write(root.path().join("secret.txt"), b"secret").unwrap();
+
+        let legacy = dir.join("c1.png").to_string_lossy().to_string();
+        assert_eq!(normalize_avatar_path_in(&dir, &legacy).unwrap(), "c1.png");
+        assert!(resolve_avatar_path_in(&dir, "c1.png").is_some());
+        assert!(resolve_avatar_path_in(&dir, &legacy).is_some());
+
+        let outside = root.path().join("secret.txt");
+        assert!(normalize_avatar_path_in(&dir, &outside.to_string_lossy()).is_err());
+        assert!(resolve_avatar_path_in(&dir, "../secret.txt").is_none());
+        assert!(resolve_avatar_path_in(&dir, "missing.png").is_none());
+
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(&outside, dir.join("link.png")).unwrap();
+            assert!(resolve_avatar_path_in(&dir, "link.png").is_none());
+        }
+    }
+}
@@ -1,7 +1,13 @@
 use crate::ai::context::AIOrchestrator;
+use crate::character_profiles::{
+    self, CharacterProfile, CharacterProfileService, CharacterSwitched,
+};
 use crate::error::KokoroError;
+use crate::trash::Trash;
 use serde::{Deserialize, Serialize};
-use tauri::State;
+use sqlx::sqlite::SqliteRow;
+use sqlx::{Row, SqlitePool};
+use tauri::{AppHandle, State};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CharacterRecord {
@@ -12,6 +18,23 @@ pub struct CharacterRecord {
     pub source_format: String,
     pub created_at: i64,
     pub updated_at: i64,
+    #[serde(default)]
+    pub first_message: String,
+    #[serde(default)]
+    pub avatar_path: Option<String>,
+    /// Default Live2D model (relative to the models dir); a profile binding wins.
+    #[serde(default)]
+    pub model_path: Option<String>,
+    /// Default TTS voice; a profile binding wins.
+    #[serde(default)]
+    pub voice: Option<String>,
+    /// Free-form per-character settings owned by the frontend.
+    #[serde(default = "empty_settings")]
+    pub settings: serde_json::Value,
+}
+
+fn empty_settings() -> serde_json::Value {
+    serde_json::Value::Object(Default::default())
 }
 
 #[derive(Debug, Deserialize)]
@@ -23,8 +46,19 @@ pub struct CreateCharacterRequest {
     pub source_format: String,
     pub created_at: i64,
     pub updated_at: i64,
+    #[serde(default)]
+    pub first_message: Option<String>,
+    #[serde(default)]
+    pub avatar_path: Option<String>,
+    #[serde(default)]
+    pub model_path: Option<String>,
+    #[serde(default)]
+    pub voice: Option<String>,
+    #[serde(default)]
+    pub settings: Option<serde_json::Value>,
 }
 
+/// Optional fields left out keep their stored value.
 #[derive(Debug, Deserialize)]
 pub struct UpdateCharacterRequest {
     pub id: String,
@@ -33,43 +67,120 @@ pub struct UpdateCharacterRequest {
     pub user_nickname: String,
     pub source_format: String,
     pub updated_at: i64,
+    #[serde(default)]
+    pub first_message: Option<String>,
+    #[serde(default)]
+    pub avatar_path: Option<String>,
+    #[serde(default)]
+    pub model_path: Option<String>,
+    #[serde(default)]
+    pub voice: Option<String>,
+    #[serde(default)]
+    pub settings: Option<serde_json::Value>,
+}
+
+const CHARACTER_COLUMNS: &str = "id, name, persona, user_nickname, source_format, created_at, updated_at, first_message, avatar_path, model_path, voice, settings_json";
+
+fn record_from_row(row: &SqliteRow) -> CharacterRecord {
+    let settings_json: String = row.get("settings_json");
+    CharacterRecord {
+        id: row.get("id"),
+        name: row.get("name"),
+        persona: row.get("persona"),
+        user_nickname: row.get("user_nickname"),
+        source_format: row.get("source_format"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+        first_message: row.get("first_message"),
+        avatar_path: row.get("avatar_path"),
+        model_path: row.get("model_path"),
+        voice: row.get("voice"),
+        settings: serde_json::from_str(&settings_json).unwrap_or_else(|_| empty_settings()),
+    }
+}
+
+fn settings_to_json(settings: &serde_json::Value) -> Result<String, KokoroError> {
+    if !settings.is_object() {
+        return Err(KokoroError::Validation(
+            "character settings must be a JSON object".to_string(),
+        ));
+    }
+    Ok(settings.to_string())
+}
+
+/// Empty clears the model; anything else must stay inside the models dir.
+fn model_path_to_column(model_path: Option<&str>) -> Result<Option<String>, KokoroError> {
+    match model_path.map(str::trim) {
+        None => Ok(None),
+        Some("") => Ok(Some(String::new())),
+        Some(path) => crate::commands::live2d::normalize_relative_model_path(path)
+            .map(Some)
+            .map_err(KokoroError::Validation),
+    }
+}
+
+/// Empty clears the avatar; anything else must stay inside the avatars dir.
+fn avatar_path_to_column(avatar_path: Option<&str>) -> Result<Option<String>, KokoroError> {
+    match avatar_path.map(str::trim) {
+        None => Ok(None),
+        Some("") => Ok(Some(String::new())),
+        Some(path) => crate::commands::character::normalize_avatar_path(path)
+            .map(Some)
+            .map_err(KokoroError::Validation),
+    }
+}
+
+/// Look up a character by id. Backend services (Telegram, heartbeat, prompt
+/// composition) use this instead of relying on frontend state.
+pub async fn load_character(
+    db: &SqlitePool,
+    id: &str,
+) -> Result<Option<CharacterRecord>, KokoroError> {
+    let row = sqlx::query(&format!(
+        "SELECT {CHARACTER_COLUMNS} FROM characters WHERE id = ?"
+    ))
+    .bind(id)
+    .fetch_optional(db)
+    .await?;
+    Ok(row.as_ref().map(record_from_row))
 }
 
 #[tauri::command]
 pub async fn list_characters(
     orchestrator: State<'_, AIOrchestrator>,
 ) -> Result<Vec<CharacterRecord>, KokoroError> {
-    let rows = sqlx::query_as::<_, (String, String, String, String, String, i64, i64)>(
-        "SELECT id, name, persona, user_nickname, source_format, created_at, updated_at FROM characters ORDER BY created_at ASC"
-    )
+    let rows = sqlx::query(&format!(
+        "SELECT {CHARACTER_COLUMNS} FROM characters ORDER BY created_at ASC"
+    ))
     .fetch_all(&orchestrator.db)
     .await?;
+    Ok(rows.iter().map(record_from_row).collect())
+}
 
-    Ok(rows
-        .into_iter()
-        .map(
-            |(id, name, persona, user_nickname, source_format, created_at, updated_at)| {
-                CharacterRecord {
-                    id,
-                    name,
-                    persona,
-                    user_nickname,
-                    source_format,
-                    created_at,
-                    updated_at,
-                }
-            },
-        )
-        .collect())
+#[tauri::command]
+pub async fn get_character(
+    id: String,
+    orchestrator: State<'_, AIOrchestrator>,
+) -> Result<CharacterRecord, KokoroError> {
+    load_character(&orchestrator.db, &id)
+        .await?
+        .ok_or_else(|| KokoroError::NotFound(format!("character '{}'", id)))
 }
 
+/// Registers a character. An existing row is kept, unless it is the
+/// placeholder a conversation or memory registered for an unknown id.
 #[tauri::command]
 pub async fn create_character(
     request: CreateCharacterRequest,
     orchestrator: State<'_, AIOrchestrator>,
 ) -> Result<(), KokoroError> {
+    let settings_json = settings_to_json(&request.settings.unwrap_or_else(empty_settings))?;
+    let avatar_path = avatar_path_to_column(request.avatar_path.as_deref())?;
+    let model_path = model_path_to_column(request.model_path.as_deref())?;
     sqlx::query(
-        "INSERT OR IGNORE INTO characters (id, name, persona, user_nickname, source_format, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?)"
+        "INSERT INTO characters (id, name, persona, user_nickname, source_format, created_at, updated_at, first_message, avatar_path, model_path, voice, settings_json) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?) \
+         ON CONFLICT(id) DO UPDATE SET name = excluded.name, persona = excluded.persona, user_nickname = excluded.user_nickname, source_format = excluded.source_format, created_at = excluded.created_at, updated_at = excluded.updated_at, first_message = excluded.first_message, avatar_path = excluded.avatar_path, model_path = excluded.model_path, voice = excluded.voice, settings_json = excluded.settings_json \
+         WHERE characters.source_format = 'auto'"
     )
     .bind(&request.id)
     .bind(&request.name)
@@ -78,6 +189,11 @@ pub async fn create_character(
     .bind(&request.source_format)
     .bind(request.created_at)
     .bind(request.updated_at)
+    .bind(request.first_message.unwrap_or_default())
+    .bind(avatar_path)
+    .bind(model_path)
+    .bind(&request.voice)
+    .bind(settings_json)
     .execute(&orchestrator.db)
     .await?;
     Ok(())
@@ -87,29 +203,242 @@ pub async fn create_character(
 pub async fn update_character(
     request: UpdateCharacterRequest,
     orchestrator: State<'_, AIOrchestrator>,
+    app: AppHandle,
 ) -> Result<(), KokoroError> {
+    let settings_json = request
+        .settings
+        .as_ref()
+        .map(settings_to_json)
+        .transpose()?;
+    let avatar_path = avatar_path_to_column(request.avatar_path.as_deref())?;
+    let model_path = model_path_to_column(request.model_path.as_deref())?;
     sqlx::query(
-        "UPDATE characters SET name = ?, persona = ?, user_nickname = ?, source_format = ?, updated_at = ? WHERE id = ?"
+        "UPDATE characters SET name = ?, persona = ?, user_nickname = ?, source_format = ?, updated_at = ?, first_message = COALESCE(?, first_message), avatar_path = COALESCE(?, avatar_path), model_path = COALESCE(?, model_path), voice = COALESCE(?, voice), settings_json = COALESCE(?, settings_json) WHERE id = ?"
     )
     .bind(&request.name)
     .bind(&request.persona)
     .bind(&request.user_nickname)
     .bind(&request.source_format)
     .bind(request.updated_at)
+    .bind(&request.first_message)
+    .bind(avatar_path)
+    .bind(model_path)
+    .bind(&request.voice)
+    .bind(settings_json)
     .bind(&request.id)
     .execute(&orchestrator.db)
     .await?;
+    // A persona edit can change the derived emotion personality.
+    character_profiles::refresh_if_active(&app, &request.id).await?;
     Ok(())
 }
 
+/// Moves the character, its conversations, its memories and its profile to
+/// the trash; see `undo_last_delete`. The avatar file is kept until the trash
+/// is purged.
 #[tauri::command]
 pub async fn delete_character(
     id: String,
     orchestrator: State<'_, AIOrchestrator>,
+    profiles: State<'_, CharacterProfileService>,
+    trash: State<'_, Trash>,
 ) -> Result<(), KokoroError> {
-    sqlx::query("DELETE FROM characters WHERE id = ?")
-        .bind(&id)
-        .execute(&orchestrator.db)
-        .await?;
+    let conversation_ids: Vec<String> =
+        sqlx::query_scalar("SELECT id FROM conversations WHERE character_id = ?")
+            .bind(&id)
+            .fetch_all(&orchestrator.db)
+            .await?;
+    let profile = profiles
+        .stored_profile(&id)
+        .await
+        .map(serde_json::to_value)
+        .transpose()?;
+    trash.delete_character(&id, profile).await?;
+
+    orchestrator.close_conversations(&conversation_ids).await;
+    profiles.set_profile(&id, None).await?;
     Ok(())
 }
+
+/// Make `character_id` the active character with its persona, bindings,
+/// emotion, Live2D model and mods; emits `character:switched`.
+#[tauri::command]
+pub async fn switch_character(
+    character_id: String,
+    app: AppHandle,
+) -> Result<CharacterSwitched, KokoroError> {
+    character_profiles::activate_character(&app, &character_id).await
+}
+
+#[tauri::command]
+pub async fn get_character_profile(
+    character_id: String,
+    profiles: State<'_, CharacterProfileService>,
+) -> Result<CharacterProfile, KokoroError> {
+    Ok(profiles.get_profile(&character_id).await)
+}
+
+#[tauri::command]
+pub async fn save_character_profile(
+    character_id: String,
+    profile: CharacterProfile,
+    profiles: State<'_, CharacterProfileService>,
+    app: AppHandle,
+) -> Result<(), KokoroError> {
+    if let Some(temperature) = profile.temperature {
+        if !(0.0..=2.0).contains(&temperature) {
+            return Err(KokoroError::Validation(format!(
+                "temperature must be between 0 and 2, got {}",
+                temperature
+            )));
+        }
+    }
+    profile.proactive.validate()?;
+    profile.image_preset.validate()?;
+    profiles.set_profile(&character_id, Some(profile)).await?;
+    character_profiles::refresh_if_active(&app, &character_id).await
+}
+
+#[tauri::command]
+pub async fn delete_character_profile(
+    character_id: String,
+    profiles: State<'_, CharacterProfileService>,
+    app: AppHandle,
+) -> Result<(), KokoroError> {
+    profiles.set_profile(&character_id, None).await?;
+    character_profiles::refresh_if_active(&app, &character_id).await
+}
+
+#[tauri::command]
+pub async fn get_llm_trait_extraction(
+    profiles: State<'_, CharacterProfileService>,
+) -> Result<bool, KokoroError> {
+    Ok(profiles.llm_trait_extraction().await)
+}
+
+/// Enabling re-applies the active character so its persona gets scored now.
+#[tauri::command]
+pub async fn set_llm_trait_extraction(
+    enabled: bool,
+    profiles: State<'_, CharacterProfileService>,
+    app: AppHandle,
+) -> Result<(), KokoroError> {
+    profiles.set_llm_trait_extraction(enabled).await?;
+    if let Some(active) = profiles.active().await {
+        character_profiles::refresh_if_active(&app, &active.character_id).await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn load_character_reads_card_fields_and_settings() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+        sqlx::query(
+            "INSERT INTO characters (id, name, persona, user_nickname, source_format, created_at, updated_at, first_message) VALUES ('c1', 'Mio', 'Shy.', 'you', 'manual', 1, 1, 'Hi!')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let record = load_character(&pool, "c1").await.unwrap().expect("row");
+        assert_eq!(record.first_message, "Hi!");
+        assert_eq!(record.avatar_path, None);
+        assert_eq!(record.settings, empty_settings());
+        assert!(load_character(&pool, "missing").await.unwrap().is_none());
+
+        assert!(settings_to_json(&serde_json::json!([1, 2])).is_err());
+    }
+
+    #[tokio::test]
+    async fn conversations_and_memories_always_have_a_character_row() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+        sqlx::query("INSERT INTO characters (id, name) VALUES ('c1', 'Mio')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        for id in ["c1", "c2"] {
+            sqlx::query(
+                "INSERT INTO conversations (id, character_id, created_at, updated_at) VALUES (?, ?, '', '')",
+            )
+            .bind(format!("conv-{id}"))
+            .bind(id)
+            .execute(&pool)
+            .await
+            .unwrap();
+            sqlx::query(
+                "INSERT INTO conversation_messages (conversation_id, role, content, created_at) VALUES (?, 'user', 'hi', '')",
+            )
+            .bind(format!("conv-{id}"))
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+        sqlx::query(
+            "INSERT INTO memories (content, embedding, created_at) VALUES ('likes tea', x'00', 0)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        // Unknown ids get a placeholder row; registered ones are untouched.
+        let rows: Vec<(String, String, String)> =
+            sqlx::query_as("SELECT id, name, source_format FROM characters ORDER BY id")
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+        assert_eq!(
+            rows,
+            vec![
+                ("c1".to_string(), "Mio".to_string(), "manual".to_string()),
+                ("c2".to_string(), "c2".to_string(), "auto".to_string()),
+                (
+                    "default".to_string(),
+                    "default".to_string(),
+                    "auto".to_string()
+                ),
+            ]
+        );
+
+        // `delete_character` trashes these itself; the row delete alone must
+        // not destroy them.
+        sqlx::query("DELETE FROM characters WHERE id = 'c1'")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let owners: Vec<String> =
+            sqlx::query_scalar("SELECT character_id FROM conversations ORDER BY character_id")
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+        assert_eq!(owners, vec!["c1", "c2"]);
+        let messages: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM conversation_messages")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(messages, 2);
+    }
+
+    #[test]
+    fn model_paths_stay_inside_the_models_dir() {
+        assert_eq!(model_path_to_column(None).unwrap(), None);
+        assert_eq!(
+            model_path_to_column(Some(" ")).unwrap(),
+            Some(String::new())
+        );
+        assert!(model_path_to_column(Some("../outside/model.json")).is_err());
+        assert!(model_path_to_column(Some("/abs/model.json")).is_err());
+
+        assert_eq!(
+            avatar_path_to_column(Some("c1.png")).unwrap(),
+            Some("c1.png".to_string())
+        );
+        assert!(avatar_path_to_column(Some("../../.ssh/id_rsa")).is_err());
+        assert!(avatar_path_to_column(Some("/etc/passwd")).is_err());
+    }
+}
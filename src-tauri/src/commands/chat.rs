@@ -4,22 +4,29 @@ use crate::actions::executor::{
     apply_before_action_args_payload, assistant_tool_call_metadata_value,
     build_action_hook_payload, build_before_action_args_payload, tool_metadata_value,
 };
+use crate::actions::plan::{
+    is_plan_tool, parse_plan, resolve_step_refs, ChatPlanEvent, PlanConfig, PlanControlState,
+    PlanStatus, PlanStepStatus, CHAT_PLAN_EVENT, PLAN_TOOL_NAME,
+};
 use crate::actions::tool_settings::ToolSettings;
 use crate::actions::{
-    build_tool_audit_event, builtin_tool_id, execute_tool_calls, ActionContext, ActionRegistry,
-    ActionResult, PermissionDecision, ToolAuditInput, ToolInvocation,
+    build_tool_audit_event, builtin_tool_id, evaluate_permission_decision, execute_tool_calls,
+    ActionContext, ActionRegistry, ActionResult, PermissionDecision, ToolAuditInput,
+    ToolExecutionOutcome, ToolInvocation,
 };
 use crate::ai::context::AIOrchestrator;
 use crate::ai::context::Message;
+use crate::ai::context_breakdown::ContextBreakdown;
+use crate::ai::latency::{FirstTokenWatch, LatencyBudgetConfig};
 use crate::ai::memory_event_ingress::{
     build_cooldown_key, select_memory_ingress_decision, should_use_structured_extraction,
     MemoryEventIngressOptions,
 };
 use crate::ai::memory_extractor;
 use crate::chat::tags::{
-    extract_translate_tags, find_safe_emit_boundary, merge_continuation_text,
-    merge_round_tool_calls, parse_tool_call_tags, strip_leaked_tags, strip_translate_tags,
-    ToolCall,
+    extract_remember_tags, extract_translate_tags, find_safe_emit_boundary,
+    merge_continuation_text, merge_round_tool_calls, parse_tool_call_tags, strip_leaked_tags,
+    strip_translate_tags, ToolCall,
 };
 use crate::commands::system::WindowSizeState;
 use crate::error::{ChatErrorEvent, KokoroError};
@@ -36,6 +43,7 @@ use crate::llm::messages::{
 };
 use crate::llm::provider::{LlmChatMessage, LlmStreamEvent};
 use crate::llm::service::LlmService;
+use crate::mods::chat_hooks::{self, ChatTransform};
 use futures::StreamExt;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
@@ -176,6 +184,10 @@ struct PendingToolApproval {
 #[derive(Default)]
 pub struct TurnCancellationState {
     cancelled: RwLock<HashMap<String, Option<String>>>,
+    /// Turn currently running in each window, keyed by window label.
+    window_turns: RwLock<HashMap<String, String>>,
+    /// Wakes streams that are waiting on the provider when any turn is cancelled.
+    wakeup: tokio::sync::Notify,
 }
 
 const TURN_CANCELLED_BY_USER_MESSAGE: &str = "turn cancelled by user";
@@ -209,17 +221,51 @@ impl TurnCancellationState {
         }))
     }
 
+    async fn register_window_turn(&self, window_label: &str, turn_id: &str) {
+        self.register_turn(turn_id).await;
+        self.window_turns
+            .write()
+            .await
+            .insert(window_label.to_string(), turn_id.to_string());
+    }
+
     async fn cancel_turn(&self, turn_id: &str, reason: Option<String>) -> Result<(), String> {
         let mut map = self.cancelled.write().await;
         if let Some(entry) = map.get_mut(turn_id) {
             if entry.is_none() {
-                *entry = reason;
+                *entry = reason.or_else(|| Some("cancelled".to_string()));
             }
+            drop(map);
+            self.wakeup.notify_waiters();
             return Ok(());
         }
         Err(format!("unknown turn_id: {}", turn_id))
     }
 
+    /// Cancel whatever turn the window is running. Returns the turn id, if any.
+    pub(crate) async fn cancel_window_turn(
+        &self,
+        window_label: &str,
+        reason: Option<String>,
+    ) -> Option<String> {
+        let turn_id = self.window_turns.read().await.get(window_label).cloned()?;
+        self.cancel_turn(&turn_id, reason).await.ok()?;
+        Some(turn_id)
+    }
+
+    /// Resolves once `turn_id` is cancelled; used to interrupt a stalled stream.
+    async fn wait_cancelled(&self, turn_id: &str) {
+        loop {
+            let notified = self.wakeup.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+            if self.is_cancelled(turn_id).await {
+                return;
+            }
+            notified.await;
+        }
+    }
+
     async fn is_cancelled(&self, turn_id: &str) -> bool {
         self.cancelled
             .read()
@@ -231,6 +277,10 @@ impl TurnCancellationState {
 
     async fn clear_turn(&self, turn_id: &str) {
         self.cancelled.write().await.remove(turn_id);
+        self.window_turns
+            .write()
+            .await
+            .retain(|_, running| running != turn_id);
     }
 }
 
@@ -436,6 +486,83 @@ pub async fn cancel_chat_turn(
     cancel_chat_turn_inner(turn_id, reason, cancel_state.inner().clone()).await
 }
 
+/// Stop the generation running in the calling window, whether or not the
+/// frontend has seen its turn id yet. Returns false when nothing was running.
+#[tauri::command]
+pub async fn cancel_chat(
+    window: Window,
+    reason: Option<String>,
+    cancel_state: State<'_, Arc<TurnCancellationState>>,
+) -> Result<bool, KokoroError> {
+    let cancelled = cancel_state
+        .cancel_window_turn(window.label(), reason)
+        .await;
+    if let Some(turn_id) = cancelled.as_ref() {
+        tracing::info!(
+            target: "chat",
+            "[Chat] Cancel requested for turn {} in window '{}'",
+            turn_id,
+            window.label()
+        );
+    }
+    Ok(cancelled.is_some())
+}
+
+/// Keep the text generated before a cancellation: finalize (or create) the
+/// draft row and append it to in-memory history. Returns whether it was saved.
+async fn persist_cancelled_reply(
+    state: &AIOrchestrator,
+    char_id: &str,
+    turn_id: &str,
+    text: &str,
+    draft_row_id: Option<i64>,
+) -> bool {
+    let metadata_value = serde_json::json!({
+        "turn_id": turn_id,
+        "cancelled": true,
+    });
+    let row_id = match draft_row_id {
+        Some(id) => id,
+        None => match state.persist_streaming_draft(text, char_id).await {
+            Ok(id) => id,
+            Err(e) => {
+                tracing::error!(target: "chat", "[Chat] Failed to persist cancelled reply: {}", e);
+                return false;
+            }
+        },
+    };
+    if let Err(e) = state
+        .update_streaming_draft(row_id, text, Some(&metadata_value.to_string()))
+        .await
+    {
+        tracing::error!(target: "chat", "[Chat] Failed to finalize cancelled reply: {}", e);
+        return false;
+    }
+    state
+        .push_history_message(Message {
+            role: "assistant".to_string(),
+            content: text.to_string(),
+            metadata: Some(metadata_value),
+        })
+        .await;
+    true
+}
+
+/// Delete the streaming draft row of a reply that is not kept.
+async fn discard_reply_draft(state: &AIOrchestrator, draft_row_id: Option<i64>) {
+    let Some(row_id) = draft_row_id else {
+        return;
+    };
+    if let Err(error) = state.delete_message_by_id(row_id).await {
+        tracing::error!(
+            target: "chat",
+            "[Chat] Failed to delete reply draft {}: {}",
+            row_id,
+            error
+        );
+    }
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct ContextSettings {
     pub strategy: String,
@@ -453,6 +580,15 @@ pub async fn get_context_settings(
     })
 }
 
+/// Sections, memories, summaries and history trimming of the last composed
+/// prompt; `None` until a prompt has been composed.
+#[tauri::command]
+pub async fn get_context_breakdown(
+    state: State<'_, AIOrchestrator>,
+) -> Result<Option<ContextBreakdown>, KokoroError> {
+    Ok(state.last_context_breakdown().await)
+}
+
 #[tauri::command]
 pub async fn set_context_settings(
     state: State<'_, AIOrchestrator>,
@@ -502,6 +638,10 @@ pub struct ChatRequest {
     /// Used for touch interactions and proactive triggers where the instruction shouldn't appear in chat.
     #[serde(default)]
     pub hidden: bool,
+    /// Set after `regenerate_last_response`: the user message is already in
+    /// history, so only a new reply is generated.
+    #[serde(default)]
+    pub regenerate: bool,
 }
 
 #[derive(Serialize, Clone)]
@@ -1006,6 +1146,238 @@ async fn wait_for_tool_approval_and_execute(
     }
 }
 
+/// Like `execute_tool_calls`, but `run_plan` calls go through [`run_tool_plan`].
+async fn execute_round_tool_calls(
+    app: &tauri::AppHandle,
+    registry_state: &Arc<RwLock<ActionRegistry>>,
+    tool_settings_state: &Arc<RwLock<ToolSettings>>,
+    cancel_state: &TurnCancellationState,
+    character_id: &str,
+    turn_id: &str,
+    invocations: &[ToolInvocation],
+) -> Vec<ToolExecutionOutcome> {
+    if !invocations.iter().any(|call| is_plan_tool(&call.name)) {
+        return execute_tool_calls(
+            app,
+            registry_state,
+            tool_settings_state,
+            character_id,
+            invocations,
+        )
+        .await;
+    }
+    let mut outcomes = Vec::with_capacity(invocations.len());
+    for invocation in invocations {
+        if is_plan_tool(&invocation.name) {
+            outcomes.push(
+                run_tool_plan(
+                    app,
+                    registry_state,
+                    tool_settings_state,
+                    cancel_state,
+                    character_id,
+                    turn_id,
+                    invocation,
+                )
+                .await,
+            );
+        } else {
+            outcomes.extend(
+                execute_tool_calls(
+                    app,
+                    registry_state,
+                    tool_settings_state,
+                    character_id,
+                    std::slice::from_ref(invocation),
+                )
+                .await,
+            );
+        }
+    }
+    outcomes
+}
+
+/// Execute a `run_plan` call step by step. Each step goes through the normal
+/// tool pipeline; the returned outcome carries the summary of the whole plan.
+async fn run_tool_plan(
+    app: &tauri::AppHandle,
+    registry_state: &Arc<RwLock<ActionRegistry>>,
+    tool_settings_state: &Arc<RwLock<ToolSettings>>,
+    cancel_state: &TurnCancellationState,
+    character_id: &str,
+    turn_id: &str,
+    invocation: &ToolInvocation,
+) -> ToolExecutionOutcome {
+    let plan_action = registry_state
+        .read()
+        .await
+        .resolve_action(&invocation.name)
+        .ok();
+    let finish = |result: Result<ActionResult, String>| ToolExecutionOutcome {
+        invocation: invocation.clone(),
+        action: plan_action.clone(),
+        result,
+        needs_feedback: true,
+        permission_decision: None,
+    };
+
+    if !tool_settings_state
+        .read()
+        .await
+        .is_enabled(&builtin_tool_id(PLAN_TOOL_NAME))
+    {
+        return finish(Err(format!(
+            "Tool '{}' is disabled",
+            builtin_tool_id(PLAN_TOOL_NAME)
+        )));
+    }
+    let config = match app.try_state::<Arc<RwLock<PlanConfig>>>() {
+        Some(config) => config.read().await.clone(),
+        None => PlanConfig::default(),
+    };
+    let Some(control) = app
+        .try_state::<Arc<PlanControlState>>()
+        .map(|state| state.inner().clone())
+    else {
+        return finish(Err("Tool plans are unavailable".to_string()));
+    };
+    let plan = match parse_plan(&invocation.args, config.max_steps) {
+        Ok(plan) => plan,
+        Err(error) => return finish(Err(error)),
+    };
+
+    let plan_id = Uuid::new_v4().to_string();
+    let mut event = ChatPlanEvent::new(turn_id, &plan_id, &plan);
+    let _ = app.emit(CHAT_PLAN_EVENT, &event);
+    tracing::info!(
+        target: "chat::tools",
+        "[Plan] {} started with {} step(s): {}",
+        plan_id,
+        plan.steps.len(),
+        plan.goal
+    );
+
+    let mut results: Vec<Option<String>> = Vec::with_capacity(plan.steps.len());
+    for (index, step) in plan.steps.iter().enumerate() {
+        if control.is_aborted(&plan_id).await || cancel_state.is_cancelled(turn_id).await {
+            event.status = PlanStatus::Aborted;
+            break;
+        }
+        let step_invocation = ToolInvocation {
+            tool_call_id: None,
+            name: step.tool.clone(),
+            args: resolve_step_refs(&step.args, &results),
+        };
+        let step_action = match registry_state.read().await.resolve_action(&step.tool) {
+            Ok(action) => action,
+            Err(error) => {
+                event.set_step(index, PlanStepStatus::Failed, Some(error.0));
+                event.status = PlanStatus::Failed;
+                break;
+            }
+        };
+
+        // Confirming a step also grants the approval its tool policy asks for.
+        let pending_approval = matches!(
+            evaluate_permission_decision(&step_action, &*tool_settings_state.read().await),
+            PermissionDecision::DenyPendingApproval { .. }
+        );
+        let mut approved = false;
+        if pending_approval || config.needs_confirmation(&step_action) {
+            let decision = control.request_confirmation(&plan_id, index).await;
+            event.set_step(index, PlanStepStatus::AwaitingConfirmation, None);
+            let _ = app.emit(CHAT_PLAN_EVENT, &event);
+            let answer = tokio::select! {
+                answer = decision => answer.ok(),
+                _ = cancel_state.wait_cancelled(turn_id) => None,
+            };
+            match answer {
+                Some(true) => approved = true,
+                Some(false) => {
+                    event.set_step(
+                        index,
+                        PlanStepStatus::Skipped,
+                        Some("declined by the user".to_string()),
+                    );
+                    results.push(None);
+                    let _ = app.emit(CHAT_PLAN_EVENT, &event);
+                    continue;
+                }
+                None => {
+                    event.status = PlanStatus::Aborted;
+                    break;
+                }
+            }
+        }
+
+        event.set_step(index, PlanStepStatus::Running, None);
+        let _ = app.emit(CHAT_PLAN_EVENT, &event);
+        let step_outcome = if pending_approval && approved {
+            ToolExecutionOutcome {
+                result: execute_single_tool_after_approval(
+                    app,
+                    registry_state,
+                    character_id,
+                    &step_invocation,
+                )
+                .await,
+                invocation: step_invocation,
+                needs_feedback: step_action.needs_feedback,
+                action: Some(step_action),
+                permission_decision: Some(PermissionDecision::Allow),
+            }
+        } else {
+            match execute_tool_calls(
+                app,
+                registry_state,
+                tool_settings_state,
+                character_id,
+                std::slice::from_ref(&step_invocation),
+            )
+            .await
+            .pop()
+            {
+                Some(outcome) => outcome,
+                None => break,
+            }
+        };
+        emit_tool_trace_event(app, turn_id, &step_outcome);
+
+        match step_outcome.result {
+            Ok(result) => {
+                event.set_step(index, PlanStepStatus::Done, Some(result.message.clone()));
+                results.push(Some(result.message));
+                let _ = app.emit(CHAT_PLAN_EVENT, &event);
+            }
+            Err(error) => {
+                event.set_step(index, PlanStepStatus::Failed, Some(error));
+                event.status = PlanStatus::Failed;
+                break;
+            }
+        }
+    }
+
+    match event.status {
+        PlanStatus::Running => event.status = PlanStatus::Completed,
+        PlanStatus::Aborted => event.close_remaining(PlanStepStatus::Aborted, "plan aborted"),
+        PlanStatus::Failed => {
+            event.close_remaining(PlanStepStatus::Skipped, "an earlier step failed")
+        }
+        PlanStatus::Completed => {}
+    }
+    let _ = app.emit(CHAT_PLAN_EVENT, &event);
+    control.finish(&plan_id).await;
+    tracing::info!(
+        target: "chat::tools",
+        "[Plan] {} finished: {:?}",
+        plan_id,
+        event.status
+    );
+
+    let data = serde_json::to_value(&event).unwrap_or_default();
+    finish(Ok(ActionResult::ok_with_data(event.summary(), data)))
+}
+
 #[cfg(test)]
 fn sample_action_result(message: &str) -> crate::actions::ActionResult {
     crate::actions::ActionResult {
@@ -1130,6 +1502,47 @@ pub async fn stream_chat(
         std::sync::Arc<tokio::sync::Mutex<crate::vision::server::VisionServer>>,
     >,
 ) -> Result<(), KokoroError> {
+    // Mod chat hooks may rewrite or veto the user's message before it is queued.
+    let mod_chat_hooks = chat_hooks::for_app(&app).await;
+    let mut request = request;
+    let send_hooks = mod_chat_hooks
+        .as_ref()
+        .filter(|_| !request.hidden && !request.regenerate);
+    if let Some(hooks) = send_hooks {
+        let payload = serde_json::json!({
+            "text": request.message,
+            "character_id": request.character_id,
+        });
+        match hooks.transform(chat_hooks::BEFORE_SEND, payload).await {
+            ChatTransform::Keep => {}
+            ChatTransform::Replace { text } => request.message = text,
+            ChatTransform::Veto { reason } => {
+                return Err(KokoroError::Validation(reason.unwrap_or_else(|| {
+                    "This message was blocked by a mod".to_string()
+                })));
+            }
+        }
+    }
+    // While a mod rewrites replies, deltas are held back so the raw text
+    // never shows; the final text arrives with chat-turn-text-complete.
+    let display_hooks = mod_chat_hooks.filter(|hooks| hooks.is_watched(chat_hooks::BEFORE_DISPLAY));
+
+    // Wait for earlier turns of this window; in restart mode a newer user
+    // message cancels the running turn instead of waiting it out.
+    let queue_ticket =
+        state
+            .input_queue
+            .enqueue(&app, window.label(), &request.message, request.hidden)?;
+    if queue_ticket.supersedes_running() {
+        cancel_state
+            .cancel_window_turn(
+                window.label(),
+                Some("superseded by a newer message".to_string()),
+            )
+            .await;
+    }
+    let queue_slot = queue_ticket.wait_turn().await;
+
     // 0. Resolve character ID for this request (not stored in shared state)
     let char_id = request
         .character_id
@@ -1137,8 +1550,9 @@ pub async fn stream_chat(
         .unwrap_or_else(|| "default".to_string());
     let conversation_id = state.current_conversation_id.lock().await.clone();
     let hook_runtime = app.try_state::<HookRuntime>();
-    // Keep shared character_id in sync for modules that still read it (heartbeat)
-    state.set_character_id(char_id.clone()).await;
+    // Keep shared character_id in sync for modules that still read it (heartbeat);
+    // a change of character also rebinds its model, voice and personality.
+    crate::character_profiles::switch_character(&app, &char_id, false).await?;
 
     if let Some(hooks) = hook_runtime.as_ref() {
         hooks
@@ -1160,14 +1574,23 @@ pub async fn stream_chat(
     // Record user activity
     state.touch_activity().await;
 
+    // Registered up front so `cancel_chat` can stop the turn while the prompt is still being built.
+    let assistant_turn_id = uuid::Uuid::new_v4().to_string();
+    cancel_state
+        .register_window_turn(window.label(), &assistant_turn_id)
+        .await;
+    let _turn_guard =
+        TurnCancellationGuard::new(cancel_state.inner().clone(), assistant_turn_id.clone());
+
     // Typing simulation
-    {
+    if !queue_slot.merged() {
         let is_question = request.message.contains('?') || request.message.contains('？');
+        let personality = state.get_personality().await;
         let typing_params = crate::ai::typing_sim::calculate_typing_delay(
             "neutral",
-            0.5,
-            0.6,
-            request.message.chars().count(),
+            personality.default_mood,
+            personality.expressiveness,
+            crate::utils::segment::reading_length(&request.message),
             is_question,
         );
         let _ = app.emit("chat-typing", &typing_params);
@@ -1179,18 +1602,19 @@ pub async fn stream_chat(
         .latest_completed_observation(chrono::Utc::now())
         .await;
 
-    // 2. Update History with User Message (skip for hidden/touch interactions)
+    // 2. Update History with User Message (skip for hidden/touch interactions and regenerations)
     let system_provider = llm_state.system_provider().await;
-    if !request.hidden {
+    if !request.hidden && !request.regenerate {
         if let Some(observation) = selected_vision_observation.as_ref() {
             persist_vision_context_message(&state, observation, &char_id, None).await;
         }
 
+        let user_metadata = state.user_message_metadata(&request.message).await;
         state
             .add_message_with_metadata(
                 "user".to_string(),
                 request.message.clone(),
-                None,
+                user_metadata,
                 &char_id,
                 Some(system_provider.clone()),
             )
@@ -1214,6 +1638,17 @@ pub async fn stream_chat(
         }
     }
 
+    // A newer message absorbed this one: it is in history now and gets
+    // answered together with the rest by the turn that superseded it.
+    if queue_slot.merged() {
+        tracing::info!(
+            target: "chat",
+            "[Chat] Queued message merged into a newer turn ({})",
+            window.label()
+        );
+        return Ok(());
+    }
+
     // ── LAYER 1 & 2: SYSTEM SETUP ───────────────────────────────
 
     // ── EXECUTION & STATE UPDATE ────────────────────────────────
@@ -1221,14 +1656,19 @@ pub async fn stream_chat(
     // ── LAYER 3: PERSONA GENERATION ─────────────────────────────
 
     let llm_config = llm_state.config().await;
-    let chat_provider = llm_state.provider().await;
+    let model_override = match conversation_id.as_deref() {
+        Some(id) => crate::commands::conversation::load_model_override(&state.db, id)
+            .await
+            .unwrap_or_else(|e| {
+                tracing::warn!(target: "chat", "[Chat] Failed to load conversation model: {}", e);
+                None
+            }),
+        None => None,
+    };
+    let chat_provider = llm_state.provider_for(model_override.as_ref()).await;
+    let chat_params = llm_state.chat_params_for(model_override.as_ref()).await;
     let effective_provider_id = chat_provider.id().to_string();
-    let native_tools_enabled = llm_config
-        .providers
-        .iter()
-        .find(|provider| provider.id == effective_provider_id)
-        .map(|provider| provider.supports_native_tools)
-        .unwrap_or_else(|| chat_provider.supports_native_tools());
+    let native_tools_enabled = llm_state.native_tools_enabled(chat_provider.as_ref()).await;
     tracing::info!(
         target: "chat",
         "[Chat] configured_active_provider={}, effective_active_provider={}, native_tools_enabled={}",
@@ -1273,7 +1713,7 @@ pub async fn stream_chat(
     let memory_target_language = state.response_language.lock().await.clone();
 
     // Compose Persona Prompt
-    let (prompt_messages, compose_warnings) = state
+    let (mut prompt_messages, compose_warnings) = state
         .compose_prompt(
             &request.message,
             request.allow_image_gen.unwrap_or(false),
@@ -1284,17 +1724,23 @@ pub async fn stream_chat(
         .await
         .map_err(|e| KokoroError::Chat(e.to_string()))?;
 
+    // Only this pipeline handles [REMEMBER:...], so the tag is taught here
+    // rather than in the shared persona prompt.
+    if !request.hidden && state.is_memory_write_enabled() {
+        if let Some(system) = prompt_messages.iter_mut().find(|m| m.role == "system") {
+            system.content.push_str("\n\n");
+            system
+                .content
+                .push_str(crate::ai::prompts::REMEMBER_TAG_PROMPT);
+        }
+    }
+
     // 将构建过程中产生的非致命警告（如记忆检索失败）通知前端
     for warning in compose_warnings {
         tracing::warn!("[compose_prompt] {}", warning);
         let _ = app.emit("chat-warning", &warning);
     }
 
-    let assistant_turn_id = uuid::Uuid::new_v4().to_string();
-    cancel_state.register_turn(&assistant_turn_id).await;
-    let _turn_guard =
-        TurnCancellationGuard::new(cancel_state.inner().clone(), assistant_turn_id.clone());
-
     let stream_result: Result<(), KokoroError> = async {
     let mut before_llm_request_payload = build_before_llm_request_payload(
         conversation_id.clone(),
@@ -1432,28 +1878,59 @@ pub async fn stream_chat(
     };
     let mut all_cleaned_text = String::new();
     let mut all_translations = Vec::new();
+    let mut remembered_facts = Vec::new();
     let mut bg_generated_by_tool = false;
     let mut cue_set_by_tool = false;
     let mut draft_row_id: Option<i64> = None;
     let mut stream_failed = false;
+    let mut turn_cancelled = false;
     let mut all_reasoning_content = String::new();
+    // Covers opening the stream too: some backends only answer once the model is loaded.
+    let latency_budget = match app.try_state::<Arc<RwLock<LatencyBudgetConfig>>>() {
+        Some(budget) => budget.read().await.clone(),
+        None => LatencyBudgetConfig {
+            enabled: false,
+            ..Default::default()
+        },
+    };
+    let mut first_token_watch = FirstTokenWatch::start(
+        &app,
+        &latency_budget,
+        &assistant_turn_id,
+        &effective_provider_id,
+        &memory_target_language,
+    );
 
     for round in 0..max_tool_rounds {
         tracing::info!(target: "chat", "[Chat] Tool loop round {}", round + 1);
-        ensure_turn_not_cancelled(cancel_state.inner().as_ref(), &assistant_turn_id)
+        if ensure_turn_not_cancelled(cancel_state.inner().as_ref(), &assistant_turn_id)
             .await
-            .map_err(KokoroError::Chat)?;
+            .is_err()
+        {
+            turn_cancelled = true;
+            break;
+        }
 
+        // Thinking until this round's first visible text.
+        let mut thinking = Some(crate::ai::companion_state::enter(
+            &app,
+            crate::ai::companion_state::CompanionActivity::Thinking,
+            None,
+        ));
         let mut stream: std::pin::Pin<
             Box<dyn futures::Stream<Item = Result<LlmStreamEvent, String>> + Send>,
         > = if native_tools_enabled {
             chat_provider
-                .chat_stream_with_tools_rich(client_messages.clone(), None, native_tools.clone())
+                .chat_stream_with_tools_rich(
+                    client_messages.clone(),
+                    chat_params.clone(),
+                    native_tools.clone(),
+                )
                 .await
                 .map_err(KokoroError::Chat)?
         } else {
             chat_provider
-                .chat_stream_rich(client_messages.clone(), None)
+                .chat_stream_rich(client_messages.clone(), chat_params.clone())
                 .await
                 .map_err(KokoroError::Chat)?
         };
@@ -1463,7 +1940,17 @@ pub async fn stream_chat(
         let mut emit_buffer = String::new();
         let mut native_tool_calls = Vec::new();
 
-        while let Some(result) = stream.next().await {
+        loop {
+            let result = tokio::select! {
+                next = stream.next() => match next {
+                    Some(result) => result,
+                    None => break,
+                },
+                _ = cancel_state.wait_cancelled(&assistant_turn_id) => {
+                    turn_cancelled = true;
+                    break;
+                }
+            };
             match result {
                 Ok(event) => {
                     match event {
@@ -1476,15 +1963,24 @@ pub async fn stream_chat(
                             if safe > 0 {
                                 let to_emit = emit_buffer[..safe].to_string();
                                 emit_buffer = emit_buffer[safe..].to_string();
-                                let payload = build_turn_delta_payload_if_not_cancelled(
+                                let Ok(payload) = build_turn_delta_payload_if_not_cancelled(
                                     cancel_state.inner().as_ref(),
                                     &assistant_turn_id,
                                     to_emit,
                                 )
                                 .await
-                                .map_err(KokoroError::Chat)?;
-                                app.emit("chat-turn-delta", payload)
-                                    .map_err(|e| KokoroError::Chat(e.to_string()))?;
+                                else {
+                                    turn_cancelled = true;
+                                    break;
+                                };
+                                if let Some(thinking) = thinking.take() {
+                                    thinking.finish();
+                                }
+                                first_token_watch.stop();
+                                if display_hooks.is_none() {
+                                    app.emit("chat-turn-delta", payload)
+                                        .map_err(|e| KokoroError::Chat(e.to_string()))?;
+                                }
                             }
                         }
                         LlmStreamEvent::ReasoningContent(content) => {
@@ -1528,25 +2024,39 @@ pub async fn stream_chat(
             }
         }
 
+        if turn_cancelled {
+            // Keep what was streamed so far; partial tool-call tags are dropped.
+            let (partial_text, _) = parse_tool_call_tags(&round_response);
+            let (partial_text, _) = extract_translate_tags(&partial_text);
+            let (partial_text, _) = extract_remember_tags(&partial_text);
+            merge_continuation_text(&mut all_cleaned_text, &partial_text);
+            break;
+        }
+
         // Flush remaining buffer — strip any complete tags before emitting
         if !emit_buffer.is_empty() {
             let (cleaned_remainder, _) = parse_tool_call_tags(&emit_buffer);
             let cleaned_remainder = strip_translate_tags(&cleaned_remainder);
-            if !cleaned_remainder.is_empty() {
-                let payload = build_turn_delta_payload_if_not_cancelled(
+            let (cleaned_remainder, _) = extract_remember_tags(&cleaned_remainder);
+            if !cleaned_remainder.is_empty() && display_hooks.is_none() {
+                // The stream already finished; a late cancel only suppresses the delta.
+                if let Ok(payload) = build_turn_delta_payload_if_not_cancelled(
                     cancel_state.inner().as_ref(),
                     &assistant_turn_id,
                     cleaned_remainder,
                 )
                 .await
-                .map_err(KokoroError::Chat)?;
-                app.emit("chat-turn-delta", payload)
-                    .map_err(|e| KokoroError::Chat(e.to_string()))?;
+                {
+                    app.emit("chat-turn-delta", payload)
+                        .map_err(|e| KokoroError::Chat(e.to_string()))?;
+                }
             }
         }
 
         let (cleaned_text, parsed_tool_calls) = parse_tool_call_tags(&round_response);
         let (cleaned_text, round_translation) = extract_translate_tags(&cleaned_text);
+        let (cleaned_text, round_facts) = extract_remember_tags(&cleaned_text);
+        remembered_facts.extend(round_facts);
         let (tool_calls, deduped_textual_tool_call_count) =
             merge_round_tool_calls(parsed_tool_calls, native_tool_calls);
 
@@ -1647,20 +2157,30 @@ pub async fn stream_chat(
                 })
                 .collect::<Result<Vec<_>, _>>()?
         };
-        ensure_turn_not_cancelled(cancel_state.inner().as_ref(), &assistant_turn_id)
+        if ensure_turn_not_cancelled(cancel_state.inner().as_ref(), &assistant_turn_id)
             .await
-            .map_err(KokoroError::Chat)?;
-        let execution_outcomes = execute_tool_calls(
+            .is_err()
+        {
+            turn_cancelled = true;
+            break;
+        }
+        let execution_outcomes = execute_round_tool_calls(
             window.app_handle(),
             &_action_registry.inner().clone(),
             &tool_settings_state.inner().clone(),
+            cancel_state.inner().as_ref(),
             &char_id,
+            &assistant_turn_id,
             &tool_invocations,
         )
         .await;
-        ensure_turn_not_cancelled(cancel_state.inner().as_ref(), &assistant_turn_id)
+        if ensure_turn_not_cancelled(cancel_state.inner().as_ref(), &assistant_turn_id)
             .await
-            .map_err(KokoroError::Chat)?;
+            .is_err()
+        {
+            turn_cancelled = true;
+            break;
+        }
         let mut tool_results = Vec::new();
         let mut tool_result_messages = Vec::new();
         let mut continuation_tool_calls: Vec<serde_json::Value> = Vec::new();
@@ -1850,18 +2370,60 @@ pub async fn stream_chat(
         );
     }
 
-    let full_response = strip_leaked_tags(&all_cleaned_text);
+    let mut full_response = strip_leaked_tags(&all_cleaned_text);
 
-    if request.hidden && is_proactive_noop_response(&full_response) {
-        if let Some(row_id) = draft_row_id {
-            if let Err(error) = state.delete_message_by_id(row_id).await {
-                tracing::error!(
+    if turn_cancelled {
+        let persisted = !request.hidden
+            && !full_response.is_empty()
+            && persist_cancelled_reply(
+                &state,
+                &char_id,
+                &assistant_turn_id,
+                &full_response,
+                draft_row_id,
+            )
+            .await;
+        if !persisted {
+            // Nothing worth keeping: don't leave a half-streamed draft behind.
+            discard_reply_draft(&state, draft_row_id).await;
+        }
+        let _ = app.emit(
+            "chat-cancelled",
+            serde_json::json!({
+                "turn_id": assistant_turn_id,
+                "text": full_response,
+                "persisted": persisted,
+            }),
+        );
+        return Err(KokoroError::Chat(TURN_CANCELLED_BY_USER_MESSAGE.to_string()));
+    }
+
+    let mut display_vetoed = false;
+    let reply_hooks = display_hooks.as_ref().filter(|_| !full_response.is_empty());
+    if let Some(hooks) = reply_hooks {
+        let payload = serde_json::json!({
+            "text": full_response,
+            "character_id": char_id,
+            "conversation_id": conversation_id,
+            "turn_id": assistant_turn_id,
+        });
+        match hooks.transform(chat_hooks::BEFORE_DISPLAY, payload).await {
+            ChatTransform::Keep => {}
+            ChatTransform::Replace { text } => full_response = text,
+            ChatTransform::Veto { reason } => {
+                tracing::info!(
                     target: "chat",
-                    "[Chat] Failed to delete hidden proactive no-op draft: {}",
-                    error
+                    "[Chat] Reply {} vetoed by a mod: {}",
+                    assistant_turn_id,
+                    reason.as_deref().unwrap_or("no reason given")
                 );
+                display_vetoed = true;
             }
         }
+    }
+
+    if display_vetoed || (request.hidden && is_proactive_noop_response(&full_response)) {
+        discard_reply_draft(&state, draft_row_id).await;
         app.emit(
             "chat-turn-text-complete",
             serde_json::json!({
@@ -2061,6 +2623,13 @@ pub async fn stream_chat(
                     None,
                 )
                 .await;
+            if let Some(composer) =
+                app.try_state::<crate::ai::proactive_composer::ProactiveComposer>()
+            {
+                composer
+                    .record_reply(&state.db, &char_id, &request.message, &full_response)
+                    .await;
+            }
         } else {
             // Update the draft row with final content + metadata (DB already has the row)
             if let Some(row_id) = draft_row_id {
@@ -2084,6 +2653,30 @@ pub async fn stream_chat(
         }
     }
 
+    // Facts the character was explicitly asked to remember skip the extractor.
+    if !request.hidden && !remembered_facts.is_empty() && state.is_memory_write_enabled() {
+        let memory_mgr = state.memory_manager.clone();
+        let char_id_for_pin = char_id.clone();
+        let app_for_pin = app.clone();
+        tauri::async_runtime::spawn(async move {
+            for fact in remembered_facts {
+                match memory_mgr.pin_memory(&fact, &char_id_for_pin, None).await {
+                    Ok(id) => tracing::info!(
+                        target: "memory",
+                        "[Memory] Pinned memory {} from [REMEMBER] tag",
+                        id
+                    ),
+                    Err(e) => tracing::warn!(
+                        target: "memory",
+                        "[Memory] Failed to pin [REMEMBER] fact: {}",
+                        e
+                    ),
+                }
+            }
+            let _ = app_for_pin.emit("memory:updated", &char_id_for_pin);
+        });
+    }
+
     // Event-driven + periodic memory extraction
     let msg_count = state.get_message_count().await;
     let memory_msg_count = state.get_memory_trigger_count().await;
@@ -2100,7 +2693,7 @@ pub async fn stream_chat(
         msg_count, memory_msg_count
     );
 
-    if !request.hidden && state.is_memory_enabled() {
+    if !request.hidden && state.is_memory_write_enabled() {
         if let Some(decision) = select_memory_ingress_decision(&request.message, &ingress_options) {
             let conversation_key = conversation_id
                 .as_deref()
@@ -2158,10 +2751,18 @@ pub async fn stream_chat(
         }
     }
 
-    if !request.hidden && state.is_memory_enabled() && memory_msg_count > 0 && memory_msg_count % 5 == 0 {
+    let topic_trigger = if !request.hidden && state.is_memory_write_enabled() {
+        state
+            .observe_topic_turn(&char_id, &request.message, &crate::ai::topic_shift::TopicShiftOptions::from(&upgrade_config))
+            .await
+    } else {
+        None
+    };
+    if let Some(trigger) = topic_trigger {
         tracing::info!(
             target: "memory",
-            "[Memory] Triggering memory extraction (count={})",
+            "[Memory] Triggering memory extraction (trigger={}, count={})",
+            trigger.as_str(),
             msg_count
         );
         let history = state.get_recent_memory_history(10).await;
@@ -2193,7 +2794,7 @@ pub async fn stream_chat(
     }
 
     // Periodic memory consolidation (every 20 user messages)
-    if !request.hidden && state.is_memory_enabled() && memory_msg_count > 0 && memory_msg_count % 20 == 0 {
+    if !request.hidden && state.is_memory_write_enabled() && memory_msg_count > 0 && memory_msg_count % 20 == 0 {
         let memory_mgr = state.memory_manager.clone();
         let char_id_for_consolidation = char_id.clone();
         let provider_for_consolidation = system_provider.clone();
@@ -3445,6 +4046,31 @@ mod tests {
         assert!(payload.is_err());
     }
 
+    #[tokio::test]
+    async fn cancel_window_turn_wakes_waiting_stream() {
+        let state = Arc::new(TurnCancellationState::new());
+        assert!(state.cancel_window_turn("main", None).await.is_none());
+
+        state.register_window_turn("main", "turn-1").await;
+        let waiter = {
+            let state = Arc::clone(&state);
+            tokio::spawn(async move { state.wait_cancelled("turn-1").await })
+        };
+        tokio::task::yield_now().await;
+
+        assert_eq!(
+            state.cancel_window_turn("main", None).await.as_deref(),
+            Some("turn-1")
+        );
+        tokio::time::timeout(std::time::Duration::from_secs(1), waiter)
+            .await
+            .expect("waiter should wake on cancel")
+            .unwrap();
+
+        state.clear_turn("turn-1").await;
+        assert!(state.cancel_window_turn("main", None).await.is_none());
+    }
+
     #[tokio::test]
     async fn cancel_chat_turn_returns_error_for_unknown_turn_id() {
         let state = Arc::new(TurnCancellationState::new());
@@ -0,0 +1,25 @@
+//! Sandboxed code execution settings.
+
+use crate::actions::code_exec::{self, CodeExecConfig};
+use crate::error::KokoroError;
+use std::sync::Arc;
+use tauri::State;
+use tokio::sync::RwLock;
+
+#[tauri::command]
+pub async fn get_code_exec_config(
+    state: State<'_, Arc<RwLock<CodeExecConfig>>>,
+) -> Result<CodeExecConfig, KokoroError> {
+    Ok(state.read().await.clone())
+}
+
+#[tauri::command]
+pub async fn save_code_exec_config(
+    config: CodeExecConfig,
+    state: State<'_, Arc<RwLock<CodeExecConfig>>>,
+) -> Result<(), KokoroError> {
+    let sanitized = config.sanitized();
+    code_exec::save_config(&code_exec::config_path(), &sanitized)?;
+    *state.write().await = sanitized;
+    Ok(())
+}
@@ -0,0 +1,24 @@
+//! Companion status line IPC commands.
+
+use crate::ai::companion_state::{self, CompanionActivity, CompanionState, CompanionStateService};
+use crate::error::KokoroError;
+use tauri::State;
+
+/// Current state, for surfaces that mount after the last `companion:state` event.
+#[tauri::command]
+pub async fn get_companion_state(
+    state: State<'_, CompanionStateService>,
+) -> Result<CompanionState, KokoroError> {
+    Ok(state.snapshot())
+}
+
+/// The frontend owns audio playback, so it reports when speech is audible;
+/// the backend only knows when synthesis starts and ends.
+#[tauri::command]
+pub async fn set_companion_playback(
+    app: tauri::AppHandle,
+    playing: bool,
+) -> Result<(), KokoroError> {
+    companion_state::set_active(&app, CompanionActivity::Speaking, playing);
+    Ok(())
+}
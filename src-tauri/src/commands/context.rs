@@ -1,10 +1,14 @@
 // pattern: Mixed (unavoidable)
 // Reason: Tauri command 文件天然承担 IPC 输入校验、状态编排与磁盘持久化副作用；Phase 1 仅在现有命令边界上低侵入扩展。
 use crate::ai::context::AIOrchestrator;
+use crate::ai::history::ChatSurface;
+use crate::ai::proactive_composer::{
+    ProactiveComposer, ProactiveComposerConfig, ProactiveHistoryEntry,
+};
 use crate::error::KokoroError;
 use crate::llm::messages::{system_message, user_text_message};
 use crate::llm::provider::{build_openai_client, create_chat};
-use tauri::{AppHandle, Manager, State};
+use tauri::{AppHandle, Emitter, Manager, State};
 
 pub use crate::config::MemoryUpgradeConfig;
 
@@ -58,7 +62,7 @@ fn save_user_profile_settings_to_app_data(
     std::fs::write(path, json).map_err(KokoroError::from)
 }
 
-fn update_user_profile_settings<F>(app: &AppHandle, update: F) -> Result<(), KokoroError>
+pub(crate) fn update_user_profile_settings<F>(app: &AppHandle, update: F) -> Result<(), KokoroError>
 where
     F: FnOnce(&mut UserProfileSettings),
 {
@@ -91,6 +95,13 @@ pub async fn get_memory_observability_summary(
         .map_err(|e| KokoroError::Database(e.to_string()))
 }
 
+#[tauri::command]
+pub async fn get_embedding_cache_stats(
+    state: State<'_, AIOrchestrator>,
+) -> Result<crate::ai::embedding_cache::EmbeddingCacheStats, KokoroError> {
+    Ok(state.memory_manager.embedding_cache_stats())
+}
+
 #[tauri::command]
 pub async fn get_latest_memory_write_event(
     state: State<'_, AIOrchestrator>,
@@ -169,11 +180,8 @@ pub async fn set_character_name(
 }
 
 #[tauri::command]
-pub async fn set_active_character_id(
-    id: String,
-    state: State<'_, AIOrchestrator>,
-) -> Result<(), KokoroError> {
-    state.set_character_id(id.clone()).await;
+pub async fn set_active_character_id(id: String, app: AppHandle) -> Result<(), KokoroError> {
+    crate::character_profiles::switch_character(&app, &id, false).await?;
     crate::ai::context::AIOrchestrator::persist_active_character_id(&id);
     Ok(())
 }
@@ -273,6 +281,41 @@ pub async fn get_proactive_enabled(state: State<'_, AIOrchestrator>) -> Result<b
     Ok(state.is_proactive_enabled())
 }
 
+#[tauri::command]
+pub async fn get_proactive_composer_config(
+    composer: State<'_, ProactiveComposer>,
+) -> Result<ProactiveComposerConfig, KokoroError> {
+    Ok(composer.config().await)
+}
+
+#[tauri::command]
+pub async fn save_proactive_composer_config(
+    config: ProactiveComposerConfig,
+    composer: State<'_, ProactiveComposer>,
+) -> Result<(), KokoroError> {
+    config.validate()?;
+    crate::ai::proactive_composer::save_config(
+        &crate::ai::proactive_composer::config_path(),
+        &config,
+    )?;
+    composer.set_config(config).await;
+    Ok(())
+}
+
+/// Recent unprompted messages for a character (the active one by default).
+#[tauri::command]
+pub async fn list_proactive_history(
+    character_id: Option<String>,
+    limit: Option<usize>,
+    state: State<'_, AIOrchestrator>,
+) -> Result<Vec<ProactiveHistoryEntry>, KokoroError> {
+    let character_id = match character_id {
+        Some(id) => id,
+        None => state.get_character_id().await,
+    };
+    crate::ai::proactive_composer::list_history(&state.db, &character_id, limit.unwrap_or(20)).await
+}
+
 #[tauri::command]
 pub async fn set_memory_enabled(
     enabled: bool,
@@ -291,6 +334,31 @@ pub async fn get_memory_enabled(state: State<'_, AIOrchestrator>) -> Result<bool
     Ok(state.is_memory_enabled())
 }
 
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct PrivacyModeEvent {
+    pub enabled: bool,
+}
+
+/// Toggle privacy mode for the current session. Emits `privacy-mode-changed`
+/// so every window can show (or hide) the indicator.
+#[tauri::command]
+pub async fn set_privacy_mode(
+    enabled: bool,
+    app: AppHandle,
+    state: State<'_, AIOrchestrator>,
+) -> Result<bool, KokoroError> {
+    if state.set_incognito(enabled).await {
+        tracing::info!(target: "context", "[Context] Privacy mode {}", if enabled { "on" } else { "off" });
+        let _ = app.emit("privacy-mode-changed", PrivacyModeEvent { enabled });
+    }
+    Ok(enabled)
+}
+
+#[tauri::command]
+pub async fn get_privacy_mode(state: State<'_, AIOrchestrator>) -> Result<bool, KokoroError> {
+    Ok(state.is_incognito())
+}
+
 #[tauri::command]
 pub async fn clear_history(state: State<'_, AIOrchestrator>) -> Result<(), KokoroError> {
     state.clear_history().await;
@@ -302,30 +370,35 @@ pub async fn delete_last_messages(
     count: usize,
     state: State<'_, AIOrchestrator>,
 ) -> Result<(), KokoroError> {
-    let mut history = state.history.lock().await;
-    let current_len = history.len();
-    let to_remove = count.min(current_len);
+    delete_last_messages_on(&state, ChatSurface::Desktop, count).await
+}
+
+/// Remove the newest `count` turns of `surface`'s conversation from history
+/// and the DB.
+pub(crate) async fn delete_last_messages_on(
+    state: &AIOrchestrator,
+    surface: ChatSurface,
+    count: usize,
+) -> Result<(), KokoroError> {
+    let (to_remove, conv_id) = state.drop_last_messages(surface, count).await;
 
     if to_remove == 0 {
         return Ok(());
     }
 
-    history.truncate(current_len - to_remove);
     tracing::info!(
         target: "ai",
-        "Deleted last {} message(s) from history (now {} messages)",
-        to_remove,
-        history.len()
+        "Deleted last {} message(s) from history",
+        to_remove
     );
 
     // 从数据库末尾删除，直到删够 to_remove 条「可见」消息为止。
     // 一条可见消息可能对应多行 DB（assistant_tool_calls + tool_result + assistant），
     // 需要跳过不可见行继续计数，否则重启后残留行会重新显示。
-    let conv_id = state.current_conversation_id.lock().await.clone();
     if let Some(conversation_id) = conv_id {
         // 从末尾倒序读取所有行（id + metadata）
         let rows: Vec<(i64, Option<String>)> = sqlx::query_as(
-            "SELECT id, metadata FROM conversation_messages WHERE conversation_id = ? ORDER BY id DESC"
+            "SELECT id, metadata FROM conversation_messages WHERE conversation_id = ? AND is_active = 1 ORDER BY id DESC"
         )
         .bind(&conversation_id)
         .fetch_all(&state.db)
@@ -401,7 +474,7 @@ pub async fn end_session(
     request: EndSessionRequest,
     state: State<'_, AIOrchestrator>,
 ) -> Result<(), KokoroError> {
-    if !state.is_memory_enabled() {
+    if !state.is_memory_write_enabled() {
         state.clear_history().await;
         return Ok(());
     }
@@ -1,7 +1,12 @@
+use crate::ai::branches::{self, MessageVariantGroup, RegenerationTicket};
+use crate::ai::checkpoints::{self, Checkpoint};
 use crate::ai::context::AIOrchestrator;
 use crate::error::KokoroError;
+use crate::llm::service::{LlmConversationOverride, LlmService};
+use crate::trash::Trash;
 use serde::{Deserialize, Serialize};
-use tauri::State;
+use sqlx::SqlitePool;
+use tauri::{AppHandle, State};
 
 #[derive(Serialize)]
 pub struct ConversationInfo {
@@ -12,10 +17,12 @@ pub struct ConversationInfo {
     pub pinned_state: String,
     pub created_at: String,
     pub updated_at: String,
+    pub model_override: Option<LlmConversationOverride>,
 }
 
 #[derive(Serialize)]
 pub struct ConversationMessage {
+    pub id: i64,
     pub role: String,
     pub content: String,
     pub metadata: Option<String>,
@@ -62,8 +69,20 @@ pub async fn list_conversations(
     request: ListConversationsRequest,
     state: State<'_, AIOrchestrator>,
 ) -> Result<Vec<ConversationInfo>, KokoroError> {
-    let rows = sqlx::query_as::<_, (String, String, String, String, String, String, String)>(
-        "SELECT id, character_id, title, topic, pinned_state, created_at, updated_at FROM conversations WHERE character_id = ? ORDER BY updated_at DESC",
+    let rows = sqlx::query_as::<
+        _,
+        (
+            String,
+            String,
+            String,
+            String,
+            String,
+            String,
+            String,
+            Option<String>,
+        ),
+    >(
+        "SELECT id, character_id, title, topic, pinned_state, created_at, updated_at, model_override FROM conversations WHERE character_id = ? ORDER BY updated_at DESC",
     )
     .bind(&request.character_id)
     .fetch_all(&state.db)
@@ -73,7 +92,7 @@ pub async fn list_conversations(
     Ok(rows
         .into_iter()
         .map(
-            |(id, character_id, title, topic, pinned_state, created_at, updated_at)| {
+            |(id, character_id, title, topic, pinned_state, created_at, updated_at, model)| {
                 ConversationInfo {
                     id,
                     character_id,
@@ -82,56 +101,78 @@ pub async fn list_conversations(
                     pinned_state,
                     created_at,
                     updated_at,
+                    model_override: model.and_then(|raw| serde_json::from_str(&raw).ok()),
                 }
             },
         )
         .collect())
 }
 
+#[derive(Deserialize)]
+pub struct SwitchMessageVariantRequest {
+    pub variant_group: String,
+    pub variant_index: i64,
+}
+
+/// Opens a conversation; one idle for days also gets a "previously…" recap
+/// in its next prompt.
 #[tauri::command]
 pub async fn load_conversation(
     request: LoadConversationRequest,
     state: State<'_, AIOrchestrator>,
+    llm: State<'_, LlmService>,
+) -> Result<LoadedConversation, KokoroError> {
+    let loaded = load_active_conversation(&request.id, &state).await?;
+    if let Err(e) = state
+        .catch_up_conversation(&request.id, llm.system_provider().await)
+        .await
+    {
+        tracing::warn!(target: "ai", "[Conversation] Catch-up for '{}' failed: {}", request.id, e);
+    }
+    Ok(loaded)
+}
+
+/// Load the active branch of a conversation into the orchestrator and make it current.
+async fn load_active_conversation(
+    id: &str,
+    state: &AIOrchestrator,
 ) -> Result<LoadedConversation, KokoroError> {
     let conversation_row = sqlx::query_as::<_, (String, String)>(
         "SELECT topic, pinned_state FROM conversations WHERE id = ?",
     )
-    .bind(&request.id)
+    .bind(id)
     .fetch_one(&state.db)
     .await
     .map_err(|e| KokoroError::Database(e.to_string()))?;
 
-    let rows = sqlx::query_as::<_, (String, String, Option<String>, String)>(
-        "SELECT role, content, metadata, created_at FROM conversation_messages WHERE conversation_id = ? ORDER BY id ASC",
+    let rows = sqlx::query_as::<_, (i64, String, String, Option<String>, String)>(
+        "SELECT id, role, content, metadata, created_at FROM conversation_messages WHERE conversation_id = ? AND is_active = 1 ORDER BY id ASC",
     )
-    .bind(&request.id)
+    .bind(id)
     .fetch_all(&state.db)
     .await
     .map_err(|e| KokoroError::Database(e.to_string()))?;
 
-    {
-        let mut history = state.history.lock().await;
-        history.clear();
-        for (role, content, metadata, _) in &rows {
-            history.push_back(crate::ai::context::Message {
+    let buffered = rows
+        .iter()
+        .map(
+            |(_, role, content, metadata, _)| crate::ai::context::Message {
                 role: role.clone(),
                 content: content.clone(),
                 metadata: metadata
                     .as_deref()
                     .and_then(|raw| serde_json::from_str::<serde_json::Value>(raw).ok()),
-            });
-        }
-    }
-
-    {
-        let mut conv_id = state.current_conversation_id.lock().await;
-        *conv_id = Some(request.id.clone());
-        crate::ai::context::AIOrchestrator::persist_conversation_id(Some(&request.id));
-    }
+            },
+        )
+        .collect();
+    state
+        .switch_conversation(id, Some(buffered))
+        .await
+        .map_err(|e| KokoroError::Database(e.to_string()))?;
 
     let messages = rows
         .into_iter()
-        .filter_map(|(role, content, metadata, created_at)| {
+        .filter_map(|(id, role, content, metadata, created_at)| {
             let metadata_value = metadata
                 .as_deref()
                 .and_then(|raw| serde_json::from_str::<serde_json::Value>(raw).ok());
@@ -146,6 +187,7 @@ pub async fn load_conversation(
                 return None;
             }
             Some(ConversationMessage {
+                id,
                 role,
                 content,
                 metadata,
@@ -161,29 +203,16 @@ pub async fn load_conversation(
     })
 }
 
+/// Moves the conversation to the trash; see `undo_last_delete`.
 #[tauri::command]
 pub async fn delete_conversation(
     request: DeleteConversationRequest,
     state: State<'_, AIOrchestrator>,
+    trash: State<'_, Trash>,
 ) -> Result<(), KokoroError> {
-    sqlx::query("DELETE FROM conversation_messages WHERE conversation_id = ?")
-        .bind(&request.id)
-        .execute(&state.db)
-        .await
-        .map_err(|e| KokoroError::Database(e.to_string()))?;
+    trash.delete_conversation(&request.id).await?;
 
-    sqlx::query("DELETE FROM conversations WHERE id = ?")
-        .bind(&request.id)
-        .execute(&state.db)
-        .await
-        .map_err(|e| KokoroError::Database(e.to_string()))?;
-
-    {
-        let mut conv_id = state.current_conversation_id.lock().await;
-        if conv_id.as_deref() == Some(&request.id) {
-            *conv_id = None;
-        }
-    }
+    state.close_conversations(&[request.id]).await;
 
     Ok(())
 }
@@ -256,3 +285,270 @@ pub async fn update_conversation_state(
 
     Ok(())
 }
+
+#[derive(Deserialize)]
+pub struct SetConversationModelRequest {
+    /// Defaults to the current conversation.
+    pub conversation_id: Option<String>,
+    /// All fields empty clears the pin.
+    #[serde(flatten)]
+    pub model: LlmConversationOverride,
+}
+
+/// Model pinned to a conversation, if any. Unreadable values are ignored.
+pub(crate) async fn load_model_override(
+    db: &SqlitePool,
+    conversation_id: &str,
+) -> Result<Option<LlmConversationOverride>, KokoroError> {
+    let raw = sqlx::query_scalar::<_, Option<String>>(
+        "SELECT model_override FROM conversations WHERE id = ?",
+    )
+    .bind(conversation_id)
+    .fetch_optional(db)
+    .await
+    .map_err(|e| KokoroError::Database(e.to_string()))?
+    .flatten();
+
+    Ok(raw.and_then(|raw| match serde_json::from_str(&raw) {
+        Ok(model) => Some(model),
+        Err(e) => {
+            tracing::warn!(
+                target: "ai",
+                "Ignoring unreadable model override on conversation '{}': {}",
+                conversation_id,
+                e
+            );
+            None
+        }
+    }))
+}
+
+async fn resolve_conversation_id(
+    requested: Option<String>,
+    state: &AIOrchestrator,
+) -> Result<String, KokoroError> {
+    match requested {
+        Some(id) => Ok(id),
+        None => state
+            .current_conversation_id
+            .lock()
+            .await
+            .clone()
+            .ok_or_else(|| KokoroError::Validation("No active conversation".to_string())),
+    }
+}
+
+/// Pin a provider/model/temperature to one conversation, overriding the
+/// character binding and global config for its chat turns.
+#[tauri::command]
+pub async fn set_conversation_model(
+    request: SetConversationModelRequest,
+    state: State<'_, AIOrchestrator>,
+    llm: State<'_, LlmService>,
+) -> Result<(), KokoroError> {
+    let conversation_id = resolve_conversation_id(request.conversation_id, &state).await?;
+    let mut model = request.model;
+    model.provider_id = model.provider_id.filter(|id| !id.trim().is_empty());
+    model.model = model.model.filter(|m| !m.trim().is_empty());
+
+    if let Some(provider_id) = model.provider_id.as_deref() {
+        let config = llm.config().await;
+        if !config
+            .providers
+            .iter()
+            .any(|p| p.id == provider_id && p.enabled)
+        {
+            return Err(KokoroError::Validation(format!(
+                "LLM provider '{}' is not configured or disabled",
+                provider_id
+            )));
+        }
+    }
+    if let Some(temperature) = model.temperature {
+        if !(0.0..=2.0).contains(&temperature) {
+            return Err(KokoroError::Validation(format!(
+                "temperature must be between 0 and 2, got {}",
+                temperature
+            )));
+        }
+    }
+
+    let stored = if model.is_empty() {
+        None
+    } else {
+        Some(serde_json::to_string(&model).map_err(|e| KokoroError::Internal(e.to_string()))?)
+    };
+    let result = sqlx::query("UPDATE conversations SET model_override = ? WHERE id = ?")
+        .bind(&stored)
+        .bind(&conversation_id)
+        .execute(&state.db)
+        .await
+        .map_err(|e| KokoroError::Database(e.to_string()))?;
+    if result.rows_affected() == 0 {
+        return Err(KokoroError::NotFound(format!(
+            "Conversation '{}' not found",
+            conversation_id
+        )));
+    }
+
+    tracing::info!(
+        target: "ai",
+        "[Conversation] Model for '{}' set to {:?}",
+        conversation_id,
+        stored
+    );
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_conversation_model(
+    conversation_id: Option<String>,
+    state: State<'_, AIOrchestrator>,
+) -> Result<Option<LlmConversationOverride>, KokoroError> {
+    let conversation_id = match conversation_id {
+        Some(id) => id,
+        None => match state.current_conversation_id.lock().await.clone() {
+            Some(id) => id,
+            None => return Ok(None),
+        },
+    };
+    load_model_override(&state.db, &conversation_id).await
+}
+
+/// Set the last reply aside as a variant and prepare the current conversation
+/// for a new one. The frontend then re-sends the returned user message through
+/// `stream_chat` with `regenerate: true`.
+#[tauri::command]
+pub async fn regenerate_last_response(
+    state: State<'_, AIOrchestrator>,
+) -> Result<RegenerationTicket, KokoroError> {
+    let conversation_id = state
+        .current_conversation_id
+        .lock()
+        .await
+        .clone()
+        .ok_or_else(|| KokoroError::Validation("No active conversation".to_string()))?;
+
+    let ticket = branches::prepare_regeneration(&state.db, &conversation_id).await?;
+    load_active_conversation(&conversation_id, &state).await?;
+    tracing::info!(
+        target: "ai",
+        "[Branches] Regenerating reply in '{}' as variant {} of group {}",
+        conversation_id,
+        ticket.variant_index,
+        ticket.variant_group
+    );
+    Ok(ticket)
+}
+
+#[tauri::command]
+pub async fn list_message_variants(
+    request: LoadConversationRequest,
+    state: State<'_, AIOrchestrator>,
+) -> Result<Vec<MessageVariantGroup>, KokoroError> {
+    branches::list_variants(&state.db, &request.id).await
+}
+
+/// Select another variant and return the conversation as seen on the new path.
+#[tauri::command]
+pub async fn switch_message_variant(
+    request: SwitchMessageVariantRequest,
+    state: State<'_, AIOrchestrator>,
+) -> Result<LoadedConversation, KokoroError> {
+    let conversation_id =
+        branches::switch_variant(&state.db, &request.variant_group, request.variant_index).await?;
+    load_active_conversation(&conversation_id, &state).await
+}
+
+#[derive(Deserialize)]
+pub struct CreateCheckpointRequest {
+    /// Defaults to the current conversation.
+    pub conversation_id: Option<String>,
+    /// Defaults to the start of the last message.
+    pub label: Option<String>,
+}
+
+/// Save the current point of a conversation (history, emotion, topic and
+/// pinned state) to rewind to later.
+#[tauri::command]
+pub async fn create_checkpoint(
+    request: CreateCheckpointRequest,
+    state: State<'_, AIOrchestrator>,
+) -> Result<Checkpoint, KokoroError> {
+    let conversation_id = resolve_conversation_id(request.conversation_id, &state).await?;
+    let emotion = state.emotion.lock().await.state();
+    let checkpoint = checkpoints::create_checkpoint(
+        &state.db,
+        &conversation_id,
+        request.label.as_deref(),
+        &emotion,
+    )
+    .await?;
+    tracing::info!(
+        target: "ai",
+        "[Checkpoints] Saved '{}' in '{}' at message {}",
+        checkpoint.label,
+        conversation_id,
+        checkpoint.message_id
+    );
+    Ok(checkpoint)
+}
+
+#[tauri::command]
+pub async fn list_checkpoints(
+    conversation_id: Option<String>,
+    state: State<'_, AIOrchestrator>,
+) -> Result<Vec<Checkpoint>, KokoroError> {
+    let conversation_id = match conversation_id {
+        Some(id) => id,
+        None => match state.current_conversation_id.lock().await.clone() {
+            Some(id) => id,
+            None => return Ok(Vec::new()),
+        },
+    };
+    checkpoints::list_checkpoints(&state.db, &conversation_id).await
+}
+
+/// Rewind to a checkpoint. The turns after it are kept as a message variant,
+/// so `switch_message_variant` can bring them back.
+#[tauri::command]
+pub async fn rewind_to_checkpoint(
+    id: String,
+    app: AppHandle,
+    state: State<'_, AIOrchestrator>,
+) -> Result<LoadedConversation, KokoroError> {
+    let checkpoint = checkpoints::rewind_to_checkpoint(&state.db, &id).await?;
+    let loaded = load_active_conversation(&checkpoint.conversation_id, &state).await?;
+
+    // The emotion engine follows the active character only.
+    let active_character = state.get_character_id().await;
+    if let Some(saved) = checkpoint
+        .emotion
+        .as_ref()
+        .filter(|_| checkpoint.character_id == active_character)
+    {
+        let emotion = {
+            let mut engine = state.emotion.lock().await;
+            engine.restore(saved.to_state());
+            engine.state()
+        };
+        crate::ai::emotion::broadcast(&app, &state, emotion, "checkpoint").await;
+    }
+
+    tracing::info!(
+        target: "ai",
+        "[Checkpoints] Rewound '{}' to '{}' (message {})",
+        checkpoint.conversation_id,
+        checkpoint.label,
+        checkpoint.message_id
+    );
+    Ok(loaded)
+}
+
+#[tauri::command]
+pub async fn delete_checkpoint(
+    id: String,
+    state: State<'_, AIOrchestrator>,
+) -> Result<(), KokoroError> {
+    checkpoints::delete_checkpoint(&state.db, &id).await
+}
@@ -1,6 +1,7 @@
 use crate::ai::context::AIOrchestrator;
+use crate::ai::purge::{PurgeReport, PurgeScope};
 use crate::error::KokoroError;
-use tauri::State;
+use tauri::{AppHandle, Emitter, State};
 
 #[derive(serde::Serialize)]
 pub struct DbTestResult {
@@ -47,3 +48,42 @@ pub async fn test_vector_store(
         record_count: results.len(),
     })
 }
+
+/// Checkpoint the WAL and VACUUM the main database, reporting the space reclaimed.
+#[tauri::command]
+pub async fn compact_database(
+    state: State<'_, AIOrchestrator>,
+) -> Result<crate::db::CompactReport, KokoroError> {
+    let report = crate::db::compact(&state.db).await?;
+    tracing::info!(
+        target: "db",
+        "[DB] Manual compaction reclaimed {} bytes ({} -> {}) in {} ms",
+        report.reclaimed_bytes,
+        report.bytes_before,
+        report.bytes_after,
+        report.duration_ms
+    );
+    Ok(report)
+}
+
+/// Delete memories, messages, vision logs, summaries and emotion history in
+/// `scope`. With `dry_run` nothing is removed and the report previews the
+/// rows that would be.
+#[tauri::command]
+pub async fn purge_data(
+    app: AppHandle,
+    scope: PurgeScope,
+    dry_run: bool,
+    state: State<'_, AIOrchestrator>,
+) -> Result<PurgeReport, KokoroError> {
+    let report = crate::ai::purge::purge(&state.db, &state.memory_manager, &scope, dry_run).await?;
+    if dry_run {
+        return Ok(report);
+    }
+    // The live sessions may still hold purged turns; start over if so.
+    state
+        .close_conversations(&report.touched_conversations)
+        .await;
+    let _ = app.emit("data-purged", &report);
+    Ok(report)
+}
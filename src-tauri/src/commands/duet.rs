@@ -0,0 +1,36 @@
+//! Duet IPC commands — let two characters talk to each other.
+
+use crate::ai::duet::{DuetRequest, DuetService, DuetStatus};
+use crate::error::KokoroError;
+use tauri::{AppHandle, State};
+
+#[tauri::command]
+pub async fn start_duet(
+    app: AppHandle,
+    state: State<'_, DuetService>,
+    request: DuetRequest,
+) -> Result<DuetStatus, KokoroError> {
+    state.start(&app, request).await
+}
+
+#[tauri::command]
+pub async fn interject_duet(
+    app: AppHandle,
+    state: State<'_, DuetService>,
+    text: String,
+) -> Result<(), KokoroError> {
+    state.interject(&app, &text).await
+}
+
+#[tauri::command]
+pub async fn stop_duet(
+    app: AppHandle,
+    state: State<'_, DuetService>,
+) -> Result<DuetStatus, KokoroError> {
+    Ok(state.stop(&app).await)
+}
+
+#[tauri::command]
+pub async fn get_duet_status(state: State<'_, DuetService>) -> Result<DuetStatus, KokoroError> {
+    Ok(state.status().await)
+}
@@ -0,0 +1,33 @@
+//! Emotion state IPC commands.
+
+use crate::ai::context::AIOrchestrator;
+use crate::ai::emotion::{self, EmotionState};
+use crate::error::KokoroError;
+use tauri::State;
+
+/// Current state, for surfaces that mount after the last `emotion:update` event.
+#[tauri::command]
+pub async fn get_emotion_state(
+    state: State<'_, AIOrchestrator>,
+) -> Result<EmotionState, KokoroError> {
+    Ok(state.emotion.lock().await.state())
+}
+
+/// Push an emotion from outside the chat loop (mod UIs, overlays,
+/// integrations). `intensity` defaults to 0.8.
+#[tauri::command]
+pub async fn set_emotion(
+    app: tauri::AppHandle,
+    emotion: String,
+    intensity: Option<f32>,
+) -> Result<(), KokoroError> {
+    let resolved = emotion::emotion_from_label(&emotion).ok_or_else(|| {
+        KokoroError::Validation(format!(
+            "unknown emotion '{}', expected one of {}",
+            emotion,
+            emotion::EMOTIONS.join(", ")
+        ))
+    })?;
+    emotion::observe(&app, resolved, intensity.unwrap_or(0.8), "external").await;
+    Ok(())
+}
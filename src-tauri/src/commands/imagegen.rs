@@ -1,24 +1,172 @@
+use crate::ai::context::AIOrchestrator;
 use crate::commands::system::WindowSizeState;
 use crate::error::KokoroError;
 use crate::imagegen::config::{load_config, save_config, ImageGenSystemConfig};
+use crate::imagegen::gallery::{self, GalleryEntry};
 use crate::imagegen::{ImageGenParams, ImageGenResult, ImageGenService};
-use tauri::{command, State};
+use serde::{Deserialize, Serialize};
+use tauri::{command, Emitter, Manager, State};
+
+/// Arguments of `generate_image`, kept as the payload when the request is
+/// deferred while offline.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ImageRequest {
+    prompt: String,
+    provider_id: Option<String>,
+    params: Option<ImageGenParams>,
+    selfie: Option<bool>,
+    in_character: Option<bool>,
+}
+
+impl ImageRequest {
+    async fn run(
+        self,
+        state: &ImageGenService,
+        window_size: (u32, u32),
+    ) -> Result<ImageGenResult, KokoroError> {
+        let result = if self.selfie.unwrap_or(false) {
+            state
+                .generate_selfie(
+                    self.prompt,
+                    self.provider_id,
+                    self.params,
+                    Some(window_size),
+                )
+                .await
+        } else if self.in_character.unwrap_or(false) {
+            state
+                .generate_in_character(
+                    self.prompt,
+                    self.provider_id,
+                    self.params,
+                    Some(window_size),
+                )
+                .await
+        } else {
+            state
+                .generate(
+                    self.prompt,
+                    self.provider_id,
+                    self.params,
+                    Some(window_size),
+                )
+                .await
+        };
+        result.map_err(KokoroError::from)
+    }
+}
 
 #[command]
 pub async fn generate_image(
+    app: tauri::AppHandle,
     state: State<'_, ImageGenService>,
     window_size_state: State<'_, WindowSizeState>,
     prompt: String,
     provider_id: Option<String>,
     params: Option<ImageGenParams>,
+    selfie: Option<bool>,
+    in_character: Option<bool>,
+) -> Result<ImageGenResult, KokoroError> {
+    let request = ImageRequest {
+        prompt,
+        provider_id,
+        params,
+        selfie,
+        in_character,
+    };
+    if crate::offline::should_defer_imagegen(&app, request.provider_id.as_deref()).await {
+        if let Some(offline) = app.try_state::<crate::offline::OfflineModeService>() {
+            let task = offline
+                .defer("imagegen", serde_json::to_value(&request)?)
+                .await;
+            return Err(KokoroError::ExternalService(format!(
+                "Offline: image generation queued as {} until connectivity returns",
+                task.id
+            )));
+        }
+    }
+    let window_size = window_size_state.get().await;
+    request.run(&state, window_size).await
+}
+
+/// Run an image request deferred while offline. The result reaches the UI
+/// through `imagegen:done` / `imagegen:error`, as it would for a background
+/// generation.
+pub(crate) async fn replay_deferred_image(
+    app: &tauri::AppHandle,
+    payload: serde_json::Value,
+) -> Result<ImageGenResult, KokoroError> {
+    let request: ImageRequest = serde_json::from_value(payload)?;
+    let state = app
+        .try_state::<ImageGenService>()
+        .ok_or_else(|| KokoroError::Internal("image generation is not initialized".to_string()))?;
+    let window_size = match app.try_state::<WindowSizeState>() {
+        Some(window_size) => window_size.get().await,
+        None => WindowSizeState::new().get().await,
+    };
+    match request.run(&state, window_size).await {
+        Ok(result) => {
+            let _ = app.emit("imagegen:done", &result);
+            Ok(result)
+        }
+        Err(e) => {
+            let _ = app.emit("imagegen:error", e.to_string());
+            Err(e)
+        }
+    }
+}
+
+/// Rework a previous image (path or `"last"`), optionally inpainting a masked area.
+#[command]
+pub async fn generate_image_variation(
+    state: State<'_, ImageGenService>,
+    window_size_state: State<'_, WindowSizeState>,
+    source: String,
+    prompt: Option<String>,
+    strength: Option<f32>,
+    mask: Option<String>,
+    provider_id: Option<String>,
 ) -> Result<ImageGenResult, KokoroError> {
     let window_size = window_size_state.get().await;
     state
-        .generate(prompt, provider_id, params, Some(window_size))
+        .generate_variation(
+            source,
+            prompt,
+            strength,
+            mask,
+            provider_id,
+            Some(window_size),
+        )
         .await
         .map_err(KokoroError::from)
 }
 
+/// Generation history, newest first; `conversation_id` narrows it to one chat.
+#[command]
+pub async fn list_generated_images(
+    orchestrator: State<'_, AIOrchestrator>,
+    conversation_id: Option<String>,
+    limit: Option<i64>,
+    offset: Option<i64>,
+) -> Result<Vec<GalleryEntry>, KokoroError> {
+    gallery::list(
+        &orchestrator.db,
+        conversation_id.as_deref(),
+        limit.unwrap_or(50).clamp(1, 500),
+        offset.unwrap_or(0).max(0),
+    )
+    .await
+}
+
+#[command]
+pub async fn delete_generated_image(
+    state: State<'_, ImageGenService>,
+    orchestrator: State<'_, AIOrchestrator>,
+    id: i64,
+) -> Result<(), KokoroError> {
+    state.delete_gallery_entry(&orchestrator.db, id).await
+}
+
 #[command]
 pub async fn get_imagegen_config() -> Result<ImageGenSystemConfig, KokoroError> {
     let app_data = dirs_next::data_dir()
@@ -0,0 +1,50 @@
+//! Input mapping IPC commands — external trigger config, status and testing.
+
+use crate::error::KokoroError;
+use crate::input_mapping::{InputMappingConfig, InputMappingService, InputMappingStatus};
+use tauri::{AppHandle, State};
+
+#[tauri::command]
+pub async fn get_input_mapping_config(
+    state: State<'_, InputMappingService>,
+) -> Result<InputMappingConfig, KokoroError> {
+    Ok(state.get_config().await)
+}
+
+/// Save and restart the listeners. Listener problems (busy shortcut, missing
+/// MIDI device) don't fail the save; they come back as status warnings.
+#[tauri::command]
+pub async fn save_input_mapping_config(
+    app: AppHandle,
+    state: State<'_, InputMappingService>,
+    config: InputMappingConfig,
+) -> Result<InputMappingStatus, KokoroError> {
+    crate::input_mapping::validate(&config)?;
+    crate::input_mapping::save_config(&crate::input_mapping::config_path(), &config)?;
+    Ok(state.update_config(&app, config).await)
+}
+
+#[tauri::command]
+pub async fn get_input_mapping_status(
+    state: State<'_, InputMappingService>,
+) -> Result<InputMappingStatus, KokoroError> {
+    Ok(state.status())
+}
+
+#[tauri::command]
+pub async fn list_midi_inputs() -> Result<Vec<String>, KokoroError> {
+    tokio::task::spawn_blocking(crate::input_mapping::list_midi_inputs)
+        .await
+        .map_err(|e| KokoroError::Internal(e.to_string()))?
+        .map_err(KokoroError::ExternalService)
+}
+
+/// Run a mapping's action without its trigger.
+#[tauri::command]
+pub async fn test_input_mapping(
+    app: AppHandle,
+    state: State<'_, InputMappingService>,
+    id: String,
+) -> Result<(), KokoroError> {
+    state.run_mapping(&app, &id).await
+}
@@ -0,0 +1,33 @@
+//! Input queue IPC commands.
+
+use crate::ai::context::AIOrchestrator;
+use crate::ai::input_queue::{self, ChatQueueEvent, InputQueueConfig};
+use crate::error::KokoroError;
+use tauri::{State, Window};
+
+#[tauri::command]
+pub async fn get_input_queue_config(
+    state: State<'_, AIOrchestrator>,
+) -> Result<InputQueueConfig, KokoroError> {
+    Ok(state.input_queue.config())
+}
+
+#[tauri::command]
+pub async fn save_input_queue_config(
+    config: InputQueueConfig,
+    state: State<'_, AIOrchestrator>,
+) -> Result<(), KokoroError> {
+    let sanitized = config.sanitized();
+    input_queue::save_config(&input_queue::config_path(), &sanitized)?;
+    state.input_queue.set_config(sanitized);
+    Ok(())
+}
+
+/// Queue of the calling window, for restoring the indicator after a reload.
+#[tauri::command]
+pub async fn get_input_queue_state(
+    window: Window,
+    state: State<'_, AIOrchestrator>,
+) -> Result<ChatQueueEvent, KokoroError> {
+    Ok(state.input_queue.snapshot(window.label()))
+}
@@ -0,0 +1,25 @@
+//! First-token latency budget IPC commands.
+
+use crate::ai::latency::{self, LatencyBudgetConfig};
+use crate::error::KokoroError;
+use std::sync::Arc;
+use tauri::State;
+use tokio::sync::RwLock;
+
+#[tauri::command]
+pub async fn get_latency_budget_config(
+    state: State<'_, Arc<RwLock<LatencyBudgetConfig>>>,
+) -> Result<LatencyBudgetConfig, KokoroError> {
+    Ok(state.read().await.clone())
+}
+
+#[tauri::command]
+pub async fn save_latency_budget_config(
+    config: LatencyBudgetConfig,
+    state: State<'_, Arc<RwLock<LatencyBudgetConfig>>>,
+) -> Result<(), KokoroError> {
+    let sanitized = config.sanitized();
+    latency::save_config(&latency::config_path(), &sanitized)?;
+    *state.write().await = sanitized;
+    Ok(())
+}
@@ -1,3 +1,5 @@
+use super::live2d_dedup::{self, DuplicateAction, Live2dDuplicate};
+use super::live2d_import;
 use crate::error::KokoroError;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -62,10 +64,16 @@ pub struct Live2dModelProfile {
 ///
 /// We extract the full zip into `{app_data_dir}/live2d_models/` and then
 /// locate the `.model3.json` inside.
+///
+/// If the same archive was imported before, `on_duplicate` decides whether
+/// to reuse that model (`skip`) or import a copy under a new folder name
+/// (`rename`); without it the import proceeds as usual. Textures identical to
+/// ones already installed are hard-linked instead of stored twice.
 #[tauri::command]
 pub async fn import_live2d_zip(
     app: tauri::AppHandle,
     zip_path: String,
+    on_duplicate: Option<DuplicateAction>,
 ) -> Result<String, KokoroError> {
     let archive_path = std::path::Path::new(&zip_path);
     if !archive_path.exists() {
@@ -79,6 +87,29 @@ pub async fn import_live2d_zip(
     let models_dir = app_data.join("live2d_models");
     fs::create_dir_all(&models_dir)
         .map_err(|e| KokoroError::Internal(format!("Failed to create models dir: {}", e)))?;
+
+    let archive_hash = live2d_dedup::hash_file(archive_path)
+        .map_err(|e| KokoroError::Internal(format!("Failed to hash zip file: {}", e)))?;
+    let duplicate = live2d_dedup::find_duplicate(&models_dir, &archive_hash);
+    if let (Some(existing), Some(DuplicateAction::Skip)) = (&duplicate, on_duplicate) {
+        ensure_profile_for_model(&models_dir, &existing.path).map_err(KokoroError::Internal)?;
+        return Ok(existing.path.clone());
+    }
+
+    // Validate before writing anything, so a broken archive fails fast and
+    // names every missing file.
+    let emit_progress = |progress: live2d_import::Live2dImportProgress| {
+        let _ = app.emit(live2d_import::PROGRESS_EVENT, &progress);
+    };
+    emit_progress(live2d_import::Live2dImportProgress::stage(
+        "validating",
+        0,
+        0,
+    ));
+    let file = fs::File::open(archive_path).map_err(KokoroError::from)?;
+    let mut archive = zip::ZipArchive::new(file).map_err(KokoroError::from)?;
+    let plan = live2d_import::validate_archive(&mut archive)?;
+
     let import_tmp_dir = app_data.join(format!(
         "live2d_import_tmp_{}",
         chrono::Utc::now().timestamp_millis()
@@ -97,32 +128,25 @@ pub async fn import_live2d_zip(
     fs::create_dir_all(&import_tmp_dir)
         .map_err(|e| KokoroError::Internal(format!("Failed to create temp import dir: {}", e)))?;
 
-    let file = fs::File::open(archive_path).map_err(KokoroError::from)?;
-    let mut archive = zip::ZipArchive::new(file).map_err(KokoroError::from)?;
-
-    for i in 0..archive.len() {
-        let mut entry = archive.by_index(i).map_err(KokoroError::from)?;
-        let outpath = match entry.enclosed_name() {
-            Some(path) => import_tmp_dir.join(path),
-            None => continue,
-        };
-        if entry.name().ends_with('/') {
-            fs::create_dir_all(&outpath).map_err(KokoroError::from)?;
-        } else {
-            if let Some(parent) = outpath.parent() {
-                if !parent.exists() {
-                    fs::create_dir_all(parent).map_err(KokoroError::from)?;
-                }
-            }
-            let mut outfile = fs::File::create(&outpath).map_err(KokoroError::from)?;
-            io::copy(&mut entry, &mut outfile).map_err(KokoroError::from)?;
-        }
-    }
-
     let result = (|| -> Result<String, KokoroError> {
-        let model_json = find_model3_json(&import_tmp_dir).ok_or_else(|| {
-            KokoroError::NotFound("No .model3.json file found in the zip archive".to_string())
-        })?;
+        live2d_import::extract_archive(
+            &mut archive,
+            &import_tmp_dir,
+            plan.total_bytes,
+            emit_progress,
+        )?;
+        emit_progress(live2d_import::Live2dImportProgress::stage(
+            "installing",
+            plan.total_bytes,
+            plan.total_bytes,
+        ));
+
+        let model_json = Some(import_tmp_dir.join(&plan.model_entry))
+            .filter(|path| path.is_file())
+            .or_else(|| find_model3_json(&import_tmp_dir))
+            .ok_or_else(|| {
+                KokoroError::NotFound("No .model3.json file found in the zip archive".to_string())
+            })?;
         let model_root = find_model_root(&model_json).ok_or_else(|| {
             KokoroError::NotFound(
                 "Cannot find model root directory (no .moc3 file found near .model3.json)"
@@ -140,42 +164,49 @@ pub async fn import_live2d_zip(
                 .ok_or_else(|| KokoroError::Validation("Invalid model folder name".to_string()))?
                 .to_string()
         };
+        let folder_name = if on_duplicate == Some(DuplicateAction::Rename) {
+            live2d_dedup::unique_folder_name(&models_dir, &folder_name)
+        } else {
+            folder_name
+        };
         let target_dir = models_dir.join(&folder_name);
 
-        if target_dir.exists() {
-            if target_dir.is_dir() {
-                fs::remove_dir_all(&target_dir).map_err(|e| {
-                    KokoroError::Internal(format!("Failed to remove existing model folder: {}", e))
+        // A model already at `target_dir` is kept until the new one is fully
+        // installed, and put back if any step below fails.
+        live2d_import::install_model_dir(
+            &target_dir,
+            |target| fs::rename(&model_root, target),
+            || {
+                let copied_model_json = find_model3_json(&target_dir).ok_or_else(|| {
+                    KokoroError::NotFound(
+                        "Copied model folder does not contain a .model3.json file".to_string(),
+                    )
                 })?;
-            } else {
-                fs::remove_file(&target_dir).map_err(|e| {
-                    KokoroError::Internal(format!("Failed to remove existing model file: {}", e))
+                let relative = copied_model_json.strip_prefix(&models_dir).map_err(|e| {
+                    KokoroError::Internal(format!("Failed to compute relative path: {}", e))
                 })?;
-            }
-        }
-
-        fs::rename(&model_root, &target_dir).map_err(|e| {
-            KokoroError::Internal(format!(
-                "Failed to move extracted model folder into place: {}",
-                e
-            ))
-        })?;
-
-        let copied_model_json = find_model3_json(&target_dir).ok_or_else(|| {
-            KokoroError::NotFound(
-                "Copied model folder does not contain a .model3.json file".to_string(),
-            )
-        })?;
-        let relative = copied_model_json.strip_prefix(&models_dir).map_err(|e| {
-            KokoroError::Internal(format!("Failed to compute relative path: {}", e))
-        })?;
-        let relative_str = relative.to_string_lossy().replace('\\', "/");
+                let relative_str = relative.to_string_lossy().replace('\\', "/");
 
-        ensure_profile_for_model(&models_dir, &relative_str).map_err(KokoroError::Internal)?;
-        Ok(relative_str)
+                ensure_profile_for_model(&models_dir, &relative_str)
+                    .map_err(KokoroError::Internal)?;
+                live2d_dedup::record_archive(&models_dir, &archive_hash, &folder_name)
+                    .and_then(|_| live2d_dedup::share_textures(&models_dir, &folder_name))
+                    .map_err(|e| {
+                        KokoroError::Internal(format!("Failed to update import index: {}", e))
+                    })?;
+                Ok(relative_str)
+            },
+        )
     })();
 
     let _ = fs::remove_dir_all(&import_tmp_dir);
+    if result.is_ok() {
+        emit_progress(live2d_import::Live2dImportProgress::stage(
+            "complete",
+            plan.total_bytes,
+            plan.total_bytes,
+        ));
+    }
     result
 }
 
@@ -231,40 +262,13 @@ pub async fn list_live2d_models(
     Ok(models)
 }
 
-/// Delete an imported Live2D model by its folder name.
+/// Move an imported Live2D model folder to the trash; see `undo_last_delete`.
 #[tauri::command]
 pub async fn delete_live2d_model(
-    app: tauri::AppHandle,
+    trash: tauri::State<'_, crate::trash::Trash>,
     model_name: String,
 ) -> Result<(), KokoroError> {
-    let app_data = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| KokoroError::Internal(format!("Cannot resolve app data dir: {}", e)))?;
-    let model_path = app_data.join("live2d_models").join(&model_name);
-
-    // Security: ensure the path is inside live2d_models
-    if !model_path.starts_with(app_data.join("live2d_models")) {
-        return Err(KokoroError::Validation("Invalid model name".to_string()));
-    }
-
-    if !model_path.exists() {
-        return Err(KokoroError::NotFound(format!(
-            "Model '{}' not found",
-            model_name
-        )));
-    }
-
-    if model_path.is_dir() {
-        fs::remove_dir_all(&model_path).map_err(|e| {
-            KokoroError::Internal(format!("Failed to delete model '{}': {}", model_name, e))
-        })?;
-    } else {
-        fs::remove_file(&model_path).map_err(|e| {
-            KokoroError::Internal(format!("Failed to delete model '{}': {}", model_name, e))
-        })?;
-    }
-
+    trash.delete_live2d_model(&model_name).await?;
     Ok(())
 }
 
@@ -299,6 +303,9 @@ pub async fn rename_live2d_model(
 
     fs::rename(&old_path, &new_path)
         .map_err(|e| format!("Failed to rename model folder: {}", e))?;
+    if let Err(e) = live2d_dedup::rename_folder(&models_dir, old_root, &new_root) {
+        tracing::warn!(target: "live2d", "[Live2D] Failed to update import index after rename: {}", e);
+    }
 
     let rest = normalized
         .strip_prefix(old_root)
@@ -361,6 +368,8 @@ pub async fn import_live2d_folder(
         )
     })?;
 
+    live2d_import::validate_model_dir(json_path)?;
+
     let folder_name =
         model_folder_name_from_model_json(json_path).map_err(KokoroError::Validation)?;
 
@@ -372,37 +381,50 @@ pub async fn import_live2d_folder(
     let models_dir = app_data.join("live2d_models");
     let target_dir = models_dir.join(&folder_name);
 
-    // If target already exists, remove it first (re-import scenario)
-    if target_dir.exists() {
-        if target_dir.is_dir() {
-            fs::remove_dir_all(&target_dir).map_err(|e| {
-                KokoroError::Internal(format!("Failed to remove existing model folder: {}", e))
-            })?;
-        } else {
-            fs::remove_file(&target_dir).map_err(|e| {
-                KokoroError::Internal(format!("Failed to remove existing model file: {}", e))
+    // Copy the entire model folder. A model already at `target_dir` (re-import)
+    // is only replaced once the copy and profile succeed.
+    live2d_import::install_model_dir(
+        &target_dir,
+        |target| copy_dir_recursive(&model_root, target),
+        || {
+            // Validate the copy by finding .model3.json in the target
+            let model_json = find_model3_json(&target_dir).ok_or_else(|| {
+                KokoroError::NotFound(
+                    "Copied folder does not contain a .model3.json file".to_string(),
+                )
             })?;
-        }
-    }
 
-    // Copy the entire model folder
-    copy_dir_recursive(&model_root, &target_dir)
-        .map_err(|e| KokoroError::Internal(format!("Failed to copy model folder: {}", e)))?;
-
-    // Validate the copy by finding .model3.json in the target
-    let model_json = find_model3_json(&target_dir).ok_or_else(|| {
-        KokoroError::NotFound("Copied folder does not contain a .model3.json file".to_string())
-    })?;
+            let relative = model_json.strip_prefix(&models_dir).map_err(|e| {
+                KokoroError::Internal(format!("Failed to compute relative path: {}", e))
+            })?;
 
-    let relative = model_json
-        .strip_prefix(&models_dir)
-        .map_err(|e| KokoroError::Internal(format!("Failed to compute relative path: {}", e)))?;
+            let relative_str = relative.to_string_lossy().replace('\\', "/");
 
-    let relative_str = relative.to_string_lossy().replace('\\', "/");
+            ensure_profile_for_model(&models_dir, &relative_str).map_err(KokoroError::Internal)?;
+            live2d_dedup::share_textures(&models_dir, &folder_name).map_err(|e| {
+                KokoroError::Internal(format!("Failed to update import index: {}", e))
+            })?;
 
-    ensure_profile_for_model(&models_dir, &relative_str).map_err(KokoroError::Internal)?;
+            Ok(relative_str)
+        },
+    )
+}
 
-    Ok(relative_str)
+/// The installed model imported from the same zip as `zip_path`, if any.
+/// Lets the UI ask whether to reuse it before calling `import_live2d_zip`.
+#[tauri::command]
+pub async fn check_live2d_zip_duplicate(
+    app: tauri::AppHandle,
+    zip_path: String,
+) -> Result<Option<Live2dDuplicate>, KokoroError> {
+    let archive_path = std::path::Path::new(&zip_path);
+    if !archive_path.exists() {
+        return Err(KokoroError::NotFound("Zip file does not exist".to_string()));
+    }
+    let models_dir = get_models_dir(&app).map_err(KokoroError::Internal)?;
+    let archive_hash = live2d_dedup::hash_file(archive_path)
+        .map_err(|e| KokoroError::Internal(format!("Failed to hash zip file: {}", e)))?;
+    Ok(live2d_dedup::find_duplicate(&models_dir, &archive_hash))
 }
 
 #[tauri::command]
@@ -819,7 +841,7 @@ fn save_model_profile(
     Ok(())
 }
 
-fn normalize_relative_model_path(model_path: &str) -> Result<String, String> {
+pub(crate) fn normalize_relative_model_path(model_path: &str) -> Result<String, String> {
     let path = std::path::Path::new(model_path);
     if path.is_absolute() {
         return Err("Absolute model paths are not allowed".to_string());
@@ -971,7 +993,7 @@ fn add_dir_to_zip(
     Ok(())
 }
 
-fn find_model3_json(dir: &std::path::Path) -> Option<PathBuf> {
+pub(crate) fn find_model3_json(dir: &std::path::Path) -> Option<PathBuf> {
     let entries = fs::read_dir(dir).ok()?;
     let mut dirs = Vec::new();
 
@@ -0,0 +1,240 @@
+//! Duplicate detection and texture sharing for imported Live2D models.
+//!
+//! `live2d_models/.import_index.json` records the SHA-256 of every imported
+//! zip and of every texture. Re-importing a package is detected by its archive
+//! hash, and a texture identical to one already on disk is replaced with a hard
+//! link to it. Entries are checked against the filesystem on use, so models
+//! deleted or moved to the trash simply stop matching.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+pub const INDEX_FILE: &str = ".import_index.json";
+const TEXTURE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "webp"];
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ImportIndex {
+    /// Archive hash → model folder name.
+    #[serde(default)]
+    archives: BTreeMap<String, String>,
+    /// Texture hash → path relative to `live2d_models/`.
+    #[serde(default)]
+    textures: BTreeMap<String, String>,
+}
+
+/// What `import_live2d_zip` does when the archive was already imported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DuplicateAction {
+    /// Keep the existing model and return its path.
+    Skip,
+    /// Import again under a free folder name.
+    Rename,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Live2dDuplicate {
+    /// Folder name of the model imported from the same archive.
+    pub name: String,
+    /// Relative path to its `.model3.json`.
+    pub path: String,
+}
+
+pub fn hash_file(path: &Path) -> io::Result<String> {
+    let mut hasher = Sha256::new();
+    io::copy(&mut fs::File::open(path)?, &mut hasher)?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// The model previously imported from an archive with this hash, if it is
+/// still installed.
+pub fn find_duplicate(models_dir: &Path, archive_hash: &str) -> Option<Live2dDuplicate> {
+    let index = load_index(models_dir);
+    let name = index.archives.get(archive_hash)?;
+    let model_json = super::live2d::find_model3_json(&models_dir.join(name))?;
+    let relative = model_json.strip_prefix(models_dir).ok()?;
+    Some(Live2dDuplicate {
+        name: name.clone(),
+        path: relative.to_string_lossy().replace('\\', "/"),
+    })
+}
+
+pub fn record_archive(models_dir: &Path, archive_hash: &str, folder_name: &str) -> io::Result<()> {
+    let mut index = load_index(models_dir);
+    index
+        .archives
+        .insert(archive_hash.to_string(), folder_name.to_string());
+    save_index(models_dir, &index)
+}
+
+/// `name`, or `name (2)`, `name (3)`, … whichever is not taken yet.
+pub fn unique_folder_name(models_dir: &Path, name: &str) -> String {
+    if !models_dir.join(name).exists() {
+        return name.to_string();
+    }
+    (2..)
+        .map(|n| format!("{} ({})", name, n))
+        .find(|candidate| !models_dir.join(candidate).exists())
+        .unwrap_or_else(|| name.to_string())
+}
+
+/// Replace textures in `folder_name` that already exist elsewhere under
+/// `models_dir` with hard links, and index the rest. Returns the bytes saved.
+/// Filesystems without hard links keep the copies.
+pub fn share_textures(models_dir: &Path, folder_name: &str) -> io::Result<u64> {
+    let mut index = load_index(models_dir);
+    let mut textures = Vec::new();
+    collect_textures(&models_dir.join(folder_name), &mut textures)?;
+
+    let mut saved = 0;
+    for path in textures {
+        let Ok(relative) = path.strip_prefix(models_dir) else {
+            continue;
+        };
+        let relative = relative.to_string_lossy().replace('\\', "/");
+        let hash = hash_file(&path)?;
+        let len = fs::metadata(&path)?.len();
+
+        let existing = index
+            .textures
+            .get(&hash)
+            .filter(|existing| **existing != relative)
+            .map(|existing| models_dir.join(existing))
+            .filter(|existing| {
+                fs::metadata(existing).is_ok_and(|meta| meta.is_file() && meta.len() == len)
+            });
+        match existing {
+            Some(existing) if link_over(&existing, &path).is_ok() => saved += len,
+            _ => {
+                index.textures.insert(hash, relative);
+            }
+        }
+    }
+    save_index(models_dir, &index)?;
+    Ok(saved)
+}
+
+/// Keep the index pointing at a model folder after it is renamed.
+pub fn rename_folder(models_dir: &Path, old_name: &str, new_name: &str) -> io::Result<()> {
+    let mut index = load_index(models_dir);
+    for folder in index.archives.values_mut() {
+        if folder == old_name {
+            *folder = new_name.to_string();
+        }
+    }
+    let prefix = format!("{}/", old_name);
+    for path in index.textures.values_mut() {
+        if let Some(rest) = path.strip_prefix(&prefix) {
+            *path = format!("{}/{}", new_name, rest);
+        }
+    }
+    save_index(models_dir, &index)
+}
+
+fn link_over(existing: &Path, target: &Path) -> io::Result<()> {
+    let mut tmp = target.as_os_str().to_owned();
+    tmp.push(".link");
+    let tmp = PathBuf::from(tmp);
+    let _ = fs::remove_file(&tmp);
+    fs::hard_link(existing, &tmp)?;
+    fs::rename(&tmp, target).inspect_err(|_| {
+        let _ = fs::remove_file(&tmp);
+    })
+}
+
+fn collect_textures(dir: &Path, out: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_textures(&path, out)?;
+        } else if path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| TEXTURE_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+        {
+            out.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn load_index(models_dir: &Path) -> ImportIndex {
+    fs::read(models_dir.join(INDEX_FILE))
+        .ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+        .unwrap_or_default()
+}
+
+fn save_index(models_dir: &Path, index: &ImportIndex) -> io::Result<()> {
+    fs::create_dir_all(models_dir)?;
+    let path = models_dir.join(INDEX_FILE);
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, serde_json::to_vec_pretty(index)?)?;
+    fs::rename(&tmp, &path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write_model(models_dir: &Path, name: &str, texture: &[u8]) {
+        let root = models_dir.join(name);
+        fs::create_dir_all(root.join("textures")).unwrap();
+        fs::write(root.join(format!("{}.model3.json", name)), "{}").unwrap();
+        fs::write(root.join(format!("{}.moc3", name)), "moc").unwrap();
+        fs::write(root.join("textures").join("texture_00.png"), texture).unwrap();
+    }
+
+    #[test]
+    fn archives_are_matched_while_the_model_exists() {
+        let tmp = TempDir::new().unwrap();
+        let models_dir = tmp.path();
+        write_model(models_dir, "mio", b"png");
+        record_archive(models_dir, "abc", "mio").unwrap();
+
+        let duplicate = find_duplicate(models_dir, "abc").unwrap();
+        assert_eq!(duplicate.name, "mio");
+        assert_eq!(duplicate.path, "mio/mio.model3.json");
+        assert!(find_duplicate(models_dir, "other").is_none());
+        assert_eq!(unique_folder_name(models_dir, "mio"), "mio (2)");
+        assert_eq!(unique_folder_name(models_dir, "rin"), "rin");
+
+        fs::rename(models_dir.join("mio"), models_dir.join("mio2")).unwrap();
+        rename_folder(models_dir, "mio", "mio2").unwrap();
+        assert_eq!(find_duplicate(models_dir, "abc").unwrap().name, "mio2");
+
+        fs::remove_dir_all(models_dir.join("mio2")).unwrap();
+        assert!(find_duplicate(models_dir, "abc").is_none());
+    }
+
+    #[test]
+    fn identical_textures_share_one_file() {
+        let tmp = TempDir::new().unwrap();
+        let models_dir = tmp.path();
+        write_model(models_dir, "mio", b"same pixels");
+        write_model(models_dir, "mio (2)", b"same pixels");
+        write_model(models_dir, "rin", b"other pixels");
+
+        assert_eq!(share_textures(models_dir, "mio").unwrap(), 0);
+        assert_eq!(share_textures(models_dir, "mio (2)").unwrap(), 11);
+        assert_eq!(share_textures(models_dir, "rin").unwrap(), 0);
+
+        let copy = models_dir.join("mio (2)/textures/texture_00.png");
+        assert_eq!(fs::read(&copy).unwrap(), b"same pixels");
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::MetadataExt;
+            assert_eq!(fs::metadata(&copy).unwrap().nlink(), 2);
+        }
+
+        // The shared file survives removal of the model it was first seen in.
+        fs::remove_dir_all(models_dir.join("mio")).unwrap();
+        assert_eq!(fs::read(&copy).unwrap(), b"same pixels");
+        assert_eq!(share_textures(models_dir, "mio (2)").unwrap(), 0);
+    }
+}
@@ -0,0 +1,458 @@
+//! Validation, streamed extraction and rollback for Live2D imports.
+//!
+//! An archive is checked before anything is written: it must contain a
+//! `.model3.json` whose referenced files (moc, textures, physics, motions, …)
+//! are all present, and every missing one is reported by name. Extraction
+//! then reports progress through `live2d:import-progress`, and a model
+//! replaced by the import is only removed once the new one is in place.
+
+use crate::error::KokoroError;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashSet;
+use std::fs;
+use std::io::{self, Read, Seek, Write};
+use std::path::{Path, PathBuf};
+
+pub const PROGRESS_EVENT: &str = "live2d:import-progress";
+/// Bytes extracted between two progress events within one file.
+const PROGRESS_STEP: u64 = 4 * 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Live2dImportProgress {
+    /// `validating`, `extracting`, `installing` or `complete`.
+    pub stage: &'static str,
+    pub processed_bytes: u64,
+    pub total_bytes: u64,
+    /// Archive entry being extracted.
+    pub file: Option<String>,
+}
+
+impl Live2dImportProgress {
+    pub fn stage(stage: &'static str, processed_bytes: u64, total_bytes: u64) -> Self {
+        Self {
+            stage,
+            processed_bytes,
+            total_bytes,
+            file: None,
+        }
+    }
+}
+
+/// What extraction needs from a validated archive.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArchivePlan {
+    /// Entry name of the `.model3.json`, `/`-separated.
+    pub model_entry: String,
+    /// Uncompressed size of all entries.
+    pub total_bytes: u64,
+}
+
+/// Files a `.model3.json` points to, relative to its folder.
+pub fn referenced_files(model: &Value) -> Vec<String> {
+    let Some(refs) = model.get("FileReferences") else {
+        return Vec::new();
+    };
+    let mut files = Vec::new();
+    let mut push = |value: Option<&Value>| {
+        if let Some(path) = value
+            .and_then(Value::as_str)
+            .filter(|p| !p.trim().is_empty())
+        {
+            if !files.iter().any(|known| known == path) {
+                files.push(path.to_string());
+            }
+        }
+    };
+    for key in ["Moc", "Physics", "Pose", "DisplayInfo", "UserData"] {
+        push(refs.get(key));
+    }
+    for texture in refs
+        .get("Textures")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        push(Some(texture));
+    }
+    for expression in refs
+        .get("Expressions")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        push(expression.get("File"));
+    }
+    for motion in refs
+        .get("Motions")
+        .and_then(Value::as_object)
+        .into_iter()
+        .flat_map(|groups| groups.values())
+        .filter_map(Value::as_array)
+        .flatten()
+    {
+        push(motion.get("File"));
+        push(motion.get("Sound"));
+    }
+    files
+}
+
+/// Check a parsed `.model3.json` against the files that exist next to it.
+/// `exists` gets paths relative to the archive or model root.
+fn check_references(
+    model: &Value,
+    model_dir: &str,
+    exists: impl Fn(&str) -> bool,
+) -> Result<(), KokoroError> {
+    if model
+        .pointer("/FileReferences/Moc")
+        .and_then(Value::as_str)
+        .is_none_or(|moc| moc.trim().is_empty())
+    {
+        return Err(KokoroError::Validation(
+            ".model3.json does not reference a .moc3 file".to_string(),
+        ));
+    }
+    let missing: Vec<String> = referenced_files(model)
+        .into_iter()
+        .filter(|file| resolve(model_dir, file).is_none_or(|path| !exists(&path)))
+        .collect();
+    if missing.is_empty() {
+        return Ok(());
+    }
+    Err(KokoroError::Validation(format!(
+        "Live2D model is missing {} referenced file(s): {}",
+        missing.len(),
+        missing.join(", ")
+    )))
+}
+
+/// Find the `.model3.json` in a zip and check everything it references is in
+/// the archive, without extracting anything.
+pub fn validate_archive<R: Read + Seek>(
+    archive: &mut zip::ZipArchive<R>,
+) -> Result<ArchivePlan, KokoroError> {
+    let mut names = HashSet::new();
+    let mut total_bytes = 0u64;
+    for i in 0..archive.len() {
+        let entry = archive.by_index_raw(i).map_err(KokoroError::from)?;
+        total_bytes += entry.size();
+        if !entry.is_dir() {
+            names.insert(normalize_entry_name(entry.name()));
+        }
+    }
+
+    let model_entry = names
+        .iter()
+        .filter(|name| name.to_ascii_lowercase().ends_with(".model3.json"))
+        .filter(|name| !name.starts_with("__MACOSX/"))
+        .min_by_key(|name| (name.matches('/').count(), (*name).clone()))
+        .cloned()
+        .ok_or_else(|| {
+            KokoroError::NotFound("No .model3.json file found in the zip archive".to_string())
+        })?;
+
+    let index = (0..archive.len())
+        .find(|&i| {
+            archive
+                .name_for_index(i)
+                .is_some_and(|name| normalize_entry_name(name) == model_entry)
+        })
+        .ok_or_else(|| KokoroError::NotFound(format!("Cannot read '{}'", model_entry)))?;
+    let mut raw = String::new();
+    archive
+        .by_index(index)
+        .map_err(KokoroError::from)?
+        .read_to_string(&mut raw)
+        .map_err(|e| KokoroError::Validation(format!("Cannot read '{}': {}", model_entry, e)))?;
+    let model: Value = serde_json::from_str(raw.trim_start_matches('\u{feff}'))
+        .map_err(|e| KokoroError::Validation(format!("Invalid '{}': {}", model_entry, e)))?;
+
+    let model_dir = model_entry
+        .rsplit_once('/')
+        .map(|(dir, _)| dir)
+        .unwrap_or("");
+    check_references(&model, model_dir, |path| names.contains(path))?;
+
+    Ok(ArchivePlan {
+        model_entry,
+        total_bytes,
+    })
+}
+
+/// Same checks as [`validate_archive`] for a model folder on disk.
+pub fn validate_model_dir(model_json: &Path) -> Result<(), KokoroError> {
+    let raw = fs::read_to_string(model_json).map_err(KokoroError::from)?;
+    let model: Value = serde_json::from_str(raw.trim_start_matches('\u{feff}')).map_err(|e| {
+        KokoroError::Validation(format!("Invalid '{}': {}", model_json.display(), e))
+    })?;
+    let root = model_json.parent().unwrap_or(Path::new("."));
+    check_references(&model, "", |path| root.join(path).is_file())
+}
+
+/// Extract every entry into `dest`, reporting progress as bytes are written.
+pub fn extract_archive<R: Read + Seek>(
+    archive: &mut zip::ZipArchive<R>,
+    dest: &Path,
+    total_bytes: u64,
+    mut on_progress: impl FnMut(Live2dImportProgress),
+) -> Result<(), KokoroError> {
+    let mut processed = 0u64;
+    let mut buffer = vec![0u8; 64 * 1024];
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).map_err(KokoroError::from)?;
+        let Some(relative) = entry.enclosed_name() else {
+            continue;
+        };
+        let outpath = dest.join(relative);
+        if entry.is_dir() {
+            fs::create_dir_all(&outpath).map_err(KokoroError::from)?;
+            continue;
+        }
+        if let Some(parent) = outpath.parent() {
+            fs::create_dir_all(parent).map_err(KokoroError::from)?;
+        }
+
+        let name = normalize_entry_name(entry.name());
+        let mut outfile = fs::File::create(&outpath).map_err(KokoroError::from)?;
+        let mut since_report = 0u64;
+        loop {
+            let read = entry.read(&mut buffer).map_err(|e| {
+                KokoroError::Validation(format!("Corrupt archive entry '{}': {}", name, e))
+            })?;
+            if read == 0 {
+                break;
+            }
+            outfile
+                .write_all(&buffer[..read])
+                .map_err(KokoroError::from)?;
+            processed += read as u64;
+            since_report += read as u64;
+            if since_report >= PROGRESS_STEP {
+                since_report = 0;
+                on_progress(Live2dImportProgress {
+                    file: Some(name.clone()),
+                    ..Live2dImportProgress::stage("extracting", processed, total_bytes)
+                });
+            }
+        }
+        on_progress(Live2dImportProgress {
+            file: Some(name),
+            ..Live2dImportProgress::stage("extracting", processed, total_bytes)
+        });
+    }
+    Ok(())
+}
+
+/// Put a new model folder at `target` via `place`, then run `finalize`.
+/// A folder already at `target` is set aside first and only deleted once both
+/// succeed; on failure the new folder is removed and the old one restored.
+pub fn install_model_dir<T>(
+    target: &Path,
+    place: impl FnOnce(&Path) -> io::Result<()>,
+    finalize: impl FnOnce() -> Result<T, KokoroError>,
+) -> Result<T, KokoroError> {
+    let backup = if target.exists() {
+        let backup = backup_path(target);
+        fs::rename(target, &backup).map_err(|e| {
+            KokoroError::Internal(format!("Failed to move existing model aside: {}", e))
+        })?;
+        Some(backup)
+    } else {
+        None
+    };
+
+    let result = place(target)
+        .map_err(|e| {
+            KokoroError::Internal(format!("Failed to move model folder into place: {}", e))
+        })
+        .and_then(|_| finalize());
+
+    match (&result, backup) {
+        (Ok(_), Some(backup)) => {
+            let _ = remove_path(&backup);
+        }
+        (Err(_), backup) => {
+            let _ = remove_path(target);
+            if let Some(backup) = backup {
+                let _ = fs::rename(&backup, target);
+            }
+        }
+        (Ok(_), None) => {}
+    }
+    result
+}
+
+fn backup_path(target: &Path) -> PathBuf {
+    let name = target
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    target.with_file_name(format!(
+        ".{}.replaced-{}",
+        name,
+        chrono::Utc::now().timestamp_millis()
+    ))
+}
+
+fn remove_path(path: &Path) -> io::Result<()> {
+    if path.is_dir() {
+        fs::remove_dir_all(path)
+    } else if path.exists() {
+        fs::remove_file(path)
+    } else {
+        Ok(())
+    }
+}
+
+fn normalize_entry_name(name: &str) -> String {
+    name.replace('\\', "/").trim_start_matches("./").to_string()
+}
+
+/// Join a `/`-separated `relative` onto `base`, resolving `.` and `..`.
+/// `None` when the path climbs out of the archive.
+fn resolve(base: &str, relative: &str) -> Option<String> {
+    let mut parts: Vec<&str> = base.split('/').filter(|p| !p.is_empty()).collect();
+    for part in relative.split(['/', '\\']) {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop()?;
+            }
+            part => parts.push(part),
+        }
+    }
+    Some(parts.join("/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::io::Cursor;
+    use tempfile::TempDir;
+    use zip::write::SimpleFileOptions;
+
+    fn model_json() -> Value {
+        json!({
+            "Version": 3,
+            "FileReferences": {
+                "Moc": "mio.moc3",
+                "Textures": ["mio.2048/texture_00.png"],
+                "Physics": "mio.physics3.json",
+                "Expressions": [{ "Name": "smile", "File": "exp/smile.exp3.json" }],
+                "Motions": { "Idle": [{ "File": "motion/idle.motion3.json", "Sound": "" }] }
+            }
+        })
+    }
+
+    fn build_zip(files: &[(&str, &[u8])]) -> zip::ZipArchive<Cursor<Vec<u8>>> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, bytes) in files {
+            writer
+                .start_file(*name, SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(bytes).unwrap();
+        }
+        zip::ZipArchive::new(writer.finish().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn references_cover_every_asset_kind() {
+        assert_eq!(
+            referenced_files(&model_json()),
+            vec![
+                "mio.moc3",
+                "mio.physics3.json",
+                "mio.2048/texture_00.png",
+                "exp/smile.exp3.json",
+                "motion/idle.motion3.json",
+            ]
+        );
+        assert_eq!(
+            resolve("pkg/runtime", "../tex/a.png").as_deref(),
+            Some("pkg/tex/a.png")
+        );
+        assert_eq!(resolve("", "../a.png"), None);
+    }
+
+    #[test]
+    fn archives_report_every_missing_file_before_extraction() {
+        let model = model_json().to_string();
+        let mut incomplete = build_zip(&[
+            ("mio/runtime/mio.model3.json", model.as_bytes()),
+            ("mio/runtime/mio.moc3", b"moc"),
+            ("mio/runtime/mio.physics3.json", b"{}"),
+        ]);
+        let err = validate_archive(&mut incomplete).unwrap_err().to_string();
+        assert!(err.contains("3 referenced file(s)"), "{}", err);
+        assert!(err.contains("mio.2048/texture_00.png"));
+        assert!(err.contains("exp/smile.exp3.json"));
+        assert!(err.contains("motion/idle.motion3.json"));
+
+        let mut complete = build_zip(&[
+            ("mio/runtime/mio.model3.json", model.as_bytes()),
+            ("mio/runtime/mio.moc3", b"moc"),
+            ("mio/runtime/mio.physics3.json", b"{}"),
+            ("mio/runtime/mio.2048/texture_00.png", b"png"),
+            ("mio/runtime/exp/smile.exp3.json", b"{}"),
+            ("mio/runtime/motion/idle.motion3.json", b"{}"),
+            ("mio/ReadMe.txt", b"hi"),
+        ]);
+        let plan = validate_archive(&mut complete).unwrap();
+        assert_eq!(plan.model_entry, "mio/runtime/mio.model3.json");
+
+        let tmp = TempDir::new().unwrap();
+        let mut events = Vec::new();
+        extract_archive(&mut complete, tmp.path(), plan.total_bytes, |p| {
+            events.push(p)
+        })
+        .unwrap();
+        assert_eq!(events.last().unwrap().processed_bytes, plan.total_bytes);
+        assert!(tmp
+            .path()
+            .join("mio/runtime/mio.2048/texture_00.png")
+            .is_file());
+
+        let mut broken = build_zip(&[("a.model3.json", b"{ not json")]);
+        assert!(validate_archive(&mut broken).is_err());
+        let mut empty = build_zip(&[("readme.txt", b"")]);
+        assert!(matches!(
+            validate_archive(&mut empty),
+            Err(KokoroError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn failed_installs_restore_the_previous_model() {
+        let tmp = TempDir::new().unwrap();
+        let target = tmp.path().join("mio");
+        fs::create_dir_all(&target).unwrap();
+        fs::write(target.join("old.txt"), "old").unwrap();
+
+        let result: Result<(), _> = install_model_dir(
+            &target,
+            |dir| {
+                fs::create_dir_all(dir)?;
+                fs::write(dir.join("new.txt"), "new")
+            },
+            || Err(KokoroError::Internal("profile failed".to_string())),
+        );
+        assert!(result.is_err());
+        assert!(target.join("old.txt").is_file());
+        assert!(!target.join("new.txt").exists());
+
+        install_model_dir(
+            &target,
+            |dir| {
+                fs::create_dir_all(dir)?;
+                fs::write(dir.join("new.txt"), "new")
+            },
+            || Ok(()),
+        )
+        .unwrap();
+        assert!(target.join("new.txt").is_file());
+        assert!(!target.join("old.txt").exists());
+        assert_eq!(fs::read_dir(tmp.path()).unwrap().count(), 1);
+    }
+}
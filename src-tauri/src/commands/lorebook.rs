@@ -0,0 +1,79 @@
+use crate::ai::context::AIOrchestrator;
+use crate::ai::lorebook::{self, Lorebook, LorebookEntry};
+use crate::error::KokoroError;
+use tauri::State;
+
+/// Returns the character's lorebook, or an empty one if it has none yet.
+#[tauri::command]
+pub async fn get_lorebook(
+    character_id: String,
+    orchestrator: State<'_, AIOrchestrator>,
+) -> Result<Lorebook, KokoroError> {
+    Ok(lorebook::load_lorebook(&orchestrator.db, &character_id)
+        .await?
+        .unwrap_or_default())
+}
+
+/// Replace the whole lorebook (settings and entries).
+#[tauri::command]
+pub async fn save_lorebook(
+    character_id: String,
+    mut book: Lorebook,
+    orchestrator: State<'_, AIOrchestrator>,
+) -> Result<Lorebook, KokoroError> {
+    book.normalize();
+    lorebook::save_lorebook(&orchestrator.db, &character_id, &book).await?;
+    Ok(book)
+}
+
+/// Insert or update a single entry. An `id` of 0 (or an unknown id) creates a new entry.
+#[tauri::command]
+pub async fn upsert_lorebook_entry(
+    character_id: String,
+    mut entry: LorebookEntry,
+    orchestrator: State<'_, AIOrchestrator>,
+) -> Result<LorebookEntry, KokoroError> {
+    if entry.keys.iter().all(|k| k.trim().is_empty()) && !entry.constant {
+        return Err(KokoroError::Validation(
+            "Lorebook entry needs at least one key unless it is constant".to_string(),
+        ));
+    }
+    let mut book = lorebook::load_lorebook(&orchestrator.db, &character_id)
+        .await?
+        .unwrap_or_default();
+    match book
+        .entries
+        .iter_mut()
+        .find(|e| e.id == entry.id && entry.id > 0)
+    {
+        Some(existing) => *existing = entry.clone(),
+        None => {
+            entry.id = book.next_entry_id();
+            book.entries.push(entry.clone());
+        }
+    }
+    lorebook::save_lorebook(&orchestrator.db, &character_id, &book).await?;
+    Ok(entry)
+}
+
+#[tauri::command]
+pub async fn delete_lorebook_entry(
+    character_id: String,
+    entry_id: i64,
+    orchestrator: State<'_, AIOrchestrator>,
+) -> Result<(), KokoroError> {
+    let mut book = lorebook::load_lorebook(&orchestrator.db, &character_id)
+        .await?
+        .ok_or_else(|| {
+            KokoroError::NotFound(format!("Character {} has no lorebook", character_id))
+        })?;
+    let before = book.entries.len();
+    book.entries.retain(|e| e.id != entry_id);
+    if book.entries.len() == before {
+        return Err(KokoroError::NotFound(format!(
+            "Lorebook entry {} not found",
+            entry_id
+        )));
+    }
+    lorebook::save_lorebook(&orchestrator.db, &character_id, &book).await
+}
@@ -1,11 +1,15 @@
 use crate::actions::ActionRegistry;
 use crate::error::KokoroError;
-use crate::mcp::manager::{McpManager, McpServerConfig, McpServerStatus};
+use crate::mcp::bridge::McpToolConfirmState;
+use crate::mcp::manager::{
+    McpManager, McpServerConfig, McpServerStatus, McpToolPolicy, McpToolStatus,
+};
 use std::sync::Arc;
-use tauri::State;
+use tauri::{AppHandle, State};
+use tauri_plugin_opener::OpenerExt;
 use tokio::sync::{Mutex, RwLock};
 
-fn format_connection_error(error: &KokoroError) -> String {
+pub(crate) fn format_connection_error(error: &KokoroError) -> String {
     match error {
         KokoroError::Config(message)
         | KokoroError::Database(message)
@@ -68,7 +72,8 @@ pub async fn add_mcp_server(
         let cfg = config.clone();
         tauri::async_runtime::spawn(async move {
             tracing::info!(target: "mcp", "Background connecting to '{}'...", cfg.name);
-            let build_result = crate::mcp::manager::build_connected_client(&cfg).await;
+            let oauth = mgr_arc.lock().await.oauth_store();
+            let build_result = crate::mcp::manager::build_connected_client(&cfg, &oauth).await;
             let connect_result = {
                 let mut mgr = mgr_arc.lock().await;
                 mgr.clear_connecting(&cfg.name);
@@ -141,7 +146,8 @@ pub async fn reconnect_mcp_server(
 
     tauri::async_runtime::spawn(async move {
         tracing::info!(target: "mcp", "Retrying connection to '{}'...", cfg.name);
-        let build_result = crate::mcp::manager::build_connected_client(&cfg).await;
+        let oauth = mgr_arc.lock().await.oauth_store();
+        let build_result = crate::mcp::manager::build_connected_client(&cfg, &oauth).await;
         let connect_result = {
             let mut mgr = mgr_arc.lock().await;
             mgr.clear_connecting(&cfg.name);
@@ -201,7 +207,8 @@ pub async fn toggle_mcp_server(
         // Enable: spawn background connection
         tauri::async_runtime::spawn(async move {
             tracing::info!(target: "mcp", "Enabling and connecting '{}'...", cfg.name);
-            let build_result = crate::mcp::manager::build_connected_client(&cfg).await;
+            let oauth = mgr_arc.lock().await.oauth_store();
+            let build_result = crate::mcp::manager::build_connected_client(&cfg, &oauth).await;
             let connect_result = {
                 let mut mgr = mgr_arc.lock().await;
                 mgr.clear_connecting(&cfg.name);
@@ -233,3 +240,95 @@ pub async fn toggle_mcp_server(
 
     Ok(())
 }
+
+/// List tools of connected servers with their enabled/auto-approve policy.
+#[tauri::command]
+pub async fn list_mcp_tools(
+    manager: State<'_, Arc<Mutex<McpManager>>>,
+) -> Result<Vec<McpToolStatus>, KokoroError> {
+    let mgr = manager.lock().await;
+    Ok(mgr.list_tools().await)
+}
+
+/// Persist one tool's policy and re-register tools so it applies immediately.
+#[tauri::command]
+pub async fn set_mcp_tool_policy(
+    server_name: String,
+    tool_name: String,
+    policy: McpToolPolicy,
+    manager: State<'_, Arc<Mutex<McpManager>>>,
+    registry: State<'_, Arc<RwLock<ActionRegistry>>>,
+) -> Result<(), KokoroError> {
+    manager
+        .lock()
+        .await
+        .set_tool_policy(&server_name, &tool_name, policy)?;
+    crate::mcp::bridge::register_mcp_tools(&manager.inner().clone(), registry.inner()).await;
+    Ok(())
+}
+
+/// Answer a `tool-confirm` prompt.
+#[tauri::command]
+pub async fn resolve_mcp_tool_confirm(
+    request_id: String,
+    approved: bool,
+    confirm_state: State<'_, Arc<McpToolConfirmState>>,
+) -> Result<(), KokoroError> {
+    confirm_state.resolve(&request_id, approved).await
+}
+
+/// Sign in to a server that requires OAuth: opens the system browser, waits
+/// for the redirect (up to five minutes), stores the tokens and reconnects.
+#[tauri::command]
+pub async fn authorize_mcp_server(
+    name: String,
+    app: AppHandle,
+    manager: State<'_, Arc<Mutex<McpManager>>>,
+    registry: State<'_, Arc<RwLock<ActionRegistry>>>,
+) -> Result<(), KokoroError> {
+    let (cfg, oauth) = {
+        let mgr = manager.lock().await;
+        let cfg = mgr
+            .get_config(&name)
+            .ok_or_else(|| KokoroError::NotFound(format!("Server '{}' not found", name)))?;
+        (cfg, mgr.oauth_store())
+    };
+    let url = cfg.url.clone().ok_or_else(|| {
+        KokoroError::Validation(format!(
+            "Server '{}' has no URL; only HTTP servers use OAuth",
+            name
+        ))
+    })?;
+
+    crate::mcp::oauth::authorize(
+        &cfg.name,
+        &url,
+        &cfg.oauth.clone().unwrap_or_default(),
+        &oauth,
+        |auth_url| {
+            app.opener()
+                .open_url(auth_url, None::<&str>)
+                .map_err(|e| KokoroError::Internal(format!("Failed to open browser: {}", e)))
+        },
+    )
+    .await?;
+
+    reconnect_mcp_server(name, manager, registry).await
+}
+
+/// Forget a server's OAuth tokens and disconnect it.
+#[tauri::command]
+pub async fn sign_out_mcp_server(
+    name: String,
+    manager: State<'_, Arc<Mutex<McpManager>>>,
+    registry: State<'_, Arc<RwLock<ActionRegistry>>>,
+) -> Result<(), KokoroError> {
+    {
+        let mut mgr = manager.lock().await;
+        mgr.oauth_store().remove(&name)?;
+        mgr.disconnect_server(&name).await?;
+        mgr.set_connection_error(&name, "Signed out".to_string());
+    }
+    crate::mcp::bridge::register_mcp_tools(&manager.inner().clone(), registry.inner()).await;
+    Ok(())
+}
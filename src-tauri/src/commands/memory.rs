@@ -1,8 +1,9 @@
 use crate::ai::context::AIOrchestrator;
 use crate::error::KokoroError;
 use crate::llm::service::LlmService;
+use crate::trash::Trash;
 use serde::Deserialize;
-use tauri::State;
+use tauri::{Emitter, State};
 
 #[derive(Deserialize)]
 pub struct ListMemoriesRequest {
@@ -72,16 +73,14 @@ pub struct DeleteMemoryRequest {
     pub id: i64,
 }
 
+/// Moves the memory to the trash; see `undo_last_delete`.
 #[tauri::command]
 pub async fn delete_memory(
     request: DeleteMemoryRequest,
-    state: State<'_, AIOrchestrator>,
+    trash: State<'_, Trash>,
 ) -> Result<(), KokoroError> {
-    state
-        .memory_manager
-        .delete_memory(request.id)
-        .await
-        .map_err(|e| KokoroError::Database(e.to_string()))
+    trash.delete_memory(request.id).await?;
+    Ok(())
 }
 
 #[derive(Deserialize)]
@@ -107,6 +106,38 @@ pub async fn update_memory_tier(
         .map_err(|e| KokoroError::Database(e.to_string()))
 }
 
+#[derive(Deserialize)]
+pub struct RememberMessageRequest {
+    pub character_id: String,
+    /// The fact in the user's own words: the message, a selection of it, or an edit.
+    pub content: String,
+    /// Chat message the fact came from.
+    #[serde(default)]
+    pub message_id: Option<i64>,
+}
+
+/// Pins a message or selection as a core memory, bypassing the extractor.
+/// Returns the memory id.
+#[tauri::command]
+pub async fn remember_message(
+    request: RememberMessageRequest,
+    state: State<'_, AIOrchestrator>,
+    app: tauri::AppHandle,
+) -> Result<i64, KokoroError> {
+    if request.content.trim().is_empty() {
+        return Err(KokoroError::Validation(
+            "Memory content cannot be empty".to_string(),
+        ));
+    }
+    let id = state
+        .memory_manager
+        .pin_memory(&request.content, &request.character_id, request.message_id)
+        .await
+        .map_err(|e| KokoroError::Database(e.to_string()))?;
+    let _ = app.emit("memory:updated", &request.character_id);
+    Ok(id)
+}
+
 #[derive(Deserialize)]
 pub struct DreamCharacterRequest {
     pub character_id: String,
@@ -231,8 +262,6 @@ pub async fn get_memory_embedding_model_status(
 pub async fn download_memory_embedding_model(
     app: tauri::AppHandle,
 ) -> Result<crate::ai::memory::MemoryEmbeddingModelStatus, KokoroError> {
-    use tauri::Emitter;
-
     crate::ai::memory::download_memory_embedding_model(move |progress| {
         app.emit("memory:embedding-model-progress", &progress)
             .map_err(|error| error.to_string())
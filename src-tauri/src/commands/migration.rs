@@ -0,0 +1,566 @@
+//! Machine migration: one archive with everything needed to move Kokoro to
+//! another computer.
+//!
+//! A migration archive is a regular backup (`manifest.json`, `kokoro.db`,
+//! `characters.json`, `configs/`) plus `migration.json` and the app-data
+//! directories under `files/`: Live2D models, mods and their storage,
+//! generated images and stored assets. `import_migration` checks the
+//! versions and restores those directories; the backup part is then imported
+//! with `import_data` like any backup. Downloaded local models (STT, VAD,
+//! embeddings) are too large to carry, so `include_local_models` only writes
+//! a list of them and the importer reports which still need downloading. The
+//! TTS cache lives in memory and has nothing to migrate.
+
+use crate::commands::backup::export_data_to_path;
+use crate::commands::system::compare_release_versions;
+use crate::error::KokoroError;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs;
+use std::io::{Read, Seek, Write};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter, Manager};
+use zip::write::SimpleFileOptions;
+
+// pattern: Imperative Shell
+
+/// Bumped when the archive layout changes incompatibly.
+pub const MIGRATION_FORMAT_VERSION: u32 = 1;
+pub const MIGRATION_PROGRESS_EVENT: &str = "migration:progress";
+
+const MIGRATION_MANIFEST: &str = "migration.json";
+const LOCAL_MODELS_MANIFEST: &str = "local_models.json";
+const FILES_DIR: &str = "files";
+
+/// App-data directories carried by a migration, grouped by section.
+const MIGRATION_SECTIONS: &[(&str, &[&str])] = &[
+    ("live2d_models", &["live2d_models"]),
+    ("mods", &["mods", "mod_storage"]),
+    ("generated_images", &["generated_images"]),
+    ("assets", &["assets"]),
+];
+
+/// App-data directories holding downloaded local models.
+const LOCAL_MODEL_DIRS: &[&str] = &["stt", "models"];
+
+/// Emit a progress event every this many files.
+const PROGRESS_EVERY: u64 = 25;
+
+// ── Types ────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MigrationManifest {
+    pub format_version: u32,
+    pub app_version: String,
+    pub created_at: String,
+    /// OS of the exporting machine, e.g. "windows".
+    pub platform: String,
+    pub sections: Vec<MigrationSection>,
+    /// Whether `local_models.json` lists the old machine's local models.
+    pub local_models: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MigrationSection {
+    pub name: String,
+    pub files: u64,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocalModelFile {
+    /// Relative to the app data dir, `/`-separated.
+    pub path: String,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MigrationProgress {
+    /// "export" or "import".
+    pub phase: &'static str,
+    /// Section being processed; "backup" for the database and configs.
+    pub section: String,
+    pub done: u64,
+    pub total: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MigrationExportResult {
+    pub path: String,
+    pub size_bytes: u64,
+    pub manifest: MigrationManifest,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MigrationPreview {
+    pub manifest: MigrationManifest,
+    /// Why this archive cannot be imported by the running version, if so.
+    pub incompatible: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MigrationImportResult {
+    pub restored_files: u64,
+    pub skipped_files: u64,
+    pub restored_bytes: u64,
+    /// Local model directories of the old machine missing here, e.g.
+    /// `stt/whisper_local`; they have to be downloaded again.
+    pub missing_local_models: Vec<String>,
+}
+
+struct MigrationFile {
+    path: PathBuf,
+    /// Relative to the app data dir, `/`-separated.
+    name: String,
+    bytes: u64,
+}
+
+// ── Helpers ──────────────────────────────────────────
+
+fn app_data_dir(app: &AppHandle) -> Result<PathBuf, KokoroError> {
+    app.path()
+        .app_data_dir()
+        .map_err(|e| KokoroError::Internal(format!("Failed to resolve app data dir: {}", e)))
+}
+
+fn zip_error(e: zip::result::ZipError) -> KokoroError {
+    KokoroError::Internal(format!("ZIP error: {}", e))
+}
+
+/// Regular files under `app_data/dir`, skipping symlinks.
+fn collect_files(app_data: &Path, dir: &str) -> Result<Vec<MigrationFile>, KokoroError> {
+    let mut files = Vec::new();
+    let mut pending = vec![app_data.join(dir)];
+    while let Some(current) = pending.pop() {
+        if !current.is_dir() {
+            continue;
+        }
+        for entry in fs::read_dir(&current).map_err(KokoroError::from)? {
+            let path = entry.map_err(KokoroError::from)?.path();
+            let meta = fs::symlink_metadata(&path).map_err(KokoroError::from)?;
+            if meta.is_dir() {
+                pending.push(path);
+            } else if meta.is_file() {
+                let name = path
+                    .strip_prefix(app_data)
+                    .map_err(|e| KokoroError::Internal(e.to_string()))?
+                    .to_string_lossy()
+                    .replace('\\', "/");
+                files.push(MigrationFile {
+                    path,
+                    name,
+                    bytes: meta.len(),
+                });
+            }
+        }
+    }
+    files.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(files)
+}
+
+fn check_compatibility(manifest: &MigrationManifest, app_version: &str) -> Result<(), String> {
+    if manifest.format_version > MIGRATION_FORMAT_VERSION {
+        return Err(format!(
+            "This archive uses migration format {}, but this version of Kokoro reads up to {}. Update Kokoro first.",
+            manifest.format_version, MIGRATION_FORMAT_VERSION
+        ));
+    }
+    if compare_release_versions(&manifest.app_version, app_version) > 0 {
+        return Err(format!(
+            "This archive was made with Kokoro {}, newer than the installed {}. Update Kokoro first.",
+            manifest.app_version, app_version
+        ));
+    }
+    Ok(())
+}
+
+/// Where an archive entry is restored, or `None` when it is not a file of a
+/// migration section. `entry` must already be free of `..` and roots.
+fn restore_target(app_data: &Path, entry: &Path) -> Option<PathBuf> {
+    let relative = entry.strip_prefix(FILES_DIR).ok()?;
+    let dir = relative.components().next()?.as_os_str().to_str()?;
+    let known = MIGRATION_SECTIONS
+        .iter()
+        .any(|(_, dirs)| dirs.contains(&dir));
+    (known && relative.components().count() > 1).then(|| app_data.join(relative))
+}
+
+/// Distinct model directories (first two path components) whose files are
+/// not all present under `app_data`.
+fn missing_local_models(app_data: &Path, models: &[LocalModelFile]) -> Vec<String> {
+    models
+        .iter()
+        .filter(|model| !app_data.join(&model.path).is_file())
+        .map(|model| {
+            model
+                .path
+                .splitn(3, '/')
+                .take(2)
+                .collect::<Vec<_>>()
+                .join("/")
+        })
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+fn read_manifest<R: Read + Seek>(
+    archive: &mut zip::ZipArchive<R>,
+) -> Result<MigrationManifest, KokoroError> {
+    let mut entry = archive.by_name(MIGRATION_MANIFEST).map_err(|_| {
+        KokoroError::Validation(
+            "Not a migration archive (no migration.json); import it as a backup instead"
+                .to_string(),
+        )
+    })?;
+    let mut buf = String::new();
+    entry.read_to_string(&mut buf).map_err(KokoroError::from)?;
+    serde_json::from_str(&buf)
+        .map_err(|e| KokoroError::Validation(format!("Invalid migration.json: {}", e)))
+}
+
+// ── Export ───────────────────────────────────────────
+
+fn write_archive(
+    app_data: &Path,
+    backup_path: &Path,
+    out_path: &Path,
+    include_local_models: bool,
+    progress: impl Fn(MigrationProgress),
+) -> Result<MigrationManifest, KokoroError> {
+    let file = fs::File::create(out_path).map_err(KokoroError::from)?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated)
+        .large_file(true);
+
+    // The backup entries go in unchanged so `preview_import`/`import_data`
+    // read the archive like a backup.
+    let mut backup = zip::ZipArchive::new(fs::File::open(backup_path).map_err(KokoroError::from)?)
+        .map_err(zip_error)?;
+    for i in 0..backup.len() {
+        zip.raw_copy_file(backup.by_index_raw(i).map_err(zip_error)?)
+            .map_err(zip_error)?;
+    }
+
+    let mut sections = Vec::new();
+    for (section, dirs) in MIGRATION_SECTIONS {
+        let mut files = Vec::new();
+        for dir in *dirs {
+            files.extend(collect_files(app_data, dir)?);
+        }
+        let total = files.len() as u64;
+        let mut bytes = 0;
+        progress(MigrationProgress {
+            phase: "export",
+            section: section.to_string(),
+            done: 0,
+            total,
+        });
+        for (done, file) in (1..).zip(&files) {
+            zip.start_file(format!("{}/{}", FILES_DIR, file.name), options)
+                .map_err(zip_error)?;
+            let mut source = fs::File::open(&file.path).map_err(KokoroError::from)?;
+            bytes += std::io::copy(&mut source, &mut zip).map_err(KokoroError::from)?;
+            if done % PROGRESS_EVERY == 0 || done == total {
+                progress(MigrationProgress {
+                    phase: "export",
+                    section: section.to_string(),
+                    done,
+                    total,
+                });
+            }
+        }
+        sections.push(MigrationSection {
+            name: section.to_string(),
+            files: total,
+            bytes,
+        });
+    }
+
+    if include_local_models {
+        let mut models = Vec::new();
+        for dir in LOCAL_MODEL_DIRS {
+            models.extend(
+                collect_files(app_data, dir)?
+                    .into_iter()
+                    .map(|file| LocalModelFile {
+                        path: file.name,
+                        bytes: file.bytes,
+                    }),
+            );
+        }
+        let json = serde_json::to_string_pretty(&models)
+            .map_err(|e| KokoroError::Internal(format!("Serialize error: {}", e)))?;
+        zip.start_file(LOCAL_MODELS_MANIFEST, options)
+            .map_err(zip_error)?;
+        zip.write_all(json.as_bytes()).map_err(KokoroError::from)?;
+    }
+
+    let manifest = MigrationManifest {
+        format_version: MIGRATION_FORMAT_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        created_at: chrono::Utc::now().to_rfc3339(),
+        platform: std::env::consts::OS.to_string(),
+        sections,
+        local_models: include_local_models,
+    };
+    let json = serde_json::to_string_pretty(&manifest)
+        .map_err(|e| KokoroError::Internal(format!("Serialize error: {}", e)))?;
+    zip.start_file(MIGRATION_MANIFEST, options)
+        .map_err(zip_error)?;
+    zip.write_all(json.as_bytes()).map_err(KokoroError::from)?;
+
+    zip.finish()
+        .map_err(|e| KokoroError::Internal(format!("ZIP finish error: {}", e)))?;
+    Ok(manifest)
+}
+
+/// Export the full app state for moving to another machine. Progress is
+/// reported through `migration:progress` events.
+#[tauri::command]
+pub async fn export_migration(
+    app: AppHandle,
+    export_path: String,
+    characters_json: Option<String>,
+    include_local_models: bool,
+) -> Result<MigrationExportResult, KokoroError> {
+    let app_data = app_data_dir(&app)?;
+    let out_path = PathBuf::from(&export_path);
+
+    let emit = {
+        let app = app.clone();
+        move |progress: MigrationProgress| {
+            let _ = app.emit(MIGRATION_PROGRESS_EVENT, &progress);
+        }
+    };
+    emit(MigrationProgress {
+        phase: "export",
+        section: "backup".to_string(),
+        done: 0,
+        total: 1,
+    });
+    let backup_path = std::env::temp_dir().join(format!(
+        "kokoro_migration_{}.zip",
+        chrono::Utc::now().timestamp_millis()
+    ));
+    let backup = export_data_to_path(&app_data, &backup_path, characters_json).await;
+    if let Err(e) = backup {
+        let _ = fs::remove_file(&backup_path);
+        return Err(e);
+    }
+
+    let written = {
+        let app_data = app_data.clone();
+        let backup_path = backup_path.clone();
+        let out_path = out_path.clone();
+        tokio::task::spawn_blocking(move || {
+            write_archive(
+                &app_data,
+                &backup_path,
+                &out_path,
+                include_local_models,
+                emit,
+            )
+        })
+        .await
+        .map_err(|e| KokoroError::Internal(format!("Migration export task failed: {}", e)))?
+    };
+    let _ = fs::remove_file(&backup_path);
+    let manifest = match written {
+        Ok(manifest) => manifest,
+        Err(e) => {
+            let _ = fs::remove_file(&out_path);
+            return Err(e);
+        }
+    };
+
+    let size_bytes = fs::metadata(&out_path).map(|m| m.len()).unwrap_or(0);
+    tracing::info!(
+        target: "backup",
+        "[Migration] Exported to {} ({} bytes, {} sections)",
+        export_path, size_bytes, manifest.sections.len()
+    );
+    Ok(MigrationExportResult {
+        path: export_path,
+        size_bytes,
+        manifest,
+    })
+}
+
+// ── Import ───────────────────────────────────────────
+
+#[tauri::command]
+pub async fn preview_migration(file_path: String) -> Result<MigrationPreview, KokoroError> {
+    let file = fs::File::open(&file_path).map_err(KokoroError::from)?;
+    let mut archive = zip::ZipArchive::new(file)
+        .map_err(|e| KokoroError::Validation(format!("Invalid ZIP archive: {}", e)))?;
+    let manifest = read_manifest(&mut archive)?;
+    let incompatible = check_compatibility(&manifest, env!("CARGO_PKG_VERSION")).err();
+    Ok(MigrationPreview {
+        manifest,
+        incompatible,
+    })
+}
+
+fn restore_archive(
+    app_data: &Path,
+    file_path: &Path,
+    overwrite: bool,
+    progress: impl Fn(MigrationProgress),
+) -> Result<MigrationImportResult, KokoroError> {
+    let file = fs::File::open(file_path).map_err(KokoroError::from)?;
+    let mut archive = zip::ZipArchive::new(file)
+        .map_err(|e| KokoroError::Validation(format!("Invalid ZIP archive: {}", e)))?;
+    let manifest = read_manifest(&mut archive)?;
+    check_compatibility(&manifest, env!("CARGO_PKG_VERSION")).map_err(KokoroError::Validation)?;
+
+    let mut result = MigrationImportResult {
+        restored_files: 0,
+        skipped_files: 0,
+        restored_bytes: 0,
+        missing_local_models: Vec::new(),
+    };
+    let total: u64 = manifest.sections.iter().map(|s| s.files).sum();
+    let mut done = 0;
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).map_err(zip_error)?;
+        if entry.is_dir() {
+            continue;
+        }
+        let Some(target) = entry
+            .enclosed_name()
+            .and_then(|name| restore_target(app_data, &name))
+        else {
+            continue;
+        };
+        done += 1;
+        if target.exists() && !overwrite {
+            result.skipped_files += 1;
+        } else {
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent).map_err(KokoroError::from)?;
+            }
+            let mut out = fs::File::create(&target).map_err(KokoroError::from)?;
+            result.restored_bytes +=
+                std::io::copy(&mut entry, &mut out).map_err(KokoroError::from)?;
+            result.restored_files += 1;
+        }
+        if done % PROGRESS_EVERY == 0 || done == total {
+            progress(MigrationProgress {
+                phase: "import",
+                section: FILES_DIR.to_string(),
+                done,
+                total,
+            });
+        }
+    }
+
+    if manifest.local_models {
+        if let Ok(mut entry) = archive.by_name(LOCAL_MODELS_MANIFEST) {
+            let mut buf = String::new();
+            entry.read_to_string(&mut buf).map_err(KokoroError::from)?;
+            let models: Vec<LocalModelFile> = serde_json::from_str(&buf).map_err(|e| {
+                KokoroError::Validation(format!("Invalid {}: {}", LOCAL_MODELS_MANIFEST, e))
+            })?;
+            result.missing_local_models = missing_local_models(app_data, &models);
+        }
+    }
+    Ok(result)
+}
+
+/// Restore the directories of a migration archive into the app data dir.
+/// Existing files are kept unless `overwrite`. The database, characters and
+/// configs are imported afterwards with `import_data` on the same file.
+#[tauri::command]
+pub async fn import_migration(
+    app: AppHandle,
+    file_path: String,
+    overwrite: bool,
+) -> Result<MigrationImportResult, KokoroError> {
+    let app_data = app_data_dir(&app)?;
+    let emit = {
+        let app = app.clone();
+        move |progress: MigrationProgress| {
+            let _ = app.emit(MIGRATION_PROGRESS_EVENT, &progress);
+        }
+    };
+    let result = tokio::task::spawn_blocking(move || {
+        restore_archive(&app_data, Path::new(&file_path), overwrite, emit)
+    })
+    .await
+    .map_err(|e| KokoroError::Internal(format!("Migration import task failed: {}", e)))??;
+
+    tracing::info!(
+        target: "backup",
+        "[Migration] Restored {} files ({} bytes), skipped {}, {} local models to download",
+        result.restored_files, result.restored_bytes, result.skipped_files,
+        result.missing_local_models.len()
+    );
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn migration_archive_round_trips_between_machines() {
+        let root =
+            std::env::temp_dir().join(format!("kokoro_migration_test_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let old = root.join("old");
+        let new = root.join("new");
+        for (path, body) in [
+            ("live2d_models/hiyori/hiyori.model3.json", "{}"),
+            ("mods/clock/mod.json", "{\"id\":\"clock\"}"),
+            ("generated_images/a.png", "png"),
+            ("stt/whisper_local/ggml-base.bin", "model"),
+            ("llm_config.json", "{}"),
+        ] {
+            let path = old.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, body).unwrap();
+        }
+        fs::create_dir_all(new.join("generated_images")).unwrap();
+        fs::write(new.join("generated_images/a.png"), "mine").unwrap();
+
+        let backup = root.join("backup.zip");
+        let mut zip = zip::ZipWriter::new(fs::File::create(&backup).unwrap());
+        zip.start_file("manifest.json", SimpleFileOptions::default())
+            .unwrap();
+        zip.write_all(b"{}").unwrap();
+        zip.finish().unwrap();
+
+        let archive = root.join("move.kokoro");
+        let manifest = write_archive(&old, &backup, &archive, true, |_| {}).unwrap();
+        assert_eq!(manifest.sections[0].files, 1);
+        assert_eq!(manifest.sections[1].name, "mods");
+
+        let result = restore_archive(&new, &archive, false, |_| {}).unwrap();
+        assert_eq!(result.restored_files, 2);
+        assert_eq!(result.skipped_files, 1);
+        assert_eq!(
+            fs::read_to_string(new.join("mods/clock/mod.json")).unwrap(),
+            "{\"id\":\"clock\"}"
+        );
+        assert_eq!(
+            fs::read_to_string(new.join("generated_images/a.png")).unwrap(),
+            "mine"
+        );
+        assert!(!new.join("llm_config.json").exists());
+        assert_eq!(result.missing_local_models, vec!["stt/whisper_local"]);
+
+        let mut newer = manifest.clone();
+        newer.app_version = "999.0.0".to_string();
+        assert!(check_compatibility(&newer, "1.0.0").is_err());
+        assert!(check_compatibility(&manifest, env!("CARGO_PKG_VERSION")).is_ok());
+        assert_eq!(
+            restore_target(&new, Path::new("files/configs/x.json")),
+            None
+        );
+        assert_eq!(restore_target(&new, Path::new("kokoro.db")), None);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
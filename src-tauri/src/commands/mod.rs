@@ -1,24 +1,54 @@
 pub mod actions;
+pub mod address_forms;
+pub mod api_server;
+pub mod assets;
 pub mod auto_backup;
+pub mod automation;
 pub mod backup;
 pub mod bot;
+pub mod capabilities;
+pub mod captions;
 pub mod character;
 pub mod characters;
 pub mod chat;
+pub mod code_exec;
+pub mod companion_state;
 pub mod context;
 pub mod conversation;
 pub mod database;
+pub mod duet;
+pub mod emotion;
 pub mod imagegen;
+pub mod input_mapping;
+pub mod input_queue;
+pub mod latency;
 pub mod live2d;
+pub mod live2d_dedup;
+pub mod live2d_import;
 pub mod live2d_protocol;
 pub mod llm;
+pub mod lorebook;
 pub mod mcp;
 pub mod memory;
+pub mod migration;
 pub mod mods;
+pub mod offline;
+pub mod onboarding;
 pub mod pet;
+pub mod plan;
+pub mod schedules;
+pub mod story;
 pub mod stt;
 pub mod system;
+pub mod tables;
 pub mod telegram;
 pub mod tool_settings;
+pub mod trash;
 pub mod tts;
 pub mod vision;
+pub mod voice_commands;
+pub mod voice_session;
+pub mod vts;
+pub mod wallpaper;
+pub mod warmup;
+pub mod webhooks;
@@ -1,10 +1,17 @@
 use crate::error::KokoroError;
-use crate::mods::{ModManager, ModManifest, ModThemeJson};
+use crate::mods::manager::read_manifest;
+use crate::mods::registry::{self, ModRegistryListing};
+use crate::mods::{http, permissions};
+use crate::mods::{ModManager, ModManifest, ModPermission, ModThemeJson};
+use serde::Serialize;
 use serde_json::Value as JsonValue;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::io;
+use std::path::{Path, PathBuf};
 use tauri::{command, AppHandle, State};
 use tokio::sync::Mutex;
+use zip::write::SimpleFileOptions;
 
 /// Validate mod ID format: must be non-empty and contain only alphanumeric, underscore, or dash
 fn is_valid_mod_id(id: &str) -> bool {
@@ -14,6 +21,11 @@ fn is_valid_mod_id(id: &str) -> bool {
             .all(|c| c.is_alphanumeric() || c == '_' || c == '-')
 }
 
+const MAX_FILE_SIZE: u64 = 10 * 1024 * 1024;
+const MAX_TOTAL_SIZE: u64 = 50 * 1024 * 1024;
+/// Extension of distributable mod packages (a zip with `mod.json` at its root).
+const MOD_PACKAGE_EXTENSION: &str = "kokoromod";
+
 /// Check if a file extension is allowed for MOD extraction
 fn is_allowed_mod_file(ext: &str) -> bool {
     const ALLOWED_EXTENSIONS: &[&str] = &[
@@ -77,7 +89,117 @@ pub async fn install_mod(
 
     let file = fs::File::open(archive_path).map_err(KokoroError::from)?;
     let mut archive = zip::ZipArchive::new(file).map_err(KokoroError::from)?;
+    let (manifest, previous) = install_archive(&mut archive, &mods_dir, None)?;
+    reset_grants_on_new_access(&mod_manager, previous.as_ref(), &manifest).await?;
+    Ok(manifest)
+}
+
+/// Mods listed by the registry at `registry_url`, with installed versions.
+#[command]
+pub async fn browse_mod_registry(
+    mod_manager: State<'_, Mutex<ModManager>>,
+    registry_url: String,
+) -> Result<Vec<ModRegistryListing>, KokoroError> {
+    let index = registry::fetch_index(&registry_url)
+        .await
+        .map_err(KokoroError::Mod)?;
+    let installed: HashMap<String, String> = {
+        let mut manager = mod_manager.lock().await;
+        manager
+            .scan_mods()
+            .into_iter()
+            .map(|manifest| (manifest.id, manifest.version))
+            .collect()
+    };
+    Ok(index
+        .mods
+        .into_iter()
+        .map(|entry| ModRegistryListing {
+            installed_version: installed.get(&entry.id).cloned(),
+            entry,
+        })
+        .collect())
+}
+
+/// Download the package of registry entry `mod_id`, check it against
+/// `sha256` and install it. A package whose mod.json has another id is refused.
+#[command]
+pub async fn install_mod_from_url(
+    mod_manager: State<'_, Mutex<ModManager>>,
+    mod_id: String,
+    url: String,
+    sha256: String,
+) -> Result<ModManifest, KokoroError> {
+    let url = registry::check_url(&url).map_err(KokoroError::Validation)?;
+    let bytes = registry::download(&url, registry::MAX_PACKAGE_BYTES)
+        .await
+        .map_err(KokoroError::Mod)?;
+    registry::verify_checksum(&bytes, &sha256).map_err(KokoroError::Mod)?;
+
+    let mods_dir = {
+        let manager = mod_manager.lock().await;
+        manager.mods_path.clone()
+    };
+    let mut archive = zip::ZipArchive::new(io::Cursor::new(bytes)).map_err(KokoroError::from)?;
+    let (manifest, previous) = install_archive(&mut archive, &mods_dir, Some(&mod_id))?;
+    reset_grants_on_new_access(&mod_manager, previous.as_ref(), &manifest).await?;
+    tracing::info!(
+        target: "mods",
+        "[ModRegistry] Installed mod '{}' {} from {}",
+        manifest.id,
+        manifest.version,
+        url
+    );
+    Ok(manifest)
+}
+
+/// Drop the user's grants for a mod when the installed copy asks for other
+/// permissions or hosts than the one it replaced, or replaced none, so they
+/// are reviewed again.
+async fn reset_grants_on_new_access(
+    mod_manager: &Mutex<ModManager>,
+    previous: Option<&ModManifest>,
+    manifest: &ModManifest,
+) -> Result<(), KokoroError> {
+    let mut manager = mod_manager.lock().await;
+    if manager.permission_grants().granted(&manifest.id).is_empty()
+        || previous.is_some_and(|previous| !access_changed(previous, manifest))
+    {
+        return Ok(());
+    }
+    tracing::info!(
+        target: "mods",
+        "[ModManager] Mod '{}' asks for different access; its permission grants were reset",
+        manifest.id
+    );
+    manager
+        .set_permissions(&manifest.id, Vec::new())
+        .map_err(KokoroError::Mod)
+}
+
+/// Whether `next` requests other permissions or network hosts than `previous`.
+fn access_changed(previous: &ModManifest, next: &ModManifest) -> bool {
+    let permissions = |manifest: &ModManifest| -> HashSet<ModPermission> {
+        permissions::requested(&manifest.permissions)
+            .into_iter()
+            .collect()
+    };
+    let hosts = |manifest: &ModManifest| -> HashSet<String> {
+        http::normalize_allowlist(&manifest.network_allowlist)
+            .into_iter()
+            .collect()
+    };
+    permissions(previous) != permissions(next) || hosts(previous) != hosts(next)
+}
 
+/// Extract a mod package into `mods_dir/<id>`, replacing an older copy whose
+/// manifest is returned alongside the new one. With `expected_id`, a package
+/// for another mod is refused before anything is written.
+fn install_archive<R: io::Read + io::Seek>(
+    archive: &mut zip::ZipArchive<R>,
+    mods_dir: &Path,
+    expected_id: Option<&str>,
+) -> Result<(ModManifest, Option<ModManifest>), KokoroError> {
     let mut manifest_content = String::new();
     {
         let mut manifest_file = archive
@@ -96,16 +218,21 @@ pub async fn install_mod(
         ));
     }
 
+    if let Some(expected_id) = expected_id.filter(|id| *id != manifest.id) {
+        return Err(KokoroError::Validation(format!(
+            "Package contains mod '{}', not the selected '{}'",
+            manifest.id, expected_id
+        )));
+    }
+
     let target_dir = mods_dir.join(&manifest.id);
+    let previous = read_manifest(&target_dir).ok();
     if target_dir.exists() {
         fs::remove_dir_all(&target_dir)
             .map_err(|e| KokoroError::Mod(format!("Failed to remove old mod: {}", e)))?;
     }
     fs::create_dir_all(&target_dir).map_err(KokoroError::from)?;
 
-    const MAX_FILE_SIZE: u64 = 10 * 1024 * 1024;
-    const MAX_TOTAL_SIZE: u64 = 50 * 1024 * 1024;
-
     let mut total_size: u64 = 0;
     for i in 0..archive.len() {
         let mut file = archive
@@ -149,7 +276,233 @@ pub async fn install_mod(
         }
     }
 
-    Ok(manifest)
+    Ok((manifest, previous))
+}
+
+#[derive(Debug, Serialize)]
+pub struct ModExport {
+    pub path: String,
+    pub files: usize,
+    pub bytes: u64,
+    /// Files left out because `install_mod` would not extract them.
+    pub skipped: Vec<String>,
+}
+
+/// Package an installed mod as a `.kokoromod` archive that `install_mod`
+/// accepts. The extension is added to `out_path` when missing.
+#[command]
+pub async fn export_mod(
+    mod_manager: State<'_, Mutex<ModManager>>,
+    mod_id: String,
+    out_path: String,
+) -> Result<ModExport, KokoroError> {
+    if !is_valid_mod_id(&mod_id) {
+        return Err(KokoroError::Validation(format!(
+            "Invalid mod ID '{}'",
+            mod_id
+        )));
+    }
+    let mod_dir = {
+        let manager = mod_manager.lock().await;
+        manager.mods_path.join(&mod_id)
+    };
+    if !mod_dir.is_dir() {
+        return Err(KokoroError::NotFound(format!("Mod '{}' not found", mod_id)));
+    }
+
+    let mut out_path = PathBuf::from(out_path);
+    if out_path.extension().and_then(|e| e.to_str()) != Some(MOD_PACKAGE_EXTENSION) {
+        let mut name = out_path.into_os_string();
+        name.push(format!(".{}", MOD_PACKAGE_EXTENSION));
+        out_path = PathBuf::from(name);
+    }
+    tokio::task::spawn_blocking(move || package_mod(&mod_dir, &out_path))
+        .await
+        .map_err(|e| KokoroError::Internal(e.to_string()))?
+}
+
+fn package_mod(mod_dir: &Path, out_path: &Path) -> Result<ModExport, KokoroError> {
+    let manifest = read_manifest(mod_dir).map_err(KokoroError::Mod)?;
+    if mod_dir.file_name().and_then(|n| n.to_str()) != Some(manifest.id.as_str()) {
+        return Err(KokoroError::Validation(format!(
+            "mod.json id '{}' does not match the mod directory",
+            manifest.id
+        )));
+    }
+    // Writing into the mod itself would package a partial copy of the archive.
+    let out_parent = out_path
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let out_parent = fs::canonicalize(out_parent).map_err(KokoroError::from)?;
+    if out_parent.starts_with(fs::canonicalize(mod_dir).map_err(KokoroError::from)?) {
+        return Err(KokoroError::Validation(
+            "The package cannot be written inside the mod directory".to_string(),
+        ));
+    }
+
+    let (files, skipped) = collect_package_files(mod_dir)?;
+    for referenced in manifest_paths(&manifest) {
+        let normalized = referenced.trim_start_matches("./");
+        if !is_safe_package_path(normalized) {
+            return Err(KokoroError::Validation(format!(
+                "mod.json references unsafe path '{}'",
+                referenced
+            )));
+        }
+        if !files.iter().any(|(name, _, _)| name == normalized) {
+            return Err(KokoroError::Validation(format!(
+                "mod.json references '{}', which would not be packaged",
+                referenced
+            )));
+        }
+    }
+
+    let file = fs::File::create(out_path).map_err(KokoroError::from)?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    let mut bytes = 0;
+    let written: Result<(), KokoroError> = files.iter().try_for_each(|(name, path, size)| {
+        zip.start_file(name.as_str(), options)
+            .map_err(|e| KokoroError::Internal(format!("ZIP error: {}", e)))?;
+        io::copy(&mut fs::File::open(path)?, &mut zip)?;
+        bytes += size;
+        Ok(())
+    });
+    let finished = written.and_then(|()| {
+        zip.finish()
+            .map(drop)
+            .map_err(|e| KokoroError::Internal(format!("ZIP error: {}", e)))
+    });
+    if let Err(e) = finished {
+        let _ = fs::remove_file(out_path);
+        return Err(e);
+    }
+
+    Ok(ModExport {
+        path: out_path.to_string_lossy().into_owned(),
+        files: files.len(),
+        bytes,
+        skipped,
+    })
+}
+
+/// Files referenced by the manifest, relative to the mod directory.
+fn manifest_paths(manifest: &ModManifest) -> Vec<&str> {
+    manifest
+        .layout
+        .iter()
+        .chain(&manifest.theme)
+        .chain(manifest.components.values())
+        .chain(&manifest.scripts)
+        .chain(&manifest.entry)
+        .chain(&manifest.ui_entry)
+        .map(String::as_str)
+        .collect()
+}
+
+/// Relative, `/`-separated and without `..`, `.` or empty segments.
+fn is_safe_package_path(path: &str) -> bool {
+    !path.is_empty()
+        && !path.starts_with('/')
+        && !path.contains('\\')
+        && !path.contains(':')
+        && path
+            .split('/')
+            .all(|segment| !segment.is_empty() && segment != "." && segment != "..")
+}
+
+type PackageFile = (String, PathBuf, u64);
+
+/// Files to package, sorted by archive name, plus the ones left out.
+/// Hidden entries are ignored; symlinks are refused so nothing outside the
+/// mod directory can end up in the archive.
+fn collect_package_files(mod_dir: &Path) -> Result<(Vec<PackageFile>, Vec<String>), KokoroError> {
+    let mut files = Vec::new();
+    let mut skipped = Vec::new();
+    let mut total: u64 = 0;
+    let mut pending = vec![mod_dir.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        for entry in fs::read_dir(&dir).map_err(KokoroError::from)? {
+            let entry = entry.map_err(KokoroError::from)?;
+            let file_name = entry.file_name();
+            let Some(file_name) = file_name.to_str() else {
+                return Err(KokoroError::Validation(format!(
+                    "File name '{}' is not valid UTF-8",
+                    entry.path().display()
+                )));
+            };
+            if file_name.starts_with('.') {
+                continue;
+            }
+            let path = entry.path();
+            let name = path
+                .strip_prefix(mod_dir)
+                .map_err(|e| KokoroError::Internal(e.to_string()))?
+                .to_string_lossy()
+                .replace('\\', "/");
+            let meta = fs::symlink_metadata(&path).map_err(KokoroError::from)?;
+            if meta.file_type().is_symlink() {
+                return Err(KokoroError::Validation(format!(
+                    "'{}' is a symlink; mods must not contain links",
+                    name
+                )));
+            }
+            if meta.is_dir() {
+                pending.push(path);
+                continue;
+            }
+            let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+            if !is_allowed_mod_file(ext) || !is_safe_package_path(&name) {
+                skipped.push(name);
+                continue;
+            }
+            if meta.len() > MAX_FILE_SIZE {
+                return Err(KokoroError::Mod(format!(
+                    "File '{}' exceeds maximum size of 10MB",
+                    name
+                )));
+            }
+            total += meta.len();
+            if total > MAX_TOTAL_SIZE {
+                return Err(KokoroError::Mod(
+                    "MOD package total size exceeds 50MB limit".to_string(),
+                ));
+            }
+            files.push((name, path, meta.len()));
+        }
+    }
+    files.sort_by(|a, b| a.0.cmp(&b.0));
+    skipped.sort();
+    Ok((files, skipped))
+}
+
+/// Permissions the user granted, keyed by mod id.
+#[command]
+pub async fn get_mod_permissions(
+    mod_manager: State<'_, Mutex<ModManager>>,
+) -> Result<HashMap<String, Vec<ModPermission>>, KokoroError> {
+    let manager = mod_manager.lock().await;
+    Ok(manager.permission_grants().grants.clone())
+}
+
+/// Grant a mod exactly `permissions`; applies the next time it loads.
+#[command]
+pub async fn set_mod_permissions(
+    mod_manager: State<'_, Mutex<ModManager>>,
+    mod_id: String,
+    permissions: Vec<ModPermission>,
+) -> Result<(), KokoroError> {
+    if !is_valid_mod_id(&mod_id) {
+        return Err(KokoroError::Validation(format!(
+            "Invalid mod ID '{}'",
+            mod_id
+        )));
+    }
+    let mut manager = mod_manager.lock().await;
+    manager
+        .set_permissions(&mod_id, permissions)
+        .map_err(KokoroError::Config)
 }
 
 #[command]
@@ -179,6 +532,95 @@ pub async fn unload_mod(
 mod tests {
     use super::*;
 
+    #[test]
+    fn packaged_mods_install_back_and_bad_references_are_refused() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let mod_dir = tmp.path().join("clock");
+        fs::create_dir_all(mod_dir.join("scripts")).unwrap();
+        fs::create_dir_all(mod_dir.join(".git")).unwrap();
+        fs::write(
+            mod_dir.join("mod.json"),
+            r#"{"id":"clock","name":"Clock","version":"1.0.0","description":"","scripts":["scripts/main.js"]}"#,
+        )
+        .unwrap();
+        fs::write(mod_dir.join("scripts/main.js"), "Kokoro.on('x', () => {});").unwrap();
+        fs::write(mod_dir.join("build.sh"), "#!/bin/sh").unwrap();
+        fs::write(mod_dir.join(".git/HEAD"), "ref").unwrap();
+
+        let out = tmp.path().join("clock.kokoromod");
+        let export = package_mod(&mod_dir, &out).unwrap();
+        assert_eq!(export.files, 2);
+        assert_eq!(export.skipped, ["build.sh"]);
+
+        let installed = tmp.path().join("installed");
+        let mut archive = zip::ZipArchive::new(fs::File::open(&out).unwrap()).unwrap();
+        assert_eq!(
+            install_archive(&mut archive, &installed, Some("clock"))
+                .unwrap()
+                .0
+                .id,
+            "clock"
+        );
+        assert!(installed.join("clock/scripts/main.js").exists());
+
+        assert!(matches!(
+            package_mod(&mod_dir, &mod_dir.join("self.kokoromod")),
+            Err(KokoroError::Validation(_))
+        ));
+        fs::write(
+            mod_dir.join("mod.json"),
+            r#"{"id":"clock","name":"Clock","version":"1.0.0","description":"","scripts":["../outside.js"]}"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            package_mod(&mod_dir, &out),
+            Err(KokoroError::Validation(_))
+        ));
+
+        assert!(is_safe_package_path("scripts/main.js"));
+        assert!(!is_safe_package_path("scripts//main.js"));
+        assert!(!is_safe_package_path("/etc/passwd"));
+        assert!(!is_safe_package_path("C:/mods/x.js"));
+    }
+
+    #[test]
+    fn registry_installs_check_the_id_and_notice_new_access() {
+        let package = |manifest: &str| {
+            let mut writer = zip::ZipWriter::new(io::Cursor::new(Vec::new()));
+            writer
+                .start_file("mod.json", SimpleFileOptions::default())
+                .unwrap();
+            io::Write::write_all(&mut writer, manifest.as_bytes()).unwrap();
+            zip::ZipArchive::new(writer.finish().unwrap()).unwrap()
+        };
+        let tmp = tempfile::TempDir::new().unwrap();
+        let v1 = r#"{"id":"clock","name":"Clock","version":"1.0.0","description":"","permissions":["storage"]}"#;
+        let other = r#"{"id":"weather","name":"Weather","version":"1.0.0","description":""}"#;
+
+        assert!(matches!(
+            install_archive(&mut package(other), tmp.path(), Some("clock")),
+            Err(KokoroError::Validation(_))
+        ));
+        assert!(!tmp.path().join("weather").exists());
+
+        let (first, previous) =
+            install_archive(&mut package(v1), tmp.path(), Some("clock")).unwrap();
+        assert!(previous.is_none());
+        let (_, previous) = install_archive(&mut package(v1), tmp.path(), Some("clock")).unwrap();
+        let previous = previous.expect("replaced copy");
+        assert!(!access_changed(&previous, &first));
+
+        let with_network = r#"{"id":"clock","name":"Clock","version":"1.1.0","description":"","permissions":["storage","network"],"network_allowlist":["api.example.com"]}"#;
+        let (next, _) =
+            install_archive(&mut package(with_network), tmp.path(), Some("clock")).unwrap();
+        assert!(access_changed(&first, &next));
+        let mut moved = next.clone();
+        moved.network_allowlist = vec!["evil.example.com".to_string()];
+        assert!(access_changed(&next, &moved));
+        moved.network_allowlist = vec!["API.example.com.".to_string()];
+        assert!(!access_changed(&next, &moved));
+    }
+
     #[test]
     fn test_is_valid_mod_id_empty() {
         assert!(!is_valid_mod_id(""), "Empty ID should be invalid");
@@ -0,0 +1,31 @@
+//! Offline mode IPC commands — config and live status.
+
+use crate::error::KokoroError;
+use crate::offline::{OfflineModeConfig, OfflineModeService, OfflineStatus};
+use tauri::State;
+
+#[tauri::command]
+pub async fn get_offline_mode_config(
+    state: State<'_, OfflineModeService>,
+) -> Result<OfflineModeConfig, KokoroError> {
+    Ok(state.get_config().await)
+}
+
+#[tauri::command]
+pub async fn save_offline_mode_config(
+    state: State<'_, OfflineModeService>,
+    app: tauri::AppHandle,
+    config: OfflineModeConfig,
+) -> Result<(), KokoroError> {
+    crate::offline::save_config(&crate::offline::config_path(), &config)?;
+    state.update_config(config).await;
+    crate::offline::refresh_fallbacks(&app).await;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_offline_status(
+    state: State<'_, OfflineModeService>,
+) -> Result<OfflineStatus, KokoroError> {
+    Ok(state.status().await)
+}
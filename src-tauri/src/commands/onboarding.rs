@@ -0,0 +1,109 @@
+//! "About me" onboarding interview IPC commands.
+
+use crate::ai::context::AIOrchestrator;
+use crate::ai::onboarding::{self, OnboardingQuestion, OnboardingStatus};
+use crate::commands::context::update_user_profile_settings;
+use crate::error::KokoroError;
+use std::sync::LazyLock;
+use tauri::{AppHandle, Emitter, State};
+use tokio::sync::Mutex;
+
+/// Serializes read-modify-write cycles on the progress file.
+static PROGRESS_LOCK: LazyLock<Mutex<()>> = LazyLock::new(|| Mutex::new(()));
+
+#[tauri::command]
+pub async fn get_onboarding_status() -> Result<OnboardingStatus, KokoroError> {
+    Ok(onboarding::load_progress(&onboarding::progress_path()).status())
+}
+
+/// Answer the current interview question; a missing or empty `answer` skips
+/// it. A given answer is pinned as a core memory of `character_id` and
+/// written to the user profile right away.
+#[tauri::command]
+pub async fn answer_onboarding_question(
+    character_id: String,
+    question: OnboardingQuestion,
+    answer: Option<String>,
+    app: AppHandle,
+    orchestrator: State<'_, AIOrchestrator>,
+) -> Result<OnboardingStatus, KokoroError> {
+    let _guard = PROGRESS_LOCK.lock().await;
+    let path = onboarding::progress_path();
+    let mut progress = onboarding::load_progress(&path);
+    if progress.next_question() != Some(question) {
+        return Err(KokoroError::Validation(format!(
+            "{:?} is not the current onboarding question",
+            question
+        )));
+    }
+    let answer = answer
+        .map(|answer| answer.trim().to_string())
+        .filter(|answer| !answer.is_empty());
+
+    if let Some(answer) = &answer {
+        if answer.chars().count() > question.max_chars() {
+            return Err(KokoroError::Validation(format!(
+                "Answer must be at most {} characters",
+                question.max_chars()
+            )));
+        }
+        orchestrator
+            .memory_manager
+            .pin_memory(&question.memory(answer), &character_id, None)
+            .await
+            .map_err(|e| KokoroError::Database(e.to_string()))?;
+        let _ = app.emit("memory:updated", &character_id);
+
+        match question.persona_label() {
+            Some(label) => update_user_profile_settings(&app, |settings| {
+                settings.user_persona =
+                    onboarding::merge_persona_line(&settings.user_persona, label, answer);
+            })?,
+            None => {
+                orchestrator.set_user_name(answer.clone()).await;
+                update_user_profile_settings(&app, |settings| {
+                    settings.user_name = answer.clone();
+                })?;
+            }
+        }
+    }
+
+    let finished = progress.record(question, answer, chrono::Utc::now().timestamp());
+    onboarding::save_progress(&path, &progress)?;
+    if finished {
+        if let Some(instruction) = onboarding::completion_instruction(&progress) {
+            let _ = app.emit(
+                "proactive-trigger",
+                serde_json::json!({
+                    "trigger": "onboarding_complete",
+                    "idle_seconds": orchestrator.idle_seconds().await,
+                    "instruction": instruction,
+                }),
+            );
+        }
+    }
+    Ok(progress.status())
+}
+
+/// Close the interview without asking the remaining questions.
+#[tauri::command]
+pub async fn dismiss_onboarding() -> Result<OnboardingStatus, KokoroError> {
+    let _guard = PROGRESS_LOCK.lock().await;
+    let path = onboarding::progress_path();
+    let mut progress = onboarding::load_progress(&path);
+    if progress.completed_at.is_none() {
+        progress.completed_at = Some(chrono::Utc::now().timestamp());
+        onboarding::save_progress(&path, &progress)?;
+    }
+    Ok(progress.status())
+}
+
+/// Start the interview over. Earlier answers stay in memory and the profile
+/// until new ones replace them.
+#[tauri::command]
+pub async fn restart_onboarding() -> Result<OnboardingStatus, KokoroError> {
+    let _guard = PROGRESS_LOCK.lock().await;
+    let progress = onboarding::OnboardingProgress::default();
+    onboarding::save_progress(&onboarding::progress_path(), &progress)?;
+    Ok(progress.status())
+}
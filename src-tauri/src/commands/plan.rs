@@ -0,0 +1,46 @@
+//! Tool plan IPC commands.
+
+use crate::actions::plan::{self, PlanConfig, PlanControlState};
+use crate::error::KokoroError;
+use std::sync::Arc;
+use tauri::State;
+use tokio::sync::RwLock;
+
+#[tauri::command]
+pub async fn get_tool_plan_config(
+    state: State<'_, Arc<RwLock<PlanConfig>>>,
+) -> Result<PlanConfig, KokoroError> {
+    Ok(state.read().await.clone())
+}
+
+#[tauri::command]
+pub async fn save_tool_plan_config(
+    config: PlanConfig,
+    state: State<'_, Arc<RwLock<PlanConfig>>>,
+) -> Result<(), KokoroError> {
+    let sanitized = config.sanitized();
+    plan::save_config(&plan::config_path(), &sanitized)?;
+    *state.write().await = sanitized;
+    Ok(())
+}
+
+/// Answer a step that is waiting for confirmation; declining skips the step.
+#[tauri::command]
+pub async fn confirm_plan_step(
+    plan_id: String,
+    step_index: usize,
+    approve: bool,
+    state: State<'_, Arc<PlanControlState>>,
+) -> Result<(), KokoroError> {
+    state.confirm(&plan_id, step_index, approve).await
+}
+
+/// Stop a plan before its next step; the model still gets the results so far.
+#[tauri::command]
+pub async fn abort_tool_plan(
+    plan_id: String,
+    state: State<'_, Arc<PlanControlState>>,
+) -> Result<(), KokoroError> {
+    state.abort(&plan_id).await;
+    Ok(())
+}
@@ -0,0 +1,67 @@
+//! Scheduler IPC commands — list, add, pause and remove schedules.
+
+use crate::actions::ActionRegistry;
+use crate::ai::context::AIOrchestrator;
+use crate::ai::scheduler::{self, NewSchedule, Schedule, ScheduleAction};
+use crate::error::KokoroError;
+use std::sync::Arc;
+use tauri::State;
+use tokio::sync::RwLock;
+
+#[tauri::command]
+pub async fn list_schedules(
+    orchestrator: State<'_, AIOrchestrator>,
+) -> Result<Vec<Schedule>, KokoroError> {
+    scheduler::list_schedules(&orchestrator.db).await
+}
+
+#[tauri::command]
+pub async fn add_schedule(
+    orchestrator: State<'_, AIOrchestrator>,
+    registry: State<'_, Arc<RwLock<ActionRegistry>>>,
+    request: NewSchedule,
+) -> Result<Schedule, KokoroError> {
+    match &request.action {
+        ScheduleAction::Proactive { instruction } if instruction.trim().is_empty() => {
+            return Err(KokoroError::Validation(
+                "proactive schedule needs an instruction".to_string(),
+            ));
+        }
+        ScheduleAction::Action { name, .. } => {
+            registry
+                .read()
+                .await
+                .resolve_action_for_execution(name)
+                .map_err(|e| KokoroError::Validation(e.to_string()))?;
+        }
+        ScheduleAction::Proactive { .. } => {}
+    }
+    scheduler::add_schedule(&orchestrator.db, request, chrono::Utc::now().timestamp()).await
+}
+
+#[tauri::command]
+pub async fn remove_schedule(
+    orchestrator: State<'_, AIOrchestrator>,
+    id: String,
+) -> Result<(), KokoroError> {
+    if scheduler::remove_schedule(&orchestrator.db, &id).await? {
+        Ok(())
+    } else {
+        Err(KokoroError::NotFound(format!("schedule '{}'", id)))
+    }
+}
+
+#[tauri::command]
+pub async fn set_schedule_enabled(
+    orchestrator: State<'_, AIOrchestrator>,
+    id: String,
+    enabled: bool,
+) -> Result<Schedule, KokoroError> {
+    scheduler::set_schedule_enabled(
+        &orchestrator.db,
+        &id,
+        enabled,
+        chrono::Utc::now().timestamp(),
+    )
+    .await
+}
@@ -0,0 +1,71 @@
+//! Story mode IPC commands — start, play, branch and delete interactive stories.
+
+use crate::ai::context::AIOrchestrator;
+use crate::ai::story::{self, NewStory, Story, StoryMove, StoryService, StoryView};
+use crate::error::KokoroError;
+use tauri::{AppHandle, State};
+
+#[tauri::command]
+pub async fn list_stories(
+    orchestrator: State<'_, AIOrchestrator>,
+) -> Result<Vec<Story>, KokoroError> {
+    story::list_stories(&orchestrator.db).await
+}
+
+#[tauri::command]
+pub async fn get_story(
+    orchestrator: State<'_, AIOrchestrator>,
+    id: String,
+) -> Result<StoryView, KokoroError> {
+    story::story_view(&orchestrator.db, &id).await
+}
+
+/// Create a story and narrate its opening beat.
+#[tauri::command]
+pub async fn start_story(
+    app: AppHandle,
+    state: State<'_, StoryService>,
+    request: NewStory,
+) -> Result<StoryView, KokoroError> {
+    state.start(&app, request).await
+}
+
+/// Continue with a choice or free-text action; continuing from an earlier
+/// beat starts a new branch.
+#[tauri::command]
+pub async fn choose_story_option(
+    app: AppHandle,
+    state: State<'_, StoryService>,
+    id: String,
+    request: StoryMove,
+) -> Result<StoryView, KokoroError> {
+    state.choose(&app, &id, request).await
+}
+
+/// Make another beat the current one without generating anything.
+#[tauri::command]
+pub async fn switch_story_beat(
+    orchestrator: State<'_, AIOrchestrator>,
+    id: String,
+    beat_id: String,
+) -> Result<StoryView, KokoroError> {
+    story::switch_beat(
+        &orchestrator.db,
+        &id,
+        &beat_id,
+        chrono::Utc::now().timestamp(),
+    )
+    .await
+}
+
+#[tauri::command]
+pub async fn delete_story(
+    orchestrator: State<'_, AIOrchestrator>,
+    id: String,
+) -> Result<(), KokoroError> {
+    if story::delete_story(&orchestrator.db, &id).await? {
+        Ok(())
+    } else {
+        Err(KokoroError::NotFound(format!("story '{}'", id)))
+    }
+}
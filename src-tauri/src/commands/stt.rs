@@ -1,12 +1,13 @@
 use crate::error::KokoroError;
 use crate::stt::config::save_config;
+use crate::stt::mic::MicOptions;
 use crate::stt::{
     AudioChunk, AudioSource, NativeMicState, NativeWakeWordState, SenseVoiceLocalModelStatus,
-    SttConfig, SttService,
+    SttConfig, SttService, WhisperLocalModelStatus,
 };
 use std::sync::Arc;
 use tauri::State;
-use tauri::{command, AppHandle};
+use tauri::{command, AppHandle, Manager};
 
 /// Transcribe audio bytes to text using the active STT provider.
 #[command]
@@ -106,12 +107,25 @@ pub async fn start_native_mic(
     mic_state: State<'_, NativeMicState>,
     auto_stop_on_silence: Option<bool>,
 ) -> Result<(), KokoroError> {
+    let barge_in = match app.try_state::<SttService>() {
+        Some(stt) => stt.get_config().await.barge_in,
+        None => false,
+    };
     crate::stt::mic::start_native_mic_with_options(
         &app,
         mic_state.inner(),
-        auto_stop_on_silence.unwrap_or(false),
+        MicOptions {
+            auto_stop_on_silence: auto_stop_on_silence.unwrap_or(false),
+            barge_in,
+        },
     )
-    .map_err(KokoroError::Stt)
+    .map_err(KokoroError::Stt)?;
+    crate::ai::companion_state::set_active(
+        &app,
+        crate::ai::companion_state::CompanionActivity::Listening,
+        true,
+    );
+    Ok(())
 }
 
 #[command]
@@ -119,7 +133,14 @@ pub async fn stop_native_mic(
     app: AppHandle,
     mic_state: State<'_, NativeMicState>,
 ) -> Result<(), KokoroError> {
-    crate::stt::mic::stop_native_mic(&app, mic_state.inner()).map_err(KokoroError::Stt)
+    let result =
+        crate::stt::mic::stop_native_mic(&app, mic_state.inner()).map_err(KokoroError::Stt);
+    crate::ai::companion_state::set_active(
+        &app,
+        crate::ai::companion_state::CompanionActivity::Listening,
+        false,
+    );
+    result
 }
 
 #[command]
@@ -164,3 +185,25 @@ pub async fn download_sensevoice_local_model(
     .await
     .map_err(KokoroError::Stt)
 }
+
+#[command]
+pub async fn get_whisper_local_status() -> Result<WhisperLocalModelStatus, KokoroError> {
+    // GPU detection may spawn `nvidia-smi`, so keep it off the async runtime.
+    tokio::task::spawn_blocking(crate::stt::whisper_local::model_status)
+        .await
+        .map_err(|e| KokoroError::Internal(e.to_string()))
+}
+
+#[command]
+pub async fn download_whisper_local_model(
+    app: tauri::AppHandle,
+    model: String,
+) -> Result<WhisperLocalModelStatus, KokoroError> {
+    use tauri::Emitter;
+    crate::stt::whisper_local::download_model(&model, move |progress| {
+        app.emit("stt:whisper-local-progress", &progress)
+            .map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(KokoroError::Stt)
+}
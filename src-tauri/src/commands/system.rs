@@ -1,4 +1,5 @@
 use crate::ai::context::AIOrchestrator;
+use crate::ai::system_state::{probe_system_state, SystemState};
 use crate::commands::live2d::load_active_live2d_profile;
 use crate::error::KokoroError;
 use serde::{Deserialize, Serialize};
@@ -175,7 +176,37 @@ pub fn get_system_status(app: tauri::AppHandle, state: State<'_, AIOrchestrator>
     }
 }
 
-fn compare_release_versions(left: &str, right: &str) -> i8 {
+/// Returns the latest battery / network snapshot, probing on demand if the
+/// heartbeat has not produced one yet.
+#[tauri::command]
+pub async fn get_system_state(
+    state: State<'_, AIOrchestrator>,
+) -> Result<SystemState, KokoroError> {
+    if let Some(snapshot) = state.get_system_state().await {
+        return Ok(snapshot);
+    }
+    let snapshot = probe_system_state().await;
+    state.set_system_state(snapshot.clone()).await;
+    Ok(snapshot)
+}
+
+/// Returns the crash report left by the previous session, if the user hasn't
+/// dismissed it yet.
+#[tauri::command]
+pub fn get_last_crash_report(
+    store: State<'_, crate::crash::CrashReportStore>,
+) -> Option<crate::crash::CrashReport> {
+    store.last_report()
+}
+
+#[tauri::command]
+pub fn dismiss_crash_report(
+    store: State<'_, crate::crash::CrashReportStore>,
+) -> Result<(), KokoroError> {
+    store.dismiss()
+}
+
+pub(crate) fn compare_release_versions(left: &str, right: &str) -> i8 {
     let left_parts = release_version_parts(left);
     let right_parts = release_version_parts(right);
     let max_len = left_parts.len().max(right_parts.len());
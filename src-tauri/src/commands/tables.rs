@@ -0,0 +1,46 @@
+//! CSV tables the `query_table` tool can read.
+
+use crate::actions::tables::{self, TableStore, TableSummary};
+use crate::error::KokoroError;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tauri::State;
+
+/// Parse a CSV file into an in-memory table; a table with the same name is replaced.
+#[tauri::command]
+pub async fn load_csv_table(
+    path: String,
+    name: Option<String>,
+    store: State<'_, Arc<TableStore>>,
+) -> Result<TableSummary, KokoroError> {
+    let table = tables::load_csv_file(&PathBuf::from(&path), name).await?;
+    tracing::info!(
+        target: "tools",
+        "Loaded CSV table '{}' from {}",
+        table.name,
+        path
+    );
+    Ok(store.insert(table))
+}
+
+#[tauri::command]
+pub async fn list_csv_tables(
+    store: State<'_, Arc<TableStore>>,
+) -> Result<Vec<TableSummary>, KokoroError> {
+    Ok(store.list())
+}
+
+#[tauri::command]
+pub async fn remove_csv_table(
+    name: String,
+    store: State<'_, Arc<TableStore>>,
+) -> Result<(), KokoroError> {
+    if store.remove(&name) {
+        Ok(())
+    } else {
+        Err(KokoroError::NotFound(format!(
+            "Table '{}' is not loaded",
+            name
+        )))
+    }
+}
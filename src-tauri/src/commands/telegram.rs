@@ -56,3 +56,10 @@ pub async fn get_telegram_status(
         has_token: config.resolve_bot_token().is_some(),
     })
 }
+
+#[tauri::command]
+pub async fn create_telegram_pairing_code(
+    state: State<'_, TelegramService>,
+) -> Result<crate::telegram::TelegramPairingCode, KokoroError> {
+    Ok(state.issue_pairing_code())
+}
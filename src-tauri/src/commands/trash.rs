@@ -0,0 +1,54 @@
+//! Trash IPC commands — undo and permanent deletion of soft-deleted items.
+
+use crate::character_profiles::CharacterProfileService;
+use crate::error::KokoroError;
+use crate::trash::{Trash, TrashItem};
+use tauri::State;
+
+/// Trashed items, newest first.
+#[tauri::command]
+pub async fn list_trash(trash: State<'_, Trash>) -> Result<Vec<TrashItem>, KokoroError> {
+    trash.list().await
+}
+
+#[tauri::command]
+pub async fn restore_from_trash(
+    id: i64,
+    trash: State<'_, Trash>,
+    profiles: State<'_, CharacterProfileService>,
+) -> Result<TrashItem, KokoroError> {
+    let item = trash.restore(id).await?;
+    restore_profile(&item, &profiles).await?;
+    Ok(item)
+}
+
+/// Restore the most recently deleted item; `None` when the trash is empty.
+#[tauri::command]
+pub async fn undo_last_delete(
+    trash: State<'_, Trash>,
+    profiles: State<'_, CharacterProfileService>,
+) -> Result<Option<TrashItem>, KokoroError> {
+    let item = trash.undo_last().await?;
+    if let Some(item) = &item {
+        restore_profile(item, &profiles).await?;
+    }
+    Ok(item)
+}
+
+/// Permanently delete everything in the trash. Returns how many items went.
+#[tauri::command]
+pub async fn empty_trash(trash: State<'_, Trash>) -> Result<usize, KokoroError> {
+    trash.empty().await
+}
+
+/// Store the bindings a restored character had before it was deleted.
+async fn restore_profile(
+    item: &TrashItem,
+    profiles: &CharacterProfileService,
+) -> Result<(), KokoroError> {
+    let Some(profile) = &item.profile else {
+        return Ok(());
+    };
+    let profile = serde_json::from_value(profile.clone())?;
+    profiles.set_profile(&item.item_id, Some(profile)).await
+}
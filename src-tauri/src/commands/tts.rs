@@ -1,15 +1,30 @@
+use crate::ai::context::AIOrchestrator;
+use crate::assets::{AssetCategory, AssetStore};
 use crate::error::KokoroError;
 use crate::tts::config::{save_config, TtsSystemConfig};
+use crate::tts::podcast::{self, Chapter, RenderedSegment};
+use crate::tts::queue::TtsPriority;
 use crate::tts::{ProviderStatus, TtsParams, TtsService, VoiceProfile};
-use tauri::{command, AppHandle, State};
+use serde::{Deserialize, Serialize};
+use tauri::{command, AppHandle, Emitter, Manager, State};
 
-#[derive(serde::Deserialize)]
+#[derive(Deserialize, Default)]
 pub struct TtsConfig {
     pub provider_id: Option<String>,
     pub voice: Option<String>,
     pub speed: Option<f32>,
     pub pitch: Option<f32>,
     pub emotion: Option<String>,
+    /// Condition the voice on the character's current emotion (chat replies).
+    /// Ignored when `emotion` is set explicitly.
+    #[serde(default)]
+    pub follow_emotion: bool,
+    /// Only used by `synthesize`; see [`TtsPriority`].
+    #[serde(default)]
+    pub priority: TtsPriority,
+    /// Newer utterances from the same source replace this one while it waits.
+    #[serde(default)]
+    pub source: Option<String>,
 }
 
 #[command]
@@ -19,7 +34,7 @@ pub async fn synthesize(
     text: String,
     config: TtsConfig,
 ) -> Result<(), KokoroError> {
-    let params = TtsParams {
+    let mut params = TtsParams {
         voice: config.voice,
         speed: config.speed,
         pitch: config.pitch,
@@ -27,13 +42,224 @@ pub async fn synthesize(
         required_capabilities: None,
         extra_params: None,
     };
+    if config.follow_emotion && params.emotion.is_none() {
+        if let Some(orchestrator) = app.try_state::<AIOrchestrator>() {
+            let current = orchestrator.emotion.lock().await.state();
+            crate::tts::emotion_tts::condition_params(
+                &mut params,
+                current.emotion,
+                current.intensity,
+                current.mood,
+            );
+        }
+    }
 
     state
-        .speak(app, text, config.provider_id, Some(params))
+        .speak(
+            app,
+            text,
+            config.provider_id,
+            Some(params),
+            config.priority,
+            config.source,
+        )
         .await
         .map_err(KokoroError::Tts)
 }
 
+impl TtsConfig {
+    fn params(&self) -> TtsParams {
+        TtsParams {
+            voice: self.voice.clone(),
+            speed: self.speed,
+            pitch: self.pitch,
+            emotion: self.emotion.clone(),
+            required_capabilities: None,
+            extra_params: None,
+        }
+    }
+}
+
+fn default_gap_ms() -> u32 {
+    600
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Deserialize)]
+pub struct ExportConversationAudioRequest {
+    pub conversation_id: String,
+    /// Destination file; defaults to `exports/` in the app data dir. The extension
+    /// is replaced to match the container the providers produced.
+    #[serde(default)]
+    pub output_path: Option<String>,
+    #[serde(default)]
+    pub user_voice: Option<TtsConfig>,
+    #[serde(default)]
+    pub assistant_voice: Option<TtsConfig>,
+    #[serde(default = "default_true")]
+    pub include_user: bool,
+    /// Silence between turns (WAV output only).
+    #[serde(default = "default_gap_ms")]
+    pub gap_ms: u32,
+}
+
+#[derive(Serialize)]
+pub struct ConversationAudioExport {
+    pub path: String,
+    pub format: String,
+    pub duration_ms: u64,
+    pub chapters: Vec<Chapter>,
+}
+
+#[derive(Clone, Serialize)]
+struct ConversationAudioExportProgress {
+    conversation_id: String,
+    current: usize,
+    total: usize,
+}
+
+/// Render a whole conversation to one audio file with a chapter per message.
+/// Emits `tts:export-progress` after each synthesized message.
+#[command]
+pub async fn export_conversation_audio(
+    app: AppHandle,
+    state: State<'_, TtsService>,
+    orchestrator: State<'_, AIOrchestrator>,
+    request: ExportConversationAudioRequest,
+) -> Result<ConversationAudioExport, KokoroError> {
+    let rows = sqlx::query_as::<_, (String, String, Option<String>)>(
+        "SELECT role, content, metadata FROM conversation_messages WHERE conversation_id = ? AND is_active = 1 ORDER BY id ASC",
+    )
+    .bind(&request.conversation_id)
+    .fetch_all(&orchestrator.db)
+    .await
+    .map_err(|e| KokoroError::Database(e.to_string()))?;
+
+    let messages: Vec<(String, String)> = rows
+        .into_iter()
+        .filter(|(role, _, metadata)| {
+            let technical = metadata
+                .as_deref()
+                .and_then(|raw| serde_json::from_str::<serde_json::Value>(raw).ok())
+                .and_then(|meta| meta.get("type").and_then(|t| t.as_str()).map(str::to_owned))
+                .is_some_and(|t| t == "assistant_tool_calls" || t == "translation_instruction");
+            !technical && (role == "assistant" || (role == "user" && request.include_user))
+        })
+        .map(|(role, content, _)| {
+            let text = crate::chat::tags::strip_translate_tags(
+                &crate::chat::tags::strip_leaked_tags(&content),
+            );
+            (role, text)
+        })
+        .filter(|(_, text)| !text.is_empty())
+        .collect();
+
+    if messages.is_empty() {
+        return Err(KokoroError::NotFound(format!(
+            "Conversation {} has no speakable messages",
+            request.conversation_id
+        )));
+    }
+
+    let character_name = orchestrator.get_character_name().await;
+    let user_voice = request.user_voice.unwrap_or_default();
+    let assistant_voice = request.assistant_voice.unwrap_or_default();
+    let total = messages.len();
+    let mut segments = Vec::with_capacity(total);
+
+    for (index, (role, text)) in messages.into_iter().enumerate() {
+        let (voice, speaker) = if role == "user" {
+            (&user_voice, "User")
+        } else {
+            (&assistant_voice, character_name.as_str())
+        };
+        let audio = state
+            .synthesize_text_with_provider(
+                &text,
+                voice.provider_id.as_deref(),
+                Some(voice.params()),
+            )
+            .await
+            .map_err(|e| KokoroError::Tts(format!("message {}: {}", index + 1, e)))?;
+        segments.push(RenderedSegment {
+            title: podcast::chapter_title(speaker, &text),
+            role,
+            audio,
+        });
+        let _ = app.emit(
+            "tts:export-progress",
+            ConversationAudioExportProgress {
+                conversation_id: request.conversation_id.clone(),
+                current: index + 1,
+                total,
+            },
+        );
+    }
+
+    let assembled = podcast::assemble(&segments, request.gap_ms).map_err(KokoroError::Tts)?;
+
+    let path = match request.output_path {
+        Some(path) => {
+            let path = std::path::PathBuf::from(path).with_extension(assembled.extension);
+            if let Some(parent) = path.parent() {
+                tokio::fs::create_dir_all(parent).await?;
+            }
+            tokio::fs::write(&path, &assembled.bytes).await?;
+            path
+        }
+        // Without an explicit destination the render is a cache-like artifact:
+        // keep it in the asset store so it counts against the audio quota.
+        None => match app.try_state::<AssetStore>() {
+            Some(store) => std::path::PathBuf::from(
+                store
+                    .put(
+                        AssetCategory::TtsAudio,
+                        &assembled.bytes,
+                        assembled.extension,
+                    )
+                    .await?
+                    .path,
+            ),
+            None => {
+                let path = dirs_next::data_dir()
+                    .unwrap_or_else(|| std::path::PathBuf::from("."))
+                    .join("com.chyin.kokoro")
+                    .join("exports")
+                    .join(format!(
+                        "conversation_{}_{}.{}",
+                        request.conversation_id,
+                        chrono::Local::now().format("%Y%m%d_%H%M%S"),
+                        assembled.extension
+                    ));
+                if let Some(parent) = path.parent() {
+                    tokio::fs::create_dir_all(parent).await?;
+                }
+                tokio::fs::write(&path, &assembled.bytes).await?;
+                path
+            }
+        },
+    };
+
+    tracing::info!(
+        target: "tts",
+        "Exported conversation {} to {} ({} chapters, {} ms)",
+        request.conversation_id,
+        path.display(),
+        assembled.chapters.len(),
+        assembled.duration_ms
+    );
+
+    Ok(ConversationAudioExport {
+        path: path.to_string_lossy().to_string(),
+        format: assembled.extension.to_string(),
+        duration_ms: assembled.duration_ms,
+        chapters: assembled.chapters,
+    })
+}
+
 #[command]
 pub async fn list_tts_providers(
     state: State<'_, TtsService>,
@@ -56,6 +282,27 @@ pub async fn get_tts_provider_status(
     Ok(state.get_provider_status(&provider_id).await)
 }
 
+/// Cut off the utterance in progress and flush queued playback.
+#[command]
+pub async fn stop_speaking(
+    app: AppHandle,
+    state: State<'_, TtsService>,
+) -> Result<(), KokoroError> {
+    state.stop_speaking(&app, "user");
+    Ok(())
+}
+
+/// Playback position of the current utterance, which paces prefetching.
+#[command]
+pub async fn report_tts_playback(
+    state: State<'_, TtsService>,
+    utterance_id: u64,
+    position_ms: u64,
+) -> Result<(), KokoroError> {
+    state.report_playback(utterance_id, position_ms);
+    Ok(())
+}
+
 #[command]
 pub async fn clear_tts_cache(state: State<'_, TtsService>) -> Result<(), KokoroError> {
     state.clear_cache().await;
@@ -82,6 +329,7 @@ pub async fn save_tts_config(
         .unwrap_or_else(|| std::path::PathBuf::from("."))
         .join("com.chyin.kokoro");
     let config_path = app_data.join("tts_config.json");
+    config.normalization.validate()?;
 
     // Write to disk
     save_config(&config_path, &config)?;
@@ -4,10 +4,11 @@ use crate::vision::capture::{
 };
 use crate::vision::config::VisionConfig;
 use crate::vision::context::{VisionObservation, VisionObservationSource};
+use crate::vision::image_files::{FolderDescription, ImageDescription, ImageDescriptionCache};
 use crate::vision::server::VisionServer;
 use crate::vision::watcher::VisionWatcher;
 use std::sync::Arc;
-use tauri::{AppHandle, State};
+use tauri::{AppHandle, Emitter, State};
 use tokio::sync::Mutex;
 
 #[tauri::command]
@@ -128,3 +129,39 @@ pub async fn capture_screen_now(
         .await;
     Ok(description)
 }
+
+/// Describe a local image file (e.g. one dropped onto the window).
+#[tauri::command]
+pub async fn analyze_image_file(
+    state: State<'_, VisionWatcher>,
+    cache: State<'_, Arc<ImageDescriptionCache>>,
+    path: String,
+    question: Option<String>,
+) -> Result<ImageDescription, KokoroError> {
+    crate::vision::image_files::describe_image_file(&state, &cache, &path, question.as_deref())
+        .await
+        .map_err(KokoroError::ExternalService)
+}
+
+/// Describe the most recent images in a folder, emitting `vision:image-batch-progress`.
+#[tauri::command]
+pub async fn analyze_image_folder(
+    app_handle: AppHandle,
+    state: State<'_, VisionWatcher>,
+    cache: State<'_, Arc<ImageDescriptionCache>>,
+    path: String,
+    limit: Option<usize>,
+) -> Result<FolderDescription, KokoroError> {
+    crate::vision::image_files::describe_folder(&state, &cache, &path, limit, |progress| {
+        let _ = app_handle.emit("vision:image-batch-progress", &progress);
+    })
+    .await
+    .map_err(KokoroError::ExternalService)
+}
+
+#[tauri::command]
+pub async fn clear_image_descriptions(
+    cache: State<'_, Arc<ImageDescriptionCache>>,
+) -> Result<usize, KokoroError> {
+    Ok(cache.clear())
+}
@@ -0,0 +1,87 @@
+//! Voice command IPC — grammar config and routing of transcribed utterances.
+
+use crate::ai::context::AIOrchestrator;
+use crate::error::KokoroError;
+use crate::stt::voice_commands::{self, VoiceCommandConfig, VoiceIntent};
+use serde::Serialize;
+use tauri::State;
+
+/// A recognized command, resolved far enough for the frontend to run it.
+#[derive(Debug, Clone, Serialize)]
+pub struct VoiceCommandOutcome {
+    pub intent: VoiceIntent,
+    /// Resolved target of `switch_character`.
+    pub character_id: Option<String>,
+    pub character_name: Option<String>,
+}
+
+#[tauri::command]
+pub async fn get_voice_command_config() -> Result<VoiceCommandConfig, KokoroError> {
+    Ok(voice_commands::load_config(
+        &voice_commands::voice_commands_config_path(),
+    ))
+}
+
+#[tauri::command]
+pub async fn save_voice_command_config(config: VoiceCommandConfig) -> Result<(), KokoroError> {
+    config.validate()?;
+    voice_commands::save_config(&voice_commands::voice_commands_config_path(), &config)
+}
+
+/// Check a final transcript for a control phrase. `None` means the text is an
+/// ordinary message and should go to the LLM.
+#[tauri::command]
+pub async fn route_voice_command(
+    text: String,
+    orchestrator: State<'_, AIOrchestrator>,
+) -> Result<Option<VoiceCommandOutcome>, KokoroError> {
+    let config = voice_commands::load_config(&voice_commands::voice_commands_config_path());
+    let Some(matched) = voice_commands::match_command(&config, &text) else {
+        return Ok(None);
+    };
+
+    let (character_id, character_name) = match (matched.intent, matched.argument.as_deref()) {
+        (VoiceIntent::SwitchCharacter, Some(spoken)) => {
+            let characters: Vec<(String, String)> =
+                sqlx::query_as("SELECT id, name FROM characters ORDER BY created_at ASC")
+                    .fetch_all(&orchestrator.db)
+                    .await?;
+            match resolve_character(&characters, spoken) {
+                Some((id, name)) => (Some(id.clone()), Some(name.clone())),
+                None => {
+                    tracing::info!(
+                        target: "stt",
+                        "[VoiceCommand] No character matches '{}', sending as chat",
+                        spoken
+                    );
+                    return Ok(None);
+                }
+            }
+        }
+        _ => (None, None),
+    };
+
+    tracing::info!(target: "stt", "[VoiceCommand] Recognized {:?}", matched.intent);
+    Ok(Some(VoiceCommandOutcome {
+        intent: matched.intent,
+        character_id,
+        character_name,
+    }))
+}
+
+/// Exact name first, then a name containing (or contained in) what was heard.
+fn resolve_character<'a>(
+    characters: &'a [(String, String)],
+    spoken: &str,
+) -> Option<&'a (String, String)> {
+    let spoken = voice_commands::normalize(spoken);
+    characters
+        .iter()
+        .find(|(_, name)| voice_commands::normalize(name) == spoken)
+        .or_else(|| {
+            characters.iter().find(|(_, name)| {
+                let name = voice_commands::normalize(name);
+                !name.is_empty() && (name.contains(&spoken) || spoken.contains(&name))
+            })
+        })
+}
@@ -0,0 +1,46 @@
+//! Voice session IPC commands — start and stop the hands-free conversation loop.
+
+use crate::commands::tts::TtsConfig;
+use crate::error::KokoroError;
+use crate::tts::TtsParams;
+use crate::voice_session::{VoiceSessionService, VoiceSessionStatus, VoiceSettings};
+use tauri::{AppHandle, State};
+
+/// `tts` takes the same settings as chat auto-speak; `priority` and `source`
+/// are ignored.
+#[tauri::command]
+pub async fn start_voice_session(
+    app: AppHandle,
+    state: State<'_, VoiceSessionService>,
+    tts: Option<TtsConfig>,
+) -> Result<VoiceSessionStatus, KokoroError> {
+    let tts = tts.unwrap_or_default();
+    let voice = VoiceSettings {
+        provider_id: tts.provider_id,
+        params: TtsParams {
+            voice: tts.voice,
+            speed: tts.speed,
+            pitch: tts.pitch,
+            emotion: tts.emotion,
+            required_capabilities: None,
+            extra_params: None,
+        },
+        follow_emotion: tts.follow_emotion,
+    };
+    state.start(&app, voice).await.map_err(KokoroError::Stt)
+}
+
+#[tauri::command]
+pub async fn stop_voice_session(
+    app: AppHandle,
+    state: State<'_, VoiceSessionService>,
+) -> Result<VoiceSessionStatus, KokoroError> {
+    state.stop(&app).await.map_err(KokoroError::Stt)
+}
+
+#[tauri::command]
+pub async fn get_voice_session_status(
+    state: State<'_, VoiceSessionService>,
+) -> Result<VoiceSessionStatus, KokoroError> {
+    Ok(state.status().await)
+}
@@ -0,0 +1,63 @@
+//! VTube Studio integration IPC commands.
+
+use crate::error::KokoroError;
+use crate::vts::{VtsConfig, VtsHotkey, VtsService, VtsStatus};
+use tauri::State;
+
+#[tauri::command]
+pub async fn get_vts_config(state: State<'_, VtsService>) -> Result<VtsConfig, KokoroError> {
+    Ok(state.get_config().await)
+}
+
+/// Saves settings. The stored plugin token is kept unless the caller sends one.
+#[tauri::command]
+pub async fn save_vts_config(
+    state: State<'_, VtsService>,
+    mut config: VtsConfig,
+) -> Result<(), KokoroError> {
+    if !config.url.starts_with("ws://") && !config.url.starts_with("wss://") {
+        return Err(KokoroError::Validation(
+            "VTube Studio URL must start with ws:// or wss://".to_string(),
+        ));
+    }
+    if config.auth_token.is_none() {
+        config.auth_token = state.get_config().await.auth_token;
+    }
+    crate::vts::save_config(&crate::vts::config_path(), &config)?;
+    state.update_config(config).await;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn start_vts(state: State<'_, VtsService>) -> Result<VtsStatus, KokoroError> {
+    state.start().await.map_err(KokoroError::ExternalService)?;
+    Ok(state.status().await)
+}
+
+#[tauri::command]
+pub async fn stop_vts(state: State<'_, VtsService>) -> Result<(), KokoroError> {
+    state.stop().await.map_err(KokoroError::ExternalService)
+}
+
+#[tauri::command]
+pub async fn get_vts_status(state: State<'_, VtsService>) -> Result<VtsStatus, KokoroError> {
+    Ok(state.status().await)
+}
+
+#[tauri::command]
+pub async fn list_vts_hotkeys(state: State<'_, VtsService>) -> Result<Vec<VtsHotkey>, KokoroError> {
+    state
+        .list_hotkeys()
+        .await
+        .map_err(KokoroError::ExternalService)
+}
+
+/// Called by the frontend audio player with the current playback amplitude.
+#[tauri::command]
+pub async fn set_vts_mouth_level(
+    state: State<'_, VtsService>,
+    level: f32,
+) -> Result<(), KokoroError> {
+    state.set_mouth_level(level).await;
+    Ok(())
+}
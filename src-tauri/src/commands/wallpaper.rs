@@ -0,0 +1,65 @@
+//! Desktop wallpaper IPC commands — config, history, apply and revert.
+
+use crate::error::KokoroError;
+use crate::wallpaper::{WallpaperConfig, WallpaperEntry, WallpaperHistory, WallpaperService};
+use tauri::{AppHandle, State};
+
+#[tauri::command]
+pub async fn get_wallpaper_config(
+    state: State<'_, WallpaperService>,
+) -> Result<WallpaperConfig, KokoroError> {
+    Ok(state.get_config().await)
+}
+
+#[tauri::command]
+pub async fn save_wallpaper_config(
+    state: State<'_, WallpaperService>,
+    config: WallpaperConfig,
+) -> Result<(), KokoroError> {
+    crate::wallpaper::save_config(&crate::wallpaper::config_path(), &config)?;
+    state.update_config(config).await;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_wallpaper_history(
+    state: State<'_, WallpaperService>,
+) -> Result<WallpaperHistory, KokoroError> {
+    Ok(state.history().await)
+}
+
+/// Set a local image as the wallpaper. User-initiated, so not rate limited.
+#[tauri::command]
+pub async fn set_wallpaper_image(
+    app: AppHandle,
+    state: State<'_, WallpaperService>,
+    path: String,
+) -> Result<WallpaperEntry, KokoroError> {
+    state.apply_image(&app, &path, None).await
+}
+
+/// Set a gradient for `mood`, or for the character's current emotion.
+#[tauri::command]
+pub async fn set_wallpaper_palette(
+    app: AppHandle,
+    state: State<'_, WallpaperService>,
+    mood: Option<String>,
+) -> Result<WallpaperEntry, KokoroError> {
+    state.apply_palette(&app, mood.as_deref()).await
+}
+
+#[tauri::command]
+pub async fn revert_wallpaper(
+    app: AppHandle,
+    state: State<'_, WallpaperService>,
+) -> Result<Option<String>, KokoroError> {
+    state.revert(&app).await
+}
+
+#[tauri::command]
+pub async fn restore_original_wallpaper(
+    app: AppHandle,
+    state: State<'_, WallpaperService>,
+) -> Result<Option<String>, KokoroError> {
+    state.restore_original(&app).await
+}
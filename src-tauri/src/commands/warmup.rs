@@ -0,0 +1,33 @@
+//! Provider warm-up IPC commands — config and typing notifications.
+
+use crate::error::KokoroError;
+use crate::warmup::{WarmupConfig, WarmupService};
+use tauri::State;
+
+#[tauri::command]
+pub async fn get_warmup_config(
+    state: State<'_, WarmupService>,
+) -> Result<WarmupConfig, KokoroError> {
+    Ok(state.get_config().await)
+}
+
+#[tauri::command]
+pub async fn save_warmup_config(
+    state: State<'_, WarmupService>,
+    config: WarmupConfig,
+) -> Result<(), KokoroError> {
+    config.validate()?;
+    crate::warmup::save_config(&crate::warmup::config_path(), &config)?;
+    state.update_config(config).await;
+    Ok(())
+}
+
+/// Called by the chat input when the user starts composing a message.
+#[tauri::command]
+pub async fn notify_typing(
+    state: State<'_, WarmupService>,
+    app: tauri::AppHandle,
+) -> Result<(), KokoroError> {
+    state.notify_typing(&app).await;
+    Ok(())
+}
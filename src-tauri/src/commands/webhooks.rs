@@ -0,0 +1,38 @@
+//! Webhook IPC commands — outbound webhook config, testing and delivery log.
+
+use crate::ai::context::AIOrchestrator;
+use crate::error::KokoroError;
+use crate::webhooks::{WebhookConfig, WebhookDelivery, WebhookService};
+use tauri::State;
+
+#[tauri::command]
+pub async fn get_webhook_config(
+    state: State<'_, WebhookService>,
+) -> Result<WebhookConfig, KokoroError> {
+    Ok(state.get_config().await)
+}
+
+#[tauri::command]
+pub async fn save_webhook_config(
+    state: State<'_, WebhookService>,
+    config: WebhookConfig,
+) -> Result<(), KokoroError> {
+    crate::webhooks::validate(&config)?;
+    crate::webhooks::save_config(&crate::webhooks::config_path(), &config)?;
+    state.update_config(config).await;
+    Ok(())
+}
+
+/// Post a test event to one webhook right away; errors come back to the caller.
+#[tauri::command]
+pub async fn test_webhook(state: State<'_, WebhookService>, id: String) -> Result<(), KokoroError> {
+    state.send_test(&id).await
+}
+
+#[tauri::command]
+pub async fn list_webhook_deliveries(
+    orchestrator: State<'_, AIOrchestrator>,
+    limit: Option<i64>,
+) -> Result<Vec<WebhookDelivery>, KokoroError> {
+    crate::webhooks::list_deliveries(&orchestrator.db, limit.unwrap_or(50).clamp(1, 500)).await
+}
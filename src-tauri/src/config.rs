@@ -92,6 +92,11 @@ pub struct MemoryUpgradeConfig {
     pub dream_auto_apply_level: String,
     pub dream_daily_hour: u8,
     pub dream_review_required_for_conflicts: bool,
+    /// Cosine similarity (percent) below which a user turn starts a new topic
+    /// and the previous one is extracted.
+    pub topic_shift_similarity_pct: u8,
+    /// User turns on one topic before it is extracted anyway.
+    pub topic_max_turns: u32,
 }
 
 impl Default for MemoryUpgradeConfig {
@@ -107,6 +112,8 @@ impl Default for MemoryUpgradeConfig {
             dream_auto_apply_level: "aggressive".to_string(),
             dream_daily_hour: 3,
             dream_review_required_for_conflicts: true,
+            topic_shift_similarity_pct: 40,
+            topic_max_turns: 12,
         }
     }
 }
@@ -128,6 +135,8 @@ fn normalize_memory_upgrade_config(config: MemoryUpgradeConfig) -> MemoryUpgrade
         dream_auto_apply_level: auto_apply_level,
         dream_daily_hour: config.dream_daily_hour,
         dream_review_required_for_conflicts: true,
+        topic_shift_similarity_pct: config.topic_shift_similarity_pct,
+        topic_max_turns: config.topic_max_turns,
     }
 }
 
@@ -144,6 +153,16 @@ pub fn validate_memory_upgrade_config(
             "dream_daily_hour must be between 0 and 23".to_string(),
         ));
     }
+    if config.topic_shift_similarity_pct > 100 {
+        return Err(KokoroError::Validation(
+            "topic_shift_similarity_pct must be between 0 and 100".to_string(),
+        ));
+    }
+    if config.topic_max_turns < 2 {
+        return Err(KokoroError::Validation(
+            "topic_max_turns must be at least 2".to_string(),
+        ));
+    }
 
     Ok(normalize_memory_upgrade_config(config))
 }
@@ -184,6 +203,8 @@ mod tests {
                 dream_auto_apply_level: "aggressive".to_string(),
                 dream_daily_hour: 3,
                 dream_review_required_for_conflicts: true,
+                topic_shift_similarity_pct: 40,
+                topic_max_turns: 12,
             }
         );
     }
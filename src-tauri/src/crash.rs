@@ -0,0 +1,294 @@
+//! Crash Reports — local-only panic capture.
+//!
+//! A panic hook writes the backtrace, the last log lines and the subsystems
+//! that were running into `crash_reports/` under the app data dir. Nothing is
+//! uploaded; on the next launch the frontend offers the report so the user can
+//! read it or attach it to an issue. Home-directory paths are replaced with `~`
+//! so reports can be shared as-is.
+
+use std::collections::{BTreeSet, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::KokoroError;
+
+/// Log lines kept in memory for the next report.
+const LOG_TAIL_LINES: usize = 200;
+/// Older reports beyond this count are deleted when a new one is written.
+const MAX_REPORTS: usize = 10;
+const DISMISSED_FILE: &str = "dismissed";
+
+static LOG_TAIL: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+static SUBSYSTEMS: Mutex<BTreeSet<&'static str>> = Mutex::new(BTreeSet::new());
+static CRASH_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CrashReport {
+    pub id: String,
+    pub created_at: String,
+    pub app_version: String,
+    pub os: String,
+    pub arch: String,
+    pub thread: String,
+    pub message: String,
+    pub location: Option<String>,
+    pub backtrace: String,
+    pub active_subsystems: Vec<String>,
+    pub recent_logs: Vec<String>,
+    /// Where the report lives on disk; filled in when it is loaded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+}
+
+pub fn crash_dir(app_data_dir: &Path) -> PathBuf {
+    app_data_dir.join("crash_reports")
+}
+
+/// Called by the log formatter for every line (without color codes).
+pub fn record_log_line(line: String) {
+    // try_lock: a panic raised while logging must not deadlock the hook.
+    if let Ok(mut tail) = LOG_TAIL.try_lock() {
+        if tail.len() == LOG_TAIL_LINES {
+            tail.pop_front();
+        }
+        tail.push_back(line);
+    }
+}
+
+/// Marks a subsystem as running (or stopped) for future crash reports.
+pub fn set_subsystem_active(name: &'static str, active: bool) {
+    if let Ok(mut set) = SUBSYSTEMS.lock() {
+        if active {
+            set.insert(name);
+        } else {
+            set.remove(name);
+        }
+    }
+}
+
+/// Installs the panic hook. The previous hook still runs afterwards so the
+/// panic is printed to stderr as usual.
+pub fn install_panic_hook(dir: PathBuf) {
+    if CRASH_DIR.set(dir).is_err() {
+        return;
+    }
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        if let Some(dir) = CRASH_DIR.get() {
+            let report = build_report(info);
+            if let Err(e) = write_report(dir, &report) {
+                eprintln!("[crash] failed to write crash report: {}", e);
+            }
+        }
+        previous(info);
+    }));
+}
+
+fn build_report(info: &std::panic::PanicHookInfo<'_>) -> CrashReport {
+    let message = info
+        .payload()
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| info.payload().downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "non-string panic payload".to_string());
+    let location = info
+        .location()
+        .map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column()));
+    let thread = std::thread::current()
+        .name()
+        .unwrap_or("<unnamed>")
+        .to_string();
+    let backtrace = std::backtrace::Backtrace::force_capture().to_string();
+    let home = dirs_next::home_dir();
+    let scrub = |text: &str| anonymize(text, home.as_deref());
+    let recent_logs = LOG_TAIL
+        .try_lock()
+        .map(|tail| tail.iter().map(|line| scrub(line)).collect())
+        .unwrap_or_default();
+    let active_subsystems = SUBSYSTEMS
+        .try_lock()
+        .map(|set| set.iter().map(|s| s.to_string()).collect())
+        .unwrap_or_default();
+
+    let now = chrono::Utc::now();
+    CrashReport {
+        id: format!("crash-{}", now.format("%Y%m%d-%H%M%S-%3f")),
+        created_at: now.to_rfc3339(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        thread,
+        message: scrub(&message),
+        location,
+        backtrace: scrub(&backtrace),
+        active_subsystems,
+        recent_logs,
+        path: None,
+    }
+}
+
+/// Replaces the user's home directory (and so their account name) with `~`.
+fn anonymize(text: &str, home: Option<&Path>) -> String {
+    let Some(home) = home.map(|h| h.to_string_lossy()) else {
+        return text.to_string();
+    };
+    if home.len() <= 1 {
+        return text.to_string();
+    }
+    let mut out = text.replace(home.as_ref(), "~");
+    // Backtraces on Windows may use forward slashes.
+    let alt = home.replace('\\', "/");
+    if alt != home {
+        out = out.replace(&alt, "~");
+    }
+    out
+}
+
+fn write_report(dir: &Path, report: &CrashReport) -> std::io::Result<()> {
+    std::fs::create_dir_all(dir)?;
+    let json = serde_json::to_string_pretty(report)?;
+    std::fs::write(dir.join(format!("{}.json", report.id)), json)?;
+    prune_reports(dir);
+    Ok(())
+}
+
+fn report_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok().map(|e| e.path()))
+                .filter(|p| {
+                    p.extension().is_some_and(|ext| ext == "json")
+                        && p.file_name()
+                            .is_some_and(|n| n.to_string_lossy().starts_with("crash-"))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    // Ids embed the timestamp, so name order is age order.
+    files.sort();
+    files
+}
+
+fn prune_reports(dir: &Path) {
+    let files = report_files(dir);
+    if files.len() > MAX_REPORTS {
+        for old in &files[..files.len() - MAX_REPORTS] {
+            let _ = std::fs::remove_file(old);
+        }
+    }
+}
+
+/// Newest report the user hasn't dismissed, if any.
+fn load_pending(dir: &Path) -> Option<CrashReport> {
+    let newest = report_files(dir).pop()?;
+    let dismissed = std::fs::read_to_string(dir.join(DISMISSED_FILE)).unwrap_or_default();
+    let raw = std::fs::read_to_string(&newest).ok()?;
+    let mut report: CrashReport = serde_json::from_str(&raw).ok()?;
+    if dismissed.trim() == report.id {
+        return None;
+    }
+    report.path = Some(newest.to_string_lossy().to_string());
+    Some(report)
+}
+
+/// The crash report left by a previous session, read once at startup.
+pub struct CrashReportStore {
+    dir: PathBuf,
+    pending: Mutex<Option<CrashReport>>,
+}
+
+impl CrashReportStore {
+    pub fn load(dir: PathBuf) -> Self {
+        let pending = load_pending(&dir);
+        if let Some(report) = &pending {
+            tracing::warn!(
+                target: "startup",
+                "previous session crashed: {} ({})",
+                report.message,
+                report.id
+            );
+        }
+        Self {
+            dir,
+            pending: Mutex::new(pending),
+        }
+    }
+
+    pub fn last_report(&self) -> Option<CrashReport> {
+        self.pending.lock().ok().and_then(|p| p.clone())
+    }
+
+    /// Stops offering the current report. The file itself is kept.
+    pub fn dismiss(&self) -> Result<(), KokoroError> {
+        let taken = self.pending.lock().ok().and_then(|mut p| p.take());
+        if let Some(report) = taken {
+            std::fs::write(self.dir.join(DISMISSED_FILE), &report.id)
+                .map_err(|e| KokoroError::Io(format!("Failed to dismiss crash report: {}", e)))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(id: &str) -> CrashReport {
+        CrashReport {
+            id: id.to_string(),
+            created_at: "2026-01-01T00:00:00Z".to_string(),
+            app_version: "0.0.0".to_string(),
+            os: "linux".to_string(),
+            arch: "x86_64".to_string(),
+            thread: "main".to_string(),
+            message: "boom".to_string(),
+            location: Some("src/lib.rs:1:1".to_string()),
+            backtrace: String::new(),
+            active_subsystems: vec!["tts".to_string()],
+            recent_logs: vec!["[INFO][ai] hello".to_string()],
+            path: None,
+        }
+    }
+
+    #[test]
+    fn anonymize_strips_home_directory() {
+        let home = Path::new("/home/alice");
+        assert_eq!(
+            anonymize("at /home/alice/.cargo/src/lib.rs:3", Some(home)),
+            "at ~/.cargo/src/lib.rs:3"
+        );
+        assert_eq!(anonymize("no paths", None), "no paths");
+    }
+
+    #[test]
+    fn newest_report_is_pending_until_dismissed() {
+        let dir = std::env::temp_dir().join(format!("kokoro-crash-{}", uuid::Uuid::new_v4()));
+        write_report(&dir, &sample("crash-20260101-000000-000")).unwrap();
+        write_report(&dir, &sample("crash-20260102-000000-000")).unwrap();
+
+        let store = CrashReportStore::load(dir.clone());
+        let report = store.last_report().unwrap();
+        assert_eq!(report.id, "crash-20260102-000000-000");
+        assert!(report.path.is_some());
+
+        store.dismiss().unwrap();
+        assert!(store.last_report().is_none());
+        assert!(CrashReportStore::load(dir.clone()).last_report().is_none());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn old_reports_are_pruned() {
+        let dir = std::env::temp_dir().join(format!("kokoro-crash-{}", uuid::Uuid::new_v4()));
+        for i in 0..(MAX_REPORTS + 3) {
+            write_report(&dir, &sample(&format!("crash-20260101-0000{:02}-000", i))).unwrap();
+        }
+        let files = report_files(&dir);
+        assert_eq!(files.len(), MAX_REPORTS);
+        assert!(files[0].to_string_lossy().contains("crash-20260101-000003"));
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,286 @@
+//! SQLite pool configuration and maintenance.
+//!
+//! Every connection to the main database goes through [`connect_options`] so
+//! WAL mode, busy timeout and synchronous level are set in one place. The
+//! heartbeat drives [`MaintenanceSchedule`] for periodic `PRAGMA optimize`
+//! and idle-time `VACUUM`; `compact_database` runs a full pass on demand.
+
+use serde::Serialize;
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePool, SqliteSynchronous};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+/// How long a writer waits on a locked database before giving up.
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+/// How often the heartbeat looks at the database at all.
+const CHECK_INTERVAL: Duration = Duration::from_secs(15 * 60);
+const OPTIMIZE_INTERVAL: Duration = Duration::from_secs(6 * 3600);
+const VACUUM_MIN_INTERVAL: Duration = Duration::from_secs(7 * 24 * 3600);
+/// Scheduled VACUUM only runs once free pages reach this share of the file...
+const VACUUM_FREELIST_RATIO: f64 = 0.2;
+/// ...and amount to at least this many bytes.
+const VACUUM_MIN_RECLAIMABLE_BYTES: u64 = 16 * 1024 * 1024;
+
+/// Connection options for the main database: WAL journal, NORMAL sync
+/// (safe under WAL), and a busy timeout so background writers queue instead
+/// of failing with `SQLITE_BUSY`.
+pub fn connect_options(db_url: &str) -> Result<SqliteConnectOptions, sqlx::Error> {
+    Ok(SqliteConnectOptions::from_str(db_url)?
+        .create_if_missing(true)
+        .journal_mode(SqliteJournalMode::Wal)
+        .synchronous(SqliteSynchronous::Normal)
+        .busy_timeout(BUSY_TIMEOUT))
+}
+
+pub async fn connect(db_url: &str) -> Result<SqlitePool, sqlx::Error> {
+    SqlitePool::connect_with(connect_options(db_url)?).await
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+pub struct DatabaseSpace {
+    pub page_size: u64,
+    pub page_count: u64,
+    pub freelist_count: u64,
+    /// Current size of the `-wal` file, 0 for in-memory databases.
+    pub wal_bytes: u64,
+}
+
+impl DatabaseSpace {
+    pub fn file_bytes(&self) -> u64 {
+        self.page_size * self.page_count
+    }
+
+    pub fn reclaimable_bytes(&self) -> u64 {
+        self.page_size * self.freelist_count
+    }
+
+    fn should_vacuum(&self) -> bool {
+        self.page_count > 0
+            && self.reclaimable_bytes() >= VACUUM_MIN_RECLAIMABLE_BYTES
+            && self.freelist_count as f64 / self.page_count as f64 >= VACUUM_FREELIST_RATIO
+    }
+}
+
+/// Result of `compact_database`.
+#[derive(Debug, Clone, Serialize)]
+pub struct CompactReport {
+    pub bytes_before: u64,
+    pub bytes_after: u64,
+    pub reclaimed_bytes: u64,
+    pub duration_ms: u64,
+}
+
+async fn pragma_u64(pool: &SqlitePool, pragma: &str) -> Result<u64, sqlx::Error> {
+    let value: i64 = sqlx::query_scalar(&format!("PRAGMA {}", pragma))
+        .fetch_one(pool)
+        .await?;
+    Ok(value.max(0) as u64)
+}
+
+async fn main_db_path(pool: &SqlitePool) -> Result<Option<PathBuf>, sqlx::Error> {
+    let rows: Vec<(i64, String, String)> = sqlx::query_as("PRAGMA database_list")
+        .fetch_all(pool)
+        .await?;
+    Ok(rows
+        .into_iter()
+        .find(|(_, name, _)| name == "main")
+        .map(|(_, _, file)| file)
+        .filter(|file| !file.is_empty())
+        .map(PathBuf::from))
+}
+
+pub async fn space_usage(pool: &SqlitePool) -> Result<DatabaseSpace, sqlx::Error> {
+    let wal_bytes = match main_db_path(pool).await? {
+        Some(path) => {
+            let mut wal = path.into_os_string();
+            wal.push("-wal");
+            tokio::fs::metadata(&wal)
+                .await
+                .map(|meta| meta.len())
+                .unwrap_or(0)
+        }
+        None => 0,
+    };
+    Ok(DatabaseSpace {
+        page_size: pragma_u64(pool, "page_size").await?,
+        page_count: pragma_u64(pool, "page_count").await?,
+        freelist_count: pragma_u64(pool, "freelist_count").await?,
+        wal_bytes,
+    })
+}
+
+/// Refresh query planner statistics; cheap enough to run every few hours.
+pub async fn optimize(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    sqlx::query("PRAGMA optimize").execute(pool).await?;
+    Ok(())
+}
+
+/// Checkpoint the WAL, rebuild the file with `VACUUM`, and re-run `optimize`.
+pub async fn compact(pool: &SqlitePool) -> Result<CompactReport, sqlx::Error> {
+    let started = Instant::now();
+    let before = space_usage(pool).await?;
+
+    sqlx::query("PRAGMA wal_checkpoint(TRUNCATE)")
+        .execute(pool)
+        .await?;
+    sqlx::query("VACUUM").execute(pool).await?;
+    // VACUUM in WAL mode writes the rebuilt pages through the log.
+    sqlx::query("PRAGMA wal_checkpoint(TRUNCATE)")
+        .execute(pool)
+        .await?;
+    optimize(pool).await?;
+
+    let after = space_usage(pool).await?;
+    let bytes_before = before.file_bytes() + before.wal_bytes;
+    let bytes_after = after.file_bytes() + after.wal_bytes;
+    Ok(CompactReport {
+        bytes_before,
+        bytes_after,
+        reclaimed_bytes: bytes_before.saturating_sub(bytes_after),
+        duration_ms: started.elapsed().as_millis() as u64,
+    })
+}
+
+/// Tracks when the heartbeat last ran each maintenance step.
+pub struct MaintenanceSchedule {
+    last_check: Instant,
+    last_optimize: Instant,
+    last_vacuum: Option<Instant>,
+}
+
+impl Default for MaintenanceSchedule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MaintenanceSchedule {
+    pub fn new() -> Self {
+        Self {
+            last_check: Instant::now(),
+            last_optimize: Instant::now(),
+            last_vacuum: None,
+        }
+    }
+
+    /// Count `slept` towards every interval. `Instant` stops while the OS is
+    /// suspended on most platforms, so without this a long sleep would push
+    /// each step back by however long the machine was asleep.
+    pub fn catch_up(&mut self, slept: Duration) {
+        let backdate = |at: Instant| at.checked_sub(slept).unwrap_or(at);
+        self.last_check = backdate(self.last_check);
+        self.last_optimize = backdate(self.last_optimize);
+        self.last_vacuum = self.last_vacuum.map(backdate);
+    }
+
+    /// Run whatever maintenance is due. `VACUUM` rewrites the whole file, so
+    /// it only runs while the user is idle and enough space can be reclaimed.
+    pub async fn run_due(&mut self, pool: &SqlitePool, user_idle: bool) {
+        if self.last_check.elapsed() < CHECK_INTERVAL {
+            return;
+        }
+        self.last_check = Instant::now();
+
+        if self.last_optimize.elapsed() >= OPTIMIZE_INTERVAL {
+            self.last_optimize = Instant::now();
+            if let Err(e) = optimize(pool).await {
+                tracing::warn!(target: "db", "[DB] PRAGMA optimize failed: {}", e);
+            }
+        }
+
+        let vacuum_due = self
+            .last_vacuum
+            .map(|ts| ts.elapsed() >= VACUUM_MIN_INTERVAL)
+            .unwrap_or(true);
+        if !user_idle || !vacuum_due {
+            return;
+        }
+        match space_usage(pool).await {
+            Ok(space) if space.should_vacuum() => {
+                self.last_vacuum = Some(Instant::now());
+                match compact(pool).await {
+                    Ok(report) => tracing::info!(
+                        target: "db",
+                        "[DB] Scheduled compaction reclaimed {} bytes in {} ms",
+                        report.reclaimed_bytes,
+                        report.duration_ms
+                    ),
+                    Err(e) => tracing::warn!(target: "db", "[DB] Scheduled VACUUM failed: {}", e),
+                }
+            }
+            Ok(_) => {}
+            Err(e) => tracing::warn!(target: "db", "[DB] Failed to read space usage: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_db_url() -> (PathBuf, String) {
+        let path = std::env::temp_dir().join(format!("kokoro-db-test-{}.db", uuid::Uuid::new_v4()));
+        let url = format!("sqlite://{}", path.to_string_lossy().replace('\\', "/"));
+        (path, url)
+    }
+
+    fn remove_db_files(path: &std::path::Path) {
+        for suffix in ["", "-wal", "-shm"] {
+            let mut file = path.as_os_str().to_owned();
+            file.push(suffix);
+            let _ = std::fs::remove_file(file);
+        }
+    }
+
+    #[test]
+    fn vacuum_threshold_needs_ratio_and_size() {
+        let space = |page_count, freelist_count| DatabaseSpace {
+            page_size: 4096,
+            page_count,
+            freelist_count,
+            wal_bytes: 0,
+        };
+        // 40 MB file, 20 MB free.
+        assert!(space(10_240, 5_120).should_vacuum());
+        // Same ratio, but only 2 MB reclaimable.
+        assert!(!space(1_024, 512).should_vacuum());
+        // Plenty of bytes, low ratio.
+        assert!(!space(100_000, 5_000).should_vacuum());
+    }
+
+    #[tokio::test]
+    async fn connect_enables_wal_and_compact_reclaims_space() {
+        let (path, url) = temp_db_url();
+        let pool = connect(&url).await.unwrap();
+
+        let mode: String = sqlx::query_scalar("PRAGMA journal_mode")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(mode, "wal");
+
+        sqlx::query("CREATE TABLE blobs (data BLOB NOT NULL)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        for _ in 0..64 {
+            sqlx::query("INSERT INTO blobs (data) VALUES (zeroblob(16384))")
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+        sqlx::query("DELETE FROM blobs")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let report = compact(&pool).await.unwrap();
+        assert!(report.reclaimed_bytes > 0);
+        assert!(report.bytes_after < report.bytes_before);
+        assert_eq!(space_usage(&pool).await.unwrap().freelist_count, 0);
+
+        pool.close().await;
+        remove_db_files(&path);
+    }
+}
@@ -49,6 +49,13 @@ pub struct ImageGenSystemConfig {
     pub enabled: bool,
     #[serde(default)]
     pub providers: Vec<ImageGenProviderConfig>,
+    /// Regenerations allowed when a selfie fails the appearance check; 0 disables the check.
+    #[serde(default = "default_selfie_max_retries")]
+    pub selfie_max_retries: u32,
+}
+
+fn default_selfie_max_retries() -> u32 {
+    2
 }
 
 impl Default for ImageGenSystemConfig {
@@ -90,6 +97,7 @@ impl Default for ImageGenSystemConfig {
                     extra: HashMap::new(),
                 },
             ],
+            selfie_max_retries: default_selfie_max_retries(),
         }
     }
 }
@@ -0,0 +1,196 @@
+//! Selfie consistency checks.
+//!
+//! Pictures of the character are shown to the vision model together with the
+//! appearance notes from the character profile. When the model reports a
+//! mismatch (wrong hair color, different outfit, ...) the image service
+//! regenerates, up to `selfie_max_retries` times.
+
+use crate::vision::image_files::prepare_image;
+use crate::vision::watcher::{analyze_image, VisionWatcher, VlmRequest};
+use serde::Serialize;
+use std::path::Path;
+use tauri::{AppHandle, Manager};
+
+const VERIFY_MAX_TOKENS: u32 = 120;
+/// Upper bound on regenerations regardless of config.
+pub const MAX_SELFIE_RETRIES: u32 = 5;
+
+/// Who a selfie is supposed to show.
+#[derive(Debug, Clone)]
+pub struct SelfieReference {
+    pub name: String,
+    pub appearance: String,
+}
+
+/// Outcome of the appearance check, attached to the generation result.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ConsistencyReport {
+    /// Generations it took, including the accepted one.
+    pub attempts: u32,
+    pub matched: bool,
+    /// Differences the vision model reported on the returned image.
+    pub mismatches: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Verdict {
+    pub matches: bool,
+    pub mismatches: Vec<String>,
+}
+
+/// Reference for the active character, if its profile has appearance notes.
+pub(crate) async fn active_reference(app: &AppHandle) -> Option<SelfieReference> {
+    let profiles = app.try_state::<crate::character_profiles::CharacterProfileService>()?;
+    let active = profiles.active().await?;
+    let appearance = active
+        .profile
+        .appearance
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())?
+        .to_string();
+
+    let orchestrator = app.try_state::<crate::ai::context::AIOrchestrator>()?;
+    let name = crate::commands::characters::load_character(&orchestrator.db, &active.character_id)
+        .await
+        .ok()
+        .flatten()
+        .map(|record| record.name)
+        .filter(|name| !name.trim().is_empty())
+        .unwrap_or_else(|| "the character".to_string());
+
+    Some(SelfieReference { name, appearance })
+}
+
+/// Fold the reference appearance into the generation prompt.
+pub(crate) fn selfie_prompt(reference: &SelfieReference, prompt: &str) -> String {
+    let prompt = prompt.trim();
+    if prompt.is_empty() {
+        format!("{}, {}", reference.name, reference.appearance)
+    } else {
+        format!("{}, {}, {}", reference.name, reference.appearance, prompt)
+    }
+}
+
+fn verification_prompt(reference: &SelfieReference) -> String {
+    format!(
+        "This image is supposed to show {name}, who looks like this: {appearance}\n\n\
+         Compare only stable traits such as hair color and style, eye color, and outfit; \
+         ignore pose, expression, background and art style. \
+         Answer with exactly one line: MATCH if the main character fits the description, \
+         or MISMATCH: followed by a short comma-separated list of the differences.",
+        name = reference.name,
+        appearance = reference.appearance,
+    )
+}
+
+/// Read the VLM answer. Anything that isn't a clear MISMATCH counts as a
+/// match so a chatty verifier can't burn through generations.
+pub(crate) fn parse_verdict(answer: &str) -> Verdict {
+    let line = answer
+        .lines()
+        .map(|line| line.trim().trim_start_matches(['*', '`', '"']).trim())
+        .find(|line| !line.is_empty())
+        .unwrap_or_default();
+
+    let upper = line.to_ascii_uppercase();
+    if !upper.starts_with("MISMATCH") {
+        return Verdict {
+            matches: true,
+            mismatches: Vec::new(),
+        };
+    }
+
+    let details = line["MISMATCH".len()..].trim_start_matches([':', '-', ' ', '*']);
+    let mismatches = details
+        .split([',', ';'])
+        .map(|item| item.trim().trim_end_matches('.').trim())
+        .filter(|item| !item.is_empty())
+        .map(str::to_string)
+        .collect();
+    Verdict {
+        matches: false,
+        mismatches,
+    }
+}
+
+/// Ask the vision model whether the image at `path` matches `reference`.
+pub(crate) async fn verify(
+    watcher: &VisionWatcher,
+    reference: &SelfieReference,
+    path: &str,
+) -> Result<Verdict, String> {
+    let config = watcher.config.read().await.clone();
+    if !config.vlm_enabled {
+        return Err("vision model is disabled".to_string());
+    }
+
+    let bytes = tokio::fs::read(path)
+        .await
+        .map_err(|e| format!("Failed to read generated image: {e}"))?;
+    let ext = Path::new(path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or("png")
+        .to_ascii_lowercase();
+    let (prepared, mime_type) = tokio::task::spawn_blocking(move || prepare_image(&bytes, &ext))
+        .await
+        .map_err(|e| e.to_string())??;
+
+    let prompt = verification_prompt(reference);
+    let answer = analyze_image(
+        &watcher.client,
+        &config,
+        &VlmRequest {
+            image: &prepared,
+            mime_type,
+            prompt: &prompt,
+            max_tokens: VERIFY_MAX_TOKENS,
+        },
+        watcher.llm_service.as_ref(),
+    )
+    .await?;
+    Ok(parse_verdict(&answer))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reference() -> SelfieReference {
+        SelfieReference {
+            name: "Kokoro".to_string(),
+            appearance: "silver hair, red eyes, black sailor uniform".to_string(),
+        }
+    }
+
+    #[test]
+    fn selfie_prompt_leads_with_the_reference() {
+        assert_eq!(
+            selfie_prompt(&reference(), " waving at the beach "),
+            "Kokoro, silver hair, red eyes, black sailor uniform, waving at the beach"
+        );
+        assert_eq!(
+            selfie_prompt(&reference(), ""),
+            "Kokoro, silver hair, red eyes, black sailor uniform"
+        );
+    }
+
+    #[test]
+    fn parses_mismatch_details() {
+        let verdict = parse_verdict("**MISMATCH:** hair is blonde; wearing a hoodie.\n");
+        assert!(!verdict.matches);
+        assert_eq!(
+            verdict.mismatches,
+            vec!["hair is blonde", "wearing a hoodie"]
+        );
+    }
+
+    #[test]
+    fn unclear_answers_count_as_match() {
+        assert!(parse_verdict("MATCH").matches);
+        assert!(parse_verdict("match - looks right").matches);
+        assert!(parse_verdict("I think she looks fine.").matches);
+        assert!(parse_verdict("").matches);
+    }
+}
@@ -0,0 +1,199 @@
+//! Generation history ("gallery").
+//!
+//! Each successful generation is recorded in `generated_images` with the
+//! prompt, provider, seed and the conversation it came from. Deleting an
+//! entry gives up its asset-store reference, or removes the file directly for
+//! images saved outside the store.
+
+use crate::error::KokoroError;
+use serde::Serialize;
+use sqlx::{Row, SqlitePool};
+use std::path::Path;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GalleryEntry {
+    pub id: i64,
+    pub prompt: String,
+    pub negative_prompt: Option<String>,
+    pub provider_id: String,
+    pub seed: Option<i64>,
+    pub image_path: String,
+    #[serde(skip)]
+    pub asset_hash: Option<String>,
+    pub conversation_id: Option<String>,
+    pub character_id: Option<String>,
+    pub created_at: i64,
+    /// False when the file was removed outside the app (or by an asset clean).
+    pub file_exists: bool,
+}
+
+/// Fields recorded for a new generation.
+#[derive(Debug, Clone, Default)]
+pub struct NewGalleryEntry {
+    pub prompt: String,
+    pub negative_prompt: Option<String>,
+    pub provider_id: String,
+    pub seed: Option<i64>,
+    pub image_path: String,
+    pub asset_hash: Option<String>,
+    pub conversation_id: Option<String>,
+    pub character_id: Option<String>,
+}
+
+pub async fn record(db: &SqlitePool, entry: &NewGalleryEntry) -> Result<i64, KokoroError> {
+    let result = sqlx::query(
+        "INSERT INTO generated_images
+             (prompt, negative_prompt, provider_id, seed, image_path, asset_hash, conversation_id, character_id, created_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&entry.prompt)
+    .bind(&entry.negative_prompt)
+    .bind(&entry.provider_id)
+    .bind(entry.seed)
+    .bind(&entry.image_path)
+    .bind(&entry.asset_hash)
+    .bind(&entry.conversation_id)
+    .bind(&entry.character_id)
+    .bind(chrono::Utc::now().timestamp())
+    .execute(db)
+    .await?;
+    Ok(result.last_insert_rowid())
+}
+
+const SELECT_COLUMNS: &str = "SELECT id, prompt, negative_prompt, provider_id, seed, image_path, asset_hash, conversation_id, character_id, created_at FROM generated_images";
+
+fn entry_from_row(row: &sqlx::sqlite::SqliteRow) -> GalleryEntry {
+    let image_path: String = row.get("image_path");
+    GalleryEntry {
+        id: row.get("id"),
+        prompt: row.get("prompt"),
+        negative_prompt: row.get("negative_prompt"),
+        provider_id: row.get("provider_id"),
+        seed: row.get("seed"),
+        file_exists: Path::new(&image_path).exists(),
+        image_path,
+        asset_hash: row.get("asset_hash"),
+        conversation_id: row.get("conversation_id"),
+        character_id: row.get("character_id"),
+        created_at: row.get("created_at"),
+    }
+}
+
+/// Newest first, optionally limited to one conversation.
+pub async fn list(
+    db: &SqlitePool,
+    conversation_id: Option<&str>,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<GalleryEntry>, KokoroError> {
+    let rows = match conversation_id {
+        Some(conversation_id) => {
+            sqlx::query(&format!(
+                "{} WHERE conversation_id = ? ORDER BY created_at DESC, id DESC LIMIT ? OFFSET ?",
+                SELECT_COLUMNS
+            ))
+            .bind(conversation_id)
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(db)
+            .await?
+        }
+        None => {
+            sqlx::query(&format!(
+                "{} ORDER BY created_at DESC, id DESC LIMIT ? OFFSET ?",
+                SELECT_COLUMNS
+            ))
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(db)
+            .await?
+        }
+    };
+    Ok(rows.iter().map(entry_from_row).collect())
+}
+
+pub async fn get(db: &SqlitePool, id: i64) -> Result<GalleryEntry, KokoroError> {
+    let row = sqlx::query(&format!("{} WHERE id = ?", SELECT_COLUMNS))
+        .bind(id)
+        .fetch_optional(db)
+        .await?
+        .ok_or_else(|| KokoroError::NotFound(format!("Generated image {} not found", id)))?;
+    Ok(entry_from_row(&row))
+}
+
+/// Remove the row and return it; the caller releases the file.
+pub async fn remove(db: &SqlitePool, id: i64) -> Result<GalleryEntry, KokoroError> {
+    let entry = get(db, id).await?;
+    sqlx::query("DELETE FROM generated_images WHERE id = ?")
+        .bind(id)
+        .execute(db)
+        .await?;
+    Ok(entry)
+}
+
+/// Whether another row still points at `path` (same file, e.g. deduplicated
+/// content outside the asset store).
+pub async fn path_in_use(db: &SqlitePool, path: &str) -> Result<bool, KokoroError> {
+    let count: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM generated_images WHERE image_path = ?")
+            .bind(path)
+            .fetch_one(db)
+            .await?;
+    Ok(count > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn db() -> SqlitePool {
+        let db = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::migrate!("./migrations").run(&db).await.unwrap();
+        db
+    }
+
+    fn entry(prompt: &str, conversation: Option<&str>) -> NewGalleryEntry {
+        NewGalleryEntry {
+            prompt: prompt.to_string(),
+            provider_id: "sd".to_string(),
+            seed: Some(42),
+            image_path: format!("/nonexistent/{}.png", prompt),
+            conversation_id: conversation.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn lists_newest_first_and_filters_by_conversation() {
+        let db = db().await;
+        record(&db, &entry("a", Some("c1"))).await.unwrap();
+        record(&db, &entry("b", Some("c2"))).await.unwrap();
+        record(&db, &entry("c", None)).await.unwrap();
+
+        let all = list(&db, None, 10, 0).await.unwrap();
+        assert_eq!(
+            all.iter().map(|e| e.prompt.as_str()).collect::<Vec<_>>(),
+            ["c", "b", "a"]
+        );
+        assert_eq!(all[0].seed, Some(42));
+        assert!(!all[0].file_exists);
+
+        let c1 = list(&db, Some("c1"), 10, 0).await.unwrap();
+        assert_eq!(c1.len(), 1);
+        assert_eq!(c1[0].prompt, "a");
+        assert_eq!(list(&db, None, 1, 1).await.unwrap()[0].prompt, "b");
+    }
+
+    #[tokio::test]
+    async fn remove_returns_the_row_once() {
+        let db = db().await;
+        let id = record(&db, &entry("a", None)).await.unwrap();
+        let removed = remove(&db, id).await.unwrap();
+        assert_eq!(removed.prompt, "a");
+        assert!(!path_in_use(&db, &removed.image_path).await.unwrap());
+        assert!(matches!(
+            remove(&db, id).await,
+            Err(KokoroError::NotFound(_))
+        ));
+    }
+}
@@ -172,6 +172,7 @@ impl ImageGenProvider for GoogleImageGenProvider {
         Ok(ImageGenResponse {
             data: image_data,
             format: "png".to_string(),
+            seed: None,
         })
     }
 }
@@ -44,6 +44,27 @@ pub struct ImageGenParams {
     pub quality: Option<String>, // e.g. "standard", "hd"
     pub style: Option<String>,   // e.g. "vivid", "natural"
     pub n: usize,                // Number of images to generate (default 1)
+    /// Local image to start from (image-to-image / inpainting).
+    #[serde(default)]
+    pub init_image: Option<String>,
+    /// Local mask for inpainting: white repaints, black keeps. Needs `init_image`.
+    #[serde(default)]
+    pub mask: Option<String>,
+    /// How far to move away from `init_image` (0.0 keeps it, 1.0 ignores it).
+    #[serde(default)]
+    pub strength: Option<f32>,
+    /// Local picture of the subject for IP-Adapter style conditioning.
+    #[serde(default)]
+    pub reference_image: Option<String>,
+    /// How strongly `reference_image` steers the result.
+    #[serde(default)]
+    pub reference_weight: Option<f32>,
+}
+
+impl ImageGenParams {
+    pub fn is_edit(&self) -> bool {
+        self.init_image.is_some()
+    }
 }
 
 impl Default for ImageGenParams {
@@ -56,6 +77,11 @@ impl Default for ImageGenParams {
             quality: None,
             style: None,
             n: 1,
+            init_image: None,
+            mask: None,
+            strength: None,
+            reference_image: None,
+            reference_weight: None,
         }
     }
 }
@@ -66,6 +92,21 @@ impl Default for ImageGenParams {
 pub struct ImageGenResponse {
     pub format: String, // "png", "jpg"
     pub data: Vec<u8>,  // Raw image bytes
+    /// Seed the provider used, when it reports one.
+    pub seed: Option<i64>,
+}
+
+/// Snapshot of a running generation, emitted as `imagegen:progress`.
+#[derive(Debug, Clone, Serialize)]
+pub struct ImageGenProgress {
+    pub provider_id: String,
+    /// 0–100.
+    pub percent: f32,
+    pub eta_secs: Option<f32>,
+    pub step: Option<u32>,
+    pub steps: Option<u32>,
+    /// Live preview as a `data:` URL, when the backend renders one.
+    pub preview: Option<String>,
 }
 
 // ── Provider Trait ──────────────────────────────────────
@@ -83,4 +124,36 @@ pub trait ImageGenProvider: Send + Sync {
 
     /// Generate an image from the prompt
     async fn generate(&self, params: ImageGenParams) -> Result<ImageGenResponse, ImageGenError>;
+
+    /// Whether `generate` honours `init_image`, `mask` and `strength`.
+    fn supports_edits(&self) -> bool {
+        false
+    }
+
+    /// Whether `generate` honours `reference_image`.
+    fn supports_reference_image(&self) -> bool {
+        false
+    }
+
+    /// Whether `progress` reports on a running `generate` call.
+    fn supports_progress(&self) -> bool {
+        false
+    }
+
+    /// Progress of the generation currently running, if any.
+    async fn progress(&self) -> Option<ImageGenProgress> {
+        None
+    }
+
+    /// Load the model ahead of a request. Providers without cold starts do nothing.
+    async fn warm_up(&self) -> Result<(), ImageGenError> {
+        Ok(())
+    }
+}
+
+/// Read a source image or mask named in [`ImageGenParams`].
+pub fn read_image_file(path: &str) -> Result<Vec<u8>, ImageGenError> {
+    std::fs::read(path).map_err(|e| {
+        ImageGenError::GenerationFailed(format!("Cannot read image '{}': {}", path, e))
+    })
 }
@@ -1,7 +1,10 @@
 pub mod config;
+pub mod consistency;
+pub mod gallery;
 pub mod google;
 pub mod interface;
 pub mod openai;
+pub mod preset;
 pub mod service;
 pub mod stable_diffusion;
 
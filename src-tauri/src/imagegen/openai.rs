@@ -1,3 +1,4 @@
+use crate::imagegen::interface::read_image_file;
 use crate::imagegen::{ImageGenError, ImageGenParams, ImageGenProvider, ImageGenResponse};
 use async_trait::async_trait;
 use base64::{engine::general_purpose, Engine as _};
@@ -63,7 +64,15 @@ impl ImageGenProvider for OpenAIImageGenProvider {
         !self.api_key.is_empty()
     }
 
+    /// `/images/edits` exists for dall-e-2 and gpt-image models, not dall-e-3.
+    fn supports_edits(&self) -> bool {
+        self.model != "dall-e-3"
+    }
+
     async fn generate(&self, params: ImageGenParams) -> Result<ImageGenResponse, ImageGenError> {
+        if params.is_edit() {
+            return self.edit(params).await;
+        }
         let url = format!("{}/images/generations", self.base_url.trim_end_matches('/'));
 
         let body = OpenAIRequest {
@@ -113,31 +122,145 @@ impl ImageGenProvider for OpenAIImageGenProvider {
             )));
         }
 
-        let json: Value = res
-            .json()
-            .await
-            .map_err(|e| ImageGenError::GenerationFailed(format!("Invalid JSON: {}", e)))?;
-
-        // Extract b64_json
-        // Response format: { "created": ..., "data": [ { "b64_json": "..." } ] }
-        if let Some(data) = json.get("data").and_then(|v| v.as_array()) {
-            if let Some(first) = data.first() {
-                if let Some(b64) = first.get("b64_json").and_then(|v| v.as_str()) {
-                    // Decode base64
-                    let bytes = general_purpose::STANDARD.decode(b64).map_err(|e| {
-                        ImageGenError::GenerationFailed(format!("Base64 decode error: {}", e))
-                    })?;
-
-                    return Ok(ImageGenResponse {
-                        format: "png".to_string(), // DALL-E returns PNG
-                        data: bytes,
-                    });
+        decode_response(res).await
+    }
+}
+
+impl OpenAIImageGenProvider {
+    /// Image-to-image through `/images/edits`. The API has no strength knob:
+    /// without a mask the whole image is reworked from the prompt.
+    async fn edit(&self, params: ImageGenParams) -> Result<ImageGenResponse, ImageGenError> {
+        let url = format!("{}/images/edits", self.base_url.trim_end_matches('/'));
+        let image = read_image_file(params.init_image.as_deref().unwrap_or_default())?;
+        let mask = params
+            .mask
+            .as_deref()
+            .map(read_image_file)
+            .transpose()?
+            .map(|bytes| mask_to_alpha_png(&bytes))
+            .transpose()?;
+        let size = params.size.unwrap_or_else(|| "1024x1024".to_string());
+
+        let client = self.client.clone();
+        let api_key = self.api_key.clone();
+        let model = self.model.clone();
+        let prompt = params.prompt;
+
+        let res = crate::utils::http::request_with_retry(
+            move || {
+                let mut form = reqwest::multipart::Form::new()
+                    .text("model", model.clone())
+                    .text("prompt", prompt.clone())
+                    .text("size", size.clone())
+                    .text("n", "1")
+                    .part("image", png_part(image.clone()));
+                if let Some(mask) = &mask {
+                    form = form.part("mask", png_part(mask.clone()));
+                }
+                // gpt-image models always answer in b64 and reject the field
+                if model.starts_with("dall-e") {
+                    form = form.text("response_format", "b64_json");
                 }
+                let request = client
+                    .post(&url)
+                    .header("Authorization", format!("Bearer {}", api_key))
+                    .multipart(form);
+                async move { request.send().await }
+            },
+            2,
+        )
+        .await
+        .map_err(|e| ImageGenError::GenerationFailed(e.to_string()))?;
+
+        if !res.status().is_success() {
+            let status = res.status();
+            let text = res
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(ImageGenError::GenerationFailed(format!(
+                "OpenAI API Error {}: {}",
+                status, text
+            )));
+        }
+
+        decode_response(res).await
+    }
+}
+
+fn png_part(bytes: Vec<u8>) -> reqwest::multipart::Part {
+    reqwest::multipart::Part::bytes(bytes)
+        .file_name("image.png")
+        .mime_str("image/png")
+        .expect("static mime type")
+}
+
+/// OpenAI masks mark the area to repaint with transparency; ours are
+/// white-on-black like SD WebUI's. Light pixels become fully transparent.
+fn mask_to_alpha_png(bytes: &[u8]) -> Result<Vec<u8>, ImageGenError> {
+    let luma = image::load_from_memory(bytes)
+        .map_err(|e| ImageGenError::GenerationFailed(format!("Invalid mask image: {}", e)))?
+        .to_luma8();
+    let rgba = image::RgbaImage::from_fn(luma.width(), luma.height(), |x, y| {
+        let alpha = if luma.get_pixel(x, y).0[0] >= 128 {
+            0
+        } else {
+            255
+        };
+        image::Rgba([0, 0, 0, alpha])
+    });
+    let mut out = std::io::Cursor::new(Vec::new());
+    rgba.write_to(&mut out, image::ImageFormat::Png)
+        .map_err(|e| ImageGenError::GenerationFailed(format!("Mask encode error: {}", e)))?;
+    Ok(out.into_inner())
+}
+
+async fn decode_response(res: reqwest::Response) -> Result<ImageGenResponse, ImageGenError> {
+    let json: Value = res
+        .json()
+        .await
+        .map_err(|e| ImageGenError::GenerationFailed(format!("Invalid JSON: {}", e)))?;
+
+    // Extract b64_json
+    // Response format: { "created": ..., "data": [ { "b64_json": "..." } ] }
+    if let Some(data) = json.get("data").and_then(|v| v.as_array()) {
+        if let Some(first) = data.first() {
+            if let Some(b64) = first.get("b64_json").and_then(|v| v.as_str()) {
+                // Decode base64
+                let bytes = general_purpose::STANDARD.decode(b64).map_err(|e| {
+                    ImageGenError::GenerationFailed(format!("Base64 decode error: {}", e))
+                })?;
+
+                return Ok(ImageGenResponse {
+                    format: "png".to_string(), // DALL-E returns PNG
+                    data: bytes,
+                    seed: None,
+                });
             }
         }
+    }
+
+    Err(ImageGenError::GenerationFailed(
+        "Response missing 'data[0].b64_json'".to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn white_mask_areas_become_transparent() {
+        let mut mask = image::GrayImage::new(2, 1);
+        mask.put_pixel(0, 0, image::Luma([255]));
+        mask.put_pixel(1, 0, image::Luma([0]));
+        let mut png = std::io::Cursor::new(Vec::new());
+        mask.write_to(&mut png, image::ImageFormat::Png).unwrap();
 
-        Err(ImageGenError::GenerationFailed(
-            "Response missing 'data[0].b64_json'".to_string(),
-        ))
+        let converted = mask_to_alpha_png(png.get_ref()).unwrap();
+        let rgba = image::load_from_memory(&converted).unwrap().to_rgba8();
+        assert_eq!(rgba.get_pixel(0, 0).0[3], 0);
+        assert_eq!(rgba.get_pixel(1, 0).0[3], 255);
+        assert!(mask_to_alpha_png(b"not an image").is_err());
     }
 }
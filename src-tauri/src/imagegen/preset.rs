@@ -0,0 +1,170 @@
+//! Per-character image presets.
+//!
+//! A character profile can pin how its pictures are generated: tags placed
+//! in front of every prompt, a negative prompt, LoRA tags, a provider style
+//! and a reference picture for IP-Adapter. The preset is applied to
+//! `[IMAGE_PROMPT:]` generations and selfies so the character looks the same
+//! from one image to the next.
+
+use super::interface::ImageGenParams;
+use crate::error::KokoroError;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+pub const DEFAULT_REFERENCE_WEIGHT: f32 = 0.7;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct ImagePreset {
+    /// Tags put in front of every prompt, e.g. "1girl, silver hair, red eyes".
+    pub base_prompt: String,
+    /// Replaces the provider's default negative prompt when set.
+    pub negative_prompt: String,
+    /// LoRA / embedding tags, e.g. `<lora:alice:0.8>`. Only Stable Diffusion
+    /// understands them.
+    pub lora: String,
+    /// Provider style: a saved style name for Stable Diffusion WebUI,
+    /// "vivid" / "natural" for DALL·E.
+    pub style: String,
+    /// Picture of the character used as an IP-Adapter reference by providers
+    /// that support it.
+    pub reference_image: Option<String>,
+    /// How strongly the reference steers the result (0–2).
+    pub reference_weight: Option<f32>,
+}
+
+impl ImagePreset {
+    pub fn is_empty(&self) -> bool {
+        self.base_prompt.trim().is_empty()
+            && self.negative_prompt.trim().is_empty()
+            && self.lora.trim().is_empty()
+            && self.style.trim().is_empty()
+            && self.reference_image.is_none()
+    }
+
+    pub fn validate(&self) -> Result<(), KokoroError> {
+        if let Some(weight) = self.reference_weight {
+            if !(0.0..=2.0).contains(&weight) {
+                return Err(KokoroError::Validation(format!(
+                    "reference weight must be between 0 and 2, got {}",
+                    weight
+                )));
+            }
+        }
+        if let Some(path) = &self.reference_image {
+            if !std::path::Path::new(path).is_file() {
+                return Err(KokoroError::Validation(format!(
+                    "reference image not found: {}",
+                    path
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// The prompt with the preset's tags in front.
+    pub fn compose_prompt(&self, prompt: &str) -> String {
+        [self.base_prompt.as_str(), self.lora.as_str(), prompt]
+            .iter()
+            .map(|part| part.trim().trim_matches(',').trim())
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Fill `params` from the preset. Values the caller already set win; the
+    /// reference image is skipped for edits, which have their own source.
+    pub fn apply(&self, prompt: &str, mut params: ImageGenParams) -> (String, ImageGenParams) {
+        let base = if params.prompt.trim().is_empty() {
+            prompt
+        } else {
+            params.prompt.as_str()
+        };
+        let composed = self.compose_prompt(base);
+        params.prompt = composed.clone();
+
+        if params.negative_prompt.is_none() && !self.negative_prompt.trim().is_empty() {
+            params.negative_prompt = Some(self.negative_prompt.trim().to_string());
+        }
+        if params.style.is_none() && !self.style.trim().is_empty() {
+            params.style = Some(self.style.trim().to_string());
+        }
+        if params.reference_image.is_none() && !params.is_edit() {
+            if let Some(reference) = &self.reference_image {
+                params.reference_image = Some(reference.clone());
+                params.reference_weight = Some(
+                    self.reference_weight
+                        .unwrap_or(DEFAULT_REFERENCE_WEIGHT)
+                        .clamp(0.0, 2.0),
+                );
+            }
+        }
+        (composed, params)
+    }
+}
+
+/// The active character's preset, if it has one.
+pub(crate) async fn active_preset(app: &AppHandle) -> Option<ImagePreset> {
+    let profiles = app.try_state::<crate::character_profiles::CharacterProfileService>()?;
+    let preset = profiles.active().await?.profile.image_preset;
+    (!preset.is_empty()).then_some(preset)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn preset() -> ImagePreset {
+        ImagePreset {
+            base_prompt: "1girl, silver hair, red eyes,".to_string(),
+            negative_prompt: "extra fingers".to_string(),
+            lora: "<lora:alice:0.8>".to_string(),
+            style: "anime".to_string(),
+            reference_image: Some("/tmp/alice.png".to_string()),
+            reference_weight: None,
+        }
+    }
+
+    #[test]
+    fn prompt_gets_preset_tags_in_front() {
+        assert_eq!(
+            preset().compose_prompt(" at the beach "),
+            "1girl, silver hair, red eyes, <lora:alice:0.8>, at the beach"
+        );
+        assert_eq!(ImagePreset::default().compose_prompt("cat"), "cat");
+    }
+
+    #[test]
+    fn apply_fills_only_unset_params() {
+        let (prompt, params) = preset().apply("reading", ImageGenParams::default());
+        assert_eq!(prompt, params.prompt);
+        assert!(prompt.ends_with("reading"));
+        assert_eq!(params.negative_prompt.as_deref(), Some("extra fingers"));
+        assert_eq!(params.style.as_deref(), Some("anime"));
+        assert_eq!(params.reference_image.as_deref(), Some("/tmp/alice.png"));
+        assert_eq!(params.reference_weight, Some(DEFAULT_REFERENCE_WEIGHT));
+
+        let explicit = ImageGenParams {
+            prompt: "explicit".to_string(),
+            negative_prompt: Some("blurry".to_string()),
+            init_image: Some("/tmp/source.png".to_string()),
+            ..Default::default()
+        };
+        let (prompt, params) = preset().apply("ignored", explicit);
+        assert!(prompt.ends_with("explicit"));
+        assert_eq!(params.negative_prompt.as_deref(), Some("blurry"));
+        assert_eq!(params.reference_image, None);
+    }
+
+    #[test]
+    fn empty_preset_and_weight_bounds() {
+        assert!(ImagePreset::default().is_empty());
+        assert!(!preset().is_empty());
+        let bad = ImagePreset {
+            reference_weight: Some(3.0),
+            ..Default::default()
+        };
+        assert!(bad.validate().is_err());
+        assert!(ImagePreset::default().validate().is_ok());
+    }
+}
@@ -1,21 +1,42 @@
 use super::config::{ImageGenProviderConfig, ImageGenSystemConfig};
+use super::consistency::{self, ConsistencyReport, MAX_SELFIE_RETRIES};
+use super::gallery;
 use super::google::GoogleImageGenProvider;
-use super::interface::{ImageGenError, ImageGenParams, ImageGenProvider};
+use super::interface::{ImageGenError, ImageGenParams, ImageGenProvider, ImageGenResponse};
 use super::openai::OpenAIImageGenProvider;
+use super::preset;
 use super::stable_diffusion::StableDiffusionProvider;
+use crate::assets::{AssetCategory, AssetStore};
+use crate::error::KokoroError;
 use serde::Serialize;
 use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use std::sync::Arc;
+use tauri::{Emitter, Manager};
 use tokio::sync::RwLock;
 
+/// `source` value that refers to the most recent generation.
+pub const LAST_IMAGE: &str = "last";
+pub const IMAGEGEN_PROGRESS_EVENT: &str = "imagegen:progress";
+const PROGRESS_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
+
 #[derive(Clone, Serialize)]
 pub struct ImageGenResult {
     pub image_url: String, // file:// path
     pub prompt: String,
     pub provider_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub negative_prompt: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed: Option<i64>,
+    /// Gallery row, when the generation was recorded.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gallery_id: Option<i64>,
+    /// Set for selfies that went through the appearance check.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub consistency: Option<ConsistencyReport>,
 }
 
 #[derive(Clone)]
@@ -24,7 +45,14 @@ pub struct ImageGenService {
     provider_configs: Arc<RwLock<HashMap<String, ImageGenProviderConfig>>>,
     default_provider: Arc<RwLock<Option<String>>>,
     output_dir: PathBuf,
+    /// When attached, images are written to the content-addressable asset store.
+    asset_store: Arc<RwLock<Option<AssetStore>>>,
+    /// When attached, generations are reported on the companion status line.
+    app_handle: Arc<RwLock<Option<tauri::AppHandle>>>,
     generating: Arc<AtomicBool>,
+    selfie_max_retries: Arc<AtomicU32>,
+    /// Most recent successful generation; the default source for variations.
+    last_result: Arc<RwLock<Option<ImageGenResult>>>,
 }
 
 impl ImageGenService {
@@ -44,7 +72,11 @@ impl ImageGenService {
             provider_configs: Arc::new(RwLock::new(HashMap::new())),
             default_provider: Arc::new(RwLock::new(config.default_provider.clone())),
             output_dir,
+            asset_store: Arc::new(RwLock::new(None)),
+            app_handle: Arc::new(RwLock::new(None)),
             generating: Arc::new(AtomicBool::new(false)),
+            selfie_max_retries: Arc::new(AtomicU32::new(config.selfie_max_retries)),
+            last_result: Arc::new(RwLock::new(None)),
         };
 
         if !config.enabled {
@@ -99,11 +131,23 @@ impl ImageGenService {
                     }
                 }
             }
-            "stable_diffusion" => Some(Box::new(StableDiffusionProvider::new(
-                config.id.clone(),
-                config.base_url.clone(),
-                config.model.clone(),
-            ))),
+            "stable_diffusion" => {
+                let extra = |key: &str| {
+                    config
+                        .extra
+                        .get(key)
+                        .and_then(|v| v.as_str())
+                        .map(str::to_string)
+                };
+                Some(Box::new(
+                    StableDiffusionProvider::new(
+                        config.id.clone(),
+                        config.base_url.clone(),
+                        config.model.clone(),
+                    )
+                    .with_ip_adapter(extra("ip_adapter_module"), extra("ip_adapter_model")),
+                ))
+            }
             "google" => match GoogleImageGenProvider::new(config) {
                 Ok(provider) => Some(Box::new(provider)),
                 Err(e) => {
@@ -135,6 +179,23 @@ impl ImageGenService {
         configs.insert(id, config);
     }
 
+    /// Whether the provider that would serve `provider_id` runs locally (usable offline).
+    pub async fn is_local_provider(&self, provider_id: Option<&str>) -> bool {
+        let target_id = match provider_id {
+            Some(id) => Some(id.to_string()),
+            None => self.default_provider.read().await.clone(),
+        };
+        let Some(target_id) = target_id else {
+            return false;
+        };
+        self.provider_configs
+            .read()
+            .await
+            .get(&target_id)
+            .map(|cfg| cfg.provider_type == "stable_diffusion")
+            .unwrap_or(false)
+    }
+
     pub async fn generate(
         &self,
         prompt: String,
@@ -153,13 +214,104 @@ impl ImageGenService {
             ));
         }
 
+        let _activity = self.app_handle.read().await.as_ref().map(|app| {
+            crate::ai::companion_state::enter(
+                app,
+                crate::ai::companion_state::CompanionActivity::GeneratingImage,
+                None,
+            )
+        });
         let result = self
             .generate_inner(prompt, provider_id, params, window_size)
             .await;
         self.generating.store(false, Ordering::SeqCst);
+        if let Ok(result) = &result {
+            *self.last_result.write().await = Some(result.clone());
+        }
         result
     }
 
+    /// Rework an existing image. `source` is a local path or `"last"` for the
+    /// most recent generation; without a prompt the source's prompt is reused
+    /// when it is known.
+    pub async fn generate_variation(
+        &self,
+        source: String,
+        prompt: Option<String>,
+        strength: Option<f32>,
+        mask: Option<String>,
+        provider_id: Option<String>,
+        window_size: Option<(u32, u32)>,
+    ) -> Result<ImageGenResult, ImageGenError> {
+        let last = self.last_result.read().await.clone();
+        let known = last.filter(|r| source == LAST_IMAGE || r.image_url == source);
+        let init_image = match (&known, source.as_str()) {
+            (Some(last), _) => last.image_url.clone(),
+            (None, LAST_IMAGE) => {
+                return Err(ImageGenError::GenerationFailed(
+                    "No image has been generated yet".to_string(),
+                ))
+            }
+            (None, path) => path.to_string(),
+        };
+        let prompt = prompt
+            .map(|p| p.trim().to_string())
+            .filter(|p| !p.is_empty())
+            .or_else(|| known.as_ref().map(|r| r.prompt.clone()))
+            .ok_or_else(|| {
+                ImageGenError::GenerationFailed("A prompt is required for this image".to_string())
+            })?;
+        // Keep the source's provider unless the caller picked one
+        let provider_id = provider_id.or_else(|| known.map(|r| r.provider_id));
+
+        let params = ImageGenParams {
+            prompt: prompt.clone(),
+            init_image: Some(init_image),
+            mask,
+            strength,
+            ..Default::default()
+        };
+        self.generate(prompt, provider_id, Some(params), window_size)
+            .await
+    }
+
+    /// Generate with the active character's image preset applied, as for
+    /// `[IMAGE_PROMPT:]` tags. Without a preset this is `generate`.
+    pub async fn generate_in_character(
+        &self,
+        prompt: String,
+        provider_id: Option<String>,
+        params: Option<ImageGenParams>,
+        window_size: Option<(u32, u32)>,
+    ) -> Result<ImageGenResult, ImageGenError> {
+        let (prompt, params) = self.with_character_preset(prompt, params).await;
+        self.generate(prompt, provider_id, params, window_size)
+            .await
+    }
+
+    async fn with_character_preset(
+        &self,
+        prompt: String,
+        params: Option<ImageGenParams>,
+    ) -> (String, Option<ImageGenParams>) {
+        let app = self.app_handle.read().await.clone();
+        let preset = match app.as_ref() {
+            Some(app) => preset::active_preset(app).await,
+            None => None,
+        };
+        match preset {
+            Some(preset) => {
+                let (prompt, params) = preset.apply(&prompt, params.unwrap_or_default());
+                (prompt, Some(params))
+            }
+            None => (prompt, params),
+        }
+    }
+
+    pub async fn last_result(&self) -> Option<ImageGenResult> {
+        self.last_result.read().await.clone()
+    }
+
     async fn generate_inner(
         &self,
         prompt: String,
@@ -194,6 +346,13 @@ impl ImageGenService {
             .get(&target_id)
             .ok_or(ImageGenError::ProviderNotFound(target_id.clone()))?;
 
+        if params.as_ref().is_some_and(ImageGenParams::is_edit) && !provider.supports_edits() {
+            return Err(ImageGenError::Unavailable(format!(
+                "Provider {} cannot edit existing images",
+                target_id
+            )));
+        }
+
         if !provider.is_available().await {
             return Err(ImageGenError::Unavailable(format!(
                 "Provider {} is not available",
@@ -202,6 +361,14 @@ impl ImageGenService {
         }
 
         let mut gen_params = params.unwrap_or_default();
+        if gen_params.reference_image.is_some() && !provider.supports_reference_image() {
+            tracing::debug!(
+                target: "imagegen",
+                "Provider '{}' has no reference image support; ignoring it",
+                target_id
+            );
+            gen_params.reference_image = None;
+        }
         if gen_params.prompt.is_empty() {
             gen_params.prompt = prompt.clone();
         }
@@ -231,6 +398,7 @@ impl ImageGenService {
         }
 
         let effective_prompt = gen_params.prompt.clone();
+        let negative_prompt = gen_params.negative_prompt.clone();
         let prompt_chars = effective_prompt.chars().count();
         tracing::info!(
             target: "imagegen",
@@ -239,19 +407,42 @@ impl ImageGenService {
             prompt_chars
         );
 
-        let response = provider.generate(gen_params).await?;
+        let response = self
+            .generate_with_progress(provider.as_ref(), gen_params)
+            .await?;
 
         // Save image to disk
-        let filename = format!(
-            "{}_{}.{}",
-            chrono::Utc::now().format("%Y%m%d_%H%M%S"),
-            uuid::Uuid::new_v4(),
-            response.format
-        );
-        let path = self.output_dir.join(&filename);
-
-        fs::write(&path, &response.data)
-            .map_err(|e| ImageGenError::GenerationFailed(format!("Failed to save image: {}", e)))?;
+        let asset_store = self.asset_store.read().await.clone();
+        let mut asset_hash = None;
+        let path = match asset_store {
+            Some(store) => {
+                let stored = store
+                    .put(
+                        AssetCategory::GeneratedImages,
+                        &response.data,
+                        &response.format,
+                    )
+                    .await
+                    .map_err(|e| {
+                        ImageGenError::GenerationFailed(format!("Failed to save image: {}", e))
+                    })?;
+                asset_hash = Some(stored.hash);
+                PathBuf::from(stored.path)
+            }
+            None => {
+                let filename = format!(
+                    "{}_{}.{}",
+                    chrono::Utc::now().format("%Y%m%d_%H%M%S"),
+                    uuid::Uuid::new_v4(),
+                    response.format
+                );
+                let path = self.output_dir.join(&filename);
+                fs::write(&path, &response.data).map_err(|e| {
+                    ImageGenError::GenerationFailed(format!("Failed to save image: {}", e))
+                })?;
+                path
+            }
+        };
 
         // Construct file URL
         // In Tauri v2, we can't easily guess the "asset protocol" URL perfectly without knowing the scope,
@@ -264,11 +455,212 @@ impl ImageGenService {
 
         let abs_path = path.to_string_lossy().to_string();
 
-        Ok(ImageGenResult {
+        let mut result = ImageGenResult {
             image_url: abs_path,
             prompt: effective_prompt,
             provider_id: target_id,
-        })
+            negative_prompt,
+            seed: response.seed,
+            gallery_id: None,
+            consistency: None,
+        };
+        result.gallery_id = self.record_in_gallery(&result, asset_hash).await;
+        Ok(result)
+    }
+
+    /// Run the provider, polling its progress into `imagegen:progress` events
+    /// while it works when it can report progress.
+    async fn generate_with_progress(
+        &self,
+        provider: &dyn ImageGenProvider,
+        params: ImageGenParams,
+    ) -> Result<ImageGenResponse, ImageGenError> {
+        let app = self.app_handle.read().await.clone();
+        let Some(app) = app.filter(|_| provider.supports_progress()) else {
+            return provider.generate(params).await;
+        };
+        let generation = provider.generate(params);
+        tokio::pin!(generation);
+        let mut ticker = tokio::time::interval(PROGRESS_POLL_INTERVAL);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        // The first tick fires at once, before the backend has started.
+        ticker.tick().await;
+        loop {
+            tokio::select! {
+                response = &mut generation => return response,
+                _ = ticker.tick() => {
+                    if let Some(progress) = provider.progress().await {
+                        let _ = app.emit(IMAGEGEN_PROGRESS_EVENT, &progress);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Add a finished generation to the gallery, tagged with the active
+    /// conversation and character. Failures only cost the history entry.
+    async fn record_in_gallery(
+        &self,
+        result: &ImageGenResult,
+        asset_hash: Option<String>,
+    ) -> Option<i64> {
+        let app = self.app_handle.read().await.clone()?;
+        let orchestrator = app.try_state::<crate::ai::context::AIOrchestrator>()?;
+        let entry = gallery::NewGalleryEntry {
+            prompt: result.prompt.clone(),
+            negative_prompt: result.negative_prompt.clone(),
+            provider_id: result.provider_id.clone(),
+            seed: result.seed,
+            image_path: result.image_url.clone(),
+            asset_hash,
+            conversation_id: orchestrator.current_conversation_id.lock().await.clone(),
+            character_id: Some(orchestrator.get_character_id().await),
+        };
+        match gallery::record(&orchestrator.db, &entry).await {
+            Ok(id) => Some(id),
+            Err(e) => {
+                tracing::warn!(target: "imagegen", "Failed to record generation in gallery: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Drop a gallery entry and its image. Asset-store images lose this
+    /// entry's reference (and go at the next clean once unreferenced); other
+    /// files are deleted unless another entry still points at them.
+    pub async fn delete_gallery_entry(
+        &self,
+        db: &sqlx::SqlitePool,
+        id: i64,
+    ) -> Result<(), KokoroError> {
+        let entry = gallery::remove(db, id).await?;
+        match (&entry.asset_hash, self.asset_store.read().await.as_ref()) {
+            (Some(hash), Some(store)) => {
+                if let Err(e) = store.release(AssetCategory::GeneratedImages, hash).await {
+                    tracing::warn!(target: "imagegen", "Failed to release gallery image {}: {}", id, e);
+                }
+            }
+            (Some(_), None) => {}
+            (None, _) => {
+                let path = Path::new(&entry.image_path);
+                if path.starts_with(&self.output_dir)
+                    && !gallery::path_in_use(db, &entry.image_path).await?
+                {
+                    if let Err(e) = fs::remove_file(path) {
+                        if e.kind() != std::io::ErrorKind::NotFound {
+                            return Err(e.into());
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Generate a picture of the active character, with its image preset
+    /// applied. The result is checked
+    /// against the profile's appearance notes by the vision model and
+    /// regenerated on mismatch; without notes or a VLM this is `generate`.
+    pub async fn generate_selfie(
+        &self,
+        prompt: String,
+        provider_id: Option<String>,
+        params: Option<ImageGenParams>,
+        window_size: Option<(u32, u32)>,
+    ) -> Result<ImageGenResult, ImageGenError> {
+        let (prompt, mut params) = self.with_character_preset(prompt, params).await;
+        let app = self.app_handle.read().await.clone();
+        let reference = match app.as_ref() {
+            Some(app) => consistency::active_reference(app).await,
+            None => None,
+        };
+        let Some(reference) = reference else {
+            return self
+                .generate(prompt, provider_id, params, window_size)
+                .await;
+        };
+
+        let prompt = consistency::selfie_prompt(&reference, &prompt);
+        if let Some(params) = params.as_mut().filter(|p| !p.prompt.is_empty()) {
+            params.prompt = consistency::selfie_prompt(&reference, &params.prompt);
+        }
+
+        let retries = self
+            .selfie_max_retries
+            .load(Ordering::Relaxed)
+            .min(MAX_SELFIE_RETRIES);
+        let watcher = app
+            .as_ref()
+            .and_then(|app| app.try_state::<crate::vision::watcher::VisionWatcher>());
+
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            let mut result = self
+                .generate(
+                    prompt.clone(),
+                    provider_id.clone(),
+                    params.clone(),
+                    window_size,
+                )
+                .await?;
+            let Some(watcher) = watcher.as_ref().filter(|_| retries > 0) else {
+                return Ok(result);
+            };
+
+            let verdict = match consistency::verify(watcher, &reference, &result.image_url).await {
+                Ok(verdict) => verdict,
+                Err(e) => {
+                    tracing::warn!(target: "imagegen", "Selfie check skipped: {}", e);
+                    return Ok(result);
+                }
+            };
+            result.consistency = Some(ConsistencyReport {
+                attempts: attempt,
+                matched: verdict.matches,
+                mismatches: verdict.mismatches,
+            });
+            if verdict.matches || attempt > retries {
+                if !verdict.matches {
+                    tracing::warn!(
+                        target: "imagegen",
+                        "Selfie still off-model after {} attempts; keeping the last one",
+                        attempt
+                    );
+                }
+                return Ok(result);
+            }
+            tracing::info!(
+                target: "imagegen",
+                "Selfie attempt {} did not match the character ({}); regenerating",
+                attempt,
+                result
+                    .consistency
+                    .as_ref()
+                    .map(|report| report.mismatches.join(", "))
+                    .unwrap_or_default()
+            );
+        }
+    }
+
+    pub async fn set_asset_store(&self, store: AssetStore) {
+        *self.asset_store.write().await = Some(store);
+    }
+
+    pub async fn set_app_handle(&self, app: tauri::AppHandle) {
+        *self.app_handle.write().await = Some(app);
+    }
+
+    pub async fn default_provider_id(&self) -> Option<String> {
+        self.default_provider.read().await.clone()
+    }
+
+    pub async fn warm_up(&self, provider_id: &str) -> Result<(), ImageGenError> {
+        let providers = self.providers.read().await;
+        let provider = providers
+            .get(provider_id)
+            .ok_or_else(|| ImageGenError::ProviderNotFound(provider_id.to_string()))?;
+        provider.warm_up().await
     }
 
     pub async fn list_providers(&self) -> Vec<String> {
@@ -280,6 +672,8 @@ impl ImageGenService {
         &self,
         config: &ImageGenSystemConfig,
     ) -> Result<(), ImageGenError> {
+        self.selfie_max_retries
+            .store(config.selfie_max_retries, Ordering::Relaxed);
         if !config.enabled {
             let mut providers = self.providers.write().await;
             providers.clear();
@@ -1,3 +1,4 @@
+use crate::imagegen::interface::{read_image_file, ImageGenProgress};
 use crate::imagegen::{ImageGenError, ImageGenParams, ImageGenProvider, ImageGenResponse};
 use async_trait::async_trait;
 use base64::{engine::general_purpose, Engine as _};
@@ -10,8 +11,14 @@ pub struct StableDiffusionProvider {
     base_url: String,       // Defaults to "http://127.0.0.1:7860"
     _model: Option<String>, // Optional checkpoint override (not always supported nicely via API without extra call, so maybe just ignored or used for SDXL refiner)
     client: Client,
+    /// ControlNet preprocessor/model used for reference images.
+    ip_adapter_module: String,
+    ip_adapter_model: String,
 }
 
+const DEFAULT_IP_ADAPTER_MODULE: &str = "ip-adapter_clip_sd15";
+const DEFAULT_IP_ADAPTER_MODEL: &str = "ip-adapter_sd15";
+
 impl StableDiffusionProvider {
     pub fn new(id: String, base_url: Option<String>, model: Option<String>) -> Self {
         Self {
@@ -23,7 +30,32 @@ impl StableDiffusionProvider {
                 .no_proxy()
                 .build()
                 .unwrap_or_default(),
+            ip_adapter_module: DEFAULT_IP_ADAPTER_MODULE.to_string(),
+            ip_adapter_model: DEFAULT_IP_ADAPTER_MODEL.to_string(),
+        }
+    }
+
+    /// Override the ControlNet IP-Adapter preprocessor and model (SDXL
+    /// checkpoints need the `_sdxl` variants).
+    pub fn with_ip_adapter(mut self, module: Option<String>, model: Option<String>) -> Self {
+        if let Some(module) = module.filter(|m| !m.trim().is_empty()) {
+            self.ip_adapter_module = module;
+        }
+        if let Some(model) = model.filter(|m| !m.trim().is_empty()) {
+            self.ip_adapter_model = model;
         }
+        self
+    }
+
+    /// ControlNet unit conditioning the generation on a reference picture.
+    fn ip_adapter_unit(&self, image_b64: String, weight: f32) -> Value {
+        serde_json::json!({
+            "enabled": true,
+            "image": image_b64,
+            "module": self.ip_adapter_module,
+            "model": self.ip_adapter_model,
+            "weight": weight,
+        })
     }
 }
 
@@ -41,6 +73,25 @@ struct SdTxt2ImgRequest {
     batch_size: usize,
 }
 
+/// txt2img fields plus the source image; sent to `/sdapi/v1/img2img`.
+#[derive(Serialize, Clone)]
+struct SdImg2ImgRequest {
+    #[serde(flatten)]
+    base: SdTxt2ImgRequest,
+    init_images: Vec<String>,
+    denoising_strength: f32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    mask: Option<String>,
+    mask_blur: u32,
+    /// 1 = start masked areas from the original content.
+    inpainting_fill: u32,
+    inpaint_full_res: bool,
+}
+
+const DEFAULT_EDIT_STRENGTH: f32 = 0.6;
+/// Progress polls must not hold up the next one.
+const PROGRESS_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(3);
+
 #[async_trait]
 impl ImageGenProvider for StableDiffusionProvider {
     fn id(&self) -> String {
@@ -57,8 +108,71 @@ impl ImageGenProvider for StableDiffusionProvider {
         self.client.get(&url).send().await.is_ok()
     }
 
+    /// Moves the checkpoint back into VRAM if the WebUI unloaded it.
+    async fn warm_up(&self) -> Result<(), ImageGenError> {
+        let url = format!(
+            "{}/sdapi/v1/reload-checkpoint",
+            self.base_url.trim_end_matches('/')
+        );
+        let res = self
+            .client
+            .post(&url)
+            .send()
+            .await
+            .map_err(|e| ImageGenError::Unavailable(e.to_string()))?;
+        if !res.status().is_success() {
+            return Err(ImageGenError::Unavailable(format!(
+                "reload-checkpoint returned {}",
+                res.status()
+            )));
+        }
+        Ok(())
+    }
+
+    fn supports_edits(&self) -> bool {
+        true
+    }
+
+    fn supports_progress(&self) -> bool {
+        true
+    }
+
+    async fn progress(&self) -> Option<ImageGenProgress> {
+        let url = format!(
+            "{}/sdapi/v1/progress?skip_current_image=false",
+            self.base_url.trim_end_matches('/')
+        );
+        let json: Value = self
+            .client
+            .get(&url)
+            .timeout(PROGRESS_TIMEOUT)
+            .send()
+            .await
+            .ok()?
+            .json()
+            .await
+            .ok()?;
+        let mut progress = parse_progress(&json)?;
+        progress.provider_id = self.id.clone();
+        Some(progress)
+    }
+
+    /// Through the ControlNet extension's IP-Adapter models.
+    fn supports_reference_image(&self) -> bool {
+        true
+    }
+
     async fn generate(&self, params: ImageGenParams) -> Result<ImageGenResponse, ImageGenError> {
-        let url = format!("{}/sdapi/v1/txt2img", self.base_url.trim_end_matches('/'));
+        let endpoint = if params.is_edit() {
+            "img2img"
+        } else {
+            "txt2img"
+        };
+        let url = format!(
+            "{}/sdapi/v1/{}",
+            self.base_url.trim_end_matches('/'),
+            endpoint
+        );
 
         // Parse size string "1024x1024" -> (1024, 1024)
         let (width, height) = parse_size(&params.size).unwrap_or((512, 512));
@@ -72,6 +186,22 @@ impl ImageGenProvider for StableDiffusionProvider {
         // Map "style" string to styles vector if present
         let styles = params.style.map(|s| vec![s]).unwrap_or_default();
 
+        let init_image = params
+            .init_image
+            .as_deref()
+            .map(read_image_file)
+            .transpose()?;
+        let mask = params.mask.as_deref().map(read_image_file).transpose()?;
+        let strength = params.strength;
+        let reference = params
+            .reference_image
+            .as_deref()
+            .map(read_image_file)
+            .transpose()?;
+        let reference_weight = params
+            .reference_weight
+            .unwrap_or(crate::imagegen::preset::DEFAULT_REFERENCE_WEIGHT);
+
         let body = SdTxt2ImgRequest {
             prompt: params.prompt,
             negative_prompt: params.negative_prompt.unwrap_or_default(),
@@ -87,6 +217,29 @@ impl ImageGenProvider for StableDiffusionProvider {
             // Ideally we should fix trait to return Vec<Image>.
             batch_size: params.n,
         };
+        let mut body = match init_image {
+            Some(image) => serde_json::to_value(SdImg2ImgRequest {
+                base: body,
+                init_images: vec![general_purpose::STANDARD.encode(image)],
+                denoising_strength: strength.unwrap_or(DEFAULT_EDIT_STRENGTH).clamp(0.0, 1.0),
+                mask: mask.map(|m| general_purpose::STANDARD.encode(m)),
+                mask_blur: 4,
+                inpainting_fill: 1,
+                inpaint_full_res: false,
+            }),
+            None => serde_json::to_value(body),
+        }
+        .map_err(|e| ImageGenError::GenerationFailed(e.to_string()))?;
+        if let Some(reference) = reference {
+            body["alwayson_scripts"] = serde_json::json!({
+                "controlnet": {
+                    "args": [self.ip_adapter_unit(
+                        general_purpose::STANDARD.encode(reference),
+                        reference_weight,
+                    )],
+                },
+            });
+        }
 
         let client = self.client.clone();
         let body_clone = body.clone();
@@ -132,6 +285,7 @@ impl ImageGenProvider for StableDiffusionProvider {
                 return Ok(ImageGenResponse {
                     format: "png".to_string(), // SD WebUI usually returns PNG
                     data: bytes,
+                    seed: seed_from_info(&json),
                 });
             }
         }
@@ -142,6 +296,59 @@ impl ImageGenProvider for StableDiffusionProvider {
     }
 }
 
+/// The seed actually used; `info` is a JSON document encoded as a string.
+fn seed_from_info(response: &Value) -> Option<i64> {
+    let info: Value = serde_json::from_str(response.get("info")?.as_str()?).ok()?;
+    info.get("seed")?.as_i64()
+}
+
+/// Read a `/sdapi/v1/progress` response; `None` while the WebUI is idle.
+fn parse_progress(json: &Value) -> Option<ImageGenProgress> {
+    let fraction = json.get("progress")?.as_f64()?;
+    let state = json.get("state");
+    let step = |key: &str| {
+        state
+            .and_then(|s| s.get(key))
+            .and_then(Value::as_u64)
+            .map(|v| v as u32)
+    };
+    let job_count = state
+        .and_then(|s| s.get("job_count"))
+        .and_then(Value::as_i64)
+        .unwrap_or(0);
+    if fraction <= 0.0 && job_count <= 0 {
+        return None;
+    }
+    let preview = json
+        .get("current_image")
+        .and_then(Value::as_str)
+        .filter(|image| !image.is_empty())
+        .map(|image| format!("data:{};base64,{}", preview_mime(image), image));
+    Some(ImageGenProgress {
+        provider_id: String::new(),
+        percent: (fraction.clamp(0.0, 1.0) * 100.0) as f32,
+        eta_secs: json
+            .get("eta_relative")
+            .and_then(Value::as_f64)
+            .filter(|eta| *eta > 0.0)
+            .map(|eta| eta as f32),
+        step: step("sampling_step"),
+        steps: step("sampling_steps").filter(|steps| *steps > 0),
+        preview,
+    })
+}
+
+/// Live previews follow the WebUI's preview format setting.
+fn preview_mime(base64: &str) -> &'static str {
+    if base64.starts_with("/9j/") {
+        "image/jpeg"
+    } else if base64.starts_with("UklGR") {
+        "image/webp"
+    } else {
+        "image/png"
+    }
+}
+
 fn parse_size(size_str: &Option<String>) -> Option<(u32, u32)> {
     if let Some(s) = size_str {
         let parts: Vec<&str> = s.split('x').collect();
@@ -153,3 +360,48 @@ fn parse_size(size_str: &Option<String>) -> Option<(u32, u32)> {
     }
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seed_comes_from_the_info_string() {
+        let response = serde_json::json!({
+            "images": [],
+            "info": "{\"seed\": 1234567, \"all_seeds\": [1234567]}",
+        });
+        assert_eq!(seed_from_info(&response), Some(1234567));
+        assert_eq!(seed_from_info(&serde_json::json!({ "images": [] })), None);
+        assert_eq!(
+            seed_from_info(&serde_json::json!({ "info": "not json" })),
+            None
+        );
+    }
+
+    #[test]
+    fn progress_reports_percent_steps_and_preview() {
+        let busy = serde_json::json!({
+            "progress": 0.35,
+            "eta_relative": 4.2,
+            "state": { "job_count": 1, "sampling_step": 7, "sampling_steps": 20 },
+            "current_image": "/9j/4AAQ",
+        });
+        let progress = parse_progress(&busy).unwrap();
+        assert!((progress.percent - 35.0).abs() < 0.01);
+        assert_eq!(progress.step, Some(7));
+        assert_eq!(progress.steps, Some(20));
+        assert_eq!(
+            progress.preview.as_deref(),
+            Some("data:image/jpeg;base64,/9j/4AAQ")
+        );
+
+        let idle = serde_json::json!({
+            "progress": 0.0,
+            "eta_relative": 0.0,
+            "state": { "job_count": 0, "sampling_step": 0, "sampling_steps": 0 },
+            "current_image": null,
+        });
+        assert!(parse_progress(&idle).is_none());
+    }
+}
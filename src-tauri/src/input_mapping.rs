@@ -0,0 +1,665 @@
+//! Input mapping — external triggers for streamers and power users.
+//!
+//! Maps triggers from outside the app (a `POST /trigger/{name}` call on the
+//! local API, a MIDI note from a pad controller, a global keyboard shortcut)
+//! to engine actions: play an expression cue, speak a phrase, read the
+//! selected text aloud, or toggle the voice session. Mappings live in
+//! `input_mappings.json`.
+
+use crate::error::KokoroError;
+use crate::tts::queue::TtsPriority;
+use crate::tts::TtsService;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+use tokio::sync::RwLock;
+
+pub const INPUT_TRIGGERED_EVENT: &str = "input-mapping:triggered";
+const SPEECH_SOURCE: &str = "input_mapping";
+const MIDI_CLIENT_NAME: &str = "Kokoro Engine";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct InputMappingConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// MIDI input to listen on, matched against port names; first port when unset.
+    #[serde(default)]
+    pub midi_port: Option<String>,
+    #[serde(default)]
+    pub mappings: Vec<InputMapping>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct InputMapping {
+    pub id: String,
+    #[serde(default)]
+    pub label: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    pub trigger: InputTrigger,
+    pub action: MappedAction,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum InputTrigger {
+    /// `POST /trigger/{name}` on the local API server.
+    Http { name: String },
+    /// Note-on from the MIDI input. `channel` is 1–16; any channel when unset.
+    MidiNote {
+        note: u8,
+        #[serde(default)]
+        channel: Option<u8>,
+    },
+    /// Global shortcut such as `CmdOrCtrl+Shift+1`.
+    Keyboard { shortcut: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum MappedAction {
+    /// Play a configured Live2D cue (expression or motion).
+    Expression {
+        cue: String,
+    },
+    Speak {
+        text: String,
+    },
+    ToggleVoiceSession,
+    /// Speak the selected (or copied) text, optionally summarized first and
+    /// followed by the character's own comment.
+    ReadAloud {
+        #[serde(default)]
+        summarize: bool,
+        #[serde(default)]
+        comment: bool,
+    },
+}
+
+/// A trigger as it arrives from one of the listeners.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TriggerEvent {
+    Http(String),
+    MidiNote { channel: u8, note: u8 },
+    Keyboard(String),
+}
+
+impl InputTrigger {
+    pub fn matches(&self, event: &TriggerEvent) -> bool {
+        match (self, event) {
+            (Self::Http { name }, TriggerEvent::Http(called)) => name.trim() == called.trim(),
+            (
+                Self::MidiNote { note, channel },
+                TriggerEvent::MidiNote {
+                    channel: got_channel,
+                    note: got_note,
+                },
+            ) => note == got_note && channel.is_none_or(|c| c == *got_channel),
+            (Self::Keyboard { shortcut }, TriggerEvent::Keyboard(pressed)) => {
+                shortcut.trim().eq_ignore_ascii_case(pressed.trim())
+            }
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct InputMappingStatus {
+    pub enabled: bool,
+    /// MIDI input currently listened to.
+    pub midi_port: Option<String>,
+    pub shortcuts: usize,
+    /// Listeners that could not be started (busy shortcut, missing MIDI device, ...).
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct TriggeredEvent {
+    mapping_id: String,
+    label: String,
+}
+
+fn data_dir() -> PathBuf {
+    dirs_next::data_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("com.chyin.kokoro")
+}
+
+pub fn config_path() -> PathBuf {
+    data_dir().join("input_mappings.json")
+}
+
+pub fn load_config(path: &Path) -> InputMappingConfig {
+    crate::config::load_json_config(path, "INPUT_MAPPING")
+}
+
+pub fn save_config(path: &Path, config: &InputMappingConfig) -> Result<(), KokoroError> {
+    crate::config::save_json_config(path, config, "INPUT_MAPPING")
+}
+
+pub fn validate(config: &InputMappingConfig) -> Result<(), KokoroError> {
+    let mut ids = HashSet::new();
+    for mapping in &config.mappings {
+        let id = mapping.id.trim();
+        if id.is_empty() {
+            return Err(KokoroError::Validation(
+                "Mapping id cannot be empty".to_string(),
+            ));
+        }
+        if !ids.insert(id) {
+            return Err(KokoroError::Validation(format!(
+                "Duplicate mapping id '{}'",
+                id
+            )));
+        }
+        match &mapping.trigger {
+            InputTrigger::Http { name } => {
+                let name = name.trim();
+                if name.is_empty() || name.contains('/') {
+                    return Err(KokoroError::Validation(format!(
+                        "Mapping '{}': HTTP trigger name must be a non-empty path segment",
+                        id
+                    )));
+                }
+            }
+            InputTrigger::MidiNote { note, channel } => {
+                if *note > 127 || channel.is_some_and(|c| !(1..=16).contains(&c)) {
+                    return Err(KokoroError::Validation(format!(
+                        "Mapping '{}': MIDI note must be 0–127 and channel 1–16",
+                        id
+                    )));
+                }
+            }
+            InputTrigger::Keyboard { shortcut } => {
+                shortcut.trim().parse::<Shortcut>().map_err(|e| {
+                    KokoroError::Validation(format!(
+                        "Mapping '{}': invalid shortcut '{}': {}",
+                        id, shortcut, e
+                    ))
+                })?;
+            }
+        }
+        let empty = match &mapping.action {
+            MappedAction::Expression { cue } => cue.trim().is_empty(),
+            MappedAction::Speak { text } => text.trim().is_empty(),
+            MappedAction::ToggleVoiceSession | MappedAction::ReadAloud { .. } => false,
+        };
+        if empty {
+            return Err(KokoroError::Validation(format!(
+                "Mapping '{}': action needs a cue or text",
+                id
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Channel (1–16) and note of a MIDI note-on. Note-on with velocity 0 is a note-off.
+pub(crate) fn parse_note_on(message: &[u8]) -> Option<(u8, u8)> {
+    match message {
+        [status, note, velocity, ..] if status & 0xF0 == 0x90 && *velocity > 0 => {
+            Some(((status & 0x0F) + 1, *note))
+        }
+        _ => None,
+    }
+}
+
+/// Index of the port to open: the first whose name contains `wanted`
+/// (case-insensitive), or the first port when nothing is configured.
+pub(crate) fn pick_port(names: &[String], wanted: Option<&str>) -> Option<usize> {
+    match wanted.map(str::trim).filter(|w| !w.is_empty()) {
+        Some(wanted) => {
+            let wanted = wanted.to_lowercase();
+            names
+                .iter()
+                .position(|name| name.to_lowercase().contains(&wanted))
+        }
+        None => (!names.is_empty()).then_some(0),
+    }
+}
+
+pub fn list_midi_inputs() -> Result<Vec<String>, String> {
+    let input = midir::MidiInput::new(MIDI_CLIENT_NAME).map_err(|e| e.to_string())?;
+    Ok(input
+        .ports()
+        .iter()
+        .filter_map(|port| input.port_name(port).ok())
+        .collect())
+}
+
+/// Keeps the MIDI connection alive on its own thread; dropping it disconnects.
+struct MidiListener {
+    _stop: mpsc::Sender<()>,
+    port: String,
+}
+
+fn start_midi_listener(app: AppHandle, wanted: Option<String>) -> Result<MidiListener, String> {
+    let (ready_tx, ready_rx) = mpsc::sync_channel(1);
+    let (stop_tx, stop_rx) = mpsc::channel::<()>();
+    std::thread::Builder::new()
+        .name("input-mapping-midi".to_string())
+        .spawn(move || {
+            let connection = match connect_midi(app, wanted.as_deref()) {
+                Ok((connection, port)) => {
+                    let _ = ready_tx.send(Ok(port));
+                    connection
+                }
+                Err(err) => {
+                    let _ = ready_tx.send(Err(err));
+                    return;
+                }
+            };
+            // Returns once the listener is dropped.
+            let _ = stop_rx.recv();
+            connection.close();
+        })
+        .map_err(|e| format!("Failed to start MIDI listener: {e}"))?;
+    let port = ready_rx
+        .recv()
+        .map_err(|_| "MIDI listener exited during startup".to_string())??;
+    Ok(MidiListener {
+        _stop: stop_tx,
+        port,
+    })
+}
+
+fn connect_midi(
+    app: AppHandle,
+    wanted: Option<&str>,
+) -> Result<(midir::MidiInputConnection<()>, String), String> {
+    let mut input = midir::MidiInput::new(MIDI_CLIENT_NAME).map_err(|e| e.to_string())?;
+    input.ignore(midir::Ignore::All);
+    let ports = input.ports();
+    let names: Vec<String> = ports
+        .iter()
+        .map(|port| input.port_name(port).unwrap_or_default())
+        .collect();
+    let index = pick_port(&names, wanted).ok_or_else(|| match wanted {
+        Some(wanted) => format!("MIDI input '{}' not found", wanted),
+        None => "No MIDI input device found".to_string(),
+    })?;
+    let name = names[index].clone();
+    let connection = input
+        .connect(
+            &ports[index],
+            "kokoro-input-mapping",
+            move |_stamp, message, _| {
+                let Some((channel, note)) = parse_note_on(message) else {
+                    return;
+                };
+                let app = app.clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Some(service) = app.try_state::<InputMappingService>() {
+                        service
+                            .fire(&app, TriggerEvent::MidiNote { channel, note })
+                            .await;
+                    }
+                });
+            },
+            (),
+        )
+        .map_err(|e| e.to_string())?;
+    Ok((connection, name))
+}
+
+/// Managed Tauri state for input mappings.
+pub struct InputMappingService {
+    config: Arc<RwLock<InputMappingConfig>>,
+    shortcuts: Mutex<Vec<Shortcut>>,
+    midi: Mutex<Option<MidiListener>>,
+    status: Mutex<InputMappingStatus>,
+}
+
+impl InputMappingService {
+    pub fn new(config: InputMappingConfig) -> Self {
+        Self {
+            config: Arc::new(RwLock::new(config)),
+            shortcuts: Mutex::new(Vec::new()),
+            midi: Mutex::new(None),
+            status: Mutex::new(InputMappingStatus::default()),
+        }
+    }
+
+    pub async fn get_config(&self) -> InputMappingConfig {
+        self.config.read().await.clone()
+    }
+
+    pub async fn update_config(
+        &self,
+        app: &AppHandle,
+        config: InputMappingConfig,
+    ) -> InputMappingStatus {
+        *self.config.write().await = config;
+        self.apply(app).await
+    }
+
+    pub fn status(&self) -> InputMappingStatus {
+        self.status
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// (Re)start the shortcut and MIDI listeners for the current config.
+    pub async fn apply(&self, app: &AppHandle) -> InputMappingStatus {
+        let config = self.config.read().await.clone();
+        self.release(app);
+
+        let mut status = InputMappingStatus {
+            enabled: config.enabled,
+            ..Default::default()
+        };
+        if !config.enabled {
+            *self.status.lock().unwrap_or_else(|e| e.into_inner()) = status.clone();
+            return status;
+        }
+
+        let active = config.mappings.iter().filter(|m| m.enabled);
+        let mut keyboard = Vec::new();
+        let mut wants_midi = false;
+        for mapping in active {
+            match &mapping.trigger {
+                InputTrigger::Keyboard { shortcut } => {
+                    let shortcut = shortcut.trim().to_string();
+                    if !keyboard.contains(&shortcut) {
+                        keyboard.push(shortcut);
+                    }
+                }
+                InputTrigger::MidiNote { .. } => wants_midi = true,
+                InputTrigger::Http { .. } => {}
+            }
+        }
+
+        let mut registered = Vec::new();
+        for configured in keyboard {
+            match register_shortcut(app, &configured) {
+                Ok(shortcut) => registered.push(shortcut),
+                Err(e) => status.warnings.push(e),
+            }
+        }
+        status.shortcuts = registered.len();
+        *self.shortcuts.lock().unwrap_or_else(|e| e.into_inner()) = registered;
+
+        if wants_midi {
+            let midi_app = app.clone();
+            let wanted = config.midi_port.clone();
+            match tokio::task::spawn_blocking(move || start_midi_listener(midi_app, wanted)).await {
+                Ok(Ok(listener)) => {
+                    status.midi_port = Some(listener.port.clone());
+                    *self.midi.lock().unwrap_or_else(|e| e.into_inner()) = Some(listener);
+                }
+                Ok(Err(e)) => status.warnings.push(e),
+                Err(e) => status.warnings.push(e.to_string()),
+            }
+        }
+
+        for warning in &status.warnings {
+            tracing::warn!(target: "input_mapping", "[InputMapping] {}", warning);
+        }
+        *self.status.lock().unwrap_or_else(|e| e.into_inner()) = status.clone();
+        status
+    }
+
+    fn release(&self, app: &AppHandle) {
+        for shortcut in self
+            .shortcuts
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .drain(..)
+        {
+            if let Err(e) = app.global_shortcut().unregister(shortcut) {
+                tracing::warn!(target: "input_mapping", "[InputMapping] Failed to unregister shortcut: {}", e);
+            }
+        }
+        self.midi.lock().unwrap_or_else(|e| e.into_inner()).take();
+    }
+
+    /// Run every enabled mapping that matches `event`. Returns how many fired.
+    pub async fn fire(&self, app: &AppHandle, event: TriggerEvent) -> usize {
+        let config = self.config.read().await.clone();
+        if !config.enabled {
+            return 0;
+        }
+        let matched: Vec<InputMapping> = config
+            .mappings
+            .into_iter()
+            .filter(|m| m.enabled && m.trigger.matches(&event))
+            .collect();
+        for mapping in &matched {
+            tracing::info!(target: "input_mapping", "[InputMapping] {:?} -> '{}'", event, mapping.id);
+            if let Err(e) = run_action(app, &mapping.action).await {
+                tracing::warn!(target: "input_mapping", "[InputMapping] '{}' failed: {}", mapping.id, e);
+            }
+            let _ = app.emit(
+                INPUT_TRIGGERED_EVENT,
+                TriggeredEvent {
+                    mapping_id: mapping.id.clone(),
+                    label: mapping.label.clone(),
+                },
+            );
+        }
+        matched.len()
+    }
+
+    /// Run one mapping's action directly, e.g. from the settings "test" button.
+    pub async fn run_mapping(&self, app: &AppHandle, id: &str) -> Result<(), KokoroError> {
+        let mapping = self
+            .config
+            .read()
+            .await
+            .mappings
+            .iter()
+            .find(|m| m.id == id)
+            .cloned()
+            .ok_or_else(|| KokoroError::NotFound(format!("Mapping '{}' not found", id)))?;
+        run_action(app, &mapping.action).await
+    }
+}
+
+fn register_shortcut(app: &AppHandle, configured: &str) -> Result<Shortcut, String> {
+    let shortcut = configured
+        .parse::<Shortcut>()
+        .map_err(|e| format!("Invalid shortcut '{}': {}", configured, e))?;
+    if app.global_shortcut().is_registered(shortcut) {
+        return Err(format!("Shortcut '{}' is already in use", configured));
+    }
+    let shortcut_app = app.clone();
+    let pressed = configured.to_string();
+    app.global_shortcut()
+        .on_shortcut(shortcut, move |_app, _shortcut, event| {
+            if event.state() != ShortcutState::Pressed {
+                return;
+            }
+            let app = shortcut_app.clone();
+            let pressed = pressed.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Some(service) = app.try_state::<InputMappingService>() {
+                    service.fire(&app, TriggerEvent::Keyboard(pressed)).await;
+                }
+            });
+        })
+        .map_err(|e| format!("Failed to register shortcut '{}': {}", configured, e))?;
+    Ok(shortcut)
+}
+
+async fn run_action(app: &AppHandle, action: &MappedAction) -> Result<(), KokoroError> {
+    match action {
+        MappedAction::Expression { cue } => {
+            let _ = app.emit(
+                "chat-cue",
+                serde_json::json!({ "cue": cue.trim(), "source": "input-mapping" }),
+            );
+        }
+        MappedAction::Speak { text } => {
+            let tts = app
+                .try_state::<TtsService>()
+                .ok_or_else(|| KokoroError::Internal("TTS service not available".to_string()))?;
+            // Playback can take a while; the trigger shouldn't wait for it.
+            let tts = tts.inner().clone();
+            let app = app.clone();
+            let text = text.trim().to_string();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = tts
+                    .speak(
+                        app,
+                        text,
+                        None,
+                        None,
+                        TtsPriority::High,
+                        Some(SPEECH_SOURCE.to_string()),
+                    )
+                    .await
+                {
+                    tracing::warn!(target: "input_mapping", "[InputMapping] Speech failed: {}", e);
+                }
+            });
+        }
+        MappedAction::ToggleVoiceSession => {
+            let session = app
+                .try_state::<crate::voice_session::VoiceSessionService>()
+                .ok_or_else(|| KokoroError::Internal("Voice session not available".to_string()))?;
+            session.toggle(app).await.map_err(KokoroError::Stt)?;
+        }
+        MappedAction::ReadAloud { summarize, comment } => {
+            crate::read_aloud::read_aloud(app, *summarize, *comment).await?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mapping(id: &str, trigger: InputTrigger) -> InputMapping {
+        InputMapping {
+            id: id.to_string(),
+            label: String::new(),
+            enabled: true,
+            trigger,
+            action: MappedAction::ToggleVoiceSession,
+        }
+    }
+
+    #[test]
+    fn midi_triggers_match_note_and_optional_channel() {
+        let any = InputTrigger::MidiNote {
+            note: 36,
+            channel: None,
+        };
+        let ch10 = InputTrigger::MidiNote {
+            note: 36,
+            channel: Some(10),
+        };
+        let hit = TriggerEvent::MidiNote {
+            channel: 10,
+            note: 36,
+        };
+        let other_channel = TriggerEvent::MidiNote {
+            channel: 1,
+            note: 36,
+        };
+        assert!(any.matches(&hit));
+        assert!(any.matches(&other_channel));
+        assert!(ch10.matches(&hit));
+        assert!(!ch10.matches(&other_channel));
+        assert!(!ch10.matches(&TriggerEvent::Http("36".to_string())));
+    }
+
+    #[test]
+    fn keyboard_and_http_triggers_compare_loosely() {
+        let key = InputTrigger::Keyboard {
+            shortcut: "CmdOrCtrl+Shift+1".to_string(),
+        };
+        assert!(key.matches(&TriggerEvent::Keyboard(" cmdorctrl+shift+1".to_string())));
+        let http = InputTrigger::Http {
+            name: "wave".to_string(),
+        };
+        assert!(http.matches(&TriggerEvent::Http("wave".to_string())));
+        assert!(!http.matches(&TriggerEvent::Http("wave2".to_string())));
+    }
+
+    #[test]
+    fn parses_note_on_only() {
+        assert_eq!(parse_note_on(&[0x99, 36, 100]), Some((10, 36)));
+        assert_eq!(parse_note_on(&[0x90, 60, 0]), None);
+        assert_eq!(parse_note_on(&[0x80, 60, 64]), None);
+        assert_eq!(parse_note_on(&[0x90, 60]), None);
+    }
+
+    #[test]
+    fn picks_port_by_name() {
+        let names = vec!["Midi Through".to_string(), "Launchpad Mini".to_string()];
+        assert_eq!(pick_port(&names, Some("launchpad")), Some(1));
+        assert_eq!(pick_port(&names, Some("nanoPAD")), None);
+        assert_eq!(pick_port(&names, None), Some(0));
+        assert_eq!(pick_port(&names, Some("  ")), Some(0));
+        assert_eq!(pick_port(&[], None), None);
+    }
+
+    #[test]
+    fn validation_rejects_bad_mappings() {
+        let ok = InputMappingConfig {
+            enabled: true,
+            midi_port: None,
+            mappings: vec![
+                mapping(
+                    "a",
+                    InputTrigger::Http {
+                        name: "wave".to_string(),
+                    },
+                ),
+                mapping(
+                    "b",
+                    InputTrigger::Keyboard {
+                        shortcut: "CmdOrCtrl+Shift+1".to_string(),
+                    },
+                ),
+            ],
+        };
+        assert!(validate(&ok).is_ok());
+
+        let mut duplicate = ok.clone();
+        duplicate.mappings[1].id = "a".to_string();
+        assert!(validate(&duplicate).is_err());
+
+        let mut bad_channel = ok.clone();
+        bad_channel.mappings[0].trigger = InputTrigger::MidiNote {
+            note: 36,
+            channel: Some(17),
+        };
+        assert!(validate(&bad_channel).is_err());
+
+        let mut empty_text = ok.clone();
+        empty_text.mappings[0].action = MappedAction::Speak {
+            text: " ".to_string(),
+        };
+        assert!(validate(&empty_text).is_err());
+
+        let mut bad_key = ok;
+        bad_key.mappings[1].trigger = InputTrigger::Keyboard {
+            shortcut: "Shift+Nope".to_string(),
+        };
+        assert!(validate(&bad_key).is_err());
+    }
+
+    #[test]
+    fn read_aloud_options_default_off() {
+        let action: MappedAction = serde_json::from_str(r#"{"type":"read_aloud"}"#).unwrap();
+        assert_eq!(
+            action,
+            MappedAction::ReadAloud {
+                summarize: false,
+                comment: false,
+            }
+        );
+    }
+}
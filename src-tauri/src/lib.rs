@@ -2,20 +2,37 @@
 // Reason: 应用入口文件需要同时声明模块、注册 Tauri 命令、初始化服务与恢复磁盘状态，天然属于编排层。
 pub mod actions;
 pub mod ai;
+pub mod api_server;
+pub mod assets;
+pub mod automation;
+pub mod captions;
+pub mod character_profiles;
 pub mod chat;
 pub mod commands;
 pub mod config;
+pub mod crash;
+pub mod db;
 pub mod error;
 pub mod hooks;
 pub mod imagegen;
+pub mod input_mapping;
+pub mod lifecycle;
 pub mod llm;
 pub mod mcp;
 pub mod mods;
+pub mod offline;
+pub mod read_aloud;
 pub mod stt;
 pub mod telegram;
+pub mod trash;
 pub mod tts;
 pub mod utils;
 pub mod vision;
+pub mod voice_session;
+pub mod vts;
+pub mod wallpaper;
+pub mod warmup;
+pub mod webhooks;
 use crate::hooks::{AuditLogHookHandler, HookRuntime};
 use crate::mods::ModManager;
 use crate::utils::logging::init_logging;
@@ -45,6 +62,11 @@ async fn auto_start_pet_on_launch<F, Fut>(
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     init_logging();
+    crate::crash::install_panic_hook(crate::crash::crash_dir(
+        &dirs_next::data_dir()
+            .unwrap_or_else(|| std::path::PathBuf::from("."))
+            .join("com.chyin.kokoro"),
+    ));
 
     // Pin the ONNX Runtime dylib to the copy we ship, so the ort crate
     // never accidentally loads an incompatible system-wide library
@@ -85,35 +107,62 @@ pub fn run() {
             commands::system::get_engine_info,
             commands::system::check_latest_release,
             commands::system::get_system_status,
+            commands::capabilities::get_engine_capabilities,
+            commands::system::get_system_state,
+            commands::system::get_last_crash_report,
+            commands::system::dismiss_crash_report,
             commands::system::set_window_size,
             commands::character::get_character_state,
             commands::character::play_cue,
             commands::character::send_message,
+            commands::character::import_character_card,
+            commands::character::export_character_card,
+            commands::character::analyze_persona,
+            commands::lorebook::get_lorebook,
+            commands::lorebook::save_lorebook,
+            commands::lorebook::upsert_lorebook_entry,
+            commands::lorebook::delete_lorebook_entry,
+            commands::address_forms::get_address_forms,
+            commands::address_forms::set_address_form,
             commands::database::init_db,
             commands::database::test_vector_store,
+            commands::database::compact_database,
+            commands::database::purge_data,
             commands::chat::stream_chat,
             commands::chat::get_context_settings,
+            commands::chat::get_context_breakdown,
             commands::chat::set_context_settings,
             commands::chat::approve_tool_approval,
             commands::chat::reject_tool_approval,
             commands::chat::cancel_chat_turn,
+            commands::chat::cancel_chat,
             commands::context::set_persona,
             commands::context::set_character_name,
             commands::context::set_active_character_id,
             commands::context::get_user_profile_settings,
             commands::context::set_user_name,
             commands::context::set_user_persona,
+            commands::onboarding::get_onboarding_status,
+            commands::onboarding::answer_onboarding_question,
+            commands::onboarding::dismiss_onboarding,
+            commands::onboarding::restart_onboarding,
             commands::context::set_response_language,
             commands::context::set_user_language,
             commands::context::set_jailbreak_prompt,
             commands::context::get_jailbreak_prompt,
             commands::context::set_proactive_enabled,
             commands::context::get_proactive_enabled,
+            commands::context::get_proactive_composer_config,
+            commands::context::save_proactive_composer_config,
+            commands::context::list_proactive_history,
             commands::context::set_memory_enabled,
             commands::context::get_memory_enabled,
+            commands::context::set_privacy_mode,
+            commands::context::get_privacy_mode,
             commands::context::set_memory_upgrade_config,
             commands::context::get_memory_upgrade_config,
             commands::context::get_memory_observability_summary,
+            commands::context::get_embedding_cache_stats,
             commands::context::get_latest_memory_write_event,
             commands::context::get_latest_memory_retrieval_log,
             commands::context::get_latest_memory_retrieval_eval_summary,
@@ -121,22 +170,31 @@ pub fn run() {
             commands::context::delete_last_messages,
             commands::context::end_session,
             commands::tts::synthesize,
+            commands::tts::export_conversation_audio,
             commands::tts::list_tts_providers,
             commands::tts::list_tts_voices,
             commands::tts::get_tts_provider_status,
             commands::tts::clear_tts_cache,
+            commands::tts::stop_speaking,
+            commands::tts::report_tts_playback,
             commands::tts::get_tts_config,
             commands::tts::save_tts_config,
             commands::tts::list_gpt_sovits_models,
             commands::mods::list_mods,
             commands::mods::load_mod,
             commands::mods::install_mod,
+            commands::mods::browse_mod_registry,
+            commands::mods::install_mod_from_url,
+            commands::mods::export_mod,
             commands::mods::get_mod_theme,
             commands::mods::get_mod_layout,
+            commands::mods::get_mod_permissions,
+            commands::mods::set_mod_permissions,
             commands::mods::dispatch_mod_event,
             commands::mods::unload_mod,
             commands::live2d::import_live2d_zip,
             commands::live2d::import_live2d_folder,
+            commands::live2d::check_live2d_zip_duplicate,
             commands::live2d::export_live2d_model,
             commands::live2d::list_live2d_models,
             commands::live2d::delete_live2d_model,
@@ -145,6 +203,9 @@ pub fn run() {
             commands::live2d::save_live2d_model_profile,
             commands::live2d::set_active_live2d_model,
             commands::imagegen::generate_image,
+            commands::imagegen::generate_image_variation,
+            commands::imagegen::list_generated_images,
+            commands::imagegen::delete_generated_image,
             commands::imagegen::get_imagegen_config,
             commands::imagegen::save_imagegen_config,
             commands::imagegen::test_sd_connection,
@@ -156,10 +217,14 @@ pub fn run() {
             commands::vision::stop_vision_watcher,
             commands::vision::set_vision_text_input_focused,
             commands::vision::capture_screen_now,
+            commands::vision::analyze_image_file,
+            commands::vision::analyze_image_folder,
+            commands::vision::clear_image_descriptions,
             commands::memory::list_memories,
             commands::memory::update_memory,
             commands::memory::delete_memory,
             commands::memory::update_memory_tier,
+            commands::memory::remember_message,
             commands::memory::run_dream_now,
             commands::memory::get_dreaming_summary,
             commands::memory::list_dream_jobs,
@@ -169,9 +234,16 @@ pub fn run() {
             commands::memory::get_memory_embedding_model_status,
             commands::memory::download_memory_embedding_model,
             commands::characters::list_characters,
+            commands::characters::get_character,
             commands::characters::create_character,
             commands::characters::update_character,
             commands::characters::delete_character,
+            commands::characters::switch_character,
+            commands::characters::get_character_profile,
+            commands::characters::save_character_profile,
+            commands::characters::delete_character_profile,
+            commands::characters::get_llm_trait_extraction,
+            commands::characters::set_llm_trait_extraction,
             commands::conversation::list_conversations,
             commands::conversation::load_conversation,
             commands::conversation::delete_conversation,
@@ -179,6 +251,15 @@ pub fn run() {
             commands::conversation::rename_conversation,
             commands::conversation::update_conversation_state,
             commands::conversation::list_character_ids,
+            commands::conversation::regenerate_last_response,
+            commands::conversation::list_message_variants,
+            commands::conversation::switch_message_variant,
+            commands::conversation::set_conversation_model,
+            commands::conversation::get_conversation_model,
+            commands::conversation::create_checkpoint,
+            commands::conversation::list_checkpoints,
+            commands::conversation::rewind_to_checkpoint,
+            commands::conversation::delete_checkpoint,
             commands::llm::get_llm_config,
             commands::llm::save_llm_config,
             commands::llm::test_llm_connection,
@@ -195,30 +276,128 @@ pub fn run() {
             commands::stt::stop_native_wake_word,
             commands::stt::get_sensevoice_local_status,
             commands::stt::download_sensevoice_local_model,
+            commands::stt::get_whisper_local_status,
+            commands::stt::download_whisper_local_model,
             commands::actions::list_actions,
             commands::actions::list_builtin_tools,
             commands::actions::execute_action,
             commands::tool_settings::get_tool_settings,
             commands::tool_settings::save_tool_settings,
+            commands::latency::get_latency_budget_config,
+            commands::input_queue::get_input_queue_config,
+            commands::input_queue::save_input_queue_config,
+            commands::input_queue::get_input_queue_state,
+            commands::latency::save_latency_budget_config,
+            commands::plan::get_tool_plan_config,
+            commands::plan::save_tool_plan_config,
+            commands::plan::confirm_plan_step,
+            commands::plan::abort_tool_plan,
+            commands::code_exec::get_code_exec_config,
+            commands::code_exec::save_code_exec_config,
+            commands::tables::load_csv_table,
+            commands::tables::list_csv_tables,
+            commands::tables::remove_csv_table,
             commands::mcp::list_mcp_servers,
             commands::mcp::add_mcp_server,
             commands::mcp::remove_mcp_server,
             commands::mcp::refresh_mcp_tools,
             commands::mcp::reconnect_mcp_server,
             commands::mcp::toggle_mcp_server,
+            commands::mcp::list_mcp_tools,
+            commands::mcp::set_mcp_tool_policy,
+            commands::mcp::resolve_mcp_tool_confirm,
+            commands::mcp::authorize_mcp_server,
+            commands::mcp::sign_out_mcp_server,
             commands::bot::get_bot_config,
             commands::bot::save_bot_config,
             commands::bot::start_bot_platform,
             commands::bot::stop_bot_platform,
             commands::bot::get_bot_status,
+            commands::offline::get_offline_mode_config,
+            commands::offline::save_offline_mode_config,
+            commands::offline::get_offline_status,
+            commands::wallpaper::get_wallpaper_config,
+            commands::wallpaper::save_wallpaper_config,
+            commands::wallpaper::get_wallpaper_history,
+            commands::wallpaper::set_wallpaper_image,
+            commands::wallpaper::set_wallpaper_palette,
+            commands::wallpaper::revert_wallpaper,
+            commands::wallpaper::restore_original_wallpaper,
+            commands::warmup::get_warmup_config,
+            commands::warmup::save_warmup_config,
+            commands::warmup::notify_typing,
+            commands::captions::set_captions_enabled,
+            commands::captions::get_captions_status,
+            commands::voice_session::start_voice_session,
+            commands::voice_session::stop_voice_session,
+            commands::voice_session::get_voice_session_status,
+            commands::input_mapping::get_input_mapping_config,
+            commands::input_mapping::save_input_mapping_config,
+            commands::input_mapping::get_input_mapping_status,
+            commands::input_mapping::list_midi_inputs,
+            commands::input_mapping::test_input_mapping,
+            commands::webhooks::get_webhook_config,
+            commands::webhooks::save_webhook_config,
+            commands::webhooks::test_webhook,
+            commands::webhooks::list_webhook_deliveries,
+            commands::duet::start_duet,
+            commands::duet::interject_duet,
+            commands::duet::stop_duet,
+            commands::duet::get_duet_status,
+            commands::story::list_stories,
+            commands::story::get_story,
+            commands::story::start_story,
+            commands::story::choose_story_option,
+            commands::story::switch_story_beat,
+            commands::story::delete_story,
+            commands::schedules::list_schedules,
+            commands::schedules::add_schedule,
+            commands::schedules::remove_schedule,
+            commands::schedules::set_schedule_enabled,
+            commands::automation::list_automation_rules,
+            commands::automation::save_automation_rule,
+            commands::automation::delete_automation_rule,
+            commands::automation::set_automation_rule_enabled,
+            commands::automation::run_automation_rule,
+            commands::automation::run_startup_automations,
+            commands::companion_state::get_companion_state,
+            commands::companion_state::set_companion_playback,
+            commands::emotion::get_emotion_state,
+            commands::emotion::set_emotion,
+            commands::voice_commands::get_voice_command_config,
+            commands::voice_commands::save_voice_command_config,
+            commands::voice_commands::route_voice_command,
+            commands::assets::get_asset_quota_config,
+            commands::assets::save_asset_quota_config,
+            commands::assets::clean_assets,
+            commands::trash::list_trash,
+            commands::trash::restore_from_trash,
+            commands::trash::undo_last_delete,
+            commands::trash::empty_trash,
             commands::telegram::get_telegram_config,
             commands::telegram::save_telegram_config,
             commands::telegram::start_telegram_bot,
             commands::telegram::stop_telegram_bot,
             commands::telegram::get_telegram_status,
+            commands::telegram::create_telegram_pairing_code,
+            commands::api_server::get_api_server_config,
+            commands::api_server::save_api_server_config,
+            commands::api_server::start_api_server,
+            commands::api_server::stop_api_server,
+            commands::api_server::get_api_server_status,
+            commands::vts::get_vts_config,
+            commands::vts::save_vts_config,
+            commands::vts::start_vts,
+            commands::vts::stop_vts,
+            commands::vts::get_vts_status,
+            commands::vts::list_vts_hotkeys,
+            commands::vts::set_vts_mouth_level,
             commands::backup::export_data,
             commands::backup::preview_import,
             commands::backup::import_data,
+            commands::migration::export_migration,
+            commands::migration::preview_migration,
+            commands::migration::import_migration,
             commands::auto_backup::get_auto_backup_config,
             commands::auto_backup::save_auto_backup_config,
             commands::auto_backup::run_auto_backup_now,
@@ -235,6 +414,7 @@ pub fn run() {
             commands::pet::hide_bubble_window,
             stt::stream::process_audio_chunk,
             stt::stream::complete_audio_stream,
+            stt::stream::set_stream_vad,
             stt::stream::discard_audio_stream,
             stt::stream::snapshot_audio_stream,
             stt::stream::prune_audio_buffer,
@@ -272,6 +452,12 @@ pub fn run() {
             let startup_begin = std::time::Instant::now();
             tracing::info!(target: "startup", "setup begin");
             app.manage(crate::commands::pet::PetShortcutState::default());
+            app.manage(crate::ai::companion_state::CompanionStateService::new());
+            app.manage(crate::crash::CrashReportStore::load(crate::crash::crash_dir(
+                &dirs_next::data_dir()
+                    .unwrap_or_else(|| std::path::PathBuf::from("."))
+                    .join("com.chyin.kokoro"),
+            )));
 
             let app_handle = app.handle();
             tauri::async_runtime::block_on(async move {
@@ -293,6 +479,7 @@ pub fn run() {
                             "stage=ai.init.ok elapsed_ms={}",
                             startup_begin.elapsed().as_millis()
                         );
+                        crate::crash::set_subsystem_active("ai", true);
 
                         // Restore proactive_enabled from disk
                         let proactive_path = app_data_dir.join("proactive_enabled.json");
@@ -318,6 +505,10 @@ pub fn run() {
                         orchestrator.set_memory_enabled(memory_enabled).await;
                         tracing::info!(target: "ai", "Restored memory_enabled={}", memory_enabled);
 
+                        orchestrator.input_queue.set_config(crate::ai::input_queue::load_config(
+                            &crate::ai::input_queue::config_path(),
+                        ));
+
                         // Restore jailbreak_prompt from disk
                         let jailbreak_path = app_data_dir.join("jailbreak_prompt.json");
                         if let Ok(content) = std::fs::read_to_string(&jailbreak_path) {
@@ -363,29 +554,15 @@ pub fn run() {
                         if let Ok(content) = std::fs::read_to_string(&conv_id_path) {
                             if let Ok(val) = serde_json::from_str::<serde_json::Value>(&content) {
                                 if let Some(id) = val.get("conversation_id").and_then(|v| v.as_str()) {
-                                    {
-                                        let mut conv_id = orchestrator.current_conversation_id.lock().await;
-                                        *conv_id = Some(id.to_string());
-                                    }
                                     // Reload messages into in-memory history so LLM has conversation context
-                                    if let Ok(rows) = sqlx::query_as::<_, (String, String, Option<String>)>(
-                                        "SELECT role, content, metadata FROM conversation_messages WHERE conversation_id = ? ORDER BY id ASC"
-                                    )
-                                    .bind(id)
-                                    .fetch_all(&orchestrator.db)
-                                    .await {
-                                        let mut history = orchestrator.history.lock().await;
-                                        history.clear();
-                                        for (role, content, metadata) in &rows {
-                                            history.push_back(crate::ai::context::Message {
-                                                role: role.clone(),
-                                                content: content.clone(),
-                                                metadata: metadata
-                                                    .as_deref()
-                                                    .and_then(|raw| serde_json::from_str::<serde_json::Value>(raw).ok()),
-                                            });
+                                    match orchestrator.switch_conversation(id, None).await {
+                                        Ok(()) => {
+                                            let buffered = orchestrator.history_on(crate::ai::history::ChatSurface::Desktop).await.len();
+                                            tracing::info!(target: "ai", "Restored current_conversation_id: {} ({} messages)", id, buffered);
+                                        }
+                                        Err(e) => {
+                                            tracing::error!(target: "ai", "Failed to restore conversation {}: {}", id, e);
                                         }
-                                        tracing::info!(target: "ai", "Restored current_conversation_id: {} ({} messages)", id, rows.len());
                                     }
                                 }
                             }
@@ -457,6 +634,7 @@ pub fn run() {
                 "stage=tts.init.done elapsed_ms={}",
                 startup_begin.elapsed().as_millis()
             );
+            crate::crash::set_subsystem_active("tts", true);
 
             // ImageGen
             let imagegen_config_path = app_data.join("imagegen_config.json");
@@ -470,12 +648,36 @@ pub fn run() {
             let imagegen_service = tauri::async_runtime::block_on(async {
                 crate::imagegen::ImageGenService::init_from_config(&imagegen_config).await
             });
+            // Asset store (shares the orchestrator's database for its index)
+            if let Some(orchestrator) = app.try_state::<crate::ai::context::AIOrchestrator>() {
+                let asset_store =
+                    crate::assets::AssetStore::new(orchestrator.db.clone(), app_data.clone());
+                tauri::async_runtime::block_on(
+                    imagegen_service.set_asset_store(asset_store.clone()),
+                );
+                app.manage(asset_store);
+
+                // Trash (soft delete for memories, conversations, characters and models)
+                let trash = crate::trash::Trash::new(
+                    orchestrator.db.clone(),
+                    &app.path().app_data_dir().unwrap_or_else(|_| app_data.clone()),
+                );
+                let sweeper = trash.clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = sweeper.purge_expired().await {
+                        tracing::warn!(target: "trash", "[Trash] Retention sweep failed: {}", e);
+                    }
+                });
+                app.manage(trash);
+            }
+            tauri::async_runtime::block_on(imagegen_service.set_app_handle(app.handle().clone()));
             app.manage(imagegen_service);
             tracing::info!(
                 target: "startup",
                 "stage=imagegen.init.done elapsed_ms={}",
                 startup_begin.elapsed().as_millis()
             );
+            crate::crash::set_subsystem_active("imagegen", true);
 
             // WindowSizeState
             app.manage(crate::commands::system::WindowSizeState::new());
@@ -496,6 +698,7 @@ pub fn run() {
                 "stage=llm.init.done elapsed_ms={}",
                 startup_begin.elapsed().as_millis()
             );
+            crate::crash::set_subsystem_active("llm", true);
 
             // STT
             let stt_config_path = app_data.join("stt_config.json");
@@ -514,6 +717,63 @@ pub fn run() {
                 "stage=stt.init.done elapsed_ms={}",
                 startup_begin.elapsed().as_millis()
             );
+            crate::crash::set_subsystem_active("stt", true);
+
+            // Offline mode (fallback routing is applied by the heartbeat's network probe)
+            let offline_config = crate::offline::load_config(&crate::offline::config_path());
+            app.manage(crate::offline::OfflineModeService::new(offline_config));
+            let wallpaper_config = crate::wallpaper::load_config(&crate::wallpaper::config_path());
+            app.manage(crate::wallpaper::WallpaperService::new(wallpaper_config));
+
+            // Provider warm-up (typing pre-warm and keep-alive pings for local backends)
+            let warmup_service = crate::warmup::WarmupService::new(crate::warmup::load_config(
+                &crate::warmup::config_path(),
+            ));
+            warmup_service.spawn_keep_alive_loop(app.handle().clone());
+            app.manage(warmup_service);
+            app.manage(crate::captions::CaptionService::new());
+            app.manage(crate::voice_session::VoiceSessionService::new());
+            app.manage(crate::ai::duet::DuetService::new());
+            app.manage(crate::ai::story::StoryService::new());
+            app.manage(crate::ai::proactive_composer::ProactiveComposer::new(
+                crate::ai::proactive_composer::load_config(
+                    &crate::ai::proactive_composer::config_path(),
+                ),
+            ));
+
+            // External triggers (local API, MIDI, global shortcuts) mapped to engine actions
+            app.manage(crate::input_mapping::InputMappingService::new(
+                crate::input_mapping::load_config(&crate::input_mapping::config_path()),
+            ));
+            let mapping_handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                let service = mapping_handle.state::<crate::input_mapping::InputMappingService>();
+                service.apply(&mapping_handle).await;
+            });
+
+            // Outbound webhooks (memory, mood and reminder events) with a retrying outbox
+            let webhook_service =
+                crate::webhooks::WebhookService::new(crate::webhooks::load_config(
+                    &crate::webhooks::config_path(),
+                ));
+            webhook_service.spawn_loop(app.handle().clone());
+            app.manage(webhook_service);
+
+            // Character profiles: bind the restored character's model, voice and personality
+            // now that the LLM and TTS services exist.
+            let profiles_config =
+                crate::character_profiles::load_config(&crate::character_profiles::config_path());
+            app.manage(crate::character_profiles::CharacterProfileService::new(profiles_config));
+            if let Some(char_id) = crate::ai::context::AIOrchestrator::load_active_character_id() {
+                let app_handle = app.handle().clone();
+                tauri::async_runtime::block_on(async move {
+                    if let Err(e) =
+                        crate::character_profiles::switch_character(&app_handle, &char_id, true).await
+                    {
+                        tracing::warn!(target: "ai", "Failed to apply character profile for {}: {}", char_id, e);
+                    }
+                });
+            }
 
             let hook_runtime = HookRuntime::new();
             hook_runtime.register(Arc::new(AuditLogHookHandler));
@@ -537,6 +797,17 @@ pub fn run() {
                 action_registry,
             )));
             app.manage(Arc::new(tokio::sync::RwLock::new(tool_settings)));
+            app.manage(Arc::new(tokio::sync::RwLock::new(
+                crate::ai::latency::load_config(&crate::ai::latency::config_path()),
+            )));
+            app.manage(Arc::new(tokio::sync::RwLock::new(
+                crate::actions::plan::load_config(&crate::actions::plan::config_path()),
+            )));
+            app.manage(Arc::new(crate::actions::plan::PlanControlState::default()));
+            app.manage(Arc::new(tokio::sync::RwLock::new(
+                crate::actions::code_exec::load_config(&crate::actions::code_exec::config_path()),
+            )));
+            app.manage(Arc::new(crate::actions::tables::TableStore::default()));
 
             // MCP Manager
             let mcp_config_path = app_data.join("mcp_servers.json");
@@ -550,11 +821,20 @@ pub fn run() {
             mcp_manager.load_configs();
             let mcp_manager = Arc::new(tokio::sync::Mutex::new(mcp_manager));
             app.manage(mcp_manager.clone());
+            app.manage(Arc::new(crate::mcp::bridge::McpToolConfirmState::new()));
+            crate::mcp::health::spawn_health_monitor(
+                app.handle().clone(),
+                mcp_manager.clone(),
+                app.state::<Arc<tokio::sync::RwLock<crate::actions::ActionRegistry>>>()
+                    .inner()
+                    .clone(),
+            );
             tracing::info!(
                 target: "startup",
                 "stage=mcp.init.done elapsed_ms={}",
                 startup_begin.elapsed().as_millis()
             );
+            crate::crash::set_subsystem_active("mcp", true);
 
             // Connect MCP servers in background — per-server tasks so the
             // manager lock is only held briefly and list_mcp_servers stays responsive.
@@ -565,9 +845,9 @@ pub fn run() {
                 tokio::time::sleep(tokio::time::Duration::from_secs(3)).await;
 
                 // Grab configs & mark "connecting", then release lock immediately
-                let configs = {
+                let (configs, oauth) = {
                     let mut mgr = mcp_mgr_clone.lock().await;
-                    mgr.prepare_connect_all()
+                    (mgr.prepare_connect_all(), mgr.oauth_store())
                 };
 
                 // Spawn a task per server so they connect in parallel
@@ -575,11 +855,12 @@ pub fn run() {
                 for cfg in configs {
                     let mgr_arc = mcp_mgr_clone.clone();
                     let app_handle = mcp_app.clone();
+                    let oauth = oauth.clone();
                     handles.push(tauri::async_runtime::spawn(async move {
                         // Slow I/O (process spawn / TCP / MCP handshake) happens
                         // outside the lock so list_mcp_servers stays responsive.
                         let build_result =
-                            crate::mcp::manager::build_connected_client(&cfg).await;
+                            crate::mcp::manager::build_connected_client(&cfg, &oauth).await;
 
                         // Brief lock only to insert the result.
                         let connect_result = {
@@ -647,6 +928,7 @@ pub fn run() {
                 "stage=vision.server.done elapsed_ms={}",
                 startup_begin.elapsed().as_millis()
             );
+            crate::crash::set_subsystem_active("vision", true);
 
             // ModManager init: spawns QuickJS thread + event relay
             // In debug (dev) mode, fall back to the project-relative `mods/` directory
@@ -698,20 +980,29 @@ pub fn run() {
                 "stage=mods.init.start elapsed_ms={}",
                 startup_begin.elapsed().as_millis()
             );
-            let mut mod_manager = ModManager::new(mods_path);
+            let mut mod_manager = ModManager::new(mods_path)
+                .with_permissions_file(app_data.join("mod_permissions.json"))
+                .with_storage_dir(app_data.join("mod_storage"));
             mod_manager.init(app.handle().clone());
             app.manage(tokio::sync::Mutex::new(mod_manager));
+            crate::mods::watcher::spawn(app.handle().clone());
             tracing::info!(
                 target: "startup",
                 "stage=mods.init.done elapsed_ms={}",
                 startup_begin.elapsed().as_millis()
             );
+            crate::crash::set_subsystem_active("mods", true);
+
+            // Sleep/wake lifecycle — the heartbeat reacts to resumes it records
+            app.manage(crate::lifecycle::LifecycleManager::new());
+            crate::lifecycle::spawn_sleep_watcher(app.handle().clone());
 
             // Heartbeat — proactive behavior background loop
             let heartbeat_handle = app.handle().clone();
             tauri::async_runtime::spawn(async move {
                 crate::ai::heartbeat::heartbeat_loop(heartbeat_handle).await;
             });
+            crate::ai::emotion::forward_app_events(app.handle());
 
             // Vision Watcher
             let vision_config_path = app_data.join("vision_config.json");
@@ -725,6 +1016,11 @@ pub fn run() {
             let vision_watcher = crate::vision::watcher::VisionWatcher::new(vision_config.clone())
                 .with_llm_service(llm_svc_for_vision);
             app.manage(vision_watcher.clone());
+            app.manage(Arc::new(
+                crate::vision::image_files::ImageDescriptionCache::load(
+                    app_data.join("image_descriptions.json"),
+                ),
+            ));
             tracing::info!(
                 target: "startup",
                 "stage=vision.watcher.init.done elapsed_ms={}",
@@ -764,6 +1060,7 @@ pub fn run() {
                 "stage=telegram.init.done elapsed_ms={}",
                 startup_begin.elapsed().as_millis()
             );
+            crate::crash::set_subsystem_active("telegram", true);
 
             // Auto-start Telegram bot if enabled
             if telegram_enabled {
@@ -785,6 +1082,43 @@ pub fn run() {
                 });
             }
 
+            // Local API server (opt-in)
+            let api_server_config = crate::api_server::load_config(&crate::api_server::config_path());
+            let api_server_enabled = api_server_config.enabled;
+            let api_server = crate::api_server::ApiServerService::new(api_server_config);
+            api_server.forward_app_events(app.handle());
+            app.manage(api_server.clone());
+            if api_server_enabled {
+                let api_app = app.handle().clone();
+                tauri::async_runtime::spawn(async move {
+                    tokio::time::sleep(std::time::Duration::from_secs(3)).await;
+                    if let Err(e) = api_server.start(api_app).await {
+                        tracing::error!(target: "api", "API server auto-start failed: {}", e);
+                    }
+                });
+            }
+
+            // VTube Studio integration (opt-in)
+            let vts_config = crate::vts::load_config(&crate::vts::config_path());
+            let vts_enabled = vts_config.enabled;
+            let vts_service = crate::vts::VtsService::new(vts_config);
+            vts_service.forward_app_events(app.handle());
+            app.manage(vts_service.clone());
+            if vts_enabled {
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = vts_service.start().await {
+                        tracing::error!(target: "vts", "VTube Studio auto-connect failed: {}", e);
+                    }
+                });
+            }
+
+            // Automation rules (startup rules wait for the main window)
+            let automation_service = crate::automation::AutomationService::new(
+                crate::automation::load_config(&crate::automation::config_path()),
+            );
+            automation_service.spawn_cron_loop(app.handle().clone());
+            app.manage(automation_service);
+
             // Global shortcut + Pet window auto-start
             {
                 let pet_cfg = crate::commands::pet::load_pet_config();
@@ -0,0 +1,163 @@
+//! Sleep/wake lifecycle.
+//!
+//! Neither std nor Tauri reports OS suspend, so the watcher samples the wall
+//! clock between short monotonic sleeps: a tick that lands minutes after it
+//! was due means the machine was asleep in between. On resume the watcher
+//! emits `system-resumed` and re-warms providers, MCP reconnects skip their
+//! backoff, and a pending [`Resume`] is left for the heartbeat, which owns
+//! the proactive limiter and maintenance timers, to greet the user, catch up
+//! on chores and reconcile schedules that came due while asleep.
+
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+/// How often the watcher samples the wall clock.
+const WATCH_TICK: Duration = Duration::from_secs(5);
+/// Wall-clock drift past the tick that counts as a sleep. Large enough that
+/// NTP corrections and a busy runtime don't register.
+const MIN_SLEEP_SECS: i64 = 60;
+/// Absence after which the character greets the user on resume.
+pub const GREET_AFTER_SECS: u64 = 30 * 60;
+
+/// Payload of `system-resumed`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Resume {
+    /// Approximate time spent asleep.
+    pub slept_secs: u64,
+    /// Unix timestamp (seconds) of the wake-up.
+    pub resumed_at: i64,
+}
+
+/// Managed Tauri state shared by the watcher and the loops that react to it.
+#[derive(Debug, Default)]
+pub struct LifecycleManager {
+    pending: Mutex<Option<Resume>>,
+    resumes: AtomicU64,
+}
+
+impl LifecycleManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Wake-ups seen since launch, so a loop can notice a resume without
+    /// consuming it.
+    pub fn resume_count(&self) -> u64 {
+        self.resumes.load(Ordering::SeqCst)
+    }
+
+    /// Take the resume the heartbeat has not handled yet.
+    pub fn take_pending_resume(&self) -> Option<Resume> {
+        self.pending
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take()
+    }
+
+    /// Back-to-back sleeps before the heartbeat ticks merge into one resume.
+    fn record_resume(&self, resume: Resume) {
+        let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+        let earlier = pending.map_or(0, |p| p.slept_secs);
+        *pending = Some(Resume {
+            slept_secs: earlier + resume.slept_secs,
+            ..resume
+        });
+        self.resumes.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+/// Seconds asleep between two wall-clock samples taken `tick` apart on the
+/// monotonic clock, if the machine slept at all.
+fn slept_between(before: i64, after: i64, tick: Duration) -> Option<u64> {
+    let drift = after - before - tick.as_secs() as i64;
+    (drift >= MIN_SLEEP_SECS).then_some(drift as u64)
+}
+
+/// Start the sleep watcher; it runs for the lifetime of the app.
+pub fn spawn_sleep_watcher(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut last_seen = chrono::Utc::now().timestamp();
+        loop {
+            tokio::time::sleep(WATCH_TICK).await;
+            let now = chrono::Utc::now().timestamp();
+            let slept = slept_between(last_seen, now, WATCH_TICK);
+            last_seen = now;
+            let Some(slept_secs) = slept else {
+                continue;
+            };
+
+            tracing::info!(
+                target: "lifecycle",
+                "[Lifecycle] Resumed after about {} min asleep",
+                slept_secs / 60
+            );
+            let resume = Resume {
+                slept_secs,
+                resumed_at: now,
+            };
+            if let Some(lifecycle) = app.try_state::<LifecycleManager>() {
+                lifecycle.record_resume(resume);
+            }
+            let _ = app.emit("system-resumed", resume);
+            if let Some(warmup) = app.try_state::<crate::warmup::WarmupService>() {
+                warmup.warm_after_resume(&app).await;
+            }
+        }
+    });
+}
+
+/// Proactive instruction for greeting the user after `absent_secs` away.
+pub fn greeting_instruction(absent_secs: u64) -> String {
+    format!(
+        "The computer just woke from sleep and the user is back after about {} away. Welcome them back briefly, in a way that fits how long it has been.",
+        describe_absence(absent_secs)
+    )
+}
+
+/// "40 minutes", "5 hours", then the catch-up wording for a day or more.
+fn describe_absence(secs: u64) -> String {
+    match secs {
+        0..=5_399 => format!("{} minutes", (secs / 60).max(2)),
+        5_400..=84_599 => format!("{} hours", (secs + 1_800) / 3_600),
+        _ => crate::ai::catch_up::describe_gap(secs as i64),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_sleep_from_wall_clock_drift() {
+        assert_eq!(slept_between(1_000, 1_005, WATCH_TICK), None);
+        assert_eq!(slept_between(1_000, 1_060, WATCH_TICK), None);
+        assert_eq!(slept_between(1_000, 4_605, WATCH_TICK), Some(3_600));
+        // The clock was set back; not a sleep.
+        assert_eq!(slept_between(1_000, 0, WATCH_TICK), None);
+
+        let lifecycle = LifecycleManager::new();
+        assert_eq!(lifecycle.take_pending_resume(), None);
+        for (slept_secs, resumed_at) in [(600, 10_000), (900, 20_000)] {
+            lifecycle.record_resume(Resume {
+                slept_secs,
+                resumed_at,
+            });
+        }
+        assert_eq!(lifecycle.resume_count(), 2);
+        assert_eq!(
+            lifecycle.take_pending_resume(),
+            Some(Resume {
+                slept_secs: 1_500,
+                resumed_at: 20_000
+            })
+        );
+        assert_eq!(lifecycle.take_pending_resume(), None);
+
+        assert_eq!(describe_absence(40 * 60), "40 minutes");
+        assert_eq!(describe_absence(5 * 3_600 + 100), "5 hours");
+        assert_eq!(describe_absence(3 * 86_400), "3 days");
+    }
+}
@@ -99,6 +99,8 @@ impl AnthropicProvider {
             .send()
             .await
             .map_err(|error| format!("Failed to call Anthropic Messages API: {}", error))?;
+        crate::utils::rate_limit::limiter(&self.provider_id)
+            .observe(response.status(), response.headers());
 
         let response = ensure_success(response).await?;
         response
@@ -121,6 +123,8 @@ impl AnthropicProvider {
             .send()
             .await
             .map_err(|error| format!("Failed to start Anthropic stream: {}", error))?;
+        crate::utils::rate_limit::limiter(&self.provider_id)
+            .observe(response.status(), response.headers());
 
         let response = ensure_success(response).await?;
         let mut stream = response.bytes_stream().eventsource();
@@ -0,0 +1,481 @@
+//! Custom HTTP provider — request/response mapping described in config.
+//!
+//! For backends that are neither OpenAI- nor Anthropic-compatible. The
+//! provider's `extra.custom_http` object describes the request (URL, headers,
+//! JSON body template) and where the text sits in the response, so a new
+//! backend needs a config entry instead of a new adapter:
+//!
+//! ```json
+//! {
+//!   "url": "{{base_url}}/generate",
+//!   "headers": { "Authorization": "Bearer {{api_key}}" },
+//!   "body": { "model": "{{model}}", "messages": "{{messages}}", "stream": "{{stream}}" },
+//!   "stream_format": "sse",
+//!   "stream_delta_path": "$.choices[0].delta.content",
+//!   "response_path": "$.choices[0].message.content"
+//! }
+//! ```
+//!
+//! Body placeholders: `{{messages}}` (array of `{role, content}`), `{{prompt}}`
+//! (the conversation flattened to text), `{{model}}`, `{{stream}}`,
+//! `{{temperature}}`, `{{max_tokens}}`, `{{top_p}}`, `{{stop}}`; URL and headers
+//! can also use `{{base_url}}` and `{{api_key}}`. Template rules are in
+//! [`crate::utils::template`].
+
+use async_openai::types::chat::ChatCompletionRequestMessage;
+use async_trait::async_trait;
+use eventsource_stream::Eventsource;
+use futures::{channel::mpsc, Stream, StreamExt};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::pin::Pin;
+
+use crate::error::KokoroError;
+use crate::llm::llm_config::LlmProviderConfig;
+use crate::llm::messages::extract_message_text;
+use crate::llm::provider::{LlmParams, LlmProvider};
+use crate::utils::template::{self, select_text, TemplateVars};
+
+/// Key of the spec inside `LlmProviderConfig::extra`.
+pub const CUSTOM_HTTP_EXTRA_KEY: &str = "custom_http";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum StreamFormat {
+    /// `text/event-stream`; each `data:` payload is one JSON chunk.
+    #[default]
+    Sse,
+    /// One JSON object per line.
+    Ndjson,
+    /// No streaming; the full response is delivered as a single chunk.
+    None,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CustomHttpSpec {
+    /// Request URL; may use `{{base_url}}` and `{{model}}`. Defaults to `base_url`.
+    #[serde(default)]
+    pub url: Option<String>,
+    /// Header values may use `{{api_key}}` and `{{model}}`.
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    /// JSON body template.
+    pub body: Value,
+    #[serde(default)]
+    pub stream_format: StreamFormat,
+    /// Path to the text delta in each stream chunk, e.g. `$.choices[0].delta.content`.
+    #[serde(default)]
+    pub stream_delta_path: Option<String>,
+    /// Path to the full text in a non-streamed response.
+    pub response_path: String,
+    /// Path to an error message in a response or chunk, e.g. `$.error.message`.
+    #[serde(default)]
+    pub error_path: Option<String>,
+    /// Stream payload that marks the end, e.g. `[DONE]`.
+    #[serde(default = "default_done_marker")]
+    pub done_marker: Option<String>,
+    /// Renames roles in `{{messages}}`, e.g. `{"assistant": "model"}`.
+    #[serde(default)]
+    pub role_map: HashMap<String, String>,
+}
+
+fn default_done_marker() -> Option<String> {
+    Some("[DONE]".to_string())
+}
+
+impl CustomHttpSpec {
+    pub fn from_provider_config(cfg: &LlmProviderConfig) -> Result<Self, KokoroError> {
+        let raw = cfg.extra.get(CUSTOM_HTTP_EXTRA_KEY).ok_or_else(|| {
+            KokoroError::Config(format!(
+                "custom_http provider '{}' needs an extra.{} object",
+                cfg.id, CUSTOM_HTTP_EXTRA_KEY
+            ))
+        })?;
+        let spec: Self = serde_json::from_value(raw.clone()).map_err(|e| {
+            KokoroError::Config(format!(
+                "Invalid extra.{} for provider '{}': {}",
+                CUSTOM_HTTP_EXTRA_KEY, cfg.id, e
+            ))
+        })?;
+        spec.validate(cfg.base_url.as_deref())?;
+        Ok(spec)
+    }
+
+    fn validate(&self, base_url: Option<&str>) -> Result<(), KokoroError> {
+        if self.url.is_none() && base_url.is_none_or(|url| url.trim().is_empty()) {
+            return Err(KokoroError::Config(
+                "custom_http needs `url` or a base URL".to_string(),
+            ));
+        }
+        if self.stream_format != StreamFormat::None && self.stream_delta_path.is_none() {
+            return Err(KokoroError::Config(
+                "custom_http streaming needs `stream_delta_path` (or stream_format \"none\")"
+                    .to_string(),
+            ));
+        }
+        let paths = [Some(&self.response_path), self.stream_delta_path.as_ref()];
+        for path in paths.into_iter().flatten().chain(self.error_path.as_ref()) {
+            template::validate_path(path).map_err(KokoroError::Config)?;
+        }
+        Ok(())
+    }
+}
+
+// ── Request templating ─────────────────────────────────
+
+fn role_name(message: &ChatCompletionRequestMessage) -> &'static str {
+    match message {
+        ChatCompletionRequestMessage::System(_) | ChatCompletionRequestMessage::Developer(_) => {
+            "system"
+        }
+        ChatCompletionRequestMessage::User(_) => "user",
+        ChatCompletionRequestMessage::Assistant(_) => "assistant",
+        ChatCompletionRequestMessage::Tool(_) => "tool",
+        ChatCompletionRequestMessage::Function(_) => "function",
+    }
+}
+
+fn request_vars(
+    spec: &CustomHttpSpec,
+    base_url: &str,
+    api_key: &str,
+    model: &str,
+    messages: &[ChatCompletionRequestMessage],
+    options: Option<&LlmParams>,
+    stream: bool,
+) -> TemplateVars {
+    let turns: Vec<(String, String)> = messages
+        .iter()
+        .map(|message| {
+            let role = role_name(message);
+            let role = spec.role_map.get(role).map(String::as_str).unwrap_or(role);
+            (role.to_string(), extract_message_text(message))
+        })
+        .collect();
+    let prompt = turns
+        .iter()
+        .map(|(role, content)| format!("{}: {}", role, content))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    let messages: Vec<Value> = turns
+        .into_iter()
+        .map(|(role, content)| json!({ "role": role, "content": content }))
+        .collect();
+
+    let mut vars = TemplateVars::new();
+    vars.set("messages", messages)
+        .set("prompt", prompt)
+        .set("model", model)
+        .set("stream", stream)
+        .set("base_url", base_url.trim_end_matches('/'))
+        .set("api_key", api_key);
+    if let Some(options) = options {
+        vars.set_opt("temperature", options.temperature)
+            .set_opt("max_tokens", options.max_tokens)
+            .set_opt("top_p", options.top_p)
+            .set_opt("stop", options.stop.clone());
+    }
+    vars
+}
+
+// ── Provider ───────────────────────────────────────────
+
+pub struct CustomHttpProvider {
+    client: Client,
+    spec: CustomHttpSpec,
+    base_url: String,
+    api_key: String,
+    model: String,
+    provider_id: String,
+}
+
+impl CustomHttpProvider {
+    pub fn from_config(cfg: &LlmProviderConfig) -> Result<Self, KokoroError> {
+        Ok(Self {
+            client: Client::new(),
+            spec: CustomHttpSpec::from_provider_config(cfg)?,
+            base_url: cfg.base_url.clone().unwrap_or_default(),
+            api_key: cfg.resolve_api_key().unwrap_or_default(),
+            model: cfg.model.clone().unwrap_or_default(),
+            provider_id: cfg.id.clone(),
+        })
+    }
+
+    async fn send(
+        &self,
+        messages: &[ChatCompletionRequestMessage],
+        options: Option<&LlmParams>,
+        stream: bool,
+    ) -> Result<reqwest::Response, String> {
+        let vars = request_vars(
+            &self.spec,
+            &self.base_url,
+            &self.api_key,
+            &self.model,
+            messages,
+            options,
+            stream,
+        );
+        let url = vars.interpolate(self.spec.url.as_deref().unwrap_or("{{base_url}}"));
+        let body = vars.render(&self.spec.body).unwrap_or(Value::Null);
+        let mut request = self.client.post(&url).json(&body);
+        for (name, value) in &self.spec.headers {
+            request = request.header(name.as_str(), vars.interpolate(value));
+        }
+        let response = request
+            .send()
+            .await
+            .map_err(|e| format!("Custom HTTP request to {} failed: {}", url, e))?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            let message = serde_json::from_str::<Value>(&text)
+                .ok()
+                .zip(self.spec.error_path.as_deref())
+                .and_then(|(body, path)| select_text(&body, path))
+                .unwrap_or(text);
+            return Err(format!(
+                "Custom HTTP request failed (HTTP {}): {}",
+                status,
+                message.trim()
+            ));
+        }
+        Ok(response)
+    }
+
+    fn parse_full_response(&self, text: &str) -> Result<String, String> {
+        let body: Value = serde_json::from_str(text)
+            .map_err(|e| format!("Failed to parse custom HTTP response JSON: {}", e))?;
+        if let Some(error) = self
+            .spec
+            .error_path
+            .as_deref()
+            .and_then(|path| select_text(&body, path))
+        {
+            return Err(error);
+        }
+        select_text(&body, &self.spec.response_path).ok_or_else(|| {
+            format!(
+                "Custom HTTP response has no text at '{}'",
+                self.spec.response_path
+            )
+        })
+    }
+}
+
+/// What one stream chunk contributes.
+#[derive(Debug, PartialEq)]
+enum ChunkOutcome {
+    Text(String),
+    Skip,
+    Done,
+    Error(String),
+}
+
+fn parse_stream_chunk(spec: &CustomHttpSpec, payload: &str) -> ChunkOutcome {
+    let payload = payload.trim();
+    if payload.is_empty() {
+        return ChunkOutcome::Skip;
+    }
+    if spec.done_marker.as_deref() == Some(payload) {
+        return ChunkOutcome::Done;
+    }
+    let Ok(chunk) = serde_json::from_str::<Value>(payload) else {
+        // Keep-alives and comments some backends interleave.
+        return ChunkOutcome::Skip;
+    };
+    if let Some(error) = spec
+        .error_path
+        .as_deref()
+        .and_then(|path| select_text(&chunk, path))
+    {
+        return ChunkOutcome::Error(error);
+    }
+    match spec
+        .stream_delta_path
+        .as_deref()
+        .and_then(|path| select_text(&chunk, path))
+    {
+        Some(text) if !text.is_empty() => ChunkOutcome::Text(text),
+        _ => ChunkOutcome::Skip,
+    }
+}
+
+/// Send one payload's contribution; false once the stream is finished or the
+/// receiver is gone.
+fn forward_chunk(
+    spec: &CustomHttpSpec,
+    tx: &mut mpsc::UnboundedSender<Result<String, String>>,
+    payload: &str,
+) -> bool {
+    match parse_stream_chunk(spec, payload) {
+        ChunkOutcome::Text(text) => tx.start_send(Ok(text)).is_ok(),
+        ChunkOutcome::Skip => true,
+        ChunkOutcome::Done => false,
+        ChunkOutcome::Error(e) => {
+            let _ = tx.start_send(Err(e));
+            false
+        }
+    }
+}
+
+#[async_trait]
+impl LlmProvider for CustomHttpProvider {
+    async fn chat(
+        &self,
+        messages: Vec<ChatCompletionRequestMessage>,
+        options: Option<LlmParams>,
+    ) -> Result<String, String> {
+        let response = self.send(&messages, options.as_ref(), false).await?;
+        let text = response
+            .text()
+            .await
+            .map_err(|e| format!("Failed to read custom HTTP response: {}", e))?;
+        self.parse_full_response(&text)
+    }
+
+    async fn chat_stream(
+        &self,
+        messages: Vec<ChatCompletionRequestMessage>,
+        options: Option<LlmParams>,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<String, String>> + Send>>, String> {
+        if self.spec.stream_format == StreamFormat::None {
+            let text = self.chat(messages, options).await?;
+            return Ok(Box::pin(futures::stream::once(async move { Ok(text) })));
+        }
+
+        let response = self.send(&messages, options.as_ref(), true).await?;
+        let (mut tx, rx) = mpsc::unbounded::<Result<String, String>>();
+        let spec = self.spec.clone();
+
+        tokio::spawn(async move {
+            if spec.stream_format == StreamFormat::Sse {
+                let mut events = response.bytes_stream().eventsource();
+                while let Some(event) = events.next().await {
+                    match event {
+                        Ok(event) if forward_chunk(&spec, &mut tx, &event.data) => {}
+                        Ok(_) => return,
+                        Err(e) => {
+                            let _ =
+                                tx.start_send(Err(format!("Custom HTTP SSE stream error: {}", e)));
+                            return;
+                        }
+                    }
+                }
+                return;
+            }
+
+            let mut bytes = response.bytes_stream();
+            let mut buffer = Vec::new();
+            while let Some(chunk) = bytes.next().await {
+                let chunk = match chunk {
+                    Ok(chunk) => chunk,
+                    Err(e) => {
+                        let _ = tx.start_send(Err(format!("Custom HTTP stream error: {}", e)));
+                        return;
+                    }
+                };
+                buffer.extend_from_slice(&chunk);
+                while let Some(pos) = buffer.iter().position(|b| *b == b'\n') {
+                    let line: Vec<u8> = buffer.drain(..=pos).collect();
+                    if !forward_chunk(&spec, &mut tx, &String::from_utf8_lossy(&line)) {
+                        return;
+                    }
+                }
+            }
+            // Last line may lack a trailing newline.
+            forward_chunk(&spec, &mut tx, &String::from_utf8_lossy(&buffer));
+        });
+
+        Ok(Box::pin(rx))
+    }
+
+    fn id(&self) -> &str {
+        &self.provider_id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::messages::{system_message, user_text_message};
+
+    fn spec() -> CustomHttpSpec {
+        serde_json::from_value(json!({
+            "url": "{{base_url}}/v2/generate",
+            "headers": { "Authorization": "Bearer {{api_key}}" },
+            "body": {
+                "model": "{{model}}",
+                "history": "{{messages}}",
+                "options": { "temperature": "{{temperature}}", "top_p": "{{top_p}}" },
+                "stream": "{{stream}}",
+                "note": "model={{model}}"
+            },
+            "stream_format": "ndjson",
+            "stream_delta_path": "$.output[0].text",
+            "response_path": "result.0.text",
+            "error_path": "$.error.message",
+            "role_map": { "assistant": "model" }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn renders_templates_and_extracts_text_by_path() {
+        let spec = spec();
+        let messages = vec![system_message("be nice"), user_text_message("hi")];
+        let options = LlmParams {
+            temperature: Some(0.5),
+            ..LlmParams::default()
+        };
+        let vars = request_vars(
+            &spec,
+            "http://localhost:9000/",
+            "k",
+            "m1",
+            &messages,
+            Some(&options),
+            true,
+        );
+
+        assert_eq!(
+            vars.interpolate(spec.url.as_deref().unwrap()),
+            "http://localhost:9000/v2/generate"
+        );
+        assert_eq!(vars.interpolate(&spec.headers["Authorization"]), "Bearer k");
+        assert_eq!(
+            vars.render(&spec.body).unwrap(),
+            json!({
+                "model": "m1",
+                "history": [
+                    { "role": "system", "content": "be nice" },
+                    { "role": "user", "content": "hi" }
+                ],
+                // Unset top_p is dropped rather than sent as a string.
+                "options": { "temperature": 0.5 },
+                "stream": true,
+                "note": "model=m1"
+            })
+        );
+
+        assert_eq!(
+            parse_stream_chunk(&spec, r#"{"output":[{"text":"Hel"}]}"#),
+            ChunkOutcome::Text("Hel".to_string())
+        );
+        assert_eq!(
+            parse_stream_chunk(&spec, ": keep-alive"),
+            ChunkOutcome::Skip
+        );
+        assert_eq!(parse_stream_chunk(&spec, "[DONE]"), ChunkOutcome::Done);
+        assert_eq!(
+            parse_stream_chunk(&spec, r#"{"error":{"message":"overloaded"}}"#),
+            ChunkOutcome::Error("overloaded".to_string())
+        );
+        assert_eq!(
+            select_text(&json!({"result": [{"text": "full"}]}), &spec.response_path),
+            Some("full".to_string())
+        );
+    }
+}
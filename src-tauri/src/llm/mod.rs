@@ -1,8 +1,10 @@
 pub mod anthropic;
 pub mod context;
+pub mod custom_http;
 pub mod llama_cpp;
 pub mod llm_config;
 pub mod messages;
 pub mod ollama;
 pub mod provider;
+pub mod rate_limited;
 pub mod service;
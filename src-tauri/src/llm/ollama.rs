@@ -26,6 +26,8 @@ pub struct OllamaModelInfo {
 
 pub struct OllamaProvider {
     client: Client<OpenAIConfig>,
+    /// Server root without `/v1`, for the native API.
+    native_base_url: String,
     model: String,
 }
 
@@ -33,9 +35,11 @@ impl OllamaProvider {
     pub fn new(base_url: Option<String>, model: String) -> Self {
         let compat_base =
             normalize_ollama_chat_base_url(base_url.as_deref().unwrap_or("http://localhost:11434"));
+        let native_base_url = compat_base.trim_end_matches("/v1").to_string();
 
         Self {
             client: build_openai_client("ollama".to_string(), Some(compat_base)),
+            native_base_url,
             model,
         }
     }
@@ -128,6 +132,30 @@ impl LlmProvider for OllamaProvider {
         create_chat_stream_with_tools(&self.client, &self.model, messages, options, tools).await
     }
 
+    /// A generate request without a prompt loads the model and resets
+    /// Ollama's idle unload timer.
+    async fn warm_up(&self) -> Result<(), String> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(120))
+            .no_proxy()
+            .build()
+            .map_err(|e| format!("Failed to build Ollama client: {}", e))?;
+        let response = client
+            .post(format!("{}/api/generate", self.native_base_url))
+            .json(&serde_json::json!({ "model": self.model }))
+            .send()
+            .await
+            .map_err(|e| format!("Failed to warm up Ollama model {}: {}", self.model, e))?;
+        if !response.status().is_success() {
+            return Err(format!(
+                "Ollama /api/generate returned error {} while loading {}",
+                response.status(),
+                self.model
+            ));
+        }
+        Ok(())
+    }
+
     fn id(&self) -> &str {
         "ollama"
     }
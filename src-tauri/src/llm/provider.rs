@@ -74,6 +74,35 @@ impl From<ChatCompletionRequestMessage> for LlmChatMessage {
     }
 }
 
+/// Text and native tool calls drained from one streamed round.
+#[derive(Debug, Default)]
+pub struct LlmRoundOutput {
+    pub text: String,
+    pub tool_calls: Vec<LlmToolCall>,
+    /// Stream error that ended the round early, if any.
+    pub error: Option<String>,
+}
+
+/// Drain a tool-aware stream. Reasoning content is dropped; the first stream
+/// error ends the round and is reported alongside any partial output.
+pub async fn collect_stream_round(
+    mut stream: Pin<Box<dyn Stream<Item = Result<LlmStreamEvent, String>> + Send>>,
+) -> LlmRoundOutput {
+    let mut output = LlmRoundOutput::default();
+    while let Some(result) = stream.next().await {
+        match result {
+            Ok(LlmStreamEvent::Text(content)) => output.text.push_str(&content),
+            Ok(LlmStreamEvent::ReasoningContent(_)) => {}
+            Ok(LlmStreamEvent::ToolCall(tool_call)) => output.tool_calls.push(tool_call),
+            Err(error) => {
+                output.error = Some(error);
+                break;
+            }
+        }
+    }
+    output
+}
+
 #[derive(Default)]
 struct PartialToolCall {
     id: String,
@@ -160,6 +189,12 @@ pub trait LlmProvider: Send + Sync {
         .await
     }
 
+    /// Load the model ahead of a request so the first token isn't delayed by a
+    /// cold start. Remote providers have nothing to warm.
+    async fn warm_up(&self) -> Result<(), String> {
+        Ok(())
+    }
+
     fn id(&self) -> &str;
 }
 
@@ -525,6 +560,8 @@ impl OpenAIProvider {
             .send()
             .await
             .map_err(|error| format!("Failed to call OpenAI-compatible chat API: {}", error))?;
+        crate::utils::rate_limit::limiter(&self.provider_id)
+            .observe(response.status(), response.headers());
 
         if response.status().is_success() {
             return Ok(response);
@@ -0,0 +1,147 @@
+//! `LlmProvider` decorator that runs every call through the provider's shared
+//! [`RateLimiter`](crate::utils::rate_limit::RateLimiter).
+//!
+//! Streaming calls are the replies a user is waiting on (chat, Telegram, the
+//! API server, duet and story turns), so they run as interactive. One-shot
+//! `chat` calls are the background jobs (memory extraction, consolidation,
+//! summaries, translation, proactive checks) and get the background share.
+
+use crate::llm::messages::extract_message_text;
+use crate::llm::provider::{
+    LlmChatMessage, LlmParams, LlmProvider, LlmStreamEvent, LlmToolDefinition,
+};
+use crate::utils::rate_limit::{self, Priority, RateLimiter, RateLimits};
+use async_openai::types::chat::ChatCompletionRequestMessage;
+use async_trait::async_trait;
+use futures::Stream;
+use std::pin::Pin;
+use std::sync::Arc;
+
+/// Conservative for mixed CJK/Latin text, like the prompt budget.
+const CHARS_PER_TOKEN: usize = 2;
+/// Completion size assumed when the call sets no `max_tokens`.
+const DEFAULT_COMPLETION_TOKENS: u32 = 512;
+
+pub struct RateLimitedProvider {
+    inner: Box<dyn LlmProvider>,
+    limiter: Arc<RateLimiter>,
+}
+
+impl RateLimitedProvider {
+    /// Wrap `inner`, sharing the limiter of config id `provider_id`.
+    pub fn new(inner: Box<dyn LlmProvider>, provider_id: &str, limits: RateLimits) -> Self {
+        let limiter = rate_limit::limiter(provider_id);
+        limiter.configure(limits);
+        Self { inner, limiter }
+    }
+
+    async fn acquire<'a>(
+        &self,
+        messages: impl IntoIterator<Item = &'a ChatCompletionRequestMessage>,
+        options: Option<&LlmParams>,
+        priority: Priority,
+    ) {
+        let prompt_chars: usize = messages
+            .into_iter()
+            .map(|message| extract_message_text(message).chars().count())
+            .sum();
+        let completion = options
+            .and_then(|options| options.max_tokens)
+            .unwrap_or(DEFAULT_COMPLETION_TOKENS);
+        let tokens = (prompt_chars.div_ceil(CHARS_PER_TOKEN) as u32).saturating_add(completion);
+        self.limiter.acquire(tokens, priority).await;
+    }
+}
+
+#[async_trait]
+impl LlmProvider for RateLimitedProvider {
+    async fn chat(
+        &self,
+        messages: Vec<ChatCompletionRequestMessage>,
+        options: Option<LlmParams>,
+    ) -> Result<String, String> {
+        self.acquire(&messages, options.as_ref(), Priority::Background)
+            .await;
+        self.inner.chat(messages, options).await
+    }
+
+    async fn chat_stream(
+        &self,
+        messages: Vec<ChatCompletionRequestMessage>,
+        options: Option<LlmParams>,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<String, String>> + Send>>, String> {
+        self.acquire(&messages, options.as_ref(), Priority::Interactive)
+            .await;
+        self.inner.chat_stream(messages, options).await
+    }
+
+    async fn chat_stream_with_tools(
+        &self,
+        messages: Vec<ChatCompletionRequestMessage>,
+        options: Option<LlmParams>,
+        tools: Vec<LlmToolDefinition>,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<LlmStreamEvent, String>> + Send>>, String> {
+        self.acquire(&messages, options.as_ref(), Priority::Interactive)
+            .await;
+        self.inner
+            .chat_stream_with_tools(messages, options, tools)
+            .await
+    }
+
+    fn supports_native_tools(&self) -> bool {
+        self.inner.supports_native_tools()
+    }
+
+    async fn chat_rich(
+        &self,
+        messages: Vec<LlmChatMessage>,
+        options: Option<LlmParams>,
+    ) -> Result<String, String> {
+        self.acquire(
+            messages.iter().map(|message| &message.message),
+            options.as_ref(),
+            Priority::Background,
+        )
+        .await;
+        self.inner.chat_rich(messages, options).await
+    }
+
+    async fn chat_stream_rich(
+        &self,
+        messages: Vec<LlmChatMessage>,
+        options: Option<LlmParams>,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<LlmStreamEvent, String>> + Send>>, String> {
+        self.acquire(
+            messages.iter().map(|message| &message.message),
+            options.as_ref(),
+            Priority::Interactive,
+        )
+        .await;
+        self.inner.chat_stream_rich(messages, options).await
+    }
+
+    async fn chat_stream_with_tools_rich(
+        &self,
+        messages: Vec<LlmChatMessage>,
+        options: Option<LlmParams>,
+        tools: Vec<LlmToolDefinition>,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<LlmStreamEvent, String>> + Send>>, String> {
+        self.acquire(
+            messages.iter().map(|message| &message.message),
+            options.as_ref(),
+            Priority::Interactive,
+        )
+        .await;
+        self.inner
+            .chat_stream_with_tools_rich(messages, options, tools)
+            .await
+    }
+
+    async fn warm_up(&self) -> Result<(), String> {
+        self.inner.warm_up().await
+    }
+
+    fn id(&self) -> &str {
+        self.inner.id()
+    }
+}
@@ -2,12 +2,15 @@
 
 use crate::error::KokoroError;
 use crate::llm::anthropic::AnthropicProvider;
+use crate::llm::custom_http::CustomHttpProvider;
 use crate::llm::llama_cpp::LlamaCppProvider;
 use crate::llm::llm_config::{LlmConfig, LlmPreset, LlmProviderConfig};
 use crate::llm::messages::user_text_message;
 use crate::llm::ollama::OllamaProvider;
-use crate::llm::provider::{LlmProvider, OpenAIProvider};
-use serde::Serialize;
+use crate::llm::provider::{LlmParams, LlmProvider, OpenAIProvider};
+use crate::llm::rate_limited::RateLimitedProvider;
+use crate::utils::rate_limit::RateLimits;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -23,6 +26,57 @@ pub struct LlmService {
     active_provider_id: Arc<RwLock<String>>,
     config: Arc<RwLock<LlmConfig>>,
     config_path: PathBuf,
+    /// Local provider forced while the engine is in offline mode.
+    offline_provider_id: Arc<RwLock<Option<String>>>,
+    /// Model settings bound to the active character.
+    character_binding: Arc<RwLock<LlmCharacterBinding>>,
+}
+
+/// Per-character override of the chat model. Empty fields keep the global config.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LlmCharacterBinding {
+    pub provider_id: Option<String>,
+    pub model: Option<String>,
+    pub temperature: Option<f32>,
+}
+
+/// Model settings pinned to one conversation, persisted on its row. A provider
+/// or model here replaces the character binding's; temperature falls back to it.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LlmConversationOverride {
+    #[serde(default)]
+    pub provider_id: Option<String>,
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub temperature: Option<f32>,
+}
+
+impl LlmConversationOverride {
+    pub fn is_empty(&self) -> bool {
+        self.provider_id.is_none() && self.model.is_none() && self.temperature.is_none()
+    }
+}
+
+/// Layer a conversation override on top of the character binding.
+fn effective_binding(
+    character: &LlmCharacterBinding,
+    conversation: Option<&LlmConversationOverride>,
+) -> LlmCharacterBinding {
+    let Some(conversation) = conversation else {
+        return character.clone();
+    };
+    let (provider_id, model) = if conversation.provider_id.is_some() || conversation.model.is_some()
+    {
+        (conversation.provider_id.clone(), conversation.model.clone())
+    } else {
+        (character.provider_id.clone(), character.model.clone())
+    };
+    LlmCharacterBinding {
+        provider_id,
+        model,
+        temperature: conversation.temperature.or(character.temperature),
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize)]
@@ -98,20 +152,41 @@ impl LlmService {
             active_provider_id: Arc::new(RwLock::new(active_provider_id)),
             config: Arc::new(RwLock::new(normalized_config)),
             config_path,
+            offline_provider_id: Arc::new(RwLock::new(None)),
+            character_binding: Arc::new(RwLock::new(LlmCharacterBinding::default())),
         }
     }
 
     /// Try get a clone of the active provider (Arc'd for async use).
     pub async fn try_provider(&self) -> Result<Arc<dyn LlmProvider>, KokoroError> {
+        self.try_provider_for(None).await
+    }
+
+    /// Like [`Self::try_provider`], honouring a conversation's pinned model.
+    pub async fn try_provider_for(
+        &self,
+        conversation: Option<&LlmConversationOverride>,
+    ) -> Result<Arc<dyn LlmProvider>, KokoroError> {
         let active_id = self.active_provider_id.read().await.clone();
         let providers = self.providers.read().await;
 
+        if let Some(offline_id) = self.offline_provider_id.read().await.as_ref() {
+            if let Some(provider) = providers.get(offline_id) {
+                return Ok(provider.clone());
+            }
+        }
+
         if providers.is_empty() {
             return Err(KokoroError::Config(
                 "No available LLM provider: provider map is empty".to_string(),
             ));
         }
 
+        let binding = effective_binding(&self.character_binding.read().await, conversation);
+        if let Some(provider) = self.bound_provider(&providers, &binding, &active_id).await {
+            return Ok(provider);
+        }
+
         providers.get(&active_id).cloned().ok_or_else(|| {
             KokoroError::Config(format!(
                 "No available LLM provider: active provider '{}' is not configured",
@@ -120,12 +195,101 @@ impl LlmService {
         })
     }
 
+    /// Provider for a character or conversation binding, or `None` when it has no provider/model
+    /// override or names a provider that is not configured.
+    async fn bound_provider(
+        &self,
+        providers: &HashMap<String, Arc<dyn LlmProvider>>,
+        binding: &LlmCharacterBinding,
+        active_id: &str,
+    ) -> Option<Arc<dyn LlmProvider>> {
+        if binding.provider_id.is_none() && binding.model.is_none() {
+            return None;
+        }
+        let provider_id = match binding.provider_id.as_deref() {
+            Some(id) if providers.contains_key(id) => id,
+            Some(id) => {
+                tracing::warn!(
+                    target: "llm",
+                    "Bound provider '{}' is not configured or disabled; using active provider",
+                    id
+                );
+                active_id
+            }
+            None => active_id,
+        };
+        let Some(model) = binding.model.as_ref() else {
+            return providers.get(provider_id).cloned();
+        };
+        let config = self.config.read().await;
+        let provider_config = config
+            .providers
+            .iter()
+            .find(|cfg| cfg.id == provider_id && cfg.enabled)?;
+        let mut temporary_provider_config = provider_config.clone();
+        temporary_provider_config.model = Some(model.clone());
+        Some(Arc::from(build_from_provider_config(
+            &temporary_provider_config,
+        )))
+    }
+
+    /// Bind model settings to the active character; the default binding clears it.
+    pub async fn set_character_binding(&self, binding: LlmCharacterBinding) {
+        *self.character_binding.write().await = binding;
+    }
+
+    /// Sampling parameters for chat turns, from the character binding.
+    pub async fn chat_params(&self) -> Option<LlmParams> {
+        self.chat_params_for(None).await
+    }
+
+    /// Sampling parameters for a turn in a conversation with a pinned model.
+    pub async fn chat_params_for(
+        &self,
+        conversation: Option<&LlmConversationOverride>,
+    ) -> Option<LlmParams> {
+        let temperature = conversation.and_then(|c| c.temperature).or(self
+            .character_binding
+            .read()
+            .await
+            .temperature)?;
+        Some(LlmParams {
+            temperature: Some(temperature),
+            ..LlmParams::default()
+        })
+    }
+
     /// Get a clone of the active provider (Arc'd for async use).
     pub async fn provider(&self) -> Arc<dyn LlmProvider> {
-        self.try_provider().await.unwrap_or_else(|error| {
-            tracing::error!(target: "llm", "Failed to resolve active provider: {}", error);
-            default_provider()
-        })
+        self.provider_for(None).await
+    }
+
+    /// Like [`Self::provider`], honouring a conversation's pinned model.
+    pub async fn provider_for(
+        &self,
+        conversation: Option<&LlmConversationOverride>,
+    ) -> Arc<dyn LlmProvider> {
+        self.try_provider_for(conversation)
+            .await
+            .unwrap_or_else(|error| {
+                tracing::error!(target: "llm", "Failed to resolve active provider: {}", error);
+                default_provider()
+            })
+    }
+
+    /// Config id of the provider chat is routed to right now.
+    pub async fn routed_provider_id(&self) -> String {
+        let providers = self.providers.read().await;
+        if let Some(offline_id) = self.offline_provider_id.read().await.as_ref() {
+            if providers.contains_key(offline_id) {
+                return offline_id.clone();
+            }
+        }
+        let binding = self.character_binding.read().await;
+        match binding.provider_id.as_ref() {
+            Some(id) if providers.contains_key(id) => id.clone(),
+            _ => self.active_provider_id.read().await.clone(),
+        }
     }
 
     /// Get a clone of the current config.
@@ -152,12 +316,47 @@ impl LlmService {
 
         Ok(())
     }
+
+    /// Route all LLM traffic to `provider_id` while offline; `None` restores normal routing.
+    pub async fn set_offline_fallback(&self, provider_id: Option<String>) {
+        if let Some(id) = provider_id.as_ref() {
+            if !self.providers.read().await.contains_key(id) {
+                tracing::warn!(
+                    target: "llm",
+                    "Offline fallback provider '{}' is not configured or disabled; keeping active provider",
+                    id
+                );
+            }
+        }
+        *self.offline_provider_id.write().await = provider_id;
+    }
+
+    /// Whether `provider` should be driven through native function calling.
+    /// The per-provider config flag wins; otherwise the provider's own capability decides.
+    pub async fn native_tools_enabled(&self, provider: &dyn LlmProvider) -> bool {
+        let provider_id = provider.id();
+        self.config
+            .read()
+            .await
+            .providers
+            .iter()
+            .find(|cfg| cfg.id == provider_id)
+            .map(|cfg| cfg.supports_native_tools)
+            .unwrap_or_else(|| provider.supports_native_tools())
+    }
+
     /// Get the system provider (or fallback to active).
     pub async fn system_provider(&self) -> Arc<dyn LlmProvider> {
         let config = self.config.read().await.clone();
         let active_id = self.active_provider_id.read().await.clone();
         let providers = self.providers.read().await;
 
+        if let Some(offline_id) = self.offline_provider_id.read().await.as_ref() {
+            if let Some(provider) = providers.get(offline_id) {
+                return provider.clone();
+            }
+        }
+
         let resolved_id = config
             .system_provider
             .as_ref()
@@ -449,15 +648,32 @@ fn build_from_provider_config(cfg: &LlmProviderConfig) -> Box<dyn LlmProvider> {
 
         let api_key = cfg.resolve_api_key().unwrap_or_default();
         let model = cfg.model.clone().unwrap_or_else(|| "gpt-4".to_string());
-        Box::new(
-            OpenAIProvider::new(api_key, cfg.base_url.clone(), Some(model)).with_id(cfg.id.clone()),
+        rate_limited(
+            cfg,
+            Box::new(
+                OpenAIProvider::new(api_key, cfg.base_url.clone(), Some(model))
+                    .with_id(cfg.id.clone()),
+            ),
         )
     })
 }
 
+/// Share the per-provider rate limiter with every other client of `cfg.id`.
+fn rate_limited(cfg: &LlmProviderConfig, provider: Box<dyn LlmProvider>) -> Box<dyn LlmProvider> {
+    Box::new(RateLimitedProvider::new(
+        provider,
+        &cfg.id,
+        RateLimits::from_extra(&cfg.extra),
+    ))
+}
+
 fn try_build_from_provider_config(
     cfg: &LlmProviderConfig,
 ) -> Result<Box<dyn LlmProvider>, KokoroError> {
+    Ok(rate_limited(cfg, try_build_provider_client(cfg)?))
+}
+
+fn try_build_provider_client(cfg: &LlmProviderConfig) -> Result<Box<dyn LlmProvider>, KokoroError> {
     match cfg.provider_type.as_str() {
         "anthropic" => {
             let api_key = cfg.resolve_api_key().unwrap_or_default();
@@ -503,6 +719,16 @@ fn try_build_from_provider_config(
                 cfg.id.clone(),
             )))
         }
+        "custom_http" => {
+            let provider = CustomHttpProvider::from_config(cfg)?;
+            tracing::info!(
+                target: "llm",
+                "Initializing custom HTTP provider: id={}, model={}",
+                cfg.id,
+                cfg.model.as_deref().unwrap_or("<unset>")
+            );
+            Ok(Box::new(provider))
+        }
         "openai" => {
             let api_key = cfg.resolve_api_key().unwrap_or_default();
             let model = cfg.model.clone().unwrap_or_else(|| "gpt-4".to_string());
@@ -702,6 +928,41 @@ mod tests {
         assert!(Arc::ptr_eq(&provider, &expected_active));
     }
 
+    #[tokio::test]
+    async fn conversation_override_takes_precedence_over_character_binding() {
+        let service = make_service_with_active_and_system_provider();
+        service
+            .set_character_binding(LlmCharacterBinding {
+                provider_id: Some("system-provider".to_string()),
+                model: None,
+                temperature: Some(0.3),
+            })
+            .await;
+
+        let pinned = LlmConversationOverride {
+            provider_id: Some("other-provider".to_string()),
+            model: None,
+            temperature: None,
+        };
+        let provider = service.provider_for(Some(&pinned)).await;
+        assert_eq!(provider.id(), "other-provider");
+        let params = service.chat_params_for(Some(&pinned)).await.unwrap();
+        assert_eq!(params.temperature, Some(0.3));
+
+        let warmer = LlmConversationOverride {
+            temperature: Some(1.2),
+            ..LlmConversationOverride::default()
+        };
+        assert_eq!(
+            service.provider_for(Some(&warmer)).await.id(),
+            "system-provider"
+        );
+        let params = service.chat_params_for(Some(&warmer)).await.unwrap();
+        assert_eq!(params.temperature, Some(1.2));
+
+        assert_eq!(service.provider().await.id(), "system-provider");
+    }
+
     #[tokio::test]
     async fn returns_explicit_error_when_no_available_provider() {
         let service = make_service_with_no_enabled_provider();
@@ -2,15 +2,88 @@
 //!
 //! Wraps each MCP tool as an `ActionHandler` so the LLM can invoke
 //! MCP tools through the same `[TOOL_CALL:...]` mechanism as builtins.
+//!
+//! Per-tool policies from `mcp_servers.json` apply here: disabled tools are
+//! never registered, and tools that need confirmation emit `tool-confirm`
+//! and wait for the user before the call reaches the server. Doing this in
+//! the handler covers every caller (chat, Telegram, direct execution).
 
 use super::manager::McpManager;
 use crate::actions::registry::{
-    ActionContext, ActionError, ActionHandler, ActionParam, ActionResult,
+    ActionContext, ActionError, ActionHandler, ActionParam, ActionResult, ActionRiskTag,
 };
+use crate::error::KokoroError;
 use async_trait::async_trait;
+use serde::Serialize;
 use std::collections::HashMap;
 use std::sync::Arc;
-use tokio::sync::Mutex;
+use std::time::Duration;
+use tauri::{Emitter, Manager};
+use tokio::sync::{oneshot, Mutex};
+
+/// How long a confirmation prompt waits before the call is declined.
+const CONFIRM_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Payload of the `tool-confirm` event.
+#[derive(Debug, Clone, Serialize)]
+pub struct McpToolConfirmRequest {
+    pub request_id: String,
+    pub server_name: String,
+    pub tool_name: String,
+    pub arguments: serde_json::Value,
+    pub character_id: String,
+    pub source: Option<String>,
+    pub timeout_secs: u64,
+}
+
+/// Outstanding `tool-confirm` prompts, resolved by `resolve_mcp_tool_confirm`.
+#[derive(Default)]
+pub struct McpToolConfirmState {
+    pending: Mutex<HashMap<String, oneshot::Sender<bool>>>,
+}
+
+impl McpToolConfirmState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    async fn register(&self, request_id: &str) -> oneshot::Receiver<bool> {
+        let (tx, rx) = oneshot::channel();
+        self.pending.lock().await.insert(request_id.to_string(), tx);
+        rx
+    }
+
+    /// Deliver the user's answer; unknown ids have already timed out.
+    pub async fn resolve(&self, request_id: &str, approved: bool) -> Result<(), KokoroError> {
+        let tx = self
+            .pending
+            .lock()
+            .await
+            .remove(request_id)
+            .ok_or_else(|| {
+                KokoroError::NotFound(format!("Tool confirmation '{}' not pending", request_id))
+            })?;
+        let _ = tx.send(approved);
+        Ok(())
+    }
+
+    /// Ask the frontend and wait; anything but an explicit approval declines.
+    async fn confirm(&self, app: &tauri::AppHandle, request: McpToolConfirmRequest) -> bool {
+        let request_id = request.request_id.clone();
+        let rx = self.register(&request_id).await;
+        if let Err(e) = app.emit("tool-confirm", &request) {
+            tracing::error!(target: "mcp", "Failed to emit tool-confirm: {}", e);
+            self.pending.lock().await.remove(&request_id);
+            return false;
+        }
+        let approved = matches!(
+            tokio::time::timeout(CONFIRM_TIMEOUT, rx).await,
+            Ok(Ok(true))
+        );
+        self.pending.lock().await.remove(&request_id);
+        approved
+    }
+}
 
 /// An ActionHandler that delegates to an MCP server tool.
 pub struct McpToolHandler {
@@ -24,47 +97,10 @@ pub struct McpToolHandler {
     pub input_schema: Option<serde_json::Value>,
     /// Shared reference to the MCP manager.
     pub manager: Arc<Mutex<McpManager>>,
-}
-
-impl McpToolHandler {
-    /// Parse JSON Schema properties into ActionParam list.
-    fn schema_to_params(schema: &Option<serde_json::Value>) -> Vec<ActionParam> {
-        let schema = match schema {
-            Some(s) => s,
-            None => return Vec::new(),
-        };
-
-        let properties = match schema.get("properties").and_then(|p| p.as_object()) {
-            Some(p) => p,
-            None => return Vec::new(),
-        };
-
-        let required: Vec<String> = schema
-            .get("required")
-            .and_then(|r| r.as_array())
-            .map(|arr| {
-                arr.iter()
-                    .filter_map(|v| v.as_str().map(|s| s.to_string()))
-                    .collect()
-            })
-            .unwrap_or_default();
-
-        properties
-            .iter()
-            .map(|(name, prop)| {
-                let description = prop
-                    .get("description")
-                    .and_then(|d| d.as_str())
-                    .unwrap_or("")
-                    .to_string();
-                ActionParam {
-                    name: name.clone(),
-                    description,
-                    required: required.contains(name),
-                }
-            })
-            .collect()
-    }
+    /// Whether the server marks this tool as destructive.
+    pub destructive: bool,
+    /// Ask the user before each call.
+    pub requires_confirmation: bool,
 }
 
 #[async_trait]
@@ -78,17 +114,28 @@ impl ActionHandler for McpToolHandler {
     }
 
     fn parameters(&self) -> Vec<ActionParam> {
-        Self::schema_to_params(&self.input_schema)
+        self.input_schema
+            .as_ref()
+            .map(ActionParam::from_json_schema)
+            .unwrap_or_default()
     }
 
     fn needs_feedback(&self) -> bool {
         true
     }
 
+    fn risk_tags(&self) -> Vec<ActionRiskTag> {
+        if self.destructive {
+            vec![ActionRiskTag::Write]
+        } else {
+            vec![ActionRiskTag::Read]
+        }
+    }
+
     async fn execute(
         &self,
         args: HashMap<String, String>,
-        _ctx: ActionContext,
+        ctx: ActionContext,
     ) -> Result<ActionResult, ActionError> {
         // Convert HashMap<String, String> to JSON object
         let arguments = serde_json::Value::Object(
@@ -101,6 +148,37 @@ impl ActionHandler for McpToolHandler {
                 .collect(),
         );
 
+        if self.requires_confirmation {
+            // Fail closed: without the confirm state nobody can approve.
+            let approved = match ctx.app.try_state::<Arc<McpToolConfirmState>>() {
+                Some(state) => {
+                    let request = McpToolConfirmRequest {
+                        request_id: uuid::Uuid::new_v4().to_string(),
+                        server_name: self.server_name.clone(),
+                        tool_name: self.tool_name.clone(),
+                        arguments: arguments.clone(),
+                        character_id: ctx.character_id.clone(),
+                        source: ctx.source.clone(),
+                        timeout_secs: CONFIRM_TIMEOUT.as_secs(),
+                    };
+                    state.confirm(&ctx.app, request).await
+                }
+                None => false,
+            };
+            if !approved {
+                tracing::info!(
+                    target: "mcp",
+                    "Call to '{}' on '{}' was not approved",
+                    self.tool_name,
+                    self.server_name
+                );
+                return Ok(ActionResult::err(format!(
+                    "The user did not approve running '{}'.",
+                    self.tool_name
+                )));
+            }
+        }
+
         let manager = self.manager.lock().await;
         let result = manager
             .call_tool(&self.server_name, &self.tool_name, arguments)
@@ -141,19 +219,78 @@ pub async fn register_mcp_tools(
     registry: &tokio::sync::RwLock<crate::actions::ActionRegistry>,
 ) {
     let mgr = manager.lock().await;
-    let tools = mgr.all_tools().await;
+    let tools: Vec<_> = mgr
+        .all_tools()
+        .await
+        .into_iter()
+        .map(|(server_name, tool)| {
+            let policy = mgr.tool_policy(&server_name, &tool.name);
+            (server_name, tool, policy)
+        })
+        .collect();
     drop(mgr); // Release lock before acquiring registry write lock
 
     let mut reg = registry.write().await;
     reg.clear_mcp_tools();
-    for (server_name, tool) in tools {
+    for (server_name, tool, policy) in tools {
+        if !policy.enabled {
+            continue;
+        }
+        let destructive = tool.is_destructive();
         let handler = McpToolHandler {
             server_name: server_name.clone(),
             tool_name: tool.name.clone(),
             description: tool.description.unwrap_or_default(),
             input_schema: tool.input_schema,
             manager: manager.clone(),
+            destructive,
+            requires_confirmation: policy.requires_confirmation(destructive),
         };
         reg.register_mcp(server_name, handler);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mcp::client::McpToolInfo;
+    use crate::mcp::manager::McpToolPolicy;
+
+    fn tool(annotations: serde_json::Value) -> McpToolInfo {
+        serde_json::from_value(serde_json::json!({
+            "name": "t",
+            "annotations": annotations,
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn confirmation_follows_annotations_and_overrides() {
+        let plain: McpToolInfo =
+            serde_json::from_value(serde_json::json!({ "name": "t" })).unwrap();
+        assert!(!plain.is_destructive());
+        assert!(!tool(serde_json::json!({ "readOnlyHint": true })).is_destructive());
+        assert!(tool(serde_json::json!({ "readOnlyHint": false })).is_destructive());
+        assert!(!tool(serde_json::json!({ "destructiveHint": false })).is_destructive());
+
+        let default = McpToolPolicy::default();
+        assert!(default.requires_confirmation(true));
+        assert!(!default.requires_confirmation(false));
+        let always_ask = McpToolPolicy {
+            auto_approve: Some(false),
+            ..McpToolPolicy::default()
+        };
+        assert!(always_ask.requires_confirmation(false));
+        let trusted = McpToolPolicy {
+            auto_approve: Some(true),
+            ..McpToolPolicy::default()
+        };
+        assert!(!trusted.requires_confirmation(true));
+
+        let state = McpToolConfirmState::new();
+        let rx = state.register("req").await;
+        state.resolve("req", true).await.unwrap();
+        assert_eq!(rx.await, Ok(true));
+        assert!(state.resolve("req", true).await.is_err());
+    }
+}
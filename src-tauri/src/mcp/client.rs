@@ -18,6 +18,32 @@ pub struct McpToolInfo {
     /// JSON Schema for tool input
     #[serde(default, rename = "inputSchema")]
     pub input_schema: Option<Value>,
+    /// Behaviour hints the server declares for this tool.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub annotations: Option<McpToolAnnotations>,
+}
+
+/// MCP tool annotations. Servers are untrusted, so these are hints only:
+/// they pick the default confirmation policy, which the user can override.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct McpToolAnnotations {
+    #[serde(default, rename = "readOnlyHint")]
+    pub read_only_hint: Option<bool>,
+    #[serde(default, rename = "destructiveHint")]
+    pub destructive_hint: Option<bool>,
+}
+
+impl McpToolInfo {
+    /// Per the MCP spec a tool is assumed destructive unless it is read-only
+    /// or says otherwise; tools without annotations are treated as unknown
+    /// rather than destructive so plain servers don't prompt on every call.
+    pub fn is_destructive(&self) -> bool {
+        match &self.annotations {
+            Some(a) if a.read_only_hint == Some(true) => false,
+            Some(a) => a.destructive_hint.unwrap_or(true),
+            None => false,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
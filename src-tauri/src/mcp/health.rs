@@ -0,0 +1,200 @@
+//! MCP Health Monitor — reconnects servers that drop after startup.
+//!
+//! A stdio server that crashes (or an HTTP server that goes away) leaves a
+//! disconnected client behind and its tools fail on every call. The monitor
+//! polls the manager, drops dead clients, and retries them with exponential
+//! backoff, re-registering tools once a server is back. Every transition is
+//! emitted as `mcp-server-status` so the settings UI can follow along.
+
+use super::manager::{build_connected_client, McpManager, McpServerStatus};
+use crate::actions::ActionRegistry;
+use crate::commands::mcp::format_connection_error;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::{Mutex, RwLock};
+
+/// How often connection health is checked.
+const CHECK_INTERVAL: Duration = Duration::from_secs(5);
+/// Delay after the first failed attempt; doubled per failure up to [`MAX_BACKOFF`].
+const INITIAL_BACKOFF: Duration = Duration::from_secs(2);
+const MAX_BACKOFF: Duration = Duration::from_secs(300);
+
+/// Payload of `mcp-server-status`.
+#[derive(Debug, Clone, Serialize)]
+pub struct McpServerStatusEvent {
+    #[serde(flatten)]
+    pub status: McpServerStatus,
+    /// Failed reconnect attempts since the server dropped.
+    pub reconnect_attempts: u32,
+    /// Seconds until the next attempt, while the server is down.
+    pub next_retry_secs: Option<u64>,
+}
+
+/// Delay before the next attempt after `failures` failed ones.
+fn backoff_delay(failures: u32) -> Duration {
+    INITIAL_BACKOFF
+        .checked_mul(1u32 << failures.saturating_sub(1).min(16))
+        .map_or(MAX_BACKOFF, |delay| delay.min(MAX_BACKOFF))
+}
+
+#[derive(Debug)]
+struct Retry {
+    failures: u32,
+    next_at: Instant,
+}
+
+/// Start the monitor; it runs for the lifetime of the app.
+pub fn spawn_health_monitor(
+    app: AppHandle,
+    manager: Arc<Mutex<McpManager>>,
+    registry: Arc<RwLock<ActionRegistry>>,
+) {
+    tauri::async_runtime::spawn(async move {
+        let mut retries: HashMap<String, Retry> = HashMap::new();
+        let mut seen_resumes = 0;
+        loop {
+            tokio::time::sleep(CHECK_INTERVAL).await;
+
+            let (unhealthy, oauth) = {
+                let mgr = manager.lock().await;
+                (mgr.unhealthy_servers().await, mgr.oauth_store())
+            };
+            // Servers that recovered, were disabled or removed, or are being
+            // reconnected by hand are no longer ours to retry.
+            retries.retain(|name, _| unhealthy.iter().any(|c| &c.name == name));
+            // After the machine wakes, retry at once instead of sitting out
+            // a backoff that mostly elapsed while it was asleep.
+            let resumes = app
+                .try_state::<crate::lifecycle::LifecycleManager>()
+                .map_or(0, |lifecycle| lifecycle.resume_count());
+            if resumes != seen_resumes {
+                seen_resumes = resumes;
+                let now = Instant::now();
+                for retry in retries.values_mut() {
+                    retry.next_at = now;
+                }
+            }
+
+            for config in unhealthy {
+                let (failures, next_at) = match retries.get(&config.name) {
+                    Some(retry) => (retry.failures, retry.next_at),
+                    None => {
+                        tracing::warn!(
+                            target: "mcp",
+                            "[MCP/Health] '{}' is disconnected, scheduling reconnect",
+                            config.name
+                        );
+                        {
+                            let mut mgr = manager.lock().await;
+                            // Drop the dead client so its tools leave the registry.
+                            let _ = mgr.disconnect_server(&config.name).await;
+                            if mgr
+                                .server_status(&config.name)
+                                .await
+                                .and_then(|s| s.error)
+                                .is_none()
+                            {
+                                mgr.set_connection_error(
+                                    &config.name,
+                                    "Server disconnected".to_string(),
+                                );
+                            }
+                        }
+                        super::bridge::register_mcp_tools(&manager, &registry).await;
+                        (0, Instant::now())
+                    }
+                };
+                if next_at > Instant::now() {
+                    continue;
+                }
+
+                manager.lock().await.mark_connecting(&config.name);
+                emit_status(&app, &manager, &config.name, failures, None).await;
+
+                let build_result = build_connected_client(&config, &oauth).await;
+                let connected = {
+                    let mut mgr = manager.lock().await;
+                    mgr.clear_connecting(&config.name);
+                    let still_wanted = mgr.get_config(&config.name).is_some_and(|c| c.enabled);
+                    match build_result {
+                        Ok(client) if still_wanted => {
+                            mgr.insert_client(config.name.clone(), client);
+                            true
+                        }
+                        // Removed or disabled while we were connecting.
+                        Ok(client) => {
+                            let _ = client.shutdown().await;
+                            continue;
+                        }
+                        Err(e) => {
+                            mgr.set_connection_error(&config.name, format_connection_error(&e));
+                            false
+                        }
+                    }
+                };
+
+                if connected {
+                    tracing::info!(target: "mcp", "[MCP/Health] Reconnected '{}'", config.name);
+                    retries.remove(&config.name);
+                    super::bridge::register_mcp_tools(&manager, &registry).await;
+                    emit_status(&app, &manager, &config.name, 0, None).await;
+                } else {
+                    let failures = failures + 1;
+                    let delay = backoff_delay(failures);
+                    tracing::warn!(
+                        target: "mcp",
+                        "[MCP/Health] Reconnect {} for '{}' failed, next in {}s",
+                        failures,
+                        config.name,
+                        delay.as_secs()
+                    );
+                    retries.insert(
+                        config.name.clone(),
+                        Retry {
+                            failures,
+                            next_at: Instant::now() + delay,
+                        },
+                    );
+                    emit_status(&app, &manager, &config.name, failures, Some(delay)).await;
+                }
+            }
+        }
+    });
+}
+
+async fn emit_status(
+    app: &AppHandle,
+    manager: &Arc<Mutex<McpManager>>,
+    name: &str,
+    reconnect_attempts: u32,
+    next_retry: Option<Duration>,
+) {
+    let Some(status) = manager.lock().await.server_status(name).await else {
+        return;
+    };
+    let event = McpServerStatusEvent {
+        status,
+        reconnect_attempts,
+        next_retry_secs: next_retry.map(|d| d.as_secs()),
+    };
+    if let Err(e) = app.emit("mcp-server-status", &event) {
+        tracing::error!(target: "mcp", "Failed to emit mcp-server-status: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_and_caps() {
+        assert_eq!(backoff_delay(1), Duration::from_secs(2));
+        assert_eq!(backoff_delay(2), Duration::from_secs(4));
+        assert_eq!(backoff_delay(6), Duration::from_secs(64));
+        assert_eq!(backoff_delay(9), MAX_BACKOFF);
+        assert_eq!(backoff_delay(u32::MAX), MAX_BACKOFF);
+    }
+}
@@ -3,6 +3,7 @@
 //! Loads server configs, starts/stops servers, aggregates tools.
 
 use super::client::McpClient;
+use super::oauth::{McpOAuthConfig, McpOAuthSession, McpOAuthStore};
 use super::transport::{SseTransport, StdioTransport, StreamableHttpTransport};
 use crate::error::KokoroError;
 use serde::{Deserialize, Serialize};
@@ -36,6 +37,41 @@ pub struct McpServerConfig {
     /// Whether to auto-connect on startup.
     #[serde(default = "default_true")]
     pub enabled: bool,
+    /// Per-tool overrides, keyed by tool name. Tools without an entry are
+    /// enabled and follow the server's annotations for confirmation.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub tools: HashMap<String, McpToolPolicy>,
+    /// Pre-registered OAuth client for HTTP servers; without it, servers that
+    /// require sign-in get a dynamically registered client.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub oauth: Option<McpOAuthConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct McpToolPolicy {
+    /// Disabled tools are not registered with the action registry.
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// `Some(true)` runs without asking, `Some(false)` always asks,
+    /// `None` asks only for tools the server marks destructive.
+    #[serde(default)]
+    pub auto_approve: Option<bool>,
+}
+
+impl Default for McpToolPolicy {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            auto_approve: None,
+        }
+    }
+}
+
+impl McpToolPolicy {
+    /// Whether a call must be confirmed by the user before it runs.
+    pub fn requires_confirmation(&self, destructive: bool) -> bool {
+        self.auto_approve.map_or(destructive, |auto| !auto)
+    }
 }
 
 fn default_transport_type() -> String {
@@ -57,6 +93,21 @@ pub struct McpServerStatus {
     pub status: String,
     /// Error message if connection failed.
     pub error: Option<String>,
+    /// "authorized" when signed in via OAuth, "required" when the server
+    /// rejected us for lack of a sign-in; `None` otherwise.
+    pub auth: Option<String>,
+}
+
+/// One discovered tool with its effective policy (for the settings UI).
+#[derive(Debug, Clone, Serialize)]
+pub struct McpToolStatus {
+    pub server_name: String,
+    pub tool_name: String,
+    pub description: Option<String>,
+    /// Whether the server marks the tool as destructive.
+    pub destructive: bool,
+    pub enabled: bool,
+    pub auto_approve: Option<bool>,
 }
 
 // ── Manager ─────────────────────────────────────────────
@@ -69,6 +120,8 @@ pub struct McpManager {
     pending_connections: HashSet<String>,
     /// Error messages from failed connection attempts.
     connection_errors: HashMap<String, String>,
+    /// OAuth tokens of remote servers, stored next to the server list.
+    oauth: Arc<McpOAuthStore>,
 }
 
 impl McpManager {
@@ -79,9 +132,16 @@ impl McpManager {
             config_path: config_path.to_string(),
             pending_connections: HashSet::new(),
             connection_errors: HashMap::new(),
+            oauth: Arc::new(McpOAuthStore::new(
+                Path::new(config_path).with_file_name("mcp_oauth.json"),
+            )),
         }
     }
 
+    pub fn oauth_store(&self) -> Arc<McpOAuthStore> {
+        self.oauth.clone()
+    }
+
     /// Mark a server as currently connecting.
     pub fn mark_connecting(&mut self, name: &str) {
         self.pending_connections.insert(name.to_string());
@@ -100,6 +160,7 @@ impl McpManager {
 
     /// Load server configs from disk.
     pub fn load_configs(&mut self) {
+        self.oauth.load();
         let path = Path::new(&self.config_path);
         if !path.exists() {
             tracing::info!(
@@ -162,7 +223,7 @@ impl McpManager {
 
     /// Connect to a single server.
     pub async fn connect_server(&mut self, config: &McpServerConfig) -> Result<(), KokoroError> {
-        let client = build_connected_client(config).await?;
+        let client = build_connected_client(config, &self.oauth).await?;
         self.clients
             .insert(config.name.clone(), Arc::new(Mutex::new(client)));
         Ok(())
@@ -179,9 +240,15 @@ impl McpManager {
     /// Add a new server config and optionally connect.
     pub async fn add_server(
         &mut self,
-        config: McpServerConfig,
+        mut config: McpServerConfig,
         connect: bool,
     ) -> Result<(), KokoroError> {
+        // Re-adding a server from pasted JSON keeps its tool policies.
+        if config.tools.is_empty() {
+            if let Some(existing) = self.configs.iter().find(|c| c.name == config.name) {
+                config.tools = existing.tools.clone();
+            }
+        }
         // Remove existing with same name
         self.configs.retain(|c| c.name != config.name);
         self.configs.push(config.clone());
@@ -198,6 +265,7 @@ impl McpManager {
         self.disconnect_server(name).await?;
         self.configs.retain(|c| c.name != name);
         self.save_configs()?;
+        self.oauth.remove(name)?;
         Ok(())
     }
 
@@ -219,6 +287,35 @@ impl McpManager {
         Ok(())
     }
 
+    /// Set (or reset, when it equals the default) the policy of one tool.
+    pub fn set_tool_policy(
+        &mut self,
+        server_name: &str,
+        tool_name: &str,
+        policy: McpToolPolicy,
+    ) -> Result<(), KokoroError> {
+        let config = self
+            .configs
+            .iter_mut()
+            .find(|c| c.name == server_name)
+            .ok_or_else(|| KokoroError::NotFound(format!("Server '{}' not found", server_name)))?;
+        if policy == McpToolPolicy::default() {
+            config.tools.remove(tool_name);
+        } else {
+            config.tools.insert(tool_name.to_string(), policy);
+        }
+        self.save_configs()
+    }
+
+    /// Effective policy of a tool (the default when none is stored).
+    pub fn tool_policy(&self, server_name: &str, tool_name: &str) -> McpToolPolicy {
+        self.configs
+            .iter()
+            .find(|c| c.name == server_name)
+            .and_then(|c| c.tools.get(tool_name).cloned())
+            .unwrap_or_default()
+    }
+
     /// Get status of all configured servers.
     /// Only locks individual clients that are actually connected — pending /
     /// disconnected servers return immediately without extra lock contention.
@@ -228,6 +325,15 @@ impl McpManager {
         for config in &self.configs {
             let is_pending = self.pending_connections.contains(&config.name);
             let error = self.connection_errors.get(&config.name).cloned();
+            let auth = if error.as_deref().is_some_and(super::oauth::is_auth_required) {
+                Some("required".to_string())
+            } else {
+                config
+                    .url
+                    .as_deref()
+                    .and_then(|url| self.oauth.get(&config.name, url))
+                    .map(|_| "authorized".to_string())
+            };
 
             // Fast path: if the server is still connecting or has no client,
             // skip the client lock entirely.
@@ -240,6 +346,7 @@ impl McpManager {
                     server_version: None,
                     status: "connecting".to_string(),
                     error: None,
+                    auth,
                 });
                 continue;
             }
@@ -270,12 +377,47 @@ impl McpManager {
                 server_version: version,
                 status,
                 error,
+                auth,
             });
         }
 
         statuses
     }
 
+    /// Status of one configured server.
+    pub async fn server_status(&self, name: &str) -> Option<McpServerStatus> {
+        self.list_status()
+            .await
+            .into_iter()
+            .find(|s| s.name == name)
+    }
+
+    /// Enabled servers that should be connected but aren't: their client
+    /// dropped (crashed process, closed stream) or their last connection
+    /// attempt failed. Servers mid-connect and never-attempted ones are skipped,
+    /// as are servers waiting for the user to sign in.
+    pub async fn unhealthy_servers(&self) -> Vec<McpServerConfig> {
+        let mut unhealthy = Vec::new();
+        for config in self.configs.iter().filter(|c| c.enabled) {
+            if self.pending_connections.contains(&config.name)
+                || self
+                    .connection_errors
+                    .get(&config.name)
+                    .is_some_and(|e| super::oauth::is_auth_required(e))
+            {
+                continue;
+            }
+            let dropped = match self.clients.get(&config.name) {
+                Some(client) => !client.lock().await.is_connected(),
+                None => self.connection_errors.contains_key(&config.name),
+            };
+            if dropped {
+                unhealthy.push(config.clone());
+            }
+        }
+        unhealthy
+    }
+
     /// Call a tool on a specific server.
     pub async fn call_tool(
         &self,
@@ -303,6 +445,31 @@ impl McpManager {
         all
     }
 
+    /// All discovered tools, including disabled ones, with their policies.
+    pub async fn list_tools(&self) -> Vec<McpToolStatus> {
+        let mut tools: Vec<McpToolStatus> = self
+            .all_tools()
+            .await
+            .into_iter()
+            .map(|(server_name, tool)| {
+                let policy = self.tool_policy(&server_name, &tool.name);
+                McpToolStatus {
+                    destructive: tool.is_destructive(),
+                    tool_name: tool.name,
+                    description: tool.description,
+                    server_name,
+                    enabled: policy.enabled,
+                    auto_approve: policy.auto_approve,
+                }
+            })
+            .collect();
+        tools.sort_by(|a, b| {
+            (a.server_name.as_str(), a.tool_name.as_str())
+                .cmp(&(b.server_name.as_str(), b.tool_name.as_str()))
+        });
+        tools
+    }
+
     /// Get configs (for serialization to frontend).
     pub fn configs(&self) -> &[McpServerConfig] {
         &self.configs
@@ -317,7 +484,10 @@ impl McpManager {
 /// Build and fully initialize an MCP client for the given config **without holding
 /// any manager lock**. All slow I/O (process spawn, TCP handshake, MCP initialize)
 /// happens here so callers can insert the result with only a brief lock.
-pub async fn build_connected_client(config: &McpServerConfig) -> Result<McpClient, KokoroError> {
+pub async fn build_connected_client(
+    config: &McpServerConfig,
+    oauth: &Arc<McpOAuthStore>,
+) -> Result<McpClient, KokoroError> {
     tracing::info!(
         target: "mcp",
         "Connecting to '{}' (transport: {})...",
@@ -332,7 +502,7 @@ pub async fn build_connected_client(config: &McpServerConfig) -> Result<McpClien
                     config.name, config.transport_type
                 ))
             })?;
-            Arc::new(StreamableHttpTransport::new(url))
+            Arc::new(http_transport(config, url, oauth))
         }
         "sse" => {
             let url = config.url.as_deref().ok_or_else(|| {
@@ -359,7 +529,7 @@ pub async fn build_connected_client(config: &McpServerConfig) -> Result<McpClien
                             "Auto-detected Streamable HTTP transport for '{}'",
                             config.name
                         );
-                        Arc::new(StreamableHttpTransport::new(url))
+                        Arc::new(http_transport(config, url, oauth))
                     }
                 } else {
                     return Err(KokoroError::Config(format!(
@@ -379,3 +549,15 @@ pub async fn build_connected_client(config: &McpServerConfig) -> Result<McpClien
     client.connect().await?;
     Ok(client)
 }
+
+fn http_transport(
+    config: &McpServerConfig,
+    url: &str,
+    oauth: &Arc<McpOAuthStore>,
+) -> StreamableHttpTransport {
+    StreamableHttpTransport::new(url).with_auth(Arc::new(McpOAuthSession::new(
+        &config.name,
+        url,
+        oauth.clone(),
+    )))
+}
@@ -1,6 +1,8 @@
 pub mod bridge;
 pub mod client;
+pub mod health;
 pub mod manager;
+pub mod oauth;
 pub mod transport;
 
 pub use client::McpClient;
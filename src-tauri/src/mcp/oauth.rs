@@ -0,0 +1,774 @@
+//! MCP OAuth — authorization for remote (Streamable HTTP) MCP servers.
+//!
+//! Follows the MCP authorization spec: the server's protected resource
+//! metadata (RFC 9728) names its authorization server, whose metadata
+//! (RFC 8414) lists the endpoints. Kokoro registers itself dynamically
+//! (RFC 7591) unless the server config carries a client id, runs the
+//! authorization code flow with PKCE through the system browser and a
+//! loopback redirect, and keeps the tokens in `mcp_oauth.json` next to the
+//! server list, refreshing them before they expire.
+
+use crate::error::KokoroError;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use reqwest::Url;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// Marker in transport errors that only a (new) sign-in can fix.
+pub const AUTH_REQUIRED: &str = "Authorization required";
+/// How long the browser sign-in may take before it is abandoned.
+const AUTHORIZE_TIMEOUT: Duration = Duration::from_secs(300);
+/// Tokens are refreshed this long before they expire.
+const REFRESH_MARGIN_SECS: i64 = 60;
+const CLIENT_NAME: &str = "Kokoro Engine";
+const CALLBACK_PATH: &str = "/callback";
+
+pub fn is_auth_required(error: &str) -> bool {
+    error.contains(AUTH_REQUIRED)
+}
+
+/// OAuth settings for servers with a pre-registered client.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct McpOAuthConfig {
+    #[serde(default)]
+    pub client_id: Option<String>,
+    #[serde(default)]
+    pub client_secret: Option<String>,
+    /// Space-separated scopes; defaults to the ones the server advertises.
+    #[serde(default)]
+    pub scope: Option<String>,
+}
+
+/// Client registration and tokens for one server, kept across restarts.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct McpOAuthCredentials {
+    /// Server URL the tokens were issued for; a changed URL needs a new sign-in.
+    pub server_url: String,
+    pub client_id: String,
+    #[serde(default)]
+    pub client_secret: Option<String>,
+    pub redirect_uri: String,
+    pub token_endpoint: String,
+    pub access_token: String,
+    #[serde(default)]
+    pub refresh_token: Option<String>,
+    /// Unix seconds; `None` when the server did not say.
+    #[serde(default)]
+    pub expires_at: Option<i64>,
+    /// Registered by us, so it can be re-registered when the redirect changes.
+    #[serde(default)]
+    pub dynamic_client: bool,
+}
+
+impl McpOAuthCredentials {
+    fn expires_soon(&self, now: i64) -> bool {
+        self.expires_at
+            .is_some_and(|at| at - REFRESH_MARGIN_SECS <= now)
+    }
+}
+
+/// Tokens of every signed-in server, shared by the manager and the transports.
+pub struct McpOAuthStore {
+    path: PathBuf,
+    entries: std::sync::Mutex<HashMap<String, McpOAuthCredentials>>,
+    /// Serialises refreshes so concurrent requests don't spend a refresh token twice.
+    refresh_lock: tokio::sync::Mutex<()>,
+}
+
+impl McpOAuthStore {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            entries: std::sync::Mutex::new(HashMap::new()),
+            refresh_lock: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    /// Load stored tokens from disk; a missing file means nobody signed in yet.
+    pub fn load(&self) {
+        let Ok(content) = std::fs::read_to_string(&self.path) else {
+            return;
+        };
+        match serde_json::from_str::<HashMap<String, McpOAuthCredentials>>(&content) {
+            Ok(entries) => {
+                tracing::info!(target: "mcp", "[MCP/OAuth] Loaded tokens for {} servers", entries.len());
+                *self.lock() = entries;
+            }
+            Err(e) => {
+                tracing::error!(target: "mcp", "[MCP/OAuth] Failed to parse token store: {}", e)
+            }
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, McpOAuthCredentials>> {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn save(&self, entries: &HashMap<String, McpOAuthCredentials>) -> Result<(), KokoroError> {
+        let content = serde_json::to_string_pretty(entries)
+            .map_err(|e| KokoroError::Config(format!("Serialize error: {}", e)))?;
+        std::fs::write(&self.path, content)
+            .map_err(|e| KokoroError::Io(format!("Write error: {}", e)))
+    }
+
+    /// Credentials for `server`, only if they were issued for `url`.
+    pub fn get(&self, server: &str, url: &str) -> Option<McpOAuthCredentials> {
+        self.lock()
+            .get(server)
+            .filter(|creds| same_resource(&creds.server_url, url))
+            .cloned()
+    }
+
+    pub fn set(&self, server: &str, credentials: McpOAuthCredentials) -> Result<(), KokoroError> {
+        let mut entries = self.lock();
+        entries.insert(server.to_string(), credentials);
+        self.save(&entries)
+    }
+
+    /// Forget a server's tokens; returns whether there were any.
+    pub fn remove(&self, server: &str) -> Result<bool, KokoroError> {
+        let mut entries = self.lock();
+        if entries.remove(server).is_none() {
+            return Ok(false);
+        }
+        self.save(&entries)?;
+        Ok(true)
+    }
+}
+
+fn same_resource(a: &str, b: &str) -> bool {
+    a.trim_end_matches('/') == b.trim_end_matches('/')
+}
+
+fn now() -> i64 {
+    chrono::Utc::now().timestamp()
+}
+
+/// Token source for one server's transport.
+pub struct McpOAuthSession {
+    server: String,
+    url: String,
+    store: Arc<McpOAuthStore>,
+    http: reqwest::Client,
+}
+
+impl McpOAuthSession {
+    pub fn new(server: &str, url: &str, store: Arc<McpOAuthStore>) -> Self {
+        Self {
+            server: server.to_string(),
+            url: url.to_string(),
+            store,
+            http: http_client(),
+        }
+    }
+
+    /// Current access token, refreshed first when it is about to expire.
+    /// `None` when the user has not signed in to this server.
+    pub async fn access_token(&self) -> Option<String> {
+        let creds = self.store.get(&self.server, &self.url)?;
+        if creds.refresh_token.is_some() && creds.expires_soon(now()) {
+            match self.refresh(Some(&creds.access_token)).await {
+                Ok(token) => return Some(token),
+                Err(e) => tracing::warn!(
+                    target: "mcp",
+                    "[MCP/OAuth] Refresh for '{}' failed: {}",
+                    self.server,
+                    e
+                ),
+            }
+        }
+        Some(creds.access_token)
+    }
+
+    /// Trade the refresh token for a new access token. `rejected` is the token
+    /// the caller saw fail; if another request already replaced it, the newer
+    /// one is returned without refreshing again.
+    pub async fn refresh(&self, rejected: Option<&str>) -> Result<String, String> {
+        let _guard = self.store.refresh_lock.lock().await;
+        let mut creds = self
+            .store
+            .get(&self.server, &self.url)
+            .ok_or_else(|| format!("{}: not signed in to '{}'", AUTH_REQUIRED, self.server))?;
+        if rejected.is_some_and(|token| token != creds.access_token) {
+            return Ok(creds.access_token);
+        }
+        let refresh_token = creds.refresh_token.clone().ok_or_else(|| {
+            format!(
+                "{}: sign-in for '{}' has expired",
+                AUTH_REQUIRED, self.server
+            )
+        })?;
+
+        let mut form = vec![
+            ("grant_type", "refresh_token"),
+            ("refresh_token", refresh_token.as_str()),
+            ("client_id", creds.client_id.as_str()),
+            ("resource", creds.server_url.as_str()),
+        ];
+        if let Some(secret) = creds.client_secret.as_deref() {
+            form.push(("client_secret", secret));
+        }
+        let tokens = request_tokens(&self.http, &creds.token_endpoint, &form)
+            .await
+            .map_err(|e| format!("{}: token refresh failed: {}", AUTH_REQUIRED, e))?;
+
+        creds.access_token = tokens.access_token.clone();
+        if tokens.refresh_token.is_some() {
+            creds.refresh_token = tokens.refresh_token;
+        }
+        creds.expires_at = tokens.expires_in.map(|secs| now() + secs);
+        if let Err(e) = self.store.set(&self.server, creds) {
+            tracing::warn!(target: "mcp", "[MCP/OAuth] Failed to persist refreshed token: {}", e);
+        }
+        tracing::info!(target: "mcp", "[MCP/OAuth] Refreshed token for '{}'", self.server);
+        Ok(tokens.access_token)
+    }
+}
+
+fn http_client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .timeout(Duration::from_secs(30))
+        .build()
+        .unwrap_or_default()
+}
+
+// ── Discovery ───────────────────────────────────────────
+
+#[derive(Debug, Default, Deserialize)]
+struct ResourceMetadata {
+    #[serde(default)]
+    authorization_servers: Vec<String>,
+    #[serde(default)]
+    scopes_supported: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct AuthServerMetadata {
+    authorization_endpoint: String,
+    token_endpoint: String,
+    #[serde(default)]
+    registration_endpoint: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ClientRegistration {
+    client_id: String,
+    #[serde(default)]
+    client_secret: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    #[serde(default)]
+    refresh_token: Option<String>,
+    #[serde(default)]
+    expires_in: Option<i64>,
+}
+
+/// `resource_metadata` URL from a `WWW-Authenticate: Bearer ...` challenge.
+fn resource_metadata_url(challenge: &str) -> Option<String> {
+    let start = challenge.find("resource_metadata=")? + "resource_metadata=".len();
+    let rest = &challenge[start..];
+    let value = match rest.strip_prefix('"') {
+        Some(quoted) => &quoted[..quoted.find('"')?],
+        None => rest.split([',', ' ']).next()?,
+    };
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+/// RFC 8615 well-known locations for `base`, path-specific first.
+fn well_known_urls(base: &str, name: &str) -> Vec<String> {
+    let Ok(url) = Url::parse(base) else {
+        return Vec::new();
+    };
+    let origin = url.origin().ascii_serialization();
+    let path = url.path().trim_end_matches('/');
+    let mut urls = Vec::new();
+    if !path.is_empty() {
+        urls.push(format!("{}/.well-known/{}{}", origin, name, path));
+    }
+    urls.push(format!("{}/.well-known/{}", origin, name));
+    urls
+}
+
+async fn fetch_json<T: DeserializeOwned>(http: &reqwest::Client, url: &str) -> Option<T> {
+    let resp = http
+        .get(url)
+        .header("Accept", "application/json")
+        .send()
+        .await
+        .ok()?;
+    if !resp.status().is_success() {
+        return None;
+    }
+    resp.json().await.ok()
+}
+
+async fn discover_resource_metadata(http: &reqwest::Client, url: &str) -> ResourceMetadata {
+    let mut candidates = Vec::new();
+    // An unauthenticated request is answered with a challenge pointing at the metadata.
+    let probe = http
+        .post(url)
+        .header("Accept", "application/json, text/event-stream")
+        .json(&serde_json::json!({ "jsonrpc": "2.0", "id": 0, "method": "ping" }))
+        .send()
+        .await;
+    if let Ok(resp) = probe {
+        if let Some(found) = resp
+            .headers()
+            .get(reqwest::header::WWW_AUTHENTICATE)
+            .and_then(|v| v.to_str().ok())
+            .and_then(resource_metadata_url)
+        {
+            candidates.push(found);
+        }
+    }
+    candidates.extend(well_known_urls(url, "oauth-protected-resource"));
+
+    for candidate in candidates {
+        if let Some(metadata) = fetch_json::<ResourceMetadata>(http, &candidate).await {
+            return metadata;
+        }
+    }
+    ResourceMetadata::default()
+}
+
+async fn discover_authorization_server(
+    http: &reqwest::Client,
+    issuer: &str,
+) -> Result<AuthServerMetadata, KokoroError> {
+    let candidates = well_known_urls(issuer, "oauth-authorization-server")
+        .into_iter()
+        .chain(well_known_urls(issuer, "openid-configuration"));
+    for candidate in candidates {
+        if let Some(metadata) = fetch_json::<AuthServerMetadata>(http, &candidate).await {
+            return Ok(metadata);
+        }
+    }
+
+    // Servers without metadata use the default endpoints at the issuer's origin.
+    let origin = Url::parse(issuer)
+        .map_err(|e| {
+            KokoroError::Config(format!("Invalid authorization server '{}': {}", issuer, e))
+        })?
+        .origin()
+        .ascii_serialization();
+    tracing::info!(
+        target: "mcp",
+        "[MCP/OAuth] No metadata for '{}', using default endpoints",
+        issuer
+    );
+    Ok(AuthServerMetadata {
+        authorization_endpoint: format!("{}/authorize", origin),
+        token_endpoint: format!("{}/token", origin),
+        registration_endpoint: Some(format!("{}/register", origin)),
+    })
+}
+
+async fn register_client(
+    http: &reqwest::Client,
+    metadata: &AuthServerMetadata,
+    redirect_uri: &str,
+) -> Result<ClientRegistration, KokoroError> {
+    let endpoint = metadata.registration_endpoint.as_deref().ok_or_else(|| {
+        KokoroError::Config(
+            "The server does not support dynamic client registration; set oauth.client_id in its config"
+                .to_string(),
+        )
+    })?;
+    let resp = http
+        .post(endpoint)
+        .json(&serde_json::json!({
+            "client_name": CLIENT_NAME,
+            "redirect_uris": [redirect_uri],
+            "grant_types": ["authorization_code", "refresh_token"],
+            "response_types": ["code"],
+            "token_endpoint_auth_method": "none",
+        }))
+        .send()
+        .await
+        .map_err(|e| KokoroError::ExternalService(format!("Client registration failed: {}", e)))?;
+    if !resp.status().is_success() {
+        let status = resp.status();
+        let text = resp.text().await.unwrap_or_default();
+        return Err(KokoroError::ExternalService(format!(
+            "Client registration returned {}: {}",
+            status, text
+        )));
+    }
+    resp.json()
+        .await
+        .map_err(|e| KokoroError::ExternalService(format!("Invalid registration response: {}", e)))
+}
+
+async fn request_tokens(
+    http: &reqwest::Client,
+    endpoint: &str,
+    form: &[(&str, &str)],
+) -> Result<TokenResponse, String> {
+    let resp = http
+        .post(endpoint)
+        .header("Accept", "application/json")
+        .form(form)
+        .send()
+        .await
+        .map_err(|e| format!("token request failed: {}", e))?;
+    if !resp.status().is_success() {
+        let status = resp.status();
+        let text = resp.text().await.unwrap_or_default();
+        return Err(format!("token endpoint returned {}: {}", status, text));
+    }
+    resp.json()
+        .await
+        .map_err(|e| format!("invalid token response: {}", e))
+}
+
+// ── Authorization Code Flow ─────────────────────────────
+
+fn pkce_verifier() -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(64)
+        .map(char::from)
+        .collect()
+}
+
+fn pkce_challenge(verifier: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()))
+}
+
+#[derive(Debug, PartialEq)]
+enum CallbackOutcome {
+    /// Not our redirect (favicon, stale tab); keep waiting.
+    Ignore,
+    Code(String),
+    Denied(String),
+}
+
+/// Interpret the request target the browser was redirected to.
+fn parse_callback(target: &str, expected_state: &str) -> CallbackOutcome {
+    let Ok(url) = Url::parse(&format!("http://127.0.0.1{}", target)) else {
+        return CallbackOutcome::Ignore;
+    };
+    if url.path() != CALLBACK_PATH {
+        return CallbackOutcome::Ignore;
+    }
+    let params: HashMap<String, String> = url.query_pairs().into_owned().collect();
+    if params.get("state").map(String::as_str) != Some(expected_state) {
+        return CallbackOutcome::Ignore;
+    }
+    if let Some(error) = params.get("error") {
+        return CallbackOutcome::Denied(match params.get("error_description") {
+            Some(description) => format!("{}: {}", error, description),
+            None => error.clone(),
+        });
+    }
+    match params.get("code") {
+        Some(code) => CallbackOutcome::Code(code.clone()),
+        None => CallbackOutcome::Ignore,
+    }
+}
+
+/// Listen on loopback, reusing the port of the last sign-in when free so a
+/// dynamically registered client stays valid.
+async fn bind_loopback(previous_redirect: Option<&str>) -> Result<TcpListener, KokoroError> {
+    let previous_port = previous_redirect
+        .and_then(|uri| Url::parse(uri).ok())
+        .and_then(|url| url.port());
+    if let Some(port) = previous_port {
+        if let Ok(listener) = TcpListener::bind(("127.0.0.1", port)).await {
+            return Ok(listener);
+        }
+    }
+    TcpListener::bind(("127.0.0.1", 0))
+        .await
+        .map_err(|e| KokoroError::Io(format!("Failed to open OAuth callback listener: {}", e)))
+}
+
+async fn respond(stream: &mut TcpStream, status: &str, message: &str) {
+    let body = format!(
+        "<!doctype html><html><body style=\"font-family:sans-serif;padding:2em\"><p>{}</p></body></html>",
+        message
+    );
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    let _ = stream.write_all(response.as_bytes()).await;
+    let _ = stream.shutdown().await;
+}
+
+async fn wait_for_callback(listener: &TcpListener, state: &str) -> Result<String, KokoroError> {
+    loop {
+        let (mut stream, _) = listener
+            .accept()
+            .await
+            .map_err(|e| KokoroError::Io(format!("OAuth callback failed: {}", e)))?;
+        let mut buf = vec![0u8; 8192];
+        let mut len = 0;
+        while len < buf.len() {
+            match stream.read(&mut buf[len..]).await {
+                Ok(0) | Err(_) => break,
+                Ok(n) => len += n,
+            }
+            if buf[..len].windows(4).any(|w| w == b"\r\n\r\n") {
+                break;
+            }
+        }
+        let head = String::from_utf8_lossy(&buf[..len]);
+        let target = head
+            .lines()
+            .next()
+            .and_then(|line| line.split_whitespace().nth(1))
+            .unwrap_or("");
+
+        match parse_callback(target, state) {
+            CallbackOutcome::Ignore => respond(&mut stream, "404 Not Found", "Not found.").await,
+            CallbackOutcome::Code(code) => {
+                respond(
+                    &mut stream,
+                    "200 OK",
+                    "Signed in to the MCP server. You can close this window and return to Kokoro.",
+                )
+                .await;
+                return Ok(code);
+            }
+            CallbackOutcome::Denied(reason) => {
+                respond(
+                    &mut stream,
+                    "200 OK",
+                    "Sign-in was cancelled. You can close this window.",
+                )
+                .await;
+                return Err(KokoroError::Unauthorized(format!(
+                    "Authorization denied: {}",
+                    reason
+                )));
+            }
+        }
+    }
+}
+
+/// Sign in to `server` at `url` through the system browser and store the
+/// tokens. `open_browser` receives the authorization URL.
+pub async fn authorize(
+    server: &str,
+    url: &str,
+    config: &McpOAuthConfig,
+    store: &McpOAuthStore,
+    open_browser: impl FnOnce(&str) -> Result<(), KokoroError>,
+) -> Result<(), KokoroError> {
+    let http = http_client();
+    let resource = discover_resource_metadata(&http, url).await;
+    let issuer = match resource.authorization_servers.first() {
+        Some(issuer) => issuer.clone(),
+        None => Url::parse(url)
+            .map_err(|e| KokoroError::Config(format!("Invalid server URL '{}': {}", url, e)))?
+            .origin()
+            .ascii_serialization(),
+    };
+    let metadata = discover_authorization_server(&http, &issuer).await?;
+    let scope = config.scope.clone().or_else(|| {
+        (!resource.scopes_supported.is_empty()).then(|| resource.scopes_supported.join(" "))
+    });
+
+    let previous = store.get(server, url);
+    let listener = bind_loopback(previous.as_ref().map(|c| c.redirect_uri.as_str())).await?;
+    let port = listener
+        .local_addr()
+        .map_err(|e| KokoroError::Io(e.to_string()))?
+        .port();
+    let redirect_uri = format!("http://127.0.0.1:{}{}", port, CALLBACK_PATH);
+
+    let (client_id, client_secret, dynamic_client) = match (&config.client_id, previous) {
+        (Some(client_id), _) => (client_id.clone(), config.client_secret.clone(), false),
+        (None, Some(prev))
+            if prev.dynamic_client
+                && prev.redirect_uri == redirect_uri
+                && prev.token_endpoint == metadata.token_endpoint =>
+        {
+            (prev.client_id, prev.client_secret, true)
+        }
+        _ => {
+            let registration = register_client(&http, &metadata, &redirect_uri).await?;
+            tracing::info!(target: "mcp", "[MCP/OAuth] Registered client for '{}'", server);
+            (registration.client_id, registration.client_secret, true)
+        }
+    };
+
+    let verifier = pkce_verifier();
+    let state = uuid::Uuid::new_v4().to_string();
+    let mut auth_url = Url::parse(&metadata.authorization_endpoint).map_err(|e| {
+        KokoroError::Config(format!(
+            "Invalid authorization endpoint '{}': {}",
+            metadata.authorization_endpoint, e
+        ))
+    })?;
+    {
+        let mut query = auth_url.query_pairs_mut();
+        query
+            .append_pair("response_type", "code")
+            .append_pair("client_id", &client_id)
+            .append_pair("redirect_uri", &redirect_uri)
+            .append_pair("code_challenge", &pkce_challenge(&verifier))
+            .append_pair("code_challenge_method", "S256")
+            .append_pair("state", &state)
+            .append_pair("resource", url);
+        if let Some(scope) = scope.as_deref() {
+            query.append_pair("scope", scope);
+        }
+    }
+
+    tracing::info!(target: "mcp", "[MCP/OAuth] Opening browser to sign in to '{}'", server);
+    open_browser(auth_url.as_str())?;
+    let code = tokio::time::timeout(AUTHORIZE_TIMEOUT, wait_for_callback(&listener, &state))
+        .await
+        .map_err(|_| KokoroError::Unauthorized("Sign-in timed out".to_string()))??;
+
+    let mut form = vec![
+        ("grant_type", "authorization_code"),
+        ("code", code.as_str()),
+        ("redirect_uri", redirect_uri.as_str()),
+        ("client_id", client_id.as_str()),
+        ("code_verifier", verifier.as_str()),
+        ("resource", url),
+    ];
+    if let Some(secret) = client_secret.as_deref() {
+        form.push(("client_secret", secret));
+    }
+    let tokens = request_tokens(&http, &metadata.token_endpoint, &form)
+        .await
+        .map_err(KokoroError::ExternalService)?;
+
+    store.set(
+        server,
+        McpOAuthCredentials {
+            server_url: url.to_string(),
+            client_id,
+            client_secret,
+            redirect_uri,
+            token_endpoint: metadata.token_endpoint,
+            access_token: tokens.access_token,
+            refresh_token: tokens.refresh_token,
+            expires_at: tokens.expires_in.map(|secs| now() + secs),
+            dynamic_client,
+        },
+    )?;
+    tracing::info!(target: "mcp", "[MCP/OAuth] Signed in to '{}'", server);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pkce_challenge_is_unpadded_base64url_sha256() {
+        assert_eq!(
+            pkce_challenge("dBjftJeZ4CVP-mJ92Gf-eHhGOn8KmbcXhdVjDEbG9fs"),
+            "HrOrEI-I4VT2K4Tr4AeKL6zgWF9Xp_OOuXar4GH4Jq8"
+        );
+        assert_eq!(pkce_verifier().len(), 64);
+    }
+
+    #[test]
+    fn discovery_urls_follow_challenge_and_well_known_paths() {
+        assert_eq!(
+            resource_metadata_url(
+                r#"Bearer error="invalid_token", resource_metadata="https://mcp.example.com/.well-known/oauth-protected-resource""#
+            )
+            .as_deref(),
+            Some("https://mcp.example.com/.well-known/oauth-protected-resource")
+        );
+        assert_eq!(
+            resource_metadata_url("Bearer resource_metadata=https://a.example/m, scope=x")
+                .as_deref(),
+            Some("https://a.example/m")
+        );
+        assert_eq!(resource_metadata_url("Bearer realm=\"x\""), None);
+
+        assert_eq!(
+            well_known_urls(
+                "https://mcp.example.com/v1/mcp/",
+                "oauth-protected-resource"
+            ),
+            vec![
+                "https://mcp.example.com/.well-known/oauth-protected-resource/v1/mcp",
+                "https://mcp.example.com/.well-known/oauth-protected-resource",
+            ]
+        );
+        assert_eq!(
+            well_known_urls("https://auth.example.com", "oauth-authorization-server"),
+            vec!["https://auth.example.com/.well-known/oauth-authorization-server"]
+        );
+    }
+
+    #[test]
+    fn callback_requires_matching_state() {
+        assert_eq!(
+            parse_callback("/callback?code=abc&state=s1", "s1"),
+            CallbackOutcome::Code("abc".to_string())
+        );
+        assert_eq!(
+            parse_callback("/callback?code=abc&state=other", "s1"),
+            CallbackOutcome::Ignore
+        );
+        assert_eq!(
+            parse_callback("/favicon.ico", "s1"),
+            CallbackOutcome::Ignore
+        );
+        assert_eq!(
+            parse_callback(
+                "/callback?error=access_denied&error_description=User%20said%20no&state=s1",
+                "s1"
+            ),
+            CallbackOutcome::Denied("access_denied: User said no".to_string())
+        );
+    }
+
+    #[test]
+    fn store_scopes_tokens_to_server_url() {
+        let path = std::env::temp_dir().join(format!("mcp_oauth_{}.json", uuid::Uuid::new_v4()));
+        let store = McpOAuthStore::new(path.clone());
+        let creds = McpOAuthCredentials {
+            server_url: "https://mcp.example.com/mcp".to_string(),
+            client_id: "client".to_string(),
+            client_secret: None,
+            redirect_uri: "http://127.0.0.1:4000/callback".to_string(),
+            token_endpoint: "https://auth.example.com/token".to_string(),
+            access_token: "token".to_string(),
+            refresh_token: Some("refresh".to_string()),
+            expires_at: Some(1_000),
+            dynamic_client: true,
+        };
+        store.set("remote", creds.clone()).unwrap();
+        assert!(store
+            .get("remote", "https://mcp.example.com/mcp/")
+            .is_some());
+        assert!(store
+            .get("remote", "https://other.example.com/mcp")
+            .is_none());
+        assert!(creds.expires_soon(950));
+        assert!(!creds.expires_soon(900));
+
+        let reloaded = McpOAuthStore::new(path.clone());
+        reloaded.load();
+        assert_eq!(reloaded.get("remote", &creds.server_url), Some(creds));
+        assert!(reloaded.remove("remote").unwrap());
+        assert!(!reloaded.remove("remote").unwrap());
+        let _ = std::fs::remove_file(path);
+    }
+}
@@ -1,9 +1,12 @@
 //! MCP Transport Layer — trait-based abstraction for MCP server communication.
 //!
 //! Implements stdio transport (subprocess stdin/stdout),
-//! Streamable HTTP transport (POST JSON-RPC to an HTTP endpoint),
-//! and SSE transport (GET event stream + POST to dynamic endpoint).
+//! Streamable HTTP transport (POST JSON-RPC to an HTTP endpoint, with optional
+//! OAuth bearer tokens),
+//! and SSE transport (GET event stream + POST to dynamic endpoint, reconnecting
+//! when the stream drops).
 
+use super::oauth::{McpOAuthSession, AUTH_REQUIRED};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -325,6 +328,8 @@ pub struct StreamableHttpTransport {
     connected: Arc<std::sync::atomic::AtomicBool>,
     /// MCP session ID returned by the server via `Mcp-Session-Id` header.
     session_id: Arc<Mutex<Option<String>>>,
+    /// Bearer tokens for servers that require OAuth.
+    auth: Option<Arc<McpOAuthSession>>,
 }
 
 impl StreamableHttpTransport {
@@ -341,6 +346,59 @@ impl StreamableHttpTransport {
             next_id: AtomicU64::new(1),
             connected: Arc::new(std::sync::atomic::AtomicBool::new(true)),
             session_id: Arc::new(Mutex::new(None)),
+            auth: None,
+        }
+    }
+
+    /// Attach OAuth tokens to every request, refreshing once on HTTP 401.
+    pub fn with_auth(mut self, auth: Arc<McpOAuthSession>) -> Self {
+        self.auth = Some(auth);
+        self
+    }
+
+    /// POST a JSON-RPC message with the session ID and bearer token attached.
+    /// A 401 is retried once with a refreshed token; if that is not possible
+    /// the error says authorization is required.
+    async fn send_message(&self, body: &Value) -> Result<reqwest::Response, String> {
+        let mut token = match self.auth.as_ref() {
+            Some(auth) => auth.access_token().await,
+            None => None,
+        };
+        let mut refreshed = false;
+        loop {
+            let mut req = self
+                .client
+                .post(&self.url)
+                .header("Content-Type", "application/json")
+                .header("Accept", "application/json, text/event-stream");
+
+            // Attach session ID if we have one
+            if let Some(ref sid) = *self.session_id.lock().await {
+                req = req.header("Mcp-Session-Id", sid.clone());
+            }
+            if let Some(ref token) = token {
+                req = req.bearer_auth(token);
+            }
+
+            let resp = req
+                .json(body)
+                .send()
+                .await
+                .map_err(|e| format!("HTTP request failed: {}", e))?;
+
+            if resp.status() != reqwest::StatusCode::UNAUTHORIZED {
+                return Ok(resp);
+            }
+            match (self.auth.as_ref(), token.as_deref()) {
+                (Some(auth), Some(rejected)) if !refreshed => {
+                    token = Some(auth.refresh(Some(rejected)).await?);
+                    refreshed = true;
+                }
+                _ => {
+                    self.connected.store(false, Ordering::SeqCst);
+                    return Err(format!("{} by {} (HTTP 401)", AUTH_REQUIRED, self.url));
+                }
+            }
         }
     }
 }
@@ -363,22 +421,7 @@ impl McpTransport for StreamableHttpTransport {
             body["params"] = p;
         }
 
-        let mut req = self
-            .client
-            .post(&self.url)
-            .header("Content-Type", "application/json")
-            .header("Accept", "application/json, text/event-stream");
-
-        // Attach session ID if we have one
-        if let Some(ref sid) = *self.session_id.lock().await {
-            req = req.header("Mcp-Session-Id", sid.clone());
-        }
-
-        let resp = req
-            .json(&body)
-            .send()
-            .await
-            .map_err(|e| format!("HTTP request failed: {}", e))?;
+        let resp = self.send_message(&body).await?;
 
         // Capture session ID from response header
         if let Some(sid) = resp.headers().get("mcp-session-id") {
@@ -438,21 +481,7 @@ impl McpTransport for StreamableHttpTransport {
             body["params"] = p;
         }
 
-        let mut req = self
-            .client
-            .post(&self.url)
-            .header("Content-Type", "application/json")
-            .header("Accept", "application/json, text/event-stream");
-
-        if let Some(ref sid) = *self.session_id.lock().await {
-            req = req.header("Mcp-Session-Id", sid.clone());
-        }
-
-        let resp = req
-            .json(&body)
-            .send()
-            .await
-            .map_err(|e| format!("HTTP notify failed: {}", e))?;
+        let resp = self.send_message(&body).await?;
 
         if !resp.status().is_success() {
             let status = resp.status();
@@ -498,208 +527,176 @@ fn parse_sse_response(sse_text: &str, expected_id: u64) -> Result<Value, String>
 
 // ── SSE Transport ──────────────────────────────────────
 
-/// Communicates with an MCP server using the SSE transport protocol.
+/// First reconnect delay; doubled per failed attempt up to [`SSE_MAX_BACKOFF`].
+const SSE_INITIAL_BACKOFF: std::time::Duration = std::time::Duration::from_secs(1);
+const SSE_MAX_BACKOFF: std::time::Duration = std::time::Duration::from_secs(30);
+/// How long a request waits for an in-progress reconnect before failing.
+const SSE_RECONNECT_GRACE: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// One dispatched server-sent event.
+#[derive(Debug, Clone, PartialEq)]
+struct SseEvent {
+    event: String,
+    data: String,
+}
+
+/// Incremental `text/event-stream` parser.
 ///
-/// Protocol flow:
-/// 1. GET the configured URL to establish an SSE event stream
-/// 2. Server sends `event: endpoint` with the POST path (e.g. `/messages?sessionId=xxx`)
-/// 3. Client POSTs JSON-RPC requests to `{origin}{endpoint_path}`
-/// 4. Server pushes `event: message` with JSON-RPC responses via the SSE stream
-pub struct SseTransport {
-    /// The full SSE endpoint URL provided by the user (e.g. "http://localhost:8080/sse").
-    sse_url: String,
-    /// Origin extracted from the URL (e.g. "http://localhost:8080") for building POST URLs.
-    origin: String,
-    client: reqwest::Client,
-    next_id: AtomicU64,
-    connected: Arc<std::sync::atomic::AtomicBool>,
-    /// POST endpoint path received from the SSE stream (e.g. "/messages?sessionId=xxx").
-    post_endpoint: Arc<Mutex<Option<String>>>,
-    /// Pending request map: request ID → oneshot sender for the response.
-    pending: PendingMap,
+/// Bytes are buffered until a full line is available, so multi-byte UTF-8
+/// characters split across chunks survive; multi-line `data:` fields are
+/// joined with `\n` as the spec requires.
+#[derive(Debug, Default)]
+struct SseParser {
+    buffer: Vec<u8>,
+    event: String,
+    data: Vec<String>,
+    /// Last `id:` seen, sent back as `Last-Event-ID` on reconnect.
+    last_event_id: Option<String>,
+    /// Reconnect delay requested by the server via `retry:`.
+    retry: Option<std::time::Duration>,
 }
 
-impl SseTransport {
-    pub fn new(url: &str) -> Self {
-        // No global timeout — SSE stream is long-lived.
-        // Per-request timeouts are applied on POST calls instead.
-        let client = reqwest::Client::builder().build().unwrap_or_default();
+impl SseParser {
+    fn feed(&mut self, chunk: &[u8]) -> Vec<SseEvent> {
+        self.buffer.extend_from_slice(chunk);
+        let mut events = Vec::new();
+        while let Some(pos) = self.buffer.iter().position(|b| *b == b'\n') {
+            let raw: Vec<u8> = self.buffer.drain(..=pos).collect();
+            let line = String::from_utf8_lossy(&raw);
+            let line = line.trim_end_matches(['\n', '\r']);
+            if let Some(event) = self.line(line) {
+                events.push(event);
+            }
+        }
+        events
+    }
 
-        // Extract origin (scheme + host + port) from the URL for building POST URLs.
-        // e.g. "http://localhost:8080/sse" → "http://localhost:8080"
-        let origin = match reqwest::Url::parse(url) {
-            Ok(parsed) => parsed.origin().ascii_serialization(),
-            Err(_) => url.trim_end_matches('/').to_string(),
+    fn line(&mut self, line: &str) -> Option<SseEvent> {
+        if line.is_empty() {
+            let event = std::mem::take(&mut self.event);
+            if self.data.is_empty() {
+                return None;
+            }
+            return Some(SseEvent {
+                event: if event.is_empty() {
+                    "message".to_string()
+                } else {
+                    event
+                },
+                data: std::mem::take(&mut self.data).join("\n"),
+            });
+        }
+        if line.starts_with(':') {
+            return None;
+        }
+        let (field, value) = match line.split_once(':') {
+            Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+            None => (line, ""),
         };
-
-        Self {
-            sse_url: url.to_string(),
-            origin,
-            client,
-            next_id: AtomicU64::new(1),
-            connected: Arc::new(std::sync::atomic::AtomicBool::new(false)),
-            post_endpoint: Arc::new(Mutex::new(None)),
-            pending: Arc::new(Mutex::new(HashMap::new())),
+        match field {
+            "event" => self.event = value.to_string(),
+            "data" => self.data.push(value.to_string()),
+            "id" => self.last_event_id = Some(value.to_string()),
+            "retry" => {
+                if let Ok(ms) = value.trim().parse::<u64>() {
+                    self.retry = Some(std::time::Duration::from_millis(ms));
+                }
+            }
+            _ => {}
         }
+        None
     }
 
-    /// Establish the SSE connection by GET-ing the configured URL.
-    /// Spawns a background task that reads the event stream and dispatches responses.
-    pub async fn connect(&self) -> Result<(), String> {
-        tracing::info!(target: "mcp", "[MCP/SSE] Connecting to {}", self.sse_url);
+    /// Drop any half-read event from a stream that ended.
+    fn reset_stream(&mut self) {
+        self.buffer.clear();
+        self.event.clear();
+        self.data.clear();
+    }
+}
 
-        let resp = self
+/// Resolve the `endpoint` event against the SSE URL. Absolute endpoints must
+/// keep the origin of the initial connection (SSRF guard).
+fn resolve_sse_endpoint(sse_url: &str, endpoint: &str) -> Result<String, String> {
+    let base = reqwest::Url::parse(sse_url)
+        .map_err(|e| format!("Invalid SSE URL '{}': {}", sse_url, e))?;
+    let url = base
+        .join(endpoint)
+        .map_err(|e| format!("Invalid SSE endpoint '{}': {}", endpoint, e))?;
+    if url.origin() != base.origin() {
+        return Err(format!(
+            "SSE endpoint origin '{}' does not match expected origin '{}'",
+            url.origin().ascii_serialization(),
+            base.origin().ascii_serialization()
+        ));
+    }
+    Ok(url.to_string())
+}
+
+/// State shared between [`SseTransport`] and its background stream task.
+struct SseShared {
+    sse_url: String,
+    client: reqwest::Client,
+    next_id: AtomicU64,
+    connected: std::sync::atomic::AtomicBool,
+    /// Set by `shutdown`; stops reconnecting.
+    closed: std::sync::atomic::AtomicBool,
+    /// POST URL for the current session, from the server's `endpoint` event.
+    post_url: Mutex<Option<String>>,
+    pending: PendingMap,
+    /// `initialize` params, replayed after a reconnect so the new session is
+    /// initialized before requests resume.
+    handshake: Mutex<Option<Value>>,
+}
+
+impl SseShared {
+    async fn open_stream(&self, last_event_id: Option<&str>) -> Result<reqwest::Response, String> {
+        let mut request = self
             .client
             .get(&self.sse_url)
-            .header("Accept", "text/event-stream")
+            .header("Accept", "text/event-stream");
+        if let Some(id) = last_event_id {
+            request = request.header("Last-Event-ID", id);
+        }
+        let resp = request
             .send()
             .await
             .map_err(|e| format!("SSE GET failed: {}", e))?;
-
         if !resp.status().is_success() {
             let status = resp.status();
             let text = resp.text().await.unwrap_or_default();
             return Err(format!("SSE connect failed HTTP {}: {}", status, text));
         }
-
-        self.connected.store(true, Ordering::SeqCst);
-
-        let connected = self.connected.clone();
-        let post_endpoint = self.post_endpoint.clone();
-        let pending = self.pending.clone();
-
-        // Spawn background task to read the SSE stream
-        tokio::spawn(async move {
-            use futures::StreamExt;
-
-            let mut stream = resp.bytes_stream();
-            let mut buffer = String::new();
-            let mut current_event = String::new();
-
-            while let Some(chunk) = stream.next().await {
-                let chunk = match chunk {
-                    Ok(c) => c,
-                    Err(e) => {
-                        tracing::error!(target: "mcp", "[MCP/SSE] Stream read error: {}", e);
-                        break;
-                    }
-                };
-
-                let text = match std::str::from_utf8(&chunk) {
-                    Ok(t) => t,
-                    Err(_) => continue,
-                };
-
-                buffer.push_str(text);
-
-                // Process complete lines from the buffer
-                while let Some(newline_pos) = buffer.find('\n') {
-                    let line = buffer[..newline_pos].trim_end_matches('\r').to_string();
-                    buffer = buffer[newline_pos + 1..].to_string();
-
-                    if line.is_empty() {
-                        // Empty line = end of event, reset event type
-                        current_event.clear();
-                        continue;
-                    }
-
-                    if let Some(event_type) = line.strip_prefix("event:") {
-                        current_event = event_type.trim().to_string();
-                    } else if let Some(data) = line.strip_prefix("data:") {
-                        let data = data.trim();
-
-                        match current_event.as_str() {
-                            "endpoint" => {
-                                tracing::info!(target: "mcp", "[MCP/SSE] Received endpoint: {}", data);
-                                *post_endpoint.lock().await = Some(data.to_string());
-                            }
-                            "message" => {
-                                if let Ok(resp) = serde_json::from_str::<JsonRpcResponse>(data) {
-                                    if let Some(id) = resp.id {
-                                        if let Some(sender) = pending.lock().await.remove(&id) {
-                                            let result = if let Some(error) = resp.error {
-                                                Err(error.to_string())
-                                            } else {
-                                                Ok(resp.result.unwrap_or(Value::Null))
-                                            };
-                                            let _ = sender.send(result);
-                                        }
-                                    }
-                                }
-                            }
-                            _ => {
-                                // Ignore unknown event types
-                            }
-                        }
-                    }
-                    // Ignore lines that don't match event:/data: (e.g. comments starting with :)
-                }
-            }
-
-            // Stream ended — mark disconnected and notify all pending requests
-            tracing::error!(target: "mcp", "[MCP/SSE] Event stream closed");
-            connected.store(false, Ordering::SeqCst);
-            let mut pending = pending.lock().await;
-            for (_, sender) in pending.drain() {
-                let _ = sender.send(Err("SSE stream closed".to_string()));
-            }
-        });
-
-        // Wait for the endpoint to be received (with timeout)
-        let deadline = tokio::time::Instant::now() + std::time::Duration::from_secs(10);
-        loop {
-            if self.post_endpoint.lock().await.is_some() {
-                tracing::info!(target: "mcp", "[MCP/SSE] Connected successfully");
-                return Ok(());
-            }
-            if tokio::time::Instant::now() >= deadline {
-                self.connected.store(false, Ordering::SeqCst);
-                return Err("Timed out waiting for SSE endpoint event".to_string());
-            }
-            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
-        }
+        Ok(resp)
     }
 
-    /// Build the full POST URL from the base URL and the endpoint path.
-    /// 验证绝对 URL 的 origin 与初始连接一致，防止 SSRF。
-    async fn get_post_url(&self) -> Result<String, String> {
-        let endpoint = self
-            .post_endpoint
+    async fn post(&self, body: &Value) -> Result<(), String> {
+        let url = self
+            .post_url
             .lock()
             .await
             .clone()
             .ok_or("SSE endpoint not yet received")?;
-
-        // The endpoint may be an absolute path or a relative path
-        if endpoint.starts_with("http://") || endpoint.starts_with("https://") {
-            // 验证 origin 与初始连接一致，防止恶意服务器重定向到内网地址（SSRF）
-            let ep_origin = reqwest::Url::parse(&endpoint)
-                .ok()
-                .map(|u| u.origin().ascii_serialization());
-            if ep_origin.as_deref() != Some(&self.origin) {
-                return Err(format!(
-                    "SSE endpoint origin '{}' does not match expected origin '{}'",
-                    ep_origin.unwrap_or_default(),
-                    self.origin
-                ));
-            }
-            Ok(endpoint)
-        } else {
-            Ok(format!("{}{}", self.origin, endpoint))
+        let resp = self
+            .client
+            .post(&url)
+            .timeout(std::time::Duration::from_secs(30))
+            .header("Content-Type", "application/json")
+            .json(body)
+            .send()
+            .await
+            .map_err(|e| format!("SSE POST failed: {}", e))?;
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let text = resp.text().await.unwrap_or_default();
+            return Err(format!("SSE POST HTTP {}: {}", status, text));
         }
+        Ok(())
     }
-}
 
-#[async_trait]
-impl McpTransport for SseTransport {
-    async fn request(&self, method: &str, params: Option<Value>) -> Result<Value, String> {
-        if !self.is_connected() {
-            return Err("Transport disconnected".to_string());
-        }
-
-        let url = self.get_post_url().await?;
+    /// POST a request and wait for its response on the event stream.
+    async fn send_request(&self, method: &str, params: Option<Value>) -> Result<Value, String> {
         let id = self.next_id.fetch_add(1, Ordering::SeqCst);
-
         let mut body = serde_json::json!({
             "jsonrpc": "2.0",
             "id": id,
@@ -712,52 +709,21 @@ impl McpTransport for SseTransport {
         // Register pending response before sending
         let (tx, rx) = oneshot::channel();
         self.pending.lock().await.insert(id, tx);
-
-        // POST the request (with per-request timeout, not global)
-        let resp = self
-            .client
-            .post(&url)
-            .timeout(std::time::Duration::from_secs(30))
-            .header("Content-Type", "application/json")
-            .json(&body)
-            .send()
-            .await
-            .map_err(|e| {
-                // Clean up pending on send failure
-                let pending = self.pending.clone();
-                tokio::spawn(async move {
-                    pending.lock().await.remove(&id);
-                });
-                format!("SSE POST failed: {}", e)
-            })?;
-
-        if !resp.status().is_success() {
+        if let Err(e) = self.post(&body).await {
             self.pending.lock().await.remove(&id);
-            let status = resp.status();
-            let text = resp.text().await.unwrap_or_default();
-            return Err(format!("SSE POST HTTP {}: {}", status, text));
+            return Err(e);
         }
 
-        // Wait for the response to arrive via the SSE stream
-        tokio::time::timeout(std::time::Duration::from_secs(30), rx)
-            .await
-            .map_err(|_| {
-                let pending = self.pending.clone();
-                tokio::spawn(async move {
-                    pending.lock().await.remove(&id);
-                });
-                format!("MCP SSE request '{}' timed out", method)
-            })?
-            .map_err(|_| "SSE response channel dropped".to_string())?
-    }
-
-    async fn notify(&self, method: &str, params: Option<Value>) -> Result<(), String> {
-        if !self.is_connected() {
-            return Err("Transport disconnected".to_string());
+        match tokio::time::timeout(std::time::Duration::from_secs(30), rx).await {
+            Ok(result) => result.map_err(|_| "SSE response channel dropped".to_string())?,
+            Err(_) => {
+                self.pending.lock().await.remove(&id);
+                Err(format!("MCP SSE request '{}' timed out", method))
+            }
         }
+    }
 
-        let url = self.get_post_url().await?;
-
+    async fn send_notification(&self, method: &str, params: Option<Value>) -> Result<(), String> {
         let mut body = serde_json::json!({
             "jsonrpc": "2.0",
             "method": method,
@@ -765,37 +731,376 @@ impl McpTransport for SseTransport {
         if let Some(p) = params {
             body["params"] = p;
         }
-
-        let resp = self
-            .client
-            .post(&url)
-            .timeout(std::time::Duration::from_secs(30))
-            .header("Content-Type", "application/json")
-            .json(&body)
-            .send()
+        self.post(&body)
             .await
-            .map_err(|e| format!("SSE notify POST failed: {}", e))?;
+            .map_err(|e| format!("SSE notify failed: {}", e))
+    }
 
-        if !resp.status().is_success() {
-            let status = resp.status();
-            let text = resp.text().await.unwrap_or_default();
-            return Err(format!("SSE notify HTTP {}: {}", status, text));
+    /// Wait for a reconnect in progress; fails once the transport is closed.
+    async fn wait_connected(&self) -> Result<(), String> {
+        let deadline = tokio::time::Instant::now() + SSE_RECONNECT_GRACE;
+        loop {
+            if self.closed.load(Ordering::SeqCst) {
+                return Err("Transport disconnected".to_string());
+            }
+            if self.connected.load(Ordering::SeqCst) {
+                return Ok(());
+            }
+            if tokio::time::Instant::now() >= deadline {
+                return Err("Transport disconnected (reconnecting)".to_string());
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
         }
+    }
 
-        Ok(())
+    async fn dispatch(self: &Arc<Self>, event: SseEvent) {
+        match event.event.as_str() {
+            "endpoint" => {
+                let url = match resolve_sse_endpoint(&self.sse_url, event.data.trim()) {
+                    Ok(url) => url,
+                    Err(e) => {
+                        tracing::error!(target: "mcp", "[MCP/SSE] {}", e);
+                        return;
+                    }
+                };
+                tracing::info!(target: "mcp", "[MCP/SSE] Received endpoint: {}", url);
+                *self.post_url.lock().await = Some(url);
+
+                let handshake = self.handshake.lock().await.clone();
+                match handshake {
+                    // A new session after a reconnect: initialize it before
+                    // letting requests through. Spawned because the response
+                    // arrives on the stream this task is reading.
+                    Some(params) => {
+                        let shared = self.clone();
+                        tokio::spawn(async move {
+                            let result = async {
+                                shared.send_request("initialize", Some(params)).await?;
+                                shared
+                                    .send_notification(
+                                        "notifications/initialized",
+                                        Some(serde_json::json!({})),
+                                    )
+                                    .await
+                            }
+                            .await;
+                            match result {
+                                Ok(()) => {
+                                    tracing::info!(target: "mcp", "[MCP/SSE] Session re-initialized");
+                                    shared.connected.store(true, Ordering::SeqCst);
+                                }
+                                Err(e) => {
+                                    tracing::error!(target: "mcp", "[MCP/SSE] Re-initialize failed: {}", e);
+                                }
+                            }
+                        });
+                    }
+                    None => self.connected.store(true, Ordering::SeqCst),
+                }
+            }
+            "message" => {
+                if let Ok(resp) = serde_json::from_str::<JsonRpcResponse>(&event.data) {
+                    if let Some(id) = resp.id {
+                        if let Some(sender) = self.pending.lock().await.remove(&id) {
+                            let result = if let Some(error) = resp.error {
+                                Err(error.to_string())
+                            } else {
+                                Ok(resp.result.unwrap_or(Value::Null))
+                            };
+                            let _ = sender.send(result);
+                        }
+                    }
+                }
+            }
+            _ => {
+                // Ignore unknown event types
+            }
+        }
     }
 
-    fn is_connected(&self) -> bool {
-        self.connected.load(Ordering::SeqCst)
+    /// Read one connection's event stream until it ends.
+    async fn read_stream(self: &Arc<Self>, resp: reqwest::Response, parser: &mut SseParser) {
+        use futures::StreamExt;
+
+        parser.reset_stream();
+        let mut stream = resp.bytes_stream();
+        while let Some(chunk) = stream.next().await {
+            let chunk = match chunk {
+                Ok(c) => c,
+                Err(e) => {
+                    tracing::error!(target: "mcp", "[MCP/SSE] Stream read error: {}", e);
+                    break;
+                }
+            };
+            for event in parser.feed(&chunk) {
+                self.dispatch(event).await;
+            }
+        }
     }
 
-    async fn shutdown(&self) -> Result<(), String> {
+    /// The stream dropped: the session is gone, so are its pending requests.
+    async fn mark_disconnected(&self, reason: &str) {
         self.connected.store(false, Ordering::SeqCst);
-        // Clean up all pending requests
+        *self.post_url.lock().await = None;
         let mut pending = self.pending.lock().await;
         for (_, sender) in pending.drain() {
-            let _ = sender.send(Err("Transport shutting down".to_string()));
+            let _ = sender.send(Err(reason.to_string()));
         }
+    }
+
+    /// Background task: read the stream, and reconnect with exponential
+    /// backoff whenever it drops, until the transport is shut down.
+    async fn run(self: Arc<Self>, first: reqwest::Response) {
+        let mut parser = SseParser::default();
+        let mut resp = Some(first);
+        loop {
+            if let Some(resp) = resp.take() {
+                self.read_stream(resp, &mut parser).await;
+            }
+            if self.closed.load(Ordering::SeqCst) {
+                return;
+            }
+            tracing::warn!(target: "mcp", "[MCP/SSE] Event stream closed, reconnecting");
+            self.mark_disconnected("SSE stream closed").await;
+
+            let mut delay = parser.retry.unwrap_or(SSE_INITIAL_BACKOFF);
+            while resp.is_none() {
+                tokio::time::sleep(delay).await;
+                if self.closed.load(Ordering::SeqCst) {
+                    return;
+                }
+                match self.open_stream(parser.last_event_id.as_deref()).await {
+                    Ok(next) => {
+                        tracing::info!(target: "mcp", "[MCP/SSE] Reconnected to {}", self.sse_url);
+                        resp = Some(next);
+                    }
+                    Err(e) => {
+                        tracing::warn!(
+                            target: "mcp",
+                            "[MCP/SSE] Reconnect failed (retry in {:?}): {}",
+                            delay, e
+                        );
+                        delay = (delay * 2).min(SSE_MAX_BACKOFF);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Communicates with an MCP server using the SSE transport protocol.
+///
+/// Protocol flow:
+/// 1. GET the configured URL to establish an SSE event stream
+/// 2. Server sends `event: endpoint` with the POST path (e.g. `/messages?sessionId=xxx`)
+/// 3. Client POSTs JSON-RPC requests to the endpoint, resolved against the URL
+/// 4. Server pushes `event: message` with JSON-RPC responses via the SSE stream
+///
+/// When the stream drops, the transport reconnects with exponential backoff
+/// and replays the `initialize` handshake on the new session; requests made
+/// meanwhile wait briefly for the reconnect instead of failing at once.
+pub struct SseTransport {
+    shared: Arc<SseShared>,
+    reader: std::sync::Mutex<Option<tokio::task::JoinHandle<()>>>,
+}
+
+impl SseTransport {
+    pub fn new(url: &str) -> Self {
+        // No global timeout — SSE stream is long-lived.
+        // Per-request timeouts are applied on POST calls instead.
+        let client = reqwest::Client::builder().build().unwrap_or_default();
+
+        Self {
+            shared: Arc::new(SseShared {
+                sse_url: url.to_string(),
+                client,
+                next_id: AtomicU64::new(1),
+                connected: std::sync::atomic::AtomicBool::new(false),
+                closed: std::sync::atomic::AtomicBool::new(false),
+                post_url: Mutex::new(None),
+                pending: Arc::new(Mutex::new(HashMap::new())),
+                handshake: Mutex::new(None),
+            }),
+            reader: std::sync::Mutex::new(None),
+        }
+    }
+
+    /// Establish the SSE connection by GET-ing the configured URL.
+    /// Spawns a background task that reads the event stream, dispatches
+    /// responses and reconnects when the stream drops.
+    pub async fn connect(&self) -> Result<(), String> {
+        tracing::info!(target: "mcp", "[MCP/SSE] Connecting to {}", self.shared.sse_url);
+
+        let resp = self.shared.open_stream(None).await?;
+        let task = tokio::spawn(self.shared.clone().run(resp));
+        if let Some(previous) = self
+            .reader
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .replace(task)
+        {
+            previous.abort();
+        }
+
+        // Wait for the endpoint to be received (with timeout)
+        let deadline = tokio::time::Instant::now() + std::time::Duration::from_secs(10);
+        loop {
+            if self.shared.connected.load(Ordering::SeqCst) {
+                tracing::info!(target: "mcp", "[MCP/SSE] Connected successfully");
+                return Ok(());
+            }
+            if tokio::time::Instant::now() >= deadline {
+                let _ = self.shutdown().await;
+                return Err("Timed out waiting for SSE endpoint event".to_string());
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        }
+    }
+}
+
+#[async_trait]
+impl McpTransport for SseTransport {
+    async fn request(&self, method: &str, params: Option<Value>) -> Result<Value, String> {
+        self.shared.wait_connected().await?;
+        let result = self.shared.send_request(method, params.clone()).await;
+        if method == "initialize" && result.is_ok() {
+            *self.shared.handshake.lock().await = params.or(Some(serde_json::json!({})));
+        }
+        result
+    }
+
+    async fn notify(&self, method: &str, params: Option<Value>) -> Result<(), String> {
+        self.shared.wait_connected().await?;
+        self.shared.send_notification(method, params).await
+    }
+
+    fn is_connected(&self) -> bool {
+        self.shared.connected.load(Ordering::SeqCst)
+    }
+
+    async fn shutdown(&self) -> Result<(), String> {
+        self.shared.closed.store(true, Ordering::SeqCst);
+        if let Some(task) = self.reader.lock().unwrap_or_else(|e| e.into_inner()).take() {
+            task.abort();
+        }
+        // Clean up all pending requests
+        self.shared
+            .mark_disconnected("Transport shutting down")
+            .await;
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sse_parser_handles_split_chunks_and_multiline_data() {
+        let mut parser = SseParser::default();
+        let stream = "event: endpoint\ndata: /messages?sessionId=1\n\n: ping\n\nid: 7\nretry: 2500\ndata: {\"a\":\ndata:  \"日本\"}\n\n";
+        let bytes = stream.as_bytes();
+        // Split inside the multi-byte character to exercise byte buffering.
+        let split = stream.find('日').unwrap() + 1;
+        let mut events = parser.feed(&bytes[..split]);
+        events.extend(parser.feed(&bytes[split..]));
+
+        assert_eq!(
+            events,
+            vec![
+                SseEvent {
+                    event: "endpoint".to_string(),
+                    data: "/messages?sessionId=1".to_string(),
+                },
+                SseEvent {
+                    event: "message".to_string(),
+                    data: "{\"a\":\n \"日本\"}".to_string(),
+                },
+            ]
+        );
+        assert_eq!(parser.last_event_id.as_deref(), Some("7"));
+        assert_eq!(parser.retry, Some(std::time::Duration::from_millis(2500)));
+    }
+
+    #[test]
+    fn sse_endpoint_resolves_relative_paths_and_rejects_other_origins() {
+        let base = "http://localhost:8080/mcp/sse";
+        assert_eq!(
+            resolve_sse_endpoint(base, "/messages?sessionId=1").unwrap(),
+            "http://localhost:8080/messages?sessionId=1"
+        );
+        assert_eq!(
+            resolve_sse_endpoint(base, "messages?sessionId=1").unwrap(),
+            "http://localhost:8080/mcp/messages?sessionId=1"
+        );
+        assert!(resolve_sse_endpoint(base, "http://169.254.169.254/latest").is_err());
+    }
+
+    #[tokio::test]
+    async fn http_transport_refreshes_rejected_token_once() {
+        use super::super::oauth::{McpOAuthCredentials, McpOAuthStore};
+        use wiremock::matchers::{body_string_contains, header, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/mcp"))
+            .and(header("authorization", "Bearer fresh"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0", "id": 1, "result": { "ok": true }
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/mcp"))
+            .respond_with(ResponseTemplate::new(401))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/token"))
+            .and(body_string_contains("grant_type=refresh_token"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "access_token": "fresh", "expires_in": 3600
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let url = format!("{}/mcp", server.uri());
+        let store_path =
+            std::env::temp_dir().join(format!("mcp_oauth_{}.json", uuid::Uuid::new_v4()));
+        let store = Arc::new(McpOAuthStore::new(store_path.clone()));
+        store
+            .set(
+                "remote",
+                McpOAuthCredentials {
+                    server_url: url.clone(),
+                    client_id: "client".to_string(),
+                    client_secret: None,
+                    redirect_uri: "http://127.0.0.1:4000/callback".to_string(),
+                    token_endpoint: format!("{}/token", server.uri()),
+                    access_token: "stale".to_string(),
+                    refresh_token: Some("refresh".to_string()),
+                    expires_at: None,
+                    dynamic_client: true,
+                },
+            )
+            .unwrap();
+
+        let transport = StreamableHttpTransport::new(&url).with_auth(Arc::new(
+            McpOAuthSession::new("remote", &url, store.clone()),
+        ));
+        let result = transport.request("tools/list", None).await.unwrap();
+        assert_eq!(result, serde_json::json!({ "ok": true }));
+        assert_eq!(store.get("remote", &url).unwrap().access_token, "fresh");
+
+        // Without a refresh token the 401 surfaces as a sign-in request.
+        let unauthenticated = StreamableHttpTransport::new(&url);
+        let error = unauthenticated
+            .request("tools/list", None)
+            .await
+            .unwrap_err();
+        assert!(super::super::oauth::is_auth_required(&error));
+        let _ = std::fs::remove_file(store_path);
+    }
+}
@@ -1,6 +1,13 @@
-use rquickjs::{Ctx, Function, Object, Result};
+use crate::mods::chat_hooks;
+use crate::mods::http::{self, ActiveHosts, FetchRequest};
+use crate::mods::permissions::{self, ActivePermissions, ModPermission};
+use crate::mods::storage::ActiveStorage;
+use crate::mods::timers::SharedTimers;
+use crate::mods::tools;
+use rquickjs::{Ctx, Exception, Function, Object, Result};
 use serde::{Deserialize, Serialize};
 use std::sync::mpsc::Sender;
+use std::time::{Duration, Instant};
 
 /// Events emitted by QuickJS scripts, forwarded to the Tauri event bus.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,12 +24,61 @@ pub enum ScriptEvent {
     },
     /// Kokoro.character.playCue(cue) → chat-cue Tauri event
     PlayCue { cue: String },
+    /// Kokoro.character.setEmotion(emotion, intensity?) → emotion engine
+    SetEmotion { emotion: String, intensity: f32 },
+    /// Kokoro.http.fetch(url, opts) → request on the async runtime; the result
+    /// settles promise `id` in the script
+    HttpFetch {
+        id: u32,
+        allowlist: Vec<String>,
+        request: FetchRequest,
+    },
+    /// Kokoro.registerTool(name, schema, handler) → proxy action in the
+    /// ActionRegistry
+    RegisterTool {
+        name: String,
+        description: String,
+        schema: serde_json::Value,
+    },
+    /// A tool handler settled; completes pending call `id`
+    ToolResult {
+        id: u32,
+        result: std::result::Result<serde_json::Value, String>,
+    },
+    /// Kokoro.on("chat:before-send" | "chat:before-display", …) → the chat
+    /// pipeline starts waiting for this hook
+    WatchHook { event: String },
+}
+
+/// Throw in the calling script unless the running mod holds `permission`.
+fn require(ctx: &Ctx<'_>, active: &ActivePermissions, permission: ModPermission) -> Result<()> {
+    if permissions::is_active(active, permission) {
+        return Ok(());
+    }
+    Err(Exception::throw_message(
+        ctx,
+        &format!(
+            "Permission '{}' not granted to this mod",
+            permission.as_str()
+        ),
+    ))
 }
 
 /// Register the Kokoro API into the QuickJS context.
 /// `event_tx` is a std::sync channel sender used by closures to forward events
-/// out of the QuickJS thread to the Tauri event bus.
-pub fn register_api(ctx: &Ctx<'_>, event_tx: Sender<ScriptEvent>) -> Result<()> {
+/// out of the QuickJS thread to the Tauri event bus. `active` holds the running
+/// mod's granted permissions and is checked on every gated call. `storage`
+/// backs `Kokoro.storage` and `hosts` limits `Kokoro.http` for whichever mod
+/// is running. `timers` is the script thread's timer queue behind
+/// `setTimeout`, `setInterval` and `Kokoro.schedule`.
+pub fn register_api<'js>(
+    ctx: &Ctx<'js>,
+    event_tx: Sender<ScriptEvent>,
+    active: ActivePermissions,
+    storage: ActiveStorage,
+    hosts: ActiveHosts,
+    timers: SharedTimers,
+) -> Result<()> {
     let globals = ctx.globals();
     let kokoro = Object::new(ctx.clone())?;
 
@@ -58,6 +114,54 @@ pub fn register_api(ctx: &Ctx<'_>, event_tx: Sender<ScriptEvent>) -> Result<()>
     "#,
     )?;
 
+    // ── Chat hooks ── listeners for chat:before-* return a replacement string
+    // or `false` to veto; __transform folds them and reports the outcome as
+    // JSON. The chat pipeline only waits for hooks announced via __watchHook.
+    let watch_tx = event_tx.clone();
+    let watch_permissions = active.clone();
+    kokoro.set(
+        "__watchHook",
+        Function::new(ctx.clone(), move |event: String| {
+            if chat_hooks::is_hook_event(&event)
+                && permissions::is_active(&watch_permissions, ModPermission::ChatTransform)
+            {
+                let _ = watch_tx.send(ScriptEvent::WatchHook { event });
+            }
+        })?,
+    )?;
+    ctx.eval::<(), _>(
+        r#"
+        globalThis.__transform = function(event, payload) {
+            var cbs = globalThis.__listeners[event] || [];
+            var text = payload.text;
+            var changed = false;
+            for (var i = 0; i < cbs.length; i++) {
+                var result;
+                try {
+                    result = cbs[i](Object.assign({}, payload, { text: text }));
+                } catch (e) {
+                    Kokoro.log("Chat hook error: " + e);
+                    continue;
+                }
+                if (result === false) return JSON.stringify({ action: "veto", reason: null });
+                if (result && typeof result === "object" && result.veto) {
+                    var reason = result.reason === undefined ? null : String(result.reason);
+                    return JSON.stringify({ action: "veto", reason: reason });
+                }
+                if (typeof result === "string") {
+                    text = result;
+                    changed = true;
+                }
+            }
+            return JSON.stringify(changed ? { action: "replace", text: text } : { action: "keep" });
+        };
+        globalThis.__clearChatHooks = function() {
+            delete globalThis.__listeners["chat:before-send"];
+            delete globalThis.__listeners["chat:before-display"];
+        };
+    "#,
+    )?;
+
     // ── Kokoro.on(eventName, callback) ──
     // Implemented in JS to avoid Rust closure lifetime issues with rquickjs.
     // Pushes callbacks into __listeners[eventName], which __dispatch() iterates.
@@ -68,6 +172,7 @@ pub fn register_api(ctx: &Ctx<'_>, event_tx: Sender<ScriptEvent>) -> Result<()>
                 globalThis.__listeners[eventName] = [];
             }
             globalThis.__listeners[eventName].push(callback);
+            Kokoro.__watchHook(String(eventName));
         };
     "#,
     )?;
@@ -113,14 +218,295 @@ pub fn register_api(ctx: &Ctx<'_>, event_tx: Sender<ScriptEvent>) -> Result<()>
     let character = Object::new(ctx.clone())?;
 
     let char_tx = event_tx.clone();
+    let cue_permissions = active.clone();
     character.set(
         "playCue",
-        Function::new(ctx.clone(), move |cue: String| {
-            let _ = char_tx.send(ScriptEvent::PlayCue { cue });
-        })?,
+        Function::new(
+            ctx.clone(),
+            move |ctx: Ctx<'_>, cue: String| -> Result<()> {
+                require(&ctx, &cue_permissions, ModPermission::ExpressionControl)?;
+                let _ = char_tx.send(ScriptEvent::PlayCue { cue });
+                Ok(())
+            },
+        )?,
+    )?;
+
+    let emotion_tx = event_tx.clone();
+    let emotion_permissions = active.clone();
+    character.set(
+        "setEmotion",
+        Function::new(
+            ctx.clone(),
+            move |ctx: Ctx<'_>, emotion: String, intensity: rquickjs::Value<'_>| -> Result<()> {
+                require(&ctx, &emotion_permissions, ModPermission::ExpressionControl)?;
+                let intensity = js_value_to_json(&intensity).as_f64().unwrap_or(0.8) as f32;
+                let _ = emotion_tx.send(ScriptEvent::SetEmotion { emotion, intensity });
+                Ok(())
+            },
+        )?,
     )?;
 
     kokoro.set("character", character)?;
+
+    // ── Kokoro.storage ── (persistent key-value store, per mod)
+    let storage_ns = Object::new(ctx.clone())?;
+
+    let get_storage = storage.clone();
+    let get_permissions = active.clone();
+    storage_ns.set(
+        "get",
+        Function::new(
+            ctx.clone(),
+            move |ctx: Ctx<'js>, key: String| -> Result<rquickjs::Value<'js>> {
+                require(&ctx, &get_permissions, ModPermission::Storage)?;
+                match get_storage.get(&key) {
+                    Ok(Some(value)) => ctx.json_parse(value.to_string()),
+                    Ok(None) => Ok(rquickjs::Value::new_undefined(ctx.clone())),
+                    Err(e) => Err(Exception::throw_message(&ctx, &e)),
+                }
+            },
+        )?,
+    )?;
+
+    let set_storage = storage.clone();
+    let set_permissions = active.clone();
+    storage_ns.set(
+        "set",
+        Function::new(
+            ctx.clone(),
+            move |ctx: Ctx<'_>, key: String, value: rquickjs::Value<'_>| -> Result<()> {
+                require(&ctx, &set_permissions, ModPermission::Storage)?;
+                set_storage
+                    .set(&key, js_value_to_json(&value))
+                    .map_err(|e| Exception::throw_message(&ctx, &e))
+            },
+        )?,
+    )?;
+
+    let delete_permissions = active.clone();
+    storage_ns.set(
+        "delete",
+        Function::new(
+            ctx.clone(),
+            move |ctx: Ctx<'_>, key: String| -> Result<bool> {
+                require(&ctx, &delete_permissions, ModPermission::Storage)?;
+                storage
+                    .delete(&key)
+                    .map_err(|e| Exception::throw_message(&ctx, &e))
+            },
+        )?,
+    )?;
+
+    kokoro.set("storage", storage_ns)?;
+
+    // ── Kokoro.http ── (fetch limited to the manifest's network_allowlist)
+    // `__request` checks and queues the request; the promise bookkeeping is in
+    // JS and `__settleFetch` is called by the runtime when the result is back.
+    let http_ns = Object::new(ctx.clone())?;
+    let fetch_tx = event_tx.clone();
+    let fetch_permissions = active.clone();
+    http_ns.set(
+        "__request",
+        Function::new(
+            ctx.clone(),
+            move |ctx: Ctx<'_>, id: u32, url: String, opts: rquickjs::Value<'_>| -> Result<()> {
+                require(&ctx, &fetch_permissions, ModPermission::Network)?;
+                let allowlist = hosts.read().map(|hosts| hosts.clone()).unwrap_or_default();
+                http::check_url(&allowlist, &url)
+                    .map_err(|e| Exception::throw_message(&ctx, &e))?;
+                let request = FetchRequest::from_script(url, &js_value_to_json(&opts));
+                let _ = fetch_tx.send(ScriptEvent::HttpFetch {
+                    id,
+                    allowlist,
+                    request,
+                });
+                Ok(())
+            },
+        )?,
+    )?;
+    kokoro.set("http", http_ns)?;
+    ctx.eval::<(), _>(
+        r#"
+        globalThis.__fetches = {};
+        globalThis.__fetchSeq = 0;
+        Kokoro.http.fetch = function(url, opts) {
+            return new Promise(function(resolve, reject) {
+                var id = ++globalThis.__fetchSeq;
+                globalThis.__fetches[id] = { resolve: resolve, reject: reject };
+                try {
+                    Kokoro.http.__request(id, String(url), opts || {});
+                } catch (e) {
+                    delete globalThis.__fetches[id];
+                    reject(e);
+                }
+            });
+        };
+        globalThis.__settleFetch = function(id, response, error) {
+            var pending = globalThis.__fetches[id];
+            if (!pending) return;
+            delete globalThis.__fetches[id];
+            if (error !== null) {
+                pending.reject(new Error(error));
+                return;
+            }
+            response.text = function() { return Promise.resolve(response.body); };
+            response.json = function() {
+                return new Promise(function(resolve) { resolve(JSON.parse(response.body)); });
+            };
+            pending.resolve(response);
+        };
+    "#,
+    )?;
+
+    // ── Timers ── setTimeout / setInterval / Kokoro.schedule(cron, cb)
+    // Rust keeps ids and due times; callbacks live in __timerCallbacks and
+    // the script thread calls __fireTimer(id, done) when one is due.
+    let add_timers = timers.clone();
+    kokoro.set(
+        "__addTimer",
+        Function::new(
+            ctx.clone(),
+            move |ctx: Ctx<'_>, delay_ms: f64, repeat: bool| -> Result<u32> {
+                let delay = Duration::from_millis(delay_ms.clamp(0.0, u32::MAX as f64) as u64);
+                add_timers
+                    .borrow_mut()
+                    .add(Instant::now(), delay, repeat)
+                    .map_err(|e| Exception::throw_message(&ctx, &e))
+            },
+        )?,
+    )?;
+    let cron_timers = timers.clone();
+    kokoro.set(
+        "__addCron",
+        Function::new(
+            ctx.clone(),
+            move |ctx: Ctx<'_>, expr: String| -> Result<u32> {
+                cron_timers
+                    .borrow_mut()
+                    .add_cron(Instant::now(), &expr)
+                    .map_err(|e| Exception::throw_message(&ctx, &e))
+            },
+        )?,
+    )?;
+    kokoro.set(
+        "__cancelTimer",
+        Function::new(ctx.clone(), move |id: u32| timers.borrow_mut().cancel(id))?,
+    )?;
+    ctx.eval::<(), _>(
+        r#"
+        globalThis.__timerCallbacks = {};
+        function __registerTimer(cb, args, id) {
+            globalThis.__timerCallbacks[id] = { cb: cb, args: args };
+            return id;
+        }
+        globalThis.setTimeout = function(cb, ms) {
+            var args = Array.prototype.slice.call(arguments, 2);
+            if (typeof cb !== "function") throw new TypeError("Timer callback must be a function");
+            return __registerTimer(cb, args, Kokoro.__addTimer(Number(ms) || 0, false));
+        };
+        globalThis.setInterval = function(cb, ms) {
+            var args = Array.prototype.slice.call(arguments, 2);
+            if (typeof cb !== "function") throw new TypeError("Timer callback must be a function");
+            return __registerTimer(cb, args, Kokoro.__addTimer(Number(ms) || 0, true));
+        };
+        globalThis.clearTimeout = globalThis.clearInterval = function(id) {
+            if (!(id in globalThis.__timerCallbacks)) return;
+            delete globalThis.__timerCallbacks[id];
+            Kokoro.__cancelTimer(id);
+        };
+        Kokoro.schedule = function(cron, cb) {
+            if (typeof cb !== "function") throw new TypeError("Schedule callback must be a function");
+            return __registerTimer(cb, [], Kokoro.__addCron(String(cron)));
+        };
+        Kokoro.unschedule = globalThis.clearInterval;
+        globalThis.__fireTimer = function(id, done) {
+            var timer = globalThis.__timerCallbacks[id];
+            if (!timer) return;
+            if (done) delete globalThis.__timerCallbacks[id];
+            try { timer.cb.apply(null, timer.args); } catch (e) { Kokoro.log("Timer error: " + e); }
+        };
+        globalThis.__clearTimers = function() { globalThis.__timerCallbacks = {}; };
+    "#,
+    )?;
+
+    // ── Kokoro.registerTool(name, schema, handler) ── tools the LLM can call
+    // Handlers live in __tools; the runtime calls __callTool(id, name, args)
+    // and the settled value comes back through __toolResult.
+    let register_tx = event_tx.clone();
+    let tool_permissions = active.clone();
+    kokoro.set(
+        "__registerTool",
+        Function::new(
+            ctx.clone(),
+            move |ctx: Ctx<'_>, name: String, schema: rquickjs::Value<'_>| -> Result<()> {
+                require(&ctx, &tool_permissions, ModPermission::Tools)?;
+                tools::validate_tool_name(&name).map_err(|e| Exception::throw_message(&ctx, &e))?;
+                let mut schema = js_value_to_json(&schema);
+                if !schema.is_object() {
+                    schema = serde_json::json!({ "type": "object", "properties": {} });
+                }
+                let description = schema
+                    .get("description")
+                    .and_then(|d| d.as_str())
+                    .unwrap_or_default()
+                    .to_string();
+                let _ = register_tx.send(ScriptEvent::RegisterTool {
+                    name,
+                    description,
+                    schema,
+                });
+                Ok(())
+            },
+        )?,
+    )?;
+    let result_tx = event_tx.clone();
+    kokoro.set(
+        "__toolResult",
+        Function::new(
+            ctx.clone(),
+            move |id: u32, value: rquickjs::Value<'_>, error: Option<String>| {
+                let result = match error {
+                    Some(error) => Err(error),
+                    None => Ok(js_value_to_json(&value)),
+                };
+                let _ = result_tx.send(ScriptEvent::ToolResult { id, result });
+            },
+        )?,
+    )?;
+    ctx.eval::<(), _>(
+        r#"
+        globalThis.__tools = {};
+        Kokoro.registerTool = function(name, schema, handler) {
+            if (typeof handler !== "function") throw new TypeError("Tool handler must be a function");
+            name = String(name);
+            Kokoro.__registerTool(name, schema || {});
+            globalThis.__tools[name] = handler;
+        };
+        globalThis.__callTool = function(id, name, args) {
+            var handler = globalThis.__tools[name];
+            if (!handler) {
+                Kokoro.__toolResult(id, null, "Tool '" + name + "' is not registered");
+                return;
+            }
+            new Promise(function(resolve) { resolve(handler(args)); }).then(
+                function(value) { Kokoro.__toolResult(id, value === undefined ? null : value, null); },
+                function(e) { Kokoro.__toolResult(id, null, String(e)); }
+            );
+        };
+        globalThis.__clearTools = function() { globalThis.__tools = {}; };
+    "#,
+    )?;
+
+    // ── Kokoro.permissions.has(name) ── lets scripts degrade gracefully
+    let permissions_ns = Object::new(ctx.clone())?;
+    permissions_ns.set(
+        "has",
+        Function::new(ctx.clone(), move |name: String| {
+            ModPermission::parse(&name)
+                .is_some_and(|permission| permissions::is_active(&active, permission))
+        })?,
+    )?;
+    kokoro.set("permissions", permissions_ns)?;
     Ok(())
 }
 
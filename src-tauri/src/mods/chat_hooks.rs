@@ -0,0 +1,137 @@
+//! Chat pipeline hooks: `chat:before-send` and `chat:before-display`.
+//!
+//! Listeners registered with `Kokoro.on` for these events run synchronously
+//! on the script thread and may return a string to replace the text or
+//! `false` to veto it. The chat pipeline only waits for hooks a mod actually
+//! registered, and a hook that does not answer within `TRANSFORM_TIMEOUT`
+//! leaves the text unchanged.
+
+use crate::mods::manager::ScriptCommand;
+use crate::mods::permissions::{self, ActivePermissions, ModPermission};
+use serde::Deserialize;
+use serde_json::Value as JsonValue;
+use std::collections::HashSet;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
+
+/// The user's message, before it is queued for the LLM.
+pub const BEFORE_SEND: &str = "chat:before-send";
+/// The character's finished reply, before it is shown, stored and spoken.
+pub const BEFORE_DISPLAY: &str = "chat:before-display";
+/// Longest a hook may run; the script is interrupted after this.
+pub const TRANSFORM_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// What the mod's listeners decided.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum ChatTransform {
+    Keep,
+    Replace { text: String },
+    Veto { reason: Option<String> },
+}
+
+impl ChatTransform {
+    /// Parse the JSON returned by `__transform`; anything else keeps the text.
+    pub fn from_script(raw: Result<String, String>) -> Self {
+        match raw {
+            Ok(json) => serde_json::from_str(&json).unwrap_or(ChatTransform::Keep),
+            Err(e) => {
+                tracing::warn!(target: "mods", "[ModManager] Chat hook failed: {}", e);
+                ChatTransform::Keep
+            }
+        }
+    }
+}
+
+/// Hook events the running mod has listeners for.
+pub type WatchedHooks = Arc<RwLock<HashSet<String>>>;
+
+pub fn is_hook_event(event: &str) -> bool {
+    event == BEFORE_SEND || event == BEFORE_DISPLAY
+}
+
+/// Handle the chat pipeline uses to run hooks without holding the
+/// `ModManager` lock while the script works.
+#[derive(Clone)]
+pub struct ChatHooks {
+    pub(crate) script_tx: mpsc::Sender<ScriptCommand>,
+    pub(crate) watched: WatchedHooks,
+    pub(crate) active: ActivePermissions,
+}
+
+impl ChatHooks {
+    pub fn is_watched(&self, event: &str) -> bool {
+        permissions::is_active(&self.active, ModPermission::ChatTransform)
+            && self
+                .watched
+                .read()
+                .map(|watched| watched.contains(event))
+                .unwrap_or(false)
+    }
+
+    /// Run the listeners for `event` with `payload` (which carries `text`).
+    pub async fn transform(&self, event: &str, payload: JsonValue) -> ChatTransform {
+        if !self.is_watched(event) {
+            return ChatTransform::Keep;
+        }
+        let (reply, rx) = oneshot::channel();
+        let sent = self
+            .script_tx
+            .send(ScriptCommand::Transform {
+                event: event.to_string(),
+                payload,
+                reply,
+            })
+            .await;
+        if sent.is_err() {
+            return ChatTransform::Keep;
+        }
+        match tokio::time::timeout(TRANSFORM_TIMEOUT, rx).await {
+            Ok(Ok(outcome)) => outcome,
+            _ => {
+                tracing::warn!(
+                    target: "mods",
+                    "[ModManager] '{}' hook timed out; text left unchanged",
+                    event
+                );
+                ChatTransform::Keep
+            }
+        }
+    }
+}
+
+/// Hooks of the running mod, if its script runtime is up.
+pub async fn for_app<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> Option<ChatHooks> {
+    use tauri::Manager;
+    let manager = app.try_state::<tokio::sync::Mutex<crate::mods::ModManager>>()?;
+    let manager = manager.lock().await;
+    manager.chat_hooks()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn script_outcomes_parse_and_default_to_keep() {
+        assert_eq!(
+            ChatTransform::from_script(Ok(r#"{"action":"replace","text":"hi"}"#.to_string())),
+            ChatTransform::Replace {
+                text: "hi".to_string()
+            }
+        );
+        assert_eq!(
+            ChatTransform::from_script(Ok(r#"{"action":"veto","reason":null}"#.to_string())),
+            ChatTransform::Veto { reason: None }
+        );
+        assert_eq!(
+            ChatTransform::from_script(Ok("undefined".to_string())),
+            ChatTransform::Keep
+        );
+        assert_eq!(
+            ChatTransform::from_script(Err("interrupted".to_string())),
+            ChatTransform::Keep
+        );
+    }
+}
@@ -0,0 +1,242 @@
+//! Sandboxed HTTP for mod scripts (`Kokoro.http.fetch`).
+//!
+//! A mod can only reach the hosts its manifest lists in `network_allowlist`,
+//! and only with the `network` permission granted. Redirects are followed
+//! only while they stay on allowed hosts, and response bodies are capped.
+//! Requests run on the async runtime; the result goes back to the QuickJS
+//! thread, which settles the promise `fetch` returned.
+
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+const MAX_REDIRECTS: usize = 5;
+/// Largest response body handed to a script.
+pub const MAX_RESPONSE_BYTES: usize = 2 * 1024 * 1024;
+
+/// Allowlist of the running mod, shared with the QuickJS thread.
+pub type ActiveHosts = Arc<RwLock<Vec<String>>>;
+
+/// `fetch(url, opts)` options, as sent by the script.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FetchRequest {
+    pub url: String,
+    #[serde(default)]
+    pub method: Option<String>,
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    #[serde(default)]
+    pub body: Option<String>,
+}
+
+impl FetchRequest {
+    /// Build from `fetch(url, opts)`; a non-string `body` is sent as JSON.
+    pub fn from_script(url: String, opts: &serde_json::Value) -> Self {
+        let body = match opts.get("body") {
+            None | Some(serde_json::Value::Null) => None,
+            Some(serde_json::Value::String(body)) => Some(body.clone()),
+            Some(other) => Some(other.to_string()),
+        };
+        Self {
+            url,
+            method: opts
+                .get("method")
+                .and_then(|method| method.as_str())
+                .map(str::to_string),
+            headers: opts
+                .get("headers")
+                .and_then(|headers| serde_json::from_value(headers.clone()).ok())
+                .unwrap_or_default(),
+            body,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FetchResponse {
+    pub ok: bool,
+    pub status: u16,
+    pub status_text: String,
+    /// Final URL after redirects.
+    pub url: String,
+    pub headers: HashMap<String, String>,
+    pub body: String,
+}
+
+/// Normalized allowlist entries: lowercase hosts, `*.` prefixes kept.
+pub fn normalize_allowlist(entries: &[String]) -> Vec<String> {
+    let mut out: Vec<String> = Vec::new();
+    for entry in entries
+        .iter()
+        .map(|entry| entry.trim().trim_end_matches('.').to_ascii_lowercase())
+        .filter(|entry| !entry.is_empty() && entry != "*" && entry != "*.")
+    {
+        if !out.contains(&entry) {
+            out.push(entry);
+        }
+    }
+    out
+}
+
+/// `example.com` matches only itself; `*.example.com` matches its subdomains.
+pub fn host_allowed(allowlist: &[String], host: &str) -> bool {
+    let host = host.trim_end_matches('.').to_ascii_lowercase();
+    allowlist
+        .iter()
+        .any(|entry| match entry.strip_prefix("*.") {
+            Some(domain) => host
+                .strip_suffix(domain)
+                .is_some_and(|prefix| prefix.ends_with('.') && prefix.len() > 1),
+            None => *entry == host,
+        })
+}
+
+/// Parse `url` and check it is http(s) on an allowed host.
+pub fn check_url(allowlist: &[String], url: &str) -> Result<Url, String> {
+    let parsed = Url::parse(url.trim()).map_err(|e| format!("Invalid URL '{}': {}", url, e))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(format!(
+            "Only http and https URLs can be fetched, got '{}'",
+            url
+        ));
+    }
+    let host = parsed
+        .host_str()
+        .ok_or_else(|| format!("URL '{}' has no host", url))?;
+    if !host_allowed(allowlist, host) {
+        return Err(format!(
+            "Host '{}' is not in this mod's network_allowlist",
+            host
+        ));
+    }
+    Ok(parsed)
+}
+
+pub async fn fetch(allowlist: Vec<String>, request: FetchRequest) -> Result<FetchResponse, String> {
+    let url = check_url(&allowlist, &request.url)?;
+    let method = request
+        .method
+        .as_deref()
+        .unwrap_or("GET")
+        .trim()
+        .to_uppercase();
+    let method = reqwest::Method::from_bytes(method.as_bytes())
+        .map_err(|_| format!("Invalid HTTP method '{}'", method))?;
+
+    let redirect_allowlist = allowlist.clone();
+    let client = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .redirect(reqwest::redirect::Policy::custom(move |attempt| {
+            if attempt.previous().len() >= MAX_REDIRECTS {
+                attempt.error("too many redirects")
+            } else if check_url(&redirect_allowlist, attempt.url().as_str()).is_err() {
+                attempt.error("redirect left the mod's network_allowlist")
+            } else {
+                attempt.follow()
+            }
+        }))
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    let mut builder = client.request(method, url);
+    for (name, value) in &request.headers {
+        builder = builder.header(name.as_str(), value.as_str());
+    }
+    if let Some(body) = request.body {
+        builder = builder.body(body);
+    }
+    let mut response = builder
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {}", e))?;
+
+    let status = response.status();
+    let final_url = response.url().to_string();
+    let headers = response
+        .headers()
+        .iter()
+        .filter_map(|(name, value)| {
+            value
+                .to_str()
+                .ok()
+                .map(|value| (name.as_str().to_string(), value.to_string()))
+        })
+        .collect();
+    let mut body = Vec::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| format!("Failed to read response: {}", e))?
+    {
+        if body.len() + chunk.len() > MAX_RESPONSE_BYTES {
+            return Err(format!(
+                "Response is larger than {} bytes",
+                MAX_RESPONSE_BYTES
+            ));
+        }
+        body.extend_from_slice(&chunk);
+    }
+
+    Ok(FetchResponse {
+        ok: status.is_success(),
+        status: status.as_u16(),
+        status_text: status.canonical_reason().unwrap_or_default().to_string(),
+        url: final_url,
+        headers,
+        body: String::from_utf8_lossy(&body).into_owned(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn allowlist(entries: &[&str]) -> Vec<String> {
+        normalize_allowlist(&entries.iter().map(|e| e.to_string()).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn exact_hosts_and_wildcard_subdomains() {
+        let list = allowlist(&[" API.Open-Meteo.com ", "*.example.com", "*", ""]);
+        assert_eq!(list, vec!["api.open-meteo.com", "*.example.com"]);
+
+        assert!(host_allowed(&list, "api.open-meteo.com"));
+        assert!(!host_allowed(&list, "open-meteo.com"));
+        assert!(host_allowed(&list, "feeds.example.com"));
+        assert!(!host_allowed(&list, "example.com"));
+        assert!(!host_allowed(&list, "badexample.com"));
+        assert!(!host_allowed(&[], "api.open-meteo.com"));
+    }
+
+    #[test]
+    fn script_options_become_a_request() {
+        let request = FetchRequest::from_script(
+            "https://api.example.com".to_string(),
+            &serde_json::json!({
+                "method": "POST",
+                "headers": { "Accept": "application/json" },
+                "body": { "q": 1 }
+            }),
+        );
+        assert_eq!(request.method.as_deref(), Some("POST"));
+        assert_eq!(request.headers["Accept"], "application/json");
+        assert_eq!(request.body.as_deref(), Some(r#"{"q":1}"#));
+
+        let bare = FetchRequest::from_script("https://a.io".to_string(), &serde_json::json!({}));
+        assert_eq!(bare.method, None);
+        assert_eq!(bare.body, None);
+    }
+
+    #[test]
+    fn urls_must_be_http_on_allowed_hosts() {
+        let list = allowlist(&["api.open-meteo.com"]);
+        assert!(check_url(&list, "https://api.open-meteo.com/v1/forecast?lat=1").is_ok());
+        assert!(check_url(&list, "http://api.open-meteo.com.evil.io/").is_err());
+        assert!(check_url(&list, "https://user@evil.io/?api.open-meteo.com").is_err());
+        assert!(check_url(&list, "file:///etc/passwd").is_err());
+        assert!(check_url(&list, "not a url").is_err());
+    }
+}
@@ -1,14 +1,22 @@
 use crate::hooks::{HookEvent, HookPayload, HookRuntime, ModHookPayload};
 use crate::mods::api::ScriptEvent;
+use crate::mods::chat_hooks::{self, ChatHooks, ChatTransform, WatchedHooks};
+use crate::mods::http::{self, ActiveHosts};
 use crate::mods::manifest::ModManifest;
+use crate::mods::permissions::{self, ActivePermissions, ModPermission, ModPermissionGrants};
+use crate::mods::storage::{ActiveStorage, ModStorage};
 use crate::mods::theme::ModThemeJson;
+use crate::mods::timers;
+use crate::mods::tools::{ModToolHandler, PendingToolCalls};
 use serde_json::Value as JsonValue;
-use std::collections::HashMap;
+use std::cell::Cell;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::rc::Rc;
 use std::sync::atomic::{AtomicU8, Ordering};
-use std::sync::Arc;
-use std::time::Duration;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
 use tauri::{Emitter, Manager};
 use tokio::sync::{mpsc, oneshot};
 
@@ -45,6 +53,31 @@ pub enum ScriptCommand {
         event: String,
         payload: serde_json::Value,
     },
+    /// Settle the promise of a finished Kokoro.http.fetch call
+    SettleFetch {
+        id: u32,
+        result: Result<http::FetchResponse, String>,
+    },
+    /// Run the handler of a tool registered via Kokoro.registerTool(); the
+    /// result comes back as ScriptEvent::ToolResult
+    CallTool {
+        id: u32,
+        name: String,
+        args: serde_json::Value,
+    },
+    /// Drop every pending timer and schedule (mod unloaded)
+    ClearTimers,
+    /// Forget every registered tool handler (mod unloaded)
+    ClearTools,
+    /// Run the chat:before-* listeners synchronously, interrupting them after
+    /// chat_hooks::TRANSFORM_TIMEOUT
+    Transform {
+        event: String,
+        payload: serde_json::Value,
+        reply: oneshot::Sender<ChatTransform>,
+    },
+    /// Drop the chat:before-* listeners (mod unloaded)
+    ClearChatHooks,
     Shutdown,
 }
 
@@ -68,6 +101,9 @@ struct CuePayload {
     cue: String,
 }
 
+/// How long a restarted script runtime may take to come up.
+const RUNTIME_START_TIMEOUT: Duration = Duration::from_secs(1);
+
 fn validate_manifest_capabilities(manifest: &ModManifest) -> Result<(), String> {
     for capability in &manifest.capabilities {
         if capability.name.trim().is_empty() {
@@ -80,6 +116,16 @@ fn validate_manifest_capabilities(manifest: &ModManifest) -> Result<(), String>
     Ok(())
 }
 
+/// Parse and validate a single mod's `mod.json`.
+pub(crate) fn read_manifest(mod_dir: &Path) -> Result<ModManifest, String> {
+    let content = fs::read_to_string(mod_dir.join("mod.json"))
+        .map_err(|e| format!("Failed to read mod.json: {}", e))?;
+    let manifest = serde_json::from_str::<ModManifest>(&content)
+        .map_err(|e| format!("Failed to parse mod.json: {}", e))?;
+    validate_manifest_capabilities(&manifest)?;
+    Ok(manifest)
+}
+
 fn build_mod_hook_payload(manifest: &ModManifest, stage: &str) -> HookPayload {
     let script_count = if !manifest.scripts.is_empty() {
         manifest.scripts.len()
@@ -151,6 +197,21 @@ pub struct ModManager {
     pub active_theme: Option<ModThemeJson>,
     /// Currently active layout loaded from a mod's layout.json
     pub active_layout: Option<JsonValue>,
+    /// User-granted permissions per mod
+    permission_grants: ModPermissionGrants,
+    permissions_path: Option<PathBuf>,
+    /// Permissions of the running mod, read by the Kokoro JS API
+    active_permissions: ActivePermissions,
+    /// Backs `Kokoro.storage`, scoped to the running mod
+    storage: ActiveStorage,
+    /// Hosts the running mod may fetch from
+    active_hosts: ActiveHosts,
+    /// Id of the running mod, which owns tools it registers
+    active_mod_id: Arc<RwLock<Option<String>>>,
+    /// Tool calls waiting on a script handler
+    pending_tools: Arc<PendingToolCalls>,
+    /// Chat hooks the running mod listens for
+    watched_hooks: WatchedHooks,
 }
 
 impl ModManager {
@@ -174,15 +235,86 @@ impl ModManager {
             runtime_state: Arc::new(AtomicU8::new(ModRuntimeState::Uninitialized.as_u8())),
             active_theme: None,
             active_layout: None,
+            permission_grants: ModPermissionGrants::default(),
+            permissions_path: None,
+            active_permissions: Arc::new(RwLock::new(HashSet::new())),
+            storage: Arc::new(ModStorage::default()),
+            active_hosts: Arc::new(RwLock::new(Vec::new())),
+            active_mod_id: Arc::new(RwLock::new(None)),
+            pending_tools: Arc::new(PendingToolCalls::default()),
+            watched_hooks: Arc::new(RwLock::new(HashSet::new())),
         }
     }
 
+    /// Persist `Kokoro.storage` as one JSON file per mod in `dir`.
+    pub fn with_storage_dir(mut self, dir: PathBuf) -> Self {
+        self.storage = Arc::new(ModStorage::new(Some(dir)));
+        self
+    }
+
+    /// Load and persist permission grants at `path`.
+    pub fn with_permissions_file(mut self, path: PathBuf) -> Self {
+        self.permission_grants = permissions::load_grants(&path);
+        self.permissions_path = Some(path);
+        self
+    }
+
+    pub fn permission_grants(&self) -> &ModPermissionGrants {
+        &self.permission_grants
+    }
+
+    /// Replace the user's grants for a mod. Takes effect the next time it loads.
+    pub fn set_permissions(
+        &mut self,
+        mod_id: &str,
+        granted: Vec<ModPermission>,
+    ) -> Result<(), String> {
+        let mut next = self.permission_grants.clone();
+        next.set(mod_id, granted);
+        if let Some(path) = &self.permissions_path {
+            permissions::save_grants(path, &next).map_err(|e| e.to_string())?;
+        }
+        self.permission_grants = next;
+        tracing::info!(
+            target: "mods",
+            "[ModManager] Permissions for mod '{}' set to {:?}",
+            mod_id,
+            self.permission_grants.granted(mod_id)
+        );
+        Ok(())
+    }
+
+    fn set_active_permissions(&self, active: HashSet<ModPermission>) {
+        if let Ok(mut current) = self.active_permissions.write() {
+            *current = active;
+        }
+    }
+
+    fn set_active_hosts(&self, hosts: Vec<String>) {
+        if let Ok(mut current) = self.active_hosts.write() {
+            *current = hosts;
+        }
+    }
+
+    fn set_active_mod_id(&self, mod_id: Option<&str>) {
+        if let Ok(mut current) = self.active_mod_id.write() {
+            *current = mod_id.map(str::to_string);
+        }
+    }
+
+    /// Id of the running mod, if any.
+    pub fn active_mod_id(&self) -> Option<String> {
+        self.active_mod_id.read().ok().and_then(|id| id.clone())
+    }
+
     /// Spawn the QuickJS runtime thread and the event relay task.
     /// The event relay forwards ScriptEvents from QuickJS → Tauri event bus.
     pub fn init<R: tauri::Runtime>(&mut self, app_handle: tauri::AppHandle<R>) {
         self.set_runtime_state(ModRuntimeState::Initializing);
 
         let (tx, mut rx) = mpsc::channel::<ScriptCommand>(32);
+        let fetch_reply_tx = tx.clone();
+        let tool_call_tx = tx.clone();
         self.script_tx = Some(tx);
 
         // Outgoing event channel: QuickJS closures → event relay → Tauri
@@ -190,6 +322,12 @@ impl ModManager {
         let (event_tx, event_rx) = std::sync::mpsc::channel::<ScriptEvent>();
         let state_for_script = self.runtime_state.clone();
         let state_for_health = self.runtime_state.clone();
+        let active_permissions = self.active_permissions.clone();
+        let storage = self.storage.clone();
+        let active_hosts = self.active_hosts.clone();
+        let active_mod_id = self.active_mod_id.clone();
+        let pending_tools = self.pending_tools.clone();
+        let watched_hooks = self.watched_hooks.clone();
 
         // ── QuickJS runtime thread ──
         std::thread::spawn(move || {
@@ -210,17 +348,64 @@ impl ModManager {
                 }
             };
 
+            // Drives the wait for "next command or next due timer".
+            let clock = match tokio::runtime::Builder::new_current_thread()
+                .enable_time()
+                .build()
+            {
+                Ok(clock) => clock,
+                Err(e) => {
+                    tracing::error!(target: "mods", "[ModManager] Failed to create script timer clock: {}", e);
+                    state_for_script.store(ModRuntimeState::Failed.as_u8(), Ordering::SeqCst);
+                    return;
+                }
+            };
+            let script_timers = timers::SharedTimers::default();
+
+            // Set while a chat hook runs so a runaway listener is interrupted.
+            let hook_deadline = Rc::new(Cell::new(None::<Instant>));
+            let interrupt_deadline = hook_deadline.clone();
+            rt.set_interrupt_handler(Some(Box::new(move || {
+                interrupt_deadline
+                    .get()
+                    .is_some_and(|deadline| Instant::now() >= deadline)
+            })));
+
             // Register the Kokoro API with the event sender
             ctx.with(|ctx| {
-                if let Err(e) = crate::mods::api::register_api(&ctx, event_tx) {
+                if let Err(e) = crate::mods::api::register_api(
+                    &ctx,
+                    event_tx,
+                    active_permissions.clone(),
+                    storage,
+                    active_hosts,
+                    script_timers.clone(),
+                ) {
                     eprintln!("Failed to register Kokoro API: {}", e);
                 }
             });
 
             state_for_script.store(ModRuntimeState::Running.as_u8(), Ordering::SeqCst);
 
-            // Event loop: process incoming script commands
-            while let Some(cmd) = rx.blocking_recv() {
+            // Event loop: process incoming script commands, waking early for
+            // timers. `None` from the wait means a timer is due.
+            loop {
+                let next_due = script_timers.borrow().next_due();
+                let received = clock.block_on(async {
+                    match next_due {
+                        Some(due) => tokio::time::timeout_at(due.into(), rx.recv()).await.ok(),
+                        None => Some(rx.recv().await),
+                    }
+                });
+                let cmd = match received {
+                    Some(Some(cmd)) => cmd,
+                    Some(None) => break,
+                    None => {
+                        timers::fire_due(&ctx, &script_timers);
+                        drain_pending_jobs(&rt);
+                        continue;
+                    }
+                };
                 match cmd {
                     ScriptCommand::Eval { code, reply } => {
                         let result = ctx.with(|ctx| {
@@ -230,6 +415,11 @@ impl ModManager {
                         let _ = reply.send(result);
                     }
                     ScriptCommand::DispatchEvent { event, payload } => {
+                        if let Some(permission) = permissions::required_for_event(&event) {
+                            if !permissions::is_active(&active_permissions, permission) {
+                                continue;
+                            }
+                        }
                         // Call globalThis.__dispatch(event, payload) in QuickJS
                         ctx.with(|ctx| {
                             // 使用 serde_json 双重编码确保安全，避免字符串拼接注入
@@ -247,8 +437,85 @@ impl ModManager {
                             }
                         });
                     }
+                    ScriptCommand::SettleFetch { id, result } => {
+                        ctx.with(|ctx| {
+                            let (response, error) = match result {
+                                Ok(response) => (
+                                    serde_json::to_string(&response).unwrap_or_default(),
+                                    "null".to_string(),
+                                ),
+                                Err(e) => (
+                                    "null".to_string(),
+                                    serde_json::to_string(&e).unwrap_or_default(),
+                                ),
+                            };
+                            let response_escaped =
+                                serde_json::to_string(&response).unwrap_or_default();
+                            let settle_code = format!(
+                                "globalThis.__settleFetch({}, JSON.parse({}), {});",
+                                id, response_escaped, error
+                            );
+                            if let Err(e) = ctx.eval::<(), _>(settle_code.as_str()) {
+                                tracing::warn!(target: "mods", "[ModManager] Failed to settle fetch {}: {}", id, e);
+                            }
+                        });
+                    }
+                    ScriptCommand::CallTool { id, name, args } => {
+                        ctx.with(|ctx| {
+                            let name_json = serde_json::to_string(&name).unwrap_or_default();
+                            let args_str = serde_json::to_string(&args).unwrap_or_default();
+                            let args_escaped = serde_json::to_string(&args_str).unwrap_or_default();
+                            let call_code = format!(
+                                "globalThis.__callTool({}, {}, JSON.parse({}));",
+                                id, name_json, args_escaped
+                            );
+                            if let Err(e) = ctx.eval::<(), _>(call_code.as_str()) {
+                                tracing::warn!(target: "mods", "[ModManager] Failed to call tool '{}': {}", name, e);
+                            }
+                        });
+                    }
+                    ScriptCommand::ClearTools => {
+                        ctx.with(|ctx| {
+                            let _ = ctx.eval::<(), _>("globalThis.__clearTools();");
+                        });
+                    }
+                    ScriptCommand::Transform {
+                        event,
+                        payload,
+                        reply,
+                    } => {
+                        hook_deadline.set(Some(Instant::now() + chat_hooks::TRANSFORM_TIMEOUT));
+                        let raw = ctx.with(|ctx| {
+                            let event_json = serde_json::to_string(&event).unwrap_or_default();
+                            let payload_str = serde_json::to_string(&payload).unwrap_or_default();
+                            let payload_escaped =
+                                serde_json::to_string(&payload_str).unwrap_or_default();
+                            let transform_code = format!(
+                                "globalThis.__transform({}, JSON.parse({}));",
+                                event_json, payload_escaped
+                            );
+                            ctx.eval::<String, _>(transform_code.as_str())
+                                .map_err(|e| format!("{}", e))
+                        });
+                        hook_deadline.set(None);
+                        let _ = reply.send(ChatTransform::from_script(raw));
+                    }
+                    ScriptCommand::ClearChatHooks => {
+                        ctx.with(|ctx| {
+                            let _ = ctx.eval::<(), _>("globalThis.__clearChatHooks();");
+                        });
+                    }
+                    ScriptCommand::ClearTimers => {
+                        script_timers.borrow_mut().clear();
+                        ctx.with(|ctx| {
+                            let _ = ctx.eval::<(), _>("globalThis.__clearTimers();");
+                        });
+                    }
                     ScriptCommand::Shutdown => break,
                 }
+
+                // Run promise callbacks queued by the command (e.g. fetch `.then`).
+                drain_pending_jobs(&rt);
             }
 
             state_for_script.store(ModRuntimeState::Disconnected.as_u8(), Ordering::SeqCst);
@@ -288,6 +555,69 @@ impl ModManager {
                         let _ = handle.emit("chat-cue", CuePayload { cue: cue.clone() });
                         tracing::info!(target: "mods", "[ModManager] Cue triggered '{}'", cue);
                     }
+                    ScriptEvent::HttpFetch {
+                        id,
+                        allowlist,
+                        request,
+                    } => {
+                        let reply_tx = fetch_reply_tx.clone();
+                        tauri::async_runtime::spawn(async move {
+                            let url = request.url.clone();
+                            let result = http::fetch(allowlist, request).await;
+                            if let Err(e) = &result {
+                                tracing::warn!(target: "mods", "[ModManager] Fetch '{}' failed: {}", url, e);
+                            }
+                            let _ = reply_tx
+                                .send(ScriptCommand::SettleFetch { id, result })
+                                .await;
+                        });
+                    }
+                    ScriptEvent::RegisterTool {
+                        name,
+                        description,
+                        schema,
+                    } => {
+                        let Some(mod_id) = active_mod_id.read().ok().and_then(|id| id.clone())
+                        else {
+                            tracing::warn!(target: "mods", "[ModManager] Tool '{}' registered with no mod loaded", name);
+                            continue;
+                        };
+                        let Some(registry) = handle
+                            .try_state::<Arc<tokio::sync::RwLock<crate::actions::ActionRegistry>>>()
+                            .map(|state| state.inner().clone())
+                        else {
+                            continue;
+                        };
+                        let handler = ModToolHandler {
+                            tool_name: name.clone(),
+                            description,
+                            input_schema: schema,
+                            script_tx: tool_call_tx.clone(),
+                            pending: pending_tools.clone(),
+                        };
+                        tauri::async_runtime::spawn(async move {
+                            registry.write().await.register_mod(mod_id.clone(), handler);
+                            tracing::info!(target: "mods", "[ModManager] Mod '{}' registered tool '{}'", mod_id, name);
+                        });
+                    }
+                    ScriptEvent::ToolResult { id, result } => {
+                        pending_tools.settle(id, result);
+                    }
+                    ScriptEvent::WatchHook { event } => {
+                        if let Ok(mut watched) = watched_hooks.write() {
+                            watched.insert(event);
+                        }
+                    }
+                    ScriptEvent::SetEmotion { emotion, intensity } => {
+                        let Some(emotion) = crate::ai::emotion::emotion_from_label(&emotion) else {
+                            tracing::warn!(target: "mods", "[ModManager] Unknown emotion '{}'", emotion);
+                            continue;
+                        };
+                        let handle = handle.clone();
+                        tauri::async_runtime::spawn(async move {
+                            crate::ai::emotion::observe(&handle, emotion, intensity, "mod").await;
+                        });
+                    }
                 }
             }
             tracing::info!(target: "mods", "[ModManager] Event relay shut down.");
@@ -386,6 +716,32 @@ impl ModManager {
 
         let mod_dir = self.mods_path.join(mod_id);
 
+        // Scripts only see what the user granted and the manifest still asks for.
+        let requested = permissions::requested(&manifest.permissions);
+        let active = self.permission_grants.effective(mod_id, &requested);
+        if active.len() < requested.len() {
+            tracing::info!(
+                target: "mods",
+                "[ModManager] Mod '{}' runs with {} of {} requested permissions",
+                mod_id,
+                active.len(),
+                requested.len()
+            );
+        }
+        let network = active.contains(&ModPermission::Network);
+        self.set_active_permissions(active);
+        self.storage.set_active_mod(Some(mod_id));
+        self.set_active_mod_id(Some(mod_id));
+        let hosts = http::normalize_allowlist(&manifest.network_allowlist);
+        if network && hosts.is_empty() {
+            tracing::info!(
+                target: "mods",
+                "[ModManager] Mod '{}' has network access but no network_allowlist; fetches will be refused",
+                mod_id
+            );
+        }
+        self.set_active_hosts(hosts);
+
         // ── 1. Load theme.json ──
         if let Some(theme_path) = &manifest.theme {
             let full_path = match safe_join(&mod_dir, theme_path) {
@@ -622,6 +978,18 @@ impl ModManager {
         })
     }
 
+    /// Handle for running the chat:before-* hooks, if the runtime is up.
+    pub fn chat_hooks(&self) -> Option<ChatHooks> {
+        if !self.runtime_ready() {
+            return None;
+        }
+        Some(ChatHooks {
+            script_tx: self.script_tx.clone()?,
+            watched: self.watched_hooks.clone(),
+            active: self.active_permissions.clone(),
+        })
+    }
+
     pub fn runtime_status_module_tag(&self) -> Option<String> {
         if self.runtime_ready() {
             Some("mods".to_string())
@@ -660,11 +1028,75 @@ impl ModManager {
         self.active_layout.as_ref()
     }
 
+    /// Re-read the running mod from disk and start it again in a fresh script
+    /// runtime, so listeners and top-level declarations of the old code are
+    /// gone. If mod.json no longer parses, the old version keeps running.
+    pub async fn reload_active_mod<R: tauri::Runtime>(
+        &mut self,
+        app_handle: &tauri::AppHandle<R>,
+    ) -> Result<(), String> {
+        let Some(mod_id) = self.active_mod_id() else {
+            return Ok(());
+        };
+        let manifest = read_manifest(&self.mods_path.join(&mod_id))?;
+        self.loaded_mods.insert(mod_id.clone(), manifest);
+
+        self.restart_runtime(app_handle).await;
+        // The frontend drops the old theme, layout and components; load_mod
+        // sends the new ones.
+        self.active_theme = None;
+        self.active_layout = None;
+        let _ = app_handle.emit("mod:unload", ());
+        self.load_mod(&mod_id, app_handle).await
+    }
+
+    /// Shut the script thread down and spawn a new one.
+    async fn restart_runtime<R: tauri::Runtime>(&mut self, app_handle: &tauri::AppHandle<R>) {
+        if let Some(tx) = self.script_tx.take() {
+            let _ = tx.send(ScriptCommand::Shutdown).await;
+        }
+        if let Ok(mut watched) = self.watched_hooks.write() {
+            watched.clear();
+        }
+        if let Some(registry) =
+            app_handle.try_state::<Arc<tokio::sync::RwLock<crate::actions::ActionRegistry>>>()
+        {
+            registry.write().await.clear_mod_tools();
+        }
+        self.pending_tools.cancel_all();
+
+        // The old thread marks its own state disconnected when it exits.
+        self.runtime_state = Arc::new(AtomicU8::new(ModRuntimeState::Uninitialized.as_u8()));
+        self.init(app_handle.clone());
+        let deadline = Instant::now() + RUNTIME_START_TIMEOUT;
+        while self.runtime_state() == ModRuntimeState::Initializing && Instant::now() < deadline {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    }
+
     /// 卸载当前活跃的 Mod（清除主题、布局、组件），恢复原生模式
     pub async fn unload_mod<R: tauri::Runtime>(&mut self, app_handle: &tauri::AppHandle<R>) {
         let manifest = self.loaded_mods.values().next().cloned();
         self.active_theme = None;
         self.active_layout = None;
+        if let Some(tx) = &self.script_tx {
+            let _ = tx.send(ScriptCommand::ClearTimers).await;
+            let _ = tx.send(ScriptCommand::ClearTools).await;
+            let _ = tx.send(ScriptCommand::ClearChatHooks).await;
+        }
+        if let Ok(mut watched) = self.watched_hooks.write() {
+            watched.clear();
+        }
+        if let Some(registry) =
+            app_handle.try_state::<Arc<tokio::sync::RwLock<crate::actions::ActionRegistry>>>()
+        {
+            registry.write().await.clear_mod_tools();
+        }
+        self.pending_tools.cancel_all();
+        self.set_active_permissions(HashSet::new());
+        self.storage.set_active_mod(None);
+        self.set_active_mod_id(None);
+        self.set_active_hosts(Vec::new());
         let _ = app_handle.emit("mod:unload", ());
         if let (Some(hooks), Some(manifest)) = (app_handle.try_state::<HookRuntime>(), manifest) {
             hooks
@@ -678,6 +1110,17 @@ impl ModManager {
     }
 }
 
+/// Run queued promise callbacks and other jobs until the queue is empty.
+fn drain_pending_jobs(rt: &rquickjs::Runtime) {
+    loop {
+        match rt.execute_pending_job() {
+            Ok(true) => {}
+            Ok(false) => break,
+            Err(e) => tracing::warn!(target: "mods", "[ModManager] Script job failed: {}", e),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -725,6 +1168,7 @@ mod tests {
             )]),
             scripts: vec!["scripts/main.js".to_string()],
             permissions: vec![],
+            network_allowlist: vec![],
             capabilities: vec![],
             entry: None,
             ui_entry: None,
@@ -755,6 +1199,18 @@ mod tests {
         assert!(mods[0].components.contains_key("TestPanel"));
     }
 
+    #[test]
+    fn read_manifest_parses_and_rejects_broken_json() {
+        let (_tmp, mods_path) = setup_temp_mods();
+        let mod_dir = mods_path.join("test-mod");
+        assert_eq!(read_manifest(&mod_dir).unwrap().id, "test-mod");
+
+        fs::write(mod_dir.join("mod.json"), "{ not json").unwrap();
+        assert!(read_manifest(&mod_dir)
+            .unwrap_err()
+            .contains("Failed to parse mod.json"));
+    }
+
     #[test]
     fn scan_mods_empty_dir() {
         let tmp = TempDir::new().unwrap();
@@ -836,6 +1292,23 @@ mod tests {
         assert!(manager.active_layout.is_none());
     }
 
+    #[test]
+    fn permission_grants_persist_across_managers() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("mod_permissions.json");
+
+        let mut manager = ModManager::new(tmp.path()).with_permissions_file(path.clone());
+        manager
+            .set_permissions("test-mod", vec![ModPermission::ChatRead])
+            .unwrap();
+
+        let reloaded = ModManager::new(tmp.path()).with_permissions_file(path);
+        assert_eq!(
+            reloaded.permission_grants().granted("test-mod"),
+            &[ModPermission::ChatRead]
+        );
+    }
+
     #[test]
     fn production_init_path_avoids_runtime_expect_panics() {
         let source = include_str!("manager.rs");
@@ -37,10 +37,16 @@ pub struct ModManifest {
     #[serde(default)]
     pub scripts: Vec<String>,
 
-    /// Requested permissions, e.g. ["tts", "system.info"]
+    /// Requested permissions, e.g. ["network", "chat-read", "expression-control", "storage"].
+    /// Nothing is active until the user grants it; unknown names are ignored.
     #[serde(default)]
     pub permissions: Vec<String>,
 
+    /// Hosts `Kokoro.http.fetch` may reach, e.g. ["api.open-meteo.com"];
+    /// "*.example.com" allows its subdomains. Needs the "network" permission.
+    #[serde(default)]
+    pub network_allowlist: Vec<String>,
+
     /// Declarative capabilities for fine-grained intent (minimal model)
     #[serde(default)]
     pub capabilities: Vec<ModCapability>,
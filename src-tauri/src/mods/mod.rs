@@ -1,10 +1,19 @@
 pub mod api;
+pub mod chat_hooks;
+pub mod http;
 pub mod manager;
 pub mod manifest;
+pub mod permissions;
 pub mod protocol;
+pub mod registry;
+pub mod storage;
 pub mod theme;
+pub mod timers;
+pub mod tools;
+pub mod watcher;
 
 pub use api::ScriptEvent;
 pub use manager::ModManager;
 pub use manifest::ModManifest;
+pub use permissions::{ModPermission, ModPermissionGrants};
 pub use theme::ModThemeJson;
@@ -0,0 +1,184 @@
+//! Mod permissions — what a mod's scripts may do through the Kokoro JS API.
+//!
+//! A manifest lists the permissions it wants; the user reviews them when the
+//! mod is installed and grants all or some. Only granted permissions that the
+//! manifest also requests are active while the mod runs, and APIs behind a
+//! missing permission throw in the script.
+
+use crate::error::KokoroError;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ModPermission {
+    /// Outbound HTTP requests.
+    Network,
+    /// Receive the character's chat replies through `Kokoro.on`.
+    ChatRead,
+    /// Play cues and change the character's emotion.
+    ExpressionControl,
+    /// Persistent key-value storage.
+    Storage,
+    /// Register tools the character can call through `Kokoro.registerTool`.
+    Tools,
+    /// Rewrite or veto chat text through the `chat:before-*` hooks.
+    ChatTransform,
+}
+
+impl ModPermission {
+    pub const ALL: [ModPermission; 6] = [
+        ModPermission::Network,
+        ModPermission::ChatRead,
+        ModPermission::ExpressionControl,
+        ModPermission::Storage,
+        ModPermission::Tools,
+        ModPermission::ChatTransform,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ModPermission::Network => "network",
+            ModPermission::ChatRead => "chat-read",
+            ModPermission::ExpressionControl => "expression-control",
+            ModPermission::Storage => "storage",
+            ModPermission::Tools => "tools",
+            ModPermission::ChatTransform => "chat-transform",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|permission| permission.as_str() == name.trim())
+    }
+}
+
+/// Known permissions requested by a manifest; unknown names are dropped.
+pub fn requested(names: &[String]) -> Vec<ModPermission> {
+    let mut out = Vec::new();
+    for permission in names.iter().filter_map(|name| ModPermission::parse(name)) {
+        if !out.contains(&permission) {
+            out.push(permission);
+        }
+    }
+    out
+}
+
+/// The permission a dispatched engine event needs before scripts see it.
+pub fn required_for_event(event: &str) -> Option<ModPermission> {
+    match event {
+        "chat" | "chat-done" => Some(ModPermission::ChatRead),
+        "chat:before-send" | "chat:before-display" => Some(ModPermission::ChatTransform),
+        _ => None,
+    }
+}
+
+/// Permissions of the running mod, shared with the QuickJS thread.
+pub type ActivePermissions = Arc<RwLock<HashSet<ModPermission>>>;
+
+pub fn is_active(active: &ActivePermissions, permission: ModPermission) -> bool {
+    active
+        .read()
+        .map(|set| set.contains(&permission))
+        .unwrap_or(false)
+}
+
+/// User grants per mod id, persisted to `mod_permissions.json`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ModPermissionGrants {
+    #[serde(default)]
+    pub grants: HashMap<String, Vec<ModPermission>>,
+}
+
+impl ModPermissionGrants {
+    pub fn granted(&self, mod_id: &str) -> &[ModPermission] {
+        self.grants.get(mod_id).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Granted permissions the manifest still requests.
+    pub fn effective(&self, mod_id: &str, requested: &[ModPermission]) -> HashSet<ModPermission> {
+        self.granted(mod_id)
+            .iter()
+            .copied()
+            .filter(|permission| requested.contains(permission))
+            .collect()
+    }
+
+    pub fn set(&mut self, mod_id: &str, permissions: Vec<ModPermission>) {
+        if permissions.is_empty() {
+            self.grants.remove(mod_id);
+        } else {
+            self.grants.insert(mod_id.to_string(), permissions);
+        }
+    }
+}
+
+pub fn load_grants(path: &Path) -> ModPermissionGrants {
+    crate::config::load_json_config(path, "MOD_PERMISSIONS")
+}
+
+pub fn save_grants(path: &Path, grants: &ModPermissionGrants) -> Result<(), KokoroError> {
+    crate::config::save_json_config(path, grants, "MOD_PERMISSIONS")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn requested_keeps_known_permissions_once() {
+        let names = vec![
+            "network".to_string(),
+            "tts".to_string(),
+            " chat-read ".to_string(),
+            "network".to_string(),
+        ];
+        assert_eq!(
+            requested(&names),
+            vec![ModPermission::Network, ModPermission::ChatRead]
+        );
+    }
+
+    #[test]
+    fn effective_grants_require_a_matching_request() {
+        let mut grants = ModPermissionGrants::default();
+        grants.set(
+            "demo",
+            vec![ModPermission::Storage, ModPermission::ExpressionControl],
+        );
+        let active = grants.effective("demo", &[ModPermission::ExpressionControl]);
+        assert_eq!(active, HashSet::from([ModPermission::ExpressionControl]));
+        assert!(grants.effective("other", &ModPermission::ALL).is_empty());
+
+        grants.set("demo", Vec::new());
+        assert!(grants.granted("demo").is_empty());
+    }
+
+    #[test]
+    fn chat_events_need_chat_read() {
+        assert_eq!(required_for_event("chat"), Some(ModPermission::ChatRead));
+        assert_eq!(
+            required_for_event("chat-done"),
+            Some(ModPermission::ChatRead)
+        );
+        assert_eq!(
+            required_for_event("chat:before-display"),
+            Some(ModPermission::ChatTransform)
+        );
+        assert_eq!(required_for_event("emotion"), None);
+        assert_eq!(required_for_event("init"), None);
+    }
+
+    #[test]
+    fn grants_round_trip_as_kebab_case() {
+        let mut grants = ModPermissionGrants::default();
+        grants.set("demo", vec![ModPermission::ChatRead]);
+        let json = serde_json::to_string(&grants).unwrap();
+        assert!(json.contains("\"chat-read\""));
+        let back: ModPermissionGrants = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.granted("demo"), &[ModPermission::ChatRead]);
+    }
+}
@@ -0,0 +1,200 @@
+//! Online mod registry.
+//!
+//! A registry is an index JSON listing mods, each with a download URL for
+//! its zip package and the package's SHA-256. `browse_mod_registry` fetches
+//! the index; `install_mod_from_url` downloads a package, checks it against
+//! the checksum and installs it like a local `install_mod`. Registries must
+//! be served over https (plain http is accepted on loopback for testing).
+
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::time::Duration;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+const MAX_INDEX_BYTES: usize = 1024 * 1024;
+/// Matches the extracted-size cap of `install_mod`.
+pub const MAX_PACKAGE_BYTES: usize = 50 * 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModRegistryIndex {
+    #[serde(default)]
+    pub name: Option<String>,
+    pub mods: Vec<ModRegistryEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModRegistryEntry {
+    pub id: String,
+    pub name: String,
+    pub version: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub author: Option<String>,
+    /// Absolute, or relative to the index URL.
+    pub download_url: String,
+    /// Hex SHA-256 of the zip package.
+    pub sha256: String,
+}
+
+/// A registry entry as shown to the user.
+#[derive(Debug, Clone, Serialize)]
+pub struct ModRegistryListing {
+    #[serde(flatten)]
+    pub entry: ModRegistryEntry,
+    /// Version of the same mod already installed, if any.
+    pub installed_version: Option<String>,
+}
+
+/// Parse `url`, allowing https anywhere and http only on loopback.
+pub fn check_url(url: &str) -> Result<Url, String> {
+    let parsed = Url::parse(url.trim()).map_err(|e| format!("Invalid URL '{}': {}", url, e))?;
+    let loopback = matches!(
+        parsed.host_str(),
+        Some("localhost") | Some("127.0.0.1") | Some("[::1]")
+    );
+    match parsed.scheme() {
+        "https" => Ok(parsed),
+        "http" if loopback => Ok(parsed),
+        _ => Err(format!("Mod registries must use https, got '{}'", url)),
+    }
+}
+
+/// Parse an index and resolve each download URL against `base`. Entries
+/// with an unusable URL or checksum are dropped.
+pub fn parse_index(base: &Url, body: &[u8]) -> Result<ModRegistryIndex, String> {
+    let mut index: ModRegistryIndex =
+        serde_json::from_slice(body).map_err(|e| format!("Invalid registry index: {}", e))?;
+    index.mods.retain_mut(|entry| {
+        let resolved = base
+            .join(entry.download_url.trim())
+            .map_err(|e| e.to_string())
+            .and_then(|url| check_url(url.as_str()));
+        match resolved {
+            Ok(url) if is_sha256_hex(&entry.sha256) => {
+                entry.download_url = url.to_string();
+                entry.sha256 = entry.sha256.to_ascii_lowercase();
+                true
+            }
+            _ => {
+                tracing::warn!(
+                    target: "mods",
+                    "[ModRegistry] Skipping entry '{}': bad download_url or sha256",
+                    entry.id
+                );
+                false
+            }
+        }
+    });
+    Ok(index)
+}
+
+fn is_sha256_hex(value: &str) -> bool {
+    value.len() == 64 && value.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+pub fn verify_checksum(bytes: &[u8], expected: &str) -> Result<(), String> {
+    let actual = format!("{:x}", Sha256::digest(bytes));
+    if actual.eq_ignore_ascii_case(expected.trim()) {
+        Ok(())
+    } else {
+        Err(format!(
+            "Checksum mismatch: expected {}, got {}",
+            expected.trim(),
+            actual
+        ))
+    }
+}
+
+/// GET `url`, failing once the body grows past `max_bytes`.
+pub async fn download(url: &Url, max_bytes: usize) -> Result<Vec<u8>, String> {
+    let client = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .redirect(reqwest::redirect::Policy::custom(|attempt| {
+            if attempt.previous().len() >= 5 {
+                attempt.error("too many redirects")
+            } else if check_url(attempt.url().as_str()).is_err() {
+                attempt.error("redirect left https")
+            } else {
+                attempt.follow()
+            }
+        }))
+        .build()
+        .map_err(|e| e.to_string())?;
+    let mut response = client
+        .get(url.clone())
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("{} returned {}", url, response.status()));
+    }
+    let mut body = Vec::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| format!("Failed to read response: {}", e))?
+    {
+        if body.len() + chunk.len() > max_bytes {
+            return Err(format!("{} is larger than {} bytes", url, max_bytes));
+        }
+        body.extend_from_slice(&chunk);
+    }
+    Ok(body)
+}
+
+pub async fn fetch_index(url: &str) -> Result<ModRegistryIndex, String> {
+    let url = check_url(url)?;
+    let body = download(&url, MAX_INDEX_BYTES).await?;
+    parse_index(&url, &body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn index_entries_are_resolved_and_checked() {
+        let base = check_url("https://mods.example.com/registry/index.json").unwrap();
+        let body = serde_json::json!({
+            "mods": [
+                {
+                    "id": "clock",
+                    "name": "Clock",
+                    "version": "1.0.0",
+                    "download_url": "packages/clock.zip",
+                    "sha256": "AB".repeat(32)
+                },
+                {
+                    "id": "plain",
+                    "name": "Plain",
+                    "version": "1.0.0",
+                    "download_url": "http://mods.example.com/plain.zip",
+                    "sha256": "ab".repeat(32)
+                },
+                {
+                    "id": "short",
+                    "name": "Short",
+                    "version": "1.0.0",
+                    "download_url": "short.zip",
+                    "sha256": "abc"
+                }
+            ]
+        });
+        let index = parse_index(&base, body.to_string().as_bytes()).unwrap();
+        assert_eq!(index.mods.len(), 1);
+        assert_eq!(
+            index.mods[0].download_url,
+            "https://mods.example.com/registry/packages/clock.zip"
+        );
+        assert_eq!(index.mods[0].sha256, "ab".repeat(32));
+
+        assert!(check_url("http://localhost:8080/index.json").is_ok());
+        assert!(check_url("file:///etc/passwd").is_err());
+
+        let digest = format!("{:x}", Sha256::digest(b"zip"));
+        assert!(verify_checksum(b"zip", &digest.to_uppercase()).is_ok());
+        assert!(verify_checksum(b"zap", &digest).is_err());
+    }
+}
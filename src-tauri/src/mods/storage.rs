@@ -0,0 +1,191 @@
+//! Persistent key-value storage for mod scripts (`Kokoro.storage`).
+//!
+//! Each mod gets its own JSON file under `mod_storage/`, named after the mod
+//! id, so mods cannot read or overwrite each other's state. Calls come from
+//! the QuickJS thread and use blocking file IO on it.
+
+use serde_json::Value as JsonValue;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+
+/// Longest key a script may use.
+pub const MAX_KEY_LEN: usize = 256;
+/// Largest a single mod's storage file may grow.
+pub const MAX_STORAGE_BYTES: usize = 1024 * 1024;
+
+type Entries = BTreeMap<String, JsonValue>;
+
+/// Storage of the running mod, shared with the QuickJS thread.
+pub type ActiveStorage = Arc<ModStorage>;
+
+#[derive(Debug, Default)]
+pub struct ModStorage {
+    /// `None` keeps storage in memory only (tests, or no app data dir).
+    dir: Option<PathBuf>,
+    mod_id: RwLock<Option<String>>,
+    memory: RwLock<BTreeMap<String, Entries>>,
+}
+
+impl ModStorage {
+    pub fn new(dir: Option<PathBuf>) -> Self {
+        Self {
+            dir,
+            ..Self::default()
+        }
+    }
+
+    /// Scope storage calls to `mod_id`; `None` while no mod is running.
+    pub fn set_active_mod(&self, mod_id: Option<&str>) {
+        if let Ok(mut current) = self.mod_id.write() {
+            *current = mod_id.map(str::to_string);
+        }
+    }
+
+    fn active_mod(&self) -> Result<String, String> {
+        self.mod_id
+            .read()
+            .ok()
+            .and_then(|id| id.clone())
+            .ok_or_else(|| "No mod is running".to_string())
+    }
+
+    pub fn get(&self, key: &str) -> Result<Option<JsonValue>, String> {
+        let mod_id = self.active_mod()?;
+        Ok(self.read(&mod_id)?.remove(key))
+    }
+
+    pub fn set(&self, key: &str, value: JsonValue) -> Result<(), String> {
+        validate_key(key)?;
+        let mod_id = self.active_mod()?;
+        let mut entries = self.read(&mod_id)?;
+        entries.insert(key.to_string(), value);
+        self.write(&mod_id, &entries)
+    }
+
+    /// Returns whether the key existed.
+    pub fn delete(&self, key: &str) -> Result<bool, String> {
+        let mod_id = self.active_mod()?;
+        let mut entries = self.read(&mod_id)?;
+        if entries.remove(key).is_none() {
+            return Ok(false);
+        }
+        self.write(&mod_id, &entries)?;
+        Ok(true)
+    }
+
+    fn read(&self, mod_id: &str) -> Result<Entries, String> {
+        let Some(dir) = &self.dir else {
+            return Ok(self
+                .memory
+                .read()
+                .ok()
+                .and_then(|memory| memory.get(mod_id).cloned())
+                .unwrap_or_default());
+        };
+        match std::fs::read(storage_path(dir, mod_id)) {
+            Ok(bytes) => serde_json::from_slice(&bytes)
+                .map_err(|e| format!("Storage for mod '{}' is corrupt: {}", mod_id, e)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Entries::new()),
+            Err(e) => Err(format!("Failed to read storage: {}", e)),
+        }
+    }
+
+    fn write(&self, mod_id: &str, entries: &Entries) -> Result<(), String> {
+        let bytes = serde_json::to_vec(entries).map_err(|e| e.to_string())?;
+        if bytes.len() > MAX_STORAGE_BYTES {
+            return Err(format!(
+                "Storage for mod '{}' would exceed {} bytes",
+                mod_id, MAX_STORAGE_BYTES
+            ));
+        }
+        let Some(dir) = &self.dir else {
+            if let Ok(mut memory) = self.memory.write() {
+                memory.insert(mod_id.to_string(), entries.clone());
+            }
+            return Ok(());
+        };
+        std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create storage dir: {}", e))?;
+        let path = storage_path(dir, mod_id);
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, bytes).map_err(|e| format!("Failed to write storage: {}", e))?;
+        std::fs::rename(&tmp, &path).map_err(|e| format!("Failed to write storage: {}", e))
+    }
+}
+
+fn validate_key(key: &str) -> Result<(), String> {
+    if key.is_empty() || key.len() > MAX_KEY_LEN {
+        return Err(format!(
+            "Storage keys must be 1 to {} bytes long",
+            MAX_KEY_LEN
+        ));
+    }
+    Ok(())
+}
+
+/// Mod ids are directory names; anything outside `[A-Za-z0-9._-]` is replaced
+/// so the file always lands inside `dir`.
+fn storage_path(dir: &Path, mod_id: &str) -> PathBuf {
+    let name: String = mod_id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let name = name.trim_start_matches('.');
+    dir.join(format!("{}.json", if name.is_empty() { "_" } else { name }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    #[test]
+    fn values_persist_per_mod() {
+        let tmp = TempDir::new().unwrap();
+        let storage = ModStorage::new(Some(tmp.path().to_path_buf()));
+        assert!(storage.get("count").is_err());
+
+        storage.set_active_mod(Some("clock"));
+        storage.set("count", json!(3)).unwrap();
+        storage.set("theme", json!({ "dark": true })).unwrap();
+
+        let reopened = ModStorage::new(Some(tmp.path().to_path_buf()));
+        reopened.set_active_mod(Some("clock"));
+        assert_eq!(reopened.get("count").unwrap(), Some(json!(3)));
+        assert!(reopened.delete("count").unwrap());
+        assert!(!reopened.delete("count").unwrap());
+        assert_eq!(reopened.get("count").unwrap(), None);
+
+        reopened.set_active_mod(Some("other"));
+        assert_eq!(reopened.get("theme").unwrap(), None);
+    }
+
+    #[test]
+    fn keys_and_size_are_bounded() {
+        let storage = ModStorage::new(None);
+        storage.set_active_mod(Some("demo"));
+        assert!(storage.set("", json!(1)).is_err());
+        assert!(storage.set(&"k".repeat(MAX_KEY_LEN + 1), json!(1)).is_err());
+        assert!(storage
+            .set("blob", json!("x".repeat(MAX_STORAGE_BYTES)))
+            .is_err());
+        assert_eq!(storage.get("blob").unwrap(), None);
+    }
+
+    #[test]
+    fn storage_files_stay_inside_the_dir() {
+        let dir = Path::new("/data/mod_storage");
+        assert_eq!(
+            storage_path(dir, "../../etc/passwd"),
+            dir.join("_.._etc_passwd.json")
+        );
+        assert_eq!(storage_path(dir, "neon-clock"), dir.join("neon-clock.json"));
+    }
+}
@@ -0,0 +1,202 @@
+//! Timers for mod scripts: `setTimeout`, `setInterval` and `Kokoro.schedule`.
+//!
+//! The queue lives on the QuickJS thread and only tracks ids and due times;
+//! callbacks stay in the JS-side `__timerCallbacks` registry. The script
+//! thread's event loop waits for either the next command or the earliest due
+//! timer, then calls `__fireTimer(id, done)` for each one that is due.
+
+use crate::ai::scheduler::{CronRule, ScheduleRule};
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+/// Most timers a mod may have pending at once.
+pub const MAX_TIMERS: usize = 1000;
+/// Shortest `setInterval` period, so a zero delay cannot spin the thread.
+const MIN_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Timer queue shared by the API closures and the script thread's loop.
+pub type SharedTimers = Rc<RefCell<Timers>>;
+
+#[derive(Debug, Clone)]
+enum Repeat {
+    Once,
+    Every(Duration),
+    Cron(String),
+}
+
+#[derive(Debug, Clone)]
+struct Timer {
+    due: Instant,
+    repeat: Repeat,
+}
+
+#[derive(Debug, Default)]
+pub struct Timers {
+    next_id: u32,
+    entries: BTreeMap<u32, Timer>,
+}
+
+impl Timers {
+    /// `setTimeout` when `repeat` is false, `setInterval` otherwise.
+    pub fn add(&mut self, now: Instant, delay: Duration, repeat: bool) -> Result<u32, String> {
+        let repeat = if repeat {
+            Repeat::Every(delay.max(MIN_INTERVAL))
+        } else {
+            Repeat::Once
+        };
+        let due = match &repeat {
+            Repeat::Every(period) => now + *period,
+            _ => now + delay,
+        };
+        self.insert(Timer { due, repeat })
+    }
+
+    /// Fire at every local time matching a five-field cron rule.
+    pub fn add_cron(&mut self, now: Instant, expr: &str) -> Result<u32, String> {
+        CronRule::parse(expr).map_err(|e| e.to_string())?;
+        let due = next_cron_run(now, expr)
+            .ok_or_else(|| format!("cron rule '{}' never matches", expr))?;
+        self.insert(Timer {
+            due,
+            repeat: Repeat::Cron(expr.to_string()),
+        })
+    }
+
+    pub fn cancel(&mut self, id: u32) {
+        self.entries.remove(&id);
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    pub fn next_due(&self) -> Option<Instant> {
+        self.entries.values().map(|timer| timer.due).min()
+    }
+
+    /// Ids due at `now`, each paired with whether it is finished. Repeating
+    /// timers are rescheduled from `now`; one-shots are removed.
+    pub fn take_due(&mut self, now: Instant) -> Vec<(u32, bool)> {
+        let due: Vec<u32> = self
+            .entries
+            .iter()
+            .filter(|(_, timer)| timer.due <= now)
+            .map(|(id, _)| *id)
+            .collect();
+        due.into_iter()
+            .map(|id| {
+                let next = self.entries.get(&id).and_then(|timer| match &timer.repeat {
+                    Repeat::Once => None,
+                    Repeat::Every(period) => Some(now + *period),
+                    Repeat::Cron(expr) => next_cron_run(now, expr),
+                });
+                match (next, self.entries.get_mut(&id)) {
+                    (Some(due), Some(timer)) => {
+                        timer.due = due;
+                        (id, false)
+                    }
+                    _ => {
+                        self.entries.remove(&id);
+                        (id, true)
+                    }
+                }
+            })
+            .collect()
+    }
+
+    fn insert(&mut self, timer: Timer) -> Result<u32, String> {
+        if self.entries.len() >= MAX_TIMERS {
+            return Err(format!("A mod can have at most {} timers", MAX_TIMERS));
+        }
+        self.next_id = self.next_id.wrapping_add(1).max(1);
+        while self.entries.contains_key(&self.next_id) {
+            self.next_id = self.next_id.wrapping_add(1).max(1);
+        }
+        self.entries.insert(self.next_id, timer);
+        Ok(self.next_id)
+    }
+}
+
+/// Instant of the cron rule's next wall-clock match after `now`.
+fn next_cron_run(now: Instant, expr: &str) -> Option<Instant> {
+    let wall_now = chrono::Utc::now().timestamp();
+    let at = ScheduleRule::Cron {
+        expr: expr.to_string(),
+    }
+    .next_run_after(wall_now)?;
+    Some(now + Duration::from_secs(at.saturating_sub(wall_now).max(0) as u64))
+}
+
+/// Run the callbacks of every due timer. The queue is not borrowed while a
+/// callback runs, so callbacks may add or clear timers.
+pub fn fire_due(ctx: &rquickjs::Context, timers: &SharedTimers) {
+    let due = timers.borrow_mut().take_due(Instant::now());
+    if due.is_empty() {
+        return;
+    }
+    ctx.with(|ctx| {
+        for (id, done) in due {
+            let code = format!("globalThis.__fireTimer({}, {});", id, done);
+            if let Err(e) = ctx.eval::<(), _>(code.as_str()) {
+                tracing::warn!(target: "mods", "[ModManager] Timer {} failed: {}", id, e);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timeouts_fire_once_and_intervals_repeat() {
+        let start = Instant::now();
+        let mut timers = Timers::default();
+        let timeout = timers.add(start, Duration::from_millis(50), false).unwrap();
+        let interval = timers.add(start, Duration::from_millis(20), true).unwrap();
+        assert_ne!(timeout, interval);
+        assert_eq!(timers.next_due(), Some(start + Duration::from_millis(20)));
+
+        assert!(timers.take_due(start).is_empty());
+        let at_20 = start + Duration::from_millis(20);
+        assert_eq!(timers.take_due(at_20), vec![(interval, false)]);
+        assert_eq!(timers.next_due(), Some(at_20 + Duration::from_millis(20)));
+
+        let at_60 = start + Duration::from_millis(60);
+        assert_eq!(
+            timers.take_due(at_60),
+            vec![(timeout, true), (interval, false)]
+        );
+        timers.cancel(interval);
+        assert_eq!(timers.next_due(), None);
+    }
+
+    #[test]
+    fn zero_intervals_are_clamped_and_timers_are_capped() {
+        let start = Instant::now();
+        let mut timers = Timers::default();
+        timers.add(start, Duration::ZERO, true).unwrap();
+        assert_eq!(timers.next_due(), Some(start + MIN_INTERVAL));
+
+        timers.clear();
+        for _ in 0..MAX_TIMERS {
+            timers.add(start, Duration::from_secs(1), false).unwrap();
+        }
+        assert!(timers.add(start, Duration::from_secs(1), false).is_err());
+    }
+
+    #[test]
+    fn cron_timers_reschedule_within_a_minute() {
+        let start = Instant::now();
+        let mut timers = Timers::default();
+        assert!(timers.add_cron(start, "every minute").is_err());
+        let id = timers.add_cron(start, "* * * * *").unwrap();
+        let due = timers.next_due().unwrap();
+        assert!(due <= start + Duration::from_secs(61));
+
+        assert_eq!(timers.take_due(due), vec![(id, false)]);
+        assert!(timers.next_due().unwrap() > due);
+    }
+}
@@ -0,0 +1,195 @@
+//! Tools registered by mod scripts through `Kokoro.registerTool`.
+//!
+//! Each tool is a proxy `ActionHandler` in the `ActionRegistry`. Executing it
+//! sends `ScriptCommand::CallTool` to the QuickJS thread, which runs the JS
+//! handler and reports back through `ScriptEvent::ToolResult`; the relay
+//! thread then completes the pending call by id.
+
+use crate::actions::registry::{
+    ActionContext, ActionError, ActionHandler, ActionParam, ActionResult,
+};
+use crate::mods::manager::ScriptCommand;
+use async_trait::async_trait;
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
+
+/// How long a tool call waits for the script before giving up.
+const CALL_TIMEOUT: Duration = Duration::from_secs(30);
+/// Longest tool name a mod may register.
+const MAX_NAME_LEN: usize = 64;
+
+/// Tool calls waiting for their script handler to settle.
+#[derive(Default)]
+pub struct PendingToolCalls {
+    next_id: AtomicU32,
+    calls: Mutex<HashMap<u32, oneshot::Sender<Result<JsonValue, String>>>>,
+}
+
+impl PendingToolCalls {
+    fn register(&self) -> (u32, oneshot::Receiver<Result<JsonValue, String>>) {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed).wrapping_add(1);
+        let (tx, rx) = oneshot::channel();
+        if let Ok(mut calls) = self.calls.lock() {
+            calls.insert(id, tx);
+        }
+        (id, rx)
+    }
+
+    fn forget(&self, id: u32) {
+        if let Ok(mut calls) = self.calls.lock() {
+            calls.remove(&id);
+        }
+    }
+
+    /// Deliver a handler's result; unknown ids have already timed out.
+    pub fn settle(&self, id: u32, result: Result<JsonValue, String>) {
+        let tx = self
+            .calls
+            .lock()
+            .ok()
+            .and_then(|mut calls| calls.remove(&id));
+        if let Some(tx) = tx {
+            let _ = tx.send(result);
+        }
+    }
+
+    /// Fail every outstanding call (mod unloaded).
+    pub fn cancel_all(&self) {
+        if let Ok(mut calls) = self.calls.lock() {
+            for (_, tx) in calls.drain() {
+                let _ = tx.send(Err("The mod providing this tool was unloaded".to_string()));
+            }
+        }
+    }
+}
+
+/// Reject names the LLM could not call reliably.
+pub fn validate_tool_name(name: &str) -> Result<(), String> {
+    if name.is_empty() || name.len() > MAX_NAME_LEN {
+        return Err(format!(
+            "Tool name must be 1-{} characters long",
+            MAX_NAME_LEN
+        ));
+    }
+    if !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return Err(format!(
+            "Tool name '{}' may only contain letters, digits and '_'",
+            name
+        ));
+    }
+    Ok(())
+}
+
+/// An ActionHandler that runs a mod script's tool handler.
+pub struct ModToolHandler {
+    /// The tool name passed to `Kokoro.registerTool`.
+    pub tool_name: String,
+    /// Human-readable description for the LLM prompt.
+    pub description: String,
+    /// JSON Schema for the tool input.
+    pub input_schema: JsonValue,
+    pub script_tx: mpsc::Sender<ScriptCommand>,
+    pub pending: Arc<PendingToolCalls>,
+}
+
+#[async_trait]
+impl ActionHandler for ModToolHandler {
+    fn name(&self) -> &str {
+        &self.tool_name
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn parameters(&self) -> Vec<ActionParam> {
+        ActionParam::from_json_schema(&self.input_schema)
+    }
+
+    fn needs_feedback(&self) -> bool {
+        true
+    }
+
+    async fn execute(
+        &self,
+        args: HashMap<String, String>,
+        _ctx: ActionContext,
+    ) -> Result<ActionResult, ActionError> {
+        // Same conversion as MCP tools: JSON-looking values keep their type.
+        let args = JsonValue::Object(
+            args.into_iter()
+                .map(|(k, v)| {
+                    let val = serde_json::from_str(&v).unwrap_or(JsonValue::String(v));
+                    (k, val)
+                })
+                .collect(),
+        );
+
+        let (id, rx) = self.pending.register();
+        let sent = self
+            .script_tx
+            .send(ScriptCommand::CallTool {
+                id,
+                name: self.tool_name.clone(),
+                args,
+            })
+            .await;
+        if sent.is_err() {
+            self.pending.forget(id);
+            return Err(ActionError("Mod script runtime is not running".to_string()));
+        }
+
+        let result = match tokio::time::timeout(CALL_TIMEOUT, rx).await {
+            Ok(Ok(result)) => result,
+            Ok(Err(_)) => Err("The mod dropped the tool call".to_string()),
+            Err(_) => {
+                self.pending.forget(id);
+                Err(format!(
+                    "Tool '{}' did not respond within {}s",
+                    self.tool_name,
+                    CALL_TIMEOUT.as_secs()
+                ))
+            }
+        };
+
+        Ok(match result {
+            Ok(JsonValue::String(text)) => ActionResult::ok(text),
+            Ok(JsonValue::Null) => ActionResult::ok("Done"),
+            Ok(value) => ActionResult::ok_with_data(value.to_string(), value),
+            Err(e) => ActionResult::err(e),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tool_names_are_identifier_like() {
+        assert!(validate_tool_name("get_weather").is_ok());
+        assert!(validate_tool_name("").is_err());
+        assert!(validate_tool_name("get weather").is_err());
+        assert!(validate_tool_name("mod__x").is_ok());
+        assert!(validate_tool_name(&"a".repeat(MAX_NAME_LEN + 1)).is_err());
+    }
+
+    #[test]
+    fn pending_calls_settle_once_by_id() {
+        let pending = PendingToolCalls::default();
+        let (first, mut first_rx) = pending.register();
+        let (second, mut second_rx) = pending.register();
+        assert_ne!(first, second);
+
+        pending.settle(second, Ok(serde_json::json!(3)));
+        pending.settle(second, Ok(serde_json::json!(4)));
+        assert_eq!(second_rx.try_recv().unwrap(), Ok(serde_json::json!(3)));
+
+        pending.cancel_all();
+        assert!(first_rx.try_recv().unwrap().is_err());
+    }
+}
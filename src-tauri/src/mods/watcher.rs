@@ -0,0 +1,181 @@
+//! Hot reload for mod development.
+//!
+//! Polls the running mod's directory and, once a change has settled, reloads
+//! the mod through `ModManager::reload_active_mod` and emits `mod:reloaded`.
+//! Polling keeps this dependency-free; a mod directory is small enough that
+//! reading its metadata once a second is cheap.
+
+use crate::mods::ModManager;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tauri::{Emitter, Manager};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// Editors often write a file in several steps; wait for them to finish.
+const SETTLE_DELAY: Duration = Duration::from_millis(300);
+/// Files beyond this are not watched, so a stray `node_modules` stays cheap.
+const MAX_FILES: usize = 2000;
+
+/// Modification time and size of every file under a mod directory, keyed by
+/// path relative to it.
+pub type Snapshot = BTreeMap<PathBuf, (Option<SystemTime>, u64)>;
+
+/// Payload of `mod:reloaded`.
+#[derive(Debug, Clone, Serialize)]
+pub struct ModReloadedEvent {
+    pub mod_id: String,
+    /// Files that changed, relative to the mod directory.
+    pub changed: Vec<String>,
+    /// Set when the reload failed and the previous version keeps running.
+    pub error: Option<String>,
+}
+
+pub fn snapshot(dir: &Path) -> Snapshot {
+    let mut files = Snapshot::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(current) = pending.pop() {
+        let Ok(entries) = fs::read_dir(&current) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            if entry.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
+            let Ok(meta) = entry.metadata() else {
+                continue;
+            };
+            let path = entry.path();
+            if meta.is_dir() {
+                pending.push(path);
+            } else if files.len() < MAX_FILES {
+                let relative = path.strip_prefix(dir).unwrap_or(&path).to_path_buf();
+                files.insert(relative, (meta.modified().ok(), meta.len()));
+            }
+        }
+    }
+    files
+}
+
+/// Files added, removed or modified between two snapshots.
+pub fn changed_files(before: &Snapshot, after: &Snapshot) -> Vec<String> {
+    let mut changed: Vec<String> = after
+        .iter()
+        .filter(|(path, stamp)| before.get(*path) != Some(stamp))
+        .map(|(path, _)| path.to_string_lossy().replace('\\', "/"))
+        .chain(
+            before
+                .keys()
+                .filter(|path| !after.contains_key(*path))
+                .map(|path| path.to_string_lossy().replace('\\', "/")),
+        )
+        .collect();
+    changed.sort();
+    changed
+}
+
+async fn snapshot_async(dir: PathBuf) -> Snapshot {
+    tokio::task::spawn_blocking(move || snapshot(&dir))
+        .await
+        .unwrap_or_default()
+}
+
+/// Watch whichever mod is running for as long as the app lives.
+pub fn spawn<R: tauri::Runtime>(app: tauri::AppHandle<R>) {
+    tauri::async_runtime::spawn(async move {
+        let mut watched: Option<(String, Snapshot)> = None;
+        loop {
+            tokio::time::sleep(POLL_INTERVAL).await;
+            let Some(manager) = app.try_state::<tokio::sync::Mutex<ModManager>>() else {
+                continue;
+            };
+            let (mod_id, dir) = {
+                let manager = manager.lock().await;
+                match manager.active_mod_id() {
+                    Some(id) => {
+                        let dir = manager.mods_path.join(&id);
+                        (id, dir)
+                    }
+                    None => {
+                        watched = None;
+                        continue;
+                    }
+                }
+            };
+
+            let current = snapshot_async(dir.clone()).await;
+            let previous = match watched.take() {
+                Some((id, previous)) if id == mod_id => previous,
+                _ => {
+                    watched = Some((mod_id, current));
+                    continue;
+                }
+            };
+            if changed_files(&previous, &current).is_empty() {
+                watched = Some((mod_id, current));
+                continue;
+            }
+
+            tokio::time::sleep(SETTLE_DELAY).await;
+            let settled = snapshot_async(dir).await;
+            let changed = changed_files(&previous, &settled);
+            watched = Some((mod_id.clone(), settled));
+
+            let mut manager = manager.lock().await;
+            if manager.active_mod_id().as_deref() != Some(mod_id.as_str()) {
+                continue;
+            }
+            tracing::info!(
+                target: "mods",
+                "[ModManager] Reloading mod '{}' after changes to {:?}",
+                mod_id,
+                changed
+            );
+            let error = manager.reload_active_mod(&app).await.err();
+            if let Some(e) = &error {
+                tracing::error!(target: "mods", "[ModManager] Reload of mod '{}' failed: {}", mod_id, e);
+            }
+            let _ = app.emit(
+                "mod:reloaded",
+                ModReloadedEvent {
+                    mod_id,
+                    changed,
+                    error,
+                },
+            );
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn snapshots_report_added_modified_and_removed_files() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+        fs::create_dir_all(dir.join("scripts")).unwrap();
+        fs::create_dir_all(dir.join(".git")).unwrap();
+        fs::write(dir.join("mod.json"), "{}").unwrap();
+        fs::write(dir.join("scripts/main.js"), "1").unwrap();
+        fs::write(dir.join(".git/HEAD"), "ref").unwrap();
+
+        let before = snapshot(dir);
+        assert_eq!(before.len(), 2);
+        assert!(changed_files(&before, &snapshot(dir)).is_empty());
+
+        fs::write(dir.join("scripts/main.js"), "12").unwrap();
+        fs::remove_file(dir.join("mod.json")).unwrap();
+        fs::write(dir.join("theme.json"), "{}").unwrap();
+        fs::write(dir.join(".git/HEAD"), "other").unwrap();
+
+        assert_eq!(
+            changed_files(&before, &snapshot(dir)),
+            ["mod.json", "scripts/main.js", "theme.json"]
+        );
+    }
+}
@@ -0,0 +1,475 @@
+//! Offline mode — central connectivity state and graceful provider degradation.
+//!
+//! The heartbeat's network probe feeds [`apply_network_state`]. When the
+//! connection drops, LLM / TTS / STT are pinned to the configured local
+//! fallbacks (e.g. Ollama, Piper, whisper.cpp) and cloud-only features are
+//! queued until connectivity returns.
+
+use crate::error::KokoroError;
+use crate::imagegen::ImageGenService;
+use crate::llm::service::LlmService;
+use crate::stt::SttService;
+use crate::telegram::TelegramService;
+use crate::tts::TtsService;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::RwLock;
+
+/// Oldest deferred tasks are dropped once the queue reaches this size.
+const MAX_DEFERRED_TASKS: usize = 50;
+/// Failed replays before a deferred task is given up on.
+const MAX_REPLAY_ATTEMPTS: u32 = 3;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct OfflineModeConfig {
+    /// Whether losing connectivity switches the engine into offline mode.
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// LLM provider id to route to while offline (e.g. an Ollama entry).
+    #[serde(default)]
+    pub llm_fallback_provider: Option<String>,
+    /// TTS provider id to route to while offline (e.g. a local VITS / Piper entry).
+    #[serde(default)]
+    pub tts_fallback_provider: Option<String>,
+    /// STT provider id to route to while offline (e.g. whisper.cpp).
+    #[serde(default)]
+    pub stt_fallback_provider: Option<String>,
+    /// Tell whitelisted Telegram chats when the engine comes back online.
+    #[serde(default = "default_true")]
+    pub notify_telegram: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for OfflineModeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            llm_fallback_provider: None,
+            tts_fallback_provider: None,
+            stt_fallback_provider: None,
+            notify_telegram: true,
+        }
+    }
+}
+
+pub fn config_path() -> PathBuf {
+    dirs_next::data_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("com.chyin.kokoro")
+        .join("offline_mode_config.json")
+}
+
+pub fn load_config(path: &Path) -> OfflineModeConfig {
+    crate::config::load_json_config(path, "OFFLINE")
+}
+
+pub fn save_config(path: &Path, config: &OfflineModeConfig) -> Result<(), KokoroError> {
+    crate::config::save_json_config(path, config, "OFFLINE")
+}
+
+/// A cloud-only request parked while offline and replayed by
+/// [`replay_deferred`] once connectivity returns.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DeferredTask {
+    pub id: String,
+    /// Feature key, e.g. `"imagegen"`.
+    pub kind: String,
+    pub payload: serde_json::Value,
+    pub queued_at: i64,
+    /// Replays that failed so far.
+    #[serde(default)]
+    pub attempts: u32,
+}
+
+/// Payload of the `offline-mode-changed` event and `get_offline_status`.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct OfflineStatus {
+    pub offline: bool,
+    /// Unix timestamp when offline mode was entered.
+    pub since: Option<i64>,
+    pub llm_fallback_provider: Option<String>,
+    pub tts_fallback_provider: Option<String>,
+    pub stt_fallback_provider: Option<String>,
+    pub queued_tasks: usize,
+}
+
+/// What a network observation means for the offline state machine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Transition {
+    None,
+    EnterOffline,
+    ExitOffline,
+}
+
+fn transition_for(enabled: bool, currently_offline: bool, network_online: bool) -> Transition {
+    match (currently_offline, network_online) {
+        (false, false) if enabled => Transition::EnterOffline,
+        (true, true) => Transition::ExitOffline,
+        // Disabling the feature while offline releases the fallbacks immediately.
+        (true, false) if !enabled => Transition::ExitOffline,
+        _ => Transition::None,
+    }
+}
+
+fn push_bounded(queue: &mut VecDeque<DeferredTask>, task: DeferredTask) {
+    while queue.len() >= MAX_DEFERRED_TASKS {
+        if let Some(dropped) = queue.pop_front() {
+            tracing::warn!(
+                target: "offline",
+                "Deferred task queue full; dropping oldest {} task {}",
+                dropped.kind,
+                dropped.id
+            );
+        }
+    }
+    queue.push_back(task);
+}
+
+/// Managed Tauri state holding the offline-mode config, state and deferred queue.
+#[derive(Clone)]
+pub struct OfflineModeService {
+    config: Arc<RwLock<OfflineModeConfig>>,
+    offline_since: Arc<RwLock<Option<i64>>>,
+    queue: Arc<RwLock<VecDeque<DeferredTask>>>,
+}
+
+impl OfflineModeService {
+    pub fn new(config: OfflineModeConfig) -> Self {
+        Self {
+            config: Arc::new(RwLock::new(config)),
+            offline_since: Arc::new(RwLock::new(None)),
+            queue: Arc::new(RwLock::new(VecDeque::new())),
+        }
+    }
+
+    pub async fn get_config(&self) -> OfflineModeConfig {
+        self.config.read().await.clone()
+    }
+
+    /// Update the in-memory config (caller is responsible for persisting to disk).
+    pub async fn update_config(&self, config: OfflineModeConfig) {
+        *self.config.write().await = config;
+    }
+
+    pub async fn is_offline(&self) -> bool {
+        self.offline_since.read().await.is_some()
+    }
+
+    pub async fn status(&self) -> OfflineStatus {
+        let config = self.config.read().await.clone();
+        OfflineStatus {
+            offline: self.is_offline().await,
+            since: *self.offline_since.read().await,
+            llm_fallback_provider: config.llm_fallback_provider,
+            tts_fallback_provider: config.tts_fallback_provider,
+            stt_fallback_provider: config.stt_fallback_provider,
+            queued_tasks: self.queue.read().await.len(),
+        }
+    }
+
+    /// Park a cloud-only request until connectivity returns.
+    pub async fn defer(&self, kind: &str, payload: serde_json::Value) -> DeferredTask {
+        let task = DeferredTask {
+            id: uuid::Uuid::new_v4().to_string(),
+            kind: kind.to_string(),
+            payload,
+            queued_at: chrono::Utc::now().timestamp(),
+            attempts: 0,
+        };
+        push_bounded(&mut *self.queue.write().await, task.clone());
+        tracing::info!(target: "offline", "Deferred {} task {} until back online", kind, task.id);
+        task
+    }
+
+    async fn drain_queue(&self) -> Vec<DeferredTask> {
+        self.queue.write().await.drain(..).collect()
+    }
+
+    /// Put tasks taken for replay back in front of any deferred since.
+    async fn requeue(&self, tasks: Vec<DeferredTask>) {
+        if tasks.is_empty() {
+            return;
+        }
+        let mut queue = self.queue.write().await;
+        let newer: Vec<DeferredTask> = queue.drain(..).collect();
+        for task in tasks.into_iter().chain(newer) {
+            push_bounded(&mut queue, task);
+        }
+    }
+}
+
+/// Push the configured fallbacks (or `None` to restore normal routing) into each service.
+async fn apply_fallbacks(app: &AppHandle, config: Option<&OfflineModeConfig>) {
+    // The default config carries no fallbacks, which clears every override.
+    let config = config.cloned().unwrap_or_default();
+    if let Some(llm) = app.try_state::<LlmService>() {
+        llm.set_offline_fallback(config.llm_fallback_provider).await;
+    }
+    if let Some(tts) = app.try_state::<TtsService>() {
+        tts.set_offline_fallback(config.tts_fallback_provider).await;
+    }
+    if let Some(stt) = app.try_state::<SttService>() {
+        stt.set_offline_fallback(config.stt_fallback_provider).await;
+    }
+}
+
+/// Replay one deferred task. `Ok(false)` means it still has to wait.
+async fn resume_task(app: &AppHandle, task: DeferredTask) -> Result<bool, KokoroError> {
+    match task.kind.as_str() {
+        "imagegen" => {
+            let provider_id = task.payload["provider_id"].as_str();
+            if should_defer_imagegen(app, provider_id).await {
+                return Ok(false);
+            }
+            crate::commands::imagegen::replay_deferred_image(app, task.payload).await?;
+            Ok(true)
+        }
+        kind => Err(KokoroError::Internal(format!(
+            "no handler for deferred {} tasks",
+            kind
+        ))),
+    }
+}
+
+/// Replay deferred tasks one at a time, oldest first, through `run`. Going
+/// offline again stops the replay; the tasks not run yet and the ones that
+/// failed (up to [`MAX_REPLAY_ATTEMPTS`] times) go back in the queue.
+/// Returns how many succeeded.
+async fn replay_deferred<F, Fut>(
+    service: &OfflineModeService,
+    tasks: Vec<DeferredTask>,
+    mut run: F,
+) -> usize
+where
+    F: FnMut(DeferredTask) -> Fut,
+    Fut: std::future::Future<Output = Result<bool, KokoroError>>,
+{
+    let mut resumed = 0;
+    let mut requeue = Vec::new();
+    let mut tasks = tasks.into_iter();
+    while let Some(mut task) = tasks.next() {
+        if service.is_offline().await {
+            requeue.push(task);
+            requeue.extend(tasks);
+            break;
+        }
+        match run(task.clone()).await {
+            Ok(true) => resumed += 1,
+            Ok(false) => requeue.push(task),
+            Err(e) => {
+                task.attempts += 1;
+                if task.attempts < MAX_REPLAY_ATTEMPTS {
+                    tracing::warn!(target: "offline", "Deferred {} task {} failed, will retry: {}", task.kind, task.id, e);
+                    requeue.push(task);
+                } else {
+                    tracing::warn!(target: "offline", "Deferred {} task {} failed {} times, dropping it: {}", task.kind, task.id, task.attempts, e);
+                }
+            }
+        }
+    }
+    service.requeue(requeue).await;
+    resumed
+}
+
+/// Feed a network observation into the offline state machine.
+/// Emits `offline-mode-changed` on every transition and replays the deferred
+/// tasks in the background once connectivity returns.
+pub async fn apply_network_state(app: &AppHandle, network_online: bool) {
+    let Some(service) = app.try_state::<OfflineModeService>() else {
+        return;
+    };
+    let config = service.get_config().await;
+    let currently_offline = service.is_offline().await;
+
+    match transition_for(config.enabled, currently_offline, network_online) {
+        Transition::None => {}
+        Transition::EnterOffline => {
+            *service.offline_since.write().await = Some(chrono::Utc::now().timestamp());
+            apply_fallbacks(app, Some(&config)).await;
+            tracing::warn!(target: "offline", "Connectivity lost; switched to local fallbacks");
+            let _ = app.emit("offline-mode-changed", service.status().await);
+        }
+        Transition::ExitOffline => {
+            let since = service.offline_since.write().await.take();
+            apply_fallbacks(app, None).await;
+            let tasks = service.drain_queue().await;
+            tracing::info!(
+                target: "offline",
+                "Connectivity restored; released fallbacks, resuming {} deferred task(s)",
+                tasks.len()
+            );
+            let _ = app.emit("offline-mode-changed", service.status().await);
+
+            let notify = config.notify_telegram && network_online;
+            let app = app.clone();
+            let service = service.inner().clone();
+            tauri::async_runtime::spawn(async move {
+                let total = tasks.len();
+                let resumed =
+                    replay_deferred(&service, tasks, |task| resume_task(&app, task)).await;
+                if total > 0 {
+                    tracing::info!(target: "offline", "Resumed {}/{} deferred task(s)", resumed, total);
+                }
+                if !notify {
+                    return;
+                }
+                if let Some(telegram) = app.try_state::<TelegramService>() {
+                    let minutes = since
+                        .map(|ts| (chrono::Utc::now().timestamp() - ts).max(0) / 60)
+                        .unwrap_or(0);
+                    telegram
+                        .broadcast(&format!(
+                            "Back online after ~{} min offline. {} queued request(s) resumed.",
+                            minutes, resumed
+                        ))
+                        .await;
+                }
+            });
+        }
+    }
+}
+
+/// Re-apply fallbacks after a config change so an ongoing offline period picks it up.
+pub async fn refresh_fallbacks(app: &AppHandle) {
+    let Some(service) = app.try_state::<OfflineModeService>() else {
+        return;
+    };
+    if service.is_offline().await {
+        let config = service.get_config().await;
+        apply_fallbacks(app, Some(&config)).await;
+    }
+}
+
+/// Whether an image generation request must be deferred (offline + cloud provider).
+pub async fn should_defer_imagegen(app: &AppHandle, provider_id: Option<&str>) -> bool {
+    let Some(service) = app.try_state::<OfflineModeService>() else {
+        return false;
+    };
+    if !service.is_offline().await {
+        return false;
+    }
+    match app.try_state::<ImageGenService>() {
+        Some(imagegen) => !imagegen.is_local_provider(provider_id).await,
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(id: &str) -> DeferredTask {
+        DeferredTask {
+            id: id.to_string(),
+            kind: "imagegen".to_string(),
+            payload: serde_json::Value::Null,
+            queued_at: 0,
+            attempts: 0,
+        }
+    }
+
+    async fn queued(service: &OfflineModeService) -> Vec<(String, u32)> {
+        let queue = service.queue.read().await;
+        queue.iter().map(|t| (t.id.clone(), t.attempts)).collect()
+    }
+
+    #[test]
+    fn enters_offline_only_when_enabled() {
+        assert_eq!(transition_for(true, false, false), Transition::EnterOffline);
+        assert_eq!(transition_for(false, false, false), Transition::None);
+        assert_eq!(transition_for(true, false, true), Transition::None);
+    }
+
+    #[test]
+    fn exits_offline_on_reconnect_or_disable() {
+        assert_eq!(transition_for(true, true, true), Transition::ExitOffline);
+        assert_eq!(transition_for(false, true, false), Transition::ExitOffline);
+        assert_eq!(transition_for(true, true, false), Transition::None);
+    }
+
+    #[test]
+    fn deferred_queue_drops_oldest_when_full() {
+        let mut queue = VecDeque::new();
+        for i in 0..=MAX_DEFERRED_TASKS {
+            push_bounded(&mut queue, task(&i.to_string()));
+        }
+        assert_eq!(queue.len(), MAX_DEFERRED_TASKS);
+        assert_eq!(queue.front().map(|t| t.id.as_str()), Some("1"));
+    }
+
+    #[tokio::test]
+    async fn replay_waits_while_offline_and_requeues_failures() {
+        let service = OfflineModeService::new(OfflineModeConfig::default());
+        *service.offline_since.write().await = Some(0);
+        let tasks = vec![task("a"), task("b")];
+        let resumed = replay_deferred(&service, tasks, |_| async {
+            panic!("nothing replays while offline")
+        })
+        .await;
+        assert_eq!(resumed, 0);
+        assert_eq!(queued(&service).await, [("a".into(), 0), ("b".into(), 0)]);
+
+        // Back online: "a" runs, "b" fails and is kept, "c" is still waiting
+        // on its provider; "d" was deferred while the replay ran.
+        *service.offline_since.write().await = None;
+        let mut tasks = service.drain_queue().await;
+        tasks.push(task("c"));
+        service.defer("imagegen", serde_json::Value::Null).await;
+        let resumed = replay_deferred(&service, tasks, |task| async move {
+            match task.id.as_str() {
+                "a" => Ok(true),
+                "b" => Err(KokoroError::ExternalService("503".to_string())),
+                _ => Ok(false),
+            }
+        })
+        .await;
+        assert_eq!(resumed, 1);
+        let queue = queued(&service).await;
+        assert_eq!(queue[..2], [("b".into(), 1), ("c".into(), 0)]);
+        assert_eq!(queue.len(), 3);
+
+        // Connectivity drops again mid-replay: the rest stays queued.
+        let tasks = service.drain_queue().await;
+        let offline = service.clone();
+        let resumed = replay_deferred(&service, tasks, move |_| {
+            let offline = offline.clone();
+            async move {
+                *offline.offline_since.write().await = Some(1);
+                Err(KokoroError::ExternalService("timeout".to_string()))
+            }
+        })
+        .await;
+        assert_eq!(resumed, 0);
+        let queue = queued(&service).await;
+        assert_eq!(queue.len(), 3);
+        assert_eq!(queue[0], ("b".into(), 2));
+        assert_eq!(queue[1], ("c".into(), 0));
+    }
+
+    #[tokio::test]
+    async fn tasks_failing_too_often_are_dropped() {
+        let service = OfflineModeService::new(OfflineModeConfig::default());
+        let mut failing = task("x");
+        failing.attempts = MAX_REPLAY_ATTEMPTS - 1;
+        let resumed = replay_deferred(&service, vec![failing], |_| async {
+            Err(KokoroError::ExternalService("400".to_string()))
+        })
+        .await;
+        assert_eq!(resumed, 0);
+        assert!(queued(&service).await.is_empty());
+    }
+
+    #[test]
+    fn config_defaults_enable_offline_mode() {
+        let config: OfflineModeConfig = serde_json::from_str("{}").unwrap();
+        assert_eq!(config, OfflineModeConfig::default());
+        assert!(config.enabled);
+        assert!(config.llm_fallback_provider.is_none());
+    }
+}
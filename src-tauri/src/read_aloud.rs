@@ -0,0 +1,251 @@
+//! Read-aloud — speak the selected text on a hotkey.
+//!
+//! Runs as the `read_aloud` action of an input mapping. The text comes from
+//! the primary selection on Linux and from the clipboard elsewhere, so on
+//! Windows and macOS the user copies it first. Long text can be summarized by
+//! the system LLM before it is spoken in the character's voice, and the
+//! character can add a short comment of its own once playback ends.
+
+use crate::error::KokoroError;
+use crate::llm::messages::{system_message, user_text_message};
+use crate::llm::service::LlmService;
+use crate::tts::queue::TtsPriority;
+use crate::tts::TtsService;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+pub const READ_ALOUD_STARTED_EVENT: &str = "read-aloud:started";
+/// Asks the chat panel for a hidden turn once the read-aloud has finished playing.
+pub const READ_ALOUD_COMMENT_EVENT: &str = "read-aloud:comment";
+const SPEECH_SOURCE: &str = "read_aloud";
+/// Longer text is summarized first when the mapping asks for it.
+const SUMMARIZE_OVER_CHARS: usize = 600;
+const MAX_SPOKEN_CHARS: usize = 3000;
+/// Input sent to the summarizer.
+const MAX_SUMMARY_INPUT_CHARS: usize = 12000;
+const COMMENT_EXCERPT_CHARS: usize = 400;
+
+const SUMMARY_PROMPT: &str =
+    "Summarize the text the user sends in a few short sentences so it can be read aloud. \
+     Keep the text's language. Reply with the summary only, without an introduction.";
+
+#[derive(Debug, Clone, Serialize)]
+struct ReadAloudStarted {
+    chars: usize,
+    summarized: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct ReadAloudComment {
+    instruction: String,
+}
+
+/// Grab the selection (or clipboard), then speak it. Returns once speech is
+/// queued; playback and the optional comment follow in the background.
+pub async fn read_aloud(
+    app: &AppHandle,
+    summarize: bool,
+    comment: bool,
+) -> Result<(), KokoroError> {
+    let raw = tokio::task::spawn_blocking(grab_text)
+        .await
+        .map_err(|e| KokoroError::Internal(e.to_string()))?;
+    let text = raw.as_deref().and_then(clean_text).ok_or_else(|| {
+        KokoroError::Validation("Nothing selected or copied to read aloud".to_string())
+    })?;
+
+    let mut summarized = false;
+    let spoken = if summarize && text.chars().count() > SUMMARIZE_OVER_CHARS {
+        match summarize_text(app, &text).await {
+            Ok(summary) => {
+                summarized = true;
+                summary
+            }
+            Err(e) => {
+                tracing::warn!(target: "read_aloud", "[ReadAloud] Summary failed, reading the start instead: {}", e);
+                truncate_at_sentence(&text, MAX_SPOKEN_CHARS)
+            }
+        }
+    } else {
+        truncate_at_sentence(&text, MAX_SPOKEN_CHARS)
+    };
+
+    let tts = app
+        .try_state::<TtsService>()
+        .ok_or_else(|| KokoroError::Internal("TTS service not available".to_string()))?
+        .inner()
+        .clone();
+    tracing::info!(
+        target: "read_aloud",
+        "[ReadAloud] Reading {} chars (summarized={})",
+        spoken.chars().count(),
+        summarized
+    );
+    let _ = app.emit(
+        READ_ALOUD_STARTED_EVENT,
+        ReadAloudStarted {
+            chars: text.chars().count(),
+            summarized,
+        },
+    );
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = tts
+            .speak(
+                app.clone(),
+                spoken,
+                None,
+                None,
+                TtsPriority::High,
+                Some(SPEECH_SOURCE.to_string()),
+            )
+            .await
+        {
+            tracing::warn!(target: "read_aloud", "[ReadAloud] Speech failed: {}", e);
+            return;
+        }
+        if comment {
+            let _ = app.emit(
+                READ_ALOUD_COMMENT_EVENT,
+                ReadAloudComment {
+                    instruction: comment_instruction(&text, summarized),
+                },
+            );
+        }
+    });
+    Ok(())
+}
+
+async fn summarize_text(app: &AppHandle, text: &str) -> Result<String, KokoroError> {
+    let llm = app
+        .try_state::<LlmService>()
+        .ok_or_else(|| KokoroError::Internal("LLM service not available".to_string()))?;
+    let provider = llm.system_provider().await;
+    let input: String = text.chars().take(MAX_SUMMARY_INPUT_CHARS).collect();
+    let summary = provider
+        .chat(
+            vec![system_message(SUMMARY_PROMPT), user_text_message(input)],
+            None,
+        )
+        .await
+        .map_err(KokoroError::Llm)?;
+    clean_text(&summary).ok_or_else(|| KokoroError::Llm("Empty summary".to_string()))
+}
+
+fn comment_instruction(text: &str, summarized: bool) -> String {
+    let excerpt = truncate_at_sentence(text, COMMENT_EXCERPT_CHARS);
+    format!(
+        "The user selected some text and had you read it aloud{}. It begins: \"{}\". \
+         Now react to it in one or two sentences of your own, in character. Don't read it again.",
+        if summarized { " as a summary" } else { "" },
+        excerpt
+    )
+}
+
+/// Collapse whitespace and line breaks; `None` when nothing is left.
+pub(crate) fn clean_text(raw: &str) -> Option<String> {
+    let text = raw.split_whitespace().collect::<Vec<_>>().join(" ");
+    (!text.is_empty()).then_some(text)
+}
+
+/// At most `max` chars, cut after the last sentence end in the second half of
+/// the window when there is one.
+pub(crate) fn truncate_at_sentence(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text.to_string();
+    }
+    let window: String = text.chars().take(max).collect();
+    let cut = window
+        .char_indices()
+        .filter(|(_, c)| matches!(c, '.' | '!' | '?' | '。' | '！' | '？'))
+        .map(|(i, c)| i + c.len_utf8())
+        .filter(|end| *end >= window.len() / 2)
+        .last();
+    match cut {
+        Some(end) => window[..end].to_string(),
+        None => format!("{}…", window.trim_end()),
+    }
+}
+
+/// The primary selection, falling back to the clipboard.
+#[cfg(target_os = "linux")]
+fn grab_text() -> Option<String> {
+    const READERS: &[(&str, &[&str])] = &[
+        ("wl-paste", &["--primary", "--no-newline"]),
+        ("xclip", &["-o", "-selection", "primary"]),
+        ("xsel", &["--primary", "--output"]),
+        ("wl-paste", &["--no-newline"]),
+        ("xclip", &["-o", "-selection", "clipboard"]),
+        ("xsel", &["--clipboard", "--output"]),
+    ];
+    READERS.iter().find_map(|(program, args)| {
+        std::process::Command::new(program)
+            .args(*args)
+            .output()
+            .ok()
+            .filter(|out| out.status.success())
+            .map(|out| String::from_utf8_lossy(&out.stdout).into_owned())
+            .filter(|text| !text.trim().is_empty())
+    })
+}
+
+#[cfg(target_os = "macos")]
+fn grab_text() -> Option<String> {
+    std::process::Command::new("pbpaste")
+        .output()
+        .ok()
+        .filter(|out| out.status.success())
+        .map(|out| String::from_utf8_lossy(&out.stdout).into_owned())
+}
+
+#[cfg(target_os = "windows")]
+fn grab_text() -> Option<String> {
+    use std::os::windows::process::CommandExt;
+
+    std::process::Command::new("powershell")
+        .args([
+            "-NoProfile",
+            "-Command",
+            "[Console]::OutputEncoding = [Text.Encoding]::UTF8; Get-Clipboard -Raw",
+        ])
+        // CREATE_NO_WINDOW: avoid flashing a console window on every read
+        .creation_flags(0x08000000)
+        .output()
+        .ok()
+        .filter(|out| out.status.success())
+        .map(|out| String::from_utf8_lossy(&out.stdout).into_owned())
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn grab_text() -> Option<String> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clean_text_collapses_whitespace() {
+        assert_eq!(
+            clean_text("  Hello,\r\n\n  world\t!  ").as_deref(),
+            Some("Hello, world !")
+        );
+        assert_eq!(clean_text(" \n\t "), None);
+    }
+
+    #[test]
+    fn truncation_prefers_sentence_ends() {
+        assert_eq!(truncate_at_sentence("Short.", 100), "Short.");
+        assert_eq!(
+            truncate_at_sentence("One two three. Four five six seven", 20),
+            "One two three."
+        );
+        assert_eq!(truncate_at_sentence("abcdefghij", 4), "abcd…");
+        assert_eq!(
+            truncate_at_sentence("今日は晴れです。明日は雨でしょう", 10),
+            "今日は晴れです。"
+        );
+    }
+}
@@ -7,7 +7,8 @@ use std::path::Path;
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SttProviderConfig {
     pub id: String,
-    /// "openai_whisper", "whisper_cpp", "faster_whisper", "local_whisper", "sensevoice_local"
+    /// "openai_whisper", "whisper_cpp", "faster_whisper", "local_whisper", "sensevoice_local",
+    /// "whisper_local"
     pub provider_type: String,
     #[serde(default = "default_true")]
     pub enabled: bool,
@@ -18,7 +19,7 @@ pub struct SttProviderConfig {
     pub api_key_env: Option<String>,
     /// Base URL for the API
     pub base_url: Option<String>,
-    /// Model name (e.g., "whisper-1")
+    /// Model name (e.g., "whisper-1"; for whisper_local a catalog id like "base")
     pub model: Option<String>,
 
     // ── sensevoice_local fields ────────────────────────
@@ -72,6 +73,15 @@ pub struct SttConfig {
     #[serde(default)]
     pub wake_word: Option<String>,
 
+    /// Stop the character's speech as soon as the native mic's VAD hears the user.
+    #[serde(default = "default_true")]
+    pub barge_in: bool,
+
+    /// Label who said what in multi-person recordings. Needs a provider that
+    /// diarizes (e.g. an OpenAI `*-diarize` model); others are unaffected.
+    #[serde(default)]
+    pub diarization: bool,
+
     #[serde(default = "default_providers")]
     pub providers: Vec<SttProviderConfig>,
 }
@@ -151,6 +161,19 @@ fn default_providers() -> Vec<SttProviderConfig> {
             num_threads: Some(2),
             use_itn: Some(true),
         },
+        SttProviderConfig {
+            id: "whisper_local".to_string(),
+            provider_type: "whisper_local".to_string(),
+            enabled: false,
+            api_key: None,
+            api_key_env: None,
+            base_url: None,
+            model: Some("base".to_string()),
+            model_path: None,
+            tokens_path: None,
+            num_threads: None,
+            use_itn: None,
+        },
     ]
 }
 
@@ -163,6 +186,8 @@ impl Default for SttConfig {
             wake_word_enabled: false,
             continuous_listening: false,
             wake_word: None,
+            barge_in: true,
+            diarization: false,
             providers: default_providers(),
         }
     }
@@ -0,0 +1,187 @@
+//! Speaker attribution for multi-person recordings.
+//!
+//! Diarizing providers tag each segment with an opaque speaker label. The
+//! speaker with the most speech is taken to be the user at the mic; everyone
+//! else is another person in the room. When a recording has more than one
+//! speaker, its text is rewritten as labelled lines ("User: …",
+//! "Other person: …") so the chat model and memory extraction can tell
+//! who said what.
+
+use super::interface::{TranscriptionResult, TranscriptionSegment};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SpeakerRole {
+    User,
+    Other,
+}
+
+pub const USER_LINE_LABEL: &str = "User";
+pub const OTHER_LINE_LABEL: &str = "Other person";
+
+/// The label with the most speaking time; ties go to whoever spoke first.
+pub fn primary_speaker(segments: &[TranscriptionSegment]) -> Option<String> {
+    let mut totals: Vec<(&str, f32)> = Vec::new();
+    for segment in segments {
+        let Some(label) = segment.speaker.as_deref() else {
+            continue;
+        };
+        let spoken = (segment.end - segment.start).max(0.0);
+        match totals.iter_mut().find(|(l, _)| *l == label) {
+            Some((_, total)) => *total += spoken,
+            None => totals.push((label, spoken)),
+        }
+    }
+    totals
+        .into_iter()
+        .fold(None::<(&str, f32)>, |best, (label, total)| match best {
+            Some((_, best_total)) if best_total >= total => best,
+            _ => Some((label, total)),
+        })
+        .map(|(label, _)| label.to_string())
+}
+
+/// Set each segment's role and, if more than one person spoke, replace the
+/// text with labelled lines. Returns whether the text was rewritten.
+pub fn attribute(result: &mut TranscriptionResult) -> bool {
+    let Some(user) = primary_speaker(&result.segments) else {
+        return false;
+    };
+
+    // Others are numbered in order of appearance: "Other person", "Other person 2", ...
+    let mut others: HashMap<String, usize> = HashMap::new();
+    let mut lines: Vec<(String, String)> = Vec::new();
+    for segment in &mut result.segments {
+        let Some(label) = segment.speaker.as_deref() else {
+            continue;
+        };
+        let name = if label == user {
+            segment.role = Some(SpeakerRole::User);
+            USER_LINE_LABEL.to_string()
+        } else {
+            segment.role = Some(SpeakerRole::Other);
+            let next = others.len() + 1;
+            match *others.entry(label.to_string()).or_insert(next) {
+                1 => OTHER_LINE_LABEL.to_string(),
+                n => format!("{} {}", OTHER_LINE_LABEL, n),
+            }
+        };
+        let text = segment.text.trim();
+        if text.is_empty() {
+            continue;
+        }
+        match lines.last_mut() {
+            Some((last, joined)) if *last == name => {
+                joined.push(' ');
+                joined.push_str(text);
+            }
+            _ => lines.push((name, text.to_string())),
+        }
+    }
+
+    if others.is_empty() {
+        return false;
+    }
+    result.text = lines
+        .into_iter()
+        .map(|(name, text)| format!("{}: {}", name, text))
+        .collect::<Vec<_>>()
+        .join("\n");
+    true
+}
+
+/// Whether `text` contains lines attributed to someone other than the user.
+pub fn mentions_other_speakers(text: &str) -> bool {
+    text.lines().any(|line| {
+        line.strip_prefix(OTHER_LINE_LABEL).is_some_and(|rest| {
+            rest.trim_start_matches(|c: char| c == ' ' || c.is_ascii_digit())
+                .starts_with(':')
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn segment(speaker: Option<&str>, start: f32, end: f32, text: &str) -> TranscriptionSegment {
+        TranscriptionSegment {
+            start,
+            end,
+            text: text.to_string(),
+            confidence: None,
+            speaker: speaker.map(str::to_string),
+            role: None,
+        }
+    }
+
+    fn result(segments: Vec<TranscriptionSegment>) -> TranscriptionResult {
+        TranscriptionResult {
+            text: segments
+                .iter()
+                .map(|s| s.text.as_str())
+                .collect::<Vec<_>>()
+                .join(" "),
+            segments,
+            processing_time: Duration::ZERO,
+        }
+    }
+
+    #[test]
+    fn longest_speaker_is_the_user() {
+        let segments = vec![
+            segment(Some("A"), 0.0, 1.0, "Hi."),
+            segment(Some("B"), 1.0, 4.0, "I had a long day at work."),
+            segment(Some("A"), 4.0, 5.0, "Oh?"),
+        ];
+        assert_eq!(primary_speaker(&segments).as_deref(), Some("B"));
+        assert_eq!(primary_speaker(&[segment(None, 0.0, 1.0, "x")]), None);
+    }
+
+    #[test]
+    fn multi_speaker_text_becomes_labelled_lines() {
+        let mut r = result(vec![
+            segment(Some("A"), 0.0, 3.0, "My sister is visiting."),
+            segment(Some("A"), 3.0, 5.0, "Say hi!"),
+            segment(Some("B"), 5.0, 6.0, "Hello there."),
+            segment(Some("C"), 6.0, 6.5, "Hey."),
+            segment(Some("A"), 6.5, 7.0, "See?"),
+        ]);
+        assert!(attribute(&mut r));
+        assert_eq!(
+            r.text,
+            "User: My sister is visiting. Say hi!\n\
+             Other person: Hello there.\n\
+             Other person 2: Hey.\n\
+             User: See?"
+        );
+        assert_eq!(r.segments[0].role, Some(SpeakerRole::User));
+        assert_eq!(r.segments[2].role, Some(SpeakerRole::Other));
+        assert!(mentions_other_speakers(&r.text));
+    }
+
+    #[test]
+    fn single_speaker_keeps_plain_text() {
+        let mut r = result(vec![
+            segment(Some("A"), 0.0, 1.0, "Just me."),
+            segment(Some("A"), 1.0, 2.0, "Talking."),
+        ]);
+        assert!(!attribute(&mut r));
+        assert_eq!(r.text, "Just me. Talking.");
+        assert_eq!(r.segments[1].role, Some(SpeakerRole::User));
+
+        let mut unlabelled = result(vec![segment(None, 0.0, 1.0, "No labels.")]);
+        assert!(!attribute(&mut unlabelled));
+        assert_eq!(unlabelled.segments[0].role, None);
+    }
+
+    #[test]
+    fn detects_other_speaker_lines_only_at_line_start() {
+        assert!(mentions_other_speakers("User: hi\nOther person 3: yo"));
+        assert!(!mentions_other_speakers("I told the Other person: no"));
+        assert!(!mentions_other_speakers("Other personally, fine"));
+    }
+}
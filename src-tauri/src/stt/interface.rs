@@ -3,6 +3,7 @@
 //! Defines the abstract contract for Speech-to-Text engines, standardized data structures
 //! for audio chunks and transcription results, and semantic error handling.
 
+use super::diarization::SpeakerRole;
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use std::fmt;
@@ -87,6 +88,12 @@ pub struct TranscriptionSegment {
     pub text: String,
     /// Confidence score (0.0 - 1.0), if available.
     pub confidence: Option<f32>,
+    /// Provider's speaker label ("A", "spk_0", ...), when it diarizes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speaker: Option<String>,
+    /// Who the speaker is to us; set by [`super::diarization::attribute`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub role: Option<SpeakerRole>,
 }
 
 /// The full result of a transcription task.
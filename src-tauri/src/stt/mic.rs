@@ -1,4 +1,6 @@
+use crate::ai::companion_state::{CompanionActivity, CompanionStateService};
 use crate::stt::stream::{AudioBuffer, SAMPLE_RATE};
+use crate::tts::TtsService;
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
 use cpal::{FromSample, Sample, SampleFormat, SizedSample, Stream, StreamConfig};
 use rubato::{FastFixedIn, PolynomialDegree, Resampler};
@@ -16,9 +18,24 @@ const SILERO_VAD_MODEL_URL: &str =
     "https://github.com/k2-fsa/sherpa-onnx/releases/download/asr-models/silero_vad.onnx";
 const SILERO_VAD_MODEL_NAME: &str = "silero_vad.onnx";
 
+/// How the native mic treats detected speech.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MicOptions {
+    /// Emit `stt:mic-auto-stop` after the first complete utterance.
+    pub auto_stop_on_silence: bool,
+    /// Interrupt TTS playback when the user starts talking over it.
+    pub barge_in: bool,
+}
+
+impl MicOptions {
+    fn needs_vad(&self) -> bool {
+        self.auto_stop_on_silence || self.barge_in
+    }
+}
+
 enum WorkerCommand {
     Start {
-        auto_stop_on_silence: bool,
+        options: MicOptions,
         response: SyncSender<Result<(), String>>,
     },
     Stop {
@@ -132,15 +149,18 @@ struct NativeAudioFrame {
 struct NativeFrameProcessor {
     app: AppHandle,
     vad: Option<VoiceActivityDetector>,
+    options: MicOptions,
     auto_stop_emitted: bool,
+    /// VAD speech state on the previous frame, to catch speech onsets.
+    speech_active: bool,
     last_volume_emit: Instant,
     vad_detected_logged: bool,
     vad_frame_counter: usize,
 }
 
 impl NativeFrameProcessor {
-    fn new(app: AppHandle, auto_stop_on_silence: bool) -> Result<Self, String> {
-        let vad = if auto_stop_on_silence {
+    fn new(app: AppHandle, options: MicOptions) -> Result<Self, String> {
+        let vad = if options.needs_vad() {
             Some(create_voice_activity_detector()?)
         } else {
             None
@@ -149,7 +169,9 @@ impl NativeFrameProcessor {
         Ok(Self {
             app,
             vad,
+            options,
             auto_stop_emitted: false,
+            speech_active: false,
             last_volume_emit: Instant::now() - VOLUME_EVENT_INTERVAL,
             vad_detected_logged: false,
             vad_frame_counter: 0,
@@ -171,44 +193,66 @@ impl NativeFrameProcessor {
         if let Some(vad) = self.vad.as_ref() {
             self.vad_frame_counter += 1;
             vad.accept_waveform(&frame.samples);
-            if !self.vad_detected_logged && vad.detected() {
+            let detected = vad.detected();
+            if !self.vad_detected_logged && detected {
                 self.vad_detected_logged = true;
             }
-            if !self.auto_stop_emitted && !vad.is_empty() {
-                self.auto_stop_emitted = true;
-                let _ = self.app.emit("stt:mic-auto-stop", ());
+            if detected && !self.speech_active && self.options.barge_in {
+                barge_in(&self.app);
+            }
+            self.speech_active = detected;
+            if self.options.auto_stop_on_silence {
+                if !self.auto_stop_emitted && !vad.is_empty() {
+                    self.auto_stop_emitted = true;
+                    let _ = self.app.emit("stt:mic-auto-stop", ());
+                }
+            } else {
+                // Only onsets matter for barge-in; don't let segments pile up.
+                vad.clear();
             }
         }
 
         let audio_buffer = self.app.state::<AudioBuffer>();
+        audio_buffer.start_partials(&self.app);
         if let Err(err) = audio_buffer.append_samples(frame.samples) {
             tracing::error!(target: "stt", "[STT] Native mic append failed: {err}");
         }
     }
 }
 
+/// Stop TTS when the user talks over it. Only fires while the character is
+/// audibly speaking, so ordinary dictation doesn't emit interrupts.
+fn barge_in(app: &AppHandle) {
+    let speaking = app
+        .try_state::<CompanionStateService>()
+        .is_some_and(|state| {
+            state
+                .snapshot()
+                .active
+                .contains(&CompanionActivity::Speaking)
+        });
+    if !speaking {
+        return;
+    }
+    if let Some(tts) = app.try_state::<TtsService>() {
+        tracing::info!(target: "stt", "[STT] Speech detected during playback, barging in");
+        tts.stop_speaking(app, "barge_in");
+    }
+}
+
 pub fn start_native_mic(app: &AppHandle, mic_state: &NativeMicState) -> Result<(), String> {
-    let tx = mic_state.ensure_worker(app)?;
-    let (response_tx, response_rx) = mpsc::sync_channel(1);
-    tx.send(WorkerCommand::Start {
-        auto_stop_on_silence: false,
-        response: response_tx,
-    })
-    .map_err(|_| "Native microphone worker is unavailable".to_string())?;
-    response_rx
-        .recv()
-        .map_err(|_| "Native microphone worker did not respond".to_string())?
+    start_native_mic_with_options(app, mic_state, MicOptions::default())
 }
 
 pub fn start_native_mic_with_options(
     app: &AppHandle,
     mic_state: &NativeMicState,
-    auto_stop_on_silence: bool,
+    options: MicOptions,
 ) -> Result<(), String> {
     let tx = mic_state.ensure_worker(app)?;
     let (response_tx, response_rx) = mpsc::sync_channel(1);
     tx.send(WorkerCommand::Start {
-        auto_stop_on_silence,
+        options,
         response: response_tx,
     })
     .map_err(|_| "Native microphone worker is unavailable".to_string())?;
@@ -235,14 +279,11 @@ fn spawn_mic_worker(rx: Receiver<WorkerCommand>, app: AppHandle) {
 
         while let Ok(command) = rx.recv() {
             match command {
-                WorkerCommand::Start {
-                    auto_stop_on_silence,
-                    response,
-                } => {
+                WorkerCommand::Start { options, response } => {
                     let result = if stream.is_some() {
                         Ok(())
                     } else {
-                        match build_native_input_stream(&app, auto_stop_on_silence) {
+                        match build_native_input_stream(&app, options) {
                             Ok(new_stream) => {
                                 if let Err(err) = new_stream.play() {
                                     Err(format!("Failed to start microphone stream: {err}"))
@@ -272,10 +313,7 @@ fn spawn_mic_worker(rx: Receiver<WorkerCommand>, app: AppHandle) {
     });
 }
 
-fn build_native_input_stream(
-    app: &AppHandle,
-    auto_stop_on_silence: bool,
-) -> Result<Stream, String> {
+fn build_native_input_stream(app: &AppHandle, options: MicOptions) -> Result<Stream, String> {
     let host = cpal::default_host();
     let device = host
         .default_input_device()
@@ -296,7 +334,7 @@ fn build_native_input_stream(
             channels,
             sample_rate,
             app_handle,
-            auto_stop_on_silence,
+            options,
         ),
         SampleFormat::I16 => build_input_stream::<i16>(
             &device,
@@ -304,7 +342,7 @@ fn build_native_input_stream(
             channels,
             sample_rate,
             app_handle,
-            auto_stop_on_silence,
+            options,
         ),
         SampleFormat::I32 => build_input_stream::<i32>(
             &device,
@@ -312,7 +350,7 @@ fn build_native_input_stream(
             channels,
             sample_rate,
             app_handle,
-            auto_stop_on_silence,
+            options,
         ),
         SampleFormat::I64 => build_input_stream::<i64>(
             &device,
@@ -320,7 +358,7 @@ fn build_native_input_stream(
             channels,
             sample_rate,
             app_handle,
-            auto_stop_on_silence,
+            options,
         ),
         SampleFormat::U8 => build_input_stream::<u8>(
             &device,
@@ -328,7 +366,7 @@ fn build_native_input_stream(
             channels,
             sample_rate,
             app_handle,
-            auto_stop_on_silence,
+            options,
         ),
         SampleFormat::U16 => build_input_stream::<u16>(
             &device,
@@ -336,7 +374,7 @@ fn build_native_input_stream(
             channels,
             sample_rate,
             app_handle,
-            auto_stop_on_silence,
+            options,
         ),
         SampleFormat::U32 => build_input_stream::<u32>(
             &device,
@@ -344,7 +382,7 @@ fn build_native_input_stream(
             channels,
             sample_rate,
             app_handle,
-            auto_stop_on_silence,
+            options,
         ),
         SampleFormat::U64 => build_input_stream::<u64>(
             &device,
@@ -352,7 +390,7 @@ fn build_native_input_stream(
             channels,
             sample_rate,
             app_handle,
-            auto_stop_on_silence,
+            options,
         ),
         SampleFormat::F32 => build_input_stream::<f32>(
             &device,
@@ -360,7 +398,7 @@ fn build_native_input_stream(
             channels,
             sample_rate,
             app_handle,
-            auto_stop_on_silence,
+            options,
         ),
         SampleFormat::F64 => build_input_stream::<f64>(
             &device,
@@ -368,7 +406,7 @@ fn build_native_input_stream(
             channels,
             sample_rate,
             app_handle,
-            auto_stop_on_silence,
+            options,
         ),
         sample_format => Err(format!(
             "Unsupported microphone sample format: {sample_format}"
@@ -382,7 +420,7 @@ fn build_input_stream<T>(
     channels: usize,
     sample_rate: u32,
     app: AppHandle,
-    auto_stop_on_silence: bool,
+    options: MicOptions,
 ) -> Result<Stream, String>
 where
     T: SizedSample + Sample + Send + 'static,
@@ -391,7 +429,7 @@ where
     let mut processor = NativeInputProcessor::new(sample_rate, channels)?;
     let err_app = app.clone();
     let (frame_tx, frame_rx) = mpsc::sync_channel::<NativeAudioFrame>(8);
-    spawn_frame_processor(app.clone(), frame_rx, auto_stop_on_silence)?;
+    spawn_frame_processor(app.clone(), frame_rx, options)?;
 
     device
         .build_input_stream(
@@ -427,13 +465,20 @@ where
 fn spawn_frame_processor(
     app: AppHandle,
     frame_rx: Receiver<NativeAudioFrame>,
-    auto_stop_on_silence: bool,
+    mut options: MicOptions,
 ) -> Result<(), String> {
-    if auto_stop_on_silence {
-        let _ = create_voice_activity_detector()?;
+    if options.needs_vad() {
+        if let Err(err) = create_voice_activity_detector() {
+            if options.auto_stop_on_silence {
+                return Err(err);
+            }
+            // Barge-in is best-effort; dictation still works without the VAD.
+            tracing::warn!(target: "stt", "[STT] VAD unavailable, barge-in disabled: {err}");
+            options.barge_in = false;
+        }
     }
     std::thread::spawn(move || {
-        let mut processor = match NativeFrameProcessor::new(app, auto_stop_on_silence) {
+        let mut processor = match NativeFrameProcessor::new(app, options) {
             Ok(processor) => processor,
             Err(err) => {
                 tracing::error!(target: "stt", "[STT] Native mic frame processor init failed: {err}");
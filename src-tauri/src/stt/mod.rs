@@ -1,4 +1,5 @@
 pub mod config;
+pub mod diarization;
 pub mod interface;
 pub mod mic;
 pub mod openai;
@@ -6,8 +7,10 @@ pub mod sensevoice;
 pub mod sensevoice_local;
 pub mod service;
 pub mod stream;
+pub mod voice_commands;
 pub mod wake_word;
 pub mod whisper_cpp;
+pub mod whisper_local;
 
 pub use config::{load_config, SttConfig};
 pub use interface::{
@@ -17,3 +20,4 @@ pub use mic::NativeMicState;
 pub use sensevoice_local::{SenseVoiceLocalDownloadProgress, SenseVoiceLocalModelStatus};
 pub use service::SttService;
 pub use wake_word::NativeWakeWordState;
+pub use whisper_local::{WhisperLocalDownloadProgress, WhisperLocalModelStatus};
@@ -40,6 +40,12 @@ impl OpenAIWhisperProvider {
             client,
         })
     }
+
+    /// Diarizing models (e.g. `gpt-4o-transcribe-diarize`) label each segment
+    /// with a speaker but only answer in `diarized_json`.
+    fn diarizes(&self) -> bool {
+        self.model.contains("diarize")
+    }
 }
 
 fn transcription_url(base_url: &str) -> String {
@@ -69,7 +75,7 @@ fn transcription_url(base_url: &str) -> String {
     }
 }
 
-// Response structures for verbose_json
+// Response structures for verbose_json / diarized_json
 #[derive(Debug, Deserialize)]
 struct OpenAIResponse {
     text: String,
@@ -89,6 +95,9 @@ struct OpenAISegment {
     // but usually keys are standardized in v1. We'll leave confidence optional.
     #[serde(default)]
     no_speech_prob: f32,
+    /// Only in `diarized_json` responses.
+    #[serde(default)]
+    speaker: Option<String>,
 }
 
 #[async_trait]
@@ -147,6 +156,7 @@ impl SttEngine for OpenAIWhisperProvider {
         let language = language.map(|s| s.to_string());
         let mime_type = mime_type.to_string();
         let file_name = file_name.to_string();
+        let diarize = self.diarizes();
         let url = transcription_url(&self.base_url);
         let url_arc = std::sync::Arc::new(url);
 
@@ -170,8 +180,13 @@ impl SttEngine for OpenAIWhisperProvider {
 
                     let mut form = multipart::Form::new()
                         .part("file", part)
-                        .text("model", model)
-                        .text("response_format", "verbose_json");
+                        .text("model", model);
+                    form = if diarize {
+                        form.text("response_format", "diarized_json")
+                            .text("chunking_strategy", "auto")
+                    } else {
+                        form.text("response_format", "verbose_json")
+                    };
 
                     if let Some(lang) = &language {
                         form = form.text("language", lang.clone());
@@ -230,6 +245,8 @@ impl SttEngine for OpenAIWhisperProvider {
                 end: s.end,
                 text: s.text.trim().to_string(),
                 confidence: Some(1.0 - s.no_speech_prob), // Rough proxy for confidence
+                speaker: s.speaker,
+                role: None,
             })
             .collect();
 
@@ -154,6 +154,8 @@ impl SttEngine for SenseVoiceProvider {
                     end,
                     text: token,
                     confidence: None,
+                    speaker: None,
+                    role: None,
                 })
             })
             .collect();
@@ -165,6 +167,8 @@ impl SttEngine for SenseVoiceProvider {
                 end: audio.duration_seconds(),
                 text: text.clone(),
                 confidence: None,
+                speaker: None,
+                role: None,
             }]
         } else {
             segments
@@ -252,7 +252,7 @@ impl SenseVoiceLocalProvider {
     }
 }
 
-fn decode_wav_bytes(data: &[u8]) -> Result<(i32, Vec<f32>), SttError> {
+pub(super) fn decode_wav_bytes(data: &[u8]) -> Result<(i32, Vec<f32>), SttError> {
     let cursor = std::io::Cursor::new(data);
     let mut reader = hound::WavReader::new(cursor)
         .map_err(|e| SttError::AudioFormatInvalid(format!("Failed to read WAV: {}", e)))?;
@@ -260,7 +260,7 @@ fn decode_wav_bytes(data: &[u8]) -> Result<(i32, Vec<f32>), SttError> {
 
     if spec.channels != 1 {
         return Err(SttError::AudioFormatInvalid(
-            "sensevoice_local expects mono WAV input".to_string(),
+            "local STT expects mono WAV input".to_string(),
         ));
     }
 
@@ -327,6 +327,8 @@ impl SttEngine for SenseVoiceLocalProvider {
                 end: audio.duration_seconds(),
                 text: result.text.trim().to_string(),
                 confidence: None,
+                speaker: None,
+                role: None,
             }],
             _ => Vec::new(),
         };
@@ -362,6 +364,8 @@ fn build_segments(text: &str, tokens: &[String], timestamps: &[f32]) -> Vec<Tran
             end,
             text: cleaned.to_string(),
             confidence: None,
+            speaker: None,
+            role: None,
         });
     }
     segments
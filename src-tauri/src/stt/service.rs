@@ -1,11 +1,13 @@
 //! STT Service — manages providers and routes transcription requests.
 
 use super::config::{SttConfig, SttProviderConfig};
+use super::diarization;
 use super::interface::{AudioSource, SttEngine, SttError, TranscriptionResult};
 use super::openai::OpenAIWhisperProvider;
 use super::sensevoice::SenseVoiceProvider;
 use super::sensevoice_local::SenseVoiceLocalProvider;
 use super::whisper_cpp::WhisperCppProvider;
+use super::whisper_local::WhisperLocalProvider;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
@@ -13,6 +15,8 @@ use tokio::sync::RwLock;
 pub struct SttService {
     providers: Arc<RwLock<Vec<Arc<dyn SttEngine>>>>,
     config: Arc<RwLock<SttConfig>>,
+    /// Local provider forced while the engine is in offline mode.
+    offline_provider: Arc<RwLock<Option<String>>>,
 }
 
 impl Default for SttService {
@@ -26,6 +30,7 @@ impl SttService {
         Self {
             providers: Arc::new(RwLock::new(Vec::new())),
             config: Arc::new(RwLock::new(SttConfig::default())),
+            offline_provider: Arc::new(RwLock::new(None)),
         }
     }
 
@@ -89,6 +94,7 @@ impl SttService {
                 }
             }
             "sensevoice_local" => Some(Arc::new(SenseVoiceLocalProvider::new(config, None))),
+            "whisper_local" => Some(Arc::new(WhisperLocalProvider::new(config))),
             other => {
                 tracing::error!(target: "stt", "Unknown provider type: {}", other);
                 None
@@ -98,34 +104,59 @@ impl SttService {
 
     /// Transcribe audio using the active provider.
     /// If `language_override` is Some, use that; otherwise fall back to config language.
+    /// With diarization on, multi-speaker results come back as labelled lines.
     pub async fn transcribe(
         &self,
         audio: &AudioSource,
         language_override: Option<&str>,
     ) -> Result<TranscriptionResult, SttError> {
-        let config = self.config.read().await;
-        let config_language = config.language.clone();
-        let active_id = config.active_provider.clone();
-        drop(config);
-
+        let (config_language, diarization) = {
+            let config = self.config.read().await;
+            (config.language.clone(), config.diarization)
+        };
         let language = language_override.map(|s| s.to_string()).or(config_language);
 
-        let provider = {
-            let providers = self.providers.read().await;
-
-            // Find the active provider
-            providers
-                .iter()
-                .find(|p| p.id() == active_id)
-                .or_else(|| providers.first())
-                .cloned()
-                .ok_or_else(|| {
-                    SttError::ProviderNotFound("No STT providers configured".to_string())
-                })?
-        };
+        let provider = self
+            .active_engine()
+            .await
+            .ok_or_else(|| SttError::ProviderNotFound("No STT providers configured".to_string()))?;
 
         // Lock is released here, so we can await safely without blocking
-        provider.transcribe(audio, language.as_deref()).await
+        let mut result = provider.transcribe(audio, language.as_deref()).await?;
+        if diarization && diarization::attribute(&mut result) {
+            tracing::info!(
+                target: "stt",
+                "[STT] Attributed {} segments to multiple speakers",
+                result.segments.len()
+            );
+        }
+        Ok(result)
+    }
+
+    /// The provider `transcribe` would use right now and whether it is reachable.
+    pub async fn active_provider_status(&self) -> Option<(String, bool)> {
+        let provider = self.active_engine().await?;
+        let available = provider.is_available().await;
+        Some((provider.id(), available))
+    }
+
+    async fn active_engine(&self) -> Option<Arc<dyn SttEngine>> {
+        let active_id = self.config.read().await.active_provider.clone();
+        let offline_id = self.offline_provider.read().await.clone();
+        let providers = self.providers.read().await;
+
+        // Find the active provider (the offline fallback wins while offline)
+        providers
+            .iter()
+            .find(|p| offline_id.as_deref() == Some(p.id().as_str()))
+            .or_else(|| providers.iter().find(|p| p.id() == active_id))
+            .or_else(|| providers.first())
+            .cloned()
+    }
+
+    /// Prefer `provider_id` for transcription while offline; `None` restores normal routing.
+    pub async fn set_offline_fallback(&self, provider_id: Option<String>) {
+        *self.offline_provider.write().await = provider_id;
     }
 
     /// Get the current config.
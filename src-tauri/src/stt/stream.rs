@@ -2,15 +2,39 @@
 //!
 //! Handles incoming raw PCM audio chunks from the frontend, buffers them with limits,
 //! and dispatches to the STT service as standardized AudioChunks.
+//!
+//! An optional VAD stage (silero, on its own thread) watches the same samples
+//! and finalizes the stream by itself when an utterance ends, so voice input
+//! works hands-free.
+//!
+//! While audio keeps arriving, a partial decoder re-transcribes the growing
+//! buffer every couple of seconds and emits `stt:partial` with interim text.
 
+use crate::stt::mic::create_voice_activity_detector;
 use crate::stt::{AudioChunk, SttService, TranscriptionResult};
+use sherpa_onnx::VoiceActivityDetector;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, SyncSender, TrySendError};
 use std::sync::Mutex;
-use tauri::{AppHandle, Manager, State};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
 
 // Limit buffer to 120 seconds to prevent OOM
 pub const SAMPLE_RATE: u32 = 16000;
 const MAX_BUFFER_SECONDS: usize = 120;
 pub const MAX_SAMPLES: usize = MAX_BUFFER_SECONDS * SAMPLE_RATE as usize;
+/// Audio kept ahead of detected speech while the VAD hears nothing.
+const VAD_PRE_ROLL_SECONDS: f32 = 0.6;
+/// Chunks queued for the VAD worker before new ones are dropped.
+const VAD_QUEUE_DEPTH: usize = 64;
+/// How often the partial decoder looks at the buffer.
+const PARTIAL_INTERVAL: Duration = Duration::from_secs(2);
+/// New audio needed before another interim decode is worth it (0.5s).
+const PARTIAL_MIN_NEW_SAMPLES: u64 = SAMPLE_RATE as u64 / 2;
+/// Buffered audio needed before the first interim decode (0.8s).
+const PARTIAL_MIN_SAMPLES: usize = SAMPLE_RATE as usize * 4 / 5;
+/// Intervals without new audio before the partial decoder exits.
+const PARTIAL_IDLE_TICKS: u32 = 3;
 
 #[derive(Debug)]
 pub struct AudioStreamState {
@@ -18,6 +42,12 @@ pub struct AudioStreamState {
     /// How many seconds of audio have been pruned from the start.
     /// Used to adjust timestamps so they remain consistent relative to the start of the recording.
     pub time_offset_seconds: f32,
+    /// Samples handed to the VAD worker that it has not processed yet.
+    vad_backlog: usize,
+    /// Samples ever appended; lets the partial decoder spot new audio.
+    appended: u64,
+    /// Bumped whenever the buffer is taken, so stale partials are dropped.
+    epoch: u64,
 }
 
 impl AudioStreamState {
@@ -25,12 +55,45 @@ impl AudioStreamState {
         Self {
             samples: Vec::with_capacity(SAMPLE_RATE as usize * 10),
             time_offset_seconds: 0.0,
+            vad_backlog: 0,
+            appended: 0,
+            epoch: 0,
         }
     }
+
+    /// Drop everything but the last `keep_samples`, tracking the pruned time.
+    fn prune(&mut self, keep_samples: usize) {
+        if self.samples.len() > keep_samples {
+            let split_idx = self.samples.len() - keep_samples;
+
+            // Calculate how many seconds we are pruning to update the offset
+            let pruned_seconds = split_idx as f32 / SAMPLE_RATE as f32;
+            self.time_offset_seconds += pruned_seconds;
+
+            self.samples.drain(0..split_idx);
+        }
+    }
+
+    /// Take the buffered audio and its time offset, leaving the buffer empty.
+    fn take(&mut self) -> (Vec<f32>, f32) {
+        let data = std::mem::take(&mut self.samples);
+        let offset = self.time_offset_seconds;
+        self.time_offset_seconds = 0.0;
+        self.epoch += 1;
+        (data, offset)
+    }
+}
+
+enum VadInput {
+    Samples(Vec<f32>),
+    Reset,
 }
 
 pub struct AudioBuffer {
     pub state: Mutex<AudioStreamState>,
+    /// Feed for the stream VAD worker; `None` while the VAD is off.
+    vad: Mutex<Option<SyncSender<VadInput>>>,
+    partial_running: AtomicBool,
 }
 
 impl Default for AudioBuffer {
@@ -43,6 +106,8 @@ impl AudioBuffer {
     pub fn new() -> Self {
         Self {
             state: Mutex::new(AudioStreamState::new()),
+            vad: Mutex::new(None),
+            partial_running: AtomicBool::new(false),
         }
     }
 
@@ -73,18 +138,346 @@ impl AudioBuffer {
             ));
         }
 
-        stream.samples.extend(sanitized_chunk);
+        stream.appended += sanitized_chunk.len() as u64;
+        if self.vad_enabled() {
+            stream.vad_backlog += sanitized_chunk.len();
+            stream.samples.extend_from_slice(&sanitized_chunk);
+            drop(stream);
+            self.send_to_vad(VadInput::Samples(sanitized_chunk));
+        } else {
+            stream.samples.extend(sanitized_chunk);
+        }
         Ok(())
     }
+
+    /// Start the partial decoder unless it is already running. It stops on
+    /// its own once audio stops arriving.
+    pub fn start_partials(&self, app: &AppHandle) {
+        if self
+            .partial_running
+            .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+            .is_ok()
+        {
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                run_partial_decoder(&app).await;
+                app.state::<AudioBuffer>()
+                    .partial_running
+                    .store(false, Ordering::SeqCst);
+            });
+        }
+    }
+
+    pub fn vad_enabled(&self) -> bool {
+        self.vad.lock().map(|vad| vad.is_some()).unwrap_or(false)
+    }
+
+    fn set_vad(&self, sender: Option<SyncSender<VadInput>>) {
+        if let Ok(mut vad) = self.vad.lock() {
+            *vad = sender;
+        }
+    }
+
+    fn send_to_vad(&self, input: VadInput) {
+        let Ok(mut vad) = self.vad.lock() else {
+            return;
+        };
+        let Some(sender) = vad.as_ref() else {
+            return;
+        };
+        match sender.try_send(input) {
+            Ok(()) => {}
+            Err(TrySendError::Full(input)) => {
+                tracing::warn!(target: "stt", "[STT] Stream VAD is falling behind, dropping a chunk");
+                if let VadInput::Samples(samples) = input {
+                    self.finish_vad_chunk(samples.len());
+                }
+            }
+            Err(TrySendError::Disconnected(_)) => {
+                tracing::warn!(target: "stt", "[STT] Stream VAD worker stopped");
+                *vad = None;
+            }
+        }
+    }
+
+    fn finish_vad_chunk(&self, len: usize) {
+        if let Ok(mut stream) = self.state.lock() {
+            stream.vad_backlog = stream.vad_backlog.saturating_sub(len);
+        }
+    }
+
+    /// Trim leading silence while no speech is in progress, keeping a short
+    /// pre-roll plus anything the VAD has not looked at yet.
+    fn prune_idle(&self, pre_roll_samples: usize) {
+        if let Ok(mut stream) = self.state.lock() {
+            let keep = pre_roll_samples + stream.vad_backlog;
+            stream.prune(keep);
+        }
+    }
+
+    fn take(&self) -> Result<(Vec<f32>, f32), String> {
+        let mut stream = self
+            .state
+            .lock()
+            .map_err(|_| "Failed to lock audio buffer")?;
+        Ok(stream.take())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PartialTick {
+    Decode,
+    Wait,
+    Stop,
+}
+
+/// Decides on each interval whether the buffer changed enough to re-decode.
+#[derive(Debug, Default)]
+struct PartialSchedule {
+    decoded_at: u64,
+    idle_ticks: u32,
+}
+
+impl PartialSchedule {
+    fn tick(&mut self, appended: u64, buffered: usize) -> PartialTick {
+        let fresh = appended.saturating_sub(self.decoded_at);
+        if fresh == 0 {
+            self.idle_ticks += 1;
+            return if self.idle_ticks >= PARTIAL_IDLE_TICKS {
+                PartialTick::Stop
+            } else {
+                PartialTick::Wait
+            };
+        }
+        self.idle_ticks = 0;
+        if fresh < PARTIAL_MIN_NEW_SAMPLES || buffered < PARTIAL_MIN_SAMPLES {
+            return PartialTick::Wait;
+        }
+        self.decoded_at = appended;
+        PartialTick::Decode
+    }
+}
+
+async fn run_partial_decoder(app: &AppHandle) {
+    let mut schedule = PartialSchedule::default();
+    loop {
+        tokio::time::sleep(PARTIAL_INTERVAL).await;
+        let buffer = app.state::<AudioBuffer>();
+        let snapshot = {
+            let Ok(stream) = buffer.state.lock() else {
+                return;
+            };
+            match schedule.tick(stream.appended, stream.samples.len()) {
+                PartialTick::Stop => return,
+                PartialTick::Wait => None,
+                PartialTick::Decode => Some((
+                    stream.samples.clone(),
+                    stream.time_offset_seconds,
+                    stream.epoch,
+                )),
+            }
+        };
+        let Some((data, offset, epoch)) = snapshot else {
+            continue;
+        };
+
+        let result = match transcribe_helper(app, data, offset).await {
+            Ok(result) => result,
+            Err(err) => {
+                tracing::debug!(target: "stt", "[STT] Partial decode failed: {err}");
+                continue;
+            }
+        };
+        // The stream was completed or discarded while we were decoding.
+        let current_epoch = buffer.state.lock().map(|s| s.epoch).unwrap_or(epoch);
+        if current_epoch != epoch || result.text.trim().is_empty() {
+            continue;
+        }
+        crate::captions::caption_user_transcript(app, &result, false);
+        let _ = app.emit("stt:partial", &result);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum VadEvent {
+    SpeechStart,
+    SpeechEnd,
+}
+
+/// Turns per-chunk VAD readings into utterance start/end events.
+#[derive(Debug, Default)]
+struct UtteranceTracker {
+    in_speech: bool,
+}
+
+impl UtteranceTracker {
+    /// `detected`: speech is audible now; `segment_closed`: the VAD finished a
+    /// speech segment (speech followed by enough silence).
+    fn update(&mut self, detected: bool, segment_closed: bool) -> Option<VadEvent> {
+        if segment_closed {
+            self.in_speech = false;
+            return Some(VadEvent::SpeechEnd);
+        }
+        if detected && !self.in_speech {
+            self.in_speech = true;
+            return Some(VadEvent::SpeechStart);
+        }
+        None
+    }
+
+    fn is_idle(&self) -> bool {
+        !self.in_speech
+    }
+}
+
+fn spawn_vad_worker(app: AppHandle) -> Result<SyncSender<VadInput>, String> {
+    let (tx, rx) = mpsc::sync_channel(VAD_QUEUE_DEPTH);
+    let (ready_tx, ready_rx) = mpsc::sync_channel(1);
+    std::thread::Builder::new()
+        .name("stt-stream-vad".to_string())
+        .spawn(move || {
+            // The detector is not Send, so it lives and dies on this thread.
+            let detector = match create_voice_activity_detector() {
+                Ok(detector) => {
+                    let _ = ready_tx.send(Ok(()));
+                    detector
+                }
+                Err(err) => {
+                    let _ = ready_tx.send(Err(err));
+                    return;
+                }
+            };
+            run_vad_worker(app, detector, rx);
+        })
+        .map_err(|err| format!("Failed to start stream VAD: {err}"))?;
+    ready_rx
+        .recv()
+        .map_err(|_| "Stream VAD worker exited during startup".to_string())??;
+    Ok(tx)
+}
+
+fn run_vad_worker(app: AppHandle, detector: VoiceActivityDetector, rx: Receiver<VadInput>) {
+    let pre_roll_samples = (VAD_PRE_ROLL_SECONDS * SAMPLE_RATE as f32) as usize;
+    let mut tracker = UtteranceTracker::default();
+
+    while let Ok(input) = rx.recv() {
+        let samples = match input {
+            VadInput::Samples(samples) => samples,
+            VadInput::Reset => {
+                detector.reset();
+                tracker = UtteranceTracker::default();
+                continue;
+            }
+        };
+
+        detector.accept_waveform(&samples);
+        let buffer = app.state::<AudioBuffer>();
+        buffer.finish_vad_chunk(samples.len());
+
+        let segment_closed = !detector.is_empty();
+        if segment_closed {
+            detector.clear();
+        }
+
+        match tracker.update(detector.detected(), segment_closed) {
+            Some(VadEvent::SpeechStart) => {
+                set_listening(&app, true);
+                let _ = app.emit("stt:vad-speech-start", ());
+            }
+            Some(VadEvent::SpeechEnd) => {
+                let _ = app.emit("stt:vad-speech-end", ());
+                // Take the audio now, before idle pruning can touch it.
+                match buffer.take() {
+                    Ok((data, offset)) => {
+                        let app = app.clone();
+                        tauri::async_runtime::spawn(async move {
+                            complete_vad_utterance(&app, data, offset).await;
+                        });
+                    }
+                    Err(err) => tracing::error!(target: "stt", "[STT] {err}"),
+                }
+            }
+            None => {}
+        }
+
+        if tracker.is_idle() {
+            buffer.prune_idle(pre_roll_samples);
+        }
+    }
+    tracing::info!(target: "stt", "[STT] Stream VAD stopped");
+}
+
+/// What `complete_audio_stream` does, for an utterance the VAD closed.
+async fn complete_vad_utterance(app: &AppHandle, data: Vec<f32>, offset: f32) {
+    set_listening(app, false);
+    match transcribe_helper(app, data, offset).await {
+        Ok(result) => {
+            crate::captions::caption_user_transcript(app, &result, true);
+            if !result.text.trim().is_empty() {
+                let _ = app.emit("stt:vad-utterance", &result);
+            }
+        }
+        Err(err) => {
+            tracing::warn!(target: "stt", "[STT] Hands-free transcription failed: {err}");
+            let _ = app.emit("stt:vad-error", err);
+        }
+    }
+}
+
+/// Turn the stream VAD on or off. While on, every finished utterance is
+/// transcribed automatically and emitted as `stt:vad-utterance`.
+#[tauri::command]
+pub async fn set_stream_vad(
+    app_handle: AppHandle,
+    state: State<'_, AudioBuffer>,
+    enabled: bool,
+) -> Result<(), String> {
+    configure_stream_vad(app_handle, state.inner(), enabled).await
+}
+
+pub(crate) async fn configure_stream_vad(
+    app: AppHandle,
+    state: &AudioBuffer,
+    enabled: bool,
+) -> Result<(), String> {
+    if !enabled {
+        state.set_vad(None);
+        return Ok(());
+    }
+    if state.vad_enabled() {
+        return Ok(());
+    }
+
+    // Creating the detector may download the model, so keep it off the runtime.
+    let sender = tokio::task::spawn_blocking(move || spawn_vad_worker(app))
+        .await
+        .map_err(|e| e.to_string())??;
+    state.set_vad(Some(sender));
+    Ok(())
 }
 
 /// Append a chunk of audio data (float32 PCM, 16kHz mono).
 #[tauri::command]
 pub async fn process_audio_chunk(
+    app_handle: AppHandle,
     state: State<'_, AudioBuffer>,
     chunk: Vec<f32>,
 ) -> Result<(), String> {
-    state.append_samples(chunk)
+    // With the VAD on, "listening" follows detected speech instead.
+    if !state.vad_enabled() {
+        set_listening(&app_handle, true);
+    }
+    state.append_samples(chunk)?;
+    state.start_partials(&app_handle);
+    Ok(())
+}
+
+fn set_listening(app_handle: &AppHandle, listening: bool) {
+    crate::ai::companion_state::set_active(
+        app_handle,
+        crate::ai::companion_state::CompanionActivity::Listening,
+        listening,
+    );
 }
 
 /// Finalize the stream and transcribe. Clears the buffer.
@@ -93,19 +486,12 @@ pub async fn complete_audio_stream(
     app_handle: AppHandle,
     state: State<'_, AudioBuffer>,
 ) -> Result<TranscriptionResult, String> {
-    let (raw_data, offset) = {
-        let mut stream = state
-            .state
-            .lock()
-            .map_err(|_| "Failed to lock audio buffer")?;
-        let data = stream.samples.clone();
-        let offset = stream.time_offset_seconds;
-        stream.samples.clear(); // Reset for next turn
-        stream.time_offset_seconds = 0.0; // Reset offset too
-        (data, offset)
-    };
+    let (raw_data, offset) = state.take()?;
+    set_listening(&app_handle, false);
 
-    transcribe_helper(&app_handle, raw_data, offset).await
+    let result = transcribe_helper(&app_handle, raw_data, offset).await?;
+    crate::captions::caption_user_transcript(&app_handle, &result, true);
+    Ok(result)
 }
 
 /// Transcribe the current buffer WITHOUT clearing it.
@@ -123,7 +509,9 @@ pub async fn snapshot_audio_stream(
         (stream.samples.clone(), stream.time_offset_seconds)
     };
 
-    transcribe_helper(&app_handle, raw_data, offset).await
+    let result = transcribe_helper(&app_handle, raw_data, offset).await?;
+    crate::captions::caption_user_transcript(&app_handle, &result, false);
+    Ok(result)
 }
 
 /// Prune the audio buffer, keeping only the last `keep_seconds`.
@@ -137,30 +525,22 @@ pub async fn prune_audio_buffer(
         .state
         .lock()
         .map_err(|_| "Failed to lock audio buffer")?;
-
-    let keep_samples = (keep_seconds * SAMPLE_RATE as f32) as usize;
-    if stream.samples.len() > keep_samples {
-        let split_idx = stream.samples.len() - keep_samples;
-
-        // Calculate how many seconds we are pruning to update the offset
-        let pruned_seconds = split_idx as f32 / SAMPLE_RATE as f32;
-        stream.time_offset_seconds += pruned_seconds;
-
-        stream.samples.drain(0..split_idx);
-    }
-
+    stream.prune((keep_seconds * SAMPLE_RATE as f32) as usize);
     Ok(())
 }
 
 /// Discard current buffer without transcribing.
 #[tauri::command]
-pub async fn discard_audio_stream(state: State<'_, AudioBuffer>) -> Result<(), String> {
-    let mut stream = state
-        .state
-        .lock()
-        .map_err(|_| "Failed to lock audio buffer")?;
-    stream.samples.clear();
-    stream.time_offset_seconds = 0.0;
+pub async fn discard_audio_stream(
+    app_handle: AppHandle,
+    state: State<'_, AudioBuffer>,
+) -> Result<(), String> {
+    if let Some(captions) = app_handle.try_state::<crate::captions::CaptionService>() {
+        captions.discard_user_utterance();
+    }
+    set_listening(&app_handle, false);
+    state.send_to_vad(VadInput::Reset);
+    state.take()?;
     Ok(())
 }
 
@@ -203,3 +583,69 @@ async fn transcribe_helper(
 
     Ok(result)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tracker_reports_start_once_and_end_on_closed_segment() {
+        let mut tracker = UtteranceTracker::default();
+        assert_eq!(tracker.update(false, false), None);
+        assert_eq!(tracker.update(true, false), Some(VadEvent::SpeechStart));
+        assert_eq!(tracker.update(true, false), None);
+        // Trailing silence before the VAD closes the segment is still speech.
+        assert_eq!(tracker.update(false, false), None);
+        assert!(!tracker.is_idle());
+        assert_eq!(tracker.update(false, true), Some(VadEvent::SpeechEnd));
+        assert!(tracker.is_idle());
+        // A long utterance split by the VAD starts again right away.
+        assert_eq!(tracker.update(true, false), Some(VadEvent::SpeechStart));
+    }
+
+    #[test]
+    fn partial_schedule_waits_for_fresh_audio_and_stops_when_idle() {
+        let mut schedule = PartialSchedule::default();
+        // Too little audio buffered yet.
+        assert_eq!(schedule.tick(4_000, 4_000), PartialTick::Wait);
+        assert_eq!(schedule.tick(16_000, 16_000), PartialTick::Decode);
+        // Only a sliver of new audio since the last decode.
+        assert_eq!(schedule.tick(17_000, 17_000), PartialTick::Wait);
+        assert_eq!(schedule.tick(30_000, 30_000), PartialTick::Decode);
+
+        for _ in 1..PARTIAL_IDLE_TICKS {
+            assert_eq!(schedule.tick(30_000, 30_000), PartialTick::Wait);
+        }
+        assert_eq!(schedule.tick(30_000, 30_000), PartialTick::Stop);
+    }
+
+    #[test]
+    fn prune_and_take_keep_offsets_consistent() {
+        let mut stream = AudioStreamState::new();
+        stream.samples = vec![0.0; SAMPLE_RATE as usize * 3];
+        stream.prune(SAMPLE_RATE as usize);
+        assert_eq!(stream.samples.len(), SAMPLE_RATE as usize);
+        assert!((stream.time_offset_seconds - 2.0).abs() < 1e-6);
+
+        let (data, offset) = stream.take();
+        assert_eq!(data.len(), SAMPLE_RATE as usize);
+        assert!((offset - 2.0).abs() < 1e-6);
+        assert!(stream.samples.is_empty());
+        assert_eq!(stream.time_offset_seconds, 0.0);
+    }
+
+    #[test]
+    fn idle_pruning_keeps_audio_the_vad_has_not_seen() {
+        let buffer = AudioBuffer::new();
+        {
+            let mut stream = buffer.state.lock().unwrap();
+            stream.samples = vec![0.0; 20_000];
+            stream.vad_backlog = 4_000;
+        }
+        buffer.prune_idle(1_000);
+        assert_eq!(buffer.state.lock().unwrap().samples.len(), 5_000);
+
+        buffer.finish_vad_chunk(10_000);
+        assert_eq!(buffer.state.lock().unwrap().vad_backlog, 0);
+    }
+}
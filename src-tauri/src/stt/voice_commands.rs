@@ -0,0 +1,290 @@
+//! Voice command grammar — control phrases recognized in transcribed speech.
+//!
+//! A short utterance that matches a configured phrase ("be quiet", "switch to
+//! Hiyori", "删掉上一条") is routed to an engine command instead of the LLM.
+//! Only whole utterances match, so "can you stop talking about work" is still
+//! a chat message. Persisted to `voice_commands.json`.
+
+use crate::error::KokoroError;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Placeholder for the character name in `switch_character` phrases.
+pub const CHARACTER_SLOT: &str = "{character}";
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum VoiceIntent {
+    MuteTts,
+    UnmuteTts,
+    StopTalking,
+    SwitchCharacter,
+    DeleteLastMessage,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct VoiceCommandRule {
+    pub intent: VoiceIntent,
+    /// Phrases for this intent; `switch_character` phrases contain `{character}`.
+    pub phrases: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct VoiceCommandConfig {
+    pub enabled: bool,
+    pub commands: Vec<VoiceCommandRule>,
+}
+
+fn rule(intent: VoiceIntent, phrases: &[&str]) -> VoiceCommandRule {
+    VoiceCommandRule {
+        intent,
+        phrases: phrases.iter().map(|p| p.to_string()).collect(),
+    }
+}
+
+impl Default for VoiceCommandConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            commands: vec![
+                rule(
+                    VoiceIntent::MuteTts,
+                    &["be quiet", "mute", "mute voice", "静音", "安静", "ミュート"],
+                ),
+                rule(
+                    VoiceIntent::UnmuteTts,
+                    &["unmute", "you can talk again", "取消静音", "ミュート解除"],
+                ),
+                rule(
+                    VoiceIntent::StopTalking,
+                    &[
+                        "stop talking",
+                        "stop",
+                        "shut up",
+                        "别说了",
+                        "停",
+                        "停下",
+                        "止めて",
+                    ],
+                ),
+                rule(
+                    VoiceIntent::SwitchCharacter,
+                    &[
+                        "switch to {character}",
+                        "switch character to {character}",
+                        "切换到{character}",
+                        "换成{character}",
+                        "{character}に切り替えて",
+                    ],
+                ),
+                rule(
+                    VoiceIntent::DeleteLastMessage,
+                    &[
+                        "delete last message",
+                        "delete that",
+                        "undo that",
+                        "删掉上一条",
+                        "删除上一条消息",
+                        "撤回",
+                        "前のメッセージを削除",
+                    ],
+                ),
+            ],
+        }
+    }
+}
+
+impl VoiceCommandConfig {
+    pub fn validate(&self) -> Result<(), KokoroError> {
+        for rule in &self.commands {
+            for phrase in &rule.phrases {
+                let slots = phrase.matches(CHARACTER_SLOT).count();
+                let expected = usize::from(rule.intent == VoiceIntent::SwitchCharacter);
+                if slots != expected {
+                    return Err(KokoroError::Validation(format!(
+                        "voice command phrase '{}' must contain {} {} placeholder(s)",
+                        phrase, expected, CHARACTER_SLOT
+                    )));
+                }
+                if normalize(&phrase.replace(CHARACTER_SLOT, "")).is_empty() {
+                    return Err(KokoroError::Validation(format!(
+                        "voice command phrase '{}' has no words",
+                        phrase
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct VoiceCommandMatch {
+    pub intent: VoiceIntent,
+    /// Spoken character name for `switch_character`, as transcribed.
+    pub argument: Option<String>,
+}
+
+/// Politeness words around a command that don't change its meaning.
+const FILLERS: &[&str] = &["please", "ok", "okay", "hey", "请", "吧", "啊", "呀"];
+
+/// Lowercase, drop punctuation and collapse whitespace. Whitespace between
+/// CJK characters is dropped too, since STT engines insert it inconsistently.
+pub(crate) fn normalize(text: &str) -> String {
+    let cleaned: String = text
+        .to_lowercase()
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '\'' {
+                c
+            } else {
+                ' '
+            }
+        })
+        .collect();
+    let words: Vec<&str> = cleaned.split_whitespace().collect();
+    let mut out = String::new();
+    for word in words {
+        let joins_cjk = out.chars().last().is_some_and(|c| !c.is_ascii())
+            && word.chars().next().is_some_and(|c| !c.is_ascii());
+        if !out.is_empty() && !joins_cjk {
+            out.push(' ');
+        }
+        out.push_str(word);
+    }
+    out
+}
+
+fn strip_fillers(text: &str) -> &str {
+    let mut text = text.trim();
+    loop {
+        let before = text;
+        for filler in FILLERS {
+            // Whole words only for Latin fillers ("okay" must not lose its "ok").
+            let whole = |rest: &str, at_start: bool| {
+                !filler.is_ascii()
+                    || rest.is_empty()
+                    || if at_start {
+                        rest.starts_with(' ')
+                    } else {
+                        rest.ends_with(' ')
+                    }
+            };
+            if let Some(rest) = text.strip_prefix(filler).filter(|r| whole(r, true)) {
+                text = rest.trim_start();
+            }
+            if let Some(rest) = text.strip_suffix(filler).filter(|r| whole(r, false)) {
+                text = rest.trim_end();
+            }
+        }
+        if text == before {
+            return text;
+        }
+    }
+}
+
+/// Match a whole utterance against the configured phrases.
+pub fn match_command(config: &VoiceCommandConfig, text: &str) -> Option<VoiceCommandMatch> {
+    if !config.enabled {
+        return None;
+    }
+    let normalized = normalize(text);
+    let utterance = strip_fillers(&normalized);
+    if utterance.is_empty() {
+        return None;
+    }
+    for rule in &config.commands {
+        for phrase in &rule.phrases {
+            match phrase.split_once(CHARACTER_SLOT) {
+                None => {
+                    if normalize(phrase) == utterance {
+                        return Some(VoiceCommandMatch {
+                            intent: rule.intent,
+                            argument: None,
+                        });
+                    }
+                }
+                Some((prefix, suffix)) => {
+                    let (prefix, suffix) = (normalize(prefix), normalize(suffix));
+                    let name = utterance
+                        .strip_prefix(prefix.as_str())
+                        .and_then(|rest| rest.strip_suffix(suffix.as_str()))
+                        .map(str::trim)
+                        .filter(|name| !name.is_empty());
+                    if let Some(name) = name {
+                        return Some(VoiceCommandMatch {
+                            intent: rule.intent,
+                            argument: Some(name.to_string()),
+                        });
+                    }
+                }
+            }
+        }
+    }
+    None
+}
+
+pub fn voice_commands_config_path() -> PathBuf {
+    dirs_next::data_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("com.chyin.kokoro")
+        .join("voice_commands.json")
+}
+
+pub fn load_config(path: &Path) -> VoiceCommandConfig {
+    crate::config::load_json_config(path, "VOICE_COMMANDS")
+}
+
+pub fn save_config(path: &Path, config: &VoiceCommandConfig) -> Result<(), KokoroError> {
+    crate::config::save_json_config(path, config, "VOICE_COMMANDS")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn intent(text: &str) -> Option<(VoiceIntent, Option<String>)> {
+        match_command(&VoiceCommandConfig::default(), text).map(|m| (m.intent, m.argument))
+    }
+
+    #[test]
+    fn matches_whole_utterances_and_character_slot() {
+        assert_eq!(intent("Be quiet."), Some((VoiceIntent::MuteTts, None)));
+        assert_eq!(
+            intent("OK, stop talking please!"),
+            Some((VoiceIntent::StopTalking, None))
+        );
+        assert_eq!(intent("别说了吧"), Some((VoiceIntent::StopTalking, None)));
+        assert_eq!(
+            intent("Switch to Hiyori"),
+            Some((VoiceIntent::SwitchCharacter, Some("hiyori".to_string())))
+        );
+        assert_eq!(
+            intent("切换到 小樱。"),
+            Some((VoiceIntent::SwitchCharacter, Some("小樱".to_string())))
+        );
+        assert_eq!(
+            intent("删掉上一条"),
+            Some((VoiceIntent::DeleteLastMessage, None))
+        );
+
+        // Commands inside ordinary sentences go to the LLM.
+        assert_eq!(intent("can you stop talking about work"), None);
+        assert_eq!(intent("switch to"), None);
+        assert_eq!(intent("okay"), None);
+
+        let config = VoiceCommandConfig {
+            enabled: false,
+            ..VoiceCommandConfig::default()
+        };
+        assert_eq!(match_command(&config, "mute"), None);
+
+        assert!(VoiceCommandConfig::default().validate().is_ok());
+        let bad = VoiceCommandConfig {
+            enabled: true,
+            commands: vec![rule(VoiceIntent::MuteTts, &["mute {character}"])],
+        };
+        assert!(bad.validate().is_err());
+    }
+}
@@ -154,6 +154,8 @@ impl SttEngine for WhisperCppProvider {
                     end: s.end,
                     text: s.text,
                     confidence: None,
+                    speaker: None,
+                    role: None,
                 })
                 .collect()
         } else {
@@ -163,6 +165,8 @@ impl SttEngine for WhisperCppProvider {
                 end: duration_sec,
                 text: text.clone(),
                 confidence: None,
+                speaker: None,
+                role: None,
             }]
         };
 
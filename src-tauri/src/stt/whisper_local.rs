@@ -0,0 +1,581 @@
+//! In-process whisper.cpp backend via `whisper-rs`.
+//!
+//! Unlike `whisper_cpp`, which talks to an external `whisper-server`, this
+//! provider loads a ggml model directly and needs nothing but a download.
+
+use super::config::SttProviderConfig;
+use super::interface::{
+    AudioSource, SttEngine, SttError, TranscriptionResult, TranscriptionSegment,
+};
+use super::sensevoice_local::{app_data_dir, decode_wav_bytes};
+use async_trait::async_trait;
+use rubato::{FastFixedIn, PolynomialDegree, Resampler};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters};
+
+/// whisper.cpp only accepts 16 kHz mono input.
+const WHISPER_SAMPLE_RATE: u32 = 16_000;
+const RESAMPLER_CHUNK_SIZE: usize = 1024;
+const DEFAULT_MODEL_ID: &str = "base";
+const MODEL_BASE_URL: &str = "https://huggingface.co/ggerganov/whisper.cpp/resolve/main";
+/// Segments whisper itself flags as silence are dropped above this probability.
+const NO_SPEECH_THRESHOLD: f32 = 0.8;
+
+struct ModelSpec {
+    id: &'static str,
+    file_name: &'static str,
+    approx_size_mb: u32,
+}
+
+const MODEL_CATALOG: &[ModelSpec] = &[
+    ModelSpec {
+        id: "tiny",
+        file_name: "ggml-tiny.bin",
+        approx_size_mb: 75,
+    },
+    ModelSpec {
+        id: "base",
+        file_name: "ggml-base.bin",
+        approx_size_mb: 142,
+    },
+    ModelSpec {
+        id: "small",
+        file_name: "ggml-small.bin",
+        approx_size_mb: 466,
+    },
+    ModelSpec {
+        id: "medium",
+        file_name: "ggml-medium.bin",
+        approx_size_mb: 1500,
+    },
+    ModelSpec {
+        id: "large-v3-turbo",
+        file_name: "ggml-large-v3-turbo.bin",
+        approx_size_mb: 1620,
+    },
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WhisperLocalModelInfo {
+    pub id: String,
+    pub download_url: String,
+    pub model_path: String,
+    pub approx_size_mb: u32,
+    pub installed: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WhisperLocalGpuInfo {
+    /// Backend compiled into this build: "cuda", "vulkan", "metal" or "cpu".
+    pub backend: String,
+    /// Name of the detected GPU, if any.
+    pub device: Option<String>,
+    /// Whether inference will actually run on the GPU.
+    pub gpu_enabled: bool,
+    /// Set when a GPU was found that this build cannot use.
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WhisperLocalModelStatus {
+    pub install_dir: String,
+    pub default_model_id: String,
+    pub models: Vec<WhisperLocalModelInfo>,
+    pub gpu: WhisperLocalGpuInfo,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WhisperLocalDownloadProgress {
+    pub model_id: String,
+    pub stage: String,
+    pub message: String,
+    pub downloaded_bytes: u64,
+    pub total_bytes: Option<u64>,
+}
+
+fn install_dir() -> PathBuf {
+    app_data_dir().join("stt").join("whisper_local")
+}
+
+fn find_model(model_id: &str) -> Option<&'static ModelSpec> {
+    MODEL_CATALOG
+        .iter()
+        .find(|spec| spec.id.eq_ignore_ascii_case(model_id.trim()))
+}
+
+fn model_path_for(spec: &ModelSpec) -> PathBuf {
+    install_dir().join(spec.file_name)
+}
+
+fn download_url_for(spec: &ModelSpec) -> String {
+    format!("{}/{}", MODEL_BASE_URL, spec.file_name)
+}
+
+fn model_info(spec: &ModelSpec) -> WhisperLocalModelInfo {
+    let path = model_path_for(spec);
+    WhisperLocalModelInfo {
+        id: spec.id.to_string(),
+        download_url: download_url_for(spec),
+        model_path: path.to_string_lossy().into_owned(),
+        approx_size_mb: spec.approx_size_mb,
+        installed: path.is_file(),
+    }
+}
+
+pub fn model_status() -> WhisperLocalModelStatus {
+    WhisperLocalModelStatus {
+        install_dir: install_dir().to_string_lossy().into_owned(),
+        default_model_id: DEFAULT_MODEL_ID.to_string(),
+        models: MODEL_CATALOG.iter().map(model_info).collect(),
+        gpu: detect_gpu(),
+    }
+}
+
+/// True when any catalog model is on disk.
+pub fn any_model_installed() -> bool {
+    MODEL_CATALOG
+        .iter()
+        .any(|spec| model_path_for(spec).is_file())
+}
+
+pub async fn download_model<F>(
+    model_id: &str,
+    emit_progress: F,
+) -> Result<WhisperLocalModelStatus, String>
+where
+    F: Fn(WhisperLocalDownloadProgress) -> Result<(), String> + Send + Sync + 'static,
+{
+    let spec = find_model(model_id).ok_or_else(|| format!("Unknown whisper model: {model_id}"))?;
+    let emit_progress = Arc::new(emit_progress);
+    let model_path = model_path_for(spec);
+
+    if model_path.is_file() {
+        emit_progress(WhisperLocalDownloadProgress {
+            model_id: spec.id.to_string(),
+            stage: "ready".to_string(),
+            message: format!("Whisper model '{}' is already installed", spec.id),
+            downloaded_bytes: 0,
+            total_bytes: None,
+        })?;
+        return Ok(model_status());
+    }
+
+    fs::create_dir_all(install_dir()).map_err(|e| e.to_string())?;
+
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(1800))
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    let progress = crate::utils::download::download_file_with_progress(
+        &client,
+        &download_url_for(spec),
+        &model_path,
+        crate::utils::download::DownloadOptions::default(),
+        {
+            let emit_progress = emit_progress.clone();
+            let model_id = spec.id.to_string();
+            Arc::new(move |progress| {
+                emit_progress(WhisperLocalDownloadProgress {
+                    model_id: model_id.clone(),
+                    stage: "downloading".to_string(),
+                    message: format!("Downloading whisper model '{}'", model_id),
+                    downloaded_bytes: progress.downloaded_bytes,
+                    total_bytes: progress.total_bytes,
+                })
+            })
+        },
+    )
+    .await?;
+
+    emit_progress(WhisperLocalDownloadProgress {
+        model_id: spec.id.to_string(),
+        stage: "complete".to_string(),
+        message: format!("Whisper model '{}' installed", spec.id),
+        downloaded_bytes: progress.downloaded_bytes,
+        total_bytes: progress.total_bytes,
+    })?;
+
+    Ok(model_status())
+}
+
+// ── GPU detection ──────────────────────────────────────
+
+fn compiled_backend() -> &'static str {
+    if cfg!(feature = "whisper-cuda") {
+        "cuda"
+    } else if cfg!(feature = "whisper-vulkan") {
+        "vulkan"
+    } else if cfg!(target_os = "macos") {
+        "metal"
+    } else {
+        "cpu"
+    }
+}
+
+/// Names of NVIDIA GPUs reported by the driver, empty when none or no driver.
+fn nvidia_devices() -> Vec<String> {
+    std::process::Command::new("nvidia-smi")
+        .args(["--query-gpu=name", "--format=csv,noheader"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| {
+            String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(feature = "whisper-vulkan")]
+fn vulkan_devices() -> Vec<String> {
+    whisper_rs::vulkan::list_devices()
+        .into_iter()
+        .map(|device| device.name)
+        .collect()
+}
+
+#[cfg(not(feature = "whisper-vulkan"))]
+fn vulkan_devices() -> Vec<String> {
+    Vec::new()
+}
+
+/// Work out which accelerator the embedded backend can use on this machine.
+pub fn detect_gpu() -> WhisperLocalGpuInfo {
+    let backend = compiled_backend();
+    let (device, note) = match backend {
+        "cuda" => (nvidia_devices().into_iter().next(), None),
+        "vulkan" => (vulkan_devices().into_iter().next(), None),
+        "metal" if cfg!(target_arch = "aarch64") => (Some("Apple Silicon GPU".to_string()), None),
+        "metal" => (None, None),
+        _ => {
+            let note = nvidia_devices().into_iter().next().map(|name| {
+                format!("{name} detected, but this build has no GPU support for whisper; rebuild with the whisper-cuda feature")
+            });
+            (None, note)
+        }
+    };
+
+    WhisperLocalGpuInfo {
+        backend: backend.to_string(),
+        gpu_enabled: device.is_some(),
+        device,
+        note,
+    }
+}
+
+// ── Audio preparation ──────────────────────────────────
+
+/// Resample mono audio to whisper's 16 kHz input rate.
+fn resample_to_whisper_rate(samples: &[f32], sample_rate: u32) -> Result<Vec<f32>, SttError> {
+    if sample_rate == 0 {
+        return Err(SttError::AudioFormatInvalid(
+            "Audio has a sample rate of 0".to_string(),
+        ));
+    }
+    if sample_rate == WHISPER_SAMPLE_RATE || samples.is_empty() {
+        return Ok(samples.to_vec());
+    }
+
+    let ratio = WHISPER_SAMPLE_RATE as f64 / sample_rate as f64;
+    let mut resampler =
+        FastFixedIn::<f32>::new(ratio, 1.0, PolynomialDegree::Cubic, RESAMPLER_CHUNK_SIZE, 1)
+            .map_err(|e| {
+                SttError::AudioFormatInvalid(format!("Failed to create resampler: {e}"))
+            })?;
+    let resample_err =
+        |e: rubato::ResampleError| SttError::AudioFormatInvalid(format!("Resampling failed: {e}"));
+
+    let expected_len = (samples.len() as f64 * ratio).round() as usize;
+    let delay = resampler.output_delay();
+    let mut output = Vec::with_capacity(expected_len + delay + RESAMPLER_CHUNK_SIZE);
+    let mut remaining = samples;
+
+    while remaining.len() >= resampler.input_frames_next() {
+        let needed = resampler.input_frames_next();
+        let processed = resampler
+            .process(&[&remaining[..needed]], None)
+            .map_err(resample_err)?;
+        output.extend_from_slice(&processed[0]);
+        remaining = &remaining[needed..];
+    }
+    if !remaining.is_empty() {
+        let processed = resampler
+            .process_partial(Some(&[remaining]), None)
+            .map_err(resample_err)?;
+        output.extend_from_slice(&processed[0]);
+    }
+    // Flush the filter delay so the tail of the utterance is not cut off.
+    while output.len() < expected_len + delay {
+        let processed = resampler
+            .process_partial::<&[f32]>(None, None)
+            .map_err(resample_err)?;
+        if processed[0].is_empty() {
+            break;
+        }
+        output.extend_from_slice(&processed[0]);
+    }
+
+    output.drain(..delay.min(output.len()));
+    output.truncate(expected_len);
+    Ok(output)
+}
+
+/// Map whisper's centisecond timestamps onto transcript segments.
+fn to_segment(
+    start_cs: i64,
+    end_cs: i64,
+    text: &str,
+    no_speech: f32,
+) -> Option<TranscriptionSegment> {
+    let text = text.trim();
+    if text.is_empty() || no_speech > NO_SPEECH_THRESHOLD {
+        return None;
+    }
+    Some(TranscriptionSegment {
+        start: start_cs.max(0) as f32 / 100.0,
+        end: end_cs.max(start_cs).max(0) as f32 / 100.0,
+        text: text.to_string(),
+        confidence: Some((1.0 - no_speech).clamp(0.0, 1.0)),
+        speaker: None,
+        role: None,
+    })
+}
+
+fn join_segments(segments: &[TranscriptionSegment]) -> String {
+    segments
+        .iter()
+        .map(|segment| segment.text.as_str())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// ── Provider ───────────────────────────────────────────
+
+pub struct WhisperLocalProvider {
+    provider_id: String,
+    model_path: PathBuf,
+    num_threads: i32,
+    /// Loaded lazily on first use; model loading takes seconds for larger models.
+    context: Mutex<Option<Arc<WhisperContext>>>,
+}
+
+impl WhisperLocalProvider {
+    pub fn new(config: &SttProviderConfig) -> Self {
+        let model_path = config
+            .model_path
+            .as_ref()
+            .map(PathBuf::from)
+            .filter(|path| !path.as_os_str().is_empty())
+            .unwrap_or_else(|| {
+                let model_id = config.model.as_deref().unwrap_or(DEFAULT_MODEL_ID);
+                let spec = find_model(model_id)
+                    .or_else(|| find_model(DEFAULT_MODEL_ID))
+                    .expect("default whisper model is in the catalog");
+                model_path_for(spec)
+            });
+
+        let default_threads = std::thread::available_parallelism()
+            .map(|n| n.get().min(8) as i32)
+            .unwrap_or(4);
+
+        Self {
+            provider_id: config.id.clone(),
+            model_path,
+            num_threads: config.num_threads.unwrap_or(default_threads).max(1),
+            context: Mutex::new(None),
+        }
+    }
+
+    async fn context(&self) -> Result<Arc<WhisperContext>, SttError> {
+        let mut guard = self.context.lock().await;
+        if let Some(context) = guard.as_ref() {
+            return Ok(context.clone());
+        }
+        if !self.model_path.is_file() {
+            return Err(SttError::ModelNotLoaded);
+        }
+
+        let gpu = detect_gpu();
+        tracing::info!(
+            target: "stt",
+            "[STT] Loading whisper model {} (backend: {}, gpu: {})",
+            self.model_path.display(),
+            gpu.backend,
+            gpu.device.as_deref().unwrap_or("none")
+        );
+
+        let model_path = self.model_path.clone();
+        let context =
+            tokio::task::spawn_blocking(move || load_context(&model_path, gpu.gpu_enabled))
+                .await
+                .map_err(|e| SttError::Unknown(e.to_string()))??;
+        let context = Arc::new(context);
+        *guard = Some(context.clone());
+        Ok(context)
+    }
+
+    fn decode_source(audio: &AudioSource) -> Result<(u32, Vec<f32>), SttError> {
+        match audio {
+            AudioSource::Chunk(chunk) => Ok((chunk.sample_rate, chunk.samples.as_ref().clone())),
+            AudioSource::Encoded { data, format } => {
+                if !format.eq_ignore_ascii_case("wav") {
+                    return Err(SttError::AudioFormatInvalid(
+                        "whisper_local currently supports WAV input for encoded audio".to_string(),
+                    ));
+                }
+                let (rate, samples) = decode_wav_bytes(data)?;
+                Ok((rate as u32, samples))
+            }
+        }
+    }
+}
+
+fn load_context(model_path: &Path, use_gpu: bool) -> Result<WhisperContext, SttError> {
+    whisper_rs::install_logging_hooks();
+    let mut params = WhisperContextParameters::default();
+    params.use_gpu(use_gpu);
+
+    match WhisperContext::new_with_params(model_path, params) {
+        Ok(context) => Ok(context),
+        Err(e) if use_gpu => {
+            tracing::warn!(target: "stt", "[STT] whisper GPU init failed ({e}), falling back to CPU");
+            let mut params = WhisperContextParameters::default();
+            params.use_gpu(false);
+            WhisperContext::new_with_params(model_path, params).map_err(|e| {
+                SttError::EngineUnavailable(format!("Failed to load whisper model: {e}"))
+            })
+        }
+        Err(e) => Err(SttError::EngineUnavailable(format!(
+            "Failed to load whisper model: {e}"
+        ))),
+    }
+}
+
+fn run_whisper(
+    context: &WhisperContext,
+    samples: &[f32],
+    language: Option<&str>,
+    num_threads: i32,
+) -> Result<Vec<TranscriptionSegment>, SttError> {
+    let mut state = context
+        .create_state()
+        .map_err(|e| SttError::EngineUnavailable(format!("Failed to create whisper state: {e}")))?;
+
+    let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+    params.set_n_threads(num_threads);
+    params.set_language(Some(language.unwrap_or("auto")));
+    params.set_translate(false);
+    params.set_no_context(true);
+    params.set_suppress_blank(true);
+    params.set_print_special(false);
+    params.set_print_progress(false);
+    params.set_print_realtime(false);
+    params.set_print_timestamps(false);
+
+    state
+        .full(params, samples)
+        .map_err(|e| SttError::ChunkFailed(format!("whisper inference failed: {e}")))?;
+
+    let mut segments = Vec::new();
+    for segment in state.as_iter() {
+        let text = segment
+            .to_str_lossy()
+            .map_err(|e| SttError::ChunkFailed(e.to_string()))?;
+        if let Some(segment) = to_segment(
+            segment.start_timestamp(),
+            segment.end_timestamp(),
+            &text,
+            segment.no_speech_probability(),
+        ) {
+            segments.push(segment);
+        }
+    }
+    Ok(segments)
+}
+
+#[async_trait]
+impl SttEngine for WhisperLocalProvider {
+    fn id(&self) -> String {
+        self.provider_id.clone()
+    }
+
+    async fn is_available(&self) -> bool {
+        self.model_path.is_file()
+    }
+
+    async fn transcribe(
+        &self,
+        audio: &AudioSource,
+        language: Option<&str>,
+    ) -> Result<TranscriptionResult, SttError> {
+        let start_time = std::time::Instant::now();
+        let context = self.context().await?;
+
+        let (sample_rate, samples) = Self::decode_source(audio)?;
+        let samples = resample_to_whisper_rate(&samples, sample_rate)?;
+        let language = language
+            .map(|value| value.trim().to_lowercase())
+            .filter(|value| !value.is_empty());
+        let num_threads = self.num_threads;
+
+        let segments = tokio::task::spawn_blocking(move || {
+            run_whisper(&context, &samples, language.as_deref(), num_threads)
+        })
+        .await
+        .map_err(|e| SttError::Unknown(e.to_string()))??;
+
+        Ok(TranscriptionResult {
+            text: join_segments(&segments),
+            segments,
+            processing_time: start_time.elapsed(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn catalog_lookup_is_case_insensitive_and_has_default() {
+        assert!(find_model(DEFAULT_MODEL_ID).is_some());
+        assert_eq!(find_model(" Small ").map(|spec| spec.id), Some("small"));
+        assert!(find_model("gigantic").is_none());
+        assert!(download_url_for(find_model("tiny").unwrap()).ends_with("/ggml-tiny.bin"));
+    }
+
+    #[test]
+    fn resample_produces_expected_length() {
+        let samples: Vec<f32> = (0..48_000)
+            .map(|i| (i as f32 * 440.0 * std::f32::consts::TAU / 48_000.0).sin())
+            .collect();
+        let resampled = resample_to_whisper_rate(&samples, 48_000).unwrap();
+        assert_eq!(resampled.len(), 16_000);
+
+        let passthrough = resample_to_whisper_rate(&samples[..100], 16_000).unwrap();
+        assert_eq!(passthrough.len(), 100);
+        assert!(resample_to_whisper_rate(&samples, 0).is_err());
+    }
+
+    #[test]
+    fn segments_convert_centiseconds_and_skip_silence() {
+        let segment = to_segment(150, 320, "  hello there ", 0.1).unwrap();
+        assert_eq!(segment.start, 1.5);
+        assert_eq!(segment.end, 3.2);
+        assert_eq!(segment.text, "hello there");
+        assert!(to_segment(0, 100, "[BLANK]", 0.95).is_none());
+        assert!(to_segment(0, 100, "   ", 0.0).is_none());
+
+        let joined = join_segments(&[segment.clone(), segment]);
+        assert_eq!(joined, "hello there hello there");
+    }
+}
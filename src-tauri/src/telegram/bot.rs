@@ -4,8 +4,9 @@
 
 use super::config::TelegramConfig;
 use crate::actions::tool_settings::ToolSettings;
-use crate::actions::{execute_tool_calls, ToolInvocation};
+use crate::actions::{execute_tool_calls, native_tool_continuation_messages, ToolInvocation};
 use crate::ai::context::AIOrchestrator;
+use crate::ai::history::ChatSurface;
 use crate::ai::memory_event_ingress::{
     build_cooldown_key, select_memory_ingress_decision, should_use_structured_extraction,
     MemoryEventIngressOptions,
@@ -16,17 +17,18 @@ use crate::llm::messages::{
     assistant_text_message, is_user_message, replace_user_message_with_images, role_text_message,
     system_message, user_message_with_images, user_text_message,
 };
+use crate::llm::provider::{collect_stream_round, LlmToolCall};
 use crate::llm::service::LlmService;
 use crate::stt::{AudioSource, SttService};
 use crate::tts::TtsService;
-use futures::StreamExt;
+use crate::utils::segment;
 use serde::Serialize;
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tauri::{Emitter, Manager};
 use teloxide::prelude::*;
-use teloxide::types::InputFile;
+use teloxide::types::{InlineKeyboardButton, InlineKeyboardMarkup, InputFile, MessageId};
 use tokio::sync::{oneshot, RwLock};
 
 /// 每用户速率限制：滑动窗口内最多允许的消息数
@@ -47,12 +49,60 @@ type RateLimiter = Arc<RwLock<HashMap<ChatId, RateState>>>;
 enum SessionMode {
     /// Continue the desktop conversation (default).
     Continue,
-    /// Fresh conversation started via /new.
+    /// Fresh conversation started via /new, kept apart from the desktop's.
     New,
 }
 
 type Sessions = Arc<RwLock<HashMap<ChatId, SessionMode>>>;
 
+/// Whose conversation a chat's turns belong to.
+async fn chat_surface(sessions: &Sessions, chat_id: ChatId) -> ChatSurface {
+    match sessions.read().await.get(&chat_id) {
+        Some(SessionMode::New) => ChatSurface::Telegram(chat_id.0),
+        _ => ChatSurface::Desktop,
+    }
+}
+
+/// The newest reply in a chat, the only one that can be regenerated.
+#[derive(Clone, Debug)]
+struct LastReply {
+    /// The chunk carrying the inline keyboard.
+    message_id: MessageId,
+    /// The stored assistant text, without the translation.
+    response: String,
+}
+
+type LastReplies = Arc<RwLock<HashMap<ChatId, LastReply>>>;
+
+/// Callback data of the inline buttons under a reply.
+const CALLBACK_REGENERATE: &str = "regen";
+const CALLBACK_SWITCH_CHARACTER: &str = "chars";
+const CALLBACK_VOICE: &str = "voice";
+/// Prefix of a pick in the character menu; the character id follows.
+const CALLBACK_PICK_CHARACTER: &str = "char:";
+/// Telegram rejects longer callback data.
+const MAX_CALLBACK_DATA_LEN: usize = 64;
+
+#[derive(Debug, PartialEq, Eq)]
+enum CallbackAction<'a> {
+    Regenerate,
+    SwitchCharacter,
+    Voice,
+    PickCharacter(&'a str),
+}
+
+fn parse_callback_data(data: &str) -> Option<CallbackAction<'_>> {
+    match data {
+        CALLBACK_REGENERATE => Some(CallbackAction::Regenerate),
+        CALLBACK_SWITCH_CHARACTER => Some(CallbackAction::SwitchCharacter),
+        CALLBACK_VOICE => Some(CallbackAction::Voice),
+        _ => data
+            .strip_prefix(CALLBACK_PICK_CHARACTER)
+            .filter(|id| !id.is_empty())
+            .map(CallbackAction::PickCharacter),
+    }
+}
+
 async fn max_tool_rounds(app: &tauri::AppHandle) -> usize {
     if let Some(settings) = app.try_state::<Arc<RwLock<ToolSettings>>>() {
         settings.read().await.max_tool_rounds.max(1)
@@ -68,6 +118,9 @@ struct TelegramChatSync {
     text: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     translation: Option<String>,
+    /// Replaces the last assistant message instead of appending (regenerate).
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    replaces_last: bool,
 }
 
 /// Run the long-polling loop. Blocks until `shutdown_rx` fires or an error occurs.
@@ -80,16 +133,20 @@ pub async fn run_polling(
     let bot = Bot::new(&token);
     let sessions: Sessions = Arc::new(RwLock::new(HashMap::new()));
     let rate_limiter: RateLimiter = Arc::new(RwLock::new(HashMap::new()));
+    let last_replies: LastReplies = Arc::new(RwLock::new(HashMap::new()));
 
     // Build the update handler
-    let handler = Update::filter_message().endpoint(handle_message);
+    let handler = dptree::entry()
+        .branch(Update::filter_message().endpoint(handle_message))
+        .branch(Update::filter_callback_query().endpoint(handle_callback));
 
     let mut dispatcher = Dispatcher::builder(bot.clone(), handler)
         .dependencies(dptree::deps![
             config.clone(),
             sessions.clone(),
             app.clone(),
-            rate_limiter.clone()
+            rate_limiter.clone(),
+            last_replies.clone()
         ])
         .default_handler(|_upd| async {})
         .build();
@@ -132,6 +189,7 @@ async fn handle_message(
     sessions: Sessions,
     app: tauri::AppHandle,
     rate_limiter: RateLimiter,
+    last_replies: LastReplies,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     let chat_id = msg.chat.id;
     // 不记录消息内容，避免敏感信息泄露到日志
@@ -140,8 +198,17 @@ async fn handle_message(
     // Snapshot config for this request
     let config = Arc::new(config.read().await.clone());
 
-    // Access control: check whitelist
-    if !config.allowed_chat_ids.is_empty() && !config.allowed_chat_ids.contains(&chat_id.0) {
+    // Access control: check whitelist. `/authorize` is the one command an
+    // unknown chat may send, so it can pair itself.
+    let authorize_code = msg
+        .text()
+        .and_then(parse_command)
+        .filter(|(command, _)| *command == "/authorize")
+        .map(|(_, code)| code);
+    if authorize_code.is_none()
+        && !config.allowed_chat_ids.is_empty()
+        && !config.allowed_chat_ids.contains(&chat_id.0)
+    {
         tracing::info!(target: "telegram", "[Telegram] Chat {} not in whitelist, ignoring", chat_id.0);
         return Ok(());
     }
@@ -167,31 +234,279 @@ async fn handle_message(
         }
     }
 
+    if let Some(code) = authorize_code {
+        handle_authorize(&bot, chat_id, code, &config, &app).await;
+        return Ok(());
+    }
+
     // Check for commands
     if let Some(text) = msg.text() {
         if text.starts_with('/') {
-            return handle_command(&bot, &msg, text, &config, &sessions, &app).await;
+            return handle_command(&bot, &msg, text, &config, &sessions, &app, &last_replies).await;
         }
     }
 
     // Voice message
     if msg.voice().is_some() {
-        return handle_voice(&bot, &msg, &config, &sessions, &app).await;
+        return handle_voice(&bot, &msg, &config, &sessions, &app, &last_replies).await;
     }
 
     // Photo message (with optional caption)
     if msg.photo().is_some() {
-        return handle_photo(&bot, &msg, &config, &app).await;
+        return handle_photo(&bot, &msg, &config, &sessions, &app).await;
     }
 
     // Regular text message
     if let Some(text) = msg.text() {
-        return handle_text(&bot, &msg, text, &config, &app).await;
+        let surface = chat_surface(&sessions, chat_id).await;
+        return handle_text(&bot, &msg, text, surface, &config, &app, &last_replies).await;
+    }
+
+    Ok(())
+}
+
+/// Inline-button handler for the keyboards under bot replies.
+async fn handle_callback(
+    bot: Bot,
+    q: CallbackQuery,
+    config: Arc<RwLock<TelegramConfig>>,
+    sessions: Sessions,
+    app: tauri::AppHandle,
+    last_replies: LastReplies,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let Some(message) = q.message.as_ref() else {
+        bot.answer_callback_query(q.id.clone()).await.ok();
+        return Ok(());
+    };
+    let chat_id = message.chat().id;
+    let message_id = message.id();
+
+    let config = Arc::new(config.read().await.clone());
+    if !config.allowed_chat_ids.is_empty() && !config.allowed_chat_ids.contains(&chat_id.0) {
+        bot.answer_callback_query(q.id.clone()).await.ok();
+        return Ok(());
+    }
+
+    let action = q.data.as_deref().and_then(parse_callback_data);
+    let surface = chat_surface(&sessions, chat_id).await;
+    let regenerate = match action {
+        Some(CallbackAction::Regenerate) => Some(
+            take_reply_for_regeneration(chat_id, message_id, surface, &app, &last_replies).await,
+        ),
+        _ => None,
+    };
+    // Answer right away so the button stops spinning; refusals show as a toast.
+    let mut answer = bot.answer_callback_query(q.id.clone());
+    if let Some(Err(notice)) = &regenerate {
+        answer = answer.text(*notice);
+    }
+    answer.await.ok();
+
+    match action {
+        Some(CallbackAction::Regenerate) => {
+            if let Some(Ok(text)) = regenerate {
+                bot.edit_message_reply_markup(chat_id, message_id)
+                    .await
+                    .ok();
+                reply_to_turn(
+                    &bot,
+                    chat_id,
+                    &text,
+                    surface,
+                    &config,
+                    &app,
+                    &last_replies,
+                    false,
+                )
+                .await?;
+            }
+        }
+        Some(CallbackAction::SwitchCharacter) => {
+            send_character_menu(&bot, chat_id, &config, &app).await?;
+        }
+        Some(CallbackAction::PickCharacter(character_id)) => {
+            let reply = if !can_switch_character(&config) {
+                "🎭 This bot is pinned to one character in the desktop settings.".to_string()
+            } else {
+                match crate::character_profiles::activate_character(&app, character_id).await {
+                    Ok(switched) => format!("🎭 Switched to {}.", switched.name),
+                    Err(e) => {
+                        tracing::error!(target: "telegram", "[Telegram] Character switch to '{}' failed: {}", character_id, e);
+                        format!("❌ {}", e)
+                    }
+                }
+            };
+            bot.edit_message_text(chat_id, message_id, reply).await.ok();
+        }
+        Some(CallbackAction::Voice) => {
+            let latest = last_replies
+                .read()
+                .await
+                .get(&chat_id)
+                .filter(|reply| reply.message_id == message_id)
+                .map(|reply| reply.response.clone());
+            // Older replies only have the chunk the keyboard hangs on.
+            let text = latest.or_else(|| {
+                message
+                    .regular_message()
+                    .and_then(|m| m.text())
+                    .map(|text| text.split_once("\n\n📝 ").map_or(text, |(reply, _)| reply))
+                    .map(str::to_string)
+            });
+            if let Some(text) = text {
+                send_voice_reply(&bot, chat_id, &text, &app).await;
+            }
+        }
+        None => {}
     }
 
     Ok(())
 }
 
+/// Drop the newest reply from the conversation so it can be generated again.
+/// Returns the user turn it answered, or a notice for the user.
+async fn take_reply_for_regeneration(
+    chat_id: ChatId,
+    message_id: MessageId,
+    surface: ChatSurface,
+    app: &tauri::AppHandle,
+    last_replies: &LastReplies,
+) -> Result<String, &'static str> {
+    // Held throughout so a double tap can't remove two messages.
+    let mut replies = last_replies.write().await;
+    let latest = replies
+        .get(&chat_id)
+        .filter(|reply| reply.message_id == message_id)
+        .ok_or("Only the latest reply can be regenerated.")?;
+    let orchestrator = app
+        .try_state::<AIOrchestrator>()
+        .ok_or("Chat is not ready yet.")?;
+
+    let user_text = {
+        let history = orchestrator.history_on(surface).await;
+        let mut recent = history.iter().rev();
+        let reply_is_last = recent
+            .next()
+            .is_some_and(|m| m.role == "assistant" && m.content == latest.response);
+        if !reply_is_last {
+            return Err("The conversation has moved on since this reply.");
+        }
+        recent
+            .next()
+            .filter(|m| m.role == "user")
+            .map(|m| m.content.clone())
+            .ok_or("This reply can't be regenerated.")?
+    };
+
+    if let Err(e) =
+        crate::commands::context::delete_last_messages_on(&orchestrator, surface, 1).await
+    {
+        tracing::error!(target: "telegram", "[Telegram] Failed to drop reply for regeneration: {}", e);
+        return Err("Could not remove the old reply.");
+    }
+    replies.remove(&chat_id);
+    Ok(user_text)
+}
+
+/// Offer the characters as buttons. Picking one switches the desktop as well.
+async fn send_character_menu(
+    bot: &Bot,
+    chat_id: ChatId,
+    config: &TelegramConfig,
+    app: &tauri::AppHandle,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    if !can_switch_character(config) {
+        bot.send_message(
+            chat_id,
+            "🎭 This bot is pinned to one character in the desktop settings.",
+        )
+        .await
+        .ok();
+        return Ok(());
+    }
+    let orchestrator = app
+        .try_state::<AIOrchestrator>()
+        .ok_or("AIOrchestrator not available")?;
+    let active = desktop_char_id(&orchestrator).await;
+    let characters: Vec<(String, String)> =
+        sqlx::query_as("SELECT id, name FROM characters ORDER BY created_at ASC")
+            .fetch_all(&orchestrator.db)
+            .await?;
+
+    let rows: Vec<Vec<InlineKeyboardButton>> = characters
+        .into_iter()
+        .filter_map(|(id, name)| {
+            let data = format!("{}{}", CALLBACK_PICK_CHARACTER, id);
+            if data.len() > MAX_CALLBACK_DATA_LEN {
+                return None;
+            }
+            let label = if id == active {
+                format!("✓ {}", name)
+            } else {
+                name
+            };
+            Some(vec![InlineKeyboardButton::callback(label, data)])
+        })
+        .collect();
+    if rows.is_empty() {
+        bot.send_message(chat_id, "🎭 No characters to switch to.")
+            .await
+            .ok();
+        return Ok(());
+    }
+    bot.send_message(chat_id, "🎭 Choose a character:")
+        .reply_markup(InlineKeyboardMarkup::new(rows))
+        .await?;
+    Ok(())
+}
+
+/// Handle `/authorize <code>`: add this chat to the whitelist with the
+/// pairing code shown on the desktop.
+async fn handle_authorize(
+    bot: &Bot,
+    chat_id: ChatId,
+    code: &str,
+    config: &TelegramConfig,
+    app: &tauri::AppHandle,
+) {
+    let reply = if config.allowed_chat_ids.contains(&chat_id.0) {
+        "✅ This chat is already authorized.".to_string()
+    } else if code.is_empty() {
+        "Usage: /authorize <code>\nGenerate the code under Settings → Bots in the desktop app."
+            .to_string()
+    } else if let Some(service) = app.try_state::<super::TelegramService>() {
+        match service.authorize_chat(chat_id.0, code).await {
+            Ok(_) => {
+                tracing::info!(target: "telegram", "[Telegram] Chat {} authorized via pairing code", chat_id.0);
+                let _ = app.emit(
+                    "telegram:chat-authorized",
+                    serde_json::json!({ "chat_id": chat_id.0 }),
+                );
+                "✅ Chat authorized. You can talk to me here now!".to_string()
+            }
+            Err(e) => {
+                tracing::info!(target: "telegram", "[Telegram] Pairing failed for chat_id={}: {}", chat_id.0, e);
+                format!("❌ {}", e)
+            }
+        }
+    } else {
+        "❌ Telegram service unavailable.".to_string()
+    };
+    bot.send_message(chat_id, reply).await.ok();
+}
+
+/// Split a command message into the command word and the rest of the text.
+/// In group chats Telegram sends commands as `/command@BotName`; the
+/// `@BotName` suffix is dropped.
+fn parse_command(text: &str) -> Option<(&str, &str)> {
+    if !text.starts_with('/') {
+        return None;
+    }
+    let (word, rest) = text.split_once(char::is_whitespace).unwrap_or((text, ""));
+    let command = word.split_once('@').map_or(word, |(command, _)| command);
+    Some((command, rest.trim()))
+}
+
 /// Handle bot commands: /start, /new, /continue, /status
 async fn handle_command(
     bot: &Bot,
@@ -200,9 +515,10 @@ async fn handle_command(
     _config: &Arc<TelegramConfig>,
     sessions: &Sessions,
     app: &tauri::AppHandle,
+    last_replies: &LastReplies,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     let chat_id = msg.chat.id;
-    let cmd = text.split_whitespace().next().unwrap_or("");
+    let cmd = parse_command(text).map_or("", |(command, _)| command);
 
     match cmd {
         "/start" => {
@@ -210,20 +526,20 @@ async fn handle_command(
                 Commands:\n\
                 /continue — Resume the desktop conversation\n\
                 /new — Start a fresh conversation\n\
-                /status — Show current session info\n\n\
+                /status — Show current session info\n\
+                /authorize <code> — Pair this chat using the desktop code\n\n\
                 Just send a text or voice message to chat!";
             if let Err(e) = bot.send_message(chat_id, text).await {
                 tracing::error!(target: "telegram", "[Telegram] Failed to send /start reply: {}", e);
             }
         }
         "/new" => {
-            // Clear orchestrator history to start fresh
+            // Start fresh in a conversation of this chat's own; the desktop's
+            // conversation is left alone
             if let Some(orchestrator) = app.try_state::<AIOrchestrator>() {
-                let mut history = orchestrator.history.lock().await;
-                history.clear();
-                drop(history);
-                let mut conv_id = orchestrator.current_conversation_id.lock().await;
-                *conv_id = None;
+                orchestrator
+                    .new_conversation_on(ChatSurface::Telegram(chat_id.0))
+                    .await;
             }
             {
                 let mut s = sessions.write().await;
@@ -243,16 +559,13 @@ async fn handle_command(
                 .ok();
         }
         "/status" => {
-            let mode = {
-                let s = sessions.read().await;
-                s.get(&chat_id).cloned().unwrap_or(SessionMode::Continue)
-            };
-            let mode_str = match mode {
-                SessionMode::Continue => "Continue (desktop)",
-                SessionMode::New => "New conversation",
+            let surface = chat_surface(sessions, chat_id).await;
+            let mode_str = match surface {
+                ChatSurface::Desktop => "Continue (desktop)",
+                _ => "New conversation",
             };
             let history_len = if let Some(orchestrator) = app.try_state::<AIOrchestrator>() {
-                orchestrator.history.lock().await.len()
+                orchestrator.history_on(surface).await.len()
             } else {
                 0
             };
@@ -270,7 +583,8 @@ async fn handle_command(
             // Unknown command — treat as text
             let clean = text.trim_start_matches('/');
             if !clean.is_empty() {
-                handle_text(bot, msg, text, _config, app).await?;
+                let surface = chat_surface(sessions, chat_id).await;
+                handle_text(bot, msg, text, surface, _config, app, last_replies).await?;
             }
         }
     }
@@ -278,40 +592,36 @@ async fn handle_command(
     Ok(())
 }
 
-/// Handle a plain text message — run through the LLM pipeline and reply.
+/// Handle a plain text message — record the user turn, then reply.
 async fn handle_text(
     bot: &Bot,
     msg: &Message,
     text: &str,
+    surface: ChatSurface,
     config: &Arc<TelegramConfig>,
     app: &tauri::AppHandle,
+    last_replies: &LastReplies,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     let chat_id = msg.chat.id;
 
     let orchestrator = app
         .try_state::<AIOrchestrator>()
         .ok_or("AIOrchestrator not available")?;
-    let llm_service = app
-        .try_state::<LlmService>()
-        .ok_or("LlmService not available")?;
 
     // 1. Record user message
     // char_id 解析优先级：config 指定 > orchestrator 内存状态 > 磁盘文件 > "default"
-    let char_id = match config.character_id.as_deref().filter(|s| !s.is_empty()) {
-        Some(id) => id.to_string(),
-        None => {
-            let mem_id = orchestrator.get_character_id().await;
-            if !mem_id.is_empty() && mem_id != "default" {
-                mem_id
-            } else {
-                crate::ai::context::AIOrchestrator::load_active_character_id()
-                    .unwrap_or_else(|| "default".to_string())
-            }
-        }
-    };
+    let char_id = resolve_char_id(config, &orchestrator).await;
     tracing::info!(target: "telegram", "[Telegram] Resolved char_id='{}' for this request", char_id);
+    let user_metadata = orchestrator.user_message_metadata(text).await;
     orchestrator
-        .add_message("user".to_string(), text.to_string(), &char_id)
+        .add_message_on(
+            surface,
+            "user".to_string(),
+            text.to_string(),
+            user_metadata,
+            &char_id,
+            None,
+        )
         .await;
 
     // Sync user message to desktop UI
@@ -321,9 +631,34 @@ async fn handle_text(
             role: "user".to_string(),
             text: text.to_string(),
             translation: None,
+            replaces_last: false,
         },
     );
 
+    reply_to_turn(bot, chat_id, text, surface, config, app, last_replies, true).await
+}
+
+/// Run the recorded user turn `text` through the LLM pipeline and reply.
+/// `fresh_turn` is false when regenerating, so memory triggers don't fire twice.
+#[allow(clippy::too_many_arguments)]
+async fn reply_to_turn(
+    bot: &Bot,
+    chat_id: ChatId,
+    text: &str,
+    surface: ChatSurface,
+    config: &Arc<TelegramConfig>,
+    app: &tauri::AppHandle,
+    last_replies: &LastReplies,
+    fresh_turn: bool,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let orchestrator = app
+        .try_state::<AIOrchestrator>()
+        .ok_or("AIOrchestrator not available")?;
+    let llm_service = app
+        .try_state::<LlmService>()
+        .ok_or("LlmService not available")?;
+    let char_id = resolve_char_id(config, &orchestrator).await;
+
     // 2. Compose prompt context (with tool prompt)
     let action_registry = app
         .try_state::<Arc<RwLock<crate::actions::ActionRegistry>>>()
@@ -331,7 +666,20 @@ async fn handle_text(
     let tool_settings = app
         .try_state::<Arc<RwLock<ToolSettings>>>()
         .ok_or("ToolSettings not available")?;
-    let tool_prompt = {
+    // Providers with function calling get structured tool definitions; the
+    // textual [TOOL_CALL:] prompt is only a fallback for the rest.
+    let provider = llm_service.provider().await;
+    let native_tools_enabled = llm_service.native_tools_enabled(provider.as_ref()).await;
+    let native_tools = if native_tools_enabled {
+        let registry = action_registry.read().await;
+        let settings = tool_settings.read().await;
+        registry.list_tools_for_llm_with_settings(orchestrator.is_memory_enabled(), &settings)
+    } else {
+        Vec::new()
+    };
+    let tool_prompt = if native_tools_enabled {
+        None
+    } else {
         let registry = action_registry.read().await;
         let settings = tool_settings.read().await;
         let p = registry.generate_tool_prompt_for_prompt_with_settings(
@@ -346,7 +694,14 @@ async fn handle_text(
     };
 
     let (prompt_messages, compose_warnings) = orchestrator
-        .compose_prompt(text, false, tool_prompt, false, &char_id)
+        .compose_prompt_on(
+            surface,
+            text,
+            false,
+            tool_prompt,
+            native_tools_enabled,
+            &char_id,
+        )
         .await
         .map_err(|e| e.to_string())?;
     for w in &compose_warnings {
@@ -365,33 +720,46 @@ async fn handle_text(
     }
 
     // 3. LLM call with tool execution loop
-    let provider = llm_service.provider().await;
     let max_rounds = max_tool_rounds(app).await;
     let mut all_cleaned_text = String::new();
     let mut all_translations: Vec<String> = Vec::new();
 
     for _round in 0..max_rounds {
-        let mut stream = provider
-            .chat_stream(client_messages.clone(), None)
-            .await
-            .map_err(|e| format!("LLM stream error: {}", e))?;
-
-        let mut response = String::new();
-        while let Some(result) = stream.next().await {
-            match result {
-                Ok(delta) => response.push_str(&delta),
-                Err(e) => {
-                    tracing::error!(target: "telegram", "[Telegram] LLM stream error: {}", e);
-                    break;
-                }
-            }
+        let stream = if native_tools_enabled {
+            provider
+                .chat_stream_with_tools(client_messages.clone(), None, native_tools.clone())
+                .await
+        } else {
+            provider
+                .chat_stream_rich(
+                    client_messages.iter().cloned().map(Into::into).collect(),
+                    None,
+                )
+                .await
         }
+        .map_err(|e| format!("LLM stream error: {}", e))?;
 
-        if response.is_empty() {
+        let round = collect_stream_round(stream).await;
+        if let Some(e) = &round.error {
+            tracing::error!(target: "telegram", "[Telegram] LLM stream error: {}", e);
+        }
+        let response = round.text;
+        let native_tool_calls = round
+            .tool_calls
+            .into_iter()
+            .map(ToolCall::from)
+            .collect::<Vec<_>>();
+
+        if response.is_empty() && native_tool_calls.is_empty() {
             break;
         }
 
-        let (cleaned, tool_calls) = parse_tool_call_tags(&response);
+        let (cleaned, tag_tool_calls) = parse_tool_call_tags(&response);
+        let tool_calls = if native_tool_calls.is_empty() {
+            tag_tool_calls
+        } else {
+            native_tool_calls
+        };
         let (cleaned, round_translation) = extract_translate_tags(&cleaned);
         let cleaned = strip_leaked_tags(&cleaned);
 
@@ -425,7 +793,7 @@ async fn handle_text(
                         &registry,
                         &tool_call.name,
                         tool_call.args.clone(),
-                        None,
+                        tool_call.tool_call_id.clone(),
                     )
                     .map_err(|error| format!("Tool resolution error: {}", error.0))
                 })
@@ -462,6 +830,11 @@ async fn handle_text(
             .iter()
             .any(|outcome| outcome.needs_feedback);
 
+        if let Some(messages) = native_tool_continuation_messages(&cleaned, &execution_outcomes) {
+            client_messages.extend(messages);
+            continue;
+        }
+
         if !any_needs_feedback {
             break;
         }
@@ -492,7 +865,8 @@ async fn handle_text(
         .as_ref()
         .map(|t| serde_json::json!({ "translation": t }).to_string());
     orchestrator
-        .add_message_with_metadata(
+        .add_message_on(
+            surface,
             "assistant".to_string(),
             response.clone(),
             metadata,
@@ -501,6 +875,70 @@ async fn handle_text(
         )
         .await;
 
+    if fresh_turn {
+        trigger_memory_extraction(
+            &orchestrator,
+            &llm_service,
+            chat_id,
+            surface,
+            &char_id,
+            text,
+        )
+        .await;
+    }
+
+    // Sync assistant message to desktop UI
+    let _ = app.emit(
+        "telegram:chat-sync",
+        TelegramChatSync {
+            role: "assistant".to_string(),
+            text: response.clone(),
+            translation: translation.clone(),
+            replaces_last: !fresh_turn,
+        },
+    );
+
+    // 6. Build reply text (include translation if present)
+    let reply_text = if let Some(ref t) = translation {
+        format!("{}\n\n📝 {}", response, t)
+    } else {
+        response.clone()
+    };
+
+    // 7. Send text reply with the action buttons; only the newest can be regenerated
+    let keyboard = reply_keyboard(true, can_switch_character(config));
+    if let Some(message_id) =
+        send_text_reply_with_keyboard(bot, chat_id, &reply_text, keyboard).await
+    {
+        last_replies.write().await.insert(
+            chat_id,
+            LastReply {
+                message_id,
+                response: response.clone(),
+            },
+        );
+    }
+
+    // 8. Optionally send voice reply
+    if config.send_voice_reply {
+        send_voice_reply(bot, chat_id, &response, app).await;
+    }
+
+    // 9. Handle image generation tags
+    handle_image_tags(bot, chat_id, &response, app).await;
+
+    Ok(())
+}
+
+/// Event-driven, topic-shift and periodic memory extraction after a new turn.
+async fn trigger_memory_extraction(
+    orchestrator: &AIOrchestrator,
+    llm_service: &LlmService,
+    chat_id: ChatId,
+    surface: ChatSurface,
+    char_id: &str,
+    text: &str,
+) {
     // Event-driven + periodic memory extraction
     let msg_count = orchestrator.get_message_count().await;
     let memory_msg_count = orchestrator.get_memory_trigger_count().await;
@@ -518,10 +956,10 @@ async fn handle_text(
         msg_count, memory_msg_count, char_id
     );
 
-    if orchestrator.is_memory_enabled() {
+    if orchestrator.is_memory_write_enabled() {
         if let Some(decision) = select_memory_ingress_decision(text, &ingress_options) {
             let cooldown_key =
-                build_cooldown_key(&char_id, &chat_id.to_string(), decision.event.event_type);
+                build_cooldown_key(char_id, &chat_id.to_string(), decision.event.event_type);
             if orchestrator
                 .should_trigger_memory_event(&cooldown_key, decision.event.cooldown_secs)
                 .await
@@ -533,10 +971,10 @@ async fn handle_text(
                     msg_count
                 );
 
-                let history = orchestrator.get_recent_memory_history(10).await;
+                let history = orchestrator.get_recent_memory_history_on(surface, 10).await;
                 let memory_mgr = orchestrator.memory_manager.clone();
                 let provider_for_mem = llm_service.provider().await;
-                let char_id_for_mem = char_id.clone();
+                let char_id_for_mem = char_id.to_string();
                 let memory_enabled = orchestrator.memory_enabled_flag();
                 let observation_started_at = std::time::Instant::now();
                 let trigger_for_observation = decision.trigger_label.to_string();
@@ -572,16 +1010,28 @@ async fn handle_text(
         }
     }
 
-    if orchestrator.is_memory_enabled() && memory_msg_count > 0 && memory_msg_count % 5 == 0 {
+    let topic_trigger = if orchestrator.is_memory_write_enabled() {
+        orchestrator
+            .observe_topic_turn(
+                char_id,
+                text,
+                &crate::ai::topic_shift::TopicShiftOptions::from(&upgrade_config),
+            )
+            .await
+    } else {
+        None
+    };
+    if let Some(trigger) = topic_trigger {
         tracing::info!(
             target: "telegram::memory",
-            "[Telegram/Memory] Triggering memory extraction (count={})",
+            "[Telegram/Memory] Triggering memory extraction (trigger={}, count={})",
+            trigger.as_str(),
             msg_count
         );
-        let history = orchestrator.get_recent_memory_history(10).await;
+        let history = orchestrator.get_recent_memory_history_on(surface, 10).await;
         let memory_mgr = orchestrator.memory_manager.clone();
         let provider_for_mem = llm_service.provider().await;
-        let char_id_for_mem = char_id.clone();
+        let char_id_for_mem = char_id.to_string();
         let memory_enabled = orchestrator.memory_enabled_flag();
         let observation_started_at = std::time::Instant::now();
         let extraction_options = memory_extractor::MemoryExtractionOptions {
@@ -605,9 +1055,10 @@ async fn handle_text(
             .await;
         });
     }
-    if orchestrator.is_memory_enabled() && memory_msg_count > 0 && memory_msg_count % 20 == 0 {
+    if orchestrator.is_memory_write_enabled() && memory_msg_count > 0 && memory_msg_count % 20 == 0
+    {
         let memory_mgr = orchestrator.memory_manager.clone();
-        let char_id_for_consolidation = char_id.clone();
+        let char_id_for_consolidation = char_id.to_string();
         let provider_for_consolidation = llm_service.provider().await;
         let memory_enabled = orchestrator.memory_enabled_flag();
         let observation_started_at = std::time::Instant::now();
@@ -641,36 +1092,6 @@ async fn handle_text(
             }
         });
     }
-
-    // Sync assistant message to desktop UI
-    let _ = app.emit(
-        "telegram:chat-sync",
-        TelegramChatSync {
-            role: "assistant".to_string(),
-            text: response.clone(),
-            translation: translation.clone(),
-        },
-    );
-
-    // 6. Build reply text (include translation if present)
-    let reply_text = if let Some(ref t) = translation {
-        format!("{}\n\n📝 {}", response, t)
-    } else {
-        response.clone()
-    };
-
-    // 7. Send text reply
-    bot.send_message(chat_id, &reply_text).await.ok();
-
-    // 8. Optionally send voice reply
-    if config.send_voice_reply {
-        send_voice_reply(bot, chat_id, &response, app).await;
-    }
-
-    // 9. Handle image generation tags
-    handle_image_tags(bot, chat_id, &response, app).await;
-
-    Ok(())
 }
 
 /// Handle voice messages — download, transcribe via STT, then process as text.
@@ -678,8 +1099,9 @@ async fn handle_voice(
     bot: &Bot,
     msg: &Message,
     config: &Arc<TelegramConfig>,
-    _sessions: &Sessions,
+    sessions: &Sessions,
     app: &tauri::AppHandle,
+    last_replies: &LastReplies,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     let chat_id = msg.chat.id;
     let voice = msg.voice().ok_or("No voice data")?;
@@ -728,7 +1150,8 @@ async fn handle_voice(
         .ok();
 
     // Process as regular text
-    handle_text(bot, msg, &transcription, config, app).await
+    let surface = chat_surface(sessions, chat_id).await;
+    handle_text(bot, msg, &transcription, surface, config, app, last_replies).await
 }
 
 /// Handle photo messages — download image, convert to base64, send to LLM with vision.
@@ -736,9 +1159,11 @@ async fn handle_photo(
     bot: &Bot,
     msg: &Message,
     config: &Arc<TelegramConfig>,
+    sessions: &Sessions,
     app: &tauri::AppHandle,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     let chat_id = msg.chat.id;
+    let surface = chat_surface(sessions, chat_id).await;
     let photos = msg.photo().ok_or("No photo data")?;
 
     // Telegram sends multiple sizes — pick the largest one
@@ -777,25 +1202,21 @@ async fn handle_photo(
 
     // 1. Record user message
     // char_id 解析优先级：config 指定 > orchestrator 内存状态 > 磁盘文件 > "default"
-    let char_id = match config.character_id.as_deref().filter(|s| !s.is_empty()) {
-        Some(id) => id.to_string(),
-        None => {
-            let mem_id = orchestrator.get_character_id().await;
-            if !mem_id.is_empty() && mem_id != "default" {
-                mem_id
-            } else {
-                crate::ai::context::AIOrchestrator::load_active_character_id()
-                    .unwrap_or_else(|| "default".to_string())
-            }
-        }
-    };
+    let char_id = resolve_char_id(config, &orchestrator).await;
     tracing::info!(
         target: "telegram",
         "[Telegram] Resolved char_id='{}' for photo request",
         char_id
     );
     orchestrator
-        .add_message("user".to_string(), caption.clone(), &char_id)
+        .add_message_on(
+            surface,
+            "user".to_string(),
+            caption.clone(),
+            None,
+            &char_id,
+            None,
+        )
         .await;
 
     // Sync user message to desktop UI
@@ -805,6 +1226,7 @@ async fn handle_photo(
             role: "user".to_string(),
             text: format!("[TG] 📷 {}", caption),
             translation: None,
+            replaces_last: false,
         },
     );
 
@@ -815,7 +1237,20 @@ async fn handle_photo(
     let tool_settings = app
         .try_state::<Arc<RwLock<ToolSettings>>>()
         .ok_or("ToolSettings not available")?;
-    let tool_prompt = {
+    // Providers with function calling get structured tool definitions; the
+    // textual [TOOL_CALL:] prompt is only a fallback for the rest.
+    let provider = llm_service.provider().await;
+    let native_tools_enabled = llm_service.native_tools_enabled(provider.as_ref()).await;
+    let native_tools = if native_tools_enabled {
+        let registry = action_registry.read().await;
+        let settings = tool_settings.read().await;
+        registry.list_tools_for_llm_with_settings(orchestrator.is_memory_enabled(), &settings)
+    } else {
+        Vec::new()
+    };
+    let tool_prompt = if native_tools_enabled {
+        None
+    } else {
         let registry = action_registry.read().await;
         let settings = tool_settings.read().await;
         let p = registry.generate_tool_prompt_for_prompt_with_settings(
@@ -830,7 +1265,14 @@ async fn handle_photo(
     };
 
     let (prompt_messages, compose_warnings) = orchestrator
-        .compose_prompt(&caption, false, tool_prompt, false, &char_id)
+        .compose_prompt_on(
+            surface,
+            &caption,
+            false,
+            tool_prompt,
+            native_tools_enabled,
+            &char_id,
+        )
         .await
         .map_err(|e| e.to_string())?;
     for w in &compose_warnings {
@@ -852,33 +1294,46 @@ async fn handle_photo(
     }
 
     // 3. LLM call with tool execution loop
-    let provider = llm_service.provider().await;
     let max_rounds = max_tool_rounds(app).await;
     let mut all_cleaned_text = String::new();
     let mut all_translations: Vec<String> = Vec::new();
 
     for _round in 0..max_rounds {
-        let mut stream = provider
-            .chat_stream(client_messages.clone(), None)
-            .await
-            .map_err(|e| format!("LLM stream error: {}", e))?;
-
-        let mut round_response = String::new();
-        while let Some(result) = stream.next().await {
-            match result {
-                Ok(delta) => round_response.push_str(&delta),
-                Err(e) => {
-                    tracing::error!(target: "telegram", "[Telegram] LLM stream error: {}", e);
-                    break;
-                }
-            }
+        let stream = if native_tools_enabled {
+            provider
+                .chat_stream_with_tools(client_messages.clone(), None, native_tools.clone())
+                .await
+        } else {
+            provider
+                .chat_stream_rich(
+                    client_messages.iter().cloned().map(Into::into).collect(),
+                    None,
+                )
+                .await
         }
+        .map_err(|e| format!("LLM stream error: {}", e))?;
 
-        if round_response.is_empty() {
+        let round = collect_stream_round(stream).await;
+        if let Some(e) = &round.error {
+            tracing::error!(target: "telegram", "[Telegram] LLM stream error: {}", e);
+        }
+        let round_response = round.text;
+        let native_tool_calls = round
+            .tool_calls
+            .into_iter()
+            .map(ToolCall::from)
+            .collect::<Vec<_>>();
+
+        if round_response.is_empty() && native_tool_calls.is_empty() {
             break;
         }
 
-        let (cleaned, tool_calls) = parse_tool_call_tags(&round_response);
+        let (cleaned, tag_tool_calls) = parse_tool_call_tags(&round_response);
+        let tool_calls = if native_tool_calls.is_empty() {
+            tag_tool_calls
+        } else {
+            native_tool_calls
+        };
         let (cleaned, round_translation) = extract_translate_tags(&cleaned);
         let cleaned = strip_leaked_tags(&cleaned);
 
@@ -912,7 +1367,7 @@ async fn handle_photo(
                         &registry,
                         &tool_call.name,
                         tool_call.args.clone(),
-                        None,
+                        tool_call.tool_call_id.clone(),
                     )
                     .map_err(|error| format!("Tool resolution error: {}", error.0))
                 })
@@ -949,6 +1404,11 @@ async fn handle_photo(
             .iter()
             .any(|outcome| outcome.needs_feedback);
 
+        if let Some(messages) = native_tool_continuation_messages(&cleaned, &execution_outcomes) {
+            client_messages.extend(messages);
+            continue;
+        }
+
         if !any_needs_feedback {
             break;
         }
@@ -979,7 +1439,8 @@ async fn handle_photo(
         .as_ref()
         .map(|t| serde_json::json!({ "translation": t }).to_string());
     orchestrator
-        .add_message_with_metadata(
+        .add_message_on(
+            surface,
             "assistant".to_string(),
             response.clone(),
             metadata,
@@ -988,7 +1449,7 @@ async fn handle_photo(
         )
         .await;
 
-    // Trigger periodic memory extraction (every 5 user messages)
+    // Trigger memory extraction at topic boundaries
     let msg_count = orchestrator.get_message_count().await;
     let memory_msg_count = orchestrator.get_memory_trigger_count().await;
     let memory_target_language = orchestrator.response_language.lock().await.clone();
@@ -997,13 +1458,28 @@ async fn handle_photo(
         "[Telegram/Memory] User message count: {}, memory trigger count: {}, char_id: {}",
         msg_count, memory_msg_count, char_id
     );
-    if orchestrator.is_memory_enabled() && memory_msg_count > 0 && memory_msg_count % 5 == 0 {
+    let topic_trigger = if orchestrator.is_memory_write_enabled() {
+        let upgrade_config = crate::config::load_memory_upgrade_config(
+            &crate::ai::memory::memory_upgrade_config_path(),
+        );
+        orchestrator
+            .observe_topic_turn(
+                &char_id,
+                &caption,
+                &crate::ai::topic_shift::TopicShiftOptions::from(&upgrade_config),
+            )
+            .await
+    } else {
+        None
+    };
+    if let Some(trigger) = topic_trigger {
         tracing::info!(
             target: "telegram::memory",
-            "[Telegram/Memory] Triggering memory extraction (count={})",
+            "[Telegram/Memory] Triggering memory extraction (trigger={}, count={})",
+            trigger.as_str(),
             msg_count
         );
-        let history = orchestrator.get_recent_memory_history(10).await;
+        let history = orchestrator.get_recent_memory_history_on(surface, 10).await;
         let memory_mgr = orchestrator.memory_manager.clone();
         let provider_for_mem = llm_service.provider().await;
         let char_id_for_mem = char_id.clone();
@@ -1030,7 +1506,8 @@ async fn handle_photo(
             .await;
         });
     }
-    if orchestrator.is_memory_enabled() && memory_msg_count > 0 && memory_msg_count % 20 == 0 {
+    if orchestrator.is_memory_write_enabled() && memory_msg_count > 0 && memory_msg_count % 20 == 0
+    {
         let memory_mgr = orchestrator.memory_manager.clone();
         let char_id_for_consolidation = char_id.clone();
         let provider_for_consolidation = llm_service.provider().await;
@@ -1074,6 +1551,7 @@ async fn handle_photo(
             role: "assistant".to_string(),
             text: response.clone(),
             translation: translation.clone(),
+            replaces_last: false,
         },
     );
 
@@ -1083,7 +1561,8 @@ async fn handle_photo(
     } else {
         response.clone()
     };
-    bot.send_message(chat_id, &reply_text).await.ok();
+    let keyboard = reply_keyboard(false, can_switch_character(config));
+    send_text_reply_with_keyboard(bot, chat_id, &reply_text, keyboard).await;
 
     // 7. Voice reply
     if config.send_voice_reply {
@@ -1093,7 +1572,196 @@ async fn handle_photo(
     Ok(())
 }
 
+/// Character for Telegram conversations: config, then the desktop's active character.
+async fn resolve_char_id(config: &TelegramConfig, orchestrator: &AIOrchestrator) -> String {
+    match config.character_id.as_deref().filter(|s| !s.is_empty()) {
+        Some(id) => id.to_string(),
+        None => desktop_char_id(orchestrator).await,
+    }
+}
+
+/// The character active in the desktop app (in memory, then on disk).
+pub(crate) async fn desktop_char_id(orchestrator: &AIOrchestrator) -> String {
+    let mem_id = orchestrator.get_character_id().await;
+    if !mem_id.is_empty() && mem_id != "default" {
+        mem_id
+    } else {
+        crate::ai::context::AIOrchestrator::load_active_character_id()
+            .unwrap_or_else(|| "default".to_string())
+    }
+}
+
+/// Strip tool-call, translate and control tags from a raw model reply.
+/// Returns the display text and the translation, if the model produced one.
+pub(crate) fn clean_model_reply(raw: &str) -> (String, Option<String>) {
+    let (cleaned, _) = parse_tool_call_tags(raw);
+    let (cleaned, translation) = extract_translate_tags(&cleaned);
+    let response = strip_control_tags(&compact_newlines(&strip_leaked_tags(&cleaned)));
+    (response, translation)
+}
+
+/// Generate a heartbeat proactive message on the backend and "text" it to
+/// every allowed chat. Used while nobody is looking at the desktop window.
+///
+/// The instruction is a hidden turn and is not stored; the reply is persisted
+/// and synced to the desktop UI like any other Telegram reply.
+pub(crate) async fn send_proactive_message(
+    app: &tauri::AppHandle,
+    config: &TelegramConfig,
+    instruction: &str,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let token = config
+        .resolve_bot_token()
+        .ok_or("No bot token configured")?;
+    let orchestrator = app
+        .try_state::<AIOrchestrator>()
+        .ok_or("AIOrchestrator not available")?;
+    let llm_service = app
+        .try_state::<LlmService>()
+        .ok_or("LlmService not available")?;
+
+    let char_id = resolve_char_id(config, &orchestrator).await;
+    let (prompt_messages, compose_warnings) = orchestrator
+        .compose_prompt(instruction, false, None, false, &char_id)
+        .await
+        .map_err(|e| e.to_string())?;
+    for w in &compose_warnings {
+        tracing::warn!("[telegram compose_prompt] {}", w);
+    }
+    let mut client_messages = prompt_messages
+        .into_iter()
+        .map(|m| role_text_message(&m.role, m.content))
+        .collect::<Result<Vec<_>, _>>()?;
+    client_messages.push(user_text_message(instruction.to_string()));
+
+    let provider = llm_service.provider().await;
+    let stream = provider
+        .chat_stream_rich(client_messages.into_iter().map(Into::into).collect(), None)
+        .await
+        .map_err(|e| format!("LLM stream error: {}", e))?;
+    let round = collect_stream_round(stream).await;
+    if let Some(e) = round.error {
+        return Err(format!("LLM stream error: {}", e).into());
+    }
+
+    // Tool calls are not executed for unprompted messages; drop any that leaked.
+    let (response, translation) = clean_model_reply(&round.text);
+    if response.is_empty() {
+        return Ok(());
+    }
+
+    let mut metadata = serde_json::json!({ "proactive": true, "delivered_via": "telegram" });
+    if let Some(t) = &translation {
+        metadata["translation"] = serde_json::json!(t);
+    }
+    let metadata = metadata.to_string();
+    orchestrator
+        .add_message_with_metadata(
+            "assistant".to_string(),
+            response.clone(),
+            Some(metadata),
+            &char_id,
+            None,
+        )
+        .await;
+    if let Some(composer) = app.try_state::<crate::ai::proactive_composer::ProactiveComposer>() {
+        composer
+            .record_reply(&orchestrator.db, &char_id, instruction, &response)
+            .await;
+    }
+    let _ = app.emit(
+        "telegram:chat-sync",
+        TelegramChatSync {
+            role: "assistant".to_string(),
+            text: response.clone(),
+            translation,
+            replaces_last: false,
+        },
+    );
+
+    let bot = Bot::new(token);
+    for &chat_id in config.proactive_recipients() {
+        send_text_reply(&bot, ChatId(chat_id), &response).await;
+        if config.send_voice_reply {
+            send_voice_reply(&bot, ChatId(chat_id), &response, app).await;
+        }
+    }
+    tracing::info!(
+        target: "telegram",
+        "[Telegram] Proactive message sent to {} chat(s), char_id='{}'",
+        config.proactive_recipients().len(),
+        char_id
+    );
+    Ok(())
+}
+
 /// Synthesize text via TTS and send as a Telegram voice message.
+/// Send a reply in sentence-aligned chunks that fit Telegram's message limit.
+pub(crate) async fn send_text_reply(bot: &Bot, chat_id: ChatId, text: &str) {
+    for chunk in segment::chunk_text(text, segment::TELEGRAM_MAX_MESSAGE_LEN) {
+        if let Err(e) = bot.send_message(chat_id, chunk).await {
+            tracing::error!(target: "telegram", "[Telegram] Failed to send reply: {}", e);
+            break;
+        }
+    }
+}
+
+/// Like [`send_text_reply`], with `keyboard` on the last chunk. Returns that chunk's id.
+async fn send_text_reply_with_keyboard(
+    bot: &Bot,
+    chat_id: ChatId,
+    text: &str,
+    keyboard: InlineKeyboardMarkup,
+) -> Option<MessageId> {
+    let chunks = segment::chunk_text(text, segment::TELEGRAM_MAX_MESSAGE_LEN);
+    let last = chunks.len().checked_sub(1)?;
+    for (i, chunk) in chunks.into_iter().enumerate() {
+        let request = bot.send_message(chat_id, chunk);
+        let sent = if i == last {
+            request.reply_markup(keyboard.clone()).await
+        } else {
+            request.await
+        };
+        match sent {
+            Ok(sent) if i == last => return Some(sent.id),
+            Ok(_) => {}
+            Err(e) => {
+                tracing::error!(target: "telegram", "[Telegram] Failed to send reply: {}", e);
+                break;
+            }
+        }
+    }
+    None
+}
+
+/// Buttons under a reply.
+fn reply_keyboard(regenerate: bool, switch_character: bool) -> InlineKeyboardMarkup {
+    let mut row = Vec::new();
+    if regenerate {
+        row.push(InlineKeyboardButton::callback(
+            "🔁 Regenerate",
+            CALLBACK_REGENERATE,
+        ));
+    }
+    if switch_character {
+        row.push(InlineKeyboardButton::callback(
+            "🎭 Switch character",
+            CALLBACK_SWITCH_CHARACTER,
+        ));
+    }
+    row.push(InlineKeyboardButton::callback("🔊 Voice", CALLBACK_VOICE));
+    InlineKeyboardMarkup::new([row])
+}
+
+/// Switching is off while the config pins the bot to one character.
+fn can_switch_character(config: &TelegramConfig) -> bool {
+    config
+        .character_id
+        .as_deref()
+        .filter(|id| !id.is_empty())
+        .is_none()
+}
+
 async fn send_voice_reply(bot: &Bot, chat_id: ChatId, text: &str, app: &tauri::AppHandle) {
     let tts_service = match app.try_state::<TtsService>() {
         Some(s) => s,
@@ -1146,7 +1814,7 @@ async fn handle_image_tags(bot: &Bot, chat_id: ChatId, response: &str, app: &tau
         };
 
         match imagegen
-            .generate(prompt.to_string(), None, None, None)
+            .generate_in_character(prompt.to_string(), None, None, None)
             .await
         {
             Ok(result) => {
@@ -1180,6 +1848,7 @@ const TRANSLATE_TAG_PREFIX: &str = "[TRANSLATE:";
 
 #[derive(Debug, Clone)]
 struct ToolCall {
+    tool_call_id: Option<String>,
     name: String,
     args: HashMap<String, String>,
 }
@@ -1187,7 +1856,17 @@ struct ToolCall {
 impl From<ToolCall> for ToolInvocation {
     fn from(value: ToolCall) -> Self {
         Self {
-            tool_call_id: None,
+            tool_call_id: value.tool_call_id,
+            name: value.name,
+            args: value.args,
+        }
+    }
+}
+
+impl From<LlmToolCall> for ToolCall {
+    fn from(value: LlmToolCall) -> Self {
+        Self {
+            tool_call_id: Some(value.id),
             name: value.name,
             args: value.args,
         }
@@ -1214,7 +1893,11 @@ fn parse_tool_call_tags(text: &str) -> (String, Vec<ToolCall>) {
                         args.insert(key, val);
                     }
                 }
-                calls.push(ToolCall { name, args });
+                calls.push(ToolCall {
+                    tool_call_id: None,
+                    name,
+                    args,
+                });
             }
             let tag_end = start + end_bracket + 1;
             result = format!(
@@ -1260,7 +1943,11 @@ fn parse_tool_call_tags(text: &str) -> (String, Vec<ToolCall>) {
                         args.insert(key, val);
                     }
                 }
-                extra_calls.push(ToolCall { name, args });
+                extra_calls.push(ToolCall {
+                    tool_call_id: None,
+                    name,
+                    args,
+                });
                 let tag_end = start + end + 1;
                 cleaned = format!(
                     "{}{}",
@@ -1388,6 +2075,46 @@ fn compact_newlines(text: &str) -> String {
 mod tests {
     use super::*;
 
+    // ── parse_command ─────────────────────────────────────
+
+    #[test]
+    fn test_parse_command_drops_bot_name_suffix() {
+        assert_eq!(
+            parse_command("/authorize@KokoroBot 123456"),
+            Some(("/authorize", "123456"))
+        );
+        assert_eq!(
+            parse_command("/authorize  123456 "),
+            Some(("/authorize", "123456"))
+        );
+        assert_eq!(parse_command("/status@KokoroBot"), Some(("/status", "")));
+        assert_eq!(parse_command("/authorized 1"), Some(("/authorized", "1")));
+        assert_eq!(parse_command("hello /authorize 1"), None);
+    }
+
+    // ── proactive recipients ──────────────────────────────
+
+    #[test]
+    fn test_proactive_messages_only_reach_paired_chats() {
+        let mut config = TelegramConfig {
+            allowed_chat_ids: vec![111, 222],
+            proactive_when_away: true,
+            ..TelegramConfig::default()
+        };
+        assert_eq!(config.proactive_recipients(), &[111, 222]);
+
+        // A chat that talked to the bot without pairing is not a recipient.
+        assert!(!config.proactive_recipients().contains(&333));
+
+        // An empty whitelist lets anyone chat in, but nobody gets pushed to.
+        config.allowed_chat_ids.clear();
+        assert!(config.proactive_recipients().is_empty());
+
+        config.allowed_chat_ids = vec![111];
+        config.proactive_when_away = false;
+        assert!(config.proactive_recipients().is_empty());
+    }
+
     // ── compact_newlines ──────────────────────────────────
 
     #[test]
@@ -1482,6 +2209,38 @@ mod tests {
         assert!(!text.contains("[TOOL_CALL:"));
     }
 
+    // ── inline keyboard ───────────────────────────────────
+
+    #[test]
+    fn test_parse_callback_data() {
+        assert_eq!(
+            parse_callback_data(CALLBACK_REGENERATE),
+            Some(CallbackAction::Regenerate)
+        );
+        assert_eq!(
+            parse_callback_data(CALLBACK_VOICE),
+            Some(CallbackAction::Voice)
+        );
+        assert_eq!(
+            parse_callback_data("char:abc-123"),
+            Some(CallbackAction::PickCharacter("abc-123"))
+        );
+        assert_eq!(parse_callback_data("char:"), None);
+        assert_eq!(parse_callback_data("unknown"), None);
+    }
+
+    #[test]
+    fn test_reply_keyboard_hides_unavailable_actions() {
+        let labels = |keyboard: InlineKeyboardMarkup| {
+            keyboard.inline_keyboard[0]
+                .iter()
+                .map(|button| button.text.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(labels(reply_keyboard(true, true)).len(), 3);
+        assert_eq!(labels(reply_keyboard(false, false)), vec!["🔊 Voice"]);
+    }
+
     #[test]
     fn test_telegram_error_log_prefix_format() {
         let rendered = format!(
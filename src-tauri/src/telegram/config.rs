@@ -26,6 +26,17 @@ pub struct TelegramConfig {
     /// If empty, falls back to the currently active character in the desktop app.
     #[serde(default)]
     pub character_id: Option<String>,
+    /// Deliver heartbeat proactive messages to the allowed chats while the
+    /// desktop window is minimized, hidden or left alone.
+    #[serde(default)]
+    pub proactive_when_away: bool,
+    /// Minutes without input after which an unfocused desktop counts as away.
+    #[serde(default = "default_away_after_idle_minutes")]
+    pub away_after_idle_minutes: u32,
+}
+
+fn default_away_after_idle_minutes() -> u32 {
+    15
 }
 
 impl Default for TelegramConfig {
@@ -37,6 +48,8 @@ impl Default for TelegramConfig {
             allowed_chat_ids: Vec::new(),
             send_voice_reply: false,
             character_id: None,
+            proactive_when_away: false,
+            away_after_idle_minutes: default_away_after_idle_minutes(),
         }
     }
 }
@@ -46,6 +59,17 @@ impl TelegramConfig {
     pub fn resolve_bot_token(&self) -> Option<String> {
         crate::config::resolve_api_key(&self.bot_token, &self.bot_token_env)
     }
+
+    /// Chats that receive proactive messages: the paired whitelist, and none
+    /// when the toggle is off. Unlike incoming messages, an empty whitelist
+    /// never means "anyone".
+    pub fn proactive_recipients(&self) -> &[i64] {
+        if self.proactive_when_away {
+            &self.allowed_chat_ids
+        } else {
+            &[]
+        }
+    }
 }
 
 pub fn load_config(path: &Path) -> TelegramConfig {
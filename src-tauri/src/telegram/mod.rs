@@ -2,10 +2,15 @@
 
 pub mod bot;
 pub mod config;
+pub mod pairing;
 
 pub use config::{load_config, save_config, TelegramConfig};
+pub use pairing::{PairingError, TelegramPairingCode};
 
-use std::sync::Arc;
+use pairing::PairingCodes;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use teloxide::prelude::*;
 use tokio::sync::{oneshot, RwLock};
 
 /// Managed Tauri state for the Telegram bot service.
@@ -14,6 +19,7 @@ pub struct TelegramService {
     config: Arc<RwLock<TelegramConfig>>,
     /// Sender half of the shutdown signal. `Some` = bot is running.
     shutdown_tx: Arc<RwLock<Option<oneshot::Sender<()>>>>,
+    pairing: Arc<Mutex<PairingCodes>>,
 }
 
 impl TelegramService {
@@ -21,6 +27,7 @@ impl TelegramService {
         Self {
             config: Arc::new(RwLock::new(config)),
             shutdown_tx: Arc::new(RwLock::new(None)),
+            pairing: Arc::new(Mutex::new(PairingCodes::default())),
         }
     }
 
@@ -40,6 +47,41 @@ impl TelegramService {
         *cfg = new_config;
     }
 
+    /// Issue a code that lets a chat add itself to the whitelist via `/authorize`.
+    pub fn issue_pairing_code(&self) -> TelegramPairingCode {
+        self.pairing
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .issue(Instant::now())
+    }
+
+    /// Redeem a pairing code for `chat_id` and persist the grown whitelist.
+    /// Returns `false` when the chat was already allowed.
+    pub async fn authorize_chat(&self, chat_id: i64, code: &str) -> Result<bool, String> {
+        self.pairing
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .redeem(code, Instant::now())
+            .map_err(|e| match e {
+                PairingError::NoActiveCode => {
+                    "No active pairing code. Generate one in the desktop app first.".to_string()
+                }
+                PairingError::WrongCode => "Wrong pairing code.".to_string(),
+            })?;
+
+        let mut config = self.config.write().await;
+        if config.allowed_chat_ids.contains(&chat_id) {
+            return Ok(false);
+        }
+        let mut bot_config = crate::commands::bot::load_bot_config();
+        if !bot_config.telegram.allowed_chat_ids.contains(&chat_id) {
+            bot_config.telegram.allowed_chat_ids.push(chat_id);
+        }
+        crate::commands::bot::save_bot_config_file(&bot_config).map_err(|e| e.to_string())?;
+        config.allowed_chat_ids.push(chat_id);
+        Ok(true)
+    }
+
     /// Start the bot polling loop. Returns Err if already running or no token.
     pub async fn start(&self, app: tauri::AppHandle) -> Result<(), String> {
         if self.is_running().await {
@@ -73,6 +115,45 @@ impl TelegramService {
         Ok(())
     }
 
+    /// Send a plain-text notice to every whitelisted chat. No-op when the bot is stopped.
+    pub async fn broadcast(&self, text: &str) {
+        if !self.is_running().await {
+            return;
+        }
+        let config = self.config.read().await.clone();
+        let Some(token) = config.resolve_bot_token() else {
+            return;
+        };
+        let bot = Bot::new(token);
+        let chunks = crate::utils::segment::chunk_text(
+            text,
+            crate::utils::segment::TELEGRAM_MAX_MESSAGE_LEN,
+        );
+        for chat_id in config.allowed_chat_ids {
+            for chunk in &chunks {
+                if let Err(e) = bot.send_message(ChatId(chat_id), chunk).await {
+                    tracing::warn!(
+                        target: "telegram",
+                        "[Telegram] Failed to broadcast to chat_id={}: {}",
+                        chat_id,
+                        e
+                    );
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Config for pushing a proactive message, when the toggle is on, the bot
+    /// is running and there is someone to send it to.
+    pub async fn proactive_config(&self) -> Option<TelegramConfig> {
+        let config = self.config.read().await.clone();
+        if config.proactive_recipients().is_empty() {
+            return None;
+        }
+        self.is_running().await.then_some(config)
+    }
+
     /// Stop the bot polling loop gracefully.
     pub async fn stop(&self) -> Result<(), String> {
         let mut shutdown = self.shutdown_tx.write().await;
@@ -0,0 +1,112 @@
+//! One-time pairing codes for adding a chat to the Telegram whitelist.
+//!
+//! The desktop issues a short numeric code; sending `/authorize <code>` to the
+//! bot from any chat appends that chat's id to `allowed_chat_ids`. Only one
+//! code is live at a time, it expires after a few minutes, and it is burned
+//! after a handful of wrong guesses so it can't be brute-forced.
+
+use rand::Rng;
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+const CODE_TTL: Duration = Duration::from_secs(10 * 60);
+const MAX_FAILED_ATTEMPTS: u32 = 5;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TelegramPairingCode {
+    pub code: String,
+    pub expires_in_secs: u64,
+}
+
+#[derive(Debug)]
+struct PendingCode {
+    code: String,
+    expires_at: Instant,
+    failed_attempts: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PairingError {
+    /// No code was issued, or it expired or was used up.
+    NoActiveCode,
+    WrongCode,
+}
+
+#[derive(Debug, Default)]
+pub struct PairingCodes {
+    pending: Option<PendingCode>,
+}
+
+impl PairingCodes {
+    /// Issue a fresh code, replacing any earlier one.
+    pub fn issue(&mut self, now: Instant) -> TelegramPairingCode {
+        let code = format!("{:06}", rand::thread_rng().gen_range(0..1_000_000));
+        self.pending = Some(PendingCode {
+            code: code.clone(),
+            expires_at: now + CODE_TTL,
+            failed_attempts: 0,
+        });
+        TelegramPairingCode {
+            code,
+            expires_in_secs: CODE_TTL.as_secs(),
+        }
+    }
+
+    /// Check `attempt` against the live code. A match consumes the code.
+    pub fn redeem(&mut self, attempt: &str, now: Instant) -> Result<(), PairingError> {
+        let pending = self
+            .pending
+            .as_mut()
+            .filter(|pending| pending.expires_at > now)
+            .ok_or(PairingError::NoActiveCode)?;
+        if pending.code == attempt.trim() {
+            self.pending = None;
+            return Ok(());
+        }
+        pending.failed_attempts += 1;
+        if pending.failed_attempts >= MAX_FAILED_ATTEMPTS {
+            self.pending = None;
+        }
+        Err(PairingError::WrongCode)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codes_are_single_use_and_burn_after_wrong_guesses() {
+        let now = Instant::now();
+        let mut codes = PairingCodes::default();
+        assert_eq!(codes.redeem("123456", now), Err(PairingError::NoActiveCode));
+
+        let issued = codes.issue(now);
+        assert_eq!(issued.code.len(), 6);
+        assert!(codes.redeem(&format!(" {} ", issued.code), now).is_ok());
+        assert_eq!(
+            codes.redeem(&issued.code, now),
+            Err(PairingError::NoActiveCode)
+        );
+
+        let issued = codes.issue(now);
+        assert_eq!(
+            codes.redeem(&issued.code, now + CODE_TTL),
+            Err(PairingError::NoActiveCode)
+        );
+
+        let issued = codes.issue(now);
+        let wrong = if issued.code == "000000" {
+            "111111"
+        } else {
+            "000000"
+        };
+        for _ in 0..MAX_FAILED_ATTEMPTS {
+            assert_eq!(codes.redeem(wrong, now), Err(PairingError::WrongCode));
+        }
+        assert_eq!(
+            codes.redeem(&issued.code, now),
+            Err(PairingError::NoActiveCode)
+        );
+    }
+}
@@ -0,0 +1,909 @@
+//! Soft delete for memories, conversations, characters and Live2D models.
+//!
+//! Deleting one of these moves it to the `trash` table instead of destroying
+//! it: memories are archived and remember their previous status,
+//! conversations are snapshotted (with their messages, variant groups and
+//! checkpoints) before the rows are removed, characters are snapshotted with
+//! their conversations while their memories are archived, and model folders
+//! are moved under `trash/live2d_models/`. `undo_last_delete` restores the newest
+//! entry; `empty_trash` and the retention sweep delete for good.
+
+use crate::error::KokoroError;
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+use sqlx::{Row, SqliteConnection, SqlitePool};
+use std::path::{Component, Path, PathBuf};
+use std::time::Duration;
+
+/// How long deleted items stay restorable.
+pub const TRASH_RETENTION: Duration = Duration::from_secs(30 * 24 * 3600);
+const LABEL_CHARS: usize = 80;
+
+/// Conversation rows snapshotted on delete, as (payload key, table, column
+/// holding the conversation id). Restored in this order.
+const CONVERSATION_TABLES: [(&str, &str, &str); 5] = [
+    ("conversation", "conversations", "id"),
+    ("summaries", "conversation_summaries", "conversation_id"),
+    ("messages", "conversation_messages", "conversation_id"),
+    (
+        "variant_groups",
+        "message_variant_groups",
+        "conversation_id",
+    ),
+    ("checkpoints", "conversation_checkpoints", "conversation_id"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrashKind {
+    Memory,
+    Conversation,
+    Character,
+    Live2dModel,
+}
+
+impl TrashKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            TrashKind::Memory => "memory",
+            TrashKind::Conversation => "conversation",
+            TrashKind::Character => "character",
+            TrashKind::Live2dModel => "live2d_model",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "memory" => Some(TrashKind::Memory),
+            "conversation" => Some(TrashKind::Conversation),
+            "character" => Some(TrashKind::Character),
+            "live2d_model" => Some(TrashKind::Live2dModel),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TrashItem {
+    pub id: i64,
+    pub kind: TrashKind,
+    /// Memory id, conversation id, character id or model folder name.
+    pub item_id: String,
+    /// Memory text, conversation title, character name or model name,
+    /// shortened.
+    pub label: String,
+    pub deleted_at: i64,
+    /// Character profile (bindings) saved with a trashed character, handed
+    /// back on restore so the caller can store it again.
+    #[serde(skip)]
+    pub profile: Option<JsonValue>,
+}
+
+#[derive(Clone)]
+pub struct Trash {
+    db: SqlitePool,
+    models_dir: PathBuf,
+    dir: PathBuf,
+}
+
+impl Trash {
+    pub fn new(db: SqlitePool, app_data: &Path) -> Self {
+        Self {
+            db,
+            models_dir: app_data.join("live2d_models"),
+            dir: app_data.join("trash"),
+        }
+    }
+
+    /// Archive a memory, keeping its status for restore.
+    pub async fn delete_memory(&self, id: i64) -> Result<TrashItem, KokoroError> {
+        let mut tx = self.db.begin().await?;
+        let row = sqlx::query(
+            "SELECT content, status FROM memories WHERE rowid = ? AND status != 'archived'",
+        )
+        .bind(id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| KokoroError::NotFound(format!("memory {}", id)))?;
+        let content: String = row.get("content");
+        let status: String = row.get("status");
+        sqlx::query("UPDATE memories SET status = 'archived', updated_at = ? WHERE rowid = ?")
+            .bind(now_ts())
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        let item = insert_entry(
+            &mut tx,
+            TrashKind::Memory,
+            &id.to_string(),
+            &content,
+            &serde_json::json!({ "status": status }),
+        )
+        .await?;
+        tx.commit().await?;
+        Ok(item)
+    }
+
+    /// Snapshot a conversation and everything hanging off it, then delete it.
+    pub async fn delete_conversation(&self, id: &str) -> Result<TrashItem, KokoroError> {
+        let mut tx = self.db.begin().await?;
+        let payload = take_conversation(&mut tx, id).await?;
+        let title = payload["conversation"]
+            .get(0)
+            .and_then(|row| row.get("title"))
+            .and_then(JsonValue::as_str)
+            .map(str::to_string)
+            .ok_or_else(|| KokoroError::NotFound(format!("conversation '{}'", id)))?;
+        let item = insert_entry(&mut tx, TrashKind::Conversation, id, &title, &payload).await?;
+        tx.commit().await?;
+        Ok(item)
+    }
+
+    /// Snapshot a character row and its conversations, archive its memories
+    /// (remembering their status), then delete the row and the conversations.
+    /// `profile` is kept in the entry and returned by [`restore`](Self::restore).
+    pub async fn delete_character(
+        &self,
+        id: &str,
+        profile: Option<JsonValue>,
+    ) -> Result<TrashItem, KokoroError> {
+        let mut tx = self.db.begin().await?;
+        let character = snapshot_rows(&mut tx, "characters", "id", id).await?;
+        let name = character
+            .first()
+            .and_then(|row| row.get("name"))
+            .and_then(JsonValue::as_str)
+            .map(str::to_string)
+            .ok_or_else(|| KokoroError::NotFound(format!("character '{}'", id)))?;
+
+        let conversation_ids: Vec<String> = sqlx::query_scalar(
+            "SELECT id FROM conversations WHERE character_id = ? ORDER BY rowid",
+        )
+        .bind(id)
+        .fetch_all(&mut *tx)
+        .await?;
+        let mut conversations = Vec::with_capacity(conversation_ids.len());
+        for conversation_id in &conversation_ids {
+            conversations.push(take_conversation(&mut tx, conversation_id).await?);
+        }
+
+        let memories: Vec<(i64, String)> = sqlx::query_as(
+            "SELECT rowid, status FROM memories WHERE character_id = ? AND status != 'archived'",
+        )
+        .bind(id)
+        .fetch_all(&mut *tx)
+        .await?;
+        sqlx::query(
+            "UPDATE memories SET status = 'archived', updated_at = ? WHERE character_id = ? AND status != 'archived'",
+        )
+        .bind(now_ts())
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query("DELETE FROM characters WHERE id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        let payload = serde_json::json!({
+            "character": character,
+            "conversations": conversations,
+            "memories": memories
+                .iter()
+                .map(|(id, status)| serde_json::json!({ "id": id, "status": status }))
+                .collect::<Vec<_>>(),
+            "profile": profile,
+        });
+        let item = insert_entry(&mut tx, TrashKind::Character, id, &name, &payload).await?;
+        tx.commit().await?;
+        Ok(item)
+    }
+
+    /// Move an imported model folder (or file) into the trash dir.
+    pub async fn delete_live2d_model(&self, name: &str) -> Result<TrashItem, KokoroError> {
+        let mut components = Path::new(name).components();
+        if !matches!(components.next(), Some(Component::Normal(_))) || components.next().is_some() {
+            return Err(KokoroError::Validation("Invalid model name".to_string()));
+        }
+        let source = self.models_dir.join(name);
+        if !source.exists() {
+            return Err(KokoroError::NotFound(format!("Model '{}' not found", name)));
+        }
+
+        let mut tx = self.db.begin().await?;
+        let item = insert_entry(
+            &mut tx,
+            TrashKind::Live2dModel,
+            name,
+            name,
+            &JsonValue::Null,
+        )
+        .await?;
+        let target = self
+            .dir
+            .join("live2d_models")
+            .join(format!("{}-{}", item.id, name));
+        sqlx::query("UPDATE trash SET payload = ? WHERE id = ?")
+            .bind(serde_json::json!({ "path": target.to_string_lossy() }).to_string())
+            .bind(item.id)
+            .execute(&mut *tx)
+            .await?;
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::rename(&source, &target).map_err(|e| {
+            KokoroError::Internal(format!("Failed to delete model '{}': {}", name, e))
+        })?;
+        tx.commit().await?;
+        Ok(item)
+    }
+
+    pub async fn list(&self) -> Result<Vec<TrashItem>, KokoroError> {
+        let rows = sqlx::query(
+            "SELECT id, kind, item_id, label, deleted_at FROM trash ORDER BY deleted_at DESC, id DESC",
+        )
+        .fetch_all(&self.db)
+        .await?;
+        Ok(rows.iter().filter_map(item_from_row).collect())
+    }
+
+    /// Restore the most recently deleted item, if any.
+    pub async fn undo_last(&self) -> Result<Option<TrashItem>, KokoroError> {
+        let id: Option<i64> =
+            sqlx::query_scalar("SELECT id FROM trash ORDER BY deleted_at DESC, id DESC LIMIT 1")
+                .fetch_optional(&self.db)
+                .await?;
+        match id {
+            Some(id) => self.restore(id).await.map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Put a trashed item back where it was and drop the entry. An entry whose
+    /// item no longer exists is dropped too, and reported as not found.
+    pub async fn restore(&self, id: i64) -> Result<TrashItem, KokoroError> {
+        let mut tx = self.db.begin().await?;
+        let row = sqlx::query(
+            "SELECT id, kind, item_id, label, payload, deleted_at FROM trash WHERE id = ?",
+        )
+        .bind(id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| KokoroError::NotFound(format!("trash entry {}", id)))?;
+        let mut item = item_from_row(&row)
+            .ok_or_else(|| KokoroError::Internal(format!("Unknown trash entry {}", id)))?;
+        let payload: JsonValue =
+            serde_json::from_str(&row.get::<String, _>("payload")).unwrap_or(JsonValue::Null);
+        item.profile = payload.get("profile").filter(|p| !p.is_null()).cloned();
+        sqlx::query("DELETE FROM trash WHERE id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+
+        let restored = match item.kind {
+            TrashKind::Memory => {
+                let status = payload
+                    .get("status")
+                    .and_then(JsonValue::as_str)
+                    .unwrap_or("active");
+                sqlx::query(
+                    "UPDATE memories SET status = ?, updated_at = ? WHERE rowid = ? AND status = 'archived'",
+                )
+                .bind(status)
+                .bind(now_ts())
+                .bind(item.item_id.parse::<i64>().unwrap_or(-1))
+                .execute(&mut *tx)
+                .await?
+                .rows_affected()
+                    > 0
+            }
+            TrashKind::Conversation => {
+                restore_conversation(&mut tx, &item.item_id, &payload).await?
+            }
+            TrashKind::Character => restore_character(&mut tx, &item.item_id, &payload).await?,
+            TrashKind::Live2dModel => {
+                let from = payload_path(&payload);
+                let to = self.models_dir.join(&item.item_id);
+                if to.exists() {
+                    return Err(KokoroError::Validation(format!(
+                        "A model named '{}' already exists",
+                        item.item_id
+                    )));
+                }
+                match from.filter(|from| from.exists()) {
+                    Some(from) => {
+                        std::fs::create_dir_all(&self.models_dir)?;
+                        std::fs::rename(&from, &to)?;
+                        true
+                    }
+                    None => false,
+                }
+            }
+        };
+        tx.commit().await?;
+
+        if !restored {
+            return Err(KokoroError::NotFound(format!(
+                "{} '{}' can no longer be restored",
+                item.kind.as_str(),
+                item.item_id
+            )));
+        }
+        tracing::info!(target: "trash", "[Trash] Restored {} '{}'", item.kind.as_str(), item.item_id);
+        Ok(item)
+    }
+
+    /// Permanently delete every trashed item. Returns how many were removed.
+    pub async fn empty(&self) -> Result<usize, KokoroError> {
+        self.purge_older_than(i64::MAX).await
+    }
+
+    /// Permanently delete items trashed longer than [`TRASH_RETENTION`] ago.
+    pub async fn purge_expired(&self) -> Result<usize, KokoroError> {
+        self.purge_older_than(now_ts() - TRASH_RETENTION.as_secs() as i64)
+            .await
+    }
+
+    async fn purge_older_than(&self, cutoff: i64) -> Result<usize, KokoroError> {
+        let rows = sqlx::query("SELECT id, kind, item_id, payload FROM trash WHERE deleted_at < ?")
+            .bind(cutoff)
+            .fetch_all(&self.db)
+            .await?;
+        for row in &rows {
+            let id: i64 = row.get("id");
+            let item_id: String = row.get("item_id");
+            let payload: JsonValue =
+                serde_json::from_str(&row.get::<String, _>("payload")).unwrap_or(JsonValue::Null);
+            match TrashKind::parse(&row.get::<String, _>("kind")) {
+                Some(TrashKind::Memory) => {
+                    sqlx::query("DELETE FROM memories WHERE rowid = ? AND status = 'archived'")
+                        .bind(item_id.parse::<i64>().unwrap_or(-1))
+                        .execute(&self.db)
+                        .await?;
+                }
+                Some(TrashKind::Character) => {
+                    for memory in payload["memories"].as_array().into_iter().flatten() {
+                        sqlx::query("DELETE FROM memories WHERE rowid = ? AND status = 'archived'")
+                            .bind(memory["id"].as_i64().unwrap_or(-1))
+                            .execute(&self.db)
+                            .await?;
+                    }
+                    let avatar = payload["character"][0]["avatar_path"]
+                        .as_str()
+                        .and_then(crate::commands::character::resolve_avatar_path);
+                    if let Some(path) = avatar {
+                        let _ = std::fs::remove_file(path);
+                    }
+                }
+                Some(TrashKind::Live2dModel) => {
+                    if let Some(path) = payload_path(&payload).filter(|path| path.exists()) {
+                        let removed = if path.is_dir() {
+                            std::fs::remove_dir_all(&path)
+                        } else {
+                            std::fs::remove_file(&path)
+                        };
+                        if let Err(e) = removed {
+                            tracing::warn!(target: "trash", "[Trash] Failed to remove {}: {}", path.display(), e);
+                            continue;
+                        }
+                    }
+                }
+                // The snapshot is the only copy left.
+                Some(TrashKind::Conversation) | None => {}
+            }
+            sqlx::query("DELETE FROM trash WHERE id = ?")
+                .bind(id)
+                .execute(&self.db)
+                .await?;
+        }
+        if !rows.is_empty() {
+            tracing::info!(target: "trash", "[Trash] Permanently deleted {} item(s)", rows.len());
+        }
+        Ok(rows.len())
+    }
+}
+
+fn now_ts() -> i64 {
+    chrono::Utc::now().timestamp()
+}
+
+fn item_from_row(row: &sqlx::sqlite::SqliteRow) -> Option<TrashItem> {
+    Some(TrashItem {
+        id: row.get("id"),
+        kind: TrashKind::parse(&row.get::<String, _>("kind"))?,
+        item_id: row.get("item_id"),
+        label: row.get("label"),
+        deleted_at: row.get("deleted_at"),
+        profile: None,
+    })
+}
+
+fn payload_path(payload: &JsonValue) -> Option<PathBuf> {
+    payload
+        .get("path")
+        .and_then(JsonValue::as_str)
+        .map(PathBuf::from)
+}
+
+async fn insert_entry(
+    conn: &mut SqliteConnection,
+    kind: TrashKind,
+    item_id: &str,
+    label: &str,
+    payload: &JsonValue,
+) -> Result<TrashItem, KokoroError> {
+    let label: String = label.chars().take(LABEL_CHARS).collect();
+    let deleted_at = now_ts();
+    let id = sqlx::query(
+        "INSERT INTO trash (kind, item_id, label, payload, deleted_at) VALUES (?, ?, ?, ?, ?)",
+    )
+    .bind(kind.as_str())
+    .bind(item_id)
+    .bind(&label)
+    .bind(payload.to_string())
+    .bind(deleted_at)
+    .execute(&mut *conn)
+    .await?
+    .last_insert_rowid();
+    tracing::info!(target: "trash", "[Trash] Moved {} '{}' to trash", kind.as_str(), item_id);
+    Ok(TrashItem {
+        id,
+        kind,
+        item_id: item_id.to_string(),
+        label,
+        deleted_at,
+        profile: None,
+    })
+}
+
+async fn table_columns(
+    conn: &mut SqliteConnection,
+    table: &str,
+) -> Result<Vec<String>, KokoroError> {
+    let rows = sqlx::query(&format!("PRAGMA table_info({table})"))
+        .fetch_all(&mut *conn)
+        .await?;
+    Ok(rows.iter().map(|row| row.get("name")).collect())
+}
+
+/// Rows of `table` where `column = value`, as JSON objects of every column.
+async fn snapshot_rows(
+    conn: &mut SqliteConnection,
+    table: &str,
+    column: &str,
+    value: &str,
+) -> Result<Vec<JsonValue>, KokoroError> {
+    let fields = table_columns(conn, table)
+        .await?
+        .iter()
+        .map(|name| format!("'{name}', \"{name}\""))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let rows: Vec<String> = sqlx::query_scalar(&format!(
+        "SELECT json_object({fields}) FROM {table} WHERE {column} = ? ORDER BY rowid"
+    ))
+    .bind(value)
+    .fetch_all(&mut *conn)
+    .await?;
+    rows.iter()
+        .map(|row| serde_json::from_str(row).map_err(KokoroError::from))
+        .collect()
+}
+
+/// Snapshot a conversation's rows (see [`CONVERSATION_TABLES`]) and delete
+/// them. The snapshot's `conversation` array is empty if it did not exist.
+async fn take_conversation(
+    conn: &mut SqliteConnection,
+    id: &str,
+) -> Result<JsonValue, KokoroError> {
+    let mut payload = serde_json::Map::new();
+    for (key, table, column) in CONVERSATION_TABLES {
+        let rows = snapshot_rows(conn, table, column, id).await?;
+        payload.insert(key.to_string(), JsonValue::Array(rows));
+    }
+    for (_, table, column) in CONVERSATION_TABLES.iter().rev() {
+        sqlx::query(&format!("DELETE FROM {table} WHERE {column} = ?"))
+            .bind(id)
+            .execute(&mut *conn)
+            .await?;
+    }
+    Ok(JsonValue::Object(payload))
+}
+
+/// Insert snapshotted rows back. Columns added since the snapshot get their
+/// defaults; columns that no longer exist are skipped.
+async fn restore_rows(
+    conn: &mut SqliteConnection,
+    table: &str,
+    rows: &JsonValue,
+) -> Result<(), KokoroError> {
+    let Some(first) = rows.as_array().and_then(|rows| rows.first()) else {
+        return Ok(());
+    };
+    let columns: Vec<String> = table_columns(conn, table)
+        .await?
+        .into_iter()
+        .filter(|name| first.get(name).is_some())
+        .collect();
+    let names = columns
+        .iter()
+        .map(|name| format!("\"{name}\""))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let values = columns
+        .iter()
+        .map(|name| format!("json_extract(value, '$.\"{name}\"')"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    sqlx::query(&format!(
+        "INSERT INTO {table} ({names}) SELECT {values} FROM json_each(?)"
+    ))
+    .bind(rows.to_string())
+    .execute(&mut *conn)
+    .await?;
+    Ok(())
+}
+
+async fn restore_conversation(
+    conn: &mut SqliteConnection,
+    id: &str,
+    payload: &JsonValue,
+) -> Result<bool, KokoroError> {
+    if payload["conversation"]
+        .as_array()
+        .is_none_or(|rows| rows.is_empty())
+    {
+        return Ok(false);
+    }
+    let exists: Option<String> = sqlx::query_scalar("SELECT id FROM conversations WHERE id = ?")
+        .bind(id)
+        .fetch_optional(&mut *conn)
+        .await?;
+    if exists.is_some() {
+        return Err(KokoroError::Validation(format!(
+            "Conversation '{}' already exists",
+            id
+        )));
+    }
+    for (key, table, _) in CONVERSATION_TABLES {
+        restore_rows(conn, table, &payload[key]).await?;
+        if key == "conversation" {
+            // Messages come back with their own branch; keep the insert
+            // trigger from moving them onto the active one.
+            sqlx::query("UPDATE conversations SET active_branch_group = NULL WHERE id = ?")
+                .bind(id)
+                .execute(&mut *conn)
+                .await?;
+        }
+    }
+    sqlx::query("UPDATE conversations SET active_branch_group = ? WHERE id = ?")
+        .bind(payload["conversation"][0]["active_branch_group"].as_str())
+        .bind(id)
+        .execute(&mut *conn)
+        .await?;
+    Ok(true)
+}
+
+async fn restore_character(
+    conn: &mut SqliteConnection,
+    id: &str,
+    payload: &JsonValue,
+) -> Result<bool, KokoroError> {
+    if payload["character"]
+        .as_array()
+        .is_none_or(|rows| rows.is_empty())
+    {
+        return Ok(false);
+    }
+    // A conversation or memory added for the id since the delete registered
+    // a placeholder row; the snapshot replaces it.
+    sqlx::query("DELETE FROM characters WHERE id = ? AND source_format = 'auto'")
+        .bind(id)
+        .execute(&mut *conn)
+        .await?;
+    let exists: Option<String> = sqlx::query_scalar("SELECT id FROM characters WHERE id = ?")
+        .bind(id)
+        .fetch_optional(&mut *conn)
+        .await?;
+    if exists.is_some() {
+        return Err(KokoroError::Validation(format!(
+            "Character '{}' already exists",
+            id
+        )));
+    }
+    restore_rows(conn, "characters", &payload["character"]).await?;
+    for conversation in payload["conversations"].as_array().into_iter().flatten() {
+        if let Some(conversation_id) = conversation["conversation"][0]["id"].as_str() {
+            restore_conversation(conn, conversation_id, conversation).await?;
+        }
+    }
+    for memory in payload["memories"].as_array().into_iter().flatten() {
+        sqlx::query(
+            "UPDATE memories SET status = ?, updated_at = ? WHERE rowid = ? AND status = 'archived'",
+        )
+        .bind(memory["status"].as_str().unwrap_or("active"))
+        .bind(now_ts())
+        .bind(memory["id"].as_i64().unwrap_or(-1))
+        .execute(&mut *conn)
+        .await?;
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    async fn setup() -> (Trash, TempDir) {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+        let tmp = TempDir::new().unwrap();
+        (Trash::new(pool, tmp.path()), tmp)
+    }
+
+    #[tokio::test]
+    async fn memory_delete_and_undo_keeps_status() {
+        let (trash, _tmp) = setup().await;
+        sqlx::query(
+            "INSERT INTO memories (content, embedding, created_at, character_id, status) VALUES ('likes tea', x'00', 0, 'c1', 'active')",
+        )
+        .execute(&trash.db)
+        .await
+        .unwrap();
+
+        let item = trash.delete_memory(1).await.unwrap();
+        assert_eq!(item.kind, TrashKind::Memory);
+        assert_eq!(item.label, "likes tea");
+        assert!(trash.delete_memory(1).await.is_err());
+
+        let restored = trash.undo_last().await.unwrap().expect("entry");
+        assert_eq!(restored.item_id, "1");
+        let status: String = sqlx::query_scalar("SELECT status FROM memories WHERE rowid = 1")
+            .fetch_one(&trash.db)
+            .await
+            .unwrap();
+        assert_eq!(status, "active");
+        assert!(trash.undo_last().await.unwrap().is_none());
+
+        trash.delete_memory(1).await.unwrap();
+        assert_eq!(trash.empty().await.unwrap(), 1);
+        let left: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM memories")
+            .fetch_one(&trash.db)
+            .await
+            .unwrap();
+        assert_eq!(left, 0);
+    }
+
+    #[tokio::test]
+    async fn conversation_comes_back_with_messages_and_branches() {
+        let (trash, _tmp) = setup().await;
+        sqlx::query(
+            "INSERT INTO conversations (id, character_id, title, created_at, updated_at) \
+             VALUES ('conv', 'c1', 'Tea talk', 't0', 't1')",
+        )
+        .execute(&trash.db)
+        .await
+        .unwrap();
+        for (id, content, branch, variant) in [
+            (1, "hi", None, 0),
+            (2, "first reply", Some("g1"), 0),
+            (3, "second reply", Some("g1"), 1),
+        ] {
+            sqlx::query(
+                "INSERT INTO conversation_messages (id, conversation_id, role, content, created_at, branch_group, variant_index) \
+                 VALUES (?, 'conv', 'assistant', ?, 't', ?, ?)",
+            )
+            .bind(id)
+            .bind(content)
+            .bind(branch)
+            .bind(variant)
+            .execute(&trash.db)
+            .await
+            .unwrap();
+        }
+        sqlx::query(
+            "INSERT INTO message_variant_groups (id, conversation_id, anchor_message_id, active_index, variant_count, created_at) \
+             VALUES ('g1', 'conv', 1, 1, 2, 0)",
+        )
+        .execute(&trash.db)
+        .await
+        .unwrap();
+        sqlx::query(
+            "UPDATE conversations SET active_branch_group = 'g1', active_variant_index = 1 WHERE id = 'conv'",
+        )
+        .execute(&trash.db)
+        .await
+        .unwrap();
+
+        let item = trash.delete_conversation("conv").await.unwrap();
+        assert_eq!(item.label, "Tea talk");
+        let messages: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM conversation_messages")
+            .fetch_one(&trash.db)
+            .await
+            .unwrap();
+        assert_eq!(messages, 0);
+        assert_eq!(trash.list().await.unwrap().len(), 1);
+
+        trash.restore(item.id).await.unwrap();
+        let rows: Vec<(i64, Option<String>, i64)> = sqlx::query_as(
+            "SELECT id, branch_group, variant_index FROM conversation_messages ORDER BY id",
+        )
+        .fetch_all(&trash.db)
+        .await
+        .unwrap();
+        assert_eq!(
+            rows,
+            vec![
+                (1, None, 0),
+                (2, Some("g1".to_string()), 0),
+                (3, Some("g1".to_string()), 1)
+            ]
+        );
+        let (branch, title): (Option<String>, String) = sqlx::query_as(
+            "SELECT active_branch_group, title FROM conversations WHERE id = 'conv'",
+        )
+        .fetch_one(&trash.db)
+        .await
+        .unwrap();
+        assert_eq!(branch.as_deref(), Some("g1"));
+        assert_eq!(title, "Tea talk");
+        assert!(trash.list().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn conversation_summaries_survive_trash_and_undo() {
+        let (trash, _tmp) = setup().await;
+        sqlx::query(
+            "INSERT INTO conversations (id, character_id, title, created_at, updated_at) \
+             VALUES ('conv', 'c1', 'Tea talk', 't0', 't1')",
+        )
+        .execute(&trash.db)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO conversation_messages (id, conversation_id, role, content, created_at) \
+             VALUES (1, 'conv', 'user', 'hi', 't')",
+        )
+        .execute(&trash.db)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO conversation_summaries \
+             (conversation_id, character_id, version, start_message_id, end_message_id, summary, status, created_at, updated_at) \
+             VALUES ('conv', 'c1', 1, 1, 1, 'They said hi.', 'ready', 0, 0)",
+        )
+        .execute(&trash.db)
+        .await
+        .unwrap();
+
+        let item = trash.delete_conversation("conv").await.unwrap();
+        let summaries = || async {
+            sqlx::query_as::<_, (String, i64, String)>(
+                "SELECT conversation_id, version, summary FROM conversation_summaries",
+            )
+            .fetch_all(&trash.db)
+            .await
+            .unwrap()
+        };
+        assert!(summaries().await.is_empty());
+
+        trash.restore(item.id).await.unwrap();
+        assert_eq!(
+            summaries().await,
+            vec![("conv".to_string(), 1, "They said hi.".to_string())]
+        );
+    }
+
+    #[tokio::test]
+    async fn character_comes_back_with_conversations_and_memories() {
+        let (trash, _tmp) = setup().await;
+        for id in ["c1", "c2"] {
+            sqlx::query("INSERT INTO characters (id, name, persona) VALUES (?, ?, 'Shy.')")
+                .bind(id)
+                .bind(format!("Mio {id}"))
+                .execute(&trash.db)
+                .await
+                .unwrap();
+            sqlx::query(
+                "INSERT INTO conversations (id, character_id, title, created_at, updated_at) VALUES (?, ?, 'Chat', '', '')",
+            )
+            .bind(format!("conv-{id}"))
+            .bind(id)
+            .execute(&trash.db)
+            .await
+            .unwrap();
+            sqlx::query(
+                "INSERT INTO conversation_messages (conversation_id, role, content, created_at) VALUES (?, 'user', 'hi', '')",
+            )
+            .bind(format!("conv-{id}"))
+            .execute(&trash.db)
+            .await
+            .unwrap();
+            sqlx::query(
+                "INSERT INTO memories (content, embedding, created_at, character_id, status) VALUES ('likes tea', x'00', 0, ?, 'superseded')",
+            )
+            .bind(id)
+            .execute(&trash.db)
+            .await
+            .unwrap();
+        }
+
+        let profile = serde_json::json!({ "tts_voice": "mio-soft" });
+        let item = trash
+            .delete_character("c1", Some(profile.clone()))
+            .await
+            .unwrap();
+        assert_eq!(item.kind, TrashKind::Character);
+        assert_eq!(item.label, "Mio c1");
+        assert!(trash.delete_character("c1", None).await.is_err());
+        let owners: Vec<String> = sqlx::query_scalar("SELECT character_id FROM conversations")
+            .fetch_all(&trash.db)
+            .await
+            .unwrap();
+        assert_eq!(owners, vec!["c2"]);
+        let statuses: Vec<String> =
+            sqlx::query_scalar("SELECT status FROM memories ORDER BY rowid")
+                .fetch_all(&trash.db)
+                .await
+                .unwrap();
+        assert_eq!(statuses, vec!["archived", "superseded"]);
+        // Chatting on under the deleted id registers a placeholder row.
+        sqlx::query(
+            "INSERT INTO conversations (id, character_id, title, created_at, updated_at) VALUES ('conv-new', 'c1', 'Chat', '', '')",
+        )
+        .execute(&trash.db)
+        .await
+        .unwrap();
+
+        let restored = trash.undo_last().await.unwrap().expect("entry");
+        assert_eq!(restored.profile, Some(profile));
+        let persona: String = sqlx::query_scalar("SELECT persona FROM characters WHERE id = 'c1'")
+            .fetch_one(&trash.db)
+            .await
+            .unwrap();
+        assert_eq!(persona, "Shy.");
+        let messages: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM conversation_messages WHERE conversation_id = 'conv-c1'",
+        )
+        .fetch_one(&trash.db)
+        .await
+        .unwrap();
+        assert_eq!(messages, 1);
+        let statuses: Vec<String> =
+            sqlx::query_scalar("SELECT status FROM memories ORDER BY rowid")
+                .fetch_all(&trash.db)
+                .await
+                .unwrap();
+        assert_eq!(statuses, vec!["superseded", "superseded"]);
+
+        trash.delete_character("c1", None).await.unwrap();
+        assert_eq!(trash.empty().await.unwrap(), 1);
+        let memories: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM memories")
+            .fetch_one(&trash.db)
+            .await
+            .unwrap();
+        assert_eq!(memories, 1);
+    }
+
+    #[tokio::test]
+    async fn live2d_model_folder_moves_out_and_back() {
+        let (trash, tmp) = setup().await;
+        let model = tmp.path().join("live2d_models").join("mio");
+        std::fs::create_dir_all(&model).unwrap();
+        std::fs::write(model.join("mio.model3.json"), "{}").unwrap();
+
+        assert!(trash.delete_live2d_model("../mio").await.is_err());
+        let item = trash.delete_live2d_model("mio").await.unwrap();
+        assert!(!model.exists());
+
+        trash.restore(item.id).await.unwrap();
+        assert!(model.join("mio.model3.json").exists());
+
+        trash.delete_live2d_model("mio").await.unwrap();
+        assert_eq!(trash.purge_expired().await.unwrap(), 0);
+        assert_eq!(trash.empty().await.unwrap(), 1);
+        let trashed = std::fs::read_dir(tmp.path().join("trash").join("live2d_models")).unwrap();
+        assert_eq!(trashed.count(), 0);
+    }
+}
@@ -1,4 +1,5 @@
 use super::config::ProviderConfig;
+use super::emotion_tts;
 use super::interface::{
     Gender, ProviderCapabilities, TtsEngine, TtsError, TtsParams, TtsProvider, VoiceProfile,
 };
@@ -89,6 +90,33 @@ fn escape_ssml_text(text: &str) -> String {
         .replace('\'', "&apos;")
 }
 
+/// Wraps the text in an `mstts:express-as` style when the request carries an
+/// emotion. Voices without that style ignore it and speak normally.
+fn build_ssml(voice_name: &str, text: &str, params: &TtsParams) -> String {
+    let escaped_text = escape_ssml_text(text);
+    let intensity = params
+        .extra_params
+        .as_ref()
+        .and_then(|extra| extra.get(emotion_tts::INTENSITY_PARAM))
+        .and_then(|v| v.as_f64())
+        .map(|v| v as f32);
+    let body = match params
+        .emotion
+        .as_deref()
+        .and_then(|emotion| emotion_tts::azure_style(emotion, intensity))
+    {
+        Some((style, degree)) => format!(
+            "<mstts:express-as style=\"{}\" styledegree=\"{}\">{}</mstts:express-as>",
+            style, degree, escaped_text
+        ),
+        None => escaped_text,
+    };
+    format!(
+        "<speak version=\"1.0\" xmlns=\"http://www.w3.org/2001/10/synthesis\" xmlns:mstts=\"https://www.w3.org/2001/mstts\" xml:lang=\"en-US\"><voice name=\"{}\">{}</voice></speak>",
+        voice_name, body
+    )
+}
+
 #[async_trait]
 impl TtsProvider for AzureTtsProvider {
     fn id(&self) -> String {
@@ -133,11 +161,7 @@ impl TtsProvider for AzureTtsProvider {
             .unwrap_or(&voice)
             .to_string();
 
-        let escaped_text = escape_ssml_text(text);
-        let ssml = format!(
-            "<speak version=\"1.0\" xmlns=\"http://www.w3.org/2001/10/synthesis\" xml:lang=\"en-US\"><voice name=\"{}\">{}</voice></speak>",
-            voice_name, escaped_text
-        );
+        let ssml = build_ssml(&voice_name, text, &params);
 
         let response = self
             .client
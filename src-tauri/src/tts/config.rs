@@ -1,3 +1,4 @@
+use super::normalize::NormalizationConfig;
 use crate::error::KokoroError;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -8,7 +9,7 @@ use std::path::Path;
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProviderConfig {
     pub id: String,
-    pub provider_type: String, // "openai", "edge_tts", "local_vits", "gpt_sovits", "omnivoice", "azure", "elevenlabs", "browser"
+    pub provider_type: String, // "openai", "edge_tts", "local_vits", "gpt_sovits", "omnivoice", "azure", "elevenlabs", "custom_http", "browser"
     #[serde(default = "default_true")]
     pub enabled: bool,
 
@@ -72,11 +73,18 @@ fn default_ttl_secs() -> u64 {
 pub struct QueueConfig {
     #[serde(default = "default_max_concurrent")]
     pub max_concurrent: usize,
+    /// Utterances allowed to wait; beyond this the lowest-priority, oldest
+    /// one is dropped.
+    #[serde(default = "default_max_depth")]
+    pub max_depth: usize,
 }
 
 impl Default for QueueConfig {
     fn default() -> Self {
-        Self { max_concurrent: 3 }
+        Self {
+            max_concurrent: 3,
+            max_depth: default_max_depth(),
+        }
     }
 }
 
@@ -84,6 +92,67 @@ fn default_max_concurrent() -> usize {
     3
 }
 
+fn default_max_depth() -> usize {
+    4
+}
+
+// ── Failover Config ────────────────────────────────────
+
+/// Switching to the next provider when the active one fails mid-utterance.
+/// Candidates are tried in `providers` order and must meet every requested
+/// capability.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FailoverConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// A sentence that produces no audio within this time counts as failed.
+    #[serde(default = "default_latency_threshold_ms")]
+    pub latency_threshold_ms: u64,
+}
+
+impl Default for FailoverConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            latency_threshold_ms: default_latency_threshold_ms(),
+        }
+    }
+}
+
+fn default_latency_threshold_ms() -> u64 {
+    8000
+}
+
+// ── Prefetch Config ────────────────────────────────────
+
+/// How far synthesis runs ahead of playback; see `tts::prefetch`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrefetchConfig {
+    /// Audio to keep queued ahead of the playback position.
+    #[serde(default = "default_lead_ms")]
+    pub lead_ms: u64,
+    /// Most sentences synthesizing at once.
+    #[serde(default = "default_max_sentences")]
+    pub max_sentences: usize,
+}
+
+impl Default for PrefetchConfig {
+    fn default() -> Self {
+        Self {
+            lead_ms: default_lead_ms(),
+            max_sentences: default_max_sentences(),
+        }
+    }
+}
+
+fn default_lead_ms() -> u64 {
+    6000
+}
+
+fn default_max_sentences() -> usize {
+    4
+}
+
 // ── Top-Level System Config ────────────────────────────
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -95,7 +164,14 @@ pub struct TtsSystemConfig {
     #[serde(default)]
     pub queue: QueueConfig,
     #[serde(default)]
+    pub failover: FailoverConfig,
+    #[serde(default)]
+    pub prefetch: PrefetchConfig,
+    #[serde(default)]
     pub providers: Vec<ProviderConfig>,
+    /// Markup stripping, number expansion and the pronunciation dictionary.
+    #[serde(default)]
+    pub normalization: NormalizationConfig,
 }
 
 impl Default for TtsSystemConfig {
@@ -104,6 +180,8 @@ impl Default for TtsSystemConfig {
             default_provider: Some("browser".to_string()),
             cache: CacheConfig::default(),
             queue: QueueConfig::default(),
+            failover: FailoverConfig::default(),
+            prefetch: PrefetchConfig::default(),
             providers: vec![
                 // Browser provider is always available as fallback
                 ProviderConfig {
@@ -120,6 +198,7 @@ impl Default for TtsSystemConfig {
                     extra: HashMap::new(),
                 },
             ],
+            normalization: NormalizationConfig::default(),
         }
     }
 }
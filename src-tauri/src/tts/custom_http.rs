@@ -0,0 +1,450 @@
+use super::config::ProviderConfig;
+use super::interface::{
+    Gender, ProviderCapabilities, TtsEngine, TtsError, TtsParams, TtsProvider, VoiceProfile,
+};
+use crate::utils::template::{self, select_text, TemplateVars};
+use async_trait::async_trait;
+use base64::Engine;
+use reqwest::{Client, Method};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Key of the spec inside `ProviderConfig::extra`.
+pub const CUSTOM_HTTP_EXTRA_KEY: &str = "custom_http";
+
+/// How the audio comes back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum AudioResponse {
+    /// The response body is the audio file.
+    #[default]
+    Binary,
+    /// JSON with base64 audio (optionally a `data:` URL) at `audio_path`.
+    Base64,
+    /// JSON with a URL to the audio at `audio_path`, fetched in a second request.
+    Url,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CustomVoice {
+    pub id: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub gender: Option<Gender>,
+}
+
+/// Request/response mapping for a TTS server, stored in `extra.custom_http`.
+///
+/// Placeholders: `{{text}}`, `{{voice}}`, `{{speed}}`, `{{pitch}}`,
+/// `{{emotion}}`, `{{model}}`, `{{base_url}}`, `{{api_key}}`, plus any
+/// per-request extra param by name. See [`crate::utils::template`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CustomHttpTtsSpec {
+    /// Request URL. Defaults to the provider's endpoint / base URL.
+    #[serde(default)]
+    pub url: Option<String>,
+    /// "POST" (default) or "GET".
+    #[serde(default = "default_method")]
+    pub method: String,
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    /// Query parameters; values are templates. Use these rather than the URL
+    /// for `{{text}}`, since they are URL-encoded.
+    #[serde(default)]
+    pub query: HashMap<String, String>,
+    /// JSON body template, sent when present.
+    #[serde(default)]
+    pub body: Option<Value>,
+    #[serde(default)]
+    pub response: AudioResponse,
+    /// Path to the audio (base64 or URL) for JSON responses.
+    #[serde(default)]
+    pub audio_path: Option<String>,
+    /// Path to an error message in a JSON response.
+    #[serde(default)]
+    pub error_path: Option<String>,
+    /// URL probed by `is_available`; without it the provider is assumed up.
+    #[serde(default)]
+    pub health_url: Option<String>,
+    #[serde(default)]
+    pub voices: Vec<CustomVoice>,
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+fn default_method() -> String {
+    "POST".to_string()
+}
+
+fn default_timeout_secs() -> u64 {
+    60
+}
+
+impl CustomHttpTtsSpec {
+    fn validate(&self, base_url: &str) -> Result<Method, TtsError> {
+        if self.url.is_none() && base_url.trim().is_empty() {
+            return Err(TtsError::ConfigError(
+                "custom_http needs `url` or an endpoint".to_string(),
+            ));
+        }
+        let method = match self.method.to_ascii_uppercase().as_str() {
+            "POST" => Method::POST,
+            "GET" => Method::GET,
+            other => {
+                return Err(TtsError::ConfigError(format!(
+                    "custom_http method must be GET or POST, got {}",
+                    other
+                )))
+            }
+        };
+        if self.response != AudioResponse::Binary && self.audio_path.is_none() {
+            return Err(TtsError::ConfigError(
+                "custom_http needs `audio_path` for base64/url responses".to_string(),
+            ));
+        }
+        for path in self.audio_path.iter().chain(self.error_path.iter()) {
+            template::validate_path(path).map_err(TtsError::ConfigError)?;
+        }
+        Ok(method)
+    }
+
+    /// Whether any part of the request uses `{{name}}`.
+    fn uses(&self, name: &str) -> bool {
+        self.body
+            .as_ref()
+            .is_some_and(|body| template::references(body, name))
+            || self
+                .query
+                .values()
+                .chain(self.url.iter())
+                .any(|value| template::references(&Value::String(value.clone()), name))
+    }
+}
+
+/// Config-described TTS provider — maps synthesis params onto an arbitrary
+/// HTTP API, for local servers without a dedicated provider.
+pub struct CustomHttpTtsProvider {
+    client: Client,
+    spec: CustomHttpTtsSpec,
+    method: Method,
+    base_url: String,
+    api_key: String,
+    model: Option<String>,
+    default_voice: Option<String>,
+    provider_id: String,
+}
+
+impl CustomHttpTtsProvider {
+    pub fn from_config(config: &ProviderConfig) -> Option<Self> {
+        match Self::try_from_config(config) {
+            Ok(provider) => Some(provider),
+            Err(e) => {
+                tracing::error!(target: "tts", "[CustomHTTP] Provider '{}': {}", config.id, e);
+                None
+            }
+        }
+    }
+
+    fn try_from_config(config: &ProviderConfig) -> Result<Self, TtsError> {
+        let raw = config.extra.get(CUSTOM_HTTP_EXTRA_KEY).ok_or_else(|| {
+            TtsError::ConfigError(format!("missing extra.{}", CUSTOM_HTTP_EXTRA_KEY))
+        })?;
+        let spec: CustomHttpTtsSpec = serde_json::from_value(raw.clone()).map_err(|e| {
+            TtsError::ConfigError(format!("invalid extra.{}: {}", CUSTOM_HTTP_EXTRA_KEY, e))
+        })?;
+        let base_url = config
+            .endpoint
+            .clone()
+            .or(config.base_url.clone())
+            .unwrap_or_default();
+        let method = spec.validate(&base_url)?;
+        Ok(Self {
+            client: Client::new(),
+            spec,
+            method,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            api_key: config.resolve_api_key().unwrap_or_default(),
+            model: config.model.clone().filter(|m| !m.trim().is_empty()),
+            default_voice: config.default_voice.clone(),
+            provider_id: config.id.clone(),
+        })
+    }
+
+    fn placeholder_voice_id(&self) -> String {
+        format!("{}_default", self.provider_id)
+    }
+
+    fn vars(&self, text: &str, params: &TtsParams) -> TemplateVars {
+        let mut vars = TemplateVars::new();
+        // Extra params first, so the named fields below always win.
+        for (name, value) in params.extra_params.iter().flatten() {
+            vars.set(name, value.clone());
+        }
+        let voice = params
+            .voice
+            .clone()
+            .filter(|voice| *voice != self.placeholder_voice_id())
+            .or_else(|| self.default_voice.clone());
+        vars.set("text", text)
+            .set("base_url", self.base_url.as_str())
+            .set("api_key", self.api_key.as_str())
+            .set_opt("voice", voice)
+            .set_opt("speed", params.speed)
+            .set_opt("pitch", params.pitch)
+            .set_opt("emotion", params.emotion.clone())
+            .set_opt("model", self.model.clone());
+        vars
+    }
+
+    fn json_error(&self, body: &[u8]) -> Option<String> {
+        let path = self.spec.error_path.as_deref()?;
+        select_text(&serde_json::from_slice(body).ok()?, path)
+    }
+
+    async fn decode_audio(&self, body: &[u8]) -> Result<Vec<u8>, TtsError> {
+        if self.spec.response == AudioResponse::Binary {
+            return Ok(body.to_vec());
+        }
+        let json: Value = serde_json::from_slice(body).map_err(|e| {
+            TtsError::SynthesisFailed(format!("Custom HTTP TTS response is not JSON: {}", e))
+        })?;
+        if let Some(error) = self
+            .spec
+            .error_path
+            .as_deref()
+            .and_then(|path| select_text(&json, path))
+        {
+            return Err(TtsError::SynthesisFailed(error));
+        }
+        let path = self.spec.audio_path.as_deref().unwrap_or_default();
+        let audio = select_text(&json, path).ok_or_else(|| {
+            TtsError::SynthesisFailed(format!(
+                "Custom HTTP TTS response has no audio at '{}'",
+                path
+            ))
+        })?;
+        match self.spec.response {
+            AudioResponse::Base64 => decode_base64_audio(&audio),
+            _ => {
+                let url = reqwest::Url::parse(&audio)
+                    .or_else(|_| reqwest::Url::parse(&self.base_url).and_then(|b| b.join(&audio)))
+                    .map_err(|e| {
+                        TtsError::SynthesisFailed(format!("Bad audio URL '{}': {}", audio, e))
+                    })?;
+                let response = self
+                    .client
+                    .get(url)
+                    .timeout(Duration::from_secs(self.spec.timeout_secs))
+                    .send()
+                    .await
+                    .and_then(|r| r.error_for_status())
+                    .map_err(|e| {
+                        TtsError::SynthesisFailed(format!("Custom HTTP TTS audio fetch: {}", e))
+                    })?;
+                let bytes = response.bytes().await.map_err(|e| {
+                    TtsError::SynthesisFailed(format!("Custom HTTP TTS audio fetch: {}", e))
+                })?;
+                Ok(bytes.to_vec())
+            }
+        }
+    }
+}
+
+fn decode_base64_audio(encoded: &str) -> Result<Vec<u8>, TtsError> {
+    // Accept `data:audio/wav;base64,....` as well as bare base64.
+    let payload = match encoded.split_once(";base64,") {
+        Some((prefix, data)) if prefix.starts_with("data:") => data,
+        _ => encoded,
+    };
+    base64::engine::general_purpose::STANDARD
+        .decode(payload.trim())
+        .map_err(|e| TtsError::SynthesisFailed(format!("Invalid base64 audio: {}", e)))
+}
+
+#[async_trait]
+impl TtsProvider for CustomHttpTtsProvider {
+    fn id(&self) -> String {
+        self.provider_id.clone()
+    }
+
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities {
+            supports_streaming: false,
+            supports_emotions: self.spec.uses("emotion"),
+            supports_speed: self.spec.uses("speed"),
+            supports_pitch: self.spec.uses("pitch"),
+            supports_cloning: false,
+            supports_ssml: false,
+        }
+    }
+
+    fn voices(&self) -> Vec<VoiceProfile> {
+        if self.spec.voices.is_empty() {
+            return vec![VoiceProfile {
+                voice_id: self.placeholder_voice_id(),
+                name: "Custom HTTP Default".to_string(),
+                gender: Gender::Neutral,
+                language: "auto".to_string(),
+                engine: TtsEngine::Vits,
+                provider_id: self.provider_id.clone(),
+                extra_params: Default::default(),
+            }];
+        }
+        self.spec
+            .voices
+            .iter()
+            .map(|voice| VoiceProfile {
+                voice_id: voice.id.clone(),
+                name: voice.name.clone().unwrap_or_else(|| voice.id.clone()),
+                gender: voice.gender.clone().unwrap_or(Gender::Neutral),
+                language: voice.language.clone().unwrap_or_else(|| "auto".to_string()),
+                engine: TtsEngine::Vits,
+                provider_id: self.provider_id.clone(),
+                extra_params: Default::default(),
+            })
+            .collect()
+    }
+
+    fn cache_key_salt(&self) -> Option<String> {
+        Some(
+            serde_json::json!({
+                "base_url": &self.base_url,
+                "model": self.model.as_deref(),
+                "spec": &self.spec,
+            })
+            .to_string(),
+        )
+    }
+
+    async fn is_available(&self) -> bool {
+        let Some(health_url) = self.spec.health_url.as_deref() else {
+            return true;
+        };
+        let url = self.vars("", &TtsParams::default()).interpolate(health_url);
+        match self
+            .client
+            .get(&url)
+            .timeout(Duration::from_secs(3))
+            .send()
+            .await
+        {
+            Ok(resp) => resp.status().is_success(),
+            Err(_) => false,
+        }
+    }
+
+    async fn synthesize(&self, text: &str, params: TtsParams) -> Result<Vec<u8>, TtsError> {
+        let vars = self.vars(text, &params);
+        let url = vars.interpolate(self.spec.url.as_deref().unwrap_or("{{base_url}}"));
+        let query: Vec<(String, String)> = self
+            .spec
+            .query
+            .iter()
+            .filter_map(|(name, value)| {
+                let value = match vars.render(&Value::String(value.clone()))? {
+                    Value::String(text) => text,
+                    other => other.to_string(),
+                };
+                Some((name.clone(), value))
+            })
+            .collect();
+
+        let mut request = self
+            .client
+            .request(self.method.clone(), &url)
+            .query(&query)
+            .timeout(Duration::from_secs(self.spec.timeout_secs));
+        for (name, value) in &self.spec.headers {
+            request = request.header(name.as_str(), vars.interpolate(value));
+        }
+        if let Some(body) = self.spec.body.as_ref().and_then(|body| vars.render(body)) {
+            request = request.json(&body);
+        }
+
+        let response = request.send().await.map_err(|e| {
+            TtsError::SynthesisFailed(format!("Custom HTTP TTS request to {} failed: {}", url, e))
+        })?;
+        let status = response.status();
+        let body = response.bytes().await.map_err(|e| {
+            TtsError::SynthesisFailed(format!("Custom HTTP TTS response error: {}", e))
+        })?;
+        if !status.is_success() {
+            let message = self
+                .json_error(&body)
+                .unwrap_or_else(|| String::from_utf8_lossy(&body).into_owned());
+            return Err(TtsError::SynthesisFailed(format!(
+                "Custom HTTP TTS server error (HTTP {}): {}",
+                status,
+                message.trim()
+            )));
+        }
+        self.decode_audio(&body).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn maps_params_into_request_and_decodes_json_audio() {
+        let config = ProviderConfig {
+            id: "my_tts".to_string(),
+            provider_type: "custom_http".to_string(),
+            enabled: true,
+            api_key: None,
+            api_key_env: None,
+            base_url: None,
+            endpoint: Some("http://127.0.0.1:9880/".to_string()),
+            model: None,
+            default_voice: Some("narrator".to_string()),
+            model_path: None,
+            extra: HashMap::from([(
+                CUSTOM_HTTP_EXTRA_KEY.to_string(),
+                json!({
+                    "url": "{{base_url}}/tts",
+                    "body": { "text": "{{text}}", "speaker": "{{voice}}", "speed": "{{speed}}", "style": "{{emotion}}" },
+                    "response": "base64",
+                    "audio_path": "$.data.audio"
+                }),
+            )]),
+        };
+        let provider = CustomHttpTtsProvider::from_config(&config).unwrap();
+        assert!(provider.capabilities().supports_speed);
+        assert!(!provider.capabilities().supports_pitch);
+
+        let params = TtsParams {
+            voice: Some("my_tts_default".to_string()),
+            ..TtsParams::default()
+        };
+        let vars = provider.vars("hello", &params);
+        assert_eq!(
+            vars.interpolate(provider.spec.url.as_deref().unwrap()),
+            "http://127.0.0.1:9880/tts"
+        );
+        assert_eq!(
+            vars.render(provider.spec.body.as_ref().unwrap()).unwrap(),
+            json!({ "text": "hello", "speaker": "narrator", "speed": 1.0 })
+        );
+
+        let body = json!({ "data": { "audio": "data:audio/wav;base64,UklGRg==" } });
+        let audio = provider
+            .decode_audio(body.to_string().as_bytes())
+            .await
+            .unwrap();
+        assert_eq!(audio, b"RIFF");
+
+        let mut bad = config.clone();
+        bad.extra.insert(
+            CUSTOM_HTTP_EXTRA_KEY.to_string(),
+            json!({ "body": {}, "response": "url" }),
+        );
+        assert!(CustomHttpTtsProvider::from_config(&bad).is_none());
+    }
+}
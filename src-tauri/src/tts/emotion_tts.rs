@@ -2,9 +2,16 @@
 //!
 //! Maps emotion states to speech parameter adjustments (speed, pitch)
 //! so the character's voice sounds expressive and matches their mood.
+//! Providers with their own emotion controls read `TtsParams::emotion` and
+//! [`INTENSITY_PARAM`]: Azure picks an `mstts:express-as` style
+//! ([`azure_style`]), GPT-SoVITS a per-emotion reference clip.
 
+use super::interface::TtsParams;
 use serde::{Deserialize, Serialize};
 
+/// `extra_params` key carrying the emotion intensity (0.1–1.0).
+pub const INTENSITY_PARAM: &str = "emotion_intensity";
+
 /// Modifiers applied to base TTS parameters based on emotion.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EmotionTtsModifiers {
@@ -62,10 +69,77 @@ pub fn apply_modifiers(
     (final_speed, final_pitch)
 }
 
+/// Condition chat speech on the character's current emotion: sets
+/// `params.emotion`, bends speed and pitch (scaled by intensity, so a fading
+/// feeling sounds fainter) and records the intensity for providers with
+/// native styles. A neutral or settled state leaves `params` untouched.
+pub fn condition_params(params: &mut TtsParams, emotion: &str, intensity: f32, mood: f32) {
+    if emotion == "neutral" || intensity <= 0.0 {
+        return;
+    }
+    // Coarse steps keep the synthesis cache useful while a feeling fades.
+    let intensity = ((intensity * 10.0).round() / 10.0).clamp(0.1, 1.0);
+    let modifiers = get_modifiers(emotion, mood);
+    let speed_factor = 1.0 + (modifiers.speed_factor - 1.0) * intensity;
+    let pitch_factor = 1.0 + modifiers.pitch_offset * intensity;
+
+    params.speed = Some((params.speed.unwrap_or(1.0) * speed_factor).clamp(0.5, 2.0));
+    params.pitch = Some((params.pitch.unwrap_or(1.0) * pitch_factor).clamp(0.5, 2.0));
+    params.emotion = Some(emotion.to_string());
+    params
+        .extra_params
+        .get_or_insert_with(Default::default)
+        .insert(INTENSITY_PARAM.to_string(), serde_json::json!(intensity));
+}
+
+/// Azure neural voice style for an emotion and its `styledegree` (0.01–2.0).
+pub fn azure_style(emotion: &str, intensity: Option<f32>) -> Option<(&'static str, f32)> {
+    let style = match emotion {
+        "joy" => "cheerful",
+        "love" => "affectionate",
+        "sadness" => "sad",
+        "anger" => "angry",
+        "surprise" => "excited",
+        "fear" => "fearful",
+        _ => return None,
+    };
+    let degree = 0.5 + intensity.unwrap_or(1.0).clamp(0.0, 1.0) * 1.5;
+    Some((style, (degree * 10.0).round() / 10.0))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn condition_params_scales_with_intensity() {
+        let mut strong = TtsParams::default();
+        condition_params(&mut strong, "joy", 1.0, 0.9);
+        let mut faint = TtsParams::default();
+        condition_params(&mut faint, "joy", 0.24, 0.9);
+        assert_eq!(strong.emotion.as_deref(), Some("joy"));
+        assert!(strong.speed.unwrap() > faint.speed.unwrap());
+        assert!(faint.speed.unwrap() > 1.0);
+        assert!(strong.pitch.unwrap() > 1.0);
+        assert_eq!(
+            faint.extra_params.unwrap()[INTENSITY_PARAM],
+            serde_json::json!(0.2_f32)
+        );
+
+        let mut neutral = TtsParams::default();
+        condition_params(&mut neutral, "neutral", 1.0, 0.5);
+        assert_eq!(neutral.emotion, None);
+        assert_eq!(neutral.speed, Some(1.0));
+        assert!(neutral.extra_params.is_none());
+    }
+
+    #[test]
+    fn azure_styles_follow_intensity() {
+        assert_eq!(azure_style("sadness", Some(1.0)), Some(("sad", 2.0)));
+        assert_eq!(azure_style("joy", Some(0.2)), Some(("cheerful", 0.8)));
+        assert_eq!(azure_style("neutral", Some(1.0)), None);
+    }
+
     #[test]
     fn neutral_returns_no_change() {
         let m = get_modifiers("neutral", 0.5);
@@ -195,4 +195,9 @@ pub trait TtsProvider: Send + Sync {
         let stream = futures::stream::once(async move { Ok(audio) });
         Ok(Box::pin(stream))
     }
+
+    /// Load models ahead of a request. Providers without cold starts do nothing.
+    async fn warm_up(&self) -> Result<(), TtsError> {
+        Ok(())
+    }
 }
@@ -4,7 +4,8 @@ use super::interface::{
 };
 use async_trait::async_trait;
 use reqwest::Client;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use tokio::sync::Mutex;
 
 /// Local GPT-SoVITS provider — sends HTTP requests to a local GPT-SoVITS inference server.
@@ -24,6 +25,8 @@ pub struct LocalGPTSoVITSProvider {
     default_prompt_text: Option<String>,
     default_prompt_lang: Option<String>,
     default_text_lang: String,
+    /// Emotion name → reference clip spoken in that emotion (`extra.emotion_refs`).
+    emotion_refs: BTreeMap<String, EmotionRef>,
     // Model weight paths
     gpt_weights: Option<String>,
     sovits_weights: Option<String>,
@@ -32,6 +35,19 @@ pub struct LocalGPTSoVITSProvider {
     active_sovits_weights: Mutex<Option<String>>,
 }
 
+const WARM_UP_TEXT: &str = "Hello.";
+
+/// A reference clip for one emotion. The prompt language falls back to the
+/// provider default.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct EmotionRef {
+    ref_audio_path: String,
+    #[serde(default)]
+    prompt_text: Option<String>,
+    #[serde(default)]
+    prompt_lang: Option<String>,
+}
+
 #[derive(Serialize)]
 struct GPTSoVITSRequest {
     text: String,
@@ -64,6 +80,7 @@ impl LocalGPTSoVITSProvider {
             default_prompt_text: None,
             default_prompt_lang: None,
             default_text_lang: "zh".to_string(),
+            emotion_refs: BTreeMap::new(),
             gpt_weights: None,
             sovits_weights: None,
             active_gpt_weights: Mutex::new(None),
@@ -103,6 +120,21 @@ impl LocalGPTSoVITSProvider {
             .unwrap_or("zh")
             .to_string();
 
+        let emotion_refs = config
+            .extra
+            .get("emotion_refs")
+            .and_then(|v| {
+                serde_json::from_value::<BTreeMap<String, EmotionRef>>(v.clone())
+                    .map_err(|e| {
+                        tracing::warn!(target: "tts", "[GPT-SoVITS] Ignoring invalid emotion_refs: {}", e);
+                    })
+                    .ok()
+            })
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|(_, r)| !r.ref_audio_path.trim().is_empty())
+            .collect();
+
         let gpt_weights = config
             .extra
             .get("gpt_weights")
@@ -126,76 +158,17 @@ impl LocalGPTSoVITSProvider {
             default_prompt_text,
             default_prompt_lang,
             default_text_lang,
+            emotion_refs,
             gpt_weights,
             sovits_weights,
             active_gpt_weights: Mutex::new(None),
             active_sovits_weights: Mutex::new(None),
         })
     }
-}
-
-#[async_trait]
-impl TtsProvider for LocalGPTSoVITSProvider {
-    fn id(&self) -> String {
-        self.provider_id.clone()
-    }
-
-    fn capabilities(&self) -> ProviderCapabilities {
-        ProviderCapabilities {
-            supports_streaming: false,
-            supports_emotions: false, // GPT-SoVITS uses ref audio for emotion, not explicit param yet
-            supports_speed: true,
-            supports_pitch: false,
-            supports_cloning: true, // It's literally a voice cloning model
-            supports_ssml: false,
-        }
-    }
-
-    fn voices(&self) -> Vec<VoiceProfile> {
-        // GPT-SoVITS doesn't have a fixed list of voices in the same way.
-        // It uses reference audio. We return a generic voice.
-        vec![VoiceProfile {
-            voice_id: "gpt_sovits_default".to_string(),
-            name: "GPT-SoVITS Default".to_string(),
-            gender: Gender::Neutral,
-            language: "auto".to_string(),
-            engine: TtsEngine::Vits, // It's generic/VITS-like
-            provider_id: self.provider_id.clone(),
-            extra_params: Default::default(),
-        }]
-    }
-
-    fn cache_key_salt(&self) -> Option<String> {
-        Some(
-            serde_json::json!({
-                "base_url": &self.base_url,
-                "ref_audio": self.default_ref_audio.as_deref(),
-                "prompt_text": self.default_prompt_text.as_deref(),
-                "prompt_lang": self.default_prompt_lang.as_deref(),
-                "text_lang": &self.default_text_lang,
-                "gpt_weights": self.gpt_weights.as_deref(),
-                "sovits_weights": self.sovits_weights.as_deref(),
-            })
-            .to_string(),
-        )
-    }
 
-    async fn is_available(&self) -> bool {
-        // Ping /tts endpoint — a running api_v2.py will respond (even 400 for missing params).
-        // Any HTTP response means the server is reachable.
-        let url = format!("{}/tts", self.base_url.trim_end_matches('/'));
-        (self
-            .client
-            .get(&url)
-            .timeout(std::time::Duration::from_secs(3))
-            .send()
-            .await)
-            .is_ok()
-    }
-
-    async fn synthesize(&self, text: &str, params: TtsParams) -> Result<Vec<u8>, TtsError> {
-        // Switch models if configured (server handles idempotent load).
-        // Switch models if configured (server handles idempotent load).
+    /// Switch to the configured weights if the server isn't on them yet
+    /// (the server handles idempotent loads).
+    async fn ensure_weights(&self) {
         let base = self.base_url.trim_end_matches('/');
 
         if let Some(gpt) = &self.gpt_weights {
@@ -257,31 +230,109 @@ impl TtsProvider for LocalGPTSoVITSProvider {
                 }
             }
         }
+    }
+}
 
-        // Use per-request extra_params if provided, otherwise fall back to provider defaults.
-        let ref_audio_path = params
-            .extra_params
-            .as_ref()
-            .and_then(|p| p.get("ref_audio_path"))
-            .and_then(|v| v.as_str())
-            .map(String::from)
-            .or_else(|| self.default_ref_audio.clone());
+#[async_trait]
+impl TtsProvider for LocalGPTSoVITSProvider {
+    fn id(&self) -> String {
+        self.provider_id.clone()
+    }
 
-        let prompt_text = params
-            .extra_params
-            .as_ref()
-            .and_then(|p| p.get("prompt_text"))
-            .and_then(|v| v.as_str())
-            .map(String::from)
-            .or_else(|| self.default_prompt_text.clone());
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities {
+            supports_streaming: false,
+            // Emotion comes from swapping the reference clip.
+            supports_emotions: !self.emotion_refs.is_empty(),
+            supports_speed: true,
+            supports_pitch: false,
+            supports_cloning: true, // It's literally a voice cloning model
+            supports_ssml: false,
+        }
+    }
 
-        let prompt_lang = params
-            .extra_params
-            .as_ref()
-            .and_then(|p| p.get("prompt_lang"))
-            .and_then(|v| v.as_str())
-            .map(String::from)
-            .or_else(|| self.default_prompt_lang.clone());
+    fn voices(&self) -> Vec<VoiceProfile> {
+        // GPT-SoVITS doesn't have a fixed list of voices in the same way.
+        // It uses reference audio. We return a generic voice.
+        vec![VoiceProfile {
+            voice_id: "gpt_sovits_default".to_string(),
+            name: "GPT-SoVITS Default".to_string(),
+            gender: Gender::Neutral,
+            language: "auto".to_string(),
+            engine: TtsEngine::Vits, // It's generic/VITS-like
+            provider_id: self.provider_id.clone(),
+            extra_params: Default::default(),
+        }]
+    }
+
+    fn cache_key_salt(&self) -> Option<String> {
+        Some(
+            serde_json::json!({
+                "base_url": &self.base_url,
+                "ref_audio": self.default_ref_audio.as_deref(),
+                "prompt_text": self.default_prompt_text.as_deref(),
+                "prompt_lang": self.default_prompt_lang.as_deref(),
+                "text_lang": &self.default_text_lang,
+                "emotion_refs": &self.emotion_refs,
+                "gpt_weights": self.gpt_weights.as_deref(),
+                "sovits_weights": self.sovits_weights.as_deref(),
+            })
+            .to_string(),
+        )
+    }
+
+    async fn is_available(&self) -> bool {
+        // Ping /tts endpoint — a running api_v2.py will respond (even 400 for missing params).
+        // Any HTTP response means the server is reachable.
+        let url = format!("{}/tts", self.base_url.trim_end_matches('/'));
+        (self
+            .client
+            .get(&url)
+            .timeout(std::time::Duration::from_secs(3))
+            .send()
+            .await)
+            .is_ok()
+    }
+
+    async fn synthesize(&self, text: &str, params: TtsParams) -> Result<Vec<u8>, TtsError> {
+        self.ensure_weights().await;
+
+        // Use per-request extra_params if provided, then the clip for the
+        // requested emotion, then provider defaults. A clip's transcript
+        // only pairs with that clip.
+        let extra_str = |key: &str| {
+            params
+                .extra_params
+                .as_ref()
+                .and_then(|p| p.get(key))
+                .and_then(|v| v.as_str())
+                .map(String::from)
+        };
+        let emotion_ref = params
+            .emotion
+            .as_deref()
+            .and_then(|emotion| self.emotion_refs.get(emotion));
+        let (ref_audio_path, prompt_text, prompt_lang) = match extra_str("ref_audio_path") {
+            Some(path) => (
+                Some(path),
+                extra_str("prompt_text").or_else(|| self.default_prompt_text.clone()),
+                extra_str("prompt_lang").or_else(|| self.default_prompt_lang.clone()),
+            ),
+            None => match emotion_ref {
+                Some(clip) => (
+                    Some(clip.ref_audio_path.clone()),
+                    clip.prompt_text.clone(),
+                    clip.prompt_lang
+                        .clone()
+                        .or_else(|| self.default_prompt_lang.clone()),
+                ),
+                None => (
+                    self.default_ref_audio.clone(),
+                    extra_str("prompt_text").or_else(|| self.default_prompt_text.clone()),
+                    extra_str("prompt_lang").or_else(|| self.default_prompt_lang.clone()),
+                ),
+            },
+        };
 
         let text_lang = params
             .extra_params
@@ -334,4 +385,16 @@ impl TtsProvider for LocalGPTSoVITSProvider {
             .map_err(|e| TtsError::SynthesisFailed(format!("GPT-SoVITS bytes error: {}", e)))?;
         Ok(bytes.to_vec())
     }
+
+    /// Loads the weights and runs one short synthesis so the first real
+    /// sentence doesn't pay for CUDA initialisation.
+    async fn warm_up(&self) -> Result<(), TtsError> {
+        if self.default_ref_audio.is_none() {
+            self.ensure_weights().await;
+            return Ok(());
+        }
+        self.synthesize(WARM_UP_TEXT, TtsParams::default())
+            .await
+            .map(|_| ())
+    }
 }
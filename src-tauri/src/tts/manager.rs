@@ -2,17 +2,22 @@ use super::azure::AzureTtsProvider;
 use super::browser::BrowserTTSProvider;
 use super::cache::{CacheKey, TtsCache};
 use super::cloud_base::CloudTTSProvider;
-use super::config::{ProviderConfig, TtsSystemConfig};
+use super::config::{FailoverConfig, PrefetchConfig, ProviderConfig, TtsSystemConfig};
+use super::custom_http::CustomHttpTtsProvider;
 use super::edge::EdgeTtsProvider;
 use super::interface::{ProviderCapabilities, TtsError, TtsParams, TtsProvider, VoiceProfile};
 use super::local_gpt_sovits::LocalGPTSoVITSProvider;
 use super::local_vits::LocalVITSProvider;
+use super::normalize::TextNormalizer;
 use super::omnivoice::OmniVoiceProvider;
 use super::openai::OpenAITtsProvider;
-use super::queue::TtsQueue;
+use super::prefetch::PlaybackClock;
+use super::queue::{DropReason, DroppedUtterance, TtsPriority, TtsQueue, UtteranceInfo};
 use super::router::TtsRouter;
+use super::visemes::SentenceVisemes;
 use super::voice_registry::VoiceRegistry;
 
+use crate::captions::CaptionService;
 use crate::hooks::{HookEvent, HookPayload, HookRuntime, TtsHookPayload};
 use futures::StreamExt;
 use serde::Serialize;
@@ -20,14 +25,17 @@ use sha2::{Digest, Sha256};
 use std::collections::HashMap;
 use std::pin::Pin;
 use std::sync::Arc;
+use std::time::Duration;
 use tauri::{AppHandle, Emitter, Manager};
-use tokio::sync::RwLock;
+use tokio::sync::{watch, RwLock};
 
 // ── Tauri Event Payloads ───────────────────────────────
 
 #[derive(Clone, Serialize)]
 struct TtsStartEvent {
     text: String,
+    /// Identifies the utterance in `report_tts_playback`.
+    utterance_id: u64,
 }
 
 #[derive(Clone, Serialize)]
@@ -40,6 +48,20 @@ struct TtsEndEvent {
     text: String,
 }
 
+/// Emitted when an utterance is cut short; the frontend flushes its playback queue.
+#[derive(Clone, Serialize)]
+struct TtsInterruptedEvent {
+    reason: &'static str,
+}
+
+/// Emitted when synthesis moves to another provider mid-utterance.
+#[derive(Clone, Serialize)]
+struct TtsFailoverEvent {
+    from: String,
+    to: String,
+    reason: String,
+}
+
 #[derive(Clone, Serialize)]
 struct TtsBrowserDelegateEvent {
     text: String,
@@ -57,6 +79,14 @@ pub struct ProviderStatus {
     pub capabilities: ProviderCapabilities,
 }
 
+type AudioStream = Pin<Box<dyn futures::Stream<Item = Result<Vec<u8>, TtsError>> + Send>>;
+
+/// A sentence that started producing audio (or was handed to the browser).
+enum OpenedAudio {
+    Stream(AudioStream),
+    BrowserDelegate,
+}
+
 // ── TtsService ─────────────────────────────────────────
 
 #[derive(Clone)]
@@ -65,8 +95,28 @@ pub struct TtsService {
     default_provider: Arc<RwLock<Option<String>>>,
     voice_registry: Arc<RwLock<VoiceRegistry>>,
     cache: Arc<RwLock<TtsCache>>,
-    _queue: Arc<TtsQueue>,
+    queue: Arc<TtsQueue>,
     cache_enabled: bool,
+    /// Local provider forced while the engine is in offline mode.
+    offline_provider: Arc<RwLock<Option<String>>>,
+    /// Provider/voice bound to the active character.
+    character_voice: Arc<RwLock<TtsCharacterBinding>>,
+    failover: Arc<RwLock<FailoverConfig>>,
+    /// Provider ids in config order, which is also the failover order.
+    provider_order: Arc<RwLock<Vec<String>>>,
+    normalizer: Arc<RwLock<TextNormalizer>>,
+    /// Bumped by [`TtsService::stop_speaking`]; every running `speak` watches it.
+    interrupt: Arc<watch::Sender<u64>>,
+    prefetch: Arc<RwLock<PrefetchConfig>>,
+    /// Playback position reported by the frontend.
+    playback: Arc<PlaybackClock>,
+}
+
+/// Per-character voice. Empty fields keep whatever the caller asked for.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TtsCharacterBinding {
+    pub provider_id: Option<String>,
+    pub voice: Option<String>,
 }
 
 impl Default for TtsService {
@@ -82,8 +132,16 @@ impl TtsService {
             default_provider: Arc::new(RwLock::new(None)),
             voice_registry: Arc::new(RwLock::new(VoiceRegistry::new())),
             cache: Arc::new(RwLock::new(TtsCache::new(500, 3600))),
-            _queue: Arc::new(TtsQueue::new(3)),
+            queue: Arc::new(TtsQueue::new(3, 4)),
             cache_enabled: true,
+            offline_provider: Arc::new(RwLock::new(None)),
+            character_voice: Arc::new(RwLock::new(TtsCharacterBinding::default())),
+            failover: Arc::new(RwLock::new(FailoverConfig::default())),
+            provider_order: Arc::new(RwLock::new(Vec::new())),
+            normalizer: Arc::new(RwLock::new(TextNormalizer::default())),
+            interrupt: Arc::new(watch::channel(0).0),
+            prefetch: Arc::new(RwLock::new(PrefetchConfig::default())),
+            playback: Arc::new(PlaybackClock::default()),
         }
     }
 
@@ -97,8 +155,19 @@ impl TtsService {
                 config.cache.max_entries,
                 config.cache.ttl_secs,
             ))),
-            _queue: Arc::new(TtsQueue::new(config.queue.max_concurrent)),
+            queue: Arc::new(TtsQueue::new(
+                config.queue.max_concurrent,
+                config.queue.max_depth,
+            )),
             cache_enabled: config.cache.enabled,
+            offline_provider: Arc::new(RwLock::new(None)),
+            character_voice: Arc::new(RwLock::new(TtsCharacterBinding::default())),
+            failover: Arc::new(RwLock::new(config.failover.clone())),
+            provider_order: Arc::new(RwLock::new(Vec::new())),
+            normalizer: Arc::new(RwLock::new(TextNormalizer::new(&config.normalization))),
+            interrupt: Arc::new(watch::channel(0).0),
+            prefetch: Arc::new(RwLock::new(config.prefetch.clone())),
+            playback: Arc::new(PlaybackClock::default()),
         };
 
         for provider_config in &config.providers {
@@ -150,6 +219,8 @@ impl TtsService {
             }
             "elevenlabs" => CloudTTSProvider::elevenlabs_style(config)
                 .map(|p| Box::new(p) as Box<dyn TtsProvider>),
+            "custom_http" => CustomHttpTtsProvider::from_config(config)
+                .map(|p| Box::new(p) as Box<dyn TtsProvider>),
             other => {
                 tracing::error!(target: "tts", "Unknown provider type: {}", other);
                 None
@@ -179,21 +250,76 @@ impl TtsService {
             }
         }
 
+        {
+            let mut order = self.provider_order.write().await;
+            if !order.contains(&id) {
+                order.push(id.clone());
+            }
+        }
+
         let mut providers = self.providers.write().await;
         providers.insert(id, provider);
     }
 
     /// Main synthesis method with cache → queue → route → synthesize pipeline.
+    ///
+    /// Utterances speak one at a time in `priority` order; see [`TtsQueue`].
+    /// Anything dropped on the way is reported through `tts:dropped`.
     pub async fn speak(
         &self,
         app: AppHandle,
         text: String,
         provider_id: Option<String>,
         params: Option<TtsParams>,
+        priority: TtsPriority,
+        source: Option<String>,
     ) -> Result<(), String> {
-        let params = params.unwrap_or_default();
+        self.speak_routed(app, text, None, provider_id, params, priority, source)
+            .await
+    }
 
+    /// Like [`Self::speak`], but in `voice` instead of the active character's
+    /// bound voice, e.g. for a second character in the same scene.
+    pub async fn speak_as(
+        &self,
+        app: AppHandle,
+        text: String,
+        voice: TtsCharacterBinding,
+        params: Option<TtsParams>,
+        priority: TtsPriority,
+        source: Option<String>,
+    ) -> Result<(), String> {
+        self.speak_routed(app, text, Some(voice), None, params, priority, source)
+            .await
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn speak_routed(
+        &self,
+        app: AppHandle,
+        text: String,
+        voice: Option<TtsCharacterBinding>,
+        provider_id: Option<String>,
+        params: Option<TtsParams>,
+        priority: TtsPriority,
+        source: Option<String>,
+    ) -> Result<(), String> {
+        let utterance = UtteranceInfo::new(&text, priority, source);
+        let (ticket, dropped) = self.queue.admit(utterance.clone());
+        emit_dropped(&app, dropped);
+        let Some(turn) = ticket.wait().await else {
+            return Ok(());
+        };
+
+        let interrupt_rx = self.interrupt.subscribe();
         let hook_runtime = app.try_state::<HookRuntime>();
+        let voice = match voice {
+            Some(voice) => voice,
+            None => self.character_voice.read().await.clone(),
+        };
+        let (provider_id, params) = self
+            .resolve_route_inputs_with(voice, provider_id, params)
+            .await;
 
         // Route to the best provider
         let router = TtsRouter::new(self.providers.clone(), self.default_provider.clone());
@@ -217,64 +343,88 @@ impl TtsService {
                 .await;
         }
 
+        let _speaking = crate::ai::companion_state::enter(
+            &app,
+            crate::ai::companion_state::CompanionActivity::Speaking,
+            None,
+        );
+
+        let prefetch = self.prefetch.read().await.clone();
+        let pacer = self.playback.begin(&prefetch);
+
         // Emit Start
-        app.emit("tts:start", TtsStartEvent { text: text.clone() })
-            .map_err(|e| e.to_string())?;
+        app.emit(
+            "tts:start",
+            TtsStartEvent {
+                text: text.clone(),
+                utterance_id: pacer.utterance(),
+            },
+        )
+        .map_err(|e| e.to_string())?;
 
-        // Split into sentences for incremental delivery
-        let sentences: Vec<String> = split_sentences(&text)
-            .into_iter()
-            .map(|s| s.to_string())
-            .filter(|s| !s.trim().is_empty())
+        // Split into sentences for incremental delivery. Captions show the
+        // cleaned sentence; providers get its spoken form.
+        let normalizer = self.normalizer.read().await.clone();
+        let cleaned = normalizer.clean(&text);
+        let language = crate::ai::language_detect::detect_language(&cleaned).map(|l| l.code);
+        let (caption_texts, sentences): (Vec<String>, Vec<String>) =
+            crate::utils::segment::split_sentences(&cleaned)
+                .into_iter()
+                .map(|s| (s.to_string(), normalizer.speakable(s, language)))
+                .filter(|(_, spoken)| !spoken.trim().is_empty())
+                .unzip();
+        let sentence_count = sentences.len();
+        let caption_id = uuid::Uuid::new_v4().to_string();
+        let caption_speed = params.speed;
+        let captions = app.try_state::<CaptionService>();
+        let estimates: Vec<u64> = sentences
+            .iter()
+            .map(|s| SentenceVisemes::new(s, caption_speed).estimated_ms())
             .collect();
 
-        // Pipelined synthesis: Concurrency = 2
-        // We iterate over sentences, map them to async synthesis tasks, and buffer them.
-        // buffered(n) ensures we have at most n tasks running, but yields results IN ORDER.
+        // Pipelined synthesis: each sentence waits for the pacer before it is
+        // synthesized, and buffered(n) yields the results IN ORDER.
         let service = self.clone();
         let service_for_cache = self.clone();
         let app_handle = app.clone();
-        let provider_id_route = route.provider_id.clone();
+        let failover_app = app.clone();
+        let primary_provider = route.provider_id.clone();
+        // Shared by all sentences so a failover holds for the rest of the utterance.
+        let active_provider = Arc::new(std::sync::Mutex::new(route.provider_id.clone()));
         let params_clone = params.clone();
+        let gate = pacer.clone();
 
-        let mut stream = futures::stream::iter(sentences)
+        let synthesis = futures::stream::iter(sentences.into_iter().zip(estimates))
+            .then(move |(sentence, estimate_ms)| {
+                let gate = gate.clone();
+                async move {
+                    gate.admit(estimate_ms).await;
+                    sentence
+                }
+            })
             .map(move |sentence| {
                 let service = service.clone();
                 let params = params_clone.clone();
-                let provider_id = provider_id_route.clone();
+                let primary = primary_provider.clone();
+                let active = active_provider.clone();
+                let app = failover_app.clone();
 
                 async move {
-                    let cache_salt = {
-                        let providers = service.providers.read().await;
-                        let provider = providers
-                            .get(&provider_id)
-                            .ok_or_else(|| format!("Provider {} not found", provider_id))?;
-                        cache_variant_hash(provider.as_ref(), &params)
-                    };
-                    let voice_id = params.voice.clone().unwrap_or_default();
-                    let cache_key = CacheKey::new(
-                        &sentence,
-                        &voice_id,
-                        &provider_id,
-                        params.speed,
-                        params.pitch,
-                        cache_salt.as_deref(),
-                    );
+                    let provider_id = active.lock().unwrap_or_else(|e| e.into_inner()).clone();
 
                     // 1. Check cache
                     if service.cache_enabled {
+                        let cached_params =
+                            service.params_for(&provider_id, &primary, &params).await;
+                        let cache_key = service
+                            .cache_key_for(&sentence, &provider_id, &cached_params)
+                            .await?;
                         let mut cache = service.cache.write().await;
                         if let Some(cached_audio) = cache.get(&cache_key) {
                             let stream = futures::stream::once(async move { Ok(cached_audio) });
                             return Ok((
                                 sentence,
-                                Some(Box::pin(stream)
-                                    as Pin<
-                                        Box<
-                                            dyn futures::Stream<Item = Result<Vec<u8>, TtsError>>
-                                                + Send,
-                                        >,
-                                    >),
+                                Some(Box::pin(stream) as AudioStream),
                                 None,
                                 Some(cache_key),
                             ));
@@ -283,15 +433,25 @@ impl TtsService {
                         }
                     }
 
-                    // 2. Synthesize
-                    let providers = service.providers.read().await;
-                    let provider = providers
-                        .get(&provider_id)
-                        .ok_or_else(|| format!("Provider {} not found", provider_id))?;
-
-                    match provider.synthesize_stream(&sentence, params.clone()).await {
-                        Ok(stream) => Ok((sentence, Some(stream), None, Some(cache_key))),
-                        Err(TtsError::BrowserDelegate) => {
+                    // 2. Synthesize, failing over if the provider errors or stalls
+                    let (provider_id, params, opened) = service
+                        .open_with_failover(
+                            &sentence,
+                            provider_id,
+                            &params,
+                            &primary,
+                            &active,
+                            Some(&app),
+                        )
+                        .await?;
+                    match opened {
+                        OpenedAudio::Stream(stream) => {
+                            let cache_key = service
+                                .cache_key_for(&sentence, &provider_id, &params)
+                                .await?;
+                            Ok((sentence, Some(stream), None, Some(cache_key)))
+                        }
+                        OpenedAudio::BrowserDelegate => {
                             let evt = TtsBrowserDelegateEvent {
                                 text: sentence.clone(),
                                 voice: params.voice.clone(),
@@ -300,26 +460,76 @@ impl TtsService {
                             };
                             Ok((sentence, None, Some(evt), None))
                         }
-                        Err(e) => Err(format!("Synthesis error for '{}': {}", sentence, e)),
                     }
                 }
             })
-            .buffered(2); // Pipeline depth
+            .buffered(prefetch.max_sentences.max(1));
+
+        // Synthesis runs in its own task so it keeps going while the loop
+        // below streams audio; the loop only sees finished sentences.
+        let (results_tx, results) = futures::channel::mpsc::unbounded();
+        let producer = AbortOnDrop(tokio::spawn(async move {
+            let mut synthesis = Box::pin(synthesis);
+            while let Some(result) = synthesis.next().await {
+                if results_tx.unbounded_send(result).is_err() {
+                    break;
+                }
+            }
+        }));
+        // Dropping the producer on interrupt cancels in-flight synthesis too.
+        let mut stream = results.take_until(Box::pin(interrupted(interrupt_rx.clone())));
 
         // Process results in order
-        while let Some(result) = stream.next().await {
+        let mut sentence_index = 0;
+        // Where the next chunk starts on the frontend's playback timeline.
+        let mut viseme_offset_ms = 0u64;
+        loop {
+            if turn.is_preempted() {
+                emit_dropped(
+                    &app,
+                    vec![DroppedUtterance {
+                        utterance: utterance.clone(),
+                        reason: DropReason::Preempted,
+                        unspoken_sentences: Some(sentence_count - sentence_index),
+                    }],
+                );
+                break;
+            }
+            let Some(result) = stream.next().await else {
+                break;
+            };
+            sentence_index += 1;
+            pacer.started();
+            if let (Some(captions), Ok(_)) = (captions.as_ref(), result.as_ref()) {
+                captions.assistant_sentence(
+                    &app_handle,
+                    &caption_id,
+                    &caption_texts[sentence_index - 1],
+                    caption_speed,
+                    sentence_index == sentence_count,
+                );
+            }
             match result {
-                Ok((sentence, Some(mut audio_stream), _, cache_key_opt)) => {
+                Ok((sentence, Some(audio_stream), _, cache_key_opt)) => {
+                    let mut audio_stream =
+                        audio_stream.take_until(Box::pin(interrupted(interrupt_rx.clone())));
                     let mut full_audio = Vec::new();
                     let mut failed = false;
+                    let mut visemes = SentenceVisemes::new(&sentence, caption_speed);
 
                     while let Some(chunk_res) = audio_stream.next().await {
                         match chunk_res {
                             Ok(chunk) => {
                                 full_audio.extend_from_slice(&chunk);
+                                let viseme_event = visemes.chunk_event(&chunk, viseme_offset_ms);
                                 app_handle
                                     .emit("tts:audio", TtsAudioEvent { data: chunk })
                                     .map_err(|e| e.to_string())?;
+                                if let Some(event) = viseme_event {
+                                    viseme_offset_ms += event.duration_ms;
+                                    pacer.emitted(event.duration_ms);
+                                    let _ = app_handle.emit("tts:visemes", event);
+                                }
                             }
                             Err(e) => {
                                 tracing::error!(target: "tts", "Stream error for '{}': {}", sentence, e);
@@ -329,6 +539,9 @@ impl TtsService {
                         }
                     }
 
+                    // A sentence cut off by an interrupt is incomplete; don't cache it.
+                    failed |= interrupt_rx.has_changed().unwrap_or(false);
+
                     // Cache if successful and not already cached
                     if !failed && !full_audio.is_empty() {
                         if let Some(key) = cache_key_opt {
@@ -358,6 +571,7 @@ impl TtsService {
                 }
             }
         }
+        drop(producer);
 
         // Emit End
         app.emit("tts:end", TtsEndEvent { text: text.clone() })
@@ -378,6 +592,20 @@ impl TtsService {
         Ok(())
     }
 
+    /// Record how far the frontend has played utterance `utterance_id`.
+    pub fn report_playback(&self, utterance_id: u64, position_ms: u64) {
+        self.playback.report(utterance_id, position_ms);
+    }
+
+    /// Cut off every utterance in progress: pending sentences are dropped,
+    /// in-flight synthesis is cancelled and the frontend flushes its queue.
+    pub fn stop_speaking(&self, app: &AppHandle, reason: &'static str) {
+        self.interrupt.send_modify(|epoch| *epoch += 1);
+        tracing::info!(target: "tts", "Speech interrupted ({})", reason);
+        emit_dropped(app, self.queue.clear_waiting());
+        let _ = app.emit("tts:interrupted", TtsInterruptedEvent { reason });
+    }
+
     // ── Query methods ──────────────────────────────────
 
     /// List all registered provider IDs with their status.
@@ -418,6 +646,7 @@ impl TtsService {
     pub async fn reload_from_config(&self, config: &TtsSystemConfig) -> Result<(), TtsError> {
         let mut new_providers: HashMap<String, Box<dyn TtsProvider>> = HashMap::new();
         let mut new_registry = VoiceRegistry::new();
+        let mut new_order = Vec::new();
         let mut first_provider_id: Option<String> = None;
 
         for provider_config in &config.providers {
@@ -434,6 +663,7 @@ impl TtsService {
                         first_provider_id = Some(provider_id.clone());
                     }
                     new_registry.register_all(provider.voices());
+                    new_order.push(provider_id.clone());
                     new_providers.insert(provider_id, provider);
                 }
                 None => {
@@ -482,6 +712,11 @@ impl TtsService {
             let mut default = self.default_provider.write().await;
             *default = new_default;
         }
+        *self.provider_order.write().await = new_order;
+        *self.failover.write().await = config.failover.clone();
+        *self.prefetch.write().await = config.prefetch.clone();
+        self.queue.set_max_depth(config.queue.max_depth);
+        *self.normalizer.write().await = TextNormalizer::new(&config.normalization);
 
         // Clear cache since providers changed
         self.clear_cache().await;
@@ -501,22 +736,44 @@ impl TtsService {
         text: &str,
         params: Option<TtsParams>,
     ) -> Result<Vec<u8>, String> {
-        let params = params.unwrap_or_default();
+        self.synthesize_text_with_provider(text, None, params).await
+    }
+
+    /// Like [`Self::synthesize_text`], but prefers `provider_id` when it is available.
+    pub async fn synthesize_text_with_provider(
+        &self,
+        text: &str,
+        provider_id: Option<&str>,
+        params: Option<TtsParams>,
+    ) -> Result<Vec<u8>, String> {
+        let text = &self.normalizer.read().await.normalize(text);
+        let (provider_id, params) = self
+            .resolve_route_inputs(provider_id.map(str::to_string), params)
+            .await;
         let router = TtsRouter::new(self.providers.clone(), self.default_provider.clone());
         let route = router
-            .select_provider(None, params.required_capabilities.as_ref())
+            .select_provider(
+                provider_id.as_deref(),
+                params.required_capabilities.as_ref(),
+            )
             .await
             .map_err(|e| e.to_string())?;
 
-        let providers = self.providers.read().await;
-        let provider = providers
-            .get(&route.provider_id)
-            .ok_or_else(|| format!("Provider {} not found", route.provider_id))?;
-
-        let mut stream = provider
-            .synthesize_stream(text, params)
-            .await
-            .map_err(|e| e.to_string())?;
+        let active = std::sync::Mutex::new(route.provider_id.clone());
+        let (_, _, opened) = self
+            .open_with_failover(
+                text,
+                route.provider_id.clone(),
+                &params,
+                &route.provider_id,
+                &active,
+                None,
+            )
+            .await?;
+        let mut stream = match opened {
+            OpenedAudio::Stream(stream) => stream,
+            OpenedAudio::BrowserDelegate => return Err(TtsError::BrowserDelegate.to_string()),
+        };
 
         let mut audio = Vec::new();
         while let Some(chunk_res) = stream.next().await {
@@ -528,6 +785,250 @@ impl TtsService {
         Ok(audio)
     }
 
+    // ── Failover ───────────────────────────────────────
+
+    /// Open `sentence` on `provider_id`. When that errors or produces no audio
+    /// within the latency threshold, move to the next provider in config order
+    /// that meets the requested capabilities, and make it `active` so the
+    /// remaining sentences of the utterance start there.
+    async fn open_with_failover(
+        &self,
+        sentence: &str,
+        mut provider_id: String,
+        params: &TtsParams,
+        primary: &str,
+        active: &std::sync::Mutex<String>,
+        app: Option<&AppHandle>,
+    ) -> Result<(String, TtsParams, OpenedAudio), String> {
+        let failover = self.failover.read().await.clone();
+        let caps = params.required_capabilities.as_ref();
+        let mut tried: Vec<String> = Vec::new();
+        loop {
+            let attempt_params = self.params_for(&provider_id, primary, params).await;
+            // Only cut a slow provider off when there is somewhere to go.
+            let mut excluded = tried.clone();
+            excluded.push(provider_id.clone());
+            let timeout = (failover.enabled
+                && !self.failover_candidates(&excluded, caps).await.is_empty())
+            .then_some(Duration::from_millis(failover.latency_threshold_ms));
+
+            let reason = match self
+                .open_audio(&provider_id, sentence, attempt_params.clone(), timeout)
+                .await
+            {
+                Ok(opened) => return Ok((provider_id, attempt_params, opened)),
+                Err(reason) => reason,
+            };
+            tried.push(provider_id.clone());
+            if !failover.enabled {
+                return Err(format!("Synthesis error for '{}': {}", sentence, reason));
+            }
+
+            let mut next = None;
+            for candidate in self.failover_candidates(&tried, caps).await {
+                let available = match self.providers.read().await.get(&candidate) {
+                    Some(provider) => provider.is_available().await,
+                    None => false,
+                };
+                if available {
+                    next = Some(candidate);
+                    break;
+                }
+            }
+
+            let next = {
+                let mut current = active.lock().unwrap_or_else(|e| e.into_inner());
+                if !tried.contains(&*current) {
+                    // A parallel sentence already failed over.
+                    Some(current.clone())
+                } else if let Some(next) = next {
+                    tracing::warn!(
+                        target: "tts",
+                        "Provider '{}' failed ({}); failing over to '{}'",
+                        provider_id,
+                        reason,
+                        next
+                    );
+                    if let Some(app) = app {
+                        let _ = app.emit(
+                            "tts:failover",
+                            TtsFailoverEvent {
+                                from: provider_id.clone(),
+                                to: next.clone(),
+                                reason: reason.clone(),
+                            },
+                        );
+                    }
+                    *current = next.clone();
+                    Some(next)
+                } else {
+                    None
+                }
+            };
+            match next {
+                Some(next) => provider_id = next,
+                None => return Err(format!("Synthesis error for '{}': {}", sentence, reason)),
+            }
+        }
+    }
+
+    /// Start synthesis and wait for the first chunk, so a failing or stalled
+    /// provider is caught before any of its audio is emitted.
+    async fn open_audio(
+        &self,
+        provider_id: &str,
+        sentence: &str,
+        params: TtsParams,
+        timeout: Option<Duration>,
+    ) -> Result<OpenedAudio, String> {
+        let attempt = async {
+            let opened = {
+                let providers = self.providers.read().await;
+                let provider = providers
+                    .get(provider_id)
+                    .ok_or_else(|| format!("Provider {} not found", provider_id))?;
+                provider.synthesize_stream(sentence, params).await
+            };
+            let mut stream = match opened {
+                Ok(stream) => stream,
+                Err(TtsError::BrowserDelegate) => return Ok(OpenedAudio::BrowserDelegate),
+                Err(e) => return Err(e.to_string()),
+            };
+            match stream.next().await {
+                Some(Ok(first)) => Ok(OpenedAudio::Stream(Box::pin(
+                    futures::stream::once(async move { Ok(first) }).chain(stream),
+                ))),
+                Some(Err(e)) => Err(e.to_string()),
+                None => Ok(OpenedAudio::Stream(Box::pin(futures::stream::empty()))),
+            }
+        };
+        match timeout {
+            Some(limit) => tokio::time::timeout(limit, attempt)
+                .await
+                .unwrap_or_else(|_| Err(format!("no audio within {} ms", limit.as_millis()))),
+            None => attempt.await,
+        }
+    }
+
+    /// Failover targets not yet tried, in config order, meeting every
+    /// requested capability.
+    async fn failover_candidates(
+        &self,
+        tried: &[String],
+        caps: Option<&ProviderCapabilities>,
+    ) -> Vec<String> {
+        let providers = self.providers.read().await;
+        let order = self.provider_order.read().await;
+        order
+            .iter()
+            .filter(|id| !tried.contains(id))
+            .filter(|id| {
+                providers.get(*id).is_some_and(|provider| {
+                    caps.is_none_or(|caps| provider.capabilities().match_score(caps) >= 1.0)
+                })
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// The requested voice belongs to the primary provider; a fallback keeps
+    /// it only if it has a voice with the same id.
+    async fn params_for(&self, provider_id: &str, primary: &str, params: &TtsParams) -> TtsParams {
+        let mut params = params.clone();
+        if provider_id != primary {
+            if let Some(voice) = params.voice.as_deref() {
+                let providers = self.providers.read().await;
+                let known = providers
+                    .get(provider_id)
+                    .is_some_and(|p| p.voices().iter().any(|v| v.voice_id == voice));
+                if !known {
+                    params.voice = None;
+                }
+            }
+        }
+        params
+    }
+
+    async fn cache_key_for(
+        &self,
+        sentence: &str,
+        provider_id: &str,
+        params: &TtsParams,
+    ) -> Result<CacheKey, String> {
+        let cache_salt = {
+            let providers = self.providers.read().await;
+            let provider = providers
+                .get(provider_id)
+                .ok_or_else(|| format!("Provider {} not found", provider_id))?;
+            cache_variant_hash(provider.as_ref(), params)
+        };
+        let voice_id = params.voice.clone().unwrap_or_default();
+        Ok(CacheKey::new(
+            sentence,
+            &voice_id,
+            provider_id,
+            params.speed,
+            params.pitch,
+            cache_salt.as_deref(),
+        ))
+    }
+
+    /// Prefer `provider_id` for every request while offline; `None` restores normal routing.
+    pub async fn set_offline_fallback(&self, provider_id: Option<String>) {
+        *self.offline_provider.write().await = provider_id;
+    }
+
+    /// Bind a provider/voice to the active character; the default binding clears it.
+    pub async fn set_character_voice(&self, binding: TtsCharacterBinding) {
+        *self.character_voice.write().await = binding;
+    }
+
+    /// Apply overrides in priority order: offline fallback, then the character
+    /// binding, then the caller's choice. The bound voice is skipped while
+    /// offline since it belongs to a different provider.
+    async fn resolve_route_inputs(
+        &self,
+        provider_id: Option<String>,
+        params: Option<TtsParams>,
+    ) -> (Option<String>, TtsParams) {
+        let binding = self.character_voice.read().await.clone();
+        self.resolve_route_inputs_with(binding, provider_id, params)
+            .await
+    }
+
+    async fn resolve_route_inputs_with(
+        &self,
+        binding: TtsCharacterBinding,
+        provider_id: Option<String>,
+        params: Option<TtsParams>,
+    ) -> (Option<String>, TtsParams) {
+        let mut params = params.unwrap_or_default();
+        if let Some(offline) = self.offline_provider.read().await.clone() {
+            return (Some(offline), params);
+        }
+        if binding.voice.is_some() {
+            params.voice = binding.voice;
+        }
+        (binding.provider_id.or(provider_id), params)
+    }
+
+    /// Provider chat speech is routed to when no provider is requested.
+    pub async fn routed_provider_id(&self) -> Option<String> {
+        let (provider_id, _) = self.resolve_route_inputs(None, None).await;
+        match provider_id {
+            Some(id) => Some(id),
+            None => self.default_provider.read().await.clone(),
+        }
+    }
+
+    pub async fn warm_up(&self, provider_id: &str) -> Result<(), TtsError> {
+        let providers = self.providers.read().await;
+        let provider = providers
+            .get(provider_id)
+            .ok_or_else(|| TtsError::ProviderNotFound(provider_id.to_string()))?;
+        provider.warm_up().await
+    }
+
     /// Clear the synthesis cache.
     pub async fn clear_cache(&self) {
         let mut cache = self.cache.write().await;
@@ -535,6 +1036,33 @@ impl TtsService {
     }
 }
 
+/// Resolves at the next [`TtsService::stop_speaking`].
+/// Aborts the synthesis task when the utterance ends, however it ends.
+struct AbortOnDrop(tokio::task::JoinHandle<()>);
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+async fn interrupted(mut rx: watch::Receiver<u64>) {
+    let _ = rx.changed().await;
+}
+
+fn emit_dropped(app: &AppHandle, dropped: Vec<DroppedUtterance>) {
+    for event in dropped {
+        tracing::info!(
+            target: "tts",
+            "Dropped {:?} utterance '{}' ({:?})",
+            event.utterance.priority,
+            event.utterance.preview,
+            event.reason
+        );
+        let _ = app.emit("tts:dropped", event);
+    }
+}
+
 fn cache_variant_hash(provider: &dyn TtsProvider, params: &TtsParams) -> Option<String> {
     let mut parts = Vec::new();
 
@@ -550,6 +1078,10 @@ fn cache_variant_hash(provider: &dyn TtsProvider, params: &TtsParams) -> Option<
         }
     }
 
+    if let Some(emotion) = params.emotion.as_deref().filter(|e| !e.is_empty()) {
+        parts.push(format!("emotion:{emotion}"));
+    }
+
     if parts.is_empty() {
         return None;
     }
@@ -559,29 +1091,133 @@ fn cache_variant_hash(provider: &dyn TtsProvider, params: &TtsParams) -> Option<
     Some(format!("{:x}", hasher.finalize()))
 }
 
-fn split_sentences(text: &str) -> Vec<&str> {
-    // 支持中英文标点分句
-    let mut result = Vec::new();
-    let mut last = 0;
-    for (i, c) in text.char_indices() {
-        match c {
-            '.' | '!' | '?' | '。' | '！' | '？' => {
-                let end = i + c.len_utf8();
-                let segment = &text[last..end];
-                if !segment.trim().is_empty() {
-                    result.push(segment);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+
+    enum Behaviour {
+        Ok(&'static [u8]),
+        Fail,
+        Stall,
+    }
+
+    struct MockProvider {
+        id: &'static str,
+        behaviour: Behaviour,
+    }
+
+    #[async_trait]
+    impl TtsProvider for MockProvider {
+        fn id(&self) -> String {
+            self.id.to_string()
+        }
+
+        fn capabilities(&self) -> ProviderCapabilities {
+            ProviderCapabilities::default()
+        }
+
+        fn voices(&self) -> Vec<VoiceProfile> {
+            Vec::new()
+        }
+
+        async fn is_available(&self) -> bool {
+            true
+        }
+
+        async fn synthesize(&self, _text: &str, _params: TtsParams) -> Result<Vec<u8>, TtsError> {
+            match self.behaviour {
+                Behaviour::Ok(audio) => Ok(audio.to_vec()),
+                Behaviour::Fail => Err(TtsError::SynthesisFailed("boom".to_string())),
+                Behaviour::Stall => {
+                    tokio::time::sleep(Duration::from_secs(30)).await;
+                    Ok(Vec::new())
                 }
-                last = end;
             }
-            _ => {}
         }
     }
-    // 处理末尾没有标点的剩余文本
-    if last < text.len() {
-        let remaining = &text[last..];
-        if !remaining.trim().is_empty() {
-            result.push(remaining);
+
+    async fn service(primary: Behaviour) -> TtsService {
+        let service = TtsService::new();
+        *service.failover.write().await = FailoverConfig {
+            enabled: true,
+            latency_threshold_ms: 50,
+        };
+        service
+            .register_provider(Box::new(MockProvider {
+                id: "primary",
+                behaviour: primary,
+            }))
+            .await;
+        service
+            .register_provider(Box::new(MockProvider {
+                id: "backup",
+                behaviour: Behaviour::Ok(b"backup"),
+            }))
+            .await;
+        service
+    }
+
+    #[tokio::test]
+    async fn fails_over_on_error_and_stall() {
+        let healthy = service(Behaviour::Ok(b"primary")).await;
+        assert_eq!(
+            healthy
+                .synthesize_text_with_provider("hi", Some("primary"), None)
+                .await
+                .unwrap(),
+            b"primary"
+        );
+
+        for behaviour in [Behaviour::Fail, Behaviour::Stall] {
+            let service = service(behaviour).await;
+            let active = std::sync::Mutex::new("primary".to_string());
+            let (provider_id, _, _) = service
+                .open_with_failover(
+                    "hi",
+                    "primary".to_string(),
+                    &TtsParams::default(),
+                    "primary",
+                    &active,
+                    None,
+                )
+                .await
+                .unwrap();
+            assert_eq!(provider_id, "backup");
+            assert_eq!(*active.lock().unwrap(), "backup");
+            assert_eq!(
+                service
+                    .synthesize_text_with_provider("hi", Some("primary"), None)
+                    .await
+                    .unwrap(),
+                b"backup"
+            );
         }
+
+        let disabled = service(Behaviour::Fail).await;
+        disabled.failover.write().await.enabled = false;
+        assert!(disabled
+            .synthesize_text_with_provider("hi", Some("primary"), None)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn interrupts_reach_only_running_utterances() {
+        let service = TtsService::new();
+        let running = service.interrupt.subscribe();
+        service.interrupt.send_modify(|epoch| *epoch += 1);
+        assert!(running.has_changed().unwrap());
+        tokio::time::timeout(Duration::from_millis(100), interrupted(running))
+            .await
+            .expect("running utterance should see the interrupt");
+
+        let next = service.interrupt.subscribe();
+        assert!(
+            tokio::time::timeout(Duration::from_millis(50), interrupted(next))
+                .await
+                .is_err(),
+            "an utterance started after the interrupt keeps playing"
+        );
     }
-    result
 }
@@ -3,20 +3,25 @@ pub mod browser;
 pub mod cache;
 pub mod cloud_base;
 pub mod config;
+pub mod custom_http;
 pub mod edge;
 pub mod emotion_tts;
 pub mod interface;
 pub mod local_gpt_sovits;
 pub mod local_vits;
 pub mod manager;
+pub mod normalize;
 pub mod omnivoice;
 pub mod openai;
+pub mod podcast;
+pub mod prefetch;
 pub mod queue;
 pub mod router;
+pub mod visemes;
 pub mod voice_registry;
 
 pub use config::{load_config, TtsSystemConfig};
 pub use interface::{
     Gender, ProviderCapabilities, TtsEngine, TtsError, TtsParams, TtsProvider, VoiceProfile,
 };
-pub use manager::{ProviderStatus, TtsService};
+pub use manager::{ProviderStatus, TtsCharacterBinding, TtsService};
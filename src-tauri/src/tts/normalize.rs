@@ -0,0 +1,766 @@
+//! Text normalization before synthesis.
+//!
+//! Chat replies are written for reading: markdown, code blocks, emoji and
+//! digits that many engines skip or read out badly. [`TextNormalizer`] strips
+//! the markup from the whole reply before it is split into sentences, then
+//! turns each sentence into speakable text: the user's pronunciation
+//! dictionary first, then numbers, dates, times, currency and units spelled
+//! out. Spelling out is implemented for English, Chinese and Japanese; other
+//! languages keep their digits, which their engines usually handle.
+
+use crate::error::KokoroError;
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+use std::sync::LazyLock;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PronunciationRule {
+    /// Regular expression, e.g. `\bKokoro\b`.
+    pub pattern: String,
+    /// Replacement; `$1` etc. refer to capture groups.
+    pub replacement: String,
+    #[serde(default)]
+    pub case_insensitive: bool,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct NormalizationConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Drop code blocks, markdown markers, links and emoji.
+    #[serde(default = "default_true")]
+    pub strip_markup: bool,
+    /// Spell out numbers, dates, times, currency and units.
+    #[serde(default = "default_true")]
+    pub expand_numbers: bool,
+    /// Applied in order, before numbers are spelled out.
+    #[serde(default)]
+    pub pronunciations: Vec<PronunciationRule>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for NormalizationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            strip_markup: true,
+            expand_numbers: true,
+            pronunciations: Vec::new(),
+        }
+    }
+}
+
+impl NormalizationConfig {
+    /// Reject dictionary entries whose pattern does not compile.
+    pub fn validate(&self) -> Result<(), KokoroError> {
+        for (index, rule) in self.pronunciations.iter().enumerate() {
+            compile_rule(rule).map_err(|e| {
+                KokoroError::Validation(format!("pronunciation rule {}: {}", index + 1, e))
+            })?;
+        }
+        Ok(())
+    }
+}
+
+fn compile_rule(rule: &PronunciationRule) -> Result<Regex, regex::Error> {
+    if rule.case_insensitive {
+        Regex::new(&format!("(?i){}", rule.pattern))
+    } else {
+        Regex::new(&rule.pattern)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Lang {
+    En,
+    Zh,
+    Ja,
+}
+
+impl Lang {
+    fn from_code(code: &str) -> Option<Self> {
+        match code {
+            "en" => Some(Self::En),
+            "zh" => Some(Self::Zh),
+            "ja" => Some(Self::Ja),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TextNormalizer {
+    enabled: bool,
+    strip_markup: bool,
+    expand_numbers: bool,
+    rules: Vec<(Regex, String)>,
+}
+
+impl Default for TextNormalizer {
+    fn default() -> Self {
+        Self::new(&NormalizationConfig::default())
+    }
+}
+
+impl TextNormalizer {
+    /// Invalid dictionary patterns are skipped with a warning.
+    pub fn new(config: &NormalizationConfig) -> Self {
+        let rules = config
+            .pronunciations
+            .iter()
+            .filter(|rule| rule.enabled && !rule.pattern.is_empty())
+            .filter_map(|rule| match compile_rule(rule) {
+                Ok(regex) => Some((regex, rule.replacement.clone())),
+                Err(e) => {
+                    tracing::warn!(target: "tts", "Skipping pronunciation rule '{}': {}", rule.pattern, e);
+                    None
+                }
+            })
+            .collect();
+        Self {
+            enabled: config.enabled,
+            strip_markup: config.strip_markup,
+            expand_numbers: config.expand_numbers,
+            rules,
+        }
+    }
+
+    /// Whole-reply pass, before sentence splitting (code fences span lines).
+    pub fn clean(&self, text: &str) -> String {
+        if !self.enabled || !self.strip_markup {
+            return text.to_string();
+        }
+        strip_markup(text)
+    }
+
+    /// Per-sentence pass. `language` is an ISO 639-1 code for the reply, used
+    /// when the sentence alone is too short to tell.
+    pub fn speakable(&self, sentence: &str, language: Option<&str>) -> String {
+        if !self.enabled {
+            return sentence.to_string();
+        }
+        let mut text = sentence.to_string();
+        for (regex, replacement) in &self.rules {
+            text = regex.replace_all(&text, replacement.as_str()).into_owned();
+        }
+        if self.expand_numbers && text.bytes().any(|b| b.is_ascii_digit()) {
+            let lang = crate::ai::language_detect::detect_language(&text)
+                .map(|l| l.code)
+                .or(language)
+                .and_then(Lang::from_code);
+            if let Some(lang) = lang {
+                text = expand_numbers(&text, lang);
+            }
+        }
+        text
+    }
+
+    /// Both passes, for text that is synthesized in one piece.
+    pub fn normalize(&self, text: &str) -> String {
+        let cleaned = self.clean(text);
+        let language = crate::ai::language_detect::detect_language(&cleaned).map(|l| l.code);
+        self.speakable(&cleaned, language)
+    }
+}
+
+// ── Markup ─────────────────────────────────────────────
+
+static IMAGE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"!\[([^\]]*)\]\([^)]*\)").unwrap());
+static LINK: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\[([^\]]+)\]\([^)]*\)").unwrap());
+static URL: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"https?://\S+").unwrap());
+static INLINE_CODE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"`([^`]*)`").unwrap());
+static EMPHASIS: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\*{1,3}|_{2,3}|~~").unwrap());
+static LINE_MARKER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\s*(?:#{1,6}\s+|>\s?|[-*+]\s+)").unwrap());
+
+fn strip_markup(text: &str) -> String {
+    let mut lines = Vec::new();
+    let mut in_fence = false;
+    for line in text.lines() {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence || line.trim_start().starts_with('|') && line.trim_end().ends_with('|') {
+            continue;
+        }
+        let line = LINE_MARKER.replace(line, "");
+        lines.push(line.into_owned());
+    }
+    let text = lines.join("\n");
+    let text = IMAGE.replace_all(&text, "$1");
+    let text = LINK.replace_all(&text, "$1");
+    let text = URL.replace_all(&text, "");
+    let text = INLINE_CODE.replace_all(&text, "$1");
+    let text = EMPHASIS.replace_all(&text, "");
+    let text: String = text
+        .chars()
+        .filter(|c| !crate::utils::segment::is_emoji_part(*c))
+        .collect();
+    text.lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string()
+}
+
+// ── Numbers ────────────────────────────────────────────
+
+// ASCII word boundaries: CJK characters count as word characters in Unicode
+// mode, which would hide "は10:30" from `\b`.
+static ISO_DATE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?-u:\b)(\d{4})-(\d{1,2})-(\d{1,2})(?-u:\b)").unwrap());
+static CLOCK: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?-u:\b)([01]?\d|2[0-3]):([0-5]\d)(?-u:\b)").unwrap());
+static CURRENCY: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"([$€£¥])\s?(\d{1,3}(?:,\d{3})+|\d+)(?:\.(\d{1,2}))?\b").unwrap());
+/// A number with an optional unit or ordinal suffix. Identifiers that contain
+/// digits (`mp3`, `GPT4`, `v2.0`) match the first branch and are left alone.
+static NUMBER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"[A-Za-z_][A-Za-z0-9_]*(?:\.\d+)*|(\d{1,3}(?:,\d{3})+|\d+)(?:\.(\d+))?(?:(\s?)(km/h|°C|°F|%|[A-Za-z]+|年))?",
+    )
+    .unwrap()
+});
+
+const MONTHS: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+fn expand_numbers(text: &str, lang: Lang) -> String {
+    let text = ISO_DATE.replace_all(text, |c: &Captures| {
+        let (y, m, d) = (num(&c[1]), num(&c[2]), num(&c[3]));
+        if !(1..=12).contains(&m) || !(1..=31).contains(&d) {
+            return c[0].to_string();
+        }
+        match lang {
+            Lang::En => format!(
+                "{} {}, {}",
+                MONTHS[m as usize - 1],
+                en_ordinal(d),
+                en_year(y)
+            ),
+            Lang::Zh => format!(
+                "{}年{}月{}日",
+                cjk_digits(&c[1]),
+                cjk_int(m, lang),
+                cjk_int(d, lang)
+            ),
+            Lang::Ja => format!(
+                "{}年{}月{}日",
+                cjk_int(y, lang),
+                cjk_int(m, lang),
+                cjk_int(d, lang)
+            ),
+        }
+    });
+    let text = CLOCK.replace_all(&text, |c: &Captures| {
+        let (h, m) = (num(&c[1]), num(&c[2]));
+        match lang {
+            Lang::En => match m {
+                0 if h <= 12 => format!("{} o'clock", en_int(h)),
+                0 => format!("{} hundred", en_int(h)),
+                1..=9 => format!("{} oh {}", en_int(h), en_int(m)),
+                _ => format!("{} {}", en_int(h), en_int(m)),
+            },
+            Lang::Zh | Lang::Ja => {
+                let hour = if lang == Lang::Zh { "点" } else { "時" };
+                if m == 0 {
+                    format!("{}{}", cjk_int(h, lang), hour)
+                } else {
+                    format!("{}{}{}分", cjk_int(h, lang), hour, cjk_int(m, lang))
+                }
+            }
+        }
+    });
+    let text = CURRENCY.replace_all(&text, |c: &Captures| {
+        let amount = num(&c[2].replace(',', ""));
+        let cents = c.get(3).map(|m| num(&format!("{:0<2}", m.as_str())));
+        currency_words(&c[1], amount, cents, lang)
+    });
+    NUMBER
+        .replace_all(&text, |c: &Captures| {
+            let Some(int_text) = c.get(1) else {
+                return c[0].to_string();
+            };
+            let int_text = int_text.as_str().replace(',', "");
+            let fraction = c.get(2).map(|m| m.as_str());
+            let space = c.get(3).map_or("", |m| m.as_str());
+            let unit = c.get(4).map(|m| m.as_str());
+            number_with_unit(&int_text, fraction, space, unit, lang)
+        })
+        .into_owned()
+}
+
+fn num(digits: &str) -> u64 {
+    digits.parse().unwrap_or(0)
+}
+
+fn number_with_unit(
+    int_text: &str,
+    fraction: Option<&str>,
+    space: &str,
+    unit: Option<&str>,
+    lang: Lang,
+) -> String {
+    // Phone numbers, codes with leading zeros and very long runs are read digit by digit.
+    let as_digits = int_text.len() > 15 || (int_text.len() > 1 && int_text.starts_with('0'));
+    let n = num(int_text);
+
+    if let (Some(suffix), None, true) = (unit, fraction, space.is_empty()) {
+        if lang == Lang::En && matches!(suffix, "st" | "nd" | "rd" | "th") && !as_digits {
+            return en_ordinal(n);
+        }
+        if lang == Lang::En && suffix == "s" && !as_digits {
+            // Decades: "1990s", "80s".
+            let words = if int_text.len() == 4 {
+                en_year(n)
+            } else {
+                en_int(n)
+            };
+            return match words.strip_suffix('y') {
+                Some(stem) => format!("{}ies", stem),
+                None => format!("{}s", words),
+            };
+        }
+        if suffix == "年" && int_text.len() == 4 {
+            // Chinese reads years digit by digit, Japanese as a number.
+            let year = if lang == Lang::Zh {
+                cjk_digits(int_text)
+            } else {
+                cjk_int(n, lang)
+            };
+            return format!("{}年", year);
+        }
+    }
+
+    let plural = fraction.is_some() || n != 1;
+    let spoken = if as_digits {
+        match lang {
+            Lang::En => en_digits(int_text),
+            Lang::Zh | Lang::Ja => cjk_digits(int_text),
+        }
+    } else {
+        match (lang, fraction) {
+            (Lang::En, Some(f)) => format!("{} point {}", en_int(n), en_digits(f)),
+            // Bare four-digit numbers in this range read best as years ("nineteen ninety-nine").
+            (Lang::En, None)
+                if unit.is_none() && (1100..=2099).contains(&n) && int_text.len() == 4 =>
+            {
+                en_year(n)
+            }
+            (Lang::En, None) => en_int(n),
+            (_, Some(f)) => format!("{}点{}", cjk_int(n, lang), cjk_digits(f)),
+            (_, None) => cjk_int(n, lang),
+        }
+    };
+
+    let Some(unit) = unit else {
+        return spoken;
+    };
+    match unit_words(unit, plural, lang) {
+        Some(UnitWords::Prefix(word)) => format!("{}{}", word, spoken),
+        Some(UnitWords::Suffix(word)) if lang == Lang::En => format!("{} {}", spoken, word),
+        Some(UnitWords::Suffix(word)) => format!("{}{}", spoken, word),
+        None => format!("{}{}{}", spoken, space, unit),
+    }
+}
+
+enum UnitWords {
+    Prefix(&'static str),
+    Suffix(&'static str),
+}
+
+fn unit_words(unit: &str, plural: bool, lang: Lang) -> Option<UnitWords> {
+    use UnitWords::{Prefix, Suffix};
+    let en = |one: &'static str, many: &'static str| Some(Suffix(if plural { many } else { one }));
+    match lang {
+        Lang::En => match unit {
+            "%" => Some(Suffix("percent")),
+            "°C" => en("degree Celsius", "degrees Celsius"),
+            "°F" => en("degree Fahrenheit", "degrees Fahrenheit"),
+            "km/h" => en("kilometer per hour", "kilometers per hour"),
+            "mph" => en("mile per hour", "miles per hour"),
+            "km" => en("kilometer", "kilometers"),
+            "cm" => en("centimeter", "centimeters"),
+            "mm" => en("millimeter", "millimeters"),
+            "kg" => en("kilogram", "kilograms"),
+            "mg" => en("milligram", "milligrams"),
+            "ml" | "mL" => en("milliliter", "milliliters"),
+            "ms" => en("millisecond", "milliseconds"),
+            "KB" => en("kilobyte", "kilobytes"),
+            "MB" => en("megabyte", "megabytes"),
+            "GB" => en("gigabyte", "gigabytes"),
+            "TB" => en("terabyte", "terabytes"),
+            "GHz" => Some(Suffix("gigahertz")),
+            "MHz" => Some(Suffix("megahertz")),
+            _ => None,
+        },
+        Lang::Zh => match unit {
+            "%" => Some(Prefix("百分之")),
+            "°C" => Some(Suffix("摄氏度")),
+            "°F" => Some(Suffix("华氏度")),
+            "km/h" => Some(Suffix("公里每小时")),
+            "km" => Some(Suffix("公里")),
+            "cm" => Some(Suffix("厘米")),
+            "mm" => Some(Suffix("毫米")),
+            "kg" => Some(Suffix("公斤")),
+            "mg" => Some(Suffix("毫克")),
+            "ml" | "mL" => Some(Suffix("毫升")),
+            "ms" => Some(Suffix("毫秒")),
+            _ => None,
+        },
+        Lang::Ja => match unit {
+            "%" => Some(Suffix("パーセント")),
+            "°C" | "°F" => Some(Suffix("度")),
+            "km/h" => Some(Suffix("キロ毎時")),
+            "km" => Some(Suffix("キロメートル")),
+            "cm" => Some(Suffix("センチ")),
+            "mm" => Some(Suffix("ミリ")),
+            "kg" => Some(Suffix("キロ")),
+            "mg" => Some(Suffix("ミリグラム")),
+            "ml" | "mL" => Some(Suffix("ミリリットル")),
+            "ms" => Some(Suffix("ミリ秒")),
+            _ => None,
+        },
+    }
+}
+
+fn currency_words(symbol: &str, amount: u64, cents: Option<u64>, lang: Lang) -> String {
+    match lang {
+        Lang::En => {
+            let (one, many, minor) = match symbol {
+                "$" => ("dollar", "dollars", Some("cents")),
+                "€" => ("euro", "euros", Some("cents")),
+                "£" => ("pound", "pounds", Some("pence")),
+                _ => ("yen", "yen", None),
+            };
+            let main = format!(
+                "{} {}",
+                en_int(amount),
+                if amount == 1 { one } else { many }
+            );
+            match (cents, minor) {
+                (Some(c), Some(minor)) if c > 0 => format!("{} and {} {}", main, en_int(c), minor),
+                _ => main,
+            }
+        }
+        Lang::Zh | Lang::Ja => {
+            let name = match (symbol, lang) {
+                ("$", Lang::Zh) => "美元",
+                ("€", Lang::Zh) => "欧元",
+                ("£", Lang::Zh) => "英镑",
+                ("¥", Lang::Zh) => "元",
+                ("$", _) => "ドル",
+                ("€", _) => "ユーロ",
+                ("£", _) => "ポンド",
+                _ => "円",
+            };
+            match cents.filter(|c| *c > 0) {
+                Some(c) => format!(
+                    "{}点{}{}",
+                    cjk_int(amount, lang),
+                    cjk_digits(format!("{:02}", c).trim_end_matches('0')),
+                    name
+                ),
+                None => format!("{}{}", cjk_int(amount, lang), name),
+            }
+        }
+    }
+}
+
+// ── English words ──────────────────────────────────────
+
+const EN_ONES: [&str; 20] = [
+    "zero",
+    "one",
+    "two",
+    "three",
+    "four",
+    "five",
+    "six",
+    "seven",
+    "eight",
+    "nine",
+    "ten",
+    "eleven",
+    "twelve",
+    "thirteen",
+    "fourteen",
+    "fifteen",
+    "sixteen",
+    "seventeen",
+    "eighteen",
+    "nineteen",
+];
+const EN_TENS: [&str; 10] = [
+    "", "", "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety",
+];
+const EN_SCALES: [(u64, &str); 4] = [
+    (1_000_000_000_000, "trillion"),
+    (1_000_000_000, "billion"),
+    (1_000_000, "million"),
+    (1_000, "thousand"),
+];
+
+fn en_int(n: u64) -> String {
+    if n < 20 {
+        return EN_ONES[n as usize].to_string();
+    }
+    if n < 100 {
+        let tens = EN_TENS[(n / 10) as usize];
+        return match n % 10 {
+            0 => tens.to_string(),
+            ones => format!("{}-{}", tens, EN_ONES[ones as usize]),
+        };
+    }
+    if n < 1000 {
+        let head = format!("{} hundred", EN_ONES[(n / 100) as usize]);
+        return match n % 100 {
+            0 => head,
+            rest => format!("{} {}", head, en_int(rest)),
+        };
+    }
+    for (scale, name) in EN_SCALES {
+        if n >= scale {
+            let head = format!("{} {}", en_int(n / scale), name);
+            return match n % scale {
+                0 => head,
+                rest => format!("{} {}", head, en_int(rest)),
+            };
+        }
+    }
+    unreachable!("numbers below 1000 return early")
+}
+
+fn en_ordinal(n: u64) -> String {
+    let words = en_int(n);
+    let split = words.rfind(['-', ' ']).map_or(0, |i| i + 1);
+    let (head, last) = words.split_at(split);
+    let last = match last {
+        "one" => "first".to_string(),
+        "two" => "second".to_string(),
+        "three" => "third".to_string(),
+        "five" => "fifth".to_string(),
+        "eight" => "eighth".to_string(),
+        "nine" => "ninth".to_string(),
+        "twelve" => "twelfth".to_string(),
+        word if word.ends_with('y') => format!("{}ieth", &word[..word.len() - 1]),
+        word => format!("{}th", word),
+    };
+    format!("{}{}", head, last)
+}
+
+fn en_year(y: u64) -> String {
+    match y {
+        2000 => "two thousand".to_string(),
+        2001..=2009 => format!("two thousand {}", en_int(y % 10)),
+        1100..=1999 | 2010..=2099 => {
+            let (hi, lo) = (y / 100, y % 100);
+            match lo {
+                0 => format!("{} hundred", en_int(hi)),
+                1..=9 => format!("{} oh {}", en_int(hi), en_int(lo)),
+                _ => format!("{} {}", en_int(hi), en_int(lo)),
+            }
+        }
+        _ => en_int(y),
+    }
+}
+
+fn en_digits(digits: &str) -> String {
+    digits
+        .chars()
+        .filter_map(|c| c.to_digit(10))
+        .map(|d| EN_ONES[d as usize])
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// ── Chinese / Japanese numerals ────────────────────────
+
+const CJK_DIGITS: [&str; 10] = ["零", "一", "二", "三", "四", "五", "六", "七", "八", "九"];
+
+fn cjk_digits(digits: &str) -> String {
+    digits
+        .chars()
+        .filter_map(|c| c.to_digit(10))
+        .map(|d| CJK_DIGITS[d as usize])
+        .collect()
+}
+
+/// Below 10 000. Chinese marks skipped places with 零 and Japanese drops
+/// them; Japanese also drops 一 before 十, 百 and 千.
+fn cjk_section(n: u64, lang: Lang) -> String {
+    const UNITS: [&str; 4] = ["千", "百", "十", ""];
+    let places = [n / 1000, n / 100 % 10, n / 10 % 10, n % 10];
+    let mut out = String::new();
+    let mut gap = false;
+    for (digit, unit) in places.into_iter().zip(UNITS) {
+        if digit == 0 {
+            gap |= !out.is_empty();
+            continue;
+        }
+        if gap && lang == Lang::Zh {
+            out.push('零');
+        }
+        gap = false;
+        if !(lang == Lang::Ja && digit == 1 && !unit.is_empty()) {
+            out.push_str(CJK_DIGITS[digit as usize]);
+        }
+        out.push_str(unit);
+    }
+    out
+}
+
+fn cjk_int(n: u64, lang: Lang) -> String {
+    if n == 0 {
+        return CJK_DIGITS[0].to_string();
+    }
+    let big = if lang == Lang::Zh { "亿" } else { "億" };
+    let groups = [
+        (n / 1_000_000_000_000, "兆"),
+        (n / 100_000_000 % 10_000, big),
+        (n / 10_000 % 10_000, "万"),
+        (n % 10_000, ""),
+    ];
+    let mut out = String::new();
+    let mut gap = false;
+    for (group, unit) in groups {
+        if group == 0 {
+            gap |= !out.is_empty();
+            continue;
+        }
+        if lang == Lang::Zh && !out.is_empty() && (gap || group < 1000) {
+            out.push('零');
+        }
+        gap = false;
+        let mut section = cjk_section(group, lang);
+        // Japanese keeps 一 before 万 and 億 (一万), but not before 千 within a group.
+        if lang == Lang::Ja && !unit.is_empty() && group == 1 {
+            section = CJK_DIGITS[1].to_string();
+        }
+        out.push_str(&section);
+        out.push_str(unit);
+    }
+    // 10–19 read 十, 十一… in Chinese too.
+    if lang == Lang::Zh && (10..20).contains(&n) {
+        out = out.trim_start_matches('一').to_string();
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn speak(text: &str, lang: &str) -> String {
+        let normalizer = TextNormalizer::default();
+        normalizer.speakable(&normalizer.clean(text), Some(lang))
+    }
+
+    #[test]
+    fn strips_markdown_code_and_emoji() {
+        let text = "## Plan\n- **Bold** move 😄\n```rust\nfn main() {}\n```\nSee [the docs](https://x.io) or https://y.io `now`.";
+        assert_eq!(
+            TextNormalizer::default().clean(text),
+            "Plan\nBold move\nSee the docs or now."
+        );
+    }
+
+    #[test]
+    fn spells_out_english_numbers_dates_and_units() {
+        assert_eq!(
+            speak("It's 21°C and 3.5 km away, 45% done.", "en"),
+            "It's twenty-one degrees Celsius and three point five kilometers away, forty-five percent done."
+        );
+        assert_eq!(
+            speak("Meet on 2026-10-17 at 9:05 for the 2nd time.", "en"),
+            "Meet on October seventeenth, twenty twenty-six at nine oh five for the second time."
+        );
+        assert_eq!(
+            speak("That costs $1,250.50 since 1999.", "en"),
+            "That costs one thousand two hundred fifty dollars and fifty cents since nineteen ninety-nine."
+        );
+        assert_eq!(
+            speak("Call 007 about the mp3 on GPT4 v2.0 in the 1990s.", "en"),
+            "Call zero zero seven about the mp3 on GPT4 v2.0 in the nineteen nineties."
+        );
+    }
+
+    #[test]
+    fn spells_out_chinese_and_japanese_numbers() {
+        assert_eq!(
+            speak("我有105个苹果，15%是红的。", "zh"),
+            "我有一百零五个苹果，百分之十五是红的。"
+        );
+        assert_eq!(
+            speak("2026年10点30分，气温3.5°C", "zh"),
+            "二零二六年十点三十分，气温三点五摄氏度"
+        );
+        assert_eq!(cjk_int(10_005, Lang::Zh), "一万零五");
+        assert_eq!(cjk_int(110, Lang::Zh), "一百一十");
+        assert_eq!(cjk_int(2026, Lang::Ja), "二千二十六");
+        assert_eq!(cjk_int(10_000, Lang::Ja), "一万");
+        assert_eq!(cjk_int(1_100, Lang::Ja), "千百");
+        assert_eq!(
+            speak("今日は10:30に15kmを走った。", "ja"),
+            "今日は十時三十分に十五キロメートルを走った。"
+        );
+    }
+
+    #[test]
+    fn pronunciation_rules_apply_before_numbers() {
+        let config = NormalizationConfig {
+            pronunciations: vec![
+                PronunciationRule {
+                    pattern: r"\bkokoro\b".to_string(),
+                    replacement: "koh-koh-roh".to_string(),
+                    case_insensitive: true,
+                    enabled: true,
+                },
+                PronunciationRule {
+                    pattern: r"v(\d)".to_string(),
+                    replacement: "version $1".to_string(),
+                    case_insensitive: false,
+                    enabled: true,
+                },
+            ],
+            ..Default::default()
+        };
+        assert!(config.validate().is_ok());
+        let normalizer = TextNormalizer::new(&config);
+        assert_eq!(
+            normalizer.speakable("Kokoro v2 is out.", Some("en")),
+            "koh-koh-roh version two is out."
+        );
+
+        let broken = NormalizationConfig {
+            pronunciations: vec![PronunciationRule {
+                pattern: "(".to_string(),
+                replacement: String::new(),
+                case_insensitive: false,
+                enabled: true,
+            }],
+            ..Default::default()
+        };
+        assert!(broken.validate().is_err());
+        assert!(TextNormalizer::new(&broken).rules.is_empty());
+    }
+}
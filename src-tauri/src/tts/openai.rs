@@ -2,11 +2,13 @@ use super::config::ProviderConfig;
 use super::interface::{
     Gender, ProviderCapabilities, TtsEngine, TtsError, TtsParams, TtsProvider, VoiceProfile,
 };
+use crate::utils::rate_limit::{self, Priority, RateLimiter, RateLimits};
 use async_trait::async_trait;
 use futures::Stream;
 use reqwest::Client;
 use serde::Serialize;
 use std::pin::Pin; // Add this
+use std::sync::Arc;
 
 #[derive(Serialize, Clone)]
 struct TtsRequest {
@@ -25,6 +27,8 @@ pub struct OpenAITtsProvider {
     base_url: String,
     model: String,
     default_voice: String,
+    /// Shared by every client of this provider id.
+    limiter: Arc<RateLimiter>,
 }
 
 impl OpenAITtsProvider {
@@ -42,6 +46,7 @@ impl OpenAITtsProvider {
 
         Ok(Self {
             client,
+            limiter: rate_limit::limiter(&format!("tts:{}", provider_id)),
             provider_id,
             api_key,
             base_url: base_url.unwrap_or_else(|| "https://api.openai.com/v1".to_string()),
@@ -61,6 +66,11 @@ impl OpenAITtsProvider {
             config.default_voice.clone(),
         )
         .ok()
+        .inspect(|provider| {
+            provider
+                .limiter
+                .configure(RateLimits::from_extra(&config.extra))
+        })
     }
 
     fn normalize_voice_id(&self, raw_voice: &str) -> String {
@@ -137,6 +147,7 @@ impl TtsProvider for OpenAITtsProvider {
         let api_key = self.api_key.clone();
         let body = request_body.clone();
 
+        self.limiter.acquire(0, Priority::Interactive).await;
         let response = crate::utils::http::request_with_retry(
             move || {
                 let client = client.clone();
@@ -157,6 +168,7 @@ impl TtsProvider for OpenAITtsProvider {
         )
         .await
         .map_err(|e| TtsError::SynthesisFailed(format!("Request failed: {}", e)))?;
+        self.limiter.observe(response.status(), response.headers());
 
         if !response.status().is_success() {
             let error_text = response.text().await.unwrap_or_default();
@@ -193,6 +205,7 @@ impl TtsProvider for OpenAITtsProvider {
         let api_key = self.api_key.clone();
         let body = request_body.clone();
 
+        self.limiter.acquire(0, Priority::Interactive).await;
         let response = crate::utils::http::request_with_retry(
             move || {
                 let client = client.clone();
@@ -213,6 +226,7 @@ impl TtsProvider for OpenAITtsProvider {
         )
         .await
         .map_err(|e| TtsError::SynthesisFailed(format!("Request failed: {}", e)))?;
+        self.limiter.observe(response.status(), response.headers());
 
         if !response.status().is_success() {
             let error_text = response.text().await.unwrap_or_default();
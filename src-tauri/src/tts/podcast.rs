@@ -0,0 +1,532 @@
+//! Conversation → podcast rendering: stitches per-message TTS segments into a
+//! single audio file with one chapter marker per message.
+//!
+//! WAV segments are merged into one PCM stream (with silence between turns) and
+//! chapters are written as `cue ` points with `labl` names. MP3 segments are
+//! concatenated frame-wise and chapters go into an ID3v2.4 `CTOC` / `CHAP` tag.
+
+use serde::Serialize;
+use std::io::Cursor;
+
+/// One synthesized message, in conversation order.
+pub struct RenderedSegment {
+    pub role: String,
+    pub title: String,
+    pub audio: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct Chapter {
+    pub index: usize,
+    pub role: String,
+    pub title: String,
+    pub start_ms: u64,
+    pub end_ms: u64,
+}
+
+pub struct AssembledAudio {
+    pub bytes: Vec<u8>,
+    /// File extension matching the container, without the dot.
+    pub extension: &'static str,
+    pub chapters: Vec<Chapter>,
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SegmentFormat {
+    Wav,
+    Mp3,
+}
+
+fn detect_format(audio: &[u8]) -> Option<SegmentFormat> {
+    if audio.len() >= 12 && &audio[0..4] == b"RIFF" && &audio[8..12] == b"WAVE" {
+        return Some(SegmentFormat::Wav);
+    }
+    if audio.starts_with(b"ID3")
+        || (audio.len() >= 2 && audio[0] == 0xFF && audio[1] & 0xE0 == 0xE0)
+    {
+        return Some(SegmentFormat::Mp3);
+    }
+    None
+}
+
+/// Short chapter title: `"<speaker>: <first words>"`.
+pub fn chapter_title(speaker: &str, text: &str) -> String {
+    const MAX_CHARS: usize = 40;
+    let flat = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let snippet: String = flat.chars().take(MAX_CHARS).collect();
+    if flat.chars().count() > MAX_CHARS {
+        format!("{}: {}…", speaker, snippet)
+    } else {
+        format!("{}: {}", speaker, snippet)
+    }
+}
+
+/// Stitch all segments into one file. Every segment must share a container format.
+pub fn assemble(segments: &[RenderedSegment], gap_ms: u32) -> Result<AssembledAudio, String> {
+    let first = segments
+        .first()
+        .ok_or_else(|| "No audio segments to assemble".to_string())?;
+    let format = detect_format(&first.audio)
+        .ok_or_else(|| "Unsupported audio format returned by TTS provider".to_string())?;
+    if let Some(mismatch) = segments
+        .iter()
+        .position(|s| detect_format(&s.audio) != Some(format))
+    {
+        return Err(format!(
+            "Segment {} uses a different audio format; use one TTS provider type for all speakers",
+            mismatch + 1
+        ));
+    }
+
+    match format {
+        SegmentFormat::Wav => assemble_wav(segments, gap_ms),
+        SegmentFormat::Mp3 => assemble_mp3(segments),
+    }
+}
+
+// ── WAV ────────────────────────────────────────────────
+
+fn decode_wav(audio: &[u8]) -> Result<(hound::WavSpec, Vec<i16>), String> {
+    let reader = hound::WavReader::new(Cursor::new(audio)).map_err(|e| e.to_string())?;
+    let spec = reader.spec();
+    let samples = match (spec.sample_format, spec.bits_per_sample) {
+        (hound::SampleFormat::Int, 16) => reader
+            .into_samples::<i16>()
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())?,
+        (hound::SampleFormat::Int, bits) if bits <= 32 => {
+            let shift = bits.saturating_sub(16);
+            reader
+                .into_samples::<i32>()
+                .map(|s| s.map(|v| (v >> shift) as i16))
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| e.to_string())?
+        }
+        (hound::SampleFormat::Float, 32) => reader
+            .into_samples::<f32>()
+            .map(|s| s.map(|v| (v.clamp(-1.0, 1.0) * i16::MAX as f32) as i16))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())?,
+        (format, bits) => {
+            return Err(format!(
+                "Unsupported WAV sample format {:?}/{}",
+                format, bits
+            ));
+        }
+    };
+    Ok((spec, samples))
+}
+
+/// Convert interleaved samples to the target channel count and sample rate.
+fn conform(samples: Vec<i16>, from: hound::WavSpec, channels: u16, sample_rate: u32) -> Vec<i16> {
+    let from_channels = from.channels.max(1) as usize;
+    let mono: Vec<i16> = if from_channels == 1 {
+        samples
+    } else {
+        samples
+            .chunks(from_channels)
+            .map(|frame| (frame.iter().map(|&s| s as i32).sum::<i32>() / frame.len() as i32) as i16)
+            .collect()
+    };
+
+    let resampled = if from.sample_rate == sample_rate || mono.is_empty() {
+        mono
+    } else {
+        let ratio = from.sample_rate as f64 / sample_rate as f64;
+        let out_len = (mono.len() as f64 / ratio).round() as usize;
+        (0..out_len)
+            .map(|i| {
+                let pos = i as f64 * ratio;
+                let idx = pos.floor() as usize;
+                let frac = pos - idx as f64;
+                let a = mono[idx.min(mono.len() - 1)] as f64;
+                let b = mono[(idx + 1).min(mono.len() - 1)] as f64;
+                (a + (b - a) * frac) as i16
+            })
+            .collect()
+    };
+
+    resampled
+        .into_iter()
+        .flat_map(|s| std::iter::repeat_n(s, channels as usize))
+        .collect()
+}
+
+fn assemble_wav(segments: &[RenderedSegment], gap_ms: u32) -> Result<AssembledAudio, String> {
+    let (first_spec, _) = decode_wav(&segments[0].audio)?;
+    let channels = first_spec.channels.max(1);
+    let sample_rate = first_spec.sample_rate;
+    let gap_frames = gap_ms as u64 * sample_rate as u64 / 1000;
+    let gap = vec![0i16; gap_frames as usize * channels as usize];
+
+    let mut pcm: Vec<i16> = Vec::new();
+    let mut chapters = Vec::with_capacity(segments.len());
+    let mut cue_frames = Vec::with_capacity(segments.len());
+
+    for (index, segment) in segments.iter().enumerate() {
+        let (spec, samples) = decode_wav(&segment.audio)?;
+        let samples = if spec.channels == channels && spec.sample_rate == sample_rate {
+            samples
+        } else {
+            conform(samples, spec, channels, sample_rate)
+        };
+        if index > 0 {
+            pcm.extend_from_slice(&gap);
+        }
+        let start_frame = (pcm.len() / channels as usize) as u64;
+        pcm.extend_from_slice(&samples);
+        let end_frame = (pcm.len() / channels as usize) as u64;
+        cue_frames.push(start_frame as u32);
+        chapters.push(Chapter {
+            index,
+            role: segment.role.clone(),
+            title: segment.title.clone(),
+            start_ms: start_frame * 1000 / sample_rate as u64,
+            end_ms: end_frame * 1000 / sample_rate as u64,
+        });
+    }
+
+    let duration_ms = chapters.last().map(|c| c.end_ms).unwrap_or(0);
+    let labels: Vec<&str> = chapters.iter().map(|c| c.title.as_str()).collect();
+    Ok(AssembledAudio {
+        bytes: write_wav_with_cues(&pcm, channels, sample_rate, &cue_frames, &labels),
+        extension: "wav",
+        chapters,
+        duration_ms,
+    })
+}
+
+fn push_chunk(out: &mut Vec<u8>, id: &[u8; 4], body: &[u8]) {
+    out.extend_from_slice(id);
+    out.extend_from_slice(&(body.len() as u32).to_le_bytes());
+    out.extend_from_slice(body);
+    if !body.len().is_multiple_of(2) {
+        out.push(0);
+    }
+}
+
+/// 16-bit PCM WAV with a `cue ` chunk and `LIST/adtl` labels (one per chapter).
+fn write_wav_with_cues(
+    pcm: &[i16],
+    channels: u16,
+    sample_rate: u32,
+    cue_frames: &[u32],
+    labels: &[&str],
+) -> Vec<u8> {
+    let block_align = channels * 2;
+    let mut fmt = Vec::with_capacity(16);
+    fmt.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    fmt.extend_from_slice(&channels.to_le_bytes());
+    fmt.extend_from_slice(&sample_rate.to_le_bytes());
+    fmt.extend_from_slice(&(sample_rate * block_align as u32).to_le_bytes());
+    fmt.extend_from_slice(&block_align.to_le_bytes());
+    fmt.extend_from_slice(&16u16.to_le_bytes());
+
+    let data: Vec<u8> = pcm.iter().flat_map(|s| s.to_le_bytes()).collect();
+
+    let mut cue = Vec::new();
+    cue.extend_from_slice(&(cue_frames.len() as u32).to_le_bytes());
+    for (i, frame) in cue_frames.iter().enumerate() {
+        let id = i as u32 + 1;
+        cue.extend_from_slice(&id.to_le_bytes());
+        cue.extend_from_slice(&frame.to_le_bytes()); // position
+        cue.extend_from_slice(b"data");
+        cue.extend_from_slice(&0u32.to_le_bytes()); // chunk start
+        cue.extend_from_slice(&0u32.to_le_bytes()); // block start
+        cue.extend_from_slice(&frame.to_le_bytes()); // sample offset
+    }
+
+    let mut adtl = b"adtl".to_vec();
+    for (i, label) in labels.iter().enumerate() {
+        let mut body = (i as u32 + 1).to_le_bytes().to_vec();
+        body.extend_from_slice(label.as_bytes());
+        body.push(0);
+        push_chunk(&mut adtl, b"labl", &body);
+    }
+
+    let mut riff = b"WAVE".to_vec();
+    push_chunk(&mut riff, b"fmt ", &fmt);
+    push_chunk(&mut riff, b"data", &data);
+    push_chunk(&mut riff, b"cue ", &cue);
+    push_chunk(&mut riff, b"LIST", &adtl);
+
+    let mut out = Vec::with_capacity(riff.len() + 8);
+    push_chunk(&mut out, b"RIFF", &riff);
+    out
+}
+
+// ── MP3 ────────────────────────────────────────────────
+
+/// Drop a leading ID3v2 tag and trailing ID3v1 tag so frames can be concatenated.
+fn strip_id3(audio: &[u8]) -> &[u8] {
+    let mut data = audio;
+    if data.len() >= 10 && data.starts_with(b"ID3") {
+        let size = syncsafe_decode(&data[6..10]) as usize;
+        let footer = if data[5] & 0x10 != 0 { 10 } else { 0 };
+        data = &data[(10 + size + footer).min(data.len())..];
+    }
+    if data.len() >= 128 && &data[data.len() - 128..data.len() - 125] == b"TAG" {
+        data = &data[..data.len() - 128];
+    }
+    data
+}
+
+fn syncsafe_decode(bytes: &[u8]) -> u32 {
+    bytes
+        .iter()
+        .fold(0u32, |acc, &b| (acc << 7) | (b & 0x7F) as u32)
+}
+
+fn syncsafe_encode(value: u32) -> [u8; 4] {
+    [
+        ((value >> 21) & 0x7F) as u8,
+        ((value >> 14) & 0x7F) as u8,
+        ((value >> 7) & 0x7F) as u8,
+        (value & 0x7F) as u8,
+    ]
+}
+
+/// Sum MPEG audio frame durations. Garbage bytes between frames are skipped.
+fn mp3_duration_ms(data: &[u8]) -> u64 {
+    const V1_L1: [u32; 15] = [
+        0, 32, 64, 96, 128, 160, 192, 224, 256, 288, 320, 352, 384, 416, 448,
+    ];
+    const V1_L2: [u32; 15] = [
+        0, 32, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320, 384,
+    ];
+    const V1_L3: [u32; 15] = [
+        0, 32, 40, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320,
+    ];
+    const V2_L1: [u32; 15] = [
+        0, 32, 48, 56, 64, 80, 96, 112, 128, 144, 160, 176, 192, 224, 256,
+    ];
+    const V2_L23: [u32; 15] = [0, 8, 16, 24, 32, 40, 48, 56, 64, 80, 96, 112, 128, 144, 160];
+
+    let mut total_us: u64 = 0;
+    let mut i = 0usize;
+    while i + 4 <= data.len() {
+        let header = u32::from_be_bytes([data[i], data[i + 1], data[i + 2], data[i + 3]]);
+        let version = (header >> 19) & 0x3; // 0 = 2.5, 2 = 2, 3 = 1
+        let layer = (header >> 17) & 0x3; // 1 = III, 2 = II, 3 = I
+        let bitrate_idx = ((header >> 12) & 0xF) as usize;
+        let rate_idx = ((header >> 10) & 0x3) as usize;
+        let padding = (header >> 9) & 0x1;
+
+        if (header >> 21) & 0x7FF != 0x7FF
+            || version == 1
+            || layer == 0
+            || bitrate_idx == 0
+            || bitrate_idx == 15
+            || rate_idx == 3
+        {
+            i += 1;
+            continue;
+        }
+
+        let sample_rate = [44_100u32, 48_000, 32_000][rate_idx]
+            >> match version {
+                3 => 0,
+                2 => 1,
+                _ => 2,
+            };
+        let mpeg1 = version == 3;
+        let bitrate_kbps = match (mpeg1, layer) {
+            (true, 3) => V1_L1[bitrate_idx],
+            (true, 2) => V1_L2[bitrate_idx],
+            (true, _) => V1_L3[bitrate_idx],
+            (false, 3) => V2_L1[bitrate_idx],
+            (false, _) => V2_L23[bitrate_idx],
+        };
+        let samples: u32 = match layer {
+            3 => 384,
+            2 => 1152,
+            _ if mpeg1 => 1152,
+            _ => 576,
+        };
+        let frame_len = (if layer == 3 {
+            (12 * bitrate_kbps * 1000 / sample_rate + padding) * 4
+        } else {
+            samples / 8 * bitrate_kbps * 1000 / sample_rate + padding
+        }) as usize;
+
+        total_us += samples as u64 * 1_000_000 / sample_rate as u64;
+        i += frame_len.max(1);
+    }
+    total_us / 1000
+}
+
+fn id3_frame(id: &[u8; 4], body: &[u8]) -> Vec<u8> {
+    let mut frame = id.to_vec();
+    frame.extend_from_slice(&syncsafe_encode(body.len() as u32));
+    frame.extend_from_slice(&[0, 0]);
+    frame.extend_from_slice(body);
+    frame
+}
+
+/// ID3v2.4 tag with a top-level ordered `CTOC` and one `CHAP` (+ `TIT2`) per chapter.
+fn chapter_tag(chapters: &[Chapter]) -> Vec<u8> {
+    let element_id = |i: usize| format!("ch{}", i);
+
+    let mut frames = Vec::new();
+    let mut toc = b"toc\0".to_vec();
+    toc.push(0x03); // top-level | ordered
+    let toc_entries = chapters.len().min(u8::MAX as usize);
+    toc.push(toc_entries as u8);
+    for chapter in &chapters[..toc_entries] {
+        toc.extend_from_slice(element_id(chapter.index).as_bytes());
+        toc.push(0);
+    }
+    frames.extend(id3_frame(b"CTOC", &toc));
+
+    for chapter in chapters {
+        let mut body = element_id(chapter.index).into_bytes();
+        body.push(0);
+        body.extend_from_slice(&(chapter.start_ms as u32).to_be_bytes());
+        body.extend_from_slice(&(chapter.end_ms as u32).to_be_bytes());
+        body.extend_from_slice(&u32::MAX.to_be_bytes()); // byte offsets unused
+        body.extend_from_slice(&u32::MAX.to_be_bytes());
+        let mut title = vec![0x03]; // UTF-8
+        title.extend_from_slice(chapter.title.as_bytes());
+        body.extend(id3_frame(b"TIT2", &title));
+        frames.extend(id3_frame(b"CHAP", &body));
+    }
+
+    let mut tag = b"ID3".to_vec();
+    tag.extend_from_slice(&[4, 0, 0]);
+    tag.extend_from_slice(&syncsafe_encode(frames.len() as u32));
+    tag.extend(frames);
+    tag
+}
+
+fn assemble_mp3(segments: &[RenderedSegment]) -> Result<AssembledAudio, String> {
+    let mut body = Vec::new();
+    let mut chapters = Vec::with_capacity(segments.len());
+    let mut cursor_ms = 0u64;
+
+    for (index, segment) in segments.iter().enumerate() {
+        let frames = strip_id3(&segment.audio);
+        let duration = mp3_duration_ms(frames);
+        chapters.push(Chapter {
+            index,
+            role: segment.role.clone(),
+            title: segment.title.clone(),
+            start_ms: cursor_ms,
+            end_ms: cursor_ms + duration,
+        });
+        cursor_ms += duration;
+        body.extend_from_slice(frames);
+    }
+
+    let mut bytes = chapter_tag(&chapters);
+    bytes.extend(body);
+    Ok(AssembledAudio {
+        bytes,
+        extension: "mp3",
+        chapters,
+        duration_ms: cursor_ms,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wav(sample_rate: u32, channels: u16, frames: usize) -> Vec<u8> {
+        let spec = hound::WavSpec {
+            channels,
+            sample_rate,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut cursor = Cursor::new(Vec::new());
+        {
+            let mut writer = hound::WavWriter::new(&mut cursor, spec).unwrap();
+            for i in 0..frames * channels as usize {
+                writer.write_sample((i % 100) as i16).unwrap();
+            }
+            writer.finalize().unwrap();
+        }
+        cursor.into_inner()
+    }
+
+    fn segment(role: &str, audio: Vec<u8>) -> RenderedSegment {
+        RenderedSegment {
+            role: role.to_string(),
+            title: chapter_title(role, "hello there"),
+            audio,
+        }
+    }
+
+    /// MPEG-1 Layer III, 128 kbps, 44.1 kHz, no padding → 417-byte frames of 1152 samples.
+    fn mp3_frames(count: usize) -> Vec<u8> {
+        let mut out = Vec::new();
+        for _ in 0..count {
+            let mut frame = vec![0u8; 417];
+            frame[..4].copy_from_slice(&[0xFF, 0xFB, 0x90, 0x00]);
+            out.extend(frame);
+        }
+        out
+    }
+
+    #[test]
+    fn wav_segments_merge_with_gaps_and_cues() {
+        let segments = vec![
+            segment("user", wav(16_000, 1, 16_000)),
+            segment("assistant", wav(16_000, 1, 8_000)),
+        ];
+        let out = assemble(&segments, 500).unwrap();
+        assert_eq!(out.extension, "wav");
+        assert_eq!(out.chapters[0].start_ms, 0);
+        assert_eq!(out.chapters[0].end_ms, 1000);
+        assert_eq!(out.chapters[1].start_ms, 1500);
+        assert_eq!(out.duration_ms, 2000);
+
+        let reader = hound::WavReader::new(Cursor::new(&out.bytes)).unwrap();
+        assert_eq!(reader.duration(), 32_000);
+        assert!(out.bytes.windows(4).any(|w| w == b"cue "));
+    }
+
+    #[test]
+    fn wav_segments_are_conformed_to_first_spec() {
+        let segments = vec![
+            segment("user", wav(16_000, 1, 1_600)),
+            segment("assistant", wav(32_000, 2, 3_200)),
+        ];
+        let out = assemble(&segments, 0).unwrap();
+        let reader = hound::WavReader::new(Cursor::new(&out.bytes)).unwrap();
+        assert_eq!(reader.spec().channels, 1);
+        assert_eq!(reader.duration(), 3_200);
+    }
+
+    #[test]
+    fn mp3_segments_get_chapter_tag() {
+        let segments = vec![
+            segment("user", mp3_frames(10)),
+            segment("assistant", mp3_frames(20)),
+        ];
+        let out = assemble(&segments, 0).unwrap();
+        assert_eq!(out.extension, "mp3");
+        assert!(out.bytes.starts_with(b"ID3"));
+        assert_eq!(out.chapters[0].end_ms, 261);
+        assert_eq!(out.chapters[1].start_ms, 261);
+        assert_eq!(out.duration_ms, 261 + 522);
+        assert!(out.bytes.windows(4).any(|w| w == b"CHAP"));
+    }
+
+    #[test]
+    fn mixed_formats_are_rejected() {
+        let segments = vec![
+            segment("user", wav(16_000, 1, 10)),
+            segment("assistant", mp3_frames(1)),
+        ];
+        assert!(assemble(&segments, 0).is_err());
+    }
+
+    #[test]
+    fn chapter_titles_are_truncated() {
+        let title = chapter_title("Kokoro", &"word ".repeat(30));
+        assert!(title.starts_with("Kokoro: word"));
+        assert!(title.ends_with('…'));
+    }
+}
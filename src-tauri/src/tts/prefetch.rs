@@ -0,0 +1,218 @@
+//! Synthesis pacing against the frontend's playback position.
+//!
+//! The frontend owns playback and reports how far into the current utterance
+//! it is through `report_tts_playback`, on the same timeline as the
+//! `tts:visemes` offsets. A sentence is only sent for synthesis while the audio
+//! already delivered plus the estimated length of sentences still being
+//! synthesized covers less than `lead_ms` of playback, so slow playback does
+//! not pile up audio that an interrupt throws away and fast playback does not
+//! run dry. Until the first report of an utterance arrives (browser speech,
+//! or a frontend that does not report) a fixed number of sentences is kept in
+//! flight instead.
+
+use super::config::PrefetchConfig;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::Notify;
+
+/// Sentences kept in flight while there is no playback position to go by.
+const FALLBACK_DEPTH: usize = 2;
+/// Re-check interval in case a wake-up is missed.
+const RECHECK: Duration = Duration::from_millis(250);
+
+#[derive(Debug, Default)]
+struct Position {
+    utterance: u64,
+    played_ms: Option<u64>,
+}
+
+/// Playback position of the utterance being spoken.
+#[derive(Debug, Default)]
+pub struct PlaybackClock {
+    position: Mutex<Position>,
+    changed: Notify,
+}
+
+impl PlaybackClock {
+    /// Start a new utterance timeline and return the pacer for it.
+    pub fn begin(self: &Arc<Self>, config: &PrefetchConfig) -> Pacer {
+        let utterance = {
+            let mut position = self.position.lock().unwrap_or_else(|e| e.into_inner());
+            position.utterance += 1;
+            position.played_ms = None;
+            position.utterance
+        };
+        Pacer {
+            clock: self.clone(),
+            utterance,
+            lead_ms: config.lead_ms,
+            max_sentences: config.max_sentences.max(1),
+            state: Arc::new(Mutex::new(PacerState::default())),
+        }
+    }
+
+    /// Record a playback report; stale utterances and rewinds are ignored.
+    pub fn report(&self, utterance: u64, played_ms: u64) {
+        let mut position = self.position.lock().unwrap_or_else(|e| e.into_inner());
+        if position.utterance != utterance || position.played_ms >= Some(played_ms) {
+            return;
+        }
+        position.played_ms = Some(played_ms);
+        drop(position);
+        self.changed.notify_waiters();
+    }
+
+    fn played_ms(&self, utterance: u64) -> Option<u64> {
+        let position = self.position.lock().unwrap_or_else(|e| e.into_inner());
+        (position.utterance == utterance)
+            .then_some(position.played_ms)
+            .flatten()
+    }
+}
+
+#[derive(Debug, Default)]
+struct PacerState {
+    /// Audio handed to the frontend so far.
+    emitted_ms: u64,
+    /// Estimated length of sentences sent for synthesis whose audio has not
+    /// started arriving, oldest first.
+    pending: VecDeque<u64>,
+}
+
+/// Decides when the next sentence of one utterance may be synthesized.
+#[derive(Clone)]
+pub struct Pacer {
+    clock: Arc<PlaybackClock>,
+    utterance: u64,
+    lead_ms: u64,
+    max_sentences: usize,
+    state: Arc<Mutex<PacerState>>,
+}
+
+impl Pacer {
+    pub fn utterance(&self) -> u64 {
+        self.utterance
+    }
+
+    /// Audio queued ahead of the playback position, once playback reports.
+    pub fn ahead_ms(&self) -> Option<u64> {
+        let played = self.clock.played_ms(self.utterance)?;
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let queued = state.emitted_ms + state.pending.iter().sum::<u64>();
+        Some(queued.saturating_sub(played))
+    }
+
+    fn has_room(&self) -> bool {
+        let pending = self
+            .state
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .pending
+            .len();
+        if pending >= self.max_sentences {
+            return false;
+        }
+        match self.ahead_ms() {
+            Some(ahead) => ahead < self.lead_ms.max(1),
+            None => pending < FALLBACK_DEPTH,
+        }
+    }
+
+    /// Wait until a sentence estimated at `estimate_ms` may be synthesized.
+    pub async fn admit(&self, estimate_ms: u64) {
+        loop {
+            let changed = self.clock.changed.notified();
+            if self.has_room() {
+                break;
+            }
+            let _ = tokio::time::timeout(RECHECK, changed).await;
+        }
+        self.state
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .pending
+            .push_back(estimate_ms);
+    }
+
+    /// The oldest pending sentence started producing audio.
+    pub fn started(&self) {
+        self.state
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .pending
+            .pop_front();
+        self.clock.changed.notify_waiters();
+    }
+
+    /// `duration_ms` of audio was handed to the frontend.
+    pub fn emitted(&self, duration_ms: u64) {
+        self.state
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .emitted_ms += duration_ms;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(lead_ms: u64) -> PrefetchConfig {
+        PrefetchConfig {
+            lead_ms,
+            max_sentences: 4,
+        }
+    }
+
+    #[tokio::test]
+    async fn without_reports_two_sentences_stay_in_flight() {
+        let clock = Arc::new(PlaybackClock::default());
+        let pacer = clock.begin(&config(5_000));
+        pacer.admit(1_000).await;
+        pacer.admit(1_000).await;
+        assert!(!pacer.has_room());
+        pacer.started();
+        assert!(pacer.has_room());
+    }
+
+    #[tokio::test]
+    async fn synthesis_waits_for_playback_to_catch_up() {
+        let clock = Arc::new(PlaybackClock::default());
+        let pacer = clock.begin(&config(3_000));
+        clock.report(pacer.utterance(), 0);
+        pacer.admit(2_000).await;
+        pacer.started();
+        pacer.emitted(2_000);
+        pacer.admit(2_000).await;
+        // 4 s queued against a 3 s lead.
+        assert_eq!(pacer.ahead_ms(), Some(4_000));
+        assert!(!pacer.has_room());
+
+        let waiting = tokio::spawn({
+            let pacer = pacer.clone();
+            async move { pacer.admit(2_000).await }
+        });
+        tokio::task::yield_now().await;
+        assert!(!waiting.is_finished());
+
+        // Reports for an older utterance or going backwards change nothing.
+        clock.report(pacer.utterance() - 1, 5_000);
+        assert_eq!(pacer.ahead_ms(), Some(4_000));
+        clock.report(pacer.utterance(), 1_500);
+        waiting.await.unwrap();
+        assert_eq!(pacer.ahead_ms(), Some(4_500));
+        clock.report(pacer.utterance(), 1_000);
+        assert_eq!(pacer.ahead_ms(), Some(4_500));
+    }
+
+    #[tokio::test]
+    async fn a_new_utterance_forgets_the_old_position() {
+        let clock = Arc::new(PlaybackClock::default());
+        let first = clock.begin(&config(3_000));
+        clock.report(first.utterance(), 10_000);
+        let second = clock.begin(&config(3_000));
+        assert_eq!(second.ahead_ms(), None);
+        assert_eq!(first.ahead_ms(), None);
+    }
+}
@@ -1,21 +1,217 @@
-use std::sync::Arc;
-use tokio::sync::Semaphore;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use tokio::sync::{oneshot, Semaphore};
 use tokio::task::JoinHandle;
 
 use super::interface::TtsError;
 
-/// Concurrency-limited async queue for TTS generation.
+const PREVIEW_CHARS: usize = 60;
+
+/// How urgently an utterance should be spoken.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum TtsPriority {
+    /// Proactive lines and mirrored replies; first to be dropped.
+    Low,
+    /// Chat replies.
+    #[default]
+    Normal,
+    /// Direct user requests (voice tests, read-aloud).
+    High,
+}
+
+/// Why an utterance was dropped before it finished.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DropReason {
+    /// A newer utterance from the same source replaced it while it waited.
+    Superseded,
+    /// A higher-priority utterance cut it off at a sentence boundary.
+    Preempted,
+    /// The queue was over `max_depth`.
+    QueueFull,
+    /// Speech was stopped (barge-in or the stop button).
+    Interrupted,
+}
+
+/// One utterance as reported in `tts:dropped`.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct UtteranceInfo {
+    pub id: String,
+    pub preview: String,
+    pub priority: TtsPriority,
+    /// Utterances sharing a source supersede each other while waiting.
+    pub source: Option<String>,
+}
+
+impl UtteranceInfo {
+    pub fn new(text: &str, priority: TtsPriority, source: Option<String>) -> Self {
+        let trimmed = text.trim();
+        let mut preview: String = trimmed.chars().take(PREVIEW_CHARS).collect();
+        if trimmed.chars().count() > PREVIEW_CHARS {
+            preview.push('…');
+        }
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            preview,
+            priority,
+            source: source.filter(|s| !s.trim().is_empty()),
+        }
+    }
+}
+
+/// Payload of `tts:dropped`.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct DroppedUtterance {
+    #[serde(flatten)]
+    pub utterance: UtteranceInfo,
+    pub reason: DropReason,
+    /// Sentences that were never spoken; `None` when it never started.
+    pub unspoken_sentences: Option<usize>,
+}
+
+impl DroppedUtterance {
+    fn waiting(utterance: UtteranceInfo, reason: DropReason) -> Self {
+        Self {
+            utterance,
+            reason,
+            unspoken_sentences: None,
+        }
+    }
+}
+
+struct Waiting {
+    info: UtteranceInfo,
+    wake: oneshot::Sender<()>,
+}
+
+struct Speaking {
+    info: UtteranceInfo,
+    preempted: bool,
+}
+
+/// Which utterance is speaking and which wait, in arrival order.
+struct ScheduleState {
+    speaking: Option<Speaking>,
+    waiting: Vec<Waiting>,
+    max_depth: usize,
+}
+
+impl ScheduleState {
+    fn new(max_depth: usize) -> Self {
+        Self {
+            speaking: None,
+            waiting: Vec::new(),
+            max_depth: max_depth.max(1),
+        }
+    }
+
+    fn admit(&mut self, item: Waiting) -> Vec<DroppedUtterance> {
+        let mut dropped = Vec::new();
+        if let Some(source) = item.info.source.as_deref() {
+            let (stale, kept): (Vec<_>, Vec<_>) = std::mem::take(&mut self.waiting)
+                .into_iter()
+                .partition(|w| w.info.source.as_deref() == Some(source));
+            self.waiting = kept;
+            dropped.extend(
+                stale
+                    .into_iter()
+                    .map(|w| DroppedUtterance::waiting(w.info, DropReason::Superseded)),
+            );
+        }
+        if let Some(speaking) = self.speaking.as_mut() {
+            if item.info.priority > speaking.info.priority {
+                speaking.preempted = true;
+            }
+        }
+        self.waiting.push(item);
+        while self.waiting.len() > self.max_depth {
+            // Lowest priority first; among equals the one that waited longest.
+            let Some(index) = self
+                .waiting
+                .iter()
+                .enumerate()
+                .min_by_key(|(index, w)| (w.info.priority, *index))
+                .map(|(index, _)| index)
+            else {
+                break;
+            };
+            let evicted = self.waiting.remove(index);
+            dropped.push(DroppedUtterance::waiting(
+                evicted.info,
+                DropReason::QueueFull,
+            ));
+        }
+        self.dispatch();
+        dropped
+    }
+
+    /// Start the best waiting utterance if nothing is speaking.
+    fn dispatch(&mut self) {
+        while self.speaking.is_none() {
+            // Highest priority first; among equals the one that arrived first.
+            let Some(index) = self
+                .waiting
+                .iter()
+                .enumerate()
+                .max_by_key(|(index, w)| (w.info.priority, std::cmp::Reverse(*index)))
+                .map(|(index, _)| index)
+            else {
+                return;
+            };
+            let next = self.waiting.remove(index);
+            if next.wake.send(()).is_ok() {
+                self.speaking = Some(Speaking {
+                    info: next.info,
+                    preempted: false,
+                });
+            }
+        }
+    }
+
+    fn release(&mut self, id: &str) {
+        self.waiting.retain(|w| w.info.id != id);
+        if self
+            .speaking
+            .as_ref()
+            .is_some_and(|speaking| speaking.info.id == id)
+        {
+            self.speaking = None;
+        }
+        self.dispatch();
+    }
+
+    fn is_preempted(&self, id: &str) -> bool {
+        self.speaking
+            .as_ref()
+            .is_some_and(|speaking| speaking.info.id == id && speaking.preempted)
+    }
+
+    fn clear_waiting(&mut self) -> Vec<DroppedUtterance> {
+        std::mem::take(&mut self.waiting)
+            .into_iter()
+            .map(|w| DroppedUtterance::waiting(w.info, DropReason::Interrupted))
+            .collect()
+    }
+}
+
+/// Concurrency-limited async queue for TTS generation, plus the priority
+/// schedule that decides which utterance gets to speak.
 ///
 /// Prevents overwhelming local models or hitting API rate limits
-/// by limiting the number of concurrent synthesis requests.
+/// by limiting the number of concurrent synthesis requests. Utterances speak
+/// one at a time: higher priorities go first and cut a lower-priority
+/// utterance off after its current sentence.
 pub struct TtsQueue {
     semaphore: Arc<Semaphore>,
+    schedule: Arc<Mutex<ScheduleState>>,
 }
 
 impl TtsQueue {
-    pub fn new(max_concurrent: usize) -> Self {
+    pub fn new(max_concurrent: usize, max_depth: usize) -> Self {
         Self {
             semaphore: Arc::new(Semaphore::new(max_concurrent)),
+            schedule: Arc::new(Mutex::new(ScheduleState::new(max_depth))),
         }
     }
 
@@ -40,4 +236,168 @@ impl TtsQueue {
     pub fn available_permits(&self) -> usize {
         self.semaphore.available_permits()
     }
+
+    pub fn set_max_depth(&self, max_depth: usize) {
+        self.lock().max_depth = max_depth.max(1);
+    }
+
+    /// Join the speaking schedule. Returns the ticket and whatever the new
+    /// utterance pushed out of the queue (possibly itself).
+    pub fn admit(&self, info: UtteranceInfo) -> (UtteranceTicket, Vec<DroppedUtterance>) {
+        let (wake, rx) = oneshot::channel();
+        let id = info.id.clone();
+        let dropped = self.lock().admit(Waiting { info, wake });
+        let ticket = UtteranceTicket {
+            schedule: self.schedule.clone(),
+            id,
+            rx: Some(rx),
+        };
+        (ticket, dropped)
+    }
+
+    /// Drop every waiting utterance; the speaking one is stopped by the caller.
+    pub fn clear_waiting(&self) -> Vec<DroppedUtterance> {
+        self.lock().clear_waiting()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, ScheduleState> {
+        self.schedule.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// A place in the speaking schedule.
+pub struct UtteranceTicket {
+    schedule: Arc<Mutex<ScheduleState>>,
+    id: String,
+    rx: Option<oneshot::Receiver<()>>,
+}
+
+impl UtteranceTicket {
+    /// Wait for this utterance's turn; `None` if it was dropped while waiting.
+    pub async fn wait(mut self) -> Option<UtteranceTurn> {
+        self.rx.take()?.await.ok()?;
+        Some(UtteranceTurn {
+            schedule: self.schedule.clone(),
+            id: std::mem::take(&mut self.id),
+        })
+    }
+}
+
+impl Drop for UtteranceTicket {
+    fn drop(&mut self) {
+        if !self.id.is_empty() {
+            release(&self.schedule, &self.id);
+        }
+    }
+}
+
+/// The utterance currently speaking; the next one starts when this is dropped.
+pub struct UtteranceTurn {
+    schedule: Arc<Mutex<ScheduleState>>,
+    id: String,
+}
+
+impl UtteranceTurn {
+    /// A higher-priority utterance is waiting; stop after the current sentence.
+    pub fn is_preempted(&self) -> bool {
+        self.schedule
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .is_preempted(&self.id)
+    }
+}
+
+impl Drop for UtteranceTurn {
+    fn drop(&mut self) {
+        release(&self.schedule, &self.id);
+    }
+}
+
+fn release(schedule: &Mutex<ScheduleState>, id: &str) {
+    schedule
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .release(id);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn waiting(
+        state: &mut ScheduleState,
+        priority: TtsPriority,
+        source: Option<&str>,
+    ) -> (String, oneshot::Receiver<()>, Vec<DroppedUtterance>) {
+        let info = UtteranceInfo::new("hello", priority, source.map(str::to_string));
+        let id = info.id.clone();
+        let (wake, rx) = oneshot::channel();
+        let dropped = state.admit(Waiting { info, wake });
+        (id, rx, dropped)
+    }
+
+    #[test]
+    fn higher_priority_preempts_and_goes_first() {
+        let mut state = ScheduleState::new(4);
+        let (chat, mut chat_rx, _) = waiting(&mut state, TtsPriority::Normal, Some("chat"));
+        assert!(chat_rx.try_recv().is_ok());
+
+        let (low, mut low_rx, _) = waiting(&mut state, TtsPriority::Low, None);
+        assert!(!state.is_preempted(&chat));
+        let (high, mut high_rx, _) = waiting(&mut state, TtsPriority::High, None);
+        assert!(state.is_preempted(&chat));
+
+        state.release(&chat);
+        assert!(high_rx.try_recv().is_ok());
+        assert!(low_rx.try_recv().is_err());
+        state.release(&high);
+        assert!(low_rx.try_recv().is_ok());
+        state.release(&low);
+        assert!(state.speaking.is_none());
+    }
+
+    #[test]
+    fn same_source_supersedes_waiting_utterance() {
+        let mut state = ScheduleState::new(4);
+        let (running, _running_rx, _) = waiting(&mut state, TtsPriority::Normal, Some("chat"));
+        let (stale, _stale_rx, _) = waiting(&mut state, TtsPriority::Normal, Some("chat"));
+        let (_, _other_rx, dropped) = waiting(&mut state, TtsPriority::Normal, Some("chat"));
+
+        assert_eq!(dropped.len(), 1);
+        assert_eq!(dropped[0].utterance.id, stale);
+        assert_eq!(dropped[0].reason, DropReason::Superseded);
+        // The speaking one is never superseded, only preempted.
+        assert_eq!(state.speaking.as_ref().map(|s| &s.info.id), Some(&running));
+    }
+
+    #[test]
+    fn full_queue_evicts_lowest_priority_oldest_first() {
+        let mut state = ScheduleState::new(2);
+        let _running = waiting(&mut state, TtsPriority::Normal, None);
+        let (old_low, _a, _) = waiting(&mut state, TtsPriority::Low, None);
+        let (_, _b, _) = waiting(&mut state, TtsPriority::Low, None);
+        let (_, _c, dropped) = waiting(&mut state, TtsPriority::Normal, None);
+
+        assert_eq!(dropped.len(), 1);
+        assert_eq!(dropped[0].utterance.id, old_low);
+        assert_eq!(dropped[0].reason, DropReason::QueueFull);
+        assert_eq!(state.waiting.len(), 2);
+
+        let cleared = state.clear_waiting();
+        assert_eq!(cleared.len(), 2);
+        assert!(cleared.iter().all(|d| d.reason == DropReason::Interrupted));
+    }
+
+    #[test]
+    fn abandoned_ticket_hands_over_to_next() {
+        let mut state = ScheduleState::new(4);
+        let (first, _first_rx, _) = waiting(&mut state, TtsPriority::Normal, None);
+        let (_, gone_rx, _) = waiting(&mut state, TtsPriority::Normal, None);
+        let (third, mut third_rx, _) = waiting(&mut state, TtsPriority::Normal, None);
+        drop(gone_rx);
+
+        state.release(&first);
+        assert!(third_rx.try_recv().is_ok());
+        assert_eq!(state.speaking.as_ref().map(|s| &s.info.id), Some(&third));
+    }
 }
@@ -0,0 +1,543 @@
+//! Viseme timelines for lip sync, computed alongside `tts:audio`.
+//!
+//! Every audio chunk gets a matching `tts:visemes` event whose frames carry
+//! timestamps relative to the start of the utterance's audio, so the frontend
+//! can look them up by playback position. Mouth opening follows the real
+//! loudness envelope when the chunk is PCM WAV; for MP3 it follows syllable
+//! pacing derived from the text. Mouth shape always comes from the vowels in
+//! the text (Latin, kana, Hangul and Cyrillic; Han characters have no reading
+//! without a dictionary and get a neutral open shape).
+
+use serde::Serialize;
+
+/// Spacing between frames.
+pub const FRAME_MS: u32 = 40;
+/// Nominal length of one syllable at speed 1.0.
+const SYLLABLE_MS: f32 = 170.0;
+/// Punctuation pauses count as this many syllables.
+const PAUSE_UNITS: f32 = 1.5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Viseme {
+    Sil,
+    A,
+    I,
+    U,
+    E,
+    O,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct VisemeFrame {
+    /// Milliseconds since the utterance's audio started.
+    pub t: u64,
+    pub viseme: Viseme,
+    /// Mouth opening, 0–1.
+    pub open: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VisemeSource {
+    /// Opening follows the decoded loudness envelope.
+    Audio,
+    /// Opening follows estimated syllable timing.
+    Text,
+}
+
+/// Payload of `tts:visemes`, one per `tts:audio` chunk.
+#[derive(Debug, Clone, Serialize)]
+pub struct TtsVisemesEvent {
+    pub offset_ms: u64,
+    pub duration_ms: u64,
+    pub source: VisemeSource,
+    pub frames: Vec<VisemeFrame>,
+}
+
+// ── Text → vowel units ─────────────────────────────────
+
+const KANA_A: &str = "あかさたなはまやらわがざだばぱぁゎ";
+const KANA_I: &str = "いきしちにひみりぎじぢびぴぃ";
+const KANA_U: &str = "うくすつぬふむゆるぐずづぶぷぅゔ";
+const KANA_E: &str = "えけせてねへめれげぜでべぺぇ";
+const KANA_O: &str = "おこそとのほもよろをごぞどぼぽぉ";
+
+fn kana_vowel(c: char) -> Option<Viseme> {
+    // Fold katakana onto hiragana.
+    let c = match c {
+        'ァ'..='ヶ' => char::from_u32(c as u32 - 0x60).unwrap_or(c),
+        _ => c,
+    };
+    [
+        (KANA_A, Viseme::A),
+        (KANA_I, Viseme::I),
+        (KANA_U, Viseme::U),
+        (KANA_E, Viseme::E),
+        (KANA_O, Viseme::O),
+    ]
+    .into_iter()
+    .find(|(row, _)| row.contains(c))
+    .map(|(_, v)| v)
+}
+
+fn hangul_vowel(c: char) -> Option<Viseme> {
+    let code = c as u32;
+    if !(0xAC00..=0xD7A3).contains(&code) {
+        return None;
+    }
+    // Medial vowel (jungseong) index, 0..21.
+    Some(match (code - 0xAC00) / 28 % 21 {
+        0 | 2 | 9 => Viseme::A,
+        1 | 3 | 5 | 7 | 10 | 11 | 15 => Viseme::E,
+        4 | 6 | 8 | 12 | 14 => Viseme::O,
+        13 | 17 | 18 => Viseme::U,
+        _ => Viseme::I,
+    })
+}
+
+fn letter_vowel(c: char) -> Option<Viseme> {
+    match c.to_lowercase().next().unwrap_or(c) {
+        'a' | 'á' | 'à' | 'ä' | 'â' | 'а' | 'я' => Some(Viseme::A),
+        'e' | 'é' | 'è' | 'ê' | 'е' | 'э' | 'ё' => Some(Viseme::E),
+        'i' | 'í' | 'ì' | 'î' | 'y' | 'и' | 'ы' => Some(Viseme::I),
+        'o' | 'ó' | 'ò' | 'ö' | 'ô' | 'о' => Some(Viseme::O),
+        'u' | 'ú' | 'ù' | 'ü' | 'û' | 'у' | 'ю' => Some(Viseme::U),
+        _ => None,
+    }
+}
+
+fn is_pause(c: char) -> bool {
+    matches!(
+        c,
+        ',' | '.' | '!' | '?' | ';' | ':' | '…' | '、' | '。' | '，' | '！' | '？' | '；' | '：'
+    )
+}
+
+/// Syllable-sized units with their relative length.
+pub fn vowel_units(text: &str) -> Vec<(Viseme, f32)> {
+    let mut units: Vec<(Viseme, f32)> = Vec::new();
+    let mut in_vowel_run = false;
+    for c in text.chars() {
+        let latin_like =
+            c.is_alphabetic() && (c.is_ascii() || ('\u{00C0}'..='\u{04FF}').contains(&c));
+        if latin_like {
+            // A run of vowels ("ea", "ou") is one syllable; consonants only separate them.
+            match letter_vowel(c) {
+                Some(v) if !in_vowel_run => {
+                    units.push((v, 1.0));
+                    in_vowel_run = true;
+                }
+                Some(_) => {}
+                None => in_vowel_run = false,
+            }
+            continue;
+        }
+        in_vowel_run = false;
+
+        match c {
+            // Small ya/yu/yo reshape the previous mora instead of adding one.
+            'ゃ' | 'ャ' | 'ゅ' | 'ュ' | 'ょ' | 'ョ' => {
+                if let Some(last) = units.last_mut() {
+                    last.0 = match c {
+                        'ゃ' | 'ャ' => Viseme::A,
+                        'ゅ' | 'ュ' => Viseme::U,
+                        _ => Viseme::O,
+                    };
+                }
+            }
+            'ー' => {
+                if let Some(&(v, _)) = units.last() {
+                    units.push((v, 1.0));
+                }
+            }
+            'ん' | 'ン' | 'っ' | 'ッ' => units.push((Viseme::Sil, 0.6)),
+            _ if is_pause(c) => units.push((Viseme::Sil, PAUSE_UNITS)),
+            _ => {
+                if let Some(v) = kana_vowel(c).or_else(|| hangul_vowel(c)) {
+                    units.push((v, 1.0));
+                } else if c.is_alphanumeric() {
+                    // Han characters, digits and other scripts: one open syllable.
+                    units.push((Viseme::A, 1.0));
+                }
+            }
+        }
+    }
+    units
+}
+
+// ── Audio chunk inspection ─────────────────────────────
+
+/// What could be learned from one chunk of encoded audio.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChunkAudio {
+    pub duration_ms: f32,
+    /// RMS loudness per [`FRAME_MS`] window, 0–1. `None` when not decodable.
+    pub envelope: Option<Vec<f32>>,
+}
+
+/// Inspect a WAV or MP3 chunk. `mp3_kbps` remembers the bitrate between
+/// chunks of the same stream, since later chunks may start mid-frame.
+pub fn inspect_chunk(chunk: &[u8], mp3_kbps: &mut Option<u32>) -> Option<ChunkAudio> {
+    if chunk.len() >= 12 && &chunk[0..4] == b"RIFF" && &chunk[8..12] == b"WAVE" {
+        return inspect_wav(chunk);
+    }
+    let (audio_start, found) = mp3_bitrate(chunk);
+    if let Some(kbps) = found {
+        *mp3_kbps = Some(kbps);
+    }
+    let kbps = (*mp3_kbps)?;
+    let audio_bytes = chunk.len().saturating_sub(audio_start);
+    Some(ChunkAudio {
+        // kbps is bits per millisecond.
+        duration_ms: audio_bytes as f32 * 8.0 / kbps as f32,
+        envelope: None,
+    })
+}
+
+fn inspect_wav(chunk: &[u8]) -> Option<ChunkAudio> {
+    let mut pos = 12;
+    let mut format: Option<(u16, u16, u32, u16)> = None;
+    while pos + 8 <= chunk.len() {
+        let id = &chunk[pos..pos + 4];
+        let size = u32::from_le_bytes(chunk[pos + 4..pos + 8].try_into().ok()?) as usize;
+        let body = pos + 8;
+        if id == b"fmt " && body + 16 <= chunk.len() {
+            let b = &chunk[body..body + 16];
+            format = Some((
+                u16::from_le_bytes([b[0], b[1]]),
+                u16::from_le_bytes([b[2], b[3]]).max(1),
+                u32::from_le_bytes([b[4], b[5], b[6], b[7]]),
+                u16::from_le_bytes([b[14], b[15]]),
+            ));
+        } else if id == b"data" {
+            let (tag, channels, rate, bits) = format?;
+            // Streaming writers leave the size at 0 or u32::MAX.
+            let end = if size == 0 || body + size > chunk.len() {
+                chunk.len()
+            } else {
+                body + size
+            };
+            return Some(pcm_envelope(&chunk[body..end], tag, channels, rate, bits));
+        }
+        pos = body + size + (size & 1);
+    }
+    None
+}
+
+fn pcm_envelope(data: &[u8], tag: u16, channels: u16, rate: u32, bits: u16) -> ChunkAudio {
+    let bytes_per_sample = (bits / 8).max(1) as usize;
+    let frame_bytes = bytes_per_sample * channels as usize;
+    let total_frames = data.len() / frame_bytes.max(1);
+    let duration_ms = total_frames as f32 * 1000.0 / rate.max(1) as f32;
+
+    // 1 = integer PCM, 3 = IEEE float, 0xFFFE = extensible (assume PCM by bit depth).
+    let sample = |offset: usize| -> Option<f32> {
+        let s = data.get(offset..offset + bytes_per_sample)?;
+        Some(match (tag, bits) {
+            (3, 32) => f32::from_le_bytes(s.try_into().ok()?),
+            (_, 16) => i16::from_le_bytes([s[0], s[1]]) as f32 / 32768.0,
+            (_, 8) => (s[0] as f32 - 128.0) / 128.0,
+            (_, 32) => i32::from_le_bytes(s.try_into().ok()?) as f32 / 2_147_483_648.0,
+            _ => return None,
+        })
+    };
+    if sample(0).is_none() && !data.is_empty() {
+        return ChunkAudio {
+            duration_ms,
+            envelope: None,
+        };
+    }
+
+    let window = (rate as usize * FRAME_MS as usize / 1000).max(1);
+    let envelope = (0..total_frames)
+        .step_by(window)
+        .map(|start| {
+            let end = (start + window).min(total_frames);
+            let mut sum = 0.0;
+            let mut count = 0usize;
+            for frame in start..end {
+                for ch in 0..channels as usize {
+                    if let Some(v) = sample(frame * frame_bytes + ch * bytes_per_sample) {
+                        sum += v * v;
+                        count += 1;
+                    }
+                }
+            }
+            let rms = if count == 0 {
+                0.0
+            } else {
+                (sum / count as f32).sqrt()
+            };
+            // Same scaling as the frontend analyser.
+            (rms * 4.0).min(1.0)
+        })
+        .collect();
+    ChunkAudio {
+        duration_ms,
+        envelope: Some(envelope),
+    }
+}
+
+const MPEG1_L3_KBPS: [u32; 15] = [
+    0, 32, 40, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320,
+];
+const MPEG2_L3_KBPS: [u32; 15] = [0, 8, 16, 24, 32, 40, 48, 56, 64, 80, 96, 112, 128, 144, 160];
+
+/// Bitrate of a Layer III frame header at `h`, and the frame length in bytes.
+fn mp3_frame(h: &[u8]) -> Option<(u32, usize)> {
+    if h.len() < 4 || h[0] != 0xFF || h[1] & 0xE0 != 0xE0 {
+        return None;
+    }
+    let version = (h[1] >> 3) & 0x03;
+    let layer = (h[1] >> 1) & 0x03;
+    if version == 1 || layer != 1 {
+        return None;
+    }
+    let bitrate_index = (h[2] >> 4) as usize;
+    let rate_index = ((h[2] >> 2) & 0x03) as usize;
+    if bitrate_index == 0 || bitrate_index == 15 || rate_index == 3 {
+        return None;
+    }
+    let mpeg1 = version == 3;
+    let kbps = if mpeg1 {
+        MPEG1_L3_KBPS[bitrate_index]
+    } else {
+        MPEG2_L3_KBPS[bitrate_index]
+    };
+    let sample_rate = match version {
+        3 => [44100, 48000, 32000][rate_index],
+        2 => [22050, 24000, 16000][rate_index],
+        _ => [11025, 12000, 8000][rate_index],
+    };
+    let padding = ((h[2] >> 1) & 0x01) as usize;
+    let coefficient = if mpeg1 { 144_000 } else { 72_000 };
+    let length = coefficient * kbps as usize / sample_rate + padding;
+    Some((kbps, length))
+}
+
+/// Where audio data starts (after any ID3v2 tag) and the bitrate of the
+/// first frame header that is confirmed by the next one.
+fn mp3_bitrate(chunk: &[u8]) -> (usize, Option<u32>) {
+    let mut start = 0;
+    if chunk.len() >= 10 && &chunk[0..3] == b"ID3" {
+        let size = chunk[6..10]
+            .iter()
+            .fold(0usize, |acc, &b| (acc << 7) | (b & 0x7F) as usize);
+        start = (10 + size).min(chunk.len());
+    }
+    let scan_end = chunk.len().min(start + 4096);
+    for pos in start..scan_end {
+        let Some((kbps, length)) = mp3_frame(&chunk[pos..]) else {
+            continue;
+        };
+        let next = pos + length;
+        let confirmed = match chunk.get(next..) {
+            Some(rest) if rest.len() >= 4 => mp3_frame(rest).is_some(),
+            // Frame runs to the end of the chunk; accept it.
+            _ => true,
+        };
+        if confirmed {
+            return (start, Some(kbps));
+        }
+    }
+    (start, None)
+}
+
+// ── Timeline ───────────────────────────────────────────
+
+/// Mouth shape for one sentence, advanced chunk by chunk.
+pub struct SentenceVisemes {
+    units: Vec<(Viseme, f32)>,
+    /// Cumulative end of each unit, in estimated milliseconds.
+    ends: Vec<f32>,
+    estimated_ms: f32,
+    elapsed_ms: f32,
+    mp3_kbps: Option<u32>,
+}
+
+impl SentenceVisemes {
+    pub fn new(sentence: &str, speed: Option<f32>) -> Self {
+        let unit_ms = SYLLABLE_MS / speed.filter(|s| *s > 0.0).unwrap_or(1.0);
+        let units = vowel_units(sentence);
+        let mut total = 0.0;
+        let ends = units
+            .iter()
+            .map(|(_, weight)| {
+                total += weight * unit_ms;
+                total
+            })
+            .collect();
+        Self {
+            units,
+            ends,
+            estimated_ms: total,
+            elapsed_ms: 0.0,
+            mp3_kbps: None,
+        }
+    }
+
+    /// Estimated spoken length of the sentence.
+    pub fn estimated_ms(&self) -> u64 {
+        self.estimated_ms.round() as u64
+    }
+
+    /// Build the event for the next chunk; `offset_ms` is where the chunk
+    /// starts in the utterance. Returns `None` for undecodable audio.
+    pub fn chunk_event(&mut self, chunk: &[u8], offset_ms: u64) -> Option<TtsVisemesEvent> {
+        let audio = inspect_chunk(chunk, &mut self.mp3_kbps)?;
+        let frame_count = (audio.duration_ms / FRAME_MS as f32).ceil() as usize;
+        // Real audio running longer than estimated stretches the remainder.
+        let scale = if self.estimated_ms > 0.0 {
+            (self.elapsed_ms + audio.duration_ms).max(self.estimated_ms) / self.estimated_ms
+        } else {
+            1.0
+        };
+
+        let frames = (0..frame_count)
+            .map(|k| {
+                let local_ms = k as f32 * FRAME_MS as f32;
+                let (viseme, phase) = self.unit_at((self.elapsed_ms + local_ms) / scale);
+                let open = match &audio.envelope {
+                    Some(envelope) if viseme == Viseme::Sil => {
+                        envelope.get(k).copied().unwrap_or(0.0) * 0.3
+                    }
+                    Some(envelope) => envelope.get(k).copied().unwrap_or(0.0),
+                    None if viseme == Viseme::Sil => 0.0,
+                    // Open towards the middle of each syllable, close between them.
+                    None => 0.3 + 0.6 * (phase * std::f32::consts::PI).sin(),
+                };
+                VisemeFrame {
+                    t: offset_ms + local_ms as u64,
+                    viseme,
+                    open,
+                }
+            })
+            .collect();
+        self.elapsed_ms += audio.duration_ms;
+
+        Some(TtsVisemesEvent {
+            offset_ms,
+            duration_ms: audio.duration_ms.round() as u64,
+            source: if audio.envelope.is_some() {
+                VisemeSource::Audio
+            } else {
+                VisemeSource::Text
+            },
+            frames,
+        })
+    }
+
+    /// Unit active at `ms` (estimated time) and how far into it, 0–1.
+    fn unit_at(&self, ms: f32) -> (Viseme, f32) {
+        let Some(index) = self.ends.iter().position(|&end| ms < end) else {
+            return (Viseme::Sil, 0.0);
+        };
+        let start = if index == 0 {
+            0.0
+        } else {
+            self.ends[index - 1]
+        };
+        let length = (self.ends[index] - start).max(1.0);
+        (self.units[index].0, (ms - start) / length)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wav(samples: &[i16], rate: u32) -> Vec<u8> {
+        let data_len = (samples.len() * 2) as u32;
+        let mut out = Vec::new();
+        out.extend_from_slice(b"RIFF");
+        out.extend_from_slice(&(36 + data_len).to_le_bytes());
+        out.extend_from_slice(b"WAVEfmt ");
+        out.extend_from_slice(&16u32.to_le_bytes());
+        out.extend_from_slice(&1u16.to_le_bytes());
+        out.extend_from_slice(&1u16.to_le_bytes());
+        out.extend_from_slice(&rate.to_le_bytes());
+        out.extend_from_slice(&(rate * 2).to_le_bytes());
+        out.extend_from_slice(&2u16.to_le_bytes());
+        out.extend_from_slice(&16u16.to_le_bytes());
+        out.extend_from_slice(b"data");
+        out.extend_from_slice(&data_len.to_le_bytes());
+        for s in samples {
+            out.extend_from_slice(&s.to_le_bytes());
+        }
+        out
+    }
+
+    #[test]
+    fn vowels_from_several_scripts() {
+        let units =
+            |text: &str| -> Vec<Viseme> { vowel_units(text).into_iter().map(|u| u.0).collect() };
+        assert_eq!(units("hello"), vec![Viseme::E, Viseme::O]);
+        assert_eq!(units("out, now"), vec![Viseme::O, Viseme::Sil, Viseme::O]);
+        assert_eq!(
+            units("こんにちは"),
+            vec![Viseme::O, Viseme::Sil, Viseme::I, Viseme::I, Viseme::A]
+        );
+        assert_eq!(units("キョー"), vec![Viseme::O, Viseme::O]);
+        assert_eq!(units("안녕"), vec![Viseme::A, Viseme::O]);
+        assert_eq!(units("да"), vec![Viseme::A]);
+        assert_eq!(units("你好"), vec![Viseme::A, Viseme::A]);
+    }
+
+    #[test]
+    fn wav_envelope_tracks_loudness() {
+        let rate = 8000;
+        // 40 ms of silence then 40 ms of a loud square wave.
+        let mut samples = vec![0i16; 320];
+        samples.extend((0..320).map(|i| if i % 2 == 0 { 16000 } else { -16000 }));
+        let audio = inspect_chunk(&wav(&samples, rate), &mut None).unwrap();
+        assert!((audio.duration_ms - 80.0).abs() < 0.01);
+        let envelope = audio.envelope.unwrap();
+        assert_eq!(envelope.len(), 2);
+        assert_eq!(envelope[0], 0.0);
+        assert_eq!(envelope[1], 1.0);
+    }
+
+    #[test]
+    fn mp3_duration_from_bitrate_across_chunks() {
+        // MPEG-2 Layer III, 48 kbps, 24 kHz.
+        let header = [0xFF, 0xF3, 0x64, 0xC4];
+        let (kbps, length) = mp3_frame(&header).unwrap();
+        assert_eq!(kbps, 48);
+        assert_eq!(length, 144);
+        let mut first = Vec::new();
+        for _ in 0..2 {
+            first.extend_from_slice(&header);
+            first.extend(vec![0u8; length - 4]);
+        }
+        let mut kbps_state = None;
+        let audio = inspect_chunk(&first, &mut kbps_state).unwrap();
+        assert_eq!(kbps_state, Some(48));
+        assert!((audio.duration_ms - 48.0).abs() < 0.01);
+        assert!(audio.envelope.is_none());
+
+        // A later chunk that starts mid-frame reuses the known bitrate.
+        let audio = inspect_chunk(&[0u8; 600], &mut kbps_state).unwrap();
+        assert!((audio.duration_ms - 100.0).abs() < 0.01);
+        assert!(inspect_chunk(&[0u8; 600], &mut None).is_none());
+    }
+
+    #[test]
+    fn sentence_frames_are_offset_and_shaped() {
+        // 200 ms loud, 200 ms silent; longer than the 340 ms estimate.
+        let mut samples = vec![12000i16; 1600];
+        samples.extend(vec![0i16; 1600]);
+        let mut sentence = SentenceVisemes::new("ka ki", None);
+        let event = sentence.chunk_event(&wav(&samples, 8000), 1000).unwrap();
+        assert_eq!(event.source, VisemeSource::Audio);
+        assert_eq!(event.offset_ms, 1000);
+        assert_eq!(event.duration_ms, 400);
+        assert_eq!(event.frames.len(), 10);
+        assert_eq!(event.frames[0].t, 1000);
+        assert_eq!(event.frames[0].viseme, Viseme::A);
+        assert!(event.frames[0].open > 0.9);
+        assert_eq!(event.frames[9].t, 1360);
+        assert_eq!(event.frames[9].viseme, Viseme::I);
+        assert_eq!(event.frames[9].open, 0.0);
+    }
+}
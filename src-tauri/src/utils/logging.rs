@@ -152,6 +152,7 @@ where
         event.record(&mut visitor);
         let message = visitor.into_message();
 
+        crate::crash::record_log_line(format_log_line(level, target, &message, false));
         let line = format_log_line(level, target, &message, self.with_color);
         writeln!(writer, "{}", line)
     }
@@ -1,3 +1,6 @@
 pub mod download;
 pub mod http;
 pub mod logging;
+pub mod rate_limit;
+pub mod segment;
+pub mod template;
@@ -0,0 +1,379 @@
+//! Client-side rate limiting per remote provider.
+//!
+//! Every provider gets one shared [`RateLimiter`], so the chat turn, memory
+//! extraction, consolidation, translation and proactive calls that hit the
+//! same API draw from one budget instead of racing each other into 429s.
+//! Limits come from the provider config (`requests_per_minute` and
+//! `tokens_per_minute` in `extra`) or are learned from the `x-ratelimit-*`
+//! response headers, and a 429 pauses the provider for its `Retry-After`.
+//! Background calls only get part of the budget and yield to queued
+//! interactive ones, so bookkeeping can never starve the live chat.
+
+use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
+use serde_json::Value;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, LazyLock, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+const WINDOW: Duration = Duration::from_secs(60);
+/// Share of the per-minute budget background calls may use.
+const BACKGROUND_SHARE: f64 = 0.75;
+/// Pause after a 429 without a usable `Retry-After`.
+const DEFAULT_RETRY_AFTER: Duration = Duration::from_secs(10);
+/// Upper bound for any header-driven pause.
+const MAX_PAUSE: Duration = Duration::from_secs(300);
+/// Longest sleep before re-checking, so config changes apply promptly.
+const MAX_WAIT_STEP: Duration = Duration::from_secs(5);
+/// How often background calls re-check while an interactive call is queued.
+const YIELD_STEP: Duration = Duration::from_millis(250);
+
+static LIMITERS: LazyLock<Mutex<HashMap<String, Arc<RateLimiter>>>> =
+    LazyLock::new(Default::default);
+
+/// The limiter shared by every client of `key`: an LLM provider id, or
+/// `tts:<id>` for a TTS provider.
+pub fn limiter(key: &str) -> Arc<RateLimiter> {
+    LIMITERS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .entry(key.to_string())
+        .or_default()
+        .clone()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Priority {
+    /// A reply the user is waiting on.
+    Interactive,
+    /// Memory extraction, consolidation, summaries and other bookkeeping.
+    Background,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RateLimits {
+    pub requests_per_minute: Option<u32>,
+    pub tokens_per_minute: Option<u32>,
+}
+
+impl RateLimits {
+    /// Read `requests_per_minute` / `tokens_per_minute` from a provider's `extra` map.
+    pub fn from_extra(extra: &HashMap<String, Value>) -> Self {
+        let read = |key: &str| {
+            extra
+                .get(key)
+                .and_then(Value::as_u64)
+                .filter(|value| *value > 0)
+                .map(|value| value.min(u32::MAX as u64) as u32)
+        };
+        Self {
+            requests_per_minute: read("requests_per_minute"),
+            tokens_per_minute: read("tokens_per_minute"),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct LimiterState {
+    configured: RateLimits,
+    /// Limits announced by the provider's response headers.
+    learned: RateLimits,
+    /// Start time and estimated tokens of the requests of the last minute.
+    recent: VecDeque<(Instant, u32)>,
+    paused_until: Option<Instant>,
+    interactive_waiting: usize,
+}
+
+impl LimiterState {
+    fn limits(&self) -> RateLimits {
+        RateLimits {
+            requests_per_minute: self
+                .configured
+                .requests_per_minute
+                .or(self.learned.requests_per_minute),
+            tokens_per_minute: self
+                .configured
+                .tokens_per_minute
+                .or(self.learned.tokens_per_minute),
+        }
+    }
+
+    /// Count a request starting now, or return how long to wait before asking again.
+    fn try_admit(&mut self, now: Instant, tokens: u32, priority: Priority) -> Result<(), Duration> {
+        while self
+            .recent
+            .front()
+            .is_some_and(|(at, _)| now.duration_since(*at) >= WINDOW)
+        {
+            self.recent.pop_front();
+        }
+        if let Some(until) = self.paused_until {
+            if until > now {
+                return Err(until - now);
+            }
+            self.paused_until = None;
+        }
+        if priority == Priority::Background && self.interactive_waiting > 0 {
+            return Err(YIELD_STEP);
+        }
+
+        let budget = |limit: u32| match priority {
+            Priority::Interactive => limit as u64,
+            Priority::Background => ((limit as f64 * BACKGROUND_SHARE) as u64).max(1),
+        };
+        let until_oldest_expires = self
+            .recent
+            .front()
+            .map(|(at, _)| WINDOW - now.duration_since(*at))
+            .unwrap_or(YIELD_STEP);
+        let limits = self.limits();
+        if let Some(rpm) = limits.requests_per_minute {
+            if self.recent.len() as u64 >= budget(rpm) {
+                return Err(until_oldest_expires);
+            }
+        }
+        if let Some(tpm) = limits.tokens_per_minute {
+            let used: u64 = self.recent.iter().map(|(_, tokens)| *tokens as u64).sum();
+            // A request larger than the whole budget still runs once the window is empty.
+            if !self.recent.is_empty() && used + tokens as u64 > budget(tpm) {
+                return Err(until_oldest_expires);
+            }
+        }
+        self.recent.push_back((now, tokens));
+        Ok(())
+    }
+
+    fn pause_for(&mut self, now: Instant, pause: Duration) {
+        let until = now + pause.min(MAX_PAUSE);
+        self.paused_until = Some(
+            self.paused_until
+                .map_or(until, |current| current.max(until)),
+        );
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct RateLimiter {
+    state: Mutex<LimiterState>,
+}
+
+impl RateLimiter {
+    fn lock(&self) -> MutexGuard<'_, LimiterState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn configure(&self, limits: RateLimits) {
+        self.lock().configured = limits;
+    }
+
+    /// Wait until a request of about `tokens` fits the budget, then count it.
+    pub async fn acquire(&self, tokens: u32, priority: Priority) {
+        let _queued = (priority == Priority::Interactive).then(|| InteractiveQueued::new(self));
+        loop {
+            let admitted = self.lock().try_admit(Instant::now(), tokens, priority);
+            match admitted {
+                Ok(()) => return,
+                Err(wait) => tokio::time::sleep(wait.min(MAX_WAIT_STEP)).await,
+            }
+        }
+    }
+
+    /// Learn limits from response headers, and pause on a 429 or an exhausted
+    /// request budget.
+    pub fn observe(&self, status: StatusCode, headers: &HeaderMap) {
+        let header = |names: &[&str]| {
+            names
+                .iter()
+                .find_map(|name| headers.get(*name)?.to_str().ok().map(str::trim))
+        };
+        let header_u32 = |names: &[&str]| header(names).and_then(|value| value.parse::<u32>().ok());
+
+        let mut state = self.lock();
+        if let Some(limit) = header_u32(&[
+            "x-ratelimit-limit-requests",
+            "anthropic-ratelimit-requests-limit",
+        ]) {
+            state.learned.requests_per_minute = Some(limit);
+        }
+        if let Some(limit) = header_u32(&[
+            "x-ratelimit-limit-tokens",
+            "anthropic-ratelimit-tokens-limit",
+        ]) {
+            state.learned.tokens_per_minute = Some(limit);
+        }
+
+        let pause = if status == StatusCode::TOO_MANY_REQUESTS {
+            Some(retry_after(headers).unwrap_or(DEFAULT_RETRY_AFTER))
+        } else if header_u32(&["x-ratelimit-remaining-requests"]) == Some(0) {
+            header(&["x-ratelimit-reset-requests"]).and_then(parse_reset)
+        } else {
+            None
+        };
+        if let Some(pause) = pause {
+            tracing::warn!(
+                target: "llm",
+                "[RateLimit] Provider rate limit reached (HTTP {}), pausing for {:?}",
+                status.as_u16(),
+                pause.min(MAX_PAUSE)
+            );
+            state.pause_for(Instant::now(), pause);
+        }
+    }
+}
+
+/// Marks an interactive call as queued so background calls step aside.
+struct InteractiveQueued<'a>(&'a RateLimiter);
+
+impl<'a> InteractiveQueued<'a> {
+    fn new(limiter: &'a RateLimiter) -> Self {
+        limiter.lock().interactive_waiting += 1;
+        Self(limiter)
+    }
+}
+
+impl Drop for InteractiveQueued<'_> {
+    fn drop(&mut self) {
+        let mut state = self.0.lock();
+        state.interactive_waiting = state.interactive_waiting.saturating_sub(1);
+    }
+}
+
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = |name: &str| headers.get(name)?.to_str().ok()?.trim().parse::<f64>().ok();
+    value("retry-after-ms")
+        .and_then(|ms| pause_secs(ms / 1000.0))
+        .or_else(|| value("retry-after").and_then(pause_secs))
+}
+
+/// A header-supplied pause in seconds, clamped to `0..=MAX_PAUSE`. Headers
+/// parse as any `f64`, so `inf`, `NaN` and absurd values are ignored or capped.
+fn pause_secs(secs: f64) -> Option<Duration> {
+    if !secs.is_finite() {
+        return None;
+    }
+    Duration::try_from_secs_f64(secs.clamp(0.0, MAX_PAUSE.as_secs_f64())).ok()
+}
+
+/// Parse an OpenAI reset duration such as `20ms`, `1.5s` or `6m0s`, capped
+/// at `MAX_PAUSE`.
+fn parse_reset(value: &str) -> Option<Duration> {
+    let mut total = 0.0;
+    let mut number = String::new();
+    let mut chars = value.chars().peekable();
+    while let Some(ch) = chars.next() {
+        if ch.is_ascii_digit() || ch == '.' {
+            number.push(ch);
+            continue;
+        }
+        let amount: f64 = number.parse().ok()?;
+        number.clear();
+        let unit = match ch {
+            'h' => 3600.0,
+            'm' if chars.peek() == Some(&'s') => {
+                chars.next();
+                0.001
+            }
+            'm' => 60.0,
+            's' => 1.0,
+            _ => return None,
+        };
+        total += amount * unit;
+    }
+    if !number.is_empty() {
+        total += number.parse::<f64>().ok()?;
+    }
+    pause_secs(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn background_calls_get_a_share_and_yield_to_chat() {
+        let now = Instant::now();
+        let mut state = LimiterState {
+            configured: RateLimits {
+                requests_per_minute: Some(4),
+                tokens_per_minute: None,
+            },
+            ..Default::default()
+        };
+        for _ in 0..3 {
+            assert!(state.try_admit(now, 10, Priority::Background).is_ok());
+        }
+        let wait = state.try_admit(now, 10, Priority::Background).unwrap_err();
+        assert_eq!(wait, WINDOW);
+        assert!(state.try_admit(now, 10, Priority::Interactive).is_ok());
+        assert!(state.try_admit(now, 10, Priority::Interactive).is_err());
+        assert!(state
+            .try_admit(now + WINDOW, 10, Priority::Background)
+            .is_ok());
+
+        state.interactive_waiting = 1;
+        let later = now + WINDOW * 2;
+        assert_eq!(
+            state.try_admit(later, 10, Priority::Background),
+            Err(YIELD_STEP)
+        );
+    }
+
+    #[test]
+    fn token_budget_and_headers_are_honoured() {
+        let now = Instant::now();
+        let mut state = LimiterState {
+            configured: RateLimits {
+                requests_per_minute: None,
+                tokens_per_minute: Some(1000),
+            },
+            ..Default::default()
+        };
+        assert!(state.try_admit(now, 5000, Priority::Interactive).is_ok());
+        assert!(state.try_admit(now, 1, Priority::Interactive).is_err());
+
+        let limiter = RateLimiter::default();
+        let mut headers = HeaderMap::new();
+        headers.insert("x-ratelimit-limit-requests", "500".parse().unwrap());
+        headers.insert("x-ratelimit-remaining-requests", "0".parse().unwrap());
+        headers.insert("x-ratelimit-reset-requests", "1m30s".parse().unwrap());
+        limiter.observe(StatusCode::OK, &headers);
+        {
+            let state = limiter.lock();
+            assert_eq!(state.limits().requests_per_minute, Some(500));
+            assert!(state.paused_until.unwrap() > Instant::now() + Duration::from_secs(80));
+        }
+
+        assert_eq!(parse_reset("20ms"), Some(Duration::from_millis(20)));
+        assert_eq!(parse_reset("1.5s"), Some(Duration::from_millis(1500)));
+        assert_eq!(parse_reset("later"), None);
+        let mut headers = HeaderMap::new();
+        headers.insert("retry-after", "7".parse().unwrap());
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(7)));
+    }
+
+    #[test]
+    fn odd_header_values_never_panic() {
+        let retry = |name: &'static str, value: &str| {
+            let mut headers = HeaderMap::new();
+            headers.insert(name, value.parse().unwrap());
+            retry_after(&headers)
+        };
+        assert_eq!(retry("retry-after", "inf"), None);
+        assert_eq!(retry("retry-after", "NaN"), None);
+        assert_eq!(retry("retry-after-ms", "-inf"), None);
+        assert_eq!(retry("retry-after", "1e300"), Some(MAX_PAUSE));
+        assert_eq!(retry("retry-after-ms", "1e30"), Some(MAX_PAUSE));
+        assert_eq!(retry("retry-after", "-5"), Some(Duration::ZERO));
+
+        assert_eq!(parse_reset("1e400s"), None);
+        assert_eq!(parse_reset(&"9".repeat(400)), None);
+        assert_eq!(parse_reset("99999999999999999999h"), Some(MAX_PAUSE));
+
+        let limiter = RateLimiter::default();
+        let mut headers = HeaderMap::new();
+        headers.insert("retry-after", "NaN".parse().unwrap());
+        limiter.observe(StatusCode::TOO_MANY_REQUESTS, &headers);
+        let paused = limiter.lock().paused_until.unwrap() - Instant::now();
+        assert!(paused <= DEFAULT_RETRY_AFTER);
+    }
+}
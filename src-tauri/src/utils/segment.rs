@@ -0,0 +1,333 @@
+//! Shared text segmentation for TTS sentence splitting, Telegram message
+//! chunking and typing pacing.
+//!
+//! Handles Latin and CJK sentence punctuation, ellipses (`...`, `…`), trailing
+//! closing quotes/brackets and emoji, and a little markdown: line breaks and
+//! list items end a sentence, fenced code blocks are never split.
+
+/// Telegram rejects messages longer than 4096 UTF-16 code units.
+pub const TELEGRAM_MAX_MESSAGE_LEN: usize = 4096;
+
+/// Characters that end a sentence on their own.
+fn is_terminator(c: char) -> bool {
+    matches!(
+        c,
+        '!' | '?' | '。' | '！' | '？' | '…' | '；' | '｡' | '‼' | '⁉'
+    )
+}
+
+/// Closing punctuation that belongs to the sentence before it.
+fn is_closer(c: char) -> bool {
+    matches!(
+        c,
+        '"' | '\'' | ')' | ']' | '」' | '』' | '）' | '】' | '》' | '〉' | '”' | '’' | '～' | '~'
+    )
+}
+
+pub fn is_cjk(c: char) -> bool {
+    matches!(c as u32,
+        0x3040..=0x30FF     // kana
+        | 0x3400..=0x4DBF   // CJK ext A
+        | 0x4E00..=0x9FFF   // CJK unified
+        | 0xAC00..=0xD7AF   // hangul
+        | 0xF900..=0xFAFF   // CJK compatibility
+        | 0xFF00..=0xFFEF // full-width forms
+    )
+}
+
+/// Emoji and the joiners/modifiers that glue emoji sequences together.
+pub(crate) fn is_emoji_part(c: char) -> bool {
+    matches!(c as u32,
+        0x1F000..=0x1FAFF // includes skin-tone modifiers
+        | 0x2600..=0x27BF
+        | 0x2B00..=0x2BFF
+        | 0x200D            // zero-width joiner
+        | 0xFE0E..=0xFE0F   // variation selectors
+        | 0xE0020..=0xE007F // tag sequences
+    )
+}
+
+/// A `.` ends a sentence unless it sits inside a number, URL or abbreviation
+/// (`3.14`, `example.com`, `e.g.x`), i.e. unless it is directly followed by a
+/// letter or digit.
+fn dot_ends_sentence(next: Option<char>) -> bool {
+    match next {
+        None => true,
+        Some(c) => !c.is_alphanumeric() || is_cjk(c),
+    }
+}
+
+/// Whether `line` starts a markdown list item or heading.
+fn starts_block(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    if trimmed.starts_with("- ")
+        || trimmed.starts_with("* ")
+        || trimmed.starts_with("+ ")
+        || trimmed.starts_with('#')
+        || trimmed.starts_with('>')
+    {
+        return true;
+    }
+    let digits = trimmed.chars().take_while(|c| c.is_ascii_digit()).count();
+    digits > 0 && trimmed[digits..].starts_with(". ")
+}
+
+fn push_piece<'a>(text: &'a str, from: usize, to: usize, result: &mut Vec<&'a str>) {
+    let piece = &text[from..to];
+    if !piece.trim().is_empty() {
+        result.push(piece);
+    }
+}
+
+/// Split `text` into sentences, preserving every byte: concatenating the
+/// result (plus the dropped whitespace-only pieces) gives back `text`.
+pub fn split_sentences(text: &str) -> Vec<&str> {
+    let mut result = Vec::new();
+    let mut start = 0;
+    let mut in_fence = false;
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let (pos, c) = chars[i];
+
+        if c == '\n' {
+            let line_start = pos + 1;
+            let rest = &text[line_start..];
+            let fence = rest.trim_start_matches([' ', '\t']).starts_with("```");
+            if in_fence {
+                if fence {
+                    // Closing fence: the block ends at the end of that line.
+                    let line_end = rest.find('\n').map_or(text.len(), |n| line_start + n);
+                    push_piece(text, start, line_end, &mut result);
+                    start = line_end;
+                    in_fence = false;
+                    i = chars.partition_point(|(p, _)| *p < line_end);
+                    continue;
+                }
+            } else if fence || rest.starts_with('\n') || starts_block(rest) || rest.is_empty() {
+                push_piece(text, start, line_start, &mut result);
+                start = line_start;
+                in_fence = fence;
+            } else if !text[start..pos].trim().is_empty()
+                && text[start..pos].trim_end().ends_with([':', '：'])
+            {
+                // "Here are the options:\n..." — the colon line stands alone.
+                push_piece(text, start, line_start, &mut result);
+                start = line_start;
+            }
+            i += 1;
+            continue;
+        }
+        if pos == 0 && text.trim_start_matches([' ', '\t']).starts_with("```") {
+            in_fence = true;
+        }
+        if in_fence {
+            i += 1;
+            continue;
+        }
+
+        let ends = if c == '.' {
+            dot_ends_sentence(chars.get(i + 1).map(|(_, n)| *n))
+        } else {
+            is_terminator(c)
+        };
+        if !ends {
+            i += 1;
+            continue;
+        }
+
+        // Absorb runs like "?!", "...", "。」" and trailing emoji.
+        let mut j = i + 1;
+        while j < chars.len() {
+            let n = chars[j].1;
+            if n == '.' || is_terminator(n) || is_closer(n) || is_emoji_part(n) {
+                j += 1;
+            } else if n == ' ' && chars.get(j + 1).is_some_and(|(_, e)| is_emoji_part(*e)) {
+                // "Great! 😄 Next..." keeps the emoji with "Great!".
+                j += 1;
+            } else {
+                break;
+            }
+        }
+        let end = chars.get(j).map_or(text.len(), |(p, _)| *p);
+        push_piece(text, start, end, &mut result);
+        start = end;
+        i = j;
+    }
+
+    push_piece(text, start, text.len(), &mut result);
+    result
+}
+
+fn utf16_len(text: &str) -> usize {
+    text.encode_utf16().count()
+}
+
+/// Hard-split a piece that is longer than `max_len` on its own, preferring
+/// whitespace and never cutting inside a character.
+fn hard_split(piece: &str, max_len: usize, out: &mut Vec<String>) {
+    let mut rest = piece;
+    while utf16_len(rest) > max_len {
+        let mut cut = 0;
+        let mut last_space = None;
+        let mut len = 0;
+        for (pos, c) in rest.char_indices() {
+            len += c.len_utf16();
+            if len > max_len {
+                break;
+            }
+            cut = pos + c.len_utf8();
+            if c.is_whitespace() {
+                last_space = Some(cut);
+            }
+        }
+        let cut = match last_space.filter(|s| *s > cut / 2).unwrap_or(cut) {
+            // A single character wider than the limit still has to go somewhere.
+            0 => rest.chars().next().map_or(rest.len(), char::len_utf8),
+            cut => cut,
+        };
+        out.push(rest[..cut].trim_end().to_string());
+        rest = rest[cut..].trim_start();
+    }
+    if !rest.trim().is_empty() {
+        out.push(rest.to_string());
+    }
+}
+
+/// Pack whole sentences into chunks of at most `max_len` UTF-16 code units
+/// (the unit Telegram counts in). Sentences longer than the limit are split
+/// at whitespace.
+pub fn chunk_text(text: &str, max_len: usize) -> Vec<String> {
+    let max_len = max_len.max(1);
+    if utf16_len(text.trim()) <= max_len {
+        return if text.trim().is_empty() {
+            Vec::new()
+        } else {
+            vec![text.trim().to_string()]
+        };
+    }
+
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    for sentence in split_sentences(text) {
+        if utf16_len(&current) + utf16_len(sentence) <= max_len {
+            current.push_str(sentence);
+            continue;
+        }
+        if !current.trim().is_empty() {
+            chunks.push(current.trim().to_string());
+        }
+        current.clear();
+        if utf16_len(sentence) > max_len {
+            hard_split(sentence.trim(), max_len, &mut chunks);
+        } else {
+            current.push_str(sentence.trim_start());
+        }
+    }
+    if !current.trim().is_empty() {
+        chunks.push(current.trim().to_string());
+    }
+    chunks
+}
+
+/// Length of `text` as a reader perceives it, for typing pacing: markdown
+/// markers and emoji modifiers are ignored, runs of whitespace count once and
+/// each emoji sequence counts as one character.
+pub fn reading_length(text: &str) -> usize {
+    let mut count = 0;
+    let mut prev_space = true;
+    let mut prev_emoji = false;
+    for c in text.chars() {
+        if matches!(c, '*' | '_' | '`' | '#' | '~' | '>') {
+            continue;
+        }
+        if c.is_whitespace() {
+            if !prev_space {
+                count += 1;
+            }
+            prev_space = true;
+            prev_emoji = false;
+            continue;
+        }
+        let emoji = is_emoji_part(c);
+        if !(emoji && prev_emoji) {
+            count += 1;
+        }
+        prev_space = false;
+        prev_emoji = emoji;
+    }
+    if prev_space && count > 0 {
+        count -= 1;
+    }
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_latin_and_cjk_sentences() {
+        assert_eq!(
+            split_sentences("Hello there! How are you? 今天天气很好。你呢？"),
+            vec!["Hello there!", " How are you?", " 今天天气很好。", "你呢？"]
+        );
+    }
+
+    #[test]
+    fn keeps_ellipses_quotes_emoji_and_numbers_together() {
+        assert_eq!(
+            split_sentences(
+                "Well... I guess. Pi is 3.14, see example.com! 😄 「本当？」と言った。"
+            ),
+            vec![
+                "Well...",
+                " I guess.",
+                " Pi is 3.14, see example.com! 😄",
+                " 「本当？」",
+                "と言った。"
+            ]
+        );
+        assert_eq!(
+            split_sentences("えっ……そうなの"),
+            vec!["えっ……", "そうなの"]
+        );
+    }
+
+    #[test]
+    fn markdown_lists_and_code_fences() {
+        let text = "Options:\n- one\n- two\n```\nlet x = 1. y = 2;\n```\nDone";
+        assert_eq!(
+            split_sentences(text),
+            vec![
+                "Options:\n",
+                "- one\n",
+                "- two\n",
+                "```\nlet x = 1. y = 2;\n```",
+                "\nDone"
+            ]
+        );
+    }
+
+    #[test]
+    fn chunks_respect_limit_and_sentence_boundaries() {
+        let text = "One two three. Four five six. Seven eight nine.";
+        assert_eq!(
+            chunk_text(text, 30),
+            vec!["One two three. Four five six.", "Seven eight nine."]
+        );
+        let long = "a".repeat(10) + " " + &"b".repeat(10);
+        assert_eq!(chunk_text(&long, 12), vec!["a".repeat(10), "b".repeat(10)]);
+        for chunk in chunk_text(&"字".repeat(9000), TELEGRAM_MAX_MESSAGE_LEN) {
+            assert!(utf16_len(&chunk) <= TELEGRAM_MAX_MESSAGE_LEN);
+        }
+        assert!(chunk_text("  ", 10).is_empty());
+    }
+
+    #[test]
+    fn reading_length_ignores_markup() {
+        assert_eq!(reading_length("**hi**   there"), 8);
+        assert_eq!(reading_length("👍🏽 ok"), 4);
+    }
+}
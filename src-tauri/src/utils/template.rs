@@ -0,0 +1,206 @@
+//! JSON request templates and response paths for config-described HTTP
+//! backends (custom LLM and TTS providers).
+//!
+//! Templates use `{{name}}` placeholders. A string that is exactly one
+//! placeholder is replaced by the typed value (an array, a number) and its key
+//! is dropped when the value is unset; placeholders inside longer strings are
+//! interpolated as text. Response paths are the dotted subset of JSONPath:
+//! `$.choices[0].delta.content`.
+
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+#[derive(Debug, PartialEq)]
+enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+/// Parse `$.a.b[0].c`; also accepted without `$` and with numeric segments (`a.b.0.c`).
+fn parse_path(path: &str) -> Result<Vec<PathSegment>, String> {
+    let trimmed = path.trim();
+    let body = trimmed
+        .strip_prefix('$')
+        .map(|rest| rest.strip_prefix('.').unwrap_or(rest))
+        .unwrap_or(trimmed);
+    let mut segments = Vec::new();
+    for part in body.split('.').filter(|p| !p.is_empty()) {
+        let (key, mut rest) = match part.find('[') {
+            Some(pos) => (&part[..pos], &part[pos..]),
+            None => (part, ""),
+        };
+        if !key.is_empty() {
+            segments.push(match key.parse::<usize>() {
+                Ok(index) => PathSegment::Index(index),
+                Err(_) => PathSegment::Key(key.to_string()),
+            });
+        }
+        while let Some(inner) = rest.strip_prefix('[') {
+            let end = inner
+                .find(']')
+                .ok_or_else(|| format!("Unclosed '[' in path '{}'", path))?;
+            let index = inner[..end]
+                .trim()
+                .parse::<usize>()
+                .map_err(|_| format!("Bad index '{}' in path '{}'", &inner[..end], path))?;
+            segments.push(PathSegment::Index(index));
+            rest = &inner[end + 1..];
+        }
+        if !rest.is_empty() {
+            return Err(format!("Unexpected '{}' in path '{}'", rest, path));
+        }
+    }
+    Ok(segments)
+}
+
+/// Check a path at config time, so typos surface before the first request.
+pub fn validate_path(path: &str) -> Result<(), String> {
+    parse_path(path).map(|_| ())
+}
+
+pub fn select<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    parse_path(path)
+        .ok()?
+        .iter()
+        .try_fold(value, |current, segment| match segment {
+            PathSegment::Key(key) => current.get(key),
+            PathSegment::Index(index) => current.get(*index),
+        })
+}
+
+/// Text at `path`; non-string scalars are rendered, null and missing are `None`.
+pub fn select_text(value: &Value, path: &str) -> Option<String> {
+    match select(value, path)? {
+        Value::String(text) => Some(text.clone()),
+        Value::Null => None,
+        other => Some(other.to_string()),
+    }
+}
+
+fn placeholder(name: &str) -> String {
+    format!("{{{{{}}}}}", name)
+}
+
+/// Named values available to a template.
+#[derive(Debug, Default, Clone)]
+pub struct TemplateVars {
+    values: HashMap<String, Value>,
+}
+
+impl TemplateVars {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set(&mut self, name: &str, value: impl Into<Value>) -> &mut Self {
+        self.values.insert(name.to_string(), value.into());
+        self
+    }
+
+    /// Set `name` only when there is a value; unset placeholders drop their key.
+    pub fn set_opt<T: Into<Value>>(&mut self, name: &str, value: Option<T>) -> &mut Self {
+        if let Some(value) = value {
+            self.set(name, value);
+        }
+        self
+    }
+
+    /// Replace placeholders inside a string; non-string values are inserted as JSON.
+    pub fn interpolate(&self, template: &str) -> String {
+        let mut out = template.to_string();
+        for (name, value) in &self.values {
+            let placeholder = placeholder(name);
+            if out.contains(&placeholder) {
+                let text = match value {
+                    Value::String(text) => text.clone(),
+                    other => other.to_string(),
+                };
+                out = out.replace(&placeholder, &text);
+            }
+        }
+        out
+    }
+
+    /// Render a JSON template; `None` means "drop this key".
+    pub fn render(&self, template: &Value) -> Option<Value> {
+        match template {
+            Value::String(text) => match whole_placeholder(text) {
+                Some(name) => self.values.get(name).cloned(),
+                None => Some(Value::String(self.interpolate(text))),
+            },
+            Value::Array(items) => Some(Value::Array(
+                items.iter().filter_map(|item| self.render(item)).collect(),
+            )),
+            Value::Object(fields) => Some(Value::Object(
+                fields
+                    .iter()
+                    .filter_map(|(key, value)| Some((key.clone(), self.render(value)?)))
+                    .collect::<Map<_, _>>(),
+            )),
+            other => Some(other.clone()),
+        }
+    }
+}
+
+fn whole_placeholder(text: &str) -> Option<&str> {
+    text.strip_prefix("{{")
+        .and_then(|rest| rest.strip_suffix("}}"))
+        .filter(|name| !name.contains('{') && !name.contains('}'))
+        .map(str::trim)
+}
+
+/// Whether a template mentions `{{name}}` anywhere.
+pub fn references(template: &Value, name: &str) -> bool {
+    match template {
+        Value::String(text) => text.contains(&placeholder(name)),
+        Value::Array(items) => items.iter().any(|item| references(item, name)),
+        Value::Object(fields) => fields.values().any(|value| references(value, name)),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn renders_typed_placeholders_and_selects_paths() {
+        let mut vars = TemplateVars::new();
+        vars.set("text", "hi")
+            .set("speed", 1.5)
+            .set_opt::<String>("voice", None);
+        let template = json!({
+            "input": "{{text}}",
+            "speed": "{{speed}}",
+            "voice": "{{voice}}",
+            "label": "say {{text}} at {{speed}}x",
+            "fixed": [1, "{{ text }}"]
+        });
+        assert_eq!(
+            vars.render(&template).unwrap(),
+            json!({
+                "input": "hi",
+                "speed": 1.5,
+                "label": "say hi at 1.5x",
+                "fixed": [1, "hi"]
+            })
+        );
+        assert!(references(&template, "voice"));
+        assert!(!references(&template, "pitch"));
+
+        let body = json!({ "choices": [{ "delta": { "content": "Hel" } }], "n": 3 });
+        assert_eq!(
+            select_text(&body, "$.choices[0].delta.content"),
+            Some("Hel".to_string())
+        );
+        assert_eq!(
+            select_text(&body, "choices.0.delta.content"),
+            Some("Hel".to_string())
+        );
+        assert_eq!(select_text(&body, "$.n"), Some("3".to_string()));
+        assert_eq!(select_text(&body, "$.missing"), None);
+        assert!(validate_path("$.choices[0").is_err());
+        assert!(validate_path("$.choices[x]").is_err());
+    }
+}
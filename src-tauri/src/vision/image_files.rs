@@ -0,0 +1,454 @@
+//! Image understanding for local files — a dropped photo or a whole folder.
+//!
+//! Images go through the same VLM routing as screenshots. Plain descriptions
+//! are cached by content hash in `image_descriptions.json`, so re-describing
+//! a photo library only pays for new pictures.
+
+use crate::vision::watcher::{analyze_image, VisionWatcher, VlmRequest};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+const SUPPORTED_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "webp", "gif"];
+const MAX_FILE_BYTES: u64 = 25 * 1024 * 1024;
+/// Formats we can't decode are sent as-is, but only when reasonably small.
+const MAX_PASSTHROUGH_BYTES: usize = 5 * 1024 * 1024;
+/// Longest edge sent to the VLM; larger photos are downscaled first.
+const MAX_EDGE: u32 = 1568;
+const DEFAULT_BATCH_LIMIT: usize = 20;
+const MAX_BATCH_LIMIT: usize = 100;
+const CACHE_CAPACITY: usize = 2000;
+const DESCRIBE_MAX_TOKENS: u32 = 220;
+
+const IMAGE_PROMPT: &str = "Describe this photo in 2-4 concise sentences: the setting, the main subjects and what they are doing, notable objects, colors, mood, and any clearly legible text. If a fictional/anime character is clearly recognizable, you may name them; do not identify real people from appearance alone. If something is unclear, say so briefly.";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImageDescription {
+    pub path: String,
+    pub file_name: String,
+    pub description: String,
+    /// Served from the description cache without calling the VLM.
+    pub cached: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImageFailure {
+    pub path: String,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FolderDescription {
+    pub folder: String,
+    /// Supported images found in the folder, including ones past the limit.
+    pub total_images: usize,
+    pub described: Vec<ImageDescription>,
+    pub failed: Vec<ImageFailure>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImageBatchProgress {
+    pub folder: String,
+    pub done: usize,
+    pub total: usize,
+    pub current: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedDescription {
+    description: String,
+    file_name: String,
+    described_at: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CacheFile {
+    #[serde(default)]
+    entries: HashMap<String, CachedDescription>,
+}
+
+/// Content-addressed cache of generic image descriptions.
+pub struct ImageDescriptionCache {
+    path: PathBuf,
+    entries: Mutex<HashMap<String, CachedDescription>>,
+}
+
+impl ImageDescriptionCache {
+    pub fn load(path: PathBuf) -> Self {
+        let file: CacheFile = crate::config::load_json_config(&path, "IMAGE_DESCRIPTIONS");
+        Self {
+            path,
+            entries: Mutex::new(file.entries),
+        }
+    }
+
+    fn get(&self, hash: &str) -> Option<String> {
+        self.entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(hash)
+            .map(|entry| entry.description.clone())
+    }
+
+    fn insert(&self, hash: String, file_name: String, description: String) {
+        let snapshot = {
+            let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+            entries.insert(
+                hash,
+                CachedDescription {
+                    description,
+                    file_name,
+                    described_at: chrono::Utc::now().to_rfc3339(),
+                },
+            );
+            evict_oldest(&mut entries, CACHE_CAPACITY);
+            CacheFile {
+                entries: entries.clone(),
+            }
+        };
+        if let Err(e) = crate::config::save_json_config(&self.path, &snapshot, "IMAGE_DESCRIPTIONS")
+        {
+            tracing::warn!(target: "vision", "[Vision] Failed to persist image description cache: {}", e);
+        }
+    }
+
+    /// Forget every cached description; returns how many were removed.
+    pub fn clear(&self) -> usize {
+        let removed = {
+            let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+            let removed = entries.len();
+            entries.clear();
+            removed
+        };
+        let _ = std::fs::remove_file(&self.path);
+        removed
+    }
+}
+
+/// Drop the oldest entries until at most `capacity` remain.
+fn evict_oldest(entries: &mut HashMap<String, CachedDescription>, capacity: usize) {
+    if entries.len() <= capacity {
+        return;
+    }
+    let mut by_age: Vec<(String, String)> = entries
+        .iter()
+        .map(|(hash, entry)| (entry.described_at.clone(), hash.clone()))
+        .collect();
+    by_age.sort();
+    let excess = entries.len() - capacity;
+    for (_, hash) in by_age.into_iter().take(excess) {
+        entries.remove(&hash);
+    }
+}
+
+pub fn is_supported_image(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| SUPPORTED_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+fn mime_for_extension(ext: &str) -> &'static str {
+    match ext {
+        "png" => "image/png",
+        "webp" => "image/webp",
+        "gif" => "image/gif",
+        _ => "image/jpeg",
+    }
+}
+
+fn content_hash(bytes: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(bytes);
+    format!("{:x}", hasher.finalize())
+}
+
+/// Expand a leading `~` so paths typed by the model or the user resolve.
+fn expand_home(path: &str) -> PathBuf {
+    let trimmed = path.trim().trim_matches('"');
+    if let Some(rest) = trimmed
+        .strip_prefix("~/")
+        .or_else(|| trimmed.strip_prefix("~\\"))
+    {
+        if let Some(home) = dirs_next::home_dir() {
+            return home.join(rest);
+        }
+    }
+    if trimmed == "~" {
+        if let Some(home) = dirs_next::home_dir() {
+            return home;
+        }
+    }
+    PathBuf::from(trimmed)
+}
+
+/// Re-encode decodable images as a bounded-size JPEG; pass small others through.
+pub(crate) fn prepare_image(bytes: &[u8], ext: &str) -> Result<(Vec<u8>, &'static str), String> {
+    if matches!(ext, "png" | "jpg" | "jpeg") {
+        let image =
+            image::load_from_memory(bytes).map_err(|e| format!("Cannot decode image: {e}"))?;
+        let image = if image.width().max(image.height()) > MAX_EDGE {
+            image.resize(MAX_EDGE, MAX_EDGE, image::imageops::FilterType::Triangle)
+        } else {
+            image
+        };
+        let mut buf = std::io::Cursor::new(Vec::new());
+        image::DynamicImage::ImageRgb8(image.to_rgb8())
+            .write_to(&mut buf, image::ImageFormat::Jpeg)
+            .map_err(|e| format!("Image encoding failed: {e}"))?;
+        return Ok((buf.into_inner(), "image/jpeg"));
+    }
+
+    if bytes.len() > MAX_PASSTHROUGH_BYTES {
+        return Err(format!(
+            "{} images larger than {} MB are not supported",
+            ext.to_uppercase(),
+            MAX_PASSTHROUGH_BYTES / (1024 * 1024)
+        ));
+    }
+    Ok((bytes.to_vec(), mime_for_extension(ext)))
+}
+
+fn build_prompt(question: Option<&str>) -> String {
+    match question.map(str::trim).filter(|q| !q.is_empty()) {
+        Some(question) => {
+            format!("{IMAGE_PROMPT}\n\nThen answer this question about the image: {question}")
+        }
+        None => IMAGE_PROMPT.to_string(),
+    }
+}
+
+/// Describe one local image. Plain descriptions are cached; answers to a
+/// specific `question` always go to the VLM.
+pub async fn describe_image_file(
+    watcher: &VisionWatcher,
+    cache: &ImageDescriptionCache,
+    path: &str,
+    question: Option<&str>,
+) -> Result<ImageDescription, String> {
+    let path = expand_home(path);
+    if !path.is_file() {
+        return Err(format!("Image not found: {}", path.display()));
+    }
+    if !is_supported_image(&path) {
+        return Err(format!(
+            "Unsupported image type (expected one of: {})",
+            SUPPORTED_EXTENSIONS.join(", ")
+        ));
+    }
+    let size = std::fs::metadata(&path).map_err(|e| e.to_string())?.len();
+    if size > MAX_FILE_BYTES {
+        return Err(format!(
+            "Image is larger than {} MB",
+            MAX_FILE_BYTES / (1024 * 1024)
+        ));
+    }
+
+    let bytes = tokio::fs::read(&path)
+        .await
+        .map_err(|e| format!("Failed to read image: {e}"))?;
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let path_str = path.to_string_lossy().into_owned();
+    let has_question = question.map(|q| !q.trim().is_empty()).unwrap_or(false);
+    let hash = content_hash(&bytes);
+
+    if !has_question {
+        if let Some(description) = cache.get(&hash) {
+            return Ok(ImageDescription {
+                path: path_str,
+                file_name,
+                description,
+                cached: true,
+            });
+        }
+    }
+
+    let ext = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    let (prepared, mime_type) = tokio::task::spawn_blocking(move || prepare_image(&bytes, &ext))
+        .await
+        .map_err(|e| e.to_string())??;
+
+    let config = watcher.config.read().await.clone();
+    let prompt = build_prompt(question);
+    let description = analyze_image(
+        &watcher.client,
+        &config,
+        &VlmRequest {
+            image: &prepared,
+            mime_type,
+            prompt: &prompt,
+            max_tokens: DESCRIBE_MAX_TOKENS,
+        },
+        watcher.llm_service.as_ref(),
+    )
+    .await?
+    .trim()
+    .to_string();
+
+    if !has_question {
+        cache.insert(hash, file_name.clone(), description.clone());
+    }
+
+    Ok(ImageDescription {
+        path: path_str,
+        file_name,
+        description,
+        cached: false,
+    })
+}
+
+/// Supported images directly inside `folder`, newest first.
+fn list_folder_images(folder: &Path) -> Result<Vec<PathBuf>, String> {
+    let entries = std::fs::read_dir(folder)
+        .map_err(|e| format!("Cannot read folder {}: {}", folder.display(), e))?;
+    let mut images: Vec<(std::time::SystemTime, PathBuf)> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && is_supported_image(path))
+        .map(|path| {
+            let modified = std::fs::metadata(&path)
+                .and_then(|meta| meta.modified())
+                .unwrap_or(std::time::UNIX_EPOCH);
+            (modified, path)
+        })
+        .collect();
+    images.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+    Ok(images.into_iter().map(|(_, path)| path).collect())
+}
+
+pub fn clamp_batch_limit(limit: Option<usize>) -> usize {
+    limit
+        .unwrap_or(DEFAULT_BATCH_LIMIT)
+        .clamp(1, MAX_BATCH_LIMIT)
+}
+
+/// Describe up to `limit` of the most recent images in a folder, one at a time.
+pub async fn describe_folder<F>(
+    watcher: &VisionWatcher,
+    cache: &ImageDescriptionCache,
+    folder: &str,
+    limit: Option<usize>,
+    on_progress: F,
+) -> Result<FolderDescription, String>
+where
+    F: Fn(ImageBatchProgress),
+{
+    let folder_path = expand_home(folder);
+    if !folder_path.is_dir() {
+        return Err(format!("Folder not found: {}", folder_path.display()));
+    }
+    let images = list_folder_images(&folder_path)?;
+    let total_images = images.len();
+    let selected: Vec<PathBuf> = images.into_iter().take(clamp_batch_limit(limit)).collect();
+    let folder = folder_path.to_string_lossy().into_owned();
+
+    let mut described = Vec::new();
+    let mut failed = Vec::new();
+    for (index, path) in selected.iter().enumerate() {
+        let path_str = path.to_string_lossy().into_owned();
+        on_progress(ImageBatchProgress {
+            folder: folder.clone(),
+            done: index,
+            total: selected.len(),
+            current: path_str.clone(),
+        });
+        match describe_image_file(watcher, cache, &path_str, None).await {
+            Ok(description) => described.push(description),
+            Err(error) => {
+                tracing::warn!(target: "vision", "[Vision] Failed to describe {}: {}", path_str, error);
+                failed.push(ImageFailure {
+                    path: path_str,
+                    error,
+                });
+            }
+        }
+    }
+    on_progress(ImageBatchProgress {
+        folder: folder.clone(),
+        done: selected.len(),
+        total: selected.len(),
+        current: String::new(),
+    });
+
+    Ok(FolderDescription {
+        folder,
+        total_images,
+        described,
+        failed,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(described_at: &str) -> CachedDescription {
+        CachedDescription {
+            description: "d".to_string(),
+            file_name: "f.jpg".to_string(),
+            described_at: described_at.to_string(),
+        }
+    }
+
+    #[test]
+    fn supported_extensions_are_case_insensitive() {
+        assert!(is_supported_image(Path::new("/photos/IMG_0001.JPG")));
+        assert!(is_supported_image(Path::new("cat.webp")));
+        assert!(!is_supported_image(Path::new("notes.txt")));
+        assert!(!is_supported_image(Path::new("no_extension")));
+    }
+
+    #[test]
+    fn eviction_keeps_newest_entries() {
+        let mut entries = HashMap::new();
+        entries.insert("a".to_string(), entry("2026-01-01T00:00:00+00:00"));
+        entries.insert("b".to_string(), entry("2026-03-01T00:00:00+00:00"));
+        entries.insert("c".to_string(), entry("2026-02-01T00:00:00+00:00"));
+        evict_oldest(&mut entries, 2);
+        assert_eq!(entries.len(), 2);
+        assert!(!entries.contains_key("a"));
+    }
+
+    #[test]
+    fn large_images_are_downscaled_to_jpeg() {
+        let image = image::RgbImage::from_pixel(3000, 1000, image::Rgb([200, 40, 40]));
+        let mut png = std::io::Cursor::new(Vec::new());
+        image::DynamicImage::ImageRgb8(image)
+            .write_to(&mut png, image::ImageFormat::Png)
+            .unwrap();
+
+        let (jpeg, mime) = prepare_image(&png.into_inner(), "png").unwrap();
+        assert_eq!(mime, "image/jpeg");
+        let decoded = image::load_from_memory(&jpeg).unwrap();
+        assert_eq!(decoded.width(), MAX_EDGE);
+        assert!(decoded.height() <= MAX_EDGE);
+
+        let gif = vec![0u8; MAX_PASSTHROUGH_BYTES + 1];
+        assert!(prepare_image(&gif, "gif").is_err());
+    }
+
+    #[test]
+    fn folder_listing_skips_non_images_and_batch_limit_is_clamped() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.png"), b"x").unwrap();
+        std::fs::write(dir.path().join("b.txt"), b"x").unwrap();
+        std::fs::write(dir.path().join("c.JPEG"), b"x").unwrap();
+        let images = list_folder_images(dir.path()).unwrap();
+        assert_eq!(images.len(), 2);
+
+        assert_eq!(clamp_batch_limit(None), DEFAULT_BATCH_LIMIT);
+        assert_eq!(clamp_batch_limit(Some(0)), 1);
+        assert_eq!(clamp_batch_limit(Some(10_000)), MAX_BATCH_LIMIT);
+        assert!(build_prompt(Some("  what breed? ")).ends_with("what breed?"));
+        assert_eq!(build_prompt(Some("  ")), IMAGE_PROMPT);
+    }
+}
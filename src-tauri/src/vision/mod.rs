@@ -1,6 +1,7 @@
 pub mod capture;
 pub mod config;
 pub mod context;
+pub mod image_files;
 pub mod server;
 pub mod watcher;
 
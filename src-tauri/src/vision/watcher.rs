@@ -327,9 +327,32 @@ pub async fn analyze_screenshot(
     screenshot: &[u8],
     llm_service: Option<&LlmService>,
 ) -> Result<String, String> {
-    // Encode screenshot as base64 data URL (used by both paths)
-    let b64 = base64::Engine::encode(&base64::engine::general_purpose::STANDARD, screenshot);
-    let data_url = format!("data:image/jpeg;base64,{}", b64);
+    let request = VlmRequest {
+        image: screenshot,
+        mime_type: "image/jpeg",
+        prompt: VISION_PROMPT,
+        max_tokens: 150,
+    };
+    analyze_image(client, config, &request, llm_service).await
+}
+
+/// One image plus the instruction to send with it.
+pub struct VlmRequest<'a> {
+    pub image: &'a [u8],
+    pub mime_type: &'a str,
+    pub prompt: &'a str,
+    pub max_tokens: u32,
+}
+
+/// Send an arbitrary image to the configured VLM, routed the same way as screenshots.
+pub async fn analyze_image(
+    client: &Client,
+    config: &VisionConfig,
+    request: &VlmRequest<'_>,
+    llm_service: Option<&LlmService>,
+) -> Result<String, String> {
+    let b64 = base64::Engine::encode(&base64::engine::general_purpose::STANDARD, request.image);
+    let data_url = format!("data:{};base64,{}", request.mime_type, b64);
 
     if config.vlm_provider == "llm" {
         // ── Route through the active LLM provider ──────────────────────────
@@ -337,12 +360,12 @@ pub async fn analyze_screenshot(
         let provider = svc.provider().await;
 
         let messages = vec![user_message_with_images(
-            VISION_PROMPT.to_string(),
+            request.prompt.to_string(),
             vec![data_url],
         )];
 
         let params = LlmParams {
-            max_tokens: Some(150),
+            max_tokens: Some(request.max_tokens),
             temperature: Some(0.3),
             ..Default::default()
         };
@@ -359,12 +382,12 @@ pub async fn analyze_screenshot(
         );
 
         let messages = vec![user_message_with_images(
-            VISION_PROMPT.to_string(),
+            request.prompt.to_string(),
             vec![data_url],
         )];
 
         let params = LlmParams {
-            max_tokens: Some(150),
+            max_tokens: Some(request.max_tokens),
             temperature: Some(0.3),
             ..Default::default()
         };
@@ -388,11 +411,11 @@ pub async fn analyze_screenshot(
             "messages": [{
                 "role": "user",
                 "content": [
-                    { "type": "text", "text": VISION_PROMPT },
+                    { "type": "text", "text": request.prompt },
                     { "type": "image_url", "image_url": { "url": data_url } }
                 ]
             }],
-            "max_tokens": 150,
+            "max_tokens": request.max_tokens,
             "temperature": 0.3
         });
 
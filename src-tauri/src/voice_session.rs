@@ -0,0 +1,531 @@
+//! Full-duplex voice conversation.
+//!
+//! Ties the existing voice pieces into one backend loop: the native mic feeds
+//! the stream buffer, the stream VAD cuts and transcribes each utterance
+//! (`stt:vad-utterance`), the transcript goes through `stream_chat` on the
+//! main window, and the reply is spoken sentence by sentence while it is still
+//! streaming. Talking over playback stops TTS (mic barge-in); finishing a new
+//! utterance also cancels the reply that is still being generated.
+
+use crate::commands::chat::{ChatRequest, TurnCancellationState};
+use crate::stt::mic::{MicOptions, NativeMicState};
+use crate::stt::stream::AudioBuffer;
+use crate::tts::queue::TtsPriority;
+use crate::tts::{TtsParams, TtsService};
+use crate::utils::segment::split_sentences;
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tauri::async_runtime::JoinHandle;
+use tauri::{AppHandle, Emitter, EventId, Listener, Manager};
+use tokio::sync::{mpsc, Mutex};
+
+pub const VOICE_SESSION_STATE_EVENT: &str = "voice-session:state";
+/// A transcript the session is about to send; the chat UI shows it as the user message.
+pub const VOICE_SESSION_UTTERANCE_EVENT: &str = "voice-session:utterance";
+
+/// TTS source for session speech; also tells the chat UI not to speak the reply again.
+const SPEECH_SOURCE: &str = "voice_session";
+/// Window whose chat turns the session drives.
+const SESSION_WINDOW: &str = "main";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct VoiceSessionStatus {
+    pub active: bool,
+    /// Utterances sent to chat since the session started.
+    pub turns: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct VoiceUtteranceEvent {
+    text: String,
+}
+
+/// How session replies are voiced; mirrors the chat auto-speak settings.
+#[derive(Debug, Clone, Default)]
+pub struct VoiceSettings {
+    pub provider_id: Option<String>,
+    pub params: TtsParams,
+    /// Condition each sentence on the character's current emotion.
+    pub follow_emotion: bool,
+}
+
+/// Collects streamed reply text and hands out sentences once they are
+/// complete. The last piece is held back because it may still grow.
+#[derive(Debug, Default)]
+pub(crate) struct SentenceBuffer {
+    pending: String,
+}
+
+impl SentenceBuffer {
+    pub fn push(&mut self, delta: &str) -> Vec<String> {
+        self.pending.push_str(delta);
+        let pieces = split_sentences(&self.pending);
+        let Some((last, complete)) = pieces.split_last() else {
+            return Vec::new();
+        };
+        let ready = complete
+            .iter()
+            .map(|piece| piece.trim().to_string())
+            .collect();
+        let keep_from = last.as_ptr() as usize - self.pending.as_ptr() as usize;
+        self.pending = self.pending[keep_from..].to_string();
+        ready
+    }
+
+    /// Whatever is left once the reply has finished.
+    pub fn finish(&mut self) -> Option<String> {
+        let rest = std::mem::take(&mut self.pending);
+        let rest = rest.trim();
+        (!rest.is_empty()).then(|| rest.to_string())
+    }
+
+    pub fn clear(&mut self) {
+        self.pending.clear();
+    }
+}
+
+/// Strip control tags from a reply sentence; `None` if nothing speakable is left.
+pub(crate) fn speakable(sentence: &str) -> Option<String> {
+    let (text, _) = crate::telegram::bot::clean_model_reply(sentence);
+    let text = text.trim();
+    text.chars()
+        .any(char::is_alphanumeric)
+        .then(|| text.to_string())
+}
+
+enum SessionInput {
+    Utterance(String),
+    TurnStart(String),
+    TurnDelta { turn_id: String, delta: String },
+    TurnFinish { turn_id: String, status: String },
+}
+
+struct RunningSession {
+    listeners: Vec<EventId>,
+    loop_task: JoinHandle<()>,
+    speaker_task: JoinHandle<()>,
+    turns: Arc<AtomicU64>,
+}
+
+/// Managed Tauri state for the voice session.
+#[derive(Default)]
+pub struct VoiceSessionService {
+    session: Mutex<Option<RunningSession>>,
+    /// Settings of the last start, reused by [`VoiceSessionService::toggle`].
+    last_voice: Mutex<VoiceSettings>,
+}
+
+impl VoiceSessionService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub async fn status(&self) -> VoiceSessionStatus {
+        let session = self.session.lock().await;
+        VoiceSessionStatus {
+            active: session.is_some(),
+            turns: session
+                .as_ref()
+                .map_or(0, |s| s.turns.load(Ordering::SeqCst)),
+        }
+    }
+
+    /// Start listening. Does nothing if a session is already running.
+    pub async fn start(
+        &self,
+        app: &AppHandle,
+        voice: VoiceSettings,
+    ) -> Result<VoiceSessionStatus, String> {
+        let mut session = self.session.lock().await;
+        if session.is_none() {
+            *self.last_voice.lock().await = voice.clone();
+            *session = Some(start_session(app, voice).await?);
+            tracing::info!(target: "voice_session", "[VoiceSession] Started");
+            emit_state(app, true);
+        }
+        let turns = session
+            .as_ref()
+            .map_or(0, |s| s.turns.load(Ordering::SeqCst));
+        Ok(VoiceSessionStatus {
+            active: true,
+            turns,
+        })
+    }
+
+    /// Start with the last used voice settings, or stop if already running.
+    /// For triggers that can't carry the frontend's TTS settings.
+    pub async fn toggle(&self, app: &AppHandle) -> Result<VoiceSessionStatus, String> {
+        if self.session.lock().await.is_some() {
+            self.stop(app).await
+        } else {
+            let voice = self.last_voice.lock().await.clone();
+            self.start(app, voice).await
+        }
+    }
+
+    /// Stop listening and speaking. A reply that is still generating keeps
+    /// going in the chat window but is no longer voiced.
+    pub async fn stop(&self, app: &AppHandle) -> Result<VoiceSessionStatus, String> {
+        let Some(session) = self.session.lock().await.take() else {
+            return Ok(VoiceSessionStatus {
+                active: false,
+                turns: 0,
+            });
+        };
+        for id in session.listeners {
+            app.unlisten(id);
+        }
+        session.loop_task.abort();
+        session.speaker_task.abort();
+        if let Some(tts) = app.try_state::<TtsService>() {
+            tts.stop_speaking(app, "voice_session_stopped");
+        }
+        let result = stop_capture(app).await;
+        tracing::info!(target: "voice_session", "[VoiceSession] Stopped");
+        emit_state(app, false);
+        result.map(|_| VoiceSessionStatus {
+            active: false,
+            turns: session.turns.load(Ordering::SeqCst),
+        })
+    }
+}
+
+fn emit_state(app: &AppHandle, active: bool) {
+    let _ = app.emit(
+        VOICE_SESSION_STATE_EVENT,
+        serde_json::json!({ "active": active }),
+    );
+}
+
+async fn start_session(app: &AppHandle, voice: VoiceSettings) -> Result<RunningSession, String> {
+    crate::stt::stream::configure_stream_vad(app.clone(), app.state::<AudioBuffer>().inner(), true)
+        .await?;
+    let mic_app = app.clone();
+    let started = tokio::task::spawn_blocking(move || {
+        crate::stt::mic::start_native_mic_with_options(
+            &mic_app,
+            mic_app.state::<NativeMicState>().inner(),
+            MicOptions {
+                auto_stop_on_silence: false,
+                barge_in: true,
+            },
+        )
+    })
+    .await
+    .map_err(|e| e.to_string())
+    .and_then(|result| result);
+    if let Err(err) = started {
+        let _ = crate::stt::stream::configure_stream_vad(
+            app.clone(),
+            app.state::<AudioBuffer>().inner(),
+            false,
+        )
+        .await;
+        return Err(err);
+    }
+    crate::ai::companion_state::set_active(
+        app,
+        crate::ai::companion_state::CompanionActivity::Listening,
+        true,
+    );
+
+    let (input_tx, input_rx) = mpsc::unbounded_channel();
+    let listeners = listen_inputs(app, input_tx);
+
+    let generation = Arc::new(AtomicU64::new(0));
+    let (speech_tx, speech_rx) = mpsc::unbounded_channel();
+    let speaker_task = tauri::async_runtime::spawn(run_speaker(
+        app.clone(),
+        voice,
+        generation.clone(),
+        speech_rx,
+    ));
+    let turns = Arc::new(AtomicU64::new(0));
+    let loop_task = tauri::async_runtime::spawn(run_session(
+        app.clone(),
+        input_rx,
+        speech_tx,
+        generation,
+        turns.clone(),
+    ));
+
+    Ok(RunningSession {
+        listeners,
+        loop_task,
+        speaker_task,
+        turns,
+    })
+}
+
+async fn stop_capture(app: &AppHandle) -> Result<(), String> {
+    let mic_app = app.clone();
+    let stopped = tokio::task::spawn_blocking(move || {
+        crate::stt::mic::stop_native_mic(&mic_app, mic_app.state::<NativeMicState>().inner())
+    })
+    .await
+    .map_err(|e| e.to_string())
+    .and_then(|result| result);
+    crate::ai::companion_state::set_active(
+        app,
+        crate::ai::companion_state::CompanionActivity::Listening,
+        false,
+    );
+    crate::stt::stream::configure_stream_vad(
+        app.clone(),
+        app.state::<AudioBuffer>().inner(),
+        false,
+    )
+    .await?;
+    stopped
+}
+
+/// Forward utterances and chat turn events into the session loop, in order.
+fn listen_inputs(app: &AppHandle, tx: mpsc::UnboundedSender<SessionInput>) -> Vec<EventId> {
+    fn field(payload: &serde_json::Value, key: &str) -> String {
+        payload
+            .get(key)
+            .and_then(|value| value.as_str())
+            .unwrap_or_default()
+            .to_string()
+    }
+
+    let routes: [(&str, fn(&serde_json::Value) -> Option<SessionInput>); 4] = [
+        ("stt:vad-utterance", |payload| {
+            let text = field(payload, "text").trim().to_string();
+            (!text.is_empty()).then_some(SessionInput::Utterance(text))
+        }),
+        ("chat-turn-start", |payload| {
+            Some(SessionInput::TurnStart(field(payload, "turn_id")))
+        }),
+        ("chat-turn-delta", |payload| {
+            Some(SessionInput::TurnDelta {
+                turn_id: field(payload, "turn_id"),
+                delta: field(payload, "delta"),
+            })
+        }),
+        ("chat-turn-finish", |payload| {
+            Some(SessionInput::TurnFinish {
+                turn_id: field(payload, "turn_id"),
+                status: field(payload, "status"),
+            })
+        }),
+    ];
+
+    routes
+        .into_iter()
+        .map(|(event, route)| {
+            let tx = tx.clone();
+            app.listen(event, move |event| {
+                let Ok(payload) = serde_json::from_str::<serde_json::Value>(event.payload()) else {
+                    return;
+                };
+                if let Some(input) = route(&payload) {
+                    let _ = tx.send(input);
+                }
+            })
+        })
+        .collect()
+}
+
+async fn run_session(
+    app: AppHandle,
+    mut inputs: mpsc::UnboundedReceiver<SessionInput>,
+    speech: mpsc::UnboundedSender<(u64, String)>,
+    generation: Arc<AtomicU64>,
+    turns: Arc<AtomicU64>,
+) {
+    let mut sentences = SentenceBuffer::default();
+    // Waiting for the turn id of the message just sent.
+    let mut awaiting_turn = false;
+    let mut current_turn: Option<String> = None;
+
+    while let Some(input) = inputs.recv().await {
+        match input {
+            SessionInput::Utterance(text) => {
+                let current = generation.fetch_add(1, Ordering::SeqCst) + 1;
+                if awaiting_turn || current_turn.is_some() {
+                    interrupt_reply(&app).await;
+                }
+                sentences.clear();
+                current_turn = None;
+                awaiting_turn = true;
+                turns.fetch_add(1, Ordering::SeqCst);
+                tracing::info!(
+                    target: "voice_session",
+                    "[VoiceSession] Utterance #{} ({} chars)",
+                    current,
+                    text.chars().count()
+                );
+                let _ = app.emit(
+                    VOICE_SESSION_UTTERANCE_EVENT,
+                    VoiceUtteranceEvent { text: text.clone() },
+                );
+                let turn_app = app.clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = send_to_chat(&turn_app, text).await {
+                        tracing::warn!(target: "voice_session", "[VoiceSession] Chat turn failed: {}", e);
+                    }
+                });
+            }
+            SessionInput::TurnStart(turn_id) => {
+                if awaiting_turn {
+                    awaiting_turn = false;
+                    current_turn = Some(turn_id);
+                }
+            }
+            SessionInput::TurnDelta { turn_id, delta } => {
+                if current_turn.as_deref() != Some(turn_id.as_str()) {
+                    continue;
+                }
+                let current = generation.load(Ordering::SeqCst);
+                for sentence in sentences.push(&delta) {
+                    let _ = speech.send((current, sentence));
+                }
+            }
+            SessionInput::TurnFinish { turn_id, status } => {
+                if current_turn.as_deref() != Some(turn_id.as_str()) {
+                    continue;
+                }
+                current_turn = None;
+                let rest = sentences.finish();
+                if status == "completed" {
+                    if let Some(rest) = rest {
+                        let _ = speech.send((generation.load(Ordering::SeqCst), rest));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The user spoke again before the reply finished: stop it and its speech.
+async fn interrupt_reply(app: &AppHandle) {
+    if let Some(cancel_state) = app.try_state::<Arc<TurnCancellationState>>() {
+        cancel_state
+            .cancel_window_turn(
+                SESSION_WINDOW,
+                Some("voice_session_interrupted".to_string()),
+            )
+            .await;
+    }
+    if let Some(tts) = app.try_state::<TtsService>() {
+        tts.stop_speaking(app, "voice_session_interrupted");
+    }
+}
+
+/// Run a regular chat turn in the main window, exactly as if it had been typed.
+async fn send_to_chat(app: &AppHandle, message: String) -> Result<(), String> {
+    let window = app
+        .get_webview_window(SESSION_WINDOW)
+        .map(|webview| webview.as_ref().window())
+        .ok_or_else(|| "Main window is not available".to_string())?;
+    let character_id =
+        crate::telegram::bot::desktop_char_id(&app.state::<crate::ai::context::AIOrchestrator>())
+            .await;
+    let request = ChatRequest {
+        message,
+        api_key: None,
+        endpoint: None,
+        model: None,
+        allow_image_gen: None,
+        images: None,
+        character_id: Some(character_id),
+        hidden: false,
+        regenerate: false,
+    };
+    crate::commands::chat::stream_chat(
+        window,
+        app.clone(),
+        request,
+        app.state(),
+        app.state(),
+        app.state(),
+        app.state(),
+        app.state(),
+        app.state(),
+        app.state(),
+        app.state(),
+        app.state(),
+        app.state(),
+    )
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Speak sentences one after another; anything queued before the latest
+/// utterance is dropped.
+async fn run_speaker(
+    app: AppHandle,
+    voice: VoiceSettings,
+    generation: Arc<AtomicU64>,
+    mut speech: mpsc::UnboundedReceiver<(u64, String)>,
+) {
+    while let Some((sentence_generation, sentence)) = speech.recv().await {
+        if sentence_generation != generation.load(Ordering::SeqCst) {
+            continue;
+        }
+        let Some(text) = speakable(&sentence) else {
+            continue;
+        };
+        let Some(tts) = app.try_state::<TtsService>() else {
+            continue;
+        };
+        let mut params = voice.params.clone();
+        if voice.follow_emotion && params.emotion.is_none() {
+            if let Some(orchestrator) = app.try_state::<crate::ai::context::AIOrchestrator>() {
+                let current = orchestrator.emotion.lock().await.state();
+                crate::tts::emotion_tts::condition_params(
+                    &mut params,
+                    current.emotion,
+                    current.intensity,
+                    current.mood,
+                );
+            }
+        }
+        if let Err(e) = tts
+            .speak(
+                app.clone(),
+                text,
+                voice.provider_id.clone(),
+                Some(params),
+                TtsPriority::Normal,
+                Some(SPEECH_SOURCE.to_string()),
+            )
+            .await
+        {
+            tracing::warn!(target: "voice_session", "[VoiceSession] Speech failed: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sentences_are_released_once_the_next_one_starts() {
+        let mut buffer = SentenceBuffer::default();
+        assert!(buffer.push("Hello the").is_empty());
+        assert!(buffer.push("re.").is_empty());
+        assert_eq!(buffer.push(" How are"), vec!["Hello there."]);
+        assert_eq!(buffer.push(" you? Fine."), vec!["How are you?"]);
+        assert_eq!(buffer.finish().as_deref(), Some("Fine."));
+        assert_eq!(buffer.finish(), None);
+    }
+
+    #[test]
+    fn cjk_sentences_split_without_spaces() {
+        let mut buffer = SentenceBuffer::default();
+        assert_eq!(buffer.push("你好。今天"), vec!["你好。"]);
+        assert_eq!(buffer.finish().as_deref(), Some("今天"));
+    }
+
+    #[test]
+    fn clear_drops_the_partial_sentence() {
+        let mut buffer = SentenceBuffer::default();
+        buffer.push("Half a");
+        buffer.clear();
+        assert_eq!(buffer.finish(), None);
+    }
+}
@@ -0,0 +1,150 @@
+//! VTube Studio integration configuration — load/save from app data directory.
+
+use crate::error::KokoroError;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct VtsConfig {
+    /// Connect to VTube Studio on app launch.
+    #[serde(default)]
+    pub enabled: bool,
+    /// VTube Studio plugin API endpoint.
+    #[serde(default = "default_url")]
+    pub url: String,
+    /// Token issued by VTube Studio after the user allowed the plugin.
+    #[serde(default)]
+    pub auth_token: Option<String>,
+    /// Cue name → VTS hotkey name or ID.
+    #[serde(default)]
+    pub cue_hotkeys: BTreeMap<String, String>,
+    /// Trigger a hotkey named like the cue when no mapping exists.
+    #[serde(default = "default_true")]
+    pub match_hotkey_names: bool,
+    /// Drive this input parameter from TTS audio levels.
+    #[serde(default = "default_true")]
+    pub lip_sync_enabled: bool,
+    #[serde(default = "default_mouth_parameter")]
+    pub mouth_parameter: String,
+    /// Multiplier applied to the playback amplitude before clamping to 0–1.
+    #[serde(default = "default_lip_sync_gain")]
+    pub lip_sync_gain: f32,
+}
+
+fn default_url() -> String {
+    "ws://127.0.0.1:8001".to_string()
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_mouth_parameter() -> String {
+    "MouthOpen".to_string()
+}
+
+fn default_lip_sync_gain() -> f32 {
+    2.0
+}
+
+impl Default for VtsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            url: default_url(),
+            auth_token: None,
+            cue_hotkeys: BTreeMap::new(),
+            match_hotkey_names: true,
+            lip_sync_enabled: true,
+            mouth_parameter: default_mouth_parameter(),
+            lip_sync_gain: default_lip_sync_gain(),
+        }
+    }
+}
+
+impl VtsConfig {
+    /// Hotkey to trigger for `cue`, if any.
+    pub fn hotkey_for_cue(&self, cue: &str) -> Option<String> {
+        let cue = cue.trim();
+        if cue.is_empty() {
+            return None;
+        }
+        self.cue_hotkeys
+            .get(cue)
+            .filter(|hotkey| !hotkey.trim().is_empty())
+            .cloned()
+            .or_else(|| self.match_hotkey_names.then(|| cue.to_string()))
+    }
+
+    /// Hotkey mapped to an emotion from `emotion:update`. Only explicit
+    /// `cue_hotkeys` entries count, so drifting moods never guess at names.
+    pub fn hotkey_for_emotion(&self, emotion: &str) -> Option<String> {
+        self.cue_hotkeys
+            .get(emotion)
+            .filter(|hotkey| !hotkey.trim().is_empty())
+            .cloned()
+    }
+
+    /// Mouth parameter value for a raw playback amplitude.
+    pub fn mouth_value(&self, level: f32) -> f32 {
+        if !level.is_finite() {
+            return 0.0;
+        }
+        (level * self.lip_sync_gain).clamp(0.0, 1.0)
+    }
+}
+
+pub fn config_path() -> PathBuf {
+    dirs_next::data_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("com.chyin.kokoro")
+        .join("vts_config.json")
+}
+
+pub fn load_config(path: &Path) -> VtsConfig {
+    crate::config::load_json_config(path, "VTS")
+}
+
+pub fn save_config(path: &Path, config: &VtsConfig) -> Result<(), KokoroError> {
+    crate::config::save_json_config(path, config, "VTS")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cue_mapping_wins_over_name_matching() {
+        let mut config = VtsConfig::default();
+        config
+            .cue_hotkeys
+            .insert("happy".to_string(), "Smile".to_string());
+        config
+            .cue_hotkeys
+            .insert("wave".to_string(), " ".to_string());
+
+        assert_eq!(config.hotkey_for_cue(" happy "), Some("Smile".to_string()));
+        assert_eq!(config.hotkey_for_cue("wave"), Some("wave".to_string()));
+        assert_eq!(config.hotkey_for_cue(""), None);
+        // Emotions never fall back to name matching.
+        assert_eq!(
+            config.hotkey_for_emotion("happy"),
+            Some("Smile".to_string())
+        );
+        assert_eq!(config.hotkey_for_emotion("joy"), None);
+
+        config.match_hotkey_names = false;
+        assert_eq!(config.hotkey_for_cue("wave"), None);
+        assert_eq!(config.hotkey_for_cue("happy"), Some("Smile".to_string()));
+    }
+
+    #[test]
+    fn mouth_value_is_clamped() {
+        let config = VtsConfig::default();
+        assert_eq!(config.mouth_value(0.25), 0.5);
+        assert_eq!(config.mouth_value(0.9), 1.0);
+        assert_eq!(config.mouth_value(-1.0), 0.0);
+        assert_eq!(config.mouth_value(f32::NAN), 0.0);
+    }
+}
@@ -0,0 +1,529 @@
+//! VTube Studio integration — drive a VTS model instead of the built-in Live2D renderer.
+//!
+//! Kokoro connects to the VTS plugin API as a plugin, triggers hotkeys for
+//! `chat-cue` events and emotion changes, and injects the mouth parameter
+//! from the TTS playback levels that the frontend audio player reports.
+
+pub mod config;
+pub mod protocol;
+
+pub use config::{config_path, load_config, save_config, VtsConfig};
+pub use protocol::VtsHotkey;
+
+use futures::{SinkExt, StreamExt};
+use protocol::VtsResponse;
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Listener};
+use tokio::net::TcpStream;
+use tokio::sync::{mpsc, oneshot, RwLock};
+use tokio_tungstenite::tungstenite::Message as WsMessage;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+
+type VtsSocket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+const RECONNECT_DELAY: Duration = Duration::from_secs(10);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+/// Mouth updates are dropped rather than queued once this many are pending.
+const COMMAND_BUFFER: usize = 64;
+
+enum VtsCommand {
+    TriggerHotkey(String),
+    SetParameter { id: String, value: f32 },
+    ListHotkeys(oneshot::Sender<Result<Vec<VtsHotkey>, String>>),
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct VtsStatus {
+    pub running: bool,
+    /// Connected and authenticated as a plugin.
+    pub connected: bool,
+    pub model_name: Option<String>,
+    pub error: Option<String>,
+}
+
+/// Why a session ended.
+enum SessionExit {
+    Shutdown,
+    Reconnect(String),
+    /// Retrying would not help (e.g. the user denied the plugin in VTS).
+    Fatal(String),
+}
+
+enum CallError {
+    /// VTS answered with `APIError`.
+    Api(String),
+    Transport(String),
+}
+
+impl CallError {
+    fn message(self) -> String {
+        match self {
+            CallError::Api(message) | CallError::Transport(message) => message,
+        }
+    }
+
+    fn into_exit(self) -> SessionExit {
+        match self {
+            CallError::Api(message) => SessionExit::Fatal(message),
+            CallError::Transport(message) => SessionExit::Reconnect(message),
+        }
+    }
+}
+
+/// Managed Tauri state for the VTube Studio connection.
+#[derive(Clone)]
+pub struct VtsService {
+    config: Arc<RwLock<VtsConfig>>,
+    /// Sender half of the shutdown signal. `Some` = connection loop is running.
+    shutdown_tx: Arc<RwLock<Option<oneshot::Sender<()>>>>,
+    commands: Arc<RwLock<Option<mpsc::Sender<VtsCommand>>>>,
+    status: Arc<RwLock<VtsStatus>>,
+}
+
+impl VtsService {
+    pub fn new(config: VtsConfig) -> Self {
+        Self {
+            config: Arc::new(RwLock::new(config)),
+            shutdown_tx: Arc::new(RwLock::new(None)),
+            commands: Arc::new(RwLock::new(None)),
+            status: Arc::new(RwLock::new(VtsStatus::default())),
+        }
+    }
+
+    pub async fn is_running(&self) -> bool {
+        self.shutdown_tx.read().await.is_some()
+    }
+
+    pub async fn get_config(&self) -> VtsConfig {
+        self.config.read().await.clone()
+    }
+
+    /// Update the in-memory config (caller is responsible for persisting to disk).
+    /// A changed URL applies on the next connect.
+    pub async fn update_config(&self, config: VtsConfig) {
+        *self.config.write().await = config;
+    }
+
+    pub async fn status(&self) -> VtsStatus {
+        let mut status = self.status.read().await.clone();
+        status.running = self.is_running().await;
+        status
+    }
+
+    /// Listen for cues and speech end. Call once at startup.
+    pub fn forward_app_events(&self, app: &AppHandle) {
+        let service = self.clone();
+        app.listen_any("chat-cue", move |event| {
+            let Ok(payload) = serde_json::from_str::<serde_json::Value>(event.payload()) else {
+                return;
+            };
+            let Some(cue) = payload.get("cue").and_then(|v| v.as_str()) else {
+                return;
+            };
+            let cue = cue.to_string();
+            let service = service.clone();
+            tauri::async_runtime::spawn(async move {
+                service.trigger_cue(&cue).await;
+            });
+        });
+
+        // Cues reach VTS directly above; follow the emotions they leave behind
+        // as they fade, plus those set by mods and integrations.
+        let service = self.clone();
+        let last_emotion = Arc::new(std::sync::Mutex::new(String::from("neutral")));
+        app.listen_any(crate::ai::emotion::EMOTION_UPDATE_EVENT, move |event| {
+            let Ok(payload) = serde_json::from_str::<serde_json::Value>(event.payload()) else {
+                return;
+            };
+            let (Some(emotion), Some(source)) = (
+                payload.get("emotion").and_then(|v| v.as_str()),
+                payload.get("source").and_then(|v| v.as_str()),
+            ) else {
+                return;
+            };
+            {
+                let Ok(mut last) = last_emotion.lock() else {
+                    return;
+                };
+                if *last == emotion {
+                    return;
+                }
+                *last = emotion.to_string();
+            }
+            if source == "cue" {
+                return;
+            }
+            let emotion = emotion.to_string();
+            let service = service.clone();
+            tauri::async_runtime::spawn(async move {
+                service.trigger_emotion(&emotion).await;
+            });
+        });
+
+        let service = self.clone();
+        app.listen_any("tts:end", move |_| {
+            let service = service.clone();
+            tauri::async_runtime::spawn(async move {
+                service.set_mouth_level(0.0).await;
+            });
+        });
+    }
+
+    /// Connect (and keep reconnecting) until [`Self::stop`] is called.
+    pub async fn start(&self) -> Result<(), String> {
+        if self.is_running().await {
+            return Err("VTube Studio integration is already running".to_string());
+        }
+        let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+        let (command_tx, command_rx) = mpsc::channel(COMMAND_BUFFER);
+        *self.shutdown_tx.write().await = Some(shutdown_tx);
+        *self.commands.write().await = Some(command_tx);
+        *self.status.write().await = VtsStatus::default();
+        crate::crash::set_subsystem_active("vts", true);
+
+        let service = self.clone();
+        tauri::async_runtime::spawn(async move {
+            let exit = service.run(command_rx, shutdown_rx).await;
+            // On shutdown `stop` has already reset the state.
+            if let SessionExit::Fatal(error) = exit {
+                tracing::error!(target: "vts", "VTube Studio integration stopped: {}", error);
+                service.status.write().await.error = Some(error);
+                *service.shutdown_tx.write().await = None;
+                *service.commands.write().await = None;
+                crate::crash::set_subsystem_active("vts", false);
+            }
+        });
+        Ok(())
+    }
+
+    pub async fn stop(&self) -> Result<(), String> {
+        match self.shutdown_tx.write().await.take() {
+            Some(tx) => {
+                let _ = tx.send(());
+                *self.commands.write().await = None;
+                *self.status.write().await = VtsStatus::default();
+                crate::crash::set_subsystem_active("vts", false);
+                Ok(())
+            }
+            None => Err("VTube Studio integration is not running".to_string()),
+        }
+    }
+
+    /// Trigger the hotkey mapped to `cue`. No-op while disconnected.
+    pub async fn trigger_cue(&self, cue: &str) {
+        let Some(hotkey) = self.config.read().await.hotkey_for_cue(cue) else {
+            return;
+        };
+        if self.send(VtsCommand::TriggerHotkey(hotkey.clone())).await {
+            tracing::debug!(target: "vts", "cue '{}' → hotkey '{}'", cue, hotkey);
+        }
+    }
+
+    pub async fn trigger_emotion(&self, emotion: &str) {
+        let Some(hotkey) = self.config.read().await.hotkey_for_emotion(emotion) else {
+            return;
+        };
+        if self.send(VtsCommand::TriggerHotkey(hotkey.clone())).await {
+            tracing::debug!(target: "vts", "emotion '{}' → hotkey '{}'", emotion, hotkey);
+        }
+    }
+
+    /// Drive the mouth parameter from a playback amplitude (0–1 RMS).
+    pub async fn set_mouth_level(&self, level: f32) {
+        let (id, value) = {
+            let config = self.config.read().await;
+            if !config.lip_sync_enabled {
+                return;
+            }
+            (config.mouth_parameter.clone(), config.mouth_value(level))
+        };
+        self.send(VtsCommand::SetParameter { id, value }).await;
+    }
+
+    pub async fn list_hotkeys(&self) -> Result<Vec<VtsHotkey>, String> {
+        let (reply_tx, reply_rx) = oneshot::channel();
+        if !self.send(VtsCommand::ListHotkeys(reply_tx)).await {
+            return Err("Not connected to VTube Studio".to_string());
+        }
+        reply_rx
+            .await
+            .map_err(|_| "VTube Studio connection closed".to_string())?
+    }
+
+    async fn send(&self, command: VtsCommand) -> bool {
+        if !self.status.read().await.connected {
+            return false;
+        }
+        match self.commands.read().await.as_ref() {
+            Some(tx) => tx.try_send(command).is_ok(),
+            None => false,
+        }
+    }
+
+    async fn run(
+        &self,
+        mut commands: mpsc::Receiver<VtsCommand>,
+        mut shutdown_rx: oneshot::Receiver<()>,
+    ) -> SessionExit {
+        loop {
+            let exit = self.run_session(&mut commands, &mut shutdown_rx).await;
+            {
+                let mut status = self.status.write().await;
+                status.connected = false;
+                status.model_name = None;
+            }
+            let SessionExit::Reconnect(error) = exit else {
+                return exit;
+            };
+            tracing::warn!(target: "vts", "{}; retrying in {:?}", error, RECONNECT_DELAY);
+            self.status.write().await.error = Some(error);
+            tokio::select! {
+                _ = &mut shutdown_rx => return SessionExit::Shutdown,
+                _ = tokio::time::sleep(RECONNECT_DELAY) => {}
+            }
+        }
+    }
+
+    async fn run_session(
+        &self,
+        commands: &mut mpsc::Receiver<VtsCommand>,
+        shutdown_rx: &mut oneshot::Receiver<()>,
+    ) -> SessionExit {
+        let url = self.config.read().await.url.clone();
+        let mut socket = tokio::select! {
+            _ = &mut *shutdown_rx => return SessionExit::Shutdown,
+            result = tokio_tungstenite::connect_async(url.as_str()) => match result {
+                Ok((socket, _)) => socket,
+                Err(e) => {
+                    return SessionExit::Reconnect(format!(
+                        "Cannot reach VTube Studio at {}: {}",
+                        url, e
+                    ))
+                }
+            },
+        };
+
+        let mut request_ids = 0u64;
+        let authenticated = tokio::select! {
+            _ = &mut *shutdown_rx => {
+                let _ = socket.close(None).await;
+                return SessionExit::Shutdown;
+            }
+            result = self.authenticate(&mut socket, &mut request_ids) => result,
+        };
+        if let Err(exit) = authenticated {
+            return exit;
+        }
+
+        let model_name = call(
+            &mut socket,
+            protocol::current_model_request(&next_request_id(&mut request_ids)),
+            Some(REQUEST_TIMEOUT),
+        )
+        .await
+        .ok()
+        .filter(|response| response.bool_field("modelLoaded"))
+        .and_then(|response| response.str_field("modelName"));
+
+        // Drop anything queued for the previous connection.
+        while commands.try_recv().is_ok() {}
+        tracing::info!(
+            target: "vts",
+            "connected to VTube Studio at {} (model: {})",
+            url,
+            model_name.as_deref().unwrap_or("none")
+        );
+        *self.status.write().await = VtsStatus {
+            running: true,
+            connected: true,
+            model_name,
+            error: None,
+        };
+
+        loop {
+            tokio::select! {
+                _ = &mut *shutdown_rx => {
+                    let _ = socket.close(None).await;
+                    return SessionExit::Shutdown;
+                }
+                command = commands.recv() => {
+                    let Some(command) = command else {
+                        return SessionExit::Shutdown;
+                    };
+                    let request_id = next_request_id(&mut request_ids);
+                    let request = match command {
+                        VtsCommand::TriggerHotkey(hotkey) => {
+                            protocol::trigger_hotkey_request(&request_id, &hotkey)
+                        }
+                        VtsCommand::SetParameter { id, value } => {
+                            protocol::inject_parameter_request(&request_id, &id, value)
+                        }
+                        VtsCommand::ListHotkeys(reply) => {
+                            let result = call(
+                                &mut socket,
+                                protocol::hotkeys_request(&request_id),
+                                Some(REQUEST_TIMEOUT),
+                            )
+                            .await;
+                            let transport_error = matches!(result, Err(CallError::Transport(_)));
+                            let _ = reply.send(
+                                result
+                                    .map(|response| response.hotkeys())
+                                    .map_err(CallError::message),
+                            );
+                            if transport_error {
+                                return SessionExit::Reconnect(
+                                    "Lost connection to VTube Studio".to_string(),
+                                );
+                            }
+                            continue;
+                        }
+                    };
+                    if let Err(e) = socket.send(WsMessage::Text(request.to_string().into())).await {
+                        return SessionExit::Reconnect(format!("Lost connection to VTube Studio: {}", e));
+                    }
+                }
+                message = socket.next() => match message {
+                    Some(Ok(WsMessage::Text(text))) => {
+                        // Replies to fire-and-forget requests; only errors matter.
+                        if let Some(error) = VtsResponse::parse(text.as_str()).and_then(|r| r.error()) {
+                            tracing::warn!(target: "vts", "VTube Studio error: {}", error);
+                        }
+                    }
+                    Some(Ok(WsMessage::Close(_))) | None => {
+                        return SessionExit::Reconnect("VTube Studio closed the connection".to_string());
+                    }
+                    Some(Err(e)) => {
+                        return SessionExit::Reconnect(format!("Lost connection to VTube Studio: {}", e));
+                    }
+                    Some(Ok(_)) => {}
+                },
+            }
+        }
+    }
+
+    async fn authenticate(
+        &self,
+        socket: &mut VtsSocket,
+        request_ids: &mut u64,
+    ) -> Result<(), SessionExit> {
+        let stored = self
+            .config
+            .read()
+            .await
+            .auth_token
+            .clone()
+            .filter(|token| !token.is_empty());
+        let token = match stored {
+            Some(token) => token,
+            None => {
+                tracing::info!(
+                    target: "vts",
+                    "requesting plugin access; allow \"{}\" in VTube Studio",
+                    protocol::PLUGIN_NAME
+                );
+                // No timeout: VTS waits for the user to click allow or deny.
+                let response = call(
+                    socket,
+                    protocol::token_request(&next_request_id(request_ids)),
+                    None,
+                )
+                .await
+                .map_err(CallError::into_exit)?;
+                let token = response.str_field("authenticationToken").ok_or_else(|| {
+                    SessionExit::Fatal("VTube Studio returned no token".to_string())
+                })?;
+                self.persist_token(Some(token.clone())).await;
+                token
+            }
+        };
+
+        let response = call(
+            socket,
+            protocol::auth_request(&next_request_id(request_ids), &token),
+            Some(REQUEST_TIMEOUT),
+        )
+        .await
+        .map_err(CallError::into_exit)?;
+        if !response.bool_field("authenticated") {
+            // Revoked in VTS; the next attempt asks the user again.
+            self.persist_token(None).await;
+            return Err(SessionExit::Reconnect(format!(
+                "VTube Studio rejected the stored token: {}",
+                response.str_field("reason").unwrap_or_default()
+            )));
+        }
+        Ok(())
+    }
+
+    async fn persist_token(&self, token: Option<String>) {
+        let config = {
+            let mut config = self.config.write().await;
+            config.auth_token = token;
+            config.clone()
+        };
+        if let Err(e) = save_config(&config_path(), &config) {
+            tracing::error!(target: "vts", "failed to save VTube Studio token: {}", e);
+        }
+    }
+}
+
+fn next_request_id(counter: &mut u64) -> String {
+    *counter += 1;
+    format!("kokoro-{}", counter)
+}
+
+/// Send a request and wait for the reply with the same request ID.
+async fn call(
+    socket: &mut VtsSocket,
+    request: serde_json::Value,
+    timeout: Option<Duration>,
+) -> Result<VtsResponse, CallError> {
+    let request_id = request["requestID"]
+        .as_str()
+        .unwrap_or_default()
+        .to_string();
+    socket
+        .send(WsMessage::Text(request.to_string().into()))
+        .await
+        .map_err(|e| CallError::Transport(format!("Lost connection to VTube Studio: {}", e)))?;
+
+    let reply = async {
+        loop {
+            match socket.next().await {
+                Some(Ok(WsMessage::Text(text))) => {
+                    let Some(response) = VtsResponse::parse(text.as_str()) else {
+                        continue;
+                    };
+                    if response.request_id != request_id {
+                        continue;
+                    }
+                    return match response.error() {
+                        Some(error) => Err(CallError::Api(error)),
+                        None => Ok(response),
+                    };
+                }
+                Some(Ok(WsMessage::Close(_))) | None => {
+                    return Err(CallError::Transport(
+                        "VTube Studio closed the connection".to_string(),
+                    ))
+                }
+                Some(Err(e)) => {
+                    return Err(CallError::Transport(format!(
+                        "Lost connection to VTube Studio: {}",
+                        e
+                    )))
+                }
+                Some(Ok(_)) => {}
+            }
+        }
+    };
+    match timeout {
+        Some(timeout) => tokio::time::timeout(timeout, reply)
+            .await
+            .map_err(|_| CallError::Transport("VTube Studio did not answer in time".to_string()))?,
+        None => reply.await,
+    }
+}
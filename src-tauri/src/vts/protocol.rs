@@ -0,0 +1,200 @@
+//! VTube Studio public API message builders and parsers.
+//!
+//! Only the handful of requests Kokoro needs: plugin authentication, the
+//! current model, hotkeys and parameter injection.
+
+use serde::Serialize;
+use serde_json::{json, Value};
+
+const API_NAME: &str = "VTubeStudioPublicAPI";
+const API_VERSION: &str = "1.0";
+pub const PLUGIN_NAME: &str = "Kokoro Engine";
+pub const PLUGIN_DEVELOPER: &str = "chyinan";
+
+fn request(message_type: &str, request_id: &str, data: Value) -> Value {
+    json!({
+        "apiName": API_NAME,
+        "apiVersion": API_VERSION,
+        "requestID": request_id,
+        "messageType": message_type,
+        "data": data,
+    })
+}
+
+/// Asks the user (inside VTS) to allow the plugin; answers with a token.
+pub fn token_request(request_id: &str) -> Value {
+    request(
+        "AuthenticationTokenRequest",
+        request_id,
+        json!({ "pluginName": PLUGIN_NAME, "pluginDeveloper": PLUGIN_DEVELOPER }),
+    )
+}
+
+pub fn auth_request(request_id: &str, token: &str) -> Value {
+    request(
+        "AuthenticationRequest",
+        request_id,
+        json!({
+            "pluginName": PLUGIN_NAME,
+            "pluginDeveloper": PLUGIN_DEVELOPER,
+            "authenticationToken": token,
+        }),
+    )
+}
+
+pub fn current_model_request(request_id: &str) -> Value {
+    request("CurrentModelRequest", request_id, json!({}))
+}
+
+pub fn hotkeys_request(request_id: &str) -> Value {
+    request("HotkeysInCurrentModelRequest", request_id, json!({}))
+}
+
+/// `hotkey` may be either the hotkey's ID or its name.
+pub fn trigger_hotkey_request(request_id: &str, hotkey: &str) -> Value {
+    request(
+        "HotkeyTriggerRequest",
+        request_id,
+        json!({ "hotkeyID": hotkey }),
+    )
+}
+
+/// Sets a tracking input parameter. VTS hands control back to face tracking
+/// when no value arrives for about a second.
+pub fn inject_parameter_request(request_id: &str, parameter: &str, value: f32) -> Value {
+    request(
+        "InjectParameterDataRequest",
+        request_id,
+        json!({
+            "faceFound": false,
+            "mode": "set",
+            "parameterValues": [{ "id": parameter, "value": value }],
+        }),
+    )
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct VtsResponse {
+    pub request_id: String,
+    pub message_type: String,
+    pub data: Value,
+}
+
+impl VtsResponse {
+    pub fn parse(text: &str) -> Option<Self> {
+        let value: Value = serde_json::from_str(text).ok()?;
+        Some(Self {
+            request_id: value.get("requestID")?.as_str()?.to_string(),
+            message_type: value.get("messageType")?.as_str()?.to_string(),
+            data: value.get("data").cloned().unwrap_or(Value::Null),
+        })
+    }
+
+    /// The error message when VTS answered with `APIError`.
+    pub fn error(&self) -> Option<String> {
+        (self.message_type == "APIError").then(|| {
+            let id = self
+                .data
+                .get("errorID")
+                .and_then(Value::as_i64)
+                .unwrap_or(0);
+            let message = self
+                .data
+                .get("message")
+                .and_then(Value::as_str)
+                .unwrap_or("unknown error");
+            format!("{} (error {})", message, id)
+        })
+    }
+
+    pub fn str_field(&self, key: &str) -> Option<String> {
+        self.data
+            .get(key)
+            .and_then(Value::as_str)
+            .map(str::to_string)
+    }
+
+    pub fn bool_field(&self, key: &str) -> bool {
+        self.data.get(key).and_then(Value::as_bool).unwrap_or(false)
+    }
+
+    pub fn hotkeys(&self) -> Vec<VtsHotkey> {
+        self.data
+            .get("availableHotkeys")
+            .and_then(Value::as_array)
+            .map(|hotkeys| {
+                hotkeys
+                    .iter()
+                    .filter_map(|hotkey| {
+                        Some(VtsHotkey {
+                            id: hotkey.get("hotkeyID")?.as_str()?.to_string(),
+                            name: hotkey
+                                .get("name")
+                                .and_then(Value::as_str)
+                                .unwrap_or_default()
+                                .to_string(),
+                            kind: hotkey
+                                .get("type")
+                                .and_then(Value::as_str)
+                                .unwrap_or_default()
+                                .to_string(),
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct VtsHotkey {
+    pub id: String,
+    pub name: String,
+    /// VTS action type, e.g. "ToggleExpression" or "TriggerAnimation".
+    pub kind: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn requests_carry_api_envelope() {
+        let req = inject_parameter_request("7", "MouthOpen", 0.5);
+        assert_eq!(req["apiName"], "VTubeStudioPublicAPI");
+        assert_eq!(req["requestID"], "7");
+        assert_eq!(req["messageType"], "InjectParameterDataRequest");
+        assert_eq!(req["data"]["parameterValues"][0]["id"], "MouthOpen");
+        assert_eq!(req["data"]["parameterValues"][0]["value"], 0.5);
+
+        let auth = auth_request("1", "tok");
+        assert_eq!(auth["data"]["authenticationToken"], "tok");
+        assert_eq!(auth["data"]["pluginName"], PLUGIN_NAME);
+    }
+
+    #[test]
+    fn parses_hotkeys_and_errors() {
+        let hotkeys = VtsResponse::parse(
+            r#"{"apiName":"VTubeStudioPublicAPI","requestID":"3","messageType":"HotkeysInCurrentModelResponse",
+                "data":{"availableHotkeys":[{"name":"Smile","type":"ToggleExpression","hotkeyID":"abc"},{"name":"broken"}]}}"#,
+        )
+        .unwrap();
+        assert_eq!(hotkeys.request_id, "3");
+        assert_eq!(
+            hotkeys.hotkeys(),
+            vec![VtsHotkey {
+                id: "abc".to_string(),
+                name: "Smile".to_string(),
+                kind: "ToggleExpression".to_string(),
+            }]
+        );
+        assert_eq!(hotkeys.error(), None);
+
+        let error = VtsResponse::parse(
+            r#"{"requestID":"4","messageType":"APIError","data":{"errorID":50,"message":"User denied"}}"#,
+        )
+        .unwrap();
+        assert_eq!(error.error(), Some("User denied (error 50)".to_string()));
+        assert!(VtsResponse::parse("not json").is_none());
+    }
+}
@@ -0,0 +1,604 @@
+//! Desktop wallpaper integration — opt-in.
+//!
+//! The companion can put a generated image or a mood palette on the desktop.
+//! Every change is copied into `wallpapers/` and recorded in
+//! `wallpaper_history.json` together with the wallpaper it replaced, so it can
+//! be reverted step by step or rolled back to the user's original.
+
+use crate::error::KokoroError;
+use image::{Rgb, RgbImage};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::{Mutex, RwLock};
+
+pub const WALLPAPER_CHANGED_EVENT: &str = "wallpaper:changed";
+const DEFAULT_PALETTE_SIZE: (u32, u32) = (1920, 1080);
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct WallpaperConfig {
+    /// Whether the companion may change the desktop wallpaper at all.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Minimum gap between changes the companion makes on its own.
+    #[serde(default = "default_min_interval_minutes")]
+    pub min_interval_minutes: u32,
+    /// Changes kept for revert; older ones are folded away.
+    #[serde(default = "default_history_limit")]
+    pub history_limit: usize,
+}
+
+fn default_min_interval_minutes() -> u32 {
+    180
+}
+
+fn default_history_limit() -> usize {
+    20
+}
+
+impl Default for WallpaperConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_interval_minutes: default_min_interval_minutes(),
+            history_limit: default_history_limit(),
+        }
+    }
+}
+
+fn data_dir() -> PathBuf {
+    dirs_next::data_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("com.chyin.kokoro")
+}
+
+pub fn config_path() -> PathBuf {
+    data_dir().join("wallpaper_config.json")
+}
+
+fn history_path() -> PathBuf {
+    data_dir().join("wallpaper_history.json")
+}
+
+pub fn load_config(path: &Path) -> WallpaperConfig {
+    crate::config::load_json_config(path, "WALLPAPER")
+}
+
+pub fn save_config(path: &Path, config: &WallpaperConfig) -> Result<(), KokoroError> {
+    crate::config::save_json_config(path, config, "WALLPAPER")
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WallpaperSource {
+    Image,
+    Palette,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WallpaperEntry {
+    pub id: String,
+    /// Our copy of the wallpaper, inside `wallpapers/`.
+    pub path: String,
+    pub source: WallpaperSource,
+    #[serde(default)]
+    pub mood: Option<String>,
+    pub applied_at: i64,
+    /// Wallpaper that was on the desktop before this one.
+    #[serde(default)]
+    pub previous: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct WallpaperHistory {
+    /// The user's own wallpaper from before the first change.
+    #[serde(default)]
+    pub original: Option<String>,
+    /// Oldest first.
+    #[serde(default)]
+    pub entries: Vec<WallpaperEntry>,
+}
+
+impl WallpaperHistory {
+    /// Record a change; returns files of entries dropped past `limit`.
+    fn push(&mut self, entry: WallpaperEntry, limit: usize) -> Vec<String> {
+        if self.entries.is_empty() && self.original.is_none() {
+            self.original = entry.previous.clone();
+        }
+        self.entries.push(entry);
+
+        let mut dropped = Vec::new();
+        while self.entries.len() > limit.max(1) {
+            let oldest = self.entries.remove(0);
+            // The next entry now reverts straight to what the oldest replaced.
+            if let Some(next) = self.entries.first_mut() {
+                if next.previous.as_deref() == Some(oldest.path.as_str()) {
+                    next.previous = oldest.previous.clone();
+                }
+            }
+            dropped.push(oldest.path);
+        }
+        dropped
+    }
+
+    fn last_applied_at(&self) -> Option<i64> {
+        self.entries.last().map(|entry| entry.applied_at)
+    }
+}
+
+/// Gradient stops per emotion, top to bottom.
+fn palette_for(emotion: &str) -> [[u8; 3]; 3] {
+    match emotion {
+        "joy" => [[255, 214, 102], [255, 159, 128], [255, 111, 145]],
+        "love" => [[255, 190, 210], [236, 128, 170], [160, 90, 170]],
+        "sadness" => [[120, 144, 180], [70, 90, 140], [34, 40, 70]],
+        "anger" => [[120, 20, 30], [200, 60, 40], [40, 10, 15]],
+        "surprise" => [[120, 230, 220], [110, 160, 255], [190, 120, 255]],
+        "fear" => [[40, 50, 70], [70, 60, 100], [15, 15, 30]],
+        _ => [[200, 220, 235], [150, 180, 210], [90, 110, 150]],
+    }
+}
+
+fn lerp(a: u8, b: u8, t: f32) -> u8 {
+    (a as f32 + (b as f32 - a as f32) * t).round() as u8
+}
+
+/// Vertical three-stop gradient.
+fn render_palette(width: u32, height: u32, stops: [[u8; 3]; 3]) -> RgbImage {
+    let width = width.max(1);
+    let height = height.max(1);
+    let mut image = RgbImage::new(width, height);
+    let span = (height - 1).max(1) as f32;
+    for y in 0..height {
+        let t = y as f32 / span;
+        let (from, to, local) = if t < 0.5 {
+            (stops[0], stops[1], t * 2.0)
+        } else {
+            (stops[1], stops[2], (t - 0.5) * 2.0)
+        };
+        let color = Rgb([
+            lerp(from[0], to[0], local),
+            lerp(from[1], to[1], local),
+            lerp(from[2], to[2], local),
+        ]);
+        for x in 0..width {
+            image.put_pixel(x, y, color);
+        }
+    }
+    image
+}
+
+/// Managed Tauri state for wallpaper changes and their history.
+#[derive(Clone)]
+pub struct WallpaperService {
+    config: Arc<RwLock<WallpaperConfig>>,
+    /// Also serializes changes so two can't race on the desktop.
+    history: Arc<Mutex<WallpaperHistory>>,
+    dir: PathBuf,
+}
+
+impl WallpaperService {
+    pub fn new(config: WallpaperConfig) -> Self {
+        let history: WallpaperHistory =
+            crate::config::load_json_config(&history_path(), "WALLPAPER");
+        Self {
+            config: Arc::new(RwLock::new(config)),
+            history: Arc::new(Mutex::new(history)),
+            dir: data_dir().join("wallpapers"),
+        }
+    }
+
+    pub async fn get_config(&self) -> WallpaperConfig {
+        self.config.read().await.clone()
+    }
+
+    pub async fn update_config(&self, config: WallpaperConfig) {
+        *self.config.write().await = config;
+    }
+
+    pub async fn history(&self) -> WallpaperHistory {
+        self.history.lock().await.clone()
+    }
+
+    /// Gate for changes the companion initiates itself: opt-in and rate limited.
+    pub async fn check_companion_allowed(&self) -> Result<(), KokoroError> {
+        let config = self.get_config().await;
+        if !config.enabled {
+            return Err(KokoroError::Unauthorized(
+                "Desktop wallpaper changes are disabled in settings".to_string(),
+            ));
+        }
+        if let Some(last) = self.history.lock().await.last_applied_at() {
+            let next_allowed = last + i64::from(config.min_interval_minutes) * 60;
+            if chrono::Utc::now().timestamp() < next_allowed {
+                return Err(KokoroError::Validation(format!(
+                    "The wallpaper was changed recently; wait {} minutes between changes",
+                    config.min_interval_minutes
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Put an existing image (e.g. a generated one) on the desktop.
+    pub async fn apply_image(
+        &self,
+        app: &AppHandle,
+        image_path: &str,
+        mood: Option<String>,
+    ) -> Result<WallpaperEntry, KokoroError> {
+        let source = PathBuf::from(image_path);
+        if !source.is_file() {
+            return Err(KokoroError::NotFound(format!(
+                "Image not found: {}",
+                source.display()
+            )));
+        }
+        let ext = source
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or("png")
+            .to_ascii_lowercase();
+        let id = uuid::Uuid::new_v4().to_string();
+        tokio::fs::create_dir_all(&self.dir).await?;
+        let target = self.dir.join(format!("{id}.{ext}"));
+        tokio::fs::copy(&source, &target).await?;
+        self.apply(app, id, target, WallpaperSource::Image, mood)
+            .await
+    }
+
+    /// Render a gradient for `mood` (or the current emotion) at screen size.
+    pub async fn apply_palette(
+        &self,
+        app: &AppHandle,
+        mood: Option<&str>,
+    ) -> Result<WallpaperEntry, KokoroError> {
+        let emotion = match mood.and_then(crate::ai::emotion::emotion_from_label) {
+            Some(emotion) => emotion,
+            None => current_emotion(app).await,
+        };
+        let (width, height) = app
+            .primary_monitor()
+            .ok()
+            .flatten()
+            .map(|monitor| (monitor.size().width, monitor.size().height))
+            .unwrap_or(DEFAULT_PALETTE_SIZE);
+
+        let id = uuid::Uuid::new_v4().to_string();
+        tokio::fs::create_dir_all(&self.dir).await?;
+        let target = self.dir.join(format!("{id}.png"));
+        let render_target = target.clone();
+        tokio::task::spawn_blocking(move || {
+            render_palette(width, height, palette_for(emotion)).save(&render_target)
+        })
+        .await
+        .map_err(|e| KokoroError::Internal(e.to_string()))?
+        .map_err(|e| KokoroError::Io(format!("Failed to render palette: {e}")))?;
+
+        self.apply(
+            app,
+            id,
+            target,
+            WallpaperSource::Palette,
+            Some(emotion.to_string()),
+        )
+        .await
+    }
+
+    async fn apply(
+        &self,
+        app: &AppHandle,
+        id: String,
+        path: PathBuf,
+        source: WallpaperSource,
+        mood: Option<String>,
+    ) -> Result<WallpaperEntry, KokoroError> {
+        let mut history = self.history.lock().await;
+        let set_path = path.clone();
+        let previous = tokio::task::spawn_blocking(move || {
+            let previous = platform::current().unwrap_or_else(|e| {
+                tracing::warn!(target: "wallpaper", "Could not read current wallpaper: {}", e);
+                None
+            });
+            platform::set(&set_path).map(|_| previous)
+        })
+        .await
+        .map_err(|e| KokoroError::Internal(e.to_string()))?
+        .map_err(|e| {
+            let _ = std::fs::remove_file(&path);
+            KokoroError::ExternalService(format!("Failed to set wallpaper: {e}"))
+        })?;
+
+        let entry = WallpaperEntry {
+            id,
+            path: path.to_string_lossy().into_owned(),
+            source,
+            mood,
+            applied_at: chrono::Utc::now().timestamp(),
+            previous,
+        };
+        let limit = self.config.read().await.history_limit;
+        for dropped in history.push(entry.clone(), limit) {
+            let _ = tokio::fs::remove_file(dropped).await;
+        }
+        self.persist(&history)?;
+        drop(history);
+
+        tracing::info!(target: "wallpaper", "Wallpaper set ({:?})", entry.source);
+        let _ = app.emit(WALLPAPER_CHANGED_EVENT, &entry);
+        Ok(entry)
+    }
+
+    /// Undo the most recent change. Returns the wallpaper now in place.
+    pub async fn revert(&self, app: &AppHandle) -> Result<Option<String>, KokoroError> {
+        let mut history = self.history.lock().await;
+        let Some(last) = history.entries.last().cloned() else {
+            return Err(KokoroError::NotFound(
+                "No wallpaper change to revert".to_string(),
+            ));
+        };
+        if let Some(previous) = last.previous.clone() {
+            set_blocking(previous).await?;
+        }
+        history.entries.pop();
+        let _ = tokio::fs::remove_file(&last.path).await;
+        if history.entries.is_empty() {
+            history.original = None;
+        }
+        self.persist(&history)?;
+        drop(history);
+
+        let _ = app.emit(WALLPAPER_CHANGED_EVENT, ());
+        Ok(last.previous)
+    }
+
+    /// Put the user's original wallpaper back and forget all changes.
+    pub async fn restore_original(&self, app: &AppHandle) -> Result<Option<String>, KokoroError> {
+        let mut history = self.history.lock().await;
+        let Some(first) = history.entries.first() else {
+            return Ok(None);
+        };
+        let original = history.original.clone().or_else(|| first.previous.clone());
+        if let Some(original) = original.clone() {
+            set_blocking(original).await?;
+        }
+        for entry in history.entries.drain(..) {
+            let _ = tokio::fs::remove_file(&entry.path).await;
+        }
+        history.original = None;
+        self.persist(&history)?;
+        drop(history);
+
+        let _ = app.emit(WALLPAPER_CHANGED_EVENT, ());
+        Ok(original)
+    }
+
+    fn persist(&self, history: &WallpaperHistory) -> Result<(), KokoroError> {
+        crate::config::save_json_config(&history_path(), history, "WALLPAPER")
+    }
+}
+
+async fn set_blocking(path: String) -> Result<(), KokoroError> {
+    tokio::task::spawn_blocking(move || platform::set(Path::new(&path)))
+        .await
+        .map_err(|e| KokoroError::Internal(e.to_string()))?
+        .map_err(|e| KokoroError::ExternalService(format!("Failed to set wallpaper: {e}")))
+}
+
+async fn current_emotion(app: &AppHandle) -> &'static str {
+    match app.try_state::<crate::ai::context::AIOrchestrator>() {
+        Some(orchestrator) => orchestrator.emotion.lock().await.state().emotion,
+        None => "neutral",
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use std::os::windows::process::CommandExt;
+    use std::path::Path;
+    use std::process::Command;
+
+    // CREATE_NO_WINDOW: avoid flashing a console window
+    const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+    pub fn current() -> Result<Option<String>, String> {
+        let output = Command::new("reg")
+            .args(["query", r"HKCU\Control Panel\Desktop", "/v", "WallPaper"])
+            .creation_flags(CREATE_NO_WINDOW)
+            .output()
+            .map_err(|e| e.to_string())?;
+        // `    WallPaper    REG_SZ    C:\path\to\image.jpg`
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .find_map(|line| line.split_once("REG_SZ"))
+            .map(|(_, value)| value.trim().to_string())
+            .filter(|value| !value.is_empty()))
+    }
+
+    pub fn set(path: &Path) -> Result<(), String> {
+        // SPI_SETDESKWALLPAPER (20) with SPIF_UPDATEINIFILE | SPIF_SENDCHANGE (3).
+        let script = "Add-Type -TypeDefinition 'using System.Runtime.InteropServices; \
+            public class KokoroWallpaper { [DllImport(\"user32.dll\", CharSet = CharSet.Unicode)] \
+            public static extern int SystemParametersInfo(int a, int b, string c, int d); }'; \
+            if ([KokoroWallpaper]::SystemParametersInfo(20, 0, $env:KOKORO_WALLPAPER, 3) -eq 0) { exit 1 }";
+        let status = Command::new("powershell")
+            .args(["-NoProfile", "-Command", script])
+            .env("KOKORO_WALLPAPER", path)
+            .creation_flags(CREATE_NO_WINDOW)
+            .status()
+            .map_err(|e| e.to_string())?;
+        if status.success() {
+            Ok(())
+        } else {
+            Err("SystemParametersInfo rejected the image".to_string())
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use std::path::Path;
+    use std::process::Command;
+
+    pub fn current() -> Result<Option<String>, String> {
+        let output = Command::new("osascript")
+            .args([
+                "-e",
+                "tell application \"System Events\" to get picture of desktop 1",
+            ])
+            .output()
+            .map_err(|e| e.to_string())?;
+        let path = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        Ok(Some(path).filter(|p| output.status.success() && !p.is_empty()))
+    }
+
+    pub fn set(path: &Path) -> Result<(), String> {
+        let output = Command::new("osascript")
+            .args([
+                "-e",
+                "on run argv",
+                "-e",
+                "tell application \"System Events\" to tell every desktop to set picture to (item 1 of argv)",
+                "-e",
+                "end run",
+            ])
+            .arg(path)
+            .output()
+            .map_err(|e| e.to_string())?;
+        if output.status.success() {
+            Ok(())
+        } else {
+            Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use std::path::Path;
+    use std::process::Command;
+
+    /// gsettings schema and key per desktop; the bool marks URI-valued keys.
+    fn schema() -> Result<(&'static str, &'static str, bool), String> {
+        let desktop = std::env::var("XDG_CURRENT_DESKTOP")
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        if desktop.contains("cinnamon") {
+            Ok(("org.cinnamon.desktop.background", "picture-uri", true))
+        } else if desktop.contains("mate") {
+            Ok(("org.mate.background", "picture-filename", false))
+        } else if ["gnome", "unity", "budgie", "pantheon"]
+            .iter()
+            .any(|name| desktop.contains(name))
+        {
+            Ok(("org.gnome.desktop.background", "picture-uri", true))
+        } else {
+            Err(format!(
+                "unsupported desktop environment '{}'",
+                if desktop.is_empty() {
+                    "unknown"
+                } else {
+                    &desktop
+                }
+            ))
+        }
+    }
+
+    fn gsettings(args: &[&str]) -> Result<String, String> {
+        let output = Command::new("gsettings")
+            .args(args)
+            .output()
+            .map_err(|e| e.to_string())?;
+        if output.status.success() {
+            Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+        } else {
+            Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+        }
+    }
+
+    pub fn current() -> Result<Option<String>, String> {
+        let (schema, key, _) = schema()?;
+        let value = gsettings(&["get", schema, key])?;
+        let value = value.trim_matches('\'');
+        let path = value.strip_prefix("file://").unwrap_or(value);
+        Ok(Some(path.to_string()).filter(|p| !p.is_empty()))
+    }
+
+    pub fn set(path: &Path) -> Result<(), String> {
+        let (schema, key, uri) = schema()?;
+        let path = path.to_string_lossy();
+        if !uri {
+            return gsettings(&["set", schema, key, path.as_ref()]).map(|_| ());
+        }
+        let uri = format!("file://{path}");
+        gsettings(&["set", schema, key, &uri])?;
+        if schema == "org.gnome.desktop.background" {
+            // GNOME 42+ keeps a separate wallpaper for dark mode; older ones lack the key.
+            let _ = gsettings(&["set", schema, "picture-uri-dark", &uri]);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+mod platform {
+    use std::path::Path;
+
+    pub fn current() -> Result<Option<String>, String> {
+        Ok(None)
+    }
+
+    pub fn set(_path: &Path) -> Result<(), String> {
+        Err("wallpaper changes are not supported on this platform".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: &str, previous: Option<&str>) -> WallpaperEntry {
+        WallpaperEntry {
+            id: id.to_string(),
+            path: format!("/w/{id}.png"),
+            source: WallpaperSource::Palette,
+            mood: None,
+            applied_at: 0,
+            previous: previous.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn first_change_remembers_the_original() {
+        let mut history = WallpaperHistory::default();
+        history.push(entry("a", Some("/home/me/beach.jpg")), 5);
+        history.push(entry("b", Some("/w/a.png")), 5);
+        assert_eq!(history.original.as_deref(), Some("/home/me/beach.jpg"));
+        assert_eq!(history.entries.len(), 2);
+    }
+
+    #[test]
+    fn trimming_folds_the_oldest_change_into_the_next() {
+        let mut history = WallpaperHistory::default();
+        history.push(entry("a", Some("/home/me/beach.jpg")), 2);
+        history.push(entry("b", Some("/w/a.png")), 2);
+        let dropped = history.push(entry("c", Some("/w/b.png")), 2);
+        assert_eq!(dropped, vec!["/w/a.png".to_string()]);
+        assert_eq!(history.entries[0].id, "b");
+        assert_eq!(
+            history.entries[0].previous.as_deref(),
+            Some("/home/me/beach.jpg")
+        );
+    }
+
+    #[test]
+    fn palette_is_a_top_to_bottom_gradient() {
+        let stops = palette_for("joy");
+        let image = render_palette(4, 9, stops);
+        assert_eq!(image.get_pixel(0, 0).0, stops[0]);
+        assert_eq!(image.get_pixel(3, 4).0, stops[1]);
+        assert_eq!(image.get_pixel(2, 8).0, stops[2]);
+        assert_eq!(palette_for("unknown"), palette_for("neutral"));
+    }
+}
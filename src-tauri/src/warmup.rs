@@ -0,0 +1,353 @@
+//! Provider warm-up — keeps local backends loaded between messages.
+//!
+//! Ollama, the SD WebUI and GPT-SoVITS unload or cool down after idling, which
+//! turns the next reply into a multi-second cold start. Each provider can opt
+//! in with a [`WarmupPolicy`]: pre-warm when the user starts typing
+//! (`notify_typing`) and/or ping on an interval to stay resident.
+
+use crate::error::KokoroError;
+use crate::imagegen::ImageGenService;
+use crate::llm::service::LlmService;
+use crate::tts::TtsService;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+use tokio::sync::RwLock;
+
+/// Typing keeps firing while the user composes; warm at most this often.
+const TYPING_COOLDOWN: Duration = Duration::from_secs(60);
+/// Shortest keep-alive interval accepted in config.
+pub const MIN_KEEP_ALIVE_SECS: u64 = 30;
+const KEEP_ALIVE_TICK: Duration = Duration::from_secs(15);
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum WarmupTarget {
+    Llm,
+    Tts,
+    Imagegen,
+}
+
+impl WarmupTarget {
+    const ALL: [WarmupTarget; 3] = [Self::Llm, Self::Tts, Self::Imagegen];
+
+    fn label(&self) -> &'static str {
+        match self {
+            Self::Llm => "LLM",
+            Self::Tts => "TTS",
+            Self::Imagegen => "image generation",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct WarmupPolicy {
+    /// Load the model as soon as the user starts typing.
+    #[serde(default = "default_true")]
+    pub warm_on_typing: bool,
+    /// Ping this often to keep the model loaded; `None` disables the keep-alive.
+    #[serde(default)]
+    pub keep_alive_secs: Option<u64>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Policies keyed by provider id. Providers without an entry are never warmed.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct WarmupConfig {
+    #[serde(default)]
+    pub llm: HashMap<String, WarmupPolicy>,
+    #[serde(default)]
+    pub tts: HashMap<String, WarmupPolicy>,
+    #[serde(default)]
+    pub imagegen: HashMap<String, WarmupPolicy>,
+}
+
+impl WarmupConfig {
+    pub fn policy(&self, target: WarmupTarget, provider_id: &str) -> Option<&WarmupPolicy> {
+        match target {
+            WarmupTarget::Llm => &self.llm,
+            WarmupTarget::Tts => &self.tts,
+            WarmupTarget::Imagegen => &self.imagegen,
+        }
+        .get(provider_id)
+    }
+
+    pub fn validate(&self) -> Result<(), KokoroError> {
+        for target in WarmupTarget::ALL {
+            let policies = match target {
+                WarmupTarget::Llm => &self.llm,
+                WarmupTarget::Tts => &self.tts,
+                WarmupTarget::Imagegen => &self.imagegen,
+            };
+            for (id, policy) in policies {
+                if policy
+                    .keep_alive_secs
+                    .is_some_and(|secs| secs < MIN_KEEP_ALIVE_SECS)
+                {
+                    return Err(KokoroError::Validation(format!(
+                        "{} provider '{}': keep-alive interval must be at least {} seconds",
+                        target.label(),
+                        id,
+                        MIN_KEEP_ALIVE_SECS
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+pub fn config_path() -> PathBuf {
+    dirs_next::data_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("com.chyin.kokoro")
+        .join("warmup_config.json")
+}
+
+pub fn load_config(path: &Path) -> WarmupConfig {
+    crate::config::load_json_config(path, "WARMUP")
+}
+
+pub fn save_config(path: &Path, config: &WarmupConfig) -> Result<(), KokoroError> {
+    crate::config::save_json_config(path, config, "WARMUP")
+}
+
+/// When each provider was last warmed, so typing and the keep-alive loop don't
+/// pile up requests on a backend that is still loading.
+#[derive(Debug, Default)]
+struct WarmupTracker {
+    last_warmed: HashMap<(WarmupTarget, String), Instant>,
+}
+
+impl WarmupTracker {
+    /// Record a warm-up at `now` unless one started less than `gap` ago.
+    fn claim(
+        &mut self,
+        target: WarmupTarget,
+        provider_id: &str,
+        gap: Duration,
+        now: Instant,
+    ) -> bool {
+        let key = (target, provider_id.to_string());
+        if self
+            .last_warmed
+            .get(&key)
+            .is_some_and(|at| now.saturating_duration_since(*at) < gap)
+        {
+            return false;
+        }
+        self.last_warmed.insert(key, now);
+        true
+    }
+}
+
+/// Managed Tauri state holding the warm-up config and bookkeeping.
+#[derive(Clone)]
+pub struct WarmupService {
+    config: Arc<RwLock<WarmupConfig>>,
+    tracker: Arc<Mutex<WarmupTracker>>,
+}
+
+impl WarmupService {
+    pub fn new(config: WarmupConfig) -> Self {
+        Self {
+            config: Arc::new(RwLock::new(config)),
+            tracker: Arc::new(Mutex::new(WarmupTracker::default())),
+        }
+    }
+
+    pub async fn get_config(&self) -> WarmupConfig {
+        self.config.read().await.clone()
+    }
+
+    /// Update the in-memory config (caller is responsible for persisting to disk).
+    pub async fn update_config(&self, config: WarmupConfig) {
+        *self.config.write().await = config;
+    }
+
+    /// Pre-warm every routed provider whose policy asks for it.
+    pub async fn notify_typing(&self, app: &AppHandle) {
+        for target in WarmupTarget::ALL {
+            let Some(provider_id) = routed_provider_id(app, target).await else {
+                continue;
+            };
+            let wants = self
+                .config
+                .read()
+                .await
+                .policy(target, &provider_id)
+                .is_some_and(|p| p.warm_on_typing);
+            if wants && self.claim(target, &provider_id, TYPING_COOLDOWN) {
+                spawn_warm_up(app, target, provider_id, "typing");
+            }
+        }
+    }
+
+    pub fn spawn_keep_alive_loop(&self, app: AppHandle) {
+        let service = self.clone();
+        tauri::async_runtime::spawn(async move {
+            let mut interval = tokio::time::interval(KEEP_ALIVE_TICK);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                interval.tick().await;
+                for target in WarmupTarget::ALL {
+                    let Some(provider_id) = routed_provider_id(&app, target).await else {
+                        continue;
+                    };
+                    let every = service
+                        .config
+                        .read()
+                        .await
+                        .policy(target, &provider_id)
+                        .and_then(|p| p.keep_alive_secs);
+                    let Some(every) = every else {
+                        continue;
+                    };
+                    if service.claim(target, &provider_id, Duration::from_secs(every)) {
+                        spawn_warm_up(&app, target, provider_id, "keep-alive");
+                    }
+                }
+            }
+        });
+    }
+
+    /// Re-warm every routed provider that has a policy, ignoring cooldowns.
+    /// Local backends often unload their models while the machine sleeps.
+    pub async fn warm_after_resume(&self, app: &AppHandle) {
+        for target in WarmupTarget::ALL {
+            let Some(provider_id) = routed_provider_id(app, target).await else {
+                continue;
+            };
+            let wants = self
+                .config
+                .read()
+                .await
+                .policy(target, &provider_id)
+                .is_some();
+            if wants && self.claim(target, &provider_id, Duration::ZERO) {
+                spawn_warm_up(app, target, provider_id, "resume");
+            }
+        }
+    }
+
+    fn claim(&self, target: WarmupTarget, provider_id: &str, gap: Duration) -> bool {
+        self.tracker
+            .lock()
+            .map(|mut tracker| tracker.claim(target, provider_id, gap, Instant::now()))
+            .unwrap_or(false)
+    }
+}
+
+/// Provider a request would go to right now.
+async fn routed_provider_id(app: &AppHandle, target: WarmupTarget) -> Option<String> {
+    match target {
+        WarmupTarget::Llm => Some(app.try_state::<LlmService>()?.routed_provider_id().await),
+        WarmupTarget::Tts => app.try_state::<TtsService>()?.routed_provider_id().await,
+        WarmupTarget::Imagegen => {
+            app.try_state::<ImageGenService>()?
+                .default_provider_id()
+                .await
+        }
+    }
+}
+
+fn spawn_warm_up(app: &AppHandle, target: WarmupTarget, provider_id: String, reason: &'static str) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let started = Instant::now();
+        let result = match target {
+            WarmupTarget::Llm => match app.try_state::<LlmService>() {
+                Some(llm) => match llm.try_provider().await {
+                    Ok(provider) => provider.warm_up().await,
+                    Err(e) => Err(e.to_string()),
+                },
+                None => return,
+            },
+            WarmupTarget::Tts => match app.try_state::<TtsService>() {
+                Some(tts) => tts.warm_up(&provider_id).await.map_err(|e| e.to_string()),
+                None => return,
+            },
+            WarmupTarget::Imagegen => match app.try_state::<ImageGenService>() {
+                Some(imagegen) => imagegen
+                    .warm_up(&provider_id)
+                    .await
+                    .map_err(|e| e.to_string()),
+                None => return,
+            },
+        };
+        match result {
+            Ok(()) => tracing::debug!(
+                target: "warmup",
+                "Warmed {} provider '{}' ({}) in {} ms",
+                target.label(),
+                provider_id,
+                reason,
+                started.elapsed().as_millis()
+            ),
+            Err(e) => tracing::warn!(
+                target: "warmup",
+                "Failed to warm {} provider '{}' ({}): {}",
+                target.label(),
+                provider_id,
+                reason,
+                e
+            ),
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_policies_and_rejects_short_keep_alive() {
+        let mut config: WarmupConfig = serde_json::from_str(
+            r#"{"llm":{"ollama":{"keep_alive_secs":240}},"tts":{"gpt-sovits":{"warm_on_typing":false}}}"#,
+        )
+        .unwrap();
+        let ollama = config.policy(WarmupTarget::Llm, "ollama").unwrap();
+        assert!(ollama.warm_on_typing);
+        assert_eq!(ollama.keep_alive_secs, Some(240));
+        assert!(
+            !config
+                .policy(WarmupTarget::Tts, "gpt-sovits")
+                .unwrap()
+                .warm_on_typing
+        );
+        assert!(config.policy(WarmupTarget::Tts, "ollama").is_none());
+        assert!(config.validate().is_ok());
+
+        config.imagegen.insert(
+            "sd".to_string(),
+            WarmupPolicy {
+                warm_on_typing: true,
+                keep_alive_secs: Some(MIN_KEEP_ALIVE_SECS - 1),
+            },
+        );
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn claims_are_spaced_per_provider() {
+        let mut tracker = WarmupTracker::default();
+        let gap = Duration::from_secs(60);
+        let start = Instant::now();
+        assert!(tracker.claim(WarmupTarget::Llm, "ollama", gap, start));
+        assert!(!tracker.claim(
+            WarmupTarget::Llm,
+            "ollama",
+            gap,
+            start + Duration::from_secs(30)
+        ));
+        // Same id under another target is tracked separately.
+        assert!(tracker.claim(WarmupTarget::Tts, "ollama", gap, start));
+        assert!(tracker.claim(WarmupTarget::Llm, "ollama", gap, start + gap));
+    }
+}
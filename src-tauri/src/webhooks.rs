@@ -0,0 +1,824 @@
+//! Outbound webhooks — engine events pushed to external automation.
+//!
+//! Each webhook subscribes to one trigger (a new memory about a topic, mood
+//! dropping below a threshold, a reminder coming due) and posts a JSON body
+//! rendered from its payload template. Deliveries go through the
+//! `webhook_outbox` table and are retried with exponential backoff, so a
+//! receiver that is down for a while still gets the event later. Webhooks
+//! live in `webhooks.json`.
+
+use crate::ai::context::AIOrchestrator;
+use crate::error::KokoroError;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sqlx::{Row, SqlitePool};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tokio::sync::{Mutex, RwLock};
+
+const DELIVERY_TICK: Duration = Duration::from_secs(15);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+const FIRST_RETRY_SECS: i64 = 30;
+const MAX_RETRY_SECS: i64 = 3600;
+/// Delivered and failed rows are kept this long for the delivery log.
+const OUTBOX_RETENTION_SECS: i64 = 7 * 24 * 3600;
+const MEMORY_POLL_BATCH: i64 = 50;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct WebhookConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub webhooks: Vec<Webhook>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Webhook {
+    pub id: String,
+    #[serde(default)]
+    pub label: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    pub url: String,
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    pub trigger: WebhookTrigger,
+    /// JSON body with `{{name}}` placeholders; the default body when blank.
+    #[serde(default)]
+    pub payload_template: String,
+    #[serde(default = "default_max_attempts")]
+    pub max_attempts: u32,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_max_attempts() -> u32 {
+    5
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WebhookTrigger {
+    /// A memory was stored. `topic` is a comma-separated keyword list matched
+    /// against the memory text; any memory when blank.
+    MemoryAdded {
+        #[serde(default)]
+        topic: String,
+    },
+    /// Mood (0–1) crossed below `threshold`.
+    MoodBelow { threshold: f32 },
+    /// A scheduled reminder fired.
+    ReminderDue,
+}
+
+/// Something that happened in the engine.
+#[derive(Debug, Clone, PartialEq)]
+pub enum WebhookEvent {
+    MemoryAdded {
+        character_id: String,
+        memory_id: i64,
+        content: String,
+    },
+    MoodChanged {
+        character_id: String,
+        previous: f32,
+        mood: f32,
+        emotion: String,
+    },
+    ReminderDue {
+        character_id: String,
+        schedule_id: String,
+        label: String,
+    },
+    Test,
+}
+
+impl WebhookEvent {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::MemoryAdded { .. } => "memory_added",
+            Self::MoodChanged { .. } => "mood_below",
+            Self::ReminderDue { .. } => "reminder_due",
+            Self::Test => "test",
+        }
+    }
+
+    /// Template variables besides `event` and `timestamp`.
+    fn variables(&self) -> Map<String, Value> {
+        let value = match self {
+            Self::MemoryAdded {
+                character_id,
+                memory_id,
+                content,
+            } => serde_json::json!({
+                "character_id": character_id,
+                "memory_id": memory_id,
+                "content": content,
+            }),
+            Self::MoodChanged {
+                character_id,
+                previous,
+                mood,
+                emotion,
+            } => serde_json::json!({
+                "character_id": character_id,
+                "previous_mood": round2(*previous),
+                "mood": round2(*mood),
+                "emotion": emotion,
+            }),
+            Self::ReminderDue {
+                character_id,
+                schedule_id,
+                label,
+            } => serde_json::json!({
+                "character_id": character_id,
+                "schedule_id": schedule_id,
+                "label": label,
+            }),
+            Self::Test => serde_json::json!({ "message": "Test event from Kokoro Engine" }),
+        };
+        match value {
+            Value::Object(map) => map,
+            _ => Map::new(),
+        }
+    }
+}
+
+fn round2(value: f32) -> f64 {
+    (value as f64 * 100.0).round() / 100.0
+}
+
+impl WebhookTrigger {
+    pub fn matches(&self, event: &WebhookEvent) -> bool {
+        match (self, event) {
+            (Self::MemoryAdded { topic }, WebhookEvent::MemoryAdded { content, .. }) => {
+                let content = content.to_lowercase();
+                let mut keywords = topic
+                    .split(',')
+                    .map(|k| k.trim().to_lowercase())
+                    .filter(|k| !k.is_empty())
+                    .peekable();
+                keywords.peek().is_none() || keywords.any(|k| content.contains(&k))
+            }
+            (Self::MoodBelow { threshold }, WebhookEvent::MoodChanged { previous, mood, .. }) => {
+                *previous >= *threshold && *mood < *threshold
+            }
+            (Self::ReminderDue, WebhookEvent::ReminderDue { .. }) => true,
+            _ => false,
+        }
+    }
+}
+
+/// Render a payload template. `{{name}}` becomes the variable's value: text
+/// is JSON-escaped without quotes (write `"{{content}}"`), numbers are
+/// inserted as-is, unknown names render empty. The result must be valid JSON.
+pub fn render_payload(
+    template: &str,
+    event: &str,
+    timestamp: i64,
+    variables: &Map<String, Value>,
+) -> Result<String, KokoroError> {
+    if template.trim().is_empty() {
+        return Ok(serde_json::json!({
+            "event": event,
+            "timestamp": timestamp,
+            "data": variables,
+        })
+        .to_string());
+    }
+
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        rendered.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            rendered.push_str(&rest[start..]);
+            rest = "";
+            break;
+        };
+        let name = after[..end].trim();
+        let value = match name {
+            "event" => Value::String(event.to_string()),
+            "timestamp" => Value::from(timestamp),
+            _ => variables.get(name).cloned().unwrap_or(Value::Null),
+        };
+        match value {
+            Value::String(text) => {
+                let quoted = Value::String(text).to_string();
+                rendered.push_str(&quoted[1..quoted.len() - 1]);
+            }
+            Value::Null => {}
+            other => rendered.push_str(&other.to_string()),
+        }
+        rest = &after[end + 2..];
+    }
+    rendered.push_str(rest);
+
+    serde_json::from_str::<Value>(&rendered).map_err(|e| {
+        KokoroError::Validation(format!("Payload template is not valid JSON: {}", e))
+    })?;
+    Ok(rendered)
+}
+
+/// Wait before retry number `attempts` (1-based): 30 s doubling up to an hour.
+pub fn retry_delay(attempts: u32) -> i64 {
+    let exponent = attempts.saturating_sub(1).min(16);
+    (FIRST_RETRY_SECS << exponent).min(MAX_RETRY_SECS)
+}
+
+fn data_dir() -> PathBuf {
+    dirs_next::data_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("com.chyin.kokoro")
+}
+
+pub fn config_path() -> PathBuf {
+    data_dir().join("webhooks.json")
+}
+
+pub fn load_config(path: &Path) -> WebhookConfig {
+    crate::config::load_json_config(path, "WEBHOOKS")
+}
+
+pub fn save_config(path: &Path, config: &WebhookConfig) -> Result<(), KokoroError> {
+    crate::config::save_json_config(path, config, "WEBHOOKS")
+}
+
+pub fn validate(config: &WebhookConfig) -> Result<(), KokoroError> {
+    let mut ids = HashSet::new();
+    for webhook in &config.webhooks {
+        let id = webhook.id.trim();
+        if id.is_empty() {
+            return Err(KokoroError::Validation(
+                "Webhook id cannot be empty".to_string(),
+            ));
+        }
+        if !ids.insert(id) {
+            return Err(KokoroError::Validation(format!(
+                "Duplicate webhook id '{}'",
+                id
+            )));
+        }
+        let url = webhook.url.trim();
+        if !(url.starts_with("http://") || url.starts_with("https://")) {
+            return Err(KokoroError::Validation(format!(
+                "Webhook '{}': URL must start with http:// or https://",
+                id
+            )));
+        }
+        if let WebhookTrigger::MoodBelow { threshold } = webhook.trigger {
+            if !(0.0..=1.0).contains(&threshold) {
+                return Err(KokoroError::Validation(format!(
+                    "Webhook '{}': mood threshold must be between 0 and 1",
+                    id
+                )));
+            }
+        }
+        if !(1..=20).contains(&webhook.max_attempts) {
+            return Err(KokoroError::Validation(format!(
+                "Webhook '{}': attempts must be between 1 and 20",
+                id
+            )));
+        }
+        render_payload(&webhook.payload_template, "test", 0, &Map::new())
+            .map_err(|e| KokoroError::Validation(format!("Webhook '{}': {}", id, e)))?;
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WebhookDelivery {
+    pub id: i64,
+    pub webhook_id: String,
+    pub event: String,
+    /// "pending", "delivered" or "failed".
+    pub status: String,
+    pub attempts: i64,
+    pub next_attempt_at: i64,
+    pub last_error: Option<String>,
+    pub created_at: i64,
+    pub delivered_at: Option<i64>,
+}
+
+pub async fn list_deliveries(
+    db: &SqlitePool,
+    limit: i64,
+) -> Result<Vec<WebhookDelivery>, KokoroError> {
+    let rows = sqlx::query(
+        "SELECT id, webhook_id, event, status, attempts, next_attempt_at, last_error, created_at, delivered_at
+         FROM webhook_outbox ORDER BY created_at DESC, id DESC LIMIT ?",
+    )
+    .bind(limit)
+    .fetch_all(db)
+    .await?;
+    Ok(rows
+        .iter()
+        .map(|row| WebhookDelivery {
+            id: row.get("id"),
+            webhook_id: row.get("webhook_id"),
+            event: row.get("event"),
+            status: row.get("status"),
+            attempts: row.get("attempts"),
+            next_attempt_at: row.get("next_attempt_at"),
+            last_error: row.get("last_error"),
+            created_at: row.get("created_at"),
+            delivered_at: row.get("delivered_at"),
+        })
+        .collect())
+}
+
+async fn enqueue(
+    db: &SqlitePool,
+    webhook_id: &str,
+    event: &str,
+    body: &str,
+    now: i64,
+) -> Result<i64, KokoroError> {
+    let result = sqlx::query(
+        "INSERT INTO webhook_outbox (webhook_id, event, body, status, attempts, next_attempt_at, created_at)
+         VALUES (?, ?, ?, 'pending', 0, ?, ?)",
+    )
+    .bind(webhook_id)
+    .bind(event)
+    .bind(body)
+    .bind(now)
+    .bind(now)
+    .execute(db)
+    .await?;
+    Ok(result.last_insert_rowid())
+}
+
+struct DueDelivery {
+    id: i64,
+    webhook_id: String,
+    body: String,
+    attempts: u32,
+}
+
+async fn due_deliveries(db: &SqlitePool, now: i64) -> Result<Vec<DueDelivery>, KokoroError> {
+    let rows = sqlx::query(
+        "SELECT id, webhook_id, body, attempts FROM webhook_outbox
+         WHERE status = 'pending' AND next_attempt_at <= ? ORDER BY id LIMIT 50",
+    )
+    .bind(now)
+    .fetch_all(db)
+    .await?;
+    Ok(rows
+        .iter()
+        .map(|row| DueDelivery {
+            id: row.get("id"),
+            webhook_id: row.get("webhook_id"),
+            body: row.get("body"),
+            attempts: row.get::<i64, _>("attempts").max(0) as u32,
+        })
+        .collect())
+}
+
+/// Record one attempt: delivered, rescheduled with backoff, or given up.
+async fn record_attempt(
+    db: &SqlitePool,
+    id: i64,
+    attempts: u32,
+    max_attempts: u32,
+    error: Option<&str>,
+    now: i64,
+) -> Result<(), KokoroError> {
+    match error {
+        None => {
+            sqlx::query(
+                "UPDATE webhook_outbox SET status = 'delivered', attempts = ?, last_error = NULL, delivered_at = ? WHERE id = ?",
+            )
+            .bind(attempts as i64)
+            .bind(now)
+            .bind(id)
+            .execute(db)
+            .await?;
+        }
+        Some(error) => {
+            let status = if attempts >= max_attempts {
+                "failed"
+            } else {
+                "pending"
+            };
+            sqlx::query(
+                "UPDATE webhook_outbox SET status = ?, attempts = ?, last_error = ?, next_attempt_at = ? WHERE id = ?",
+            )
+            .bind(status)
+            .bind(attempts as i64)
+            .bind(error)
+            .bind(now + retry_delay(attempts))
+            .bind(id)
+            .execute(db)
+            .await?;
+        }
+    }
+    Ok(())
+}
+
+async fn prune_outbox(db: &SqlitePool, now: i64) -> Result<(), KokoroError> {
+    sqlx::query("DELETE FROM webhook_outbox WHERE status != 'pending' AND created_at < ?")
+        .bind(now - OUTBOX_RETENTION_SECS)
+        .execute(db)
+        .await?;
+    Ok(())
+}
+
+#[derive(Clone)]
+pub struct WebhookService {
+    config: Arc<RwLock<WebhookConfig>>,
+    client: reqwest::Client,
+    /// Last mood seen per character, to detect downward crossings.
+    moods: Arc<std::sync::Mutex<HashMap<String, f32>>>,
+    /// Highest memory id already looked at; unset until the first poll.
+    last_memory_id: Arc<Mutex<Option<i64>>>,
+    /// Serializes delivery passes so a row is never sent twice at once.
+    delivering: Arc<Mutex<()>>,
+}
+
+impl WebhookService {
+    pub fn new(config: WebhookConfig) -> Self {
+        Self {
+            config: Arc::new(RwLock::new(config)),
+            client: reqwest::Client::builder()
+                .timeout(REQUEST_TIMEOUT)
+                .build()
+                .unwrap_or_default(),
+            moods: Arc::new(std::sync::Mutex::new(HashMap::new())),
+            last_memory_id: Arc::new(Mutex::new(None)),
+            delivering: Arc::new(Mutex::new(())),
+        }
+    }
+
+    pub async fn get_config(&self) -> WebhookConfig {
+        self.config.read().await.clone()
+    }
+
+    pub async fn update_config(&self, config: WebhookConfig) {
+        *self.config.write().await = config;
+    }
+
+    /// Queue the event for every enabled webhook whose trigger matches.
+    pub async fn dispatch(&self, db: &SqlitePool, event: WebhookEvent) {
+        let config = self.config.read().await.clone();
+        if !config.enabled {
+            return;
+        }
+        let now = chrono::Utc::now().timestamp();
+        let variables = event.variables();
+        let mut queued = 0;
+        for webhook in config
+            .webhooks
+            .iter()
+            .filter(|w| w.enabled && w.trigger.matches(&event))
+        {
+            let mut variables = variables.clone();
+            if let WebhookTrigger::MoodBelow { threshold } = webhook.trigger {
+                variables.insert("threshold".to_string(), Value::from(round2(threshold)));
+            }
+            let body = match render_payload(
+                &webhook.payload_template,
+                event.name(),
+                now,
+                &variables,
+            ) {
+                Ok(body) => body,
+                Err(e) => {
+                    tracing::warn!(target: "webhooks", "[Webhooks] '{}' payload failed to render: {}", webhook.id, e);
+                    continue;
+                }
+            };
+            match enqueue(db, &webhook.id, event.name(), &body, now).await {
+                Ok(_) => queued += 1,
+                Err(e) => {
+                    tracing::warn!(target: "webhooks", "[Webhooks] Failed to queue '{}': {}", webhook.id, e)
+                }
+            }
+        }
+        if queued > 0 {
+            let service = self.clone();
+            let db = db.clone();
+            tauri::async_runtime::spawn(async move {
+                service.deliver_due(&db).await;
+            });
+        }
+    }
+
+    /// Feed a mood update; fires `mood_below` webhooks on a downward crossing.
+    /// The first update for a character only records the baseline.
+    pub async fn mood_changed(
+        &self,
+        db: &SqlitePool,
+        character_id: &str,
+        emotion: &str,
+        mood: f32,
+    ) {
+        let previous = match self.moods.lock() {
+            Ok(mut moods) => moods.insert(character_id.to_string(), mood),
+            Err(_) => return,
+        };
+        let Some(previous) = previous else {
+            return;
+        };
+        if previous <= mood {
+            return;
+        }
+        self.dispatch(
+            db,
+            WebhookEvent::MoodChanged {
+                character_id: character_id.to_string(),
+                previous,
+                mood,
+                emotion: emotion.to_string(),
+            },
+        )
+        .await;
+    }
+
+    /// Dispatch memories stored since the last poll. The first poll starts
+    /// from the newest existing memory so old ones are not replayed.
+    pub async fn poll_memories(&self, db: &SqlitePool) -> Result<(), KokoroError> {
+        let mut last = self.last_memory_id.lock().await;
+        let Some(after) = *last else {
+            let max: Option<i64> = sqlx::query_scalar("SELECT MAX(id) FROM memories")
+                .fetch_one(db)
+                .await?;
+            *last = Some(max.unwrap_or(0));
+            return Ok(());
+        };
+        let rows = sqlx::query(
+            "SELECT id, character_id, content FROM memories
+             WHERE id > ? AND status = 'active' ORDER BY id LIMIT ?",
+        )
+        .bind(after)
+        .bind(MEMORY_POLL_BATCH)
+        .fetch_all(db)
+        .await?;
+        for row in &rows {
+            let memory_id: i64 = row.get("id");
+            *last = Some(memory_id);
+            self.dispatch(
+                db,
+                WebhookEvent::MemoryAdded {
+                    character_id: row.get("character_id"),
+                    memory_id,
+                    content: row.get("content"),
+                },
+            )
+            .await;
+        }
+        Ok(())
+    }
+
+    /// Send everything in the outbox that is due.
+    pub async fn deliver_due(&self, db: &SqlitePool) {
+        let _guard = self.delivering.lock().await;
+        let now = chrono::Utc::now().timestamp();
+        let due = match due_deliveries(db, now).await {
+            Ok(due) => due,
+            Err(e) => {
+                tracing::warn!(target: "webhooks", "[Webhooks] Failed to load outbox: {}", e);
+                return;
+            }
+        };
+        let config = self.config.read().await.clone();
+        for delivery in due {
+            let webhook = config
+                .webhooks
+                .iter()
+                .find(|w| w.id == delivery.webhook_id && w.enabled);
+            let attempts = delivery.attempts + 1;
+            let (max_attempts, error) = match webhook {
+                Some(webhook) => (
+                    webhook.max_attempts,
+                    self.post(webhook, &delivery.body).await.err(),
+                ),
+                None => (0, Some("webhook removed or disabled".to_string())),
+            };
+            match &error {
+                None => {
+                    tracing::info!(target: "webhooks", "[Webhooks] Delivered #{} to '{}'", delivery.id, delivery.webhook_id)
+                }
+                Some(e) => {
+                    tracing::warn!(target: "webhooks", "[Webhooks] Delivery #{} to '{}' failed (attempt {}): {}", delivery.id, delivery.webhook_id, attempts, e)
+                }
+            }
+            let now = chrono::Utc::now().timestamp();
+            if let Err(e) = record_attempt(
+                db,
+                delivery.id,
+                attempts,
+                max_attempts,
+                error.as_deref(),
+                now,
+            )
+            .await
+            {
+                tracing::warn!(target: "webhooks", "[Webhooks] Failed to update outbox row #{}: {}", delivery.id, e);
+            }
+        }
+        if let Err(e) = prune_outbox(db, now).await {
+            tracing::warn!(target: "webhooks", "[Webhooks] Failed to prune outbox: {}", e);
+        }
+    }
+
+    /// Post a test event straight to one webhook, bypassing the outbox.
+    pub async fn send_test(&self, id: &str) -> Result<(), KokoroError> {
+        let webhook = self
+            .config
+            .read()
+            .await
+            .webhooks
+            .iter()
+            .find(|w| w.id == id)
+            .cloned()
+            .ok_or_else(|| KokoroError::NotFound(format!("Webhook '{}' not found", id)))?;
+        let event = WebhookEvent::Test;
+        let body = render_payload(
+            &webhook.payload_template,
+            event.name(),
+            chrono::Utc::now().timestamp(),
+            &event.variables(),
+        )?;
+        self.post(&webhook, &body)
+            .await
+            .map_err(KokoroError::ExternalService)
+    }
+
+    async fn post(&self, webhook: &Webhook, body: &str) -> Result<(), String> {
+        let mut request = self
+            .client
+            .post(webhook.url.trim())
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body.to_string());
+        for (name, value) in &webhook.headers {
+            request = request.header(name.as_str(), value.as_str());
+        }
+        let response = request.send().await.map_err(|e| e.to_string())?;
+        let status = response.status();
+        if status.is_success() {
+            Ok(())
+        } else {
+            Err(format!("HTTP {}", status.as_u16()))
+        }
+    }
+
+    /// Poll for new memories and flush the outbox in the background.
+    pub fn spawn_loop(&self, app: AppHandle) {
+        let service = self.clone();
+        tauri::async_runtime::spawn(async move {
+            let mut interval = tokio::time::interval(DELIVERY_TICK);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                interval.tick().await;
+                let Some(db) = app
+                    .try_state::<AIOrchestrator>()
+                    .map(|orchestrator| orchestrator.db.clone())
+                else {
+                    continue;
+                };
+                if let Err(e) = service.poll_memories(&db).await {
+                    tracing::warn!(target: "webhooks", "[Webhooks] Memory poll failed: {}", e);
+                }
+                service.deliver_due(&db).await;
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn db() -> SqlitePool {
+        let db = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::migrate!("./migrations").run(&db).await.unwrap();
+        db
+    }
+
+    fn memory(content: &str) -> WebhookEvent {
+        WebhookEvent::MemoryAdded {
+            character_id: "alice".to_string(),
+            memory_id: 7,
+            content: content.to_string(),
+        }
+    }
+
+    #[test]
+    fn template_escapes_text_and_keeps_numbers() {
+        let event = memory("said \"hi\"\nthen left");
+        let body = render_payload(
+            r#"{"text": "{{content}}", "id": {{memory_id}}, "kind": "{{event}}", "x": "{{missing}}"}"#,
+            event.name(),
+            100,
+            &event.variables(),
+        )
+        .unwrap();
+        let value: Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(value["text"], "said \"hi\"\nthen left");
+        assert_eq!(value["id"], 7);
+        assert_eq!(value["kind"], "memory_added");
+        assert_eq!(value["x"], "");
+
+        assert!(render_payload("{\"a\": {{memory_id}", "e", 0, &Map::new()).is_err());
+    }
+
+    #[test]
+    fn blank_template_uses_default_body() {
+        let event = memory("cats");
+        let body = render_payload(" ", event.name(), 5, &event.variables()).unwrap();
+        let value: Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(value["event"], "memory_added");
+        assert_eq!(value["timestamp"], 5);
+        assert_eq!(value["data"]["content"], "cats");
+    }
+
+    #[test]
+    fn triggers_match_topics_and_downward_crossings() {
+        let topic = WebhookTrigger::MemoryAdded {
+            topic: "cat, Piano".to_string(),
+        };
+        assert!(topic.matches(&memory("Started learning piano")));
+        assert!(!topic.matches(&memory("likes dogs")));
+        let any = WebhookTrigger::MemoryAdded {
+            topic: " ".to_string(),
+        };
+        assert!(any.matches(&memory("anything")));
+
+        let mood = |previous, mood| WebhookEvent::MoodChanged {
+            character_id: "alice".to_string(),
+            previous,
+            mood,
+            emotion: "sadness".to_string(),
+        };
+        let below = WebhookTrigger::MoodBelow { threshold: 0.3 };
+        assert!(below.matches(&mood(0.4, 0.2)));
+        assert!(!below.matches(&mood(0.25, 0.2)));
+        assert!(!below.matches(&mood(0.4, 0.35)));
+        assert!(!WebhookTrigger::ReminderDue.matches(&memory("x")));
+    }
+
+    #[test]
+    fn retry_backoff_doubles_and_caps() {
+        assert_eq!(retry_delay(1), 30);
+        assert_eq!(retry_delay(2), 60);
+        assert_eq!(retry_delay(4), 240);
+        assert_eq!(retry_delay(20), MAX_RETRY_SECS);
+    }
+
+    #[tokio::test]
+    async fn failed_attempts_back_off_then_give_up() {
+        let db = db().await;
+        let id = enqueue(&db, "hook", "test", "{}", 1000).await.unwrap();
+        assert_eq!(due_deliveries(&db, 1000).await.unwrap().len(), 1);
+
+        record_attempt(&db, id, 1, 2, Some("HTTP 500"), 1000)
+            .await
+            .unwrap();
+        assert!(due_deliveries(&db, 1000).await.unwrap().is_empty());
+        let retry = due_deliveries(&db, 1000 + retry_delay(1)).await.unwrap();
+        assert_eq!(retry[0].attempts, 1);
+
+        record_attempt(&db, id, 2, 2, Some("HTTP 500"), 1100)
+            .await
+            .unwrap();
+        let log = list_deliveries(&db, 10).await.unwrap();
+        assert_eq!(log[0].status, "failed");
+        assert_eq!(log[0].last_error.as_deref(), Some("HTTP 500"));
+        assert!(due_deliveries(&db, i64::MAX).await.unwrap().is_empty());
+    }
+
+    #[test]
+    fn validate_rejects_bad_urls_and_templates() {
+        let webhook = Webhook {
+            id: "a".to_string(),
+            label: String::new(),
+            enabled: true,
+            url: "https://example.com/hook".to_string(),
+            headers: HashMap::new(),
+            trigger: WebhookTrigger::ReminderDue,
+            payload_template: String::new(),
+            max_attempts: 5,
+        };
+        let config = |webhook: Webhook| WebhookConfig {
+            enabled: true,
+            webhooks: vec![webhook],
+        };
+        assert!(validate(&config(webhook.clone())).is_ok());
+        assert!(validate(&config(Webhook {
+            url: "ftp://example.com".to_string(),
+            ..webhook.clone()
+        }))
+        .is_err());
+        assert!(validate(&config(Webhook {
+            payload_template: "{\"label\": {{label}".to_string(),
+            ..webhook
+        }))
+        .is_err());
+    }
+}
@@ -18,6 +18,10 @@ import OnboardingOverlay, {
   type OnboardingStep,
 } from "./ui/widgets/OnboardingOverlay";
 import MemoryModelDownloadDialog from "./ui/widgets/MemoryModelDownloadDialog";
+import AboutMeInterview, { ABOUT_ME_INTERVIEW_EVENT } from "./ui/widgets/AboutMeInterview";
+import ToolConfirmDialog from "./ui/widgets/ToolConfirmDialog";
+import CrashReportDialog from "./ui/widgets/CrashReportDialog";
+import ImageGenProgressToast from "./ui/widgets/ImageGenProgressToast";
 import { useBackgroundSlideshow } from "./ui/hooks/useBackgroundSlideshow";
 import type { Live2DDisplayMode } from "./features/live2d/Live2DViewer";
 import { live2dUrl } from "./lib/utils";
@@ -178,9 +182,12 @@ import {
   onModUiMessage,
   onModScriptEvent,
   onModUnload,
+  onModReloaded,
   onChatTurnDelta,
   onChatTurnFinish,
   onChatCue,
+  onEmotionUpdate,
+  onCharacterActivated,
   streamChat,
   dispatchModEvent,
   unloadMod,
@@ -196,6 +203,7 @@ import {
   setJailbreakPrompt,
   getProactiveEnabled,
   getUserProfileSettings,
+  getOnboardingStatus,
   getMemoryEmbeddingModelStatus,
   // Config Getters
   getLlmConfig,
@@ -258,6 +266,7 @@ import {
   // New: Live2D
   deleteLive2dModel,
   importLive2dZip,
+  checkLive2dZipDuplicate,
   importLive2dFolder,
   exportLive2dModel,
   renameLive2dModel,
@@ -291,12 +300,15 @@ import {
   type CharacterRecord,
   type MemoryEmbeddingModelStatus,
   type MemoryEmbeddingModelDownloadProgress,
+  type OnboardingStatus,
   getKokoroErrorMessage,
   onMemoryEmbeddingModelProgress,
 } from "./lib/kokoro-bridge";
 import type { ThemeConfig } from "./ui/layout/types";
 import { modMessageBus } from "./ui/mods/ModMessageBus";
 import { CameraWatcher } from "./features/camera/CameraWatcher";
+import { VtsLipSync } from "./features/vts/VtsLipSync";
+import { AutomationRunner } from "./features/automation/AutomationRunner";
 
 let _regSnap = 0;
 const _subscribeFn = (cb: () => void) => {
@@ -511,6 +523,18 @@ function App() {
     }
   };
 
+  // A character bound to a Live2D model brings it along when switched to.
+  useEffect(() => {
+    const unlisten = onCharacterActivated((event) => {
+      if (event.live2d_model_path) {
+        handleCustomModelChange(event.live2d_model_path);
+      }
+    });
+    return () => {
+      unlisten.then(fn => fn());
+    };
+  }, []);
+
   const handleRenderFpsChange = async (fps: number) => {
     setRenderFps(fps);
 
@@ -596,6 +620,41 @@ function App() {
     }
   }, [memoryModelError, memoryModelStatus, refreshMemoryModelStatus, startMemoryModelDownload]);
 
+  const [interviewStatus, setInterviewStatus] = useState<OnboardingStatus | null>(null);
+  const activeCharacter = characters.find(c => c.id === readStringSetting(APP_SETTING_KEYS.activeCharacterId, ""))
+    ?? characters[0];
+
+  const refreshInterviewStatus = useCallback(() => {
+    getOnboardingStatus()
+      .then(setInterviewStatus)
+      .catch(err => console.error("[App] Failed to load interview status:", err));
+  }, []);
+
+  const applyInterviewStatus = useCallback((status: OnboardingStatus) => {
+    setInterviewStatus(status);
+    if (status.next !== null) return;
+    // The backend wrote the answers into the user profile; mirror it and re-compose the persona.
+    getUserProfileSettings()
+      .then(async (profile) => {
+        if (!profile) return;
+        writeStringSetting(APP_SETTING_KEYS.userName, profile.user_name);
+        writeStringSetting(APP_SETTING_KEYS.userPersona, profile.user_persona);
+        if (!activeCharacter) return;
+        const { composeSystemPrompt } = await import("./ui/widgets/CharacterManager");
+        const prompt = composeSystemPrompt(activeCharacter);
+        writeStringSetting(APP_SETTING_KEYS.persona, prompt);
+        setPersonaState(prompt);
+        await setPersona(prompt);
+      })
+      .catch(err => console.error("[App] Failed to sync user profile after interview:", err));
+  }, [activeCharacter]);
+
+  useEffect(() => {
+    refreshInterviewStatus();
+    window.addEventListener(ABOUT_ME_INTERVIEW_EVENT, refreshInterviewStatus);
+    return () => window.removeEventListener(ABOUT_ME_INTERVIEW_EVENT, refreshInterviewStatus);
+  }, [refreshInterviewStatus]);
+
   const closeOnboarding = (status: "completed" | "dismissed") => {
     localStorage.setItem(ONBOARDING_STATUS_KEY, status);
     setOnboardingStep(null);
@@ -793,7 +852,7 @@ function App() {
     // Listen for chat-triggered image generation requests
     const unlistenChatImageGen = onChatImageGen(({ prompt }) => {
       console.log("[App] chat-imagegen triggered, prompt:", prompt);
-      generateImage(prompt).then(result => {
+      generateImage(prompt, undefined, false, true).then(result => {
         const assetUrl = convertFileSrc(result.image_url);
         setGeneratedImage(assetUrl);
         bgSlideshow.setConfig({ mode: "generated" });
@@ -860,6 +919,14 @@ function App() {
       dispatchModEvent('cue', data).catch(() => { });
     });
 
+    const unlistenModEmotion = onEmotionUpdate((update) => {
+      modMessageBus.broadcast({
+        type: 'event',
+        payload: { name: 'emotion-update', ...update },
+      });
+      dispatchModEvent('emotion', update).catch(() => { });
+    });
+
     const unlistenModChatDone = onChatTurnFinish(({ turn_id, status }) => {
       modMessageBus.broadcast({
         type: 'event',
@@ -888,6 +955,15 @@ function App() {
       document.dispatchEvent(new CustomEvent("kokoro:mod-theme", { detail: null }));
     });
 
+    // ── MOD System: Hot reload ──
+    const unlistenModReloaded = onModReloaded(({ mod_id, changed, error }) => {
+      if (error) {
+        console.error(`[App] Mod '${mod_id}' failed to reload:`, error);
+      } else {
+        console.log(`[App] Mod '${mod_id}' reloaded after changes to`, changed);
+      }
+    });
+
     return () => {
       ttsService.cleanup();
       unlistenImageGen.then(unlisten => unlisten());
@@ -897,9 +973,11 @@ function App() {
       unlistenModUiMessage.then(unlisten => unlisten());
       unlistenModChatDelta.then(unlisten => unlisten());
       unlistenModCue.then(unlisten => unlisten());
+      unlistenModEmotion.then(unlisten => unlisten());
       unlistenModChatDone.then(unlisten => unlisten());
       unlistenModScriptEvent.then(unlisten => unlisten());
       unlistenModUnload.then(unlisten => unlisten());
+      unlistenModReloaded.then(unlisten => unlisten());
     };
   }, []);
 
@@ -1450,6 +1528,7 @@ function App() {
         voice: readStringSetting(APP_SETTING_KEYS.ttsVoice, "") || undefined,
         speed: readNumberSetting(APP_SETTING_KEYS.ttsSpeed, 1.0),
         pitch: readNumberSetting(APP_SETTING_KEYS.ttsPitch, 1.0),
+        priority: "high",
       }).catch(err => console.error('[App] TTS test failed:', err));
     }
     if (detail.action === 'set_tts_playback' && detail.data) {
@@ -1525,7 +1604,7 @@ function App() {
           const { open } = await import('@tauri-apps/plugin-dialog');
           const selected = await open({
             multiple: false,
-            filters: [{ name: 'Mod Archive', extensions: ['zip'] }],
+            filters: [{ name: 'Mod Archive', extensions: ['kokoromod', 'zip'] }],
           });
           if (!selected || typeof selected !== 'string') return;
           await installMod(selected);
@@ -1610,7 +1689,11 @@ function App() {
           if (selected && typeof selected === 'string') {
             if (selected.toLowerCase().endsWith('.zip')) {
               try {
-                const modelPath = await importLive2dZip(selected);
+                const duplicate = await checkLive2dZipDuplicate(selected);
+                const onDuplicate = duplicate
+                  ? (window.confirm(i18n.t('settings.model.import.duplicate_confirm', { name: duplicate.name })) ? 'skip' : 'rename')
+                  : undefined;
+                const modelPath = await importLive2dZip(selected, onDuplicate);
                 setCustomModelPath(modelPath);
                 writeStringSetting(APP_SETTING_KEYS.customModelPath, modelPath);
                 const models = await listLive2dModels();
@@ -1723,17 +1806,14 @@ function App() {
       // Trigger file input from host context
       const input = document.createElement('input');
       input.type = 'file';
-      input.accept = '.json,.png';
+      input.accept = '.json,.png,.charx';
       input.onchange = async (e) => {
         const file = (e.target as HTMLInputElement).files?.[0];
         if (!file) return;
         try {
-          const { parseCharacterCard } = await import('./lib/character-card-parser');
-          const { createCharacter, listCharacters, setActiveCharacterId, setCharacterName } = await import('./lib/kokoro-bridge');
-          const profile = await parseCharacterCard(file);
-          const id = crypto.randomUUID();
-          const now = Date.now();
-          await createCharacter({ id, ...profile, created_at: now, updated_at: now });
+          const { importCharacterCard, listCharacters, setActiveCharacterId, setCharacterName } = await import('./lib/kokoro-bridge');
+          const data = Array.from(new Uint8Array(await file.arrayBuffer()));
+          const { id } = await importCharacterCard({ data });
           const all = await listCharacters();
           setCharacters(all);
           const char = all.find(c => c.id === id);
@@ -1916,6 +1996,14 @@ function App() {
         onDismiss={() => closeOnboarding("dismissed")}
       />
 
+      <AboutMeInterview
+        open={onboardingStep === null && !memoryModelDialogOpen && Boolean(activeCharacter)}
+        status={interviewStatus}
+        characterId={activeCharacter?.id ?? ""}
+        characterName={activeCharacter?.name ?? ""}
+        onStatusChange={applyInterviewStatus}
+      />
+
       <MemoryModelDownloadDialog
         open={memoryModelDialogOpen}
         status={memoryModelStatus}
@@ -1932,11 +2020,20 @@ function App() {
         }}
       />
 
+      <ToolConfirmDialog />
+
+      <CrashReportDialog />
+
+      <ImageGenProgressToast />
+
       {/* Camera watcher — lives at app root so it persists when settings panel closes */}
       <CameraWatcher
         enabled={visionConfig?.camera_enabled ?? false}
         deviceId={visionConfig?.camera_device_id ?? undefined}
       />
+
+      <VtsLipSync />
+      <AutomationRunner />
     </ThemeProvider>
   );
 }
@@ -1,11 +1,17 @@
 import { listen, emit } from "@tauri-apps/api/event";
 import { audioPlayer } from "../services";
 import { VoiceInterruptService } from "./voice-interrupt-service";
+import { reportTtsPlayback, setCompanionPlayback, stopSpeaking } from "../../lib/kokoro-bridge";
+import { visemeTrack, type TtsVisemesEvent } from "../../lib/viseme-track";
 
 interface TtsStartEvent {
     text: string;
+    utterance_id: number;
 }
 
+/** How often the playback position is reported while audio plays. */
+const PLAYBACK_REPORT_MS = 250;
+
 interface TtsAudioEvent {
     data: number[]; // Vec<u8> comes as number array in JSON
 }
@@ -14,6 +20,10 @@ interface TtsEndEvent {
     text: string;
 }
 
+interface TtsInterruptedEvent {
+    reason: "user" | "barge_in";
+}
+
 interface TtsBrowserDelegateEvent {
     text: string;
     voice: string | null;
@@ -74,6 +84,9 @@ export class TtsService {
     private interruptCleanup: (() => void) | null = null;
     /** Generation counter — incremented on each init/cleanup to invalidate stale async listeners */
     private generation = 0;
+    /** Utterance whose audio is playing, for playback position reports. */
+    private utteranceId: number | null = null;
+    private playbackReportTimer: ReturnType<typeof setInterval> | null = null;
 
     async init() {
         // Tear down any previous listeners before re-registering
@@ -86,7 +99,9 @@ export class TtsService {
         const unlistenStart = await listen<TtsStartEvent>("tts:start", (_event) => {
             if (this.generation !== gen) return;
             console.log("[TTS] Started:", _event.payload.text);
+            this.utteranceId = _event.payload.utterance_id;
             audioPlayer.clearQueue();
+            visemeTrack.clear();
             this.browserTTS.cancel();
             this.startVoiceInterrupt();
         });
@@ -101,6 +116,14 @@ export class TtsService {
         if (this.generation !== gen) { unlistenAudio(); return; }
         this.unlistenFunctions.push(unlistenAudio);
 
+        // Listen for viseme timing that accompanies each audio chunk
+        const unlistenVisemes = await listen<TtsVisemesEvent>("tts:visemes", (event) => {
+            if (this.generation !== gen) return;
+            visemeTrack.push(event.payload);
+        });
+        if (this.generation !== gen) { unlistenVisemes(); return; }
+        this.unlistenFunctions.push(unlistenVisemes);
+
         // Listen for Browser Delegate (frontend handles TTS)
         const unlistenBrowserDelegate = await listen<TtsBrowserDelegateEvent>(
             "tts:browser-delegate",
@@ -114,6 +137,34 @@ export class TtsService {
         if (this.generation !== gen) { unlistenBrowserDelegate(); return; }
         this.unlistenFunctions.push(unlistenBrowserDelegate);
 
+        // Report audible playback to the companion status line
+        const unlistenPlayState = audioPlayer.onPlayStateChange((playing) => {
+            if (this.generation !== gen) return;
+            setCompanionPlayback(playing).catch(err => {
+                console.warn("[TTS] Failed to report playback state:", err);
+            });
+            if (playing) {
+                this.startPlaybackReports();
+            } else {
+                this.stopPlaybackReports();
+            }
+        });
+        this.unlistenFunctions.push(unlistenPlayState);
+
+        // Listen for Interrupts (stop_speaking or barge-in): drop everything queued
+        const unlistenInterrupted = await listen<TtsInterruptedEvent>("tts:interrupted", (event) => {
+            if (this.generation !== gen) return;
+            console.log("[TTS] Interrupted:", event.payload.reason);
+            this.utteranceId = null;
+            this.stopPlaybackReports();
+            audioPlayer.stop();
+            visemeTrack.clear();
+            this.browserTTS.cancel();
+            this.stopVoiceInterrupt();
+        });
+        if (this.generation !== gen) { unlistenInterrupted(); return; }
+        this.unlistenFunctions.push(unlistenInterrupted);
+
         // Listen for End
         const unlistenEnd = await listen<TtsEndEvent>("tts:end", (_event) => {
             if (this.generation !== gen) return;
@@ -133,10 +184,32 @@ export class TtsService {
     cleanup() {
         this.generation++; // invalidate any in-flight init()
         this.cleanupListeners();
+        this.stopPlaybackReports();
         this.browserTTS.cancel();
         this.stopVoiceInterrupt();
     }
 
+    // ── Playback Position ──────────────────────────────
+
+    /** Keep the backend's synthesis paced against what has actually been heard. */
+    private startPlaybackReports(): void {
+        if (this.playbackReportTimer !== null) return;
+        const report = () => {
+            if (this.utteranceId === null) return;
+            reportTtsPlayback(this.utteranceId, Math.floor(audioPlayer.playbackTimeMs)).catch(err => {
+                console.warn("[TTS] Failed to report playback position:", err);
+            });
+        };
+        report();
+        this.playbackReportTimer = setInterval(report, PLAYBACK_REPORT_MS);
+    }
+
+    private stopPlaybackReports(): void {
+        if (this.playbackReportTimer === null) return;
+        clearInterval(this.playbackReportTimer);
+        this.playbackReportTimer = null;
+    }
+
     // ── Voice Interruption ─────────────────────────────
 
     private startVoiceInterrupt(): void {
@@ -149,8 +222,13 @@ export class TtsService {
         this.interruptCleanup = this.voiceInterrupt.onInterrupt(() => {
             console.log("[TTS] Voice interrupt triggered — stopping playback");
             audioPlayer.stop();
+            visemeTrack.clear();
             this.browserTTS.cancel();
             this.stopVoiceInterrupt();
+            // Also stop the backend from synthesizing the rest of the reply
+            stopSpeaking().catch(err => {
+                console.warn("[TTS] Failed to stop speech:", err);
+            });
 
             // Bridge to STT: emit event so ChatPanel can start listening
             const sttEnabled = localStorage.getItem("kokoro_stt_enabled") === "true";
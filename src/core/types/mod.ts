@@ -9,8 +9,16 @@ export interface TtsConfig {
     speed?: number;
     pitch?: number;
     emotion?: string;
+    /** Condition the voice on the character's current emotion (ignored when `emotion` is set). */
+    follow_emotion?: boolean;
+    /** Scheduling priority of the utterance (default `normal`). */
+    priority?: TtsPriority;
+    /** Newer utterances from the same source replace this one while it waits. */
+    source?: string;
 }
 
+export type TtsPriority = "low" | "normal" | "high";
+
 export interface ProviderCapabilities {
     supports_streaming: boolean;
     supports_emotions: boolean;
@@ -63,12 +71,45 @@ export interface CacheConfig {
 
 export interface QueueConfig {
     max_concurrent: number;
+    /** Utterances allowed to wait before the lowest-priority one is dropped. */
+    max_depth: number;
+}
+
+export interface FailoverConfig {
+    enabled: boolean;
+    /** A sentence with no audio after this long fails over to the next provider. */
+    latency_threshold_ms: number;
+}
+
+export interface PrefetchConfig {
+    /** Audio synthesis keeps queued ahead of the playback position. */
+    lead_ms: number;
+    /** Most sentences synthesizing at once. */
+    max_sentences: number;
+}
+
+export interface PronunciationRule {
+    /** Regular expression; `$1` in the replacement refers to capture groups. */
+    pattern: string;
+    replacement: string;
+    case_insensitive?: boolean;
+    enabled?: boolean;
+}
+
+export interface NormalizationConfig {
+    enabled: boolean;
+    strip_markup: boolean;
+    expand_numbers: boolean;
+    pronunciations: PronunciationRule[];
 }
 
 export interface TtsSystemConfig {
     default_provider?: string | null;
     cache: CacheConfig;
     queue: QueueConfig;
+    failover?: FailoverConfig;
+    prefetch?: PrefetchConfig;
+    normalization?: NormalizationConfig;
     providers: ProviderConfigData[];
 }
 
@@ -92,7 +133,10 @@ export interface ModManifest {
     theme?: string;
     components?: Record<string, string>;
     scripts?: string[];
+    /** Requested permissions; see ModPermission. */
     permissions?: string[];
+    /** Hosts `Kokoro.http.fetch` may reach; "*.example.com" allows subdomains. */
+    network_allowlist?: string[];
     entry?: string;
     ui_entry?: string;
 }
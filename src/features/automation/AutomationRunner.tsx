@@ -0,0 +1,60 @@
+import { useEffect } from "react";
+import { audioPlayer } from "@/core/services";
+import { APP_SETTING_KEYS, writeBooleanSetting } from "@/lib/app-settings";
+import { onAutomationStep, runStartupAutomations, type AutomationStep } from "@/lib/kokoro-bridge";
+
+function applyStep(step: AutomationStep) {
+    switch (step.type) {
+        case "switch_character":
+            document.dispatchEvent(new CustomEvent("kokoro:mod-action", {
+                detail: { action: "select_character", data: { id: step.character_id } },
+            }));
+            break;
+        case "set_voice_mode":
+            writeBooleanSetting(APP_SETTING_KEYS.sttEnabled, step.enabled);
+            writeBooleanSetting(APP_SETTING_KEYS.sttContinuousListening, step.enabled);
+            if (step.enabled) {
+                writeBooleanSetting(APP_SETTING_KEYS.sttAutoSend, true);
+            }
+            window.dispatchEvent(new Event("kokoro-stt-settings-changed"));
+            break;
+        case "set_tts_enabled":
+            writeBooleanSetting(APP_SETTING_KEYS.ttsEnabled, step.enabled);
+            if (!step.enabled) {
+                audioPlayer.stop();
+            }
+            break;
+        default:
+            // Other steps run in the backend.
+            break;
+    }
+}
+
+/** Carries out automation steps that change main-window state, and starts the startup rules once listening. */
+export function AutomationRunner() {
+    useEffect(() => {
+        let disposed = false;
+        let unlisten: (() => void) | undefined;
+
+        onAutomationStep(({ rule_id, step }) => {
+            console.log(`[Automation] ${rule_id}: ${step.type}`);
+            applyStep(step);
+        }).then(fn => {
+            if (disposed) {
+                fn();
+                return;
+            }
+            unlisten = fn;
+            runStartupAutomations().catch(error => {
+                console.warn("[Automation] Failed to run startup rules:", error);
+            });
+        });
+
+        return () => {
+            disposed = true;
+            unlisten?.();
+        };
+    }, []);
+
+    return null;
+}
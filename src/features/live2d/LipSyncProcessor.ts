@@ -71,6 +71,23 @@ export class LipSyncProcessor {
         }
     }
 
+    /**
+     * Update from a backend viseme frame aligned with playback. The live
+     * amplitude still gates the mouth so it closes when audio stops.
+     * @param open Jaw open amount for the current viseme (0-1)
+     * @param form Mouth form for the current viseme (-1 wide … +1 round)
+     * @param amplitude RMS amplitude (0-1)
+     */
+    public updateViseme(open: number, form: number, amplitude: number) {
+        if (amplitude * this.config.gain < this.config.threshold) {
+            this.targetOpenY = 0;
+            this.targetForm = 0;
+            return;
+        }
+        this.targetOpenY = Math.min(Math.max(open, 0), 1);
+        this.targetForm = Math.max(Math.min(form, 1), -1);
+    }
+
     /**
      * Get the current smoothed mouth parameters.
      * Should be called every frame in the render loop.
@@ -2,6 +2,7 @@ import { Live2DModel } from "pixi-live2d-display/cubism4";
 import { LipSyncProcessor } from "./LipSyncProcessor";
 import { audioPlayer } from "../../core/services";
 import type { AudioAnalysis } from "../../lib/audio-player";
+import { visemeTrack } from "../../lib/viseme-track";
 import { getLive2dModelProfile, type Live2dModelProfile } from "../../lib/kokoro-bridge";
 
 export type AnimationState = "Idle" | "Speaking" | "Interactive";
@@ -49,7 +50,12 @@ export class Live2DController {
     constructor() {
         this.lipSync = new LipSyncProcessor();
         this.amplitudeHandler = (data) => {
-            this.lipSync.updateAudio(data.amplitude, data.lowFreqEnergy, data.highFreqEnergy);
+            const shape = visemeTrack.sample(audioPlayer.playbackTimeMs);
+            if (shape) {
+                this.lipSync.updateViseme(shape.open, shape.form, data.amplitude);
+            } else {
+                this.lipSync.updateAudio(data.amplitude, data.lowFreqEnergy, data.highFreqEnergy);
+            }
             if (data.amplitude > 0.1 && this.currentState !== "Speaking") {
                 this.startSpeaking();
             } else if (data.amplitude <= 0.01 && this.currentState === "Speaking") {
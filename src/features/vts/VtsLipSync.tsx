@@ -0,0 +1,42 @@
+import { useEffect } from "react";
+import { audioPlayer } from "@/core/services";
+import type { AudioAnalysis } from "@/lib/audio-player";
+import { getVtsStatus, setVtsMouthLevel } from "@/lib/kokoro-bridge";
+
+const STATUS_POLL_MS = 5000;
+/** VTS drops injected values after ~1s of silence; 20 updates/s is plenty. */
+const SEND_INTERVAL_MS = 50;
+
+/** Feeds TTS playback amplitude to VTube Studio's mouth parameter while connected. */
+export function VtsLipSync() {
+    useEffect(() => {
+        let connected = false;
+        let lastSent = 0;
+
+        const poll = async () => {
+            try {
+                connected = (await getVtsStatus()).connected;
+            } catch {
+                connected = false;
+            }
+        };
+        void poll();
+        const timer = setInterval(() => { void poll(); }, STATUS_POLL_MS);
+
+        const handler = (data: AudioAnalysis) => {
+            if (!connected) return;
+            const now = performance.now();
+            if (now - lastSent < SEND_INTERVAL_MS) return;
+            lastSent = now;
+            setVtsMouthLevel(data.amplitude).catch(() => {});
+        };
+        audioPlayer.addAmplitudeListener(handler);
+
+        return () => {
+            clearInterval(timer);
+            audioPlayer.removeAmplitudeListener(handler);
+        };
+    }, []);
+
+    return null;
+}
@@ -50,6 +50,9 @@ export class AudioStreamManager {
     private streamEnded = false;
     private playbackStarted = false;
     private currentSource: AudioBufferSourceNode | null = null;
+    private currentSourceStartedAt = 0;
+    /** Total length of WAV buffers that finished playing in this stream. */
+    private wavPlayedMs = 0;
     private streamMode: AudioContainer | null = null;
     private _isPlaying = false;
     private analysisListeners: ((data: AudioAnalysis) => void)[] = [];
@@ -155,6 +158,17 @@ export class AudioStreamManager {
         return this._isPlaying;
     }
 
+    /** Position in the current stream, in milliseconds since its first chunk started. */
+    public get playbackTimeMs(): number {
+        if (this.streamMode === "wav") {
+            const current = this.currentSource
+                ? (this.audioContext.currentTime - this.currentSourceStartedAt) * 1000
+                : 0;
+            return this.wavPlayedMs + Math.max(current, 0);
+        }
+        return this.audioElement.currentTime * 1000;
+    }
+
     public stop() {
         this.audioElement.pause();
         if (this.objectUrl) {
@@ -188,6 +202,7 @@ export class AudioStreamManager {
         this.sourceBuffer = null;
         this.appendQueue = [];
         this.wavQueue = [];
+        this.wavPlayedMs = 0;
         this.streamEnded = false;
         this.playbackStarted = false;
         this.streamMode = null;
@@ -265,6 +280,7 @@ export class AudioStreamManager {
         source.onended = () => {
             if (this.currentSource === source) {
                 this.currentSource = null;
+                this.wavPlayedMs += buffer.duration * 1000;
             }
             if (this.wavQueue.length > 0) {
                 this.playNextWav();
@@ -277,6 +293,7 @@ export class AudioStreamManager {
             this.broadcastAnalysis({ amplitude: 0, lowFreqEnergy: 0, highFreqEnergy: 0 });
         };
 
+        this.currentSourceStartedAt = this.audioContext.currentTime;
         source.start();
     }
 
@@ -33,6 +33,56 @@ export interface SystemStatus {
     memory_usage_mb: number;
 }
 
+/** Which subsystems are configured and healthy, for hiding unavailable features. */
+export interface EngineCapabilities {
+    llm: { configured: boolean; provider: string | null };
+    tts: { providers: ProviderStatus[]; available: boolean };
+    stt: { provider: string | null; available: boolean };
+    vision: { screen: boolean; camera: boolean };
+    imagegen: { available: boolean; providers: string[] };
+    telegram: { configured: boolean; running: boolean };
+    mcp: { connected_servers: number; tool_count: number };
+    local_models: { memory_embedding: boolean; sensevoice: boolean; whisper_local: boolean };
+    api_server: boolean;
+    vts: boolean;
+}
+
+export interface SystemState {
+    battery_percent: number | null;
+    charging: boolean | null;
+    battery_saver: boolean | null;
+    network_online: boolean;
+    checked_at: number;
+}
+
+export interface CrashReport {
+    id: string;
+    created_at: string;
+    app_version: string;
+    os: string;
+    arch: string;
+    thread: string;
+    message: string;
+    location: string | null;
+    backtrace: string;
+    active_subsystems: string[];
+    recent_logs: string[];
+    path?: string;
+}
+
+export type UserPresence = "active" | "quiet" | "away" | "unknown";
+
+export interface UserPresenceEvent {
+    presence: UserPresence;
+    input_idle_secs: number | null;
+}
+
+/** Payload of `system-resumed`, emitted when the machine wakes from sleep. */
+export interface SystemResume {
+    slept_secs: number;
+    resumed_at: number;
+}
+
 export interface CharacterState {
     name: string;
     current_cue: string;
@@ -56,6 +106,749 @@ export async function getSystemStatus(): Promise<SystemStatus> {
     return invoke<SystemStatus>("get_system_status");
 }
 
+export async function getEngineCapabilities(): Promise<EngineCapabilities> {
+    return invoke<EngineCapabilities>("get_engine_capabilities");
+}
+
+export async function getSystemState(): Promise<SystemState> {
+    return invoke<SystemState>("get_system_state");
+}
+
+export async function onSystemStateChanged(callback: (state: SystemState) => void): Promise<UnlistenFn> {
+    return listen<SystemState>("system-state-changed", (event) => callback(event.payload));
+}
+
+export async function getLastCrashReport(): Promise<CrashReport | null> {
+    return invoke<CrashReport | null>("get_last_crash_report");
+}
+
+export async function dismissCrashReport(): Promise<void> {
+    return invoke("dismiss_crash_report");
+}
+
+export async function onUserPresenceChanged(callback: (event: UserPresenceEvent) => void): Promise<UnlistenFn> {
+    return listen<UserPresenceEvent>("user-presence-changed", (event) => callback(event.payload));
+}
+
+export async function onSystemResumed(callback: (event: SystemResume) => void): Promise<UnlistenFn> {
+    return listen<SystemResume>("system-resumed", (event) => callback(event.payload));
+}
+
+// ── Offline Mode ───────────────────────────────────
+
+export interface OfflineModeConfig {
+    enabled: boolean;
+    llm_fallback_provider: string | null;
+    tts_fallback_provider: string | null;
+    stt_fallback_provider: string | null;
+    notify_telegram: boolean;
+}
+
+export interface OfflineStatus {
+    offline: boolean;
+    since: number | null;
+    llm_fallback_provider: string | null;
+    tts_fallback_provider: string | null;
+    stt_fallback_provider: string | null;
+    queued_tasks: number;
+}
+
+export async function getOfflineModeConfig(): Promise<OfflineModeConfig> {
+    return invoke<OfflineModeConfig>("get_offline_mode_config");
+}
+
+export async function saveOfflineModeConfig(config: OfflineModeConfig): Promise<void> {
+    return invoke("save_offline_mode_config", { config });
+}
+
+export async function getOfflineStatus(): Promise<OfflineStatus> {
+    return invoke<OfflineStatus>("get_offline_status");
+}
+
+export async function onOfflineModeChanged(callback: (status: OfflineStatus) => void): Promise<UnlistenFn> {
+    return listen<OfflineStatus>("offline-mode-changed", (event) => callback(event.payload));
+}
+
+// ── Desktop Wallpaper ──────────────────────────────
+
+export interface WallpaperConfig {
+    /** The companion may only change the wallpaper when this is on. */
+    enabled: boolean;
+    min_interval_minutes: number;
+    history_limit: number;
+}
+
+export interface WallpaperEntry {
+    id: string;
+    path: string;
+    source: "image" | "palette";
+    mood: string | null;
+    applied_at: number;
+    previous: string | null;
+}
+
+export interface WallpaperHistory {
+    original: string | null;
+    /** Oldest first. */
+    entries: WallpaperEntry[];
+}
+
+export async function getWallpaperConfig(): Promise<WallpaperConfig> {
+    return invoke<WallpaperConfig>("get_wallpaper_config");
+}
+
+export async function saveWallpaperConfig(config: WallpaperConfig): Promise<void> {
+    return invoke("save_wallpaper_config", { config });
+}
+
+export async function getWallpaperHistory(): Promise<WallpaperHistory> {
+    return invoke<WallpaperHistory>("get_wallpaper_history");
+}
+
+export async function setWallpaperImage(path: string): Promise<WallpaperEntry> {
+    return invoke<WallpaperEntry>("set_wallpaper_image", { path });
+}
+
+export async function setWallpaperPalette(mood?: string): Promise<WallpaperEntry> {
+    return invoke<WallpaperEntry>("set_wallpaper_palette", { mood: mood ?? null });
+}
+
+export async function revertWallpaper(): Promise<string | null> {
+    return invoke<string | null>("revert_wallpaper");
+}
+
+export async function restoreOriginalWallpaper(): Promise<string | null> {
+    return invoke<string | null>("restore_original_wallpaper");
+}
+
+export async function onWallpaperChanged(callback: () => void): Promise<UnlistenFn> {
+    return listen("wallpaper:changed", () => callback());
+}
+
+// ── Provider Warm-up ───────────────────────────────
+
+export interface WarmupPolicy {
+    warm_on_typing: boolean;
+    /** Ping interval that keeps the model loaded; null disables it. Minimum 30. */
+    keep_alive_secs: number | null;
+}
+
+/** Policies keyed by provider id; providers without an entry are never warmed. */
+export interface WarmupConfig {
+    llm: Record<string, WarmupPolicy>;
+    tts: Record<string, WarmupPolicy>;
+    imagegen: Record<string, WarmupPolicy>;
+}
+
+export async function getWarmupConfig(): Promise<WarmupConfig> {
+    return invoke<WarmupConfig>("get_warmup_config");
+}
+
+export async function saveWarmupConfig(config: WarmupConfig): Promise<void> {
+    return invoke("save_warmup_config", { config });
+}
+
+/** Tell the backend the user started composing so local models can load. */
+export async function notifyTyping(): Promise<void> {
+    return invoke("notify_typing");
+}
+
+// ── Live Captions ──────────────────────────────────
+
+export interface CaptionEvent {
+    seq: number;
+    speaker: "user" | "assistant";
+    utterance_id: string;
+    text: string;
+    is_final: boolean;
+    /** Start time in ms since captions were enabled. */
+    at_ms: number;
+    duration_ms: number;
+}
+
+export interface CaptionStatus {
+    enabled: boolean;
+    file_output: boolean;
+    file_path: string;
+}
+
+export async function setCaptionsEnabled(enabled: boolean, fileOutput?: boolean): Promise<CaptionStatus> {
+    return invoke<CaptionStatus>("set_captions_enabled", { enabled, fileOutput });
+}
+
+export async function getCaptionsStatus(): Promise<CaptionStatus> {
+    return invoke<CaptionStatus>("get_captions_status");
+}
+
+export async function onCaption(callback: (event: CaptionEvent) => void): Promise<UnlistenFn> {
+    return listen<CaptionEvent>("captions", (event) => callback(event.payload));
+}
+
+// ── Voice Session ──────────────────────────────────
+
+export interface VoiceSessionStatus {
+    active: boolean;
+    /** Utterances sent to chat since the session started. */
+    turns: number;
+}
+
+/** Hands-free conversation: the backend listens, chats and speaks replies sentence by sentence. */
+export async function startVoiceSession(tts?: TtsConfig): Promise<VoiceSessionStatus> {
+    return invoke<VoiceSessionStatus>("start_voice_session", { tts });
+}
+
+export async function stopVoiceSession(): Promise<VoiceSessionStatus> {
+    return invoke<VoiceSessionStatus>("stop_voice_session");
+}
+
+export async function getVoiceSessionStatus(): Promise<VoiceSessionStatus> {
+    return invoke<VoiceSessionStatus>("get_voice_session_status");
+}
+
+export async function onVoiceSessionState(callback: (event: { active: boolean }) => void): Promise<UnlistenFn> {
+    return listen<{ active: boolean }>("voice-session:state", (event) => callback(event.payload));
+}
+
+/** A transcript the voice session is sending as the user's message. */
+export async function onVoiceSessionUtterance(callback: (event: { text: string }) => void): Promise<UnlistenFn> {
+    return listen<{ text: string }>("voice-session:utterance", (event) => callback(event.payload));
+}
+
+// ── Input Mapping ──────────────────────────────────
+
+export type InputTrigger =
+    | { type: "http"; name: string }
+    | { type: "midi_note"; note: number; channel?: number | null }
+    | { type: "keyboard"; shortcut: string };
+
+export type MappedAction =
+    | { type: "expression"; cue: string }
+    | { type: "speak"; text: string }
+    | { type: "toggle_voice_session" }
+    /** Speak the selected (Linux) or copied text in the character's voice. */
+    | { type: "read_aloud"; summarize: boolean; comment: boolean };
+
+export interface InputMapping {
+    id: string;
+    label: string;
+    enabled: boolean;
+    trigger: InputTrigger;
+    action: MappedAction;
+}
+
+export interface InputMappingConfig {
+    enabled: boolean;
+    /** Matched against MIDI port names; first port when empty. */
+    midi_port?: string | null;
+    mappings: InputMapping[];
+}
+
+export interface InputMappingStatus {
+    enabled: boolean;
+    midi_port: string | null;
+    shortcuts: number;
+    warnings: string[];
+}
+
+export async function getInputMappingConfig(): Promise<InputMappingConfig> {
+    return invoke<InputMappingConfig>("get_input_mapping_config");
+}
+
+export async function saveInputMappingConfig(config: InputMappingConfig): Promise<InputMappingStatus> {
+    return invoke<InputMappingStatus>("save_input_mapping_config", { config });
+}
+
+export async function getInputMappingStatus(): Promise<InputMappingStatus> {
+    return invoke<InputMappingStatus>("get_input_mapping_status");
+}
+
+export async function listMidiInputs(): Promise<string[]> {
+    return invoke<string[]>("list_midi_inputs");
+}
+
+export async function testInputMapping(id: string): Promise<void> {
+    return invoke("test_input_mapping", { id });
+}
+
+export async function onInputMappingTriggered(callback: (event: { mapping_id: string; label: string }) => void): Promise<UnlistenFn> {
+    return listen<{ mapping_id: string; label: string }>("input-mapping:triggered", (event) => callback(event.payload));
+}
+
+/** Read-aloud speech was queued; `chars` is the length of the grabbed text. */
+export async function onReadAloudStarted(callback: (event: { chars: number; summarized: boolean }) => void): Promise<UnlistenFn> {
+    return listen<{ chars: number; summarized: boolean }>("read-aloud:started", (event) => callback(event.payload));
+}
+
+/** The character should comment on text it just read aloud (hidden chat turn). */
+export async function onReadAloudComment(callback: (event: { instruction: string }) => void): Promise<UnlistenFn> {
+    return listen<{ instruction: string }>("read-aloud:comment", (event) => callback(event.payload));
+}
+
+// ── Webhooks ───────────────────────────────────────
+
+export type WebhookTrigger =
+    | { type: "memory_added"; topic: string }
+    | { type: "mood_below"; threshold: number }
+    | { type: "reminder_due" };
+
+export interface Webhook {
+    id: string;
+    label: string;
+    enabled: boolean;
+    url: string;
+    headers: Record<string, string>;
+    trigger: WebhookTrigger;
+    /** JSON body with {{name}} placeholders; the default body when empty. */
+    payload_template: string;
+    max_attempts: number;
+}
+
+export interface WebhookConfig {
+    enabled: boolean;
+    webhooks: Webhook[];
+}
+
+export interface WebhookDelivery {
+    id: number;
+    webhook_id: string;
+    event: string;
+    status: "pending" | "delivered" | "failed";
+    attempts: number;
+    next_attempt_at: number;
+    last_error: string | null;
+    created_at: number;
+    delivered_at: number | null;
+}
+
+export async function getWebhookConfig(): Promise<WebhookConfig> {
+    return invoke<WebhookConfig>("get_webhook_config");
+}
+
+export async function saveWebhookConfig(config: WebhookConfig): Promise<void> {
+    return invoke("save_webhook_config", { config });
+}
+
+export async function testWebhook(id: string): Promise<void> {
+    return invoke("test_webhook", { id });
+}
+
+export async function listWebhookDeliveries(limit?: number): Promise<WebhookDelivery[]> {
+    return invoke<WebhookDelivery[]>("list_webhook_deliveries", { limit });
+}
+
+// ── Duet ───────────────────────────────────────────
+
+export interface DuetRequest {
+    /** Exactly two character ids; the first one opens. */
+    characters: string[];
+    topic: string;
+    turns?: number;
+    /** Voice each line with the speaker's own TTS voice. */
+    speak?: boolean;
+}
+
+export interface DuetStatus {
+    active: boolean;
+    topic: string;
+    speakers: { id: string; name: string }[];
+    /** Lines finished so far. */
+    turn: number;
+    turns: number;
+}
+
+export interface DuetLine {
+    turn: number;
+    speaker_id: string;
+    name: string;
+    text: string;
+    emotion: string;
+    intensity: number;
+}
+
+/** Two characters talk to each other; lines arrive as `duet:*` events and aren't saved to chat history. */
+export async function startDuet(request: DuetRequest): Promise<DuetStatus> {
+    return invoke<DuetStatus>("start_duet", { request });
+}
+
+/** The user chimes in; both characters hear it from the next turn on. */
+export async function interjectDuet(text: string): Promise<void> {
+    return invoke("interject_duet", { text });
+}
+
+export async function stopDuet(): Promise<DuetStatus> {
+    return invoke<DuetStatus>("stop_duet");
+}
+
+export async function getDuetStatus(): Promise<DuetStatus> {
+    return invoke<DuetStatus>("get_duet_status");
+}
+
+/** Cumulative visible text of the line being generated. */
+export async function onDuetDelta(callback: (event: { turn: number; speaker_id: string; text: string }) => void): Promise<UnlistenFn> {
+    return listen<{ turn: number; speaker_id: string; text: string }>("duet:delta", (event) => callback(event.payload));
+}
+
+export async function onDuetLine(callback: (line: DuetLine) => void): Promise<UnlistenFn> {
+    return listen<DuetLine>("duet:line", (event) => callback(event.payload));
+}
+
+export async function onDuetInterjection(callback: (event: { turn: number; text: string }) => void): Promise<UnlistenFn> {
+    return listen<{ turn: number; text: string }>("duet:interjection", (event) => callback(event.payload));
+}
+
+export async function onDuetFinish(callback: (event: { status: "completed" | "stopped" | "error"; error?: string }) => void): Promise<UnlistenFn> {
+    return listen<{ status: "completed" | "stopped" | "error"; error?: string }>("duet:finish", (event) => callback(event.payload));
+}
+
+// ── Story Mode ─────────────────────────────────────
+
+export interface StoryChoice {
+    id: string;
+    text: string;
+}
+
+export interface Story {
+    id: string;
+    character_id: string;
+    title: string;
+    premise: string;
+    current_beat_id: string | null;
+    created_at: number;
+    updated_at: number;
+}
+
+export interface StoryBeat {
+    id: string;
+    story_id: string;
+    parent_id: string | null;
+    /** What the player picked (or typed) on the parent beat. */
+    choice_text: string | null;
+    narrative: string;
+    choices: StoryChoice[];
+    /** Story state after this beat. */
+    state: Record<string, unknown>;
+    ending: boolean;
+    created_at: number;
+}
+
+export interface StoryView {
+    story: Story;
+    /** Beats from the opening to the current one. */
+    path: StoryBeat[];
+    /** Beats that branch off the path. */
+    branches: { beat_id: string; parent_id: string; choice_text: string | null }[];
+}
+
+export async function listStories(): Promise<Story[]> {
+    return invoke<Story[]>("list_stories");
+}
+
+export async function getStory(id: string): Promise<StoryView> {
+    return invoke<StoryView>("get_story", { id });
+}
+
+/** Create a story narrated by a character and generate its opening beat. */
+export async function startStory(request: { character_id?: string; title?: string; premise: string }): Promise<StoryView> {
+    return invoke<StoryView>("start_story", { request });
+}
+
+/** Continue with a choice or a free-text action. Continuing from an earlier beat starts a new branch. */
+export async function chooseStoryOption(
+    id: string,
+    request: { beat_id?: string; choice_id?: string; custom?: string },
+): Promise<StoryView> {
+    return invoke<StoryView>("choose_story_option", { id, request });
+}
+
+export async function switchStoryBeat(id: string, beatId: string): Promise<StoryView> {
+    return invoke<StoryView>("switch_story_beat", { id, beatId });
+}
+
+export async function deleteStory(id: string): Promise<void> {
+    return invoke("delete_story", { id });
+}
+
+/** Narrative of the beat being written so far. */
+export async function onStoryDelta(callback: (event: { story_id: string; text: string }) => void): Promise<UnlistenFn> {
+    return listen<{ story_id: string; text: string }>("story:delta", (event) => callback(event.payload));
+}
+
+export async function onStoryBeat(callback: (event: { story_id: string; beat: StoryBeat }) => void): Promise<UnlistenFn> {
+    return listen<{ story_id: string; beat: StoryBeat }>("story:beat", (event) => callback(event.payload));
+}
+
+// ── Companion Status Line ──────────────────────────
+
+export type CompanionActivity =
+    | "idle"
+    | "listening"
+    | "thinking"
+    | "speaking"
+    | "executing_tool"
+    | "generating_image";
+
+export interface CompanionState {
+    activity: CompanionActivity;
+    /** e.g. the tool name while executing a tool. */
+    detail: string | null;
+    /** Every activity in progress, highest priority first. */
+    active: CompanionActivity[];
+    /** Unix ms when `activity` last changed. */
+    since: number;
+    seq: number;
+}
+
+export async function getCompanionState(): Promise<CompanionState> {
+    return invoke<CompanionState>("get_companion_state");
+}
+
+export async function setCompanionPlayback(playing: boolean): Promise<void> {
+    return invoke("set_companion_playback", { playing });
+}
+
+export async function onCompanionState(callback: (state: CompanionState) => void): Promise<UnlistenFn> {
+    return listen<CompanionState>("companion:state", (event) => callback(event.payload));
+}
+
+// ── Voice Commands ─────────────────────────────────
+
+export type VoiceIntent =
+    | "mute_tts"
+    | "unmute_tts"
+    | "stop_talking"
+    | "switch_character"
+    | "delete_last_message";
+
+export interface VoiceCommandRule {
+    intent: VoiceIntent;
+    /** `switch_character` phrases contain a `{character}` placeholder. */
+    phrases: string[];
+}
+
+export interface VoiceCommandConfig {
+    enabled: boolean;
+    commands: VoiceCommandRule[];
+}
+
+export interface VoiceCommandOutcome {
+    intent: VoiceIntent;
+    character_id: string | null;
+    character_name: string | null;
+}
+
+export async function getVoiceCommandConfig(): Promise<VoiceCommandConfig> {
+    return invoke<VoiceCommandConfig>("get_voice_command_config");
+}
+
+export async function saveVoiceCommandConfig(config: VoiceCommandConfig): Promise<void> {
+    return invoke("save_voice_command_config", { config });
+}
+
+/** Returns the command a transcript triggers, or null if it is a chat message. */
+export async function routeVoiceCommand(text: string): Promise<VoiceCommandOutcome | null> {
+    return invoke<VoiceCommandOutcome | null>("route_voice_command", { text });
+}
+
+// ── Scheduler ──────────────────────────────────────
+
+export type ScheduleRule =
+    | { type: "once"; at: number }
+    /** Five-field cron expression in local time, e.g. "0 8 * * *". */
+    | { type: "cron"; expr: string };
+
+export type ScheduleAction =
+    | { type: "proactive"; instruction: string }
+    | { type: "action"; name: string; args?: Record<string, string> };
+
+export interface Schedule {
+    id: string;
+    character_id: string;
+    label: string;
+    rule: ScheduleRule;
+    action: ScheduleAction;
+    enabled: boolean;
+    next_run_at: number | null;
+    last_run_at: number | null;
+    created_at: number;
+}
+
+export interface NewSchedule {
+    character_id?: string;
+    label: string;
+    rule: ScheduleRule;
+    action: ScheduleAction;
+}
+
+export interface ScheduleFiredEvent {
+    id: string;
+    label: string;
+    success: boolean;
+    message: string;
+}
+
+export async function listSchedules(): Promise<Schedule[]> {
+    return invoke<Schedule[]>("list_schedules");
+}
+
+export async function addSchedule(request: NewSchedule): Promise<Schedule> {
+    return invoke<Schedule>("add_schedule", { request });
+}
+
+export async function removeSchedule(id: string): Promise<void> {
+    return invoke("remove_schedule", { id });
+}
+
+export async function setScheduleEnabled(id: string, enabled: boolean): Promise<Schedule> {
+    return invoke<Schedule>("set_schedule_enabled", { id, enabled });
+}
+
+export async function onScheduleFired(callback: (event: ScheduleFiredEvent) => void): Promise<UnlistenFn> {
+    return listen<ScheduleFiredEvent>("schedule-fired", (event) => callback(event.payload));
+}
+
+// ── Automation Rules ───────────────────────────────
+
+export type AutomationTrigger =
+    | { type: "startup" }
+    /** Five-field cron expression in local time, e.g. "0 9 * * 1-5". */
+    | { type: "cron"; expr: string };
+
+export type AutomationStep =
+    | { type: "load_mod"; mod_id: string }
+    | { type: "unload_mod" }
+    | { type: "run_action"; name: string; args?: Record<string, string> }
+    | { type: "proactive"; instruction: string }
+    | { type: "delay"; seconds: number }
+    | { type: "switch_character"; character_id: string }
+    | { type: "set_voice_mode"; enabled: boolean }
+    | { type: "set_tts_enabled"; enabled: boolean };
+
+export interface AutomationRule {
+    /** Empty when creating a rule. */
+    id: string;
+    name: string;
+    enabled: boolean;
+    trigger: AutomationTrigger;
+    steps: AutomationStep[];
+}
+
+export interface AutomationRunEvent {
+    rule_id: string;
+    name: string;
+    success: boolean;
+    steps: { success: boolean; message: string }[];
+}
+
+/** A step the main window has to carry out. */
+export interface AutomationStepEvent {
+    rule_id: string;
+    step: AutomationStep;
+}
+
+export async function listAutomationRules(): Promise<AutomationRule[]> {
+    return invoke<AutomationRule[]>("list_automation_rules");
+}
+
+export async function saveAutomationRule(rule: AutomationRule): Promise<AutomationRule> {
+    return invoke<AutomationRule>("save_automation_rule", { rule });
+}
+
+export async function deleteAutomationRule(id: string): Promise<void> {
+    return invoke("delete_automation_rule", { id });
+}
+
+export async function setAutomationRuleEnabled(id: string, enabled: boolean): Promise<AutomationRule> {
+    return invoke<AutomationRule>("set_automation_rule_enabled", { id, enabled });
+}
+
+export async function runAutomationRule(id: string): Promise<AutomationRunEvent> {
+    return invoke<AutomationRunEvent>("run_automation_rule", { id });
+}
+
+/** Signals that the main window is ready; startup rules run on the first call. */
+export async function runStartupAutomations(): Promise<number> {
+    return invoke<number>("run_startup_automations");
+}
+
+export async function onAutomationStep(callback: (event: AutomationStepEvent) => void): Promise<UnlistenFn> {
+    return listen<AutomationStepEvent>("automation-step", (event) => callback(event.payload));
+}
+
+export async function onAutomationRun(callback: (event: AutomationRunEvent) => void): Promise<UnlistenFn> {
+    return listen<AutomationRunEvent>("automation-run", (event) => callback(event.payload));
+}
+
+// ── Asset Store ────────────────────────────────────
+
+export type AssetCategory = "generated_images" | "tts_audio" | "vision_captures" | "model_imports";
+
+export interface AssetQuotaConfig {
+    generated_images_mb: number;
+    tts_audio_mb: number;
+    vision_captures_mb: number;
+    model_imports_mb: number;
+}
+
+export interface RemovedAsset {
+    path: string;
+    size_bytes: number;
+    reason: "unreferenced" | "over_quota" | "orphaned" | "missing" | "expired";
+}
+
+export interface CategoryCleanReport {
+    category: AssetCategory;
+    quota_bytes: number;
+    bytes_before: number;
+    bytes_after: number;
+    removed: RemovedAsset[];
+}
+
+export interface AssetCleanReport {
+    dry_run: boolean;
+    reclaimed_bytes: number;
+    categories: CategoryCleanReport[];
+}
+
+export async function getAssetQuotaConfig(): Promise<AssetQuotaConfig> {
+    return invoke<AssetQuotaConfig>("get_asset_quota_config");
+}
+
+export async function saveAssetQuotaConfig(config: AssetQuotaConfig): Promise<void> {
+    return invoke<void>("save_asset_quota_config", { config });
+}
+
+export async function cleanAssets(dryRun: boolean): Promise<AssetCleanReport> {
+    return invoke<AssetCleanReport>("clean_assets", { dryRun });
+}
+
+export type TrashKind = "memory" | "conversation" | "character" | "live2d_model";
+
+export interface TrashItem {
+    id: number;
+    kind: TrashKind;
+    /** Memory id, conversation id, character id or model folder name. */
+    item_id: string;
+    label: string;
+    deleted_at: number;
+}
+
+export async function listTrash(): Promise<TrashItem[]> {
+    return invoke<TrashItem[]>("list_trash");
+}
+
+export async function restoreFromTrash(id: number): Promise<TrashItem> {
+    return invoke<TrashItem>("restore_from_trash", { id });
+}
+
+/** Restores the most recently deleted item; `null` when the trash is empty. */
+export async function undoLastDelete(): Promise<TrashItem | null> {
+    return invoke<TrashItem | null>("undo_last_delete");
+}
+
+export async function emptyTrash(): Promise<number> {
+    return invoke<number>("empty_trash");
+}
+
 export async function setWindowSize(width: number, height: number): Promise<void> {
     return invoke("set_window_size", { width, height });
 }
@@ -86,6 +879,57 @@ export async function testVectorStore(): Promise<DbTestResult> {
     return invoke<DbTestResult>("test_vector_store");
 }
 
+export interface CompactReport {
+    bytes_before: number;
+    bytes_after: number;
+    reclaimed_bytes: number;
+    duration_ms: number;
+}
+
+export async function compactDatabase(): Promise<CompactReport> {
+    return invoke<CompactReport>("compact_database");
+}
+
+export interface PurgeScope {
+    character_id?: string;
+    /** Inclusive bounds, unix seconds. */
+    from?: number;
+    to?: number;
+    topic?: string;
+    topic_threshold?: number;
+}
+
+export interface PurgePreviewItem {
+    kind: "memory" | "message" | "vision_log" | "summary" | "proactive";
+    character_id: string;
+    content: string;
+}
+
+export interface PurgeReport {
+    dry_run: boolean;
+    memories: number;
+    messages: number;
+    vision_logs: number;
+    summaries: number;
+    emotion_snapshots: number;
+    retrieval_logs: number;
+    /** Unprompted messages remembered in `proactive_history`. */
+    proactive_messages: number;
+    conversations: number;
+    /** Trash entries deleted or rewritten because they held purged text. */
+    trash_items: number;
+    preview: PurgePreviewItem[];
+}
+
+/** Delete data in `scope`; with `dryRun` only report what would be removed. */
+export async function purgeData(scope: PurgeScope, dryRun: boolean): Promise<PurgeReport> {
+    return invoke<PurgeReport>("purge_data", { scope, dryRun });
+}
+
+export async function onDataPurged(callback: (report: PurgeReport) => void): Promise<UnlistenFn> {
+    return listen<PurgeReport>("data-purged", (event) => callback(event.payload));
+}
+
 export async function sendMessage(message: string): Promise<ChatResponse> {
     return invoke<ChatResponse>("send_message", { message });
 }
@@ -125,20 +969,86 @@ export async function getUserProfileSettings(): Promise<UserProfileSettings | nu
     return invoke<UserProfileSettings | null>("get_user_profile_settings");
 }
 
+// ── About-me Interview ─────────────────────────────
+
+export type OnboardingQuestion = "name" | "pronouns" | "interests" | "schedule";
+
+export interface OnboardingStatus {
+    /** The question to ask now; null once the interview is over. */
+    next: OnboardingQuestion | null;
+    answered: number;
+    total: number;
+    completed: boolean;
+}
+
+export async function getOnboardingStatus(): Promise<OnboardingStatus> {
+    return invoke<OnboardingStatus>("get_onboarding_status");
+}
+
+/** Answer the current question; a null or blank answer skips it. */
+export async function answerOnboardingQuestion(
+    characterId: string,
+    question: OnboardingQuestion,
+    answer: string | null,
+): Promise<OnboardingStatus> {
+    return invoke<OnboardingStatus>("answer_onboarding_question", { characterId, question, answer });
+}
+
+export async function dismissOnboarding(): Promise<OnboardingStatus> {
+    return invoke<OnboardingStatus>("dismiss_onboarding");
+}
+
+export async function restartOnboarding(): Promise<OnboardingStatus> {
+    return invoke<OnboardingStatus>("restart_onboarding");
+}
+
 export async function setJailbreakPrompt(prompt: string): Promise<void> {
     return invoke("set_jailbreak_prompt", { prompt });
 }
 
-export async function getJailbreakPrompt(): Promise<string> {
-    return invoke("get_jailbreak_prompt");
+export async function getJailbreakPrompt(): Promise<string> {
+    return invoke("get_jailbreak_prompt");
+}
+
+export async function setProactiveEnabled(enabled: boolean): Promise<void> {
+    return invoke("set_proactive_enabled", { enabled });
+}
+
+export async function getProactiveEnabled(): Promise<boolean> {
+    return invoke("get_proactive_enabled");
+}
+
+export type ProactiveSource = "memory" | "vision" | "curiosity" | "time" | "event";
+
+export interface ProactiveComposerConfig {
+    /** Relative chance of each angle for idle messages; 0 disables it. */
+    weights: { memory: number; vision: number; curiosity: number; time: number };
+    /** Skip angles this similar (0–1) to a recent unprompted message. */
+    novelty_threshold: number;
+    /** Recent unprompted messages checked for repeats and openers. */
+    history_window: number;
+}
+
+export interface ProactiveHistoryEntry {
+    id: number;
+    character_id: string;
+    source: ProactiveSource;
+    topic: string;
+    opener: string;
+    message: string;
+    created_at: number;
+}
+
+export async function getProactiveComposerConfig(): Promise<ProactiveComposerConfig> {
+    return invoke("get_proactive_composer_config");
 }
 
-export async function setProactiveEnabled(enabled: boolean): Promise<void> {
-    return invoke("set_proactive_enabled", { enabled });
+export async function saveProactiveComposerConfig(config: ProactiveComposerConfig): Promise<void> {
+    return invoke("save_proactive_composer_config", { config });
 }
 
-export async function getProactiveEnabled(): Promise<boolean> {
-    return invoke("get_proactive_enabled");
+export async function listProactiveHistory(characterId?: string, limit?: number): Promise<ProactiveHistoryEntry[]> {
+    return invoke("list_proactive_history", { characterId, limit });
 }
 
 export async function clearHistory(): Promise<void> {
@@ -153,6 +1063,19 @@ export async function getMemoryEnabled(): Promise<boolean> {
     return invoke<boolean>("get_memory_enabled");
 }
 
+/** Privacy mode: the session is not saved and nothing is extracted into memory. */
+export async function setPrivacyMode(enabled: boolean): Promise<boolean> {
+    return invoke<boolean>("set_privacy_mode", { enabled });
+}
+
+export async function getPrivacyMode(): Promise<boolean> {
+    return invoke<boolean>("get_privacy_mode");
+}
+
+export async function onPrivacyModeChanged(callback: (enabled: boolean) => void): Promise<UnlistenFn> {
+    return listen<{ enabled: boolean }>("privacy-mode-changed", (event) => callback(event.payload.enabled));
+}
+
 // ── Context Settings ───────────────────────────────
 
 export interface ContextSettings {
@@ -168,6 +1091,49 @@ export async function setContextSettings(settings: ContextSettings): Promise<voi
     return invoke("set_context_settings", { settings });
 }
 
+/** One block of the composed prompt; tokens are estimates. */
+export interface ContextSection {
+    /** Block tag (`rules`, `long_term_memory`, ...), `history`, or an instruction type. */
+    name: string;
+    placement: "system" | "dynamic_context" | "history" | "instruction";
+    tokens: number;
+}
+
+export type ContextSummaryUsed =
+    | { kind: "conversation"; version: number; end_message_id: number; tokens: number }
+    | { kind: "session"; count: number; tokens: number }
+    | { kind: "catch_up"; idle_secs: number; tokens: number };
+
+export interface ContextBreakdown {
+    /** Unix seconds. */
+    composed_at: number;
+    character_id: string;
+    conversation_id: string | null;
+    sections: ContextSection[];
+    memories: { id: number; tier: string; importance: number; tokens: number }[];
+    summaries: ContextSummaryUsed[];
+    lorebook_entry_ids: number[];
+    history: {
+        available: number;
+        /** Screen context and other technical messages left out by type. */
+        excluded: number;
+        included: number;
+        /** Older messages left to the conversation summary. */
+        dropped: number;
+        cutoff: "token_budget" | "message_limit" | null;
+        truncated_messages: number;
+        budget_tokens: number;
+        used_tokens: number;
+    };
+    total_tokens: number;
+    warnings: string[];
+}
+
+/** What the last composed prompt showed the model; null before the first one. */
+export async function getContextBreakdown(): Promise<ContextBreakdown | null> {
+    return invoke<ContextBreakdown | null>("get_context_breakdown");
+}
+
 export async function deleteLastMessages(count: number): Promise<void> {
     return invoke("delete_last_messages", { count });
 }
@@ -186,6 +1152,19 @@ export interface LlmProviderConfig {
     extra?: Record<string, unknown>;
 }
 
+/** Declarative mapping for `custom_http` providers, stored in `extra.custom_http`. */
+export interface CustomHttpSpec {
+    url?: string;
+    headers?: Record<string, string>;
+    body: unknown;
+    stream_format?: "sse" | "ndjson" | "none";
+    stream_delta_path?: string;
+    response_path: string;
+    error_path?: string;
+    done_marker?: string | null;
+    role_map?: Record<string, string>;
+}
+
 export interface LlmPreset {
     id: string;
     name: string;
@@ -262,6 +1241,8 @@ export interface ChatRequest {
     character_id?: string;
     /** If true, the user instruction is hidden; non-empty assistant replies may still be saved. */
     hidden?: boolean;
+    /** Set after `regenerateLastResponse`: the user message is already persisted. */
+    regenerate?: boolean;
 }
 
 export async function streamChat(request: ChatRequest): Promise<void> {
@@ -272,6 +1253,23 @@ export async function cancelChatTurn(turnId: string, reason?: string): Promise<v
     return invoke("cancel_chat_turn", { turnId, reason: reason ?? null });
 }
 
+/** Stop whatever turn this window is generating. Resolves false if nothing was running. */
+export async function cancelChat(reason?: string): Promise<boolean> {
+    return invoke<boolean>("cancel_chat", { reason: reason ?? null });
+}
+
+export interface ChatCancelledEvent {
+    turn_id: string;
+    /** Text generated before the stop. */
+    text: string;
+    /** Whether that text was saved to the conversation. */
+    persisted: boolean;
+}
+
+export async function onChatCancelled(callback: (event: ChatCancelledEvent) => void): Promise<UnlistenFn> {
+    return listen<ChatCancelledEvent>("chat-cancelled", (event) => callback(event.payload));
+}
+
 export async function onChatError(callback: (error: string) => void): Promise<UnlistenFn> {
     return listen<unknown>("chat-error", (event) => callback(parseLegacyChatError(event.payload)));
 }
@@ -443,7 +1441,7 @@ export interface ToolTraceItem {
     toolId?: string;
     text: string;
     isError?: boolean;
-    source?: "builtin" | "mcp";
+    source?: "builtin" | "mcp" | "mod";
     serverName?: string;
     needsFeedback?: boolean;
     permissionLevel?: "safe" | "elevated";
@@ -458,7 +1456,7 @@ export interface ChatTurnToolEvent {
     tool: string;
     tool_name?: string;
     tool_id?: string;
-    source?: "builtin" | "mcp";
+    source?: "builtin" | "mcp" | "mod";
     server_name?: string;
     needs_feedback?: boolean;
     permission_level?: "safe" | "elevated";
@@ -476,6 +1474,38 @@ export async function onChatTurnStart(callback: (event: ChatTurnStartEvent) => v
     return listen<ChatTurnStartEvent>("chat-turn-start", (event) => callback(event.payload));
 }
 
+export interface ChatTurnWaitingEvent {
+    turn_id: string;
+    waited_ms: number;
+    provider_id: string;
+    /** Line for the character to say while waiting, when fillers are enabled. */
+    filler: string | null;
+}
+
+export interface QueuedInput {
+    id: string;
+    preview: string;
+    hidden: boolean;
+    queued_at: number;
+}
+
+export interface ChatQueueEvent {
+    window_label: string;
+    mode: InputQueueMode;
+    running: QueuedInput | null;
+    pending: QueuedInput[];
+}
+
+/** Fired whenever a window's input queue changes. */
+export async function onChatQueue(callback: (event: ChatQueueEvent) => void): Promise<UnlistenFn> {
+    return listen<ChatQueueEvent>("chat-queue", (event) => callback(event.payload));
+}
+
+/** Fired once when a turn's first visible text misses the latency budget. */
+export async function onChatTurnWaiting(callback: (event: ChatTurnWaitingEvent) => void): Promise<UnlistenFn> {
+    return listen<ChatTurnWaitingEvent>("chat-turn-waiting", (event) => callback(event.payload));
+}
+
 export async function onChatTurnDelta(callback: (event: ChatTurnDeltaEvent) => void): Promise<UnlistenFn> {
     return listen<ChatTurnDeltaEvent>("chat-turn-delta", (event) => callback(event.payload));
 }
@@ -505,6 +1535,36 @@ export async function onChatCue(
     return listen<CueEvent>("chat-cue", (event) => callback(event.payload));
 }
 
+// ── Emotion State ──────────────────────────────────
+
+export type EmotionName = "neutral" | "joy" | "love" | "sadness" | "anger" | "surprise" | "fear";
+
+export interface EmotionState {
+    emotion: EmotionName;
+    /** 0–1, fades between messages. */
+    intensity: number;
+    /** 0–1 longer-term mood. */
+    mood: number;
+}
+
+export interface EmotionUpdateEvent extends EmotionState {
+    character_id: string;
+    source: "cue" | "mod" | "external" | "decay" | "reset";
+}
+
+export async function getEmotionState(): Promise<EmotionState> {
+    return invoke<EmotionState>("get_emotion_state");
+}
+
+/** Push an emotion from outside the chat loop; `intensity` defaults to 0.8. */
+export async function setEmotion(emotion: string, intensity?: number): Promise<void> {
+    return invoke("set_emotion", { emotion, intensity });
+}
+
+export async function onEmotionUpdate(callback: (event: EmotionUpdateEvent) => void): Promise<UnlistenFn> {
+    return listen<EmotionUpdateEvent>("emotion:update", (event) => callback(event.payload));
+}
+
 // ── LLM Management ──────────────────────────────────
 
 export interface Model {
@@ -561,6 +1621,57 @@ export async function installMod(filePath: string): Promise<ModManifest> {
     return invoke("install_mod", { filePath });
 }
 
+export interface ModExport {
+    path: string;
+    files: number;
+    bytes: number;
+    /** Files left out because installing would not extract them. */
+    skipped: string[];
+}
+
+/** Package an installed mod as a `.kokoromod` archive. */
+export async function exportMod(modId: string, outPath: string): Promise<ModExport> {
+    return invoke("export_mod", { modId, outPath });
+}
+
+export interface ModRegistryListing {
+    id: string;
+    name: string;
+    version: string;
+    description: string;
+    author: string | null;
+    /** Absolute URL of the zip package. */
+    download_url: string;
+    sha256: string;
+    installed_version: string | null;
+}
+
+export async function browseModRegistry(registryUrl: string): Promise<ModRegistryListing[]> {
+    return invoke("browse_mod_registry", { registryUrl });
+}
+
+/** Download a package, verify its SHA-256 and install it. */
+export async function installModFromUrl(modId: string, url: string, sha256: string): Promise<ModManifest> {
+    return invoke("install_mod_from_url", { modId, url, sha256 });
+}
+
+export type ModPermission = "network" | "chat-read" | "expression-control" | "storage" | "tools" | "chat-transform";
+
+export const MOD_PERMISSIONS: ModPermission[] = ["network", "chat-read", "expression-control", "storage", "tools", "chat-transform"];
+
+/** Known permissions a manifest requests. */
+export function requestedModPermissions(mod: ModManifest): ModPermission[] {
+    return MOD_PERMISSIONS.filter(permission => mod.permissions?.includes(permission));
+}
+
+export async function getModPermissions(): Promise<Record<string, ModPermission[]>> {
+    return invoke("get_mod_permissions");
+}
+
+export async function setModPermissions(modId: string, permissions: ModPermission[]): Promise<void> {
+    return invoke("set_mod_permissions", { modId, permissions });
+}
+
 export async function getModTheme(): Promise<ModThemeJson | null> {
     return invoke("get_mod_theme");
 }
@@ -607,6 +1718,19 @@ export async function onModUnload(callback: () => void): Promise<UnlistenFn> {
     return listen<void>("mod:unload", () => callback());
 }
 
+/** Payload of `mod:reloaded`, sent after the running mod's files changed on disk. */
+export interface ModReloadedEvent {
+    mod_id: string;
+    /** Changed files, relative to the mod directory. */
+    changed: string[];
+    /** Why the reload failed; the previous version keeps running. */
+    error: string | null;
+}
+
+export async function onModReloaded(callback: (event: ModReloadedEvent) => void): Promise<UnlistenFn> {
+    return listen<ModReloadedEvent>("mod:reloaded", (event) => callback(event.payload));
+}
+
 export async function onModScriptEvent(
     callback: (data: { event: string; payload: unknown }) => void
 ): Promise<UnlistenFn> {
@@ -638,8 +1762,38 @@ export interface Live2dModelProfile {
     semantic_cue_map: Record<string, string>;
 }
 
-export async function importLive2dZip(zipPath: string): Promise<string> {
-    return invoke<string>("import_live2d_zip", { zipPath });
+export type Live2dDuplicateAction = "skip" | "rename";
+
+export interface Live2dDuplicate {
+    /** Folder name of the model imported from the same zip. */
+    name: string;
+    path: string;
+}
+
+/**
+ * Imports a Live2D zip. When the same zip was imported before, `onDuplicate`
+ * reuses that model (`skip`) or imports a renamed copy (`rename`).
+ */
+export async function importLive2dZip(zipPath: string, onDuplicate?: Live2dDuplicateAction): Promise<string> {
+    return invoke<string>("import_live2d_zip", { zipPath, onDuplicate: onDuplicate ?? null });
+}
+
+export interface Live2dImportProgress {
+    stage: "validating" | "extracting" | "installing" | "complete";
+    processed_bytes: number;
+    total_bytes: number;
+    /** Archive entry being extracted. */
+    file: string | null;
+}
+
+export async function onLive2dImportProgress(
+    callback: (progress: Live2dImportProgress) => void
+): Promise<UnlistenFn> {
+    return listen<Live2dImportProgress>("live2d:import-progress", (event) => callback(event.payload));
+}
+
+export async function checkLive2dZipDuplicate(zipPath: string): Promise<Live2dDuplicate | null> {
+    return invoke<Live2dDuplicate | null>("check_live2d_zip_duplicate", { zipPath });
 }
 
 export async function importLive2dFolder(modelJsonPath: string): Promise<string> {
@@ -680,6 +1834,46 @@ export async function synthesize(text: string, config: TtsConfig): Promise<void>
     return invoke("synthesize", { text, config });
 }
 
+export interface ExportConversationAudioRequest {
+    conversation_id: string;
+    output_path?: string;
+    user_voice?: TtsConfig;
+    assistant_voice?: TtsConfig;
+    include_user?: boolean;
+    gap_ms?: number;
+}
+
+export interface AudioChapter {
+    index: number;
+    role: string;
+    title: string;
+    start_ms: number;
+    end_ms: number;
+}
+
+export interface ConversationAudioExport {
+    path: string;
+    format: string;
+    duration_ms: number;
+    chapters: AudioChapter[];
+}
+
+export interface ConversationAudioExportProgress {
+    conversation_id: string;
+    current: number;
+    total: number;
+}
+
+export async function exportConversationAudio(request: ExportConversationAudioRequest): Promise<ConversationAudioExport> {
+    return invoke<ConversationAudioExport>("export_conversation_audio", { request });
+}
+
+export async function onConversationAudioExportProgress(
+    callback: (progress: ConversationAudioExportProgress) => void
+): Promise<UnlistenFn> {
+    return listen<ConversationAudioExportProgress>("tts:export-progress", (event) => callback(event.payload));
+}
+
 export async function listTtsProviders(): Promise<ProviderStatus[]> {
     return invoke<ProviderStatus[]>("list_tts_providers");
 }
@@ -696,6 +1890,42 @@ export async function clearTtsCache(): Promise<void> {
     return invoke("clear_tts_cache");
 }
 
+/** Cut off the utterance in progress; `tts:interrupted` tells playback to flush. */
+export async function stopSpeaking(): Promise<void> {
+    return invoke("stop_speaking");
+}
+
+/** How far playback of `tts:start`'s `utterance_id` has got; paces synthesis. */
+export async function reportTtsPlayback(utteranceId: number, positionMs: number): Promise<void> {
+    return invoke("report_tts_playback", { utteranceId, positionMs });
+}
+
+/** Payload of `tts:failover`: synthesis moved to another provider mid-utterance. */
+export interface TtsFailoverEvent {
+    from: string;
+    to: string;
+    reason: string;
+}
+
+export async function onTtsFailover(callback: (event: TtsFailoverEvent) => void): Promise<UnlistenFn> {
+    return listen<TtsFailoverEvent>("tts:failover", (event) => callback(event.payload));
+}
+
+/** Payload of `tts:dropped`: an utterance that was skipped or cut short. */
+export interface TtsDroppedEvent {
+    id: string;
+    preview: string;
+    priority: "low" | "normal" | "high";
+    source: string | null;
+    reason: "superseded" | "preempted" | "queue_full" | "interrupted";
+    /** Sentences never spoken; null when the utterance never started. */
+    unspoken_sentences: number | null;
+}
+
+export async function onTtsDropped(callback: (event: TtsDroppedEvent) => void): Promise<UnlistenFn> {
+    return listen<TtsDroppedEvent>("tts:dropped", (event) => callback(event.payload));
+}
+
 export async function getTtsConfig(): Promise<TtsSystemConfig> {
     return invoke<TtsSystemConfig>("get_tts_config");
 }
@@ -719,6 +1949,18 @@ export interface ImageGenResult {
     image_url: string;
     prompt: string;
     provider_id: string;
+    negative_prompt?: string;
+    seed?: number;
+    /** Gallery entry id, when the generation was recorded. */
+    gallery_id?: number;
+    /** Present for selfies that went through the appearance check. */
+    consistency?: SelfieConsistencyReport;
+}
+
+export interface SelfieConsistencyReport {
+    attempts: number;
+    matched: boolean;
+    mismatches: string[];
 }
 
 export interface ImageGenProviderConfig {
@@ -741,10 +1983,51 @@ export interface ImageGenSystemConfig {
     default_provider?: string;
     enabled: boolean;
     providers: ImageGenProviderConfig[];
+    /** Regenerations allowed when a selfie fails the appearance check; 0 disables it. */
+    selfie_max_retries?: number;
+}
+
+/** `inCharacter` applies the active character's image preset. */
+export async function generateImage(prompt: string, providerId?: string, selfie?: boolean, inCharacter?: boolean): Promise<ImageGenResult> {
+    return invoke("generate_image", { prompt, providerId, selfie, inCharacter });
+}
+
+export interface ImageVariationRequest {
+    /** Path of an earlier image, or "last" for the most recent generation. */
+    source: string;
+    /** Defaults to the source image's prompt when it is known. */
+    prompt?: string;
+    /** 0.0 keeps the source, 1.0 ignores it. */
+    strength?: number;
+    /** Inpainting mask path: white areas are repainted. */
+    mask?: string;
+    providerId?: string;
+}
+
+export async function generateImageVariation(request: ImageVariationRequest): Promise<ImageGenResult> {
+    return invoke("generate_image_variation", { ...request });
 }
 
-export async function generateImage(prompt: string, providerId?: string): Promise<ImageGenResult> {
-    return invoke("generate_image", { prompt, providerId });
+export interface GeneratedImage {
+    id: number;
+    prompt: string;
+    negative_prompt: string | null;
+    provider_id: string;
+    seed: number | null;
+    image_path: string;
+    conversation_id: string | null;
+    character_id: string | null;
+    created_at: number;
+    /** False when the file has since been removed from disk. */
+    file_exists: boolean;
+}
+
+export async function listGeneratedImages(conversationId?: string, limit?: number, offset?: number): Promise<GeneratedImage[]> {
+    return invoke("list_generated_images", { conversationId, limit, offset });
+}
+
+export async function deleteGeneratedImage(id: number): Promise<void> {
+    return invoke("delete_generated_image", { id });
 }
 
 export async function getImageGenConfig(): Promise<ImageGenSystemConfig> {
@@ -777,12 +2060,74 @@ export async function onImageGenError(callback: (error: string) => void): Promis
     return listen<string>("imagegen:error", (e) => callback(e.payload));
 }
 
+export interface ImageGenProgress {
+    provider_id: string;
+    /** 0–100. */
+    percent: number;
+    eta_secs: number | null;
+    step: number | null;
+    steps: number | null;
+    /** Live preview as a data: URL, when the backend renders one. */
+    preview: string | null;
+}
+
+/** Emitted about once a second while a provider that reports progress is generating. */
+export async function onImageGenProgress(callback: (progress: ImageGenProgress) => void): Promise<UnlistenFn> {
+    return listen<ImageGenProgress>("imagegen:progress", (e) => callback(e.payload));
+}
+
 // ── Vision Upload ──────────────────────────────────
 
 export async function uploadVisionImage(fileBytes: number[], filename: string): Promise<string> {
     return invoke<string>("upload_vision_image", { fileBytes, filename });
 }
 
+// ── Local Image Understanding ──────────────────────
+
+export interface ImageDescription {
+    path: string;
+    file_name: string;
+    description: string;
+    /** Served from the description cache without calling the VLM. */
+    cached: boolean;
+}
+
+export interface FolderDescription {
+    folder: string;
+    total_images: number;
+    described: ImageDescription[];
+    failed: { path: string; error: string }[];
+}
+
+export interface ImageBatchProgress {
+    folder: string;
+    done: number;
+    total: number;
+    current: string;
+}
+
+export const IMAGE_FILE_EXTENSIONS = ["png", "jpg", "jpeg", "webp", "gif"];
+
+/** Describe a local image file with the configured VLM; plain descriptions are cached. */
+export async function analyzeImageFile(path: string, question?: string): Promise<ImageDescription> {
+    return invoke<ImageDescription>("analyze_image_file", { path, question: question ?? null });
+}
+
+/** Describe the newest images in a folder (default 20). */
+export async function analyzeImageFolder(path: string, limit?: number): Promise<FolderDescription> {
+    return invoke<FolderDescription>("analyze_image_folder", { path, limit: limit ?? null });
+}
+
+export async function clearImageDescriptions(): Promise<number> {
+    return invoke<number>("clear_image_descriptions");
+}
+
+export async function onImageBatchProgress(
+    callback: (progress: ImageBatchProgress) => void
+): Promise<UnlistenFn> {
+    return listen<ImageBatchProgress>("vision:image-batch-progress", (event) => callback(event.payload));
+}
+
 // ── Vision Config & Watcher ────────────────────────
 
 export interface VisionConfig {
@@ -891,6 +2236,9 @@ export type MemoryUpgradeConfig = {
     readonly dream_auto_apply_level: "conservative" | "review_only" | "aggressive" | string;
     readonly dream_daily_hour: number;
     readonly dream_review_required_for_conflicts: boolean;
+    /** Similarity (0-100) below which a user turn counts as a new topic. */
+    readonly topic_shift_similarity_pct: number;
+    readonly topic_max_turns: number;
 };
 
 export type MemoryObservabilitySummary = {
@@ -1044,6 +2392,13 @@ export async function updateMemoryTier(id: number, tier: string): Promise<void>
     });
 }
 
+/** Pin a message, or the user's rewording of it, as a core memory. Returns the memory id. */
+export async function rememberMessage(characterId: string, content: string, messageId?: number): Promise<number> {
+    return invoke<number>("remember_message", {
+        request: { character_id: characterId, content, message_id: messageId },
+    });
+}
+
 export async function getMemoryUpgradeConfig(): Promise<MemoryUpgradeConfig> {
     return invoke<MemoryUpgradeConfig>("get_memory_upgrade_config");
 }
@@ -1056,6 +2411,18 @@ export async function getMemoryObservabilitySummary(): Promise<MemoryObservabili
     return invoke<MemoryObservabilitySummary>("get_memory_observability_summary");
 }
 
+export interface EmbeddingCacheStats {
+    hits: number;
+    misses: number;
+    entries: number;
+    capacity: number;
+    hit_rate: number;
+}
+
+export async function getEmbeddingCacheStats(): Promise<EmbeddingCacheStats> {
+    return invoke<EmbeddingCacheStats>("get_embedding_cache_stats");
+}
+
 export async function getLatestMemoryWriteEvent(): Promise<MemoryWriteEventRecord | null> {
     return invoke<MemoryWriteEventRecord | null>("get_latest_memory_write_event");
 }
@@ -1132,6 +2499,10 @@ export interface SttConfig {
     continuous_listening: boolean;
     wake_word_enabled: boolean;
     wake_word?: string;
+    /** Stop the character's speech when the native mic hears the user talking. */
+    barge_in?: boolean;
+    /** Label who said what in multi-person recordings (needs a diarizing provider). */
+    diarization?: boolean;
     providers: SttProviderConfig[];
 }
 
@@ -1152,6 +2523,36 @@ export interface SenseVoiceLocalDownloadProgress {
     total_bytes: number | null;
 }
 
+export interface WhisperLocalModelInfo {
+    id: string;
+    download_url: string;
+    model_path: string;
+    approx_size_mb: number;
+    installed: boolean;
+}
+
+export interface WhisperLocalGpuInfo {
+    backend: "cuda" | "vulkan" | "metal" | "cpu" | string;
+    device: string | null;
+    gpu_enabled: boolean;
+    note: string | null;
+}
+
+export interface WhisperLocalModelStatus {
+    install_dir: string;
+    default_model_id: string;
+    models: WhisperLocalModelInfo[];
+    gpu: WhisperLocalGpuInfo;
+}
+
+export interface WhisperLocalDownloadProgress {
+    model_id: string;
+    stage: "downloading" | "complete" | "ready" | string;
+    message: string;
+    downloaded_bytes: number;
+    total_bytes: number | null;
+}
+
 export async function transcribeAudio(audioBytes: number[], format: string): Promise<string> {
     return invoke<string>("transcribe_audio", { audioBytes, format });
 }
@@ -1181,12 +2582,29 @@ export async function onSenseVoiceLocalProgress(
     );
 }
 
+export async function getWhisperLocalStatus(): Promise<WhisperLocalModelStatus> {
+    return invoke<WhisperLocalModelStatus>("get_whisper_local_status");
+}
+
+export async function downloadWhisperLocalModel(model: string): Promise<WhisperLocalModelStatus> {
+    return invoke<WhisperLocalModelStatus>("download_whisper_local_model", { model });
+}
+
+export async function onWhisperLocalProgress(
+    callback: (progress: WhisperLocalDownloadProgress) => void
+): Promise<UnlistenFn> {
+    return listen<WhisperLocalDownloadProgress>(
+        "stt:whisper-local-progress",
+        (event) => callback(event.payload)
+    );
+}
+
 // ── Actions (Tool Calling) ─────────────────────────────
 
 export interface ActionInfo {
     id: string;
     name: string;
-    source: "builtin" | "mcp";
+    source: "builtin" | "mcp" | "mod";
     server_name?: string;
     description: string;
     parameters: { name: string; description: string; required: boolean }[];
@@ -1201,34 +2619,118 @@ export interface ActionResult {
     data?: unknown;
 }
 
-export interface ToolCallEvent {
-    tool: string;
-    tool_id?: string;
-    result?: ActionResult;
-    error?: string;
+export interface ToolCallEvent {
+    tool: string;
+    tool_id?: string;
+    result?: ActionResult;
+    error?: string;
+}
+
+export interface ToolSettings {
+    max_tool_rounds: number;
+    enabled_tools: Record<string, boolean>;
+    max_permission_level: "safe" | "elevated";
+    blocked_risk_tags: ("read" | "write" | "external" | "sensitive")[];
+}
+
+export async function listActions(): Promise<ActionInfo[]> {
+    return invoke<ActionInfo[]>("list_actions");
+}
+
+export async function listBuiltinTools(): Promise<ActionInfo[]> {
+    return invoke<ActionInfo[]>("list_builtin_tools");
+}
+
+export async function executeAction(name: string, args: Record<string, string>, characterId?: string): Promise<ActionResult> {
+    return invoke<ActionResult>("execute_action", { name, args, characterId });
+}
+
+export async function onChatTurnTool(callback: (event: ChatTurnToolEvent) => void): Promise<UnlistenFn> {
+    return listen<ChatTurnToolEvent>("chat-turn-tool", (event) => callback(event.payload));
+}
+
+export type PlanStepStatus = "pending" | "awaiting_confirmation" | "running" | "done" | "failed" | "skipped" | "aborted";
+
+export interface ChatPlanEvent {
+    turn_id: string;
+    plan_id: string;
+    goal: string;
+    status: "running" | "completed" | "failed" | "aborted";
+    steps: {
+        index: number;
+        tool: string;
+        why: string;
+        status: PlanStepStatus;
+        result: string | null;
+    }[];
+}
+
+/** Fired whenever a multi-step tool plan or one of its steps changes. */
+export async function onChatPlan(callback: (event: ChatPlanEvent) => void): Promise<UnlistenFn> {
+    return listen<ChatPlanEvent>("chat-plan", (event) => callback(event.payload));
+}
+
+/** Answer a step waiting for confirmation; declining skips it. */
+export async function confirmPlanStep(planId: string, stepIndex: number, approve: boolean): Promise<void> {
+    return invoke("confirm_plan_step", { planId, stepIndex, approve });
+}
+
+/** Stop a plan before its next step. */
+export async function abortToolPlan(planId: string): Promise<void> {
+    return invoke("abort_tool_plan", { planId });
+}
+
+export interface ToolPlanConfig {
+    /** `risky` asks before steps that write, reach external services or are elevated. */
+    confirmation: "never" | "risky" | "always";
+    max_steps: number;
+}
+
+export async function getToolPlanConfig(): Promise<ToolPlanConfig> {
+    return invoke<ToolPlanConfig>("get_tool_plan_config");
+}
+
+export async function saveToolPlanConfig(config: ToolPlanConfig): Promise<void> {
+    return invoke("save_tool_plan_config", { config });
+}
+
+export interface CodeExecConfig {
+    timeout_ms: number;
+    memory_limit_mb: number;
+    max_output_chars: number;
+    /** `docker` or `podman`; Python snippets are unavailable while unset. */
+    container_runtime: string | null;
+    /** Must already be pulled; the runner never pulls images. */
+    python_image: string;
+}
+
+export async function getCodeExecConfig(): Promise<CodeExecConfig> {
+    return invoke<CodeExecConfig>("get_code_exec_config");
 }
 
-export interface ToolSettings {
-    max_tool_rounds: number;
-    enabled_tools: Record<string, boolean>;
-    max_permission_level: "safe" | "elevated";
-    blocked_risk_tags: ("read" | "write" | "external" | "sensitive")[];
+export async function saveCodeExecConfig(config: CodeExecConfig): Promise<void> {
+    return invoke("save_code_exec_config", { config });
 }
 
-export async function listActions(): Promise<ActionInfo[]> {
-    return invoke<ActionInfo[]>("list_actions");
+export interface CsvTableSummary {
+    name: string;
+    source_path: string;
+    columns: { name: string; kind: "number" | "date" | "text" }[];
+    row_count: number;
+    loaded_at: number;
 }
 
-export async function listBuiltinTools(): Promise<ActionInfo[]> {
-    return invoke<ActionInfo[]>("list_builtin_tools");
+/** Load a CSV for the `query_table` tool; a table with the same name is replaced. */
+export async function loadCsvTable(path: string, name?: string): Promise<CsvTableSummary> {
+    return invoke<CsvTableSummary>("load_csv_table", { path, name: name ?? null });
 }
 
-export async function executeAction(name: string, args: Record<string, string>, characterId?: string): Promise<ActionResult> {
-    return invoke<ActionResult>("execute_action", { name, args, characterId });
+export async function listCsvTables(): Promise<CsvTableSummary[]> {
+    return invoke<CsvTableSummary[]>("list_csv_tables");
 }
 
-export async function onChatTurnTool(callback: (event: ChatTurnToolEvent) => void): Promise<UnlistenFn> {
-    return listen<ChatTurnToolEvent>("chat-turn-tool", (event) => callback(event.payload));
+export async function removeCsvTable(name: string): Promise<void> {
+    return invoke("remove_csv_table", { name });
 }
 
 export async function getToolSettings(): Promise<ToolSettings> {
@@ -1239,6 +2741,45 @@ export async function saveToolSettings(settings: ToolSettings): Promise<void> {
     return invoke("save_tool_settings", { settings });
 }
 
+export interface LatencyBudgetConfig {
+    enabled: boolean;
+    /** Seconds without visible output before `chat-turn-waiting` fires (1–60). */
+    first_token_secs: number;
+    filler_enabled: boolean;
+    /** Custom filler lines; empty uses built-ins in the response language. */
+    fillers: string[];
+}
+
+export async function getLatencyBudgetConfig(): Promise<LatencyBudgetConfig> {
+    return invoke<LatencyBudgetConfig>("get_latency_budget_config");
+}
+
+export async function saveLatencyBudgetConfig(config: LatencyBudgetConfig): Promise<void> {
+    return invoke("save_latency_budget_config", { config });
+}
+
+export type InputQueueMode = "sequential" | "restart";
+
+export interface InputQueueConfig {
+    /** `restart` cancels the running reply and answers everything queued at once. */
+    mode: InputQueueMode;
+    /** Messages allowed to wait per window (1–64). */
+    max_pending: number;
+}
+
+export async function getInputQueueConfig(): Promise<InputQueueConfig> {
+    return invoke<InputQueueConfig>("get_input_queue_config");
+}
+
+export async function saveInputQueueConfig(config: InputQueueConfig): Promise<void> {
+    return invoke("save_input_queue_config", { config });
+}
+
+/** Queue of the calling window. */
+export async function getInputQueueState(): Promise<ChatQueueEvent> {
+    return invoke<ChatQueueEvent>("get_input_queue_state");
+}
+
 export async function approveToolApproval(approvalRequestId: string): Promise<void> {
     return invoke("approve_tool_approval", { approvalRequestId });
 }
@@ -1259,6 +2800,43 @@ export interface McpServerConfig {
     /** HTTP endpoint URL (for streamable_http transport) */
     url?: string;
     enabled: boolean;
+    /** Per-tool overrides keyed by tool name. */
+    tools?: Record<string, McpToolPolicy>;
+    /** Pre-registered OAuth client; without it a client is registered on sign-in. */
+    oauth?: McpOAuthConfig;
+}
+
+export interface McpOAuthConfig {
+    client_id?: string;
+    client_secret?: string;
+    /** Space-separated scopes. */
+    scope?: string;
+}
+
+export interface McpToolPolicy {
+    /** Disabled tools are hidden from the LLM. */
+    enabled: boolean;
+    /** true = run without asking, false = always ask, null = ask for destructive tools. */
+    auto_approve: boolean | null;
+}
+
+export interface McpToolStatus extends McpToolPolicy {
+    server_name: string;
+    tool_name: string;
+    description: string | null;
+    /** Whether the server marks the tool as destructive. */
+    destructive: boolean;
+}
+
+/** Payload of `tool-confirm`: an MCP tool call waiting for the user. */
+export interface McpToolConfirmRequest {
+    request_id: string;
+    server_name: string;
+    tool_name: string;
+    arguments: Record<string, unknown>;
+    character_id: string;
+    source: string | null;
+    timeout_secs: number;
 }
 
 export interface McpServerStatus {
@@ -1269,6 +2847,20 @@ export interface McpServerStatus {
     server_version: string | null;
     status: "connected" | "connecting" | "disconnected";
     error: string | null;
+    /** OAuth state of HTTP servers: signed in, or waiting for the user to sign in. */
+    auth: "authorized" | "required" | null;
+}
+
+/** Payload of `mcp-server-status`, emitted by the backend health monitor. */
+export interface McpServerStatusEvent extends McpServerStatus {
+    /** Failed reconnect attempts since the server dropped. */
+    reconnect_attempts: number;
+    /** Seconds until the next reconnect attempt, while the server is down. */
+    next_retry_secs: number | null;
+}
+
+export async function onMcpServerStatus(callback: (event: McpServerStatusEvent) => void): Promise<UnlistenFn> {
+    return listen<McpServerStatusEvent>("mcp-server-status", (event) => callback(event.payload));
 }
 
 export async function listMcpServers(): Promise<McpServerStatus[]> {
@@ -1291,10 +2883,35 @@ export async function reconnectMcpServer(name: string): Promise<void> {
     return invoke("reconnect_mcp_server", { name });
 }
 
+/** Sign in through the system browser; resolves once the server is reconnecting. */
+export async function authorizeMcpServer(name: string): Promise<void> {
+    return invoke("authorize_mcp_server", { name });
+}
+
+export async function signOutMcpServer(name: string): Promise<void> {
+    return invoke("sign_out_mcp_server", { name });
+}
+
 export async function toggleMcpServer(name: string, enabled: boolean): Promise<void> {
     return invoke("toggle_mcp_server", { name, enabled });
 }
 
+export async function listMcpTools(): Promise<McpToolStatus[]> {
+    return invoke<McpToolStatus[]>("list_mcp_tools");
+}
+
+export async function setMcpToolPolicy(serverName: string, toolName: string, policy: McpToolPolicy): Promise<void> {
+    return invoke("set_mcp_tool_policy", { serverName, toolName, policy });
+}
+
+export async function resolveMcpToolConfirm(requestId: string, approved: boolean): Promise<void> {
+    return invoke("resolve_mcp_tool_confirm", { requestId, approved });
+}
+
+export async function onToolConfirm(callback: (request: McpToolConfirmRequest) => void): Promise<UnlistenFn> {
+    return listen<McpToolConfirmRequest>("tool-confirm", (event) => callback(event.payload));
+}
+
 // ── Conversation History ───────────────────────────────
 
 export interface Conversation {
@@ -1305,9 +2922,11 @@ export interface Conversation {
     pinned_state: string;
     created_at: string;
     updated_at: string;
+    model_override?: ConversationModelOverride | null;
 }
 
 export interface ConversationMessage {
+    id: number;
     role: string;
     content: string;
     metadata?: string;
@@ -1332,6 +2951,41 @@ export async function loadConversation(id: string): Promise<LoadedConversation>
     });
 }
 
+export interface RegenerationTicket {
+    user_message: string;
+    user_metadata?: string | null;
+    variant_group: string;
+    variant_index: number;
+    variant_count: number;
+}
+
+export interface MessageVariantGroup {
+    id: string;
+    anchor_message_id: number;
+    active_index: number;
+    variant_count: number;
+    variants: { index: number; preview: string }[];
+}
+
+export async function regenerateLastResponse(): Promise<RegenerationTicket> {
+    return invoke<RegenerationTicket>("regenerate_last_response");
+}
+
+export async function listMessageVariants(conversationId: string): Promise<MessageVariantGroup[]> {
+    return invoke<MessageVariantGroup[]>("list_message_variants", {
+        request: { id: conversationId },
+    });
+}
+
+export async function switchMessageVariant(
+    variantGroup: string,
+    variantIndex: number
+): Promise<LoadedConversation> {
+    return invoke<LoadedConversation>("switch_message_variant", {
+        request: { variant_group: variantGroup, variant_index: variantIndex },
+    });
+}
+
 export async function updateConversationState(
     id: string,
     patch: { topic?: string; pinned_state?: string }
@@ -1341,6 +2995,66 @@ export async function updateConversationState(
     });
 }
 
+/** A saved point in a conversation; rewinding keeps the later turns as a message variant. */
+export interface ConversationCheckpoint {
+    id: string;
+    conversation_id: string;
+    character_id: string;
+    label: string;
+    message_id: number;
+    message_count: number;
+    emotion?: { emotion: string; intensity: number; mood: number } | null;
+    topic: string;
+    pinned_state: string;
+    created_at: number;
+}
+
+/** Save a checkpoint of the current conversation (or `conversationId`). */
+export async function createCheckpoint(label?: string, conversationId?: string): Promise<ConversationCheckpoint> {
+    return invoke<ConversationCheckpoint>("create_checkpoint", {
+        request: { conversation_id: conversationId ?? null, label: label ?? null },
+    });
+}
+
+export async function listCheckpoints(conversationId?: string): Promise<ConversationCheckpoint[]> {
+    return invoke<ConversationCheckpoint[]>("list_checkpoints", {
+        conversationId: conversationId ?? null,
+    });
+}
+
+export async function rewindToCheckpoint(id: string): Promise<LoadedConversation> {
+    return invoke<LoadedConversation>("rewind_to_checkpoint", { id });
+}
+
+export async function deleteCheckpoint(id: string): Promise<void> {
+    return invoke("delete_checkpoint", { id });
+}
+
+/** Model pinned to one conversation; empty fields fall back to the character binding. */
+export interface ConversationModelOverride {
+    provider_id?: string | null;
+    model?: string | null;
+    temperature?: number | null;
+}
+
+/** Pin a model to a conversation (current one when `conversationId` is omitted); an empty override clears it. */
+export async function setConversationModel(
+    override: ConversationModelOverride,
+    conversationId?: string
+): Promise<void> {
+    return invoke("set_conversation_model", {
+        request: { conversation_id: conversationId ?? null, ...override },
+    });
+}
+
+export async function getConversationModel(
+    conversationId?: string
+): Promise<ConversationModelOverride | null> {
+    return invoke<ConversationModelOverride | null>("get_conversation_model", {
+        conversationId: conversationId ?? null,
+    });
+}
+
 export function hasPinnedConversationState(pinnedState: string): boolean {
     const normalized = pinnedState.trim();
     return normalized !== "" && normalized !== "{}";
@@ -1475,6 +3189,10 @@ export interface TelegramConfig {
     allowed_chat_ids: number[];
     send_voice_reply: boolean;
     character_id?: string;
+    /** Send heartbeat proactive messages to the allowed chats while the desktop is away. */
+    proactive_when_away: boolean;
+    /** Minutes of inactivity after which an unfocused desktop counts as away. */
+    away_after_idle_minutes: number;
 }
 
 export interface TelegramStatus {
@@ -1511,16 +3229,123 @@ export async function getTelegramStatus(): Promise<TelegramStatus> {
     return invoke<TelegramStatus>("get_telegram_status");
 }
 
+export interface TelegramPairingCode {
+    code: string;
+    expires_in_secs: number;
+}
+
+/** Issue a one-time code; sending `/authorize <code>` to the bot whitelists that chat. */
+export async function createTelegramPairingCode(): Promise<TelegramPairingCode> {
+    return invoke<TelegramPairingCode>("create_telegram_pairing_code");
+}
+
+export async function onTelegramChatAuthorized(callback: (data: { chat_id: number }) => void): Promise<UnlistenFn> {
+    return listen<{ chat_id: number }>("telegram:chat-authorized", (event) => callback(event.payload));
+}
+
 export interface TelegramChatSync {
     role: string;
     text: string;
     translation?: string;
+    /** Set when the reply was regenerated and replaces the last assistant message. */
+    replaces_last?: boolean;
 }
 
 export async function onTelegramChatSync(callback: (data: TelegramChatSync) => void): Promise<UnlistenFn> {
     return listen<TelegramChatSync>("telegram:chat-sync", (event) => callback(event.payload));
 }
 
+// ── Local API Server ──────────────────────────────
+
+export interface ApiServerConfig {
+    enabled: boolean;
+    port: number;
+    api_key?: string;
+    api_key_env?: string;
+}
+
+export interface ApiServerStatus {
+    running: boolean;
+    enabled: boolean;
+    port: number | null;
+    has_key: boolean;
+}
+
+export async function getApiServerConfig(): Promise<ApiServerConfig> {
+    return invoke<ApiServerConfig>("get_api_server_config");
+}
+
+export async function saveApiServerConfig(config: ApiServerConfig): Promise<void> {
+    return invoke("save_api_server_config", { config });
+}
+
+export async function startApiServer(): Promise<ApiServerStatus> {
+    return invoke<ApiServerStatus>("start_api_server");
+}
+
+export async function stopApiServer(): Promise<void> {
+    return invoke("stop_api_server");
+}
+
+export async function getApiServerStatus(): Promise<ApiServerStatus> {
+    return invoke<ApiServerStatus>("get_api_server_status");
+}
+
+// ── VTube Studio ──────────────────────────────────
+
+export interface VtsConfig {
+    enabled: boolean;
+    url: string;
+    auth_token: string | null;
+    /** Cue name → VTS hotkey name or ID. */
+    cue_hotkeys: Record<string, string>;
+    match_hotkey_names: boolean;
+    lip_sync_enabled: boolean;
+    mouth_parameter: string;
+    lip_sync_gain: number;
+}
+
+export interface VtsStatus {
+    running: boolean;
+    connected: boolean;
+    model_name: string | null;
+    error: string | null;
+}
+
+export interface VtsHotkey {
+    id: string;
+    name: string;
+    kind: string;
+}
+
+export async function getVtsConfig(): Promise<VtsConfig> {
+    return invoke<VtsConfig>("get_vts_config");
+}
+
+export async function saveVtsConfig(config: VtsConfig): Promise<void> {
+    return invoke("save_vts_config", { config });
+}
+
+export async function startVts(): Promise<VtsStatus> {
+    return invoke<VtsStatus>("start_vts");
+}
+
+export async function stopVts(): Promise<void> {
+    return invoke("stop_vts");
+}
+
+export async function getVtsStatus(): Promise<VtsStatus> {
+    return invoke<VtsStatus>("get_vts_status");
+}
+
+export async function listVtsHotkeys(): Promise<VtsHotkey[]> {
+    return invoke<VtsHotkey[]>("list_vts_hotkeys");
+}
+
+export async function setVtsMouthLevel(level: number): Promise<void> {
+    return invoke("set_vts_mouth_level", { level });
+}
+
 // ── Backup / Restore ──────────────────────────────
 
 export interface BackupStats {
@@ -1577,6 +3402,74 @@ export async function importData(filePath: string, options: ImportOptions): Prom
     return invoke<ImportResult>("import_data", { filePath, options });
 }
 
+// ── Machine Migration ─────────────────────────────
+
+export interface MigrationSection {
+    name: string;
+    files: number;
+    bytes: number;
+}
+
+export interface MigrationManifest {
+    format_version: number;
+    app_version: string;
+    created_at: string;
+    platform: string;
+    sections: MigrationSection[];
+    local_models: boolean;
+}
+
+export interface MigrationProgress {
+    phase: "export" | "import";
+    section: string;
+    done: number;
+    total: number;
+}
+
+export interface MigrationExportResult {
+    path: string;
+    size_bytes: number;
+    manifest: MigrationManifest;
+}
+
+export interface MigrationPreview {
+    manifest: MigrationManifest;
+    /** Why the running version cannot import this archive, if so. */
+    incompatible?: string | null;
+}
+
+export interface MigrationImportResult {
+    restored_files: number;
+    skipped_files: number;
+    restored_bytes: number;
+    /** Local model directories that must be downloaded again, e.g. "stt/whisper_local". */
+    missing_local_models: string[];
+}
+
+/** Export the full app state (a backup plus models, mods and images) for another machine. */
+export async function exportMigration(
+    exportPath: string,
+    charactersJson: string | undefined,
+    includeLocalModels: boolean
+): Promise<MigrationExportResult> {
+    return invoke<MigrationExportResult>("export_migration", { exportPath, charactersJson, includeLocalModels });
+}
+
+export async function previewMigration(filePath: string): Promise<MigrationPreview> {
+    return invoke<MigrationPreview>("preview_migration", { filePath });
+}
+
+/** Restore the directories of a migration archive; import its data with `importData` afterwards. */
+export async function importMigration(filePath: string, overwrite: boolean): Promise<MigrationImportResult> {
+    return invoke<MigrationImportResult>("import_migration", { filePath, overwrite });
+}
+
+export async function onMigrationProgress(
+    callback: (progress: MigrationProgress) => void
+): Promise<UnlistenFn> {
+    return listen<MigrationProgress>("migration:progress", (event) => callback(event.payload));
+}
+
 // ── Character CRUD (SQLite-backed) ────────────────
 
 export interface CharacterRecord {
@@ -1587,12 +3480,24 @@ export interface CharacterRecord {
     source_format: string;
     created_at: number;
     updated_at: number;
+    first_message?: string;
+    avatar_path?: string | null;
+    /** Default Live2D model (relative to the models dir); a profile binding wins. */
+    model_path?: string | null;
+    /** Default TTS voice; a profile binding wins. */
+    voice?: string | null;
+    /** Free-form per-character settings; omitted on update keeps the stored value. */
+    settings?: Record<string, unknown>;
 }
 
 export async function listCharacters(): Promise<CharacterRecord[]> {
     return invoke<CharacterRecord[]>("list_characters");
 }
 
+export async function getCharacter(id: string): Promise<CharacterRecord> {
+    return invoke<CharacterRecord>("get_character", { id });
+}
+
 export async function createCharacter(record: CharacterRecord): Promise<void> {
     return invoke("create_character", { request: record });
 }
@@ -1605,6 +3510,224 @@ export async function deleteCharacter(id: string): Promise<void> {
     return invoke("delete_character", { id });
 }
 
+// ── Character profiles (model / voice / personality bindings) ──
+
+export interface EmotionPersonality {
+    inertia: number;
+    expressiveness: number;
+    default_mood: number;
+    empathy: number;
+}
+
+export interface CharacterProfile {
+    llm_provider_id?: string | null;
+    llm_model?: string | null;
+    temperature?: number | null;
+    tts_provider_id?: string | null;
+    tts_voice?: string | null;
+    personality?: EmotionPersonality | null;
+    /** Quiet hours and frequency caps for idle auto-talk. */
+    proactive?: ProactivePolicy;
+    /** Visual traits (hair, eyes, outfit) that generated selfies must match. */
+    appearance?: string | null;
+    /** Applied to `[IMAGE_PROMPT:]` images and selfies of this character. */
+    image_preset?: ImagePreset;
+    /** Live2D model shown while this character is active; unset keeps the current one. */
+    live2d_model_path?: string | null;
+    /** Mods loaded when this character becomes active. */
+    mods?: string[];
+}
+
+export interface ImagePreset {
+    /** Tags put in front of every prompt. */
+    base_prompt: string;
+    /** Replaces the provider's default negative prompt when set. */
+    negative_prompt: string;
+    /** LoRA / embedding tags, e.g. `<lora:alice:0.8>` (Stable Diffusion only). */
+    lora: string;
+    /** Saved style name (Stable Diffusion) or "vivid" / "natural" (DALL·E). */
+    style: string;
+    /** IP-Adapter reference picture, where the provider supports it. */
+    reference_image: string | null;
+    /** 0–2; defaults to 0.7. */
+    reference_weight: number | null;
+}
+
+export interface QuietHours {
+    /** "HH:MM" local time; a window may wrap past midnight. */
+    start: string;
+    end: string;
+}
+
+export interface ProactivePolicy {
+    quiet_hours: QuietHours[];
+    min_interval_minutes: number;
+    daily_cap: number | null;
+}
+
+export interface CharacterSwitchedEvent {
+    character_id: string;
+    profile: CharacterProfile;
+    personality: EmotionPersonality;
+}
+
+export async function getCharacterProfile(characterId: string): Promise<CharacterProfile> {
+    return invoke<CharacterProfile>("get_character_profile", { characterId });
+}
+
+export async function saveCharacterProfile(characterId: string, profile: CharacterProfile): Promise<void> {
+    return invoke("save_character_profile", { characterId, profile });
+}
+
+export async function deleteCharacterProfile(characterId: string): Promise<void> {
+    return invoke("delete_character_profile", { characterId });
+}
+
+export async function getLlmTraitExtraction(): Promise<boolean> {
+    return invoke<boolean>("get_llm_trait_extraction");
+}
+
+export async function setLlmTraitExtraction(enabled: boolean): Promise<void> {
+    return invoke("set_llm_trait_extraction", { enabled });
+}
+
+export async function onCharacterSwitched(callback: (event: CharacterSwitchedEvent) => void): Promise<UnlistenFn> {
+    return listen<CharacterSwitchedEvent>("character-switched", (event) => callback(event.payload));
+}
+
+/** Result of `switchCharacter`, also broadcast as `character:switched`. */
+export interface CharacterActivatedEvent {
+    character_id: string;
+    name: string;
+    /** System prompt composed from the persona and the user profile. */
+    persona: string;
+    profile: CharacterProfile;
+    personality: EmotionPersonality;
+    emotion: EmotionState;
+    /** True when the character picked up the emotion it had when last active. */
+    emotion_restored: boolean;
+    live2d_model_path: string | null;
+    /** Mods that loaded for this character. */
+    mods: string[];
+}
+
+/** Switch persona, bindings, memory scope, emotion, Live2D model and mods in one step. */
+export async function switchCharacter(characterId: string): Promise<CharacterActivatedEvent> {
+    return invoke<CharacterActivatedEvent>("switch_character", { characterId });
+}
+
+export async function onCharacterActivated(callback: (event: CharacterActivatedEvent) => void): Promise<UnlistenFn> {
+    return listen<CharacterActivatedEvent>("character:switched", (event) => callback(event.payload));
+}
+
+export interface ImportCharacterCardRequest {
+    path?: string;
+    data?: number[];
+    activate?: boolean;
+}
+
+export type CharacterCardFormat = "png" | "json" | "charx";
+
+export async function importCharacterCard(request: ImportCharacterCardRequest): Promise<CharacterRecord> {
+    return invoke<CharacterRecord>("import_character_card", { request });
+}
+
+export async function exportCharacterCard(id: string, format: CharacterCardFormat, outputPath: string): Promise<string> {
+    return invoke<string>("export_character_card", { id, format, outputPath });
+}
+
+export type PersonaTrait = "energy" | "warmth" | "expressiveness" | "stability" | "confidence";
+
+export interface PersonaIssue {
+    severity: "info" | "warning" | "error";
+    code: string;
+    message: string;
+    excerpt?: string | null;
+}
+
+export interface PersonaReport {
+    char_count: number;
+    estimated_tokens: number;
+    traits: { trait: PersonaTrait; keyword: string; direction: number; negated: boolean; excerpt: string }[];
+    languages_mentioned: string[];
+    has_language_guidance: boolean;
+    has_style_guidance: boolean;
+    placeholders: string[];
+    issues: PersonaIssue[];
+}
+
+export async function analyzePersona(persona: string): Promise<PersonaReport> {
+    return invoke<PersonaReport>("analyze_persona", { persona });
+}
+
+// ── Lorebook / World Info ──────────────────────────
+
+export interface LorebookEntry {
+    /** 0 creates a new entry on upsert. */
+    id: number;
+    keys: string[];
+    secondary_keys: string[];
+    selective: boolean;
+    content: string;
+    enabled: boolean;
+    constant: boolean;
+    case_sensitive?: boolean | null;
+    insertion_order: number;
+    [extra: string]: unknown;
+}
+
+export interface Lorebook {
+    scan_depth: number;
+    token_budget: number;
+    recursive_scanning: boolean;
+    recursion_depth?: number | null;
+    entries: LorebookEntry[];
+    [extra: string]: unknown;
+}
+
+export async function getLorebook(characterId: string): Promise<Lorebook> {
+    return invoke<Lorebook>("get_lorebook", { characterId });
+}
+
+export async function saveLorebook(characterId: string, book: Lorebook): Promise<Lorebook> {
+    return invoke<Lorebook>("save_lorebook", { characterId, book });
+}
+
+export async function upsertLorebookEntry(characterId: string, entry: LorebookEntry): Promise<LorebookEntry> {
+    return invoke<LorebookEntry>("upsert_lorebook_entry", { characterId, entry });
+}
+
+export async function deleteLorebookEntry(characterId: string, entryId: number): Promise<void> {
+    return invoke<void>("delete_lorebook_entry", { characterId, entryId });
+}
+
+// ── Forms of Address ───────────────────────────────
+
+export type AddressTarget = "user" | "character";
+
+export interface AddressForm {
+    form: string;
+    /** "learned" from chat, or "manual" when corrected in the UI. */
+    source: "learned" | "manual";
+    updated_at: number;
+}
+
+export interface RelationshipState {
+    /** What the character calls the user. */
+    user_address?: AddressForm | null;
+    /** What the user calls the character. */
+    character_address?: AddressForm | null;
+}
+
+export async function getAddressForms(characterId: string): Promise<RelationshipState> {
+    return invoke<RelationshipState>("get_address_forms", { characterId });
+}
+
+/** Correct a learned form of address; an empty `form` clears it. */
+export async function setAddressForm(characterId: string, target: AddressTarget, form: string | null): Promise<RelationshipState> {
+    return invoke<RelationshipState>("set_address_form", { characterId, target, form });
+}
+
 // ── Auto Backup ────────────────────────────────────
 
 export interface AutoBackupConfig {
@@ -0,0 +1,37 @@
+// pattern: Functional Core
+import { describe, expect, it } from "vitest";
+import { VisemeTrack } from "./viseme-track";
+
+describe("VisemeTrack", () => {
+    it("samples the frame playing at a given time across chunks", () => {
+        const track = new VisemeTrack();
+        track.push({
+            offset_ms: 0,
+            duration_ms: 80,
+            source: "audio",
+            frames: [
+                { t: 0, viseme: "a", open: 1 },
+                { t: 40, viseme: "i", open: 0.5 },
+            ],
+        });
+        track.push({
+            offset_ms: 80,
+            duration_ms: 40,
+            source: "text",
+            frames: [{ t: 80, viseme: "o", open: 1 }],
+        });
+
+        expect(track.sample(10)).toEqual({ open: 1, form: 0 });
+        expect(track.sample(50)).toEqual({ open: 0.175, form: -0.8 });
+        expect(track.sample(119)).toEqual({ open: 0.7, form: 0.9 });
+        expect(track.sample(120)).toBeNull();
+    });
+
+    it("returns null once cleared", () => {
+        const track = new VisemeTrack();
+        track.push({ offset_ms: 0, duration_ms: 40, source: "text", frames: [{ t: 0, viseme: "u", open: 1 }] });
+        track.clear();
+        expect(track.isEmpty).toBe(true);
+        expect(track.sample(0)).toBeNull();
+    });
+});
@@ -0,0 +1,93 @@
+// pattern: Functional Core
+
+export type Viseme = "sil" | "a" | "i" | "u" | "e" | "o";
+
+export interface VisemeFrame {
+    /** Milliseconds since the utterance's audio started. */
+    t: number;
+    viseme: Viseme;
+    /** Mouth opening (0-1). */
+    open: number;
+}
+
+/** Payload of `tts:visemes`, emitted right after each `tts:audio` chunk. */
+export interface TtsVisemesEvent {
+    offset_ms: number;
+    duration_ms: number;
+    /** "audio" when opening follows the decoded envelope, "text" when estimated. */
+    source: "audio" | "text";
+    frames: VisemeFrame[];
+}
+
+export interface MouthShape {
+    /** Jaw open amount (0-1). */
+    open: number;
+    /** -1 = wide/smile, +1 = round/O, matching ParamMouthForm. */
+    form: number;
+}
+
+/** Jaw ceiling and ParamMouthForm per vowel. */
+const SHAPES: Record<Viseme, MouthShape> = {
+    sil: { open: 0, form: 0 },
+    a: { open: 1, form: 0 },
+    i: { open: 0.35, form: -0.8 },
+    u: { open: 0.3, form: 0.7 },
+    e: { open: 0.6, form: -0.5 },
+    o: { open: 0.7, form: 0.9 },
+};
+
+/** Frame spacing used by the backend. */
+const FRAME_MS = 40;
+
+/**
+ * Viseme timeline for the utterance currently playing, looked up by
+ * playback position.
+ */
+export class VisemeTrack {
+    private frames: VisemeFrame[] = [];
+
+    push(event: TtsVisemesEvent) {
+        this.frames.push(...event.frames);
+    }
+
+    clear() {
+        this.frames = [];
+    }
+
+    get isEmpty(): boolean {
+        return this.frames.length === 0;
+    }
+
+    /** Mouth shape at `timeMs`, or null when the timeline doesn't cover it. */
+    sample(timeMs: number): MouthShape | null {
+        const frames = this.frames;
+        if (frames.length === 0 || timeMs < frames[0].t) {
+            return null;
+        }
+        const last = frames[frames.length - 1];
+        if (timeMs >= last.t + FRAME_MS) {
+            return null;
+        }
+
+        // Frames arrive in order, so binary search for the last one at or before timeMs.
+        let lo = 0;
+        let hi = frames.length - 1;
+        while (lo < hi) {
+            const mid = (lo + hi + 1) >> 1;
+            if (frames[mid].t <= timeMs) {
+                lo = mid;
+            } else {
+                hi = mid - 1;
+            }
+        }
+
+        const frame = frames[lo];
+        const shape = SHAPES[frame.viseme] ?? SHAPES.a;
+        return {
+            open: Math.min(Math.max(frame.open, 0), 1) * shape.open,
+            form: shape.form,
+        };
+    }
+}
+
+export const visemeTrack = new VisemeTrack();
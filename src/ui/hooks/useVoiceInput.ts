@@ -3,7 +3,6 @@ import { invoke } from "@tauri-apps/api/core";
 import { listen, type UnlistenFn } from "@tauri-apps/api/event";
 import { isTauriEnvironment } from "../../utils/env";
 
-const SNAPSHOT_INTERVAL = 2000; // 2s: ultra-fast provisional feedback
 const FLUSH_WINDOW = 25000;      // 25s: force commit
 const OVERLAP_SECONDS = 1.0;
 const SAMPLE_RATE = 16000;
@@ -54,13 +53,16 @@ export function useVoiceInput(
     const nativeVolumeUnlisten = useRef<UnlistenFn | null>(null);
     const nativeAutoStopUnlisten = useRef<UnlistenFn | null>(null);
     const captureTransport = useRef<CaptureTransport | null>(null);
+    const vadUtteranceUnlisten = useRef<UnlistenFn | null>(null);
 
-    const snapshotTimer = useRef<number | null>(null);
+    const partialUnlisten = useRef<UnlistenFn | null>(null);
     const lastFlushTime = useRef<number>(0);
     const isRunning = useRef(false);
 
     // VAD auto-stop state
     const autoStopRef = useRef(false);
+    // Hands-free: backend VAD finalizes each utterance, capture keeps running
+    const handsFreeRef = useRef(false);
     const speechDetected = useRef(false);
     const silenceSinceMsRef = useRef<number | null>(null);
     const stopRef = useRef<(() => Promise<void>) | null>(null);
@@ -115,6 +117,28 @@ export function useVoiceInput(
             .catch(err => console.warn("Audio drop:", err));
     }, [handleSpeechActivity]);
 
+    const enableStreamVad = useCallback(async () => {
+        await invoke("set_stream_vad", { enabled: true });
+        vadUtteranceUnlisten.current = await listen<TranscriptionResult>("stt:vad-utterance", (event) => {
+            if (!isRunning.current) return;
+            const text = event.payload.text.trim();
+            segmentsRef.current = [];
+            lastFlushTime.current = Date.now();
+            setPartialText("");
+            if (text) {
+                onFinalTranscription(text);
+            }
+        });
+    }, [onFinalTranscription]);
+
+    const disableStreamVad = useCallback(async () => {
+        if (vadUtteranceUnlisten.current) {
+            vadUtteranceUnlisten.current();
+            vadUtteranceUnlisten.current = null;
+        }
+        await invoke("set_stream_vad", { enabled: false });
+    }, []);
+
     const cleanupWebCapture = useCallback(async () => {
         if (mediaStream.current) {
             mediaStream.current.getTracks().forEach(t => t.stop());
@@ -212,13 +236,11 @@ export function useVoiceInput(
 
     // ── Streaming Logic ───────────────────────────────────────────────
 
-    const performSnapshot = useCallback(async () => {
+    // The backend re-decodes the growing buffer and pushes `stt:partial`.
+    const applyPartial = useCallback(async (result: TranscriptionResult) => {
         if (!isRunning.current) return;
 
         try {
-            // Get partial result
-            const result = await invoke<TranscriptionResult>("snapshot_audio_stream");
-
             // Merge logic:
             // 1. We have existing `segmentsRef.current`.
             // 2. We receive `result.segments` which has absolute timestamps (thanks to backend fix).
@@ -269,19 +291,23 @@ export function useVoiceInput(
             }
 
         } catch (e) {
-            console.error("Snapshot failed:", e);
+            console.error("Partial transcription failed:", e);
         }
     }, [onPartialTranscription]);
 
     // ── Start/Stop Control ────────────────────────────────────────────
 
-    const start = useCallback(async (opts?: { autoStopOnSilence?: boolean }) => {
+    const start = useCallback(async (opts?: { autoStopOnSilence?: boolean; handsFree?: boolean }) => {
         if (state !== VoiceState.Idle) return;
-        const shouldAutoStop = opts?.autoStopOnSilence ?? false;
-        autoStopRef.current = shouldAutoStop;
+        const handsFree = opts?.handsFree ?? false;
+        handsFreeRef.current = handsFree;
+        autoStopRef.current = !handsFree && (opts?.autoStopOnSilence ?? false);
 
         try {
             await invoke("discard_audio_stream");
+            if (handsFree) {
+                await enableStreamVad();
+            }
 
             if (isTauriEnvironment()) {
                 try {
@@ -303,25 +329,30 @@ export function useVoiceInput(
             setPartialText("");
             segmentsRef.current = []; // Reset history
 
-            // Start Snapshot Loop
-            snapshotTimer.current = window.setInterval(performSnapshot, SNAPSHOT_INTERVAL);
+            // Live captions
+            partialUnlisten.current = await listen<TranscriptionResult>("stt:partial", (event) => {
+                applyPartial(event.payload);
+            });
 
         } catch (err) {
             console.error("Failed to start mic:", err);
             await stopCapture().catch(() => undefined);
+            if (handsFree) {
+                await disableStreamVad().catch(() => undefined);
+                handsFreeRef.current = false;
+            }
             captureTransport.current = null;
             setState(VoiceState.Error);
         }
-    }, [state, performSnapshot, startNativeCapture, startWebCapture, stopCapture]);
+    }, [state, applyPartial, startNativeCapture, startWebCapture, stopCapture, enableStreamVad, disableStreamVad]);
 
     const stop = useCallback(async () => {
         if (!isRunning.current) return; // Guard against double-stop
         isRunning.current = false;
 
-        // cleanup timers
-        if (snapshotTimer.current) {
-            clearInterval(snapshotTimer.current);
-            snapshotTimer.current = null;
+        if (partialUnlisten.current) {
+            partialUnlisten.current();
+            partialUnlisten.current = null;
         }
 
         try {
@@ -330,6 +361,11 @@ export function useVoiceInput(
             console.error("Failed to stop capture transport:", e);
         }
 
+        if (handsFreeRef.current) {
+            handsFreeRef.current = false;
+            await disableStreamVad().catch(e => console.error("Failed to stop stream VAD:", e));
+        }
+
         // Finalize: get last bit
         try {
             const result = await invoke<TranscriptionResult>("complete_audio_stream");
@@ -360,7 +396,7 @@ export function useVoiceInput(
         setPartialText("");
         segmentsRef.current = [];
 
-    }, [onFinalTranscription, stopCapture]);
+    }, [onFinalTranscription, stopCapture, disableStreamVad]);
 
     // Keep stopRef in sync so VAD auto-stop can call the latest stop()
     useEffect(() => {
@@ -91,6 +91,9 @@ const ALLOWED_MOD_COMMANDS = new Set([
     'play_motion',
     'get_live2d_model_profile',
     'save_live2d_model_profile',
+    // Emotion state
+    'get_emotion_state',
+    'set_emotion',
     // Settings (scoped to MOD namespace)
     'get_mod_setting',
     'set_mod_setting',
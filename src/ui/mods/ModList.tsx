@@ -1,10 +1,23 @@
 import { useEffect, useState, useCallback } from "react";
 import { motion, AnimatePresence } from "framer-motion";
 import { clsx } from "clsx";
-import { RefreshCw, Play, Eye, EyeOff, Package, AlertCircle, Import, RotateCcw } from "lucide-react";
-import { listMods, loadMod, installMod, unloadMod, type ModManifest } from "../../lib/kokoro-bridge";
-import { open } from "@tauri-apps/plugin-dialog";
+import { RefreshCw, Play, Eye, EyeOff, Package, AlertCircle, Import, RotateCcw, ShieldCheck, Globe, Share } from "lucide-react";
+import {
+    listMods,
+    loadMod,
+    installMod,
+    exportMod,
+    unloadMod,
+    getModPermissions,
+    setModPermissions,
+    requestedModPermissions,
+    type ModManifest,
+    type ModPermission,
+} from "../../lib/kokoro-bridge";
+import { open, save } from "@tauri-apps/plugin-dialog";
 import { IframeSandbox } from "./IframeSandbox";
+import { ModPermissionReview } from "./ModPermissionReview";
+import { ModRegistryBrowser } from "./ModRegistryBrowser";
 import { modUrl } from "../../lib/utils";
 import { useTranslation, Trans } from "react-i18next";
 
@@ -14,6 +27,9 @@ export function ModList() {
     const [loading, setLoading] = useState(false);
     const [activeUIMods, setActiveUIMods] = useState<string[]>([]);
     const [loadingMod, setLoadingMod] = useState<string | null>(null);
+    const [grants, setGrants] = useState<Record<string, ModPermission[]>>({});
+    const [reviewing, setReviewing] = useState<string | null>(null);
+    const [browsingRegistry, setBrowsingRegistry] = useState(false);
     const [status, setStatus] = useState<{ text: string; type: "success" | "error" | "info" } | null>(null);
 
     const showStatus = useCallback((text: string, type: "success" | "error" | "info" = "info") => {
@@ -24,8 +40,9 @@ export function ModList() {
     const refreshMods = async () => {
         setLoading(true);
         try {
-            const list = await listMods();
+            const [list, granted] = await Promise.all([listMods(), getModPermissions()]);
             setMods(list);
+            setGrants(granted);
             setStatus(null);
         } catch (e) {
             console.error("Failed to list mods:", e);
@@ -68,7 +85,7 @@ export function ModList() {
                 multiple: false,
                 filters: [{
                     name: 'Mod Archive',
-                    extensions: ['zip']
+                    extensions: ['kokoromod', 'zip']
                 }]
             });
 
@@ -77,7 +94,10 @@ export function ModList() {
                 // installMod returns the manifest of the installed mod
                 const manifest = await installMod(selected);
                 showStatus(t("mods.status.installed", { name: manifest.name }), "success");
-                refreshMods();
+                await refreshMods();
+                if (requestedModPermissions(manifest).length > 0) {
+                    setReviewing(manifest.id);
+                }
             }
         } catch (e) {
             console.error("Failed to import mod:", e);
@@ -85,6 +105,41 @@ export function ModList() {
         }
     };
 
+    const handleRegistryInstalled = async (manifest: ModManifest) => {
+        showStatus(t("mods.status.installed", { name: manifest.name }), "success");
+        await refreshMods();
+        if (requestedModPermissions(manifest).length > 0) {
+            setReviewing(manifest.id);
+        }
+    };
+
+    const handleExport = async (mod: ModManifest) => {
+        try {
+            const filePath = await save({
+                defaultPath: `${mod.id}-${mod.version}.kokoromod`,
+                filters: [{ name: 'Kokoro Mod', extensions: ['kokoromod'] }],
+            });
+            if (!filePath) return;
+            const result = await exportMod(mod.id, filePath);
+            showStatus(t("mods.status.exported", { path: result.path }), "success");
+        } catch (e) {
+            console.error("Failed to export mod:", e);
+            showStatus(typeof e === 'string' ? e : t("mods.status.failed_export"), "error");
+        }
+    };
+
+    const handleSavePermissions = async (id: string, granted: ModPermission[]) => {
+        try {
+            await setModPermissions(id, granted);
+            setGrants(prev => ({ ...prev, [id]: granted }));
+            setReviewing(null);
+            showStatus(t("mods.status.permissions_saved"), "success");
+        } catch (e) {
+            console.error("Failed to save mod permissions:", e);
+            showStatus(typeof e === 'string' ? e : t("mods.status.failed_permissions"), "error");
+        }
+    };
+
     const toggleUI = (mod: ModManifest) => {
         if (!mod.ui_entry) return;
         if (activeUIMods.includes(mod.id)) {
@@ -151,6 +206,20 @@ export function ModList() {
                         <Import size={12} strokeWidth={1.5} />
                         {t("mods.actions.import")}
                     </motion.button>
+                    <motion.button
+                        whileHover={{ scale: 1.05 }}
+                        whileTap={{ scale: 0.95 }}
+                        onClick={() => setBrowsingRegistry(prev => !prev)}
+                        className={clsx(
+                            "flex items-center gap-1 px-3 py-1.5 rounded bg-[var(--color-bg-elevated)] border text-xs transition-colors",
+                            browsingRegistry
+                                ? "border-[var(--color-accent)] text-[var(--color-accent)]"
+                                : "border-[var(--color-border)] text-[var(--color-text-secondary)] hover:text-[var(--color-accent)] hover:border-[var(--color-accent)]"
+                        )}
+                    >
+                        <Globe size={12} strokeWidth={1.5} />
+                        {t("mods.registry.title")}
+                    </motion.button>
                     <motion.button
                         whileHover={{ scale: 1.1 }}
                         whileTap={{ scale: 0.95 }}
@@ -167,6 +236,15 @@ export function ModList() {
                 </div>
             </div>
 
+            <AnimatePresence>
+                {browsingRegistry && (
+                    <ModRegistryBrowser
+                        onInstalled={handleRegistryInstalled}
+                        onError={message => showStatus(message, "error")}
+                    />
+                )}
+            </AnimatePresence>
+
             {/* Mod List */}
             <div className="flex-1 overflow-y-auto p-4 space-y-3 scrollable">
                 <AnimatePresence initial={false}>
@@ -210,6 +288,16 @@ export function ModList() {
                                             </span>
                                         </motion.button>
                                     )}
+                                    <motion.button
+                                        whileHover={{ scale: 1.05 }}
+                                        whileTap={{ scale: 0.95 }}
+                                        onClick={() => handleExport(mod)}
+                                        className="p-1.5 rounded-md text-[var(--color-text-muted)] hover:text-[var(--color-accent)] transition-colors"
+                                        aria-label={t("mods.actions.export")}
+                                        title={t("mods.actions.export")}
+                                    >
+                                        <Share size={14} strokeWidth={1.5} />
+                                    </motion.button>
                                     {mod.ui_entry && (
                                         <motion.button
                                             whileHover={{ scale: 1.05 }}
@@ -261,8 +349,33 @@ export function ModList() {
                                         {t("mods.badges.components", { count: Object.keys(mod.components).length })}
                                     </span>
                                 )}
+                                {requestedModPermissions(mod).length > 0 && (
+                                    <button
+                                        onClick={() => setReviewing(reviewing === mod.id ? null : mod.id)}
+                                        className="flex items-center gap-1 px-1.5 py-0.5 text-[9px] font-mono uppercase tracking-wider rounded bg-rose-900/40 border border-rose-500/30 text-rose-300 hover:border-rose-400"
+                                        title={t("mods.permissions.review")}
+                                    >
+                                        <ShieldCheck size={9} strokeWidth={2} />
+                                        {t("mods.badges.permissions", {
+                                            granted: (grants[mod.id] ?? []).filter(p => requestedModPermissions(mod).includes(p)).length,
+                                            count: requestedModPermissions(mod).length,
+                                        })}
+                                    </button>
+                                )}
                             </div>
 
+                            <AnimatePresence>
+                                {reviewing === mod.id && (
+                                    <ModPermissionReview
+                                        requested={requestedModPermissions(mod)}
+                                        granted={grants[mod.id] ?? []}
+                                        hosts={mod.network_allowlist ?? []}
+                                        onSave={granted => handleSavePermissions(mod.id, granted)}
+                                        onCancel={() => setReviewing(null)}
+                                    />
+                                )}
+                            </AnimatePresence>
+
                             {/* Mod UI iframe */}
                             <AnimatePresence>
                                 {activeUIMods.includes(mod.id) && mod.ui_entry && (
@@ -0,0 +1,85 @@
+import { useState } from "react";
+import { motion } from "framer-motion";
+import { clsx } from "clsx";
+import { ShieldCheck } from "lucide-react";
+import { useTranslation } from "react-i18next";
+import type { ModPermission } from "../../lib/kokoro-bridge";
+
+interface ModPermissionReviewProps {
+    requested: ModPermission[];
+    granted: ModPermission[];
+    /** Manifest `network_allowlist`, shown under the network permission. */
+    hosts?: string[];
+    onSave: (granted: ModPermission[]) => void;
+    onCancel: () => void;
+}
+
+/** Lets the user grant some or all of the permissions a mod requests. */
+export function ModPermissionReview({ requested, granted, hosts = [], onSave, onCancel }: ModPermissionReviewProps) {
+    const { t } = useTranslation();
+    const [selected, setSelected] = useState<ModPermission[]>(granted.filter(p => requested.includes(p)));
+
+    const toggle = (permission: ModPermission) => {
+        setSelected(prev =>
+            prev.includes(permission) ? prev.filter(p => p !== permission) : [...prev, permission]
+        );
+    };
+
+    return (
+        <motion.div
+            initial={{ height: 0, opacity: 0 }}
+            animate={{ height: "auto", opacity: 1 }}
+            exit={{ height: 0, opacity: 0 }}
+            className="mt-3 space-y-2 overflow-hidden rounded-md border border-[var(--color-border-accent)] p-3"
+        >
+            <p className="flex items-center gap-1.5 text-xs font-semibold text-[var(--color-text-primary)]">
+                <ShieldCheck size={12} strokeWidth={1.5} className="text-[var(--color-accent)]" />
+                {t("mods.permissions.review")}
+            </p>
+            <ul className="space-y-1.5">
+                {requested.map(permission => (
+                    <li key={permission}>
+                        <label className="flex cursor-pointer items-start gap-2 text-xs">
+                            <input
+                                type="checkbox"
+                                checked={selected.includes(permission)}
+                                onChange={() => toggle(permission)}
+                                className="mt-0.5"
+                            />
+                            <span>
+                                <span className="font-mono text-[var(--color-text-secondary)]">{permission}</span>
+                                <span className="block text-[10px] text-[var(--color-text-muted)]">
+                                    {t(`mods.permissions.desc.${permission}`)}
+                                </span>
+                                {permission === "network" && (
+                                    <span className="block text-[10px] text-[var(--color-text-muted)]">
+                                        {hosts.length > 0
+                                            ? t("mods.permissions.hosts", { hosts: hosts.join(", ") })
+                                            : t("mods.permissions.no_hosts")}
+                                    </span>
+                                )}
+                            </span>
+                        </label>
+                    </li>
+                ))}
+            </ul>
+            <div className="flex justify-end gap-2">
+                <button
+                    onClick={onCancel}
+                    className="px-2 py-1 text-[10px] uppercase tracking-wider text-[var(--color-text-muted)] hover:text-[var(--color-text-primary)]"
+                >
+                    {t("mods.permissions.cancel")}
+                </button>
+                <button
+                    onClick={() => onSave(selected)}
+                    className={clsx(
+                        "px-3 py-1 text-[10px] uppercase tracking-wider rounded-md",
+                        "bg-[var(--color-accent)] text-black hover:bg-white transition-colors"
+                    )}
+                >
+                    {t("mods.permissions.save")}
+                </button>
+            </div>
+        </motion.div>
+    );
+}
@@ -0,0 +1,137 @@
+import { useState } from "react";
+import { motion } from "framer-motion";
+import { clsx } from "clsx";
+import { Download, Search } from "lucide-react";
+import { useTranslation } from "react-i18next";
+import {
+    browseModRegistry,
+    installModFromUrl,
+    type ModManifest,
+    type ModRegistryListing,
+} from "../../lib/kokoro-bridge";
+
+const REGISTRY_URL_KEY = "kokoro.modRegistryUrl";
+
+interface ModRegistryBrowserProps {
+    onInstalled: (manifest: ModManifest) => void;
+    onError: (message: string) => void;
+}
+
+/** Lists the mods of an online registry and installs them in one click. */
+export function ModRegistryBrowser({ onInstalled, onError }: ModRegistryBrowserProps) {
+    const { t } = useTranslation();
+    const [url, setUrl] = useState(() => localStorage.getItem(REGISTRY_URL_KEY) ?? "");
+    const [listings, setListings] = useState<ModRegistryListing[] | null>(null);
+    const [browsing, setBrowsing] = useState(false);
+    const [installing, setInstalling] = useState<string | null>(null);
+
+    const browse = async () => {
+        if (!url.trim()) return;
+        setBrowsing(true);
+        try {
+            setListings(await browseModRegistry(url.trim()));
+            localStorage.setItem(REGISTRY_URL_KEY, url.trim());
+        } catch (e) {
+            console.error("Failed to browse mod registry:", e);
+            onError(typeof e === "string" ? e : t("mods.registry.failed_browse"));
+        } finally {
+            setBrowsing(false);
+        }
+    };
+
+    const install = async (listing: ModRegistryListing) => {
+        setInstalling(listing.id);
+        try {
+            const manifest = await installModFromUrl(listing.id, listing.download_url, listing.sha256);
+            setListings(prev => prev?.map(l => l.id === listing.id ? { ...l, installed_version: manifest.version } : l) ?? null);
+            onInstalled(manifest);
+        } catch (e) {
+            console.error("Failed to install mod from registry:", e);
+            onError(typeof e === "string" ? e : t("mods.registry.failed_install", { name: listing.name }));
+        } finally {
+            setInstalling(null);
+        }
+    };
+
+    return (
+        <motion.div
+            initial={{ height: 0, opacity: 0 }}
+            animate={{ height: "auto", opacity: 1 }}
+            exit={{ height: 0, opacity: 0 }}
+            className="overflow-hidden border-b border-[var(--color-border)]"
+        >
+            <div className="p-4 space-y-3">
+                <div className="flex gap-2">
+                    <input
+                        value={url}
+                        onChange={e => setUrl(e.target.value)}
+                        onKeyDown={e => e.key === "Enter" && browse()}
+                        placeholder={t("mods.registry.url_placeholder")}
+                        className="flex-1 min-w-0 px-2 py-1.5 rounded bg-[var(--color-bg-elevated)] border border-[var(--color-border)] text-xs font-mono text-[var(--color-text-primary)] focus:outline-none focus:border-[var(--color-accent)]"
+                    />
+                    <button
+                        onClick={browse}
+                        disabled={browsing || !url.trim()}
+                        className={clsx(
+                            "flex items-center gap-1 px-3 py-1.5 rounded text-xs",
+                            "bg-[var(--color-bg-elevated)] border border-[var(--color-border)] text-[var(--color-text-secondary)] hover:text-[var(--color-accent)] hover:border-[var(--color-accent)] transition-colors",
+                            (browsing || !url.trim()) && "opacity-50 cursor-not-allowed"
+                        )}
+                    >
+                        <Search size={12} strokeWidth={1.5} />
+                        {t("mods.registry.browse")}
+                    </button>
+                </div>
+
+                {listings?.length === 0 && (
+                    <p className="text-xs text-[var(--color-text-muted)]">{t("mods.registry.empty")}</p>
+                )}
+                {listings && listings.length > 0 && (
+                    <ul className="space-y-2 max-h-64 overflow-y-auto scrollable">
+                        {listings.map(listing => {
+                            const upToDate = listing.installed_version === listing.version;
+                            return (
+                                <li
+                                    key={listing.id}
+                                    className="flex items-start justify-between gap-2 p-2 rounded-md bg-[var(--color-bg-elevated)] border border-[var(--color-border)]"
+                                >
+                                    <div className="min-w-0">
+                                        <p className="text-xs font-semibold text-[var(--color-text-primary)] truncate">
+                                            {listing.name}
+                                            <span className="ml-1.5 text-[10px] font-mono font-normal text-[var(--color-text-muted)]">
+                                                v{listing.version}{listing.author ? ` · ${listing.author}` : ""}
+                                            </span>
+                                        </p>
+                                        {listing.description && (
+                                            <p className="text-[10px] text-[var(--color-text-muted)] mt-0.5">{listing.description}</p>
+                                        )}
+                                    </div>
+                                    <button
+                                        onClick={() => install(listing)}
+                                        disabled={upToDate || installing !== null}
+                                        className={clsx(
+                                            "shrink-0 flex items-center gap-1 px-2 py-1 rounded text-[10px] uppercase tracking-wider",
+                                            upToDate
+                                                ? "text-[var(--color-text-muted)]"
+                                                : "bg-[var(--color-accent)] text-black hover:bg-white transition-colors",
+                                            installing !== null && !upToDate && "opacity-50 cursor-not-allowed"
+                                        )}
+                                    >
+                                        <Download size={10} strokeWidth={2} />
+                                        {installing === listing.id
+                                            ? t("mods.actions.loading")
+                                            : upToDate
+                                                ? t("mods.registry.installed")
+                                                : listing.installed_version
+                                                    ? t("mods.registry.update")
+                                                    : t("mods.registry.install")}
+                                    </button>
+                                </li>
+                            );
+                        })}
+                    </ul>
+                )}
+            </div>
+        </motion.div>
+    );
+}
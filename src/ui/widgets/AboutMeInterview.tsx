@@ -0,0 +1,184 @@
+import { useEffect, useState } from "react";
+import { AnimatePresence, motion } from "framer-motion";
+import { clsx } from "clsx";
+import { Loader2, MessageCircleHeart, X } from "lucide-react";
+import { useTranslation } from "react-i18next";
+import { answerOnboardingQuestion, dismissOnboarding } from "../../lib/kokoro-bridge";
+import type { OnboardingQuestion, OnboardingStatus } from "../../lib/kokoro-bridge";
+
+export const ABOUT_ME_INTERVIEW_EVENT = "kokoro:open-about-me-interview";
+
+/** Ask the app to reload the interview status and show it if unfinished. */
+export function requestAboutMeInterview(): void {
+    if (typeof window === "undefined") {
+        return;
+    }
+
+    window.dispatchEvent(new CustomEvent(ABOUT_ME_INTERVIEW_EVENT));
+}
+
+/** Questions with longer, free-form answers. */
+const MULTILINE: OnboardingQuestion[] = ["interests", "schedule"];
+
+interface AboutMeInterviewProps {
+    open: boolean;
+    status: OnboardingStatus | null;
+    characterId: string;
+    characterName: string;
+    onStatusChange: (status: OnboardingStatus) => void;
+}
+
+/** First-run interview: the character asks the backend's fixed questions one at a time. */
+export default function AboutMeInterview({
+    open,
+    status,
+    characterId,
+    characterName,
+    onStatusChange,
+}: AboutMeInterviewProps) {
+    const { t } = useTranslation();
+    const [draft, setDraft] = useState("");
+    const [saving, setSaving] = useState(false);
+    const [error, setError] = useState<string | null>(null);
+    const question = status?.next ?? null;
+
+    useEffect(() => {
+        setDraft("");
+        setError(null);
+    }, [question]);
+
+    const submit = async (answer: string | null) => {
+        if (!question || saving) return;
+        setSaving(true);
+        try {
+            onStatusChange(await answerOnboardingQuestion(characterId, question, answer));
+        } catch (e) {
+            setError(typeof e === "string" ? e : t("onboarding.interview.failed"));
+        } finally {
+            setSaving(false);
+        }
+    };
+
+    const dismiss = async () => {
+        try {
+            onStatusChange(await dismissOnboarding());
+        } catch (e) {
+            console.error("[AboutMeInterview] Failed to dismiss interview:", e);
+        }
+    };
+
+    const isLast = status ? status.answered + 1 >= status.total : false;
+    const inputClasses = "w-full rounded-xl border border-[var(--color-border)] bg-black/30 px-3 py-2 text-sm text-[var(--color-text-primary)] placeholder:text-[var(--color-text-muted)] focus:outline-none focus:border-[var(--color-accent)]";
+
+    return (
+        <AnimatePresence>
+            {open && status && question && (
+                <motion.div
+                    initial={{ opacity: 0 }}
+                    animate={{ opacity: 1 }}
+                    exit={{ opacity: 0 }}
+                    className="fixed inset-0 z-[160] flex items-center justify-center bg-black/65 px-4 backdrop-blur-sm"
+                >
+                    <motion.div
+                        initial={{ opacity: 0, y: 20, scale: 0.98 }}
+                        animate={{ opacity: 1, y: 0, scale: 1 }}
+                        exit={{ opacity: 0, y: 12, scale: 0.98 }}
+                        transition={{ type: "spring", stiffness: 280, damping: 28 }}
+                        className="w-full max-w-[460px] rounded-3xl border border-[var(--color-border-accent)] bg-[var(--color-bg-elevated)]/95 shadow-2xl backdrop-blur-2xl"
+                    >
+                        <div className="flex items-start justify-between gap-4 border-b border-[var(--color-border)] px-6 py-5">
+                            <div className="space-y-2">
+                                <div className="flex items-center gap-2 text-[11px] font-heading font-semibold uppercase tracking-[0.18em] text-[var(--color-accent)]">
+                                    <MessageCircleHeart size={14} strokeWidth={1.6} />
+                                    {t("onboarding.interview.eyebrow")}
+                                    <span className="text-[var(--color-text-muted)]">
+                                        {status.answered + 1}/{status.total}
+                                    </span>
+                                </div>
+                                <h2 className="font-heading text-lg font-bold tracking-wide text-[var(--color-text-primary)]">
+                                    {t("onboarding.interview.title", { name: characterName })}
+                                </h2>
+                            </div>
+                            <button
+                                type="button"
+                                onClick={dismiss}
+                                className="rounded-lg p-2 text-[var(--color-text-muted)] transition-colors hover:text-[var(--color-accent)]"
+                                aria-label={t("onboarding.interview.actions.later")}
+                                title={t("onboarding.interview.actions.later")}
+                            >
+                                <X size={16} strokeWidth={1.7} />
+                            </button>
+                        </div>
+
+                        <div className="space-y-4 px-6 py-5">
+                            {status.answered === 0 && (
+                                <p className="text-sm leading-6 text-[var(--color-text-secondary)]">
+                                    {t("onboarding.interview.desc", { name: characterName })}
+                                </p>
+                            )}
+
+                            <div className="rounded-2xl border border-[var(--color-border)] bg-black/20 px-4 py-3">
+                                <div className="mb-1 text-[10px] font-heading font-semibold uppercase tracking-[0.14em] text-[var(--color-text-muted)]">
+                                    {characterName}
+                                </div>
+                                <p className="text-sm leading-6 text-[var(--color-text-primary)]">
+                                    {t(`onboarding.interview.questions.${question}`)}
+                                </p>
+                            </div>
+
+                            {MULTILINE.includes(question) ? (
+                                <textarea
+                                    autoFocus
+                                    value={draft}
+                                    onChange={e => setDraft(e.target.value)}
+                                    placeholder={t(`onboarding.interview.placeholders.${question}`)}
+                                    rows={3}
+                                    maxLength={200}
+                                    className={clsx(inputClasses, "resize-none")}
+                                />
+                            ) : (
+                                <input
+                                    autoFocus
+                                    type="text"
+                                    value={draft}
+                                    onChange={e => setDraft(e.target.value)}
+                                    onKeyDown={e => e.key === "Enter" && draft.trim() && submit(draft)}
+                                    placeholder={t(`onboarding.interview.placeholders.${question}`)}
+                                    maxLength={question === "name" ? 40 : 200}
+                                    className={inputClasses}
+                                />
+                            )}
+
+                            {error && <p className="text-xs text-red-400">{error}</p>}
+                        </div>
+
+                        <div className="flex items-center justify-between gap-3 border-t border-[var(--color-border)] px-6 py-4">
+                            <button
+                                type="button"
+                                onClick={() => submit(null)}
+                                disabled={saving}
+                                className="inline-flex h-10 items-center justify-center rounded-lg border border-[var(--color-border)] px-4 text-sm leading-none font-heading font-semibold uppercase tracking-[0.14em] text-[var(--color-text-secondary)] transition-colors hover:border-[var(--color-border-accent)] hover:text-[var(--color-accent)]"
+                            >
+                                {t("onboarding.interview.actions.skip")}
+                            </button>
+                            <button
+                                type="button"
+                                onClick={() => submit(draft)}
+                                disabled={saving || !draft.trim()}
+                                className={clsx(
+                                    "inline-flex h-10 items-center justify-center gap-2 rounded-lg px-4 text-sm leading-none font-heading font-semibold uppercase tracking-[0.14em] transition-colors",
+                                    draft.trim() && !saving
+                                        ? "bg-[var(--color-accent)] text-black hover:bg-white"
+                                        : "cursor-not-allowed bg-white/10 text-[var(--color-text-muted)]"
+                                )}
+                            >
+                                {saving && <Loader2 size={14} strokeWidth={1.7} className="animate-spin" />}
+                                {isLast ? t("onboarding.interview.actions.finish") : t("onboarding.interview.actions.next")}
+                            </button>
+                        </div>
+                    </motion.div>
+                </motion.div>
+            )}
+        </AnimatePresence>
+    );
+}
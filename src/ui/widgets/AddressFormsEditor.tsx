@@ -0,0 +1,87 @@
+import { useEffect, useState } from "react";
+import { useTranslation } from "react-i18next";
+import { getAddressForms, setAddressForm } from "../../lib/kokoro-bridge";
+import type { AddressTarget, RelationshipState } from "../../lib/kokoro-bridge";
+
+const TARGETS: AddressTarget[] = ["user", "character"];
+
+const inputClasses = "flex-1 min-w-0 px-2 py-1.5 rounded bg-black/40 border border-[var(--color-border)] text-xs text-[var(--color-text-primary)] placeholder:text-[var(--color-text-muted)] focus:outline-none focus:border-[var(--color-accent)]";
+
+function formOf(state: RelationshipState | null, target: AddressTarget) {
+    const address = target === "user" ? state?.user_address : state?.character_address;
+    return address ?? null;
+}
+
+/** Shows the forms of address learned from chat and lets the user correct them. */
+export default function AddressFormsEditor({ characterId }: { characterId: string }) {
+    const { t } = useTranslation();
+    const [state, setState] = useState<RelationshipState | null>(null);
+    const [drafts, setDrafts] = useState<Record<AddressTarget, string>>({ user: "", character: "" });
+    const [error, setError] = useState<string | null>(null);
+
+    useEffect(() => {
+        let cancelled = false;
+        setError(null);
+        getAddressForms(characterId)
+            .then(next => {
+                if (cancelled) return;
+                setState(next);
+                setDrafts({
+                    user: next.user_address?.form ?? "",
+                    character: next.character_address?.form ?? "",
+                });
+            })
+            .catch(() => {
+                if (!cancelled) setState(null);
+            });
+        return () => { cancelled = true; };
+    }, [characterId]);
+
+    const save = async (target: AddressTarget) => {
+        const draft = drafts[target].trim();
+        if (draft === (formOf(state, target)?.form ?? "")) return;
+        try {
+            const next = await setAddressForm(characterId, target, draft || null);
+            setState(next);
+            setError(null);
+        } catch (e) {
+            setError(typeof e === "string" ? e : t("settings.persona.address.failed"));
+        }
+    };
+
+    return (
+        <div className="space-y-1.5">
+            <label className="block text-[10px] font-heading font-semibold tracking-wider uppercase text-[var(--color-text-muted)]">
+                {t("settings.persona.address.title")}
+            </label>
+            {TARGETS.map(target => {
+                const current = formOf(state, target);
+                return (
+                    <div key={target} className="flex items-center gap-2">
+                        <span className="w-28 shrink-0 text-xs text-[var(--color-text-secondary)]">
+                            {t(`settings.persona.address.${target}`)}
+                        </span>
+                        <input
+                            type="text"
+                            value={drafts[target]}
+                            onChange={e => setDrafts(prev => ({ ...prev, [target]: e.target.value }))}
+                            onBlur={() => save(target)}
+                            onKeyDown={e => e.key === "Enter" && save(target)}
+                            placeholder={t("settings.persona.address.not_learned")}
+                            className={inputClasses}
+                        />
+                        {current && (
+                            <span className="shrink-0 text-[10px] uppercase tracking-wider text-[var(--color-text-muted)]">
+                                {t(`settings.persona.address.source.${current.source}`)}
+                            </span>
+                        )}
+                    </div>
+                );
+            })}
+            <p className="text-[10px] text-[var(--color-text-muted)] italic">
+                {t("settings.persona.address.desc")}
+            </p>
+            {error && <p className="text-[10px] text-red-400">{error}</p>}
+        </div>
+    );
+}
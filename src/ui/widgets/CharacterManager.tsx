@@ -9,11 +9,13 @@ import { motion, AnimatePresence } from "framer-motion";
 import { clsx } from "clsx";
 import { Plus, Upload, Trash2, UserCircle, Check, X, User } from "lucide-react";
 import { characterDb } from "../../lib/db";
-import { parseCharacterCard } from "../../lib/character-card-parser";
-import { setPersona, setCharacterName, setUserName, setUserPersona, setProactiveEnabled, getProactiveEnabled, setActiveCharacterId, listCharacters, createCharacter, updateCharacter, deleteCharacter } from "../../lib/kokoro-bridge";
+import { setPersona, setCharacterName, setUserName, setUserPersona, setProactiveEnabled, getProactiveEnabled, setActiveCharacterId, switchCharacter, listCharacters, createCharacter, updateCharacter, deleteCharacter, importCharacterCard, restartOnboarding } from "../../lib/kokoro-bridge";
 import type { CharacterRecord } from "../../lib/kokoro-bridge";
 import { Languages, MessageCircle } from "lucide-react";
 import { Select } from "@/components/ui/select";
+import ProactiveComposerSettings from "./ProactiveComposerSettings";
+import AddressFormsEditor from "./AddressFormsEditor";
+import { requestAboutMeInterview } from "./AboutMeInterview";
 import { useTranslation, Trans } from "react-i18next";
 
 export const RESPONSE_LANGUAGE_PRESETS = ["日本語", "English", "中文", "繁體中文", "한국어", "Русский"] as const;
@@ -117,6 +119,19 @@ export function composeSystemPrompt(
 
 const ACTIVE_CHAR_KEY = "kokoro_active_character_id";
 
+/**
+ * Switch the backend to `char` in one step. If that fails (e.g. a bound mod is
+ * missing), still apply the persona and id so chat keeps working.
+ */
+function activateCharacter(char: CharacterRecord) {
+    switchCharacter(char.id).catch(err => {
+        console.error("[CharacterManager] Failed to switch character:", err);
+        setPersona(composeSystemPrompt(char)).catch(() => {});
+        setCharacterName(char.name).catch(() => {});
+        setActiveCharacterId(char.id).catch(() => {});
+    });
+}
+
 // ── Props ──────────────────────────────────────────
 
 interface CharacterManagerProps {
@@ -193,11 +208,8 @@ export default function CharacterManager({ onPersonaChange, responseLanguage, on
             const active = (savedId && all.find(c => c.id === savedId)) ? all.find(c => c.id === savedId)! : all[0];
             setActiveId(active.id);
             setEditChar(active);
-            const prompt = composeSystemPrompt(active);
-            onPersonaChangeRef.current(prompt);
-            setPersona(prompt).catch(() => {});
-            setCharacterName(active.name).catch(() => {});
-            setActiveCharacterId(active.id).catch(() => {});
+            onPersonaChangeRef.current(composeSystemPrompt(active));
+            activateCharacter(active);
             localStorage.setItem(ACTIVE_CHAR_KEY, active.id);
         } catch (err) {
             console.error("[CharacterManager] Failed to load characters:", err);
@@ -215,11 +227,8 @@ export default function CharacterManager({ onPersonaChange, responseLanguage, on
         setEditChar({ ...char });
         setConfirmDeleteId(null);
         localStorage.setItem(ACTIVE_CHAR_KEY, char.id);
-        const prompt = composeSystemPrompt(char);
-        onPersonaChangeRef.current(prompt);
-        setPersona(prompt).catch(() => {});
-        setCharacterName(char.name).catch(() => {});
-        setActiveCharacterId(char.id).catch(() => {});
+        onPersonaChangeRef.current(composeSystemPrompt(char));
+        activateCharacter(char);
     };
 
     const handleCreate = async () => {
@@ -287,26 +296,16 @@ export default function CharacterManager({ onPersonaChange, responseLanguage, on
     const handleImport = async () => {
         const input = document.createElement("input");
         input.type = "file";
-        input.accept = ".json,.png";
+        input.accept = ".json,.png,.charx";
         input.onchange = async (e) => {
             const file = (e.target as HTMLInputElement).files?.[0];
             if (!file) return;
             try {
-                const profile = await parseCharacterCard(file);
-                const now = Date.now();
-                const newChar: CharacterRecord = {
-                    id: crypto.randomUUID(),
-                    name: profile.name,
-                    persona: profile.persona,
-                    user_nickname: profile.user_nickname,
-                    source_format: profile.source_format ?? "manual",
-                    created_at: now,
-                    updated_at: now,
-                };
-                await createCharacter(newChar);
+                const data = Array.from(new Uint8Array(await file.arrayBuffer()));
+                const newChar = await importCharacterCard({ data });
                 setCharacters(prev => [...prev, newChar]);
                 selectCharacter(newChar);
-                setImportFeedback(t("settings.persona.status.imported", { name: profile.name }));
+                setImportFeedback(t("settings.persona.status.imported", { name: newChar.name }));
                 setTimeout(() => setImportFeedback(null), 3000);
             } catch (err) {
                 console.error("[CharacterManager] Import failed:", err);
@@ -373,6 +372,15 @@ export default function CharacterManager({ onPersonaChange, responseLanguage, on
                         rows={3}
                         className={clsx(inputClasses, "resize-y min-h-[60px]")}
                     />
+                    <button
+                        type="button"
+                        onClick={() => restartOnboarding()
+                            .then(requestAboutMeInterview)
+                            .catch(e => console.error("[CharacterManager] Failed to restart interview:", e))}
+                        className="text-[10px] text-[var(--color-accent)] hover:underline"
+                    >
+                        {t("settings.persona.user_profile.retake_interview")}
+                    </button>
                 </div>
             </div>
 
@@ -486,6 +494,7 @@ export default function CharacterManager({ onPersonaChange, responseLanguage, on
                         />
                     </button>
                 </div>
+                {proactiveEnabled && <ProactiveComposerSettings />}
             </div>
 
             {/* ── Divider ── */}
@@ -648,6 +657,9 @@ export default function CharacterManager({ onPersonaChange, responseLanguage, on
                         </p>
                     </div>
 
+                    {/* Learned forms of address */}
+                    <AddressFormsEditor characterId={editChar.id} />
+
                     {/* Persona */}
                     <div>
                         <label className="block text-[10px] font-heading font-semibold tracking-wider uppercase text-[var(--color-text-muted)] mb-1">
@@ -1,7 +1,7 @@
-import { useState, memo } from "react";
+import { useRef, useState, memo } from "react";
 import { motion, AnimatePresence } from "framer-motion";
 import { clsx } from "clsx";
-import { Edit2, RefreshCw, Check, X, Languages, CornerDownLeft, ChevronDown, Wrench, Eye } from "lucide-react";
+import { Edit2, RefreshCw, Check, X, Languages, CornerDownLeft, ChevronDown, Wrench, Eye, BookmarkPlus, BookmarkCheck } from "lucide-react";
 import { useTranslation } from "react-i18next";
 import type { ToolTraceItem } from "../../lib/kokoro-bridge";
 
@@ -25,6 +25,8 @@ interface ChatMessageProps {
     onEdit: (newText: string) => void;
     onRegenerate: () => void;
     onContinueFrom: () => void;
+    /** Pin the given text as a core memory; rejects if saving failed. */
+    onRemember: (text: string) => Promise<void>;
     onApproveTool: (tool: ToolTraceItem) => void;
     onRejectTool: (tool: ToolTraceItem) => void;
 }
@@ -172,6 +174,7 @@ export const ChatMessage = memo(function ChatMessage({
     onEdit,
     onRegenerate,
     onContinueFrom,
+    onRemember,
     onApproveTool,
     onRejectTool,
 }: ChatMessageProps) {
@@ -179,6 +182,30 @@ export const ChatMessage = memo(function ChatMessage({
     const [isEditing, setIsEditing] = useState(false);
     const [editingText, setEditingText] = useState("");
     const [toolsExpanded, setToolsExpanded] = useState(false);
+    const [rememberText, setRememberText] = useState<string | null>(null);
+    const [remembered, setRemembered] = useState(false);
+    const textRef = useRef<HTMLDivElement>(null);
+
+    // Prefill with the selected part of this message, or the whole message.
+    const handleStartRemember = () => {
+        const selection = window.getSelection();
+        const selected = selection && textRef.current?.contains(selection.anchorNode)
+            ? selection.toString().trim()
+            : "";
+        setRememberText(selected || msg.text);
+    };
+
+    const handleSaveRemember = async () => {
+        const text = rememberText?.trim();
+        if (!text) return;
+        try {
+            await onRemember(text);
+            setRemembered(true);
+            setRememberText(null);
+        } catch {
+            // The panel reports the error; keep the draft for another try.
+        }
+    };
 
     const handleStartEdit = () => {
         setIsEditing(true);
@@ -304,10 +331,48 @@ export const ChatMessage = memo(function ChatMessage({
                 </div>
             ) : (
                 <>
-                    <div className="whitespace-pre-wrap break-words">
+                    <div ref={textRef} className="whitespace-pre-wrap break-words">
                         {msg.text}
                     </div>
 
+                    {rememberText !== null && (
+                        <div className="mt-2 space-y-1.5">
+                            <div className="text-[10px] text-[var(--color-text-muted)]">
+                                {t("chat.remember.hint")}
+                            </div>
+                            <textarea
+                                value={rememberText}
+                                onChange={(e) => setRememberText(e.target.value)}
+                                className="w-full bg-black/40 border border-[var(--color-border)] text-[var(--color-text-primary)] rounded-md px-3 py-2 text-xs focus:outline-none focus:border-[var(--color-accent)] resize-none"
+                                rows={2}
+                                autoFocus
+                                onKeyDown={(e) => {
+                                    if (e.key === "Enter" && (e.ctrlKey || e.metaKey)) {
+                                        void handleSaveRemember();
+                                    } else if (e.key === "Escape") {
+                                        setRememberText(null);
+                                    }
+                                }}
+                            />
+                            <div className="flex items-center gap-2">
+                                <button
+                                    onClick={() => void handleSaveRemember()}
+                                    className="p-1.5 rounded-md bg-[var(--color-accent)]/20 text-[var(--color-accent)] hover:bg-[var(--color-accent)]/30 transition-colors"
+                                    title={t("chat.remember.save")}
+                                >
+                                    <Check size={14} />
+                                </button>
+                                <button
+                                    onClick={() => setRememberText(null)}
+                                    className="p-1.5 rounded-md bg-slate-800/50 text-slate-400 hover:text-slate-300 transition-colors"
+                                    title={t("chat.actions.cancel")}
+                                >
+                                    <X size={14} />
+                                </button>
+                            </div>
+                        </div>
+                    )}
+
                     {/* 错误消息的重试按钮 */}
                     {!isStreaming && msg.isError && (
                         <div className="absolute top-2 right-2 flex items-center gap-1 opacity-0 group-hover:opacity-100 transition-opacity">
@@ -333,6 +398,18 @@ export const ChatMessage = memo(function ChatMessage({
                                 <CornerDownLeft size={12} strokeWidth={1.5} />
                             </button>
 
+                            {/* 记住这条消息 */}
+                            <button
+                                onMouseDown={(e) => e.preventDefault()}
+                                onClick={handleStartRemember}
+                                className="p-1.5 rounded-md bg-slate-800/90 backdrop-blur-sm text-slate-400 hover:text-[var(--color-accent)] hover:bg-slate-700/90 transition-colors shadow-lg"
+                                title={remembered ? t("chat.remember.done") : t("chat.actions.remember")}
+                            >
+                                {remembered
+                                    ? <BookmarkCheck size={12} strokeWidth={1.5} className="text-[var(--color-accent)]" />
+                                    : <BookmarkPlus size={12} strokeWidth={1.5} />}
+                            </button>
+
                             {/* 编辑按钮 */}
                             <button
                                 onClick={handleStartEdit}
@@ -1,11 +1,12 @@
 import { useState, useRef, useEffect, useCallback, useDeferredValue, memo, type KeyboardEvent as ReactKeyboardEvent, type PointerEvent as ReactPointerEvent } from "react";
 import { motion, AnimatePresence } from "framer-motion";
 import { clsx } from "clsx";
-import { Send, Trash2, AlertCircle, MessageCircle, ChevronLeft, ImagePlus, X, Mic, MicOff, History, Maximize2, Minimize2 } from "lucide-react";
-import { streamChat, cancelChatTurn, onChatTurnStart, onChatTurnDelta, onChatTurnFinish, onChatTurnTextComplete, onChatError, onChatWarning, onChatFailure, onChatTurnTranslation, clearHistory, uploadVisionImage, synthesize, onChatTurnTool, listConversations, loadConversation, onTelegramChatSync, onVisionObservation, deleteLastMessages, approveToolApproval, rejectToolApproval, getMemoryEmbeddingModelStatus, setVisionTextInputFocused } from "../../lib/kokoro-bridge";
+import { Send, Trash2, AlertCircle, MessageCircle, ChevronLeft, ImagePlus, X, Mic, MicOff, AudioLines, History, Maximize2, Minimize2, EyeOff } from "lucide-react";
+import { streamChat, cancelChatTurn, cancelChat, onChatTurnStart, onChatTurnWaiting, onChatTurnDelta, onChatTurnFinish, onChatTurnTextComplete, onChatError, onChatWarning, onChatFailure, onChatTurnTranslation, clearHistory, uploadVisionImage, synthesize, onChatTurnTool, listConversations, loadConversation, onTelegramChatSync, onVisionObservation, deleteLastMessages, regenerateLastResponse, approveToolApproval, rejectToolApproval, onTtsFailover, getMemoryEmbeddingModelStatus, setVisionTextInputFocused, onCompanionState, routeVoiceCommand, getPrivacyMode, setPrivacyMode, onPrivacyModeChanged, notifyTyping, stopSpeaking, onChatQueue, onChatPlan, analyzeImageFile, IMAGE_FILE_EXTENSIONS, confirmPlanStep, abortToolPlan, startVoiceSession, stopVoiceSession, getVoiceSessionStatus, onVoiceSessionState, onVoiceSessionUtterance, onReadAloudComment, rememberMessage, type ChatPlanEvent, type VoiceCommandOutcome } from "../../lib/kokoro-bridge";
 import type { FailureEvent, ToolTraceItem } from "../../lib/kokoro-bridge";
 import { getLatestCameraFrame } from "../../lib/camera-frame-cache";
 import { listen } from "@tauri-apps/api/event";
+import { getCurrentWebview } from "@tauri-apps/api/webview";
 import { useVoiceInput, VoiceState, useTypingReveal, useWakeWord } from "../hooks";
 import { useTranslation } from "react-i18next";
 import ConversationSidebar from "./ConversationSidebar";
@@ -35,6 +36,7 @@ import {
     readJsonSetting,
     readNumberSetting,
     readStringSetting,
+    writeBooleanSetting,
 } from "../../lib/app-settings";
 
 // ── Types ──────────────────────────────────────────────────
@@ -52,6 +54,8 @@ export type { ChatPanelMessage };
 const DEFAULT_CHAT_PANEL_WIDTH = 350;
 const CHAT_PANEL_RESIZE_GUTTER = 160;
 const CHAT_PANEL_KEYBOARD_RESIZE_STEP = 24;
+/** Give up on a read-aloud comment if playback is still going after this long. */
+const READ_ALOUD_COMMENT_WAIT_MS = 5 * 60 * 1000;
 
 const getChatPanelResizeMaxWidth = (minWidth: number) => {
     if (typeof window === "undefined") {
@@ -120,7 +124,7 @@ const getTtsPlaybackSettings = () => ({
 const isGeneratedBackgroundMode = () =>
     readJsonSetting<{ mode?: string }>(APP_SETTING_KEYS.bgConfig, {}).mode === "generated";
 
-function TypingIndicator() {
+function TypingIndicator({ note }: { note?: string | null }) {
     return (
         <motion.div
             initial={{ opacity: 0, y: 10 }}
@@ -136,6 +140,9 @@ function TypingIndicator() {
                     transition={{ duration: 1.2, repeat: Infinity, delay: i * 0.2 }}
                 />
             ))}
+            {note && (
+                <span className="ml-1.5 text-xs italic text-[var(--color-text-muted)]">{note}</span>
+            )}
         </motion.div>
     );
 }
@@ -173,6 +180,7 @@ interface MemoizedChatMessageProps {
     onEdit: (index: number, newText: string) => void;
     onRegenerate: (index: number) => Promise<void>;
     onContinueFrom: (index: number) => Promise<void>;
+    onRemember: (text: string) => Promise<void>;
     onApproveTool: (index: number, tool: ToolTraceItem) => Promise<void>;
     onRejectTool: (index: number, tool: ToolTraceItem) => Promise<void>;
 }
@@ -186,7 +194,7 @@ function createToolActionHandler<TArgs extends Array<unknown>>(
 
 const MemoizedChatMessage = memo(function MemoizedChatMessage({
     message, globalIndex, isStreaming, isTranslationExpanded,
-    onToggleTranslation, onEdit, onRegenerate, onContinueFrom, onApproveTool, onRejectTool,
+    onToggleTranslation, onEdit, onRegenerate, onContinueFrom, onRemember, onApproveTool, onRejectTool,
 }: MemoizedChatMessageProps) {
     return (
         <ChatMessage
@@ -198,6 +206,7 @@ const MemoizedChatMessage = memo(function MemoizedChatMessage({
             onEdit={(text) => onEdit(globalIndex, text)}
             onRegenerate={() => onRegenerate(globalIndex)}
             onContinueFrom={() => onContinueFrom(globalIndex)}
+            onRemember={onRemember}
             onApproveTool={createToolActionHandler(globalIndex, onApproveTool)}
             onRejectTool={createToolActionHandler(globalIndex, onRejectTool)}
         />
@@ -228,6 +237,18 @@ export default function ChatPanel({
     const cancelRequestedRef = useRef(false);
     const messagesRef = useRef<ChatMessage[]>([]);
     const [isThinking, setIsThinking] = useState(false);
+    // Shown in the typing indicator when the first token misses the latency budget.
+    const [waitingNote, setWaitingNote] = useState<string | null>(null);
+
+    // Messages sent mid-reply; their bubbles appear once the backend starts them.
+    const [queuedSends, setQueuedSends] = useState<{ text: string; images?: string[] }[]>([]);
+    const queuedSendsRef = useRef<{ text: string; images?: string[] }[]>([]);
+    const lastQueueRunningIdRef = useRef<string | null>(null);
+    const awaitingQueuedTurnRef = useRef(false);
+
+    // Multi-step tool plan of the current turn; lingers briefly once finished.
+    const [activePlan, setActivePlan] = useState<ChatPlanEvent | null>(null);
+    const planClearTimerRef = useRef<ReturnType<typeof setTimeout> | null>(null);
 
     // Per-message translation expand state (set of message indices)
     const [expandedTranslations, setExpandedTranslations] = useState<Set<number>>(new Set());
@@ -257,6 +278,8 @@ export default function ChatPanel({
     // Raw (unfiltered) full response text — accumulated from all deltas
     const rawResponseRef = useRef("");
     const currentTurnRef = useRef<PendingTurnState | null>(null);
+    // Set by the proactive trigger until its turn starts.
+    const proactiveTurnPendingRef = useRef(false);
     const pendingVisionContextRef = useRef<ChatMessage | null>(null);
 
     // Typing reveal: per-character animation
@@ -349,6 +372,11 @@ export default function ChatPanel({
         const activeTurnId = currentTurnRef.current?.turnId;
         if (activeTurnId) {
             void requestTurnCancellation(activeTurnId);
+        } else {
+            // 尚未收到 turn_id（仍在构建提示词），按窗口取消
+            void cancelChat("stopped_from_chat_panel").catch(error => {
+                console.error("[ChatPanel] Failed to cancel chat:", error);
+            });
         }
     }, [isStopping, requestTurnCancellation]);
 
@@ -376,6 +404,8 @@ export default function ChatPanel({
     const [continuousListening, setContinuousListening] = useState(
         () => readBooleanSetting(APP_SETTING_KEYS.sttContinuousListening, false)
     );
+    const [voiceSessionActive, setVoiceSessionActive] = useState(false);
+    const voiceSessionActiveRef = useRef(false);
 
     useEffect(() => {
         const syncSttSettings = () => {
@@ -395,10 +425,36 @@ export default function ChatPanel({
         };
     }, []);
 
-    const handleTranscription = useCallback((text: string) => {
-        const trimmed = text.trim();
-        if (!trimmed) return;
+    const runVoiceCommand = useCallback(async (command: VoiceCommandOutcome) => {
+        switch (command.intent) {
+            case "mute_tts":
+                writeBooleanSetting(APP_SETTING_KEYS.ttsEnabled, false);
+                audioPlayer.stop();
+                break;
+            case "unmute_tts":
+                writeBooleanSetting(APP_SETTING_KEYS.ttsEnabled, true);
+                break;
+            case "stop_talking":
+                audioPlayer.stop();
+                stopSpeaking().catch(err => console.warn("[ChatPanel] Failed to stop speech:", err));
+                handleStopGeneration();
+                break;
+            case "switch_character":
+                if (command.character_id) {
+                    document.dispatchEvent(new CustomEvent("kokoro:mod-action", {
+                        detail: { action: "select_character", data: { id: command.character_id } },
+                    }));
+                }
+                break;
+            case "delete_last_message":
+                if (messagesRef.current.length === 0) break;
+                await deleteLastMessages(1);
+                setMessages(prev => prev.slice(0, -1));
+                break;
+        }
+    }, [handleStopGeneration]);
 
+    const sendTranscription = useCallback((trimmed: string) => {
         if (sttAutoSend) {
             void (async () => {
                 if (!await ensureMemoryModelReady()) {
@@ -438,6 +494,26 @@ export default function ChatPanel({
         }
     }, [endTurnActivity, ensureMemoryModelReady, sttAutoSend, startStreaming]);
 
+    const handleTranscription = useCallback((text: string) => {
+        const trimmed = text.trim();
+        if (!trimmed) return;
+
+        void (async () => {
+            // Control phrases ("be quiet", "switch to …") never reach the LLM.
+            const command = await routeVoiceCommand(trimmed).catch(error => {
+                console.error("[ChatPanel] Voice command routing failed:", error);
+                return null;
+            });
+            if (command) {
+                await runVoiceCommand(command).catch(error => {
+                    console.error("[ChatPanel] Voice command failed:", error);
+                });
+                return;
+            }
+            sendTranscription(trimmed);
+        })();
+    }, [runVoiceCommand, sendTranscription]);
+
     const { state: voiceState, volume: micVolume, partialText: sttPartialText, start: startVoice, stop: stopVoice } = useVoiceInput(handleTranscription);
 
     // Refs to avoid stale closures in the voice-interrupt-stt listener
@@ -446,6 +522,7 @@ export default function ChatPanel({
     const sttEnabledRef = useRef(sttEnabled);
     useEffect(() => { startVoiceRef.current = startVoice; }, [startVoice]);
     useEffect(() => { sttAutoSendRef.current = sttAutoSend; }, [sttAutoSend]);
+    useEffect(() => { if (!isThinking) setWaitingNote(null); }, [isThinking]);
     useEffect(() => { sttEnabledRef.current = sttEnabled; }, [sttEnabled]);
 
     useEffect(() => {
@@ -513,6 +590,43 @@ export default function ChatPanel({
         }
     }, [sttPartialText, voiceState, sttAutoSend]);
 
+    // Photos dropped onto the window are described by the VLM and quoted into the draft.
+    useEffect(() => {
+        if (!visionEnabled) return;
+        let disposed = false;
+        let unlisten: (() => void) | undefined;
+        const isImagePath = (path: string) =>
+            IMAGE_FILE_EXTENSIONS.includes(path.split(".").pop()?.toLowerCase() ?? "");
+
+        getCurrentWebview().onDragDropEvent(async (event) => {
+            if (event.payload.type !== "drop") return;
+            const paths = event.payload.paths.filter(isImagePath).slice(0, 5);
+            if (paths.length === 0) return;
+
+            setIsUploading(true);
+            try {
+                const notes: string[] = [];
+                for (const path of paths) {
+                    const result = await analyzeImageFile(path);
+                    notes.push(t("chat.image_drop.note", { name: result.file_name, description: result.description }));
+                }
+                setInput(prev => [prev.trim(), ...notes].filter(Boolean).join("\n"));
+            } catch (err) {
+                setError(err instanceof Error ? err.message : t("chat.errors.image_analysis_failed"));
+            } finally {
+                setIsUploading(false);
+            }
+        }).then((fn) => {
+            if (disposed) fn();
+            else unlisten = fn;
+        });
+
+        return () => {
+            disposed = true;
+            unlisten?.();
+        };
+    }, [visionEnabled, t]);
+
     // Sync vision state when localStorage changes (from Settings panel)
     useEffect(() => {
         const checkVision = () => {
@@ -602,8 +716,70 @@ export default function ChatPanel({
             if (aborted) { unPetChat(); return; }
             cleanups.push(unPetChat);
 
+            // Voice session: the backend sends the transcript itself and voices the reply.
+            const unVoiceUtterance = await onVoiceSessionUtterance(({ text }) => {
+                if (aborted) return;
+                rawResponseRef.current = "";
+                currentTurnRef.current = null;
+                resetReveal();
+                setMessages(prev => [...prev, { role: "user", text }]);
+                startStreaming();
+                setIsThinking(true);
+                userScrolledRef.current = false;
+            });
+            if (aborted) { unVoiceUtterance(); return; }
+            cleanups.push(unVoiceUtterance);
+
+            const unVoiceSession = await onVoiceSessionState(({ active }) => {
+                if (aborted) return;
+                voiceSessionActiveRef.current = active;
+                setVoiceSessionActive(active);
+            });
+            if (aborted) { unVoiceSession(); return; }
+            cleanups.push(unVoiceSession);
+            getVoiceSessionStatus()
+                .then(status => {
+                    if (aborted) return;
+                    voiceSessionActiveRef.current = status.active;
+                    setVoiceSessionActive(status.active);
+                })
+                .catch(() => {});
+
+            const unPlan = await onChatPlan((plan) => {
+                if (aborted) return;
+                if (planClearTimerRef.current) clearTimeout(planClearTimerRef.current);
+                setActivePlan(plan);
+                if (plan.status !== "running") {
+                    planClearTimerRef.current = setTimeout(() => {
+                        setActivePlan(prev => (prev?.plan_id === plan.plan_id ? null : prev));
+                    }, 4000);
+                }
+            });
+            if (aborted) { unPlan(); return; }
+            cleanups.push(unPlan);
+
+            const unQueue = await onChatQueue(({ running }) => {
+                if (aborted || !running || running.hidden || running.id === lastQueueRunningIdRef.current) return;
+                lastQueueRunningIdRef.current = running.id;
+                const head = queuedSendsRef.current[0];
+                if (!head || !head.text.startsWith(running.preview.replace(/…$/, ""))) return;
+                queuedSendsRef.current = queuedSendsRef.current.slice(1);
+                setQueuedSends(queuedSendsRef.current);
+                setMessages(prev => [...prev, { role: "user", text: head.text, images: head.images }]);
+                awaitingQueuedTurnRef.current = true;
+            });
+            if (aborted) { unQueue(); return; }
+            cleanups.push(unQueue);
+
             const unTurnStart = await onChatTurnStart(({ turn_id }) => {
                 if (aborted) return;
+                if (awaitingQueuedTurnRef.current && !isStreamingRef.current) {
+                    resetReveal();
+                    startStreaming();
+                    setIsThinking(true);
+                    userScrolledRef.current = false;
+                }
+                awaitingQueuedTurnRef.current = false;
                 currentTurnRef.current = {
                     turnId: turn_id,
                     messageIndex: null,
@@ -613,7 +789,9 @@ export default function ChatPanel({
                     translationPending: false,
                     tools: [],
                     pendingContext: pendingVisionContextRef.current ?? undefined,
+                    proactive: proactiveTurnPendingRef.current,
                 };
+                proactiveTurnPendingRef.current = false;
                 pendingVisionContextRef.current = null;
                 rawResponseRef.current = "";
                 if (cancelRequestedRef.current) {
@@ -623,6 +801,21 @@ export default function ChatPanel({
             if (aborted) { unTurnStart(); return; }
             cleanups.push(unTurnStart);
 
+            const unWaiting = await onChatTurnWaiting(({ turn_id, filler }) => {
+                if (aborted || cancelRequestedRef.current) return;
+                const turn = currentTurnRef.current;
+                if (!turn || turn.turnId !== turn_id || turn.visibleTextStarted) return;
+
+                setWaitingNote(filler ?? t("chat.status.slow_reply"));
+                const playback = getTtsPlaybackSettings();
+                if (filler && playback.enabled) {
+                    const { enabled: _enabled, ...ttsConfig } = playback;
+                    synthesize(filler, { ...ttsConfig, source: "chat-filler" }).catch(err => console.error("[TTS] Filler failed:", err));
+                }
+            });
+            if (aborted) { unWaiting(); return; }
+            cleanups.push(unWaiting);
+
             const unDelta = await onChatTurnDelta(({ turn_id, delta: rawDelta }) => {
                 if (aborted || !isStreamingRef.current || cancelRequestedRef.current) return;
                 const turn = currentTurnRef.current;
@@ -760,10 +953,16 @@ export default function ChatPanel({
                 currentTurnRef.current = null;
 
                 const playback = getTtsPlaybackSettings();
-                if (status === "completed" && playback.enabled && cleanText.trim()) {
+                // The voice session already spoke this reply sentence by sentence.
+                if (status === "completed" && playback.enabled && cleanText.trim() && !voiceSessionActiveRef.current) {
                     console.log("[TTS] Auto-speak triggered, text length:", cleanText.length);
                     const { enabled: _enabled, ...ttsConfig } = playback;
-                    synthesize(cleanText.trim(), ttsConfig).catch(err => console.error("[TTS] Auto-speak failed:", err));
+                    synthesize(cleanText.trim(), {
+                        ...ttsConfig,
+                        follow_emotion: true,
+                        priority: turn.proactive ? "low" : "normal",
+                        source: turn.proactive ? "proactive" : "chat",
+                    }).catch(err => console.error("[TTS] Auto-speak failed:", err));
                 }
             });
             if (aborted) { unDone(); return; }
@@ -797,6 +996,13 @@ export default function ChatPanel({
             if (aborted) { unWarning(); return; }
             cleanups.push(unWarning);
 
+            const unTtsFailover = await onTtsFailover(({ from, to }) => {
+                if (aborted) return;
+                setError(t("chat.errors.tts_failover", { from, to }));
+            });
+            if (aborted) { unTtsFailover(); return; }
+            cleanups.push(unTtsFailover);
+
             const unToolResult = await onChatTurnTool((event) => {
                 if (aborted || cancelRequestedRef.current) return;
                 logToolEvent(event);
@@ -820,19 +1026,12 @@ export default function ChatPanel({
             if (aborted) { unVisionObservation(); return; }
             cleanups.push(unVisionObservation);
 
-            const unTtsStart = await listen("tts:start", () => {
-                if (aborted) return;
-                ttsSpeakingRef.current = true;
-            });
-            if (aborted) { unTtsStart(); return; }
-            cleanups.push(unTtsStart);
-
-            const unTtsEnd = await listen("tts:end", () => {
+            const unCompanionState = await onCompanionState((state) => {
                 if (aborted) return;
-                ttsSpeakingRef.current = false;
+                ttsSpeakingRef.current = state.active.includes("speaking");
             });
-            if (aborted) { unTtsEnd(); return; }
-            cleanups.push(unTtsEnd);
+            if (aborted) { unCompanionState(); return; }
+            cleanups.push(unCompanionState);
 
             // Telegram chat sync — show messages from Telegram bot in desktop UI
             const unTelegramSync = await onTelegramChatSync((data) => {
@@ -840,7 +1039,14 @@ export default function ChatPanel({
                 if (data.role === "user") {
                     setMessages(prev => [...prev, { role: "user", text: data.text }]);
                 } else {
-                    setMessages(prev => [...prev, { role: "kokoro", text: data.text, translation: data.translation }]);
+                    setMessages(prev => {
+                        const reply = { role: "kokoro" as const, text: data.text, translation: data.translation };
+                        const lastIndex = prev.length - 1;
+                        if (data.replaces_last && prev[lastIndex]?.role === "kokoro") {
+                            return [...prev.slice(0, lastIndex), reply];
+                        }
+                        return [...prev, reply];
+                    });
                 }
             });
             if (aborted) { unTelegramSync(); return; }
@@ -849,55 +1055,75 @@ export default function ChatPanel({
             // Interaction reactions (touch/click on Live2D model) handled via auto-generated LLM prompt in interaction-service.ts
             // We no longer listen here to avoid double-handling or showing hardcoded lines.
 
-            // Listen for proactive triggers from backend (heartbeat)
-            const unProactive = await listen<any>("proactive-trigger", (event) => {
+            // Character-initiated turn: the instruction goes in as a hidden user message.
+            const canStartHiddenTurn = () => {
                 const browserSpeaking = typeof window !== "undefined"
                     && Boolean(window.speechSynthesis?.speaking);
-                if (aborted || isBusyRef.current || ttsSpeakingRef.current || audioPlayer.isPlaying || browserSpeaking) return;
-                void (async () => {
-                    if (!await ensureMemoryModelReady({ silent: true })) {
-                        return;
-                    }
-
-                    console.log("[ChatPanel] Proactive trigger:", event.payload);
-
-                    const { instruction } = event.payload;
+                return !(aborted || isBusyRef.current || ttsSpeakingRef.current || audioPlayer.isPlaying || browserSpeaking);
+            };
+            const startHiddenTurn = async (instruction: string) => {
+                if (!await ensureMemoryModelReady({ silent: true })) {
+                    return;
+                }
 
-                    // Start streaming — compose_prompt() handles full context (system prompt, memory, emotion, history, language)
-                    startStreaming();
-                    setIsThinking(true);
-                    userScrolledRef.current = false;
-                    resetReveal();
-                    rawResponseRef.current = "";
-                    currentTurnRef.current = null;
+                // Start streaming — compose_prompt() handles full context (system prompt, memory, emotion, history, language)
+                startStreaming();
+                setIsThinking(true);
+                userScrolledRef.current = false;
+                resetReveal();
+                rawResponseRef.current = "";
+                currentTurnRef.current = null;
+                proactiveTurnPendingRef.current = true;
 
-                    streamChat({
-                        message: instruction,
-                        hidden: true,
-                        character_id: getActiveCharacterIdForRequest(),
-                    }).catch(err => {
-                        if (isTurnCancelledError(err) || cancelRequestedRef.current) {
-                            endTurnActivity();
-                            currentTurnRef.current = null;
-                            return;
-                        }
+                streamChat({
+                    message: instruction,
+                    hidden: true,
+                    character_id: getActiveCharacterIdForRequest(),
+                }).catch(err => {
+                    if (isTurnCancelledError(err) || cancelRequestedRef.current) {
                         endTurnActivity();
-                        setIsThinking(false);
-                        setError(getAsyncErrorMessage(err));
                         currentTurnRef.current = null;
-                        // Remove the empty placeholder if one was created by delta handler
-                        setMessages(prev => {
-                            const last = prev[prev.length - 1];
-                            if (last && last.role === "kokoro" && !last.text) {
-                                return prev.slice(0, -1);
-                            }
-                            return prev;
-                        });
+                        return;
+                    }
+                    endTurnActivity();
+                    setIsThinking(false);
+                    setError(getAsyncErrorMessage(err));
+                    currentTurnRef.current = null;
+                    // Remove the empty placeholder if one was created by delta handler
+                    setMessages(prev => {
+                        const last = prev[prev.length - 1];
+                        if (last && last.role === "kokoro" && !last.text) {
+                            return prev.slice(0, -1);
+                        }
+                        return prev;
                     });
-                })();
+                });
+            };
+
+            // Listen for proactive triggers from backend (heartbeat)
+            const unProactive = await listen<any>("proactive-trigger", (event) => {
+                if (!canStartHiddenTurn()) return;
+                console.log("[ChatPanel] Proactive trigger:", event.payload);
+                void startHiddenTurn(event.payload.instruction);
             });
             cleanups.push(() => unProactive());
 
+            // Read-aloud comment: wait for the reading to finish playing first.
+            const unReadAloudComment = await onReadAloudComment(({ instruction }) => {
+                const deadline = Date.now() + READ_ALOUD_COMMENT_WAIT_MS;
+                const attempt = () => {
+                    if (aborted || Date.now() > deadline) return;
+                    if (!canStartHiddenTurn()) {
+                        setTimeout(attempt, 500);
+                        return;
+                    }
+                    void startHiddenTurn(instruction);
+                };
+                setTimeout(attempt, 500);
+            });
+            if (aborted) { unReadAloudComment(); return; }
+            cleanups.push(unReadAloudComment);
+
             // Listen for interaction triggers (touch/click on Live2D model)
             // interaction-service already calls streamChat, we just need to prepare ChatPanel for receiving deltas
             const unInteraction = await listen<any>("interaction-trigger", () => {
@@ -936,9 +1162,29 @@ export default function ChatPanel({
         e?.preventDefault();
         const trimmed = input.trim();
         const messageImages = visionEnabled ? [...pendingImages] : [];
-        if ((!trimmed && messageImages.length === 0) || isBusy) return;
+        if (!trimmed && messageImages.length === 0) return;
         if (!await ensureMemoryModelReady()) return;
 
+        if (isBusyRef.current) {
+            // The backend queues it behind the running reply.
+            const text = trimmed || "(image attached)";
+            queuedSendsRef.current = [...queuedSendsRef.current, { text, images: messageImages.length > 0 ? messageImages : undefined }];
+            setQueuedSends(queuedSendsRef.current);
+            setInput("");
+            setPendingImages([]);
+            streamChat({
+                message: text,
+                allow_image_gen: isGeneratedBackgroundMode(),
+                images: messageImages.length > 0 ? messageImages : undefined,
+                character_id: getActiveCharacterIdForRequest(),
+            }).catch((err) => {
+                queuedSendsRef.current = queuedSendsRef.current.filter(q => q.text !== text);
+                setQueuedSends(queuedSendsRef.current);
+                if (!isTurnCancelledError(err)) setError(getAsyncErrorMessage(err));
+            });
+            return;
+        }
+
         setMessages(prev => [...prev, { role: "user", text: trimmed, images: messageImages.length > 0 ? messageImages : undefined }]);
         const cameraFrame = visionEnabled ? getLatestCameraFrame() : null;
         const imagesToSend = cameraFrame ? [...messageImages, cameraFrame] : messageImages;
@@ -1025,6 +1271,14 @@ export default function ChatPanel({
         setPendingImages(prev => prev.filter((_, i) => i !== index));
     };
 
+    // Starting a message is the earliest hint that a reply will be needed.
+    const handleInputChange = (value: string) => {
+        if (!input && value) {
+            notifyTyping().catch(() => {});
+        }
+        setInput(value);
+    };
+
     // ── Clipboard paste image ────────────────────────────────
     const handlePaste = async (e: React.ClipboardEvent) => {
         if (!visionEnabled) return;
@@ -1058,11 +1312,27 @@ export default function ChatPanel({
     // ── STT: Advanced VAD Microphone toggle ─────────────────
     const handleMicToggle = useCallback(() => {
         if (voiceState === VoiceState.Idle) {
-            startVoice({ autoStopOnSilence: true });
+            // Continuous listening keeps the mic open; the backend VAD ends each utterance.
+            startVoice(continuousListening ? { handsFree: true } : { autoStopOnSilence: true });
         } else {
             stopVoice();
         }
-    }, [voiceState, startVoice, stopVoice]);
+    }, [voiceState, continuousListening, startVoice, stopVoice]);
+
+    // ── Voice session: continuous spoken conversation ──────
+    const handleVoiceSessionToggle = useCallback(async () => {
+        try {
+            if (voiceSessionActive) {
+                await stopVoiceSession();
+            } else {
+                if (voiceState !== VoiceState.Idle) stopVoice();
+                const { enabled: _enabled, ...ttsConfig } = getTtsPlaybackSettings();
+                await startVoiceSession({ ...ttsConfig, follow_emotion: true });
+            }
+        } catch (err) {
+            setError(getAsyncErrorMessage(err));
+        }
+    }, [voiceSessionActive, voiceState, stopVoice]);
 
     // ── Clear history ──────────────────────────────────────
     const handleClear = async () => {
@@ -1074,6 +1344,37 @@ export default function ChatPanel({
         setMessages([]);
     };
 
+    // ── Privacy mode ───────────────────────────────────────
+    const [privacyMode, setPrivacyModeState] = useState(false);
+
+    useEffect(() => {
+        let disposed = false;
+        let unlisten: (() => void) | undefined;
+        getPrivacyMode()
+            .then(enabled => { if (!disposed) setPrivacyModeState(enabled); })
+            .catch(() => {});
+        onPrivacyModeChanged(enabled => {
+            setPrivacyModeState(enabled);
+            // The backend starts a fresh session on every switch.
+            setMessages([]);
+        }).then(fn => {
+            if (disposed) fn();
+            else unlisten = fn;
+        });
+        return () => {
+            disposed = true;
+            unlisten?.();
+        };
+    }, []);
+
+    const handlePrivacyToggle = async () => {
+        try {
+            await setPrivacyMode(!privacyMode);
+        } catch (error) {
+            console.error("[ChatPanel] Failed to toggle privacy mode:", error);
+        }
+    };
+
     // ── Stable message action callbacks ───────────────────
     const onToggleTranslation = useCallback((globalIndex: number) => {
         setExpandedTranslations(prev => {
@@ -1101,12 +1402,20 @@ export default function ChatPanel({
         if (!await ensureMemoryModelReady()) return;
 
         const messagesToDelete = msgs.length - globalIndex;
+        // 最后一轮回复：保留旧回复作为分支，而不是删除
+        const isLastTurn = !msgs.slice(userMsgIndex + 1).some(m => m.role === "user");
+        let regenerate = false;
 
         try {
-            // 先删除数据库，再更新 UI，避免竞态条件
-            await deleteLastMessages(messagesToDelete);
+            if (isLastTurn) {
+                await regenerateLastResponse();
+                regenerate = true;
+            } else {
+                // 先删除数据库，再更新 UI，避免竞态条件
+                await deleteLastMessages(messagesToDelete);
+            }
         } catch (e) {
-            console.error("[ChatPanel] Failed to delete messages:", e);
+            console.error("[ChatPanel] Failed to prepare regeneration:", e);
         }
         setMessages(prev => prev.slice(0, globalIndex));
 
@@ -1124,6 +1433,7 @@ export default function ChatPanel({
             images: userMsg.images,
             allow_image_gen: allowImageGen,
             character_id: getActiveCharacterIdForRequest(),
+            regenerate,
         }).catch(err => {
             if (isTurnCancelledError(err) || cancelRequestedRef.current) {
                 endTurnActivity();
@@ -1152,6 +1462,15 @@ export default function ChatPanel({
         }
     }, []);
 
+    const onRemember = useCallback(async (text: string) => {
+        try {
+            await rememberMessage(getActiveCharacterIdForConversationRestore(), text);
+        } catch (err) {
+            setError(getAsyncErrorMessage(err));
+            throw err;
+        }
+    }, []);
+
     const onApproveTool = useCallback(async (globalIndex: number, tool: ToolTraceItem) => {
         if (!canSubmitApproval(tool)) {
             return;
@@ -1378,8 +1697,32 @@ export default function ChatPanel({
                     <span className="font-heading text-sm font-semibold tracking-wider uppercase text-[var(--color-text-secondary)]">
                         {isStreaming ? t("chat.status.streaming") : t("chat.status.chat")}
                     </span>
+                    {privacyMode && (
+                        <span
+                            className="flex items-center gap-1 px-1.5 py-0.5 rounded border border-violet-400/40 text-[10px] font-heading font-semibold tracking-wider uppercase text-violet-300"
+                            title={t("chat.privacy.hint")}
+                        >
+                            <EyeOff size={10} strokeWidth={2} />
+                            {t("chat.status.private")}
+                        </span>
+                    )}
                 </div>
                 <div className="flex items-center gap-1">
+                    <motion.button
+                        whileHover={{ scale: 1.1 }}
+                        whileTap={{ scale: 0.95 }}
+                        onClick={handlePrivacyToggle}
+                        className={clsx(
+                            "p-2 rounded-md transition-colors",
+                            privacyMode
+                                ? "text-violet-300"
+                                : "text-[var(--color-text-muted)] hover:text-[var(--color-accent)]"
+                        )}
+                        aria-label={privacyMode ? t("chat.privacy.disable") : t("chat.privacy.enable")}
+                        title={privacyMode ? t("chat.privacy.disable") : t("chat.privacy.enable")}
+                    >
+                        <EyeOff size={14} strokeWidth={1.5} />
+                    </motion.button>
                     <motion.button
                         whileHover={{ scale: 1.1 }}
                         whileTap={{ scale: 0.95 }}
@@ -1438,19 +1781,77 @@ export default function ChatPanel({
                                 onEdit={onEdit}
                                 onRegenerate={onRegenerate}
                                 onContinueFrom={onContinueFrom}
+                                onRemember={onRemember}
                                 onApproveTool={onApproveTool}
                                 onRejectTool={onRejectTool}
                             />
                         );
                     })}
 
-                    {shouldRenderTypingIndicator({ isThinking, messages: deferredMessages, activeMessageIndex: currentTurnRef.current?.messageIndex ?? null }) && <TypingIndicator />}
+                    {shouldRenderTypingIndicator({ isThinking, messages: deferredMessages, activeMessageIndex: currentTurnRef.current?.messageIndex ?? null }) && <TypingIndicator note={waitingNote} />}
                 </AnimatePresence>
                 <div ref={messagesEndRef} />
             </div>
 
             {/* Input */}
             <form onSubmit={handleSend} className="border-t border-[var(--color-border)] bg-black/20">
+                {activePlan && (
+                    <div className="mx-3 mt-2 rounded-lg border border-[var(--color-border)] bg-black/30 px-3 py-2 text-[11px] text-[var(--color-text-secondary)]">
+                        <div className="flex items-center justify-between gap-2">
+                            <span className="truncate font-medium text-[var(--color-text-primary)]">
+                                {activePlan.goal ? `${t("chat.plan.title")}: ${activePlan.goal}` : t("chat.plan.title")}
+                            </span>
+                            {activePlan.status === "running" ? (
+                                <button
+                                    type="button"
+                                    onClick={() => { abortToolPlan(activePlan.plan_id).catch(err => console.error("[Plan] Abort failed:", err)); }}
+                                    className="shrink-0 text-red-400 hover:text-red-300 transition-colors"
+                                >
+                                    {t("chat.plan.abort")}
+                                </button>
+                            ) : (
+                                <span className="shrink-0 text-[var(--color-text-muted)]">{t(`chat.plan.status.${activePlan.status}`)}</span>
+                            )}
+                        </div>
+                        <ol className="mt-1 space-y-1">
+                            {activePlan.steps.map(step => (
+                                <li key={step.index} className="flex items-center gap-2">
+                                    <span className="w-4 shrink-0 text-[var(--color-text-muted)]">{step.index + 1}.</span>
+                                    <span className="min-w-0 flex-1 truncate" title={step.result ?? step.why}>
+                                        {step.why || step.tool}
+                                    </span>
+                                    {step.status === "awaiting_confirmation" ? (
+                                        <span className="flex shrink-0 gap-1">
+                                            <button
+                                                type="button"
+                                                onClick={() => { confirmPlanStep(activePlan.plan_id, step.index, true).catch(err => console.error("[Plan] Confirm failed:", err)); }}
+                                                className="rounded px-2 py-0.5 bg-[var(--color-accent)] text-black hover:bg-white transition-colors"
+                                            >
+                                                {t("chat.plan.confirm")}
+                                            </button>
+                                            <button
+                                                type="button"
+                                                onClick={() => { confirmPlanStep(activePlan.plan_id, step.index, false).catch(err => console.error("[Plan] Decline failed:", err)); }}
+                                                className="rounded px-2 py-0.5 border border-[var(--color-border)] hover:text-[var(--color-text-primary)] transition-colors"
+                                            >
+                                                {t("chat.plan.decline")}
+                                            </button>
+                                        </span>
+                                    ) : (
+                                        <span className="shrink-0 text-[10px] uppercase tracking-wider text-[var(--color-text-muted)]">
+                                            {t(`chat.plan.step_status.${step.status}`)}
+                                        </span>
+                                    )}
+                                </li>
+                            ))}
+                        </ol>
+                    </div>
+                )}
+                {queuedSends.length > 0 && (
+                    <div className="px-4 pt-2 text-[10px] text-[var(--color-text-muted)] truncate" title={queuedSends.map(q => q.text).join("\n")}>
+                        {t("chat.status.queued", { count: queuedSends.length })}
+                    </div>
+                )}
                 {/* Pending images preview */}
                 <AnimatePresence>
                     {hasSendableImages && (
@@ -1590,23 +1991,48 @@ export default function ChatPanel({
                         </div>
                     )}
 
+                    {/* Voice session — hands-free conversation handled by the backend */}
+                    {sttEnabled && (
+                        <motion.button
+                            type="button"
+                            whileHover={{ scale: 1.1 }}
+                            whileTap={{ scale: 0.9 }}
+                            onClick={handleVoiceSessionToggle}
+                            className={clsx(
+                                "p-2.5 rounded-lg transition-all",
+                                voiceSessionActive
+                                    ? "text-[var(--color-accent)] bg-[var(--color-accent)]/15 border border-[var(--color-accent)]/30"
+                                    : "text-[var(--color-text-muted)] hover:text-[var(--color-accent)]"
+                            )}
+                            aria-label={voiceSessionActive ? t("chat.input.voice_session.stop") : t("chat.input.voice_session.start")}
+                            title={voiceSessionActive ? t("chat.input.voice_session.stop") : t("chat.input.voice_session.start")}
+                        >
+                            <AudioLines size={16} strokeWidth={1.5} />
+                        </motion.button>
+                    )}
+
                     <div className="relative flex-1">
                         <input
                             ref={compactInputRef}
                             type="text"
                             value={input}
-                            onChange={(e) => setInput(e.target.value)}
+                            onChange={(e) => handleInputChange(e.target.value)}
                             onPaste={handlePaste}
+                            onKeyDown={(e) => {
+                                // While streaming the submit button stops the reply; Enter queues instead.
+                                if (e.key === "Enter" && !e.nativeEvent.isComposing && isBusyRef.current) {
+                                    e.preventDefault();
+                                    handleSend();
+                                }
+                            }}
                             data-onboarding-id="chat-input"
                             placeholder={t("chat.input.placeholder")}
-                            disabled={isBusy}
                             className={clsx(
                                 "w-full bg-black/40 border border-[var(--color-border)]",
                                 "text-[var(--color-text-primary)] placeholder:text-[var(--color-text-muted)]",
                                 "text-sm rounded-lg pl-4 pr-8 py-2.5 font-body",
                                 "focus:outline-none focus:border-[var(--color-accent)] focus:shadow-[var(--glow-accent)]",
-                                "transition-all",
-                                isBusy && "opacity-50 cursor-not-allowed"
+                                "transition-all"
                             )}
                         />
                         <button
@@ -1669,7 +2095,7 @@ export default function ChatPanel({
                         <textarea
                             ref={expandedTextareaRef}
                             value={input}
-                            onChange={(e) => setInput(e.target.value)}
+                            onChange={(e) => handleInputChange(e.target.value)}
                             onPaste={handlePaste}
                             onKeyDown={(e) => {
                                 if (e.key === "Enter" && (e.ctrlKey || e.metaKey)) {
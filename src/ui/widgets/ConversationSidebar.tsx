@@ -1,9 +1,9 @@
 import { useState, useEffect, useCallback, useRef } from "react";
 import { motion, AnimatePresence } from "framer-motion";
 import { clsx } from "clsx";
-import { Plus, Trash2, History, X, Check, Pencil, Pin } from "lucide-react";
-import { listConversations, loadConversation, deleteConversation, createConversation, renameConversation, getConversationDisplayTitle, hasPinnedConversationState } from "../../lib/kokoro-bridge";
-import type { Conversation } from "../../lib/kokoro-bridge";
+import { Plus, Trash2, History, X, Check, Pencil, Pin, Cpu, Bookmark, RotateCcw } from "lucide-react";
+import { listConversations, loadConversation, deleteConversation, createConversation, renameConversation, getConversationDisplayTitle, hasPinnedConversationState, getLlmConfig, setConversationModel, createCheckpoint, listCheckpoints, rewindToCheckpoint, deleteCheckpoint } from "../../lib/kokoro-bridge";
+import type { Conversation, ConversationModelOverride, ConversationCheckpoint } from "../../lib/kokoro-bridge";
 import { useTranslation } from "react-i18next";
 import { buildChatMessagesFromConversation } from "./chat-history";
 
@@ -22,6 +22,11 @@ export default function ConversationSidebar({ open, onClose, onLoadMessages }: C
     const [editingId, setEditingId] = useState<string | null>(null);
     const [editTitle, setEditTitle] = useState("");
     const editInputRef = useRef<HTMLInputElement>(null);
+    const [modelEditId, setModelEditId] = useState<string | null>(null);
+    const [modelDraft, setModelDraft] = useState<ConversationModelOverride>({});
+    const [providerIds, setProviderIds] = useState<string[]>([]);
+    const [checkpointsId, setCheckpointsId] = useState<string | null>(null);
+    const [checkpoints, setCheckpoints] = useState<ConversationCheckpoint[]>([]);
 
     const characterId = localStorage.getItem("kokoro_active_character_id") || "default";
 
@@ -102,6 +107,81 @@ export default function ConversationSidebar({ open, onClose, onLoadMessages }: C
         setEditingId(null);
     };
 
+    const handleModelEditStart = async (e: React.MouseEvent, conv: Conversation) => {
+        e.stopPropagation();
+        if (modelEditId === conv.id) {
+            setModelEditId(null);
+            return;
+        }
+        setModelDraft(conv.model_override ?? {});
+        setModelEditId(conv.id);
+        try {
+            const config = await getLlmConfig();
+            setProviderIds(config.providers.filter(p => p.enabled).map(p => p.id));
+        } catch (err) {
+            console.error("[ConversationSidebar] Failed to load LLM providers:", err);
+        }
+    };
+
+    const handleModelSave = async (id: string, override: ConversationModelOverride) => {
+        try {
+            await setConversationModel({
+                provider_id: override.provider_id || null,
+                model: override.model?.trim() || null,
+                temperature: override.temperature ?? null,
+            }, id);
+            setModelEditId(null);
+            refresh();
+        } catch (err) {
+            console.error("[ConversationSidebar] Failed to set conversation model:", err);
+        }
+    };
+
+    const handleCheckpointsToggle = async (e: React.MouseEvent, id: string) => {
+        e.stopPropagation();
+        if (checkpointsId === id) {
+            setCheckpointsId(null);
+            return;
+        }
+        setCheckpointsId(id);
+        try {
+            setCheckpoints(await listCheckpoints(id));
+        } catch (err) {
+            console.error("[ConversationSidebar] Failed to list checkpoints:", err);
+            setCheckpoints([]);
+        }
+    };
+
+    const handleCheckpointSave = async (id: string) => {
+        try {
+            await createCheckpoint(undefined, id);
+            setCheckpoints(await listCheckpoints(id));
+        } catch (err) {
+            console.error("[ConversationSidebar] Failed to save checkpoint:", err);
+        }
+    };
+
+    const handleRewind = async (checkpoint: ConversationCheckpoint) => {
+        if (!confirm(t("chat.history.checkpoints.confirmRewind"))) return;
+        try {
+            const loaded = await rewindToCheckpoint(checkpoint.id);
+            setActiveId(checkpoint.conversation_id);
+            onLoadMessages(buildChatMessagesFromConversation(loaded.messages));
+            refresh();
+        } catch (err) {
+            console.error("[ConversationSidebar] Failed to rewind:", err);
+        }
+    };
+
+    const handleCheckpointDelete = async (checkpoint: ConversationCheckpoint) => {
+        try {
+            await deleteCheckpoint(checkpoint.id);
+            setCheckpoints(prev => prev.filter(c => c.id !== checkpoint.id));
+        } catch (err) {
+            console.error("[ConversationSidebar] Failed to delete checkpoint:", err);
+        }
+    };
+
     const handleRenameKeyDown = (e: React.KeyboardEvent, id: string) => {
         if (e.key === "Enter") handleRenameConfirm(id);
         if (e.key === "Escape") setEditingId(null);
@@ -179,71 +259,202 @@ export default function ConversationSidebar({ open, onClose, onLoadMessages }: C
                             </div>
                         ) : (
                             conversations.map(conv => (
-                                <div
-                                    key={conv.id}
-                                    onClick={() => handleLoad(conv.id)}
-                                    className={clsx(
-                                        "group flex items-center gap-2 px-3 py-2.5 rounded-lg cursor-pointer transition-colors",
-                                        activeId === conv.id
-                                            ? "bg-[var(--color-accent)]/10 border border-[var(--color-accent)]/30"
-                                            : "hover:bg-white/5 border border-transparent"
+                                <div key={conv.id}>
+                                    <div
+                                        onClick={() => handleLoad(conv.id)}
+                                        className={clsx(
+                                            "group flex items-center gap-2 px-3 py-2.5 rounded-lg cursor-pointer transition-colors",
+                                            activeId === conv.id
+                                                ? "bg-[var(--color-accent)]/10 border border-[var(--color-accent)]/30"
+                                                : "hover:bg-white/5 border border-transparent"
+                                        )}
+                                    >
+                                        <div className="flex-1 min-w-0">
+                                            {editingId === conv.id ? (
+                                                <div className="flex items-center gap-1">
+                                                    <input
+                                                        ref={editInputRef}
+                                                        value={editTitle}
+                                                        onChange={e => setEditTitle(e.target.value)}
+                                                        onKeyDown={e => handleRenameKeyDown(e, conv.id)}
+                                                        onBlur={() => handleRenameConfirm(conv.id)}
+                                                        className="flex-1 bg-black/40 border border-[var(--color-border)] text-xs text-[var(--color-text-primary)] rounded px-1.5 py-0.5 focus:outline-none focus:border-[var(--color-accent)]"
+                                                        onClick={e => e.stopPropagation()}
+                                                    />
+                                                    <button
+                                                        onClick={(e) => { e.stopPropagation(); handleRenameConfirm(conv.id); }}
+                                                        className="p-0.5 text-[var(--color-accent)] hover:text-[var(--color-accent)]"
+                                                    >
+                                                        <Check size={12} strokeWidth={2} />
+                                                    </button>
+                                                </div>
+                                            ) : (
+                                                <>
+                                                    <div className="text-xs text-[var(--color-text-primary)] truncate">
+                                                        {getConversationDisplayTitle(conv)}
+                                                    </div>
+                                                    <div className="mt-0.5 flex items-center gap-1 text-[10px] text-[var(--color-text-muted)]">
+                                                        <span>{formatTime(conv.updated_at)}</span>
+                                                        {conv.topic.trim() && (
+                                                            <span className="truncate max-w-[110px]">· {conv.topic}</span>
+                                                        )}
+                                                        {hasPinnedConversationState(conv.pinned_state) && (
+                                                            <span className="inline-flex items-center gap-0.5 text-[var(--color-accent)]">
+                                                                <Pin size={9} strokeWidth={1.5} />
+                                                                已固定
+                                                            </span>
+                                                        )}
+                                                        {conv.model_override && (
+                                                            <span
+                                                                className="inline-flex items-center gap-0.5 text-[var(--color-accent)] truncate max-w-[90px]"
+                                                                title={t("chat.history.model.pinned")}
+                                                            >
+                                                                <Cpu size={9} strokeWidth={1.5} />
+                                                                {conv.model_override.model || conv.model_override.provider_id || `T ${conv.model_override.temperature}`}
+                                                            </span>
+                                                        )}
+                                                    </div>
+                                                </>
+                                            )}
+                                        </div>
+                                        {editingId !== conv.id && (
+                                            <div className="flex items-center gap-0.5 opacity-0 group-hover:opacity-100 transition-opacity">
+                                                <button
+                                                    onClick={(e) => { void handleCheckpointsToggle(e, conv.id); }}
+                                                    className="p-1 rounded text-[var(--color-text-muted)] hover:text-[var(--color-accent)] transition-colors"
+                                                    title={t("chat.history.checkpoints.title")}
+                                                >
+                                                    <Bookmark size={12} strokeWidth={1.5} />
+                                                </button>
+                                                <button
+                                                    onClick={(e) => { void handleModelEditStart(e, conv); }}
+                                                    className="p-1 rounded text-[var(--color-text-muted)] hover:text-[var(--color-accent)] transition-colors"
+                                                    title={t("chat.history.model.title")}
+                                                >
+                                                    <Cpu size={12} strokeWidth={1.5} />
+                                                </button>
+                                                <button
+                                                    onClick={(e) => handleRenameStart(e, conv)}
+                                                    className="p-1 rounded text-[var(--color-text-muted)] hover:text-[var(--color-accent)] transition-colors"
+                                                    title={t("chat.history.rename")}
+                                                >
+                                                    <Pencil size={12} strokeWidth={1.5} />
+                                                </button>
+                                                <button
+                                                    onClick={(e) => handleDelete(e, conv.id)}
+                                                    className="p-1 rounded text-[var(--color-text-muted)] hover:text-[var(--color-error)] transition-colors"
+                                                    title={t("chat.history.delete")}
+                                                >
+                                                    <Trash2 size={12} strokeWidth={1.5} />
+                                                </button>
+                                            </div>
+                                        )}
+                                    </div>
+                                    {checkpointsId === conv.id && (
+                                        <div
+                                            onClick={e => e.stopPropagation()}
+                                            className="mt-1 space-y-1 rounded-lg border border-[var(--color-border)] bg-black/20 p-2 text-[10px] text-[var(--color-text-muted)]"
+                                        >
+                                            <div className="flex items-center justify-between">
+                                                <span className="font-semibold uppercase tracking-wider">{t("chat.history.checkpoints.title")}</span>
+                                                <button
+                                                    onClick={() => { void handleCheckpointSave(conv.id); }}
+                                                    className="px-2 py-0.5 rounded bg-[var(--color-accent)] text-black hover:bg-white transition-colors"
+                                                >
+                                                    {t("chat.history.checkpoints.save")}
+                                                </button>
+                                            </div>
+                                            {checkpoints.length === 0 ? (
+                                                <div className="py-1">{t("chat.history.checkpoints.empty")}</div>
+                                            ) : (
+                                                checkpoints.map(checkpoint => (
+                                                    <div key={checkpoint.id} className="flex items-center gap-1">
+                                                        <div className="flex-1 min-w-0">
+                                                            <div className="truncate text-[var(--color-text-primary)]">{checkpoint.label}</div>
+                                                            <div>
+                                                                {formatTime(new Date(checkpoint.created_at * 1000).toISOString())}
+                                                                {" · "}
+                                                                {t("chat.history.checkpoints.messages", { count: checkpoint.message_count })}
+                                                            </div>
+                                                        </div>
+                                                        <button
+                                                            onClick={() => { void handleRewind(checkpoint); }}
+                                                            className="p-1 rounded hover:text-[var(--color-accent)] transition-colors"
+                                                            title={t("chat.history.checkpoints.rewind")}
+                                                        >
+                                                            <RotateCcw size={11} strokeWidth={1.5} />
+                                                        </button>
+                                                        <button
+                                                            onClick={() => { void handleCheckpointDelete(checkpoint); }}
+                                                            className="p-1 rounded hover:text-[var(--color-error)] transition-colors"
+                                                            title={t("chat.history.checkpoints.delete")}
+                                                        >
+                                                            <Trash2 size={11} strokeWidth={1.5} />
+                                                        </button>
+                                                    </div>
+                                                ))
+                                            )}
+                                        </div>
                                     )}
-                                >
-                                    <div className="flex-1 min-w-0">
-                                        {editingId === conv.id ? (
-                                            <div className="flex items-center gap-1">
+                                    {modelEditId === conv.id && (
+                                        <div
+                                            onClick={e => e.stopPropagation()}
+                                            className="mt-1 space-y-1.5 rounded-lg border border-[var(--color-border)] bg-black/20 p-2 text-[10px] text-[var(--color-text-muted)]"
+                                        >
+                                            <div className="font-semibold uppercase tracking-wider">{t("chat.history.model.title")}</div>
+                                            <label className="flex items-center justify-between gap-2">
+                                                {t("chat.history.model.provider")}
+                                                <select
+                                                    value={modelDraft.provider_id ?? ""}
+                                                    onChange={e => setModelDraft(d => ({ ...d, provider_id: e.target.value || null }))}
+                                                    className="w-[120px] bg-black/40 border border-[var(--color-border)] text-[var(--color-text-primary)] rounded px-1 py-0.5 focus:outline-none focus:border-[var(--color-accent)]"
+                                                >
+                                                    <option value="">{t("chat.history.model.inherit")}</option>
+                                                    {providerIds.map(id => (
+                                                        <option key={id} value={id}>{id}</option>
+                                                    ))}
+                                                </select>
+                                            </label>
+                                            <label className="flex items-center justify-between gap-2">
+                                                {t("chat.history.model.model")}
+                                                <input
+                                                    value={modelDraft.model ?? ""}
+                                                    onChange={e => setModelDraft(d => ({ ...d, model: e.target.value }))}
+                                                    placeholder={t("chat.history.model.model_placeholder")}
+                                                    className="w-[120px] bg-black/40 border border-[var(--color-border)] text-[var(--color-text-primary)] rounded px-1.5 py-0.5 focus:outline-none focus:border-[var(--color-accent)]"
+                                                />
+                                            </label>
+                                            <label className="flex items-center justify-between gap-2">
+                                                {t("chat.history.model.temperature")}
                                                 <input
-                                                    ref={editInputRef}
-                                                    value={editTitle}
-                                                    onChange={e => setEditTitle(e.target.value)}
-                                                    onKeyDown={e => handleRenameKeyDown(e, conv.id)}
-                                                    onBlur={() => handleRenameConfirm(conv.id)}
-                                                    className="flex-1 bg-black/40 border border-[var(--color-border)] text-xs text-[var(--color-text-primary)] rounded px-1.5 py-0.5 focus:outline-none focus:border-[var(--color-accent)]"
-                                                    onClick={e => e.stopPropagation()}
+                                                    type="number"
+                                                    min={0}
+                                                    max={2}
+                                                    step={0.1}
+                                                    value={modelDraft.temperature ?? ""}
+                                                    onChange={e => setModelDraft(d => ({
+                                                        ...d,
+                                                        temperature: e.target.value === "" ? null : Number(e.target.value),
+                                                    }))}
+                                                    className="w-[120px] bg-black/40 border border-[var(--color-border)] text-[var(--color-text-primary)] rounded px-1.5 py-0.5 focus:outline-none focus:border-[var(--color-accent)]"
                                                 />
+                                            </label>
+                                            <div className="flex justify-end gap-1 pt-0.5">
+                                                {conv.model_override && (
+                                                    <button
+                                                        onClick={() => { void handleModelSave(conv.id, {}); }}
+                                                        className="px-2 py-0.5 rounded border border-[var(--color-border)] hover:text-[var(--color-error)] transition-colors"
+                                                    >
+                                                        {t("chat.history.model.clear")}
+                                                    </button>
+                                                )}
                                                 <button
-                                                    onClick={(e) => { e.stopPropagation(); handleRenameConfirm(conv.id); }}
-                                                    className="p-0.5 text-[var(--color-accent)] hover:text-[var(--color-accent)]"
+                                                    onClick={() => { void handleModelSave(conv.id, modelDraft); }}
+                                                    className="px-2 py-0.5 rounded bg-[var(--color-accent)] text-black hover:bg-white transition-colors"
                                                 >
-                                                    <Check size={12} strokeWidth={2} />
+                                                    {t("chat.history.model.save")}
                                                 </button>
                                             </div>
-                                        ) : (
-                                            <>
-                                                <div className="text-xs text-[var(--color-text-primary)] truncate">
-                                                    {getConversationDisplayTitle(conv)}
-                                                </div>
-                                                <div className="mt-0.5 flex items-center gap-1 text-[10px] text-[var(--color-text-muted)]">
-                                                    <span>{formatTime(conv.updated_at)}</span>
-                                                    {conv.topic.trim() && (
-                                                        <span className="truncate max-w-[110px]">· {conv.topic}</span>
-                                                    )}
-                                                    {hasPinnedConversationState(conv.pinned_state) && (
-                                                        <span className="inline-flex items-center gap-0.5 text-[var(--color-accent)]">
-                                                            <Pin size={9} strokeWidth={1.5} />
-                                                            已固定
-                                                        </span>
-                                                    )}
-                                                </div>
-                                            </>
-                                        )}
-                                    </div>
-                                    {editingId !== conv.id && (
-                                        <div className="flex items-center gap-0.5 opacity-0 group-hover:opacity-100 transition-opacity">
-                                            <button
-                                                onClick={(e) => handleRenameStart(e, conv)}
-                                                className="p-1 rounded text-[var(--color-text-muted)] hover:text-[var(--color-accent)] transition-colors"
-                                                title={t("chat.history.rename")}
-                                            >
-                                                <Pencil size={12} strokeWidth={1.5} />
-                                            </button>
-                                            <button
-                                                onClick={(e) => handleDelete(e, conv.id)}
-                                                className="p-1 rounded text-[var(--color-text-muted)] hover:text-[var(--color-error)] transition-colors"
-                                                title={t("chat.history.delete")}
-                                            >
-                                                <Trash2 size={12} strokeWidth={1.5} />
-                                            </button>
                                         </div>
                                     )}
                                 </div>
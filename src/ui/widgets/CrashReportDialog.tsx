@@ -0,0 +1,123 @@
+import { AnimatePresence, motion } from "framer-motion";
+import { AlertTriangle, Check, Copy } from "lucide-react";
+import { useEffect, useState } from "react";
+import { useTranslation } from "react-i18next";
+import {
+    dismissCrashReport,
+    getLastCrashReport,
+    type CrashReport,
+} from "../../lib/kokoro-bridge";
+
+/**
+ * Shown once after launch when the previous session crashed.
+ * The report never leaves the machine unless the user copies it.
+ */
+export default function CrashReportDialog() {
+    const { t } = useTranslation();
+    const [report, setReport] = useState<CrashReport | null>(null);
+    const [copied, setCopied] = useState(false);
+
+    useEffect(() => {
+        getLastCrashReport()
+            .then(setReport)
+            .catch((e) => console.error("[CrashReportDialog] Failed to load crash report:", e));
+    }, []);
+
+    const copy = async () => {
+        if (!report) return;
+        try {
+            await navigator.clipboard.writeText(JSON.stringify(report, null, 2));
+            setCopied(true);
+        } catch (e) {
+            console.error("[CrashReportDialog] Failed to copy crash report:", e);
+        }
+    };
+
+    const dismiss = async () => {
+        setReport(null);
+        try {
+            await dismissCrashReport();
+        } catch (e) {
+            console.error("[CrashReportDialog] Failed to dismiss crash report:", e);
+        }
+    };
+
+    return (
+        <AnimatePresence>
+            {report && (
+                <motion.div
+                    initial={{ opacity: 0 }}
+                    animate={{ opacity: 1 }}
+                    exit={{ opacity: 0 }}
+                    className="fixed inset-0 z-[180] flex items-center justify-center bg-black/65 px-4 backdrop-blur-sm"
+                >
+                    <motion.div
+                        initial={{ opacity: 0, y: 20, scale: 0.98 }}
+                        animate={{ opacity: 1, y: 0, scale: 1 }}
+                        exit={{ opacity: 0, y: 12, scale: 0.98 }}
+                        transition={{ type: "spring", stiffness: 280, damping: 28 }}
+                        className="w-full max-w-[560px] rounded-3xl border border-red-400/40 bg-[var(--color-bg-elevated)]/95 shadow-2xl backdrop-blur-2xl"
+                    >
+                        <div className="space-y-2 border-b border-[var(--color-border)] px-6 py-5">
+                            <div className="flex items-center gap-2 text-[11px] font-heading font-semibold uppercase tracking-[0.18em] text-red-300">
+                                <AlertTriangle size={14} strokeWidth={1.6} />
+                                {t("crash_report.title")}
+                            </div>
+                            <p className="text-sm leading-6 text-[var(--color-text-secondary)]">
+                                {t("crash_report.desc")}
+                            </p>
+                        </div>
+
+                        <div className="space-y-3 px-6 py-5">
+                            <div className="text-xs font-heading font-semibold uppercase tracking-[0.14em] text-[var(--color-text-muted)]">
+                                {t("crash_report.message")}
+                            </div>
+                            <pre className="max-h-24 overflow-auto rounded-2xl border border-[var(--color-border)] bg-black/30 p-3 text-xs leading-relaxed text-[var(--color-text-primary)] whitespace-pre-wrap break-all">
+                                {report.message}
+                                {report.location && `\n${report.location}`}
+                            </pre>
+                            {report.active_subsystems.length > 0 && (
+                                <div className="text-xs text-[var(--color-text-muted)]">
+                                    {t("crash_report.subsystems")}: {report.active_subsystems.join(", ")}
+                                </div>
+                            )}
+                            {report.recent_logs.length > 0 && (
+                                <>
+                                    <div className="text-xs font-heading font-semibold uppercase tracking-[0.14em] text-[var(--color-text-muted)]">
+                                        {t("crash_report.recent_logs")}
+                                    </div>
+                                    <pre className="max-h-48 overflow-auto rounded-2xl border border-[var(--color-border)] bg-black/30 p-3 text-[11px] leading-relaxed text-[var(--color-text-secondary)] whitespace-pre-wrap break-all">
+                                        {report.recent_logs.slice(-20).join("\n")}
+                                    </pre>
+                                </>
+                            )}
+                            {report.path && (
+                                <div className="text-[11px] text-[var(--color-text-muted)] break-all">
+                                    {t("crash_report.saved_at", { path: report.path })}
+                                </div>
+                            )}
+                        </div>
+
+                        <div className="flex justify-end gap-2 border-t border-[var(--color-border)] px-6 py-4">
+                            <button
+                                type="button"
+                                onClick={() => { void copy(); }}
+                                className="flex items-center gap-1.5 rounded-lg border border-[var(--color-border)] px-4 py-2 text-xs font-heading font-semibold uppercase tracking-wider text-[var(--color-text-secondary)] transition-colors hover:border-[var(--color-accent)] hover:text-[var(--color-accent)]"
+                            >
+                                {copied ? <Check size={12} /> : <Copy size={12} />}
+                                {copied ? t("crash_report.copied") : t("crash_report.copy")}
+                            </button>
+                            <button
+                                type="button"
+                                onClick={() => { void dismiss(); }}
+                                className="rounded-lg bg-[var(--color-accent)] px-4 py-2 text-xs font-heading font-semibold uppercase tracking-wider text-black transition-colors hover:bg-white"
+                            >
+                                {t("crash_report.dismiss")}
+                            </button>
+                        </div>
+                    </motion.div>
+                </motion.div>
+            )}
+        </AnimatePresence>
+    );
+}
@@ -0,0 +1,207 @@
+import { useEffect, useRef, useState } from "react";
+import { clsx } from "clsx";
+import { useTranslation } from "react-i18next";
+import { Play, Square, Send } from "lucide-react";
+import { inputClasses, labelClasses } from "../styles/settings-primitives";
+import {
+    listCharacters,
+    startDuet,
+    stopDuet,
+    interjectDuet,
+    getDuetStatus,
+    onDuetDelta,
+    onDuetLine,
+    onDuetInterjection,
+    onDuetFinish,
+} from "../../lib/kokoro-bridge";
+import type { CharacterRecord } from "../../lib/kokoro-bridge";
+
+interface TranscriptEntry {
+    key: string;
+    name: string | null;
+    text: string;
+    emotion?: string;
+    pending?: boolean;
+}
+
+const smallInput = clsx(inputClasses, "py-1.5 px-2 text-xs");
+
+export default function DuetPanel() {
+    const { t } = useTranslation();
+    const [characters, setCharacters] = useState<CharacterRecord[]>([]);
+    const [first, setFirst] = useState("");
+    const [second, setSecond] = useState("");
+    const [topic, setTopic] = useState("");
+    const [turns, setTurns] = useState(8);
+    const [speak, setSpeak] = useState(true);
+    const [active, setActive] = useState(false);
+    const [transcript, setTranscript] = useState<TranscriptEntry[]>([]);
+    const [interjection, setInterjection] = useState("");
+    const [error, setError] = useState<string | null>(null);
+    const namesRef = useRef<Record<string, string>>({});
+
+    useEffect(() => {
+        listCharacters()
+            .then(list => {
+                setCharacters(list);
+                namesRef.current = Object.fromEntries(list.map(c => [c.id, c.name]));
+                setFirst(prev => prev || list[0]?.id || "");
+                setSecond(prev => prev || list[1]?.id || "");
+            })
+            .catch(() => setCharacters([]));
+        getDuetStatus().then(status => setActive(status.active)).catch(() => undefined);
+
+        const upsertLine = (entry: TranscriptEntry) =>
+            setTranscript(prev => {
+                const index = prev.findIndex(e => e.key === entry.key);
+                if (index === -1) return [...prev, entry];
+                const next = [...prev];
+                next[index] = entry;
+                return next;
+            });
+
+        const unlisteners = [
+            onDuetDelta(({ turn, speaker_id, text }) =>
+                upsertLine({ key: `line-${turn}`, name: namesRef.current[speaker_id] ?? speaker_id, text, pending: true })),
+            onDuetLine(line =>
+                upsertLine({ key: `line-${line.turn}`, name: line.name, text: line.text, emotion: line.emotion })),
+            onDuetInterjection(({ turn, text }) =>
+                setTranscript(prev => [...prev, { key: `user-${turn}-${prev.length}`, name: null, text }])),
+            onDuetFinish(({ status, error }) => {
+                setActive(false);
+                if (status === "error") setError(error ?? null);
+            }),
+        ];
+        return () => {
+            unlisteners.forEach(p => p.then(fn => fn()));
+        };
+    }, []);
+
+    const start = () => {
+        setError(null);
+        setTranscript([]);
+        startDuet({ characters: [first, second], topic, turns, speak })
+            .then(status => setActive(status.active))
+            .catch(err => setError(String(err)));
+    };
+
+    const stop = () => {
+        stopDuet().then(() => setActive(false)).catch(err => setError(String(err)));
+    };
+
+    const sendInterjection = () => {
+        const text = interjection.trim();
+        if (!text) return;
+        interjectDuet(text)
+            .then(() => setInterjection(""))
+            .catch(err => setError(String(err)));
+    };
+
+    const canStart = !!first && !!second && first !== second && topic.trim().length > 0;
+
+    return (
+        <div className="space-y-3 p-4 rounded-lg border border-[var(--color-border)] bg-[var(--color-bg-elevated)]">
+            <div>
+                <h3 className="text-sm font-heading font-bold text-[var(--color-text-primary)]">
+                    {t("settings.duet.title")}
+                </h3>
+                <p className="text-xs text-[var(--color-text-muted)]">
+                    {t("settings.duet.desc")}
+                </p>
+            </div>
+
+            <div className="grid grid-cols-2 gap-2">
+                {[
+                    { value: first, set: setFirst, label: t("settings.duet.first") },
+                    { value: second, set: setSecond, label: t("settings.duet.second") },
+                ].map(({ value, set, label }) => (
+                    <div key={label}>
+                        <label className={labelClasses}>{label}</label>
+                        <select value={value} onChange={e => set(e.target.value)} disabled={active} className={smallInput}>
+                            {characters.map(c => <option key={c.id} value={c.id}>{c.name}</option>)}
+                        </select>
+                    </div>
+                ))}
+            </div>
+
+            <div>
+                <label className={labelClasses}>{t("settings.duet.topic")}</label>
+                <input
+                    value={topic}
+                    onChange={e => setTopic(e.target.value)}
+                    placeholder={t("settings.duet.topic_placeholder")}
+                    disabled={active}
+                    className={smallInput}
+                />
+            </div>
+
+            <div className="flex items-center gap-4">
+                <div className="flex items-center gap-2">
+                    <label className={clsx(labelClasses, "mb-0")}>{t("settings.duet.turns")}</label>
+                    <input
+                        type="number"
+                        min={1}
+                        max={40}
+                        value={turns}
+                        onChange={e => setTurns(Math.min(40, Math.max(1, Number(e.target.value) || 1)))}
+                        disabled={active}
+                        className={clsx(smallInput, "w-16")}
+                    />
+                </div>
+                <label className="flex items-center gap-2 text-xs text-[var(--color-text-secondary)]">
+                    <input type="checkbox" checked={speak} onChange={e => setSpeak(e.target.checked)} disabled={active} />
+                    {t("settings.duet.speak")}
+                </label>
+                <div className="flex-1" />
+                {active ? (
+                    <button onClick={stop} className="px-3 py-1 text-xs rounded-md bg-red-500/80 text-white flex items-center gap-1">
+                        <Square size={12} />
+                        {t("settings.duet.stop")}
+                    </button>
+                ) : (
+                    <button
+                        onClick={start}
+                        disabled={!canStart}
+                        className="px-3 py-1 text-xs rounded-md bg-[var(--color-accent)] text-black disabled:opacity-40 flex items-center gap-1"
+                    >
+                        <Play size={12} />
+                        {t("settings.duet.start")}
+                    </button>
+                )}
+            </div>
+
+            {transcript.length > 0 && (
+                <div className="max-h-64 overflow-y-auto space-y-1.5 rounded-md bg-black/20 p-3 text-xs">
+                    {transcript.map(entry => (
+                        <p key={entry.key} className={clsx(entry.pending && "opacity-70")}>
+                            <span className="font-bold text-[var(--color-accent)]">
+                                {entry.name ?? t("settings.duet.you")}
+                            </span>
+                            {entry.emotion && entry.emotion !== "neutral" && (
+                                <span className="text-[var(--color-text-muted)]"> ({entry.emotion})</span>
+                            )}
+                            <span className="text-[var(--color-text-primary)]">: {entry.text}</span>
+                        </p>
+                    ))}
+                </div>
+            )}
+
+            {active && (
+                <div className="flex gap-2">
+                    <input
+                        value={interjection}
+                        onChange={e => setInterjection(e.target.value)}
+                        onKeyDown={e => e.key === "Enter" && sendInterjection()}
+                        placeholder={t("settings.duet.interject_placeholder")}
+                        className={smallInput}
+                    />
+                    <button onClick={sendInterjection} className="text-[var(--color-text-muted)] hover:text-[var(--color-accent)]">
+                        <Send size={14} />
+                    </button>
+                </div>
+            )}
+
+            {error && <p className="text-xs text-red-400">{error}</p>}
+        </div>
+    );
+}
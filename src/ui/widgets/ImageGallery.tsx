@@ -0,0 +1,111 @@
+import { useCallback, useEffect, useState } from "react";
+import { useTranslation } from "react-i18next";
+import { Trash2, ImageOff } from "lucide-react";
+import { convertFileSrc } from "@tauri-apps/api/core";
+import { listGeneratedImages, deleteGeneratedImage, onImageGenDone } from "../../lib/kokoro-bridge";
+import type { GeneratedImage } from "../../lib/kokoro-bridge";
+
+const PAGE_SIZE = 24;
+
+export default function ImageGallery() {
+    const { t } = useTranslation();
+    const [images, setImages] = useState<GeneratedImage[]>([]);
+    const [selected, setSelected] = useState<GeneratedImage | null>(null);
+    const [hasMore, setHasMore] = useState(false);
+    const [error, setError] = useState<string | null>(null);
+
+    const load = useCallback((offset: number) => {
+        listGeneratedImages(undefined, PAGE_SIZE, offset)
+            .then(page => {
+                setImages(prev => (offset === 0 ? page : [...prev, ...page]));
+                setHasMore(page.length === PAGE_SIZE);
+            })
+            .catch(err => setError(String(err)));
+    }, []);
+
+    useEffect(() => {
+        load(0);
+        const unlisten = onImageGenDone(() => load(0));
+        return () => {
+            unlisten.then(fn => fn());
+        };
+    }, [load]);
+
+    const remove = (image: GeneratedImage) => {
+        setError(null);
+        deleteGeneratedImage(image.id)
+            .then(() => {
+                setImages(prev => prev.filter(i => i.id !== image.id));
+                if (selected?.id === image.id) setSelected(null);
+            })
+            .catch(err => setError(String(err)));
+    };
+
+    return (
+        <div className="space-y-3 p-4 rounded-lg border border-[var(--color-border)] bg-[var(--color-bg-elevated)]">
+            <div>
+                <h3 className="text-sm font-heading font-bold text-[var(--color-text-primary)]">
+                    {t("settings.image_gen.gallery.title")}
+                </h3>
+                <p className="text-xs text-[var(--color-text-muted)]">
+                    {t("settings.image_gen.gallery.desc")}
+                </p>
+            </div>
+
+            {images.length === 0 ? (
+                <p className="text-xs text-[var(--color-text-muted)]">{t("settings.image_gen.gallery.empty")}</p>
+            ) : (
+                <div className="grid grid-cols-4 gap-2">
+                    {images.map(image => (
+                        <button
+                            key={image.id}
+                            onClick={() => setSelected(selected?.id === image.id ? null : image)}
+                            title={image.prompt}
+                            className="aspect-square rounded overflow-hidden border border-[var(--color-border)] hover:border-[var(--color-accent)] bg-black/40 flex items-center justify-center"
+                        >
+                            {image.file_exists ? (
+                                <img src={convertFileSrc(image.image_path)} className="w-full h-full object-cover" />
+                            ) : (
+                                <ImageOff size={16} className="text-[var(--color-text-muted)]" />
+                            )}
+                        </button>
+                    ))}
+                </div>
+            )}
+
+            {hasMore && (
+                <button
+                    onClick={() => load(images.length)}
+                    className="text-[10px] uppercase tracking-wider text-[var(--color-accent)] hover:underline"
+                >
+                    {t("settings.image_gen.gallery.more")}
+                </button>
+            )}
+
+            {selected && (
+                <div className="space-y-1 text-xs rounded-md bg-black/20 p-3">
+                    <p className="text-[var(--color-text-primary)] whitespace-pre-wrap">{selected.prompt}</p>
+                    {selected.negative_prompt && (
+                        <p className="text-[var(--color-text-muted)]">
+                            {t("settings.image_gen.gallery.negative")}: {selected.negative_prompt}
+                        </p>
+                    )}
+                    <p className="text-[var(--color-text-muted)]">
+                        {selected.provider_id}
+                        {selected.seed !== null && ` · ${t("settings.image_gen.gallery.seed")} ${selected.seed}`}
+                        {` · ${new Date(selected.created_at * 1000).toLocaleString()}`}
+                    </p>
+                    <button
+                        onClick={() => remove(selected)}
+                        className="flex items-center gap-1 text-[var(--color-text-muted)] hover:text-red-400"
+                    >
+                        <Trash2 size={12} />
+                        {t("settings.image_gen.gallery.delete")}
+                    </button>
+                </div>
+            )}
+
+            {error && <p className="text-xs text-red-400">{error}</p>}
+        </div>
+    );
+}
@@ -0,0 +1,83 @@
+import { AnimatePresence, motion } from "framer-motion";
+import { ImageIcon } from "lucide-react";
+import { useEffect, useRef, useState } from "react";
+import { useTranslation } from "react-i18next";
+import {
+    onImageGenDone,
+    onImageGenError,
+    onImageGenProgress,
+    type ImageGenProgress,
+} from "../../lib/kokoro-bridge";
+
+/** Progress stops arriving when a generation fails outside the chat flow. */
+const STALE_AFTER_MS = 4000;
+
+/**
+ * Progress bar with a live preview while an image is generating.
+ * Only providers that report progress (Stable Diffusion WebUI) show it.
+ */
+export default function ImageGenProgressToast() {
+    const { t } = useTranslation();
+    const [progress, setProgress] = useState<ImageGenProgress | null>(null);
+    const staleTimer = useRef<ReturnType<typeof setTimeout> | null>(null);
+
+    useEffect(() => {
+        const clear = () => {
+            if (staleTimer.current) clearTimeout(staleTimer.current);
+            staleTimer.current = null;
+            setProgress(null);
+        };
+        const unlistenProgress = onImageGenProgress((next) => {
+            setProgress(prev => ({ ...next, preview: next.preview ?? prev?.preview ?? null }));
+            if (staleTimer.current) clearTimeout(staleTimer.current);
+            staleTimer.current = setTimeout(clear, STALE_AFTER_MS);
+        });
+        const unlistenDone = onImageGenDone(clear);
+        const unlistenError = onImageGenError(clear);
+        return () => {
+            if (staleTimer.current) clearTimeout(staleTimer.current);
+            unlistenProgress.then(fn => fn());
+            unlistenDone.then(fn => fn());
+            unlistenError.then(fn => fn());
+        };
+    }, []);
+
+    return (
+        <AnimatePresence>
+            {progress && (
+                <motion.div
+                    initial={{ opacity: 0, y: 12 }}
+                    animate={{ opacity: 1, y: 0 }}
+                    exit={{ opacity: 0, y: 12 }}
+                    className="fixed bottom-4 left-4 z-[120] w-56 space-y-2 rounded-2xl border border-[var(--color-border)] bg-[var(--color-bg-elevated)]/95 p-3 shadow-xl backdrop-blur-xl"
+                >
+                    {progress.preview && (
+                        <img src={progress.preview} className="w-full rounded-lg object-cover" />
+                    )}
+                    <div className="flex items-center justify-between text-[11px] text-[var(--color-text-secondary)]">
+                        <span className="flex items-center gap-1.5">
+                            <ImageIcon size={12} />
+                            {t("image_gen_progress.generating")}
+                        </span>
+                        <span className="tabular-nums text-[var(--color-text-muted)]">
+                            {progress.step !== null && progress.steps !== null
+                                ? t("image_gen_progress.steps", { step: progress.step, steps: progress.steps })
+                                : `${Math.round(progress.percent)}%`}
+                        </span>
+                    </div>
+                    <div className="h-1.5 overflow-hidden rounded-full bg-[var(--color-border)]">
+                        <div
+                            className="h-full bg-[var(--color-accent)] transition-[width] duration-500"
+                            style={{ width: `${progress.percent}%` }}
+                        />
+                    </div>
+                    {progress.eta_secs !== null && (
+                        <p className="text-[10px] text-[var(--color-text-muted)]">
+                            {t("image_gen_progress.eta", { seconds: Math.ceil(progress.eta_secs) })}
+                        </p>
+                    )}
+                </motion.div>
+            )}
+        </AnimatePresence>
+    );
+}
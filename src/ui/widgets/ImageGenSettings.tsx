@@ -144,6 +144,29 @@ export default function ImageGenSettings({ config, onChange }: ImageGenSettingsP
                 </button>
             </div>
 
+            {/* Selfie consistency */}
+            <div className="flex items-center justify-between gap-4 p-4 rounded-lg border border-[var(--color-border)] bg-[var(--color-bg-elevated)]">
+                <div>
+                    <h3 className="text-sm font-heading font-bold text-[var(--color-text-primary)]">
+                        {t("settings.image_gen.selfie_check.title")}
+                    </h3>
+                    <p className="text-xs text-[var(--color-text-muted)]">
+                        {t("settings.image_gen.selfie_check.desc")}
+                    </p>
+                </div>
+                <input
+                    type="number"
+                    min={0}
+                    max={5}
+                    value={config.selfie_max_retries ?? 2}
+                    onChange={e => onChange({
+                        ...config,
+                        selfie_max_retries: Math.min(5, Math.max(0, Number(e.target.value) || 0)),
+                    })}
+                    className={clsx(inputClasses, "w-20 text-center")}
+                />
+            </div>
+
             {/* Providers List */}
             <div className="space-y-4">
                 <h3 className="text-xs font-heading font-bold text-[var(--color-text-muted)] uppercase tracking-wider">
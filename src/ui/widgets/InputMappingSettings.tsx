@@ -0,0 +1,306 @@
+import { useEffect, useState } from "react";
+import { motion } from "framer-motion";
+import { clsx } from "clsx";
+import { useTranslation } from "react-i18next";
+import { Plus, Play, Trash2, RefreshCw } from "lucide-react";
+import { inputClasses, labelClasses } from "../styles/settings-primitives";
+import {
+    getInputMappingConfig,
+    saveInputMappingConfig,
+    getInputMappingStatus,
+    listMidiInputs,
+    testInputMapping,
+    onInputMappingTriggered,
+} from "../../lib/kokoro-bridge";
+import type { InputMapping, InputMappingConfig, InputMappingStatus, InputTrigger, MappedAction } from "../../lib/kokoro-bridge";
+
+const defaultTrigger = (type: InputTrigger["type"]): InputTrigger => {
+    switch (type) {
+        case "http": return { type, name: "" };
+        case "midi_note": return { type, note: 36, channel: null };
+        case "keyboard": return { type, shortcut: "" };
+    }
+};
+
+const defaultAction = (type: MappedAction["type"]): MappedAction => {
+    switch (type) {
+        case "expression": return { type, cue: "" };
+        case "speak": return { type, text: "" };
+        case "toggle_voice_session": return { type };
+        case "read_aloud": return { type, summarize: true, comment: false };
+    }
+};
+
+const smallInput = clsx(inputClasses, "py-1.5 px-2 text-xs");
+
+export default function InputMappingSettings() {
+    const { t } = useTranslation();
+    const [config, setConfig] = useState<InputMappingConfig | null>(null);
+    const [status, setStatus] = useState<InputMappingStatus | null>(null);
+    const [midiPorts, setMidiPorts] = useState<string[]>([]);
+    const [dirty, setDirty] = useState(false);
+    const [error, setError] = useState<string | null>(null);
+    const [lastFired, setLastFired] = useState<string | null>(null);
+
+    const refreshPorts = () => {
+        listMidiInputs().then(setMidiPorts).catch(() => setMidiPorts([]));
+    };
+
+    useEffect(() => {
+        getInputMappingConfig().then(setConfig).catch(err => setError(String(err)));
+        getInputMappingStatus().then(setStatus).catch(() => undefined);
+        refreshPorts();
+        const unlisten = onInputMappingTriggered(({ mapping_id, label }) => setLastFired(label || mapping_id));
+        return () => {
+            unlisten.then(fn => fn());
+        };
+    }, []);
+
+    const edit = (patch: Partial<InputMappingConfig>) => {
+        if (!config) return;
+        setConfig({ ...config, ...patch });
+        setDirty(true);
+    };
+
+    const editMapping = (index: number, patch: Partial<InputMapping>) => {
+        if (!config) return;
+        edit({ mappings: config.mappings.map((m, i) => (i === index ? { ...m, ...patch } : m)) });
+    };
+
+    const addMapping = () => {
+        if (!config) return;
+        edit({
+            mappings: [
+                ...config.mappings,
+                {
+                    id: crypto.randomUUID(),
+                    label: "",
+                    enabled: true,
+                    trigger: defaultTrigger("keyboard"),
+                    action: defaultAction("expression"),
+                },
+            ],
+        });
+    };
+
+    const save = (next: InputMappingConfig) => {
+        setError(null);
+        saveInputMappingConfig(next)
+            .then(result => {
+                setStatus(result);
+                setDirty(false);
+            })
+            .catch(err => setError(String(err)));
+    };
+
+    if (!config) return null;
+
+    return (
+        <div className="space-y-3 p-4 rounded-lg border border-[var(--color-border)] bg-[var(--color-bg-elevated)]">
+            <div className="flex items-center justify-between">
+                <div>
+                    <h3 className="text-sm font-heading font-bold text-[var(--color-text-primary)]">
+                        {t("settings.input_mapping.title")}
+                    </h3>
+                    <p className="text-xs text-[var(--color-text-muted)]">
+                        {t("settings.input_mapping.desc")}
+                    </p>
+                </div>
+                <button
+                    onClick={() => {
+                        const next = { ...config, enabled: !config.enabled };
+                        setConfig(next);
+                        save(next);
+                    }}
+                    className={clsx(
+                        "w-10 h-6 rounded-full transition-colors relative shrink-0",
+                        config.enabled ? "bg-[var(--color-accent)]" : "bg-[var(--color-border)]"
+                    )}
+                >
+                    <motion.div
+                        animate={{ x: config.enabled ? 18 : 2 }}
+                        className="absolute top-1 w-4 h-4 rounded-full bg-white shadow-sm"
+                    />
+                </button>
+            </div>
+
+            {config.enabled && (
+                <>
+                    <div>
+                        <label className={labelClasses}>{t("settings.input_mapping.midi_port")}</label>
+                        <div className="flex gap-2">
+                            <select
+                                value={config.midi_port ?? ""}
+                                onChange={e => edit({ midi_port: e.target.value || null })}
+                                className={smallInput}
+                            >
+                                <option value="">{t("settings.input_mapping.midi_port_first")}</option>
+                                {midiPorts.map(port => <option key={port} value={port}>{port}</option>)}
+                            </select>
+                            <button onClick={refreshPorts} className="text-[var(--color-text-muted)] hover:text-[var(--color-accent)]">
+                                <RefreshCw size={14} />
+                            </button>
+                        </div>
+                    </div>
+
+                    <div className="space-y-2">
+                        {config.mappings.map((mapping, index) => (
+                            <div key={mapping.id} className="grid grid-cols-[auto_1fr_1fr_1fr_1fr_auto_auto] gap-2 items-center">
+                                <input
+                                    type="checkbox"
+                                    checked={mapping.enabled}
+                                    onChange={e => editMapping(index, { enabled: e.target.checked })}
+                                />
+                                <input
+                                    value={mapping.label}
+                                    placeholder={t("settings.input_mapping.label")}
+                                    onChange={e => editMapping(index, { label: e.target.value })}
+                                    className={smallInput}
+                                />
+                                <select
+                                    value={mapping.trigger.type}
+                                    onChange={e => editMapping(index, { trigger: defaultTrigger(e.target.value as InputTrigger["type"]) })}
+                                    className={smallInput}
+                                >
+                                    <option value="keyboard">{t("settings.input_mapping.trigger.keyboard")}</option>
+                                    <option value="midi_note">{t("settings.input_mapping.trigger.midi_note")}</option>
+                                    <option value="http">{t("settings.input_mapping.trigger.http")}</option>
+                                </select>
+                                {mapping.trigger.type === "keyboard" ? (
+                                    <input
+                                        value={mapping.trigger.shortcut}
+                                        placeholder="CmdOrCtrl+Shift+1"
+                                        onChange={e => editMapping(index, { trigger: { type: "keyboard", shortcut: e.target.value } })}
+                                        className={smallInput}
+                                    />
+                                ) : mapping.trigger.type === "midi_note" ? (
+                                    <div className="flex gap-1">
+                                        <input
+                                            type="number"
+                                            min={0}
+                                            max={127}
+                                            value={mapping.trigger.note}
+                                            title={t("settings.input_mapping.note")}
+                                            onChange={e => editMapping(index, { trigger: { type: "midi_note", note: Number(e.target.value) || 0, channel: mapping.trigger.type === "midi_note" ? mapping.trigger.channel : null } })}
+                                            className={smallInput}
+                                        />
+                                        <input
+                                            type="number"
+                                            min={1}
+                                            max={16}
+                                            value={mapping.trigger.channel ?? ""}
+                                            placeholder={t("settings.input_mapping.any_channel")}
+                                            onChange={e => editMapping(index, { trigger: { type: "midi_note", note: mapping.trigger.type === "midi_note" ? mapping.trigger.note : 0, channel: e.target.value ? Number(e.target.value) : null } })}
+                                            className={smallInput}
+                                        />
+                                    </div>
+                                ) : (
+                                    <input
+                                        value={mapping.trigger.name}
+                                        placeholder="/trigger/…"
+                                        onChange={e => editMapping(index, { trigger: { type: "http", name: e.target.value } })}
+                                        className={smallInput}
+                                    />
+                                )}
+                                <select
+                                    value={mapping.action.type}
+                                    onChange={e => editMapping(index, { action: defaultAction(e.target.value as MappedAction["type"]) })}
+                                    className={smallInput}
+                                >
+                                    <option value="expression">{t("settings.input_mapping.action.expression")}</option>
+                                    <option value="speak">{t("settings.input_mapping.action.speak")}</option>
+                                    <option value="toggle_voice_session">{t("settings.input_mapping.action.toggle_voice_session")}</option>
+                                    <option value="read_aloud">{t("settings.input_mapping.action.read_aloud")}</option>
+                                </select>
+                                <div className="flex gap-2 items-center">
+                                    {mapping.action.type === "expression" && (
+                                        <input
+                                            value={mapping.action.cue}
+                                            placeholder={t("settings.input_mapping.cue")}
+                                            onChange={e => editMapping(index, { action: { type: "expression", cue: e.target.value } })}
+                                            className={smallInput}
+                                        />
+                                    )}
+                                    {mapping.action.type === "speak" && (
+                                        <input
+                                            value={mapping.action.text}
+                                            placeholder={t("settings.input_mapping.phrase")}
+                                            onChange={e => editMapping(index, { action: { type: "speak", text: e.target.value } })}
+                                            className={smallInput}
+                                        />
+                                    )}
+                                    {mapping.action.type === "read_aloud" && (
+                                        <div className="flex gap-2 text-[10px] text-[var(--color-text-secondary)]">
+                                            <label className="flex items-center gap-1" title={t("settings.input_mapping.summarize_hint")}>
+                                                <input
+                                                    type="checkbox"
+                                                    checked={mapping.action.summarize}
+                                                    onChange={e => mapping.action.type === "read_aloud" && editMapping(index, { action: { ...mapping.action, summarize: e.target.checked } })}
+                                                />
+                                                {t("settings.input_mapping.summarize")}
+                                            </label>
+                                            <label className="flex items-center gap-1">
+                                                <input
+                                                    type="checkbox"
+                                                    checked={mapping.action.comment}
+                                                    onChange={e => mapping.action.type === "read_aloud" && editMapping(index, { action: { ...mapping.action, comment: e.target.checked } })}
+                                                />
+                                                {t("settings.input_mapping.comment")}
+                                            </label>
+                                        </div>
+                                    )}
+                                    <button
+                                        onClick={() => testInputMapping(mapping.id).catch(err => setError(String(err)))}
+                                        disabled={dirty}
+                                        title={t("settings.input_mapping.test")}
+                                        className="text-[var(--color-text-muted)] hover:text-[var(--color-accent)] disabled:opacity-40"
+                                    >
+                                        <Play size={14} />
+                                    </button>
+                                </div>
+                                <button
+                                    onClick={() => edit({ mappings: config.mappings.filter((_, i) => i !== index) })}
+                                    className="text-[var(--color-text-muted)] hover:text-red-400"
+                                >
+                                    <Trash2 size={14} />
+                                </button>
+                            </div>
+                        ))}
+                    </div>
+
+                    <div className="flex items-center justify-between">
+                        <button
+                            onClick={addMapping}
+                            className="text-[10px] uppercase tracking-wider text-[var(--color-accent)] hover:underline flex items-center gap-1"
+                        >
+                            <Plus size={10} />
+                            {t("settings.input_mapping.add")}
+                        </button>
+                        <button
+                            onClick={() => save(config)}
+                            disabled={!dirty}
+                            className="px-3 py-1 text-xs rounded-md bg-[var(--color-accent)] text-black disabled:opacity-40"
+                        >
+                            {t("settings.input_mapping.save")}
+                        </button>
+                    </div>
+
+                    {status && (
+                        <p className="text-xs text-[var(--color-text-muted)]">
+                            {t("settings.input_mapping.status", {
+                                shortcuts: status.shortcuts,
+                                midi: status.midi_port ?? "—",
+                            })}
+                            {lastFired && ` · ${t("settings.input_mapping.last_fired", { name: lastFired })}`}
+                        </p>
+                    )}
+                    {status?.warnings.map(warning => (
+                        <p key={warning} className="text-xs text-amber-400">{warning}</p>
+                    ))}
+                </>
+            )}
+
+            {error && <p className="text-xs text-red-400">{error}</p>}
+        </div>
+    );
+}
@@ -4,13 +4,14 @@ import { useState, useEffect, useCallback, useRef } from "react";
 import { motion, AnimatePresence } from "framer-motion";
 import { clsx } from "clsx";
 import { useTranslation } from "react-i18next";
-import { Trash2, Pencil, Check, X, Search, Brain, ChevronDown, List, Calendar, Share2, UserCircle, Moon, Play, Clock } from "lucide-react";
+import { Trash2, Pencil, Check, X, Search, Brain, ChevronDown, List, Calendar, Share2, UserCircle, Moon, Play, Clock, Undo2 } from "lucide-react";
 import { inputClasses } from "../styles/settings-primitives";
 import { Select } from "@/components/ui/select";
 import {
     listMemories,
     updateMemory,
     deleteMemory,
+    undoLastDelete,
     listCharacters,
     getMemoryEnabled,
     setMemoryEnabled,
@@ -118,6 +119,7 @@ export default function MemoryPanel({ characterId }: MemoryPanelProps) {
     const [editContentPrefix, setEditContentPrefix] = useState<string | null>(null);
     const [editImportance, setEditImportance] = useState(0.5);
     const [deletingId, setDeletingId] = useState<number | null>(null);
+    const [canUndoDelete, setCanUndoDelete] = useState(false);
     const [page, setPage] = useState(0);
     const [memoryEnabled, setMemoryEnabledState] = useState(true);
     const [togglingMemory, setTogglingMemory] = useState(false);
@@ -280,12 +282,23 @@ export default function MemoryPanel({ characterId }: MemoryPanelProps) {
         try {
             await deleteMemory(id);
             setDeletingId(null);
+            setCanUndoDelete(true);
             fetchMemories();
         } catch (e) {
             console.error("[MemoryPanel] Failed to delete memory:", e);
         }
     };
 
+    const handleUndoDelete = async () => {
+        try {
+            await undoLastDelete();
+            setCanUndoDelete(false);
+            fetchMemories();
+        } catch (e) {
+            console.error("[MemoryPanel] Failed to undo delete:", e);
+        }
+    };
+
     const hasMore = (page + 1) * pageSize < total;
 
     const handleToggleMemory = async () => {
@@ -426,9 +439,21 @@ export default function MemoryPanel({ characterId }: MemoryPanelProps) {
                             {t("settings.memory.title")}
                         </span>
                     </div>
-                    <span className="text-[10px] font-mono text-[var(--color-text-muted)]">
-                        {t("settings.memory.count", { count: total })}
-                    </span>
+                    <div className="flex items-center gap-2">
+                        {canUndoDelete && (
+                            <button
+                                onClick={handleUndoDelete}
+                                className="flex items-center gap-1 text-[10px] font-heading font-semibold uppercase tracking-wider text-[var(--color-accent)] hover:underline"
+                                title={t("settings.memory.undo_delete")}
+                            >
+                                <Undo2 size={12} />
+                                {t("settings.memory.undo_delete")}
+                            </button>
+                        )}
+                        <span className="text-[10px] font-mono text-[var(--color-text-muted)]">
+                            {t("settings.memory.count", { count: total })}
+                        </span>
+                    </div>
                 </div>
                 <div className="rounded-lg border border-[var(--color-border)] bg-black/20 p-3">
                     <div className="flex items-center justify-between gap-4">
@@ -0,0 +1,106 @@
+import { useEffect, useState } from "react";
+import { useTranslation } from "react-i18next";
+import { labelClasses } from "../styles/settings-primitives";
+import {
+    getProactiveComposerConfig,
+    saveProactiveComposerConfig,
+    listProactiveHistory,
+} from "../../lib/kokoro-bridge";
+import type { ProactiveComposerConfig, ProactiveHistoryEntry } from "../../lib/kokoro-bridge";
+
+const SOURCES = ["memory", "vision", "curiosity", "time"] as const;
+
+export default function ProactiveComposerSettings() {
+    const { t } = useTranslation();
+    const [config, setConfig] = useState<ProactiveComposerConfig | null>(null);
+    const [history, setHistory] = useState<ProactiveHistoryEntry[]>([]);
+    const [error, setError] = useState<string | null>(null);
+
+    useEffect(() => {
+        getProactiveComposerConfig().then(setConfig).catch(err => setError(String(err)));
+        listProactiveHistory(undefined, 5).then(setHistory).catch(() => setHistory([]));
+    }, []);
+
+    if (!config) {
+        return error ? <p className="text-xs text-red-400">{error}</p> : null;
+    }
+
+    const update = (next: ProactiveComposerConfig) => {
+        setConfig(next);
+        setError(null);
+        saveProactiveComposerConfig(next).catch(err => setError(String(err)));
+    };
+
+    return (
+        <div className="mt-3 space-y-3 pl-3 border-l border-[var(--color-border)]">
+            <div>
+                <label className={labelClasses}>{t("settings.persona.proactive.composer.weights")}</label>
+                <div className="space-y-1.5">
+                    {SOURCES.map(source => (
+                        <div key={source} className="flex items-center gap-2 text-xs">
+                            <span className="w-20 shrink-0 text-[var(--color-text-secondary)]">
+                                {t(`settings.persona.proactive.composer.source.${source}`)}
+                            </span>
+                            <input
+                                type="range"
+                                min={0}
+                                max={2}
+                                step={0.1}
+                                value={config.weights[source]}
+                                onChange={e =>
+                                    update({
+                                        ...config,
+                                        weights: { ...config.weights, [source]: Number(e.target.value) },
+                                    })
+                                }
+                                className="flex-1 accent-[var(--color-accent)]"
+                            />
+                            <span className="w-8 text-right tabular-nums text-[var(--color-text-muted)]">
+                                {config.weights[source].toFixed(1)}
+                            </span>
+                        </div>
+                    ))}
+                </div>
+            </div>
+
+            <div>
+                <label className={labelClasses}>{t("settings.persona.proactive.composer.novelty")}</label>
+                <div className="flex items-center gap-2 text-xs">
+                    <input
+                        type="range"
+                        min={0.5}
+                        max={1}
+                        step={0.01}
+                        value={config.novelty_threshold}
+                        onChange={e => update({ ...config, novelty_threshold: Number(e.target.value) })}
+                        className="flex-1 accent-[var(--color-accent)]"
+                    />
+                    <span className="w-8 text-right tabular-nums text-[var(--color-text-muted)]">
+                        {config.novelty_threshold.toFixed(2)}
+                    </span>
+                </div>
+                <p className="text-[10px] text-[var(--color-text-muted)] mt-1">
+                    {t("settings.persona.proactive.composer.novelty_desc", { window: config.history_window })}
+                </p>
+            </div>
+
+            {history.length > 0 && (
+                <div>
+                    <label className={labelClasses}>{t("settings.persona.proactive.composer.recent")}</label>
+                    <ul className="space-y-1 text-xs">
+                        {history.map(entry => (
+                            <li key={entry.id} className="flex gap-2 text-[var(--color-text-secondary)]">
+                                <span className="shrink-0 text-[var(--color-text-muted)]">
+                                    {t(`settings.persona.proactive.composer.source.${entry.source}`)}
+                                </span>
+                                <span className="truncate">{entry.message}</span>
+                            </li>
+                        ))}
+                    </ul>
+                </div>
+            )}
+
+            {error && <p className="text-xs text-red-400">{error}</p>}
+        </div>
+    );
+}
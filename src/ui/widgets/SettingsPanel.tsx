@@ -6,6 +6,12 @@ import { ModList } from "../mods/ModList";
 import { Select } from "@/components/ui/select";
 import CharacterManager from "./CharacterManager";
 import ImageGenSettings from "./ImageGenSettings";
+import WallpaperSettings from "./WallpaperSettings";
+import ImageGallery from "./ImageGallery";
+import InputMappingSettings from "./InputMappingSettings";
+import WebhookSettings from "./WebhookSettings";
+import DuetPanel from "./DuetPanel";
+import StoryPanel from "./StoryPanel";
 import MemoryPanel from "./MemoryPanel";
 import ApiTab from "./settings/ApiTab";
 import TtsTab from "./settings/TtsTab";
@@ -741,7 +747,7 @@ export default function SettingsPanel({ isOpen, onClose, activeTab: activeTabPro
                             )}
 
                             {mountedTabs.has("persona") && (
-                                <div className={activeTab === "persona" ? "block" : "hidden"}>
+                                <div className={clsx(activeTab === "persona" ? "block" : "hidden", "space-y-6")}>
                                     <CharacterManager
                                         onPersonaChange={(prompt) => setPersonaText(prompt)}
                                         responseLanguage={responseLang}
@@ -749,6 +755,8 @@ export default function SettingsPanel({ isOpen, onClose, activeTab: activeTabPro
                                         userLanguage={userLang}
                                         onUserLanguageChange={setUserLang}
                                     />
+                                    <DuetPanel />
+                                    <StoryPanel />
                                 </div>
                             )}
 
@@ -810,11 +818,13 @@ export default function SettingsPanel({ isOpen, onClose, activeTab: activeTabPro
                             )}
 
                             {mountedTabs.has("imagegen") && localImageGenConfig && (
-                                <div className={activeTab === "imagegen" ? "block" : "hidden"}>
+                                <div className={clsx(activeTab === "imagegen" ? "block" : "hidden", "space-y-6")}>
                                     <ImageGenSettings
                                         config={localImageGenConfig}
                                         onChange={setLocalImageGenConfig}
                                     />
+                                    <WallpaperSettings />
+                                    <ImageGallery />
                                 </div>
                             )}
 
@@ -852,13 +862,15 @@ export default function SettingsPanel({ isOpen, onClose, activeTab: activeTabPro
                                 </div>
                             )}
                             {mountedTabs.has("bot") && (
-                                <div className={activeTab === "bot" ? "block" : "hidden"}>
+                                <div className={clsx(activeTab === "bot" ? "block" : "hidden", "space-y-6")}>
                                     <BotTab
                                         botConfig={localBotConfig}
                                         initialStatus={initialTelegramStatus}
                                         initialCharacters={charactersProp}
                                         onBotConfigChange={setLocalBotConfig}
                                     />
+                                    <InputMappingSettings />
+                                    <WebhookSettings />
                                 </div>
                             )}
                             {mountedTabs.has("jailbreak") && (
@@ -0,0 +1,204 @@
+import { useEffect, useState } from "react";
+import { clsx } from "clsx";
+import { useTranslation } from "react-i18next";
+import { BookOpen, Trash2, Send, GitBranch } from "lucide-react";
+import { inputClasses, labelClasses } from "../styles/settings-primitives";
+import {
+    listStories,
+    getStory,
+    startStory,
+    chooseStoryOption,
+    switchStoryBeat,
+    deleteStory,
+    onStoryDelta,
+} from "../../lib/kokoro-bridge";
+import type { Story, StoryView } from "../../lib/kokoro-bridge";
+import { APP_SETTING_KEYS, readStringSetting } from "../../lib/app-settings";
+
+const smallInput = clsx(inputClasses, "py-1.5 px-2 text-xs");
+
+export default function StoryPanel() {
+    const { t } = useTranslation();
+    const [stories, setStories] = useState<Story[]>([]);
+    const [view, setView] = useState<StoryView | null>(null);
+    const [premise, setPremise] = useState("");
+    const [custom, setCustom] = useState("");
+    const [busy, setBusy] = useState(false);
+    const [streaming, setStreaming] = useState<string | null>(null);
+    const [error, setError] = useState<string | null>(null);
+
+    const refresh = () => {
+        listStories().then(setStories).catch(() => setStories([]));
+    };
+
+    useEffect(() => {
+        refresh();
+        const unlisten = onStoryDelta(({ text }) => setStreaming(text));
+        return () => {
+            unlisten.then(fn => fn());
+        };
+    }, []);
+
+    const run = (task: Promise<StoryView>) => {
+        setBusy(true);
+        setError(null);
+        setStreaming("");
+        task
+            .then(next => {
+                setView(next);
+                setCustom("");
+                refresh();
+            })
+            .catch(err => setError(String(err)))
+            .finally(() => {
+                setBusy(false);
+                setStreaming(null);
+            });
+    };
+
+    const begin = () => {
+        const characterId = readStringSetting(APP_SETTING_KEYS.activeCharacterId, "default") || "default";
+        run(startStory({ character_id: characterId, premise }));
+        setPremise("");
+    };
+
+    const current = view?.path[view.path.length - 1];
+
+    return (
+        <div className="space-y-3 p-4 rounded-lg border border-[var(--color-border)] bg-[var(--color-bg-elevated)]">
+            <div>
+                <h3 className="text-sm font-heading font-bold text-[var(--color-text-primary)]">
+                    {t("settings.story.title")}
+                </h3>
+                <p className="text-xs text-[var(--color-text-muted)]">
+                    {t("settings.story.desc")}
+                </p>
+            </div>
+
+            <div>
+                <label className={labelClasses}>{t("settings.story.premise")}</label>
+                <div className="flex gap-2">
+                    <input
+                        value={premise}
+                        onChange={e => setPremise(e.target.value)}
+                        placeholder={t("settings.story.premise_placeholder")}
+                        disabled={busy}
+                        className={smallInput}
+                    />
+                    <button
+                        onClick={begin}
+                        disabled={busy || !premise.trim()}
+                        className="px-3 py-1 text-xs rounded-md bg-[var(--color-accent)] text-black disabled:opacity-40 shrink-0"
+                    >
+                        {t("settings.story.start")}
+                    </button>
+                </div>
+            </div>
+
+            {stories.length > 0 && (
+                <div className="space-y-1">
+                    {stories.map(story => (
+                        <div key={story.id} className="flex items-center gap-2 text-xs">
+                            <button
+                                onClick={() => getStory(story.id).then(setView).catch(err => setError(String(err)))}
+                                className={clsx(
+                                    "flex-1 text-left truncate flex items-center gap-1.5 hover:text-[var(--color-accent)]",
+                                    view?.story.id === story.id ? "text-[var(--color-accent)]" : "text-[var(--color-text-secondary)]"
+                                )}
+                            >
+                                <BookOpen size={12} />
+                                {story.title}
+                            </button>
+                            <button
+                                onClick={() => {
+                                    deleteStory(story.id)
+                                        .then(() => {
+                                            if (view?.story.id === story.id) setView(null);
+                                            refresh();
+                                        })
+                                        .catch(err => setError(String(err)));
+                                }}
+                                className="text-[var(--color-text-muted)] hover:text-red-400"
+                            >
+                                <Trash2 size={12} />
+                            </button>
+                        </div>
+                    ))}
+                </div>
+            )}
+
+            {view && (
+                <div className="max-h-80 overflow-y-auto space-y-3 rounded-md bg-black/20 p-3 text-xs">
+                    {view.path.map(beat => {
+                        const branches = view.branches.filter(b => b.parent_id === beat.id);
+                        return (
+                            <div key={beat.id} className="space-y-1">
+                                {beat.choice_text && (
+                                    <p className="text-[var(--color-accent)]">› {beat.choice_text}</p>
+                                )}
+                                <p className="whitespace-pre-wrap text-[var(--color-text-primary)]">{beat.narrative}</p>
+                                {branches.map(branch => (
+                                    <button
+                                        key={branch.beat_id}
+                                        onClick={() => run(switchStoryBeat(view.story.id, branch.beat_id))}
+                                        disabled={busy}
+                                        className="flex items-center gap-1 text-[var(--color-text-muted)] hover:text-[var(--color-accent)]"
+                                    >
+                                        <GitBranch size={10} />
+                                        {t("settings.story.other_branch", { choice: branch.choice_text ?? "…" })}
+                                    </button>
+                                ))}
+                            </div>
+                        );
+                    })}
+                    {streaming !== null && (
+                        <p className="whitespace-pre-wrap opacity-70">{streaming || t("settings.story.writing")}</p>
+                    )}
+                    {current?.ending && streaming === null && (
+                        <p className="text-[var(--color-text-muted)] italic">{t("settings.story.the_end")}</p>
+                    )}
+                </div>
+            )}
+
+            {view && current && !current.ending && streaming === null && (
+                <div className="space-y-2">
+                    <div className="flex flex-wrap gap-2">
+                        {current.choices.map(choice => (
+                            <button
+                                key={choice.id}
+                                onClick={() => run(chooseStoryOption(view.story.id, { beat_id: current.id, choice_id: choice.id }))}
+                                disabled={busy}
+                                className="px-2.5 py-1 text-xs rounded-md border border-[var(--color-border)] text-[var(--color-text-primary)] hover:border-[var(--color-accent)] disabled:opacity-40"
+                            >
+                                {choice.text}
+                            </button>
+                        ))}
+                    </div>
+                    <div className="flex gap-2">
+                        <input
+                            value={custom}
+                            onChange={e => setCustom(e.target.value)}
+                            onKeyDown={e => {
+                                if (e.key === "Enter" && custom.trim()) {
+                                    run(chooseStoryOption(view.story.id, { beat_id: current.id, custom }));
+                                }
+                            }}
+                            placeholder={t("settings.story.custom_placeholder")}
+                            disabled={busy}
+                            className={smallInput}
+                        />
+                        <button
+                            onClick={() => run(chooseStoryOption(view.story.id, { beat_id: current.id, custom }))}
+                            disabled={busy || !custom.trim()}
+                            className="text-[var(--color-text-muted)] hover:text-[var(--color-accent)] disabled:opacity-40"
+                        >
+                            <Send size={14} />
+                        </button>
+                    </div>
+                </div>
+            )}
+
+            {error && <p className="text-xs text-red-400">{error}</p>}
+        </div>
+    );
+}
@@ -0,0 +1,123 @@
+import { AnimatePresence, motion } from "framer-motion";
+import { ShieldAlert } from "lucide-react";
+import { useEffect, useState } from "react";
+import { useTranslation } from "react-i18next";
+import {
+    onToolConfirm,
+    resolveMcpToolConfirm,
+    type McpToolConfirmRequest,
+} from "../../lib/kokoro-bridge";
+
+/**
+ * Asks the user before an MCP tool that needs confirmation runs.
+ * Requests queue up; the backend declines any that time out.
+ */
+export default function ToolConfirmDialog() {
+    const { t } = useTranslation();
+    const [queue, setQueue] = useState<McpToolConfirmRequest[]>([]);
+    const [secondsLeft, setSecondsLeft] = useState(0);
+    const current = queue[0] ?? null;
+
+    useEffect(() => {
+        let disposed = false;
+        let unlisten: (() => void) | undefined;
+        void onToolConfirm((request) => {
+            setQueue((prev) => [...prev, request]);
+        }).then((fn) => {
+            if (disposed) fn();
+            else unlisten = fn;
+        });
+        return () => {
+            disposed = true;
+            unlisten?.();
+        };
+    }, []);
+
+    useEffect(() => {
+        if (!current) return;
+        setSecondsLeft(current.timeout_secs);
+        const interval = setInterval(() => {
+            setSecondsLeft((prev) => {
+                if (prev <= 1) {
+                    // The backend has already declined it; drop the prompt.
+                    setQueue((q) => q.filter((r) => r.request_id !== current.request_id));
+                    return 0;
+                }
+                return prev - 1;
+            });
+        }, 1000);
+        return () => clearInterval(interval);
+    }, [current]);
+
+    const answer = async (approved: boolean) => {
+        if (!current) return;
+        setQueue((q) => q.filter((r) => r.request_id !== current.request_id));
+        try {
+            await resolveMcpToolConfirm(current.request_id, approved);
+        } catch (e) {
+            console.error("[ToolConfirmDialog] Failed to resolve confirmation:", e);
+        }
+    };
+
+    return (
+        <AnimatePresence>
+            {current && (
+                <motion.div
+                    initial={{ opacity: 0 }}
+                    animate={{ opacity: 1 }}
+                    exit={{ opacity: 0 }}
+                    className="fixed inset-0 z-[180] flex items-center justify-center bg-black/65 px-4 backdrop-blur-sm"
+                >
+                    <motion.div
+                        key={current.request_id}
+                        initial={{ opacity: 0, y: 20, scale: 0.98 }}
+                        animate={{ opacity: 1, y: 0, scale: 1 }}
+                        exit={{ opacity: 0, y: 12, scale: 0.98 }}
+                        transition={{ type: "spring", stiffness: 280, damping: 28 }}
+                        className="w-full max-w-[480px] rounded-3xl border border-amber-400/40 bg-[var(--color-bg-elevated)]/95 shadow-2xl backdrop-blur-2xl"
+                    >
+                        <div className="space-y-2 border-b border-[var(--color-border)] px-6 py-5">
+                            <div className="flex items-center gap-2 text-[11px] font-heading font-semibold uppercase tracking-[0.18em] text-amber-300">
+                                <ShieldAlert size={14} strokeWidth={1.6} />
+                                {t("chat.tools.confirm.title")}
+                            </div>
+                            <p className="text-sm leading-6 text-[var(--color-text-secondary)]">
+                                {t("chat.tools.confirm.desc", { tool: current.tool_name, server: current.server_name })}
+                            </p>
+                        </div>
+
+                        <div className="space-y-3 px-6 py-5">
+                            <div className="text-xs font-heading font-semibold uppercase tracking-[0.14em] text-[var(--color-text-muted)]">
+                                {t("chat.tools.request_label")}
+                            </div>
+                            <pre className="max-h-60 overflow-auto rounded-2xl border border-[var(--color-border)] bg-black/30 p-3 text-xs leading-relaxed text-[var(--color-text-primary)] whitespace-pre-wrap break-all">
+                                {JSON.stringify(current.arguments, null, 2)}
+                            </pre>
+                            <div className="text-xs text-[var(--color-text-muted)]">
+                                {t("chat.tools.confirm.auto_decline", { seconds: secondsLeft })}
+                                {queue.length > 1 && ` · ${t("chat.tools.confirm.queued", { count: queue.length - 1 })}`}
+                            </div>
+                        </div>
+
+                        <div className="flex justify-end gap-2 border-t border-[var(--color-border)] px-6 py-4">
+                            <button
+                                type="button"
+                                onClick={() => { void answer(false); }}
+                                className="rounded-lg border border-[var(--color-border)] px-4 py-2 text-xs font-heading font-semibold uppercase tracking-wider text-[var(--color-text-secondary)] transition-colors hover:border-red-400 hover:text-red-400"
+                            >
+                                {t("chat.tools.actions.reject")}
+                            </button>
+                            <button
+                                type="button"
+                                onClick={() => { void answer(true); }}
+                                className="rounded-lg bg-[var(--color-accent)] px-4 py-2 text-xs font-heading font-semibold uppercase tracking-wider text-black transition-colors hover:bg-white"
+                            >
+                                {t("chat.tools.actions.approve")}
+                            </button>
+                        </div>
+                    </motion.div>
+                </motion.div>
+            )}
+        </AnimatePresence>
+    );
+}
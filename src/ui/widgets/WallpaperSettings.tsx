@@ -0,0 +1,130 @@
+import { useCallback, useEffect, useState } from "react";
+import { motion } from "framer-motion";
+import { clsx } from "clsx";
+import { useTranslation } from "react-i18next";
+import { Undo2, RotateCcw } from "lucide-react";
+import { convertFileSrc } from "@tauri-apps/api/core";
+import { inputClasses, labelClasses } from "../styles/settings-primitives";
+import {
+    getWallpaperConfig,
+    saveWallpaperConfig,
+    getWallpaperHistory,
+    revertWallpaper,
+    restoreOriginalWallpaper,
+    onWallpaperChanged,
+} from "../../lib/kokoro-bridge";
+import type { WallpaperConfig, WallpaperHistory } from "../../lib/kokoro-bridge";
+
+export default function WallpaperSettings() {
+    const { t } = useTranslation();
+    const [config, setConfig] = useState<WallpaperConfig | null>(null);
+    const [history, setHistory] = useState<WallpaperHistory>({ original: null, entries: [] });
+    const [error, setError] = useState<string | null>(null);
+
+    const refreshHistory = useCallback(() => {
+        getWallpaperHistory().then(setHistory).catch(() => undefined);
+    }, []);
+
+    useEffect(() => {
+        getWallpaperConfig().then(setConfig).catch(err => setError(String(err)));
+        refreshHistory();
+        const unlisten = onWallpaperChanged(refreshHistory);
+        return () => {
+            unlisten.then(fn => fn());
+        };
+    }, [refreshHistory]);
+
+    const update = (patch: Partial<WallpaperConfig>) => {
+        if (!config) return;
+        const next = { ...config, ...patch };
+        setConfig(next);
+        saveWallpaperConfig(next).catch(err => setError(String(err)));
+    };
+
+    const run = (action: () => Promise<unknown>) => {
+        setError(null);
+        action().then(refreshHistory).catch(err => setError(String(err)));
+    };
+
+    if (!config) return null;
+
+    return (
+        <div className="space-y-3 p-4 rounded-lg border border-[var(--color-border)] bg-[var(--color-bg-elevated)]">
+            <div className="flex items-center justify-between">
+                <div>
+                    <h3 className="text-sm font-heading font-bold text-[var(--color-text-primary)]">
+                        {t("settings.image_gen.wallpaper.title")}
+                    </h3>
+                    <p className="text-xs text-[var(--color-text-muted)]">
+                        {t("settings.image_gen.wallpaper.desc")}
+                    </p>
+                </div>
+                <button
+                    onClick={() => update({ enabled: !config.enabled })}
+                    className={clsx(
+                        "w-10 h-6 rounded-full transition-colors relative shrink-0",
+                        config.enabled ? "bg-[var(--color-accent)]" : "bg-[var(--color-border)]"
+                    )}
+                >
+                    <motion.div
+                        animate={{ x: config.enabled ? 18 : 2 }}
+                        className="absolute top-1 w-4 h-4 rounded-full bg-white shadow-sm"
+                    />
+                </button>
+            </div>
+
+            {config.enabled && (
+                <div>
+                    <label className={labelClasses}>{t("settings.image_gen.wallpaper.interval")}</label>
+                    <input
+                        type="number"
+                        min={0}
+                        value={config.min_interval_minutes}
+                        onChange={e => update({ min_interval_minutes: Math.max(0, Number(e.target.value) || 0) })}
+                        className={clsx(inputClasses, "w-28")}
+                    />
+                </div>
+            )}
+
+            <div>
+                <div className="flex items-center justify-between mb-2">
+                    <label className={labelClasses.replace("mb-2", "mb-0")}>{t("settings.image_gen.wallpaper.history")}</label>
+                    <div className="flex gap-2">
+                        <button
+                            onClick={() => run(revertWallpaper)}
+                            disabled={history.entries.length === 0}
+                            className="text-[10px] uppercase tracking-wider text-[var(--color-accent)] hover:underline disabled:opacity-50 flex items-center gap-1"
+                        >
+                            <Undo2 size={10} />
+                            {t("settings.image_gen.wallpaper.revert")}
+                        </button>
+                        <button
+                            onClick={() => run(restoreOriginalWallpaper)}
+                            disabled={history.entries.length === 0}
+                            className="text-[10px] uppercase tracking-wider text-[var(--color-accent)] hover:underline disabled:opacity-50 flex items-center gap-1"
+                        >
+                            <RotateCcw size={10} />
+                            {t("settings.image_gen.wallpaper.restore")}
+                        </button>
+                    </div>
+                </div>
+                {history.entries.length === 0 ? (
+                    <p className="text-xs text-[var(--color-text-muted)]">{t("settings.image_gen.wallpaper.empty")}</p>
+                ) : (
+                    <div className="flex gap-2 overflow-x-auto">
+                        {[...history.entries].reverse().map(entry => (
+                            <img
+                                key={entry.id}
+                                src={convertFileSrc(entry.path)}
+                                title={new Date(entry.applied_at * 1000).toLocaleString()}
+                                className="w-20 h-12 object-cover rounded border border-[var(--color-border)]"
+                            />
+                        ))}
+                    </div>
+                )}
+            </div>
+
+            {error && <p className="text-xs text-red-400">{error}</p>}
+        </div>
+    );
+}
@@ -0,0 +1,295 @@
+import { useEffect, useState } from "react";
+import { motion } from "framer-motion";
+import { clsx } from "clsx";
+import { useTranslation } from "react-i18next";
+import { Plus, Play, Trash2 } from "lucide-react";
+import { inputClasses, labelClasses } from "../styles/settings-primitives";
+import {
+    getWebhookConfig,
+    saveWebhookConfig,
+    testWebhook,
+    listWebhookDeliveries,
+} from "../../lib/kokoro-bridge";
+import type { Webhook, WebhookConfig, WebhookDelivery, WebhookTrigger } from "../../lib/kokoro-bridge";
+
+const defaultTrigger = (type: WebhookTrigger["type"]): WebhookTrigger => {
+    switch (type) {
+        case "memory_added": return { type, topic: "" };
+        case "mood_below": return { type, threshold: 0.3 };
+        case "reminder_due": return { type };
+    }
+};
+
+const headersToText = (headers: Record<string, string>) =>
+    Object.entries(headers).map(([name, value]) => `${name}: ${value}`).join("\n");
+
+const textToHeaders = (text: string) =>
+    Object.fromEntries(
+        text
+            .split("\n")
+            .map(line => line.split(/:(.*)/s).map(part => part.trim()))
+            .filter(([name]) => name)
+            .map(([name, value]) => [name, value ?? ""])
+    );
+
+const smallInput = clsx(inputClasses, "py-1.5 px-2 text-xs");
+
+export default function WebhookSettings() {
+    const { t } = useTranslation();
+    const [config, setConfig] = useState<WebhookConfig | null>(null);
+    const [deliveries, setDeliveries] = useState<WebhookDelivery[]>([]);
+    const [dirty, setDirty] = useState(false);
+    const [error, setError] = useState<string | null>(null);
+    const [tested, setTested] = useState<string | null>(null);
+    // Raw header text per webhook, so half-typed lines survive re-renders.
+    const [headerDrafts, setHeaderDrafts] = useState<Record<string, string>>({});
+
+    const refreshDeliveries = () => {
+        listWebhookDeliveries(5).then(setDeliveries).catch(() => setDeliveries([]));
+    };
+
+    useEffect(() => {
+        getWebhookConfig().then(setConfig).catch(err => setError(String(err)));
+        refreshDeliveries();
+    }, []);
+
+    const edit = (patch: Partial<WebhookConfig>) => {
+        if (!config) return;
+        setConfig({ ...config, ...patch });
+        setDirty(true);
+    };
+
+    const editWebhook = (index: number, patch: Partial<Webhook>) => {
+        if (!config) return;
+        edit({ webhooks: config.webhooks.map((w, i) => (i === index ? { ...w, ...patch } : w)) });
+    };
+
+    const addWebhook = () => {
+        if (!config) return;
+        edit({
+            webhooks: [
+                ...config.webhooks,
+                {
+                    id: crypto.randomUUID(),
+                    label: "",
+                    enabled: true,
+                    url: "",
+                    headers: {},
+                    trigger: defaultTrigger("memory_added"),
+                    payload_template: "",
+                    max_attempts: 5,
+                },
+            ],
+        });
+    };
+
+    const save = (next: WebhookConfig) => {
+        setError(null);
+        saveWebhookConfig(next)
+            .then(() => setDirty(false))
+            .catch(err => setError(String(err)));
+    };
+
+    const test = (webhook: Webhook) => {
+        setError(null);
+        setTested(null);
+        testWebhook(webhook.id)
+            .then(() => setTested(webhook.label || webhook.url))
+            .catch(err => setError(String(err)));
+    };
+
+    if (!config) return null;
+
+    return (
+        <div className="space-y-3 p-4 rounded-lg border border-[var(--color-border)] bg-[var(--color-bg-elevated)]">
+            <div className="flex items-center justify-between">
+                <div>
+                    <h3 className="text-sm font-heading font-bold text-[var(--color-text-primary)]">
+                        {t("settings.webhooks.title")}
+                    </h3>
+                    <p className="text-xs text-[var(--color-text-muted)]">
+                        {t("settings.webhooks.desc")}
+                    </p>
+                </div>
+                <button
+                    onClick={() => {
+                        const next = { ...config, enabled: !config.enabled };
+                        setConfig(next);
+                        save(next);
+                    }}
+                    className={clsx(
+                        "w-10 h-6 rounded-full transition-colors relative shrink-0",
+                        config.enabled ? "bg-[var(--color-accent)]" : "bg-[var(--color-border)]"
+                    )}
+                >
+                    <motion.div
+                        animate={{ x: config.enabled ? 18 : 2 }}
+                        className="absolute top-1 w-4 h-4 rounded-full bg-white shadow-sm"
+                    />
+                </button>
+            </div>
+
+            {config.enabled && (
+                <>
+                    <div className="space-y-3">
+                        {config.webhooks.map((webhook, index) => (
+                            <div key={webhook.id} className="space-y-2 rounded-md border border-[var(--color-border)] p-3">
+                                <div className="grid grid-cols-[auto_1fr_1fr_1fr_auto_auto] gap-2 items-center">
+                                    <input
+                                        type="checkbox"
+                                        checked={webhook.enabled}
+                                        onChange={e => editWebhook(index, { enabled: e.target.checked })}
+                                    />
+                                    <input
+                                        value={webhook.label}
+                                        placeholder={t("settings.webhooks.label")}
+                                        onChange={e => editWebhook(index, { label: e.target.value })}
+                                        className={smallInput}
+                                    />
+                                    <select
+                                        value={webhook.trigger.type}
+                                        onChange={e => editWebhook(index, { trigger: defaultTrigger(e.target.value as WebhookTrigger["type"]) })}
+                                        className={smallInput}
+                                    >
+                                        <option value="memory_added">{t("settings.webhooks.trigger.memory_added")}</option>
+                                        <option value="mood_below">{t("settings.webhooks.trigger.mood_below")}</option>
+                                        <option value="reminder_due">{t("settings.webhooks.trigger.reminder_due")}</option>
+                                    </select>
+                                    {webhook.trigger.type === "memory_added" ? (
+                                        <input
+                                            value={webhook.trigger.topic}
+                                            placeholder={t("settings.webhooks.topic")}
+                                            onChange={e => editWebhook(index, { trigger: { type: "memory_added", topic: e.target.value } })}
+                                            className={smallInput}
+                                        />
+                                    ) : webhook.trigger.type === "mood_below" ? (
+                                        <input
+                                            type="number"
+                                            min={0}
+                                            max={1}
+                                            step={0.05}
+                                            value={webhook.trigger.threshold}
+                                            title={t("settings.webhooks.threshold")}
+                                            onChange={e => editWebhook(index, { trigger: { type: "mood_below", threshold: Number(e.target.value) || 0 } })}
+                                            className={smallInput}
+                                        />
+                                    ) : (
+                                        <span />
+                                    )}
+                                    <button
+                                        onClick={() => test(webhook)}
+                                        disabled={dirty}
+                                        title={t("settings.webhooks.test")}
+                                        className="text-[var(--color-text-muted)] hover:text-[var(--color-accent)] disabled:opacity-40"
+                                    >
+                                        <Play size={14} />
+                                    </button>
+                                    <button
+                                        onClick={() => edit({ webhooks: config.webhooks.filter((_, i) => i !== index) })}
+                                        className="text-[var(--color-text-muted)] hover:text-red-400"
+                                    >
+                                        <Trash2 size={14} />
+                                    </button>
+                                </div>
+                                <div className="grid grid-cols-[1fr_auto] gap-2 items-center">
+                                    <input
+                                        value={webhook.url}
+                                        placeholder="https://maker.ifttt.com/trigger/…"
+                                        onChange={e => editWebhook(index, { url: e.target.value })}
+                                        className={smallInput}
+                                    />
+                                    <input
+                                        type="number"
+                                        min={1}
+                                        max={20}
+                                        value={webhook.max_attempts}
+                                        title={t("settings.webhooks.attempts")}
+                                        onChange={e => editWebhook(index, { max_attempts: Number(e.target.value) || 1 })}
+                                        className={clsx(smallInput, "w-16")}
+                                    />
+                                </div>
+                                <div className="grid grid-cols-2 gap-2">
+                                    <div>
+                                        <label className={labelClasses}>{t("settings.webhooks.payload")}</label>
+                                        <textarea
+                                            value={webhook.payload_template}
+                                            placeholder={'{"value1": "{{content}}"}'}
+                                            rows={3}
+                                            onChange={e => editWebhook(index, { payload_template: e.target.value })}
+                                            className={clsx(smallInput, "font-mono resize-y")}
+                                        />
+                                    </div>
+                                    <div>
+                                        <label className={labelClasses}>{t("settings.webhooks.headers")}</label>
+                                        <textarea
+                                            value={headerDrafts[webhook.id] ?? headersToText(webhook.headers)}
+                                            placeholder="Authorization: Bearer …"
+                                            rows={3}
+                                            onChange={e => {
+                                                const text = e.target.value;
+                                                setHeaderDrafts(prev => ({ ...prev, [webhook.id]: text }));
+                                                editWebhook(index, { headers: textToHeaders(text) });
+                                            }}
+                                            className={clsx(smallInput, "font-mono resize-y")}
+                                        />
+                                    </div>
+                                </div>
+                            </div>
+                        ))}
+                    </div>
+                    <p className="text-[10px] text-[var(--color-text-muted)]">{t("settings.webhooks.variables", { example: "{{content}}" })}</p>
+
+                    <div className="flex items-center justify-between">
+                        <button
+                            onClick={addWebhook}
+                            className="text-[10px] uppercase tracking-wider text-[var(--color-accent)] hover:underline flex items-center gap-1"
+                        >
+                            <Plus size={10} />
+                            {t("settings.webhooks.add")}
+                        </button>
+                        <button
+                            onClick={() => save(config)}
+                            disabled={!dirty}
+                            className="px-3 py-1 text-xs rounded-md bg-[var(--color-accent)] text-black disabled:opacity-40"
+                        >
+                            {t("settings.webhooks.save")}
+                        </button>
+                    </div>
+
+                    {tested && (
+                        <p className="text-xs text-[var(--color-text-muted)]">{t("settings.webhooks.test_sent", { name: tested })}</p>
+                    )}
+
+                    {deliveries.length > 0 && (
+                        <div>
+                            <label className={labelClasses}>{t("settings.webhooks.recent")}</label>
+                            <ul className="space-y-1 text-xs">
+                                {deliveries.map(delivery => (
+                                    <li key={delivery.id} className="flex gap-2 text-[var(--color-text-secondary)]">
+                                        <span
+                                            className={clsx(
+                                                "shrink-0",
+                                                delivery.status === "delivered" && "text-emerald-400",
+                                                delivery.status === "failed" && "text-red-400",
+                                                delivery.status === "pending" && "text-amber-400"
+                                            )}
+                                        >
+                                            {t(`settings.webhooks.status.${delivery.status}`)}
+                                        </span>
+                                        <span className="shrink-0 text-[var(--color-text-muted)]">{delivery.event}</span>
+                                        <span className="truncate">
+                                            {config.webhooks.find(w => w.id === delivery.webhook_id)?.label || delivery.webhook_id}
+                                            {delivery.last_error && ` · ${delivery.last_error}`}
+                                        </span>
+                                    </li>
+                                ))}
+                            </ul>
+                        </div>
+                    )}
+                </>
+            )}
+
+            {error && <p className="text-xs text-red-400">{error}</p>}
+        </div>
+    );
+}
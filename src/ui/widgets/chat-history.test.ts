@@ -4,6 +4,7 @@ import { buildChatMessagesFromConversation } from "./chat-history";
 
 function createMessage(overrides: Partial<ConversationMessage>): ConversationMessage {
     return {
+        id: 0,
         role: "assistant",
         content: "",
         created_at: "2026-04-05T00:00:00Z",
@@ -27,6 +27,8 @@ export interface PendingTurnState {
     translationPending: boolean;
     tools: ToolTraceItem[];
     pendingContext?: ChatPanelMessage;
+    /** Started by a proactive trigger; its speech yields to everything else. */
+    proactive?: boolean;
 }
 
 export const stripStreamingMarkup = (text: string) =>
@@ -10,6 +10,7 @@ import { RefreshCw, Check, AlertCircle, Save, Trash2, Plus } from "lucide-react"
 import { motion } from "framer-motion";
 import { inputClasses, labelClasses } from "../../styles/settings-primitives";
 import { Select } from "@/components/ui/select";
+import JsonSpecEditor from "./JsonSpecEditor";
 import { useTranslation } from "react-i18next";
 import {
     fetchModels,
@@ -21,11 +22,15 @@ import {
     getLlamaCppStatus,
     getContextSettings,
     setContextSettings as saveContextSettings,
+    getInputQueueConfig,
+    saveInputQueueConfig,
     type LlmConfig,
     type LlmConnectionTestResult,
     type LlmProviderConfig,
     type LlmPreset,
     type ContextSettings,
+    type InputQueueConfig,
+    type CustomHttpSpec,
 } from "../../../lib/kokoro-bridge";
 
 export interface ApiTabProps {
@@ -57,10 +62,30 @@ function normalizeSelectedProviders(config: LlmConfig): LlmConfig {
     return next;
 }
 
-type SupportedProviderType = "openai" | "anthropic" | "ollama" | "llama_cpp";
+type SupportedProviderType = "openai" | "anthropic" | "ollama" | "llama_cpp" | "custom_http";
 
 const LLAMA_CPP_CURRENT_MODEL_KEY = "llama_cpp_current_model";
 const LLAMA_CPP_CONTEXT_LENGTH_KEY = "llama_cpp_context_length";
+const REQUESTS_PER_MINUTE_KEY = "requests_per_minute";
+const TOKENS_PER_MINUTE_KEY = "tokens_per_minute";
+const CUSTOM_HTTP_SPEC_KEY = "custom_http";
+
+// Starting point for a custom backend: an OpenAI-style chat endpoint.
+const DEFAULT_CUSTOM_HTTP_SPEC: CustomHttpSpec = {
+    url: "{{base_url}}/chat/completions",
+    headers: { Authorization: "Bearer {{api_key}}" },
+    body: {
+        model: "{{model}}",
+        messages: "{{messages}}",
+        temperature: "{{temperature}}",
+        max_tokens: "{{max_tokens}}",
+        stream: "{{stream}}",
+    },
+    stream_format: "sse",
+    stream_delta_path: "$.choices[0].delta.content",
+    response_path: "$.choices[0].message.content",
+    error_path: "$.error.message",
+};
 
 function buildProviderId(providerType: SupportedProviderType, providers: LlmProviderConfig[]): string {
     const baseId = providerType === "llama_cpp" ? "llama-cpp" : providerType;
@@ -84,6 +109,9 @@ function sanitizeProviderExtra(
         delete nextExtra[LLAMA_CPP_CURRENT_MODEL_KEY];
         delete nextExtra[LLAMA_CPP_CONTEXT_LENGTH_KEY];
     }
+    if (providerType === "custom_http" && !nextExtra[CUSTOM_HTTP_SPEC_KEY]) {
+        nextExtra[CUSTOM_HTTP_SPEC_KEY] = DEFAULT_CUSTOM_HTTP_SPEC;
+    }
     return nextExtra;
 }
 
@@ -95,6 +123,8 @@ function getDefaultBaseUrl(providerType: SupportedProviderType): string {
             return "http://localhost:11434";
         case "llama_cpp":
             return "http://127.0.0.1:8080";
+        case "custom_http":
+            return "http://127.0.0.1:8000/v1";
         default:
             return "https://api.openai.com/v1";
     }
@@ -107,6 +137,7 @@ function getDefaultModel(providerType: SupportedProviderType): string {
         case "ollama":
             return "llama3";
         case "llama_cpp":
+        case "custom_http":
             return "";
         default:
             return "gpt-4";
@@ -154,6 +185,14 @@ function normalizeProviderForType(
         };
     }
 
+    if (providerType === "custom_http") {
+        // The spec has no tool-call mapping, so tools go through the prompt.
+        return {
+            ...base,
+            supports_native_tools: false,
+        };
+    }
+
     if (providerType === "ollama") {
         return {
             ...base,
@@ -194,12 +233,15 @@ function getProviderTypeLabel(providerType: string): string {
             return "Ollama";
         case "llama_cpp":
             return "llama.cpp";
+        case "custom_http":
+            return "Custom HTTP";
         default:
             return "OpenAI-Compatible";
     }
 }
 
 function getProviderLocationLabel(providerType: string): string {
+    if (providerType === "custom_http") return "Custom";
     return providerType === "openai" || providerType === "anthropic" ? "Cloud" : "Local";
 }
 
@@ -245,6 +287,7 @@ export default function ApiTab({ visionEnabled, onVisionEnabledChange, initialCo
         strategy: "window",
         max_message_chars: 2000,
     });
+    const [inputQueueConfig, setInputQueueConfig] = useState<InputQueueConfig | null>(null);
     // Load config from backend on mount
     useEffect(() => {
         if (initialConfig) {
@@ -268,6 +311,9 @@ export default function ApiTab({ visionEnabled, onVisionEnabledChange, initialCo
         getContextSettings()
             .then(setContextSettings)
             .catch((e) => console.error("Failed to load context settings:", e));
+        getInputQueueConfig()
+            .then(setInputQueueConfig)
+            .catch((e) => console.error("Failed to load input queue config:", e));
     }, [initialConfig]);
 
     useEffect(() => {
@@ -342,6 +388,16 @@ export default function ApiTab({ visionEnabled, onVisionEnabledChange, initialCo
         [contextSettings]
     );
 
+    const handleInputQueueChange = useCallback(
+        (updates: Partial<InputQueueConfig>) => {
+            if (!inputQueueConfig) return;
+            const updated = { ...inputQueueConfig, ...updates };
+            setInputQueueConfig(updated);
+            saveInputQueueConfig(updated).catch((e) => console.error("Failed to save input queue config:", e));
+        },
+        [inputQueueConfig]
+    );
+
     const handleSavePreset = useCallback(() => {
         if (!config) return;
         const normalizedConfig = normalizeSelectedProviders(config);
@@ -602,11 +658,12 @@ export default function ApiTab({ visionEnabled, onVisionEnabledChange, initialCo
     const isOllama = activeProvider.provider_type === "ollama";
     const isAnthropic = activeProvider.provider_type === "anthropic";
     const isLlamaCpp = activeProvider.provider_type === "llama_cpp";
-    const showApiKey = activeProvider.provider_type === "openai" || isAnthropic;
+    const isCustomHttp = activeProvider.provider_type === "custom_http";
+    const showApiKey = activeProvider.provider_type === "openai" || isAnthropic || isCustomHttp;
     const configuredContextLength = getProviderExtraNumber(activeProvider, LLAMA_CPP_CONTEXT_LENGTH_KEY);
     const detectedCurrentModel = getProviderExtraString(activeProvider, LLAMA_CPP_CURRENT_MODEL_KEY);
     const modelFetchDisabled =
-        isLoadingModels || ((activeProvider.provider_type === "openai" || isAnthropic) && !activeProvider.api_key);
+        isLoadingModels || isCustomHttp || ((activeProvider.provider_type === "openai" || isAnthropic) && !activeProvider.api_key);
 
     return (
         <div className="space-y-4">
@@ -692,7 +749,7 @@ export default function ApiTab({ visionEnabled, onVisionEnabledChange, initialCo
                     ))}
                 </div>
                 <div className="flex flex-wrap gap-2 mt-2">
-                    {(["openai", "anthropic", "ollama", "llama_cpp"] as const).map((providerType) => (
+                    {(["openai", "anthropic", "ollama", "llama_cpp", "custom_http"] as const).map((providerType) => (
                         <button
                             key={providerType}
                             onClick={() => {
@@ -868,6 +925,47 @@ export default function ApiTab({ visionEnabled, onVisionEnabledChange, initialCo
                 </>
             )}
 
+            {isCustomHttp && (
+                <JsonSpecEditor
+                    key={activeProvider.id}
+                    value={activeProvider.extra?.[CUSTOM_HTTP_SPEC_KEY]}
+                    fallback={DEFAULT_CUSTOM_HTTP_SPEC}
+                    label={t("settings.api.custom_http.label", { defaultValue: "Request / Response Mapping" })}
+                    hint={t("settings.api.custom_http.hint", {
+                        skipInterpolation: true,
+                        defaultValue:
+                            "Placeholders: {{messages}}, {{prompt}}, {{model}}, {{stream}}, {{temperature}}, {{max_tokens}}, {{api_key}}, {{base_url}}. Paths use $.a.b[0].c.",
+                    })}
+                    onChange={(spec) => updateActiveProviderExtra({ [CUSTOM_HTTP_SPEC_KEY]: spec })}
+                />
+            )}
+
+            <div>
+                <label className={labelClasses}>{t("settings.api.rate_limit.label")}</label>
+                <div className="grid grid-cols-2 gap-3">
+                    {([
+                        [REQUESTS_PER_MINUTE_KEY, "settings.api.rate_limit.requests_placeholder"],
+                        [TOKENS_PER_MINUTE_KEY, "settings.api.rate_limit.tokens_placeholder"],
+                    ] as const).map(([key, placeholder]) => (
+                        <input
+                            key={key}
+                            type="number"
+                            min={1}
+                            value={getProviderExtraNumber(activeProvider, key) ?? ""}
+                            onChange={(e) => {
+                                const value = e.target.value.trim();
+                                updateActiveProviderExtra({ [key]: value ? Number(value) : undefined });
+                            }}
+                            placeholder={t(placeholder)}
+                            className={clsx(inputClasses, "font-mono [appearance:textfield] [&::-webkit-outer-spin-button]:appearance-none [&::-webkit-inner-spin-button]:appearance-none")}
+                        />
+                    ))}
+                </div>
+                <p className="text-[9px] text-[var(--color-text-muted)] mt-1">
+                    {t("settings.api.rate_limit.hint")}
+                </p>
+            </div>
+
             <div>
                 <div className="flex items-start justify-between gap-3">
                     <div>
@@ -1092,6 +1190,41 @@ export default function ApiTab({ visionEnabled, onVisionEnabledChange, initialCo
                     </p>
                 </div>
             </div>
+
+            {/* Messages sent while a reply is streaming */}
+            {inputQueueConfig && (
+                <div className="pt-4 border-t border-[var(--color-border)]">
+                    <div className="mb-3">
+                        <label className="text-xs font-medium text-[var(--color-text-main)] block mb-1">
+                            {t("settings.api.input_queue.title")}
+                        </label>
+                        <p className="text-[10px] text-[var(--color-text-muted)]">
+                            {t("settings.api.input_queue.desc")}
+                        </p>
+                    </div>
+                    <div className="flex gap-2">
+                        {(["sequential", "restart"] as const).map((m) => (
+                            <button
+                                key={m}
+                                onClick={() => handleInputQueueChange({ mode: m })}
+                                className={clsx(
+                                    "flex-1 px-3 py-2 text-xs rounded-lg border transition-all",
+                                    inputQueueConfig.mode === m
+                                        ? "border-[var(--color-accent)] bg-[var(--color-accent)]/10 text-[var(--color-accent)]"
+                                        : "border-[var(--color-border)] text-[var(--color-text-muted)] hover:border-[var(--color-text-muted)]"
+                                )}
+                            >
+                                <div className="font-medium">
+                                    {t(`settings.api.input_queue.mode_${m}`)}
+                                </div>
+                                <div className="text-[9px] opacity-70 mt-0.5">
+                                    {t(`settings.api.input_queue.mode_${m}_desc`)}
+                                </div>
+                            </button>
+                        ))}
+                    </div>
+                </div>
+            )}
         </div>
     );
 }
@@ -1,11 +1,11 @@
 import React, { useState, useEffect } from 'react';
 import { motion } from 'framer-motion';
 import { clsx } from 'clsx';
-import { Download, Upload, Loader2, Check, AlertTriangle, Database, FileJson, Clock, FolderOpen, Trash2, Play } from 'lucide-react';
+import { Download, Upload, Loader2, Check, AlertTriangle, Database, FileJson, Clock, FolderOpen, Trash2, Play, Truck } from 'lucide-react';
 import { useTranslation } from 'react-i18next';
 import { save, open, open as openDialog } from '@tauri-apps/plugin-dialog';
-import { exportData, previewImport, importData, getAutoBackupConfig, saveAutoBackupConfig, runAutoBackupNow, listCharacters, setUserName, setUserPersona } from '../../../lib/kokoro-bridge';
-import type { ImportPreview, AutoBackupConfig } from '../../../lib/kokoro-bridge';
+import { exportData, previewImport, importData, getAutoBackupConfig, saveAutoBackupConfig, runAutoBackupNow, listCharacters, setUserName, setUserPersona, exportMigration, previewMigration, importMigration, onMigrationProgress } from '../../../lib/kokoro-bridge';
+import type { ImportPreview, AutoBackupConfig, MigrationProgress } from '../../../lib/kokoro-bridge';
 import { characterDb } from '../../../lib/db';
 import type { CharacterProfile } from '../../../lib/db';
 import { sectionHeadingClasses } from '../../styles/settings-primitives';
@@ -49,10 +49,22 @@ export const BackupTab: React.FC = () => {
     const [runningNow, setRunningNow] = useState(false);
     const [runNowResult, setRunNowResult] = useState<string | null>(null);
 
+    // Migration state
+    const [includeLocalModels, setIncludeLocalModels] = useState(false);
+    const [migrating, setMigrating] = useState(false);
+    const [migrationProgress, setMigrationProgress] = useState<MigrationProgress | null>(null);
+    const [migrationDone, setMigrationDone] = useState<string | null>(null);
+    const [migrationError, setMigrationError] = useState<string | null>(null);
+
     useEffect(() => {
         getAutoBackupConfig().then(setAutoBackup).catch(() => {});
     }, []);
 
+    useEffect(() => {
+        const unlisten = onMigrationProgress(setMigrationProgress);
+        return () => { unlisten.then(fn => fn()); };
+    }, []);
+
     const handleSaveAutoBackup = async () => {
         setAutoBackupError(null);
         setAutoBackupSaved(false);
@@ -86,6 +98,43 @@ export const BackupTab: React.FC = () => {
         }
     };
 
+    const serializeCharacters = async (): Promise<string> => {
+        // 序列化角色数据：从 SQLite 读文字数据，从 IndexedDB 匹配头像
+        const sqliteChars = await listCharacters();
+        const idbChars = await characterDb.getAll();
+        const idbByStableId = new Map(idbChars.filter(c => c.stableId).map(c => [c.stableId, c]));
+        const userName = localStorage.getItem('kokoro_user_name') ?? 'User';
+        const userPersona = localStorage.getItem('kokoro_user_persona') ?? '';
+        const userLanguage = localStorage.getItem('kokoro_user_language');
+        const responseLanguage = localStorage.getItem('kokoro_response_language');
+        const voiceInterrupt = localStorage.getItem('kokoro_voice_interrupt');
+
+        // Keep the on-disk profile config in sync with the localStorage payload
+        // so configs/user_profile.json and characters.json do not disagree.
+        await setUserName(userName);
+        await setUserPersona(userPersona);
+
+        const charsSerializable = await Promise.all(sqliteChars.map(async (c) => {
+            const idbChar = idbByStableId.get(c.id);
+            if (idbChar?.avatarBlob) {
+                const buf = await idbChar.avatarBlob.arrayBuffer();
+                const b64 = btoa(String.fromCharCode(...new Uint8Array(buf)));
+                return { ...c, stableId: c.id, avatarB64: b64 };
+            }
+            return { ...c, stableId: c.id };
+        }));
+        const payload = {
+            characters: charsSerializable,
+            activeCharacterId: localStorage.getItem('kokoro_active_character_id'),
+            userName,
+            userPersona,
+            userLanguage,
+            responseLanguage,
+            voiceInterrupt,
+        };
+        return JSON.stringify(payload);
+    };
+
     const handleExport = async () => {
         setExporting(true);
         setExportResult(null);
@@ -97,40 +146,7 @@ export const BackupTab: React.FC = () => {
             });
             if (!filePath) { setExporting(false); return; }
 
-            // 序列化角色数据：从 SQLite 读文字数据，从 IndexedDB 匹配头像
-            const sqliteChars = await listCharacters();
-            const idbChars = await characterDb.getAll();
-            const idbByStableId = new Map(idbChars.filter(c => c.stableId).map(c => [c.stableId, c]));
-            const userName = localStorage.getItem('kokoro_user_name') ?? 'User';
-            const userPersona = localStorage.getItem('kokoro_user_persona') ?? '';
-            const userLanguage = localStorage.getItem('kokoro_user_language');
-            const responseLanguage = localStorage.getItem('kokoro_response_language');
-            const voiceInterrupt = localStorage.getItem('kokoro_voice_interrupt');
-
-            // Keep the on-disk profile config in sync with the localStorage payload
-            // so configs/user_profile.json and characters.json do not disagree.
-            await setUserName(userName);
-            await setUserPersona(userPersona);
-
-            const charsSerializable = await Promise.all(sqliteChars.map(async (c) => {
-                const idbChar = idbByStableId.get(c.id);
-                if (idbChar?.avatarBlob) {
-                    const buf = await idbChar.avatarBlob.arrayBuffer();
-                    const b64 = btoa(String.fromCharCode(...new Uint8Array(buf)));
-                    return { ...c, stableId: c.id, avatarB64: b64 };
-                }
-                return { ...c, stableId: c.id };
-            }));
-            const payload = {
-                characters: charsSerializable,
-                activeCharacterId: localStorage.getItem('kokoro_active_character_id'),
-                userName,
-                userPersona,
-                userLanguage,
-                responseLanguage,
-                voiceInterrupt,
-            };
-            const charactersJson = JSON.stringify(payload);
+            const charactersJson = await serializeCharacters();
 
             const result = await exportData(filePath, charactersJson);
             setExportResult({
@@ -297,6 +313,73 @@ export const BackupTab: React.FC = () => {
         }
     };
 
+    const handleMigrationExport = async () => {
+        setMigrationDone(null);
+        setMigrationError(null);
+        try {
+            const filePath = await save({
+                defaultPath: `kokoro-migration-${new Date().toISOString().slice(0, 10)}.kokoro`,
+                filters: [{ name: 'Kokoro Backup', extensions: ['kokoro'] }],
+            });
+            if (!filePath) return;
+            setMigrating(true);
+            const result = await exportMigration(filePath, await serializeCharacters(), includeLocalModels);
+            setMigrationDone(t('settings.backup.migration_export_success', {
+                size: formatBytes(result.size_bytes),
+                files: result.manifest.sections.reduce((sum, section) => sum + section.files, 0),
+            }));
+        } catch (e: any) {
+            setMigrationError(typeof e === 'string' ? e : (e?.message ?? JSON.stringify(e)));
+        } finally {
+            setMigrating(false);
+            setMigrationProgress(null);
+        }
+    };
+
+    const handleMigrationImport = async () => {
+        setMigrationDone(null);
+        setMigrationError(null);
+        try {
+            const filePath = await open({
+                filters: [{ name: 'Kokoro Backup', extensions: ['kokoro'] }],
+                multiple: false,
+            });
+            if (!filePath) return;
+            const path = typeof filePath === 'string' ? filePath : filePath[0];
+            const migration = await previewMigration(path);
+            if (migration.incompatible) {
+                setMigrationError(migration.incompatible);
+                return;
+            }
+            setMigrating(true);
+            // Step 1: restore models, mods and images as they were on the old machine.
+            const result = await importMigration(path, true);
+            let message = t('settings.backup.migration_import_success', {
+                restored: result.restored_files,
+                skipped: result.skipped_files,
+            });
+            if (result.missing_local_models.length > 0) {
+                message += '\n' + t('settings.backup.migration_missing_models', {
+                    models: result.missing_local_models.join(', '),
+                });
+            }
+            setMigrationDone(message);
+            // Step 2: the archive is also a backup, so the regular import takes over.
+            setImportDone(null);
+            setImportError(null);
+            setImportFilePath(path);
+            const p = await previewImport(path);
+            setPreview(p);
+            setImportDb(p.has_database);
+            setImportConfigs(p.has_configs);
+        } catch (e: any) {
+            setMigrationError(typeof e === 'string' ? e : (e?.message ?? JSON.stringify(e)));
+        } finally {
+            setMigrating(false);
+            setMigrationProgress(null);
+        }
+    };
+
     const toggleClasses = clsx(
         "relative w-9 h-5 rounded-full transition-colors cursor-pointer",
         "after:content-[''] after:absolute after:top-0.5 after:left-0.5 after:w-4 after:h-4 after:rounded-full after:bg-white after:transition-transform"
@@ -579,6 +662,84 @@ export const BackupTab: React.FC = () => {
                     </div>
                 )}
             </div>
+
+            <div className="border-t border-[var(--color-border)]" />
+
+            {/* Migration Section */}
+            <div>
+                <div className={clsx(sectionHeadingClasses, "mb-3")}>{t('settings.backup.migration_title')}</div>
+                <p className="text-xs text-[var(--color-text-muted)] mb-4">{t('settings.backup.migration_desc')}</p>
+
+                <label className="flex items-center gap-2 text-xs text-[var(--color-text-primary)] cursor-pointer mb-3">
+                    <input type="checkbox" checked={includeLocalModels} onChange={e => setIncludeLocalModels(e.target.checked)}
+                        className={clsx(toggleClasses, includeLocalModels ? "bg-[var(--color-accent)]" : "bg-[var(--color-border)]")}
+                        style={{ appearance: 'none' }}
+                    />
+                    {t('settings.backup.migration_include_models')}
+                </label>
+
+                <div className="flex gap-2">
+                    <motion.button
+                        whileHover={{ scale: 1.02 }}
+                        whileTap={{ scale: 0.98 }}
+                        onClick={handleMigrationExport}
+                        disabled={migrating}
+                        className={clsx(
+                            "flex items-center gap-2 px-4 py-2.5 rounded-lg text-sm font-heading font-semibold tracking-wider",
+                            "bg-[var(--color-accent)] text-black hover:bg-white transition-colors",
+                            "disabled:opacity-50 disabled:cursor-not-allowed"
+                        )}
+                    >
+                        {migrating ? <Loader2 size={16} className="animate-spin" /> : <Truck size={16} />}
+                        {t('settings.backup.migration_export_button')}
+                    </motion.button>
+                    <motion.button
+                        whileHover={{ scale: 1.02 }}
+                        whileTap={{ scale: 0.98 }}
+                        onClick={handleMigrationImport}
+                        disabled={migrating}
+                        className={clsx(
+                            "flex items-center gap-2 px-4 py-2.5 rounded-lg text-sm font-heading font-semibold tracking-wider",
+                            "border border-[var(--color-border)] text-[var(--color-text-secondary)]",
+                            "hover:border-[var(--color-accent)] hover:text-[var(--color-accent)] transition-colors",
+                            "disabled:opacity-50 disabled:cursor-not-allowed"
+                        )}
+                    >
+                        <Upload size={16} />
+                        {t('settings.backup.migration_import_button')}
+                    </motion.button>
+                </div>
+
+                {migrating && migrationProgress && (
+                    <div className="mt-3 space-y-1">
+                        <div className="text-[10px] text-[var(--color-text-muted)]">
+                            {t('settings.backup.migration_progress', {
+                                section: migrationProgress.section,
+                                done: migrationProgress.done,
+                                total: migrationProgress.total,
+                            })}
+                        </div>
+                        <div className="h-1.5 rounded-full bg-black/30 overflow-hidden">
+                            <div
+                                className="h-full bg-[var(--color-accent)] transition-all"
+                                style={{ width: `${migrationProgress.total > 0 ? (migrationProgress.done / migrationProgress.total) * 100 : 0}%` }}
+                            />
+                        </div>
+                    </div>
+                )}
+                {migrationDone && (
+                    <div className="mt-3 flex items-start gap-2 text-xs text-[var(--color-accent)]">
+                        <Check size={14} className="mt-0.5 shrink-0" />
+                        <span className="whitespace-pre-wrap">{migrationDone}</span>
+                    </div>
+                )}
+                {migrationError && (
+                    <div className="mt-3 flex items-start gap-2 text-xs text-red-400">
+                        <AlertTriangle size={14} className="mt-0.5 shrink-0" />
+                        <span>{migrationError}</span>
+                    </div>
+                )}
+            </div>
         </div>
     );
 };
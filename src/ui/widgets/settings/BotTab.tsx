@@ -11,6 +11,7 @@ import {
     Play,
     RefreshCw,
     Send,
+    Server,
     Shield,
     Square,
     Volume2,
@@ -19,16 +20,25 @@ import {
 } from "lucide-react";
 import { useTranslation } from "react-i18next";
 import {
+    createTelegramPairingCode,
+    getApiServerConfig,
+    getApiServerStatus,
     getTelegramStatus,
     getBotStatus,
     listCharacters,
+    onTelegramChatAuthorized,
+    saveApiServerConfig,
     saveBotConfig,
+    startApiServer,
     startBotPlatform,
     startTelegramBot,
+    stopApiServer,
     stopBotPlatform,
     stopTelegramBot,
 } from "../../../lib/kokoro-bridge";
 import type {
+    ApiServerConfig,
+    ApiServerStatus,
     BotConfig,
     BotPlatformId,
     BotStatus,
@@ -36,6 +46,7 @@ import type {
     DiscordBotConfig,
     LineBotConfig,
     TelegramConfig,
+    TelegramPairingCode,
     TelegramStatus,
     WebhookBotConfig,
 } from "../../../lib/kokoro-bridge";
@@ -133,6 +144,24 @@ export default function BotTab({
         setListInput("");
     }, [botConfig?.selected_platform]);
 
+    // A chat paired itself via /authorize; the backend already saved it.
+    const botConfigRef = useRef(botConfig);
+    botConfigRef.current = botConfig;
+    useEffect(() => {
+        const unlisten = onTelegramChatAuthorized(({ chat_id }) => {
+            const current = botConfigRef.current;
+            if (!current || current.telegram.allowed_chat_ids.includes(chat_id)) return;
+            onBotConfigChange({
+                ...current,
+                telegram: {
+                    ...current.telegram,
+                    allowed_chat_ids: [...current.telegram.allowed_chat_ids, chat_id],
+                },
+            });
+        });
+        return () => { unlisten.then(fn => fn()); };
+    }, [onBotConfigChange]);
+
     if (loading || !botConfig) {
         return (
             <div className="flex items-center justify-center py-12">
@@ -293,6 +322,149 @@ export default function BotTab({
                     onRefresh={loadStatus}
                 />
             )}
+
+            <div className="h-px bg-[var(--color-border)]" />
+
+            <LocalApiSettings />
+        </div>
+    );
+}
+
+function errorText(error: unknown): string {
+    if (error && typeof error === "object" && "message" in error) {
+        return String((error as { message: unknown }).message);
+    }
+    return String(error);
+}
+
+function LocalApiSettings() {
+    const { t } = useTranslation();
+    const [config, setConfig] = useState<ApiServerConfig | null>(null);
+    const [status, setStatus] = useState<ApiServerStatus | null>(null);
+    const [dirty, setDirty] = useState(false);
+    const [error, setError] = useState<string | null>(null);
+
+    const refresh = async () => {
+        try {
+            setStatus(await getApiServerStatus());
+        } catch (e) {
+            console.error("[BotTab] Local API status failed:", e);
+        }
+    };
+
+    useEffect(() => {
+        getApiServerConfig()
+            .then(setConfig)
+            .catch(e => console.error("[BotTab] Failed to load local API config:", e));
+        void refresh();
+    }, []);
+
+    if (!config) {
+        return null;
+    }
+
+    const update = (patch: Partial<ApiServerConfig>) => {
+        setConfig({ ...config, ...patch });
+        setDirty(true);
+    };
+
+    const save = async () => {
+        try {
+            await saveApiServerConfig(config);
+            setDirty(false);
+            setError(null);
+        } catch (e) {
+            setError(errorText(e));
+            throw e;
+        }
+    };
+
+    const handleStart = async () => {
+        try {
+            if (dirty) {
+                await save();
+            }
+            setStatus(await startApiServer());
+            setError(null);
+        } catch (e) {
+            setError(errorText(e));
+        }
+    };
+
+    const handleStop = async () => {
+        try {
+            await stopApiServer();
+            await refresh();
+        } catch (e) {
+            setError(errorText(e));
+        }
+    };
+
+    const generateKey = () => {
+        update({ api_key: `${crypto.randomUUID()}${crypto.randomUUID()}`.replace(/-/g, "") });
+    };
+
+    return (
+        <div className="space-y-6">
+            <PlatformHeader
+                icon={Server}
+                title={t("bot.local_api.title")}
+                enabled={config.enabled}
+                running={status?.running ?? false}
+                onStart={() => { void handleStart(); }}
+                onStop={() => { void handleStop(); }}
+                onRefresh={() => { void refresh(); }}
+            />
+            <p className="text-xs leading-5 text-[var(--color-text-muted)]">{t("bot.local_api.desc")}</p>
+            <ToggleRow
+                label={t("bot.local_api.enabled")}
+                enabled={config.enabled}
+                onChange={enabled => update({ enabled })}
+                icon={CheckCircle2}
+            />
+            <div className="w-[110px]">
+                <label className={labelClasses}>{t("bot.local_api.port")}</label>
+                <input
+                    type="number"
+                    min={1}
+                    max={65535}
+                    value={config.port}
+                    onChange={e => update({ port: Number(e.target.value) || 7373 })}
+                    className={inputClasses}
+                />
+            </div>
+            <div className="space-y-2">
+                <SecretField
+                    label={t("bot.local_api.api_key")}
+                    value={config.api_key}
+                    placeholder={t("bot.local_api.api_key_placeholder")}
+                    fallbackEnv="KOKORO_API_KEY"
+                    onValueChange={api_key => update({ api_key })}
+                />
+                <button
+                    type="button"
+                    onClick={generateKey}
+                    className="flex items-center gap-1.5 text-xs text-[var(--color-accent)] hover:underline"
+                >
+                    <KeyRound size={12} /> {t("bot.local_api.generate_key")}
+                </button>
+            </div>
+            {status?.running && status.port != null && (
+                <div className="text-xs text-[var(--color-text-secondary)]">
+                    {t("bot.local_api.listening", { url: `http://127.0.0.1:${status.port}` })}
+                </div>
+            )}
+            {error && <div className="text-xs text-red-400">{error}</div>}
+            {dirty && (
+                <motion.button
+                    whileTap={{ scale: 0.95 }}
+                    onClick={() => { void save().catch(() => {}); }}
+                    className="px-4 py-2 rounded-md text-xs font-heading font-semibold tracking-wider
+                        bg-[var(--color-accent)] text-black hover:bg-white transition-colors"
+                >
+                    {t("common.actions.save")}
+                </motion.button>
+            )}
         </div>
     );
 }
@@ -571,6 +743,15 @@ function TelegramSettings({
 }) {
     const { t } = useTranslation();
     const [chatIdInput, setChatIdInput] = useState("");
+    const [pairing, setPairing] = useState<TelegramPairingCode | null>(null);
+
+    const handleCreatePairingCode = async () => {
+        try {
+            setPairing(await createTelegramPairingCode());
+        } catch (e) {
+            console.error("[BotTab] Failed to create pairing code:", e);
+        }
+    };
 
     return (
         <div className="space-y-6">
@@ -647,6 +828,35 @@ function TelegramSettings({
                 numeric
             />
 
+            <div className="space-y-2">
+                <div className="flex items-center justify-between gap-3">
+                    <div className="text-xs text-[var(--color-text-muted)]">
+                        {t("telegram.pairing.desc")}
+                    </div>
+                    <motion.button
+                        whileTap={{ scale: 0.95 }}
+                        onClick={handleCreatePairingCode}
+                        disabled={!isRunning}
+                        className={clsx(
+                            "flex items-center gap-1.5 px-3 py-1.5 rounded-md text-xs shrink-0 border border-[var(--color-border)] text-[var(--color-text-secondary)] hover:text-[var(--color-accent)] hover:border-[var(--color-accent)] transition-colors",
+                            !isRunning && "opacity-50 cursor-not-allowed"
+                        )}
+                    >
+                        <KeyRound size={12} /> {t("telegram.pairing.generate")}
+                    </motion.button>
+                </div>
+                {pairing && (
+                    <div className="px-3 py-2 rounded-md bg-[var(--color-bg-elevated)] border border-[var(--color-border)] text-xs text-[var(--color-text-secondary)]">
+                        {t("telegram.pairing.instructions", {
+                            minutes: Math.round(pairing.expires_in_secs / 60),
+                        })}
+                        <div className="mt-1 font-mono text-sm text-[var(--color-text-primary)] select-all">
+                            /authorize {pairing.code}
+                        </div>
+                    </div>
+                )}
+            </div>
+
             <CharacterSelect
                 value={config.character_id}
                 options={characterOptions}
@@ -659,6 +869,13 @@ function TelegramSettings({
                 onChange={send_voice_reply => onUpdate({ send_voice_reply })}
                 icon={Volume2}
             />
+
+            <ToggleRow
+                label={t("telegram.proactive_when_away")}
+                enabled={config.proactive_when_away}
+                onChange={proactive_when_away => onUpdate({ proactive_when_away })}
+                icon={MessageCircle}
+            />
         </div>
     );
 }
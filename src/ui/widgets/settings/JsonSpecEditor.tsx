@@ -0,0 +1,53 @@
+import { useState } from "react";
+import { clsx } from "clsx";
+import { useTranslation } from "react-i18next";
+import { labelClasses } from "../../styles/settings-primitives";
+
+export interface JsonSpecEditorProps<T extends object> {
+    value: unknown;
+    fallback: T;
+    label: string;
+    hint: string;
+    onChange: (value: T) => void;
+}
+
+/** Textarea for a JSON object stored in a provider's `extra`; commits on blur once it parses. */
+export default function JsonSpecEditor<T extends object>({ value, fallback, label, hint, onChange }: JsonSpecEditorProps<T>) {
+    const { t } = useTranslation();
+    const [text, setText] = useState(() => JSON.stringify(value ?? fallback, null, 2));
+    const [parseError, setParseError] = useState<string | null>(null);
+
+    const commit = () => {
+        try {
+            const parsed = JSON.parse(text);
+            if (!parsed || typeof parsed !== "object" || Array.isArray(parsed)) {
+                throw new Error(t("settings.json_spec.not_object", { defaultValue: "Must be a JSON object" }));
+            }
+            setParseError(null);
+            onChange(parsed as T);
+        } catch (e) {
+            setParseError(e instanceof Error ? e.message : String(e));
+        }
+    };
+
+    return (
+        <div>
+            <label className={labelClasses}>{label}</label>
+            <textarea
+                value={text}
+                onChange={(e) => setText(e.target.value)}
+                onBlur={commit}
+                rows={14}
+                spellCheck={false}
+                className={clsx(
+                    "w-full px-3 py-2 rounded-lg text-xs font-mono leading-relaxed",
+                    "bg-black/40 border",
+                    parseError ? "border-red-500/50" : "border-[var(--color-border)]",
+                    "text-[var(--color-text-main)] focus:outline-none focus:border-[var(--color-accent)] transition-colors"
+                )}
+            />
+            {parseError && <p className="text-[9px] text-red-400 mt-1">{parseError}</p>}
+            <p className="text-[9px] text-[var(--color-text-muted)] mt-1">{hint}</p>
+        </div>
+    );
+}
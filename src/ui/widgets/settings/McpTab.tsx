@@ -3,14 +3,18 @@ import { motion, AnimatePresence } from "framer-motion";
 import { clsx } from "clsx";
 import {
     Server, Plus, Trash2, RefreshCw, CheckCircle2, XCircle,
-    Loader2, Wrench, AlertCircle, Copy, Power
+    Loader2, Wrench, AlertCircle, Copy, Power, ShieldAlert, KeyRound, LogOut
 } from "lucide-react";
 import { useTranslation } from "react-i18next";
+import { open as openDialog } from "@tauri-apps/plugin-dialog";
 import {
     listMcpServers, addMcpServer, removeMcpServer, refreshMcpTools, reconnectMcpServer, toggleMcpServer,
-    listActions, getToolSettings, saveToolSettings
+    authorizeMcpServer, signOutMcpServer,
+    listActions, getToolSettings, saveToolSettings, listMcpTools, setMcpToolPolicy, onMcpServerStatus,
+    getToolPlanConfig, saveToolPlanConfig, getCodeExecConfig, saveCodeExecConfig,
+    listCsvTables, loadCsvTable, removeCsvTable
 } from "../../../lib/kokoro-bridge";
-import type { ActionInfo, McpServerConfig, McpServerStatus, ToolSettings } from "../../../lib/kokoro-bridge";
+import type { ActionInfo, CodeExecConfig, CsvTableSummary, McpServerConfig, McpServerStatus, McpToolPolicy, McpToolStatus, ToolPlanConfig, ToolSettings } from "../../../lib/kokoro-bridge";
 import {
     buildSortedToolGroups,
     getToolBadgeClass,
@@ -120,6 +124,7 @@ export default function McpTab({ initialServers, visionEnabled, isActive = false
     const [parseError, setParseError] = useState<string | null>(null);
     const [adding, setAdding] = useState(false);
     const [removingName, setRemovingName] = useState<string | null>(null);
+    const [authorizingName, setAuthorizingName] = useState<string | null>(null);
     const [successMsg, setSuccessMsg] = useState<string | null>(null);
     const [tools, setTools] = useState<ActionInfo[]>([]);
     const [toolSettings, setToolSettings] = useState<ToolSettings>({
@@ -129,6 +134,19 @@ export default function McpTab({ initialServers, visionEnabled, isActive = false
         blocked_risk_tags: [],
     });
     const [savingToolSettings, setSavingToolSettings] = useState(false);
+    const [planConfig, setPlanConfig] = useState<ToolPlanConfig>({ confirmation: "risky", max_steps: 8 });
+    const [csvTables, setCsvTables] = useState<CsvTableSummary[]>([]);
+    const [csvError, setCsvError] = useState<string | null>(null);
+    const [codeExecConfig, setCodeExecConfig] = useState<CodeExecConfig>({
+        timeout_ms: 5000,
+        memory_limit_mb: 64,
+        max_output_chars: 4000,
+        container_runtime: null,
+        python_image: "python:3.12-alpine",
+    });
+    const [mcpTools, setMcpTools] = useState<McpToolStatus[]>([]);
+    /** Next automatic reconnect per server, from `mcp-server-status`. */
+    const [retries, setRetries] = useState<Record<string, { attempts: number; seconds: number }>>({});
 
     const fetchServers = useCallback(async () => {
         try {
@@ -143,12 +161,20 @@ export default function McpTab({ initialServers, visionEnabled, isActive = false
 
     const fetchToolState = useCallback(async () => {
         try {
-            const [toolDirectory, settings] = await Promise.all([
+            const [toolDirectory, settings, policies, plan, codeExec, tables] = await Promise.all([
                 listActions(),
                 getToolSettings(),
+                listMcpTools(),
+                getToolPlanConfig(),
+                getCodeExecConfig(),
+                listCsvTables(),
             ]);
             setTools(toolDirectory);
             setToolSettings(settings);
+            setMcpTools(policies);
+            setPlanConfig(plan);
+            setCodeExecConfig(codeExec);
+            setCsvTables(tables);
         } catch (e) {
             console.error("[McpTab] Failed to fetch tool settings:", e);
         }
@@ -166,6 +192,31 @@ export default function McpTab({ initialServers, visionEnabled, isActive = false
         void reloadAll();
     }, [reloadAll, hasInitialServers, initialServers]);
 
+    // Live updates from the backend health monitor
+    useEffect(() => {
+        let disposed = false;
+        let unlisten: (() => void) | undefined;
+        void onMcpServerStatus(({ reconnect_attempts, next_retry_secs, ...status }) => {
+            setServers((prev) => prev.map((s) => (s.name === status.name ? status : s)));
+            setRetries((prev) => {
+                const rest = Object.fromEntries(Object.entries(prev).filter(([name]) => name !== status.name));
+                return next_retry_secs == null
+                    ? rest
+                    : { ...rest, [status.name]: { attempts: reconnect_attempts, seconds: next_retry_secs } };
+            });
+            if (status.status === "connected") {
+                void fetchToolState();
+            }
+        }).then((fn) => {
+            if (disposed) fn();
+            else unlisten = fn;
+        });
+        return () => {
+            disposed = true;
+            unlisten?.();
+        };
+    }, [fetchToolState]);
+
     // Auto-poll while any server is in "connecting" state
     useEffect(() => {
         const hasConnecting = servers.some(s => s.status === "connecting");
@@ -247,6 +298,28 @@ export default function McpTab({ initialServers, visionEnabled, isActive = false
         }
     };
 
+    // ── OAuth sign-in ────────────────────────────────────
+    const handleAuthorize = async (name: string) => {
+        setAuthorizingName(name);
+        try {
+            await authorizeMcpServer(name);
+            await fetchServers();
+        } catch (e) {
+            console.error("[McpTab] Sign-in failed:", e);
+        } finally {
+            setAuthorizingName(null);
+        }
+    };
+
+    const handleSignOut = async (name: string) => {
+        try {
+            await signOutMcpServer(name);
+            await reloadAll();
+        } catch (e) {
+            console.error("[McpTab] Sign-out failed:", e);
+        }
+    };
+
     // ── Toggle server enabled/disabled ────────────────────
     const handleToggle = async (name: string, currentEnabled: boolean) => {
         try {
@@ -293,6 +366,19 @@ export default function McpTab({ initialServers, visionEnabled, isActive = false
         await persistToolSettings(next);
     };
 
+    const handleToolPolicyChange = async (tool: McpToolStatus, patch: Partial<McpToolPolicy>) => {
+        const policy: McpToolPolicy = {
+            enabled: patch.enabled ?? tool.enabled,
+            auto_approve: patch.auto_approve !== undefined ? patch.auto_approve : tool.auto_approve,
+        };
+        try {
+            await setMcpToolPolicy(tool.server_name, tool.tool_name, policy);
+            await fetchToolState();
+        } catch (e) {
+            console.error("[McpTab] Failed to save tool policy:", e);
+        }
+    };
+
     const handleMaxToolRoundsChange = async (value: string) => {
         const parsed = Number.parseInt(value, 10);
         const next: ToolSettings = {
@@ -303,6 +389,50 @@ export default function McpTab({ initialServers, visionEnabled, isActive = false
         await persistToolSettings(next);
     };
 
+    const handlePlanConfigChange = async (updates: Partial<ToolPlanConfig>) => {
+        const next = { ...planConfig, ...updates };
+        setPlanConfig(next);
+        try {
+            await saveToolPlanConfig(next);
+        } catch (e) {
+            console.error("[McpTab] Failed to save plan config:", e);
+        }
+    };
+
+    const handleCodeExecConfigChange = async (updates: Partial<CodeExecConfig>) => {
+        const next = { ...codeExecConfig, ...updates };
+        setCodeExecConfig(next);
+        try {
+            await saveCodeExecConfig(next);
+        } catch (e) {
+            console.error("[McpTab] Failed to save code execution config:", e);
+        }
+    };
+
+    const handleLoadCsv = async () => {
+        setCsvError(null);
+        try {
+            const selected = await openDialog({
+                multiple: false,
+                filters: [{ name: "CSV", extensions: ["csv", "tsv", "txt"] }],
+            });
+            if (typeof selected !== "string") return;
+            await loadCsvTable(selected);
+            setCsvTables(await listCsvTables());
+        } catch (e) {
+            setCsvError(e instanceof Error ? e.message : String(e));
+        }
+    };
+
+    const handleRemoveCsv = async (name: string) => {
+        try {
+            await removeCsvTable(name);
+        } catch (e) {
+            console.error("[McpTab] Failed to remove CSV table:", e);
+        }
+        setCsvTables(await listCsvTables());
+    };
+
     // Dismiss success after 3s
     useEffect(() => {
         if (successMsg) {
@@ -439,6 +569,14 @@ export default function McpTab({ initialServers, visionEnabled, isActive = false
                                         ) : (
                                             <span className="text-red-400" title={srv.error || undefined}>
                                                 {srv.error ? t("settings.mcp.status.error", { error: srv.error }) : t("settings.mcp.status.disconnected")}
+                                                {srv.enabled && retries[srv.name] && (
+                                                    <span className="ml-1 opacity-70">
+                                                        {t("settings.mcp.status.retrying", {
+                                                            seconds: retries[srv.name].seconds,
+                                                            attempt: retries[srv.name].attempts,
+                                                        })}
+                                                    </span>
+                                                )}
                                             </span>
                                         )}
                                     </div>
@@ -459,6 +597,33 @@ export default function McpTab({ initialServers, visionEnabled, isActive = false
                                 >
                                     <Power size={14} strokeWidth={1.5} />
                                 </motion.button>
+                                {/* OAuth sign-in / sign-out */}
+                                {srv.enabled && srv.auth === "required" && (
+                                    <motion.button
+                                        whileTap={{ scale: 0.9 }}
+                                        onClick={() => handleAuthorize(srv.name)}
+                                        disabled={authorizingName === srv.name}
+                                        className="flex items-center gap-1 px-2 py-1 rounded-md text-xs font-heading font-semibold text-amber-400 border border-amber-500/40 hover:bg-amber-500/10 transition-colors disabled:opacity-50"
+                                        title={t("settings.mcp.oauth.sign_in_hint")}
+                                    >
+                                        {authorizingName === srv.name ? (
+                                            <Loader2 size={12} className="animate-spin" />
+                                        ) : (
+                                            <KeyRound size={12} strokeWidth={1.5} />
+                                        )}
+                                        {authorizingName === srv.name ? t("settings.mcp.oauth.waiting") : t("settings.mcp.oauth.sign_in")}
+                                    </motion.button>
+                                )}
+                                {srv.auth === "authorized" && (
+                                    <motion.button
+                                        whileTap={{ scale: 0.9 }}
+                                        onClick={() => handleSignOut(srv.name)}
+                                        className="p-2 rounded-md transition-colors text-[var(--color-text-muted)] hover:text-red-400"
+                                        title={t("settings.mcp.oauth.sign_out")}
+                                    >
+                                        <LogOut size={14} strokeWidth={1.5} />
+                                    </motion.button>
+                                )}
                                 {/* Retry button — visible when disconnected and enabled */}
                                 {srv.status === "disconnected" && srv.enabled && (
                                     <motion.button
@@ -494,6 +659,61 @@ export default function McpTab({ initialServers, visionEnabled, isActive = false
                 </div>
             )}
 
+            {/* Per-tool policies (stored in mcp_servers.json) */}
+            {mcpTools.length > 0 && (
+                <div className="rounded-lg bg-[var(--color-bg-surface)] border border-[var(--color-border)] p-4 space-y-3">
+                    <div>
+                        <div className="text-sm font-heading font-semibold text-[var(--color-text-primary)]">
+                            {t("settings.mcp.tool_policies.title")}
+                        </div>
+                        <div className="text-xs text-[var(--color-text-muted)]">
+                            {t("settings.mcp.tool_policies.desc")}
+                        </div>
+                    </div>
+                    {mcpTools.map((tool) => (
+                        <div
+                            key={`${tool.server_name}/${tool.tool_name}`}
+                            className={clsx(
+                                "flex items-center justify-between gap-3 text-xs",
+                                !tool.enabled && "opacity-50"
+                            )}
+                        >
+                            <label className="flex items-center gap-2 min-w-0 cursor-pointer">
+                                <input
+                                    type="checkbox"
+                                    checked={tool.enabled}
+                                    onChange={(e) => { void handleToolPolicyChange(tool, { enabled: e.target.checked }); }}
+                                    className="accent-[var(--color-accent)]"
+                                />
+                                <span className="truncate text-[var(--color-text-primary)]" title={tool.description ?? undefined}>
+                                    {tool.server_name} / {tool.tool_name}
+                                </span>
+                                {tool.destructive && (
+                                    <span className="flex items-center gap-1 text-amber-400 shrink-0">
+                                        <ShieldAlert size={12} /> {t("settings.mcp.tool_policies.destructive")}
+                                    </span>
+                                )}
+                            </label>
+                            <select
+                                value={tool.auto_approve === null ? "default" : tool.auto_approve ? "auto" : "ask"}
+                                onChange={(e) => {
+                                    const value = e.target.value;
+                                    void handleToolPolicyChange(tool, {
+                                        auto_approve: value === "default" ? null : value === "auto",
+                                    });
+                                }}
+                                disabled={!tool.enabled}
+                                className="shrink-0 rounded-md border border-[var(--color-border)] bg-[var(--color-surface-1)] px-2 py-1 text-xs text-[var(--color-text-primary)] outline-none focus:border-[var(--color-accent)]"
+                            >
+                                <option value="default">{t("settings.mcp.tool_policies.approval.default")}</option>
+                                <option value="ask">{t("settings.mcp.tool_policies.approval.ask")}</option>
+                                <option value="auto">{t("settings.mcp.tool_policies.approval.auto")}</option>
+                            </select>
+                        </div>
+                    ))}
+                </div>
+            )}
+
             {/* Add server panel */}
             <AnimatePresence>
                 {showAdd && (
@@ -603,6 +823,167 @@ export default function McpTab({ initialServers, visionEnabled, isActive = false
                     </div>
                 </div>
 
+                <div className="rounded-xl border border-[var(--color-border)] bg-[var(--color-surface-1)]/80 px-3 py-3 space-y-3">
+                    <div className="flex items-center justify-between gap-4">
+                        <div className="min-w-0">
+                            <div className="text-sm font-heading font-semibold text-[var(--color-text-primary)]">
+                                {t("settings.mcp.plan.title")}
+                            </div>
+                            <div className="mt-1 text-xs text-[var(--color-text-muted)]">
+                                {t("settings.mcp.plan.desc")}
+                            </div>
+                        </div>
+                        <div className="flex items-center gap-2 shrink-0">
+                            <span className="text-xs text-[var(--color-text-secondary)]">{t("settings.mcp.plan.max_steps")}</span>
+                            <input
+                                type="number"
+                                min={1}
+                                max={16}
+                                value={planConfig.max_steps}
+                                onChange={(e) => {
+                                    const parsed = Number.parseInt(e.target.value, 10);
+                                    void handlePlanConfigChange({ max_steps: Number.isFinite(parsed) ? Math.max(1, Math.min(16, parsed)) : 8 });
+                                }}
+                                className="w-20 rounded-lg border border-[var(--color-border)] bg-[var(--color-surface-1)] px-3 py-2 text-sm text-[var(--color-text-primary)] outline-none focus:border-[var(--color-accent)]"
+                            />
+                        </div>
+                    </div>
+                    <div className="flex gap-2">
+                        {(["never", "risky", "always"] as const).map((mode) => (
+                            <button
+                                key={mode}
+                                type="button"
+                                onClick={() => { void handlePlanConfigChange({ confirmation: mode }); }}
+                                className={clsx(
+                                    "flex-1 rounded-lg border px-3 py-2 text-xs transition-colors",
+                                    planConfig.confirmation === mode
+                                        ? "border-[var(--color-accent)] bg-[var(--color-accent)]/10 text-[var(--color-accent)]"
+                                        : "border-[var(--color-border)] text-[var(--color-text-muted)] hover:border-[var(--color-text-muted)]"
+                                )}
+                            >
+                                {t(`settings.mcp.plan.confirm_${mode}`)}
+                            </button>
+                        ))}
+                    </div>
+                </div>
+
+                <div className="rounded-xl border border-[var(--color-border)] bg-[var(--color-surface-1)]/80 px-3 py-3 space-y-3">
+                    <div className="min-w-0">
+                        <div className="text-sm font-heading font-semibold text-[var(--color-text-primary)]">
+                            {t("settings.mcp.code_exec.title")}
+                        </div>
+                        <div className="mt-1 text-xs text-[var(--color-text-muted)]">
+                            {t("settings.mcp.code_exec.desc")}
+                        </div>
+                    </div>
+                    <div className="grid grid-cols-2 gap-2">
+                        <label className="flex items-center justify-between gap-2 text-xs text-[var(--color-text-secondary)]">
+                            {t("settings.mcp.code_exec.timeout_ms")}
+                            <input
+                                type="number"
+                                min={100}
+                                max={30000}
+                                step={500}
+                                value={codeExecConfig.timeout_ms}
+                                onChange={(e) => {
+                                    const parsed = Number.parseInt(e.target.value, 10);
+                                    void handleCodeExecConfigChange({ timeout_ms: Number.isFinite(parsed) ? Math.max(100, Math.min(30000, parsed)) : 5000 });
+                                }}
+                                className="w-24 rounded-lg border border-[var(--color-border)] bg-[var(--color-surface-1)] px-3 py-2 text-sm text-[var(--color-text-primary)] outline-none focus:border-[var(--color-accent)]"
+                            />
+                        </label>
+                        <label className="flex items-center justify-between gap-2 text-xs text-[var(--color-text-secondary)]">
+                            {t("settings.mcp.code_exec.memory_limit_mb")}
+                            <input
+                                type="number"
+                                min={8}
+                                max={512}
+                                value={codeExecConfig.memory_limit_mb}
+                                onChange={(e) => {
+                                    const parsed = Number.parseInt(e.target.value, 10);
+                                    void handleCodeExecConfigChange({ memory_limit_mb: Number.isFinite(parsed) ? Math.max(8, Math.min(512, parsed)) : 64 });
+                                }}
+                                className="w-24 rounded-lg border border-[var(--color-border)] bg-[var(--color-surface-1)] px-3 py-2 text-sm text-[var(--color-text-primary)] outline-none focus:border-[var(--color-accent)]"
+                            />
+                        </label>
+                    </div>
+                    <div className="grid grid-cols-2 gap-2">
+                        <label className="space-y-1 text-xs text-[var(--color-text-secondary)]">
+                            <span>{t("settings.mcp.code_exec.container_runtime")}</span>
+                            <input
+                                type="text"
+                                placeholder="docker"
+                                value={codeExecConfig.container_runtime ?? ""}
+                                onChange={(e) => { void handleCodeExecConfigChange({ container_runtime: e.target.value.trim() || null }); }}
+                                className="w-full rounded-lg border border-[var(--color-border)] bg-[var(--color-surface-1)] px-3 py-2 text-sm text-[var(--color-text-primary)] outline-none focus:border-[var(--color-accent)]"
+                            />
+                        </label>
+                        <label className="space-y-1 text-xs text-[var(--color-text-secondary)]">
+                            <span>{t("settings.mcp.code_exec.python_image")}</span>
+                            <input
+                                type="text"
+                                value={codeExecConfig.python_image}
+                                onChange={(e) => { void handleCodeExecConfigChange({ python_image: e.target.value }); }}
+                                className="w-full rounded-lg border border-[var(--color-border)] bg-[var(--color-surface-1)] px-3 py-2 text-sm text-[var(--color-text-primary)] outline-none focus:border-[var(--color-accent)]"
+                            />
+                        </label>
+                    </div>
+                    <div className="text-[11px] text-[var(--color-text-muted)]">
+                        {t("settings.mcp.code_exec.python_hint")}
+                    </div>
+                </div>
+
+                <div className="rounded-xl border border-[var(--color-border)] bg-[var(--color-surface-1)]/80 px-3 py-3 space-y-3">
+                    <div className="flex items-center justify-between gap-4">
+                        <div className="min-w-0">
+                            <div className="text-sm font-heading font-semibold text-[var(--color-text-primary)]">
+                                {t("settings.mcp.tables.title")}
+                            </div>
+                            <div className="mt-1 text-xs text-[var(--color-text-muted)]">
+                                {t("settings.mcp.tables.desc")}
+                            </div>
+                        </div>
+                        <button
+                            type="button"
+                            onClick={() => { void handleLoadCsv(); }}
+                            className="flex shrink-0 items-center gap-1.5 rounded-lg border border-[var(--color-border)] px-3 py-2 text-xs text-[var(--color-text-secondary)] hover:border-[var(--color-accent)] hover:text-[var(--color-accent)] transition-colors"
+                        >
+                            <Plus size={14} strokeWidth={1.5} />
+                            {t("settings.mcp.tables.load")}
+                        </button>
+                    </div>
+                    {csvError && (
+                        <div className="text-xs text-red-400">{csvError}</div>
+                    )}
+                    {csvTables.length === 0 ? (
+                        <div className="text-xs text-[var(--color-text-muted)]">{t("settings.mcp.tables.empty")}</div>
+                    ) : (
+                        <div className="space-y-1">
+                            {csvTables.map((table) => (
+                                <div key={table.name} className="flex items-center justify-between gap-2 text-xs">
+                                    <div className="min-w-0">
+                                        <span className="font-mono text-[var(--color-text-primary)]">{table.name}</span>
+                                        <span className="ml-2 text-[var(--color-text-muted)]">
+                                            {t("settings.mcp.tables.summary", { rows: table.row_count, columns: table.columns.length })}
+                                        </span>
+                                        <div className="truncate text-[10px] text-[var(--color-text-muted)]" title={table.source_path}>
+                                            {table.source_path}
+                                        </div>
+                                    </div>
+                                    <button
+                                        type="button"
+                                        onClick={() => { void handleRemoveCsv(table.name); }}
+                                        className="shrink-0 p-1 text-[var(--color-text-muted)] hover:text-red-400 transition-colors"
+                                        title={t("settings.mcp.tables.remove")}
+                                    >
+                                        <Trash2 size={14} strokeWidth={1.5} />
+                                    </button>
+                                </div>
+                            ))}
+                        </div>
+                    )}
+                </div>
+
                 <div className="flex items-center justify-between gap-4">
                     <div>
                         <div className="text-sm font-heading font-semibold text-[var(--color-text-primary)]">
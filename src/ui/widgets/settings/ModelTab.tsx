@@ -6,8 +6,10 @@ import { motion, AnimatePresence } from "framer-motion";
 import { open, save } from "@tauri-apps/plugin-dialog";
 import { labelClasses } from "../../styles/settings-primitives";
 import { Select } from "@/components/ui/select";
+import VtsSettings from "./VtsSettings";
 import {
     importLive2dZip,
+    checkLive2dZipDuplicate,
     importLive2dFolder,
     exportLive2dModel,
     listLive2dModels,
@@ -16,6 +18,8 @@ import {
     getLive2dModelProfile,
     saveLive2dModelProfile,
     playCue,
+    onLive2dImportProgress,
+    getKokoroErrorMessage,
     BUILTIN_LIVE2D_MODEL_PATH,
 } from "../../../lib/kokoro-bridge";
 import type { Live2dCueBinding, Live2dModelInfo, Live2dModelProfile } from "../../../lib/kokoro-bridge";
@@ -87,6 +91,8 @@ export default function ModelTab({
         return translated === `settings.model.mapping.areas.${key}` ? value : translated;
     };
     const [isImporting, setIsImporting] = useState(false);
+    const [importPercent, setImportPercent] = useState<number | null>(null);
+    const [importError, setImportError] = useState<string | null>(null);
     const [isExporting, setIsExporting] = useState(false);
     const [models, setModels] = useState<Live2dModelInfo[]>([]);
     const [isLoadingModels, setIsLoadingModels] = useState(false);
@@ -111,6 +117,17 @@ export default function ModelTab({
         fetchModels();
     }, []);
 
+    useEffect(() => {
+        const unlisten = onLive2dImportProgress((progress) => {
+            setImportPercent(progress.total_bytes > 0
+                ? Math.floor((progress.processed_bytes / progress.total_bytes) * 100)
+                : null);
+        });
+        return () => {
+            unlisten.then((fn) => fn());
+        };
+    }, []);
+
     useEffect(() => {
         setIsProfileLoading(true);
         getLive2dModelProfile(effectiveModelPath)
@@ -161,15 +178,22 @@ export default function ModelTab({
             });
 
             if (selected && typeof selected === 'string') {
+                setImportError(null);
+                setImportPercent(null);
                 if (selected.toLowerCase().endsWith('.zip')) {
                     setIsImporting(true);
                     try {
-                        const modelPath = await importLive2dZip(selected);
+                        const duplicate = await checkLive2dZipDuplicate(selected);
+                        const onDuplicate = duplicate
+                            ? (window.confirm(t("settings.model.import.duplicate_confirm", { name: duplicate.name })) ? "skip" : "rename")
+                            : undefined;
+                        const modelPath = await importLive2dZip(selected, onDuplicate);
                         onCustomModelPathChange(modelPath);
                         // Refresh model list after import
                         await fetchModels();
                     } catch (e) {
                         console.error("Failed to import Live2D zip:", e);
+                        setImportError(getKokoroErrorMessage(e));
                     } finally {
                         setIsImporting(false);
                     }
@@ -182,6 +206,7 @@ export default function ModelTab({
                         await fetchModels();
                     } catch (e) {
                         console.error("Failed to import Live2D folder:", e);
+                        setImportError(getKokoroErrorMessage(e));
                     } finally {
                         setIsImporting(false);
                     }
@@ -667,9 +692,18 @@ export default function ModelTab({
                         <FolderOpen size={16} strokeWidth={1.5} />
                     )}
                     <span className="relative top-[2px]">
-                        {isImporting ? t("settings.model.import.loading") : t("settings.model.import.button")}
+                        {isImporting
+                            ? importPercent !== null
+                                ? t("settings.model.import.progress", { percent: importPercent })
+                                : t("settings.model.import.loading")
+                            : t("settings.model.import.button")}
                     </span>
                 </motion.button>
+                {importError && (
+                    <p className="mt-2 text-xs text-red-400 break-words">
+                        {t("settings.model.import.failed", { error: importError })}
+                    </p>
+                )}
 
                 <motion.button
                     whileHover={{ scale: 1.02 }}
@@ -982,6 +1016,8 @@ export default function ModelTab({
                     </>
                 )}
             </div>
+
+            <VtsSettings />
         </div>
     );
 }
@@ -2,9 +2,9 @@ import { motion } from "framer-motion";
 import { clsx } from "clsx";
 import { useTranslation } from "react-i18next";
 import { useEffect, useState, useRef } from "react";
-import { Mic, Languages, Send, HandMetal, Server, Wand2, Radio, Download } from "lucide-react";
-import type { SttConfig, SttProviderConfig, SenseVoiceLocalModelStatus, SenseVoiceLocalDownloadProgress } from "../../../lib/kokoro-bridge";
-import { getSenseVoiceLocalStatus, downloadSenseVoiceLocalModel, onSenseVoiceLocalProgress } from "../../../lib/kokoro-bridge";
+import { Mic, Languages, Send, HandMetal, Server, Wand2, Radio, Download, Cpu, Users } from "lucide-react";
+import type { SttConfig, SttProviderConfig, SenseVoiceLocalModelStatus, SenseVoiceLocalDownloadProgress, WhisperLocalModelStatus, WhisperLocalDownloadProgress } from "../../../lib/kokoro-bridge";
+import { getSenseVoiceLocalStatus, downloadSenseVoiceLocalModel, onSenseVoiceLocalProgress, getWhisperLocalStatus, downloadWhisperLocalModel, onWhisperLocalProgress } from "../../../lib/kokoro-bridge";
 import { Select } from "@/components/ui/select";
 
 interface SttTabProps {
@@ -221,6 +221,190 @@ function SenseVoiceLocalPanel({ config, onUpdate }: {
     );
 }
 
+function WhisperLocalPanel({ config, onUpdate }: {
+    config: SttProviderConfig;
+    onUpdate: (update: Partial<SttProviderConfig>) => void;
+}) {
+    const { t } = useTranslation();
+    const [modelStatus, setModelStatus] = useState<WhisperLocalModelStatus | null>(null);
+    const [progress, setProgress] = useState<WhisperLocalDownloadProgress | null>(null);
+    const [downloading, setDownloading] = useState(false);
+    const unlistenRef = useRef<(() => void) | null>(null);
+
+    useEffect(() => {
+        getWhisperLocalStatus().then(setModelStatus).catch(console.error);
+
+        onWhisperLocalProgress((p) => setProgress(p)).then((unlisten) => {
+            unlistenRef.current = unlisten;
+        });
+
+        return () => {
+            unlistenRef.current?.();
+        };
+    }, []);
+
+    const selectedId = config.model || modelStatus?.default_model_id || "base";
+    const selectedModel = modelStatus?.models.find(m => m.id === selectedId);
+
+    const startDownload = async () => {
+        setDownloading(true);
+        setProgress(null);
+        try {
+            const result = await downloadWhisperLocalModel(selectedId);
+            setModelStatus(result);
+        } catch (e) {
+            console.error("Whisper local model download failed:", e);
+        } finally {
+            setDownloading(false);
+            setProgress(null);
+        }
+    };
+
+    const progressPercent = progress && progress.total_bytes
+        ? Math.round((progress.downloaded_bytes / progress.total_bytes) * 100)
+        : null;
+
+    const inputClass = "w-full px-3 py-1.5 rounded-md text-sm bg-[var(--color-bg-elevated)] border border-[var(--color-border)] focus:border-[var(--color-accent)] outline-none text-[var(--color-text-primary)]";
+    const labelClass = "text-xs text-[var(--color-text-secondary)]";
+
+    return (
+        <div className="space-y-4">
+            {/* Model Install Card */}
+            <div className="rounded-xl border border-[var(--color-border)] bg-[var(--color-bg-surface)] overflow-hidden">
+                <div className="flex items-center justify-between px-4 py-3 border-b border-[var(--color-border)]/60">
+                    <div className="flex items-center gap-2">
+                        <Server size={14} strokeWidth={1.5} className="text-[var(--color-accent)]" />
+                        <span className="text-sm font-semibold text-[var(--color-text-primary)]">
+                            {t("settings.stt.whisper_local.install_title")}
+                        </span>
+                    </div>
+                    {selectedModel && (
+                        selectedModel.installed ? (
+                            <span className="text-xs text-emerald-400">
+                                {t("settings.stt.sensevoice_local.installed")}
+                            </span>
+                        ) : (
+                            <span className="text-xs text-[var(--color-text-muted)]">
+                                {t("settings.stt.sensevoice_local.not_installed")}
+                            </span>
+                        )
+                    )}
+                </div>
+
+                <div className="px-4 py-3 space-y-3">
+                    <div className="space-y-1">
+                        <div className={labelClass}>{t("settings.stt.fields.model")}</div>
+                        <Select
+                            value={selectedId}
+                            onChange={(model) => onUpdate({ model })}
+                            options={(modelStatus?.models ?? []).map(m => ({
+                                value: m.id,
+                                label: t("settings.stt.whisper_local.model_option", {
+                                    id: m.id,
+                                    size: formatBytes(m.approx_size_mb * 1024 * 1024),
+                                }) + (m.installed ? " ✓" : ""),
+                            }))}
+                        />
+                    </div>
+
+                    {downloading ? (
+                        <div className="space-y-2">
+                            <div className="w-full h-1.5 bg-[var(--color-border)] rounded-full overflow-hidden">
+                                <motion.div
+                                    className="h-full bg-[var(--color-accent)] rounded-full"
+                                    animate={{ width: progressPercent != null ? `${progressPercent}%` : "40%" }}
+                                    transition={{ ease: "linear", duration: 0.3 }}
+                                    style={{ width: progressPercent != null ? `${progressPercent}%` : undefined }}
+                                />
+                            </div>
+                            <p className="text-xs text-[var(--color-text-muted)]">
+                                {progress?.stage === "complete"
+                                    ? t("settings.stt.sensevoice_local.complete")
+                                    : t("settings.stt.sensevoice_local.downloading", {
+                                        downloaded: formatBytes(progress?.downloaded_bytes ?? 0),
+                                        total: progress?.total_bytes ? formatBytes(progress.total_bytes) : "…",
+                                    })
+                                }
+                            </p>
+                        </div>
+                    ) : (
+                        <button
+                            onClick={startDownload}
+                            disabled={!selectedModel || selectedModel.installed}
+                            className={clsx(
+                                "flex items-center gap-2 px-3 py-1.5 rounded-lg text-xs font-semibold transition-colors",
+                                !selectedModel || selectedModel.installed
+                                    ? "text-[var(--color-text-muted)] bg-[var(--color-bg-elevated)] cursor-not-allowed opacity-50"
+                                    : "text-black bg-[var(--color-accent)] hover:opacity-90 active:scale-95"
+                            )}
+                        >
+                            <Download size={12} />
+                            {t("settings.stt.whisper_local.download")}
+                        </button>
+                    )}
+
+                    {/* GPU */}
+                    {modelStatus && (
+                        <div className="flex items-start gap-2 text-xs text-[var(--color-text-muted)]">
+                            <Cpu size={12} className="mt-0.5 shrink-0" />
+                            <div className="space-y-0.5">
+                                <div>
+                                    {modelStatus.gpu.gpu_enabled
+                                        ? t("settings.stt.whisper_local.gpu_enabled", {
+                                            backend: modelStatus.gpu.backend.toUpperCase(),
+                                            device: modelStatus.gpu.device ?? "",
+                                        })
+                                        : t("settings.stt.whisper_local.cpu_only")}
+                                </div>
+                                {modelStatus.gpu.note && <div>{modelStatus.gpu.note}</div>}
+                            </div>
+                        </div>
+                    )}
+
+                    {selectedModel?.installed && (
+                        <div className="space-y-1">
+                            <div className={labelClass}>{t("settings.stt.sensevoice_local.install_dir")}</div>
+                            <div className="text-xs text-[var(--color-text-muted)] font-mono break-all bg-[var(--color-bg-elevated)] px-2 py-1 rounded">
+                                {modelStatus?.install_dir}
+                            </div>
+                        </div>
+                    )}
+                </div>
+            </div>
+
+            {/* Advanced config fields */}
+            <div className="grid grid-cols-1 gap-3">
+                <div className="space-y-1">
+                    <div className={labelClass}>{t("settings.stt.whisper_local.model_path")}</div>
+                    <input
+                        type="text"
+                        value={config.model_path || ""}
+                        onChange={(e) => onUpdate({ model_path: e.target.value || undefined })}
+                        placeholder={selectedModel?.model_path ?? ""}
+                        className={inputClass}
+                    />
+                </div>
+
+                <div className="space-y-1">
+                    <div className={labelClass}>{t("settings.stt.fields.num_threads")}</div>
+                    <input
+                        type="number"
+                        min={1}
+                        max={32}
+                        value={config.num_threads ?? ""}
+                        onChange={(e) => onUpdate({ num_threads: parseInt(e.target.value) || undefined })}
+                        placeholder={t("settings.stt.whisper_local.threads_auto")}
+                        className={clsx(
+                            inputClass,
+                            "appearance-none [appearance:textfield] [-moz-appearance:textfield] [&::-webkit-inner-spin-button]:appearance-none [&::-webkit-outer-spin-button]:appearance-none"
+                        )}
+                    />
+                </div>
+            </div>
+        </div>
+    );
+}
+
 export default function SttTab({
     sttConfig,
     onSttConfigChange,
@@ -278,6 +462,8 @@ export default function SttTab({
                 return t("settings.stt.info.whisper_cpp", { url: activeProvider.base_url || "default URL" });
             case "sensevoice_local":
                 return t("settings.stt.info.sensevoice_local");
+            case "whisper_local":
+                return t("settings.stt.info.whisper_local");
             default:
                 return t("settings.stt.info.generic");
         }
@@ -357,6 +543,11 @@ export default function SttTab({
                                         config={activeProvider}
                                         onUpdate={(update) => updateProvider(activeProvider.id, update)}
                                     />
+                                ) : activeProvider.provider_type === "whisper_local" ? (
+                                    <WhisperLocalPanel
+                                        config={activeProvider}
+                                        onUpdate={(update) => updateProvider(activeProvider.id, update)}
+                                    />
                                 ) : (
                                     <div className="grid grid-cols-1 gap-3">
                                         {/* Base URL */}
@@ -502,6 +693,74 @@ export default function SttTab({
                             </motion.button>
                         </div>
 
+                        {/* Barge-in toggle (native mic VAD) */}
+                        <div className="flex items-center justify-between">
+                            <div className="flex items-center gap-2">
+                                <HandMetal size={14} strokeWidth={1.5} className="text-[var(--color-text-muted)]" />
+                                <div>
+                                    <div className="text-sm text-[var(--color-text-primary)]">
+                                        {t("settings.stt.barge_in.title")}
+                                    </div>
+                                    <div className="text-xs text-[var(--color-text-muted)]">
+                                        {t("settings.stt.barge_in.desc")}
+                                    </div>
+                                </div>
+                            </div>
+                            <motion.button
+                                whileTap={{ scale: 0.95 }}
+                                onClick={() => updateConfig({ barge_in: !(sttConfig.barge_in ?? true) })}
+                                className={clsx(
+                                    "w-12 h-6 rounded-full relative transition-colors duration-200",
+                                    (sttConfig.barge_in ?? true)
+                                        ? "bg-[var(--color-accent)]"
+                                        : "bg-[var(--color-bg-surface)] border border-[var(--color-border)]"
+                                )}
+                            >
+                                <motion.div
+                                    animate={{ x: (sttConfig.barge_in ?? true) ? 24 : 2 }}
+                                    transition={{ type: "spring", stiffness: 500, damping: 30 }}
+                                    className={clsx(
+                                        "w-5 h-5 rounded-full absolute top-0.5",
+                                        (sttConfig.barge_in ?? true) ? "bg-black" : "bg-[var(--color-text-muted)]"
+                                    )}
+                                />
+                            </motion.button>
+                        </div>
+
+                        {/* Speaker diarization toggle */}
+                        <div className="flex items-center justify-between">
+                            <div className="flex items-center gap-2">
+                                <Users size={14} strokeWidth={1.5} className="text-[var(--color-text-muted)]" />
+                                <div>
+                                    <div className="text-sm text-[var(--color-text-primary)]">
+                                        {t("settings.stt.diarization.title")}
+                                    </div>
+                                    <div className="text-xs text-[var(--color-text-muted)]">
+                                        {t("settings.stt.diarization.desc")}
+                                    </div>
+                                </div>
+                            </div>
+                            <motion.button
+                                whileTap={{ scale: 0.95 }}
+                                onClick={() => updateConfig({ diarization: !sttConfig.diarization })}
+                                className={clsx(
+                                    "w-12 h-6 rounded-full relative transition-colors duration-200",
+                                    sttConfig.diarization
+                                        ? "bg-[var(--color-accent)]"
+                                        : "bg-[var(--color-bg-surface)] border border-[var(--color-border)]"
+                                )}
+                            >
+                                <motion.div
+                                    animate={{ x: sttConfig.diarization ? 24 : 2 }}
+                                    transition={{ type: "spring", stiffness: 500, damping: 30 }}
+                                    className={clsx(
+                                        "w-5 h-5 rounded-full absolute top-0.5",
+                                        sttConfig.diarization ? "bg-black" : "bg-[var(--color-text-muted)]"
+                                    )}
+                                />
+                            </motion.button>
+                        </div>
+
                         {/* Continuous listening toggle */}
                         <div className="flex items-center justify-between">
                             <div className="flex items-center gap-2">
@@ -7,10 +7,11 @@
 import { useState, useEffect, useCallback, useMemo } from "react";
 import { useTranslation } from "react-i18next";
 import { clsx } from "clsx";
-import { Trash2, RefreshCw } from "lucide-react";
+import { Trash2, RefreshCw, Plus } from "lucide-react";
 import { motion, AnimatePresence } from "framer-motion";
 import { inputClasses, labelClasses, sectionHeadingClasses } from "../../styles/settings-primitives";
 import { Select } from "@/components/ui/select";
+import JsonSpecEditor from "./JsonSpecEditor";
 import { synthesize, listGptSovitsModels } from "../../../lib/kokoro-bridge";
 import type { GptSovitsModels } from "../../../lib/kokoro-bridge";
 import type { ProviderStatus, VoiceProfile, TtsSystemConfig } from "../../../lib/kokoro-bridge";
@@ -23,7 +24,19 @@ const isReferenceCloneProviderType = (providerType?: string) =>
     providerType === "gpt_sovits" || providerType === "omnivoice";
 
 const providerTypeLabel = (providerType: string) =>
-    providerType === "omnivoice" ? "OmniVoice" : providerType.replace("_", " ");
+    providerType === "omnivoice"
+        ? "OmniVoice"
+        : providerType === "custom_http"
+            ? "Custom HTTP"
+            : providerType.replace("_", " ");
+
+// Starting point for a custom server: JSON in, audio bytes out.
+const DEFAULT_CUSTOM_HTTP_TTS_SPEC = {
+    url: "{{base_url}}/tts",
+    method: "POST",
+    body: { text: "{{text}}", voice: "{{voice}}", speed: "{{speed}}" },
+    response: "binary",
+};
 
 const readExtraInputValue = (extra: Record<string, unknown> | undefined, key: string) => {
     const raw = extra?.[key];
@@ -196,6 +209,10 @@ export default function TtsTab({
             // fall back to the first alphabetical entry in the voice list.
             ...(type === "edge_tts" && { default_voice: "zh-CN-XiaoyiNeural" }),
             ...(type === "openai"   && { default_voice: "alloy" }),
+            ...(type === "custom_http" && {
+                base_url: "http://127.0.0.1:5000",
+                extra: { custom_http: DEFAULT_CUSTOM_HTTP_TTS_SPEC },
+            }),
         };
         onTtsConfigChange({
             ...ttsConfig,
@@ -257,6 +274,160 @@ export default function TtsTab({
                 </button>
             </div>
 
+            {/* Provider failover */}
+            {ttsConfig && (() => {
+                const failover = ttsConfig.failover ?? { enabled: true, latency_threshold_ms: 8000 };
+                const setFailover = (patch: Partial<typeof failover>) =>
+                    onTtsConfigChange({ ...ttsConfig, failover: { ...failover, ...patch } });
+                return (
+                    <div className="p-3 rounded-lg bg-black/20 border border-[var(--color-border)] space-y-3">
+                        <div className="flex items-center justify-between">
+                            <div>
+                                <span className={labelClasses.replace("mb-2", "mb-0")}>{t("settings.tts.failover.label")}</span>
+                                <p className="text-[10px] text-[var(--color-text-muted)] mt-0.5">
+                                    {t("settings.tts.failover.desc")}
+                                </p>
+                            </div>
+                            <button
+                                onClick={() => setFailover({ enabled: !failover.enabled })}
+                                className={clsx(
+                                    "w-10 h-5 rounded-full transition-colors relative shrink-0",
+                                    failover.enabled ? "bg-[var(--color-accent)]" : "bg-[var(--color-border)]"
+                                )}
+                            >
+                                <motion.div
+                                    animate={{ x: failover.enabled ? 20 : 2 }}
+                                    className="absolute top-0.5 w-4 h-4 rounded-full bg-white"
+                                />
+                            </button>
+                        </div>
+                        {failover.enabled && (
+                            <div>
+                                <label className={labelClasses}>{t("settings.tts.failover.latency")}</label>
+                                <input
+                                    type="number"
+                                    min={1000}
+                                    step={500}
+                                    value={failover.latency_threshold_ms}
+                                    onChange={(e) => {
+                                        const value = Number.parseInt(e.target.value, 10);
+                                        if (Number.isFinite(value)) {
+                                            setFailover({ latency_threshold_ms: Math.max(1000, value) });
+                                        }
+                                    }}
+                                    className={clsx(inputClasses, "font-mono text-xs")}
+                                />
+                            </div>
+                        )}
+                    </div>
+                );
+            })()}
+
+            {/* Text normalization and pronunciation dictionary */}
+            {ttsConfig && (() => {
+                const normalization = ttsConfig.normalization ?? {
+                    enabled: true,
+                    strip_markup: true,
+                    expand_numbers: true,
+                    pronunciations: [],
+                };
+                const setNormalization = (patch: Partial<typeof normalization>) =>
+                    onTtsConfigChange({ ...ttsConfig, normalization: { ...normalization, ...patch } });
+                const rules = normalization.pronunciations;
+                const setRule = (index: number, patch: Partial<(typeof rules)[number]>) =>
+                    setNormalization({
+                        pronunciations: rules.map((rule, i) => (i === index ? { ...rule, ...patch } : rule)),
+                    });
+                return (
+                    <div className="p-3 rounded-lg bg-black/20 border border-[var(--color-border)] space-y-3">
+                        <div className="flex items-center justify-between">
+                            <div>
+                                <span className={labelClasses.replace("mb-2", "mb-0")}>{t("settings.tts.normalization.label")}</span>
+                                <p className="text-[10px] text-[var(--color-text-muted)] mt-0.5">
+                                    {t("settings.tts.normalization.desc")}
+                                </p>
+                            </div>
+                            <button
+                                onClick={() => setNormalization({ enabled: !normalization.enabled })}
+                                className={clsx(
+                                    "w-10 h-5 rounded-full transition-colors relative shrink-0",
+                                    normalization.enabled ? "bg-[var(--color-accent)]" : "bg-[var(--color-border)]"
+                                )}
+                            >
+                                <motion.div
+                                    animate={{ x: normalization.enabled ? 20 : 2 }}
+                                    className="absolute top-0.5 w-4 h-4 rounded-full bg-white"
+                                />
+                            </button>
+                        </div>
+                        {normalization.enabled && (
+                            <div className="space-y-2">
+                                <label className="flex items-center gap-2 text-xs text-[var(--color-text-secondary)]">
+                                    <input
+                                        type="checkbox"
+                                        checked={normalization.strip_markup}
+                                        onChange={(e) => setNormalization({ strip_markup: e.target.checked })}
+                                    />
+                                    {t("settings.tts.normalization.strip_markup")}
+                                </label>
+                                <label className="flex items-center gap-2 text-xs text-[var(--color-text-secondary)]">
+                                    <input
+                                        type="checkbox"
+                                        checked={normalization.expand_numbers}
+                                        onChange={(e) => setNormalization({ expand_numbers: e.target.checked })}
+                                    />
+                                    {t("settings.tts.normalization.expand_numbers")}
+                                </label>
+                                <div>
+                                    <label className={labelClasses}>{t("settings.tts.normalization.dictionary")}</label>
+                                    <div className="space-y-2">
+                                        {rules.map((rule, index) => (
+                                            <div key={index} className="flex items-center gap-2">
+                                                <input
+                                                    type="checkbox"
+                                                    checked={rule.enabled ?? true}
+                                                    onChange={(e) => setRule(index, { enabled: e.target.checked })}
+                                                />
+                                                <input
+                                                    type="text"
+                                                    value={rule.pattern}
+                                                    onChange={(e) => setRule(index, { pattern: e.target.value })}
+                                                    placeholder={t("settings.tts.normalization.pattern")}
+                                                    className={clsx(inputClasses, "font-mono text-xs")}
+                                                />
+                                                <input
+                                                    type="text"
+                                                    value={rule.replacement}
+                                                    onChange={(e) => setRule(index, { replacement: e.target.value })}
+                                                    placeholder={t("settings.tts.normalization.replacement")}
+                                                    className={clsx(inputClasses, "text-xs")}
+                                                />
+                                                <button
+                                                    onClick={() => setNormalization({ pronunciations: rules.filter((_, i) => i !== index) })}
+                                                    className="p-1 text-[var(--color-text-muted)] hover:text-red-400 shrink-0"
+                                                    title={t("settings.tts.normalization.remove")}
+                                                >
+                                                    <Trash2 size={14} />
+                                                </button>
+                                            </div>
+                                        ))}
+                                        <button
+                                            onClick={() => setNormalization({
+                                                pronunciations: [...rules, { pattern: "", replacement: "", enabled: true }],
+                                            })}
+                                            className="flex items-center gap-1 text-xs text-[var(--color-accent)] hover:underline"
+                                        >
+                                            <Plus size={12} />
+                                            {t("settings.tts.normalization.add")}
+                                        </button>
+                                    </div>
+                                </div>
+                            </div>
+                        )}
+                    </div>
+                );
+            })()}
+
             {/* Section: Playback Settings */}
             <div className="space-y-4">
                 <h3 className={clsx(sectionHeadingClasses, "mb-3")}>{t("settings.tts.active_settings.title")}</h3>
@@ -354,6 +525,7 @@ export default function TtsTab({
                                 voice: ttsVoice || undefined,
                                 speed: parseFloat(ttsSpeed || "1.0"),
                                 pitch: parseFloat(ttsPitch || "1.0"),
+                                priority: "high",
                             }).catch(err => console.error("[TTS] Test failed:", err));
                         }}
                         className={clsx(
@@ -452,7 +624,7 @@ export default function TtsTab({
                                     >
                                         {/* Common Fields */}
                                         {/* Common API Key Field */}
-                                        {(provider.provider_type === "openai" || provider.provider_type === "azure" || provider.provider_type === "elevenlabs") && (
+                                        {(provider.provider_type === "openai" || provider.provider_type === "azure" || provider.provider_type === "elevenlabs" || provider.provider_type === "custom_http") && (
                                             <div>
                                                 <label className={labelClasses}>{t("settings.tts.fields.api_key")}</label>
                                                 <input
@@ -466,10 +638,10 @@ export default function TtsTab({
                                         )}
 
                                         {/* Base URL Field */}
-                                        {(provider.provider_type === "openai" || provider.provider_type === "local_vits" || provider.provider_type === "gpt_sovits") && (
+                                        {(provider.provider_type === "openai" || provider.provider_type === "local_vits" || provider.provider_type === "gpt_sovits" || provider.provider_type === "custom_http") && (
                                             <div>
                                                 <label className={labelClasses}>
-                                                    {(provider.provider_type.includes("local") || provider.provider_type === "gpt_sovits") ? t("settings.tts.fields.server_url") : t("settings.tts.fields.base_url")}
+                                                    {(provider.provider_type.includes("local") || provider.provider_type === "gpt_sovits" || provider.provider_type === "custom_http") ? t("settings.tts.fields.server_url") : t("settings.tts.fields.base_url")}
                                                 </label>
                                                 <input
                                                     type="text"
@@ -477,7 +649,7 @@ export default function TtsTab({
                                                     onChange={e => updateProviderConfig(index, { base_url: e.target.value })}
                                                     placeholder={
                                                         provider.provider_type === "gpt_sovits" ? "http://127.0.0.1:9880" :
-                                                            provider.provider_type.includes("local") ? "http://127.0.0.1:5000" :
+                                                            (provider.provider_type.includes("local") || provider.provider_type === "custom_http") ? "http://127.0.0.1:5000" :
                                                                 "https://api.openai.com/v1"
                                                     }
                                                     className={clsx(inputClasses, "font-mono text-xs")}
@@ -625,6 +797,19 @@ export default function TtsTab({
                                             </>
                                         )}
 
+                                        {/* Custom HTTP Mapping */}
+                                        {provider.provider_type === "custom_http" && (
+                                            <JsonSpecEditor
+                                                value={provider.extra?.custom_http}
+                                                fallback={DEFAULT_CUSTOM_HTTP_TTS_SPEC}
+                                                label={t("settings.tts.fields.custom_http.label")}
+                                                hint={t("settings.tts.fields.custom_http.hint", { skipInterpolation: true })}
+                                                onChange={spec => updateProviderConfig(index, {
+                                                    extra: { ...provider.extra, custom_http: spec }
+                                                })}
+                                            />
+                                        )}
+
                                         {/* OmniVoice Specific Fields */}
                                         {provider.provider_type === "omnivoice" && (
                                             <>
@@ -871,7 +1056,7 @@ export default function TtsTab({
                 {/* Add Provider Dropdown */}
                 <div className="pt-2">
                     <div className="grid grid-cols-2 gap-2">
-                        {["openai", "edge_tts", "local_vits", "gpt_sovits", "omnivoice", "azure", "elevenlabs"].map(type => (
+                        {["openai", "edge_tts", "local_vits", "gpt_sovits", "omnivoice", "azure", "elevenlabs", "custom_http"].map(type => (
                             <button
                                 key={type}
                                 onClick={() => addProvider(type)}
@@ -0,0 +1,288 @@
+import { useEffect, useState } from "react";
+import { clsx } from "clsx";
+import { motion } from "framer-motion";
+import { useTranslation } from "react-i18next";
+import { Plug, PlugZap, Plus, RefreshCw, Trash2 } from "lucide-react";
+import { inputClasses, labelClasses } from "../../styles/settings-primitives";
+import {
+    getVtsConfig,
+    getVtsStatus,
+    listVtsHotkeys,
+    saveVtsConfig,
+    startVts,
+    stopVts,
+    type VtsConfig,
+    type VtsHotkey,
+    type VtsStatus,
+} from "../../../lib/kokoro-bridge";
+
+function errorText(error: unknown): string {
+    if (error && typeof error === "object" && "message" in error) {
+        return String((error as { message: unknown }).message);
+    }
+    return String(error);
+}
+
+/**
+ * VTube Studio bridge — for users who render their model in VTS instead of
+ * the built-in Live2D viewer. Cues trigger VTS hotkeys and TTS playback
+ * drives the mouth parameter.
+ */
+export default function VtsSettings() {
+    const { t } = useTranslation();
+    const [config, setConfig] = useState<VtsConfig | null>(null);
+    const [status, setStatus] = useState<VtsStatus | null>(null);
+    const [hotkeys, setHotkeys] = useState<VtsHotkey[]>([]);
+    const [dirty, setDirty] = useState(false);
+    const [error, setError] = useState<string | null>(null);
+
+    const refresh = async () => {
+        try {
+            const next = await getVtsStatus();
+            setStatus(next);
+            if (next.connected) {
+                setHotkeys(await listVtsHotkeys());
+            }
+        } catch (e) {
+            console.error("[VtsSettings] Status refresh failed:", e);
+        }
+    };
+
+    useEffect(() => {
+        getVtsConfig()
+            .then(setConfig)
+            .catch(e => console.error("[VtsSettings] Failed to load config:", e));
+        void refresh();
+    }, []);
+
+    if (!config) {
+        return null;
+    }
+
+    const update = (patch: Partial<VtsConfig>) => {
+        setConfig({ ...config, ...patch });
+        setDirty(true);
+    };
+
+    const mappings = Object.entries(config.cue_hotkeys);
+    const setMappings = (entries: [string, string][]) => {
+        update({ cue_hotkeys: Object.fromEntries(entries) });
+    };
+
+    const save = async () => {
+        try {
+            await saveVtsConfig(config);
+            setDirty(false);
+            setError(null);
+        } catch (e) {
+            setError(errorText(e));
+            throw e;
+        }
+    };
+
+    const handleConnect = async () => {
+        try {
+            if (dirty) {
+                await save();
+            }
+            setStatus(await startVts());
+            setError(null);
+        } catch (e) {
+            setError(errorText(e));
+        }
+    };
+
+    const handleDisconnect = async () => {
+        try {
+            await stopVts();
+            setHotkeys([]);
+            await refresh();
+        } catch (e) {
+            setError(errorText(e));
+        }
+    };
+
+    const statusLabel = status?.connected
+        ? t("settings.model.vts.status.connected", { model: status.model_name ?? "—" })
+        : status?.running
+            ? t("settings.model.vts.status.connecting")
+            : t("settings.model.vts.status.stopped");
+
+    return (
+        <div className="space-y-4 rounded-lg border border-[var(--color-border)] bg-black/10 p-4">
+            <div className="flex items-center justify-between gap-3">
+                <div>
+                    <label className={labelClasses}>{t("settings.model.vts.title")}</label>
+                    <p className="text-xs text-[var(--color-text-muted)]">{t("settings.model.vts.desc")}</p>
+                </div>
+                <div className="flex items-center gap-2">
+                    <button
+                        type="button"
+                        onClick={() => { void refresh(); }}
+                        title={t("common.actions.refresh")}
+                        className="p-2 rounded-md text-[var(--color-text-muted)] hover:text-[var(--color-accent)] transition-colors"
+                    >
+                        <RefreshCw size={14} />
+                    </button>
+                    <motion.button
+                        whileTap={{ scale: 0.95 }}
+                        onClick={() => { void (status?.running ? handleDisconnect() : handleConnect()); }}
+                        className={clsx(
+                            "flex items-center gap-1.5 px-3 py-1.5 rounded-md text-xs font-heading font-semibold tracking-wider transition-colors",
+                            status?.running
+                                ? "border border-red-500/40 text-red-300 hover:bg-red-500/10"
+                                : "bg-[var(--color-accent)] text-black hover:bg-white"
+                        )}
+                    >
+                        {status?.running ? <Plug size={12} /> : <PlugZap size={12} />}
+                        {status?.running ? t("settings.model.vts.disconnect") : t("settings.model.vts.connect")}
+                    </motion.button>
+                </div>
+            </div>
+
+            <div className="flex items-center gap-2 text-xs text-[var(--color-text-secondary)]">
+                <span
+                    className={clsx(
+                        "h-2 w-2 rounded-full",
+                        status?.connected ? "bg-emerald-400" : status?.running ? "bg-amber-400" : "bg-[var(--color-text-muted)]"
+                    )}
+                />
+                {statusLabel}
+            </div>
+            {status?.running && !status.connected && (
+                <p className="text-xs text-[var(--color-text-muted)]">{t("settings.model.vts.allow_hint")}</p>
+            )}
+            {(error ?? status?.error) && <div className="text-xs text-red-400">{error ?? status?.error}</div>}
+
+            <Switch
+                label={t("settings.model.vts.enabled")}
+                enabled={config.enabled}
+                onChange={enabled => update({ enabled })}
+            />
+            <div>
+                <label className={labelClasses}>{t("settings.model.vts.url")}</label>
+                <input
+                    value={config.url}
+                    onChange={e => update({ url: e.target.value })}
+                    placeholder="ws://127.0.0.1:8001"
+                    className={inputClasses}
+                />
+            </div>
+
+            <Switch
+                label={t("settings.model.vts.lip_sync")}
+                enabled={config.lip_sync_enabled}
+                onChange={lip_sync_enabled => update({ lip_sync_enabled })}
+            />
+            {config.lip_sync_enabled && (
+                <div className="grid grid-cols-[1fr_110px] gap-3">
+                    <div>
+                        <label className={labelClasses}>{t("settings.model.vts.mouth_parameter")}</label>
+                        <input
+                            value={config.mouth_parameter}
+                            onChange={e => update({ mouth_parameter: e.target.value })}
+                            placeholder="MouthOpen"
+                            className={inputClasses}
+                        />
+                    </div>
+                    <div>
+                        <label className={labelClasses}>{t("settings.model.vts.gain")}</label>
+                        <input
+                            type="number"
+                            min={0.1}
+                            max={10}
+                            step={0.1}
+                            value={config.lip_sync_gain}
+                            onChange={e => update({ lip_sync_gain: Number(e.target.value) || 1 })}
+                            className={inputClasses}
+                        />
+                    </div>
+                </div>
+            )}
+
+            <div className="space-y-2">
+                <label className={labelClasses}>{t("settings.model.vts.cue_hotkeys")}</label>
+                <p className="text-xs text-[var(--color-text-muted)]">{t("settings.model.vts.cue_hotkeys_desc")}</p>
+                {mappings.map(([cue, hotkey], index) => (
+                    <div key={index} className="grid grid-cols-[1fr_1fr_auto] gap-2">
+                        <input
+                            value={cue}
+                            onChange={e => setMappings(mappings.map((m, i) => (i === index ? [e.target.value, m[1]] : m)))}
+                            placeholder={t("settings.model.vts.cue_placeholder")}
+                            className={inputClasses}
+                        />
+                        <input
+                            value={hotkey}
+                            list="vts-hotkeys"
+                            onChange={e => setMappings(mappings.map((m, i) => (i === index ? [m[0], e.target.value] : m)))}
+                            placeholder={t("settings.model.vts.hotkey_placeholder")}
+                            className={inputClasses}
+                        />
+                        <button
+                            type="button"
+                            onClick={() => setMappings(mappings.filter((_, i) => i !== index))}
+                            className="px-2 text-red-300 hover:text-red-200"
+                        >
+                            <Trash2 size={14} />
+                        </button>
+                    </div>
+                ))}
+                <datalist id="vts-hotkeys">
+                    {hotkeys.map(hotkey => (
+                        <option key={hotkey.id} value={hotkey.name}>{hotkey.kind}</option>
+                    ))}
+                </datalist>
+                <button
+                    type="button"
+                    onClick={() => setMappings([...mappings, ["", ""]])}
+                    className="flex items-center gap-1.5 text-xs text-[var(--color-accent)] hover:underline"
+                >
+                    <Plus size={12} /> {t("common.actions.add")}
+                </button>
+                <Switch
+                    label={t("settings.model.vts.match_names")}
+                    enabled={config.match_hotkey_names}
+                    onChange={match_hotkey_names => update({ match_hotkey_names })}
+                />
+            </div>
+
+            {dirty && (
+                <motion.button
+                    whileTap={{ scale: 0.95 }}
+                    onClick={() => { void save().catch(() => {}); }}
+                    className="px-4 py-2 rounded-md text-xs font-heading font-semibold tracking-wider
+                        bg-[var(--color-accent)] text-black hover:bg-white transition-colors"
+                >
+                    {t("common.actions.save")}
+                </motion.button>
+            )}
+        </div>
+    );
+}
+
+function Switch({ label, enabled, onChange }: { label: string; enabled: boolean; onChange: (enabled: boolean) => void }) {
+    return (
+        <div className="flex items-center justify-between">
+            <div className="text-sm text-[var(--color-text-secondary)]">{label}</div>
+            <motion.button
+                whileTap={{ scale: 0.95 }}
+                onClick={() => onChange(!enabled)}
+                className={clsx(
+                    "w-12 h-6 rounded-full relative transition-colors duration-200",
+                    enabled
+                        ? "bg-[var(--color-accent)]"
+                        : "bg-[var(--color-bg-surface)] border border-[var(--color-border)]"
+                )}
+            >
+                <motion.div
+                    animate={{ x: enabled ? 24 : 2 }}
+                    transition={{ type: "spring", stiffness: 500, damping: 30 }}
+                    className={clsx(
+                        "w-5 h-5 rounded-full absolute top-0.5",
+                        enabled ? "bg-black" : "bg-[var(--color-text-muted)]"
+                    )}
+                />
+            </motion.button>
+        </div>
+    );
+}